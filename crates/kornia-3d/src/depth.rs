@@ -0,0 +1,244 @@
+use kornia_image::{Image, ImageError, ImageSize};
+use kornia_imgproc::calibration::CameraIntrinsic;
+
+use crate::pointcloud::PointCloud;
+
+/// A pixel type of a depth image.
+pub trait DepthSample: Copy {
+    /// Convert the depth value to meters.
+    ///
+    /// # Arguments
+    ///
+    /// * `depth_scale` - The scale to convert the raw value to meters.
+    ///
+    /// # Returns
+    ///
+    /// The depth in meters or None if the pixel has no valid measurement.
+    fn to_meters(self, depth_scale: f64) -> Option<f64>;
+}
+
+impl DepthSample for u16 {
+    fn to_meters(self, depth_scale: f64) -> Option<f64> {
+        // zero is used by the sensors to mark missing measurements
+        (self > 0).then_some(self as f64 * depth_scale)
+    }
+}
+
+impl DepthSample for f32 {
+    fn to_meters(self, depth_scale: f64) -> Option<f64> {
+        let z = self as f64 * depth_scale;
+        (z.is_finite() && z > 0.0).then_some(z)
+    }
+}
+
+/// Back-project a depth image to a point cloud in the camera frame.
+///
+/// The pixels with zero, negative or non finite depth are skipped.
+///
+/// # Arguments
+///
+/// * `depth` - The depth image, e.g. 16-bit sensor units or 32-bit floats.
+/// * `intrinsics` - The intrinsic parameters of the depth camera.
+/// * `depth_scale` - The scale to convert the depth values to meters, e.g. 1 / 1000 for millimeters.
+///
+/// # Returns
+///
+/// The point cloud with a point per valid pixel, in row-major order.
+///
+/// # Example
+///
+/// ```
+/// use kornia_3d::depth::depth_to_pointcloud;
+/// use kornia_image::Image;
+/// use kornia_imgproc::calibration::CameraIntrinsic;
+///
+/// let depth = Image::<u16, 1>::from_size_val([4, 3].into(), 1000).unwrap();
+/// let intrinsics = CameraIntrinsic { fx: 2.0, fy: 2.0, cx: 1.5, cy: 1.0 };
+/// let pointcloud = depth_to_pointcloud(&depth, &intrinsics, 1e-3);
+/// assert_eq!(pointcloud.len(), 12);
+/// ```
+pub fn depth_to_pointcloud<T: DepthSample>(
+    depth: &Image<T, 1>,
+    intrinsics: &CameraIntrinsic,
+    depth_scale: f64,
+) -> PointCloud {
+    let points = backproject(depth, intrinsics, depth_scale)
+        .map(|(_, point)| point)
+        .collect();
+    PointCloud::new(points, None, None)
+}
+
+/// Back-project a depth image to a point cloud with the colors of an aligned image.
+///
+/// # Arguments
+///
+/// * `color` - The color image aligned to the depth image.
+/// * `depth` - The depth image.
+/// * `intrinsics` - The intrinsic parameters of the depth camera.
+/// * `depth_scale` - The scale to convert the depth values to meters.
+///
+/// # Returns
+///
+/// The colored point cloud with a point per valid pixel, in row-major order.
+pub fn rgbd_to_pointcloud<T: DepthSample>(
+    color: &Image<u8, 3>,
+    depth: &Image<T, 1>,
+    intrinsics: &CameraIntrinsic,
+    depth_scale: f64,
+) -> Result<PointCloud, ImageError> {
+    if color.size() != depth.size() {
+        return Err(ImageError::InvalidImageSize(
+            color.cols(),
+            color.rows(),
+            depth.cols(),
+            depth.rows(),
+        ));
+    }
+
+    let (points, colors) = backproject(depth, intrinsics, depth_scale)
+        .map(|(idx, point)| {
+            let rgb = &color.as_slice()[idx * 3..idx * 3 + 3];
+            (point, [rgb[0], rgb[1], rgb[2]])
+        })
+        .unzip();
+
+    Ok(PointCloud::new(points, Some(colors), None))
+}
+
+// iterate over the valid pixels returning their linear index and 3D point
+fn backproject<'a, T: DepthSample>(
+    depth: &'a Image<T, 1>,
+    intrinsics: &'a CameraIntrinsic,
+    depth_scale: f64,
+) -> impl Iterator<Item = (usize, [f64; 3])> + 'a {
+    let cols = depth.cols();
+    depth
+        .as_slice()
+        .iter()
+        .enumerate()
+        .filter_map(move |(idx, &d)| {
+            let z = d.to_meters(depth_scale)?;
+            let (u, v) = ((idx % cols) as f64, (idx / cols) as f64);
+            let x = (u - intrinsics.cx) * z / intrinsics.fx;
+            let y = (v - intrinsics.cy) * z / intrinsics.fy;
+            Some((idx, [x, y, z]))
+        })
+}
+
+/// Project a point cloud in the camera frame to a depth image.
+///
+/// The points are projected to the nearest pixel and, when several points fall in the
+/// same pixel, the closest one is kept. The points behind the camera are skipped.
+///
+/// # Arguments
+///
+/// * `pointcloud` - The point cloud in the camera frame.
+/// * `intrinsics` - The intrinsic parameters of the camera.
+/// * `size` - The size of the depth image.
+///
+/// # Returns
+///
+/// The depth image in meters, where zero means no measurement.
+pub fn pointcloud_to_depth(
+    pointcloud: &PointCloud,
+    intrinsics: &CameraIntrinsic,
+    size: ImageSize,
+) -> Result<Image<f32, 1>, ImageError> {
+    let mut depth = Image::from_size_val(size, 0.0f32)?;
+    let cols = depth.cols();
+    let depth_data = depth.as_slice_mut();
+
+    for p in pointcloud.points() {
+        let z = p[2];
+        if !(z > 0.0 && z.is_finite()) {
+            continue;
+        }
+
+        let u = (intrinsics.fx * p[0] / z + intrinsics.cx).round();
+        let v = (intrinsics.fy * p[1] / z + intrinsics.cy).round();
+        if u < 0.0 || v < 0.0 || u >= size.width as f64 || v >= size.height as f64 {
+            continue;
+        }
+
+        let d = &mut depth_data[v as usize * cols + u as usize];
+        if *d == 0.0 || (z as f32) < *d {
+            *d = z as f32;
+        }
+    }
+
+    Ok(depth)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INTRINSICS: CameraIntrinsic = CameraIntrinsic {
+        fx: 2.0,
+        fy: 4.0,
+        cx: 1.0,
+        cy: 0.5,
+    };
+
+    #[test]
+    fn test_depth_to_pointcloud() -> Result<(), ImageError> {
+        let depth = Image::<u16, 1>::new([3, 2].into(), vec![1000, 0, 2000, 0, 500, 0])?;
+
+        let pointcloud = depth_to_pointcloud(&depth, &INTRINSICS, 1e-3);
+        assert_eq!(
+            pointcloud.points(),
+            &vec![[-0.5, -0.125, 1.0], [1.0, -0.25, 2.0], [0.0, 0.0625, 0.5]]
+        );
+
+        let depth = Image::<f32, 1>::new([3, 1].into(), vec![f32::NAN, -1.0, 2.0])?;
+        let pointcloud = depth_to_pointcloud(&depth, &INTRINSICS, 1.0);
+        assert_eq!(pointcloud.points(), &vec![[1.0, -0.25, 2.0]]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_rgbd_to_pointcloud() -> Result<(), ImageError> {
+        let depth = Image::<f32, 1>::new([2, 1].into(), vec![0.0, 1.0])?;
+        let color = Image::<u8, 3>::new([2, 1].into(), vec![1, 2, 3, 4, 5, 6])?;
+
+        let pointcloud = rgbd_to_pointcloud(&color, &depth, &INTRINSICS, 1.0)?;
+        assert_eq!(pointcloud.points(), &vec![[0.0, -0.125, 1.0]]);
+        assert_eq!(pointcloud.colors(), Some(&vec![[4, 5, 6]]));
+
+        let color = Image::<u8, 3>::from_size_val([1, 1].into(), 0)?;
+        assert!(rgbd_to_pointcloud(&color, &depth, &INTRINSICS, 1.0).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_pointcloud_to_depth() -> Result<(), ImageError> {
+        let size = ImageSize {
+            width: 3,
+            height: 2,
+        };
+        let depth = Image::<f32, 1>::new(size, vec![1.0, 0.0, 2.0, 0.0, 0.5, 0.0])?;
+
+        // the back-projection and projection are inverse operations
+        let pointcloud = depth_to_pointcloud(&depth, &INTRINSICS, 1.0);
+        let depth_back = pointcloud_to_depth(&pointcloud, &INTRINSICS, size)?;
+        assert_eq!(depth_back.as_slice(), depth.as_slice());
+
+        // the nearest point is kept and the points behind the camera are skipped
+        let pointcloud = PointCloud::new(
+            vec![
+                [0.0, 0.0, 3.0],
+                [0.0, 0.0, 1.5],
+                [0.0, 0.0, -1.0],
+                [10.0, 0.0, 1.0],
+            ],
+            None,
+            None,
+        );
+        let depth = pointcloud_to_depth(&pointcloud, &INTRINSICS, size)?;
+        assert_eq!(depth.as_slice(), &[0.0, 0.0, 0.0, 0.0, 1.5, 0.0]);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,282 @@
+use std::{
+    fs::File,
+    io::{BufReader, Read},
+    path::Path,
+};
+
+use super::{CameraModelId, ColmapCamera, ColmapError, ColmapImage, ColmapPoint3d};
+
+// a reader of the little endian values of the COLMAP binary files
+struct BinaryReader<R: Read> {
+    reader: R,
+}
+
+impl<R: Read> BinaryReader<R> {
+    fn read_bytes<const N: usize>(&mut self) -> Result<[u8; N], ColmapError> {
+        let mut buf = [0u8; N];
+        self.reader.read_exact(&mut buf)?;
+        Ok(buf)
+    }
+
+    fn read_u8(&mut self) -> Result<u8, ColmapError> {
+        Ok(self.read_bytes::<1>()?[0])
+    }
+
+    fn read_i32(&mut self) -> Result<i32, ColmapError> {
+        Ok(i32::from_le_bytes(self.read_bytes()?))
+    }
+
+    fn read_u32(&mut self) -> Result<u32, ColmapError> {
+        Ok(u32::from_le_bytes(self.read_bytes()?))
+    }
+
+    fn read_u64(&mut self) -> Result<u64, ColmapError> {
+        Ok(u64::from_le_bytes(self.read_bytes()?))
+    }
+
+    fn read_i64(&mut self) -> Result<i64, ColmapError> {
+        Ok(i64::from_le_bytes(self.read_bytes()?))
+    }
+
+    fn read_f64(&mut self) -> Result<f64, ColmapError> {
+        Ok(f64::from_le_bytes(self.read_bytes()?))
+    }
+
+    fn read_f64_array<const N: usize>(&mut self) -> Result<[f64; N], ColmapError> {
+        let mut values = [0.0; N];
+        for value in values.iter_mut() {
+            *value = self.read_f64()?;
+        }
+        Ok(values)
+    }
+
+    // a null-terminated string
+    fn read_string(&mut self) -> Result<String, ColmapError> {
+        let mut bytes = Vec::new();
+        loop {
+            match self.read_u8()? {
+                0 => break,
+                byte => bytes.push(byte),
+            }
+        }
+        String::from_utf8(bytes).map_err(|e| ColmapError::ParseError(e.to_string()))
+    }
+
+    // the number of elements of a file or a list
+    fn read_len(&mut self) -> Result<usize, ColmapError> {
+        let len = self.read_u64()?;
+        usize::try_from(len)
+            .map_err(|_| ColmapError::ParseError(format!("Invalid number of elements: {}", len)))
+    }
+}
+
+fn open_reader(path: impl AsRef<Path>) -> Result<BinaryReader<BufReader<File>>, ColmapError> {
+    let file = File::open(path)?;
+    Ok(BinaryReader {
+        reader: BufReader::new(file),
+    })
+}
+
+/// The camera model and its number of parameters from the model id of the binary files.
+fn camera_model_from_id(model_id: i32) -> Result<(CameraModelId, usize), ColmapError> {
+    Ok(match model_id {
+        0 => (CameraModelId::CameraModelSimplePinhole, 3),
+        1 => (CameraModelId::CameraModelPinhole, 4),
+        2 => (CameraModelId::CameraModelSimplifiedRadial, 4),
+        3 => (CameraModelId::CameraModelRadial, 5),
+        4 => (CameraModelId::CameraModelOpenCV, 8),
+        5 => (CameraModelId::CameraModelOpenCVFisheye, 8),
+        6 => (CameraModelId::CameraModelFullOpenCV, 12),
+        7 => (CameraModelId::CameraModelFOV, 5),
+        8 => (CameraModelId::CameraModelSimpleRadialFisheye, 4),
+        9 => (CameraModelId::CameraModelRadialFisheye, 5),
+        10 => (CameraModelId::CameraModelThinPrismFisheye, 12),
+        _ => {
+            return Err(ColmapError::ParseError(format!(
+                "Invalid camera model id: {}",
+                model_id
+            )))
+        }
+    })
+}
+
+/// Read the cameras.bin file and return a vector of ColmapCamera structs.
+///
+/// # Arguments
+///
+/// * `path` - The path to the cameras.bin file.
+///
+/// # Returns
+///
+/// A vector of ColmapCamera structs.
+pub fn read_cameras_bin(path: impl AsRef<Path>) -> Result<Vec<ColmapCamera>, ColmapError> {
+    let mut reader = open_reader(path)?;
+    let num_cameras = reader.read_len()?;
+
+    (0..num_cameras)
+        .map(|_| {
+            let camera_id = reader.read_u32()?;
+            let (model_id, num_params) = camera_model_from_id(reader.read_i32()?)?;
+            let width = reader.read_u64()? as usize;
+            let height = reader.read_u64()? as usize;
+            let params = (0..num_params)
+                .map(|_| reader.read_f64())
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(ColmapCamera {
+                camera_id,
+                model_id,
+                width,
+                height,
+                params,
+            })
+        })
+        .collect()
+}
+
+/// Read the images.bin file and return a vector of ColmapImage structs.
+///
+/// # Arguments
+///
+/// * `path` - The path to the images.bin file.
+///
+/// # Returns
+///
+/// A vector of ColmapImage structs.
+pub fn read_images_bin(path: impl AsRef<Path>) -> Result<Vec<ColmapImage>, ColmapError> {
+    let mut reader = open_reader(path)?;
+    let num_images = reader.read_len()?;
+
+    (0..num_images)
+        .map(|_| {
+            let image_id = reader.read_u32()?;
+            let rotation = reader.read_f64_array::<4>()?;
+            let translation = reader.read_f64_array::<3>()?;
+            let camera_id = reader.read_u32()?;
+            let name = reader.read_string()?;
+            let num_points2d = reader.read_len()?;
+            let points2d = (0..num_points2d)
+                .map(|_| Ok((reader.read_f64()?, reader.read_f64()?, reader.read_i64()?)))
+                .collect::<Result<Vec<_>, ColmapError>>()?;
+            Ok(ColmapImage {
+                name,
+                image_id,
+                camera_id,
+                rotation,
+                translation,
+                points2d,
+            })
+        })
+        .collect()
+}
+
+/// Read the points3D.bin file and return a vector of ColmapPoint3d structs.
+///
+/// # Arguments
+///
+/// * `path` - The path to the points3D.bin file.
+///
+/// # Returns
+///
+/// A vector of ColmapPoint3d structs.
+pub fn read_points3d_bin(path: impl AsRef<Path>) -> Result<Vec<ColmapPoint3d>, ColmapError> {
+    let mut reader = open_reader(path)?;
+    let num_points = reader.read_len()?;
+
+    (0..num_points)
+        .map(|_| {
+            let point3d_id = reader.read_u64()?;
+            let xyz = reader.read_f64_array::<3>()?;
+            let rgb = [reader.read_u8()?, reader.read_u8()?, reader.read_u8()?];
+            let error = reader.read_f64()?;
+            let track_len = reader.read_len()?;
+            let track = (0..track_len)
+                .map(|_| Ok((reader.read_u32()?, reader.read_u32()?)))
+                .collect::<Result<Vec<_>, ColmapError>>()?;
+            Ok(ColmapPoint3d {
+                point3d_id,
+                xyz,
+                rgb,
+                error,
+                track,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_colmap_bin() -> Result<(), ColmapError> {
+        let tmp_dir = tempfile::tempdir()?;
+
+        // a pinhole camera
+        let mut cameras = Vec::new();
+        cameras.extend(1u64.to_le_bytes());
+        cameras.extend(1u32.to_le_bytes());
+        cameras.extend(1i32.to_le_bytes());
+        cameras.extend(640u64.to_le_bytes());
+        cameras.extend(480u64.to_le_bytes());
+        for v in [500.0f64, 510.0, 320.0, 240.0] {
+            cameras.extend(v.to_le_bytes());
+        }
+        std::fs::write(tmp_dir.path().join("cameras.bin"), cameras)?;
+
+        // an image observing one point
+        let mut images = Vec::new();
+        images.extend(1u64.to_le_bytes());
+        images.extend(7u32.to_le_bytes());
+        for v in [1.0f64, 0.0, 0.0, 0.0, 0.1, 0.2, 0.3] {
+            images.extend(v.to_le_bytes());
+        }
+        images.extend(1u32.to_le_bytes());
+        images.extend(b"frame_000.png\0");
+        images.extend(2u64.to_le_bytes());
+        for (x, y, id) in [(10.5f64, 20.5f64, 3i64), (30.0, 40.0, -1)] {
+            images.extend(x.to_le_bytes());
+            images.extend(y.to_le_bytes());
+            images.extend(id.to_le_bytes());
+        }
+        std::fs::write(tmp_dir.path().join("images.bin"), images)?;
+
+        let mut points = Vec::new();
+        points.extend(1u64.to_le_bytes());
+        points.extend(3u64.to_le_bytes());
+        for v in [1.0f64, 2.0, 3.0] {
+            points.extend(v.to_le_bytes());
+        }
+        points.extend([255u8, 128, 0]);
+        points.extend(0.5f64.to_le_bytes());
+        points.extend(1u64.to_le_bytes());
+        points.extend(7u32.to_le_bytes());
+        points.extend(0u32.to_le_bytes());
+        std::fs::write(tmp_dir.path().join("points3D.bin"), points)?;
+
+        let cameras = read_cameras_bin(tmp_dir.path().join("cameras.bin"))?;
+        assert_eq!(cameras.len(), 1);
+        assert!(matches!(
+            cameras[0].model_id,
+            CameraModelId::CameraModelPinhole
+        ));
+        assert_eq!(cameras[0].params, vec![500.0, 510.0, 320.0, 240.0]);
+
+        let images = read_images_bin(tmp_dir.path().join("images.bin"))?;
+        assert_eq!(images[0].image_id, 7);
+        assert_eq!(images[0].name, "frame_000.png");
+        assert_eq!(images[0].translation, [0.1, 0.2, 0.3]);
+        assert_eq!(images[0].points2d, vec![(10.5, 20.5, 3), (30.0, 40.0, -1)]);
+
+        let points = read_points3d_bin(tmp_dir.path().join("points3D.bin"))?;
+        assert_eq!(points[0].point3d_id, 3);
+        assert_eq!(points[0].rgb, [255, 128, 0]);
+        assert_eq!(points[0].track, vec![(7, 0)]);
+
+        // a truncated file
+        let truncated = std::fs::read(tmp_dir.path().join("images.bin"))?;
+        std::fs::write(tmp_dir.path().join("images.bin"), &truncated[..40])?;
+        assert!(read_images_bin(tmp_dir.path().join("images.bin")).is_err());
+
+        Ok(())
+    }
+}
@@ -1,5 +1,7 @@
+mod binary;
 mod text;
 mod types;
 
+pub use binary::*;
 pub use text::*;
 pub use types::*;
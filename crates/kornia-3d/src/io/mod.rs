@@ -1,8 +1,8 @@
 /// Colmap reader module.
 pub mod colmap;
 
-/// PCD reader module.
+/// PCD reader and writer module.
 pub mod pcd;
 
-/// PLY reader module.
+/// PLY reader and writer module.
 pub mod ply;
@@ -1,3 +1,8 @@
 /// PCD file parser
 mod parser;
+mod reader;
+mod writer;
+
 pub use parser::*;
+pub use reader::*;
+pub use writer::*;
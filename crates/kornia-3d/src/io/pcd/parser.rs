@@ -22,6 +22,14 @@ pub enum PcdError {
     /// Invalid PCD file extension
     #[error("Invalid PCD file extension. Got:{0}")]
     InvalidFileExtension(String),
+
+    /// Invalid PCD header
+    #[error("Invalid PCD header: {0}")]
+    InvalidHeader(String),
+
+    /// Invalid PCD data
+    #[error("Invalid PCD data: {0}")]
+    InvalidData(String),
 }
 
 /// A property of a point in a PCD file.
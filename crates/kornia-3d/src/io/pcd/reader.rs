@@ -0,0 +1,259 @@
+use std::io::{BufRead, Read};
+use std::path::Path;
+
+use super::PcdError;
+use crate::pointcloud::PointCloud;
+
+/// The encoding of the data of a PCD file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PcdFormat {
+    /// Human readable text.
+    Ascii,
+    /// Binary little endian.
+    Binary,
+}
+
+// a field of the points, e.g. `x` or `rgb`
+#[derive(Debug)]
+struct Field {
+    name: String,
+    size: usize,
+    kind: char,
+    count: usize,
+}
+
+impl Field {
+    // decode the first element of the field from its little endian bytes
+    fn read(&self, bytes: &[u8]) -> Result<f64, PcdError> {
+        macro_rules! from_bytes {
+            ($t:ty) => {
+                <$t>::from_le_bytes(
+                    bytes[..std::mem::size_of::<$t>()]
+                        .try_into()
+                        .unwrap_or_default(),
+                ) as f64
+            };
+        }
+        Ok(match (self.kind, self.size) {
+            ('I', 1) => bytes[0] as i8 as f64,
+            ('U', 1) => bytes[0] as f64,
+            ('I', 2) => from_bytes!(i16),
+            ('U', 2) => from_bytes!(u16),
+            ('I', 4) => from_bytes!(i32),
+            ('U', 4) => from_bytes!(u32),
+            ('F', 4) => from_bytes!(f32),
+            ('F', 8) => from_bytes!(f64),
+            (kind, size) => {
+                return Err(PcdError::InvalidHeader(format!(
+                    "unsupported field type {kind}{size}"
+                )))
+            }
+        })
+    }
+}
+
+#[derive(Debug)]
+struct Header {
+    fields: Vec<Field>,
+    num_points: usize,
+    format: PcdFormat,
+}
+
+fn parse_header(reader: &mut impl BufRead) -> Result<Header, PcdError> {
+    let mut names = Vec::new();
+    let mut sizes = Vec::new();
+    let mut kinds = Vec::new();
+    let mut counts = Vec::new();
+    let (mut width, mut height, mut points) = (None, 1, None);
+
+    let parse = |token: &str| -> Result<usize, PcdError> {
+        token
+            .parse()
+            .map_err(|_| PcdError::InvalidHeader(format!("invalid value {token}")))
+    };
+
+    let mut line = String::new();
+    let format = loop {
+        line.clear();
+        if reader.read_line(&mut line)? == 0 {
+            return Err(PcdError::InvalidHeader("missing DATA".to_string()));
+        }
+        let mut tokens = line.split_whitespace();
+        let Some(key) = tokens.next() else {
+            continue;
+        };
+        match key {
+            "FIELDS" => names = tokens.map(|t| t.to_string()).collect(),
+            "SIZE" => sizes = tokens.map(parse).collect::<Result<_, _>>()?,
+            "TYPE" => kinds = tokens.filter_map(|t| t.chars().next()).collect(),
+            "COUNT" => counts = tokens.map(parse).collect::<Result<_, _>>()?,
+            "WIDTH" => width = tokens.next().map(parse).transpose()?,
+            "HEIGHT" => height = tokens.next().map(parse).transpose()?.unwrap_or(1),
+            "POINTS" => points = tokens.next().map(parse).transpose()?,
+            "DATA" => match tokens.next() {
+                Some("ascii") => break PcdFormat::Ascii,
+                Some("binary") => break PcdFormat::Binary,
+                data => {
+                    return Err(PcdError::InvalidHeader(format!(
+                        "unsupported data encoding {}",
+                        data.unwrap_or_default()
+                    )))
+                }
+            },
+            // comments, VERSION and VIEWPOINT
+            _ => {}
+        }
+    };
+
+    // the count is optional and defaults to one
+    if counts.is_empty() {
+        counts = vec![1; names.len()];
+    }
+    if names.is_empty() || [sizes.len(), kinds.len(), counts.len()] != [names.len(); 3] {
+        return Err(PcdError::InvalidHeader(
+            "mismatched FIELDS, SIZE, TYPE and COUNT".to_string(),
+        ));
+    }
+
+    let num_points = points
+        .or(width.map(|w| w * height))
+        .ok_or_else(|| PcdError::InvalidHeader("missing POINTS".to_string()))?;
+
+    let fields = names
+        .into_iter()
+        .zip(sizes)
+        .zip(kinds.into_iter().zip(counts))
+        .map(|((name, size), (kind, count))| Field {
+            name,
+            size,
+            kind,
+            count,
+        })
+        .collect();
+
+    Ok(Header {
+        fields,
+        num_points,
+        format,
+    })
+}
+
+/// Read a point cloud from a PCD file in ascii or binary format.
+///
+/// The points are read from the `x`, `y` and `z` fields, the normals from `normal_x`,
+/// `normal_y` and `normal_z` and the colors from the packed `rgb` or `rgba` field. The other
+/// fields, e.g. `curvature`, are ignored. The compressed binary format is not supported.
+///
+/// # Arguments
+///
+/// * `path` - The path to the PCD file.
+///
+/// # Returns
+///
+/// A `PointCloud` with the points and, if available, the colors and normals.
+pub fn read_pcd(path: impl AsRef<Path>) -> Result<PointCloud, PcdError> {
+    let file = std::fs::File::open(path)?;
+    let mut reader = std::io::BufReader::new(file);
+    let header = parse_header(&mut reader)?;
+
+    let find = |name: &str| header.fields.iter().position(|f| f.name == name);
+    let find_all = |names: [&str; 3]| -> Option<[usize; 3]> {
+        Some([find(names[0])?, find(names[1])?, find(names[2])?])
+    };
+
+    let xyz = find_all(["x", "y", "z"]).ok_or(PcdError::UnsupportedProperty)?;
+    let nxyz = find_all(["normal_x", "normal_y", "normal_z"]);
+    let rgb = find("rgb").or(find("rgba"));
+    if let Some(rgb) = rgb {
+        if header.fields[rgb].size != 4 {
+            return Err(PcdError::UnsupportedProperty);
+        }
+    }
+
+    // the offsets of the fields in a binary row
+    let mut offsets = Vec::with_capacity(header.fields.len());
+    let mut row_size = 0;
+    for field in &header.fields {
+        offsets.push(row_size);
+        row_size += field.size * field.count;
+    }
+
+    // the index of the first token of the fields in an ascii row
+    let columns = header
+        .fields
+        .iter()
+        .scan(0, |column, field| {
+            let first = *column;
+            *column += field.count;
+            Some(first)
+        })
+        .collect::<Vec<_>>();
+
+    let mut points = Vec::with_capacity(header.num_points);
+    let mut normals = Vec::with_capacity(nxyz.map_or(0, |_| header.num_points));
+    let mut colors = Vec::with_capacity(rgb.map_or(0, |_| header.num_points));
+
+    let mut row = vec![0u8; row_size];
+    let mut line = String::new();
+    for _ in 0..header.num_points {
+        // the value of a field and the packed color of the point
+        let (values, packed) = match header.format {
+            PcdFormat::Binary => {
+                reader.read_exact(&mut row)?;
+                let values = header
+                    .fields
+                    .iter()
+                    .zip(&offsets)
+                    .map(|(field, &offset)| field.read(&row[offset..]))
+                    .collect::<Result<Vec<_>, _>>()?;
+                let packed = rgb.map(|i| {
+                    let bytes = &row[offsets[i]..offsets[i] + 4];
+                    u32::from_le_bytes(bytes.try_into().unwrap_or_default())
+                });
+                (values, packed)
+            }
+            PcdFormat::Ascii => {
+                line.clear();
+                if reader.read_line(&mut line)? == 0 {
+                    return Err(PcdError::Io(std::io::ErrorKind::UnexpectedEof.into()));
+                }
+                let tokens = line.split_whitespace().collect::<Vec<_>>();
+                let fields = columns
+                    .iter()
+                    .map(|&column| tokens.get(column).copied())
+                    .collect::<Option<Vec<_>>>()
+                    .ok_or_else(|| PcdError::InvalidData("missing value".to_string()))?;
+                let invalid = |t: &str| PcdError::InvalidData(format!("invalid value {t}"));
+                let values = fields
+                    .iter()
+                    .map(|t| t.parse::<f64>().map_err(|_| invalid(t)))
+                    .collect::<Result<Vec<_>, _>>()?;
+                // a float color holds the bits of the packed color
+                let packed = rgb
+                    .map(|i| {
+                        let packed = match header.fields[i].kind {
+                            'F' => fields[i].parse::<f32>().ok().map(f32::to_bits),
+                            _ => fields[i].parse::<u32>().ok(),
+                        };
+                        packed.ok_or_else(|| invalid(fields[i]))
+                    })
+                    .transpose()?;
+                (values, packed)
+            }
+        };
+
+        points.push(xyz.map(|i| values[i]));
+        if let Some(nxyz) = nxyz {
+            normals.push(nxyz.map(|i| values[i]));
+        }
+        if let Some(packed) = packed {
+            colors.push([(packed >> 16) as u8, (packed >> 8) as u8, packed as u8]);
+        }
+    }
+
+    Ok(PointCloud::new(
+        points,
+        rgb.map(|_| colors),
+        nxyz.map(|_| normals),
+    ))
+}
@@ -0,0 +1,156 @@
+use std::io::Write;
+use std::path::Path;
+
+use super::{PcdError, PcdFormat};
+use crate::pointcloud::PointCloud;
+
+/// Write a point cloud to a PCD file in ascii or binary format.
+///
+/// The points and normals are written as 32-bit floats and the colors as the packed `rgb`
+/// field with the layout `0x00RRGGBB` used by PCL.
+///
+/// # Arguments
+///
+/// * `path` - The path to the PCD file.
+/// * `pointcloud` - The point cloud to write with its colors and normals if available.
+/// * `format` - The encoding of the data.
+///
+/// # Example
+///
+/// ```no_run
+/// use kornia_3d::io::pcd::{write_pcd, PcdFormat};
+/// use kornia_3d::pointcloud::PointCloud;
+///
+/// let pointcloud = PointCloud::new(vec![[0.0, 0.0, 1.0]], None, Some(vec![[0.0, 0.0, -1.0]]));
+/// write_pcd("cloud.pcd", &pointcloud, PcdFormat::Binary).unwrap();
+/// ```
+pub fn write_pcd(
+    path: impl AsRef<Path>,
+    pointcloud: &PointCloud,
+    format: PcdFormat,
+) -> Result<(), PcdError> {
+    let file = std::fs::File::create(path)?;
+    let mut writer = std::io::BufWriter::new(file);
+
+    let colors = pointcloud.colors();
+    let normals = pointcloud.normals();
+
+    // write the header
+    let mut fields = vec!["x", "y", "z"];
+    let mut types = vec!["F"; 3];
+    if normals.is_some() {
+        fields.extend(["normal_x", "normal_y", "normal_z"]);
+        types.extend(["F"; 3]);
+    }
+    if colors.is_some() {
+        fields.push("rgb");
+        types.push("U");
+    }
+
+    writeln!(writer, "# .PCD v0.7 - Point Cloud Data file format")?;
+    writeln!(writer, "VERSION 0.7")?;
+    writeln!(writer, "FIELDS {}", fields.join(" "))?;
+    writeln!(writer, "SIZE {}", vec!["4"; fields.len()].join(" "))?;
+    writeln!(writer, "TYPE {}", types.join(" "))?;
+    writeln!(writer, "COUNT {}", vec!["1"; fields.len()].join(" "))?;
+    writeln!(writer, "WIDTH {}", pointcloud.len())?;
+    writeln!(writer, "HEIGHT 1")?;
+    writeln!(writer, "VIEWPOINT 0 0 0 1 0 0 0")?;
+    writeln!(writer, "POINTS {}", pointcloud.len())?;
+    match format {
+        PcdFormat::Ascii => writeln!(writer, "DATA ascii")?,
+        PcdFormat::Binary => writeln!(writer, "DATA binary")?,
+    }
+
+    // write the points
+    for (i, point) in pointcloud.points().iter().enumerate() {
+        let values = point
+            .iter()
+            .chain(normals.map(|normals| &normals[i]).into_iter().flatten())
+            .map(|&v| v as f32);
+        let packed = colors.map(|colors| {
+            let [r, g, b] = colors[i];
+            ((r as u32) << 16) | ((g as u32) << 8) | b as u32
+        });
+
+        if format == PcdFormat::Ascii {
+            let mut tokens = values.map(|v| v.to_string()).collect::<Vec<_>>();
+            tokens.extend(packed.map(|p| p.to_string()));
+            writeln!(writer, "{}", tokens.join(" "))?;
+            continue;
+        }
+
+        for v in values {
+            writer.write_all(&v.to_le_bytes())?;
+        }
+        if let Some(packed) = packed {
+            writer.write_all(&packed.to_le_bytes())?;
+        }
+    }
+
+    writer.flush()?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io::pcd::read_pcd;
+
+    #[test]
+    fn test_write_read_pcd() -> Result<(), PcdError> {
+        let tmp_dir = tempfile::tempdir()?;
+
+        let pointcloud = PointCloud::new(
+            vec![[0.5, -2.0, 3.25], [0.125, 0.0, 1024.0]],
+            Some(vec![[255, 0, 10], [1, 2, 3]]),
+            Some(vec![[0.0, 0.0, 1.0], [0.75, -0.5, 0.0]]),
+        );
+
+        for format in [PcdFormat::Ascii, PcdFormat::Binary] {
+            let path = tmp_dir.path().join("cloud.pcd");
+            write_pcd(&path, &pointcloud, format)?;
+
+            let read = read_pcd(&path)?;
+            assert_eq!(read.points(), pointcloud.points());
+            assert_eq!(read.colors(), pointcloud.colors());
+            assert_eq!(read.normals(), pointcloud.normals());
+        }
+
+        let path = tmp_dir.path().join("points.pcd");
+        let points_only = PointCloud::new(vec![[1.0, 2.0, 3.0]], None, None);
+        write_pcd(&path, &points_only, PcdFormat::Binary)?;
+        let read = read_pcd(&path)?;
+        assert_eq!(read.points(), points_only.points());
+        assert!(read.colors().is_none() && read.normals().is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_pcd_float_rgb() -> Result<(), PcdError> {
+        let tmp_dir = tempfile::tempdir()?;
+        let path = tmp_dir.path().join("pcl.pcd");
+
+        // the packed color 0x00FF8001 stored as a float as written by PCL
+        let rgb = f32::from_bits(0x00FF8001);
+        std::fs::write(
+            &path,
+            format!(
+                "# .PCD v0.7\nVERSION 0.7\nFIELDS x y z rgb curvature\nSIZE 4 4 4 4 4\n\
+                 TYPE F F F F F\nCOUNT 1 1 1 1 1\nWIDTH 1\nHEIGHT 1\nPOINTS 1\nDATA ascii\n\
+                 1 2 3 {rgb:e} 0.5\n"
+            ),
+        )?;
+
+        let read = read_pcd(&path)?;
+        assert_eq!(read.points(), &vec![[1.0, 2.0, 3.0]]);
+        assert_eq!(read.colors(), Some(&vec![[255, 128, 1]]));
+
+        std::fs::write(&path, "VERSION 0.7\nFIELDS x y z\nDATA binary_compressed\n")?;
+        assert!(matches!(read_pcd(&path), Err(PcdError::InvalidHeader(_))));
+
+        Ok(())
+    }
+}
@@ -1,8 +1,12 @@
 mod parser;
 mod properties;
+mod reader;
+mod writer;
 
 pub use parser::*;
 pub use properties::*;
+pub use reader::*;
+pub use writer::*;
 
 /// Error types for the PLY module.
 #[derive(Debug, thiserror::Error)]
@@ -18,4 +22,12 @@ pub enum PlyError {
     /// Unsupported PLY property
     #[error("Unsupported PLY property")]
     UnsupportedProperty,
+
+    /// Invalid PLY header
+    #[error("Invalid PLY header: {0}")]
+    InvalidHeader(String),
+
+    /// Invalid PLY data
+    #[error("Invalid PLY data: {0}")]
+    InvalidData(String),
 }
@@ -0,0 +1,294 @@
+use std::io::BufRead;
+use std::path::Path;
+
+use super::PlyError;
+use crate::pointcloud::PointCloud;
+
+/// The encoding of the data of a PLY file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlyFormat {
+    /// Human readable text.
+    Ascii,
+    /// Binary little endian.
+    BinaryLittleEndian,
+    /// Binary big endian.
+    BinaryBigEndian,
+}
+
+// the scalar types of the PLY properties
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ScalarType {
+    I8,
+    U8,
+    I16,
+    U16,
+    I32,
+    U32,
+    F32,
+    F64,
+}
+
+impl ScalarType {
+    fn parse(name: &str) -> Result<Self, PlyError> {
+        Ok(match name {
+            "char" | "int8" => Self::I8,
+            "uchar" | "uint8" => Self::U8,
+            "short" | "int16" => Self::I16,
+            "ushort" | "uint16" => Self::U16,
+            "int" | "int32" => Self::I32,
+            "uint" | "uint32" => Self::U32,
+            "float" | "float32" => Self::F32,
+            "double" | "float64" => Self::F64,
+            _ => return Err(PlyError::InvalidHeader(format!("unknown type {name}"))),
+        })
+    }
+
+    fn size(&self) -> usize {
+        match self {
+            Self::I8 | Self::U8 => 1,
+            Self::I16 | Self::U16 => 2,
+            Self::I32 | Self::U32 | Self::F32 => 4,
+            Self::F64 => 8,
+        }
+    }
+
+    fn read(&self, bytes: &[u8], format: PlyFormat) -> f64 {
+        macro_rules! from_bytes {
+            ($t:ty) => {{
+                let bytes = bytes.try_into().unwrap_or_default();
+                match format {
+                    PlyFormat::BinaryBigEndian => <$t>::from_be_bytes(bytes) as f64,
+                    _ => <$t>::from_le_bytes(bytes) as f64,
+                }
+            }};
+        }
+        match self {
+            Self::I8 => bytes[0] as i8 as f64,
+            Self::U8 => bytes[0] as f64,
+            Self::I16 => from_bytes!(i16),
+            Self::U16 => from_bytes!(u16),
+            Self::I32 => from_bytes!(i32),
+            Self::U32 => from_bytes!(u32),
+            Self::F32 => from_bytes!(f32),
+            Self::F64 => from_bytes!(f64),
+        }
+    }
+}
+
+#[derive(Debug)]
+enum PropertyKind {
+    Scalar(ScalarType),
+    List(ScalarType, ScalarType),
+}
+
+#[derive(Debug)]
+struct Element {
+    name: String,
+    count: usize,
+    properties: Vec<(String, PropertyKind)>,
+}
+
+fn parse_header(reader: &mut impl BufRead) -> Result<(PlyFormat, Vec<Element>), PlyError> {
+    let mut line = String::new();
+    reader.read_line(&mut line)?;
+    if line.trim_end() != "ply" {
+        return Err(PlyError::InvalidHeader("missing magic number".to_string()));
+    }
+
+    let mut format = None;
+    let mut elements: Vec<Element> = Vec::new();
+    loop {
+        line.clear();
+        if reader.read_line(&mut line)? == 0 {
+            return Err(PlyError::InvalidHeader("missing end_header".to_string()));
+        }
+        let tokens = line.split_whitespace().collect::<Vec<_>>();
+        match tokens.as_slice() {
+            ["format", name, _] => {
+                format = Some(match *name {
+                    "ascii" => PlyFormat::Ascii,
+                    "binary_little_endian" => PlyFormat::BinaryLittleEndian,
+                    "binary_big_endian" => PlyFormat::BinaryBigEndian,
+                    _ => return Err(PlyError::InvalidHeader(format!("unknown format {name}"))),
+                })
+            }
+            ["element", name, count] => elements.push(Element {
+                name: name.to_string(),
+                count: count
+                    .parse()
+                    .map_err(|_| PlyError::InvalidHeader(format!("invalid count {count}")))?,
+                properties: Vec::new(),
+            }),
+            ["property", "list", count_type, item_type, name] => elements
+                .last_mut()
+                .ok_or_else(|| PlyError::InvalidHeader("property without element".to_string()))?
+                .properties
+                .push((
+                    name.to_string(),
+                    PropertyKind::List(
+                        ScalarType::parse(count_type)?,
+                        ScalarType::parse(item_type)?,
+                    ),
+                )),
+            ["property", scalar_type, name] => elements
+                .last_mut()
+                .ok_or_else(|| PlyError::InvalidHeader("property without element".to_string()))?
+                .properties
+                .push((
+                    name.to_string(),
+                    PropertyKind::Scalar(ScalarType::parse(scalar_type)?),
+                )),
+            ["end_header"] => break,
+            _ => {}
+        }
+    }
+
+    let format = format.ok_or_else(|| PlyError::InvalidHeader("missing format".to_string()))?;
+    Ok((format, elements))
+}
+
+// read the scalar values of a row of an element, skipping the lists
+fn read_row(
+    reader: &mut impl BufRead,
+    element: &Element,
+    format: PlyFormat,
+    values: &mut Vec<f64>,
+) -> Result<(), PlyError> {
+    values.clear();
+
+    if format == PlyFormat::Ascii {
+        let mut line = String::new();
+        if reader.read_line(&mut line)? == 0 {
+            return Err(PlyError::Io(std::io::ErrorKind::UnexpectedEof.into()));
+        }
+        let mut tokens = line.split_whitespace().map(|t| {
+            t.parse::<f64>()
+                .map_err(|_| PlyError::InvalidData(format!("invalid value {t}")))
+        });
+        let mut next = || {
+            tokens
+                .next()
+                .unwrap_or_else(|| Err(PlyError::InvalidData("missing value".to_string())))
+        };
+        for (_, kind) in &element.properties {
+            match kind {
+                PropertyKind::Scalar(_) => values.push(next()?),
+                PropertyKind::List(_, _) => {
+                    for _ in 0..next()? as usize {
+                        next()?;
+                    }
+                }
+            }
+        }
+        return Ok(());
+    }
+
+    let mut buffer = [0u8; 8];
+    for (_, kind) in &element.properties {
+        match kind {
+            PropertyKind::Scalar(t) => {
+                reader.read_exact(&mut buffer[..t.size()])?;
+                values.push(t.read(&buffer[..t.size()], format));
+            }
+            PropertyKind::List(count_type, item_type) => {
+                reader.read_exact(&mut buffer[..count_type.size()])?;
+                let count = count_type.read(&buffer[..count_type.size()], format) as usize;
+                let mut skip = vec![0u8; count * item_type.size()];
+                reader.read_exact(&mut skip)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Read a point cloud from a PLY file in ascii or binary format.
+///
+/// The points are read from the `x`, `y` and `z` properties of the `vertex` element, the
+/// normals from `nx`, `ny` and `nz` and the colors from `red`, `green` and `blue`, either
+/// as 8-bit integers or as floats in the range [0, 1]. The other properties and elements,
+/// e.g. the faces of a mesh, are ignored.
+///
+/// # Arguments
+///
+/// * `path` - The path to the PLY file.
+///
+/// # Returns
+///
+/// A `PointCloud` with the points and, if available, the colors and normals.
+pub fn read_ply(path: impl AsRef<Path>) -> Result<PointCloud, PlyError> {
+    let file = std::fs::File::open(path)?;
+    let mut reader = std::io::BufReader::new(file);
+    let (format, elements) = parse_header(&mut reader)?;
+
+    let mut values = Vec::new();
+    for element in &elements {
+        if element.name != "vertex" {
+            // skip the elements before the vertices
+            for _ in 0..element.count {
+                read_row(&mut reader, element, format, &mut values)?;
+            }
+            continue;
+        }
+
+        // the index of a property among the scalar properties
+        let scalars = element
+            .properties
+            .iter()
+            .filter(|(_, kind)| matches!(kind, PropertyKind::Scalar(_)))
+            .collect::<Vec<_>>();
+        let find = |names: &[&str]| {
+            scalars
+                .iter()
+                .position(|(n, _)| names.contains(&n.as_str()))
+        };
+        let find_all = |names: [&[&str]; 3]| -> Option<[usize; 3]> {
+            Some([find(names[0])?, find(names[1])?, find(names[2])?])
+        };
+
+        let xyz = find_all([&["x"], &["y"], &["z"]]).ok_or(PlyError::UnsupportedProperty)?;
+        let nxyz = find_all([&["nx"], &["ny"], &["nz"]]);
+        let rgb = find_all([
+            &["red", "r", "diffuse_red"],
+            &["green", "g", "diffuse_green"],
+            &["blue", "b", "diffuse_blue"],
+        ]);
+        let float_colors = rgb.is_some_and(|rgb| {
+            matches!(
+                scalars[rgb[0]].1,
+                PropertyKind::Scalar(ScalarType::F32 | ScalarType::F64)
+            )
+        });
+
+        let mut points = Vec::with_capacity(element.count);
+        let mut normals = Vec::with_capacity(nxyz.map_or(0, |_| element.count));
+        let mut colors = Vec::with_capacity(rgb.map_or(0, |_| element.count));
+        for _ in 0..element.count {
+            read_row(&mut reader, element, format, &mut values)?;
+            points.push(xyz.map(|i| values[i]));
+            if let Some(nxyz) = nxyz {
+                normals.push(nxyz.map(|i| values[i]));
+            }
+            if let Some(rgb) = rgb {
+                colors.push(rgb.map(|i| {
+                    let v = if float_colors {
+                        values[i] * 255.0
+                    } else {
+                        values[i]
+                    };
+                    v.round().clamp(0.0, 255.0) as u8
+                }));
+            }
+        }
+
+        return Ok(PointCloud::new(
+            points,
+            rgb.map(|_| colors),
+            nxyz.map(|_| normals),
+        ));
+    }
+
+    Err(PlyError::InvalidHeader(
+        "missing vertex element".to_string(),
+    ))
+}
@@ -0,0 +1,158 @@
+use std::io::Write;
+use std::path::Path;
+
+use super::{PlyError, PlyFormat};
+use crate::pointcloud::PointCloud;
+
+/// Write a point cloud to a PLY file in ascii or binary format.
+///
+/// The points and normals are written as doubles and the colors as 8-bit integers.
+///
+/// # Arguments
+///
+/// * `path` - The path to the PLY file.
+/// * `pointcloud` - The point cloud to write with its colors and normals if available.
+/// * `format` - The encoding of the data.
+///
+/// # Example
+///
+/// ```no_run
+/// use kornia_3d::io::ply::{write_ply, PlyFormat};
+/// use kornia_3d::pointcloud::PointCloud;
+///
+/// let pointcloud = PointCloud::new(vec![[0.0, 0.0, 1.0]], Some(vec![[255, 0, 0]]), None);
+/// write_ply("cloud.ply", &pointcloud, PlyFormat::BinaryLittleEndian).unwrap();
+/// ```
+pub fn write_ply(
+    path: impl AsRef<Path>,
+    pointcloud: &PointCloud,
+    format: PlyFormat,
+) -> Result<(), PlyError> {
+    let file = std::fs::File::create(path)?;
+    let mut writer = std::io::BufWriter::new(file);
+
+    let colors = pointcloud.colors();
+    let normals = pointcloud.normals();
+
+    // write the header
+    let format_name = match format {
+        PlyFormat::Ascii => "ascii",
+        PlyFormat::BinaryLittleEndian => "binary_little_endian",
+        PlyFormat::BinaryBigEndian => "binary_big_endian",
+    };
+    writeln!(writer, "ply")?;
+    writeln!(writer, "format {format_name} 1.0")?;
+    writeln!(writer, "comment generated by kornia")?;
+    writeln!(writer, "element vertex {}", pointcloud.len())?;
+    for name in ["x", "y", "z"] {
+        writeln!(writer, "property double {name}")?;
+    }
+    if normals.is_some() {
+        for name in ["nx", "ny", "nz"] {
+            writeln!(writer, "property double {name}")?;
+        }
+    }
+    if colors.is_some() {
+        for name in ["red", "green", "blue"] {
+            writeln!(writer, "property uchar {name}")?;
+        }
+    }
+    writeln!(writer, "end_header")?;
+
+    // write the vertices
+    for (i, point) in pointcloud.points().iter().enumerate() {
+        let normal = normals.map(|normals| normals[i]);
+        let color = colors.map(|colors| colors[i]);
+
+        if format == PlyFormat::Ascii {
+            let mut fields = point.iter().map(|v| v.to_string()).collect::<Vec<_>>();
+            fields.extend(normal.iter().flatten().map(|v| v.to_string()));
+            fields.extend(color.iter().flatten().map(|v| v.to_string()));
+            writeln!(writer, "{}", fields.join(" "))?;
+            continue;
+        }
+
+        let to_bytes = |v: f64| match format {
+            PlyFormat::BinaryBigEndian => v.to_be_bytes(),
+            _ => v.to_le_bytes(),
+        };
+        for v in point.iter().chain(normal.iter().flatten()) {
+            writer.write_all(&to_bytes(*v))?;
+        }
+        if let Some(color) = color {
+            writer.write_all(&color)?;
+        }
+    }
+
+    writer.flush()?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io::ply::read_ply;
+
+    #[test]
+    fn test_write_read_ply() -> Result<(), PlyError> {
+        let tmp_dir = tempfile::tempdir()?;
+
+        let pointcloud = PointCloud::new(
+            vec![[0.1, -2.0, 3.5], [1e-3, 0.0, 1e6]],
+            Some(vec![[255, 0, 10], [1, 2, 3]]),
+            Some(vec![[0.0, 0.0, 1.0], [0.6, 0.8, 0.0]]),
+        );
+
+        for format in [
+            PlyFormat::Ascii,
+            PlyFormat::BinaryLittleEndian,
+            PlyFormat::BinaryBigEndian,
+        ] {
+            let path = tmp_dir.path().join("cloud.ply");
+            write_ply(&path, &pointcloud, format)?;
+
+            let read = read_ply(&path)?;
+            assert_eq!(read.points(), pointcloud.points());
+            assert_eq!(read.colors(), pointcloud.colors());
+            assert_eq!(read.normals(), pointcloud.normals());
+        }
+
+        let path = tmp_dir.path().join("points.ply");
+        let points_only = PointCloud::new(vec![[1.0, 2.0, 3.0]], None, None);
+        write_ply(&path, &points_only, PlyFormat::Ascii)?;
+        let read = read_ply(&path)?;
+        assert_eq!(read.points(), points_only.points());
+        assert!(read.colors().is_none() && read.normals().is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_ply_mesh_float_colors() -> Result<(), PlyError> {
+        let tmp_dir = tempfile::tempdir()?;
+        let path = tmp_dir.path().join("mesh.ply");
+        std::fs::write(
+            &path,
+            "ply\nformat ascii 1.0\ncomment a mesh\nelement camera 1\nproperty float fx\n\
+             element vertex 3\nproperty float x\nproperty float y\nproperty float z\n\
+             property float r\nproperty float g\nproperty float b\n\
+             element face 1\nproperty list uchar int vertex_indices\nend_header\n\
+             500\n0 0 0 1 0 0\n1 0 0 0 1 0\n0 1 0 0 0 0.5\n3 0 1 2\n",
+        )?;
+
+        let read = read_ply(&path)?;
+        assert_eq!(read.len(), 3);
+        assert_eq!(read.points()[2], [0.0, 1.0, 0.0]);
+        assert_eq!(
+            read.colors(),
+            Some(&vec![[255, 0, 0], [0, 255, 0], [0, 0, 128]])
+        );
+        assert!(read.normals().is_none());
+
+        std::fs::write(&path, "ply\nformat ascii 1.0\nend_header\n")?;
+        assert!(matches!(read_ply(&path), Err(PlyError::InvalidHeader(_))));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,346 @@
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+/// The maximum number of points stored in a leaf of the tree.
+const LEAF_SIZE: usize = 16;
+
+/// The minimum number of points of a subtree to build its children in parallel.
+const PARALLEL_THRESHOLD: usize = 4096;
+
+/// A neighbor returned by the queries of the [`KdTree`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Neighbor {
+    /// The index of the point in the slice used to build the tree.
+    pub index: usize,
+    /// The squared euclidean distance to the query point.
+    pub distance_sq: f64,
+}
+
+impl Eq for Neighbor {}
+
+impl PartialOrd for Neighbor {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Neighbor {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.distance_sq
+            .total_cmp(&other.distance_sq)
+            .then(self.index.cmp(&other.index))
+    }
+}
+
+/// A KD-tree for nearest neighbor searches over a set of points of dimension `D`.
+///
+/// The tree is stored implicitly in a permutation of the point indices where each subtree
+/// occupies a contiguous range split in two halves at its middle.
+/// The subtrees are split along the dimension with the largest spread and large subtrees
+/// are built in parallel.
+///
+/// # Example
+///
+/// ```
+/// use kornia_3d::kdtree::KdTree;
+///
+/// let points = vec![[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 2.0, 0.0]];
+/// let tree = KdTree::new(&points);
+///
+/// let nearest = tree.nearest(&[0.9, 0.1, 0.0]).unwrap();
+/// assert_eq!(nearest.index, 1);
+///
+/// let neighbors = tree.within_radius(&[0.0, 0.0, 0.0], 1.5);
+/// assert_eq!(neighbors.len(), 2);
+/// ```
+#[derive(Debug, Clone)]
+pub struct KdTree<const D: usize> {
+    points: Vec<[f64; D]>,
+    // the permutation of the point indices defining the tree
+    indices: Vec<usize>,
+    // the splitting dimension and value of the node at the middle of each range
+    splits: Vec<(u8, f64)>,
+}
+
+impl<const D: usize> KdTree<D> {
+    /// Build a KD-tree from a set of points.
+    ///
+    /// # Arguments
+    ///
+    /// * `points` - The points to index. The queries return indices into this slice.
+    ///
+    /// # Returns
+    ///
+    /// The KD-tree owning a copy of the points.
+    pub fn new(points: &[[f64; D]]) -> Self {
+        let mut indices = (0..points.len()).collect::<Vec<_>>();
+        let mut splits = vec![(0, 0.0); points.len()];
+        build(points, &mut indices, &mut splits);
+
+        Self {
+            points: points.to_vec(),
+            indices,
+            splits,
+        }
+    }
+
+    /// Get the number of points in the tree.
+    pub fn len(&self) -> usize {
+        self.points.len()
+    }
+
+    /// Check if the tree is empty.
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+
+    /// Get the points indexed by the tree.
+    pub fn points(&self) -> &[[f64; D]] {
+        &self.points
+    }
+
+    /// Find the nearest point to a query point.
+    ///
+    /// # Arguments
+    ///
+    /// * `query` - The query point.
+    ///
+    /// # Returns
+    ///
+    /// The nearest neighbor or None if the tree is empty.
+    pub fn nearest(&self, query: &[f64; D]) -> Option<Neighbor> {
+        self.knn(query, 1).pop()
+    }
+
+    /// Find the k nearest points to a query point.
+    ///
+    /// # Arguments
+    ///
+    /// * `query` - The query point.
+    /// * `k` - The number of neighbors to find.
+    ///
+    /// # Returns
+    ///
+    /// Up to `k` neighbors sorted by increasing distance.
+    pub fn knn(&self, query: &[f64; D], k: usize) -> Vec<Neighbor> {
+        if k == 0 {
+            return Vec::new();
+        }
+
+        // max-heap with the k best candidates found so far
+        let mut heap = BinaryHeap::with_capacity(k + 1);
+        self.search_knn(query, k, 0, self.indices.len(), &mut heap);
+
+        heap.into_sorted_vec()
+    }
+
+    /// Find all the points within a radius of a query point.
+    ///
+    /// # Arguments
+    ///
+    /// * `query` - The query point.
+    /// * `radius` - The search radius, inclusive.
+    ///
+    /// # Returns
+    ///
+    /// The neighbors sorted by increasing distance.
+    pub fn within_radius(&self, query: &[f64; D], radius: f64) -> Vec<Neighbor> {
+        let mut neighbors = Vec::new();
+        self.search_radius(
+            query,
+            radius * radius,
+            0,
+            self.indices.len(),
+            &mut neighbors,
+        );
+
+        neighbors.sort_unstable();
+        neighbors
+    }
+
+    fn search_knn(
+        &self,
+        query: &[f64; D],
+        k: usize,
+        lo: usize,
+        hi: usize,
+        heap: &mut BinaryHeap<Neighbor>,
+    ) {
+        if hi - lo <= LEAF_SIZE {
+            for &index in &self.indices[lo..hi] {
+                let distance_sq = distance_sq(&self.points[index], query);
+                if heap.len() < k {
+                    heap.push(Neighbor { index, distance_sq });
+                } else if heap
+                    .peek()
+                    .is_some_and(|worst| distance_sq < worst.distance_sq)
+                {
+                    heap.pop();
+                    heap.push(Neighbor { index, distance_sq });
+                }
+            }
+            return;
+        }
+
+        let mid = (lo + hi) / 2;
+        let (dim, value) = self.splits[mid];
+        let diff = query[dim as usize] - value;
+
+        // visit first the side of the query and then the other side if it can improve
+        let (near, far) = if diff < 0.0 {
+            ((lo, mid), (mid, hi))
+        } else {
+            ((mid, hi), (lo, mid))
+        };
+        self.search_knn(query, k, near.0, near.1, heap);
+
+        let worst = match heap.peek() {
+            Some(worst) if heap.len() == k => worst.distance_sq,
+            _ => f64::INFINITY,
+        };
+        if diff * diff <= worst {
+            self.search_knn(query, k, far.0, far.1, heap);
+        }
+    }
+
+    fn search_radius(
+        &self,
+        query: &[f64; D],
+        radius_sq: f64,
+        lo: usize,
+        hi: usize,
+        neighbors: &mut Vec<Neighbor>,
+    ) {
+        if hi - lo <= LEAF_SIZE {
+            neighbors.extend(self.indices[lo..hi].iter().filter_map(|&index| {
+                let distance_sq = distance_sq(&self.points[index], query);
+                (distance_sq <= radius_sq).then_some(Neighbor { index, distance_sq })
+            }));
+            return;
+        }
+
+        let mid = (lo + hi) / 2;
+        let (dim, value) = self.splits[mid];
+        let diff = query[dim as usize] - value;
+
+        if diff < 0.0 || diff * diff <= radius_sq {
+            self.search_radius(query, radius_sq, lo, mid, neighbors);
+        }
+        if diff >= 0.0 || diff * diff <= radius_sq {
+            self.search_radius(query, radius_sq, mid, hi, neighbors);
+        }
+    }
+}
+
+// sort recursively the indices so that each range is split at its middle element
+fn build<const D: usize>(points: &[[f64; D]], indices: &mut [usize], splits: &mut [(u8, f64)]) {
+    if indices.len() <= LEAF_SIZE {
+        return;
+    }
+
+    // split along the dimension with the largest spread
+    let mut min = [f64::INFINITY; D];
+    let mut max = [f64::NEG_INFINITY; D];
+    for &i in indices.iter() {
+        for d in 0..D {
+            min[d] = min[d].min(points[i][d]);
+            max[d] = max[d].max(points[i][d]);
+        }
+    }
+    let dim = (0..D)
+        .max_by(|&a, &b| (max[a] - min[a]).total_cmp(&(max[b] - min[b])))
+        .unwrap_or(0);
+
+    let mid = indices.len() / 2;
+    indices.select_nth_unstable_by(mid, |&a, &b| points[a][dim].total_cmp(&points[b][dim]));
+    // the slot in the middle is not used by the nodes of the children
+    splits[mid] = (dim as u8, points[indices[mid]][dim]);
+
+    let (left, right) = indices.split_at_mut(mid);
+    let (left_splits, right_splits) = splits.split_at_mut(mid);
+    if left.len() + right.len() >= PARALLEL_THRESHOLD {
+        rayon::join(
+            || build(points, left, left_splits),
+            || build(points, right, right_splits),
+        );
+    } else {
+        build(points, left, left_splits);
+        build(points, right, right_splits);
+    }
+}
+
+fn distance_sq<const D: usize>(a: &[f64; D], b: &[f64; D]) -> f64 {
+    a.iter().zip(b.iter()).map(|(x, y)| (x - y) * (x - y)).sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // a deterministic cloud of points with a linear congruential generator
+    fn random_points<const D: usize>(num_points: usize) -> Vec<[f64; D]> {
+        let mut state = 12345u64;
+        (0..num_points)
+            .map(|_| {
+                std::array::from_fn(|_| {
+                    state = state.wrapping_mul(6364136223846793005).wrapping_add(1);
+                    (state >> 11) as f64 / (1u64 << 53) as f64
+                })
+            })
+            .collect()
+    }
+
+    fn brute_force<const D: usize>(points: &[[f64; D]], query: &[f64; D]) -> Vec<Neighbor> {
+        let mut neighbors = points
+            .iter()
+            .enumerate()
+            .map(|(index, p)| Neighbor {
+                index,
+                distance_sq: distance_sq(p, query),
+            })
+            .collect::<Vec<_>>();
+        neighbors.sort_unstable();
+        neighbors
+    }
+
+    #[test]
+    fn test_knn_matches_brute_force() {
+        let points = random_points::<3>(10000);
+        let tree = KdTree::new(&points);
+        assert_eq!(tree.len(), points.len());
+
+        for query in random_points::<3>(20) {
+            let expected = brute_force(&points, &query);
+            assert_eq!(tree.nearest(&query), Some(expected[0]));
+            assert_eq!(tree.knn(&query, 10), expected[..10]);
+        }
+    }
+
+    #[test]
+    fn test_radius_matches_brute_force() {
+        let points = random_points::<2>(1000);
+        let tree = KdTree::new(&points);
+
+        for query in random_points::<2>(20) {
+            let expected = brute_force(&points, &query)
+                .into_iter()
+                .filter(|n| n.distance_sq <= 0.01)
+                .collect::<Vec<_>>();
+            assert_eq!(tree.within_radius(&query, 0.1), expected);
+        }
+    }
+
+    #[test]
+    fn test_small_and_empty() {
+        let tree = KdTree::<2>::new(&[]);
+        assert!(tree.is_empty());
+        assert_eq!(tree.nearest(&[0.0, 0.0]), None);
+        assert!(tree.within_radius(&[0.0, 0.0], 1.0).is_empty());
+
+        let points = vec![[0.0, 0.0], [1.0, 1.0], [1.0, 1.0]];
+        let tree = KdTree::new(&points);
+        assert_eq!(tree.knn(&[2.0, 2.0], 5).len(), 3);
+        assert!(tree.knn(&[2.0, 2.0], 0).is_empty());
+        assert_eq!(tree.nearest(&[-1.0, 0.0]).map(|n| n.index), Some(0));
+    }
+}
@@ -1,21 +1,51 @@
 #![deny(missing_docs)]
 #![doc = env!("CARGO_PKG_DESCRIPTION")]
 
+/// Conversions between depth images and point clouds.
+pub mod depth;
+
 /// I/O utilities for reading and writing 3D data.
 pub mod io;
 
+/// KD-tree for nearest neighbor searches.
+pub mod kdtree;
+
 /// Linear algebra utilities.
 pub mod linalg;
 
+/// Triangle mesh representation.
+pub mod mesh;
+
+/// Normal estimation for point clouds and depth images.
+pub mod normals;
+
+/// Dense RGB-D visual odometry.
+pub mod odometry;
+
 /// Operations on 3D data processing.
 pub mod ops;
 
 /// Point cloud traits.
 pub mod pointcloud;
 
+/// Geometric primitives fitting with RANSAC.
+pub mod primitives;
+
 /// Pose estimation algorithms.
 pub mod pose;
 
+/// Pose graph optimization and rotation averaging.
+pub mod pose_graph;
+
+/// Robust estimation with random sample consensus.
+pub mod ransac;
+
+/// Stereo disparity reprojection to depth and point clouds.
+pub mod stereo;
+
+/// Truncated signed distance function volume for depth fusion.
+pub mod tsdf;
+
 /// 3D transforms algorithms.
 pub mod transforms;
 
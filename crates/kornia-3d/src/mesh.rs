@@ -0,0 +1,60 @@
+/// A triangle mesh with vertices, colors and triangles.
+#[derive(Debug, Clone)]
+pub struct TriangleMesh {
+    // The vertices of the mesh.
+    vertices: Vec<[f64; 3]>,
+    // The colors of the vertices.
+    colors: Option<Vec<[u8; 3]>>,
+    // The triangles as indices of the vertices in counter-clockwise order.
+    triangles: Vec<[usize; 3]>,
+}
+
+impl TriangleMesh {
+    /// Create a new triangle mesh from vertices, colors (optional), and triangles.
+    pub fn new(
+        vertices: Vec<[f64; 3]>,
+        colors: Option<Vec<[u8; 3]>>,
+        triangles: Vec<[usize; 3]>,
+    ) -> Self {
+        Self {
+            vertices,
+            colors,
+            triangles,
+        }
+    }
+
+    /// Get the number of vertices in the mesh.
+    #[inline]
+    pub fn num_vertices(&self) -> usize {
+        self.vertices.len()
+    }
+
+    /// Get the number of triangles in the mesh.
+    #[inline]
+    pub fn num_triangles(&self) -> usize {
+        self.triangles.len()
+    }
+
+    /// Check if the mesh has no triangles.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.triangles.is_empty()
+    }
+
+    /// Get as reference the vertices of the mesh.
+    pub fn vertices(&self) -> &Vec<[f64; 3]> {
+        &self.vertices
+    }
+
+    /// Get as reference the colors of the vertices of the mesh.
+    pub fn colors(&self) -> Option<&Vec<[u8; 3]>> {
+        self.colors.as_ref()
+    }
+
+    /// Get as reference the triangles of the mesh.
+    ///
+    /// The vertices of each triangle are in counter-clockwise order seen from the front.
+    pub fn triangles(&self) -> &Vec<[usize; 3]> {
+        &self.triangles
+    }
+}
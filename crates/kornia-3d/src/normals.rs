@@ -0,0 +1,264 @@
+use rayon::prelude::*;
+
+use kornia_image::{Image, ImageError};
+use kornia_imgproc::calibration::CameraIntrinsic;
+
+use crate::{
+    depth::DepthSample,
+    kdtree::KdTree,
+    linalg::{cross_vec3, dot_product3},
+    pointcloud::PointCloud,
+};
+
+/// The neighborhood used to estimate the normal of a point.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NormalSearch {
+    /// The k nearest neighbors of the point, including the point itself.
+    Knn(usize),
+    /// The neighbors within a radius of the point.
+    Radius(f64),
+}
+
+/// Estimate the normals of a point cloud with the principal component analysis of the
+/// neighborhood of each point.
+///
+/// The normal is the eigenvector of the covariance of the neighborhood with the smallest
+/// eigenvalue, oriented towards the viewpoint. The points with less than three neighbors
+/// get a zero normal.
+///
+/// # Arguments
+///
+/// * `pointcloud` - The point cloud.
+/// * `search` - The neighborhood used for each point.
+/// * `viewpoint` - The position the normals are oriented towards, e.g. the sensor origin.
+///
+/// # Returns
+///
+/// The point cloud with the estimated unit normals.
+///
+/// # Example
+///
+/// ```
+/// use kornia_3d::normals::{estimate_normals, NormalSearch};
+/// use kornia_3d::pointcloud::PointCloud;
+///
+/// let points = (0..25).map(|i| [(i % 5) as f64, (i / 5) as f64, 0.0]).collect();
+/// let pointcloud = PointCloud::new(points, None, None);
+///
+/// let pointcloud = estimate_normals(&pointcloud, NormalSearch::Knn(8), &[0.0, 0.0, 10.0]);
+/// assert!(pointcloud.normals().unwrap().iter().all(|n| n[2] > 0.99));
+/// ```
+pub fn estimate_normals(
+    pointcloud: &PointCloud,
+    search: NormalSearch,
+    viewpoint: &[f64; 3],
+) -> PointCloud {
+    let points = pointcloud.points();
+    let kdtree = KdTree::new(points);
+
+    let normals = points
+        .par_iter()
+        .map(|p| {
+            let neighbors = match search {
+                NormalSearch::Knn(k) => kdtree.knn(p, k),
+                NormalSearch::Radius(radius) => kdtree.within_radius(p, radius),
+            };
+            if neighbors.len() < 3 {
+                return [0.0; 3];
+            }
+
+            let normal = smallest_principal_axis(neighbors.iter().map(|n| &points[n.index]));
+
+            // flip the normal to point towards the viewpoint
+            let to_viewpoint = [
+                viewpoint[0] - p[0],
+                viewpoint[1] - p[1],
+                viewpoint[2] - p[2],
+            ];
+            if dot_product3(&normal, &to_viewpoint) < 0.0 {
+                normal.map(|v| -v)
+            } else {
+                normal
+            }
+        })
+        .collect();
+
+    PointCloud::new(points.clone(), pointcloud.colors().cloned(), Some(normals))
+}
+
+// the eigenvector with the smallest eigenvalue of the covariance of the points
+pub(crate) fn smallest_principal_axis<'a>(
+    points: impl Iterator<Item = &'a [f64; 3]> + Clone,
+) -> [f64; 3] {
+    let mut centroid = [0.0; 3];
+    let mut count = 0;
+    for p in points.clone() {
+        for k in 0..3 {
+            centroid[k] += p[k];
+        }
+        count += 1;
+    }
+    let centroid = centroid.map(|v| v / count as f64);
+
+    let mut cov = faer::Mat::<f64>::zeros(3, 3);
+    for p in points {
+        let d = [p[0] - centroid[0], p[1] - centroid[1], p[2] - centroid[2]];
+        for i in 0..3 {
+            for j in 0..3 {
+                cov.write(i, j, cov.read(i, j) + d[i] * d[j]);
+            }
+        }
+    }
+
+    smallest_eigenvector(&cov)
+}
+
+// the eigenvector with the smallest eigenvalue of a symmetric 3x3 matrix
+pub(crate) fn smallest_eigenvector(m: &faer::Mat<f64>) -> [f64; 3] {
+    let eigen = m.selfadjoint_eigendecomposition(faer::Side::Lower);
+    let s = eigen.s().column_vector();
+    let min_idx = (0..3)
+        .min_by(|&a, &b| s.read(a).total_cmp(&s.read(b)))
+        .unwrap_or(0);
+
+    let u = eigen.u();
+    [u.read(0, min_idx), u.read(1, min_idx), u.read(2, min_idx)]
+}
+
+/// Estimate the normals of a depth image with the cross product of the tangent vectors.
+///
+/// The tangent vectors are computed with central differences of the back-projected points
+/// and the normals are oriented towards the camera. The pixels at the border or with a
+/// missing neighbor get a zero normal.
+///
+/// # Arguments
+///
+/// * `depth` - The depth image.
+/// * `intrinsics` - The intrinsic parameters of the depth camera.
+/// * `depth_scale` - The scale to convert the depth values to meters.
+///
+/// # Returns
+///
+/// The image with the unit normals in the camera frame.
+pub fn estimate_depth_normals<T: DepthSample + Sync>(
+    depth: &Image<T, 1>,
+    intrinsics: &CameraIntrinsic,
+    depth_scale: f64,
+) -> Result<Image<f32, 3>, ImageError> {
+    let (cols, rows) = (depth.cols(), depth.rows());
+    let mut normals = Image::from_size_val(depth.size(), 0.0f32)?;
+
+    // back-project the pixel to the camera frame
+    let point_at = |u: usize, v: usize| {
+        let z = depth.as_slice()[v * cols + u].to_meters(depth_scale)?;
+        Some([
+            (u as f64 - intrinsics.cx) * z / intrinsics.fx,
+            (v as f64 - intrinsics.cy) * z / intrinsics.fy,
+            z,
+        ])
+    };
+
+    normals
+        .as_slice_mut()
+        .par_chunks_exact_mut(cols * 3)
+        .enumerate()
+        .filter(|(v, _)| *v > 0 && *v + 1 < rows)
+        .for_each(|(v, row)| {
+            for (u, normal) in row.chunks_exact_mut(3).enumerate().take(cols - 1).skip(1) {
+                let (Some(p), Some(left), Some(right), Some(up), Some(down)) = (
+                    point_at(u, v),
+                    point_at(u - 1, v),
+                    point_at(u + 1, v),
+                    point_at(u, v - 1),
+                    point_at(u, v + 1),
+                ) else {
+                    continue;
+                };
+
+                let du = [right[0] - left[0], right[1] - left[1], right[2] - left[2]];
+                let dv = [down[0] - up[0], down[1] - up[1], down[2] - up[2]];
+                let mut n = [0.0; 3];
+                cross_vec3(&du, &dv, &mut n);
+
+                let norm = dot_product3(&n, &n).sqrt();
+                if norm == 0.0 {
+                    continue;
+                }
+
+                // the camera is at the origin
+                let sign = if dot_product3(&n, &p) > 0.0 {
+                    -1.0
+                } else {
+                    1.0
+                };
+                for k in 0..3 {
+                    normal[k] = (sign * n[k] / norm) as f32;
+                }
+            }
+        });
+
+    Ok(normals)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn test_estimate_normals_plane() {
+        // a tilted plane z = 0.5 * x sampled on a grid
+        let points = (0..100)
+            .map(|i| {
+                let (x, y) = ((i % 10) as f64 * 0.1, (i / 10) as f64 * 0.1);
+                [x, y, 0.5 * x]
+            })
+            .collect();
+        let pointcloud = PointCloud::new(points, None, None);
+        let expected = [-0.5 / 1.25f64.sqrt(), 0.0, 1.0 / 1.25f64.sqrt()];
+
+        for search in [NormalSearch::Knn(10), NormalSearch::Radius(0.25)] {
+            let result = estimate_normals(&pointcloud, search, &[0.0, 0.0, 10.0]);
+            for n in result.normals().unwrap() {
+                for k in 0..3 {
+                    assert_relative_eq!(n[k], expected[k], epsilon = 1e-9);
+                }
+            }
+        }
+
+        // flip the viewpoint to flip the normals
+        let result = estimate_normals(&pointcloud, NormalSearch::Knn(10), &[0.0, 0.0, -10.0]);
+        assert!(result.normals().unwrap().iter().all(|n| n[2] < 0.0));
+
+        // not enough neighbors
+        let result = estimate_normals(&pointcloud, NormalSearch::Radius(0.01), &[0.0; 3]);
+        assert!(result.normals().unwrap().iter().all(|n| *n == [0.0; 3]));
+    }
+
+    #[test]
+    fn test_estimate_depth_normals() -> Result<(), ImageError> {
+        // a fronto-parallel plane at 2 meters with a missing measurement
+        let mut depth = Image::<u16, 1>::from_size_val([5, 4].into(), 2000)?;
+        depth.as_slice_mut()[2 * 5 + 3] = 0;
+
+        let intrinsics = CameraIntrinsic {
+            fx: 10.0,
+            fy: 10.0,
+            cx: 2.0,
+            cy: 1.5,
+        };
+        let normals = estimate_depth_normals(&depth, &intrinsics, 1e-3)?;
+
+        for v in 0..4 {
+            for u in 0..5 {
+                let n = &normals.as_slice()[(v * 5 + u) * 3..(v * 5 + u) * 3 + 3];
+                let interior = u > 0 && u < 4 && v > 0 && v < 3;
+                let valid = interior && ![(2, 2), (3, 1), (3, 2)].contains(&(u, v));
+                let expected = if valid { [0.0, 0.0, -1.0] } else { [0.0; 3] };
+                assert_eq!(n, &expected, "pixel ({u}, {v})");
+            }
+        }
+
+        Ok(())
+    }
+}
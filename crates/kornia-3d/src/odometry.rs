@@ -0,0 +1,502 @@
+use faer::prelude::SpSolver;
+
+use kornia_image::{Image, ImageError};
+use kornia_imgproc::calibration::CameraIntrinsic;
+
+use crate::{
+    depth::DepthSample,
+    linalg::{cross_vec3, dot_product3, matmul33},
+    transforms::axis_angle_to_rotation_matrix,
+};
+
+/// Parameters of the dense RGB-D odometry.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RgbdOdometryParams {
+    /// The number of levels of the image pyramid.
+    pub num_levels: usize,
+    /// The maximum number of Gauss-Newton iterations per level.
+    pub max_iterations: usize,
+    /// The maximum difference in meters between the warped and the target depth.
+    pub max_depth_diff: f64,
+    /// The weight of the depth residuals relative to the intensity residuals.
+    pub depth_weight: f64,
+    /// The minimum norm of the update to continue iterating.
+    pub tolerance: f64,
+}
+
+impl Default for RgbdOdometryParams {
+    fn default() -> Self {
+        Self {
+            num_levels: 3,
+            max_iterations: 20,
+            max_depth_diff: 0.07,
+            depth_weight: 1.0,
+            tolerance: 1e-6,
+        }
+    }
+}
+
+/// Result of the dense RGB-D odometry.
+///
+/// The transformation maps the points from the source to the target camera frame.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RgbdOdometryResult {
+    /// Estimated rotation matrix.
+    pub rotation: [[f64; 3]; 3],
+    /// Estimated translation vector.
+    pub translation: [f64; 3],
+    /// The total number of iterations performed over all the levels.
+    pub num_iterations: usize,
+    /// The RMSE of the intensity residuals at the finest level.
+    pub rmse: f64,
+    /// The ratio of valid source pixels with a correspondence at the finest level.
+    pub fitness: f64,
+}
+
+// a level of the pyramid with the intensities and the depth in meters, zero if missing
+struct Level {
+    cols: usize,
+    rows: usize,
+    gray: Vec<f32>,
+    depth: Vec<f32>,
+    intrinsics: CameraIntrinsic,
+}
+
+impl Level {
+    fn new<T: DepthSample>(
+        gray: &Image<f32, 1>,
+        depth: &Image<T, 1>,
+        intrinsics: &CameraIntrinsic,
+        depth_scale: f64,
+    ) -> Self {
+        Self {
+            cols: gray.cols(),
+            rows: gray.rows(),
+            gray: gray.as_slice().to_vec(),
+            depth: depth
+                .as_slice()
+                .iter()
+                .map(|d| d.to_meters(depth_scale).unwrap_or(0.0) as f32)
+                .collect(),
+            intrinsics: *intrinsics,
+        }
+    }
+
+    // halve the resolution averaging the blocks of 2x2 pixels, skipping the missing depths
+    fn downsample(&self) -> Self {
+        let (cols, rows) = (self.cols / 2, self.rows / 2);
+        let mut gray = Vec::with_capacity(cols * rows);
+        let mut depth = Vec::with_capacity(cols * rows);
+        for v in 0..rows {
+            for u in 0..cols {
+                let block = [(0, 0), (1, 0), (0, 1), (1, 1)]
+                    .map(|(du, dv)| (2 * v + dv) * self.cols + 2 * u + du);
+                gray.push(block.iter().map(|&i| self.gray[i]).sum::<f32>() / 4.0);
+
+                let valid = block
+                    .iter()
+                    .map(|&i| self.depth[i])
+                    .filter(|&d| d > 0.0)
+                    .collect::<Vec<_>>();
+                depth.push(if valid.is_empty() {
+                    0.0
+                } else {
+                    valid.iter().sum::<f32>() / valid.len() as f32
+                });
+            }
+        }
+
+        let k = &self.intrinsics;
+        Self {
+            cols,
+            rows,
+            gray,
+            depth,
+            intrinsics: CameraIntrinsic {
+                fx: k.fx / 2.0,
+                fy: k.fy / 2.0,
+                cx: (k.cx + 0.5) / 2.0 - 0.5,
+                cy: (k.cy + 0.5) / 2.0 - 0.5,
+            },
+        }
+    }
+
+    // bilinear interpolation of the intensity
+    fn gray_at(&self, u: f64, v: f64) -> Option<f64> {
+        let taps = self.taps(u, v)?;
+        Some(taps.iter().map(|&(i, w)| self.gray[i] as f64 * w).sum())
+    }
+
+    // bilinear interpolation of the depth if all the neighbors are valid
+    fn depth_at(&self, u: f64, v: f64) -> Option<f64> {
+        let taps = self.taps(u, v)?;
+        taps.iter()
+            .map(|&(i, w)| (self.depth[i] > 0.0).then_some(self.depth[i] as f64 * w))
+            .sum()
+    }
+
+    // the indices and weights of the four neighbors of a subpixel location
+    fn taps(&self, u: f64, v: f64) -> Option<[(usize, f64); 4]> {
+        if !(u >= 0.0 && v >= 0.0 && u + 1.0 < self.cols as f64 && v + 1.0 < self.rows as f64) {
+            return None;
+        }
+        let (u0, v0) = (u.floor(), v.floor());
+        let (a, b) = (u - u0, v - v0);
+        let i = v0 as usize * self.cols + u0 as usize;
+        Some([
+            (i, (1.0 - a) * (1.0 - b)),
+            (i + 1, a * (1.0 - b)),
+            (i + self.cols, (1.0 - a) * b),
+            (i + self.cols + 1, a * b),
+        ])
+    }
+}
+
+// a residual with its jacobian with respect to the twist (omega, v)
+struct Residual {
+    jacobian: [f64; 6],
+    value: f64,
+}
+
+/// Estimate the motion between two RGB-D frames with dense direct alignment.
+///
+/// The method minimizes jointly the photometric error of the intensities and the geometric
+/// error of the depths of the source pixels warped to the target frame, with Gauss-Newton
+/// iterations over an image pyramid from coarse to fine. The residuals are weighted with the
+/// Huber loss with a scale estimated from their median absolute deviation.
+///
+/// # Arguments
+///
+/// * `source_gray` - The intensity image of the source frame.
+/// * `source_depth` - The depth image of the source frame aligned to its intensities.
+/// * `target_gray` - The intensity image of the target frame.
+/// * `target_depth` - The depth image of the target frame aligned to its intensities.
+/// * `intrinsics` - The intrinsic parameters of the camera.
+/// * `depth_scale` - The scale to convert the depth values to meters.
+/// * `params` - The parameters of the odometry.
+///
+/// # Returns
+///
+/// The transformation from the source to the target camera frame.
+pub fn rgbd_odometry<T: DepthSample>(
+    source_gray: &Image<f32, 1>,
+    source_depth: &Image<T, 1>,
+    target_gray: &Image<f32, 1>,
+    target_depth: &Image<T, 1>,
+    intrinsics: &CameraIntrinsic,
+    depth_scale: f64,
+    params: &RgbdOdometryParams,
+) -> Result<RgbdOdometryResult, ImageError> {
+    for image_size in [source_depth.size(), target_gray.size(), target_depth.size()] {
+        if image_size != source_gray.size() {
+            return Err(ImageError::InvalidImageSize(
+                image_size.width,
+                image_size.height,
+                source_gray.cols(),
+                source_gray.rows(),
+            ));
+        }
+    }
+
+    // build the pyramids from fine to coarse
+    let mut source = vec![Level::new(
+        source_gray,
+        source_depth,
+        intrinsics,
+        depth_scale,
+    )];
+    let mut target = vec![Level::new(
+        target_gray,
+        target_depth,
+        intrinsics,
+        depth_scale,
+    )];
+    for _ in 1..params.num_levels {
+        let (s, t) = (&source[source.len() - 1], &target[target.len() - 1]);
+        if s.cols < 16 || s.rows < 16 {
+            break;
+        }
+        let (s, t) = (s.downsample(), t.downsample());
+        source.push(s);
+        target.push(t);
+    }
+
+    let mut result = RgbdOdometryResult {
+        rotation: [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]],
+        translation: [0.0; 3],
+        num_iterations: 0,
+        rmse: f64::INFINITY,
+        fitness: 0.0,
+    };
+
+    for (source, target) in source.iter().zip(target.iter()).rev() {
+        for _ in 0..params.max_iterations {
+            let (photometric, geometric, fitness) = compute_residuals(
+                source,
+                target,
+                &result.rotation,
+                &result.translation,
+                params,
+            );
+            if photometric.len() < 6 {
+                break;
+            }
+
+            result.num_iterations += 1;
+            result.fitness = fitness;
+            result.rmse = (photometric.iter().map(|r| r.value * r.value).sum::<f64>()
+                / photometric.len() as f64)
+                .sqrt();
+
+            // accumulate the normal equations of the weighted residuals
+            let mut hessian = faer::Mat::<f64>::zeros(6, 6);
+            let mut gradient = faer::Mat::<f64>::zeros(6, 1);
+            for (residuals, weight) in [(&photometric, 1.0), (&geometric, params.depth_weight)] {
+                let huber_delta = 1.345 * robust_scale(residuals);
+                for r in residuals.iter() {
+                    let w = weight * huber_weight(r.value, huber_delta);
+                    for i in 0..6 {
+                        for j in 0..6 {
+                            hessian.write(
+                                i,
+                                j,
+                                hessian.read(i, j) + w * r.jacobian[i] * r.jacobian[j],
+                            );
+                        }
+                        gradient.write(i, 0, gradient.read(i, 0) - w * r.jacobian[i] * r.value);
+                    }
+                }
+            }
+
+            let delta = hessian.partial_piv_lu().solve(&gradient);
+            let delta = (0..6).map(|i| delta.read(i, 0)).collect::<Vec<_>>();
+            if delta.iter().any(|v| !v.is_finite()) {
+                break;
+            }
+
+            // compose the update on the left as R = R_delta * R and t = R_delta * t + t_delta
+            let omega = [delta[0], delta[1], delta[2]];
+            let angle = dot_product3(&omega, &omega).sqrt();
+            if let (true, Ok(rr_delta)) =
+                (angle > 0.0, axis_angle_to_rotation_matrix(&omega, angle))
+            {
+                let rotation = result.rotation;
+                matmul33(&rr_delta, &rotation, &mut result.rotation);
+                let t = result.translation;
+                result.translation = rr_delta.map(|row| dot_product3(&row, &t));
+            }
+            for k in 0..3 {
+                result.translation[k] += delta[3 + k];
+            }
+
+            if delta.iter().map(|v| v * v).sum::<f64>().sqrt() < params.tolerance {
+                break;
+            }
+        }
+    }
+
+    Ok(result)
+}
+
+// compute the photometric and geometric residuals of the source pixels warped to the target
+fn compute_residuals(
+    source: &Level,
+    target: &Level,
+    rotation: &[[f64; 3]; 3],
+    translation: &[f64; 3],
+    params: &RgbdOdometryParams,
+) -> (Vec<Residual>, Vec<Residual>, f64) {
+    let (ks, kt) = (&source.intrinsics, &target.intrinsics);
+    let mut photometric = Vec::new();
+    let mut geometric = Vec::new();
+    let mut num_valid = 0;
+
+    for (idx, &zs) in source.depth.iter().enumerate() {
+        if zs <= 0.0 {
+            continue;
+        }
+        num_valid += 1;
+
+        // warp the source pixel to the target frame
+        let zs = zs as f64;
+        let (us, vs) = ((idx % source.cols) as f64, (idx / source.cols) as f64);
+        let ps = [(us - ks.cx) * zs / ks.fx, (vs - ks.cy) * zs / ks.fy, zs];
+        let p =
+            std::array::from_fn::<_, 3, _>(|k| dot_product3(&rotation[k], &ps) + translation[k]);
+        if p[2] <= 0.0 {
+            continue;
+        }
+        let (u, v) = (kt.fx * p[0] / p[2] + kt.cx, kt.fy * p[1] / p[2] + kt.cy);
+
+        // the values and the central differences in the target image
+        let gray = [(0.0, 0.0), (-1.0, 0.0), (1.0, 0.0), (0.0, -1.0), (0.0, 1.0)]
+            .map(|(du, dv)| target.gray_at(u + du, v + dv));
+        let depth = [(0.0, 0.0), (-1.0, 0.0), (1.0, 0.0), (0.0, -1.0), (0.0, 1.0)]
+            .map(|(du, dv)| target.depth_at(u + du, v + dv));
+        let (Some(gray), Some(depth)) = (
+            gray.into_iter().collect::<Option<Vec<_>>>(),
+            depth.into_iter().collect::<Option<Vec<_>>>(),
+        ) else {
+            continue;
+        };
+        if (depth[0] - p[2]).abs() > params.max_depth_diff {
+            continue;
+        }
+
+        // the derivatives of the projection chained with the image gradients
+        let chain = |gu: f64, gv: f64| {
+            [
+                gu * kt.fx / p[2],
+                gv * kt.fy / p[2],
+                -(gu * kt.fx * p[0] + gv * kt.fy * p[1]) / (p[2] * p[2]),
+            ]
+        };
+        // the jacobian of a residual with gradient g with respect to the point is [p x g, g]
+        let jacobian = |g: [f64; 3]| {
+            let mut pxg = [0.0; 3];
+            cross_vec3(&p, &g, &mut pxg);
+            [pxg[0], pxg[1], pxg[2], g[0], g[1], g[2]]
+        };
+
+        let g = chain(0.5 * (gray[2] - gray[1]), 0.5 * (gray[4] - gray[3]));
+        photometric.push(Residual {
+            jacobian: jacobian(g),
+            value: gray[0] - source.gray[idx] as f64,
+        });
+
+        let mut g = chain(0.5 * (depth[2] - depth[1]), 0.5 * (depth[4] - depth[3]));
+        g[2] -= 1.0;
+        geometric.push(Residual {
+            jacobian: jacobian(g),
+            value: depth[0] - p[2],
+        });
+    }
+
+    let fitness = if num_valid > 0 {
+        photometric.len() as f64 / num_valid as f64
+    } else {
+        0.0
+    };
+
+    (photometric, geometric, fitness)
+}
+
+// the standard deviation of the residuals estimated with the median absolute deviation
+fn robust_scale(residuals: &[Residual]) -> f64 {
+    if residuals.is_empty() {
+        return 0.0;
+    }
+    let mut abs = residuals.iter().map(|r| r.value.abs()).collect::<Vec<_>>();
+    let mid = abs.len() / 2;
+    let (_, median, _) = abs.select_nth_unstable_by(mid, |a, b| a.total_cmp(b));
+    1.4826 * *median
+}
+
+fn huber_weight(residual: f64, delta: f64) -> f64 {
+    let abs = residual.abs();
+    if abs <= delta {
+        1.0
+    } else {
+        delta / abs
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    const INTRINSICS: CameraIntrinsic = CameraIntrinsic {
+        fx: 60.0,
+        fy: 60.0,
+        cx: 39.5,
+        cy: 29.5,
+    };
+
+    // render a textured plane at z = 1 in the world frame from a camera with pose (R, t),
+    // where a world point p is seen in the camera frame as R * p + t
+    fn render(
+        rotation: &[[f64; 3]; 3],
+        translation: &[f64; 3],
+    ) -> Result<(Image<f32, 1>, Image<f32, 1>), ImageError> {
+        let size = [80, 60].into();
+        let mut gray = Image::<f32, 1>::from_size_val(size, 0.0)?;
+        let mut depth = Image::<f32, 1>::from_size_val(size, 0.0)?;
+
+        for v in 0..60 {
+            for u in 0..80 {
+                // intersect the ray R^T * (s * d - t) with the plane
+                let d = [
+                    (u as f64 - INTRINSICS.cx) / INTRINSICS.fx,
+                    (v as f64 - INTRINSICS.cy) / INTRINSICS.fy,
+                    1.0,
+                ];
+                let rt = |x: &[f64; 3]| {
+                    [0, 1, 2].map(|k| (0..3).map(|j| rotation[j][k] * x[j]).sum::<f64>())
+                };
+                let (rd, rtt) = (rt(&d), rt(translation));
+                let s = (1.0 + rtt[2]) / rd[2];
+                let p = [0, 1, 2].map(|k| s * rd[k] - rtt[k]);
+
+                let texture = 0.5
+                    + 0.2 * (8.0 * p[0]).sin() * (6.0 * p[1]).cos()
+                    + 0.1 * (5.0 * (p[0] + p[1])).sin();
+                gray.as_slice_mut()[v * 80 + u] = texture as f32;
+                depth.as_slice_mut()[v * 80 + u] = s as f32;
+            }
+        }
+
+        Ok((gray, depth))
+    }
+
+    #[test]
+    fn test_rgbd_odometry() -> Result<(), Box<dyn std::error::Error>> {
+        let identity = [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]];
+        let rotation = axis_angle_to_rotation_matrix(&[0.2, 1.0, -0.3], 0.02)?;
+        let translation = [0.02, -0.01, 0.03];
+
+        let (source_gray, source_depth) = render(&identity, &[0.0; 3])?;
+        let (target_gray, target_depth) = render(&rotation, &translation)?;
+
+        let result = rgbd_odometry(
+            &source_gray,
+            &source_depth,
+            &target_gray,
+            &target_depth,
+            &INTRINSICS,
+            1.0,
+            &RgbdOdometryParams::default(),
+        )?;
+
+        for (t, t_expected) in result.translation.iter().zip(translation.iter()) {
+            assert_relative_eq!(t, t_expected, epsilon = 2e-3);
+        }
+        for (row, row_expected) in result.rotation.iter().zip(rotation.iter()) {
+            for (r, r_expected) in row.iter().zip(row_expected.iter()) {
+                assert_relative_eq!(r, r_expected, epsilon = 2e-3);
+            }
+        }
+        assert!(result.fitness > 0.8);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_rgbd_odometry_size_mismatch() -> Result<(), ImageError> {
+        let gray = Image::<f32, 1>::from_size_val([8, 8].into(), 0.0)?;
+        let depth = Image::<u16, 1>::from_size_val([4, 4].into(), 0)?;
+        let result = rgbd_odometry(
+            &gray,
+            &depth,
+            &gray,
+            &depth,
+            &INTRINSICS,
+            1e-3,
+            &RgbdOdometryParams::default(),
+        );
+        assert!(result.is_err());
+        Ok(())
+    }
+}
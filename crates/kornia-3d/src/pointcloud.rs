@@ -1,3 +1,7 @@
+use std::collections::HashMap;
+
+use crate::linalg::dot_product3;
+
 /// A point cloud with points, colors, and normals.
 #[derive(Debug, Clone)]
 pub struct PointCloud {
@@ -49,6 +53,192 @@ impl PointCloud {
     pub fn normals(&self) -> Option<&Vec<[f64; 3]>> {
         self.normals.as_ref()
     }
+
+    /// Apply a rigid transformation to the point cloud.
+    ///
+    /// The points are rotated and translated, the normals are only rotated.
+    ///
+    /// # Arguments
+    ///
+    /// * `dst_r_src` - The 3x3 rotation matrix.
+    /// * `dst_t_src` - The 3D translation vector.
+    ///
+    /// # Returns
+    ///
+    /// A new point cloud in the destination frame.
+    pub fn transform(&self, dst_r_src: &[[f64; 3]; 3], dst_t_src: &[f64; 3]) -> Self {
+        let rotate = |p: &[f64; 3]| {
+            [
+                dot_product3(&dst_r_src[0], p),
+                dot_product3(&dst_r_src[1], p),
+                dot_product3(&dst_r_src[2], p),
+            ]
+        };
+
+        let points = self
+            .points
+            .iter()
+            .map(|p| {
+                let r = rotate(p);
+                [
+                    r[0] + dst_t_src[0],
+                    r[1] + dst_t_src[1],
+                    r[2] + dst_t_src[2],
+                ]
+            })
+            .collect();
+
+        let normals = self
+            .normals
+            .as_ref()
+            .map(|normals| normals.iter().map(rotate).collect());
+
+        Self::new(points, self.colors.clone(), normals)
+    }
+
+    /// Compute the axis aligned bounding box of the point cloud.
+    ///
+    /// # Returns
+    ///
+    /// The minimum and maximum corners of the box or None if the point cloud is empty.
+    pub fn bounding_box(&self) -> Option<([f64; 3], [f64; 3])> {
+        let first = *self.points.first()?;
+        Some(
+            self.points
+                .iter()
+                .fold((first, first), |(mut min, mut max), p| {
+                    for i in 0..3 {
+                        min[i] = min[i].min(p[i]);
+                        max[i] = max[i].max(p[i]);
+                    }
+                    (min, max)
+                }),
+        )
+    }
+
+    /// Keep only the points inside an axis aligned bounding box.
+    ///
+    /// # Arguments
+    ///
+    /// * `min` - The minimum corner of the box.
+    /// * `max` - The maximum corner of the box.
+    ///
+    /// # Returns
+    ///
+    /// A new point cloud with the points inside the box, including its boundary.
+    pub fn crop(&self, min: &[f64; 3], max: &[f64; 3]) -> Self {
+        let indices = self
+            .points
+            .iter()
+            .enumerate()
+            .filter(|(_, p)| (0..3).all(|i| p[i] >= min[i] && p[i] <= max[i]))
+            .map(|(i, _)| i)
+            .collect::<Vec<_>>();
+        self.select(&indices)
+    }
+
+    /// Select a subset of the points by their indices.
+    ///
+    /// # Arguments
+    ///
+    /// * `indices` - The indices of the points to keep.
+    ///
+    /// PRECONDITION: all the indices are smaller than the number of points.
+    ///
+    /// # Returns
+    ///
+    /// A new point cloud with the selected points, colors and normals.
+    pub fn select(&self, indices: &[usize]) -> Self {
+        Self::new(
+            indices.iter().map(|&i| self.points[i]).collect(),
+            self.colors
+                .as_ref()
+                .map(|colors| indices.iter().map(|&i| colors[i]).collect()),
+            self.normals
+                .as_ref()
+                .map(|normals| indices.iter().map(|&i| normals[i]).collect()),
+        )
+    }
+
+    /// Downsample the point cloud by averaging the points that fall in the same voxel.
+    ///
+    /// The colors are averaged and the normals are averaged and normalized. The voxels are
+    /// returned in the order in which they are first visited.
+    ///
+    /// # Arguments
+    ///
+    /// * `voxel_size` - The size of the edge of the voxels.
+    ///
+    /// PRECONDITION: voxel_size is greater than zero.
+    ///
+    /// # Returns
+    ///
+    /// A new point cloud with at most one point per voxel.
+    pub fn voxel_downsample(&self, voxel_size: f64) -> Self {
+        // accumulators of the points, colors and normals per voxel
+        #[derive(Default)]
+        struct Voxel {
+            count: usize,
+            point: [f64; 3],
+            color: [u32; 3],
+            normal: [f64; 3],
+        }
+
+        let mut voxel_index = HashMap::new();
+        let mut voxels: Vec<Voxel> = Vec::new();
+
+        for (i, p) in self.points.iter().enumerate() {
+            let key = [
+                (p[0] / voxel_size).floor() as i64,
+                (p[1] / voxel_size).floor() as i64,
+                (p[2] / voxel_size).floor() as i64,
+            ];
+            let index = *voxel_index.entry(key).or_insert_with(|| {
+                voxels.push(Voxel::default());
+                voxels.len() - 1
+            });
+
+            let voxel = &mut voxels[index];
+            voxel.count += 1;
+            for k in 0..3 {
+                voxel.point[k] += p[k];
+                if let Some(colors) = &self.colors {
+                    voxel.color[k] += colors[i][k] as u32;
+                }
+                if let Some(normals) = &self.normals {
+                    voxel.normal[k] += normals[i][k];
+                }
+            }
+        }
+
+        let points = voxels
+            .iter()
+            .map(|v| v.point.map(|x| x / v.count as f64))
+            .collect();
+
+        let colors = self.colors.as_ref().map(|_| {
+            voxels
+                .iter()
+                .map(|v| v.color.map(|c| (c as f64 / v.count as f64).round() as u8))
+                .collect()
+        });
+
+        let normals = self.normals.as_ref().map(|_| {
+            voxels
+                .iter()
+                .map(|v| {
+                    let norm = dot_product3(&v.normal, &v.normal).sqrt();
+                    if norm > 0.0 {
+                        v.normal.map(|x| x / norm)
+                    } else {
+                        v.normal
+                    }
+                })
+                .collect()
+        });
+
+        Self::new(points, colors, normals)
+    }
 }
 
 #[cfg(test)]
@@ -85,4 +275,81 @@ mod tests {
             assert_eq!(p1[2], 0.0);
         }
     }
+
+    #[test]
+    fn test_pointcloud_transform() {
+        let pointcloud = PointCloud::new(
+            vec![[1.0, 0.0, 0.0], [0.0, 2.0, 0.0]],
+            None,
+            Some(vec![[1.0, 0.0, 0.0], [0.0, 1.0, 0.0]]),
+        );
+
+        // rotation of 90 degrees around the z axis
+        let rotation = [[0.0, -1.0, 0.0], [1.0, 0.0, 0.0], [0.0, 0.0, 1.0]];
+        let transformed = pointcloud.transform(&rotation, &[0.0, 0.0, 1.0]);
+
+        assert_eq!(
+            transformed.points(),
+            &vec![[0.0, 1.0, 1.0], [-2.0, 0.0, 1.0]]
+        );
+        assert_eq!(
+            transformed.normals(),
+            Some(&vec![[0.0, 1.0, 0.0], [-1.0, 0.0, 0.0]])
+        );
+        assert!(transformed.colors().is_none());
+    }
+
+    #[test]
+    fn test_pointcloud_crop() {
+        let pointcloud = PointCloud::new(
+            vec![[0.0, 0.0, 0.0], [1.0, 1.0, 1.0], [2.0, -1.0, 0.5]],
+            Some(vec![[1, 1, 1], [2, 2, 2], [3, 3, 3]]),
+            None,
+        );
+
+        assert_eq!(
+            pointcloud.bounding_box(),
+            Some(([0.0, -1.0, 0.0], [2.0, 1.0, 1.0]))
+        );
+        assert_eq!(PointCloud::new(vec![], None, None).bounding_box(), None);
+
+        let cropped = pointcloud.crop(&[0.0, 0.0, 0.0], &[1.0, 1.0, 1.0]);
+        assert_eq!(cropped.points(), &vec![[0.0, 0.0, 0.0], [1.0, 1.0, 1.0]]);
+        assert_eq!(cropped.colors(), Some(&vec![[1, 1, 1], [2, 2, 2]]));
+    }
+
+    #[test]
+    fn test_pointcloud_voxel_downsample() {
+        let pointcloud = PointCloud::new(
+            vec![
+                [0.1, 0.1, 0.1],
+                [0.3, 0.3, 0.3],
+                [1.5, 0.2, 0.2],
+                [-0.5, 0.0, 0.0],
+            ],
+            Some(vec![[10, 0, 0], [20, 0, 0], [0, 255, 0], [0, 0, 255]]),
+            Some(vec![
+                [1.0, 0.0, 0.0],
+                [0.0, 1.0, 0.0],
+                [0.0, 0.0, 1.0],
+                [0.0, 0.0, 1.0],
+            ]),
+        );
+
+        let downsampled = pointcloud.voxel_downsample(1.0);
+        assert_eq!(downsampled.len(), 3);
+
+        let p0 = downsampled.points()[0];
+        for v in p0 {
+            assert!((v - 0.2).abs() < 1e-12);
+        }
+        assert_eq!(downsampled.points()[1], [1.5, 0.2, 0.2]);
+        assert_eq!(downsampled.points()[2], [-0.5, 0.0, 0.0]);
+
+        assert_eq!(downsampled.colors().map(|c| c[0]), Some([15, 0, 0]));
+
+        let n0 = downsampled.normals().map(|n| n[0]).unwrap_or_default();
+        let s = 1.0 / 2f64.sqrt();
+        assert!((n0[0] - s).abs() < 1e-12 && (n0[1] - s).abs() < 1e-12 && n0[2] == 0.0);
+    }
 }
@@ -0,0 +1,708 @@
+use faer::prelude::SpSolver;
+
+use crate::{
+    linalg::{dot_product3, matmul33, transpose_mat33},
+    transforms::axis_angle_to_rotation_matrix,
+};
+
+/// Error types for the pose graph optimization.
+#[derive(Debug, thiserror::Error, PartialEq)]
+pub enum PoseGraphError {
+    /// An edge refers to a pose that does not exist.
+    #[error("Invalid pose index {0} for a graph with {1} poses")]
+    InvalidPoseIndex(usize, usize),
+
+    /// The poses are not constrained enough by the edges, e.g. the graph is disconnected.
+    #[error("The pose graph is not constrained enough")]
+    Underconstrained,
+}
+
+/// A rigid transformation in 3D.
+///
+/// The pose of a node maps the points from the node frame to the world frame as
+/// `p_world = rotation * p_node + translation`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Pose {
+    /// The rotation matrix.
+    pub rotation: [[f64; 3]; 3],
+    /// The translation vector.
+    pub translation: [f64; 3],
+}
+
+impl Default for Pose {
+    fn default() -> Self {
+        Self {
+            rotation: [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]],
+            translation: [0.0; 3],
+        }
+    }
+}
+
+impl Pose {
+    /// Create a pose from a unit quaternion and a translation.
+    ///
+    /// # Arguments
+    ///
+    /// * `quaternion` - The rotation as the quaternion [w, x, y, z], normalized if needed.
+    /// * `translation` - The translation vector.
+    pub fn from_quaternion(quaternion: [f64; 4], translation: [f64; 3]) -> Pose {
+        let norm = quaternion.iter().map(|v| v * v).sum::<f64>().sqrt();
+        let [w, x, y, z] = quaternion.map(|v| v / norm);
+        Pose {
+            rotation: [
+                [
+                    1.0 - 2.0 * (y * y + z * z),
+                    2.0 * (x * y - w * z),
+                    2.0 * (x * z + w * y),
+                ],
+                [
+                    2.0 * (x * y + w * z),
+                    1.0 - 2.0 * (x * x + z * z),
+                    2.0 * (y * z - w * x),
+                ],
+                [
+                    2.0 * (x * z - w * y),
+                    2.0 * (y * z + w * x),
+                    1.0 - 2.0 * (x * x + y * y),
+                ],
+            ],
+            translation,
+        }
+    }
+
+    /// Compose two poses as `self * other`.
+    pub fn compose(&self, other: &Pose) -> Pose {
+        let mut rotation = [[0.0; 3]; 3];
+        matmul33(&self.rotation, &other.rotation, &mut rotation);
+        let moved = self
+            .rotation
+            .map(|row| dot_product3(&row, &other.translation));
+        Pose {
+            rotation,
+            translation: [0, 1, 2].map(|k| moved[k] + self.translation[k]),
+        }
+    }
+
+    /// The inverse of the pose.
+    pub fn inverse(&self) -> Pose {
+        let mut rotation = [[0.0; 3]; 3];
+        transpose_mat33(&self.rotation, &mut rotation);
+        let moved = rotation.map(|row| dot_product3(&row, &self.translation));
+        Pose {
+            rotation,
+            translation: moved.map(|v| -v),
+        }
+    }
+}
+
+/// A relative pose measurement between two nodes of the pose graph.
+///
+/// The measurement is the pose of the node `to` in the frame of the node `from`, that is
+/// `poses[from].inverse() * poses[to]`, such as an odometry step or a loop closure.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PoseGraphEdge {
+    /// The index of the first node.
+    pub from: usize,
+    /// The index of the second node.
+    pub to: usize,
+    /// The measured relative pose.
+    pub measurement: Pose,
+    /// The diagonal of the information matrix of the rotation and the translation errors.
+    pub information: [f64; 6],
+}
+
+impl PoseGraphEdge {
+    /// Create an edge with the identity information matrix.
+    pub fn new(from: usize, to: usize, measurement: Pose) -> Self {
+        Self {
+            from,
+            to,
+            measurement,
+            information: [1.0; 6],
+        }
+    }
+}
+
+/// A robust kernel to reduce the influence of the outlier edges.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum RobustKernel {
+    /// The squared loss.
+    None,
+    /// The Huber loss with the given threshold on the weighted error.
+    Huber(f64),
+    /// The Cauchy loss with the given scale of the weighted error.
+    Cauchy(f64),
+}
+
+impl RobustKernel {
+    // the weight of an error in the iteratively reweighted least squares
+    fn weight(&self, error: f64) -> f64 {
+        match *self {
+            RobustKernel::None => 1.0,
+            RobustKernel::Huber(delta) => {
+                if error <= delta {
+                    1.0
+                } else {
+                    delta / error
+                }
+            }
+            RobustKernel::Cauchy(scale) => 1.0 / (1.0 + (error / scale).powi(2)),
+        }
+    }
+
+    // the loss of an error, to report the cost
+    fn loss(&self, error: f64) -> f64 {
+        match *self {
+            RobustKernel::None => error * error,
+            RobustKernel::Huber(delta) => {
+                if error <= delta {
+                    error * error
+                } else {
+                    2.0 * delta * error - delta * delta
+                }
+            }
+            RobustKernel::Cauchy(scale) => scale * scale * (1.0 + (error / scale).powi(2)).ln(),
+        }
+    }
+}
+
+/// Parameters of the pose graph optimization.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PoseGraphParams {
+    /// The maximum number of Gauss-Newton iterations.
+    pub max_iterations: usize,
+    /// The minimum norm of the update to continue iterating.
+    pub tolerance: f64,
+    /// The robust kernel applied to the errors of the edges.
+    pub kernel: RobustKernel,
+}
+
+impl Default for PoseGraphParams {
+    fn default() -> Self {
+        Self {
+            max_iterations: 20,
+            tolerance: 1e-8,
+            kernel: RobustKernel::None,
+        }
+    }
+}
+
+/// Result of the pose graph optimization.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PoseGraphResult {
+    /// The optimized poses of the nodes.
+    pub poses: Vec<Pose>,
+    /// The number of iterations performed.
+    pub num_iterations: usize,
+    /// The robust cost of the initial poses.
+    pub initial_cost: f64,
+    /// The robust cost of the optimized poses.
+    pub final_cost: f64,
+}
+
+/// Optimize the poses of a pose graph with Gauss-Newton iterations.
+///
+/// The error of an edge is the rotation error `log(R_ij^T R_i^T R_j)` and the translation
+/// error `R_i^T (t_j - t_i) - t_ij`, weighted by its information. The rotations are updated
+/// on the right and the translations additively, and the robust kernel is applied with
+/// iteratively reweighted least squares. The first pose is fixed to remove the gauge freedom.
+///
+/// # Arguments
+///
+/// * `poses` - The initial poses of the nodes, e.g. from the odometry or the
+///   [`chordal_rotation_averaging`].
+/// * `edges` - The relative pose measurements between the nodes.
+/// * `params` - The parameters of the optimization.
+///
+/// # Returns
+///
+/// The optimized poses with the costs before and after the optimization.
+///
+/// # Example
+///
+/// ```
+/// use kornia_3d::pose_graph::{optimize_pose_graph, Pose, PoseGraphEdge, PoseGraphParams};
+///
+/// let step = Pose {
+///     translation: [1.0, 0.0, 0.0],
+///     ..Default::default()
+/// };
+/// let poses = vec![Pose::default(); 3];
+/// let edges = [PoseGraphEdge::new(0, 1, step), PoseGraphEdge::new(1, 2, step)];
+///
+/// let result = optimize_pose_graph(&poses, &edges, &PoseGraphParams::default()).unwrap();
+/// assert!((result.poses[2].translation[0] - 2.0).abs() < 1e-6);
+/// ```
+pub fn optimize_pose_graph(
+    poses: &[Pose],
+    edges: &[PoseGraphEdge],
+    params: &PoseGraphParams,
+) -> Result<PoseGraphResult, PoseGraphError> {
+    check_edges(poses.len(), edges)?;
+
+    let mut result = PoseGraphResult {
+        poses: poses.to_vec(),
+        num_iterations: 0,
+        initial_cost: graph_cost(poses, edges, &params.kernel),
+        final_cost: 0.0,
+    };
+
+    // the first pose is fixed and the others are the variables
+    let num_variables = 6 * poses.len().saturating_sub(1);
+    if num_variables > 0 {
+        for _ in 0..params.max_iterations {
+            let mut hessian = faer::Mat::<f64>::zeros(num_variables, num_variables);
+            let mut gradient = faer::Mat::<f64>::zeros(num_variables, 1);
+
+            for edge in edges {
+                let (error, jacobian_i, jacobian_j) = edge_error(&result.poses, edge);
+                let weighted = (0..6)
+                    .map(|k| edge.information[k] * error[k] * error[k])
+                    .sum::<f64>()
+                    .sqrt();
+                let w = params.kernel.weight(weighted);
+
+                let blocks = [(edge.from, &jacobian_i), (edge.to, &jacobian_j)];
+                for &(a, jacobian_a) in blocks.iter().filter(|(node, _)| *node > 0) {
+                    // the transposed jacobian weighted by the information
+                    let weighted_a: [[f64; 6]; 6] = std::array::from_fn(|r| {
+                        std::array::from_fn(|k| w * jacobian_a[k][r] * edge.information[k])
+                    });
+                    let offset_a = 6 * (a - 1);
+                    for (r, row) in weighted_a.iter().enumerate() {
+                        for (b, jacobian_b) in blocks.iter().filter(|(node, _)| *node > 0) {
+                            let offset_b = 6 * (b - 1);
+                            for c in 0..6 {
+                                let value = (0..6).map(|k| row[k] * jacobian_b[k][c]).sum::<f64>();
+                                hessian.write(
+                                    offset_a + r,
+                                    offset_b + c,
+                                    hessian.read(offset_a + r, offset_b + c) + value,
+                                );
+                            }
+                        }
+                        let value = (0..6).map(|k| row[k] * error[k]).sum::<f64>();
+                        gradient.write(offset_a + r, 0, gradient.read(offset_a + r, 0) - value);
+                    }
+                }
+            }
+
+            let delta = hessian.partial_piv_lu().solve(&gradient);
+            let delta = (0..num_variables)
+                .map(|i| delta.read(i, 0))
+                .collect::<Vec<_>>();
+            if delta.iter().any(|v| !v.is_finite()) {
+                return Err(PoseGraphError::Underconstrained);
+            }
+            result.num_iterations += 1;
+
+            // R_i = R_i * exp(omega_i) and t_i = t_i + v_i
+            for (pose, update) in result.poses[1..].iter_mut().zip(delta.chunks_exact(6)) {
+                let rotation = pose.rotation;
+                matmul33(
+                    &rotation,
+                    &exp_so3(&[update[0], update[1], update[2]]),
+                    &mut pose.rotation,
+                );
+                for k in 0..3 {
+                    pose.translation[k] += update[3 + k];
+                }
+            }
+
+            if delta.iter().map(|v| v * v).sum::<f64>().sqrt() < params.tolerance {
+                break;
+            }
+        }
+    }
+
+    result.final_cost = graph_cost(&result.poses, edges, &params.kernel);
+
+    Ok(result)
+}
+
+/// Estimate the absolute rotations of the nodes from their relative rotations.
+///
+/// The chordal distance `||R_j - R_i R_ij||` is minimized in the least squares sense over
+/// all the edges, relaxing the rotations to arbitrary matrices, and the solutions are
+/// projected back to the closest rotations. The first rotation is fixed to the identity.
+/// The result is a good initialization of the [`optimize_pose_graph`].
+///
+/// # Arguments
+///
+/// * `num_poses` - The number of nodes.
+/// * `edges` - The relative pose measurements, of which only the rotations are used.
+///
+/// # Returns
+///
+/// The rotation of every node.
+pub fn chordal_rotation_averaging(
+    num_poses: usize,
+    edges: &[PoseGraphEdge],
+) -> Result<Vec<[[f64; 3]; 3]>, PoseGraphError> {
+    check_edges(num_poses, edges)?;
+
+    let identity = Pose::default().rotation;
+    let num_variables = 3 * num_poses.saturating_sub(1);
+    if num_variables == 0 {
+        return Ok(vec![identity; num_poses]);
+    }
+
+    // the rows of the rotations are independent: for every row k the edge gives the
+    // equations r_j^k = R_ij^T r_i^k, solved for all the rows at once
+    let mut normal = faer::Mat::<f64>::zeros(num_variables, num_variables);
+    let mut rhs = faer::Mat::<f64>::zeros(num_variables, 3);
+    for edge in edges {
+        // the equations are A_from * r_from + A_to * r_to = 0
+        let mut a_from = [[0.0; 3]; 3];
+        transpose_mat33(&edge.measurement.rotation, &mut a_from);
+        let a_from = a_from.map(|row| row.map(|v| -v));
+        let blocks = [(edge.from, a_from), (edge.to, identity)];
+
+        for (a, block_a) in blocks.iter() {
+            for (b, block_b) in blocks.iter() {
+                // the block of the normal equations A_a^T A_b
+                let block = |r: usize, c: usize| (0..3).map(move |k| block_a[k][r] * block_b[k][c]);
+                match (*a > 0, *b > 0) {
+                    (true, true) => {
+                        for r in 0..3 {
+                            for c in 0..3 {
+                                let (i, j) = (3 * (a - 1) + r, 3 * (b - 1) + c);
+                                normal.write(i, j, normal.read(i, j) + block(r, c).sum::<f64>());
+                            }
+                        }
+                    }
+                    // the fixed identity moves to the right hand side, its row k being e_k
+                    (true, false) => {
+                        for r in 0..3 {
+                            for k in 0..3 {
+                                let i = 3 * (a - 1) + r;
+                                rhs.write(i, k, rhs.read(i, k) - block(r, k).sum::<f64>());
+                            }
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    let solution = normal.partial_piv_lu().solve(&rhs);
+    let mut rotations = vec![identity];
+    for node in 0..num_poses - 1 {
+        let mut matrix = [[0.0; 3]; 3];
+        for (k, row) in matrix.iter_mut().enumerate() {
+            for (c, value) in row.iter_mut().enumerate() {
+                *value = solution.read(3 * node + c, k);
+            }
+        }
+        if matrix.iter().flatten().any(|v| !v.is_finite()) {
+            return Err(PoseGraphError::Underconstrained);
+        }
+        rotations.push(project_to_rotation(&matrix));
+    }
+
+    Ok(rotations)
+}
+
+fn check_edges(num_poses: usize, edges: &[PoseGraphEdge]) -> Result<(), PoseGraphError> {
+    for edge in edges {
+        for node in [edge.from, edge.to] {
+            if node >= num_poses {
+                return Err(PoseGraphError::InvalidPoseIndex(node, num_poses));
+            }
+        }
+    }
+    Ok(())
+}
+
+// the error of an edge with its jacobians with respect to the updates (omega, v) of the poses
+fn edge_error(poses: &[Pose], edge: &PoseGraphEdge) -> ([f64; 6], [[f64; 6]; 6], [[f64; 6]; 6]) {
+    let (pose_i, pose_j) = (&poses[edge.from], &poses[edge.to]);
+    let relative = pose_i.inverse().compose(pose_j);
+    let difference = edge.measurement.inverse().compose(&relative);
+    let rotation_error = log_so3(&difference.rotation);
+
+    // the translation of the second node in the frame of the first one
+    let mut rt_i = [[0.0; 3]; 3];
+    transpose_mat33(&pose_i.rotation, &mut rt_i);
+    let translation_error =
+        [0, 1, 2].map(|k| relative.translation[k] - edge.measurement.translation[k]);
+
+    // the rotation error changes by -R_j^T R_i omega_i and by omega_j
+    let mut rt_j = [[0.0; 3]; 3];
+    transpose_mat33(&pose_j.rotation, &mut rt_j);
+    let mut rji = [[0.0; 3]; 3];
+    matmul33(&rt_j, &pose_i.rotation, &mut rji);
+
+    // the translation error changes by [R_i^T (t_j - t_i)]x omega_i, -R_i^T v_i and R_i^T v_j
+    let d = relative.translation;
+    let skew = [[0.0, -d[2], d[1]], [d[2], 0.0, -d[0]], [-d[1], d[0], 0.0]];
+
+    let mut jacobian_i = [[0.0; 6]; 6];
+    let mut jacobian_j = [[0.0; 6]; 6];
+    for r in 0..3 {
+        for c in 0..3 {
+            jacobian_i[r][c] = -rji[r][c];
+            jacobian_j[r][c] = if r == c { 1.0 } else { 0.0 };
+            jacobian_i[3 + r][c] = skew[r][c];
+            jacobian_i[3 + r][3 + c] = -rt_i[r][c];
+            jacobian_j[3 + r][3 + c] = rt_i[r][c];
+        }
+    }
+
+    let mut error = [0.0; 6];
+    error[..3].copy_from_slice(&rotation_error);
+    error[3..].copy_from_slice(&translation_error);
+    (error, jacobian_i, jacobian_j)
+}
+
+fn graph_cost(poses: &[Pose], edges: &[PoseGraphEdge], kernel: &RobustKernel) -> f64 {
+    edges
+        .iter()
+        .map(|edge| {
+            let (error, _, _) = edge_error(poses, edge);
+            let weighted = (0..6)
+                .map(|k| edge.information[k] * error[k] * error[k])
+                .sum::<f64>()
+                .sqrt();
+            kernel.loss(weighted)
+        })
+        .sum()
+}
+
+// the rotation matrix of a rotation vector
+fn exp_so3(omega: &[f64; 3]) -> [[f64; 3]; 3] {
+    let angle = dot_product3(omega, omega).sqrt();
+    axis_angle_to_rotation_matrix(omega, angle).unwrap_or(Pose::default().rotation)
+}
+
+// the rotation vector of a rotation matrix
+fn log_so3(rotation: &[[f64; 3]; 3]) -> [f64; 3] {
+    let r = rotation;
+    let cos = ((r[0][0] + r[1][1] + r[2][2] - 1.0) / 2.0).clamp(-1.0, 1.0);
+    let angle = cos.acos();
+    let axis = [r[2][1] - r[1][2], r[0][2] - r[2][0], r[1][0] - r[0][1]];
+
+    if angle < 1e-6 {
+        return axis.map(|v| v / 2.0);
+    }
+    if std::f64::consts::PI - angle < 1e-6 {
+        // the axis is the column of R + I with the largest norm
+        let k = (0..3)
+            .max_by(|&a, &b| r[a][a].total_cmp(&r[b][b]))
+            .unwrap_or(0);
+        let column = [0, 1, 2].map(|i| r[i][k] + if i == k { 1.0 } else { 0.0 });
+        let norm = dot_product3(&column, &column).sqrt();
+        return column.map(|v| v / norm * angle);
+    }
+    axis.map(|v| v * angle / (2.0 * angle.sin()))
+}
+
+// the closest rotation matrix in the frobenius norm
+fn project_to_rotation(matrix: &[[f64; 3]; 3]) -> [[f64; 3]; 3] {
+    let mat = faer::Mat::<f64>::from_fn(3, 3, |r, c| matrix[r][c]);
+    let svd = mat.svd();
+    let (u, v) = (svd.u(), svd.v());
+
+    // flip the last singular vector for a reflection
+    let det = {
+        let uvt = u * v.transpose();
+        let m = [0, 1, 2].map(|r| [0, 1, 2].map(|c| uvt.read(r, c)));
+        crate::linalg::det_mat33(&m)
+    };
+    let sign = [1.0, 1.0, det.signum()];
+    [0, 1, 2]
+        .map(|r| [0, 1, 2].map(|c| (0..3).map(|k| u.read(r, k) * sign[k] * v.read(c, k)).sum()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    fn pose(axis: [f64; 3], angle: f64, translation: [f64; 3]) -> Pose {
+        Pose {
+            rotation: axis_angle_to_rotation_matrix(&axis, angle).unwrap(),
+            translation,
+        }
+    }
+
+    #[test]
+    fn test_pose_from_quaternion() {
+        let half = std::f64::consts::FRAC_PI_4;
+        let q = Pose::from_quaternion([half.cos(), 0.0, 0.0, half.sin()], [1.0, 2.0, 3.0]);
+        let expected = pose(
+            [0.0, 0.0, 1.0],
+            std::f64::consts::FRAC_PI_2,
+            [1.0, 2.0, 3.0],
+        );
+        for (row, row_expected) in q.rotation.iter().zip(expected.rotation.iter()) {
+            for (v, v_expected) in row.iter().zip(row_expected.iter()) {
+                assert_relative_eq!(v, v_expected, epsilon = 1e-12);
+            }
+        }
+
+        // the quaternion is normalized
+        let q2 = Pose::from_quaternion([2.0 * half.cos(), 0.0, 0.0, 2.0 * half.sin()], [0.0; 3]);
+        assert_relative_eq!(q2.rotation[1][0], 1.0, epsilon = 1e-12);
+    }
+
+    // a loop of poses on a circle, with the edges between the consecutive poses and a loop
+    // closure from the last to the first pose
+    fn circle(num_poses: usize) -> (Vec<Pose>, Vec<PoseGraphEdge>) {
+        let step = 2.0 * std::f64::consts::PI / num_poses as f64;
+        let poses = (0..num_poses)
+            .map(|i| {
+                let angle = step * i as f64;
+                pose(
+                    [0.1, 1.0, 0.2],
+                    angle,
+                    [3.0 * angle.cos(), 0.2 * i as f64, 3.0 * angle.sin()],
+                )
+            })
+            .collect::<Vec<_>>();
+        let edges = (0..num_poses)
+            .map(|i| {
+                let j = (i + 1) % num_poses;
+                PoseGraphEdge::new(i, j, poses[i].inverse().compose(&poses[j]))
+            })
+            .collect();
+        (poses, edges)
+    }
+
+    // the poses with the drift of a noisy odometry accumulated along the chain
+    fn drifted(poses: &[Pose]) -> Vec<Pose> {
+        let drift = pose([0.3, -0.2, 1.0], 0.03, [0.05, -0.02, 0.03]);
+        let mut accumulated = Pose::default();
+        poses
+            .iter()
+            .map(|p| {
+                let result = accumulated.compose(p);
+                accumulated = accumulated.compose(&drift);
+                result
+            })
+            .collect()
+    }
+
+    fn assert_poses_eq(a: &[Pose], b: &[Pose], epsilon: f64) {
+        for (a, b) in a.iter().zip(b) {
+            for r in 0..3 {
+                assert_relative_eq!(a.translation[r], b.translation[r], epsilon = epsilon);
+                for c in 0..3 {
+                    assert_relative_eq!(a.rotation[r][c], b.rotation[r][c], epsilon = epsilon);
+                }
+            }
+        }
+    }
+
+    fn translation_error(a: &Pose, b: &Pose) -> f64 {
+        (0..3)
+            .map(|k| (a.translation[k] - b.translation[k]).abs())
+            .fold(0.0, f64::max)
+    }
+
+    #[test]
+    fn test_pose_inverse() {
+        let p = pose([1.0, 2.0, 3.0], 0.7, [1.0, -2.0, 0.5]);
+        assert_poses_eq(&[p.compose(&p.inverse())], &[Pose::default()], 1e-12);
+    }
+
+    #[test]
+    fn test_log_so3() {
+        for (axis, angle) in [
+            ([1.0, 0.0, 0.0], 1e-8),
+            ([0.3, -0.5, 0.8], 1.2),
+            ([0.0, 1.0, 1.0], std::f64::consts::PI),
+        ] {
+            let omega = log_so3(&axis_angle_to_rotation_matrix(&axis, angle).unwrap());
+            assert_poses_eq(
+                &[pose(omega, dot_product3(&omega, &omega).sqrt(), [0.0; 3])],
+                &[pose(axis, angle, [0.0; 3])],
+                1e-6,
+            );
+        }
+    }
+
+    #[test]
+    fn test_optimize_pose_graph() -> Result<(), PoseGraphError> {
+        let (poses, edges) = circle(12);
+        let initial = drifted(&poses);
+
+        let result = optimize_pose_graph(&initial, &edges, &PoseGraphParams::default())?;
+        assert!(result.initial_cost > 1e-2);
+        assert!(result.final_cost < 1e-12, "{}", result.final_cost);
+        assert_poses_eq(&result.poses, &poses, 1e-6);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_optimize_pose_graph_robust() -> Result<(), PoseGraphError> {
+        let (poses, mut edges) = circle(12);
+
+        // redundant edges between every second pose and a wrong loop closure
+        for i in 0..12 {
+            let j = (i + 2) % 12;
+            edges.push(PoseGraphEdge::new(
+                i,
+                j,
+                poses[i].inverse().compose(&poses[j]),
+            ));
+        }
+        edges.push(PoseGraphEdge::new(
+            0,
+            6,
+            pose([1.0, 0.0, 0.0], 0.5, [2.0, 1.0, -1.0]),
+        ));
+
+        let initial = drifted(&poses);
+        let params = PoseGraphParams {
+            max_iterations: 50,
+            kernel: RobustKernel::Cauchy(0.1),
+            ..Default::default()
+        };
+        let result = optimize_pose_graph(&initial, &edges, &params)?;
+        assert_poses_eq(&result.poses, &poses, 1e-2);
+        let robust_error = translation_error(&result.poses[6], &poses[6]);
+
+        // the squared loss is biased by the outlier
+        let params = PoseGraphParams {
+            kernel: RobustKernel::None,
+            ..params
+        };
+        let result = optimize_pose_graph(&initial, &edges, &params)?;
+        let error = translation_error(&result.poses[6], &poses[6]);
+        assert!(error > 10.0 * robust_error, "{error} {robust_error}");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_chordal_rotation_averaging() -> Result<(), PoseGraphError> {
+        let (poses, edges) = circle(10);
+        let rotations = chordal_rotation_averaging(poses.len(), &edges)?;
+
+        // the rotations are recovered relative to the first one
+        let reference = poses[0].inverse();
+        for (rotation, p) in rotations.iter().zip(&poses) {
+            let expected = reference.compose(p).rotation;
+            for r in 0..3 {
+                for c in 0..3 {
+                    assert_relative_eq!(rotation[r][c], expected[r][c], epsilon = 1e-9);
+                }
+            }
+        }
+
+        assert_eq!(
+            chordal_rotation_averaging(2, &[PoseGraphEdge::new(0, 2, Pose::default())]),
+            Err(PoseGraphError::InvalidPoseIndex(2, 2))
+        );
+
+        Ok(())
+    }
+}
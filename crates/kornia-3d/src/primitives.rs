@@ -0,0 +1,432 @@
+use faer::prelude::SpSolver;
+
+use crate::{
+    linalg::{cross_vec3, dot_product3},
+    normals::{smallest_eigenvector, smallest_principal_axis},
+    pointcloud::PointCloud,
+    ransac::{ransac, RansacError, RansacModel, RansacParams, RansacResult},
+};
+
+/// A plane defined by the points `p` such that `normal · p + d = 0`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Plane {
+    /// The unit normal of the plane.
+    pub normal: [f64; 3],
+    /// The signed distance of the origin to the plane along the negative normal.
+    pub d: f64,
+}
+
+impl Plane {
+    /// Compute the signed distance of a point to the plane.
+    pub fn signed_distance(&self, point: &[f64; 3]) -> f64 {
+        dot_product3(&self.normal, point) + self.d
+    }
+}
+
+impl RansacModel for Plane {
+    type Data = [f64; 3];
+    const MIN_SAMPLES: usize = 3;
+
+    fn fit(points: &[&[f64; 3]]) -> Option<Self> {
+        if points.len() < Self::MIN_SAMPLES {
+            return None;
+        }
+
+        // reject the collinear minimal samples
+        if points.len() == Self::MIN_SAMPLES {
+            let mut n = [0.0; 3];
+            cross_vec3(
+                &sub(points[1], points[0]),
+                &sub(points[2], points[0]),
+                &mut n,
+            );
+            if dot_product3(&n, &n) < f64::EPSILON {
+                return None;
+            }
+        }
+
+        let centroid = centroid(points.iter().copied());
+        let normal = smallest_principal_axis(points.iter().copied());
+        Some(Self {
+            normal,
+            d: -dot_product3(&normal, &centroid),
+        })
+    }
+
+    fn residual(&self, point: &[f64; 3]) -> f64 {
+        self.signed_distance(point).abs()
+    }
+}
+
+/// A sphere defined by its center and radius.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Sphere {
+    /// The center of the sphere.
+    pub center: [f64; 3],
+    /// The radius of the sphere.
+    pub radius: f64,
+}
+
+impl RansacModel for Sphere {
+    type Data = [f64; 3];
+    const MIN_SAMPLES: usize = 4;
+
+    fn fit(points: &[&[f64; 3]]) -> Option<Self> {
+        if points.len() < Self::MIN_SAMPLES {
+            return None;
+        }
+
+        // solve in the least squares sense |p|^2 + a * x + b * y + c * z + e = 0
+        let mut ata = faer::Mat::<f64>::zeros(4, 4);
+        let mut atb = faer::Mat::<f64>::zeros(4, 1);
+        for p in points {
+            let row = [p[0], p[1], p[2], 1.0];
+            let rhs = -dot_product3(p, p);
+            for i in 0..4 {
+                for j in 0..4 {
+                    ata.write(i, j, ata.read(i, j) + row[i] * row[j]);
+                }
+                atb.write(i, 0, atb.read(i, 0) + row[i] * rhs);
+            }
+        }
+        let x = ata.partial_piv_lu().solve(&atb);
+
+        let center = [
+            -0.5 * x.read(0, 0),
+            -0.5 * x.read(1, 0),
+            -0.5 * x.read(2, 0),
+        ];
+        let radius_sq = dot_product3(&center, &center) - x.read(3, 0);
+        (radius_sq.is_finite() && radius_sq > 0.0).then(|| Self {
+            center,
+            radius: radius_sq.sqrt(),
+        })
+    }
+
+    fn residual(&self, point: &[f64; 3]) -> f64 {
+        let d = sub(point, &self.center);
+        (dot_product3(&d, &d).sqrt() - self.radius).abs()
+    }
+}
+
+/// An infinite cylinder defined by its axis and radius.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Cylinder {
+    /// A point on the axis of the cylinder.
+    pub center: [f64; 3],
+    /// The unit direction of the axis of the cylinder.
+    pub axis: [f64; 3],
+    /// The radius of the cylinder.
+    pub radius: f64,
+}
+
+impl Cylinder {
+    /// Compute the distance of a point to the axis of the cylinder.
+    pub fn distance_to_axis(&self, point: &[f64; 3]) -> f64 {
+        let d = sub(point, &self.center);
+        let mut c = [0.0; 3];
+        cross_vec3(&d, &self.axis, &mut c);
+        dot_product3(&c, &c).sqrt()
+    }
+}
+
+impl RansacModel for Cylinder {
+    /// A point with its normal.
+    type Data = ([f64; 3], [f64; 3]);
+    const MIN_SAMPLES: usize = 2;
+
+    fn fit(data: &[&([f64; 3], [f64; 3])]) -> Option<Self> {
+        if data.len() < Self::MIN_SAMPLES {
+            return None;
+        }
+
+        // the axis is orthogonal to all the normals
+        let mut m = faer::Mat::<f64>::zeros(3, 3);
+        for (_, n) in data {
+            for i in 0..3 {
+                for j in 0..3 {
+                    m.write(i, j, m.read(i, j) + n[i] * n[j]);
+                }
+            }
+        }
+        let axis = smallest_eigenvector(&m);
+
+        // an orthonormal basis of the plane orthogonal to the axis
+        let helper = if axis[0].abs() < 0.9 {
+            [1.0, 0.0, 0.0]
+        } else {
+            [0.0, 1.0, 0.0]
+        };
+        let mut e1 = [0.0; 3];
+        cross_vec3(&axis, &helper, &mut e1);
+        let norm = dot_product3(&e1, &e1).sqrt();
+        e1 = e1.map(|v| v / norm);
+        let mut e2 = [0.0; 3];
+        cross_vec3(&axis, &e1, &mut e2);
+
+        // the center is the closest point to the lines along the projected normals
+        let mut ata = [[0.0; 2]; 2];
+        let mut atb = [0.0; 2];
+        let mut projected = Vec::with_capacity(data.len());
+        for (p, n) in data {
+            let q = [dot_product3(p, &e1), dot_product3(p, &e2)];
+            let m = [dot_product3(n, &e1), dot_product3(n, &e2)];
+            let norm = (m[0] * m[0] + m[1] * m[1]).sqrt();
+            if norm < 1e-9 {
+                return None;
+            }
+            let m = [m[0] / norm, m[1] / norm];
+
+            // the projector orthogonal to the line direction
+            let proj = [
+                [1.0 - m[0] * m[0], -m[0] * m[1]],
+                [-m[0] * m[1], 1.0 - m[1] * m[1]],
+            ];
+            for i in 0..2 {
+                for j in 0..2 {
+                    ata[i][j] += proj[i][j];
+                }
+                atb[i] += proj[i][0] * q[0] + proj[i][1] * q[1];
+            }
+            projected.push(q);
+        }
+
+        let det = ata[0][0] * ata[1][1] - ata[0][1] * ata[1][0];
+        if det.abs() < 1e-12 {
+            return None;
+        }
+        let c2 = [
+            (ata[1][1] * atb[0] - ata[0][1] * atb[1]) / det,
+            (ata[0][0] * atb[1] - ata[1][0] * atb[0]) / det,
+        ];
+
+        let radius = projected
+            .iter()
+            .map(|q| ((q[0] - c2[0]).powi(2) + (q[1] - c2[1]).powi(2)).sqrt())
+            .sum::<f64>()
+            / projected.len() as f64;
+
+        Some(Self {
+            center: [0, 1, 2].map(|k| c2[0] * e1[k] + c2[1] * e2[k]),
+            axis,
+            radius,
+        })
+    }
+
+    fn residual(&self, (point, _): &([f64; 3], [f64; 3])) -> f64 {
+        (self.distance_to_axis(point) - self.radius).abs()
+    }
+}
+
+fn centroid<'a>(points: impl Iterator<Item = &'a [f64; 3]>) -> [f64; 3] {
+    let mut sum = [0.0; 3];
+    let mut count = 0;
+    for p in points {
+        for k in 0..3 {
+            sum[k] += p[k];
+        }
+        count += 1;
+    }
+    sum.map(|v| v / count as f64)
+}
+
+fn sub(a: &[f64; 3], b: &[f64; 3]) -> [f64; 3] {
+    [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
+
+/// Fit a plane to a point cloud with RANSAC, e.g. to segment the ground.
+///
+/// # Arguments
+///
+/// * `pointcloud` - The point cloud.
+/// * `params` - The RANSAC parameters, where the threshold is the distance to the plane.
+///
+/// # Returns
+///
+/// The plane and the indices of its inlier points.
+///
+/// # Example
+///
+/// ```
+/// use kornia_3d::pointcloud::PointCloud;
+/// use kornia_3d::primitives::fit_plane;
+/// use kornia_3d::ransac::RansacParams;
+///
+/// let mut points = (0..100)
+///     .map(|i| [(i % 10) as f64, (i / 10) as f64, 0.0])
+///     .collect::<Vec<_>>();
+/// points.push([0.0, 0.0, 5.0]);
+/// let pointcloud = PointCloud::new(points, None, None);
+///
+/// let result = fit_plane(&pointcloud, &RansacParams::default()).unwrap();
+/// assert_eq!(result.inliers.len(), 100);
+/// assert!(result.model.normal[2].abs() > 0.99);
+///
+/// // remove the plane from the point cloud
+/// let outliers = (0..pointcloud.len())
+///     .filter(|i| !result.inliers.contains(i))
+///     .collect::<Vec<_>>();
+/// assert_eq!(pointcloud.select(&outliers).len(), 1);
+/// ```
+pub fn fit_plane(
+    pointcloud: &PointCloud,
+    params: &RansacParams,
+) -> Result<RansacResult<Plane>, RansacError> {
+    ransac(pointcloud.points(), params)
+}
+
+/// Fit a sphere to a point cloud with RANSAC.
+///
+/// # Arguments
+///
+/// * `pointcloud` - The point cloud.
+/// * `params` - The RANSAC parameters, where the threshold is the distance to the surface.
+///
+/// # Returns
+///
+/// The sphere and the indices of its inlier points.
+pub fn fit_sphere(
+    pointcloud: &PointCloud,
+    params: &RansacParams,
+) -> Result<RansacResult<Sphere>, RansacError> {
+    ransac(pointcloud.points(), params)
+}
+
+/// Fit a cylinder to a point cloud with normals with RANSAC.
+///
+/// # Arguments
+///
+/// * `pointcloud` - The point cloud with normals.
+/// * `params` - The RANSAC parameters, where the threshold is the distance to the surface.
+///
+/// # Returns
+///
+/// The cylinder and the indices of its inlier points.
+pub fn fit_cylinder(
+    pointcloud: &PointCloud,
+    params: &RansacParams,
+) -> Result<RansacResult<Cylinder>, RansacError> {
+    let normals = pointcloud
+        .normals()
+        .ok_or_else(|| RansacError::MissingData("the cylinder requires normals".to_string()))?;
+    let data = pointcloud
+        .points()
+        .iter()
+        .copied()
+        .zip(normals.iter().copied())
+        .collect::<Vec<_>>();
+    ransac(&data, params)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    // a deterministic sequence in [0, 1) with a linear congruential generator
+    fn uniform(state: &mut u64) -> f64 {
+        *state = state.wrapping_mul(6364136223846793005).wrapping_add(1);
+        (*state >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    fn outliers(num: usize) -> Vec<[f64; 3]> {
+        let mut state = 7;
+        (0..num)
+            .map(|_| [0, 1, 2].map(|_| 4.0 * uniform(&mut state) - 2.0))
+            .collect()
+    }
+
+    #[test]
+    fn test_fit_plane() -> Result<(), RansacError> {
+        // the plane x + 2y + 2z = 3
+        let mut points = (0..200)
+            .map(|i| {
+                let (x, y) = ((i % 20) as f64 * 0.1, (i / 20) as f64 * 0.1);
+                [x, y, (3.0 - x - 2.0 * y) / 2.0]
+            })
+            .collect::<Vec<_>>();
+        points.extend(outliers(50));
+        let pointcloud = PointCloud::new(points, None, None);
+
+        let result = fit_plane(&pointcloud, &RansacParams::default())?;
+        let sign = result.model.normal[0].signum();
+        let expected = [1.0 / 3.0, 2.0 / 3.0, 2.0 / 3.0];
+        for k in 0..3 {
+            assert_relative_eq!(sign * result.model.normal[k], expected[k], epsilon = 1e-9);
+        }
+        assert_relative_eq!(sign * result.model.d, -1.0, epsilon = 1e-9);
+        assert!(result.inliers.len() >= 200);
+        assert!((0..200).all(|i| result.inliers.contains(&i)));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_fit_sphere() -> Result<(), RansacError> {
+        let (center, radius) = ([0.5, -0.2, 1.0], 0.7);
+        let mut state = 3;
+        let mut points = (0..200)
+            .map(|_| {
+                let (theta, phi) = (
+                    std::f64::consts::PI * uniform(&mut state),
+                    2.0 * std::f64::consts::PI * uniform(&mut state),
+                );
+                [
+                    center[0] + radius * theta.sin() * phi.cos(),
+                    center[1] + radius * theta.sin() * phi.sin(),
+                    center[2] + radius * theta.cos(),
+                ]
+            })
+            .collect::<Vec<_>>();
+        points.extend(outliers(50));
+        let pointcloud = PointCloud::new(points, None, None);
+
+        let result = fit_sphere(&pointcloud, &RansacParams::default())?;
+        for (c, c_expected) in result.model.center.iter().zip(center.iter()) {
+            assert_relative_eq!(c, c_expected, epsilon = 1e-9);
+        }
+        assert_relative_eq!(result.model.radius, radius, epsilon = 1e-9);
+        assert!((0..200).all(|i| result.inliers.contains(&i)));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_fit_cylinder() -> Result<(), RansacError> {
+        // a cylinder along the z axis through (1, 2, 0) with radius 0.5
+        let mut points = Vec::new();
+        let mut normals = Vec::new();
+        for i in 0..200 {
+            let angle = i as f64 * 0.1;
+            let n = [angle.cos(), angle.sin(), 0.0];
+            points.push([1.0 + 0.5 * n[0], 2.0 + 0.5 * n[1], (i % 10) as f64 * 0.1]);
+            normals.push(n);
+        }
+        let outlier_points = outliers(50);
+        normals.extend(outlier_points.iter().map(|p| {
+            let norm = dot_product3(p, p).sqrt();
+            p.map(|v| v / norm)
+        }));
+        points.extend(outlier_points);
+
+        let pointcloud = PointCloud::new(points.clone(), None, Some(normals));
+        let result = fit_cylinder(&pointcloud, &RansacParams::default())?;
+
+        assert_relative_eq!(result.model.axis[2].abs(), 1.0, epsilon = 1e-9);
+        assert_relative_eq!(result.model.radius, 0.5, epsilon = 1e-9);
+        assert_relative_eq!(
+            result.model.distance_to_axis(&[1.0, 2.0, 5.0]),
+            0.0,
+            epsilon = 1e-9
+        );
+        assert!((0..200).all(|i| result.inliers.contains(&i)));
+
+        let pointcloud = PointCloud::new(points, None, None);
+        assert!(matches!(
+            fit_cylinder(&pointcloud, &RansacParams::default()),
+            Err(RansacError::MissingData(_))
+        ));
+
+        Ok(())
+    }
+}
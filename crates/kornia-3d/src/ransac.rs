@@ -0,0 +1,241 @@
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+/// Error types for the RANSAC estimation.
+#[derive(Debug, thiserror::Error, PartialEq)]
+pub enum RansacError {
+    /// Not enough data to fit the model.
+    #[error("Not enough data to fit the model. Got {0}, expected at least {1}")]
+    NotEnoughData(usize, usize),
+
+    /// No model could be fitted to the data.
+    #[error("No model could be fitted to the data")]
+    NoModelFound,
+
+    /// The data is missing a required attribute.
+    #[error("Missing data: {0}")]
+    MissingData(String),
+}
+
+/// A model that can be robustly estimated with RANSAC.
+pub trait RansacModel: Sized {
+    /// The type of a data element, e.g. a point.
+    type Data;
+
+    /// The minimum number of data elements to fit the model.
+    const MIN_SAMPLES: usize;
+
+    /// Fit the model to a set of data elements.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - At least `MIN_SAMPLES` data elements. With more elements the model is fitted
+    ///   in the least squares sense.
+    ///
+    /// # Returns
+    ///
+    /// The model or None if the data is degenerate.
+    fn fit(data: &[&Self::Data]) -> Option<Self>;
+
+    /// Compute the distance of a data element to the model.
+    fn residual(&self, data: &Self::Data) -> f64;
+}
+
+/// Parameters of the RANSAC estimation.
+#[derive(Debug, Clone)]
+pub struct RansacParams {
+    /// The maximum number of iterations.
+    pub max_iterations: usize,
+    /// The maximum residual of an inlier.
+    pub threshold: f64,
+    /// The probability of sampling at least one outlier-free set used to stop early.
+    pub confidence: f64,
+    /// The seed of the random number generator of [`ransac`], ignored by
+    /// [`ransac_with_rng`].
+    pub seed: u64,
+}
+
+impl Default for RansacParams {
+    fn default() -> Self {
+        Self {
+            max_iterations: 1000,
+            threshold: 0.01,
+            confidence: 0.99,
+            seed: 0,
+        }
+    }
+}
+
+/// Result of the RANSAC estimation.
+#[derive(Debug, Clone)]
+pub struct RansacResult<M> {
+    /// The model refitted to all the inliers.
+    pub model: M,
+    /// The indices of the inliers of the model.
+    pub inliers: Vec<usize>,
+}
+
+/// Estimate a model robust to outliers with random sample consensus (RANSAC).
+///
+/// Minimal sets of data are sampled to fit candidate models and the model with the most
+/// inliers is refitted to all its inliers. The number of iterations is adapted to the ratio
+/// of inliers to reach the requested confidence.
+///
+/// # Arguments
+///
+/// * `data` - The data elements.
+/// * `params` - The parameters of the estimation.
+///
+/// # Returns
+///
+/// The best model and its inliers.
+pub fn ransac<M: RansacModel>(
+    data: &[M::Data],
+    params: &RansacParams,
+) -> Result<RansacResult<M>, RansacError> {
+    ransac_with_rng(data, params, &mut StdRng::seed_from_u64(params.seed))
+}
+
+/// Estimate a model robust to outliers with RANSAC and a given random number generator.
+///
+/// The estimation is the same as [`ransac`], with the samples drawn from `rng` instead of
+/// a generator seeded with `params.seed`, e.g. to share a generator across estimations.
+///
+/// # Arguments
+///
+/// * `data` - The data elements.
+/// * `params` - The parameters of the estimation.
+/// * `rng` - The random number generator.
+///
+/// # Returns
+///
+/// The best model and its inliers.
+pub fn ransac_with_rng<M: RansacModel, R: Rng + ?Sized>(
+    data: &[M::Data],
+    params: &RansacParams,
+    rng: &mut R,
+) -> Result<RansacResult<M>, RansacError> {
+    if data.len() < M::MIN_SAMPLES {
+        return Err(RansacError::NotEnoughData(data.len(), M::MIN_SAMPLES));
+    }
+
+    let inliers_of = |model: &M| -> Vec<usize> {
+        (0..data.len())
+            .filter(|&i| model.residual(&data[i]) <= params.threshold)
+            .collect()
+    };
+
+    let mut best: Option<(M, Vec<usize>)> = None;
+    let mut max_iterations = params.max_iterations;
+    let mut iteration = 0;
+
+    while iteration < max_iterations {
+        iteration += 1;
+
+        let samples = rand::seq::index::sample(rng, data.len(), M::MIN_SAMPLES)
+            .iter()
+            .map(|i| &data[i])
+            .collect::<Vec<_>>();
+        let Some(model) = M::fit(&samples) else {
+            continue;
+        };
+
+        let inliers = inliers_of(&model);
+        if best.as_ref().is_some_and(|(_, b)| b.len() >= inliers.len()) {
+            continue;
+        }
+
+        // update the number of iterations to reach the confidence
+        let inlier_ratio = inliers.len() as f64 / data.len() as f64;
+        let outlier_free = inlier_ratio.powi(M::MIN_SAMPLES as i32);
+        if outlier_free >= 1.0 {
+            max_iterations = iteration;
+        } else if outlier_free > 0.0 {
+            let needed = (1.0 - params.confidence).ln() / (1.0 - outlier_free).ln();
+            max_iterations = max_iterations.min(needed.ceil() as usize);
+        }
+
+        best = Some((model, inliers));
+    }
+
+    let (model, inliers) = best.ok_or(RansacError::NoModelFound)?;
+
+    // refit the model to all the inliers
+    let inlier_data = inliers.iter().map(|&i| &data[i]).collect::<Vec<_>>();
+    match M::fit(&inlier_data) {
+        Some(refined) => {
+            let refined_inliers = inliers_of(&refined);
+            if refined_inliers.len() >= inliers.len() {
+                return Ok(RansacResult {
+                    model: refined,
+                    inliers: refined_inliers,
+                });
+            }
+            Ok(RansacResult { model, inliers })
+        }
+        None => Ok(RansacResult { model, inliers }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // a 2D line y = a * x + b
+    struct Line {
+        a: f64,
+        b: f64,
+    }
+
+    impl RansacModel for Line {
+        type Data = [f64; 2];
+        const MIN_SAMPLES: usize = 2;
+
+        fn fit(data: &[&[f64; 2]]) -> Option<Self> {
+            let n = data.len() as f64;
+            let (sx, sy) = data
+                .iter()
+                .fold((0.0, 0.0), |s, p| (s.0 + p[0], s.1 + p[1]));
+            let (mx, my) = (sx / n, sy / n);
+            let sxx = data.iter().map(|p| (p[0] - mx).powi(2)).sum::<f64>();
+            let sxy = data.iter().map(|p| (p[0] - mx) * (p[1] - my)).sum::<f64>();
+            if sxx == 0.0 {
+                return None;
+            }
+            let a = sxy / sxx;
+            Some(Self { a, b: my - a * mx })
+        }
+
+        fn residual(&self, p: &[f64; 2]) -> f64 {
+            (p[1] - self.a * p[0] - self.b).abs()
+        }
+    }
+
+    #[test]
+    fn test_ransac_line() -> Result<(), RansacError> {
+        let mut data = (0..50)
+            .map(|i| [i as f64, 2.0 * i as f64 + 1.0])
+            .collect::<Vec<_>>();
+        data.extend((0..20).map(|i| [i as f64, 100.0 - 3.0 * i as f64]));
+
+        let result = ransac::<Line>(&data, &RansacParams::default())?;
+        assert!((result.model.a - 2.0).abs() < 1e-9);
+        assert!((result.model.b - 1.0).abs() < 1e-9);
+        assert_eq!(result.inliers, (0..50).collect::<Vec<_>>());
+
+        // the same seed gives the same result
+        let other = ransac::<Line>(&data, &RansacParams::default())?;
+        assert_eq!(other.inliers, result.inliers);
+
+        // and so does a generator with the same seed
+        let mut rng = StdRng::seed_from_u64(0);
+        let other = ransac_with_rng::<Line, _>(&data, &RansacParams::default(), &mut rng)?;
+        assert_eq!(other.inliers, result.inliers);
+
+        assert_eq!(
+            ransac::<Line>(&data[..1], &RansacParams::default()).err(),
+            Some(RansacError::NotEnoughData(1, 2))
+        );
+
+        Ok(())
+    }
+}
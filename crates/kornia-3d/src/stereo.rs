@@ -0,0 +1,254 @@
+use kornia_image::{Image, ImageError};
+use kornia_imgproc::calibration::CameraIntrinsic;
+use rayon::prelude::*;
+
+use crate::pointcloud::PointCloud;
+
+/// Compute the disparity-to-depth matrix Q of a rectified stereo pair.
+///
+/// The matrix maps a pixel `(u, v)` of the left image with disparity `d` to the homogeneous
+/// 3D point `Q * [u, v, d, 1]` in the frame of the left camera, as the `Q` output of the
+/// stereo rectification.
+///
+/// # Arguments
+///
+/// * `intrinsics` - The intrinsic parameters of the rectified left camera.
+/// * `cx_right` - The x coordinate of the principal point of the rectified right camera.
+/// * `baseline` - The distance between the camera centers, in the units of the output points.
+///
+/// # Returns
+///
+/// The 4x4 disparity-to-depth matrix.
+pub fn disparity_to_depth_matrix(
+    intrinsics: &CameraIntrinsic,
+    cx_right: f64,
+    baseline: f64,
+) -> [[f64; 4]; 4] {
+    [
+        [1.0, 0.0, 0.0, -intrinsics.cx],
+        [0.0, 1.0, 0.0, -intrinsics.cy],
+        [0.0, 0.0, 0.0, intrinsics.fx],
+        [
+            0.0,
+            0.0,
+            1.0 / baseline,
+            (cx_right - intrinsics.cx) / baseline,
+        ],
+    ]
+}
+
+// reproject a pixel with its disparity to a 3D point in front of the camera
+fn reproject(u: usize, v: usize, disparity: f32, q: &[[f64; 4]; 4]) -> Option<[f64; 3]> {
+    if !(disparity.is_finite() && disparity > 0.0) {
+        return None;
+    }
+
+    let p = [u as f64, v as f64, disparity as f64, 1.0];
+    let [x, y, z, w] = q.map(|row| row.iter().zip(&p).map(|(a, b)| a * b).sum::<f64>());
+    let point = [x / w, y / w, z / w];
+
+    (point.iter().all(|c| c.is_finite()) && point[2] > 0.0).then_some(point)
+}
+
+/// Convert a disparity image to a depth image.
+///
+/// The pixels with zero, negative or non finite disparity, or reprojected behind the
+/// camera, get a zero depth.
+///
+/// # Arguments
+///
+/// * `disparity` - The disparity image of the rectified left camera, in pixels.
+/// * `q` - The disparity-to-depth matrix, e.g. from [`disparity_to_depth_matrix`].
+///
+/// # Returns
+///
+/// The depth image in the units of the baseline.
+///
+/// # Example
+///
+/// ```
+/// use kornia_3d::stereo::{disparity_to_depth, disparity_to_depth_matrix};
+/// use kornia_image::Image;
+/// use kornia_imgproc::calibration::CameraIntrinsic;
+///
+/// let intrinsics = CameraIntrinsic { fx: 100.0, fy: 100.0, cx: 2.0, cy: 1.5 };
+/// let q = disparity_to_depth_matrix(&intrinsics, 2.0, 0.1);
+/// let disparity = Image::<f32, 1>::from_size_val([4, 3].into(), 5.0).unwrap();
+/// let depth = disparity_to_depth(&disparity, &q).unwrap();
+/// assert!((depth.as_slice()[0] - 2.0).abs() < 1e-6);
+/// ```
+pub fn disparity_to_depth(
+    disparity: &Image<f32, 1>,
+    q: &[[f64; 4]; 4],
+) -> Result<Image<f32, 1>, ImageError> {
+    let cols = disparity.cols();
+    let mut depth = Image::from_size_val(disparity.size(), 0.0f32)?;
+
+    depth
+        .as_slice_mut()
+        .par_chunks_exact_mut(cols)
+        .zip(disparity.as_slice().par_chunks_exact(cols))
+        .enumerate()
+        .for_each(|(v, (depth_row, disparity_row))| {
+            for (u, (z, &d)) in depth_row.iter_mut().zip(disparity_row).enumerate() {
+                if let Some(point) = reproject(u, v, d, q) {
+                    *z = point[2] as f32;
+                }
+            }
+        });
+
+    Ok(depth)
+}
+
+/// Reproject a disparity image to an image of 3D points.
+///
+/// The pixels with an invalid disparity get a zero point.
+///
+/// # Arguments
+///
+/// * `disparity` - The disparity image of the rectified left camera, in pixels.
+/// * `q` - The disparity-to-depth matrix, e.g. from [`disparity_to_depth_matrix`].
+///
+/// # Returns
+///
+/// The image with the 3D points in the frame of the rectified left camera.
+pub fn reproject_disparity_to_3d(
+    disparity: &Image<f32, 1>,
+    q: &[[f64; 4]; 4],
+) -> Result<Image<f32, 3>, ImageError> {
+    let cols = disparity.cols();
+    let mut points = Image::from_size_val(disparity.size(), 0.0f32)?;
+
+    points
+        .as_slice_mut()
+        .par_chunks_exact_mut(cols * 3)
+        .zip(disparity.as_slice().par_chunks_exact(cols))
+        .enumerate()
+        .for_each(|(v, (points_row, disparity_row))| {
+            for (u, (xyz, &d)) in points_row
+                .chunks_exact_mut(3)
+                .zip(disparity_row)
+                .enumerate()
+            {
+                if let Some(point) = reproject(u, v, d, q) {
+                    xyz.iter_mut()
+                        .zip(point)
+                        .for_each(|(dst, src)| *dst = src as f32);
+                }
+            }
+        });
+
+    Ok(points)
+}
+
+/// Reproject a disparity image to a point cloud.
+///
+/// # Arguments
+///
+/// * `disparity` - The disparity image of the rectified left camera, in pixels.
+/// * `color` - The optional rectified left image to color the points.
+/// * `q` - The disparity-to-depth matrix, e.g. from [`disparity_to_depth_matrix`].
+///
+/// # Returns
+///
+/// The point cloud with a point per valid pixel, in row-major order.
+pub fn disparity_to_pointcloud(
+    disparity: &Image<f32, 1>,
+    color: Option<&Image<u8, 3>>,
+    q: &[[f64; 4]; 4],
+) -> Result<PointCloud, ImageError> {
+    if let Some(color) = color {
+        if color.size() != disparity.size() {
+            return Err(ImageError::InvalidImageSize(
+                color.cols(),
+                color.rows(),
+                disparity.cols(),
+                disparity.rows(),
+            ));
+        }
+    }
+
+    let cols = disparity.cols();
+    let mut points = Vec::new();
+    let mut colors = Vec::new();
+    for (idx, &d) in disparity.as_slice().iter().enumerate() {
+        let Some(point) = reproject(idx % cols, idx / cols, d, q) else {
+            continue;
+        };
+        points.push(point);
+        if let Some(color) = color {
+            let rgb = &color.as_slice()[idx * 3..idx * 3 + 3];
+            colors.push([rgb[0], rgb[1], rgb[2]]);
+        }
+    }
+
+    Ok(PointCloud::new(points, color.map(|_| colors), None))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::depth::depth_to_pointcloud;
+    use approx::assert_relative_eq;
+
+    const INTRINSICS: CameraIntrinsic = CameraIntrinsic {
+        fx: 100.0,
+        fy: 100.0,
+        cx: 2.0,
+        cy: 1.0,
+    };
+
+    #[test]
+    fn test_disparity_to_depth() -> Result<(), ImageError> {
+        let q = disparity_to_depth_matrix(&INTRINSICS, 2.0, 0.1);
+        let disparity =
+            Image::<f32, 1>::new([3, 2].into(), vec![10.0, 0.0, 5.0, -1.0, f32::NAN, 2.0])?;
+
+        let depth = disparity_to_depth(&disparity, &q)?;
+        let expected = [1.0, 0.0, 2.0, 0.0, 0.0, 5.0];
+        for (z, e) in depth.as_slice().iter().zip(expected) {
+            assert_relative_eq!(*z, e, epsilon = 1e-6);
+        }
+
+        // a different principal point of the right camera shifts the disparity
+        let q = disparity_to_depth_matrix(&INTRINSICS, 1.0, 0.1);
+        let depth = disparity_to_depth(&disparity, &q)?;
+        assert_relative_eq!(depth.as_slice()[0], 10.0 / 9.0, epsilon = 1e-6);
+        assert_relative_eq!(depth.as_slice()[5], 10.0, epsilon = 1e-6);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_disparity_to_pointcloud() -> Result<(), ImageError> {
+        let q = disparity_to_depth_matrix(&INTRINSICS, 2.0, 0.1);
+        let disparity = Image::<f32, 1>::new([3, 2].into(), vec![10.0, 0.0, 5.0, 4.0, 20.0, 2.0])?;
+        let color = Image::<u8, 3>::new([3, 2].into(), (0..18).collect())?;
+
+        // the stereo points match the back-projection of the depth
+        let depth = disparity_to_depth(&disparity, &q)?;
+        let expected = depth_to_pointcloud(&depth, &INTRINSICS, 1.0);
+
+        let pointcloud = disparity_to_pointcloud(&disparity, Some(&color), &q)?;
+        assert_eq!(pointcloud.len(), 5);
+        for (p, e) in pointcloud.points().iter().zip(expected.points()) {
+            for (a, b) in p.iter().zip(e) {
+                assert_relative_eq!(a, b, epsilon = 1e-6);
+            }
+        }
+        assert_eq!(pointcloud.colors().unwrap()[1], [6, 7, 8]);
+
+        let points = reproject_disparity_to_3d(&disparity, &q)?;
+        assert_eq!(&points.as_slice()[3..6], &[0.0; 3]);
+        for (xyz, p) in points.as_slice()[6..]
+            .chunks_exact(3)
+            .zip(&pointcloud.points()[1..])
+        {
+            for (a, b) in xyz.iter().zip(p) {
+                assert_relative_eq!(*a as f64, b, epsilon = 1e-6);
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,476 @@
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+use rayon::prelude::*;
+
+use kornia_image::{Image, ImageError};
+use kornia_imgproc::calibration::CameraIntrinsic;
+
+use crate::{depth::DepthSample, mesh::TriangleMesh};
+
+/// The edges of a cell as pairs of corners, grouped by axis.
+///
+/// The corner `i` of a cell is at the offset `(i & 1, (i >> 1) & 1, (i >> 2) & 1)`.
+const EDGES: [(usize, usize); 12] = [
+    (0, 1),
+    (2, 3),
+    (4, 5),
+    (6, 7),
+    (0, 2),
+    (1, 3),
+    (4, 6),
+    (5, 7),
+    (0, 4),
+    (1, 5),
+    (2, 6),
+    (3, 7),
+];
+
+/// A truncated signed distance function (TSDF) volume to fuse depth frames.
+///
+/// The volume is a dense grid of voxels storing the truncated signed distance to the closest
+/// surface, positive in front of the surface and negative behind it, averaged over all the
+/// integrated frames. The surface is extracted as a triangle mesh with marching cubes.
+///
+/// # Example
+///
+/// ```
+/// use kornia_3d::tsdf::TsdfVolume;
+/// use kornia_image::Image;
+/// use kornia_imgproc::calibration::CameraIntrinsic;
+///
+/// let mut volume = TsdfVolume::new([-0.5, -0.5, 0.5], 0.05, [20, 20, 20], 0.15);
+///
+/// let depth = Image::<u16, 1>::from_size_val([64, 48].into(), 1000).unwrap();
+/// let intrinsics = CameraIntrinsic { fx: 50.0, fy: 50.0, cx: 31.5, cy: 23.5 };
+/// let identity = [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]];
+/// volume
+///     .integrate(&depth, None, &intrinsics, 1e-3, &identity, &[0.0; 3])
+///     .unwrap();
+///
+/// let mesh = volume.extract_mesh();
+/// assert!(!mesh.is_empty());
+/// ```
+#[derive(Debug, Clone)]
+pub struct TsdfVolume {
+    // the position of the corner of the volume in the world frame
+    origin: [f64; 3],
+    // the size of the side of a voxel in meters
+    voxel_size: f64,
+    // the number of voxels along each axis
+    dims: [usize; 3],
+    // the truncation distance of the signed distance in meters
+    sdf_trunc: f64,
+    // the signed distance normalized by the truncation distance
+    tsdf: Vec<f32>,
+    // the accumulated weight of the observations
+    weights: Vec<f32>,
+    // the accumulated color of the observations
+    colors: Vec<[f32; 3]>,
+    // whether any frame has been integrated with colors
+    has_colors: bool,
+}
+
+impl TsdfVolume {
+    /// Create a new empty TSDF volume.
+    ///
+    /// # Arguments
+    ///
+    /// * `origin` - The position of the corner of the volume in the world frame.
+    /// * `voxel_size` - The size of the side of a voxel in meters.
+    /// * `dims` - The number of voxels along the x, y and z axes.
+    /// * `sdf_trunc` - The truncation distance of the signed distance, usually a few voxels.
+    pub fn new(origin: [f64; 3], voxel_size: f64, dims: [usize; 3], sdf_trunc: f64) -> Self {
+        let num_voxels = dims[0] * dims[1] * dims[2];
+        Self {
+            origin,
+            voxel_size,
+            dims,
+            sdf_trunc,
+            tsdf: vec![1.0; num_voxels],
+            weights: vec![0.0; num_voxels],
+            colors: vec![[0.0; 3]; num_voxels],
+            has_colors: false,
+        }
+    }
+
+    /// Get the position of the corner of the volume in the world frame.
+    pub fn origin(&self) -> [f64; 3] {
+        self.origin
+    }
+
+    /// Get the size of the side of a voxel in meters.
+    pub fn voxel_size(&self) -> f64 {
+        self.voxel_size
+    }
+
+    /// Get the number of voxels along each axis.
+    pub fn dims(&self) -> [usize; 3] {
+        self.dims
+    }
+
+    /// Integrate a posed depth frame, and optionally its aligned color image, in the volume.
+    ///
+    /// Each voxel is projected to the depth image and the truncated signed distance along
+    /// the viewing ray is averaged with the previous observations.
+    ///
+    /// # Arguments
+    ///
+    /// * `depth` - The depth image.
+    /// * `color` - The color image aligned to the depth image.
+    /// * `intrinsics` - The intrinsic parameters of the depth camera.
+    /// * `depth_scale` - The scale to convert the depth values to meters.
+    /// * `world_r_camera` - The rotation of the camera in the world frame.
+    /// * `world_t_camera` - The position of the camera in the world frame.
+    pub fn integrate<T: DepthSample + Sync>(
+        &mut self,
+        depth: &Image<T, 1>,
+        color: Option<&Image<u8, 3>>,
+        intrinsics: &CameraIntrinsic,
+        depth_scale: f64,
+        world_r_camera: &[[f64; 3]; 3],
+        world_t_camera: &[f64; 3],
+    ) -> Result<(), ImageError> {
+        if let Some(color) = color {
+            if color.size() != depth.size() {
+                return Err(ImageError::InvalidImageSize(
+                    color.cols(),
+                    color.rows(),
+                    depth.cols(),
+                    depth.rows(),
+                ));
+            }
+            self.has_colors = true;
+        }
+
+        if self.tsdf.is_empty() {
+            return Ok(());
+        }
+
+        let (cols, rows) = (depth.cols(), depth.rows());
+        let [nx, ny, _] = self.dims;
+        let (origin, voxel_size, sdf_trunc) = (self.origin, self.voxel_size, self.sdf_trunc);
+
+        self.tsdf
+            .par_chunks_exact_mut(nx * ny)
+            .zip(self.weights.par_chunks_exact_mut(nx * ny))
+            .zip(self.colors.par_chunks_exact_mut(nx * ny))
+            .enumerate()
+            .for_each(|(z, ((tsdf, weights), colors))| {
+                for (i, ((tsdf, weight), rgb)) in tsdf
+                    .iter_mut()
+                    .zip(weights.iter_mut())
+                    .zip(colors.iter_mut())
+                    .enumerate()
+                {
+                    let voxel = [i % nx, i / nx, z];
+                    let p = std::array::from_fn::<_, 3, _>(|k| {
+                        origin[k] + (voxel[k] as f64 + 0.5) * voxel_size - world_t_camera[k]
+                    });
+
+                    // transform the voxel center to the camera frame as R^T * (p - t)
+                    let pc = std::array::from_fn::<_, 3, _>(|k| {
+                        (0..3).map(|j| world_r_camera[j][k] * p[j]).sum::<f64>()
+                    });
+                    if pc[2] <= 0.0 {
+                        continue;
+                    }
+
+                    let u = (intrinsics.fx * pc[0] / pc[2] + intrinsics.cx).round();
+                    let v = (intrinsics.fy * pc[1] / pc[2] + intrinsics.cy).round();
+                    if u < 0.0 || v < 0.0 || u >= cols as f64 || v >= rows as f64 {
+                        continue;
+                    }
+                    let pixel = v as usize * cols + u as usize;
+
+                    let Some(d) = depth.as_slice()[pixel].to_meters(depth_scale) else {
+                        continue;
+                    };
+
+                    // skip the voxels occluded by the surface
+                    let sdf = d - pc[2];
+                    if sdf < -sdf_trunc {
+                        continue;
+                    }
+                    let value = (sdf / sdf_trunc).min(1.0) as f32;
+
+                    let w = *weight;
+                    *tsdf = (*tsdf * w + value) / (w + 1.0);
+                    if let Some(color) = color {
+                        let c = &color.as_slice()[pixel * 3..pixel * 3 + 3];
+                        for k in 0..3 {
+                            rgb[k] = (rgb[k] * w + c[k] as f32) / (w + 1.0);
+                        }
+                    }
+                    *weight = w + 1.0;
+                }
+            });
+
+        Ok(())
+    }
+
+    /// Extract the zero level set of the volume as a triangle mesh with marching cubes.
+    ///
+    /// Only the cells with all the corners observed are triangulated and the triangles face
+    /// the free space. The vertices are colored if any frame was integrated with colors.
+    ///
+    /// # Returns
+    ///
+    /// The triangle mesh in the world frame.
+    pub fn extract_mesh(&self) -> TriangleMesh {
+        let table = triangle_table();
+        let [nx, ny, nz] = self.dims;
+
+        let mut vertices = Vec::new();
+        let mut colors = Vec::new();
+        let mut triangles = Vec::new();
+
+        // the vertices are shared between the cells through the voxel and axis of their edge
+        let mut edge_vertices = HashMap::<(usize, usize), usize>::new();
+
+        for z in 0..nz.saturating_sub(1) {
+            for y in 0..ny.saturating_sub(1) {
+                for x in 0..nx.saturating_sub(1) {
+                    let corners: [usize; 8] = std::array::from_fn(|c| {
+                        ((z + (c >> 2)) * ny + y + ((c >> 1) & 1)) * nx + x + (c & 1)
+                    });
+                    if corners.iter().any(|&i| self.weights[i] == 0.0) {
+                        continue;
+                    }
+
+                    let mask = (0..8)
+                        .filter(|&c| self.tsdf[corners[c]] < 0.0)
+                        .fold(0, |mask, c| mask | (1 << c));
+
+                    for triangle in &table[mask] {
+                        triangles.push(triangle.map(|edge| {
+                            let (a, b) = EDGES[edge as usize];
+                            let (ia, ib) = (corners[a], corners[b]);
+                            *edge_vertices
+                                .entry((ia, edge as usize / 4))
+                                .or_insert_with(|| {
+                                    // the signs of the distances differ along the edge
+                                    let (va, vb) = (self.tsdf[ia], self.tsdf[ib]);
+                                    let t = va / (va - vb);
+                                    let (pa, pb) = (self.voxel_center(ia), self.voxel_center(ib));
+                                    vertices.push(std::array::from_fn(|k| {
+                                        pa[k] + t as f64 * (pb[k] - pa[k])
+                                    }));
+                                    let (ca, cb) = (self.colors[ia], self.colors[ib]);
+                                    colors.push(std::array::from_fn(|k| {
+                                        (ca[k] + t * (cb[k] - ca[k])).round() as u8
+                                    }));
+                                    vertices.len() - 1
+                                })
+                        }));
+                    }
+                }
+            }
+        }
+
+        TriangleMesh::new(vertices, self.has_colors.then_some(colors), triangles)
+    }
+
+    // the position of the center of a voxel in the world frame
+    fn voxel_center(&self, index: usize) -> [f64; 3] {
+        let [nx, ny, _] = self.dims;
+        let voxel = [index % nx, (index / nx) % ny, index / (nx * ny)];
+        std::array::from_fn(|k| self.origin[k] + (voxel[k] as f64 + 0.5) * self.voxel_size)
+    }
+}
+
+// the triangles of each configuration of the corners of a cell as indices of the edges
+fn triangle_table() -> &'static [Vec<[u8; 3]>] {
+    static TABLE: OnceLock<Vec<Vec<[u8; 3]>>> = OnceLock::new();
+    TABLE.get_or_init(|| (0..256).map(cell_triangles).collect())
+}
+
+// triangulate a cell by linking the crossed edges on each face and closing the loops
+fn cell_triangles(mask: usize) -> Vec<[u8; 3]> {
+    let inside = |c: usize| mask & (1 << c) != 0;
+    let corner = |c: usize| [c & 1, (c >> 1) & 1, (c >> 2) & 1].map(|v| v as f64);
+    let edge_of = |a: usize, b: usize| {
+        EDGES
+            .iter()
+            .position(|&e| e == (a, b) || e == (b, a))
+            .unwrap_or_default()
+    };
+
+    let mut links: [Vec<usize>; 12] = Default::default();
+    for axis in 0..3 {
+        let (u, v) = ((axis + 1) % 3, (axis + 2) % 3);
+        for side in 0..2 {
+            // the corners of the face in cyclic order
+            let face =
+                [(0, 0), (1, 0), (1, 1), (0, 1)].map(|(bu, bv)| side << axis | bu << u | bv << v);
+            let face_edge = |k: usize| edge_of(face[k], face[(k + 1) % 4]);
+            let crossed = (0..4)
+                .filter(|&k| inside(face[k]) != inside(face[(k + 1) % 4]))
+                .collect::<Vec<_>>();
+
+            let mut link = |k0: usize, k1: usize| {
+                let (e0, e1) = (face_edge(k0), face_edge(k1));
+                links[e0].push(e1);
+                links[e1].push(e0);
+            };
+            match crossed.len() {
+                2 => link(crossed[0], crossed[1]),
+                // ambiguous face, separate the inside corners
+                4 => (0..4)
+                    .filter(|&k| inside(face[k]))
+                    .for_each(|k| link((k + 3) % 4, k)),
+                _ => {}
+            }
+        }
+    }
+
+    let mut triangles = Vec::new();
+    let mut visited = [false; 12];
+    for start in 0..12 {
+        if visited[start] || links[start].is_empty() {
+            continue;
+        }
+
+        // walk the loop of crossed edges
+        let mut polygon = vec![start];
+        visited[start] = true;
+        let (mut prev, mut current) = (start, links[start][0]);
+        while current != start {
+            visited[current] = true;
+            polygon.push(current);
+            let next = if links[current][0] == prev {
+                links[current][1]
+            } else {
+                links[current][0]
+            };
+            (prev, current) = (current, next);
+        }
+
+        // orient the polygon towards the outside corners with the normal of Newell
+        let midpoint = |e: usize| {
+            let (a, b) = EDGES[e];
+            let (pa, pb) = (corner(a), corner(b));
+            [0, 1, 2].map(|k| 0.5 * (pa[k] + pb[k]))
+        };
+        let mut normal = [0.0; 3];
+        let mut outward = [0.0; 3];
+        for (i, &e) in polygon.iter().enumerate() {
+            let (p, q) = (midpoint(e), midpoint(polygon[(i + 1) % polygon.len()]));
+            normal[0] += (p[1] - q[1]) * (p[2] + q[2]);
+            normal[1] += (p[2] - q[2]) * (p[0] + q[0]);
+            normal[2] += (p[0] - q[0]) * (p[1] + q[1]);
+
+            let (a, b) = EDGES[e];
+            let (pin, pout) = if inside(a) { (a, b) } else { (b, a) };
+            let (pin, pout) = (corner(pin), corner(pout));
+            for k in 0..3 {
+                outward[k] += pout[k] - pin[k];
+            }
+        }
+        if (0..3).map(|k| normal[k] * outward[k]).sum::<f64>() < 0.0 {
+            polygon.reverse();
+        }
+
+        for i in 1..polygon.len() - 1 {
+            triangles.push([polygon[0], polygon[i], polygon[i + 1]].map(|e| e as u8));
+        }
+    }
+
+    triangles
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn triangle_normal(mesh: &TriangleMesh, triangle: &[usize; 3]) -> [f64; 3] {
+        let [a, b, c] = triangle.map(|i| mesh.vertices()[i]);
+        let (u, v) = (
+            [0, 1, 2].map(|k| b[k] - a[k]),
+            [0, 1, 2].map(|k| c[k] - a[k]),
+        );
+        [
+            u[1] * v[2] - u[2] * v[1],
+            u[2] * v[0] - u[0] * v[2],
+            u[0] * v[1] - u[1] * v[0],
+        ]
+    }
+
+    #[test]
+    fn test_marching_cubes_sphere() {
+        let radius = 0.3;
+        let mut volume = TsdfVolume::new([-0.5; 3], 0.05, [20, 20, 20], 0.1);
+        for i in 0..volume.tsdf.len() {
+            let p = volume.voxel_center(i);
+            let sdf = (p.iter().map(|v| v * v).sum::<f64>()).sqrt() - radius;
+            volume.tsdf[i] = (sdf / volume.sdf_trunc).clamp(-1.0, 1.0) as f32;
+            volume.weights[i] = 1.0;
+        }
+
+        let mesh = volume.extract_mesh();
+        assert!(!mesh.is_empty());
+        assert!(mesh.colors().is_none());
+
+        for v in mesh.vertices() {
+            let r = v.iter().map(|x| x * x).sum::<f64>().sqrt();
+            assert!((r - radius).abs() < 0.01, "vertex at radius {r}");
+        }
+
+        // the mesh is closed and consistently oriented: each directed edge appears once and
+        // its opposite is used by the neighbor triangle
+        let mut edges = HashMap::new();
+        for t in mesh.triangles() {
+            for k in 0..3 {
+                *edges.entry((t[k], t[(k + 1) % 3])).or_insert(0) += 1;
+            }
+        }
+        for (&(a, b), &count) in &edges {
+            assert_eq!(count, 1);
+            assert_eq!(edges.get(&(b, a)), Some(&1));
+        }
+
+        // the triangles face outwards
+        for t in mesh.triangles() {
+            let n = triangle_normal(&mesh, t);
+            let c = mesh.vertices()[t[0]];
+            assert!((0..3).map(|k| n[k] * c[k]).sum::<f64>() > 0.0);
+        }
+    }
+
+    #[test]
+    fn test_integrate_plane() -> Result<(), ImageError> {
+        let mut volume = TsdfVolume::new([-0.5, -0.5, 0.5], 0.02, [50, 50, 50], 0.06);
+
+        let size = [64, 48].into();
+        let depth = Image::<f32, 1>::from_size_val(size, 1.0)?;
+        let color = Image::<u8, 3>::from_size_val(size, 200)?;
+        let intrinsics = CameraIntrinsic {
+            fx: 50.0,
+            fy: 50.0,
+            cx: 31.5,
+            cy: 23.5,
+        };
+        let identity = [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]];
+
+        for _ in 0..2 {
+            volume.integrate(&depth, Some(&color), &intrinsics, 1.0, &identity, &[0.0; 3])?;
+        }
+
+        let mesh = volume.extract_mesh();
+        assert!(!mesh.is_empty());
+        assert!(mesh.vertices().iter().all(|v| (v[2] - 1.0).abs() < 1e-6));
+        assert!(mesh.colors().unwrap().iter().all(|c| *c == [200; 3]));
+
+        // the surface faces the camera
+        for t in mesh.triangles() {
+            assert!(triangle_normal(&mesh, t)[2] < 0.0);
+        }
+
+        let small = Image::<u8, 3>::from_size_val([2, 2].into(), 0)?;
+        assert!(volume
+            .integrate(&depth, Some(&small), &intrinsics, 1.0, &identity, &[0.0; 3])
+            .is_err());
+
+        Ok(())
+    }
+}
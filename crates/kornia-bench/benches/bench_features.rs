@@ -0,0 +1,123 @@
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+
+use kornia_bench::inputs::{natural_image_gray8, natural_image_gray_f32, IMAGE_SIZES};
+use kornia_image::Image;
+use kornia_imgproc::features::{
+    fast_feature_detector, gftt_response, non_max_suppression, GradsMode, HarrisResponse,
+};
+
+fn bench_fast(c: &mut Criterion) {
+    let mut group = c.benchmark_group("FastDetector");
+
+    for (width, height) in IMAGE_SIZES {
+        group.throughput(criterion::Throughput::Elements((width * height) as u64));
+
+        let parameter_string = format!("{}x{}", width, height);
+
+        let image = natural_image_gray8([width, height].into()).unwrap();
+
+        group.bench_with_input(
+            BenchmarkId::new("kornia", &parameter_string),
+            &image,
+            |b, src| b.iter(|| black_box(fast_feature_detector(src, 20, 9))),
+        );
+
+        #[cfg(feature = "opencv")]
+        {
+            let src = kornia_bench::opencv::mat_from_image_u8(&image).unwrap();
+            let mut keypoints = opencv::core::Vector::<opencv::core::KeyPoint>::new();
+            group.bench_function(BenchmarkId::new("opencv", &parameter_string), |b| {
+                b.iter(|| black_box(opencv::features2d::fast_def(&src, &mut keypoints, 20)))
+            });
+        }
+    }
+    group.finish();
+}
+
+fn bench_harris(c: &mut Criterion) {
+    let mut group = c.benchmark_group("HarrisResponse");
+
+    for (width, height) in IMAGE_SIZES {
+        group.throughput(criterion::Throughput::Elements((width * height) as u64));
+
+        let parameter_string = format!("{}x{}", width, height);
+
+        let image = natural_image_gray_f32([width, height].into()).unwrap();
+        let output = Image::<f32, 1>::from_size_val(image.size(), 0.0).unwrap();
+
+        group.bench_with_input(
+            BenchmarkId::new("kornia", &parameter_string),
+            &(&image, &output),
+            |b, i| {
+                let (src, mut dst) = (i.0, i.1.clone());
+                let mut harris = HarrisResponse::new(src.size());
+                b.iter(|| black_box(harris.compute(src, &mut dst)))
+            },
+        );
+
+        #[cfg(feature = "opencv")]
+        {
+            let src = kornia_bench::opencv::mat_from_image_f32(&image).unwrap();
+            let mut dst = opencv::core::Mat::default();
+            group.bench_function(BenchmarkId::new("opencv", &parameter_string), |b| {
+                b.iter(|| {
+                    black_box(opencv::imgproc::corner_harris_def(
+                        &src, &mut dst, 3, 3, 0.04,
+                    ))
+                })
+            });
+        }
+    }
+    group.finish();
+}
+
+fn bench_good_features_to_track(c: &mut Criterion) {
+    let mut group = c.benchmark_group("GoodFeaturesToTrack");
+
+    for (width, height) in IMAGE_SIZES {
+        group.throughput(criterion::Throughput::Elements((width * height) as u64));
+
+        let parameter_string = format!("{}x{}", width, height);
+
+        let image = natural_image_gray_f32([width, height].into()).unwrap();
+        let output = Image::<f32, 1>::from_size_val(image.size(), 0.0).unwrap();
+
+        group.bench_with_input(
+            BenchmarkId::new("kornia", &parameter_string),
+            &(&image, &output),
+            |b, i| {
+                let (src, mut dst) = (i.0, i.1.clone());
+                b.iter(|| {
+                    gftt_response(src, &mut dst, GradsMode::Sobel).unwrap();
+                    black_box(non_max_suppression(&dst, 1e-4))
+                })
+            },
+        );
+
+        #[cfg(feature = "opencv")]
+        {
+            let src = kornia_bench::opencv::mat_from_image_f32(&image).unwrap();
+            let mut corners = opencv::core::Mat::default();
+            group.bench_function(BenchmarkId::new("opencv", &parameter_string), |b| {
+                b.iter(|| {
+                    black_box(opencv::imgproc::good_features_to_track_def(
+                        &src,
+                        &mut corners,
+                        0,
+                        0.01,
+                        1.0,
+                    ))
+                })
+            });
+        }
+    }
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    bench_fast,
+    bench_harris,
+    bench_good_features_to_track
+);
+criterion_main!(benches);
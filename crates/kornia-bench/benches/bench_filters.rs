@@ -0,0 +1,98 @@
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+
+use kornia_bench::inputs::{random_image, IMAGE_SIZES, SEED};
+use kornia_image::Image;
+use kornia_imgproc::filter::{box_blur, gaussian_blur};
+
+const KERNEL_SIZE: usize = 7;
+const SIGMA: f32 = 1.5;
+
+fn bench_gaussian_blur(c: &mut Criterion) {
+    let mut group = c.benchmark_group("GaussianBlur");
+
+    for (width, height) in IMAGE_SIZES {
+        group.throughput(criterion::Throughput::Elements((width * height) as u64));
+
+        let parameter_string = format!("{}x{}", width, height);
+
+        let image = random_image::<3>([width, height].into(), SEED).unwrap();
+        let output = Image::<f32, 3>::from_size_val(image.size(), 0.0).unwrap();
+
+        group.bench_with_input(
+            BenchmarkId::new("kornia", &parameter_string),
+            &(&image, &output),
+            |b, i| {
+                let (src, mut dst) = (i.0, i.1.clone());
+                b.iter(|| {
+                    black_box(gaussian_blur(
+                        src,
+                        &mut dst,
+                        (KERNEL_SIZE, KERNEL_SIZE),
+                        (SIGMA, SIGMA),
+                    ))
+                })
+            },
+        );
+
+        #[cfg(feature = "opencv")]
+        {
+            let src = kornia_bench::opencv::mat_from_image_f32(&image).unwrap();
+            let mut dst = opencv::core::Mat::default();
+            let ksize = opencv::core::Size::new(KERNEL_SIZE as i32, KERNEL_SIZE as i32);
+            group.bench_function(BenchmarkId::new("opencv", &parameter_string), |b| {
+                b.iter(|| {
+                    black_box(opencv::imgproc::gaussian_blur_def(
+                        &src,
+                        &mut dst,
+                        ksize,
+                        SIGMA as f64,
+                    ))
+                })
+            });
+        }
+    }
+    group.finish();
+}
+
+fn bench_box_blur(c: &mut Criterion) {
+    let mut group = c.benchmark_group("BoxBlur");
+
+    for (width, height) in IMAGE_SIZES {
+        group.throughput(criterion::Throughput::Elements((width * height) as u64));
+
+        let parameter_string = format!("{}x{}", width, height);
+
+        let image = random_image::<3>([width, height].into(), SEED).unwrap();
+        let output = Image::<f32, 3>::from_size_val(image.size(), 0.0).unwrap();
+
+        group.bench_with_input(
+            BenchmarkId::new("kornia", &parameter_string),
+            &(&image, &output),
+            |b, i| {
+                let (src, mut dst) = (i.0, i.1.clone());
+                b.iter(|| black_box(box_blur(src, &mut dst, (KERNEL_SIZE, KERNEL_SIZE))))
+            },
+        );
+
+        #[cfg(feature = "opencv")]
+        {
+            let src = kornia_bench::opencv::mat_from_image_f32(&image).unwrap();
+            let mut dst = opencv::core::Mat::default();
+            let ksize = opencv::core::Size::new(KERNEL_SIZE as i32, KERNEL_SIZE as i32);
+            group.bench_function(BenchmarkId::new("opencv", &parameter_string), |b| {
+                b.iter(|| {
+                    black_box(opencv::imgproc::box_filter_def(
+                        &src,
+                        &mut dst,
+                        opencv::core::CV_32F,
+                        ksize,
+                    ))
+                })
+            });
+        }
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_gaussian_blur, bench_box_blur);
+criterion_main!(benches);
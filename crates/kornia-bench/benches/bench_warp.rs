@@ -0,0 +1,92 @@
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+
+use kornia_bench::inputs::{random_image, IMAGE_SIZES, SEED};
+use kornia_image::Image;
+use kornia_imgproc::{
+    interpolation::InterpolationMode,
+    warp::{get_rotation_matrix2d, warp_affine, warp_perspective},
+};
+
+fn bench_warp_affine(c: &mut Criterion) {
+    let mut group = c.benchmark_group("WarpAffine");
+
+    for (width, height) in IMAGE_SIZES {
+        group.throughput(criterion::Throughput::Elements((width * height) as u64));
+
+        let parameter_string = format!("{}x{}", width, height);
+
+        let image = random_image::<3>([width, height].into(), SEED).unwrap();
+        let output = Image::<f32, 3>::from_size_val(image.size(), 0.0).unwrap();
+        let m = get_rotation_matrix2d((width as f32 / 2.0, height as f32 / 2.0), 30.0, 0.9);
+
+        group.bench_with_input(
+            BenchmarkId::new("kornia", &parameter_string),
+            &(&image, &output),
+            |b, i| {
+                let (src, mut dst) = (i.0, i.1.clone());
+                b.iter(|| black_box(warp_affine(src, &mut dst, &m, InterpolationMode::Bilinear)))
+            },
+        );
+
+        #[cfg(feature = "opencv")]
+        {
+            let src = kornia_bench::opencv::mat_from_image_f32(&image).unwrap();
+            let m = kornia_bench::opencv::mat_from_transform(&m).unwrap();
+            let mut dst = opencv::core::Mat::default();
+            let dsize = opencv::core::Size::new(width as i32, height as i32);
+            group.bench_function(BenchmarkId::new("opencv", &parameter_string), |b| {
+                b.iter(|| black_box(opencv::imgproc::warp_affine_def(&src, &mut dst, &m, dsize)))
+            });
+        }
+    }
+    group.finish();
+}
+
+fn bench_warp_perspective(c: &mut Criterion) {
+    let mut group = c.benchmark_group("WarpPerspective");
+
+    for (width, height) in IMAGE_SIZES {
+        group.throughput(criterion::Throughput::Elements((width * height) as u64));
+
+        let parameter_string = format!("{}x{}", width, height);
+
+        let image = random_image::<3>([width, height].into(), SEED).unwrap();
+        let output = Image::<f32, 3>::from_size_val(image.size(), 0.0).unwrap();
+        let m = [0.9, -0.1, 12.0, 0.05, 1.1, -8.0, 1e-5, 2e-5, 1.0];
+
+        group.bench_with_input(
+            BenchmarkId::new("kornia", &parameter_string),
+            &(&image, &output),
+            |b, i| {
+                let (src, mut dst) = (i.0, i.1.clone());
+                b.iter(|| {
+                    black_box(warp_perspective(
+                        src,
+                        &mut dst,
+                        &m,
+                        InterpolationMode::Bilinear,
+                    ))
+                })
+            },
+        );
+
+        #[cfg(feature = "opencv")]
+        {
+            let src = kornia_bench::opencv::mat_from_image_f32(&image).unwrap();
+            let m = kornia_bench::opencv::mat_from_transform(&m).unwrap();
+            let mut dst = opencv::core::Mat::default();
+            let dsize = opencv::core::Size::new(width as i32, height as i32);
+            group.bench_function(BenchmarkId::new("opencv", &parameter_string), |b| {
+                b.iter(|| {
+                    black_box(opencv::imgproc::warp_perspective_def(
+                        &src, &mut dst, &m, dsize,
+                    ))
+                })
+            });
+        }
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_warp_affine, bench_warp_perspective);
+criterion_main!(benches);
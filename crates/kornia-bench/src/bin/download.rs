@@ -0,0 +1,38 @@
+use argh::FromArgs;
+use std::path::PathBuf;
+
+use kornia_bench::dataset;
+
+/// Download the datasets of the benchmarks.
+#[derive(FromArgs)]
+struct Args {
+    /// the directory to store the datasets, defaults to `KORNIA_BENCH_DATA` or the crate `data`
+    #[argh(option)]
+    data_dir: Option<PathBuf>,
+
+    /// the names of the datasets to download, defaults to all
+    #[argh(positional)]
+    datasets: Vec<String>,
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let args: Args = argh::from_env();
+
+    let data_dir = args.data_dir.unwrap_or_else(dataset::data_dir);
+
+    let datasets = if args.datasets.is_empty() {
+        dataset::DATASETS.iter().collect()
+    } else {
+        args.datasets
+            .iter()
+            .map(|name| dataset::find_dataset(name))
+            .collect::<Result<Vec<_>, _>>()?
+    };
+
+    for dataset in datasets {
+        let paths = dataset.download(&data_dir)?;
+        println!("Downloaded {} images of {}", paths.len(), dataset.name);
+    }
+
+    Ok(())
+}
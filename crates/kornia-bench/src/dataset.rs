@@ -0,0 +1,168 @@
+use std::path::{Path, PathBuf};
+
+use crate::error::BenchError;
+
+/// The environment variable to override the directory of the datasets.
+pub const DATA_DIR_ENV: &str = "KORNIA_BENCH_DATA";
+
+/// An image of a benchmark dataset.
+pub struct DatasetImage {
+    /// The file name of the image.
+    pub name: &'static str,
+    /// The url to download the image.
+    pub url: &'static str,
+}
+
+/// A set of images downloaded to a sub-directory of the data directory.
+pub struct Dataset {
+    /// The name of the dataset and its sub-directory.
+    pub name: &'static str,
+    /// The images of the dataset.
+    pub images: &'static [DatasetImage],
+}
+
+/// A subset of the Kodak lossless true color image suite, with 768x512 natural images.
+pub const KODAK: Dataset = Dataset {
+    name: "kodak",
+    images: &[
+        DatasetImage {
+            name: "kodim01.png",
+            url: "https://r0k.us/graphics/kodak/kodak/kodim01.png",
+        },
+        DatasetImage {
+            name: "kodim08.png",
+            url: "https://r0k.us/graphics/kodak/kodak/kodim08.png",
+        },
+        DatasetImage {
+            name: "kodim23.png",
+            url: "https://r0k.us/graphics/kodak/kodak/kodim23.png",
+        },
+    ],
+};
+
+/// All the datasets of the benchmarks.
+pub const DATASETS: &[Dataset] = &[KODAK];
+
+/// Returns the dataset with the given name.
+///
+/// # Arguments
+///
+/// * `name` - The name of the dataset.
+pub fn find_dataset(name: &str) -> Result<&'static Dataset, BenchError> {
+    DATASETS
+        .iter()
+        .find(|dataset| dataset.name == name)
+        .ok_or_else(|| BenchError::UnknownDataset(name.to_string()))
+}
+
+/// Returns the directory of the datasets.
+///
+/// The directory is read from the `KORNIA_BENCH_DATA` environment variable and defaults
+/// to the `data` directory of this crate.
+pub fn data_dir() -> PathBuf {
+    std::env::var_os(DATA_DIR_ENV)
+        .map(PathBuf::from)
+        .unwrap_or_else(|| Path::new(env!("CARGO_MANIFEST_DIR")).join("data"))
+}
+
+impl Dataset {
+    /// Returns the paths of the images of the dataset in the given directory.
+    ///
+    /// # Arguments
+    ///
+    /// * `dir` - The directory of the datasets.
+    pub fn paths(&self, dir: &Path) -> Vec<PathBuf> {
+        let dataset_dir = dir.join(self.name);
+        self.images
+            .iter()
+            .map(|image| dataset_dir.join(image.name))
+            .collect()
+    }
+
+    /// Returns true if all the images of the dataset are in the given directory.
+    ///
+    /// # Arguments
+    ///
+    /// * `dir` - The directory of the datasets.
+    pub fn is_downloaded(&self, dir: &Path) -> bool {
+        self.paths(dir).iter().all(|path| path.exists())
+    }
+
+    /// Downloads the missing images of the dataset to the given directory.
+    ///
+    /// Each image is checked to decode before being moved to its final path, so an
+    /// interrupted or corrupted download is fetched again on the next call.
+    ///
+    /// # Arguments
+    ///
+    /// * `dir` - The directory of the datasets.
+    ///
+    /// # Returns
+    ///
+    /// The paths of the images of the dataset.
+    pub fn download(&self, dir: &Path) -> Result<Vec<PathBuf>, BenchError> {
+        std::fs::create_dir_all(dir.join(self.name))?;
+
+        let paths = self.paths(dir);
+        for (image, path) in self.images.iter().zip(&paths) {
+            if path.exists() {
+                continue;
+            }
+            let bytes = fetch(image.url)?;
+            store(&bytes, path)?;
+        }
+
+        Ok(paths)
+    }
+}
+
+// download the content of an url
+fn fetch(url: &str) -> Result<Vec<u8>, BenchError> {
+    let response = ureq::get(url)
+        .call()
+        .map_err(|e| BenchError::DownloadError(url.to_string(), e.to_string()))?;
+
+    let mut bytes = Vec::new();
+    std::io::Read::read_to_end(&mut response.into_reader(), &mut bytes)?;
+
+    Ok(bytes)
+}
+
+// check that the bytes decode to an image and write them to the path
+fn store(bytes: &[u8], path: &Path) -> Result<(), BenchError> {
+    kornia_io::functional::decode_image_any_rgb8(bytes)?;
+
+    let tmp_path = path.with_extension("part");
+    std::fs::write(&tmp_path, bytes)?;
+    std::fs::rename(&tmp_path, path)?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dataset_paths() -> Result<(), BenchError> {
+        let dir = tempfile::tempdir()?;
+
+        let dataset = find_dataset("kodak")?;
+        assert!(!dataset.is_downloaded(dir.path()));
+        assert_eq!(
+            dataset.paths(dir.path())[0],
+            dir.path().join("kodak").join("kodim01.png")
+        );
+
+        // a valid image is stored and a corrupted one is rejected
+        let bytes = std::fs::read("../../tests/data/dog.jpeg")?;
+        let path = dir.path().join("dog.jpeg");
+        store(&bytes, &path)?;
+        assert!(path.exists());
+        assert!(store(&bytes[..100], &dir.path().join("broken.jpeg")).is_err());
+
+        assert!(find_dataset("unknown").is_err());
+
+        Ok(())
+    }
+}
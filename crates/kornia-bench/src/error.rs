@@ -0,0 +1,23 @@
+/// An error type for the benchmarks.
+#[derive(thiserror::Error, Debug)]
+pub enum BenchError {
+    /// Error to download a file of a dataset.
+    #[error("Failed to download {0}. {1}")]
+    DownloadError(String, String),
+
+    /// Error to read or write a file.
+    #[error(transparent)]
+    FileError(#[from] std::io::Error),
+
+    /// Error to decode an image.
+    #[error(transparent)]
+    IoError(#[from] kornia_io::IoError),
+
+    /// Error to create or process an image.
+    #[error(transparent)]
+    ImageError(#[from] kornia_image::ImageError),
+
+    /// Error when the name of a dataset is unknown.
+    #[error("Unknown dataset: {0}")]
+    UnknownDataset(String),
+}
@@ -0,0 +1,97 @@
+use kornia_image::{Image, ImageSize};
+use kornia_imgproc::{color::gray_from_rgb_u8, interpolation::InterpolationMode, resize};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+use crate::{dataset, error::BenchError};
+
+/// The image sizes (width, height) of the benchmarks.
+pub const IMAGE_SIZES: [(usize, usize); 3] = [(640, 480), (1280, 720), (1920, 1080)];
+
+/// The seed of the random inputs of the benchmarks.
+pub const SEED: u64 = 42;
+
+// the image of the tests, used when no dataset is downloaded
+const FALLBACK_IMAGE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../../tests/data/dog.jpeg");
+
+/// Creates an image with uniform random values in the range [0, 1).
+///
+/// # Arguments
+///
+/// * `size` - The size of the image.
+/// * `seed` - The seed of the random generator, so that the runs are comparable.
+pub fn random_image<const C: usize>(
+    size: ImageSize,
+    seed: u64,
+) -> Result<Image<f32, C>, BenchError> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let data = (0..size.width * size.height * C)
+        .map(|_| rng.random::<f32>())
+        .collect();
+    Ok(Image::new(size, data)?)
+}
+
+/// Loads a natural RGB image resized to the given size.
+///
+/// The first image of the Kodak dataset is used when it is downloaded, otherwise the
+/// image of the tests of the repository.
+///
+/// # Arguments
+///
+/// * `size` - The size of the image.
+pub fn natural_image_rgb8(size: ImageSize) -> Result<Image<u8, 3>, BenchError> {
+    let path = dataset::KODAK
+        .paths(&dataset::data_dir())
+        .into_iter()
+        .next()
+        .filter(|path| path.exists())
+        .unwrap_or_else(|| FALLBACK_IMAGE.into());
+
+    let image = kornia_io::functional::read_image_any_rgb8(path)?;
+
+    let mut resized = Image::from_size_val(size, 0)?;
+    resize::resize_fast(&image, &mut resized, InterpolationMode::Bilinear)?;
+
+    Ok(resized)
+}
+
+/// Loads a natural grayscale image resized to the given size.
+///
+/// # Arguments
+///
+/// * `size` - The size of the image.
+pub fn natural_image_gray8(size: ImageSize) -> Result<Image<u8, 1>, BenchError> {
+    let rgb = natural_image_rgb8(size)?;
+    let mut gray = Image::from_size_val(size, 0)?;
+    gray_from_rgb_u8(&rgb, &mut gray)?;
+    Ok(gray)
+}
+
+/// Loads a natural grayscale image resized to the given size with values in [0, 1].
+///
+/// # Arguments
+///
+/// * `size` - The size of the image.
+pub fn natural_image_gray_f32(size: ImageSize) -> Result<Image<f32, 1>, BenchError> {
+    Ok(natural_image_gray8(size)?.cast_and_scale(1.0 / 255.0)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_inputs() -> Result<(), BenchError> {
+        let size = [64, 48].into();
+
+        let a = random_image::<3>(size, SEED)?;
+        let b = random_image::<3>(size, SEED)?;
+        assert_eq!(a.as_slice(), b.as_slice());
+        assert!(a.as_slice().iter().all(|&x| (0.0..1.0).contains(&x)));
+
+        let gray = natural_image_gray_f32(size)?;
+        assert_eq!(gray.size(), size);
+        assert!(gray.as_slice().iter().any(|&x| x > 0.0));
+
+        Ok(())
+    }
+}
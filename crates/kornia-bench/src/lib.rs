@@ -0,0 +1,17 @@
+#![deny(missing_docs)]
+#![doc = env!("CARGO_PKG_DESCRIPTION")]
+
+/// Download of the benchmark datasets.
+pub mod dataset;
+
+/// Error types for the benchmarks.
+pub mod error;
+
+/// Reproducible inputs of the benchmarks.
+pub mod inputs;
+
+/// Conversions between kornia images and OpenCV matrices.
+#[cfg(feature = "opencv")]
+pub mod opencv;
+
+pub use crate::error::BenchError;
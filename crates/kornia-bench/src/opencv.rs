@@ -0,0 +1,58 @@
+use kornia_image::Image;
+use opencv::{
+    core::{Mat, Scalar, CV_32F, CV_8U},
+    prelude::*,
+};
+
+// the OpenCV type of a matrix with `C` channels, as the `CV_MAKETYPE` macro
+fn mat_type(depth: i32, channels: usize) -> i32 {
+    depth + (((channels as i32) - 1) << 3)
+}
+
+/// Copies an image to a new OpenCV matrix with `CV_8UC(C)` type.
+///
+/// # Arguments
+///
+/// * `image` - The image to copy.
+pub fn mat_from_image_u8<const C: usize>(image: &Image<u8, C>) -> opencv::Result<Mat> {
+    let mut mat = Mat::new_rows_cols_with_default(
+        image.rows() as i32,
+        image.cols() as i32,
+        mat_type(CV_8U, C),
+        Scalar::all(0.0),
+    )?;
+    mat.data_bytes_mut()?.copy_from_slice(image.as_slice());
+    Ok(mat)
+}
+
+/// Copies an image to a new OpenCV matrix with `CV_32FC(C)` type.
+///
+/// # Arguments
+///
+/// * `image` - The image to copy.
+pub fn mat_from_image_f32<const C: usize>(image: &Image<f32, C>) -> opencv::Result<Mat> {
+    let mut mat = Mat::new_rows_cols_with_default(
+        image.rows() as i32,
+        image.cols() as i32,
+        mat_type(CV_32F, C),
+        Scalar::all(0.0),
+    )?;
+    for (dst, src) in mat
+        .data_bytes_mut()?
+        .chunks_exact_mut(4)
+        .zip(image.as_slice())
+    {
+        dst.copy_from_slice(&src.to_ne_bytes());
+    }
+    Ok(mat)
+}
+
+/// Creates an OpenCV matrix from the rows of a transformation matrix.
+///
+/// # Arguments
+///
+/// * `m` - The transformation matrix in row-major order with 3 columns.
+pub fn mat_from_transform(m: &[f32]) -> opencv::Result<Mat> {
+    let rows = m.chunks_exact(3).collect::<Vec<_>>();
+    Mat::from_slice_2d(&rows)
+}
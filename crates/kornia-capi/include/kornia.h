@@ -0,0 +1,377 @@
+#ifndef KORNIA_H
+#define KORNIA_H
+
+/* Generated with cbindgen, do not edit by hand. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+// The interpolation of the pixel values.
+typedef enum KorniaInterpolation {
+  // The value of the nearest pixel.
+  KORNIA_INTERPOLATION_NEAREST = 0,
+  // The bilinear interpolation of the four nearest pixels.
+  KORNIA_INTERPOLATION_BILINEAR = 1,
+} KorniaInterpolation;
+
+// The residual minimized by the Lucas-Kanade tracker between the windows of a point.
+typedef enum KorniaLkResidual {
+  // The difference of the raw intensities, for images of a constant exposure.
+  KORNIA_LK_RESIDUAL_INTENSITY = 0,
+  // The difference of the windows normalized to a zero mean and a unit deviation.
+  KORNIA_LK_RESIDUAL_ZERO_MEAN_NCC = 1,
+  // The difference after the fit of a gain and a bias of the intensities of the window.
+  KORNIA_LK_RESIDUAL_GAIN_BIAS = 2,
+} KorniaLkResidual;
+
+// The status returned by the functions of the C API.
+typedef enum KorniaStatus {
+  // The function succeeded.
+  KORNIA_STATUS_OK = 0,
+  // A required pointer argument is null.
+  KORNIA_STATUS_NULL_POINTER = 1,
+  // An argument is not valid, e.g. an unsupported number of channels.
+  KORNIA_STATUS_INVALID_ARGUMENT = 2,
+  // An image operation failed.
+  KORNIA_STATUS_IMAGE_ERROR = 3,
+  // An image could not be read or decoded.
+  KORNIA_STATUS_IO_ERROR = 4,
+  // The function panicked, which is a bug of the library.
+  KORNIA_STATUS_PANIC = 5,
+} KorniaStatus;
+
+// An opaque handle to an 8-bit image with 1 (gray), 3 (RGB) or 4 (RGBA) channels.
+//
+// The pixels are stored row by row with interleaved channels. The handles are created by
+// the functions of the library and must be released with [`kornia_image_free`].
+typedef struct KorniaImage KorniaImage;
+
+// A keypoint in pixels.
+typedef struct KorniaKeypoint {
+  // The column of the keypoint.
+  int32_t x;
+  // The row of the keypoint.
+  int32_t y;
+} KorniaKeypoint;
+
+// An array of keypoints owned by the library, released with [`kornia_keypoints_free`].
+typedef struct KorniaKeypoints {
+  // The keypoints.
+  struct KorniaKeypoint *data;
+  // The number of keypoints.
+  size_t len;
+} KorniaKeypoints;
+
+// The parameters of the pyramidal Lucas-Kanade tracker, see [`kornia_lk_params_default`].
+typedef struct KorniaLkParams {
+  // The width and height of the tracked window in pixels, odd.
+  size_t window_size;
+  // The number of levels of the pyramids, 1 to track at the full resolution only.
+  size_t num_levels;
+  // The maximum number of iterations at every level.
+  size_t max_iterations;
+  // The update of the flow in pixels below which the iterations stop.
+  float epsilon;
+  // The minimum eigenvalue of the gradient matrix of a window, per pixel, to be tracked.
+  float min_eigenvalue;
+  // The residual between the windows.
+  enum KorniaLkResidual residual;
+} KorniaLkParams;
+
+// A point in sub-pixel coordinates.
+typedef struct KorniaPoint2f {
+  // The column of the point.
+  float x;
+  // The row of the point.
+  float y;
+} KorniaPoint2f;
+
+// An axis aligned rectangle in pixels.
+typedef struct KorniaRect {
+  // The column of the top left corner.
+  size_t x;
+  // The row of the top left corner.
+  size_t y;
+  // The width of the rectangle.
+  size_t width;
+  // The height of the rectangle.
+  size_t height;
+} KorniaRect;
+
+// A rectangle rotated around its center.
+typedef struct KorniaRotatedRect {
+  // The column of the center.
+  float center_x;
+  // The row of the center.
+  float center_y;
+  // The length of the side along the angle.
+  float length;
+  // The length of the other side.
+  float width;
+  // The angle of the length side in degrees, from the x axis towards the y axis.
+  float angle;
+} KorniaRotatedRect;
+
+// A point tracked between two images.
+typedef struct KorniaTrackedPoint {
+  // The column of the point in the next image.
+  float x;
+  // The row of the point in the next image.
+  float y;
+  // Whether the point was tracked, false if it left the image or its window is flat.
+  bool tracked;
+  // The mean absolute residual between the windows of the point, in intensities
+  // normalized to [0, 1].
+  float error;
+} KorniaTrackedPoint;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// Get the message of the last error of the calling thread.
+//
+// The returned string is owned by the library and is valid until the next failing call
+// on the same thread.
+//
+// # Returns
+//
+// The message as a nul-terminated string, or null if no function failed.
+const char *kornia_last_error_message(void);
+
+// Create an image filled with zeros.
+//
+// # Arguments
+//
+// * `width` - The width of the image in pixels.
+// * `height` - The height of the image in pixels.
+// * `channels` - The number of channels, 1, 3 or 4.
+// * `out_image` - The location where the handle of the new image is written.
+//
+// # Safety
+//
+// `out_image` must be a valid pointer to write to.
+enum KorniaStatus kornia_image_new(size_t width,
+                                   size_t height,
+                                   size_t channels,
+                                   struct KorniaImage **out_image);
+
+// Create an image from a copy of its pixels.
+//
+// # Arguments
+//
+// * `width` - The width of the image in pixels.
+// * `height` - The height of the image in pixels.
+// * `channels` - The number of channels, 1, 3 or 4.
+// * `data` - The pixels row by row with interleaved channels.
+// * `data_len` - The number of bytes of `data`, `width * height * channels`.
+// * `out_image` - The location where the handle of the new image is written.
+//
+// # Safety
+//
+// `data` must point to `data_len` readable bytes and `out_image` must be a valid pointer
+// to write to.
+enum KorniaStatus kornia_image_from_data(size_t width,
+                                         size_t height,
+                                         size_t channels,
+                                         const uint8_t *data,
+                                         size_t data_len,
+                                         struct KorniaImage **out_image);
+
+// Release an image.
+//
+// # Safety
+//
+// `image` must be null or a handle returned by the library, which is not used afterwards.
+void kornia_image_free(struct KorniaImage *image);
+
+// Get the width of an image in pixels, or 0 for a null handle.
+//
+// # Safety
+//
+// `image` must be null or a valid handle.
+size_t kornia_image_width(const struct KorniaImage *image);
+
+// Get the height of an image in pixels, or 0 for a null handle.
+//
+// # Safety
+//
+// `image` must be null or a valid handle.
+size_t kornia_image_height(const struct KorniaImage *image);
+
+// Get the number of channels of an image, or 0 for a null handle.
+//
+// # Safety
+//
+// `image` must be null or a valid handle.
+size_t kornia_image_channels(const struct KorniaImage *image);
+
+// Get the pixels of an image, valid until the image is released.
+//
+// The number of bytes is `width * height * channels`.
+//
+// # Safety
+//
+// `image` must be null or a valid handle.
+const uint8_t *kornia_image_data(const struct KorniaImage *image);
+
+// Get the mutable pixels of an image, valid until the image is released.
+//
+// # Safety
+//
+// `image` must be null or a valid handle which is not used concurrently.
+uint8_t *kornia_image_data_mut(struct KorniaImage *image);
+
+// Resize an image.
+//
+// # Arguments
+//
+// * `src` - The image to resize.
+// * `width` - The width of the resized image.
+// * `height` - The height of the resized image.
+// * `interpolation` - The interpolation of the pixel values.
+// * `out_image` - The location where the handle of the resized image is written.
+//
+// # Safety
+//
+// `src` must be a valid handle and `out_image` must be a valid pointer to write to.
+enum KorniaStatus kornia_resize(const struct KorniaImage *src,
+                                size_t width,
+                                size_t height,
+                                enum KorniaInterpolation interpolation,
+                                struct KorniaImage **out_image);
+
+// Convert an RGB image to grayscale.
+//
+// # Arguments
+//
+// * `src` - The RGB image.
+// * `out_image` - The location where the handle of the grayscale image is written.
+//
+// # Safety
+//
+// `src` must be a valid handle and `out_image` must be a valid pointer to write to.
+enum KorniaStatus kornia_gray_from_rgb(const struct KorniaImage *src,
+                                       struct KorniaImage **out_image);
+
+// Detect FAST corners in a grayscale image.
+//
+// # Arguments
+//
+// * `src` - The grayscale image.
+// * `threshold` - The intensity difference to the center pixel.
+// * `arc_length` - The number of consecutive brighter or darker pixels on the circle.
+// * `out_keypoints` - The location where the detected keypoints are written.
+//
+// # Safety
+//
+// `src` must be a valid handle and `out_keypoints` must be a valid pointer to write to.
+enum KorniaStatus kornia_detect_fast(const struct KorniaImage *src,
+                                     uint8_t threshold,
+                                     uint8_t arc_length,
+                                     struct KorniaKeypoints *out_keypoints);
+
+// Release the keypoints returned by the library.
+//
+// The array is reset to null and empty.
+//
+// # Safety
+//
+// `keypoints` must be null or point to keypoints returned by the library.
+void kornia_keypoints_free(struct KorniaKeypoints *keypoints);
+
+// Track an object in a probability image with the continuously adaptive mean shift.
+//
+// # Arguments
+//
+// * `prob` - The grayscale probability image, e.g. a histogram back-projection.
+// * `window` - The window of the object in the previous frame, updated with the window
+//   to search in the next frame.
+// * `max_iterations` - The maximum number of mean shift iterations.
+// * `epsilon` - The shift of the window in pixels below which the iterations stop.
+// * `out_object` - The location where the rotated rectangle of the object is written.
+//
+// # Safety
+//
+// `prob` must be a valid handle, `window` must be a valid pointer to read and write, and
+// `out_object` must be a valid pointer to write to.
+enum KorniaStatus kornia_cam_shift(const struct KorniaImage *prob,
+                                   struct KorniaRect *window,
+                                   size_t max_iterations,
+                                   float epsilon,
+                                   struct KorniaRotatedRect *out_object);
+
+// Get the default parameters of the Lucas-Kanade tracker.
+//
+// # Returns
+//
+// A 21x21 window, 3 pyramid levels, 30 iterations, an epsilon of 0.01 pixels, a minimum
+// eigenvalue of 1e-4 and the intensity residual.
+struct KorniaLkParams kornia_lk_params_default(void);
+
+// Track points between two grayscale images with the pyramidal Lucas-Kanade optical flow.
+//
+// The intensities are normalized to [0, 1] before the tracking.
+//
+// # Arguments
+//
+// * `prev` - The previous grayscale image.
+// * `next` - The next grayscale image with the size of `prev`.
+// * `points` - The positions of the points in the previous image.
+// * `guesses` - The predicted positions of the points in the next image, or null to start
+//   from the previous positions.
+// * `num_points` - The number of points.
+// * `params` - The parameters of the tracker, or null for [`kornia_lk_params_default`].
+// * `out_points` - The location where the `num_points` tracked points are written, in the
+//   order of `points`.
+//
+// # Safety
+//
+// `prev` and `next` must be valid handles, `points`, `guesses` if not null and
+// `out_points` must point to `num_points` elements, and `params` must be null or a valid
+// pointer to read.
+enum KorniaStatus kornia_track_points_lk(const struct KorniaImage *prev,
+                                         const struct KorniaImage *next,
+                                         const struct KorniaPoint2f *points,
+                                         const struct KorniaPoint2f *guesses,
+                                         size_t num_points,
+                                         const struct KorniaLkParams *params,
+                                         struct KorniaTrackedPoint *out_points);
+
+// Decode an encoded image in memory, e.g. JPEG or PNG, to an RGB image.
+//
+// # Arguments
+//
+// * `data` - The encoded bytes.
+// * `data_len` - The number of encoded bytes.
+// * `out_image` - The location where the handle of the decoded image is written.
+//
+// # Safety
+//
+// `data` must point to `data_len` readable bytes and `out_image` must be a valid pointer
+// to write to.
+enum KorniaStatus kornia_decode_image(const uint8_t *data,
+                                      size_t data_len,
+                                      struct KorniaImage **out_image);
+
+// Read an image file to an RGB image.
+//
+// # Arguments
+//
+// * `path` - The path of the file as a nul-terminated UTF-8 string.
+// * `out_image` - The location where the handle of the image is written.
+//
+// # Safety
+//
+// `path` must be a valid nul-terminated string and `out_image` must be a valid pointer
+// to write to.
+enum KorniaStatus kornia_read_image(const char *path, struct KorniaImage **out_image);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* KORNIA_H */
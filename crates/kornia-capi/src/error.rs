@@ -0,0 +1,99 @@
+use std::cell::RefCell;
+use std::ffi::{c_char, CString};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+use kornia_image::ImageError;
+use kornia_io::IoError;
+
+/// The status returned by the functions of the C API.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KorniaStatus {
+    /// The function succeeded.
+    Ok = 0,
+    /// A required pointer argument is null.
+    NullPointer = 1,
+    /// An argument is not valid, e.g. an unsupported number of channels.
+    InvalidArgument = 2,
+    /// An image operation failed.
+    ImageError = 3,
+    /// An image could not be read or decoded.
+    IoError = 4,
+    /// The function panicked, which is a bug of the library.
+    Panic = 5,
+}
+
+/// An error of the C API, reported as a status and a message.
+#[derive(Debug, thiserror::Error)]
+pub(crate) enum CapiError {
+    /// A required pointer argument is null.
+    #[error("Null pointer argument: {0}")]
+    NullPointer(&'static str),
+
+    /// An argument is not valid.
+    #[error("Invalid argument: {0}")]
+    InvalidArgument(String),
+
+    /// An image operation failed.
+    #[error(transparent)]
+    Image(#[from] ImageError),
+
+    /// An image could not be read or decoded.
+    #[error(transparent)]
+    Io(#[from] IoError),
+}
+
+impl CapiError {
+    fn status(&self) -> KorniaStatus {
+        match self {
+            Self::NullPointer(_) => KorniaStatus::NullPointer,
+            Self::InvalidArgument(_) => KorniaStatus::InvalidArgument,
+            Self::Image(_) => KorniaStatus::ImageError,
+            Self::Io(_) => KorniaStatus::IoError,
+        }
+    }
+}
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+fn set_last_error(message: String) {
+    // the messages of the errors have no interior nul bytes, but never fail on them
+    let message = CString::new(message.replace('\0', " ")).unwrap_or_default();
+    LAST_ERROR.with(|e| *e.borrow_mut() = Some(message));
+}
+
+/// Run the body of an exported function, recording the error message on failure.
+///
+/// Panics are caught since unwinding into the caller is undefined behaviour.
+pub(crate) fn run(f: impl FnOnce() -> Result<(), CapiError>) -> KorniaStatus {
+    match catch_unwind(AssertUnwindSafe(f)) {
+        Ok(Ok(())) => KorniaStatus::Ok,
+        Ok(Err(e)) => {
+            set_last_error(e.to_string());
+            e.status()
+        }
+        Err(_) => {
+            set_last_error("The function panicked".to_string());
+            KorniaStatus::Panic
+        }
+    }
+}
+
+/// Get the message of the last error of the calling thread.
+///
+/// The returned string is owned by the library and is valid until the next failing call
+/// on the same thread.
+///
+/// # Returns
+///
+/// The message as a nul-terminated string, or null if no function failed.
+#[no_mangle]
+pub extern "C" fn kornia_last_error_message() -> *const c_char {
+    LAST_ERROR.with(|e| {
+        e.borrow()
+            .as_ref()
+            .map_or(std::ptr::null(), |message| message.as_ptr())
+    })
+}
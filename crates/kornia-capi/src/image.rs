@@ -0,0 +1,253 @@
+use kornia_image::{Image, ImageSize};
+
+use crate::error::{run, CapiError, KorniaStatus};
+
+/// An opaque handle to an 8-bit image with 1 (gray), 3 (RGB) or 4 (RGBA) channels.
+///
+/// The pixels are stored row by row with interleaved channels. The handles are created by
+/// the functions of the library and must be released with [`kornia_image_free`].
+pub enum KorniaImage {
+    /// A grayscale image.
+    Gray(Image<u8, 1>),
+    /// An RGB image.
+    Rgb(Image<u8, 3>),
+    /// An RGBA image.
+    Rgba(Image<u8, 4>),
+}
+
+impl KorniaImage {
+    fn size(&self) -> ImageSize {
+        match self {
+            Self::Gray(image) => image.size(),
+            Self::Rgb(image) => image.size(),
+            Self::Rgba(image) => image.size(),
+        }
+    }
+
+    fn channels(&self) -> usize {
+        match self {
+            Self::Gray(_) => 1,
+            Self::Rgb(_) => 3,
+            Self::Rgba(_) => 4,
+        }
+    }
+
+    fn as_slice(&self) -> &[u8] {
+        match self {
+            Self::Gray(image) => image.as_slice(),
+            Self::Rgb(image) => image.as_slice(),
+            Self::Rgba(image) => image.as_slice(),
+        }
+    }
+
+    fn as_slice_mut(&mut self) -> &mut [u8] {
+        match self {
+            Self::Gray(image) => image.as_slice_mut(),
+            Self::Rgb(image) => image.as_slice_mut(),
+            Self::Rgba(image) => image.as_slice_mut(),
+        }
+    }
+
+    /// Create an image from its pixels.
+    pub(crate) fn new(size: ImageSize, channels: usize, data: Vec<u8>) -> Result<Self, CapiError> {
+        Ok(match channels {
+            1 => Self::Gray(Image::new(size, data)?),
+            3 => Self::Rgb(Image::new(size, data)?),
+            4 => Self::Rgba(Image::new(size, data)?),
+            _ => {
+                return Err(CapiError::InvalidArgument(format!(
+                    "unsupported number of channels {channels}, expected 1, 3 or 4"
+                )))
+            }
+        })
+    }
+
+    /// Move the image to the heap and write its handle to `out_image`.
+    ///
+    /// # Safety
+    ///
+    /// `out_image` must be a valid pointer to write to.
+    pub(crate) unsafe fn write_to(self, out_image: *mut *mut KorniaImage) {
+        *out_image = Box::into_raw(Box::new(self));
+    }
+}
+
+/// Borrow the image of a handle, failing on null pointers.
+///
+/// # Safety
+///
+/// `image` must be null or a handle returned by the library and not yet released.
+pub(crate) unsafe fn image_ref<'a>(
+    image: *const KorniaImage,
+    name: &'static str,
+) -> Result<&'a KorniaImage, CapiError> {
+    image.as_ref().ok_or(CapiError::NullPointer(name))
+}
+
+/// Create an image filled with zeros.
+///
+/// # Arguments
+///
+/// * `width` - The width of the image in pixels.
+/// * `height` - The height of the image in pixels.
+/// * `channels` - The number of channels, 1, 3 or 4.
+/// * `out_image` - The location where the handle of the new image is written.
+///
+/// # Safety
+///
+/// `out_image` must be a valid pointer to write to.
+#[no_mangle]
+pub unsafe extern "C" fn kornia_image_new(
+    width: usize,
+    height: usize,
+    channels: usize,
+    out_image: *mut *mut KorniaImage,
+) -> KorniaStatus {
+    run(|| {
+        if out_image.is_null() {
+            return Err(CapiError::NullPointer("out_image"));
+        }
+        let data = vec![0; width * height * channels];
+        KorniaImage::new([width, height].into(), channels, data)?.write_to(out_image);
+        Ok(())
+    })
+}
+
+/// Create an image from a copy of its pixels.
+///
+/// # Arguments
+///
+/// * `width` - The width of the image in pixels.
+/// * `height` - The height of the image in pixels.
+/// * `channels` - The number of channels, 1, 3 or 4.
+/// * `data` - The pixels row by row with interleaved channels.
+/// * `data_len` - The number of bytes of `data`, `width * height * channels`.
+/// * `out_image` - The location where the handle of the new image is written.
+///
+/// # Safety
+///
+/// `data` must point to `data_len` readable bytes and `out_image` must be a valid pointer
+/// to write to.
+#[no_mangle]
+pub unsafe extern "C" fn kornia_image_from_data(
+    width: usize,
+    height: usize,
+    channels: usize,
+    data: *const u8,
+    data_len: usize,
+    out_image: *mut *mut KorniaImage,
+) -> KorniaStatus {
+    run(|| {
+        if data.is_null() {
+            return Err(CapiError::NullPointer("data"));
+        }
+        if out_image.is_null() {
+            return Err(CapiError::NullPointer("out_image"));
+        }
+        let data = std::slice::from_raw_parts(data, data_len).to_vec();
+        KorniaImage::new([width, height].into(), channels, data)?.write_to(out_image);
+        Ok(())
+    })
+}
+
+/// Release an image.
+///
+/// # Safety
+///
+/// `image` must be null or a handle returned by the library, which is not used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn kornia_image_free(image: *mut KorniaImage) {
+    if !image.is_null() {
+        drop(Box::from_raw(image));
+    }
+}
+
+/// Get the width of an image in pixels, or 0 for a null handle.
+///
+/// # Safety
+///
+/// `image` must be null or a valid handle.
+#[no_mangle]
+pub unsafe extern "C" fn kornia_image_width(image: *const KorniaImage) -> usize {
+    image.as_ref().map_or(0, |image| image.size().width)
+}
+
+/// Get the height of an image in pixels, or 0 for a null handle.
+///
+/// # Safety
+///
+/// `image` must be null or a valid handle.
+#[no_mangle]
+pub unsafe extern "C" fn kornia_image_height(image: *const KorniaImage) -> usize {
+    image.as_ref().map_or(0, |image| image.size().height)
+}
+
+/// Get the number of channels of an image, or 0 for a null handle.
+///
+/// # Safety
+///
+/// `image` must be null or a valid handle.
+#[no_mangle]
+pub unsafe extern "C" fn kornia_image_channels(image: *const KorniaImage) -> usize {
+    image.as_ref().map_or(0, |image| image.channels())
+}
+
+/// Get the pixels of an image, valid until the image is released.
+///
+/// The number of bytes is `width * height * channels`.
+///
+/// # Safety
+///
+/// `image` must be null or a valid handle.
+#[no_mangle]
+pub unsafe extern "C" fn kornia_image_data(image: *const KorniaImage) -> *const u8 {
+    image
+        .as_ref()
+        .map_or(std::ptr::null(), |image| image.as_slice().as_ptr())
+}
+
+/// Get the mutable pixels of an image, valid until the image is released.
+///
+/// # Safety
+///
+/// `image` must be null or a valid handle which is not used concurrently.
+#[no_mangle]
+pub unsafe extern "C" fn kornia_image_data_mut(image: *mut KorniaImage) -> *mut u8 {
+    image.as_mut().map_or(std::ptr::null_mut(), |image| {
+        image.as_slice_mut().as_mut_ptr()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_image_handle() {
+        let data = [1u8, 2, 3, 4, 5, 6];
+        let mut image = std::ptr::null_mut();
+        unsafe {
+            let status = kornia_image_from_data(2, 1, 3, data.as_ptr(), data.len(), &mut image);
+            assert_eq!(status, KorniaStatus::Ok);
+            assert_eq!(kornia_image_width(image), 2);
+            assert_eq!(kornia_image_height(image), 1);
+            assert_eq!(kornia_image_channels(image), 3);
+
+            *kornia_image_data_mut(image) = 10;
+            let pixels = std::slice::from_raw_parts(kornia_image_data(image), 6);
+            assert_eq!(pixels, &[10, 2, 3, 4, 5, 6]);
+            kornia_image_free(image);
+
+            // the data does not match the size
+            let status = kornia_image_from_data(2, 2, 3, data.as_ptr(), data.len(), &mut image);
+            assert_eq!(status, KorniaStatus::ImageError);
+
+            let status = kornia_image_new(2, 2, 2, &mut image);
+            assert_eq!(status, KorniaStatus::InvalidArgument);
+
+            let status = kornia_image_new(2, 2, 1, std::ptr::null_mut());
+            assert_eq!(status, KorniaStatus::NullPointer);
+            assert_eq!(kornia_image_width(std::ptr::null()), 0);
+        }
+    }
+}
@@ -0,0 +1,629 @@
+use kornia_image::Image;
+use kornia_imgproc::{color, features, interpolation::InterpolationMode, resize, video};
+
+use crate::error::{run, CapiError, KorniaStatus};
+use crate::image::{image_ref, KorniaImage};
+
+/// The interpolation of the pixel values.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KorniaInterpolation {
+    /// The value of the nearest pixel.
+    Nearest = 0,
+    /// The bilinear interpolation of the four nearest pixels.
+    Bilinear = 1,
+}
+
+impl From<KorniaInterpolation> for InterpolationMode {
+    fn from(interpolation: KorniaInterpolation) -> Self {
+        match interpolation {
+            KorniaInterpolation::Nearest => InterpolationMode::Nearest,
+            KorniaInterpolation::Bilinear => InterpolationMode::Bilinear,
+        }
+    }
+}
+
+/// A keypoint in pixels.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KorniaKeypoint {
+    /// The column of the keypoint.
+    pub x: i32,
+    /// The row of the keypoint.
+    pub y: i32,
+}
+
+/// An array of keypoints owned by the library, released with [`kornia_keypoints_free`].
+#[repr(C)]
+#[derive(Debug)]
+pub struct KorniaKeypoints {
+    /// The keypoints.
+    pub data: *mut KorniaKeypoint,
+    /// The number of keypoints.
+    pub len: usize,
+}
+
+/// An axis aligned rectangle in pixels.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KorniaRect {
+    /// The column of the top left corner.
+    pub x: usize,
+    /// The row of the top left corner.
+    pub y: usize,
+    /// The width of the rectangle.
+    pub width: usize,
+    /// The height of the rectangle.
+    pub height: usize,
+}
+
+/// A rectangle rotated around its center.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct KorniaRotatedRect {
+    /// The column of the center.
+    pub center_x: f32,
+    /// The row of the center.
+    pub center_y: f32,
+    /// The length of the side along the angle.
+    pub length: f32,
+    /// The length of the other side.
+    pub width: f32,
+    /// The angle of the length side in degrees, from the x axis towards the y axis.
+    pub angle: f32,
+}
+
+/// The residual minimized by the Lucas-Kanade tracker between the windows of a point.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KorniaLkResidual {
+    /// The difference of the raw intensities, for images of a constant exposure.
+    Intensity = 0,
+    /// The difference of the windows normalized to a zero mean and a unit deviation.
+    ZeroMeanNcc = 1,
+    /// The difference after the fit of a gain and a bias of the intensities of the window.
+    GainBias = 2,
+}
+
+impl From<KorniaLkResidual> for video::LkResidual {
+    fn from(residual: KorniaLkResidual) -> Self {
+        match residual {
+            KorniaLkResidual::Intensity => video::LkResidual::Intensity,
+            KorniaLkResidual::ZeroMeanNcc => video::LkResidual::ZeroMeanNcc,
+            KorniaLkResidual::GainBias => video::LkResidual::GainBias,
+        }
+    }
+}
+
+/// The parameters of the pyramidal Lucas-Kanade tracker, see [`kornia_lk_params_default`].
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct KorniaLkParams {
+    /// The width and height of the tracked window in pixels, odd.
+    pub window_size: usize,
+    /// The number of levels of the pyramids, 1 to track at the full resolution only.
+    pub num_levels: usize,
+    /// The maximum number of iterations at every level.
+    pub max_iterations: usize,
+    /// The update of the flow in pixels below which the iterations stop.
+    pub epsilon: f32,
+    /// The minimum eigenvalue of the gradient matrix of a window, per pixel, to be tracked.
+    pub min_eigenvalue: f32,
+    /// The residual between the windows.
+    pub residual: KorniaLkResidual,
+}
+
+impl From<KorniaLkParams> for video::LkParams {
+    fn from(params: KorniaLkParams) -> Self {
+        Self {
+            window_size: params.window_size,
+            num_levels: params.num_levels,
+            max_iterations: params.max_iterations,
+            epsilon: params.epsilon,
+            min_eigenvalue: params.min_eigenvalue,
+            residual: params.residual.into(),
+        }
+    }
+}
+
+/// A point in sub-pixel coordinates.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct KorniaPoint2f {
+    /// The column of the point.
+    pub x: f32,
+    /// The row of the point.
+    pub y: f32,
+}
+
+/// A point tracked between two images.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct KorniaTrackedPoint {
+    /// The column of the point in the next image.
+    pub x: f32,
+    /// The row of the point in the next image.
+    pub y: f32,
+    /// Whether the point was tracked, false if it left the image or its window is flat.
+    pub tracked: bool,
+    /// The mean absolute residual between the windows of the point, in intensities
+    /// normalized to [0, 1].
+    pub error: f32,
+}
+
+// resize through the float implementation, for the images without a fast path
+fn resize_float<const C: usize>(
+    src: &Image<u8, C>,
+    width: usize,
+    height: usize,
+    interpolation: InterpolationMode,
+) -> Result<Image<u8, C>, CapiError> {
+    let src = src.cast::<f32>()?;
+    let mut dst = Image::from_size_val([width, height].into(), 0.0)?;
+    resize::resize_native(&src, &mut dst, interpolation)?;
+    Ok(dst.map(|&v| v.round().clamp(0.0, 255.0) as u8)?)
+}
+
+/// Resize an image.
+///
+/// # Arguments
+///
+/// * `src` - The image to resize.
+/// * `width` - The width of the resized image.
+/// * `height` - The height of the resized image.
+/// * `interpolation` - The interpolation of the pixel values.
+/// * `out_image` - The location where the handle of the resized image is written.
+///
+/// # Safety
+///
+/// `src` must be a valid handle and `out_image` must be a valid pointer to write to.
+#[no_mangle]
+pub unsafe extern "C" fn kornia_resize(
+    src: *const KorniaImage,
+    width: usize,
+    height: usize,
+    interpolation: KorniaInterpolation,
+    out_image: *mut *mut KorniaImage,
+) -> KorniaStatus {
+    run(|| {
+        let src = image_ref(src, "src")?;
+        if out_image.is_null() {
+            return Err(CapiError::NullPointer("out_image"));
+        }
+        let interpolation = interpolation.into();
+        let resized = match src {
+            KorniaImage::Rgb(src) => {
+                let mut dst = Image::from_size_val([width, height].into(), 0)?;
+                resize::resize_fast(src, &mut dst, interpolation)?;
+                KorniaImage::Rgb(dst)
+            }
+            KorniaImage::Gray(src) => {
+                KorniaImage::Gray(resize_float(src, width, height, interpolation)?)
+            }
+            KorniaImage::Rgba(src) => {
+                KorniaImage::Rgba(resize_float(src, width, height, interpolation)?)
+            }
+        };
+        resized.write_to(out_image);
+        Ok(())
+    })
+}
+
+/// Convert an RGB image to grayscale.
+///
+/// # Arguments
+///
+/// * `src` - The RGB image.
+/// * `out_image` - The location where the handle of the grayscale image is written.
+///
+/// # Safety
+///
+/// `src` must be a valid handle and `out_image` must be a valid pointer to write to.
+#[no_mangle]
+pub unsafe extern "C" fn kornia_gray_from_rgb(
+    src: *const KorniaImage,
+    out_image: *mut *mut KorniaImage,
+) -> KorniaStatus {
+    run(|| {
+        let KorniaImage::Rgb(src) = image_ref(src, "src")? else {
+            return Err(CapiError::InvalidArgument(
+                "src: expected an RGB image".to_string(),
+            ));
+        };
+        if out_image.is_null() {
+            return Err(CapiError::NullPointer("out_image"));
+        }
+        let mut gray = Image::from_size_val(src.size(), 0)?;
+        color::gray_from_rgb_u8(src, &mut gray)?;
+        KorniaImage::Gray(gray).write_to(out_image);
+        Ok(())
+    })
+}
+
+/// Detect FAST corners in a grayscale image.
+///
+/// # Arguments
+///
+/// * `src` - The grayscale image.
+/// * `threshold` - The intensity difference to the center pixel.
+/// * `arc_length` - The number of consecutive brighter or darker pixels on the circle.
+/// * `out_keypoints` - The location where the detected keypoints are written.
+///
+/// # Safety
+///
+/// `src` must be a valid handle and `out_keypoints` must be a valid pointer to write to.
+#[no_mangle]
+pub unsafe extern "C" fn kornia_detect_fast(
+    src: *const KorniaImage,
+    threshold: u8,
+    arc_length: u8,
+    out_keypoints: *mut KorniaKeypoints,
+) -> KorniaStatus {
+    run(|| {
+        let KorniaImage::Gray(src) = image_ref(src, "src")? else {
+            return Err(CapiError::InvalidArgument(
+                "src: expected a grayscale image".to_string(),
+            ));
+        };
+        if out_keypoints.is_null() {
+            return Err(CapiError::NullPointer("out_keypoints"));
+        }
+        let keypoints = features::fast_feature_detector(src, threshold, arc_length)?
+            .into_iter()
+            .map(|[x, y]| KorniaKeypoint { x, y })
+            .collect::<Box<[_]>>();
+        let len = keypoints.len();
+        *out_keypoints = KorniaKeypoints {
+            data: Box::into_raw(keypoints) as *mut KorniaKeypoint,
+            len,
+        };
+        Ok(())
+    })
+}
+
+/// Release the keypoints returned by the library.
+///
+/// The array is reset to null and empty.
+///
+/// # Safety
+///
+/// `keypoints` must be null or point to keypoints returned by the library.
+#[no_mangle]
+pub unsafe extern "C" fn kornia_keypoints_free(keypoints: *mut KorniaKeypoints) {
+    let Some(keypoints) = keypoints.as_mut() else {
+        return;
+    };
+    if !keypoints.data.is_null() {
+        let slice = std::ptr::slice_from_raw_parts_mut(keypoints.data, keypoints.len);
+        drop(Box::from_raw(slice));
+    }
+    keypoints.data = std::ptr::null_mut();
+    keypoints.len = 0;
+}
+
+/// Track an object in a probability image with the continuously adaptive mean shift.
+///
+/// # Arguments
+///
+/// * `prob` - The grayscale probability image, e.g. a histogram back-projection.
+/// * `window` - The window of the object in the previous frame, updated with the window
+///   to search in the next frame.
+/// * `max_iterations` - The maximum number of mean shift iterations.
+/// * `epsilon` - The shift of the window in pixels below which the iterations stop.
+/// * `out_object` - The location where the rotated rectangle of the object is written.
+///
+/// # Safety
+///
+/// `prob` must be a valid handle, `window` must be a valid pointer to read and write, and
+/// `out_object` must be a valid pointer to write to.
+#[no_mangle]
+pub unsafe extern "C" fn kornia_cam_shift(
+    prob: *const KorniaImage,
+    window: *mut KorniaRect,
+    max_iterations: usize,
+    epsilon: f32,
+    out_object: *mut KorniaRotatedRect,
+) -> KorniaStatus {
+    run(|| {
+        let KorniaImage::Gray(prob) = image_ref(prob, "prob")? else {
+            return Err(CapiError::InvalidArgument(
+                "prob: expected a grayscale image".to_string(),
+            ));
+        };
+        let window = window.as_mut().ok_or(CapiError::NullPointer("window"))?;
+        if out_object.is_null() {
+            return Err(CapiError::NullPointer("out_object"));
+        }
+
+        let criteria = video::MeanShiftCriteria {
+            max_iterations,
+            epsilon,
+        };
+        let rect = [window.x, window.y, window.width, window.height];
+        let (object, [x, y, width, height]) = video::cam_shift(prob, rect, &criteria)?;
+
+        *window = KorniaRect {
+            x,
+            y,
+            width,
+            height,
+        };
+        *out_object = KorniaRotatedRect {
+            center_x: object.center[0],
+            center_y: object.center[1],
+            length: object.size[0],
+            width: object.size[1],
+            angle: object.angle,
+        };
+        Ok(())
+    })
+}
+
+/// Get the default parameters of the Lucas-Kanade tracker.
+///
+/// # Returns
+///
+/// A 21x21 window, 3 pyramid levels, 30 iterations, an epsilon of 0.01 pixels, a minimum
+/// eigenvalue of 1e-4 and the intensity residual.
+#[no_mangle]
+pub extern "C" fn kornia_lk_params_default() -> KorniaLkParams {
+    let params = video::LkParams::default();
+    KorniaLkParams {
+        window_size: params.window_size,
+        num_levels: params.num_levels,
+        max_iterations: params.max_iterations,
+        epsilon: params.epsilon,
+        min_eigenvalue: params.min_eigenvalue,
+        residual: KorniaLkResidual::Intensity,
+    }
+}
+
+/// Track points between two grayscale images with the pyramidal Lucas-Kanade optical flow.
+///
+/// The intensities are normalized to [0, 1] before the tracking.
+///
+/// # Arguments
+///
+/// * `prev` - The previous grayscale image.
+/// * `next` - The next grayscale image with the size of `prev`.
+/// * `points` - The positions of the points in the previous image.
+/// * `guesses` - The predicted positions of the points in the next image, or null to start
+///   from the previous positions.
+/// * `num_points` - The number of points.
+/// * `params` - The parameters of the tracker, or null for [`kornia_lk_params_default`].
+/// * `out_points` - The location where the `num_points` tracked points are written, in the
+///   order of `points`.
+///
+/// # Safety
+///
+/// `prev` and `next` must be valid handles, `points`, `guesses` if not null and
+/// `out_points` must point to `num_points` elements, and `params` must be null or a valid
+/// pointer to read.
+#[no_mangle]
+pub unsafe extern "C" fn kornia_track_points_lk(
+    prev: *const KorniaImage,
+    next: *const KorniaImage,
+    points: *const KorniaPoint2f,
+    guesses: *const KorniaPoint2f,
+    num_points: usize,
+    params: *const KorniaLkParams,
+    out_points: *mut KorniaTrackedPoint,
+) -> KorniaStatus {
+    run(|| {
+        let (KorniaImage::Gray(prev), KorniaImage::Gray(next)) =
+            (image_ref(prev, "prev")?, image_ref(next, "next")?)
+        else {
+            return Err(CapiError::InvalidArgument(
+                "prev, next: expected grayscale images".to_string(),
+            ));
+        };
+        if num_points == 0 {
+            return Ok(());
+        }
+        if points.is_null() {
+            return Err(CapiError::NullPointer("points"));
+        }
+        if out_points.is_null() {
+            return Err(CapiError::NullPointer("out_points"));
+        }
+
+        let points = std::slice::from_raw_parts(points, num_points);
+        let guesses = if guesses.is_null() {
+            points
+        } else {
+            std::slice::from_raw_parts(guesses, num_points)
+        };
+        let points = points
+            .iter()
+            .zip(guesses)
+            .map(|(p, g)| ([p.x, p.y], [g.x, g.y]))
+            .collect::<Vec<_>>();
+        let params = params
+            .as_ref()
+            .map_or_else(video::LkParams::default, |&params| params.into());
+
+        let prev = prev.map(|&v| v as f32 / 255.0)?;
+        let next = next.map(|&v| v as f32 / 255.0)?;
+        let tracked = video::track_points_lk_with_guesses(&prev, &next, &points, &params)?;
+
+        let out_points = std::slice::from_raw_parts_mut(out_points, num_points);
+        for (out, t) in out_points.iter_mut().zip(tracked) {
+            *out = KorniaTrackedPoint {
+                x: t.position[0],
+                y: t.position[1],
+                tracked: t.tracked,
+                error: t.error,
+            };
+        }
+        Ok(())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::image::{
+        kornia_image_channels, kornia_image_free, kornia_image_from_data, kornia_image_new,
+        kornia_image_width,
+    };
+
+    #[test]
+    fn test_resize_and_gray() {
+        let data = [255u8; 4 * 4 * 3];
+        let (mut src, mut resized, mut gray) = (
+            std::ptr::null_mut(),
+            std::ptr::null_mut(),
+            std::ptr::null_mut(),
+        );
+        unsafe {
+            kornia_image_from_data(4, 4, 3, data.as_ptr(), data.len(), &mut src);
+            let status = kornia_resize(src, 2, 3, KorniaInterpolation::Bilinear, &mut resized);
+            assert_eq!(status, KorniaStatus::Ok);
+            assert_eq!(kornia_image_width(resized), 2);
+
+            assert_eq!(kornia_gray_from_rgb(resized, &mut gray), KorniaStatus::Ok);
+            assert_eq!(kornia_image_channels(gray), 1);
+
+            // the float path of the grayscale images
+            let mut gray_resized = std::ptr::null_mut();
+            let status = kornia_resize(gray, 4, 4, KorniaInterpolation::Nearest, &mut gray_resized);
+            assert_eq!(status, KorniaStatus::Ok);
+            assert_eq!(kornia_image_width(gray_resized), 4);
+
+            // the conversion expects an RGB image
+            let mut out = std::ptr::null_mut();
+            assert_eq!(
+                kornia_gray_from_rgb(gray, &mut out),
+                KorniaStatus::InvalidArgument
+            );
+
+            for image in [src, resized, gray, gray_resized] {
+                kornia_image_free(image);
+            }
+        }
+    }
+
+    #[test]
+    fn test_detect_and_track() {
+        let mut image = std::ptr::null_mut();
+        let mut keypoints = KorniaKeypoints {
+            data: std::ptr::null_mut(),
+            len: 0,
+        };
+        unsafe {
+            kornia_image_new(40, 40, 1, &mut image);
+            let pixels = std::slice::from_raw_parts_mut(crate::kornia_image_data_mut(image), 1600);
+            for y in 20..30 {
+                pixels[y * 40 + 22..y * 40 + 32].fill(255);
+            }
+
+            let status = kornia_detect_fast(image, 100, 9, &mut keypoints);
+            assert_eq!(status, KorniaStatus::Ok);
+            assert!(keypoints.len > 0);
+            kornia_keypoints_free(&mut keypoints);
+            assert!(keypoints.data.is_null());
+
+            let mut window = KorniaRect {
+                x: 14,
+                y: 14,
+                width: 10,
+                height: 10,
+            };
+            let mut object = KorniaRotatedRect {
+                center_x: 0.0,
+                center_y: 0.0,
+                length: 0.0,
+                width: 0.0,
+                angle: 0.0,
+            };
+            let status = kornia_cam_shift(image, &mut window, 10, 1.0, &mut object);
+            assert_eq!(status, KorniaStatus::Ok);
+            assert!((object.center_x - 26.5).abs() < 1.0);
+            assert!((object.center_y - 24.5).abs() < 1.0);
+
+            kornia_image_free(image);
+        }
+    }
+
+    #[test]
+    fn test_track_points_lk() {
+        // a smooth texture translated by `shift`
+        let texture = |shift: [f32; 2]| {
+            let mut image = std::ptr::null_mut();
+            unsafe {
+                kornia_image_new(64, 48, 1, &mut image);
+                let pixels =
+                    std::slice::from_raw_parts_mut(crate::kornia_image_data_mut(image), 64 * 48);
+                for (i, p) in pixels.iter_mut().enumerate() {
+                    let x = (i % 64) as f32 - shift[0];
+                    let y = (i / 64) as f32 - shift[1];
+                    let v = 0.5
+                        + 0.25 * (x / 5.0).sin() * (y / 7.0).cos()
+                        + 0.2 * ((x + y) / 9.0).sin();
+                    *p = (v * 255.0).round() as u8;
+                }
+            }
+            image
+        };
+        let (prev, next) = (texture([0.0, 0.0]), texture([2.0, -1.0]));
+        let points = [
+            KorniaPoint2f { x: 30.0, y: 24.0 },
+            KorniaPoint2f { x: -3.0, y: 5.0 },
+        ];
+        let mut tracked = [KorniaTrackedPoint {
+            x: 0.0,
+            y: 0.0,
+            tracked: false,
+            error: 0.0,
+        }; 2];
+
+        unsafe {
+            let params = kornia_lk_params_default();
+            assert_eq!(params.window_size, 21);
+
+            let status = kornia_track_points_lk(
+                prev,
+                next,
+                points.as_ptr(),
+                std::ptr::null(),
+                points.len(),
+                &params,
+                tracked.as_mut_ptr(),
+            );
+            assert_eq!(status, KorniaStatus::Ok);
+            assert!(tracked[0].tracked, "{:?}", tracked[0]);
+            assert!((tracked[0].x - 32.0).abs() < 0.1, "{:?}", tracked[0]);
+            assert!((tracked[0].y - 23.0).abs() < 0.1, "{:?}", tracked[0]);
+            // the point outside of the image is lost
+            assert!(!tracked[1].tracked);
+
+            // the initial guesses and the default parameters
+            let guesses = [KorniaPoint2f { x: 32.0, y: 23.0 }; 2];
+            let status = kornia_track_points_lk(
+                prev,
+                next,
+                points.as_ptr(),
+                guesses.as_ptr(),
+                1,
+                std::ptr::null(),
+                tracked.as_mut_ptr(),
+            );
+            assert_eq!(status, KorniaStatus::Ok);
+            assert!(tracked[0].tracked);
+
+            let status = kornia_track_points_lk(
+                prev,
+                next,
+                std::ptr::null(),
+                std::ptr::null(),
+                1,
+                std::ptr::null(),
+                tracked.as_mut_ptr(),
+            );
+            assert_eq!(status, KorniaStatus::NullPointer);
+
+            kornia_image_free(prev);
+            kornia_image_free(next);
+        }
+    }
+}
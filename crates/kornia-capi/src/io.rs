@@ -0,0 +1,94 @@
+use std::ffi::{c_char, CStr};
+
+use kornia_io::functional as F;
+
+use crate::error::{run, CapiError, KorniaStatus};
+use crate::image::KorniaImage;
+
+/// Decode an encoded image in memory, e.g. JPEG or PNG, to an RGB image.
+///
+/// # Arguments
+///
+/// * `data` - The encoded bytes.
+/// * `data_len` - The number of encoded bytes.
+/// * `out_image` - The location where the handle of the decoded image is written.
+///
+/// # Safety
+///
+/// `data` must point to `data_len` readable bytes and `out_image` must be a valid pointer
+/// to write to.
+#[no_mangle]
+pub unsafe extern "C" fn kornia_decode_image(
+    data: *const u8,
+    data_len: usize,
+    out_image: *mut *mut KorniaImage,
+) -> KorniaStatus {
+    run(|| {
+        if data.is_null() {
+            return Err(CapiError::NullPointer("data"));
+        }
+        if out_image.is_null() {
+            return Err(CapiError::NullPointer("out_image"));
+        }
+        let image = F::decode_image_any_rgb8(std::slice::from_raw_parts(data, data_len))?;
+        KorniaImage::Rgb(image).write_to(out_image);
+        Ok(())
+    })
+}
+
+/// Read an image file to an RGB image.
+///
+/// # Arguments
+///
+/// * `path` - The path of the file as a nul-terminated UTF-8 string.
+/// * `out_image` - The location where the handle of the image is written.
+///
+/// # Safety
+///
+/// `path` must be a valid nul-terminated string and `out_image` must be a valid pointer
+/// to write to.
+#[no_mangle]
+pub unsafe extern "C" fn kornia_read_image(
+    path: *const c_char,
+    out_image: *mut *mut KorniaImage,
+) -> KorniaStatus {
+    run(|| {
+        if path.is_null() {
+            return Err(CapiError::NullPointer("path"));
+        }
+        if out_image.is_null() {
+            return Err(CapiError::NullPointer("out_image"));
+        }
+        let path = CStr::from_ptr(path)
+            .to_str()
+            .map_err(|e| CapiError::InvalidArgument(format!("path: {e}")))?;
+        let image = F::read_image_any_rgb8(path)?;
+        KorniaImage::Rgb(image).write_to(out_image);
+        Ok(())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::image::{kornia_image_free, kornia_image_height, kornia_image_width};
+
+    #[test]
+    fn test_read_image() {
+        let path = std::ffi::CString::new("../../tests/data/dog.jpeg").unwrap();
+        let mut image = std::ptr::null_mut();
+        unsafe {
+            assert_eq!(
+                kornia_read_image(path.as_ptr(), &mut image),
+                KorniaStatus::Ok
+            );
+            assert_eq!(kornia_image_width(image), 258);
+            assert_eq!(kornia_image_height(image), 195);
+            kornia_image_free(image);
+
+            let status = kornia_decode_image([0u8; 4].as_ptr(), 4, &mut image);
+            assert_eq!(status, KorniaStatus::IoError);
+            assert!(!crate::error::kornia_last_error_message().is_null());
+        }
+    }
+}
@@ -0,0 +1,23 @@
+#![deny(missing_docs)]
+//! C bindings for the kornia image processing and IO.
+//!
+//! The images are exposed as opaque handles created and released by the library. The
+//! functions return a [`KorniaStatus`] and the message of the last error is available
+//! with [`kornia_last_error_message`]. The header `include/kornia.h` is generated with
+//! `cbindgen --config cbindgen.toml --output include/kornia.h`.
+
+/// Status codes and error messages.
+mod error;
+pub use error::*;
+
+/// Opaque image handles.
+mod image;
+pub use image::*;
+
+/// Image processing, feature detection and tracking.
+mod imgproc;
+pub use imgproc::*;
+
+/// Image reading and decoding.
+mod io;
+pub use io::*;
@@ -0,0 +1,267 @@
+use std::path::{Path, PathBuf};
+
+use kornia_3d::io::colmap::{
+    read_cameras_bin, read_cameras_txt, read_images_bin, read_images_txt, read_points3d_bin,
+    read_points3d_txt, CameraModelId, ColmapCamera, ColmapImage, ColmapPoint3d,
+};
+use kornia_3d::pose_graph::Pose;
+use kornia_image::{Image, ImageSize};
+use kornia_imgproc::calibration::{
+    distortion::{CameraDistortion, FisheyeDistortion, PolynomialDistortion},
+    CameraIntrinsic,
+};
+use kornia_io::functional::read_image_any_rgb8;
+
+use crate::error::DatasetError;
+
+/// A sparse reconstruction of COLMAP with its images.
+///
+/// The model directory contains the `cameras`, `images` and `points3D` files in the binary
+/// `.bin` or in the text `.txt` format, e.g. the `sparse/0` directory of a reconstruction.
+pub struct ColmapDataset {
+    image_dir: PathBuf,
+    cameras: Vec<ColmapCamera>,
+    images: Vec<ColmapImage>,
+    points3d: Vec<ColmapPoint3d>,
+}
+
+impl ColmapDataset {
+    /// Opens a sparse reconstruction of COLMAP.
+    ///
+    /// The binary files are read if they exist, otherwise the text files.
+    ///
+    /// # Arguments
+    ///
+    /// * `model_dir` - The path to the directory of the model.
+    /// * `image_dir` - The path to the directory of the images.
+    pub fn open(
+        model_dir: impl AsRef<Path>,
+        image_dir: impl AsRef<Path>,
+    ) -> Result<Self, DatasetError> {
+        let model_dir = model_dir.as_ref();
+
+        let (cameras, images, points3d) = if model_dir.join("cameras.bin").exists() {
+            (
+                read_cameras_bin(model_dir.join("cameras.bin"))?,
+                read_images_bin(model_dir.join("images.bin"))?,
+                read_points3d_bin(model_dir.join("points3D.bin"))?,
+            )
+        } else if model_dir.join("cameras.txt").exists() {
+            (
+                read_cameras_txt(model_dir.join("cameras.txt"))?,
+                read_images_txt(model_dir.join("images.txt"))?,
+                read_points3d_txt(model_dir.join("points3D.txt"))?,
+            )
+        } else {
+            return Err(DatasetError::FileDoesNotExist(
+                model_dir.join("cameras.bin"),
+            ));
+        };
+
+        Ok(Self {
+            image_dir: image_dir.as_ref().to_path_buf(),
+            cameras,
+            images,
+            points3d,
+        })
+    }
+
+    /// Returns the cameras of the reconstruction.
+    pub fn cameras(&self) -> &[ColmapCamera] {
+        &self.cameras
+    }
+
+    /// Returns the registered images of the reconstruction.
+    pub fn images(&self) -> &[ColmapImage] {
+        &self.images
+    }
+
+    /// Returns the 3D points of the reconstruction.
+    pub fn points3d(&self) -> &[ColmapPoint3d] {
+        &self.points3d
+    }
+
+    /// Returns the camera with the given id.
+    pub fn camera(&self, camera_id: u32) -> Option<&ColmapCamera> {
+        self.cameras.iter().find(|c| c.camera_id == camera_id)
+    }
+
+    /// Returns the calibration of a camera.
+    ///
+    /// COLMAP places the center of the top-left pixel at (0.5, 0.5), so the principal point
+    /// is shifted by half a pixel to the convention of kornia with the center at (0, 0).
+    ///
+    /// # Arguments
+    ///
+    /// * `camera_id` - The id of the camera.
+    ///
+    /// # Returns
+    ///
+    /// The image size, the intrinsic parameters and the distortion of the camera.
+    pub fn camera_model(
+        &self,
+        camera_id: u32,
+    ) -> Result<(ImageSize, CameraIntrinsic, CameraDistortion), DatasetError> {
+        let camera = self
+            .camera(camera_id)
+            .ok_or_else(|| DatasetError::InvalidCamera(format!("no camera {}", camera_id)))?;
+
+        // pad the parameters to read the optional distortion coefficients
+        let mut p = [0.0; 12];
+        for (p, param) in p.iter_mut().zip(&camera.params) {
+            *p = *param;
+        }
+
+        let polynomial = |k1, k2, p1, p2, k3, k4, k5, k6| {
+            CameraDistortion::Polynomial(PolynomialDistortion {
+                k1,
+                k2,
+                k3,
+                k4,
+                k5,
+                k6,
+                p1,
+                p2,
+            })
+        };
+
+        let ([fx, fy, cx, cy], distortion) = match camera.model_id {
+            CameraModelId::CameraModelSimplePinhole => (
+                [p[0], p[0], p[1], p[2]],
+                polynomial(0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0),
+            ),
+            CameraModelId::CameraModelPinhole => (
+                [p[0], p[1], p[2], p[3]],
+                polynomial(0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0),
+            ),
+            CameraModelId::CameraModelSimplifiedRadial => (
+                [p[0], p[0], p[1], p[2]],
+                polynomial(p[3], 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0),
+            ),
+            CameraModelId::CameraModelRadial => (
+                [p[0], p[0], p[1], p[2]],
+                polynomial(p[3], p[4], 0.0, 0.0, 0.0, 0.0, 0.0, 0.0),
+            ),
+            CameraModelId::CameraModelOpenCV => (
+                [p[0], p[1], p[2], p[3]],
+                polynomial(p[4], p[5], p[6], p[7], 0.0, 0.0, 0.0, 0.0),
+            ),
+            CameraModelId::CameraModelFullOpenCV => (
+                [p[0], p[1], p[2], p[3]],
+                polynomial(p[4], p[5], p[6], p[7], p[8], p[9], p[10], p[11]),
+            ),
+            CameraModelId::CameraModelOpenCVFisheye => (
+                [p[0], p[1], p[2], p[3]],
+                CameraDistortion::Fisheye(FisheyeDistortion {
+                    k1: p[4],
+                    k2: p[5],
+                    k3: p[6],
+                    k4: p[7],
+                }),
+            ),
+            ref model_id => {
+                return Err(DatasetError::InvalidCamera(format!(
+                    "unsupported camera model {:?}",
+                    model_id
+                )))
+            }
+        };
+
+        let image_size = ImageSize {
+            width: camera.width,
+            height: camera.height,
+        };
+        let intrinsic = CameraIntrinsic {
+            fx,
+            fy,
+            cx: cx - 0.5,
+            cy: cy - 0.5,
+        };
+        Ok((image_size, intrinsic, distortion))
+    }
+
+    /// Returns the pose of the camera of an image in the world frame.
+    ///
+    /// COLMAP stores the world-to-camera transformation, which is inverted.
+    pub fn image_pose(&self, image: &ColmapImage) -> Pose {
+        Pose::from_quaternion(image.rotation, image.translation).inverse()
+    }
+
+    /// Reads an image of the reconstruction.
+    ///
+    /// # Arguments
+    ///
+    /// * `image` - The image of the reconstruction.
+    ///
+    /// # Returns
+    ///
+    /// The RGB image.
+    pub fn read_image(&self, image: &ColmapImage) -> Result<Image<u8, 3>, DatasetError> {
+        Ok(read_image_any_rgb8(self.image_dir.join(&image.name))?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use kornia_io::png::write_image_png_rgb8;
+
+    #[test]
+    fn test_colmap_dataset() -> Result<(), Box<dyn std::error::Error>> {
+        let tmp_dir = tempfile::tempdir()?;
+        let model_dir = tmp_dir.path().join("sparse/0");
+        let image_dir = tmp_dir.path().join("images");
+        std::fs::create_dir_all(&model_dir)?;
+        std::fs::create_dir_all(&image_dir)?;
+
+        std::fs::write(
+            model_dir.join("cameras.txt"),
+            "# Camera list with one line of data per camera:\n\
+             #   CAMERA_ID, MODEL, WIDTH, HEIGHT, PARAMS[]\n\
+             # Number of cameras: 2\n\
+             1 OPENCV 8 6 500 510 4.5 3.5 0.1 -0.05 0.001 0.002\n\
+             2 FOV 8 6 500 510 4 3 0.9\n",
+        )?;
+        std::fs::write(
+            model_dir.join("images.txt"),
+            "# Image list with two lines of data per image:\n\
+             #   IMAGE_ID, QW, QX, QY, QZ, TX, TY, TZ, CAMERA_ID, NAME\n\
+             #   POINTS2D[] as (X, Y, POINT3D_ID)\n\
+             # Number of images: 1\n\
+             1 0 0 0 1 1 2 3 1 frame.png\n\
+             2.5 3.5 1\n",
+        )?;
+        std::fs::write(
+            model_dir.join("points3D.txt"),
+            "# 3D point list with one line of data per point:\n\
+             #   POINT3D_ID, X, Y, Z, R, G, B, ERROR, TRACK[] as (IMAGE_ID, POINT2D_IDX)\n\
+             # Number of points: 1\n\
+             1 0.5 0.5 4 255 0 0 0.2 1 0\n",
+        )?;
+        let image = Image::<u8, 3>::from_size_val([8, 6].into(), 50)?;
+        write_image_png_rgb8(image_dir.join("frame.png"), &image)?;
+
+        let dataset = ColmapDataset::open(&model_dir, &image_dir)?;
+        assert_eq!(dataset.cameras().len(), 2);
+        assert_eq!(dataset.points3d()[0].xyz, [0.5, 0.5, 4.0]);
+
+        let (image_size, intrinsic, distortion) = dataset.camera_model(1)?;
+        assert_eq!(image_size.width, 8);
+        assert_eq!((intrinsic.cx, intrinsic.cy), (4.0, 3.0));
+        let CameraDistortion::Polynomial(distortion) = distortion else {
+            panic!("expected a polynomial distortion");
+        };
+        assert_eq!(distortion.p2, 0.002);
+        assert!(dataset.camera_model(2).is_err());
+        assert!(dataset.camera_model(3).is_err());
+
+        // a rotation of 180 degrees around z
+        let pose = dataset.image_pose(&dataset.images()[0]);
+        assert_eq!(pose.translation, [1.0, 2.0, -3.0]);
+
+        let image = dataset.read_image(&dataset.images()[0])?;
+        assert_eq!(image.size(), image_size);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,40 @@
+use std::path::PathBuf;
+
+/// An error type for the dataset loaders.
+#[derive(thiserror::Error, Debug)]
+pub enum DatasetError {
+    /// Error when a file of the dataset does not exist.
+    #[error("File does not exist: {0}")]
+    FileDoesNotExist(PathBuf),
+
+    /// Error when a line of a text file cannot be parsed.
+    #[error("Failed to parse line {line} of {}. {msg}", .path.display())]
+    ParseError {
+        /// The path to the file.
+        path: PathBuf,
+        /// The line number, starting at 1.
+        line: usize,
+        /// The description of the error.
+        msg: String,
+    },
+
+    /// Error to read a file.
+    #[error(transparent)]
+    FileError(#[from] std::io::Error),
+
+    /// Error to read an image or a calibration file.
+    #[error(transparent)]
+    IoError(#[from] kornia_io::IoError),
+
+    /// Error to read a COLMAP model.
+    #[error(transparent)]
+    ColmapError(#[from] kornia_3d::io::colmap::ColmapError),
+
+    /// Error when a frame index is out of range.
+    #[error("Invalid frame index {0} for {1} frames")]
+    InvalidFrameIndex(usize, usize),
+
+    /// Error when a camera does not exist or its model is not supported.
+    #[error("Invalid camera. {0}")]
+    InvalidCamera(String),
+}
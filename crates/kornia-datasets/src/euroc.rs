@@ -0,0 +1,375 @@
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use kornia_3d::pose_graph::Pose;
+use kornia_image::{Image, ImageSize};
+use kornia_imgproc::calibration::{distortion::PolynomialDistortion, CameraIntrinsic};
+use kornia_io::{calibration::read_file_storage, png::read_image_png_mono8};
+use serde::Deserialize;
+
+use crate::error::DatasetError;
+use crate::text::{parse_fields, parse_timestamp_and_name, read_lines, Line};
+use crate::trajectory::StampedPose;
+
+/// A camera of a EuRoC MAV sequence.
+pub struct EurocCamera {
+    /// The intrinsic parameters of the camera.
+    pub intrinsic: CameraIntrinsic,
+    /// The radial-tangential distortion of the camera.
+    pub distortion: PolynomialDistortion,
+    /// The size of the images.
+    pub image_size: ImageSize,
+    /// The frame rate of the camera in Hz.
+    pub rate_hz: f64,
+    /// The pose of the camera in the body frame of the IMU, `T_BS`.
+    pub sensor_to_body: Pose,
+    frames: Vec<(Duration, PathBuf)>,
+}
+
+impl EurocCamera {
+    /// Returns the timestamps of the images.
+    pub fn timestamps(&self) -> impl Iterator<Item = Duration> + '_ {
+        self.frames.iter().map(|(timestamp, _)| *timestamp)
+    }
+}
+
+/// A measurement of the IMU of a EuRoC MAV sequence.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ImuSample {
+    /// The timestamp of the measurement.
+    pub timestamp: Duration,
+    /// The angular velocity in rad/s.
+    pub gyroscope: [f64; 3],
+    /// The linear acceleration in m/s^2.
+    pub accelerometer: [f64; 3],
+}
+
+// the `T_BS` matrix of a `sensor.yaml` file
+#[derive(Deserialize)]
+struct SensorMatrix {
+    rows: usize,
+    cols: usize,
+    data: Vec<f64>,
+}
+
+// the calibration of a camera in its `sensor.yaml` file
+#[derive(Deserialize)]
+#[allow(non_snake_case)]
+struct CameraSensor {
+    T_BS: SensorMatrix,
+    rate_hz: f64,
+    resolution: [usize; 2],
+    camera_model: String,
+    intrinsics: [f64; 4],
+    distortion_model: String,
+    distortion_coefficients: Vec<f64>,
+}
+
+/// A sequence of the EuRoC MAV dataset in the ASL format.
+///
+/// The `mav0` directory of the sequence contains the `cam0` and `cam1` stereo cameras, the
+/// `imu0` measurements and the `state_groundtruth_estimate0` trajectory of the body frame.
+/// Each camera directory contains the `data.csv` list of the timestamped images, the
+/// `data` images and the `sensor.yaml` calibration.
+pub struct EurocDataset {
+    cameras: Vec<EurocCamera>,
+    imu: Vec<ImuSample>,
+    ground_truth: Vec<StampedPose>,
+}
+
+fn read_camera(camera_dir: &Path) -> Result<EurocCamera, DatasetError> {
+    let sensor: CameraSensor = read_file_storage(camera_dir.join("sensor.yaml"))?;
+    if sensor.camera_model != "pinhole" || sensor.distortion_model != "radial-tangential" {
+        return Err(DatasetError::InvalidCamera(format!(
+            "unsupported {} camera with {} distortion",
+            sensor.camera_model, sensor.distortion_model
+        )));
+    }
+
+    let t = &sensor.T_BS;
+    if t.rows != 4 || t.cols != 4 || t.data.len() != 16 {
+        return Err(DatasetError::InvalidCamera(format!(
+            "expected T_BS to be 4x4, found {}x{}",
+            t.rows, t.cols
+        )));
+    }
+
+    let mut d = [0.0; 5];
+    for (d, coefficient) in d.iter_mut().zip(&sensor.distortion_coefficients) {
+        *d = *coefficient;
+    }
+    let [k1, k2, p1, p2, k3] = d;
+    let [fx, fy, cx, cy] = sensor.intrinsics;
+
+    let list_path = camera_dir.join("data.csv");
+    let frames = read_lines(&list_path)?
+        .iter()
+        .map(|line| {
+            let (nanos, name) = parse_timestamp_and_name::<u64>(&list_path, line)?;
+            Ok((
+                Duration::from_nanos(nanos),
+                camera_dir.join("data").join(name),
+            ))
+        })
+        .collect::<Result<Vec<_>, DatasetError>>()?;
+
+    Ok(EurocCamera {
+        intrinsic: CameraIntrinsic { fx, fy, cx, cy },
+        distortion: PolynomialDistortion {
+            k1,
+            k2,
+            k3,
+            p1,
+            p2,
+            ..Default::default()
+        },
+        image_size: ImageSize {
+            width: sensor.resolution[0],
+            height: sensor.resolution[1],
+        },
+        rate_hz: sensor.rate_hz,
+        sensor_to_body: Pose {
+            rotation: [0, 1, 2].map(|r| [0, 1, 2].map(|c| t.data[r * 4 + c])),
+            translation: [0, 1, 2].map(|r| t.data[r * 4 + 3]),
+        },
+        frames,
+    })
+}
+
+// the timestamp in nanoseconds and the values of a line of a `data.csv` file
+fn parse_stamped_values(
+    file_path: &Path,
+    line: &Line,
+    min_values: usize,
+) -> Result<(Duration, Vec<f64>), DatasetError> {
+    let (nanos, values) = parse_timestamp_and_name::<u64>(file_path, line)?;
+    let values = Line {
+        number: line.number,
+        content: values.to_string(),
+    };
+    let values = parse_fields::<f64>(file_path, &values, min_values)?;
+    Ok((Duration::from_nanos(nanos), values))
+}
+
+fn read_imu(file_path: &Path) -> Result<Vec<ImuSample>, DatasetError> {
+    read_lines(file_path)?
+        .iter()
+        .map(|line| {
+            let (timestamp, v) = parse_stamped_values(file_path, line, 6)?;
+            Ok(ImuSample {
+                timestamp,
+                gyroscope: [v[0], v[1], v[2]],
+                accelerometer: [v[3], v[4], v[5]],
+            })
+        })
+        .collect()
+}
+
+/// Reads a trajectory in the EuRoC ASL format.
+///
+/// Each line starts with the timestamp in nanoseconds, the position `p_x, p_y, p_z` and the
+/// quaternion `q_w, q_x, q_y, q_z` of the body frame in the world frame, followed by the
+/// velocity and the biases of the IMU which are ignored.
+///
+/// # Arguments
+///
+/// * `file_path` - The path to the trajectory file, e.g.
+///   `state_groundtruth_estimate0/data.csv`.
+///
+/// # Returns
+///
+/// The poses of the body frame.
+pub fn read_euroc_trajectory(
+    file_path: impl AsRef<Path>,
+) -> Result<Vec<StampedPose>, DatasetError> {
+    let file_path = file_path.as_ref();
+    read_lines(file_path)?
+        .iter()
+        .map(|line| {
+            let (timestamp, v) = parse_stamped_values(file_path, line, 7)?;
+            Ok(StampedPose {
+                timestamp,
+                pose: Pose::from_quaternion([v[3], v[4], v[5], v[6]], [v[0], v[1], v[2]]),
+            })
+        })
+        .collect()
+}
+
+impl EurocDataset {
+    /// Opens a sequence of the EuRoC MAV dataset.
+    ///
+    /// # Arguments
+    ///
+    /// * `mav_dir` - The path to the `mav0` directory of the sequence.
+    pub fn open(mav_dir: impl AsRef<Path>) -> Result<Self, DatasetError> {
+        let mav_dir = mav_dir.as_ref();
+
+        let mut cameras = vec![read_camera(&mav_dir.join("cam0"))?];
+        if mav_dir.join("cam1").exists() {
+            cameras.push(read_camera(&mav_dir.join("cam1"))?);
+        }
+
+        let imu_path = mav_dir.join("imu0").join("data.csv");
+        let imu = match imu_path.exists() {
+            true => read_imu(&imu_path)?,
+            false => Vec::new(),
+        };
+
+        let ground_truth_path = mav_dir.join("state_groundtruth_estimate0").join("data.csv");
+        let ground_truth = match ground_truth_path.exists() {
+            true => read_euroc_trajectory(&ground_truth_path)?,
+            false => Vec::new(),
+        };
+
+        Ok(Self {
+            cameras,
+            imu,
+            ground_truth,
+        })
+    }
+
+    /// Returns the cameras of the sequence, `cam0` and `cam1` if available.
+    pub fn cameras(&self) -> &[EurocCamera] {
+        &self.cameras
+    }
+
+    /// Returns the number of images of a camera.
+    ///
+    /// # Arguments
+    ///
+    /// * `camera` - The index of the camera.
+    pub fn len(&self, camera: usize) -> usize {
+        self.cameras
+            .get(camera)
+            .map_or(0, |camera| camera.frames.len())
+    }
+
+    /// Returns true if a camera has no images.
+    ///
+    /// # Arguments
+    ///
+    /// * `camera` - The index of the camera.
+    pub fn is_empty(&self, camera: usize) -> bool {
+        self.len(camera) == 0
+    }
+
+    /// Returns the measurements of the IMU, empty if not available.
+    pub fn imu(&self) -> &[ImuSample] {
+        &self.imu
+    }
+
+    /// Returns the ground truth trajectory of the body frame, empty if not available.
+    pub fn ground_truth(&self) -> &[StampedPose] {
+        &self.ground_truth
+    }
+
+    /// Reads an image of a camera.
+    ///
+    /// # Arguments
+    ///
+    /// * `camera` - The index of the camera.
+    /// * `index` - The index of the image.
+    ///
+    /// # Returns
+    ///
+    /// The grayscale image and its timestamp.
+    pub fn read_image(
+        &self,
+        camera: usize,
+        index: usize,
+    ) -> Result<(Image<u8, 1>, Duration), DatasetError> {
+        let frames = &self
+            .cameras
+            .get(camera)
+            .ok_or_else(|| DatasetError::InvalidCamera(format!("no camera {}", camera)))?
+            .frames;
+        let (timestamp, image_path) = frames
+            .get(index)
+            .ok_or(DatasetError::InvalidFrameIndex(index, frames.len()))?;
+        Ok((read_image_png_mono8(image_path)?, *timestamp))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use kornia_io::png::write_image_png_gray8;
+
+    const SENSOR_YAML: &str = r#"# General sensor definitions.
+sensor_type: camera
+comment: VI-Sensor cam0 (MT9M034)
+
+# Sensor extrinsics wrt. the body-frame.
+T_BS:
+  cols: 4
+  rows: 4
+  data: [0.0148655429818, -0.999880929698, 0.00414029679422, -0.0216401454975,
+         0.999557249008, 0.0149672133247, 0.025715529948, -0.064676986768,
+        -0.0257744366974, 0.00375618835797, 0.999660727178, 0.00981073058949,
+         0.0, 0.0, 0.0, 1.0]
+
+# Camera specific definitions.
+rate_hz: 20
+resolution: [752, 480]
+camera_model: pinhole
+intrinsics: [458.654, 457.296, 367.215, 248.375] #fu, fv, cu, cv
+distortion_model: radial-tangential
+distortion_coefficients: [-0.28340811, 0.07395907, 0.00019359, 1.76187114e-05]
+"#;
+
+    #[test]
+    fn test_euroc_dataset() -> Result<(), Box<dyn std::error::Error>> {
+        let tmp_dir = tempfile::tempdir()?;
+        let mav_dir = tmp_dir.path().join("mav0");
+        let cam_dir = mav_dir.join("cam0");
+        std::fs::create_dir_all(cam_dir.join("data"))?;
+        std::fs::create_dir_all(mav_dir.join("imu0"))?;
+        std::fs::create_dir_all(mav_dir.join("state_groundtruth_estimate0"))?;
+
+        std::fs::write(cam_dir.join("sensor.yaml"), SENSOR_YAML)?;
+        std::fs::write(
+            cam_dir.join("data.csv"),
+            "#timestamp [ns],filename\n1403636579763555584,1403636579763555584.png\n",
+        )?;
+        let image = Image::<u8, 1>::from_size_val([752, 480].into(), 0)?;
+        write_image_png_gray8(cam_dir.join("data/1403636579763555584.png"), &image)?;
+
+        std::fs::write(
+            mav_dir.join("imu0/data.csv"),
+            "#timestamp [ns],w_RS_S_x [rad s^-1],w_RS_S_y [rad s^-1],w_RS_S_z [rad s^-1],\
+             a_RS_S_x [m s^-2],a_RS_S_y [m s^-2],a_RS_S_z [m s^-2]\n\
+             1403636579758555392,-0.099134701513277898,0.14730578886832138,\
+             0.02722713633111154,8.1476917083333333,-0.37592158333333331,-2.4026292499999999\n",
+        )?;
+        std::fs::write(
+            mav_dir.join("state_groundtruth_estimate0/data.csv"),
+            "#timestamp, p_RS_R_x [m], p_RS_R_y [m], p_RS_R_z [m], q_RS_w [], q_RS_x [], \
+             q_RS_y [], q_RS_z []\n\
+             1403636580838555648,4.688319,-1.786938,0.783338,0.534108,-0.153029,-0.827383,-0.082152,\
+             -0.027876,0.033207,0.800006,-0.003172,0.021267,0.078502,-0.025266,0.136696,0.075593\n",
+        )?;
+
+        let dataset = EurocDataset::open(&mav_dir)?;
+        let camera = &dataset.cameras()[0];
+        assert_eq!(camera.intrinsic.cx, 367.215);
+        assert_eq!(camera.distortion.p2, 1.76187114e-05);
+        assert_eq!(camera.image_size.width, 752);
+        assert_eq!(camera.sensor_to_body.translation[1], -0.064676986768);
+        assert_eq!(dataset.len(0), 1);
+        assert_eq!(dataset.len(1), 0);
+
+        let (image, timestamp) = dataset.read_image(0, 0)?;
+        assert_eq!(image.size(), camera.image_size);
+        assert_eq!(timestamp, Duration::from_nanos(1403636579763555584));
+        assert!(dataset.read_image(1, 0).is_err());
+
+        assert_eq!(dataset.imu()[0].accelerometer[0], 8.147691708333333);
+        assert_eq!(
+            dataset.imu()[0].timestamp,
+            Duration::from_nanos(1403636579758555392)
+        );
+        assert_eq!(dataset.ground_truth()[0].pose.translation[2], 0.783338);
+
+        Ok(())
+    }
+}
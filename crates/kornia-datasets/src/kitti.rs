@@ -0,0 +1,308 @@
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use kornia_3d::pose_graph::Pose;
+use kornia_image::Image;
+use kornia_imgproc::calibration::CameraIntrinsic;
+use kornia_io::{functional::read_image_any_rgb8, png::read_image_png_mono8};
+
+use crate::error::DatasetError;
+use crate::text::{parse_error, parse_fields, read_lines, Line};
+use crate::trajectory::StampedPose;
+
+/// A sequence of the KITTI odometry dataset.
+///
+/// The dataset directory contains `sequences/XX` with the `image_0` and `image_1` grayscale
+/// and the `image_2` and `image_3` color stereo images, the `calib.txt` projection matrices
+/// and the `times.txt` timestamps, and `poses/XX.txt` with the ground truth of the sequences
+/// 00 to 10.
+pub struct KittiOdometry {
+    sequence_dir: PathBuf,
+    timestamps: Vec<Duration>,
+    projections: [[[f64; 4]; 3]; 4],
+    poses: Option<Vec<Pose>>,
+}
+
+// a row-major 3x4 matrix
+fn matrix3x4(values: &[f64]) -> [[f64; 4]; 3] {
+    let mut m = [[0.0; 4]; 3];
+    for (row, values) in m.iter_mut().zip(values.chunks_exact(4)) {
+        row.copy_from_slice(values);
+    }
+    m
+}
+
+// the projection matrices `P0` to `P3` of the `calib.txt` file
+fn read_projections(file_path: &Path) -> Result<[[[f64; 4]; 3]; 4], DatasetError> {
+    let mut projections = [None; 4];
+    for line in read_lines(file_path)? {
+        let Some((name, values)) = line.content.split_once(':') else {
+            return Err(parse_error(
+                file_path,
+                &line,
+                "expected a name and a matrix",
+            ));
+        };
+        let camera = match name.trim() {
+            "P0" => 0,
+            "P1" => 1,
+            "P2" => 2,
+            "P3" => 3,
+            _ => continue,
+        };
+        let values = Line {
+            number: line.number,
+            content: values.to_string(),
+        };
+        let values = parse_fields::<f64>(file_path, &values, 12)?;
+        projections[camera] = Some(matrix3x4(&values));
+    }
+
+    let mut result = [[[0.0; 4]; 3]; 4];
+    for (camera, projection) in projections.into_iter().enumerate() {
+        result[camera] = projection.ok_or_else(|| {
+            DatasetError::InvalidCamera(format!("missing P{} in {}", camera, file_path.display()))
+        })?;
+    }
+    Ok(result)
+}
+
+/// Reads a trajectory in the KITTI format.
+///
+/// Each line contains the 12 values of the row-major 3x4 pose of the left camera in the
+/// frame of the first left camera.
+///
+/// # Arguments
+///
+/// * `file_path` - The path to the trajectory file, e.g. `poses/00.txt`.
+///
+/// # Returns
+///
+/// The poses of the frames.
+pub fn read_kitti_trajectory(file_path: impl AsRef<Path>) -> Result<Vec<Pose>, DatasetError> {
+    let file_path = file_path.as_ref();
+    read_lines(file_path)?
+        .iter()
+        .map(|line| {
+            let m = matrix3x4(&parse_fields::<f64>(file_path, line, 12)?);
+            Ok(Pose {
+                rotation: m.map(|row| [row[0], row[1], row[2]]),
+                translation: m.map(|row| row[3]),
+            })
+        })
+        .collect()
+}
+
+impl KittiOdometry {
+    /// Opens a sequence of the KITTI odometry dataset.
+    ///
+    /// # Arguments
+    ///
+    /// * `dataset_dir` - The path to the dataset directory with `sequences` and `poses`.
+    /// * `sequence` - The name of the sequence, e.g. `00`.
+    pub fn open(dataset_dir: impl AsRef<Path>, sequence: &str) -> Result<Self, DatasetError> {
+        let dataset_dir = dataset_dir.as_ref();
+        let sequence_dir = dataset_dir.join("sequences").join(sequence);
+
+        let times_path = sequence_dir.join("times.txt");
+        let timestamps = read_lines(&times_path)?
+            .iter()
+            .map(|line| {
+                let secs = parse_fields::<f64>(&times_path, line, 1)?[0];
+                Duration::try_from_secs_f64(secs)
+                    .map_err(|_| parse_error(&times_path, line, "invalid timestamp"))
+            })
+            .collect::<Result<Vec<_>, DatasetError>>()?;
+
+        let projections = read_projections(&sequence_dir.join("calib.txt"))?;
+
+        let poses_path = dataset_dir.join("poses").join(format!("{sequence}.txt"));
+        let poses = match poses_path.exists() {
+            true => Some(read_kitti_trajectory(&poses_path)?),
+            false => None,
+        };
+
+        Ok(Self {
+            sequence_dir,
+            timestamps,
+            projections,
+            poses,
+        })
+    }
+
+    /// Returns the number of frames of the sequence.
+    pub fn len(&self) -> usize {
+        self.timestamps.len()
+    }
+
+    /// Returns true if the sequence has no frames.
+    pub fn is_empty(&self) -> bool {
+        self.timestamps.is_empty()
+    }
+
+    /// Returns the timestamps of the frames since the start of the sequence.
+    pub fn timestamps(&self) -> &[Duration] {
+        &self.timestamps
+    }
+
+    /// Returns the 3x4 projection matrix of a rectified camera.
+    ///
+    /// # Arguments
+    ///
+    /// * `camera` - The camera, 0 and 1 for the grayscale and 2 and 3 for the color pair.
+    pub fn projection(&self, camera: usize) -> Result<&[[f64; 4]; 3], DatasetError> {
+        self.projections
+            .get(camera)
+            .ok_or_else(|| DatasetError::InvalidCamera(format!("no camera {}", camera)))
+    }
+
+    /// Returns the intrinsic parameters of a rectified camera.
+    ///
+    /// # Arguments
+    ///
+    /// * `camera` - The camera, 0 and 1 for the grayscale and 2 and 3 for the color pair.
+    pub fn intrinsic(&self, camera: usize) -> Result<CameraIntrinsic, DatasetError> {
+        let p = self.projection(camera)?;
+        Ok(CameraIntrinsic {
+            fx: p[0][0],
+            fy: p[1][1],
+            cx: p[0][2],
+            cy: p[1][2],
+        })
+    }
+
+    /// Returns the baseline in meters between the left and the right camera of a pair.
+    ///
+    /// # Arguments
+    ///
+    /// * `right_camera` - The right camera, 1 for the grayscale and 3 for the color pair.
+    pub fn baseline(&self, right_camera: usize) -> Result<f64, DatasetError> {
+        let left = self.projection(right_camera.saturating_sub(1))?;
+        let right = self.projection(right_camera)?;
+        Ok((left[0][3] - right[0][3]) / right[0][0])
+    }
+
+    /// Returns the ground truth poses of the left camera, if available.
+    pub fn poses(&self) -> Option<&[Pose]> {
+        self.poses.as_deref()
+    }
+
+    /// Returns the ground truth poses with the timestamps of the frames, if available.
+    pub fn ground_truth(&self) -> Option<Vec<StampedPose>> {
+        let poses = self.poses.as_ref()?;
+        Some(
+            self.timestamps
+                .iter()
+                .zip(poses)
+                .map(|(&timestamp, &pose)| StampedPose { timestamp, pose })
+                .collect(),
+        )
+    }
+
+    fn image_path(&self, camera: usize, index: usize) -> Result<PathBuf, DatasetError> {
+        if index >= self.len() {
+            return Err(DatasetError::InvalidFrameIndex(index, self.len()));
+        }
+        Ok(self
+            .sequence_dir
+            .join(format!("image_{camera}"))
+            .join(format!("{index:06}.png")))
+    }
+
+    /// Reads the grayscale stereo pair of a frame.
+    ///
+    /// # Arguments
+    ///
+    /// * `index` - The index of the frame.
+    ///
+    /// # Returns
+    ///
+    /// The images of the cameras 0 and 1.
+    pub fn read_gray_pair(
+        &self,
+        index: usize,
+    ) -> Result<(Image<u8, 1>, Image<u8, 1>), DatasetError> {
+        Ok((
+            read_image_png_mono8(self.image_path(0, index)?)?,
+            read_image_png_mono8(self.image_path(1, index)?)?,
+        ))
+    }
+
+    /// Reads the color stereo pair of a frame.
+    ///
+    /// # Arguments
+    ///
+    /// * `index` - The index of the frame.
+    ///
+    /// # Returns
+    ///
+    /// The images of the cameras 2 and 3.
+    pub fn read_color_pair(
+        &self,
+        index: usize,
+    ) -> Result<(Image<u8, 3>, Image<u8, 3>), DatasetError> {
+        Ok((
+            read_image_any_rgb8(self.image_path(2, index)?)?,
+            read_image_any_rgb8(self.image_path(3, index)?)?,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use kornia_io::png::write_image_png_gray8;
+
+    #[test]
+    fn test_kitti_odometry() -> Result<(), Box<dyn std::error::Error>> {
+        let tmp_dir = tempfile::tempdir()?;
+        let sequence_dir = tmp_dir.path().join("sequences/00");
+        std::fs::create_dir_all(sequence_dir.join("image_0"))?;
+        std::fs::create_dir_all(sequence_dir.join("image_1"))?;
+        std::fs::create_dir_all(tmp_dir.path().join("poses"))?;
+
+        let image = Image::<u8, 1>::from_size_val([6, 4].into(), 7)?;
+        for camera in [0, 1] {
+            for index in 0..2 {
+                let name = format!("image_{camera}/{index:06}.png");
+                write_image_png_gray8(sequence_dir.join(name), &image)?;
+            }
+        }
+
+        let p0 = "718.856 0 607.1928 0 0 718.856 185.2157 0 0 0 1 0";
+        let p1 = "718.856 0 607.1928 -386.1448 0 718.856 185.2157 0 0 0 1 0";
+        std::fs::write(
+            sequence_dir.join("calib.txt"),
+            format!("P0: {p0}\nP1: {p1}\nP2: {p0}\nP3: {p1}\nTr: {p0}\n"),
+        )?;
+        std::fs::write(
+            sequence_dir.join("times.txt"),
+            "0.000000e+00\n1.036973e-01\n",
+        )?;
+        std::fs::write(
+            tmp_dir.path().join("poses/00.txt"),
+            "1 0 0 0 0 1 0 0 0 0 1 0\n1 0 0 0.1 0 1 0 0 0 0 1 0.8\n",
+        )?;
+
+        let dataset = KittiOdometry::open(tmp_dir.path(), "00")?;
+        assert_eq!(dataset.len(), 2);
+        assert_eq!(dataset.intrinsic(0)?.cx, 607.1928);
+        assert!((dataset.baseline(1)? - 0.537165).abs() < 1e-6);
+        assert_eq!(
+            dataset.poses().map(|p| p[1].translation),
+            Some([0.1, 0.0, 0.8])
+        );
+        let ground_truth = dataset.ground_truth().expect("the ground truth");
+        assert_eq!(
+            ground_truth[1].timestamp,
+            Duration::from_secs_f64(0.1036973)
+        );
+
+        let (left, right) = dataset.read_gray_pair(1)?;
+        assert_eq!(left.size(), right.size());
+        assert!(dataset.read_gray_pair(2).is_err());
+        assert!(dataset.projection(4).is_err());
+
+        Ok(())
+    }
+}
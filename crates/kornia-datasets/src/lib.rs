@@ -0,0 +1,25 @@
+#![deny(missing_docs)]
+#![doc = env!("CARGO_PKG_DESCRIPTION")]
+
+/// COLMAP sparse reconstructions with their images.
+pub mod colmap;
+
+/// Error types of the dataset loaders.
+pub mod error;
+
+/// EuRoC MAV visual-inertial sequences.
+pub mod euroc;
+
+/// KITTI odometry stereo sequences.
+pub mod kitti;
+
+/// Timestamped poses of the ground truth trajectories.
+pub mod trajectory;
+
+/// TUM RGB-D sequences.
+pub mod tum;
+
+// Parsing of the text files of the datasets.
+mod text;
+
+pub use crate::error::DatasetError;
@@ -0,0 +1,86 @@
+use std::path::Path;
+use std::str::FromStr;
+
+use crate::error::DatasetError;
+
+// a line of a text file with its number, starting at 1
+pub(crate) struct Line {
+    pub number: usize,
+    pub content: String,
+}
+
+// the lines of a text file skipping the empty lines and the `#` comments
+pub(crate) fn read_lines(file_path: &Path) -> Result<Vec<Line>, DatasetError> {
+    // verify the file exists
+    if !file_path.exists() {
+        return Err(DatasetError::FileDoesNotExist(file_path.to_path_buf()));
+    }
+
+    let content = std::fs::read_to_string(file_path)?;
+    Ok(content
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty() && !line.trim_start().starts_with('#'))
+        .map(|(i, line)| Line {
+            number: i + 1,
+            content: line.trim().to_string(),
+        })
+        .collect())
+}
+
+pub(crate) fn parse_error(file_path: &Path, line: &Line, msg: impl Into<String>) -> DatasetError {
+    DatasetError::ParseError {
+        path: file_path.to_path_buf(),
+        line: line.number,
+        msg: msg.into(),
+    }
+}
+
+// parse the fields of a line, split by whitespace or by commas
+pub(crate) fn parse_fields<T: FromStr>(
+    file_path: &Path,
+    line: &Line,
+    min_fields: usize,
+) -> Result<Vec<T>, DatasetError> {
+    let fields = line
+        .content
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|field| !field.is_empty())
+        .map(|field| {
+            field
+                .parse::<T>()
+                .map_err(|_| parse_error(file_path, line, format!("invalid value {}", field)))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    if fields.len() < min_fields {
+        return Err(parse_error(
+            file_path,
+            line,
+            format!("expected {} values, found {}", min_fields, fields.len()),
+        ));
+    }
+
+    Ok(fields)
+}
+
+// split a line in a timestamp and a file name, e.g. `1305031102.175304 rgb/1305031102.175304.png`
+pub(crate) fn parse_timestamp_and_name<'a, T: FromStr>(
+    file_path: &Path,
+    line: &'a Line,
+) -> Result<(T, &'a str), DatasetError> {
+    let mut fields = line
+        .content
+        .splitn(2, |c: char| c == ',' || c.is_whitespace());
+    let (Some(timestamp), Some(name)) = (fields.next(), fields.next()) else {
+        return Err(parse_error(
+            file_path,
+            line,
+            "expected a timestamp and a file name",
+        ));
+    };
+    let timestamp = timestamp
+        .parse::<T>()
+        .map_err(|_| parse_error(file_path, line, format!("invalid timestamp {}", timestamp)))?;
+    Ok((timestamp, name.trim()))
+}
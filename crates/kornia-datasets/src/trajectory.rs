@@ -0,0 +1,61 @@
+use std::time::Duration;
+
+use kornia_3d::pose_graph::Pose;
+
+/// A pose of a trajectory at a timestamp.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StampedPose {
+    /// The timestamp of the pose.
+    pub timestamp: Duration,
+    /// The pose, mapping the points from the moving frame to the world frame.
+    pub pose: Pose,
+}
+
+/// Find the pose of a trajectory closest in time to a timestamp.
+///
+/// # Arguments
+///
+/// * `poses` - The poses sorted by timestamp.
+/// * `timestamp` - The timestamp to look up.
+/// * `max_difference` - The maximum difference between the timestamps.
+///
+/// # Returns
+///
+/// The closest pose, or `None` if no pose is within `max_difference`.
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+/// use kornia_3d::pose_graph::Pose;
+/// use kornia_datasets::trajectory::{nearest_pose, StampedPose};
+///
+/// let poses = [0, 100, 200].map(|ms| StampedPose {
+///     timestamp: Duration::from_millis(ms),
+///     pose: Pose::default(),
+/// });
+///
+/// let pose = nearest_pose(&poses, Duration::from_millis(90), Duration::from_millis(20));
+/// assert_eq!(pose.unwrap().timestamp, Duration::from_millis(100));
+/// assert!(nearest_pose(&poses, Duration::from_millis(50), Duration::from_millis(20)).is_none());
+/// ```
+pub fn nearest_pose(
+    poses: &[StampedPose],
+    timestamp: Duration,
+    max_difference: Duration,
+) -> Option<&StampedPose> {
+    let index = poses.partition_point(|pose| pose.timestamp < timestamp);
+    let before = index.checked_sub(1).and_then(|i| poses.get(i));
+    let after = poses.get(index);
+
+    let difference = |pose: &StampedPose| match pose.timestamp > timestamp {
+        true => pose.timestamp - timestamp,
+        false => timestamp - pose.timestamp,
+    };
+
+    [before, after]
+        .into_iter()
+        .flatten()
+        .min_by_key(|pose| difference(pose))
+        .filter(|pose| difference(pose) <= max_difference)
+}
@@ -0,0 +1,325 @@
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use kornia_3d::pose_graph::Pose;
+use kornia_imgproc::calibration::{distortion::PolynomialDistortion, CameraIntrinsic};
+use kornia_io::{
+    error::IoError,
+    functional::read_image_any_rgb8,
+    png::read_image_png_mono16,
+    rgbd::{DepthCamera, RgbdFrame},
+};
+
+use crate::error::DatasetError;
+use crate::text::{parse_error, parse_fields, parse_timestamp_and_name, read_lines, Line};
+use crate::trajectory::{nearest_pose, StampedPose};
+
+/// The maximum difference between the timestamps of the associated color and depth images,
+/// as in the `associate.py` tool of the dataset.
+pub const TUM_MAX_TIME_DIFFERENCE: Duration = Duration::from_millis(20);
+
+/// The scale to convert the depth values of the dataset to meters.
+pub const TUM_DEPTH_SCALE: f32 = 1.0 / 5000.0;
+
+/// A sequence of the TUM RGB-D dataset.
+///
+/// The directory of the sequence contains the `rgb.txt` and `depth.txt` lists of the
+/// timestamped images, and optionally the `groundtruth.txt` trajectory of the camera. The
+/// color and depth images are associated by their closest timestamps.
+///
+/// The intrinsics of the color camera are chosen from the name of the sequence, e.g.
+/// `rgbd_dataset_freiburg1_xyz`, otherwise the default intrinsics of the Kinect are used.
+pub struct TumRgbdDataset {
+    frames: Vec<(Duration, PathBuf, PathBuf)>,
+    ground_truth: Vec<StampedPose>,
+    intrinsics: CameraIntrinsic,
+    distortion: PolynomialDistortion,
+    index: usize,
+}
+
+// the intrinsics and the distortion of the color camera of the sequences
+fn camera_of_sequence(dir_path: &Path) -> (CameraIntrinsic, PolynomialDistortion) {
+    let name = dir_path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or_default();
+
+    let ([fx, fy, cx, cy], [k1, k2, p1, p2, k3]) = if name.contains("freiburg1") {
+        (
+            [517.3, 516.5, 318.6, 255.3],
+            [0.2624, -0.9531, -0.0054, 0.0026, 1.1633],
+        )
+    } else if name.contains("freiburg2") {
+        (
+            [520.9, 521.0, 325.1, 249.7],
+            [0.2312, -0.7849, -0.0033, -0.0001, 0.9172],
+        )
+    } else if name.contains("freiburg3") {
+        ([535.4, 539.2, 320.1, 247.6], [0.0; 5])
+    } else {
+        ([525.0, 525.0, 319.5, 239.5], [0.0; 5])
+    };
+
+    let intrinsics = CameraIntrinsic { fx, fy, cx, cy };
+    let distortion = PolynomialDistortion {
+        k1,
+        k2,
+        k3,
+        p1,
+        p2,
+        ..Default::default()
+    };
+    (intrinsics, distortion)
+}
+
+fn secs_to_duration(file_path: &Path, line: &Line, secs: f64) -> Result<Duration, DatasetError> {
+    Duration::try_from_secs_f64(secs)
+        .map_err(|_| parse_error(file_path, line, format!("invalid timestamp {}", secs)))
+}
+
+// the timestamped images of a `rgb.txt` or `depth.txt` list
+fn read_image_list(dir_path: &Path, name: &str) -> Result<Vec<(Duration, PathBuf)>, DatasetError> {
+    let file_path = dir_path.join(name);
+    read_lines(&file_path)?
+        .iter()
+        .map(|line| {
+            let (secs, image_name) = parse_timestamp_and_name::<f64>(&file_path, line)?;
+            Ok((
+                secs_to_duration(&file_path, line, secs)?,
+                dir_path.join(image_name),
+            ))
+        })
+        .collect()
+}
+
+/// Reads a trajectory in the TUM format.
+///
+/// Each line contains `timestamp tx ty tz qx qy qz qw`, the pose of the camera in the world
+/// frame, with the timestamp in seconds.
+///
+/// # Arguments
+///
+/// * `file_path` - The path to the trajectory file, e.g. `groundtruth.txt`.
+///
+/// # Returns
+///
+/// The poses sorted by timestamp.
+pub fn read_tum_trajectory(file_path: impl AsRef<Path>) -> Result<Vec<StampedPose>, DatasetError> {
+    let file_path = file_path.as_ref();
+    let mut poses = read_lines(file_path)?
+        .iter()
+        .map(|line| {
+            let v = parse_fields::<f64>(file_path, line, 8)?;
+            Ok(StampedPose {
+                timestamp: secs_to_duration(file_path, line, v[0])?,
+                pose: Pose::from_quaternion([v[7], v[4], v[5], v[6]], [v[1], v[2], v[3]]),
+            })
+        })
+        .collect::<Result<Vec<_>, DatasetError>>()?;
+
+    poses.sort_by_key(|pose| pose.timestamp);
+    Ok(poses)
+}
+
+impl TumRgbdDataset {
+    /// Opens a sequence of the TUM RGB-D dataset.
+    ///
+    /// # Arguments
+    ///
+    /// * `dir_path` - The path to the directory of the sequence.
+    pub fn open(dir_path: impl AsRef<Path>) -> Result<Self, DatasetError> {
+        let dir_path = dir_path.as_ref();
+        let color = read_image_list(dir_path, "rgb.txt")?;
+        let mut depth = read_image_list(dir_path, "depth.txt")?;
+        depth.sort_by_key(|(timestamp, _)| *timestamp);
+
+        // associate each color image to the closest depth image
+        let frames = color
+            .into_iter()
+            .filter_map(|(timestamp, color_path)| {
+                let index = depth.partition_point(|(t, _)| *t < timestamp);
+                let candidates = [index.checked_sub(1), Some(index)];
+                candidates
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|i| depth.get(i))
+                    .map(|(t, path)| {
+                        let difference = match *t > timestamp {
+                            true => *t - timestamp,
+                            false => timestamp - *t,
+                        };
+                        (difference, path)
+                    })
+                    .min_by_key(|(difference, _)| *difference)
+                    .filter(|(difference, _)| *difference <= TUM_MAX_TIME_DIFFERENCE)
+                    .map(|(_, depth_path)| (timestamp, color_path, depth_path.clone()))
+            })
+            .collect();
+
+        let ground_truth_path = dir_path.join("groundtruth.txt");
+        let ground_truth = match ground_truth_path.exists() {
+            true => read_tum_trajectory(&ground_truth_path)?,
+            false => Vec::new(),
+        };
+
+        let (intrinsics, distortion) = camera_of_sequence(dir_path);
+
+        Ok(Self {
+            frames,
+            ground_truth,
+            intrinsics,
+            distortion,
+            index: 0,
+        })
+    }
+
+    /// Returns the number of associated color and depth frames.
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    /// Returns true if the sequence has no frames.
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    /// Restarts the sequence from the first frame.
+    pub fn reset(&mut self) {
+        self.index = 0;
+    }
+
+    /// Returns the distortion of the color camera.
+    pub fn distortion(&self) -> &PolynomialDistortion {
+        &self.distortion
+    }
+
+    /// Returns the ground truth trajectory of the camera, empty if not available.
+    pub fn ground_truth(&self) -> &[StampedPose] {
+        &self.ground_truth
+    }
+
+    /// Returns the timestamp of a frame, the timestamp of its color image.
+    pub fn timestamp(&self, index: usize) -> Option<Duration> {
+        self.frames.get(index).map(|(timestamp, _, _)| *timestamp)
+    }
+
+    /// Returns the ground truth pose closest to a frame.
+    ///
+    /// # Arguments
+    ///
+    /// * `index` - The index of the frame.
+    ///
+    /// # Returns
+    ///
+    /// The pose of the camera, or `None` if no pose is within 20 ms of the frame.
+    pub fn frame_ground_truth(&self, index: usize) -> Option<&StampedPose> {
+        let timestamp = self.timestamp(index)?;
+        nearest_pose(&self.ground_truth, timestamp, TUM_MAX_TIME_DIFFERENCE)
+    }
+
+    /// Reads a frame of the sequence.
+    ///
+    /// # Arguments
+    ///
+    /// * `index` - The index of the frame.
+    ///
+    /// # Returns
+    ///
+    /// The color image, the depth image in units of 1/5000 m and the timestamp.
+    pub fn read_frame(&self, index: usize) -> Result<RgbdFrame, DatasetError> {
+        if index >= self.len() {
+            return Err(DatasetError::InvalidFrameIndex(index, self.len()));
+        }
+        Ok(self.load_frame(index)?)
+    }
+
+    fn load_frame(&self, index: usize) -> Result<RgbdFrame, IoError> {
+        let (timestamp, color_path, depth_path) = &self.frames[index];
+        Ok(RgbdFrame {
+            color: read_image_any_rgb8(color_path)?,
+            depth: read_image_png_mono16(depth_path)?,
+            timestamp: *timestamp,
+        })
+    }
+}
+
+impl DepthCamera for TumRgbdDataset {
+    fn intrinsics(&self) -> &CameraIntrinsic {
+        &self.intrinsics
+    }
+
+    fn depth_scale(&self) -> f32 {
+        TUM_DEPTH_SCALE
+    }
+
+    fn grab(&mut self) -> Result<Option<RgbdFrame>, IoError> {
+        if self.index >= self.len() {
+            return Ok(None);
+        }
+
+        self.index += 1;
+        self.load_frame(self.index - 1).map(Some)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use kornia_image::Image;
+    use kornia_io::png::{write_image_png_gray16, write_image_png_rgb8};
+
+    #[test]
+    fn test_tum_rgbd_dataset() -> Result<(), Box<dyn std::error::Error>> {
+        let tmp_dir = tempfile::tempdir()?;
+        let dir_path = tmp_dir.path().join("rgbd_dataset_freiburg1_xyz");
+        std::fs::create_dir_all(dir_path.join("rgb"))?;
+        std::fs::create_dir_all(dir_path.join("depth"))?;
+
+        let color = Image::<u8, 3>::from_size_val([4, 3].into(), 100)?;
+        let depth = Image::<u16, 1>::from_size_val([4, 3].into(), 5000)?;
+        for t in ["0.000", "0.033", "0.066"] {
+            write_image_png_rgb8(dir_path.join(format!("rgb/{t}.png")), &color)?;
+        }
+        for t in ["0.005", "0.040", "0.200"] {
+            write_image_png_gray16(dir_path.join(format!("depth/{t}.png")), &depth)?;
+        }
+
+        std::fs::write(
+            dir_path.join("rgb.txt"),
+            "# color images\n# file: 'rgbd_dataset_freiburg1_xyz.bag'\n# timestamp filename\n\
+             0.000 rgb/0.000.png\n0.033 rgb/0.033.png\n0.066 rgb/0.066.png\n",
+        )?;
+        std::fs::write(
+            dir_path.join("depth.txt"),
+            "# depth maps\n0.005 depth/0.005.png\n0.040 depth/0.040.png\n0.200 depth/0.200.png\n",
+        )?;
+        std::fs::write(
+            dir_path.join("groundtruth.txt"),
+            "# timestamp tx ty tz qx qy qz qw\n0.001 1.0 2.0 3.0 0.0 0.0 0.0 1.0\n\
+             0.035 1.5 2.0 3.0 0.0 0.0 0.0 1.0\n",
+        )?;
+
+        let mut dataset = TumRgbdDataset::open(&dir_path)?;
+        // the last color image has no depth image within 20 ms
+        assert_eq!(dataset.len(), 2);
+        assert_eq!(dataset.intrinsics().fx, 517.3);
+        assert_eq!(dataset.distortion().k3, 1.1633);
+        assert_eq!(dataset.ground_truth().len(), 2);
+        let pose = dataset.frame_ground_truth(1).expect("a ground truth pose");
+        assert_eq!(pose.pose.translation, [1.5, 2.0, 3.0]);
+
+        let frame = dataset.read_frame(1)?;
+        assert_eq!(frame.timestamp, Duration::from_millis(33));
+        assert_eq!(frame.depth.get_pixel(0, 0, 0)?, &5000);
+        assert!(dataset.read_frame(2).is_err());
+
+        let mut count = 0;
+        while let Some(frame) = dataset.grab()? {
+            assert_eq!(frame.color.size(), frame.depth.size());
+            count += 1;
+        }
+        assert_eq!(count, 2);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,116 @@
+use std::sync::Arc;
+
+use kornia_imgproc::backend::{
+    Backend, BackendError, Dispatcher, GaussianBlurFn, WarpPerspectiveFn,
+};
+
+use crate::{context::GpuContext, error::GpuError, filter, image::GpuImage, warp};
+
+impl From<GpuError> for BackendError {
+    fn from(error: GpuError) -> Self {
+        match error {
+            GpuError::ImageError(error) => BackendError::ImageError(error),
+            error => BackendError::Implementation(Box::new(error)),
+        }
+    }
+}
+
+/// Register the GPU gaussian blur in a dispatcher.
+///
+/// The implementation uploads the source image, blurs it and downloads the result.
+///
+/// # Arguments
+///
+/// * `ctx` - The GPU context to run the blur.
+/// * `dispatcher` - The dispatcher, e.g. from [`Dispatcher::gaussian_blur`].
+/// * `min_pixels` - The minimum number of pixels of the images to run on the GPU.
+pub fn register_gaussian_blur<const C: usize>(
+    ctx: &GpuContext,
+    dispatcher: &mut Dispatcher<GaussianBlurFn<C>>,
+    min_pixels: usize,
+) {
+    let ctx = ctx.clone();
+    dispatcher.register(
+        Backend::Gpu,
+        min_pixels,
+        Arc::new(move |src, dst, kernel_size, sigma| {
+            let gpu_src = GpuImage::upload(&ctx, src);
+            let mut gpu_dst = GpuImage::zeros(&ctx, dst.size());
+            filter::gaussian_blur(&gpu_src, &mut gpu_dst, kernel_size, sigma)?;
+            *dst = gpu_dst.download()?;
+            Ok(())
+        }),
+    );
+}
+
+/// Register the GPU perspective warp in a dispatcher.
+///
+/// The implementation uploads both images, since the pixels of the destination mapped
+/// outside of the source are left untouched, warps and downloads the result.
+///
+/// # Arguments
+///
+/// * `ctx` - The GPU context to run the warp.
+/// * `dispatcher` - The dispatcher, e.g. from [`Dispatcher::warp_perspective`].
+/// * `min_pixels` - The minimum number of pixels of the images to run on the GPU.
+pub fn register_warp_perspective<const C: usize>(
+    ctx: &GpuContext,
+    dispatcher: &mut Dispatcher<WarpPerspectiveFn<C>>,
+    min_pixels: usize,
+) {
+    let ctx = ctx.clone();
+    dispatcher.register(
+        Backend::Gpu,
+        min_pixels,
+        Arc::new(move |src, dst, m, interpolation| {
+            let gpu_src = GpuImage::upload(&ctx, src);
+            let mut gpu_dst = GpuImage::upload(&ctx, dst);
+            warp::warp_perspective(&gpu_src, &mut gpu_dst, m, interpolation)?;
+            *dst = gpu_dst.download()?;
+            Ok(())
+        }),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::context::test_context;
+    use approx::assert_relative_eq;
+    use kornia_image::Image;
+    use kornia_imgproc::interpolation::InterpolationMode;
+
+    #[test]
+    fn test_register_gpu() -> Result<(), BackendError> {
+        let Some(ctx) = test_context() else {
+            return Ok(());
+        };
+
+        let image =
+            Image::<f32, 1>::new([16, 16].into(), (0..256).map(|x| (x % 7) as f32).collect())?;
+
+        let cpu = Dispatcher::<GaussianBlurFn<1>>::gaussian_blur();
+        let mut blur = cpu.clone();
+        register_gaussian_blur(&ctx, &mut blur, 100);
+        assert_eq!(blur.select(image.size()), Some(Backend::Gpu));
+        assert_eq!(blur.select([8, 8].into()), Some(Backend::Cpu));
+
+        let mut expected = Image::from_size_val(image.size(), 0.0)?;
+        let mut dst = Image::from_size_val(image.size(), 0.0)?;
+        cpu.run(&image, &mut expected, (5, 5), (1.0, 1.0))?;
+        blur.run(&image, &mut dst, (5, 5), (1.0, 1.0))?;
+        for (a, b) in dst.as_slice().iter().zip(expected.as_slice()) {
+            assert_relative_eq!(a, b, epsilon = 1e-4);
+        }
+
+        let mut warp = Dispatcher::<WarpPerspectiveFn<1>>::warp_perspective();
+        register_warp_perspective(&ctx, &mut warp, 0);
+        let m = [1.0, 0.0, 3.5, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0];
+        let mut dst = Image::from_size_val(image.size(), -1.0)?;
+        warp.run(&image, &mut dst, &m, InterpolationMode::Bilinear)?;
+        assert_eq!(dst.as_slice()[0], -1.0);
+        assert_relative_eq!(dst.as_slice()[5], 1.5, epsilon = 1e-5);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,121 @@
+use crate::{
+    context::Shader,
+    error::GpuError,
+    image::{check_same_size, GpuImage},
+};
+
+const SHADER: Shader = Shader {
+    label: "color",
+    source: include_str!("shaders/color.wgsl"),
+};
+
+#[repr(C)]
+#[derive(Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+struct Params {
+    cols: u32,
+    rows: u32,
+    _pad: [u32; 2],
+}
+
+// run a pixel-wise color conversion
+fn convert<const C1: usize, const C2: usize>(
+    entry_point: &'static str,
+    src: &GpuImage<C1>,
+    dst: &mut GpuImage<C2>,
+) -> Result<(), GpuError> {
+    check_same_size(src.size(), dst.size())?;
+
+    let params = Params {
+        cols: src.cols() as u32,
+        rows: src.rows() as u32,
+        _pad: [0; 2],
+    };
+    src.context().dispatch(
+        &SHADER,
+        entry_point,
+        &params,
+        &[src.buffer(), dst.buffer()],
+        [src.cols(), src.rows()],
+    );
+
+    Ok(())
+}
+
+/// Convert an RGB image to grayscale on the GPU.
+///
+/// The grayscale value is computed as `Y = 0.299 * R + 0.587 * G + 0.114 * B`, as
+/// [`kornia_imgproc::color::gray_from_rgb`].
+///
+/// # Arguments
+///
+/// * `src` - The input RGB image.
+/// * `dst` - The output grayscale image with the same size.
+pub fn gray_from_rgb(src: &GpuImage<3>, dst: &mut GpuImage<1>) -> Result<(), GpuError> {
+    convert("gray_from_rgb", src, dst)
+}
+
+/// Convert a grayscale image to an RGB image on the GPU by replicating the channel.
+///
+/// # Arguments
+///
+/// * `src` - The input grayscale image.
+/// * `dst` - The output RGB image with the same size.
+pub fn rgb_from_gray(src: &GpuImage<1>, dst: &mut GpuImage<3>) -> Result<(), GpuError> {
+    convert("rgb_from_gray", src, dst)
+}
+
+/// Convert an RGB image to BGR on the GPU by swapping the first and last channels.
+///
+/// # Arguments
+///
+/// * `src` - The input RGB image.
+/// * `dst` - The output BGR image with the same size.
+pub fn bgr_from_rgb(src: &GpuImage<3>, dst: &mut GpuImage<3>) -> Result<(), GpuError> {
+    convert("bgr_from_rgb", src, dst)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::context::test_context;
+    use approx::assert_relative_eq;
+    use kornia_image::Image;
+
+    #[test]
+    fn test_color_conversions() -> Result<(), GpuError> {
+        let Some(ctx) = test_context() else {
+            return Ok(());
+        };
+
+        let rgb = Image::<f32, 3>::new(
+            [17, 5].into(),
+            (0..17 * 5 * 3).map(|x| (x % 13) as f32 / 13.0).collect(),
+        )?;
+        let src = GpuImage::upload(&ctx, &rgb);
+
+        let mut expected = Image::<f32, 1>::from_size_val(rgb.size(), 0.0)?;
+        kornia_imgproc::color::gray_from_rgb(&rgb, &mut expected)?;
+        let mut gray = GpuImage::zeros(&ctx, rgb.size());
+        gray_from_rgb(&src, &mut gray)?;
+        for (a, b) in gray.download()?.as_slice().iter().zip(expected.as_slice()) {
+            assert_relative_eq!(a, b, epsilon = 1e-6);
+        }
+
+        let mut rgb_gray = GpuImage::zeros(&ctx, rgb.size());
+        rgb_from_gray(&gray, &mut rgb_gray)?;
+        let rgb_gray = rgb_gray.download()?;
+        assert_eq!(rgb_gray.as_slice()[3..6], [rgb_gray.as_slice()[3]; 3]);
+
+        let mut bgr = GpuImage::zeros(&ctx, rgb.size());
+        bgr_from_rgb(&src, &mut bgr)?;
+        assert_eq!(
+            bgr.download()?.as_slice()[..3],
+            [rgb.as_slice()[2], rgb.as_slice()[1], rgb.as_slice()[0]]
+        );
+
+        let mut small = GpuImage::zeros(&ctx, [2, 2].into());
+        assert!(gray_from_rgb(&src, &mut small).is_err());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,239 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use wgpu::util::DeviceExt;
+
+use crate::error::GpuError;
+
+// the size of the workgroups of the image shaders
+const WORKGROUP_SIZE: u32 = 16;
+
+/// A WGSL compute shader module.
+pub(crate) struct Shader {
+    pub label: &'static str,
+    pub source: &'static str,
+}
+
+/// The GPU device used to store the images and run the operations.
+///
+/// The context is cheap to clone and caches the compiled compute pipelines.
+///
+/// # Example
+///
+/// ```no_run
+/// use kornia_gpu::{GpuContext, GpuImage};
+/// use kornia_image::Image;
+///
+/// let ctx = GpuContext::new().unwrap();
+/// let image = Image::<f32, 3>::from_size_val([640, 480].into(), 0.5).unwrap();
+///
+/// let src = GpuImage::upload(&ctx, &image);
+/// let mut dst = GpuImage::<1>::zeros(&ctx, image.size());
+/// kornia_gpu::color::gray_from_rgb(&src, &mut dst).unwrap();
+///
+/// let gray = dst.download().unwrap();
+/// ```
+#[derive(Clone)]
+pub struct GpuContext {
+    pub(crate) device: wgpu::Device,
+    pub(crate) queue: wgpu::Queue,
+    pipelines: Arc<Mutex<HashMap<(&'static str, &'static str), wgpu::ComputePipeline>>>,
+}
+
+impl GpuContext {
+    /// Create a context on the default GPU adapter of the system.
+    ///
+    /// # Returns
+    ///
+    /// The context or an error if no adapter or device is available.
+    pub fn new() -> Result<Self, GpuError> {
+        pollster::block_on(Self::new_async())
+    }
+
+    /// Create a context on the default GPU adapter of the system asynchronously.
+    pub async fn new_async() -> Result<Self, GpuError> {
+        let instance = wgpu::Instance::default();
+        let adapter = instance
+            .request_adapter(&wgpu::RequestAdapterOptions {
+                power_preference: wgpu::PowerPreference::HighPerformance,
+                ..Default::default()
+            })
+            .await
+            .ok_or(GpuError::NoAdapter)?;
+
+        let (device, queue) = adapter
+            .request_device(
+                &wgpu::DeviceDescriptor {
+                    label: Some("kornia-gpu"),
+                    required_features: wgpu::Features::empty(),
+                    required_limits: adapter.limits(),
+                    memory_hints: wgpu::MemoryHints::Performance,
+                },
+                None,
+            )
+            .await?;
+
+        Ok(Self::from_device(device, queue))
+    }
+
+    /// Create a context from an existing device, e.g. shared with a renderer.
+    ///
+    /// # Arguments
+    ///
+    /// * `device` - The device to create the buffers and pipelines.
+    /// * `queue` - The queue of the device to submit the operations.
+    pub fn from_device(device: wgpu::Device, queue: wgpu::Queue) -> Self {
+        Self {
+            device,
+            queue,
+            pipelines: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// The device of the context.
+    pub fn device(&self) -> &wgpu::Device {
+        &self.device
+    }
+
+    /// The queue of the context.
+    pub fn queue(&self) -> &wgpu::Queue {
+        &self.queue
+    }
+
+    /// Wait until all the submitted operations are finished.
+    pub fn synchronize(&self) {
+        self.device.poll(wgpu::Maintain::Wait);
+    }
+
+    // create a zero initialized storage buffer with `len` floats
+    pub(crate) fn create_buffer(&self, len: usize) -> wgpu::Buffer {
+        self.device.create_buffer(&wgpu::BufferDescriptor {
+            label: None,
+            // empty bindings are not allowed
+            size: (len.max(1) * std::mem::size_of::<f32>()) as u64,
+            usage: wgpu::BufferUsages::STORAGE
+                | wgpu::BufferUsages::COPY_SRC
+                | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        })
+    }
+
+    // create a storage buffer initialized with the data
+    pub(crate) fn create_buffer_init(&self, data: &[f32]) -> wgpu::Buffer {
+        if data.is_empty() {
+            return self.create_buffer(0);
+        }
+        self.device
+            .create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: None,
+                contents: bytemuck::cast_slice(data),
+                usage: wgpu::BufferUsages::STORAGE
+                    | wgpu::BufferUsages::COPY_SRC
+                    | wgpu::BufferUsages::COPY_DST,
+            })
+    }
+
+    fn pipeline(&self, shader: &Shader, entry_point: &'static str) -> wgpu::ComputePipeline {
+        let mut pipelines = self
+            .pipelines
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        pipelines
+            .entry((shader.label, entry_point))
+            .or_insert_with(|| {
+                let module = self
+                    .device
+                    .create_shader_module(wgpu::ShaderModuleDescriptor {
+                        label: Some(shader.label),
+                        source: wgpu::ShaderSource::Wgsl(shader.source.into()),
+                    });
+                self.device
+                    .create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+                        label: Some(entry_point),
+                        layout: None,
+                        module: &module,
+                        entry_point: Some(entry_point),
+                        compilation_options: Default::default(),
+                        cache: None,
+                    })
+            })
+            .clone()
+    }
+
+    /// Run a compute shader over the pixels of an image.
+    ///
+    /// The parameters are bound as a uniform at binding 0 and the buffers as storage
+    /// buffers from binding 1, in order. The shader uses workgroups of 16x16 pixels.
+    ///
+    /// # Arguments
+    ///
+    /// * `shader` - The shader module.
+    /// * `entry_point` - The name of the compute function.
+    /// * `params` - The uniform parameters of the shader.
+    /// * `buffers` - The storage buffers of the shader.
+    /// * `size` - The number of columns and rows to process.
+    pub(crate) fn dispatch<P: bytemuck::Pod>(
+        &self,
+        shader: &Shader,
+        entry_point: &'static str,
+        params: &P,
+        buffers: &[&wgpu::Buffer],
+        size: [usize; 2],
+    ) {
+        if size[0] == 0 || size[1] == 0 {
+            return;
+        }
+
+        let pipeline = self.pipeline(shader, entry_point);
+
+        let params = self
+            .device
+            .create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: None,
+                contents: bytemuck::bytes_of(params),
+                usage: wgpu::BufferUsages::UNIFORM,
+            });
+
+        let entries = std::iter::once(params.as_entire_binding())
+            .chain(buffers.iter().map(|buffer| buffer.as_entire_binding()))
+            .enumerate()
+            .map(|(binding, resource)| wgpu::BindGroupEntry {
+                binding: binding as u32,
+                resource,
+            })
+            .collect::<Vec<_>>();
+
+        let bind_group = self.device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: None,
+            layout: &pipeline.get_bind_group_layout(0),
+            entries: &entries,
+        });
+
+        let mut encoder = self
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
+        {
+            let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+                label: Some(entry_point),
+                timestamp_writes: None,
+            });
+            pass.set_pipeline(&pipeline);
+            pass.set_bind_group(0, &bind_group, &[]);
+            pass.dispatch_workgroups(
+                (size[0] as u32).div_ceil(WORKGROUP_SIZE),
+                (size[1] as u32).div_ceil(WORKGROUP_SIZE),
+                1,
+            );
+        }
+        self.queue.submit(Some(encoder.finish()));
+    }
+}
+
+// the context of the tests or `None` on machines without a GPU adapter
+#[cfg(test)]
+pub(crate) fn test_context() -> Option<GpuContext> {
+    GpuContext::new().ok()
+}
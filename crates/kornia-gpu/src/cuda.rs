@@ -0,0 +1,559 @@
+use std::sync::Arc;
+
+use cudarc::driver::{
+    CudaContext, CudaModule, CudaSlice, CudaStream, DeviceRepr, LaunchConfig, PinnedHostSlice,
+    PushKernelArg, ValidAsZeroBits,
+};
+use kornia_image::Image;
+use kornia_imgproc::{
+    filter::{kernels::GradsMode, spatial_gradient_float},
+    pyramid::pyrdown,
+    video::{LkParams, LkResidual, TrackedPoint},
+};
+
+use crate::{error::GpuError, image::check_same_size};
+
+// the size of the thread blocks of the image kernels
+const BLOCK_SIZE: u32 = 16;
+
+// the number of threads of the blocks of the point kernels
+const POINTS_BLOCK_SIZE: u32 = 64;
+
+// the device buffers and the page-locked host buffers of an image size
+struct Buffers {
+    len: usize,
+    host: PinnedHostSlice<f32>,
+    host_mask: PinnedHostSlice<u8>,
+    src: CudaSlice<f32>,
+    dst: CudaSlice<f32>,
+    moments: CudaSlice<f32>,
+    mask: CudaSlice<u8>,
+}
+
+// the device buffers of the concatenated levels of the pyramids of an image size
+struct PyramidBuffers {
+    len: usize,
+    host: PinnedHostSlice<f32>,
+    prev: CudaSlice<f32>,
+    next: CudaSlice<f32>,
+    grad_x: CudaSlice<f32>,
+    grad_y: CudaSlice<f32>,
+}
+
+/// The CUDA kernels of the feature responses and the point tracking.
+///
+/// The kernels run on a dedicated stream and the transfers go through page-locked host
+/// buffers, which are reused between calls with images of the same size. The kernels are
+/// compiled at runtime with NVRTC, so the CUDA driver and NVRTC libraries are only needed
+/// on the machine running the program.
+pub struct CudaFeatures {
+    stream: Arc<CudaStream>,
+    module: Arc<CudaModule>,
+    flow_module: Arc<CudaModule>,
+    buffers: Option<Buffers>,
+    pyramids: Option<PyramidBuffers>,
+}
+
+impl CudaFeatures {
+    /// Create the kernels on a CUDA device.
+    ///
+    /// # Arguments
+    ///
+    /// * `ordinal` - The index of the CUDA device.
+    ///
+    /// # Returns
+    ///
+    /// The kernels or an error if CUDA is not available.
+    pub fn new(ordinal: usize) -> Result<Self, GpuError> {
+        // SAFETY: only looks up the shared libraries
+        let available = unsafe {
+            cudarc::driver::sys::is_culib_present() && cudarc::nvrtc::sys::is_culib_present()
+        };
+        if !available {
+            return Err(GpuError::CudaUnavailable);
+        }
+
+        let ctx = CudaContext::new(ordinal)?;
+        let stream = ctx.new_stream()?;
+        let ptx = cudarc::nvrtc::compile_ptx(include_str!("kernels/features.cu"))?;
+        let module = ctx.load_module(ptx)?;
+        let ptx = cudarc::nvrtc::compile_ptx(include_str!("kernels/optical_flow.cu"))?;
+        let flow_module = ctx.load_module(ptx)?;
+
+        Ok(Self {
+            stream,
+            module,
+            flow_module,
+            buffers: None,
+            pyramids: None,
+        })
+    }
+
+    // the buffers for images with `len` pixels, reallocated when the size changes
+    fn buffers(&mut self, len: usize) -> Result<&mut Buffers, GpuError> {
+        if !matches!(&self.buffers, Some(buffers) if buffers.len == len) {
+            let ctx = self.stream.context();
+            self.buffers = Some(Buffers {
+                len,
+                // SAFETY: the host buffers are always written before being read
+                host: unsafe { ctx.alloc_pinned_with_flags(len, 0)? },
+                host_mask: unsafe { ctx.alloc_pinned_with_flags(len, 0)? },
+                src: self.stream.alloc_zeros(len)?,
+                dst: self.stream.alloc_zeros(len)?,
+                moments: self.stream.alloc_zeros(3 * len)?,
+                mask: self.stream.alloc_zeros(len)?,
+            });
+        }
+        // SAFETY: the buffers were just allocated
+        Ok(self.buffers.as_mut().unwrap())
+    }
+
+    // the buffers for pyramids with `len` pixels, reallocated when the size changes
+    fn pyramid_buffers(&mut self, len: usize) -> Result<&mut PyramidBuffers, GpuError> {
+        if !matches!(&self.pyramids, Some(buffers) if buffers.len == len) {
+            let ctx = self.stream.context();
+            self.pyramids = Some(PyramidBuffers {
+                len,
+                // SAFETY: the host buffer is always written before being read
+                host: unsafe { ctx.alloc_pinned_with_flags(len, 0)? },
+                prev: self.stream.alloc_zeros(len)?,
+                next: self.stream.alloc_zeros(len)?,
+                grad_x: self.stream.alloc_zeros(len)?,
+                grad_y: self.stream.alloc_zeros(len)?,
+            });
+        }
+        // SAFETY: the buffers were just allocated
+        Ok(self.pyramids.as_mut().unwrap())
+    }
+
+    /// Compute the Hessian response of an image with CUDA.
+    ///
+    /// The result matches [`kornia_imgproc::features::hessian_response`].
+    ///
+    /// # Arguments
+    ///
+    /// * `src` - The source image with shape (H, W).
+    /// * `dst` - The destination image with shape (H, W).
+    pub fn hessian_response(
+        &mut self,
+        src: &Image<f32, 1>,
+        dst: &mut Image<f32, 1>,
+    ) -> Result<(), GpuError> {
+        check_same_size(src.size(), dst.size())?;
+        if src.as_slice().is_empty() {
+            return Ok(());
+        }
+
+        let (cols, rows) = (src.cols() as i32, src.rows() as i32);
+        let function = self.module.load_function("hessian_response")?;
+        let stream = self.stream.clone();
+        let buffers = self.buffers(src.as_slice().len())?;
+
+        upload(&stream, src.as_slice(), &mut buffers.host, &mut buffers.src)?;
+        // keep the border pixels of the destination
+        upload(&stream, dst.as_slice(), &mut buffers.host, &mut buffers.dst)?;
+
+        let mut launch = stream.launch_builder(&function);
+        launch
+            .arg(&buffers.src)
+            .arg(&mut buffers.dst)
+            .arg(&cols)
+            .arg(&rows);
+        // SAFETY: the arguments match the kernel and the buffers hold all the pixels
+        unsafe { launch.launch(launch_config(src.cols(), src.rows())) }?;
+
+        let result = download(&stream, &buffers.dst, &mut buffers.host)?;
+        dst.as_slice_mut().copy_from_slice(result);
+
+        Ok(())
+    }
+
+    /// Compute the Shi-Tomasi response of an image with CUDA.
+    ///
+    /// The result matches [`kornia_imgproc::features::gftt_response`].
+    ///
+    /// # Arguments
+    ///
+    /// * `src` - The source image with shape (H, W).
+    /// * `dst` - The destination image with shape (H, W).
+    pub fn gftt_response(
+        &mut self,
+        src: &Image<f32, 1>,
+        dst: &mut Image<f32, 1>,
+    ) -> Result<(), GpuError> {
+        check_same_size(src.size(), dst.size())?;
+        if src.as_slice().is_empty() {
+            return Ok(());
+        }
+
+        let (cols, rows) = (src.cols() as i32, src.rows() as i32);
+        let gradients = self.module.load_function("gradient_moments")?;
+        let response = self.module.load_function("gftt_response")?;
+        let stream = self.stream.clone();
+        let buffers = self.buffers(src.as_slice().len())?;
+        let config = launch_config(src.cols(), src.rows());
+
+        upload(&stream, src.as_slice(), &mut buffers.host, &mut buffers.src)?;
+        // keep the border pixels of the destination
+        upload(&stream, dst.as_slice(), &mut buffers.host, &mut buffers.dst)?;
+
+        let mut launch = stream.launch_builder(&gradients);
+        launch
+            .arg(&buffers.src)
+            .arg(&mut buffers.moments)
+            .arg(&cols)
+            .arg(&rows);
+        // SAFETY: the arguments match the kernel and the buffers hold all the pixels
+        unsafe { launch.launch(config) }?;
+
+        let mut launch = stream.launch_builder(&response);
+        launch
+            .arg(&buffers.moments)
+            .arg(&mut buffers.dst)
+            .arg(&cols)
+            .arg(&rows);
+        // SAFETY: the arguments match the kernel and the buffers hold all the pixels
+        unsafe { launch.launch(config) }?;
+
+        let result = download(&stream, &buffers.dst, &mut buffers.host)?;
+        dst.as_slice_mut().copy_from_slice(result);
+
+        Ok(())
+    }
+
+    /// Find the local maxima of a response image with CUDA.
+    ///
+    /// The result matches [`kornia_imgproc::features::non_max_suppression`].
+    ///
+    /// # Arguments
+    ///
+    /// * `src` - The response image.
+    /// * `threshold` - The minimum response of a local maximum.
+    ///
+    /// # Returns
+    ///
+    /// The coordinates `[x, y]` of the local maxima in row-major order.
+    pub fn non_max_suppression(
+        &mut self,
+        src: &Image<f32, 1>,
+        threshold: f32,
+    ) -> Result<Vec<[i32; 2]>, GpuError> {
+        if src.as_slice().is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let (cols, rows) = (src.cols() as i32, src.rows() as i32);
+        let function = self.module.load_function("non_max_suppression")?;
+        let stream = self.stream.clone();
+        let buffers = self.buffers(src.as_slice().len())?;
+
+        upload(&stream, src.as_slice(), &mut buffers.host, &mut buffers.src)?;
+
+        let mut launch = stream.launch_builder(&function);
+        launch
+            .arg(&buffers.src)
+            .arg(&mut buffers.mask)
+            .arg(&cols)
+            .arg(&rows)
+            .arg(&threshold);
+        // SAFETY: the arguments match the kernel and the buffers hold all the pixels
+        unsafe { launch.launch(launch_config(src.cols(), src.rows())) }?;
+
+        let mask = download(&stream, &buffers.mask, &mut buffers.host_mask)?;
+        let keypoints = mask
+            .iter()
+            .enumerate()
+            .filter(|(_, &m)| m != 0)
+            .map(|(i, _)| [(i % src.cols()) as i32, (i / src.cols()) as i32])
+            .collect();
+
+        Ok(keypoints)
+    }
+
+    /// Track points between two images with the pyramidal Lucas-Kanade optical flow on CUDA.
+    ///
+    /// The result matches [`kornia_imgproc::video::track_points_lk`].
+    ///
+    /// # Arguments
+    ///
+    /// * `prev` - The previous image with shape (H, W).
+    /// * `next` - The next image with shape (H, W).
+    /// * `prev_pts` - The positions `[x, y]` of the points in the previous image.
+    /// * `params` - The parameters of the tracker.
+    ///
+    /// # Returns
+    ///
+    /// The tracked points, in the order of `prev_pts`.
+    pub fn track_points_lk(
+        &mut self,
+        prev: &Image<f32, 1>,
+        next: &Image<f32, 1>,
+        prev_pts: &[[f32; 2]],
+        params: &LkParams,
+    ) -> Result<Vec<TrackedPoint>, GpuError> {
+        let points = prev_pts.iter().map(|&p| (p, p)).collect::<Vec<_>>();
+        self.track_points_lk_with_guesses(prev, next, &points, params)
+    }
+
+    /// Track points between two images from initial guesses of their next positions on CUDA.
+    ///
+    /// The pyramids are built on the host and every point is tracked by a CUDA thread. The
+    /// result matches [`kornia_imgproc::video::track_points_lk_with_guesses`].
+    ///
+    /// # Arguments
+    ///
+    /// * `prev` - The previous image with shape (H, W).
+    /// * `next` - The next image with shape (H, W).
+    /// * `points` - The positions `[x, y]` of the points in the previous image and their
+    ///   predicted positions in the next image.
+    /// * `params` - The parameters of the tracker.
+    ///
+    /// # Returns
+    ///
+    /// The tracked points, in the order of `points`.
+    pub fn track_points_lk_with_guesses(
+        &mut self,
+        prev: &Image<f32, 1>,
+        next: &Image<f32, 1>,
+        points: &[([f32; 2], [f32; 2])],
+        params: &LkParams,
+    ) -> Result<Vec<TrackedPoint>, GpuError> {
+        check_same_size(prev.size(), next.size())?;
+        if points.is_empty() {
+            return Ok(Vec::new());
+        }
+        if prev.as_slice().is_empty() {
+            let lost = points.iter().map(|&(_, guess)| TrackedPoint {
+                position: guess,
+                tracked: false,
+                error: f32::INFINITY,
+            });
+            return Ok(lost.collect());
+        }
+
+        let prev_levels = build_pyramid(prev, params.num_levels)?;
+        let next_levels = build_pyramid(next, params.num_levels)?;
+        let (mut grad_x, mut grad_y) = (Vec::new(), Vec::new());
+        for level in &prev_levels {
+            let mut dx = Image::from_size_val(level.size(), 0.0)?;
+            let mut dy = Image::from_size_val(level.size(), 0.0)?;
+            spatial_gradient_float(level, &mut dx, &mut dy, GradsMode::Scharr)?;
+            grad_x.extend_from_slice(dx.as_slice());
+            grad_y.extend_from_slice(dy.as_slice());
+        }
+
+        // the offset, width and height of every level in the concatenated levels
+        let mut levels = Vec::with_capacity(3 * prev_levels.len());
+        let mut offset = 0;
+        for level in &prev_levels {
+            levels.extend([offset as i32, level.cols() as i32, level.rows() as i32]);
+            offset += level.as_slice().len();
+        }
+        let concat = |images: &[Image<f32, 1>]| {
+            images
+                .iter()
+                .flat_map(|image| image.as_slice())
+                .copied()
+                .collect::<Vec<_>>()
+        };
+        let (prev_data, next_data) = (concat(&prev_levels), concat(&next_levels));
+
+        let function = self.flow_module.load_function("track_points_lk")?;
+        let stream = self.stream.clone();
+        let buffers = self.pyramid_buffers(offset)?;
+        upload(&stream, &prev_data, &mut buffers.host, &mut buffers.prev)?;
+        upload(&stream, &next_data, &mut buffers.host, &mut buffers.next)?;
+        upload(&stream, &grad_x, &mut buffers.host, &mut buffers.grad_x)?;
+        upload(&stream, &grad_y, &mut buffers.host, &mut buffers.grad_y)?;
+
+        let num_points = points.len();
+        let window_size = 2 * (params.window_size / 2) + 1;
+        let coords = points
+            .iter()
+            .flat_map(|&(p, g)| [p[0], p[1], g[0], g[1]])
+            .collect::<Vec<_>>();
+        let levels = stream.clone_htod(&levels)?;
+        let coords = stream.clone_htod(&coords)?;
+        // the template and the next window of every point
+        let mut templates =
+            stream.alloc_zeros::<f32>(3 * window_size * window_size * num_points)?;
+        let mut windows = stream.alloc_zeros::<f32>(window_size * window_size * num_points)?;
+        let mut result = stream.alloc_zeros::<f32>(4 * num_points)?;
+
+        let (num_levels, num_points_arg) = (prev_levels.len() as i32, num_points as i32);
+        let (window_size_arg, max_iterations) = (window_size as i32, params.max_iterations as i32);
+        let residual = match params.residual {
+            LkResidual::Intensity => 0i32,
+            LkResidual::ZeroMeanNcc => 1,
+            LkResidual::GainBias => 2,
+        };
+
+        let mut launch = stream.launch_builder(&function);
+        launch
+            .arg(&buffers.prev)
+            .arg(&buffers.next)
+            .arg(&buffers.grad_x)
+            .arg(&buffers.grad_y)
+            .arg(&levels)
+            .arg(&num_levels)
+            .arg(&coords)
+            .arg(&num_points_arg)
+            .arg(&mut templates)
+            .arg(&mut windows)
+            .arg(&window_size_arg)
+            .arg(&max_iterations)
+            .arg(&params.epsilon)
+            .arg(&params.min_eigenvalue)
+            .arg(&residual)
+            .arg(&mut result);
+        let config = LaunchConfig {
+            grid_dim: ((num_points as u32).div_ceil(POINTS_BLOCK_SIZE), 1, 1),
+            block_dim: (POINTS_BLOCK_SIZE, 1, 1),
+            shared_mem_bytes: 0,
+        };
+        // SAFETY: the arguments match the kernel and the buffers hold all the points
+        unsafe { launch.launch(config) }?;
+
+        let result = stream.clone_dtoh(&result)?;
+        let tracked = result
+            .chunks_exact(4)
+            .map(|r| TrackedPoint {
+                position: [r[0], r[1]],
+                tracked: r[2] != 0.0,
+                error: r[3],
+            })
+            .collect();
+
+        Ok(tracked)
+    }
+}
+
+// the gaussian pyramid of an image as built by the tracker of kornia-imgproc
+fn build_pyramid(src: &Image<f32, 1>, num_levels: usize) -> Result<Vec<Image<f32, 1>>, GpuError> {
+    let mut levels = vec![src.clone()];
+    for _ in 1..num_levels {
+        let Some(last) = levels.last() else { break };
+        if last.cols() < 2 || last.rows() < 2 {
+            break;
+        }
+        let size = [last.cols().div_ceil(2), last.rows().div_ceil(2)].into();
+        let mut down = Image::from_size_val(size, 0.0)?;
+        pyrdown(last, &mut down)?;
+        levels.push(down);
+    }
+    Ok(levels)
+}
+
+fn launch_config(cols: usize, rows: usize) -> LaunchConfig {
+    LaunchConfig {
+        grid_dim: (
+            (cols as u32).div_ceil(BLOCK_SIZE),
+            (rows as u32).div_ceil(BLOCK_SIZE),
+            1,
+        ),
+        block_dim: (BLOCK_SIZE, BLOCK_SIZE, 1),
+        shared_mem_bytes: 0,
+    }
+}
+
+// copy host data to the device through the page-locked buffer
+fn upload(
+    stream: &Arc<CudaStream>,
+    data: &[f32],
+    host: &mut PinnedHostSlice<f32>,
+    dst: &mut CudaSlice<f32>,
+) -> Result<(), GpuError> {
+    // waits until the previous transfer of the host buffer is finished
+    host.as_mut_slice()?.copy_from_slice(data);
+    stream.memcpy_htod(host, dst)?;
+    Ok(())
+}
+
+// copy device data to the page-locked buffer and wait for the transfer
+fn download<'a, T: DeviceRepr + ValidAsZeroBits>(
+    stream: &Arc<CudaStream>,
+    src: &CudaSlice<T>,
+    host: &'a mut PinnedHostSlice<T>,
+) -> Result<&'a [T], GpuError> {
+    stream.memcpy_dtoh(src, host)?;
+    Ok(host.as_slice()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+    use kornia_imgproc::{features, video};
+
+    #[test]
+    fn test_cuda_features() -> Result<(), GpuError> {
+        let Ok(mut cuda) = CudaFeatures::new(0) else {
+            return Ok(());
+        };
+
+        let data = (0..32 * 24)
+            .map(|i| ((i % 32) / 8 + (i / 32) / 6) as f32 % 2.0)
+            .collect();
+        let image = Image::<f32, 1>::new([32, 24].into(), data)?;
+
+        let mut expected = Image::from_size_val(image.size(), 0.0)?;
+        let mut response = Image::from_size_val(image.size(), 0.0)?;
+        features::gftt_response(&image, &mut expected, features::GradsMode::Sobel)?;
+        cuda.gftt_response(&image, &mut response)?;
+        for (a, b) in response.as_slice().iter().zip(expected.as_slice()) {
+            assert_relative_eq!(a, b, epsilon = 1e-5);
+        }
+
+        assert_eq!(
+            cuda.non_max_suppression(&expected, 0.01)?,
+            features::non_max_suppression(&expected, 0.01)
+        );
+
+        features::hessian_response(&image, &mut expected, features::GradsMode::Diff)?;
+        cuda.hessian_response(&image, &mut response)?;
+        for (a, b) in response.as_slice().iter().zip(expected.as_slice()) {
+            assert_relative_eq!(a, b, epsilon = 1e-5);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_cuda_track_points_lk() -> Result<(), GpuError> {
+        let Ok(mut cuda) = CudaFeatures::new(0) else {
+            return Ok(());
+        };
+
+        // a smooth texture translated by `shift`
+        let texture = |shift: [f32; 2]| {
+            let data = (0..96 * 80)
+                .map(|i| {
+                    let x = (i % 96) as f32 - shift[0];
+                    let y = (i / 96) as f32 - shift[1];
+                    0.5 + 0.25 * (x / 5.0).sin() * (y / 7.0).cos() + 0.2 * ((x + y) / 9.0).sin()
+                })
+                .collect();
+            Image::<f32, 1>::new([96, 80].into(), data)
+        };
+        let (prev, next) = (texture([0.0, 0.0])?, texture([3.3, -2.1])?);
+        let points = [[30.0, 30.0], [48.0, 40.0], [60.5, 45.2], [-5.0, 10.0]];
+
+        for residual in [LkResidual::Intensity, LkResidual::GainBias] {
+            let params = LkParams {
+                residual,
+                ..Default::default()
+            };
+            let expected = video::track_points_lk(&prev, &next, &points, &params)?;
+            let tracked = cuda.track_points_lk(&prev, &next, &points, &params)?;
+            for (t, e) in tracked.iter().zip(&expected) {
+                assert_eq!(t.tracked, e.tracked);
+                if e.tracked {
+                    assert_relative_eq!(t.position[0], e.position[0], epsilon = 1e-3);
+                    assert_relative_eq!(t.position[1], e.position[1], epsilon = 1e-3);
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
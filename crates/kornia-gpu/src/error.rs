@@ -0,0 +1,36 @@
+use kornia_image::ImageError;
+
+/// An error type for the GPU module.
+#[derive(thiserror::Error, Debug)]
+pub enum GpuError {
+    /// Error when no GPU adapter is available.
+    #[error("No GPU adapter found")]
+    NoAdapter,
+
+    /// Error when requesting the GPU device.
+    #[error(transparent)]
+    RequestDevice(#[from] wgpu::RequestDeviceError),
+
+    /// Error when reading a buffer back from the GPU.
+    #[error(transparent)]
+    BufferMap(#[from] wgpu::BufferAsyncError),
+
+    /// Error when processing an image.
+    #[error(transparent)]
+    ImageError(#[from] ImageError),
+
+    /// Error when the CUDA driver or NVRTC library is not available.
+    #[cfg(feature = "cuda")]
+    #[error("The CUDA driver or NVRTC library is not available")]
+    CudaUnavailable,
+
+    /// Error from the CUDA driver.
+    #[cfg(feature = "cuda")]
+    #[error(transparent)]
+    Cuda(#[from] cudarc::driver::DriverError),
+
+    /// Error when compiling the CUDA kernels.
+    #[cfg(feature = "cuda")]
+    #[error(transparent)]
+    CudaCompile(#[from] cudarc::nvrtc::CompileError),
+}
@@ -0,0 +1,357 @@
+use kornia_image::{Image, ImageSize};
+use kornia_imgproc::{
+    features::GradsMode,
+    video::{LkParams, TrackedPoint},
+};
+
+use crate::{
+    context::Shader,
+    error::GpuError,
+    image::{check_same_size, GpuImage},
+};
+
+const SHADER: Shader = Shader {
+    label: "features",
+    source: include_str!("shaders/features.wgsl"),
+};
+
+#[repr(C)]
+#[derive(Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+struct Params {
+    cols: u32,
+    rows: u32,
+    k: f32,
+    _pad: u32,
+}
+
+/// Compute the Hessian response of an image on the GPU.
+///
+/// The response is the determinant of the Hessian matrix of the interior pixels, as
+/// [`kornia_imgproc::features::hessian_response`]. The border pixels of `dst` are left
+/// untouched.
+///
+/// # Arguments
+///
+/// * `src` - The source image with shape (H, W).
+/// * `dst` - The destination image with shape (H, W).
+pub fn hessian_response(src: &GpuImage<1>, dst: &mut GpuImage<1>) -> Result<(), GpuError> {
+    check_same_size(src.size(), dst.size())?;
+
+    let params = Params {
+        cols: src.cols() as u32,
+        rows: src.rows() as u32,
+        k: 0.0,
+        _pad: 0,
+    };
+    src.context().dispatch(
+        &SHADER,
+        "hessian_response",
+        &params,
+        &[src.buffer(), dst.buffer()],
+        [src.cols(), src.rows()],
+    );
+
+    Ok(())
+}
+
+/// A builder object to initialize the Harris response on the GPU.
+///
+/// The buffer of the gradient products is allocated once and reused between calls.
+pub struct HarrisResponse {
+    image_size: ImageSize,
+    k: f32,
+    moments: Option<wgpu::Buffer>,
+}
+
+impl HarrisResponse {
+    /// Creates a Harris response object with default values
+    pub fn new(image_size: ImageSize) -> Self {
+        Self {
+            image_size,
+            k: 0.04,
+            moments: None,
+        }
+    }
+
+    /// Sets the `k` value (usually between 0.04 to 0.06)
+    pub fn with_k(self, k: f32) -> Self {
+        Self { k, ..self }
+    }
+
+    /// Computes the harris response of an image on the GPU.
+    ///
+    /// The response matches [`kornia_imgproc::features::HarrisResponse`]. The border pixels
+    /// of `dst` are left untouched.
+    ///
+    /// # Arguments
+    ///
+    /// * `src` - The source image with shape (H, W).
+    /// * `dst` - The destination image with shape (H, W).
+    pub fn compute(&mut self, src: &GpuImage<1>, dst: &mut GpuImage<1>) -> Result<(), GpuError> {
+        check_same_size(src.size(), self.image_size)?;
+        check_same_size(dst.size(), self.image_size)?;
+
+        let ctx = src.context();
+        let moments = self
+            .moments
+            .get_or_insert_with(|| ctx.create_buffer(src.cols() * src.rows() * 3));
+
+        let params = Params {
+            cols: src.cols() as u32,
+            rows: src.rows() as u32,
+            k: self.k,
+            _pad: 0,
+        };
+        ctx.dispatch(
+            &SHADER,
+            "harris_gradients",
+            &params,
+            &[src.buffer(), moments],
+            [src.cols(), src.rows()],
+        );
+        ctx.dispatch(
+            &SHADER,
+            "harris_response",
+            &params,
+            &[moments, dst.buffer()],
+            [src.cols(), src.rows()],
+        );
+
+        Ok(())
+    }
+}
+
+/// The backend computing the feature responses of images in host memory.
+///
+/// The call sites do not change with the backend, so a program can select CUDA when it is
+/// available and fall back to the CPU otherwise.
+///
+/// # Example
+///
+/// ```
+/// use kornia_gpu::features::FeatureBackend;
+/// use kornia_image::Image;
+///
+/// let mut backend = FeatureBackend::auto();
+/// let image = Image::<f32, 1>::from_size_val([64, 48].into(), 0.0).unwrap();
+/// let mut response = Image::<f32, 1>::from_size_val(image.size(), 0.0).unwrap();
+///
+/// backend.gftt_response(&image, &mut response).unwrap();
+/// let keypoints = backend.non_max_suppression(&response, 0.01).unwrap();
+/// assert!(keypoints.is_empty());
+/// ```
+pub enum FeatureBackend {
+    /// The CPU implementation of `kornia-imgproc`.
+    Cpu,
+    /// The CUDA kernels.
+    #[cfg(feature = "cuda")]
+    Cuda(Box<crate::cuda::CudaFeatures>),
+}
+
+impl FeatureBackend {
+    /// Select the CUDA backend on the first device if available, otherwise the CPU.
+    pub fn auto() -> Self {
+        #[cfg(feature = "cuda")]
+        if let Ok(cuda) = crate::cuda::CudaFeatures::new(0) {
+            return Self::Cuda(Box::new(cuda));
+        }
+        Self::Cpu
+    }
+
+    /// Compute the Hessian response of an image.
+    ///
+    /// # Arguments
+    ///
+    /// * `src` - The source image with shape (H, W).
+    /// * `dst` - The destination image with shape (H, W).
+    pub fn hessian_response(
+        &mut self,
+        src: &Image<f32, 1>,
+        dst: &mut Image<f32, 1>,
+    ) -> Result<(), GpuError> {
+        match self {
+            Self::Cpu => Ok(kornia_imgproc::features::hessian_response(
+                src,
+                dst,
+                GradsMode::Diff,
+            )?),
+            #[cfg(feature = "cuda")]
+            Self::Cuda(cuda) => cuda.hessian_response(src, dst),
+        }
+    }
+
+    /// Compute the Shi-Tomasi response of an image.
+    ///
+    /// # Arguments
+    ///
+    /// * `src` - The source image with shape (H, W).
+    /// * `dst` - The destination image with shape (H, W).
+    pub fn gftt_response(
+        &mut self,
+        src: &Image<f32, 1>,
+        dst: &mut Image<f32, 1>,
+    ) -> Result<(), GpuError> {
+        match self {
+            Self::Cpu => Ok(kornia_imgproc::features::gftt_response(
+                src,
+                dst,
+                GradsMode::Sobel,
+            )?),
+            #[cfg(feature = "cuda")]
+            Self::Cuda(cuda) => cuda.gftt_response(src, dst),
+        }
+    }
+
+    /// Find the local maxima of a response image in a 3x3 neighborhood.
+    ///
+    /// # Arguments
+    ///
+    /// * `src` - The response image.
+    /// * `threshold` - The minimum response of a local maximum.
+    ///
+    /// # Returns
+    ///
+    /// The coordinates `[x, y]` of the local maxima in row-major order.
+    pub fn non_max_suppression(
+        &mut self,
+        src: &Image<f32, 1>,
+        threshold: f32,
+    ) -> Result<Vec<[i32; 2]>, GpuError> {
+        match self {
+            Self::Cpu => Ok(kornia_imgproc::features::non_max_suppression(
+                src, threshold,
+            )),
+            #[cfg(feature = "cuda")]
+            Self::Cuda(cuda) => cuda.non_max_suppression(src, threshold),
+        }
+    }
+
+    /// Track points between two images with the pyramidal Lucas-Kanade optical flow.
+    ///
+    /// # Arguments
+    ///
+    /// * `prev` - The previous image with shape (H, W).
+    /// * `next` - The next image with shape (H, W).
+    /// * `prev_pts` - The positions `[x, y]` of the points in the previous image.
+    /// * `params` - The parameters of the tracker.
+    ///
+    /// # Returns
+    ///
+    /// The tracked points, in the order of `prev_pts`.
+    pub fn track_points_lk(
+        &mut self,
+        prev: &Image<f32, 1>,
+        next: &Image<f32, 1>,
+        prev_pts: &[[f32; 2]],
+        params: &LkParams,
+    ) -> Result<Vec<TrackedPoint>, GpuError> {
+        match self {
+            Self::Cpu => Ok(kornia_imgproc::video::track_points_lk(
+                prev, next, prev_pts, params,
+            )?),
+            #[cfg(feature = "cuda")]
+            Self::Cuda(cuda) => cuda.track_points_lk(prev, next, prev_pts, params),
+        }
+    }
+
+    /// Track points between two images from initial guesses of their next positions.
+    ///
+    /// # Arguments
+    ///
+    /// * `prev` - The previous image with shape (H, W).
+    /// * `next` - The next image with shape (H, W).
+    /// * `points` - The positions `[x, y]` of the points in the previous image and their
+    ///   predicted positions in the next image.
+    /// * `params` - The parameters of the tracker.
+    ///
+    /// # Returns
+    ///
+    /// The tracked points, in the order of `points`.
+    pub fn track_points_lk_with_guesses(
+        &mut self,
+        prev: &Image<f32, 1>,
+        next: &Image<f32, 1>,
+        points: &[([f32; 2], [f32; 2])],
+        params: &LkParams,
+    ) -> Result<Vec<TrackedPoint>, GpuError> {
+        match self {
+            Self::Cpu => Ok(kornia_imgproc::video::track_points_lk_with_guesses(
+                prev, next, points, params,
+            )?),
+            #[cfg(feature = "cuda")]
+            Self::Cuda(cuda) => cuda.track_points_lk_with_guesses(prev, next, points, params),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::context::test_context;
+    use approx::assert_relative_eq;
+
+    fn test_image() -> Result<Image<f32, 1>, GpuError> {
+        // a bright square with corners on a dark background
+        let data = (0..24 * 20)
+            .map(|i| {
+                let (x, y) = (i % 24, i / 24);
+                if (6..16).contains(&x) && (5..14).contains(&y) {
+                    0.9
+                } else {
+                    0.1 + 0.01 * (x + y) as f32
+                }
+            })
+            .collect();
+        Ok(Image::new([24, 20].into(), data)?)
+    }
+
+    #[test]
+    fn test_hessian_response() -> Result<(), GpuError> {
+        let Some(ctx) = test_context() else {
+            return Ok(());
+        };
+
+        let image = test_image()?;
+        let mut expected = Image::from_size_val(image.size(), 0.0)?;
+        kornia_imgproc::features::hessian_response(&image, &mut expected, GradsMode::Diff)?;
+
+        let src = GpuImage::upload(&ctx, &image);
+        let mut dst = GpuImage::zeros(&ctx, image.size());
+        hessian_response(&src, &mut dst)?;
+
+        for (a, b) in dst.download()?.as_slice().iter().zip(expected.as_slice()) {
+            assert_relative_eq!(a, b, epsilon = 1e-5);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_harris_response() -> Result<(), GpuError> {
+        let Some(ctx) = test_context() else {
+            return Ok(());
+        };
+
+        let image = test_image()?;
+        let mut expected = Image::from_size_val(image.size(), 0.0)?;
+        kornia_imgproc::features::HarrisResponse::new(image.size())
+            .with_k(0.05)
+            .compute(&image, &mut expected)?;
+
+        let src = GpuImage::upload(&ctx, &image);
+        let mut dst = GpuImage::zeros(&ctx, image.size());
+        let mut harris = HarrisResponse::new(image.size()).with_k(0.05);
+        harris.compute(&src, &mut dst)?;
+        // the buffers are reused
+        harris.compute(&src, &mut dst)?;
+
+        for (a, b) in dst.download()?.as_slice().iter().zip(expected.as_slice()) {
+            assert_relative_eq!(a, b, epsilon = 1e-5);
+        }
+
+        let mut small = GpuImage::zeros(&ctx, [4, 4].into());
+        assert!(harris.compute(&src, &mut small).is_err());
+
+        Ok(())
+    }
+}
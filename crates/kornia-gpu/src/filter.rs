@@ -0,0 +1,134 @@
+use kornia_image::ImageError;
+use kornia_imgproc::filter::kernels;
+
+use crate::{
+    context::Shader,
+    error::GpuError,
+    image::{check_same_size, GpuImage},
+};
+
+const SHADER: Shader = Shader {
+    label: "filter",
+    source: include_str!("shaders/filter.wgsl"),
+};
+
+#[repr(C)]
+#[derive(Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+struct Params {
+    cols: u32,
+    rows: u32,
+    channels: u32,
+    kernel_offset: u32,
+    kernel_len: u32,
+    horizontal: u32,
+    _pad: [u32; 2],
+}
+
+/// Apply a separable filter to an image on the GPU.
+///
+/// The pixels outside of the image are treated as zero, as
+/// [`kornia_imgproc::filter::separable_filter`].
+///
+/// # Arguments
+///
+/// * `src` - The source image with shape (H, W, C).
+/// * `dst` - The destination image with shape (H, W, C).
+/// * `kernel_x` - The horizontal kernel.
+/// * `kernel_y` - The vertical kernel.
+pub fn separable_filter<const C: usize>(
+    src: &GpuImage<C>,
+    dst: &mut GpuImage<C>,
+    kernel_x: &[f32],
+    kernel_y: &[f32],
+) -> Result<(), GpuError> {
+    if kernel_x.is_empty() || kernel_y.is_empty() {
+        return Err(ImageError::InvalidKernelLength(kernel_x.len(), kernel_y.len()).into());
+    }
+    check_same_size(src.size(), dst.size())?;
+
+    let ctx = src.context();
+    let weights = ctx.create_buffer_init(&[kernel_x, kernel_y].concat());
+    let temp = ctx.create_buffer(src.cols() * src.rows() * C);
+
+    let pass = |kernel_offset: usize, kernel_len: usize, horizontal: bool| Params {
+        cols: src.cols() as u32,
+        rows: src.rows() as u32,
+        channels: C as u32,
+        kernel_offset: kernel_offset as u32,
+        kernel_len: kernel_len as u32,
+        horizontal: horizontal as u32,
+        _pad: [0; 2],
+    };
+
+    ctx.dispatch(
+        &SHADER,
+        "separable_pass",
+        &pass(0, kernel_x.len(), true),
+        &[src.buffer(), &weights, &temp],
+        [src.cols(), src.rows()],
+    );
+    ctx.dispatch(
+        &SHADER,
+        "separable_pass",
+        &pass(kernel_x.len(), kernel_y.len(), false),
+        &[&temp, &weights, dst.buffer()],
+        [src.cols(), src.rows()],
+    );
+
+    Ok(())
+}
+
+/// Blur an image using a gaussian blur filter on the GPU.
+///
+/// # Arguments
+///
+/// * `src` - The source image with shape (H, W, C).
+/// * `dst` - The destination image with shape (H, W, C).
+/// * `kernel_size` - The size of the kernel (kernel_x, kernel_y).
+/// * `sigma` - The sigma of the gaussian kernel.
+///
+/// NOTE: This function uses a constant border type.
+pub fn gaussian_blur<const C: usize>(
+    src: &GpuImage<C>,
+    dst: &mut GpuImage<C>,
+    kernel_size: (usize, usize),
+    sigma: (f32, f32),
+) -> Result<(), GpuError> {
+    let kernel_x = kernels::gaussian_kernel_1d(kernel_size.0, sigma.0);
+    let kernel_y = kernels::gaussian_kernel_1d(kernel_size.1, sigma.1);
+    separable_filter(src, dst, &kernel_x, &kernel_y)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::context::test_context;
+    use approx::assert_relative_eq;
+    use kornia_image::Image;
+
+    #[test]
+    fn test_gaussian_blur() -> Result<(), GpuError> {
+        let Some(ctx) = test_context() else {
+            return Ok(());
+        };
+
+        let image = Image::<f32, 2>::new(
+            [23, 19].into(),
+            (0..23 * 19 * 2).map(|x| ((x * 7) % 11) as f32).collect(),
+        )?;
+        let mut expected = Image::from_size_val(image.size(), 0.0)?;
+        kornia_imgproc::filter::gaussian_blur(&image, &mut expected, (5, 3), (1.5, 0.8))?;
+
+        let src = GpuImage::upload(&ctx, &image);
+        let mut dst = GpuImage::zeros(&ctx, image.size());
+        gaussian_blur(&src, &mut dst, (5, 3), (1.5, 0.8))?;
+
+        for (a, b) in dst.download()?.as_slice().iter().zip(expected.as_slice()) {
+            assert_relative_eq!(a, b, epsilon = 1e-4);
+        }
+
+        assert!(separable_filter(&src, &mut dst, &[], &[1.0]).is_err());
+
+        Ok(())
+    }
+}
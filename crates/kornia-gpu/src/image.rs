@@ -0,0 +1,170 @@
+use kornia_image::{Image, ImageError, ImageSize};
+
+use crate::{context::GpuContext, error::GpuError};
+
+/// An image with `C` channels of `f32` stored in GPU memory.
+///
+/// The pixels are stored interleaved in row-major order as [`Image`]. The transfers between
+/// the host and the device are explicit with [`GpuImage::upload`], [`GpuImage::write`] and
+/// [`GpuImage::download`], so chained operations keep the data on the device.
+pub struct GpuImage<const C: usize> {
+    ctx: GpuContext,
+    buffer: wgpu::Buffer,
+    size: ImageSize,
+}
+
+impl<const C: usize> GpuImage<C> {
+    /// Copy an image to the GPU.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The GPU context to store the image.
+    /// * `image` - The image to copy.
+    pub fn upload(ctx: &GpuContext, image: &Image<f32, C>) -> Self {
+        Self {
+            ctx: ctx.clone(),
+            buffer: ctx.create_buffer_init(image.as_slice()),
+            size: image.size(),
+        }
+    }
+
+    /// Create an image filled with zeros on the GPU.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The GPU context to store the image.
+    /// * `size` - The size of the image.
+    pub fn zeros(ctx: &GpuContext, size: ImageSize) -> Self {
+        Self {
+            ctx: ctx.clone(),
+            buffer: ctx.create_buffer(size.width * size.height * C),
+            size,
+        }
+    }
+
+    /// Overwrite the pixels of the image with an image of the same size from the host.
+    ///
+    /// # Arguments
+    ///
+    /// * `image` - The image to copy.
+    pub fn write(&mut self, image: &Image<f32, C>) -> Result<(), GpuError> {
+        check_same_size(image.size(), self.size)?;
+        if !image.as_slice().is_empty() {
+            self.ctx
+                .queue
+                .write_buffer(&self.buffer, 0, bytemuck::cast_slice(image.as_slice()));
+        }
+        Ok(())
+    }
+
+    /// Copy the image back to the host.
+    ///
+    /// This waits until all the operations writing the image are finished.
+    ///
+    /// # Returns
+    ///
+    /// The image on the host.
+    pub fn download(&self) -> Result<Image<f32, C>, GpuError> {
+        let len = self.size.width * self.size.height * C;
+        if len == 0 {
+            return Ok(Image::new(self.size, Vec::new())?);
+        }
+        let num_bytes = (len * std::mem::size_of::<f32>()) as u64;
+
+        let staging = self.ctx.device.create_buffer(&wgpu::BufferDescriptor {
+            label: None,
+            size: num_bytes,
+            usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let mut encoder = self
+            .ctx
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
+        encoder.copy_buffer_to_buffer(&self.buffer, 0, &staging, 0, num_bytes);
+        self.ctx.queue.submit(Some(encoder.finish()));
+
+        let slice = staging.slice(..);
+        let (sender, receiver) = std::sync::mpsc::channel();
+        slice.map_async(wgpu::MapMode::Read, move |result| {
+            let _ = sender.send(result);
+        });
+        self.ctx.synchronize();
+        receiver.recv().unwrap_or(Err(wgpu::BufferAsyncError))?;
+
+        let data = bytemuck::cast_slice::<u8, f32>(&slice.get_mapped_range()).to_vec();
+        staging.unmap();
+
+        Ok(Image::new(self.size, data)?)
+    }
+
+    /// The context storing the image.
+    pub fn context(&self) -> &GpuContext {
+        &self.ctx
+    }
+
+    /// The size of the image.
+    pub fn size(&self) -> ImageSize {
+        self.size
+    }
+
+    /// The number of columns of the image.
+    pub fn cols(&self) -> usize {
+        self.size.width
+    }
+
+    /// The number of rows of the image.
+    pub fn rows(&self) -> usize {
+        self.size.height
+    }
+
+    /// The number of channels of the image.
+    pub fn num_channels(&self) -> usize {
+        C
+    }
+
+    pub(crate) fn buffer(&self) -> &wgpu::Buffer {
+        &self.buffer
+    }
+}
+
+// check that two images have the same size
+pub(crate) fn check_same_size(a: ImageSize, b: ImageSize) -> Result<(), ImageError> {
+    if a != b {
+        return Err(ImageError::InvalidImageSize(
+            a.width, a.height, b.width, b.height,
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::context::test_context;
+
+    #[test]
+    fn test_upload_download() -> Result<(), GpuError> {
+        let Some(ctx) = test_context() else {
+            return Ok(());
+        };
+
+        let image = Image::<f32, 2>::new([3, 2].into(), (0..12).map(|x| x as f32).collect())?;
+        let mut gpu_image = GpuImage::upload(&ctx, &image);
+        assert_eq!(gpu_image.size(), image.size());
+        assert_eq!(gpu_image.download()?.as_slice(), image.as_slice());
+
+        let other = Image::<f32, 2>::from_size_val([3, 2].into(), 7.0)?;
+        gpu_image.write(&other)?;
+        assert_eq!(gpu_image.download()?.as_slice(), other.as_slice());
+
+        let small = Image::<f32, 2>::from_size_val([2, 2].into(), 0.0)?;
+        assert!(gpu_image.write(&small).is_err());
+
+        let zeros = GpuImage::<2>::zeros(&ctx, [4, 1].into());
+        assert_eq!(zeros.download()?.as_slice(), &[0.0; 8]);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,90 @@
+// the 3x3 neighborhood of an interior pixel in row-major order
+__device__ void neighborhood(const float* src, int x, int y, int cols, float* v) {
+    for (int i = 0; i < 9; ++i) {
+        v[i] = src[(y + i / 3 - 1) * cols + x + i % 3 - 1];
+    }
+}
+
+__device__ bool is_interior(int x, int y, int cols, int rows) {
+    return x >= 1 && y >= 1 && x + 1 < cols && y + 1 < rows;
+}
+
+// determinant of the hessian of the interior pixels
+extern "C" __global__ void hessian_response(const float* src, float* dst, int cols, int rows) {
+    int x = blockIdx.x * blockDim.x + threadIdx.x;
+    int y = blockIdx.y * blockDim.y + threadIdx.y;
+    if (!is_interior(x, y, cols, rows)) {
+        return;
+    }
+    float v[9];
+    neighborhood(src, x, y, cols, v);
+    float dxx = v[3] - 2.0f * v[4] + v[5];
+    float dyy = v[1] - 2.0f * v[4] + v[7];
+    float dxy = 0.25f * (v[6] - v[0] - v[8] + v[2]);
+    dst[y * cols + x] = dxx * dyy - dxy * dxy;
+}
+
+// the products of the sobel gradients, zero at the border
+extern "C" __global__ void gradient_moments(const float* src, float* moments, int cols, int rows) {
+    int x = blockIdx.x * blockDim.x + threadIdx.x;
+    int y = blockIdx.y * blockDim.y + threadIdx.y;
+    if (x >= cols || y >= rows) {
+        return;
+    }
+    int idx = (y * cols + x) * 3;
+    if (!is_interior(x, y, cols, rows)) {
+        moments[idx] = 0.0f;
+        moments[idx + 1] = 0.0f;
+        moments[idx + 2] = 0.0f;
+        return;
+    }
+    float v[9];
+    neighborhood(src, x, y, cols, v);
+    float dx = (-v[8] + v[6] - 2.0f * v[5] + 2.0f * v[3] - v[2] + v[0]) * 0.125f;
+    float dy = (-v[8] - 2.0f * v[7] - v[6] + v[2] + 2.0f * v[1] + v[0]) * 0.125f;
+    moments[idx] = dx * dx;
+    moments[idx + 1] = dy * dy;
+    moments[idx + 2] = dx * dy;
+}
+
+// the smallest eigenvalue of the 3x3 sums of the gradient products of the interior pixels
+extern "C" __global__ void gftt_response(const float* moments, float* dst, int cols, int rows) {
+    int x = blockIdx.x * blockDim.x + threadIdx.x;
+    int y = blockIdx.y * blockDim.y + threadIdx.y;
+    if (!is_interior(x, y, cols, rows)) {
+        return;
+    }
+    float m0 = 0.0f, m1 = 0.0f, m2 = 0.0f;
+    for (int i = 0; i < 9; ++i) {
+        int idx = ((y + i / 3 - 1) * cols + x + i % 3 - 1) * 3;
+        m0 += moments[idx];
+        m1 += moments[idx + 1];
+        m2 += moments[idx + 2];
+    }
+    float half_trace = 0.5f * (m0 + m1);
+    float half_diff = 0.5f * (m0 - m1);
+    dst[y * cols + x] = half_trace - sqrtf(half_diff * half_diff + m2 * m2);
+}
+
+// mark the 3x3 local maxima above the threshold, keeping the first pixel of a plateau
+extern "C" __global__ void non_max_suppression(
+    const float* src, unsigned char* mask, int cols, int rows, float threshold) {
+    int x = blockIdx.x * blockDim.x + threadIdx.x;
+    int y = blockIdx.y * blockDim.y + threadIdx.y;
+    if (x >= cols || y >= rows) {
+        return;
+    }
+    int idx = y * cols + x;
+    mask[idx] = 0;
+    if (!is_interior(x, y, cols, rows) || src[idx] <= threshold) {
+        return;
+    }
+    float v[9];
+    neighborhood(src, x, y, cols, v);
+    for (int i = 0; i < 9; ++i) {
+        if ((i < 4 && v[4] <= v[i]) || (i > 4 && v[4] < v[i])) {
+            return;
+        }
+    }
+    mask[idx] = 1;
+}
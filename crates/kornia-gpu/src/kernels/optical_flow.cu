@@ -0,0 +1,190 @@
+// the residuals of `LkResidual`
+#define RESIDUAL_INTENSITY 0
+#define RESIDUAL_ZERO_MEAN_NCC 1
+#define RESIDUAL_GAIN_BIAS 2
+
+#define EPSILON 1.1920929e-7f
+
+// a level of the pyramids, the offset of its first pixel in the concatenated levels
+struct Level {
+    int offset;
+    int cols;
+    int rows;
+};
+
+__device__ Level load_level(const int* levels, int level) {
+    Level l;
+    l.offset = levels[3 * level];
+    l.cols = levels[3 * level + 1];
+    l.rows = levels[3 * level + 2];
+    return l;
+}
+
+// bilinear sampling with the coordinates clamped to the image
+__device__ float sample(const float* image, Level l, float x, float y) {
+    x = fminf(fmaxf(x, 0.0f), (float)(l.cols - 1));
+    y = fminf(fmaxf(y, 0.0f), (float)(l.rows - 1));
+    int iu = (int)x;
+    int iv = (int)y;
+    float fu = x - (float)iu;
+    float fv = y - (float)iv;
+    const float* row = image + l.offset + iv * l.cols;
+    float v00 = row[iu];
+    float v01 = iu + 1 < l.cols ? row[iu + 1] : v00;
+    float v10 = iv + 1 < l.rows ? row[l.cols + iu] : v00;
+    float v11 = iu + 1 < l.cols && iv + 1 < l.rows ? row[l.cols + iu + 1] : v00;
+    return v00 * (1.0f - fu) * (1.0f - fv) + v01 * fu * (1.0f - fv) + v10 * (1.0f - fu) * fv
+        + v11 * fu * fv;
+}
+
+__device__ bool inside(Level l, float x, float y) {
+    return x >= 0.0f && y >= 0.0f && x <= (float)(l.cols - 1) && y <= (float)(l.rows - 1);
+}
+
+// the gain and bias of the intensities of a window relative to the template window
+__device__ bool photometric_fit(
+    const float* tmpl, const float* window, int n, int residual, float* gain, float* bias) {
+    *gain = 1.0f;
+    *bias = 0.0f;
+    if (residual == RESIDUAL_INTENSITY) {
+        return true;
+    }
+
+    float mean_t = 0.0f, mean_w = 0.0f;
+    for (int i = 0; i < n; ++i) {
+        mean_t += tmpl[3 * i];
+        mean_w += window[i];
+    }
+    mean_t /= (float)n;
+    mean_w /= (float)n;
+    float var_t = 0.0f, var_w = 0.0f, cov = 0.0f;
+    for (int i = 0; i < n; ++i) {
+        float dt = tmpl[3 * i] - mean_t;
+        float dw = window[i] - mean_w;
+        var_t += dt * dt;
+        var_w += dw * dw;
+        cov += dt * dw;
+    }
+    if (var_t <= EPSILON) {
+        return false;
+    }
+
+    *gain = residual == RESIDUAL_ZERO_MEAN_NCC ? sqrtf(var_w / var_t) : cov / var_t;
+    if (*gain <= EPSILON) {
+        return false;
+    }
+    *bias = mean_w - *gain * mean_t;
+    return true;
+}
+
+// track a point per thread from the coarsest to the finest level of the pyramids, the
+// result of a point is its position, whether it was tracked and its mean residual
+extern "C" __global__ void track_points_lk(
+    const float* prev, const float* next, const float* grad_x, const float* grad_y,
+    const int* levels, int num_levels, const float* points, int num_points,
+    float* templates, float* windows, int window_size, int max_iterations, float epsilon,
+    float min_eigenvalue, int residual, float* result) {
+    int idx = blockIdx.x * blockDim.x + threadIdx.x;
+    if (idx >= num_points) {
+        return;
+    }
+
+    int half = window_size / 2;
+    int n = window_size * window_size;
+    float* tmpl = templates + (size_t)idx * 3 * n;
+    float* window = windows + (size_t)idx * n;
+
+    float px = points[4 * idx], py = points[4 * idx + 1];
+    float gx = points[4 * idx + 2], gy = points[4 * idx + 3];
+    float* out = result + 4 * idx;
+    // lost until the point is tracked at the finest level
+    out[0] = gx;
+    out[1] = gy;
+    out[2] = 0.0f;
+    out[3] = __int_as_float(0x7f800000);
+
+    // the flow at the coarsest level from the initial guess
+    int top = num_levels - 1;
+    float top_scale = (float)(1 << top);
+    float fx = (gx - px) / top_scale, fy = (gy - py) / top_scale;
+    float error = 0.0f;
+
+    for (int level = top; level >= 0; --level) {
+        float scale = (float)(1 << level);
+        Level l = load_level(levels, level);
+        float cx = px / scale, cy = py / scale;
+        if (!inside(l, cx, cy)) {
+            return;
+        }
+
+        // the template window and its gradient matrix
+        float gxx = 0.0f, gyy = 0.0f, gxy = 0.0f;
+        for (int i = 0; i < n; ++i) {
+            float x = cx + (float)(i % window_size - half);
+            float y = cy + (float)(i / window_size - half);
+            float ix = sample(grad_x, l, x, y);
+            float iy = sample(grad_y, l, x, y);
+            tmpl[3 * i] = sample(prev, l, x, y);
+            tmpl[3 * i + 1] = ix;
+            tmpl[3 * i + 2] = iy;
+            gxx += ix * ix;
+            gyy += iy * iy;
+            gxy += ix * iy;
+        }
+        float det = gxx * gyy - gxy * gxy;
+        float half_diff = 0.5f * (gxx - gyy);
+        float min_eig = 0.5f * (gxx + gyy) - sqrtf(half_diff * half_diff + gxy * gxy);
+        if (min_eig / (float)n < min_eigenvalue || fabsf(det) <= EPSILON) {
+            return;
+        }
+
+        for (int iter = 0; iter < max_iterations; ++iter) {
+            float tx = cx + fx, ty = cy + fy;
+            if (!inside(l, tx, ty)) {
+                return;
+            }
+
+            for (int i = 0; i < n; ++i) {
+                float x = tx + (float)(i % window_size - half);
+                float y = ty + (float)(i / window_size - half);
+                window[i] = sample(next, l, x, y);
+            }
+            float gain, bias;
+            if (!photometric_fit(tmpl, window, n, residual, &gain, &bias)) {
+                return;
+            }
+
+            float bx = 0.0f, by = 0.0f;
+            error = 0.0f;
+            for (int i = 0; i < n; ++i) {
+                // the next window mapped to the exposure of the previous one
+                float diff = tmpl[3 * i] - (window[i] - bias) / gain;
+                bx += diff * tmpl[3 * i + 1];
+                by += diff * tmpl[3 * i + 2];
+                error += fabsf(diff);
+            }
+
+            float dx = (gyy * bx - gxy * by) / det;
+            float dy = (gxx * by - gxy * bx) / det;
+            fx += dx;
+            fy += dy;
+            if (hypotf(dx, dy) < epsilon) {
+                break;
+            }
+        }
+
+        if (level > 0) {
+            fx *= 2.0f;
+            fy *= 2.0f;
+        }
+    }
+
+    float x = px + fx, y = py + fy;
+    if (!inside(load_level(levels, 0), x, y)) {
+        return;
+    }
+    out[0] = x;
+    out[1] = y;
+    out[2] = 1.0f;
+    out[3] = error / (float)n;
+}
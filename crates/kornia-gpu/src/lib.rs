@@ -0,0 +1,34 @@
+#![deny(missing_docs)]
+#![doc = env!("CARGO_PKG_DESCRIPTION")]
+
+/// GPU implementations of the dispatched image operations.
+pub mod backend;
+
+/// Color conversions on the GPU.
+pub mod color;
+
+/// The GPU device and the dispatch of compute shaders.
+pub mod context;
+
+/// Feature responses and point tracking with CUDA kernels.
+#[cfg(feature = "cuda")]
+pub mod cuda;
+
+/// Error types for the GPU module.
+pub mod error;
+
+/// Feature responses on the GPU.
+pub mod features;
+
+/// Image filters on the GPU.
+pub mod filter;
+
+/// Images stored in GPU memory.
+pub mod image;
+
+/// Geometric image transformations on the GPU.
+pub mod warp;
+
+pub use crate::context::GpuContext;
+pub use crate::error::GpuError;
+pub use crate::image::GpuImage;
@@ -0,0 +1,46 @@
+struct Params {
+    cols: u32,
+    rows: u32,
+    _pad0: u32,
+    _pad1: u32,
+}
+
+@group(0) @binding(0) var<uniform> params: Params;
+@group(0) @binding(1) var<storage, read> src: array<f32>;
+@group(0) @binding(2) var<storage, read_write> dst: array<f32>;
+
+@compute @workgroup_size(16, 16)
+fn gray_from_rgb(@builtin(global_invocation_id) id: vec3<u32>) {
+    if id.x >= params.cols || id.y >= params.rows {
+        return;
+    }
+    let idx = id.y * params.cols + id.x;
+    let rgb = vec3<f32>(src[idx * 3u], src[idx * 3u + 1u], src[idx * 3u + 2u]);
+    dst[idx] = 0.299 * rgb.r + 0.587 * rgb.g + 0.114 * rgb.b;
+}
+
+@compute @workgroup_size(16, 16)
+fn rgb_from_gray(@builtin(global_invocation_id) id: vec3<u32>) {
+    if id.x >= params.cols || id.y >= params.rows {
+        return;
+    }
+    let idx = id.y * params.cols + id.x;
+    let gray = src[idx];
+    dst[idx * 3u] = gray;
+    dst[idx * 3u + 1u] = gray;
+    dst[idx * 3u + 2u] = gray;
+}
+
+@compute @workgroup_size(16, 16)
+fn bgr_from_rgb(@builtin(global_invocation_id) id: vec3<u32>) {
+    if id.x >= params.cols || id.y >= params.rows {
+        return;
+    }
+    let idx = (id.y * params.cols + id.x) * 3u;
+    let r = src[idx];
+    let g = src[idx + 1u];
+    let b = src[idx + 2u];
+    dst[idx] = b;
+    dst[idx + 1u] = g;
+    dst[idx + 2u] = r;
+}
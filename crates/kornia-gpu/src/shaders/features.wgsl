@@ -0,0 +1,73 @@
+struct Params {
+    cols: u32,
+    rows: u32,
+    k: f32,
+    _pad0: u32,
+}
+
+@group(0) @binding(0) var<uniform> params: Params;
+@group(0) @binding(1) var<storage, read> input: array<f32>;
+@group(0) @binding(2) var<storage, read_write> output: array<f32>;
+
+fn is_interior(id: vec3<u32>) -> bool {
+    return id.x >= 1u && id.y >= 1u && id.x + 1u < params.cols && id.y + 1u < params.rows;
+}
+
+// the 3x3 neighborhood of a pixel in row-major order
+fn neighborhood(id: vec3<u32>) -> array<f32, 9> {
+    var v: array<f32, 9>;
+    for (var i = 0u; i < 9u; i++) {
+        v[i] = input[(id.y + i / 3u - 1u) * params.cols + id.x + i % 3u - 1u];
+    }
+    return v;
+}
+
+// determinant of the hessian of the interior pixels
+@compute @workgroup_size(16, 16)
+fn hessian_response(@builtin(global_invocation_id) id: vec3<u32>) {
+    if !is_interior(id) {
+        return;
+    }
+    let v = neighborhood(id);
+    let dxx = v[3] - 2.0 * v[4] + v[5];
+    let dyy = v[1] - 2.0 * v[4] + v[7];
+    let dxy = 0.25 * (v[6] - v[0] - v[8] + v[2]);
+    output[id.y * params.cols + id.x] = dxx * dyy - dxy * dxy;
+}
+
+// the products of the sobel gradients, zero at the border
+@compute @workgroup_size(16, 16)
+fn harris_gradients(@builtin(global_invocation_id) id: vec3<u32>) {
+    if id.x >= params.cols || id.y >= params.rows {
+        return;
+    }
+    let idx = (id.y * params.cols + id.x) * 3u;
+    if !is_interior(id) {
+        output[idx] = 0.0;
+        output[idx + 1u] = 0.0;
+        output[idx + 2u] = 0.0;
+        return;
+    }
+    let v = neighborhood(id);
+    let dx = (-v[8] + v[6] - 2.0 * v[5] + 2.0 * v[3] - v[2] + v[0]) * 0.125;
+    let dy = (-v[8] - 2.0 * v[7] - v[6] + v[2] + 2.0 * v[1] + v[0]) * 0.125;
+    output[idx] = dx * dx;
+    output[idx + 1u] = dy * dy;
+    output[idx + 2u] = dx * dy;
+}
+
+// the harris score from the 3x3 sums of the gradient products of the interior pixels
+@compute @workgroup_size(16, 16)
+fn harris_response(@builtin(global_invocation_id) id: vec3<u32>) {
+    if !is_interior(id) {
+        return;
+    }
+    var m = vec3<f32>(0.0);
+    for (var i = 0u; i < 9u; i++) {
+        let idx = ((id.y + i / 3u - 1u) * params.cols + id.x + i % 3u - 1u) * 3u;
+        m += vec3<f32>(input[idx], input[idx + 1u], input[idx + 2u]);
+    }
+    let det = m.x * m.y - m.z * m.z;
+    let trace = m.x + m.y;
+    output[id.y * params.cols + id.x] = max(0.0, det - params.k * trace * trace);
+}
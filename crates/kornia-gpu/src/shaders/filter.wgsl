@@ -0,0 +1,41 @@
+struct Params {
+    cols: u32,
+    rows: u32,
+    channels: u32,
+    kernel_offset: u32,
+    kernel_len: u32,
+    horizontal: u32,
+    _pad0: u32,
+    _pad1: u32,
+}
+
+@group(0) @binding(0) var<uniform> params: Params;
+@group(0) @binding(1) var<storage, read> src: array<f32>;
+@group(0) @binding(2) var<storage, read> weights: array<f32>;
+@group(0) @binding(3) var<storage, read_write> dst: array<f32>;
+
+// convolve the image with a 1d kernel along the rows or the columns with zero border
+@compute @workgroup_size(16, 16)
+fn separable_pass(@builtin(global_invocation_id) id: vec3<u32>) {
+    if id.x >= params.cols || id.y >= params.rows {
+        return;
+    }
+    let radius = i32(params.kernel_len / 2u);
+    for (var ch = 0u; ch < params.channels; ch++) {
+        var acc = 0.0;
+        for (var k = 0u; k < params.kernel_len; k++) {
+            var x = i32(id.x);
+            var y = i32(id.y);
+            if params.horizontal == 1u {
+                x += i32(k) - radius;
+            } else {
+                y += i32(k) - radius;
+            }
+            if x >= 0 && x < i32(params.cols) && y >= 0 && y < i32(params.rows) {
+                let idx = (u32(y) * params.cols + u32(x)) * params.channels + ch;
+                acc += src[idx] * weights[params.kernel_offset + k];
+            }
+        }
+        dst[(id.y * params.cols + id.x) * params.channels + ch] = acc;
+    }
+}
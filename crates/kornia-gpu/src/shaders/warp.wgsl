@@ -0,0 +1,75 @@
+struct Params {
+    src_cols: u32,
+    src_rows: u32,
+    dst_cols: u32,
+    dst_rows: u32,
+    channels: u32,
+    // 0: bilinear, 1: nearest
+    interpolation: u32,
+    _pad0: u32,
+    _pad1: u32,
+    // the rows of the dst -> src matrix
+    m: array<vec4<f32>, 3>,
+}
+
+@group(0) @binding(0) var<uniform> params: Params;
+@group(0) @binding(1) var<storage, read> src: array<f32>;
+@group(0) @binding(2) var<storage, read_write> dst: array<f32>;
+
+fn pixel(x: u32, y: u32, ch: u32) -> f32 {
+    return src[(y * params.src_cols + x) * params.channels + ch];
+}
+
+@compute @workgroup_size(16, 16)
+fn warp_perspective(@builtin(global_invocation_id) id: vec3<u32>) {
+    if id.x >= params.dst_cols || id.y >= params.dst_rows {
+        return;
+    }
+
+    let p = vec3<f32>(f32(id.x), f32(id.y), 1.0);
+    let w = dot(params.m[2].xyz, p);
+    let u = dot(params.m[0].xyz, p) / w;
+    let v = dot(params.m[1].xyz, p) / w;
+
+    // the pixels mapped outside of the source image are left untouched
+    if !(u >= 0.0 && u < f32(params.src_cols) && v >= 0.0 && v < f32(params.src_rows)) {
+        return;
+    }
+    let dst_idx = (id.y * params.dst_cols + id.x) * params.channels;
+
+    if params.interpolation == 1u {
+        let iu = min(u32(floor(u + 0.5)), params.src_cols - 1u);
+        let iv = min(u32(floor(v + 0.5)), params.src_rows - 1u);
+        for (var ch = 0u; ch < params.channels; ch++) {
+            dst[dst_idx + ch] = pixel(iu, iv, ch);
+        }
+        return;
+    }
+
+    // bilinear interpolation repeating the last row and column
+    let iu = u32(u);
+    let iv = u32(v);
+    let iu1 = min(iu + 1u, params.src_cols - 1u);
+    let iv1 = min(iv + 1u, params.src_rows - 1u);
+    let fu = fract(u);
+    let fv = fract(v);
+    for (var ch = 0u; ch < params.channels; ch++) {
+        let v00 = pixel(iu, iv, ch);
+        var v01 = v00;
+        var v10 = v00;
+        var v11 = v00;
+        if iu1 != iu {
+            v01 = pixel(iu1, iv, ch);
+        }
+        if iv1 != iv {
+            v10 = pixel(iu, iv1, ch);
+        }
+        if iu1 != iu && iv1 != iv {
+            v11 = pixel(iu1, iv1, ch);
+        }
+        dst[dst_idx + ch] = v00 * (1.0 - fu) * (1.0 - fv)
+            + v01 * fu * (1.0 - fv)
+            + v10 * (1.0 - fu) * fv
+            + v11 * fu * fv;
+    }
+}
@@ -0,0 +1,145 @@
+use kornia_image::ImageError;
+use kornia_imgproc::interpolation::InterpolationMode;
+
+use crate::{context::Shader, error::GpuError, image::GpuImage};
+
+const SHADER: Shader = Shader {
+    label: "warp",
+    source: include_str!("shaders/warp.wgsl"),
+};
+
+#[repr(C)]
+#[derive(Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+struct Params {
+    src_cols: u32,
+    src_rows: u32,
+    dst_cols: u32,
+    dst_rows: u32,
+    channels: u32,
+    interpolation: u32,
+    _pad: [u32; 2],
+    // the rows of the inverse matrix padded to vec4
+    m: [[f32; 4]; 3],
+}
+
+#[rustfmt::skip]
+fn inverse_perspective_matrix(m: &[f32; 9]) -> Result<[f32; 9], ImageError> {
+    let det = m[0] * (m[4] * m[8] - m[5] * m[7]) -
+              m[1] * (m[3] * m[8] - m[5] * m[6]) +
+              m[2] * (m[3] * m[7] - m[4] * m[6]);
+
+    if det == 0.0 {
+        return Err(ImageError::CannotComputeDeterminant);
+    }
+
+    let adj = [
+        m[4] * m[8] - m[5] * m[7],
+        m[2] * m[7] - m[1] * m[8],
+        m[1] * m[5] - m[2] * m[4],
+        m[5] * m[6] - m[3] * m[8],
+        m[0] * m[8] - m[2] * m[6],
+        m[2] * m[3] - m[0] * m[5],
+        m[3] * m[7] - m[4] * m[6],
+        m[1] * m[6] - m[0] * m[7],
+        m[0] * m[4] - m[1] * m[3],
+    ];
+
+    Ok(adj.map(|x| x / det))
+}
+
+/// Applies a perspective transformation to an image on the GPU.
+///
+/// The pixels of `dst` mapped outside of `src` are left untouched, as
+/// [`kornia_imgproc::warp::warp_perspective`].
+///
+/// # Arguments
+///
+/// * `src` - The input image with shape (height, width, channels).
+/// * `dst` - The output image with shape (new_height, new_width, channels).
+/// * `m` - The 3x3 perspective transformation matrix src -> dst in row-major order.
+/// * `interpolation` - The interpolation mode to use.
+pub fn warp_perspective<const C: usize>(
+    src: &GpuImage<C>,
+    dst: &mut GpuImage<C>,
+    m: &[f32; 9],
+    interpolation: InterpolationMode,
+) -> Result<(), GpuError> {
+    let inv_m = inverse_perspective_matrix(m)?;
+
+    let params = Params {
+        src_cols: src.cols() as u32,
+        src_rows: src.rows() as u32,
+        dst_cols: dst.cols() as u32,
+        dst_rows: dst.rows() as u32,
+        channels: C as u32,
+        interpolation: match interpolation {
+            InterpolationMode::Bilinear => 0,
+            InterpolationMode::Nearest => 1,
+        },
+        _pad: [0; 2],
+        m: [0, 1, 2].map(|row| [inv_m[row * 3], inv_m[row * 3 + 1], inv_m[row * 3 + 2], 0.0]),
+    };
+
+    if src.cols() == 0 || src.rows() == 0 {
+        return Ok(());
+    }
+    src.context().dispatch(
+        &SHADER,
+        "warp_perspective",
+        &params,
+        &[src.buffer(), dst.buffer()],
+        [dst.cols(), dst.rows()],
+    );
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::context::test_context;
+    use approx::assert_relative_eq;
+    use kornia_image::Image;
+
+    #[test]
+    fn test_warp_perspective() -> Result<(), GpuError> {
+        let Some(ctx) = test_context() else {
+            return Ok(());
+        };
+
+        let image = Image::<f32, 3>::new(
+            [20, 15].into(),
+            (0..20 * 15 * 3).map(|x| (x % 17) as f32).collect(),
+        )?;
+        let src = GpuImage::upload(&ctx, &image);
+
+        // shear, scale and translation partly outside of the image
+        let m = [0.9, -0.3, 4.3, 0.0, 1.1, -2.2, 0.0, 0.0, 1.0];
+        for interpolation in [InterpolationMode::Bilinear, InterpolationMode::Nearest] {
+            let mut expected = Image::from_size_val([18, 16].into(), 0.0)?;
+            kornia_imgproc::warp::warp_perspective(&image, &mut expected, &m, interpolation)?;
+
+            let mut dst = GpuImage::zeros(&ctx, expected.size());
+            warp_perspective(&src, &mut dst, &m, interpolation)?;
+
+            for (a, b) in dst.download()?.as_slice().iter().zip(expected.as_slice()) {
+                assert_relative_eq!(a, b, epsilon = 1e-3);
+            }
+        }
+
+        // a projective transformation
+        let m = [1.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.01, 0.02, 1.0];
+        let mut dst = GpuImage::zeros(&ctx, image.size());
+        warp_perspective(&src, &mut dst, &m, InterpolationMode::Nearest)?;
+        let dst = dst.download()?;
+        let (u, v) = (10.0f32, 5.0f32);
+        let w = 1.0 - 0.01 * u - 0.02 * v;
+        let idx = ((v / w).round() as usize * 20 + (u / w).round() as usize) * 3;
+        assert_eq!(dst.as_slice()[(5 * 20 + 10) * 3], image.as_slice()[idx]);
+
+        let mut dst = GpuImage::zeros(&ctx, image.size());
+        assert!(warp_perspective(&src, &mut dst, &[0.0; 9], InterpolationMode::Nearest).is_err());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,303 @@
+use faer::prelude::SpSolver;
+
+use crate::icp_vanilla::{ICPConvergenceCriteria, ICPResult};
+use crate::ops::{fit_transformation, mad_threshold};
+use kornia_3d::{
+    kdtree::KdTree,
+    linalg::{cross_vec3, dot_product3, matmul33, transform_points3d},
+    pointcloud::PointCloud,
+    transforms::axis_angle_to_rotation_matrix,
+};
+
+/// The error metric minimized by the ICP registration.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ICPMethod {
+    /// Minimize the distance between the matched points.
+    #[default]
+    PointToPoint,
+    /// Minimize the distance between the source points and the tangent planes of the target.
+    ///
+    /// Requires the normals of the target point cloud and converges faster on smooth surfaces.
+    PointToPlane,
+}
+
+/// Structure to define the parameters of the ICP registration.
+#[derive(Debug, Clone)]
+pub struct ICPRegistrationParams {
+    /// The error metric to minimize.
+    pub method: ICPMethod,
+    /// Convergence criteria.
+    pub criteria: ICPConvergenceCriteria,
+    /// The maximum distance between two points to be considered a correspondence.
+    pub max_correspondence_distance: f64,
+}
+
+impl Default for ICPRegistrationParams {
+    fn default() -> Self {
+        Self {
+            method: ICPMethod::PointToPoint,
+            criteria: ICPConvergenceCriteria {
+                max_iterations: 50,
+                tolerance: 1e-6,
+            },
+            max_correspondence_distance: f64::INFINITY,
+        }
+    }
+}
+
+/// Iterative Closest Point (ICP) registration using point to point or point to plane distance.
+///
+/// The correspondences are found with a KD-tree over the target points. The pairs farther
+/// than the maximum correspondence distance or identified as outliers by the median absolute
+/// deviation of the distances are rejected. The point to plane error is minimized with the
+/// small angle linearization of the rotation.
+///
+/// # Arguments
+///
+/// * `source` - Source point cloud.
+/// * `target` - Target point cloud, with normals for the point to plane method.
+/// * `initial_rot` - Initial rotation matrix from the source to the target frame.
+/// * `initial_trans` - Initial translation vector from the source to the target frame.
+/// * `params` - The registration parameters.
+///
+/// # Returns
+///
+/// The transformation from the source to the target frame, the inlier RMSE and the fitness.
+pub fn icp_registration(
+    source: &PointCloud,
+    target: &PointCloud,
+    initial_rot: [[f64; 3]; 3],
+    initial_trans: [f64; 3],
+    params: &ICPRegistrationParams,
+) -> Result<ICPResult, Box<dyn std::error::Error>> {
+    let target_normals = match params.method {
+        ICPMethod::PointToPoint => None,
+        ICPMethod::PointToPlane => Some(
+            target
+                .normals()
+                .ok_or("the point to plane method requires the target normals")?,
+        ),
+    };
+
+    if source.is_empty() || target.is_empty() {
+        return Err("the point clouds must not be empty".into());
+    }
+
+    let mut result = ICPResult {
+        rotation: initial_rot,
+        translation: initial_trans,
+        num_iterations: 0,
+        rmse: f64::INFINITY,
+        fitness: 0.0,
+    };
+
+    // build kdtree for target points to speed up the nearest neighbor search
+    let kdtree = KdTree::new(target.points());
+    let max_dist_sq = params.max_correspondence_distance.powi(2);
+
+    let mut current_source = vec![[0.0; 3]; source.len()];
+
+    for i in 0..params.criteria.max_iterations {
+        transform_points3d(
+            source.points(),
+            &result.rotation,
+            &result.translation,
+            &mut current_source,
+        )?;
+
+        // find the closest target point for each source point
+        let mut matches = current_source
+            .iter()
+            .enumerate()
+            .filter_map(|(src_idx, p)| {
+                let nn = kdtree.nearest(p)?;
+                (nn.distance_sq <= max_dist_sq).then_some((src_idx, nn.index, nn.distance_sq))
+            })
+            .collect::<Vec<_>>();
+
+        if !matches.is_empty() {
+            let distances = matches.iter().map(|m| m.2).collect::<Vec<_>>();
+            let max_distance = mad_threshold(&distances);
+            matches.retain(|m| m.2 <= max_distance);
+        }
+
+        let min_matches = match params.method {
+            ICPMethod::PointToPoint => 3,
+            ICPMethod::PointToPlane => 6,
+        };
+        if matches.len() < min_matches {
+            return Err(format!("not enough correspondences: {}", matches.len()).into());
+        }
+
+        let rmse = (matches.iter().map(|m| m.2).sum::<f64>() / matches.len() as f64).sqrt();
+        result.fitness = matches.len() as f64 / source.len() as f64;
+
+        // compute the incremental transformation
+        let mut rr_delta = [[0.0; 3]; 3];
+        let mut tt_delta = [0.0; 3];
+        match target_normals {
+            None => {
+                let (src_match, dst_match): (Vec<_>, Vec<_>) = matches
+                    .iter()
+                    .map(|&(s, t, _)| (current_source[s], target.points()[t]))
+                    .unzip();
+                fit_transformation(&src_match, &dst_match, &mut rr_delta, &mut tt_delta);
+            }
+            Some(normals) => fit_point_to_plane(
+                &current_source,
+                target.points(),
+                normals,
+                &matches,
+                &mut rr_delta,
+                &mut tt_delta,
+            )?,
+        }
+
+        // compose the transformations as R = R_delta * R and t = R_delta * t + t_delta
+        let rotation = result.rotation;
+        matmul33(&rr_delta, &rotation, &mut result.rotation);
+        let t = result.translation;
+        for k in 0..3 {
+            result.translation[k] = dot_product3(&rr_delta[k], &t) + tt_delta[k];
+        }
+
+        result.num_iterations += 1;
+
+        // check convergence and exit if below tolerance
+        let converged = (result.rmse - rmse).abs() < params.criteria.tolerance;
+        result.rmse = rmse;
+        if converged {
+            log::debug!("ICP converged in {} iterations with error {}", i, rmse);
+            break;
+        }
+    }
+
+    Ok(result)
+}
+
+// solve the linearized point to plane problem for the incremental transformation
+fn fit_point_to_plane(
+    source: &[[f64; 3]],
+    target: &[[f64; 3]],
+    normals: &[[f64; 3]],
+    matches: &[(usize, usize, f64)],
+    rr_delta: &mut [[f64; 3]; 3],
+    tt_delta: &mut [f64; 3],
+) -> Result<(), Box<dyn std::error::Error>> {
+    // accumulate the normal equations A^T A x = A^T b with x = [omega, t]
+    let mut ata = faer::Mat::<f64>::zeros(6, 6);
+    let mut atb = faer::Mat::<f64>::zeros(6, 1);
+
+    for &(s, t, _) in matches {
+        let (p, q, n) = (&source[s], &target[t], &normals[t]);
+        let mut pxn = [0.0; 3];
+        cross_vec3(p, n, &mut pxn);
+
+        let a = [pxn[0], pxn[1], pxn[2], n[0], n[1], n[2]];
+        let b = dot_product3(&[q[0] - p[0], q[1] - p[1], q[2] - p[2]], n);
+
+        for i in 0..6 {
+            for j in 0..6 {
+                ata.write(i, j, ata.read(i, j) + a[i] * a[j]);
+            }
+            atb.write(i, 0, atb.read(i, 0) + a[i] * b);
+        }
+    }
+
+    let x = ata.partial_piv_lu().solve(&atb);
+    let omega = [x.read(0, 0), x.read(1, 0), x.read(2, 0)];
+    *tt_delta = [x.read(3, 0), x.read(4, 0), x.read(5, 0)];
+
+    if omega.iter().chain(tt_delta.iter()).any(|v| !v.is_finite()) {
+        return Err("degenerate point to plane system".into());
+    }
+
+    let angle = dot_product3(&omega, &omega).sqrt();
+    *rr_delta = if angle < 1e-12 {
+        [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]]
+    } else {
+        axis_angle_to_rotation_matrix(&omega, angle)?
+    };
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    // points on three faces of a cube with their normals
+    fn cube_corner(step: f64, num: usize) -> PointCloud {
+        let mut points = Vec::new();
+        let mut normals = Vec::new();
+        for i in 0..num {
+            for j in 0..num {
+                let (a, b) = (i as f64 * step, j as f64 * step);
+                points.extend([[a, b, 0.0], [a, 0.0, b], [0.0, a, b]]);
+                normals.extend([[0.0, 0.0, 1.0], [0.0, 1.0, 0.0], [1.0, 0.0, 0.0]]);
+            }
+        }
+        PointCloud::new(points, None, Some(normals))
+    }
+
+    fn check_registration(method: ICPMethod) -> Result<(), Box<dyn std::error::Error>> {
+        let target = cube_corner(0.05, 20);
+
+        // the source is the target seen from a slightly different pose
+        let dst_r_src = axis_angle_to_rotation_matrix(&[0.3, -0.5, 1.0], 0.05)?;
+        let dst_t_src = [0.02, -0.01, 0.015];
+
+        // src = R^T * (dst - t)
+        let mut src_r_dst = [[0.0; 3]; 3];
+        kornia_3d::linalg::transpose_mat33(&dst_r_src, &mut src_r_dst);
+        let mut src_t_dst = [0.0; 3];
+        kornia_3d::linalg::mat33_mul_vec3(&src_r_dst, &dst_t_src, &mut src_t_dst);
+        let source = target.transform(&src_r_dst, &src_t_dst.map(|v| -v));
+
+        let params = ICPRegistrationParams {
+            method,
+            criteria: ICPConvergenceCriteria {
+                max_iterations: 100,
+                tolerance: 1e-12,
+            },
+            max_correspondence_distance: 0.2,
+        };
+        let identity = [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]];
+        let result = icp_registration(&source, &target, identity, [0.0; 3], &params)?;
+
+        for (t, t_expected) in result.translation.iter().zip(dst_t_src.iter()) {
+            assert_relative_eq!(t, t_expected, epsilon = 1e-4);
+        }
+        for (row, row_expected) in result.rotation.iter().zip(dst_r_src.iter()) {
+            for (r, r_expected) in row.iter().zip(row_expected.iter()) {
+                assert_relative_eq!(r, r_expected, epsilon = 1e-4);
+            }
+        }
+        assert!(result.rmse < 1e-4);
+        assert!(result.fitness > 0.9);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_icp_point_to_point() -> Result<(), Box<dyn std::error::Error>> {
+        check_registration(ICPMethod::PointToPoint)
+    }
+
+    #[test]
+    fn test_icp_point_to_plane() -> Result<(), Box<dyn std::error::Error>> {
+        check_registration(ICPMethod::PointToPlane)
+    }
+
+    #[test]
+    fn test_icp_point_to_plane_requires_normals() {
+        let cloud = PointCloud::new(vec![[0.0; 3]; 10], None, None);
+        let params = ICPRegistrationParams {
+            method: ICPMethod::PointToPlane,
+            ..Default::default()
+        };
+        let identity = [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]];
+        assert!(icp_registration(&cloud, &cloud, identity, [0.0; 3], &params).is_err());
+    }
+}
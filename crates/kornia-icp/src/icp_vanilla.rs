@@ -1,9 +1,7 @@
 use core::f64;
 
-use kiddo::immutable::float::kdtree::ImmutableKdTree;
-
 use crate::ops::{find_correspondences, fit_transformation, update_transformation};
-use kornia_3d::{linalg::transform_points3d, pointcloud::PointCloud};
+use kornia_3d::{kdtree::KdTree, linalg::transform_points3d, pointcloud::PointCloud};
 
 /// Result of the ICP algorithm.
 ///
@@ -18,6 +16,8 @@ pub struct ICPResult {
     pub num_iterations: usize,
     /// last computed RMSE.
     pub rmse: f64,
+    /// The ratio of source points with a correspondence in the last iteration.
+    pub fitness: f64,
 }
 
 /// Structure to define the ICP parameters.
@@ -55,10 +55,11 @@ pub fn icp_vanilla(
         translation: initial_trans,
         num_iterations: 0,
         rmse: f64::INFINITY,
+        fitness: 0.0,
     };
 
     // build kdtree for target points to speed up the nearest neighbor search
-    let kdtree: ImmutableKdTree<f64, u32, 3, 32> = ImmutableKdTree::new_from_slice(target.points());
+    let kdtree = KdTree::new(target.points());
 
     // perform transformation using the initial rotation and translation
     let mut transformed_points = vec![[0.0; 3]; source.points().len()];
@@ -122,6 +123,7 @@ pub fn icp_vanilla(
 
         // update the result structure
         result.num_iterations += 1;
+        result.fitness = distances.len() as f64 / current_source.len() as f64;
 
         // check convergence and exit if below tolerance
         if (result.rmse - rmse).abs() < criteria.tolerance {
@@ -151,16 +153,18 @@ mod tests {
         linalg::transform_points3d, pointcloud::PointCloud,
         transforms::axis_angle_to_rotation_matrix,
     };
+    use rand::{rngs::StdRng, Rng, SeedableRng};
 
     #[test]
     fn test_icp_vanilla() -> Result<(), Box<dyn std::error::Error>> {
         let num_points = 100;
+        let mut rng = StdRng::seed_from_u64(0);
         let points_src = (0..num_points)
             .map(|_| {
                 [
-                    rand::random::<f64>(),
-                    rand::random::<f64>(),
-                    rand::random::<f64>(),
+                    rng.random::<f64>(),
+                    rng.random::<f64>(),
+                    rng.random::<f64>(),
                 ]
             })
             .collect::<Vec<_>>();
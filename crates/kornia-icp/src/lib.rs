@@ -4,4 +4,7 @@
 mod icp_vanilla;
 pub use icp_vanilla::*;
 
+mod icp_registration;
+pub use icp_registration::*;
+
 mod ops;
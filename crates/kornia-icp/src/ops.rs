@@ -1,5 +1,4 @@
-use kiddo::immutable::float::kdtree::ImmutableKdTree;
-use kornia_3d::linalg;
+use kornia_3d::{kdtree::KdTree, linalg};
 
 /// Compute the transformation between two point clouds.
 pub(crate) fn fit_transformation(
@@ -83,33 +82,27 @@ pub(crate) fn compute_centroids(
 pub(crate) fn find_correspondences(
     source: &[[f64; 3]],
     target: &[[f64; 3]],
-    kdtree: &ImmutableKdTree<f64, u32, 3, 32>,
+    kdtree: &KdTree<3>,
 ) -> (Vec<[f64; 3]>, Vec<[f64; 3]>, Vec<f64>) {
     // find nearest neighbors for each point in source
     let nn_results = source
         .iter()
-        .map(|p| kdtree.nearest_one::<kiddo::SquaredEuclidean>(p))
+        .enumerate()
+        .filter_map(|(i, p)| kdtree.nearest(p).map(|nn| (i, nn)))
         .collect::<Vec<_>>();
 
-    // compute median distance
-    let mut distances = nn_results.iter().map(|nn| nn.distance).collect::<Vec<_>>();
-    distances.sort_by(|a, b| a.partial_cmp(b).unwrap());
-    let median_dist = distances[distances.len() / 2];
-
-    // compute median absolute deviation
-    let dmed = distances
+    // reject the outliers with the median absolute deviation of the distances
+    let distances = nn_results
         .iter()
-        .map(|d| (d - median_dist).abs())
+        .map(|(_, nn)| nn.distance_sq)
         .collect::<Vec<_>>();
-    let mad = dmed[dmed.len() / 2];
-    let sigma_d = 1.4826 * mad;
+    let max_distance = mad_threshold(&distances);
 
     // put the correspondences in a vector
     let res = nn_results
         .iter()
-        .enumerate()
-        .filter(|(_, nn)| nn.distance <= median_dist + 3.0 * sigma_d)
-        .map(|(i, nn)| (source[i], target[nn.item as usize], nn.distance))
+        .filter(|(_, nn)| nn.distance_sq <= max_distance)
+        .map(|&(i, nn)| (source[i], target[nn.index], nn.distance_sq))
         .collect::<Vec<_>>();
 
     // unzip the results to separate points and distances
@@ -120,6 +113,31 @@ pub(crate) fn find_correspondences(
     (points_in_src, points_in_dst, distances)
 }
 
+/// Compute the inlier threshold of a set of distances as the median plus three times the
+/// standard deviation estimated with the median absolute deviation.
+///
+/// An empty set of distances has no threshold and returns infinity.
+pub(crate) fn mad_threshold(distances: &[f64]) -> f64 {
+    if distances.is_empty() {
+        return f64::INFINITY;
+    }
+
+    // compute median distance
+    let mut distances = distances.to_vec();
+    let mid = distances.len() / 2;
+    let median_dist = *distances.select_nth_unstable_by(mid, f64::total_cmp).1;
+
+    // compute median absolute deviation
+    let mut dmed = distances
+        .iter()
+        .map(|d| (d - median_dist).abs())
+        .collect::<Vec<_>>();
+    let mad = *dmed.select_nth_unstable_by(mid, f64::total_cmp).1;
+    let sigma_d = 1.4826 * mad;
+
+    median_dist + 3.0 * sigma_d
+}
+
 pub(crate) fn update_transformation(
     rr: &mut [[f64; 3]; 3],
     tt: &mut [f64; 3],
@@ -139,38 +157,41 @@ pub(crate) fn update_transformation(
 mod tests {
     use super::*;
     use approx::assert_relative_eq;
-    use kiddo::immutable::float::kdtree::ImmutableKdTree;
     use kornia_3d::{linalg::transform_points3d, transforms::axis_angle_to_rotation_matrix};
+    use rand::{rngs::StdRng, Rng, SeedableRng};
 
-    fn create_random_points(num_points: usize) -> Vec<[f64; 3]> {
+    fn create_random_points(rng: &mut StdRng, num_points: usize) -> Vec<[f64; 3]> {
         (0..num_points)
             .map(|_| {
                 [
-                    rand::random::<f64>(),
-                    rand::random::<f64>(),
-                    rand::random::<f64>(),
+                    rng.random::<f64>(),
+                    rng.random::<f64>(),
+                    rng.random::<f64>(),
                 ]
             })
             .collect()
     }
 
-    fn create_random_rotation(factor: f64) -> Result<[[f64; 3]; 3], &'static str> {
+    fn create_random_rotation(
+        rng: &mut StdRng,
+        factor: f64,
+    ) -> Result<[[f64; 3]; 3], &'static str> {
         let (axis, angle) = (
             [
-                rand::random::<f64>(),
-                rand::random::<f64>(),
-                rand::random::<f64>(),
+                rng.random::<f64>(),
+                rng.random::<f64>(),
+                rng.random::<f64>(),
             ],
-            rand::random::<f64>() * factor,
+            rng.random::<f64>() * factor,
         );
         axis_angle_to_rotation_matrix(&axis, angle)
     }
 
-    fn create_random_translation(factor: f64) -> [f64; 3] {
+    fn create_random_translation(rng: &mut StdRng, factor: f64) -> [f64; 3] {
         [
-            rand::random::<f64>() * factor,
-            rand::random::<f64>() * factor,
-            rand::random::<f64>() * factor,
+            rng.random::<f64>() * factor,
+            rng.random::<f64>() * factor,
+            rng.random::<f64>() * factor,
         ]
     }
 
@@ -190,7 +211,8 @@ mod tests {
     #[test]
     fn test_fit_transformation_identity() {
         let num_points = 30;
-        let points_src = create_random_points(num_points);
+        let mut rng = StdRng::seed_from_u64(0);
+        let points_src = create_random_points(&mut rng, num_points);
         let points_dst = points_src.clone();
 
         let expected_rotation = [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]];
@@ -214,7 +236,8 @@ mod tests {
     #[test]
     fn test_fit_transformation_rotation() -> Result<(), Box<dyn std::error::Error>> {
         let num_points = 30;
-        let points_src = create_random_points(num_points);
+        let mut rng = StdRng::seed_from_u64(0);
+        let points_src = create_random_points(&mut rng, num_points);
 
         let expected_rotation =
             axis_angle_to_rotation_matrix(&[1.0, 0.0, 0.0], std::f64::consts::PI / 2.0)?;
@@ -252,12 +275,13 @@ mod tests {
         let translation_factor = 0.1;
         let rotation_factor = 0.1;
 
-        let points_src = create_random_points(num_points);
+        let mut rng = StdRng::seed_from_u64(0);
+        let points_src = create_random_points(&mut rng, num_points);
 
         for _ in 0..num_test {
             // create random rotation and translation
-            let expected_rotation = create_random_rotation(rotation_factor)?;
-            let expected_translation = create_random_translation(translation_factor);
+            let expected_rotation = create_random_rotation(&mut rng, rotation_factor)?;
+            let expected_translation = create_random_translation(&mut rng, translation_factor);
 
             // transform points
             let mut points_dst = vec![[0.0; 3]; num_points];
@@ -295,7 +319,7 @@ mod tests {
         ];
         let points_dst = vec![[1.0, 0.0, 0.0], [1.0, 1.0, 0.0]];
 
-        let kdtree = ImmutableKdTree::new_from_slice(&points_dst);
+        let kdtree = KdTree::new(&points_dst);
 
         let (points_in_src, points_in_dst, distances) =
             find_correspondences(&points_src, &points_dst, &kdtree);
@@ -309,4 +333,17 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_mad_threshold() {
+        assert_eq!(mad_threshold(&[]), f64::INFINITY);
+
+        // median 2.0, absolute deviations [1, 1, 0, 0, 8] with median 1.0
+        let threshold = mad_threshold(&[1.0, 3.0, 2.0, 2.0, 10.0]);
+        assert_relative_eq!(threshold, 2.0 + 3.0 * 1.4826);
+
+        // a nan distance does not panic and sorts above every finite distance
+        let threshold = mad_threshold(&[1.0, 2.0, 3.0, f64::NAN, 0.5]);
+        assert_relative_eq!(threshold, 2.0 + 3.0 * 1.4826);
+    }
 }
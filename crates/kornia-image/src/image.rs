@@ -1,4 +1,6 @@
-use kornia_tensor::{CpuAllocator, Tensor, Tensor2, Tensor3};
+use alloc::{vec, vec::Vec};
+
+use kornia_tensor::{tensor::get_numel_from_shape, CpuAllocator, Tensor, Tensor2, Tensor3};
 
 use crate::error::ImageError;
 
@@ -20,6 +22,7 @@ use crate::error::ImageError;
 /// assert_eq!(image_size.height, 20);
 /// ```
 #[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ImageSize {
     /// Width of the image in pixels
     pub width: usize,
@@ -27,8 +30,8 @@ pub struct ImageSize {
     pub height: usize,
 }
 
-impl std::fmt::Display for ImageSize {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+impl core::fmt::Display for ImageSize {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
         write!(
             f,
             "ImageSize {{ width: {}, height: {} }}",
@@ -59,7 +62,7 @@ impl From<ImageSize> for [u32; 2] {
 pub struct Image<T, const C: usize>(pub Tensor3<T, CpuAllocator>);
 
 /// helper to deference the inner tensor
-impl<T, const C: usize> std::ops::Deref for Image<T, C> {
+impl<T, const C: usize> core::ops::Deref for Image<T, C> {
     type Target = Tensor3<T, CpuAllocator>;
 
     // Define the deref method to return a reference to the inner Tensor3<T>.
@@ -69,7 +72,7 @@ impl<T, const C: usize> std::ops::Deref for Image<T, C> {
 }
 
 /// helper to deference the inner tensor
-impl<T, const C: usize> std::ops::DerefMut for Image<T, C> {
+impl<T, const C: usize> core::ops::DerefMut for Image<T, C> {
     // Define the deref_mut method to return a mutable reference to the inner Tensor3<T>.
     fn deref_mut(&mut self) -> &mut Self::Target {
         &mut self.0
@@ -114,11 +117,9 @@ impl<T, const C: usize> Image<T, C> {
         T: Clone, // TODO: remove this bound
     {
         // check if the data length matches the image size
-        if data.len() != size.width * size.height * C {
-            return Err(ImageError::InvalidChannelShape(
-                data.len(),
-                size.width * size.height * C,
-            ));
+        let numel = get_numel_from_shape([size.height, size.width, C])?;
+        if data.len() != numel {
+            return Err(ImageError::InvalidChannelShape(data.len(), numel));
         }
 
         // allocate the image data
@@ -163,7 +164,8 @@ impl<T, const C: usize> Image<T, C> {
     where
         T: Clone + Default,
     {
-        let data = vec![val; size.width * size.height * C];
+        let numel = get_numel_from_shape([size.height, size.width, C])?;
+        let data = vec![val; numel];
         let image = Image::new(size, data)?;
 
         Ok(image)
@@ -175,7 +177,7 @@ impl<T, const C: usize> Image<T, C> {
     ///
     /// * `size` - The size of the image in pixels.
     /// * `data` - A pointer to the pixel data.
-    /// * `len` - The length of the pixel data.
+    /// * `len` - The length of the pixel data in bytes, i.e. `width * height * C * size_of::<T>()`.
     ///
     /// # Returns
     ///
@@ -294,7 +296,7 @@ impl<T, const C: usize> Image<T, C> {
     ///
     /// # Returns
     ///
-    /// A vector of images, each containing one channel of the original image.
+    /// An array of images, each containing one channel of the original image.
     ///
     /// # Examples
     ///
@@ -311,7 +313,7 @@ impl<T, const C: usize> Image<T, C> {
     /// let channels = image.split_channels().unwrap();
     /// assert_eq!(channels.len(), 2);
     /// ```
-    pub fn split_channels(&self) -> Result<Vec<Image<T, 1>>, ImageError>
+    pub fn split_channels(&self) -> Result<[Image<T, 1>; C], ImageError>
     where
         T: Clone + Copy, // TODO: remove this bound
     {
@@ -321,7 +323,52 @@ impl<T, const C: usize> Image<T, C> {
             channels.push(self.channel(i)?);
         }
 
-        Ok(channels)
+        channels
+            .try_into()
+            .map_err(|_| ImageError::InvalidChannelShape(0, C))
+    }
+
+    /// Create a new image by merging single channel images.
+    ///
+    /// # Arguments
+    ///
+    /// * `channels` - The single channel images to merge, one per output channel.
+    ///
+    /// # Returns
+    ///
+    /// A new image with the channels interleaved.
+    ///
+    /// # Errors
+    ///
+    /// If the channel images do not have the same size, an error is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use kornia_image::{Image, ImageSize};
+    ///
+    /// let size = ImageSize { width: 2, height: 1 };
+    ///
+    /// let r = Image::<u8, 1>::new(size, vec![0, 1]).unwrap();
+    /// let g = Image::<u8, 1>::new(size, vec![2, 3]).unwrap();
+    /// let b = Image::<u8, 1>::new(size, vec![4, 5]).unwrap();
+    ///
+    /// let rgb = Image::<u8, 3>::from_channels(&[r, g, b]).unwrap();
+    /// assert_eq!(rgb.as_slice(), &[0, 2, 4, 1, 3, 5]);
+    /// ```
+    pub fn from_channels(channels: &[Image<T, 1>; C]) -> Result<Self, ImageError>
+    where
+        T: Copy + Default,
+    {
+        let size = match channels.first() {
+            Some(channel) => channel.size(),
+            None => return Err(ImageError::ImageDataNotInitialized),
+        };
+
+        let mut image = Self::from_size_val(size, T::default())?;
+        crate::ops::merge_channels(channels, &mut image)?;
+
+        Ok(image)
     }
 
     /// Get the size of the image in pixels.
@@ -392,7 +439,7 @@ impl<T, const C: usize> Image<T, C> {
     /// ```
     pub fn cast_and_scale<U>(self, scale: U) -> Result<Image<U, C>, ImageError>
     where
-        U: num_traits::NumCast + std::ops::Mul<Output = U> + Clone + Copy,
+        U: num_traits::NumCast + core::ops::Mul<Output = U> + Clone + Copy,
         T: num_traits::NumCast + Clone + Copy,
     {
         let casted_data = self
@@ -419,7 +466,7 @@ impl<T, const C: usize> Image<T, C> {
     pub fn scale_and_cast<U>(&self, scale: T) -> Result<Image<U, C>, ImageError>
     where
         U: num_traits::NumCast + Clone + Copy,
-        T: num_traits::NumCast + std::ops::Mul<Output = T> + Clone + Copy,
+        T: num_traits::NumCast + core::ops::Mul<Output = T> + Clone + Copy,
     {
         let casted_data = self
             .as_slice()
@@ -546,7 +593,8 @@ impl<T, const C: usize> TryInto<Tensor3<T, CpuAllocator>> for Image<T, C> {
 #[cfg(test)]
 mod tests {
     use crate::image::{Image, ImageError, ImageSize};
-    use kornia_tensor::{CpuAllocator, Tensor};
+    use alloc::vec;
+    use kornia_tensor::{CpuAllocator, Tensor, TensorError};
 
     #[test]
     fn test_image_size() {
@@ -659,6 +707,22 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_image_from_channels() -> Result<(), ImageError> {
+        let image = Image::<f32, 3>::new(
+            ImageSize {
+                height: 2,
+                width: 1,
+            },
+            vec![0., 1., 2., 3., 4., 5.],
+        )?;
+        let channels = image.split_channels()?;
+        let merged = Image::<f32, 3>::from_channels(&channels)?;
+        assert_eq!(merged.as_slice(), image.as_slice());
+
+        Ok(())
+    }
+
     #[test]
     fn test_scale_and_cast() -> Result<(), ImageError> {
         let data = vec![0u8, 0, 255, 0, 0, 255];
@@ -735,13 +799,39 @@ mod tests {
         let data = vec![0u8, 1, 2, 3, 4, 5];
         let image =
             unsafe { Image::<_, 1>::from_raw_parts([2, 3].into(), data.as_ptr(), data.len())? };
-        std::mem::forget(data);
+        core::mem::forget(data);
         assert_eq!(image.size().width, 2);
         assert_eq!(image.size().height, 3);
         assert_eq!(image.num_channels(), 1);
         Ok(())
     }
 
+    #[test]
+    fn test_image_from_raw_parts_f32() -> Result<(), ImageError> {
+        let data = vec![0.0f32, 0.25, 0.5, 1.0];
+        let len = data.len() * core::mem::size_of::<f32>();
+        let image = unsafe { Image::<_, 1>::from_raw_parts([2, 2].into(), data.as_ptr(), len)? };
+        core::mem::forget(data);
+        assert_eq!(image.size().width, 2);
+        assert_eq!(image.size().height, 2);
+        assert_eq!(image.as_slice(), &[0.0, 0.25, 0.5, 1.0]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_image_size_overflow() {
+        let size = ImageSize {
+            width: usize::MAX / 2,
+            height: 3,
+        };
+        assert!(matches!(
+            Image::<u8, 1>::new(size, vec![]),
+            Err(ImageError::InvalidImageShape(TensorError::ShapeOverflow(_)))
+        ));
+        assert!(Image::<u8, 3>::from_size_slice([usize::MAX, 1].into(), &[]).is_err());
+        assert!(Image::<u8, 3>::from_size_val([usize::MAX, usize::MAX].into(), 0).is_err());
+    }
+
     #[test]
     fn test_get_pixel() -> Result<(), ImageError> {
         let image = Image::<u8, 3>::new(
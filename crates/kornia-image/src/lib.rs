@@ -1,6 +1,9 @@
+#![cfg_attr(not(feature = "std"), no_std)]
 #![deny(missing_docs)]
 #![doc = env!("CARGO_PKG_DESCRIPTION")]
 
+extern crate alloc;
+
 /// image representation for computer vision purposes.
 pub mod image;
 
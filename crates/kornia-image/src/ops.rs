@@ -1,3 +1,5 @@
+use half::{f16, slice::HalfFloatSliceExt};
+
 use crate::{Image, ImageError};
 
 /// Cast the pixel data of an image to a different type.
@@ -36,7 +38,7 @@ pub fn cast_and_scale<T, U, const C: usize>(
 ) -> Result<(), ImageError>
 where
     T: Copy + num_traits::NumCast,
-    U: Copy + num_traits::NumCast + std::ops::Mul<U, Output = U>,
+    U: Copy + num_traits::NumCast + core::ops::Mul<U, Output = U>,
 {
     if src.size() != dst.size() {
         return Err(ImageError::InvalidImageSize(
@@ -59,10 +61,336 @@ where
     Ok(())
 }
 
+/// Split a multi-channel image into single channel images.
+///
+/// # Arguments
+///
+/// * `src` - The source image with shape (H, W, C).
+/// * `dst` - The destination images with shape (H, W, 1), one per channel.
+///
+/// Example:
+///
+/// ```
+/// use kornia_image::{Image, ImageSize};
+/// use kornia_image::ops::split_channels;
+///
+/// let image = Image::<u8, 3>::new(
+///   ImageSize {
+///     width: 2,
+///     height: 1,
+///   },
+///   vec![0, 1, 2, 3, 4, 5],
+/// ).unwrap();
+///
+/// let mut channels = [
+///   Image::from_size_val(image.size(), 0u8).unwrap(),
+///   Image::from_size_val(image.size(), 0u8).unwrap(),
+///   Image::from_size_val(image.size(), 0u8).unwrap(),
+/// ];
+///
+/// split_channels(&image, &mut channels).unwrap();
+///
+/// assert_eq!(channels[0].as_slice(), &[0, 3]);
+/// assert_eq!(channels[1].as_slice(), &[1, 4]);
+/// assert_eq!(channels[2].as_slice(), &[2, 5]);
+/// ```
+pub fn split_channels<T, const C: usize>(
+    src: &Image<T, C>,
+    dst: &mut [Image<T, 1>; C],
+) -> Result<(), ImageError>
+where
+    T: Copy,
+{
+    for (ch, dst_channel) in dst.iter_mut().enumerate() {
+        extract_channel(src, dst_channel, ch)?;
+    }
+
+    Ok(())
+}
+
+/// Merge single channel images into a multi-channel image.
+///
+/// # Arguments
+///
+/// * `src` - The source images with shape (H, W, 1), one per channel.
+/// * `dst` - The destination image with shape (H, W, C).
+///
+/// Example:
+///
+/// ```
+/// use kornia_image::{Image, ImageSize};
+/// use kornia_image::ops::merge_channels;
+///
+/// let size = ImageSize {
+///   width: 2,
+///   height: 1,
+/// };
+///
+/// let channels = [
+///   Image::<u8, 1>::new(size, vec![0, 3]).unwrap(),
+///   Image::<u8, 1>::new(size, vec![1, 4]).unwrap(),
+///   Image::<u8, 1>::new(size, vec![2, 5]).unwrap(),
+/// ];
+///
+/// let mut image = Image::<u8, 3>::from_size_val(size, 0).unwrap();
+///
+/// merge_channels(&channels, &mut image).unwrap();
+///
+/// assert_eq!(image.as_slice(), &[0, 1, 2, 3, 4, 5]);
+/// ```
+pub fn merge_channels<T, const C: usize>(
+    src: &[Image<T, 1>; C],
+    dst: &mut Image<T, C>,
+) -> Result<(), ImageError>
+where
+    T: Copy,
+{
+    for src_channel in src.iter() {
+        if src_channel.size() != dst.size() {
+            return Err(ImageError::InvalidImageSize(
+                src_channel.width(),
+                src_channel.height(),
+                dst.width(),
+                dst.height(),
+            ));
+        }
+    }
+
+    for (ch, src_channel) in src.iter().enumerate() {
+        dst.as_slice_mut()
+            .chunks_exact_mut(C)
+            .zip(src_channel.as_slice().iter())
+            .for_each(|(dst_pixel, &val)| {
+                dst_pixel[ch] = val;
+            });
+    }
+
+    Ok(())
+}
+
+/// Extract a single channel from a multi-channel image.
+///
+/// # Arguments
+///
+/// * `src` - The source image with shape (H, W, C).
+/// * `dst` - The destination image with shape (H, W, 1).
+/// * `channel` - The index of the channel to extract.
+///
+/// Example:
+///
+/// ```
+/// use kornia_image::{Image, ImageSize};
+/// use kornia_image::ops::extract_channel;
+///
+/// let image = Image::<u8, 3>::new(
+///   ImageSize {
+///     width: 2,
+///     height: 1,
+///   },
+///   vec![0, 1, 2, 3, 4, 5],
+/// ).unwrap();
+///
+/// let mut green = Image::<u8, 1>::from_size_val(image.size(), 0).unwrap();
+///
+/// extract_channel(&image, &mut green, 1).unwrap();
+///
+/// assert_eq!(green.as_slice(), &[1, 4]);
+/// ```
+pub fn extract_channel<T, const C: usize>(
+    src: &Image<T, C>,
+    dst: &mut Image<T, 1>,
+    channel: usize,
+) -> Result<(), ImageError>
+where
+    T: Copy,
+{
+    if channel >= C {
+        return Err(ImageError::ChannelIndexOutOfBounds(channel, C));
+    }
+
+    if src.size() != dst.size() {
+        return Err(ImageError::InvalidImageSize(
+            src.width(),
+            src.height(),
+            dst.width(),
+            dst.height(),
+        ));
+    }
+
+    dst.as_slice_mut()
+        .iter_mut()
+        .zip(src.as_slice().chunks_exact(C))
+        .for_each(|(out, src_pixel)| {
+            *out = src_pixel[channel];
+        });
+
+    Ok(())
+}
+
+/// Map the channels of each pixel to a new set of channels.
+///
+/// The function receives all the channels of a source pixel and returns
+/// the channels of the destination pixel, which allows to reorder, drop
+/// or combine channels, e.g. to convert RGB to BGR or RGBA to RGB.
+///
+/// # Arguments
+///
+/// * `src` - The source image with shape (H, W, C1).
+/// * `dst` - The destination image with shape (H, W, C2).
+/// * `f` - The function to map the source channels to the destination channels.
+///
+/// Example:
+///
+/// ```
+/// use kornia_image::{Image, ImageSize};
+/// use kornia_image::ops::map_channels;
+///
+/// let image = Image::<u8, 4>::new(
+///   ImageSize {
+///     width: 2,
+///     height: 1,
+///   },
+///   vec![0, 1, 2, 255, 3, 4, 5, 255],
+/// ).unwrap();
+///
+/// let mut bgr = Image::<u8, 3>::from_size_val(image.size(), 0).unwrap();
+///
+/// map_channels(&image, &mut bgr, |&[r, g, b, _]| [b, g, r]).unwrap();
+///
+/// assert_eq!(bgr.as_slice(), &[2, 1, 0, 5, 4, 3]);
+/// ```
+pub fn map_channels<T, U, const C1: usize, const C2: usize>(
+    src: &Image<T, C1>,
+    dst: &mut Image<U, C2>,
+    f: impl Fn(&[T; C1]) -> [U; C2],
+) -> Result<(), ImageError>
+where
+    T: Copy,
+    U: Copy,
+{
+    if src.size() != dst.size() {
+        return Err(ImageError::InvalidImageSize(
+            src.width(),
+            src.height(),
+            dst.width(),
+            dst.height(),
+        ));
+    }
+
+    dst.as_slice_mut()
+        .chunks_exact_mut(C2)
+        .zip(src.as_slice().chunks_exact(C1))
+        .try_for_each(|(dst_pixel, src_pixel)| {
+            let src_pixel: &[T; C1] = src_pixel
+                .try_into()
+                .map_err(|_| ImageError::InvalidChannelShape(src_pixel.len(), C1))?;
+            dst_pixel.copy_from_slice(&f(src_pixel));
+            Ok::<(), ImageError>(())
+        })?;
+
+    Ok(())
+}
+
+/// Convert an image with single precision pixels to half precision.
+///
+/// The conversion is done in bulk over the whole image buffer which allows the
+/// use of hardware instructions when available.
+///
+/// # Arguments
+///
+/// * `src` - The source image with `f32` pixels.
+/// * `dst` - The destination image with `f16` pixels.
+///
+/// Example:
+///
+/// ```
+/// use half::f16;
+/// use kornia_image::{Image, ImageSize};
+/// use kornia_image::ops::f16_from_f32;
+///
+/// let image = Image::<f32, 1>::new(
+///   ImageSize {
+///     width: 2,
+///     height: 1,
+///   },
+///   vec![0.5, 1.0],
+/// ).unwrap();
+///
+/// let mut image_f16 = Image::<f16, 1>::from_size_val(image.size(), f16::ZERO).unwrap();
+///
+/// f16_from_f32(&image, &mut image_f16).unwrap();
+///
+/// assert_eq!(image_f16.as_slice(), &[f16::from_f32(0.5), f16::ONE]);
+/// ```
+pub fn f16_from_f32<const C: usize>(
+    src: &Image<f32, C>,
+    dst: &mut Image<f16, C>,
+) -> Result<(), ImageError> {
+    if src.size() != dst.size() {
+        return Err(ImageError::InvalidImageSize(
+            src.width(),
+            src.height(),
+            dst.width(),
+            dst.height(),
+        ));
+    }
+
+    dst.as_slice_mut().convert_from_f32_slice(src.as_slice());
+
+    Ok(())
+}
+
+/// Convert an image with half precision pixels to single precision.
+///
+/// # Arguments
+///
+/// * `src` - The source image with `f16` pixels.
+/// * `dst` - The destination image with `f32` pixels.
+///
+/// Example:
+///
+/// ```
+/// use half::f16;
+/// use kornia_image::{Image, ImageSize};
+/// use kornia_image::ops::f32_from_f16;
+///
+/// let image = Image::<f16, 1>::new(
+///   ImageSize {
+///     width: 2,
+///     height: 1,
+///   },
+///   vec![f16::from_f32(0.5), f16::ONE],
+/// ).unwrap();
+///
+/// let mut image_f32 = Image::<f32, 1>::from_size_val(image.size(), 0.0).unwrap();
+///
+/// f32_from_f16(&image, &mut image_f32).unwrap();
+///
+/// assert_eq!(image_f32.as_slice(), &[0.5, 1.0]);
+/// ```
+pub fn f32_from_f16<const C: usize>(
+    src: &Image<f16, C>,
+    dst: &mut Image<f32, C>,
+) -> Result<(), ImageError> {
+    if src.size() != dst.size() {
+        return Err(ImageError::InvalidImageSize(
+            src.width(),
+            src.height(),
+            dst.width(),
+            dst.height(),
+        ));
+    }
+
+    src.as_slice().convert_to_f32_slice(dst.as_slice_mut());
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::image::ImageSize;
+    use alloc::vec;
 
     #[test]
     fn test_cast_and_scale() -> Result<(), ImageError> {
@@ -84,4 +412,66 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_split_merge_channels() -> Result<(), ImageError> {
+        let size = ImageSize {
+            width: 2,
+            height: 1,
+        };
+        let image = Image::<u8, 3>::new(size, vec![0, 1, 2, 3, 4, 5])?;
+
+        let mut channels = [
+            Image::from_size_val(size, 0u8)?,
+            Image::from_size_val(size, 0u8)?,
+            Image::from_size_val(size, 0u8)?,
+        ];
+        super::split_channels(&image, &mut channels)?;
+        assert_eq!(channels[0].as_slice(), &[0, 3]);
+        assert_eq!(channels[1].as_slice(), &[1, 4]);
+        assert_eq!(channels[2].as_slice(), &[2, 5]);
+
+        let mut merged = Image::<u8, 3>::from_size_val(size, 0)?;
+        super::merge_channels(&channels, &mut merged)?;
+        assert_eq!(merged.as_slice(), image.as_slice());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_extract_channel_out_of_bounds() -> Result<(), ImageError> {
+        let image = Image::<u8, 2>::from_size_val([2, 2].into(), 0)?;
+        let mut dst = Image::<u8, 1>::from_size_val(image.size(), 0)?;
+        assert!(super::extract_channel(&image, &mut dst, 2).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_map_channels() -> Result<(), ImageError> {
+        let image = Image::<u8, 3>::new([2, 1].into(), vec![0, 1, 2, 3, 4, 5])?;
+        let mut dst = Image::<f32, 1>::from_size_val(image.size(), 0.0)?;
+        super::map_channels(&image, &mut dst, |&[r, g, b]| {
+            [(r as f32 + g as f32 + b as f32) / 3.0]
+        })?;
+        assert_eq!(dst.as_slice(), &[1.0, 4.0]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_f16_roundtrip() -> Result<(), ImageError> {
+        let image = Image::<f32, 2>::new([2, 1].into(), vec![0.0, 0.25, -1.5, 1024.0])?;
+
+        let mut image_f16 = Image::<f16, 2>::from_size_val(image.size(), f16::ZERO)?;
+        super::f16_from_f32(&image, &mut image_f16)?;
+
+        // the cast through the generic api must give the same result
+        let image_f16_cast = image.cast::<f16>()?;
+        assert_eq!(image_f16.as_slice(), image_f16_cast.as_slice());
+
+        let mut image_f32 = Image::<f32, 2>::from_size_val(image.size(), 0.0)?;
+        super::f32_from_f16(&image_f16, &mut image_f32)?;
+        assert_eq!(image_f32.as_slice(), image.as_slice());
+
+        Ok(())
+    }
 }
@@ -1,7 +1,7 @@
 use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
 
 use kornia_image::Image;
-use kornia_imgproc::color::{gray_from_rgb, gray_from_rgb_u8};
+use kornia_imgproc::color::{gray_from_rgb, gray_from_rgb_u8, rgb_from_yuv, YuvFormat};
 
 // vanilla version
 fn gray_vanilla_get_unchecked(
@@ -131,5 +131,35 @@ fn bench_grayscale(c: &mut Criterion) {
     group.finish();
 }
 
-criterion_group!(benches, bench_grayscale);
+fn bench_rgb_from_yuv(c: &mut Criterion) {
+    let mut group = c.benchmark_group("RgbFromYuv");
+
+    for (width, height) in [(640, 480), (1280, 720), (1920, 1080)].iter() {
+        group.throughput(criterion::Throughput::Elements((*width * *height) as u64));
+
+        let image_size = [*width, *height].into();
+        let rgb = Image::<u8, 3>::from_size_val(image_size, 0).unwrap();
+
+        for format in [YuvFormat::Nv12, YuvFormat::I420, YuvFormat::Yuyv] {
+            let parameter_string = format!("{}x{}", width, height);
+
+            // input buffer
+            let yuv = (0..format.buffer_size(image_size))
+                .map(|i| (i % 251) as u8)
+                .collect::<Vec<_>>();
+
+            group.bench_with_input(
+                BenchmarkId::new(format!("{format:?}"), &parameter_string),
+                &(&yuv, &rgb),
+                |b, i| {
+                    let (src, mut dst) = (i.0, i.1.clone());
+                    b.iter(|| black_box(rgb_from_yuv(src, &mut dst, format)))
+                },
+            );
+        }
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_grayscale, bench_rgb_from_yuv);
 criterion_main!(benches);
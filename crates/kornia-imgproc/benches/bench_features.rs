@@ -1,7 +1,8 @@
 use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
 use kornia_image::Image;
 use kornia_imgproc::{
-    color::gray_from_rgb_u8, features::*, interpolation::InterpolationMode, resize::resize_fast,
+    color::gray_from_rgb_u8, features::*, filter::kernels::KernelSizePolicy,
+    interpolation::InterpolationMode, resize::resize_fast,
 };
 use kornia_io::functional as io;
 use rand::Rng;
@@ -34,7 +35,7 @@ fn bench_fast_corner_detect(c: &mut Criterion) {
 
 fn bench_harris_response(c: &mut Criterion) {
     let mut group = c.benchmark_group("Features");
-    let mut rng = rand::thread_rng();
+    let mut rng = rand::rng();
 
     for (width, height) in [(224, 224), (1920, 1080)].iter() {
         group.throughput(criterion::Throughput::Elements((*width * *height) as u64));
@@ -43,7 +44,7 @@ fn bench_harris_response(c: &mut Criterion) {
 
         // input image
         let image_data: Vec<f32> = (0..(*width * *height))
-            .map(|_| rng.gen_range(0.0..1.0))
+            .map(|_| rng.random_range(0.0..1.0))
             .collect();
         let image_size = [*width, *height].into();
 
@@ -87,6 +88,7 @@ fn bench_dog_response(c: &mut Criterion) {
                         black_box(&mut dst),
                         black_box(0.5),
                         black_box(1.0),
+                        KernelSizePolicy::Pytorch,
                     )
                     .unwrap()
                 })
@@ -97,10 +99,62 @@ fn bench_dog_response(c: &mut Criterion) {
     group.finish();
 }
 
+fn bench_detectors(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Detectors");
+    let mut rng = rand::rng();
+
+    let image_size = [640, 480].into();
+    let image_data = (0..640 * 480).map(|_| rng.random_range(0.0..1.0)).collect();
+    let image_f32: Image<f32, 1> = Image::new(image_size, image_data).unwrap();
+
+    for name in DetectorConfig::NAMES {
+        let mut detector = DetectorConfig::from_name(name).unwrap().build();
+        group.bench_with_input(BenchmarkId::new(name, "640x480"), &image_f32, |b, src| {
+            b.iter(|| black_box(detector.detect(src)).unwrap())
+        });
+    }
+    group.finish();
+}
+
+fn bench_descriptor_index(c: &mut Criterion) {
+    let mut group = c.benchmark_group("DescriptorIndex");
+    group.sample_size(10);
+    let mut rng = rand::rng();
+
+    let mut descriptor = || -> [f32; 128] { std::array::from_fn(|_| rng.random_range(0.0..1.0)) };
+    let train = (0..10_000).map(|_| descriptor()).collect::<Vec<_>>();
+    let query = (0..1000).map(|_| descriptor()).collect::<Vec<_>>();
+
+    let params = MatcherParams::default();
+    let brute_force = Matcher::new(
+        BruteForceIndex::<L2<128>>::new(train.clone()),
+        params.clone(),
+    );
+    let kdtree = Matcher::new(
+        KdTreeIndex::new(&train, &KdTreeParams::default()),
+        params.clone(),
+    );
+    let hnsw = Matcher::new(
+        HnswIndex::<L2<128>>::new(train, HnswParams::default()),
+        params,
+    );
+
+    group.bench_function("brute_force", |b| {
+        b.iter(|| black_box(brute_force.match_descriptors(&query)))
+    });
+    group.bench_function("kdtree", |b| {
+        b.iter(|| black_box(kdtree.match_descriptors(&query)))
+    });
+    group.bench_function("hnsw", |b| {
+        b.iter(|| black_box(hnsw.match_descriptors(&query)))
+    });
+    group.finish();
+}
+
 criterion_group!(
     name = benches;
     config = Criterion::default().warm_up_time(std::time::Duration::new(10, 0));
-    targets = bench_harris_response, bench_dog_response, bench_fast_corner_detect
+    targets = bench_harris_response, bench_dog_response, bench_fast_corner_detect, bench_detectors, bench_descriptor_index
 );
 criterion_main!(benches);
 
@@ -0,0 +1,357 @@
+use std::sync::Arc;
+
+use kornia_image::{Image, ImageError, ImageSize};
+
+use crate::{filter, interpolation::InterpolationMode, resize, warp};
+
+/// The kind of hardware running an implementation.
+///
+/// When several implementations can process an image, the one with the highest priority is
+/// chosen, in the order `Gpu`, `Simd` and `Cpu`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Backend {
+    /// The portable CPU implementation.
+    Cpu,
+    /// A CPU implementation with SIMD instructions.
+    Simd,
+    /// A GPU implementation including the transfers of the images.
+    Gpu,
+}
+
+impl Backend {
+    fn priority(&self) -> u8 {
+        match self {
+            Backend::Cpu => 0,
+            Backend::Simd => 1,
+            Backend::Gpu => 2,
+        }
+    }
+}
+
+/// An error type for the dispatched operations.
+#[derive(thiserror::Error, Debug)]
+pub enum BackendError {
+    /// Error when no implementation is registered for the image size.
+    #[error("No implementation available for {0} pixels")]
+    NoImplementation(usize),
+
+    /// Error when processing an image.
+    #[error(transparent)]
+    ImageError(#[from] ImageError),
+
+    /// Error from a backend specific implementation, e.g. a GPU error.
+    #[error(transparent)]
+    Implementation(Box<dyn std::error::Error + Send + Sync>),
+}
+
+/// The signature of the resize implementations.
+pub type ResizeFn<T, const C: usize> = dyn Fn(&Image<T, C>, &mut Image<T, C>, InterpolationMode) -> Result<(), BackendError>
+    + Send
+    + Sync;
+
+/// The signature of the gaussian blur implementations.
+pub type GaussianBlurFn<const C: usize> = dyn Fn(&Image<f32, C>, &mut Image<f32, C>, (usize, usize), (f32, f32)) -> Result<(), BackendError>
+    + Send
+    + Sync;
+
+/// The signature of the perspective warp implementations.
+pub type WarpPerspectiveFn<const C: usize> = dyn Fn(&Image<f32, C>, &mut Image<f32, C>, &[f32; 9], InterpolationMode) -> Result<(), BackendError>
+    + Send
+    + Sync;
+
+struct Implementation<F: ?Sized> {
+    backend: Backend,
+    min_pixels: usize,
+    enabled: bool,
+    func: Arc<F>,
+}
+
+impl<F: ?Sized> Clone for Implementation<F> {
+    fn clone(&self) -> Self {
+        Self {
+            backend: self.backend,
+            min_pixels: self.min_pixels,
+            enabled: self.enabled,
+            func: self.func.clone(),
+        }
+    }
+}
+
+/// A set of implementations of an operation chosen at runtime.
+///
+/// Each implementation is registered for a [`Backend`] with the minimum number of pixels of
+/// the images it should process, e.g. to use the GPU only when the transfers pay off. The
+/// implementation with the highest priority for the size of the image is used at each call.
+///
+/// # Example
+///
+/// ```
+/// use kornia_image::Image;
+/// use kornia_imgproc::backend::{Backend, Dispatcher, GaussianBlurFn};
+///
+/// let mut blur = Dispatcher::<GaussianBlurFn<1>>::gaussian_blur();
+/// assert_eq!(blur.select([640, 480].into()), Some(Backend::Cpu));
+///
+/// let src = Image::<f32, 1>::from_size_val([640, 480].into(), 1.0).unwrap();
+/// let mut dst = Image::<f32, 1>::from_size_val(src.size(), 0.0).unwrap();
+/// blur.run(&src, &mut dst, (3, 3), (1.0, 1.0)).unwrap();
+/// ```
+pub struct Dispatcher<F: ?Sized> {
+    implementations: Vec<Implementation<F>>,
+}
+
+impl<F: ?Sized> Clone for Dispatcher<F> {
+    fn clone(&self) -> Self {
+        Self {
+            implementations: self.implementations.clone(),
+        }
+    }
+}
+
+impl<F: ?Sized> Default for Dispatcher<F> {
+    fn default() -> Self {
+        Self {
+            implementations: Vec::new(),
+        }
+    }
+}
+
+impl<F: ?Sized> Dispatcher<F> {
+    /// Create a dispatcher without implementations.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register an implementation, replacing the previous one of the same backend.
+    ///
+    /// # Arguments
+    ///
+    /// * `backend` - The backend of the implementation.
+    /// * `min_pixels` - The minimum number of pixels of the images to use the implementation.
+    /// * `func` - The implementation.
+    pub fn register(&mut self, backend: Backend, min_pixels: usize, func: Arc<F>) {
+        self.implementations.retain(|imp| imp.backend != backend);
+        self.implementations.push(Implementation {
+            backend,
+            min_pixels,
+            enabled: true,
+            func,
+        });
+        self.implementations
+            .sort_by_key(|imp| std::cmp::Reverse(imp.backend.priority()));
+    }
+
+    /// Enable or disable the implementation of a backend without unregistering it.
+    ///
+    /// # Arguments
+    ///
+    /// * `backend` - The backend of the implementation.
+    /// * `enabled` - Whether the implementation can be selected.
+    pub fn set_enabled(&mut self, backend: Backend, enabled: bool) {
+        self.implementations
+            .iter_mut()
+            .filter(|imp| imp.backend == backend)
+            .for_each(|imp| imp.enabled = enabled);
+    }
+
+    /// The backends with a registered implementation, by decreasing priority.
+    pub fn backends(&self) -> Vec<Backend> {
+        self.implementations.iter().map(|imp| imp.backend).collect()
+    }
+
+    /// The backend used for an image of the given size.
+    ///
+    /// # Returns
+    ///
+    /// The backend or `None` if no enabled implementation accepts the size.
+    pub fn select(&self, size: ImageSize) -> Option<Backend> {
+        self.implementation(size).map(|imp| imp.backend)
+    }
+
+    fn implementation(&self, size: ImageSize) -> Option<&Implementation<F>> {
+        let num_pixels = size.width * size.height;
+        self.implementations
+            .iter()
+            .find(|imp| imp.enabled && imp.min_pixels <= num_pixels)
+    }
+
+    fn func(&self, size: ImageSize) -> Result<&F, BackendError> {
+        self.implementation(size)
+            .map(|imp| imp.func.as_ref())
+            .ok_or(BackendError::NoImplementation(size.width * size.height))
+    }
+}
+
+impl<const C: usize> Dispatcher<ResizeFn<f32, C>> {
+    /// Create a resize dispatcher with the CPU implementation.
+    pub fn resize() -> Self {
+        let mut dispatcher = Self::new();
+        dispatcher.register(
+            Backend::Cpu,
+            0,
+            Arc::new(|src, dst, interpolation| Ok(resize::resize_native(src, dst, interpolation)?)),
+        );
+        dispatcher
+    }
+}
+
+impl Dispatcher<ResizeFn<u8, 3>> {
+    /// Create a resize dispatcher of RGB8 images with the SIMD implementation.
+    pub fn resize_u8() -> Self {
+        let mut dispatcher = Self::new();
+        dispatcher.register(
+            Backend::Simd,
+            0,
+            Arc::new(|src, dst, interpolation| Ok(resize::resize_fast(src, dst, interpolation)?)),
+        );
+        dispatcher
+    }
+}
+
+impl<T, const C: usize> Dispatcher<ResizeFn<T, C>> {
+    /// Resize an image with the implementation selected for the largest of the two images.
+    ///
+    /// # Arguments
+    ///
+    /// * `src` - The input image.
+    /// * `dst` - The output image with the new size.
+    /// * `interpolation` - The interpolation mode to use.
+    pub fn run(
+        &self,
+        src: &Image<T, C>,
+        dst: &mut Image<T, C>,
+        interpolation: InterpolationMode,
+    ) -> Result<(), BackendError> {
+        let size = if src.cols() * src.rows() >= dst.cols() * dst.rows() {
+            src.size()
+        } else {
+            dst.size()
+        };
+        self.func(size)?(src, dst, interpolation)
+    }
+}
+
+impl<const C: usize> Dispatcher<GaussianBlurFn<C>> {
+    /// Create a gaussian blur dispatcher with the CPU implementation.
+    pub fn gaussian_blur() -> Self {
+        let mut dispatcher = Self::new();
+        dispatcher.register(
+            Backend::Cpu,
+            0,
+            Arc::new(|src, dst, kernel_size, sigma| {
+                Ok(filter::gaussian_blur(src, dst, kernel_size, sigma)?)
+            }),
+        );
+        dispatcher
+    }
+
+    /// Blur an image with the implementation selected for its size.
+    ///
+    /// # Arguments
+    ///
+    /// * `src` - The source image with shape (H, W, C).
+    /// * `dst` - The destination image with shape (H, W, C).
+    /// * `kernel_size` - The size of the kernel (kernel_x, kernel_y).
+    /// * `sigma` - The sigma of the gaussian kernel.
+    pub fn run(
+        &self,
+        src: &Image<f32, C>,
+        dst: &mut Image<f32, C>,
+        kernel_size: (usize, usize),
+        sigma: (f32, f32),
+    ) -> Result<(), BackendError> {
+        self.func(src.size())?(src, dst, kernel_size, sigma)
+    }
+}
+
+impl<const C: usize> Dispatcher<WarpPerspectiveFn<C>> {
+    /// Create a perspective warp dispatcher with the CPU implementation.
+    pub fn warp_perspective() -> Self {
+        let mut dispatcher = Self::new();
+        dispatcher.register(
+            Backend::Cpu,
+            0,
+            Arc::new(|src, dst, m, interpolation| {
+                Ok(warp::warp_perspective(src, dst, m, interpolation)?)
+            }),
+        );
+        dispatcher
+    }
+
+    /// Warp an image with the implementation selected for the size of the output.
+    ///
+    /// # Arguments
+    ///
+    /// * `src` - The input image.
+    /// * `dst` - The output image.
+    /// * `m` - The 3x3 perspective transformation matrix src -> dst.
+    /// * `interpolation` - The interpolation mode to use.
+    pub fn run(
+        &self,
+        src: &Image<f32, C>,
+        dst: &mut Image<f32, C>,
+        m: &[f32; 9],
+        interpolation: InterpolationMode,
+    ) -> Result<(), BackendError> {
+        self.func(dst.size())?(src, dst, m, interpolation)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dispatcher_select() -> Result<(), BackendError> {
+        let mut blur = Dispatcher::<GaussianBlurFn<1>>::gaussian_blur();
+        blur.register(
+            Backend::Gpu,
+            100,
+            Arc::new(|_, dst, _, _| {
+                dst.as_slice_mut().fill(-1.0);
+                Ok(())
+            }),
+        );
+        assert_eq!(blur.backends(), vec![Backend::Gpu, Backend::Cpu]);
+
+        // the small images stay on the CPU
+        let src = Image::<f32, 1>::from_size_val([5, 5].into(), 1.0)?;
+        let mut dst = Image::from_size_val(src.size(), 0.0)?;
+        assert_eq!(blur.select(src.size()), Some(Backend::Cpu));
+        blur.run(&src, &mut dst, (3, 3), (1.0, 1.0))?;
+        assert!(dst.as_slice()[12] > 0.0);
+
+        let src = Image::<f32, 1>::from_size_val([10, 10].into(), 1.0)?;
+        let mut dst = Image::from_size_val(src.size(), 0.0)?;
+        assert_eq!(blur.select(src.size()), Some(Backend::Gpu));
+        blur.run(&src, &mut dst, (3, 3), (1.0, 1.0))?;
+        assert_eq!(dst.as_slice()[0], -1.0);
+
+        blur.set_enabled(Backend::Gpu, false);
+        assert_eq!(blur.select(src.size()), Some(Backend::Cpu));
+        blur.set_enabled(Backend::Cpu, false);
+        assert!(blur.run(&src, &mut dst, (3, 3), (1.0, 1.0)).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_dispatcher_resize_warp() -> Result<(), BackendError> {
+        let src = Image::<f32, 1>::new([2, 2].into(), vec![0.0, 1.0, 2.0, 3.0])?;
+        let mut dst = Image::from_size_val([3, 3].into(), 0.0)?;
+        let resize = Dispatcher::<ResizeFn<f32, 1>>::resize();
+        resize.run(&src, &mut dst, InterpolationMode::Bilinear)?;
+        assert_eq!(dst.as_slice()[4], 1.5);
+
+        let resize = Dispatcher::<ResizeFn<u8, 3>>::resize_u8();
+        assert_eq!(resize.select([2, 2].into()), Some(Backend::Simd));
+
+        let mut dst = Image::from_size_val(src.size(), 0.0)?;
+        let warp = Dispatcher::<WarpPerspectiveFn<1>>::warp_perspective();
+        let m = [1.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0];
+        warp.run(&src, &mut dst, &m, InterpolationMode::Nearest)?;
+        assert_eq!(dst.as_slice(), src.as_slice());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,258 @@
+use kornia_image::Image;
+
+use super::BarcodeError;
+use crate::threshold::otsu_threshold;
+
+// the run lengths of the left digits with odd parity, starting with a light run
+const L_CODES: [[u8; 4]; 10] = [
+    [3, 2, 1, 1],
+    [2, 2, 2, 1],
+    [2, 1, 2, 2],
+    [1, 4, 1, 1],
+    [1, 1, 3, 2],
+    [1, 2, 3, 1],
+    [1, 1, 1, 4],
+    [1, 3, 1, 2],
+    [1, 2, 1, 3],
+    [3, 1, 1, 2],
+];
+
+// the parity of the six left digits encoding the first digit, true for even parity
+const FIRST_DIGIT_PARITY: [[bool; 6]; 10] = {
+    const L: bool = false;
+    const G: bool = true;
+    [
+        [L, L, L, L, L, L],
+        [L, L, G, L, G, G],
+        [L, L, G, G, L, G],
+        [L, L, G, G, G, L],
+        [L, G, L, L, G, G],
+        [L, G, G, L, L, G],
+        [L, G, G, G, L, L],
+        [L, G, L, G, L, G],
+        [L, G, L, G, G, L],
+        [L, G, G, L, G, L],
+    ]
+};
+
+// the number of runs after the start guard: two halves of six digits with four runs, the
+// middle guard and the end guard
+const NUM_RUNS: usize = 6 * 4 + 5 + 6 * 4 + 3;
+
+/// An EAN-13 barcode found in an image.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Ean13 {
+    /// The 13 digits including the check digit.
+    pub digits: String,
+    /// The row of the image where the barcode was read.
+    pub row: usize,
+}
+
+/// Decode an EAN-13 barcode from the rows of an image.
+///
+/// The image is binarized with the Otsu threshold and each row is scanned in both
+/// directions for the guard patterns. The digits are matched on the widths of their four
+/// runs relative to the width of the digit, so that the bars can be scaled arbitrarily.
+///
+/// # Arguments
+///
+/// * `src` - The grayscale image with shape (H, W, 1), with horizontal bars.
+///
+/// # Returns
+///
+/// The first barcode with a valid check digit, scanning from the middle row outwards.
+pub fn decode_ean13(src: &Image<u8, 1>) -> Result<Ean13, BarcodeError> {
+    let threshold = otsu_threshold(src)?;
+    let (cols, rows) = (src.cols(), src.rows());
+
+    // the middle rows are the most likely to cross the bars
+    let order = (0..rows).map(|i| {
+        let offset = i.div_ceil(2);
+        if i % 2 == 0 {
+            rows / 2 + offset
+        } else {
+            rows / 2 - offset
+        }
+    });
+
+    for row in order {
+        let pixels = &src.as_slice()[row * cols..(row + 1) * cols];
+        let mut runs: Vec<(bool, usize)> = Vec::new();
+        for &pixel in pixels {
+            let dark = pixel <= threshold;
+            match runs.last_mut() {
+                Some((color, len)) if *color == dark => *len += 1,
+                _ => runs.push((dark, 1)),
+            }
+        }
+
+        let forward = runs.iter().map(|&(_, len)| len).collect::<Vec<_>>();
+        let backward = forward.iter().rev().copied().collect::<Vec<_>>();
+        let first_dark = runs.first().is_some_and(|r| r.0);
+        let last_dark = runs.last().is_some_and(|r| r.0);
+
+        for (lengths, starts_dark) in [(forward, first_dark), (backward, last_dark)] {
+            if let Some(digits) = decode_runs(&lengths, starts_dark) {
+                return Ok(Ean13 { digits, row });
+            }
+        }
+    }
+
+    Err(BarcodeError::NotFound)
+}
+
+// decode the run lengths of a scanline, alternating the colors from the first run
+fn decode_runs(lengths: &[usize], starts_dark: bool) -> Option<String> {
+    // the start guard is a dark run after a quiet zone
+    let first = if starts_dark { 1 } else { 0 };
+    (first..lengths.len().saturating_sub(NUM_RUNS + 3))
+        .step_by(2)
+        .find_map(|light| {
+            let guard = &lengths[light + 1..light + 4];
+            let unit = guard.iter().sum::<usize>() as f32 / 3.0;
+            let is_guard = guard
+                .iter()
+                .all(|&len| (len as f32 - unit).abs() <= unit * 0.6);
+            if !is_guard || (lengths[light] as f32) < 3.0 * unit {
+                return None;
+            }
+            decode_symbol(&lengths[light + 4..light + 4 + NUM_RUNS])
+        })
+}
+
+// decode the runs after the start guard
+fn decode_symbol(runs: &[usize]) -> Option<String> {
+    let mut digits = [0u8; 13];
+    let mut parity = [false; 6];
+
+    for i in 0..6 {
+        let (digit, even) = match_digit(&runs[i * 4..i * 4 + 4], true)?;
+        digits[i + 1] = digit;
+        parity[i] = even;
+    }
+
+    // the middle guard has five runs of one module
+    let middle = &runs[24..29];
+    let unit = middle.iter().sum::<usize>() as f32 / 5.0;
+    if middle
+        .iter()
+        .any(|&len| (len as f32 - unit).abs() > unit * 0.6)
+    {
+        return None;
+    }
+
+    for i in 0..6 {
+        let (digit, even) = match_digit(&runs[29 + i * 4..29 + i * 4 + 4], false)?;
+        if even {
+            return None;
+        }
+        digits[i + 7] = digit;
+    }
+
+    digits[0] = FIRST_DIGIT_PARITY.iter().position(|p| *p == parity)? as u8;
+
+    // the check digit weights the digits alternately by 1 and 3
+    let sum = digits[..12]
+        .iter()
+        .enumerate()
+        .map(|(i, &d)| d as u32 * if i % 2 == 0 { 1 } else { 3 })
+        .sum::<u32>();
+    if (10 - sum % 10) % 10 != digits[12] as u32 {
+        return None;
+    }
+
+    Some(digits.iter().map(|d| (b'0' + d) as char).collect())
+}
+
+// match the four runs of a digit, returning the digit and true for the even parity
+fn match_digit(runs: &[usize], left: bool) -> Option<(u8, bool)> {
+    let unit = runs.iter().sum::<usize>() as f32 / 7.0;
+    let widths = runs
+        .iter()
+        .map(|&len| len as f32 / unit)
+        .collect::<Vec<_>>();
+
+    // the even left digits are the odd codes mirrored, and the right digits have the
+    // widths of the odd codes with swapped colors
+    let mut best = None;
+    let mut best_distance = f32::MAX;
+    for (digit, code) in L_CODES.iter().enumerate() {
+        let candidates = if left {
+            vec![(*code, false), (mirror(code), true)]
+        } else {
+            vec![(*code, false)]
+        };
+        for (pattern, even) in candidates {
+            let distance = widths
+                .iter()
+                .zip(&pattern)
+                .map(|(w, &p)| (w - p as f32).abs())
+                .sum::<f32>();
+            if distance < best_distance {
+                best_distance = distance;
+                best = Some((digit as u8, even));
+            }
+        }
+    }
+
+    // half a module of error per run
+    (best_distance < 2.0).then_some(best).flatten()
+}
+
+fn mirror(code: &[u8; 4]) -> [u8; 4] {
+    [code[3], code[2], code[1], code[0]]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // render the bars of a code with the modules of the given width
+    fn render(digits: &str, module: usize, flip: bool) -> Image<u8, 1> {
+        let digits = digits.bytes().map(|b| b - b'0').collect::<Vec<_>>();
+        let mut runs = vec![(false, 10), (true, 1), (false, 1), (true, 1)];
+        let parity = FIRST_DIGIT_PARITY[digits[0] as usize];
+        for (i, &d) in digits[1..7].iter().enumerate() {
+            let code = L_CODES[d as usize];
+            let code = if parity[i] { mirror(&code) } else { code };
+            for (k, &w) in code.iter().enumerate() {
+                runs.push((k % 2 == 1, w as usize));
+            }
+        }
+        runs.extend([(false, 1), (true, 1), (false, 1), (true, 1), (false, 1)]);
+        for &d in &digits[7..] {
+            for (k, &w) in L_CODES[d as usize].iter().enumerate() {
+                runs.push((k % 2 == 0, w as usize));
+            }
+        }
+        runs.extend([(true, 1), (false, 1), (true, 1), (false, 10)]);
+
+        let mut row = runs
+            .iter()
+            .flat_map(|&(dark, w)| std::iter::repeat_n(if dark { 20 } else { 230 }, w * module))
+            .collect::<Vec<u8>>();
+        if flip {
+            row.reverse();
+        }
+        let cols = row.len();
+        Image::new([cols, 8].into(), row.repeat(8)).unwrap()
+    }
+
+    #[test]
+    fn test_decode_ean13() -> Result<(), BarcodeError> {
+        let code = decode_ean13(&render("4006381333931", 2, false))?;
+        assert_eq!(code.digits, "4006381333931");
+
+        // upside down and with wider bars
+        let code = decode_ean13(&render("9780201379624", 3, true))?;
+        assert_eq!(code.digits, "9780201379624");
+
+        // an invalid check digit
+        assert!(matches!(
+            decode_ean13(&render("4006381333932", 2, false)),
+            Err(BarcodeError::NotFound)
+        ));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,33 @@
+use kornia_image::ImageError;
+
+/// An error type for the barcode module.
+#[derive(thiserror::Error, Debug)]
+pub enum BarcodeError {
+    /// Error when no barcode is found in the image.
+    #[error("No barcode found")]
+    NotFound,
+
+    /// Error when the size of a QR code grid is not a valid version.
+    #[error("Invalid QR code size {0}")]
+    InvalidSize(usize),
+
+    /// Error when the format information of a QR code cannot be read.
+    #[error("Cannot read the format information")]
+    InvalidFormat,
+
+    /// Error when a block has more errors than the error correction can fix.
+    #[error("Too many errors to correct")]
+    TooManyErrors,
+
+    /// Error when a QR code uses an unsupported encoding mode, e.g. kanji.
+    #[error("Unsupported QR code mode {0}")]
+    UnsupportedMode(u8),
+
+    /// Error when the data ends before a segment.
+    #[error("Truncated barcode data")]
+    TruncatedData,
+
+    /// Error when processing the image.
+    #[error(transparent)]
+    ImageError(#[from] ImageError),
+}
@@ -0,0 +1,7 @@
+mod ean13;
+mod error;
+mod qr;
+mod reed_solomon;
+pub use ean13::*;
+pub use error::BarcodeError;
+pub use qr::*;
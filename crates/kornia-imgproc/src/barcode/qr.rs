@@ -0,0 +1,1148 @@
+use kornia_image::{Image, ImageSize};
+
+use super::{reed_solomon, BarcodeError};
+use crate::{interpolation::InterpolationMode, threshold::otsu_threshold, warp::warp_perspective};
+
+// the number of error correction bytes per block by level and version
+const ECC_CODEWORDS_PER_BLOCK: [[u8; 41]; 4] = [
+    [
+        0, 7, 10, 15, 20, 26, 18, 20, 24, 30, 18, 20, 24, 26, 30, 22, 24, 28, 30, 28, 28, 28, 28,
+        30, 30, 26, 28, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30,
+    ],
+    [
+        0, 10, 16, 26, 18, 24, 16, 18, 22, 22, 26, 30, 22, 22, 24, 24, 28, 28, 26, 26, 26, 26, 28,
+        28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28,
+    ],
+    [
+        0, 13, 22, 18, 26, 18, 24, 18, 22, 20, 24, 28, 26, 24, 20, 30, 24, 28, 28, 26, 30, 28, 30,
+        30, 30, 30, 28, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30,
+    ],
+    [
+        0, 17, 28, 22, 16, 22, 28, 26, 26, 24, 28, 24, 28, 22, 24, 24, 30, 28, 28, 26, 28, 30, 24,
+        30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30,
+    ],
+];
+
+// the number of error correction blocks by level and version
+const NUM_ERROR_CORRECTION_BLOCKS: [[u8; 41]; 4] = [
+    [
+        0, 1, 1, 1, 1, 1, 2, 2, 2, 2, 4, 4, 4, 4, 4, 6, 6, 6, 6, 7, 8, 8, 9, 9, 10, 12, 12, 12, 13,
+        14, 15, 16, 17, 18, 19, 19, 20, 21, 22, 24, 25,
+    ],
+    [
+        0, 1, 1, 1, 2, 2, 4, 4, 4, 5, 5, 5, 8, 9, 9, 10, 10, 11, 13, 14, 16, 17, 17, 18, 20, 21,
+        23, 25, 26, 28, 29, 31, 33, 35, 37, 38, 40, 43, 45, 47, 49,
+    ],
+    [
+        0, 1, 1, 2, 2, 4, 4, 6, 6, 8, 8, 8, 10, 12, 16, 12, 17, 16, 18, 21, 20, 23, 23, 25, 27, 29,
+        34, 34, 35, 38, 40, 43, 45, 48, 51, 53, 56, 59, 62, 65, 68,
+    ],
+    [
+        0, 1, 1, 2, 4, 4, 4, 5, 6, 8, 8, 11, 11, 16, 16, 18, 16, 19, 21, 25, 25, 25, 34, 30, 32,
+        35, 37, 40, 42, 45, 48, 51, 54, 57, 60, 63, 66, 70, 74, 77, 81,
+    ],
+];
+
+// the characters of the alphanumeric mode
+const ALPHANUMERIC: &[u8; 45] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ $%*+-./:";
+
+// the number of pixels per module of the rectified codes
+const PIXELS_PER_MODULE: usize = 4;
+
+/// The error correction level of a QR code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorCorrectionLevel {
+    /// Recovers about 7% of the data.
+    Low,
+    /// Recovers about 15% of the data.
+    Medium,
+    /// Recovers about 25% of the data.
+    Quartile,
+    /// Recovers about 30% of the data.
+    High,
+}
+
+impl ErrorCorrectionLevel {
+    // the level from the two bits of the format information
+    fn from_format_bits(bits: u16) -> Self {
+        match bits & 3 {
+            1 => ErrorCorrectionLevel::Low,
+            0 => ErrorCorrectionLevel::Medium,
+            3 => ErrorCorrectionLevel::Quartile,
+            _ => ErrorCorrectionLevel::High,
+        }
+    }
+
+    #[cfg(test)]
+    fn format_bits(&self) -> u16 {
+        match self {
+            ErrorCorrectionLevel::Low => 1,
+            ErrorCorrectionLevel::Medium => 0,
+            ErrorCorrectionLevel::Quartile => 3,
+            ErrorCorrectionLevel::High => 2,
+        }
+    }
+
+    fn index(&self) -> usize {
+        *self as usize
+    }
+}
+
+/// The decoded content of a QR code.
+#[derive(Debug, Clone, PartialEq)]
+pub struct QrContent {
+    /// The version of the code, from 1 to 40.
+    pub version: usize,
+    /// The error correction level.
+    pub error_correction: ErrorCorrectionLevel,
+    /// The decoded bytes of all the segments.
+    pub data: Vec<u8>,
+}
+
+impl QrContent {
+    /// Returns the data as text if it is valid UTF-8.
+    pub fn as_str(&self) -> Option<&str> {
+        std::str::from_utf8(&self.data).ok()
+    }
+}
+
+/// A QR code detected in an image.
+#[derive(Debug, Clone, PartialEq)]
+pub struct QrCode {
+    /// The centers of the finder patterns at the top-left, top-right and bottom-left
+    /// corners and the estimated center of a pattern at the bottom-right corner, in pixels
+    /// and in clockwise order.
+    pub corners: [[f32; 2]; 4],
+    /// The decoded content.
+    pub content: QrContent,
+}
+
+/// A finder pattern at a corner of a QR code.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FinderPattern {
+    /// The x coordinate of the center in pixels.
+    pub x: f32,
+    /// The y coordinate of the center in pixels.
+    pub y: f32,
+    /// The estimated size of a module in pixels.
+    pub module_size: f32,
+    /// The number of rows where the pattern was found.
+    pub count: usize,
+}
+
+/// Find the finder patterns of QR codes in an image.
+///
+/// The image is binarized with the Otsu threshold and the rows are scanned for runs with
+/// the 1:1:3:1:1 proportions of the finder patterns, which are verified along the column
+/// through their center.
+///
+/// # Arguments
+///
+/// * `src` - The grayscale image with shape (H, W, 1).
+///
+/// # Returns
+///
+/// The finder patterns sorted by decreasing count.
+pub fn find_qr_finder_patterns(src: &Image<u8, 1>) -> Result<Vec<FinderPattern>, BarcodeError> {
+    let threshold = otsu_threshold(src)?;
+    let binary = Binary::new(src, threshold);
+    Ok(find_finder_patterns(&binary))
+}
+
+/// Detect and decode the QR codes in an image.
+///
+/// The finder patterns are grouped by three, the version is estimated from their distance,
+/// and the code is rectified with a perspective warp anchored on the finder patterns and
+/// the bottom-right alignment pattern before sampling the modules.
+///
+/// # Arguments
+///
+/// * `src` - The grayscale image with shape (H, W, 1).
+///
+/// # Returns
+///
+/// The decoded QR codes, the codes which cannot be decoded are skipped.
+pub fn detect_qr_codes(src: &Image<u8, 1>) -> Result<Vec<QrCode>, BarcodeError> {
+    let threshold = otsu_threshold(src)?;
+    let binary = Binary::new(src, threshold);
+    let patterns = find_finder_patterns(&binary);
+
+    let gray = src.map(|&v| v as f32)?;
+    let mut used = vec![false; patterns.len()];
+    let mut codes = Vec::new();
+
+    for i in 0..patterns.len() {
+        for j in i + 1..patterns.len() {
+            for k in j + 1..patterns.len() {
+                if used[i] || used[j] || used[k] {
+                    continue;
+                }
+
+                let triple = [patterns[i], patterns[j], patterns[k]];
+                let Some([tl, tr, bl]) = order_finder_patterns(&triple) else {
+                    continue;
+                };
+
+                if let Some(code) = decode_at(&gray, &binary, threshold as f32, tl, tr, bl) {
+                    used[i] = true;
+                    used[j] = true;
+                    used[k] = true;
+                    codes.push(code);
+                }
+            }
+        }
+    }
+
+    Ok(codes)
+}
+
+/// Decode a sampled QR code.
+///
+/// # Arguments
+///
+/// * `modules` - The modules in row major order, true for the dark modules.
+/// * `size` - The number of modules per side.
+///
+/// # Returns
+///
+/// The decoded content, after correcting the errors.
+pub fn decode_qr_grid(modules: &[bool], size: usize) -> Result<QrContent, BarcodeError> {
+    if !(21..=177).contains(&size) || size % 4 != 1 || modules.len() != size * size {
+        return Err(BarcodeError::InvalidSize(size));
+    }
+    let version = (size - 17) / 4;
+    let module = |x: usize, y: usize| modules[y * size + x];
+
+    // read both copies of the format information and take the closest valid code
+    let mut copy1 = 0u16;
+    let mut copy2 = 0u16;
+    for (i, (x, y, x2, y2)) in format_positions(size).enumerate() {
+        copy1 |= (module(x, y) as u16) << i;
+        copy2 |= (module(x2, y2) as u16) << i;
+    }
+    let (format, distance) = (0..32u16)
+        .map(|data| {
+            let bits = format_bits(data);
+            let distance = (bits ^ copy1).count_ones().min((bits ^ copy2).count_ones());
+            (data, distance)
+        })
+        .min_by_key(|&(_, distance)| distance)
+        .ok_or(BarcodeError::InvalidFormat)?;
+    if distance > 3 {
+        return Err(BarcodeError::InvalidFormat);
+    }
+    let level = ErrorCorrectionLevel::from_format_bits(format >> 3);
+    let mask = (format & 7) as u8;
+
+    // read the codewords along the zigzag, removing the mask
+    let function = function_modules(version);
+    let num_codewords = num_raw_data_modules(version) / 8;
+    let mut codewords = vec![0u8; num_codewords];
+    let mut bit = 0;
+    for (x, y) in zigzag(size) {
+        if function[y * size + x] || bit >= num_codewords * 8 {
+            continue;
+        }
+        if module(x, y) ^ mask_bit(mask, x, y) {
+            codewords[bit / 8] |= 0x80 >> (bit % 8);
+        }
+        bit += 1;
+    }
+
+    let data = correct_blocks(&codewords, version, level)?;
+    let data = parse_segments(&data, version)?;
+
+    Ok(QrContent {
+        version,
+        error_correction: level,
+        data,
+    })
+}
+
+// a binarized image with the dark pixels as true
+struct Binary {
+    cols: usize,
+    rows: usize,
+    dark: Vec<bool>,
+}
+
+impl Binary {
+    fn new(src: &Image<u8, 1>, threshold: u8) -> Self {
+        Self {
+            cols: src.cols(),
+            rows: src.rows(),
+            dark: src.as_slice().iter().map(|&v| v <= threshold).collect(),
+        }
+    }
+
+    fn is_dark(&self, x: isize, y: isize) -> Option<bool> {
+        if x < 0 || y < 0 || x as usize >= self.cols || y as usize >= self.rows {
+            return None;
+        }
+        Some(self.dark[y as usize * self.cols + x as usize])
+    }
+}
+
+// true if the run lengths have the 1:1:3:1:1 proportions
+fn is_finder_ratio(counts: &[usize; 5]) -> bool {
+    let total = counts.iter().sum::<usize>();
+    if total < 7 || counts.contains(&0) {
+        return false;
+    }
+    let module = total as f32 / 7.0;
+    let tolerance = module / 2.0;
+    (counts[0] as f32 - module).abs() < tolerance
+        && (counts[1] as f32 - module).abs() < tolerance
+        && (counts[2] as f32 - 3.0 * module).abs() < 3.0 * tolerance
+        && (counts[3] as f32 - module).abs() < tolerance
+        && (counts[4] as f32 - module).abs() < tolerance
+}
+
+// count the runs of a finder pattern through a center along a direction, returning the
+// center of the pattern along the direction and the total length
+fn cross_check(binary: &Binary, x: isize, y: isize, dx: isize, dy: isize) -> Option<(f32, usize)> {
+    let mut counts = [0usize; 5];
+
+    // backward from the center, including it
+    let mut step = 0isize;
+    for (index, dark) in [(2, true), (1, false), (0, true)] {
+        while binary.is_dark(x - step * dx, y - step * dy) == Some(dark) {
+            counts[index] += 1;
+            step += 1;
+        }
+    }
+    let start = counts[2];
+
+    // forward from the center
+    let mut step = 1isize;
+    let mut forward = [0usize; 5];
+    for (index, dark) in [(2, true), (3, false), (4, true)] {
+        while binary.is_dark(x + step * dx, y + step * dy) == Some(dark) {
+            forward[index] += 1;
+            step += 1;
+        }
+    }
+    counts[2] += forward[2];
+    counts[3] = forward[3];
+    counts[4] = forward[4];
+
+    if !is_finder_ratio(&counts) {
+        return None;
+    }
+
+    // the offset of the middle of the center run from the given point
+    let center = (forward[2] as f32 + 1.0 - start as f32) / 2.0;
+    Some((center, counts.iter().sum()))
+}
+
+fn find_finder_patterns(binary: &Binary) -> Vec<FinderPattern> {
+    let mut patterns: Vec<FinderPattern> = Vec::new();
+
+    for y in 0..binary.rows {
+        let row = &binary.dark[y * binary.cols..(y + 1) * binary.cols];
+
+        // the run lengths of the row with their color
+        let mut runs: Vec<(bool, usize)> = Vec::new();
+        for &dark in row {
+            match runs.last_mut() {
+                Some((color, len)) if *color == dark => *len += 1,
+                _ => runs.push((dark, 1)),
+            }
+        }
+
+        let mut end = 0;
+        let ends = runs
+            .iter()
+            .map(|&(_, len)| {
+                end += len;
+                end
+            })
+            .collect::<Vec<_>>();
+
+        for i in 0..runs.len().saturating_sub(4) {
+            if !runs[i].0 {
+                continue;
+            }
+            let counts = std::array::from_fn(|k| runs[i + k].1);
+            if !is_finder_ratio(&counts) {
+                continue;
+            }
+
+            // the middle of the center run
+            let cx = ends[i + 2] as f32 - counts[2] as f32 / 2.0;
+            let px = cx.floor() as isize;
+
+            let Some((dy, vertical)) = cross_check(binary, px, y as isize, 0, 1) else {
+                continue;
+            };
+            let cy = y as f32 + 0.5 + dy;
+            let Some((dx, horizontal)) = cross_check(binary, px, cy.floor() as isize, 1, 0) else {
+                continue;
+            };
+            let cx = px as f32 + 0.5 + dx;
+
+            // the pattern is square
+            let (vertical, horizontal) = (vertical as f32, horizontal as f32);
+            if (vertical - horizontal).abs() > 0.5 * horizontal {
+                continue;
+            }
+            let module_size = (vertical + horizontal) / 14.0;
+
+            let existing = patterns.iter_mut().find(|p| {
+                (p.x - cx).abs() <= p.module_size
+                    && (p.y - cy).abs() <= p.module_size
+                    && (p.module_size - module_size).abs() <= p.module_size * 0.5
+            });
+            match existing {
+                Some(p) => {
+                    let n = p.count as f32;
+                    p.x = (p.x * n + cx) / (n + 1.0);
+                    p.y = (p.y * n + cy) / (n + 1.0);
+                    p.module_size = (p.module_size * n + module_size) / (n + 1.0);
+                    p.count += 1;
+                }
+                None => patterns.push(FinderPattern {
+                    x: cx,
+                    y: cy,
+                    module_size,
+                    count: 1,
+                }),
+            }
+        }
+    }
+
+    // a real pattern is found on several rows
+    patterns.retain(|p| p.count >= 2);
+    patterns.sort_by_key(|p| std::cmp::Reverse(p.count));
+    patterns
+}
+
+// order three finder patterns as top-left, top-right and bottom-left
+fn order_finder_patterns(patterns: &[FinderPattern; 3]) -> Option<[FinderPattern; 3]> {
+    let sizes = patterns.map(|p| p.module_size);
+    let min_size = sizes.iter().copied().fold(f32::MAX, f32::min);
+    let max_size = sizes.iter().copied().fold(0.0, f32::max);
+    if max_size > 1.5 * min_size {
+        return None;
+    }
+
+    let distance = |a: &FinderPattern, b: &FinderPattern| (a.x - b.x).hypot(a.y - b.y);
+
+    // the top-left pattern is opposite to the longest side
+    let d01 = distance(&patterns[0], &patterns[1]);
+    let d12 = distance(&patterns[1], &patterns[2]);
+    let d02 = distance(&patterns[0], &patterns[2]);
+    let (tl, a, b) = if d12 >= d01 && d12 >= d02 {
+        (patterns[0], patterns[1], patterns[2])
+    } else if d02 >= d01 {
+        (patterns[1], patterns[0], patterns[2])
+    } else {
+        (patterns[2], patterns[0], patterns[1])
+    };
+
+    // the sides from the top-left pattern are similar and roughly orthogonal
+    let (ax, ay) = (a.x - tl.x, a.y - tl.y);
+    let (bx, by) = (b.x - tl.x, b.y - tl.y);
+    let (la, lb) = (ax.hypot(ay), bx.hypot(by));
+    if la < 7.0 * min_size || lb < 7.0 * min_size || la > 1.5 * lb || lb > 1.5 * la {
+        return None;
+    }
+    let cross = ax * by - ay * bx;
+    let cos = (ax * bx + ay * by) / (la * lb);
+    if cos.abs() > 0.5 {
+        return None;
+    }
+
+    // with the y axis down, the top-right pattern is clockwise from the bottom-left one
+    Some(if cross > 0.0 { [tl, a, b] } else { [tl, b, a] })
+}
+
+// rectify and decode the code anchored on three finder patterns
+fn decode_at(
+    gray: &Image<f32, 1>,
+    binary: &Binary,
+    threshold: f32,
+    tl: FinderPattern,
+    tr: FinderPattern,
+    bl: FinderPattern,
+) -> Option<QrCode> {
+    let module_size = (tl.module_size + tr.module_size + bl.module_size) / 3.0;
+    let side = ((tr.x - tl.x).hypot(tr.y - tl.y) + (bl.x - tl.x).hypot(bl.y - tl.y)) / 2.0;
+    let estimate = ((side / module_size + 7.0 - 17.0) / 4.0)
+        .round()
+        .clamp(1.0, 40.0) as usize;
+
+    // the estimate can be off by one version for small modules
+    for version in [estimate, estimate + 1, estimate.saturating_sub(1)] {
+        if !(1..=40).contains(&version) {
+            continue;
+        }
+        let size = 17 + 4 * version;
+        let n = size as f32 - 7.0;
+
+        // the affine estimate of a module position from the finder patterns
+        let affine = |u: f32, v: f32| -> [f32; 2] {
+            let (s, t) = ((u - 3.5) / n, (v - 3.5) / n);
+            [
+                tl.x + (tr.x - tl.x) * s + (bl.x - tl.x) * t,
+                tl.y + (tr.y - tl.y) * s + (bl.y - tl.y) * t,
+            ]
+        };
+
+        let far = size as f32 - 3.5;
+        let mut module_points = vec![[3.5, 3.5], [far, 3.5], [3.5, far]];
+        let mut image_points = vec![[tl.x, tl.y], [tr.x, tr.y], [bl.x, bl.y]];
+
+        // the bottom-right alignment pattern corrects the perspective
+        let alignment = size as f32 - 6.5;
+        let found = (version >= 2)
+            .then(|| {
+                let [x, y] = affine(alignment, alignment);
+                find_alignment_pattern(binary, x, y, module_size)
+            })
+            .flatten();
+        match found {
+            Some(point) => {
+                module_points.push([alignment, alignment]);
+                image_points.push(point);
+            }
+            None => {
+                module_points.push([far, far]);
+                image_points.push(affine(far, far));
+            }
+        }
+
+        let Some(modules) = sample_modules(gray, threshold, &image_points, &module_points, size)
+        else {
+            continue;
+        };
+
+        if let Ok(content) = decode_qr_grid(&modules, size) {
+            let br = homography_from_points(&module_points, &image_points)
+                .map(|h| {
+                    let w = h[6] * far + h[7] * far + h[8];
+                    [
+                        (h[0] * far + h[1] * far + h[2]) / w,
+                        (h[3] * far + h[4] * far + h[5]) / w,
+                    ]
+                })
+                .unwrap_or(image_points[3]);
+            return Some(QrCode {
+                corners: [[tl.x, tl.y], [tr.x, tr.y], br, [bl.x, bl.y]],
+                content,
+            });
+        }
+    }
+
+    None
+}
+
+// find the center of an alignment pattern near an estimate
+fn find_alignment_pattern(binary: &Binary, x: f32, y: f32, module_size: f32) -> Option<[f32; 2]> {
+    let radius = (4.0 * module_size).ceil() as isize;
+    let directions = [
+        (1.0, 0.0),
+        (-1.0, 0.0),
+        (0.0, 1.0),
+        (0.0, -1.0),
+        (1.0, 1.0),
+        (1.0, -1.0),
+        (-1.0, 1.0),
+        (-1.0, -1.0),
+    ];
+
+    let (mut sum_x, mut sum_y, mut count) = (0.0, 0.0, 0usize);
+    for py in y as isize - radius..=y as isize + radius {
+        for px in x as isize - radius..=x as isize + radius {
+            let at = |dx: f32, dy: f32, distance: f32| {
+                binary.is_dark(
+                    (px as f32 + 0.5 + dx * distance * module_size).floor() as isize,
+                    (py as f32 + 0.5 + dy * distance * module_size).floor() as isize,
+                )
+            };
+
+            // a dark center in a light ring in a dark ring
+            let is_center = at(0.0, 0.0, 0.0) == Some(true)
+                && directions.iter().all(|&(dx, dy)| {
+                    at(dx, dy, 1.0) == Some(false) && at(dx, dy, 2.0) == Some(true)
+                });
+            if is_center {
+                sum_x += px as f32 + 0.5;
+                sum_y += py as f32 + 0.5;
+                count += 1;
+            }
+        }
+    }
+
+    (count > 0).then(|| [sum_x / count as f32, sum_y / count as f32])
+}
+
+// rectify the code with a perspective warp and threshold the centers of the modules
+fn sample_modules(
+    gray: &Image<f32, 1>,
+    threshold: f32,
+    image_points: &[[f32; 2]],
+    module_points: &[[f32; 2]],
+    size: usize,
+) -> Option<Vec<bool>> {
+    // the pixel centers are at the integer coordinates of the warp
+    let scale = PIXELS_PER_MODULE as f32;
+    let src = image_points
+        .iter()
+        .map(|p| [p[0] - 0.5, p[1] - 0.5])
+        .collect::<Vec<_>>();
+    let dst = module_points
+        .iter()
+        .map(|p| [p[0] * scale - 0.5, p[1] * scale - 0.5])
+        .collect::<Vec<_>>();
+    let m = homography_from_points(&src, &dst)?;
+
+    let side = size * PIXELS_PER_MODULE;
+    let mut rectified = Image::from_size_val(
+        ImageSize {
+            width: side,
+            height: side,
+        },
+        f32::MAX,
+    )
+    .ok()?;
+    warp_perspective(gray, &mut rectified, &m, InterpolationMode::Bilinear).ok()?;
+
+    // the mean of the central pixels of each module
+    let data = rectified.as_slice();
+    let half = PIXELS_PER_MODULE / 2;
+    let modules = (0..size * size)
+        .map(|i| {
+            let (x, y) = (
+                (i % size) * PIXELS_PER_MODULE,
+                (i / size) * PIXELS_PER_MODULE,
+            );
+            let sum = data[(y + half - 1) * side + x + half - 1]
+                + data[(y + half - 1) * side + x + half]
+                + data[(y + half) * side + x + half - 1]
+                + data[(y + half) * side + x + half];
+            sum / 4.0 <= threshold
+        })
+        .collect();
+
+    Some(modules)
+}
+
+// the homography mapping four points to four points
+fn homography_from_points(src: &[[f32; 2]], dst: &[[f32; 2]]) -> Option<[f32; 9]> {
+    // the linear system of the eight unknowns with h33 = 1
+    let mut a = [[0.0f64; 9]; 8];
+    for (i, (s, d)) in src.iter().zip(dst).take(4).enumerate() {
+        let (x, y) = (s[0] as f64, s[1] as f64);
+        let (u, v) = (d[0] as f64, d[1] as f64);
+        a[2 * i] = [x, y, 1.0, 0.0, 0.0, 0.0, -u * x, -u * y, u];
+        a[2 * i + 1] = [0.0, 0.0, 0.0, x, y, 1.0, -v * x, -v * y, v];
+    }
+
+    // gaussian elimination with partial pivoting
+    for col in 0..8 {
+        let pivot = (col..8).max_by(|&i, &j| a[i][col].abs().total_cmp(&a[j][col].abs()))?;
+        if a[pivot][col].abs() < 1e-12 {
+            return None;
+        }
+        a.swap(col, pivot);
+        for row in 0..8 {
+            if row != col {
+                let factor = a[row][col] / a[col][col];
+                let pivot_row = a[col];
+                for (value, pivot) in a[row].iter_mut().zip(&pivot_row).skip(col) {
+                    *value -= factor * pivot;
+                }
+            }
+        }
+    }
+
+    let h = std::array::from_fn::<f64, 8, _>(|i| a[i][8] / a[i][i]);
+    Some([
+        h[0] as f32,
+        h[1] as f32,
+        h[2] as f32,
+        h[3] as f32,
+        h[4] as f32,
+        h[5] as f32,
+        h[6] as f32,
+        h[7] as f32,
+        1.0,
+    ])
+}
+
+// the 15 bits of the format information with the BCH code and the mask
+fn format_bits(data: u16) -> u16 {
+    let mut rem = data;
+    for _ in 0..10 {
+        rem = (rem << 1) ^ ((rem >> 9) * 0x537);
+    }
+    ((data << 10) | (rem & 0x3ff)) ^ 0x5412
+}
+
+// the positions of the bits of both copies of the format information
+fn format_positions(size: usize) -> impl Iterator<Item = (usize, usize, usize, usize)> {
+    (0..15).map(move |i| {
+        let first = match i {
+            0..=5 => (8, i),
+            6 => (8, 7),
+            7 => (8, 8),
+            8 => (7, 8),
+            _ => (14 - i, 8),
+        };
+        let second = if i < 8 {
+            (size - 1 - i, 8)
+        } else {
+            (8, size - 15 + i)
+        };
+        (first.0, first.1, second.0, second.1)
+    })
+}
+
+fn mask_bit(mask: u8, x: usize, y: usize) -> bool {
+    match mask {
+        0 => (x + y) % 2 == 0,
+        1 => y % 2 == 0,
+        2 => x % 3 == 0,
+        3 => (x + y) % 3 == 0,
+        4 => (x / 3 + y / 2) % 2 == 0,
+        5 => x * y % 2 + x * y % 3 == 0,
+        6 => (x * y % 2 + x * y % 3) % 2 == 0,
+        _ => ((x + y) % 2 + x * y % 3) % 2 == 0,
+    }
+}
+
+// the module coordinates of the centers of the alignment patterns
+fn alignment_positions(version: usize) -> Vec<usize> {
+    if version == 1 {
+        return Vec::new();
+    }
+    let num_align = version / 7 + 2;
+    let step = if version == 32 {
+        26
+    } else {
+        (version * 4 + num_align * 2 + 1) / (num_align * 2 - 2) * 2
+    };
+    let size = 17 + 4 * version;
+    let mut positions = (0..num_align - 1)
+        .map(|i| size - 7 - i * step)
+        .collect::<Vec<_>>();
+    positions.push(6);
+    positions.reverse();
+    positions
+}
+
+// the modules of the finder, timing, alignment, format and version patterns
+fn function_modules(version: usize) -> Vec<bool> {
+    let size = 17 + 4 * version;
+    let mut function = vec![false; size * size];
+    let mut fill = |x0: usize, y0: usize, w: usize, h: usize| {
+        for y in y0..y0 + h {
+            function[y * size + x0..y * size + x0 + w].fill(true);
+        }
+    };
+
+    // the finder patterns with their separators and the format information
+    fill(0, 0, 9, 9);
+    fill(size - 8, 0, 8, 9);
+    fill(0, size - 8, 9, 8);
+
+    // the timing patterns
+    fill(6, 0, 1, size);
+    fill(0, 6, size, 1);
+
+    let positions = alignment_positions(version);
+    let last = positions.len().saturating_sub(1);
+    for (i, &y) in positions.iter().enumerate() {
+        for (j, &x) in positions.iter().enumerate() {
+            // skip the corners of the finder patterns
+            if (i == 0 && (j == 0 || j == last)) || (i == last && j == 0) {
+                continue;
+            }
+            fill(x - 2, y - 2, 5, 5);
+        }
+    }
+
+    if version >= 7 {
+        fill(size - 11, 0, 3, 6);
+        fill(0, size - 11, 6, 3);
+    }
+
+    function
+}
+
+// the number of modules of the data and the error correction
+fn num_raw_data_modules(version: usize) -> usize {
+    let mut result = (16 * version + 128) * version + 64;
+    if version >= 2 {
+        let num_align = version / 7 + 2;
+        result -= (25 * num_align - 10) * num_align - 55;
+        if version >= 7 {
+            result -= 36;
+        }
+    }
+    result
+}
+
+// the module coordinates in the order of the data bits
+fn zigzag(size: usize) -> impl Iterator<Item = (usize, usize)> {
+    // the column pairs from the right, skipping the vertical timing pattern
+    (0..size / 2).flat_map(move |pair| {
+        let right = if size - 1 - 2 * pair <= 6 {
+            size - 2 - 2 * pair
+        } else {
+            size - 1 - 2 * pair
+        };
+        let upward = pair % 2 == 0;
+        (0..size).flat_map(move |vert| {
+            let y = if upward { size - 1 - vert } else { vert };
+            [(right, y), (right - 1, y)]
+        })
+    })
+}
+
+// the block layout: the number of blocks, of short blocks, the length of a short block
+// and the number of error correction bytes per block
+fn block_layout(version: usize, level: ErrorCorrectionLevel) -> (usize, usize, usize, usize) {
+    let num_blocks = NUM_ERROR_CORRECTION_BLOCKS[level.index()][version] as usize;
+    let block_ecc = ECC_CODEWORDS_PER_BLOCK[level.index()][version] as usize;
+    let num_codewords = num_raw_data_modules(version) / 8;
+    let num_short = num_blocks - num_codewords % num_blocks;
+    (num_blocks, num_short, num_codewords / num_blocks, block_ecc)
+}
+
+// deinterleave the blocks, correct them and concatenate their data
+fn correct_blocks(
+    codewords: &[u8],
+    version: usize,
+    level: ErrorCorrectionLevel,
+) -> Result<Vec<u8>, BarcodeError> {
+    let (num_blocks, num_short, short_len, block_ecc) = block_layout(version, level);
+    let short_data = short_len - block_ecc;
+
+    // the short blocks have one data byte less than the long blocks
+    let mut blocks = (0..num_blocks)
+        .map(|b| Vec::with_capacity(short_len + (b >= num_short) as usize))
+        .collect::<Vec<Vec<u8>>>();
+    let mut bytes = codewords.iter();
+    for i in 0..=short_len {
+        for (b, block) in blocks.iter_mut().enumerate() {
+            if i == short_data && b < num_short {
+                continue;
+            }
+            if let Some(&byte) = bytes.next() {
+                block.push(byte);
+            }
+        }
+    }
+
+    let mut data = Vec::new();
+    for mut block in blocks {
+        reed_solomon::correct(&mut block, block_ecc).ok_or(BarcodeError::TooManyErrors)?;
+        data.extend_from_slice(&block[..block.len() - block_ecc]);
+    }
+
+    Ok(data)
+}
+
+// a reader of the bits with the most significant first
+struct BitReader<'a> {
+    data: &'a [u8],
+    position: usize,
+}
+
+impl BitReader<'_> {
+    fn remaining(&self) -> usize {
+        self.data.len() * 8 - self.position
+    }
+
+    fn read(&mut self, num_bits: usize) -> Result<u32, BarcodeError> {
+        if num_bits > self.remaining() {
+            return Err(BarcodeError::TruncatedData);
+        }
+        let mut value = 0;
+        for _ in 0..num_bits {
+            let bit = (self.data[self.position / 8] >> (7 - self.position % 8)) & 1;
+            value = (value << 1) | bit as u32;
+            self.position += 1;
+        }
+        Ok(value)
+    }
+}
+
+// decode the numeric, alphanumeric and byte segments
+fn parse_segments(data: &[u8], version: usize) -> Result<Vec<u8>, BarcodeError> {
+    let mut reader = BitReader { data, position: 0 };
+    let group = match version {
+        1..=9 => 0,
+        10..=26 => 1,
+        _ => 2,
+    };
+    let mut result = Vec::new();
+
+    while reader.remaining() >= 4 {
+        let mode = reader.read(4)? as u8;
+        match mode {
+            // the terminator
+            0 => break,
+            // numeric, three digits in 10 bits
+            1 => {
+                let mut count = reader.read([10, 12, 14][group])? as usize;
+                while count > 0 {
+                    let digits = count.min(3);
+                    let value = reader.read([4, 7, 10][digits - 1])?;
+                    let text = format!("{value:0digits$}");
+                    result.extend_from_slice(text.as_bytes());
+                    count -= digits;
+                }
+            }
+            // alphanumeric, two characters in 11 bits
+            2 => {
+                let mut count = reader.read([9, 11, 13][group])? as usize;
+                let char_at = |i: u32| {
+                    ALPHANUMERIC
+                        .get(i as usize)
+                        .copied()
+                        .ok_or(BarcodeError::TruncatedData)
+                };
+                while count >= 2 {
+                    let value = reader.read(11)?;
+                    result.push(char_at(value / 45)?);
+                    result.push(char_at(value % 45)?);
+                    count -= 2;
+                }
+                if count == 1 {
+                    result.push(char_at(reader.read(6)?)?);
+                }
+            }
+            // bytes
+            4 => {
+                let count = reader.read([8, 16, 16][group])?;
+                for _ in 0..count {
+                    result.push(reader.read(8)? as u8);
+                }
+            }
+            // the extended channel interpretation is skipped
+            7 => {
+                let first = reader.read(8)?;
+                if first & 0x80 != 0 {
+                    reader.read(if first & 0x40 == 0 { 8 } else { 16 })?;
+                }
+            }
+            _ => return Err(BarcodeError::UnsupportedMode(mode)),
+        }
+    }
+
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::barcode::reed_solomon;
+
+    // encode a byte segment padded to the capacity of the version
+    fn byte_segment(text: &[u8], version: usize, level: ErrorCorrectionLevel) -> Vec<u8> {
+        let (num_blocks, _, _, block_ecc) = block_layout(version, level);
+        let capacity = num_raw_data_modules(version) / 8 - num_blocks * block_ecc;
+
+        fn push(bits: &mut Vec<bool>, value: u32, n: usize) {
+            (0..n).rev().for_each(|i| bits.push((value >> i) & 1 == 1));
+        }
+
+        let mut bits = Vec::new();
+        push(&mut bits, 4, 4);
+        push(
+            &mut bits,
+            text.len() as u32,
+            if version <= 9 { 8 } else { 16 },
+        );
+        text.iter().for_each(|&b| push(&mut bits, b as u32, 8));
+        let terminator = 4.min(capacity * 8 - bits.len());
+        push(&mut bits, 0, terminator);
+        while bits.len() % 8 != 0 {
+            bits.push(false);
+        }
+
+        let mut bytes = bits
+            .chunks(8)
+            .map(|c| c.iter().fold(0u8, |acc, &b| (acc << 1) | b as u8))
+            .collect::<Vec<_>>();
+        for pad in [0xec, 0x11].iter().cycle() {
+            if bytes.len() >= capacity {
+                break;
+            }
+            bytes.push(*pad);
+        }
+        bytes
+    }
+
+    // build the modules of a code from its data codewords
+    fn encode(data: &[u8], version: usize, level: ErrorCorrectionLevel, mask: u8) -> Vec<bool> {
+        let size = 17 + 4 * version;
+        let (num_blocks, num_short, short_len, block_ecc) = block_layout(version, level);
+
+        // split the data in blocks and interleave them with their error correction
+        let mut blocks = Vec::new();
+        let mut offset = 0;
+        for b in 0..num_blocks {
+            let len = short_len - block_ecc + (b >= num_short) as usize;
+            let block = &data[offset..offset + len];
+            blocks.push((block.to_vec(), reed_solomon::encode(block, block_ecc)));
+            offset += len;
+        }
+        let mut codewords = Vec::new();
+        for i in 0..=short_len - block_ecc {
+            for (block, _) in &blocks {
+                if let Some(&byte) = block.get(i) {
+                    codewords.push(byte);
+                }
+            }
+        }
+        for i in 0..block_ecc {
+            for (_, ecc) in &blocks {
+                codewords.push(ecc[i]);
+            }
+        }
+
+        let mut modules = vec![false; size * size];
+        let function = function_modules(version);
+
+        // the finder patterns
+        for (cx, cy) in [(3, 3), (size - 4, 3), (3, size - 4)] {
+            for dy in -3i32..=3 {
+                for dx in -3i32..=3 {
+                    let ring = dx.abs().max(dy.abs());
+                    let (x, y) = ((cx as i32 + dx) as usize, (cy as i32 + dy) as usize);
+                    modules[y * size + x] = ring != 2;
+                }
+            }
+        }
+        for i in 8..size - 8 {
+            modules[6 * size + i] = i % 2 == 0;
+            modules[i * size + 6] = i % 2 == 0;
+        }
+        let positions = alignment_positions(version);
+        for &y in &positions {
+            for &x in &positions {
+                if (y < 9 && (x < 9 || x > size - 9)) || (x < 9 && y > size - 9) {
+                    continue;
+                }
+                for dy in -2i32..=2 {
+                    for dx in -2i32..=2 {
+                        let (mx, my) = ((x as i32 + dx) as usize, (y as i32 + dy) as usize);
+                        modules[my * size + mx] = dx.abs().max(dy.abs()) != 1;
+                    }
+                }
+            }
+        }
+
+        let format = format_bits((level.format_bits() << 3) | mask as u16);
+        for (i, (x, y, x2, y2)) in format_positions(size).enumerate() {
+            modules[y * size + x] = (format >> i) & 1 == 1;
+            modules[y2 * size + x2] = (format >> i) & 1 == 1;
+        }
+        modules[(size - 8) * size + 8] = true;
+
+        let mut bit = 0;
+        for (x, y) in zigzag(size) {
+            if function[y * size + x] {
+                continue;
+            }
+            let value = bit < codewords.len() * 8 && codewords[bit / 8] & (0x80 >> (bit % 8)) != 0;
+            modules[y * size + x] = value ^ mask_bit(mask, x, y);
+            bit += 1;
+        }
+
+        modules
+    }
+
+    // render the modules with a quiet zone of four modules
+    fn render(modules: &[bool], size: usize, scale: usize) -> Image<u8, 1> {
+        let side = (size + 8) * scale;
+        let data = (0..side * side)
+            .map(|i| {
+                let (x, y) = ((i % side) / scale, (i / side) / scale);
+                let inside = (4..size + 4).contains(&x) && (4..size + 4).contains(&y);
+                if inside && modules[(y - 4) * size + x - 4] {
+                    0
+                } else {
+                    255
+                }
+            })
+            .collect();
+        Image::new([side, side].into(), data).unwrap()
+    }
+
+    #[test]
+    fn test_format_bits() {
+        // the level L with the mask 4
+        assert_eq!(format_bits((1 << 3) | 4), 0b110011000101111);
+        assert_eq!(alignment_positions(7), vec![6, 22, 38]);
+        assert_eq!(num_raw_data_modules(1) / 8, 26);
+    }
+
+    #[test]
+    fn test_decode_qr_grid() -> Result<(), BarcodeError> {
+        // HELLO WORLD in alphanumeric mode
+        let data = [32, 91, 11, 120, 209, 114, 220, 77, 67, 64, 236, 17, 236];
+        let modules = encode(&data, 1, ErrorCorrectionLevel::Quartile, 2);
+        let content = decode_qr_grid(&modules, 21)?;
+        assert_eq!(content.as_str(), Some("HELLO WORLD"));
+        assert_eq!(content.error_correction, ErrorCorrectionLevel::Quartile);
+
+        // a larger version with several blocks and some damaged modules
+        let text = b"https://github.com/kornia/kornia-rs";
+        for (version, level) in [
+            (5, ErrorCorrectionLevel::Quartile),
+            (7, ErrorCorrectionLevel::Medium),
+            (10, ErrorCorrectionLevel::Low),
+        ] {
+            let data = byte_segment(text, version, level);
+            let size = 17 + 4 * version;
+            let mut modules = encode(&data, version, level, (version % 8) as u8);
+            for i in 0..6 {
+                modules[(size / 2 + i) * size + size - 2] ^= true;
+            }
+            let content = decode_qr_grid(&modules, size)?;
+            assert_eq!(content.data, text);
+            assert_eq!(content.version, version);
+        }
+
+        assert!(matches!(
+            decode_qr_grid(&modules, 22),
+            Err(BarcodeError::InvalidSize(22))
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_detect_qr_codes() -> Result<(), BarcodeError> {
+        let text = b"kornia";
+        let data = byte_segment(text, 2, ErrorCorrectionLevel::Medium);
+        let modules = encode(&data, 2, ErrorCorrectionLevel::Medium, 5);
+        let image = render(&modules, 25, 4);
+
+        let patterns = find_qr_finder_patterns(&image)?;
+        assert_eq!(patterns.len(), 3);
+
+        let codes = detect_qr_codes(&image)?;
+        assert_eq!(codes.len(), 1);
+        assert_eq!(codes[0].content.data, text);
+        assert!((codes[0].corners[0][0] - 30.0).abs() < 1.0);
+
+        // a perspective view of the code
+        let gray = image.map(|&v| v as f32)?;
+        let m = [0.9, 0.1, 10.0, -0.05, 0.95, 15.0, 0.0005, 0.0003, 1.0];
+        let mut warped = Image::from_size_val([160, 160].into(), 255.0)?;
+        warp_perspective(&gray, &mut warped, &m, InterpolationMode::Bilinear)?;
+        let warped = warped.map(|&v| v.round() as u8)?;
+
+        let codes = detect_qr_codes(&warped)?;
+        assert_eq!(codes.len(), 1);
+        assert_eq!(codes[0].content.data, text);
+
+        Ok(())
+    }
+}
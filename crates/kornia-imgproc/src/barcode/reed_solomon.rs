@@ -0,0 +1,207 @@
+// Reed-Solomon codes over GF(256) with the polynomial of QR codes, x^8 + x^4 + x^3 + x^2 + 1,
+// and the consecutive roots of the generator starting at 1.
+
+const fn gf_tables() -> ([u8; 512], [u8; 256]) {
+    let mut exp = [0u8; 512];
+    let mut log = [0u8; 256];
+    let mut x: u16 = 1;
+    let mut i = 0;
+    while i < 255 {
+        exp[i] = x as u8;
+        log[x as usize] = i as u8;
+        x <<= 1;
+        if x & 0x100 != 0 {
+            x ^= 0x11d;
+        }
+        i += 1;
+    }
+    while i < 512 {
+        exp[i] = exp[i - 255];
+        i += 1;
+    }
+    (exp, log)
+}
+
+const GF_TABLES: ([u8; 512], [u8; 256]) = gf_tables();
+const EXP: [u8; 512] = GF_TABLES.0;
+const LOG: [u8; 256] = GF_TABLES.1;
+
+fn mul(a: u8, b: u8) -> u8 {
+    if a == 0 || b == 0 {
+        return 0;
+    }
+    EXP[LOG[a as usize] as usize + LOG[b as usize] as usize]
+}
+
+fn div(a: u8, b: u8) -> u8 {
+    if a == 0 {
+        return 0;
+    }
+    EXP[LOG[a as usize] as usize + 255 - LOG[b as usize] as usize]
+}
+
+// evaluate a polynomial with the coefficients from the lowest degree
+fn eval(poly: &[u8], x: u8) -> u8 {
+    poly.iter().rev().fold(0, |acc, &c| mul(acc, x) ^ c)
+}
+
+/// Correct the errors of a codeword in place.
+///
+/// The codeword is the data followed by the `num_ecc` error correction bytes, with the
+/// coefficient of the highest degree first. Returns the number of corrected bytes or None
+/// when the errors cannot be corrected.
+pub(crate) fn correct(codeword: &mut [u8], num_ecc: usize) -> Option<usize> {
+    let n = codeword.len();
+
+    // the syndromes are the codeword evaluated at the roots of the generator
+    let syndromes = (0..num_ecc)
+        .map(|j| codeword.iter().fold(0, |acc, &c| mul(acc, EXP[j]) ^ c))
+        .collect::<Vec<_>>();
+    if syndromes.iter().all(|&s| s == 0) {
+        return Some(0);
+    }
+
+    // the error locator with the Berlekamp-Massey algorithm
+    let mut locator = vec![1u8];
+    let mut previous = vec![1u8];
+    let mut num_errors = 0;
+    let mut shift = 1;
+    let mut previous_discrepancy = 1u8;
+
+    for r in 0..num_ecc {
+        let mut discrepancy = syndromes[r];
+        for i in 1..=num_errors.min(locator.len() - 1) {
+            discrepancy ^= mul(locator[i], syndromes[r - i]);
+        }
+
+        if discrepancy == 0 {
+            shift += 1;
+            continue;
+        }
+
+        let coef = div(discrepancy, previous_discrepancy);
+        let mut next = locator.clone();
+        next.resize(next.len().max(previous.len() + shift), 0);
+        for (i, &b) in previous.iter().enumerate() {
+            next[i + shift] ^= mul(coef, b);
+        }
+
+        if 2 * num_errors <= r {
+            previous = locator;
+            num_errors = r + 1 - num_errors;
+            previous_discrepancy = discrepancy;
+            shift = 1;
+        } else {
+            shift += 1;
+        }
+        locator = next;
+    }
+
+    while locator.last() == Some(&0) {
+        locator.pop();
+    }
+    if locator.len() - 1 != num_errors || 2 * num_errors > num_ecc {
+        return None;
+    }
+
+    // the error evaluator, the syndromes times the locator modulo x^num_ecc
+    let mut evaluator = vec![0u8; num_ecc];
+    for (i, &l) in locator.iter().enumerate() {
+        for (j, &s) in syndromes.iter().enumerate() {
+            if i + j < num_ecc {
+                evaluator[i + j] ^= mul(l, s);
+            }
+        }
+    }
+
+    // the formal derivative of the locator keeps the odd terms
+    let derivative = locator
+        .iter()
+        .enumerate()
+        .skip(1)
+        .map(|(i, &l)| if i % 2 == 1 { l } else { 0 })
+        .collect::<Vec<_>>();
+
+    // find the roots with the Chien search and the values with the Forney algorithm
+    let mut num_corrected = 0;
+    for (k, byte) in codeword.iter_mut().enumerate() {
+        let power = (n - 1 - k) % 255;
+        let x = EXP[power];
+        let x_inv = EXP[(255 - power) % 255];
+        if eval(&locator, x_inv) != 0 {
+            continue;
+        }
+
+        let denominator = eval(&derivative, x_inv);
+        if denominator == 0 {
+            return None;
+        }
+        *byte ^= mul(x, div(eval(&evaluator, x_inv), denominator));
+        num_corrected += 1;
+    }
+
+    (num_corrected == num_errors).then_some(num_corrected)
+}
+
+/// Compute the error correction bytes of the data.
+#[cfg(test)]
+pub(crate) fn encode(data: &[u8], num_ecc: usize) -> Vec<u8> {
+    // the generator with the highest degree first
+    let mut generator = vec![1u8];
+    for &root in &EXP[..num_ecc] {
+        let mut next = generator.clone();
+        next.push(0);
+        for (j, &g) in generator.iter().enumerate() {
+            next[j + 1] ^= mul(g, root);
+        }
+        generator = next;
+    }
+
+    let mut message = data.to_vec();
+    message.resize(data.len() + num_ecc, 0);
+    for i in 0..data.len() {
+        let coef = message[i];
+        if coef != 0 {
+            for (j, &g) in generator.iter().enumerate().skip(1) {
+                message[i + j] ^= mul(g, coef);
+            }
+        }
+    }
+
+    message.split_off(data.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reed_solomon() {
+        // the data codewords of HELLO WORLD in a version 1-Q QR code
+        let data = [32, 91, 11, 120, 209, 114, 220, 77, 67, 64, 236, 17, 236];
+        let ecc = encode(&data, 13);
+        assert_eq!(
+            ecc,
+            vec![168, 72, 22, 82, 217, 54, 156, 0, 46, 15, 180, 122, 16]
+        );
+
+        let codeword = [data.to_vec(), ecc].concat();
+        let mut corrupted = codeword.clone();
+        assert_eq!(correct(&mut corrupted, 13), Some(0));
+
+        // up to half of the error correction bytes can be corrected
+        for (i, k) in [0, 3, 7, 12, 15, 20].iter().enumerate() {
+            corrupted[*k] ^= 0x55 + i as u8;
+        }
+        assert_eq!(correct(&mut corrupted, 13), Some(6));
+        assert_eq!(corrupted, codeword);
+
+        for k in 0..8 {
+            corrupted[k * 3] ^= 0xff;
+        }
+        assert_ne!(
+            correct(&mut corrupted, 13).map(|_| &corrupted),
+            Some(&codeword)
+        );
+    }
+}
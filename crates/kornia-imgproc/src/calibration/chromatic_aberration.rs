@@ -0,0 +1,405 @@
+use super::{max_radius2, solve3};
+use crate::interpolation::{grid::meshgrid_from_fn, interpolate_pixel, InterpolationMode};
+use crate::parallel::prelude::*;
+use kornia_image::{Image, ImageError, ImageSize};
+use kornia_tensor::{CpuTensor2, TensorError};
+
+// the side of the tiles matched between the channels for the estimation
+const TILE_SIZE: usize = 32;
+
+// the largest shift in pixels searched between the channels
+const MAX_SHIFT: isize = 4;
+
+/// The radial magnification of a channel relative to the reference channel.
+///
+/// A point at the normalized radius r in the reference channel appears at the radius
+/// r (scale + k1 r^2 + k2 r^4) in the channel, where the radius is the distance to the
+/// center divided by the distance from the center to the farthest corner.
+///
+/// # Fields
+///
+/// * `scale` - The linear magnification
+/// * `k1` - The cubic coefficient
+/// * `k2` - The quintic coefficient
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RadialScale {
+    /// The linear magnification
+    pub scale: f64,
+    /// The cubic coefficient
+    pub k1: f64,
+    /// The quintic coefficient
+    pub k2: f64,
+}
+
+impl Default for RadialScale {
+    fn default() -> Self {
+        Self {
+            scale: 1.0,
+            k1: 0.0,
+            k2: 0.0,
+        }
+    }
+}
+
+impl RadialScale {
+    fn factor(&self, r2: f64) -> f64 {
+        self.scale + r2 * (self.k1 + r2 * self.k2)
+    }
+}
+
+/// The lateral chromatic aberration of a lens, as a radial magnification of every channel.
+///
+/// # Fields
+///
+/// * `center` - The optical center as [x, y] in pixels
+/// * `channels` - The magnification of every channel, the identity for the reference
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ChromaticAberration<const C: usize> {
+    /// The optical center as [x, y] in pixels
+    pub center: [f64; 2],
+    /// The magnification of every channel, the identity for the reference
+    pub channels: [RadialScale; C],
+}
+
+impl<const C: usize> ChromaticAberration<C> {
+    /// The position in a channel of a point of the reference channel.
+    pub fn distort_point(&self, x: f64, y: f64, channel: usize, size: ImageSize) -> (f64, f64) {
+        let (dx, dy) = (x - self.center[0], y - self.center[1]);
+        let r2 = (dx * dx + dy * dy) / max_radius2(self.center, size);
+        let factor = self.channels[channel].factor(r2);
+        (self.center[0] + dx * factor, self.center[1] + dy * factor)
+    }
+}
+
+/// The remap tables which correct the chromatic aberration of every channel.
+///
+/// The tables are computed once with [`generate_chromatic_aberration_maps`] and applied to
+/// every frame with [`correct_chromatic_aberration`].
+pub struct ChromaticAberrationMaps {
+    size: ImageSize,
+    maps: Vec<(CpuTensor2<f32>, CpuTensor2<f32>)>,
+}
+
+/// Generate the remap tables which correct the chromatic aberration.
+///
+/// # Arguments
+///
+/// * `aberration` - The chromatic aberration of the lens
+/// * `size` - The size of the images to correct
+///
+/// # Returns
+///
+/// The tables which sample every channel at the position of the reference channel.
+pub fn generate_chromatic_aberration_maps<const C: usize>(
+    aberration: &ChromaticAberration<C>,
+    size: &ImageSize,
+) -> Result<ChromaticAberrationMaps, TensorError> {
+    let maps = (0..C)
+        .map(|c| {
+            meshgrid_from_fn(size.width, size.height, |x, y| {
+                let (xs, ys) = aberration.distort_point(x as f64, y as f64, c, *size);
+                Ok((xs as f32, ys as f32))
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(ChromaticAberrationMaps { size: *size, maps })
+}
+
+/// Correct the chromatic aberration of an image.
+///
+/// Every channel is resampled with its own table, and the positions outside of the image
+/// are clamped to its borders.
+///
+/// # Arguments
+///
+/// * `src` - The image with shape (H, W, C).
+/// * `dst` - The corrected image with shape (H, W, C).
+/// * `maps` - The tables of the aberration, generated for C channels and the image size.
+/// * `interpolation` - The interpolation mode to use.
+pub fn correct_chromatic_aberration<const C: usize>(
+    src: &Image<f32, C>,
+    dst: &mut Image<f32, C>,
+    maps: &ChromaticAberrationMaps,
+    interpolation: InterpolationMode,
+) -> Result<(), ImageError> {
+    for size in [src.size(), dst.size()] {
+        if size != maps.size {
+            return Err(ImageError::InvalidImageSize(
+                size.width,
+                size.height,
+                maps.size.width,
+                maps.size.height,
+            ));
+        }
+    }
+    if maps.maps.len() != C {
+        return Err(ImageError::ChannelIndexOutOfBounds(maps.maps.len(), C));
+    }
+
+    let cols = src.cols();
+    let (max_x, max_y) = ((src.cols() - 1) as f32, (src.rows() - 1) as f32);
+    dst.as_slice_mut()
+        .par_chunks_exact_mut(C)
+        .enumerate()
+        .for_each(|(i, pixel)| {
+            let (x, y) = (i % cols, i / cols);
+            for (c, (value, (map_x, map_y))) in pixel.iter_mut().zip(&maps.maps).enumerate() {
+                let u = map_x.get_unchecked([y, x]).clamp(0.0, max_x);
+                let v = map_y.get_unchecked([y, x]).clamp(0.0, max_y);
+                *value = interpolate_pixel(src, u, v, c, interpolation);
+            }
+        });
+
+    Ok(())
+}
+
+/// Estimate the chromatic aberration from an image of a calibration chart.
+///
+/// The image is split in tiles, and the textured tiles of every channel are matched with
+/// the reference channel with the normalized cross-correlation, to the sub-pixel. The
+/// radial components of the shifts are fitted with the magnification of the channel
+/// around the center of the image. Any chart with a dense texture, such as a checkerboard
+/// or a grid of dots, covering the image is suitable.
+///
+/// # Arguments
+///
+/// * `src` - The image of the chart with shape (H, W, C).
+/// * `reference` - The index of the reference channel, usually the green one.
+///
+/// # Errors
+///
+/// Returns an error if the reference channel is out of bounds or the image does not have
+/// enough textured tiles.
+pub fn estimate_chromatic_aberration<const C: usize>(
+    src: &Image<f32, C>,
+    reference: usize,
+) -> Result<ChromaticAberration<C>, ImageError> {
+    if reference >= C {
+        return Err(ImageError::ChannelIndexOutOfBounds(reference, C));
+    }
+
+    let size = src.size();
+    let center = [
+        0.5 * (size.width as f64 - 1.0),
+        0.5 * (size.height as f64 - 1.0),
+    ];
+    let max_r2 = max_radius2(center, size);
+    let channel = |c: usize| {
+        src.as_slice()
+            .iter()
+            .skip(c)
+            .step_by(C)
+            .copied()
+            .collect::<Vec<_>>()
+    };
+    let reference_data = channel(reference);
+
+    // the tiles that fit with the margin of the search
+    let margin = MAX_SHIFT as usize + 1;
+    let tiles = (0..)
+        .map(|j| margin + j * TILE_SIZE)
+        .take_while(|y| y + TILE_SIZE + margin <= size.height)
+        .flat_map(|y| {
+            (0..)
+                .map(move |i| (margin + i * TILE_SIZE, y))
+                .take_while(|(x, _)| x + TILE_SIZE + margin <= size.width)
+        })
+        .collect::<Vec<_>>();
+
+    let mut channels = [RadialScale::default(); C];
+    for (c, scale) in channels.iter_mut().enumerate() {
+        if c == reference {
+            continue;
+        }
+
+        let data = channel(c);
+        let shifts = tiles
+            .par_iter()
+            .filter_map(|&(x, y)| {
+                match_tile(&reference_data, &data, size.width, x, y).map(|shift| (x, y, shift))
+            })
+            .collect::<Vec<_>>();
+
+        // the least squares of the radial shifts d = r (scale - 1) + k1 r^3 + k2 r^5
+        let mut ata = [[0.0f64; 3]; 3];
+        let mut atb = [0.0f64; 3];
+        for (x, y, [sx, sy]) in &shifts {
+            let tile_center = TILE_SIZE as f64 / 2.0 - 0.5;
+            let dx = *x as f64 + tile_center - center[0];
+            let dy = *y as f64 + tile_center - center[1];
+            let radius = dx.hypot(dy);
+            if radius < 1.0 {
+                continue;
+            }
+
+            // the radial shift and the radius normalized by the farthest corner
+            let norm = max_r2.sqrt();
+            let shift = (sx * dx + sy * dy) / radius / norm;
+            let r = radius / norm;
+            let basis = [r, r.powi(3), r.powi(5)];
+            for i in 0..3 {
+                for j in 0..3 {
+                    ata[i][j] += basis[i] * basis[j];
+                }
+                atb[i] += basis[i] * shift;
+            }
+        }
+
+        let [s, k1, k2] = solve3(ata, atb).ok_or(ImageError::CannotComputeDeterminant)?;
+        *scale = RadialScale {
+            scale: 1.0 + s,
+            k1,
+            k2,
+        };
+    }
+
+    Ok(ChromaticAberration { center, channels })
+}
+
+// the sub-pixel shift of a tile of a channel relative to the reference, if it is textured
+fn match_tile(
+    reference: &[f32],
+    channel: &[f32],
+    cols: usize,
+    x: usize,
+    y: usize,
+) -> Option<[f64; 2]> {
+    let tile = |data: &[f32], x: usize, y: usize| {
+        (0..TILE_SIZE)
+            .flat_map(|j| data[(y + j) * cols + x..(y + j) * cols + x + TILE_SIZE].iter())
+            .map(|&v| v as f64)
+            .collect::<Vec<_>>()
+    };
+    let normalize = |mut values: Vec<f64>| {
+        let n = values.len() as f64;
+        let mean = values.iter().sum::<f64>() / n;
+        let var = values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / n;
+        values
+            .iter_mut()
+            .for_each(|v| *v = (*v - mean) / var.sqrt().max(1e-12));
+        (values, var)
+    };
+
+    let (target, var) = normalize(tile(reference, x, y));
+    if var < 1e-4 {
+        return None;
+    }
+
+    // the correlation of the shifted tiles of the channel
+    let side = (2 * MAX_SHIFT + 1) as usize;
+    let mut scores = vec![f64::MIN; side * side];
+    for (k, score) in scores.iter_mut().enumerate() {
+        let sx = (x as isize + (k % side) as isize - MAX_SHIFT) as usize;
+        let sy = (y as isize + (k / side) as isize - MAX_SHIFT) as usize;
+        let (candidate, var) = normalize(tile(channel, sx, sy));
+        if var >= 1e-4 {
+            *score = target
+                .iter()
+                .zip(&candidate)
+                .map(|(a, b)| a * b)
+                .sum::<f64>();
+        }
+    }
+
+    let best = (0..scores.len()).max_by(|&a, &b| scores[a].total_cmp(&scores[b]))?;
+    let (bx, by) = (best % side, best / side);
+    if bx == 0 || by == 0 || bx == side - 1 || by == side - 1 {
+        return None;
+    }
+
+    // the parabolic refinement of the peak
+    let refine = |minus: f64, peak: f64, plus: f64| {
+        let denominator = minus - 2.0 * peak + plus;
+        if denominator.abs() < 1e-12 {
+            0.0
+        } else {
+            0.5 * (minus - plus) / denominator
+        }
+    };
+    let s = &scores;
+    let dx = refine(s[best - 1], s[best], s[best + 1]);
+    let dy = refine(s[best - side], s[best], s[best + side]);
+
+    Some([
+        (bx as isize - MAX_SHIFT) as f64 + dx,
+        (by as isize - MAX_SHIFT) as f64 + dy,
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // a chart of smooth dots, with the red and blue channels magnified by the aberration
+    fn chart(aberration: &ChromaticAberration<3>, size: ImageSize) -> Image<f32, 3> {
+        let pattern = |x: f64, y: f64| ((0.4 * x).sin() * (0.4 * y).sin()) as f32;
+        let mut data = Vec::with_capacity(size.width * size.height * 3);
+        for y in 0..size.height {
+            for x in 0..size.width {
+                for c in 0..3 {
+                    // the point of the channel comes from the inverse magnification
+                    let (mut u, mut v) = (x as f64, y as f64);
+                    for _ in 0..10 {
+                        let (dx, dy) = aberration.distort_point(u, v, c, size);
+                        (u, v) = (u + x as f64 - dx, v + y as f64 - dy);
+                    }
+                    data.push(pattern(u, v));
+                }
+            }
+        }
+        Image::new(size, data).unwrap()
+    }
+
+    #[test]
+    fn test_chromatic_aberration() -> Result<(), ImageError> {
+        let size = ImageSize {
+            width: 240,
+            height: 180,
+        };
+        let aberration = ChromaticAberration {
+            center: [119.5, 89.5],
+            channels: [
+                RadialScale {
+                    scale: 1.01,
+                    k1: 0.005,
+                    k2: 0.0,
+                },
+                RadialScale::default(),
+                RadialScale {
+                    scale: 0.995,
+                    k1: -0.004,
+                    k2: 0.0,
+                },
+            ],
+        };
+        let src = chart(&aberration, size);
+
+        let estimated = estimate_chromatic_aberration(&src, 1)?;
+        assert_eq!(estimated.channels[1], RadialScale::default());
+        for c in [0, 2] {
+            for (x, y) in [(0.0, 0.0), (60.0, 45.0), (239.0, 90.0)] {
+                let a = estimated.distort_point(x, y, c, size);
+                let b = aberration.distort_point(x, y, c, size);
+                assert!((a.0 - b.0).abs() < 0.15 && (a.1 - b.1).abs() < 0.15);
+            }
+        }
+
+        // the corrected channels are aligned with the reference
+        let maps = generate_chromatic_aberration_maps(&estimated, &size)?;
+        let mut corrected = Image::from_size_val(size, 0.0)?;
+        correct_chromatic_aberration(&src, &mut corrected, &maps, InterpolationMode::Bilinear)?;
+
+        let misalignment = |image: &Image<f32, 3>| {
+            image
+                .as_slice()
+                .chunks_exact(3)
+                .map(|p| (p[0] - p[1]).abs() + (p[2] - p[1]).abs())
+                .sum::<f32>()
+                / (size.width * size.height) as f32
+        };
+        assert!(misalignment(&corrected) < 0.25 * misalignment(&src));
+
+        Ok(())
+    }
+}
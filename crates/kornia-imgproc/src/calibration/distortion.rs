@@ -17,6 +17,8 @@ use kornia_tensor::{CpuTensor2, TensorError};
 /// # Note
 ///
 /// Higher-order coefficients (k4-k6) are often set to zero for simpler models.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PolynomialDistortion {
     /// The first radial distortion coefficient
     pub k1: f64,
@@ -147,6 +149,91 @@ pub fn generate_correction_map_polynomial(
     Ok((map_x, map_y))
 }
 
+/// Represents the distortion parameters of a fisheye camera using the Kannala-Brandt model.
+///
+/// The angle θ between a ray and the optical axis is distorted into
+/// θd = θ (1 + k1 θ^2 + k2 θ^4 + k3 θ^6 + k4 θ^8), which is projected at the distance θd
+/// from the principal point in normalized coordinates, as the OpenCV fisheye model.
+///
+/// # Fields
+///
+/// * `k1`, `k2`, `k3`, `k4` - The distortion coefficients
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FisheyeDistortion {
+    /// The first distortion coefficient
+    pub k1: f64,
+    /// The second distortion coefficient
+    pub k2: f64,
+    /// The third distortion coefficient
+    pub k3: f64,
+    /// The fourth distortion coefficient
+    pub k4: f64,
+}
+
+/// The lens distortion of a camera with one of the supported models.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum CameraDistortion {
+    /// The Brown-Conrady model, e.g. the `plumb_bob` and `rational_polynomial` models.
+    Polynomial(PolynomialDistortion),
+    /// The Kannala-Brandt model, e.g. the `equidistant` model.
+    Fisheye(FisheyeDistortion),
+}
+
+/// Projects a 3D point in the camera frame with the fisheye model.
+///
+/// The points up to 180 degrees away from the optical axis are projected, including the
+/// points behind the camera.
+///
+/// # Arguments
+///
+/// * `point` - The point [x, y, z] in the camera frame
+/// * `intrinsic` - The intrinsic parameters of the camera
+/// * `distortion` - The fisheye distortion parameters of the camera
+///
+/// # Returns
+///
+/// A tuple `(u, v)` containing the pixel coordinates of the projection
+///
+/// # Example
+///
+/// ```
+/// use kornia_imgproc::calibration::{CameraIntrinsic, distortion::{FisheyeDistortion, project_point_fisheye}};
+///
+/// let intrinsic = CameraIntrinsic { fx: 300.0, fy: 300.0, cx: 320.0, cy: 240.0 };
+///
+/// // a point 90 degrees away from the axis
+/// let (u, v) = project_point_fisheye([1.0, 0.0, 0.0], &intrinsic, &FisheyeDistortion::default());
+/// assert!((u - (320.0 + 300.0 * std::f64::consts::FRAC_PI_2)).abs() < 1e-9);
+/// assert_eq!(v, 240.0);
+/// ```
+pub fn project_point_fisheye(
+    point: [f64; 3],
+    intrinsic: &CameraIntrinsic,
+    distortion: &FisheyeDistortion,
+) -> (f64, f64) {
+    let [x, y, z] = point;
+    let r = x.hypot(y);
+    let theta = r.atan2(z);
+
+    let theta2 = theta * theta;
+    let (k1, k2, k3, k4) = (distortion.k1, distortion.k2, distortion.k3, distortion.k4);
+    let theta_d = theta * (1.0 + theta2 * (k1 + theta2 * (k2 + theta2 * (k3 + theta2 * k4))));
+
+    // the direction of the point around the optical axis
+    let (xd, yd) = if r > 1e-12 {
+        (theta_d * x / r, theta_d * y / r)
+    } else {
+        (0.0, 0.0)
+    };
+
+    (
+        intrinsic.fx * xd + intrinsic.cx,
+        intrinsic.fy * yd + intrinsic.cy,
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
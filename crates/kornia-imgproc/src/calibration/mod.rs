@@ -1,6 +1,20 @@
+/// chromatic aberration correction module.
+pub mod chromatic_aberration;
+
 /// image distortion module.
 pub mod distortion;
 
+/// fisheye and panoramic projection warps module.
+pub mod projection;
+
+/// rolling shutter correction module.
+pub mod rolling_shutter;
+
+/// vignetting and lens shading correction module.
+pub mod vignetting;
+
+use kornia_image::ImageSize;
+
 /// Represents the instrinsic parameters of a pinhole camera
 ///
 /// # Fields
@@ -9,6 +23,8 @@ pub mod distortion;
 /// * `fy` - The focal length in the y direction
 /// * `cx` - The x coordinate of the principal point
 /// * `cy` - The y coordinate of the principal point
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct CameraIntrinsic {
     /// The focal length in the x direction
     pub fx: f64,
@@ -26,9 +42,45 @@ pub struct CameraIntrinsic {
 ///
 /// * `rotation` - The rotation matrix of the camera 3x3
 /// * `translation` - The translation vector of the camera 3x1
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct CameraExtrinsic {
     /// The rotation matrix of the camera 3x3
     pub rotation: [[f64; 3]; 3],
     /// The translation vector of the camera 3x1
     pub translation: [f64; 3],
 }
+
+// the squared distance from the center to the farthest corner
+pub(crate) fn max_radius2(center: [f64; 2], size: ImageSize) -> f64 {
+    let dx = center[0].max(size.width as f64 - 1.0 - center[0]);
+    let dy = center[1].max(size.height as f64 - 1.0 - center[1]);
+    (dx * dx + dy * dy).max(f64::EPSILON)
+}
+
+// solve a 3x3 linear system with the Gaussian elimination and partial pivoting
+pub(crate) fn solve3(mut a: [[f64; 3]; 3], mut b: [f64; 3]) -> Option<[f64; 3]> {
+    for col in 0..3 {
+        let pivot = (col..3).max_by(|&i, &j| a[i][col].abs().total_cmp(&a[j][col].abs()))?;
+        if a[pivot][col].abs() < 1e-12 {
+            return None;
+        }
+        a.swap(col, pivot);
+        b.swap(col, pivot);
+        for row in col + 1..3 {
+            let factor = a[row][col] / a[col][col];
+            let pivot_row = a[col];
+            for (v, p) in a[row][col..].iter_mut().zip(&pivot_row[col..]) {
+                *v -= factor * p;
+            }
+            b[row] -= factor * b[col];
+        }
+    }
+
+    let mut x = [0.0; 3];
+    for row in (0..3).rev() {
+        let sum = (row + 1..3).map(|k| a[row][k] * x[k]).sum::<f64>();
+        x[row] = (b[row] - sum) / a[row][row];
+    }
+    Some(x)
+}
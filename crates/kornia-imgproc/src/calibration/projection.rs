@@ -0,0 +1,312 @@
+use std::f64::consts::PI;
+
+use super::{
+    distortion::{project_point_fisheye, FisheyeDistortion},
+    CameraIntrinsic,
+};
+use crate::interpolation::grid::meshgrid_from_fn;
+use kornia_image::ImageSize;
+use kornia_tensor::{CpuTensor2, TensorError};
+
+// the x and y coordinates of a remap
+type RemapMaps = (CpuTensor2<f32>, CpuTensor2<f32>);
+
+/// A face of a cubemap, named after the axis of the camera frame that it looks at.
+///
+/// The camera frame has the x axis to the right, the y axis down and the z axis forward.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CubeFace {
+    /// The face to the right
+    PositiveX,
+    /// The face to the left
+    NegativeX,
+    /// The face below
+    PositiveY,
+    /// The face above
+    NegativeY,
+    /// The face in front
+    PositiveZ,
+    /// The face behind
+    NegativeZ,
+}
+
+impl CubeFace {
+    /// All the faces in the order of the axes.
+    pub const ALL: [CubeFace; 6] = [
+        CubeFace::PositiveX,
+        CubeFace::NegativeX,
+        CubeFace::PositiveY,
+        CubeFace::NegativeY,
+        CubeFace::PositiveZ,
+        CubeFace::NegativeZ,
+    ];
+
+    // the direction of a point of the face, with the coordinates a and b in [-1, 1] along
+    // its columns and rows, seen from the inside of the cube
+    fn direction(&self, a: f64, b: f64) -> [f64; 3] {
+        match self {
+            CubeFace::PositiveX => [1.0, b, -a],
+            CubeFace::NegativeX => [-1.0, b, a],
+            CubeFace::PositiveY => [a, 1.0, -b],
+            CubeFace::NegativeY => [a, -1.0, b],
+            CubeFace::PositiveZ => [a, b, 1.0],
+            CubeFace::NegativeZ => [-a, b, -1.0],
+        }
+    }
+}
+
+/// A fisheye lens of a camera, with its calibration.
+///
+/// # Fields
+///
+/// * `intrinsic` - The intrinsic parameters in the pixels of the image
+/// * `distortion` - The fisheye distortion parameters
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FisheyeLens {
+    /// The intrinsic parameters in the pixels of the image
+    pub intrinsic: CameraIntrinsic,
+    /// The fisheye distortion parameters
+    pub distortion: FisheyeDistortion,
+}
+
+/// Generate the map which converts a fisheye image to a rectilinear image.
+///
+/// # Arguments
+///
+/// * `lens` - The calibration of the fisheye lens
+/// * `new_intrinsic` - The intrinsic parameters of the rectilinear pinhole camera
+/// * `size` - The size of the rectilinear image
+///
+/// # Returns
+///
+/// The maps to [`remap`](crate::interpolation::remap) the fisheye image into the
+/// rectilinear image.
+pub fn generate_fisheye_to_rectilinear_map(
+    lens: &FisheyeLens,
+    new_intrinsic: &CameraIntrinsic,
+    size: &ImageSize,
+) -> Result<(CpuTensor2<f32>, CpuTensor2<f32>), TensorError> {
+    meshgrid_from_fn(size.width, size.height, |x, y| {
+        let ray = [
+            (x as f64 - new_intrinsic.cx) / new_intrinsic.fx,
+            (y as f64 - new_intrinsic.cy) / new_intrinsic.fy,
+            1.0,
+        ];
+        let (u, v) = project_point_fisheye(ray, &lens.intrinsic, &lens.distortion);
+        Ok((u as f32, v as f32))
+    })
+}
+
+/// Generate the map which converts a dual fisheye image to an equirectangular panorama.
+///
+/// The two lenses look in opposite directions: the front lens along the z axis and the
+/// back lens rotated by 180 degrees around the y axis. Every direction is sampled from the
+/// lens that it faces, with both lenses calibrated in the pixels of the whole dual image,
+/// such as the left and the right halves of the frame.
+///
+/// The columns of the panorama span the longitudes from -180 to 180 degrees, with the
+/// front in the middle, and the rows span the latitudes from 90 to -90 degrees.
+///
+/// # Arguments
+///
+/// * `front` - The calibration of the front lens
+/// * `back` - The calibration of the back lens
+/// * `size` - The size of the panorama, usually twice as wide as high
+///
+/// # Returns
+///
+/// The maps to [`remap`](crate::interpolation::remap) the dual fisheye image into the
+/// panorama.
+pub fn generate_dual_fisheye_to_equirectangular_map(
+    front: &FisheyeLens,
+    back: &FisheyeLens,
+    size: &ImageSize,
+) -> Result<(CpuTensor2<f32>, CpuTensor2<f32>), TensorError> {
+    meshgrid_from_fn(size.width, size.height, |x, y| {
+        let [dx, dy, dz] = equirectangular_direction(x, y, size);
+        let (u, v) = if dz >= 0.0 {
+            project_point_fisheye([dx, dy, dz], &front.intrinsic, &front.distortion)
+        } else {
+            project_point_fisheye([-dx, dy, -dz], &back.intrinsic, &back.distortion)
+        };
+        Ok((u as f32, v as f32))
+    })
+}
+
+/// Generate the maps which convert an equirectangular panorama to the faces of a cubemap.
+///
+/// The panorama follows the convention of
+/// [`generate_dual_fisheye_to_equirectangular_map`], and every face has a field of view of
+/// 90 degrees, seen from the center of the cube.
+///
+/// # Arguments
+///
+/// * `equirectangular_size` - The size of the panorama
+/// * `face_size` - The side of the square faces
+///
+/// # Returns
+///
+/// The maps to [`remap`](crate::interpolation::remap) the panorama into every face, in
+/// the order of [`CubeFace::ALL`].
+pub fn generate_equirectangular_to_cubemap_maps(
+    equirectangular_size: &ImageSize,
+    face_size: usize,
+) -> Result<Vec<RemapMaps>, TensorError> {
+    let (width, height) = (
+        equirectangular_size.width as f64,
+        equirectangular_size.height as f64,
+    );
+
+    CubeFace::ALL
+        .iter()
+        .map(|face| {
+            meshgrid_from_fn(face_size, face_size, |x, y| {
+                let a = 2.0 * (x as f64 + 0.5) / face_size as f64 - 1.0;
+                let b = 2.0 * (y as f64 + 0.5) / face_size as f64 - 1.0;
+                let [dx, dy, dz] = face.direction(a, b);
+
+                let longitude = dx.atan2(dz);
+                let latitude = (-dy).atan2(dx.hypot(dz));
+                let u = ((longitude + PI) / (2.0 * PI) * width - 0.5).rem_euclid(width);
+                let v = (0.5 * PI - latitude) / PI * height - 0.5;
+
+                // the seam of the panorama is clamped instead of wrapped
+                Ok((u.min(width - 1.0) as f32, v.clamp(0.0, height - 1.0) as f32))
+            })
+        })
+        .collect()
+}
+
+// the unit direction of the center of a pixel of an equirectangular panorama
+fn equirectangular_direction(x: usize, y: usize, size: &ImageSize) -> [f64; 3] {
+    let longitude = (x as f64 + 0.5) / size.width as f64 * 2.0 * PI - PI;
+    let latitude = 0.5 * PI - (y as f64 + 0.5) / size.height as f64 * PI;
+    [
+        latitude.cos() * longitude.sin(),
+        -latitude.sin(),
+        latitude.cos() * longitude.cos(),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lens(cx: f64) -> FisheyeLens {
+        FisheyeLens {
+            intrinsic: CameraIntrinsic {
+                fx: 100.0,
+                fy: 100.0,
+                cx,
+                cy: 160.0,
+            },
+            distortion: FisheyeDistortion {
+                k1: 0.02,
+                k2: -0.01,
+                k3: 0.0,
+                k4: 0.0,
+            },
+        }
+    }
+
+    #[test]
+    fn test_fisheye_to_rectilinear() -> Result<(), TensorError> {
+        let fisheye = lens(160.0);
+        let pinhole = CameraIntrinsic {
+            fx: 80.0,
+            fy: 80.0,
+            cx: 99.5,
+            cy: 79.5,
+        };
+        let size = ImageSize {
+            width: 200,
+            height: 160,
+        };
+        let (map_x, map_y) = generate_fisheye_to_rectilinear_map(&fisheye, &pinhole, &size)?;
+        assert_eq!(map_x.shape, [160, 200]);
+
+        // the principal points correspond
+        let i = 79 * 200 + 99;
+        assert!((map_x.as_slice()[i] - 159.5).abs() < 0.6);
+        assert!((map_y.as_slice()[i] - 159.5).abs() < 0.6);
+
+        // a ray at 45 degrees
+        let theta = std::f64::consts::FRAC_PI_4;
+        let theta_d = theta * (1.0 + 0.02 * theta.powi(2) - 0.01 * theta.powi(4));
+        let i = 79 * 200 + 179;
+        let (x, _) =
+            project_point_fisheye([79.5, -0.5, 80.0], &fisheye.intrinsic, &fisheye.distortion);
+        assert!((map_x.as_slice()[i] as f64 - x).abs() < 1e-3);
+        assert!((x - (160.0 + 100.0 * theta_d)).abs() < 1.0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_dual_fisheye_to_equirectangular() -> Result<(), TensorError> {
+        let (front, back) = (lens(160.0), lens(480.0));
+        let size = ImageSize {
+            width: 360,
+            height: 180,
+        };
+        let (map_x, map_y) = generate_dual_fisheye_to_equirectangular_map(&front, &back, &size)?;
+
+        // the middle of the panorama is the center of the front lens
+        let i = 90 * 360 + 180;
+        assert!((map_x.as_slice()[i] - 160.0).abs() < 1.0);
+        assert!((map_y.as_slice()[i] - 160.0).abs() < 1.0);
+
+        // the left border is the center of the back lens
+        let i = 90 * 360;
+        assert!((map_x.as_slice()[i] - 480.0).abs() < 1.0);
+
+        // almost 90 degrees to the right is the right border of the front circle
+        let i = 90 * 360 + 269;
+        let edge = 160.0 + 100.0 * project_theta(std::f64::consts::FRAC_PI_2);
+        assert!((map_x.as_slice()[i] as f64 - edge).abs() < 1.0);
+
+        // and just after it the left border of the back circle
+        let edge = 480.0 - 100.0 * project_theta(std::f64::consts::FRAC_PI_2);
+        assert!((map_x.as_slice()[i + 1] as f64 - edge).abs() < 1.0);
+
+        Ok(())
+    }
+
+    fn project_theta(theta: f64) -> f64 {
+        theta * (1.0 + 0.02 * theta.powi(2) - 0.01 * theta.powi(4))
+    }
+
+    #[test]
+    fn test_equirectangular_to_cubemap() -> Result<(), TensorError> {
+        let size = ImageSize {
+            width: 400,
+            height: 200,
+        };
+        let maps = generate_equirectangular_to_cubemap_maps(&size, 64)?;
+        assert_eq!(maps.len(), 6);
+
+        // every face samples the panorama at the direction of its pixels
+        for (face, (map_x, map_y)) in CubeFace::ALL.iter().zip(&maps) {
+            for (x, y) in [(32, 32), (5, 60), (63, 0)] {
+                let a = 2.0 * (x as f64 + 0.5) / 64.0 - 1.0;
+                let b = 2.0 * (y as f64 + 0.5) / 64.0 - 1.0;
+                let d = face.direction(a, b);
+                let norm = (d[0] * d[0] + d[1] * d[1] + d[2] * d[2]).sqrt();
+
+                let (u, v) = (map_x.as_slice()[y * 64 + x], map_y.as_slice()[y * 64 + x]);
+                let (cx, cy) = (u.round() as usize, v.round() as usize);
+                let e = equirectangular_direction(cx.min(399), cy.min(199), &size);
+                let dot = (e[0] * d[0] + e[1] * d[1] + e[2] * d[2]) / norm;
+                assert!(dot > 0.999, "{face:?} {x} {y} {dot}");
+            }
+        }
+
+        // the front face is in the middle of the panorama
+        let (map_x, map_y) = &maps[4];
+        let i = 32 * 64 + 32;
+        assert!((map_x.as_slice()[i] - 199.5).abs() < 4.0);
+        assert!((map_y.as_slice()[i] - 99.5).abs() < 2.0);
+
+        Ok(())
+    }
+}
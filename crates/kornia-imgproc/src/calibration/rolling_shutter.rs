@@ -0,0 +1,298 @@
+use super::CameraIntrinsic;
+use crate::interpolation::grid::meshgrid_from_fn;
+use kornia_image::{Image, ImageError, ImageSize};
+use kornia_tensor::{CpuTensor2, TensorError};
+
+// the fixed point iterations to find the row where a pixel was captured
+const ROW_ITERATIONS: usize = 5;
+
+/// The timing of the rows of a rolling shutter sensor.
+///
+/// # Fields
+///
+/// * `line_delay` - The time between the exposures of two consecutive rows in seconds
+/// * `reference_row` - The row exposed at the time of the global shutter equivalent frame
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RowTiming {
+    /// The time between the exposures of two consecutive rows in seconds
+    pub line_delay: f64,
+    /// The row exposed at the time of the global shutter equivalent frame
+    pub reference_row: f64,
+}
+
+impl RowTiming {
+    /// Create the timing of a sensor which reads the rows in the readout time.
+    ///
+    /// The reference is the middle row, which minimizes the correction of the frame.
+    ///
+    /// # Arguments
+    ///
+    /// * `readout_time` - The time to read all the rows in seconds.
+    /// * `rows` - The number of rows of the frame.
+    pub fn from_readout_time(readout_time: f64, rows: usize) -> Self {
+        Self {
+            line_delay: readout_time / rows.max(1) as f64,
+            reference_row: 0.5 * (rows as f64 - 1.0),
+        }
+    }
+
+    /// The time of the exposure of a row relative to the reference row, in seconds.
+    pub fn row_time(&self, row: f64) -> f64 {
+        (row - self.reference_row) * self.line_delay
+    }
+}
+
+/// Generate the map which corrects the rolling shutter of a rotating camera.
+///
+/// The camera rotates with a constant angular velocity, as measured by a gyroscope, so that
+/// every row sees the scene rotated by the angle travelled since the reference row. Every
+/// pixel of the global shutter frame is projected into the rows of the captured frame, and
+/// the row of the projection is refined until it matches the time of its exposure.
+///
+/// # Arguments
+///
+/// * `intrinsic` - The intrinsic parameters of the camera
+/// * `angular_velocity` - The angular velocity of the camera in radians per second, around
+///   the axes of the camera (x right, y down, z forward)
+/// * `timing` - The timing of the rows of the sensor
+/// * `size` - The size of the frames
+///
+/// # Returns
+///
+/// The maps to [`remap`](crate::interpolation::remap) the captured frame into the global
+/// shutter frame.
+pub fn generate_rolling_shutter_map_gyro(
+    intrinsic: &CameraIntrinsic,
+    angular_velocity: [f64; 3],
+    timing: &RowTiming,
+    size: &ImageSize,
+) -> Result<(CpuTensor2<f32>, CpuTensor2<f32>), TensorError> {
+    let (fx, fy, cx, cy) = (intrinsic.fx, intrinsic.fy, intrinsic.cx, intrinsic.cy);
+
+    meshgrid_from_fn(size.width, size.height, |x, y| {
+        // the ray of the pixel at the reference time
+        let ray = [(x as f64 - cx) / fx, (y as f64 - cy) / fy, 1.0];
+
+        let project = |row: f64| {
+            // the scene rotates against the camera
+            let t = timing.row_time(row);
+            let r = rotation_from_axis_angle(angular_velocity.map(|w| -w * t));
+            let p = [0, 1, 2].map(|i| r[i][0] * ray[0] + r[i][1] * ray[1] + r[i][2] * ray[2]);
+            (fx * p[0] / p[2] + cx, fy * p[1] / p[2] + cy)
+        };
+
+        let (xs, ys) = solve_row(y as f64, project);
+        Ok((xs as f32, ys as f32))
+    })
+}
+
+/// Generate the map which corrects the rolling shutter of a translating image.
+///
+/// The content of the image moves with a constant velocity, such as the mean optical flow
+/// of a frame or the velocity from [`rolling_shutter_velocity_from_flow`], so that every
+/// row sees the content shifted by the distance travelled since the reference row.
+///
+/// # Arguments
+///
+/// * `velocity` - The velocity of the content as [vx, vy] in pixels per second
+/// * `timing` - The timing of the rows of the sensor
+/// * `size` - The size of the frames
+///
+/// # Returns
+///
+/// The maps to [`remap`](crate::interpolation::remap) the captured frame into the global
+/// shutter frame.
+pub fn generate_rolling_shutter_map_translation(
+    velocity: [f64; 2],
+    timing: &RowTiming,
+    size: &ImageSize,
+) -> Result<(CpuTensor2<f32>, CpuTensor2<f32>), TensorError> {
+    meshgrid_from_fn(size.width, size.height, |x, y| {
+        let (xs, ys) = solve_row(y as f64, |row| {
+            let t = timing.row_time(row);
+            (x as f64 + velocity[0] * t, y as f64 + velocity[1] * t)
+        });
+        Ok((xs as f32, ys as f32))
+    })
+}
+
+/// Estimate the velocity of the content of a frame from its optical flow.
+///
+/// The velocity is the median of the flow divided by the time between the frames, which
+/// is robust to the objects moving independently of the camera.
+///
+/// # Arguments
+///
+/// * `flow` - The flow with shape (H, W, 2) between two consecutive frames
+/// * `frame_interval` - The time between the frames in seconds
+///
+/// # Returns
+///
+/// The velocity as [vx, vy] in pixels per second.
+pub fn rolling_shutter_velocity_from_flow(
+    flow: &Image<f32, 2>,
+    frame_interval: f64,
+) -> Result<[f64; 2], ImageError> {
+    let mut components = [Vec::new(), Vec::new()];
+    for uv in flow.as_slice().chunks_exact(2) {
+        if uv.iter().all(|v| v.is_finite()) {
+            components[0].push(uv[0]);
+            components[1].push(uv[1]);
+        }
+    }
+
+    if components[0].is_empty() {
+        return Err(ImageError::ImageDataNotInitialized);
+    }
+
+    Ok(components.map(|mut values| {
+        values.sort_by(|a, b| a.total_cmp(b));
+        values[values.len() / 2] as f64 / frame_interval
+    }))
+}
+
+// find the point where the projection at the time of a row falls on the same row
+fn solve_row(row: f64, project: impl Fn(f64) -> (f64, f64)) -> (f64, f64) {
+    let mut point = project(row);
+    for _ in 1..ROW_ITERATIONS {
+        point = project(point.1);
+    }
+    point
+}
+
+// the rotation matrix of a rotation vector with the Rodrigues formula
+fn rotation_from_axis_angle(v: [f64; 3]) -> [[f64; 3]; 3] {
+    let theta = (v[0] * v[0] + v[1] * v[1] + v[2] * v[2]).sqrt();
+    if theta < 1e-12 {
+        return [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]];
+    }
+
+    let [x, y, z] = v.map(|c| c / theta);
+    let (s, c) = theta.sin_cos();
+    let t = 1.0 - c;
+    [
+        [t * x * x + c, t * x * y - s * z, t * x * z + s * y],
+        [t * x * y + s * z, t * y * y + c, t * y * z - s * x],
+        [t * x * z - s * y, t * y * z + s * x, t * z * z + c],
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::interpolation::{remap, InterpolationMode};
+
+    // a smooth pattern of blobs
+    fn pattern_image(size: ImageSize) -> Result<Image<f32, 1>, ImageError> {
+        Image::new(
+            size,
+            (0..size.width * size.height)
+                .map(|i| {
+                    let (x, y) = ((i % size.width) as f32, (i / size.width) as f32);
+                    (0.4 * x).sin() * (0.3 * y).cos()
+                })
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn test_rolling_shutter_translation() -> Result<(), ImageError> {
+        let size = ImageSize {
+            width: 64,
+            height: 48,
+        };
+        let timing = RowTiming::from_readout_time(0.03, size.height);
+        assert!((timing.row_time(timing.reference_row)).abs() < 1e-12);
+
+        // the content moves 200 pixels per second to the right
+        let velocity = [200.0, 0.0];
+        let (map_x, map_y) = generate_rolling_shutter_map_translation(velocity, &timing, &size)?;
+
+        // the rows are sampled further right as they are exposed later
+        let cols = size.width;
+        let shift = |y: usize| map_x.as_slice()[y * cols + 10] - 10.0;
+        assert!(shift(0) < 0.0 && shift(47) > 0.0);
+        assert!((shift(47) - 200.0 * timing.row_time(47.0) as f32).abs() < 1e-3);
+        assert_eq!(map_y.as_slice()[20 * cols + 10], 20.0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_rolling_shutter_gyro_roundtrip() -> Result<(), ImageError> {
+        let size = ImageSize {
+            width: 64,
+            height: 48,
+        };
+        let intrinsic = CameraIntrinsic {
+            fx: 60.0,
+            fy: 60.0,
+            cx: 31.5,
+            cy: 23.5,
+        };
+        let timing = RowTiming::from_readout_time(0.03, size.height);
+        let omega = [0.5, 8.0, 1.0];
+        let src = pattern_image(size)?;
+
+        // simulate the rolling shutter: every captured row sees the rotated scene
+        let (sim_x, sim_y) = meshgrid_from_fn(size.width, size.height, |x, y| {
+            let t = timing.row_time(y as f64);
+            let r = rotation_from_axis_angle(omega.map(|w| w * t));
+            let ray = [
+                (x as f64 - intrinsic.cx) / intrinsic.fx,
+                (y as f64 - intrinsic.cy) / intrinsic.fy,
+                1.0,
+            ];
+            let p = [0, 1, 2].map(|i| r[i][0] * ray[0] + r[i][1] * ray[1] + r[i][2] * ray[2]);
+            Ok((
+                (intrinsic.fx * p[0] / p[2] + intrinsic.cx) as f32,
+                (intrinsic.fy * p[1] / p[2] + intrinsic.cy) as f32,
+            ))
+        })?;
+        let mut captured = Image::from_size_val(size, 0.0)?;
+        remap(
+            &src,
+            &mut captured,
+            &sim_x,
+            &sim_y,
+            InterpolationMode::Bilinear,
+        )?;
+
+        let (map_x, map_y) = generate_rolling_shutter_map_gyro(&intrinsic, omega, &timing, &size)?;
+        let mut corrected = Image::from_size_val(size, 0.0)?;
+        remap(
+            &captured,
+            &mut corrected,
+            &map_x,
+            &map_y,
+            InterpolationMode::Bilinear,
+        )?;
+
+        // the correction recovers the pattern away from the borders
+        let error = |image: &Image<f32, 1>| {
+            let mut sum = 0.0;
+            for y in 8..40 {
+                for x in 8..56 {
+                    let i = y * size.width + x;
+                    sum += (image.as_slice()[i] - src.as_slice()[i]).abs();
+                }
+            }
+            sum / (32 * 48) as f32
+        };
+        assert!(error(&captured) > 0.2);
+        assert!(error(&corrected) < 0.03);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_rolling_shutter_velocity_from_flow() -> Result<(), ImageError> {
+        let mut flow = Image::<f32, 2>::from_size_val([4, 4].into(), 0.0)?;
+        for (i, uv) in flow.as_slice_mut().chunks_exact_mut(2).enumerate() {
+            uv.copy_from_slice(if i == 0 { &[50.0, 50.0] } else { &[3.0, -1.0] });
+        }
+        let velocity = rolling_shutter_velocity_from_flow(&flow, 0.1)?;
+        assert!((velocity[0] - 30.0).abs() < 1e-6 && (velocity[1] + 10.0).abs() < 1e-6);
+        Ok(())
+    }
+}
@@ -0,0 +1,320 @@
+use super::{max_radius2, solve3};
+use crate::parallel::prelude::*;
+use kornia_image::{Image, ImageError, ImageSize};
+
+// the number of radial bins of the profile used for the estimation
+const NUM_RADIAL_BINS: usize = 64;
+
+/// A radial model of the vignetting of a lens.
+///
+/// The attenuation at the normalized radius r, the distance to the center divided by the
+/// distance from the center to the farthest corner, is 1 + k1 r^2 + k2 r^4 + k3 r^6.
+///
+/// # Fields
+///
+/// * `center` - The center of the vignetting as [x, y] in pixels
+/// * `coefficients` - The coefficients [k1, k2, k3] of the attenuation
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RadialVignette {
+    /// The center of the vignetting as [x, y] in pixels
+    pub center: [f64; 2],
+    /// The coefficients [k1, k2, k3] of the attenuation
+    pub coefficients: [f64; 3],
+}
+
+impl RadialVignette {
+    /// The attenuation at a pixel of an image of the given size.
+    pub fn attenuation(&self, x: f64, y: f64, size: ImageSize) -> f64 {
+        let r2 = (x - self.center[0]).powi(2) + (y - self.center[1]).powi(2);
+        let r2 = r2 / max_radius2(self.center, size);
+        let [k1, k2, k3] = self.coefficients;
+        1.0 + r2 * (k1 + r2 * (k2 + r2 * k3))
+    }
+
+    /// Compute the gain map which corrects the vignetting.
+    ///
+    /// # Arguments
+    ///
+    /// * `size` - The size of the images to correct.
+    ///
+    /// # Returns
+    ///
+    /// The inverse of the attenuation of every pixel with shape (H, W, 1).
+    pub fn gain_map(&self, size: ImageSize) -> Result<Image<f32, 1>, ImageError> {
+        let data = (0..size.width * size.height)
+            .into_par_iter()
+            .map(|i| {
+                let (x, y) = ((i % size.width) as f64, (i / size.width) as f64);
+                (1.0 / self.attenuation(x, y, size).max(f64::EPSILON)) as f32
+            })
+            .collect();
+        Image::new(size, data)
+    }
+}
+
+/// Estimate the vignetting of a single image.
+///
+/// The intensities are averaged in rings around the center of the image and the radial
+/// model is fitted to the profile, normalized by the intensity at the center. The scene is
+/// assumed to be uniform on average along the rings, such as a flat field or a textured
+/// scene without a dominant bright region.
+///
+/// # Arguments
+///
+/// * `src` - The grayscale image with shape (H, W, 1).
+///
+/// # Returns
+///
+/// The vignetting centered in the image.
+///
+/// # Errors
+///
+/// Returns an error if the image is too small or too dark to fit the model.
+///
+/// # Example
+///
+/// ```
+/// use kornia_image::Image;
+/// use kornia_imgproc::calibration::vignetting::{correct_vignette, estimate_vignette};
+///
+/// let src = Image::<f32, 1>::from_size_val([64, 48].into(), 0.5).unwrap();
+///
+/// let vignette = estimate_vignette(&src).unwrap();
+/// assert!(vignette.coefficients.iter().all(|k| k.abs() < 1e-6));
+///
+/// let mut corrected = Image::<f32, 1>::from_size_val(src.size(), 0.0).unwrap();
+/// correct_vignette(&src, &mut corrected, &vignette).unwrap();
+/// ```
+pub fn estimate_vignette(src: &Image<f32, 1>) -> Result<RadialVignette, ImageError> {
+    let size = src.size();
+    let center = [
+        0.5 * (size.width as f64 - 1.0),
+        0.5 * (size.height as f64 - 1.0),
+    ];
+    let max_r2 = max_radius2(center, size);
+
+    // the mean intensity of the rings of equal width in r
+    let mut sums = [0.0f64; NUM_RADIAL_BINS];
+    let mut counts = [0usize; NUM_RADIAL_BINS];
+    for (i, &v) in src.as_slice().iter().enumerate() {
+        let (x, y) = ((i % size.width) as f64, (i / size.width) as f64);
+        let r = ((x - center[0]).powi(2) + (y - center[1]).powi(2)) / max_r2;
+        let bin = ((r.sqrt() * NUM_RADIAL_BINS as f64) as usize).min(NUM_RADIAL_BINS - 1);
+        sums[bin] += v as f64;
+        counts[bin] += 1;
+    }
+
+    let profile = (0..NUM_RADIAL_BINS)
+        .filter(|&b| counts[b] > 0)
+        .map(|b| {
+            let r = (b as f64 + 0.5) / NUM_RADIAL_BINS as f64;
+            (r * r, sums[b] / counts[b] as f64, counts[b] as f64)
+        })
+        .collect::<Vec<_>>();
+
+    // the intensity at the center, from the linear trend of the inner rings
+    let inner = &profile[..profile.len().min(4)];
+    let (sw, sx, sy, sxx, sxy) = inner
+        .iter()
+        .fold((0.0, 0.0, 0.0, 0.0, 0.0), |acc, &(x, y, w)| {
+            (
+                acc.0 + w,
+                acc.1 + w * x,
+                acc.2 + w * y,
+                acc.3 + w * x * x,
+                acc.4 + w * x * y,
+            )
+        });
+    let det = sw * sxx - sx * sx;
+    let center_value = if det.abs() > f64::EPSILON {
+        (sxx * sy - sx * sxy) / det
+    } else {
+        sy / sw
+    };
+    if profile.len() < 3 || center_value <= f64::EPSILON {
+        return Err(ImageError::ImageDataNotInitialized);
+    }
+
+    // the weighted least squares of the attenuation minus one on [r^2, r^4, r^6]
+    let mut ata = [[0.0f64; 3]; 3];
+    let mut atb = [0.0f64; 3];
+    for &(r2, value, weight) in &profile {
+        let basis = [r2, r2 * r2, r2 * r2 * r2];
+        let target = value / center_value - 1.0;
+        for i in 0..3 {
+            for j in 0..3 {
+                ata[i][j] += weight * basis[i] * basis[j];
+            }
+            atb[i] += weight * basis[i] * target;
+        }
+    }
+    let coefficients = solve3(ata, atb).ok_or(ImageError::CannotComputeDeterminant)?;
+
+    Ok(RadialVignette {
+        center,
+        coefficients,
+    })
+}
+
+/// Correct the vignetting of an image.
+///
+/// # Arguments
+///
+/// * `src` - The image with shape (H, W, C).
+/// * `dst` - The corrected image with shape (H, W, C).
+/// * `vignette` - The vignetting of the lens.
+pub fn correct_vignette<const C: usize>(
+    src: &Image<f32, C>,
+    dst: &mut Image<f32, C>,
+    vignette: &RadialVignette,
+) -> Result<(), ImageError> {
+    let gain = vignette.gain_map(src.size())?;
+    apply_gain_map(src, dst, &gain)
+}
+
+/// Multiply an image by a gain map, such as a lens shading table.
+///
+/// The gain map can have a lower resolution than the image, in which case it is
+/// interpolated bilinearly with its corners aligned to the corners of the image. A gain
+/// map with one channel applies to all the channels, otherwise every channel has its own
+/// gain.
+///
+/// # Arguments
+///
+/// * `src` - The image with shape (H, W, C).
+/// * `dst` - The corrected image with shape (H, W, C).
+/// * `gain` - The gain map with shape (h, w, G), where G is 1 or C.
+///
+/// # Errors
+///
+/// Returns an error if the sizes of the images differ or the gain map has a number of
+/// channels other than 1 or C.
+pub fn apply_gain_map<const C: usize, const G: usize>(
+    src: &Image<f32, C>,
+    dst: &mut Image<f32, C>,
+    gain: &Image<f32, G>,
+) -> Result<(), ImageError> {
+    if src.size() != dst.size() {
+        return Err(ImageError::InvalidImageSize(
+            src.cols(),
+            src.rows(),
+            dst.cols(),
+            dst.rows(),
+        ));
+    }
+    if G != 1 && G != C {
+        return Err(ImageError::ChannelIndexOutOfBounds(G, C));
+    }
+
+    let (cols, rows) = (src.cols(), src.rows());
+    let (gain_cols, gain_rows) = (gain.cols(), gain.rows());
+    let scale_x = (gain_cols - 1) as f32 / (cols.max(2) - 1) as f32;
+    let scale_y = (gain_rows - 1) as f32 / (rows.max(2) - 1) as f32;
+    let gain_data = gain.as_slice();
+
+    dst.as_slice_mut()
+        .par_chunks_exact_mut(cols * C)
+        .zip(src.as_slice().par_chunks_exact(cols * C))
+        .enumerate()
+        .for_each(|(y, (dst_row, src_row))| {
+            let gy = y as f32 * scale_y;
+            let y0 = (gy as usize).min(gain_rows - 1);
+            let y1 = (y0 + 1).min(gain_rows - 1);
+            let wy = gy - y0 as f32;
+
+            for (x, (dst_pixel, src_pixel)) in dst_row
+                .chunks_exact_mut(C)
+                .zip(src_row.chunks_exact(C))
+                .enumerate()
+            {
+                let gx = x as f32 * scale_x;
+                let x0 = (gx as usize).min(gain_cols - 1);
+                let x1 = (x0 + 1).min(gain_cols - 1);
+                let wx = gx - x0 as f32;
+
+                for (c, (d, &s)) in dst_pixel.iter_mut().zip(src_pixel).enumerate() {
+                    let g = if G == 1 { 0 } else { c };
+                    let at = |x: usize, y: usize| gain_data[(y * gain_cols + x) * G + g];
+                    let top = at(x0, y0) * (1.0 - wx) + at(x1, y0) * wx;
+                    let bottom = at(x0, y1) * (1.0 - wx) + at(x1, y1) * wx;
+                    *d = s * (top * (1.0 - wy) + bottom * wy);
+                }
+            }
+        });
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_estimate_vignette() -> Result<(), ImageError> {
+        let size = ImageSize {
+            width: 160,
+            height: 120,
+        };
+        let expected = RadialVignette {
+            center: [79.5, 59.5],
+            coefficients: [-0.3, 0.05, -0.02],
+        };
+
+        // a flat field with a fine texture, attenuated by the vignetting
+        let data = (0..size.width * size.height)
+            .map(|i| {
+                let (x, y) = (i % size.width, i / size.width);
+                let texture = if (x + y) % 2 == 0 { 0.05 } else { -0.05 };
+                let a = expected.attenuation(x as f64, y as f64, size);
+                ((0.6 + texture) * a) as f32
+            })
+            .collect();
+        let src = Image::new(size, data)?;
+
+        let vignette = estimate_vignette(&src)?;
+        assert_eq!(vignette.center, expected.center);
+        for x in [0.0, 40.0, 79.5, 159.0] {
+            let (a, b) = (
+                vignette.attenuation(x, 0.0, size),
+                expected.attenuation(x, 0.0, size),
+            );
+            assert!((a - b).abs() < 0.01, "{a} {b}");
+        }
+
+        // the correction flattens the field
+        let mut corrected = Image::from_size_val(size, 0.0)?;
+        correct_vignette(&src, &mut corrected, &vignette)?;
+        for &i in &[0, 80 * 160 + 60, size.width * size.height - 1] {
+            let texture = if (i % 160 + i / 160) % 2 == 0 {
+                0.05
+            } else {
+                -0.05
+            };
+            assert!((corrected.as_slice()[i] - (0.6 + texture)).abs() < 0.01);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_apply_gain_map() -> Result<(), ImageError> {
+        let src = Image::<f32, 2>::from_size_val([5, 3].into(), 1.0)?;
+        let mut dst = Image::from_size_val(src.size(), 0.0)?;
+
+        // a coarse gain map interpolated over the image
+        let gain = Image::<f32, 1>::new([2, 2].into(), vec![1.0, 2.0, 3.0, 4.0])?;
+        apply_gain_map(&src, &mut dst, &gain)?;
+        assert_eq!(dst.as_slice()[..4], [1.0, 1.0, 1.25, 1.25]);
+        assert_eq!(dst.as_slice()[(2 * 5 + 4) * 2], 4.0);
+
+        // a gain per channel
+        let gain = Image::<f32, 2>::new([1, 1].into(), vec![0.5, 2.0])?;
+        apply_gain_map(&src, &mut dst, &gain)?;
+        assert!(dst.as_slice().chunks_exact(2).all(|p| p == [0.5, 2.0]));
+
+        let gain = Image::<f32, 3>::from_size_val([1, 1].into(), 1.0)?;
+        assert!(apply_gain_map(&src, &mut dst, &gain).is_err());
+
+        Ok(())
+    }
+}
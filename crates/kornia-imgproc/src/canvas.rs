@@ -0,0 +1,106 @@
+use kornia_image::{Image, ImageError};
+
+/// Create an image from the pixels of an HTML canvas.
+///
+/// The pixels of a canvas `ImageData` are stored row by row as RGBA with 8 bits per
+/// channel. The alpha channel is dropped for RGB images, and grayscale images use the
+/// same luma coefficients as [`crate::color::gray_from_rgb_u8`].
+///
+/// # Arguments
+///
+/// * `data` - The RGBA pixels, e.g. `ImageData::data()`.
+/// * `width` - The width of the canvas.
+/// * `height` - The height of the canvas.
+///
+/// # Returns
+///
+/// The image with 1 (gray), 3 (RGB) or 4 (RGBA) channels.
+///
+/// # Example
+///
+/// ```
+/// use kornia_image::Image;
+/// use kornia_imgproc::canvas::{from_image_data, to_image_data};
+///
+/// let data = [255, 0, 0, 255, 0, 0, 255, 128];
+/// let image: Image<u8, 3> = from_image_data(&data, 2, 1).unwrap();
+/// assert_eq!(image.as_slice(), &[255, 0, 0, 0, 0, 255]);
+///
+/// let data = to_image_data(&image).unwrap();
+/// assert_eq!(data, vec![255, 0, 0, 255, 0, 0, 255, 255]);
+/// ```
+pub fn from_image_data<const C: usize>(
+    data: &[u8],
+    width: usize,
+    height: usize,
+) -> Result<Image<u8, C>, ImageError> {
+    if data.len() != width * height * 4 {
+        return Err(ImageError::InvalidChannelShape(
+            data.len(),
+            width * height * 4,
+        ));
+    }
+
+    let pixels = data.chunks_exact(4);
+    let pixels = match C {
+        1 => pixels
+            .map(|p| ((p[0] as u16 * 77 + p[1] as u16 * 150 + p[2] as u16 * 29) >> 8) as u8)
+            .collect(),
+        3 => pixels.flat_map(|p| [p[0], p[1], p[2]]).collect(),
+        4 => data.to_vec(),
+        _ => return Err(ImageError::IncompatiblePixelTypes),
+    };
+
+    Image::new([width, height].into(), pixels)
+}
+
+/// Convert an image to the pixels of an HTML canvas.
+///
+/// Grayscale images are replicated to the color channels and RGB images are opaque.
+///
+/// # Arguments
+///
+/// * `image` - The image with 1 (gray), 3 (RGB) or 4 (RGBA) channels.
+///
+/// # Returns
+///
+/// The RGBA pixels, e.g. for `ImageData::new_with_u8_clamped_array_and_sh`.
+pub fn to_image_data<const C: usize>(image: &Image<u8, C>) -> Result<Vec<u8>, ImageError> {
+    let pixels = image.as_slice().chunks_exact(C);
+    let data = match C {
+        1 => pixels.flat_map(|p| [p[0], p[0], p[0], 255]).collect(),
+        3 => pixels.flat_map(|p| [p[0], p[1], p[2], 255]).collect(),
+        4 => image.as_slice().to_vec(),
+        _ => return Err(ImageError::IncompatiblePixelTypes),
+    };
+
+    Ok(data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_canvas_round_trip() -> Result<(), ImageError> {
+        let data = [10, 20, 30, 40, 255, 255, 255, 0, 0, 0, 0, 255];
+
+        let rgba = from_image_data::<4>(&data, 3, 1)?;
+        assert_eq!(to_image_data(&rgba)?, data);
+
+        let rgb = from_image_data::<3>(&data, 3, 1)?;
+        assert_eq!(rgb.as_slice(), &[10, 20, 30, 255, 255, 255, 0, 0, 0]);
+
+        let gray = from_image_data::<1>(&data, 1, 3)?;
+        assert_eq!(gray.as_slice(), &[18, 255, 0]);
+        assert_eq!(
+            to_image_data(&gray)?,
+            [18, 18, 18, 255, 255, 255, 255, 255, 0, 0, 0, 255]
+        );
+
+        assert!(from_image_data::<3>(&data, 2, 2).is_err());
+        assert!(from_image_data::<2>(&data, 3, 1).is_err());
+
+        Ok(())
+    }
+}
@@ -69,6 +69,137 @@ where
     Ok(())
 }
 
+/// The luma coefficients of the red, green and blue channels of a grayscale conversion.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GrayWeights {
+    /// ITU-R BT.601, used for standard definition video and by [`gray_from_rgb`].
+    Bt601,
+    /// ITU-R BT.709, used for high definition video and sRGB.
+    Bt709,
+    /// Custom coefficients of the red, green and blue channels.
+    Custom([f64; 3]),
+}
+
+impl GrayWeights {
+    /// Returns the coefficients of the red, green and blue channels.
+    pub fn coefficients(&self) -> [f64; 3] {
+        match self {
+            GrayWeights::Bt601 => [RW, GW, BW],
+            GrayWeights::Bt709 => [0.2126, 0.7152, 0.0722],
+            GrayWeights::Custom(weights) => *weights,
+        }
+    }
+}
+
+/// The handling of the alpha channel when converting an RGBA image to grayscale.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AlphaMode {
+    /// Drop the alpha channel.
+    Ignore,
+    /// Multiply the luma by the alpha, i.e. composite the image over black.
+    Premultiply,
+}
+
+/// Convert an RGB image to grayscale with the given luma coefficients:
+///
+/// Y = w_r * R + w_g * G + w_b * B
+///
+/// # Arguments
+///
+/// * `src` - The input RGB image.
+/// * `dst` - The output grayscale image.
+/// * `weights` - The luma coefficients.
+///
+/// Precondition: the input and output images must have the same size.
+///
+/// # Example
+///
+/// ```
+/// use kornia_image::Image;
+/// use kornia_imgproc::color::{gray_from_rgb_weighted, GrayWeights};
+///
+/// let image = Image::<f32, 3>::new([1, 1].into(), vec![0.0, 1.0, 0.0]).unwrap();
+/// let mut gray = Image::<f32, 1>::from_size_val(image.size(), 0.0).unwrap();
+///
+/// gray_from_rgb_weighted(&image, &mut gray, GrayWeights::Bt709).unwrap();
+/// assert_eq!(gray.as_slice(), &[0.7152]);
+/// ```
+pub fn gray_from_rgb_weighted<T>(
+    src: &Image<T, 3>,
+    dst: &mut Image<T, 1>,
+    weights: GrayWeights,
+) -> Result<(), ImageError>
+where
+    T: Send + Sync + num_traits::Float,
+{
+    if src.size() != dst.size() {
+        return Err(ImageError::InvalidImageSize(
+            src.cols(),
+            src.rows(),
+            dst.cols(),
+            dst.rows(),
+        ));
+    }
+
+    let [rw, gw, bw] = cast_weights::<T>(weights)?;
+
+    parallel::par_iter_rows(src, dst, |src_pixel, dst_pixel| {
+        dst_pixel[0] = rw * src_pixel[0] + gw * src_pixel[1] + bw * src_pixel[2];
+    });
+
+    Ok(())
+}
+
+/// Convert an RGBA image to grayscale with the given luma coefficients.
+///
+/// # Arguments
+///
+/// * `src` - The input RGBA image with the alpha in the range [0, 1].
+/// * `dst` - The output grayscale image.
+/// * `weights` - The luma coefficients.
+/// * `alpha` - The handling of the alpha channel.
+///
+/// Precondition: the input and output images must have the same size.
+pub fn gray_from_rgba<T>(
+    src: &Image<T, 4>,
+    dst: &mut Image<T, 1>,
+    weights: GrayWeights,
+    alpha: AlphaMode,
+) -> Result<(), ImageError>
+where
+    T: Send + Sync + num_traits::Float,
+{
+    if src.size() != dst.size() {
+        return Err(ImageError::InvalidImageSize(
+            src.cols(),
+            src.rows(),
+            dst.cols(),
+            dst.rows(),
+        ));
+    }
+
+    let [rw, gw, bw] = cast_weights::<T>(weights)?;
+
+    parallel::par_iter_rows(src, dst, |src_pixel, dst_pixel| {
+        let luma = rw * src_pixel[0] + gw * src_pixel[1] + bw * src_pixel[2];
+        dst_pixel[0] = match alpha {
+            AlphaMode::Ignore => luma,
+            AlphaMode::Premultiply => luma * src_pixel[3],
+        };
+    });
+
+    Ok(())
+}
+
+fn cast_weights<T: num_traits::Float>(weights: GrayWeights) -> Result<[T; 3], ImageError> {
+    let [rw, gw, bw] = weights.coefficients();
+    Ok([
+        T::from(rw).ok_or(ImageError::CastError)?,
+        T::from(gw).ok_or(ImageError::CastError)?,
+        T::from(bw).ok_or(ImageError::CastError)?,
+    ])
+}
+
 /// Convert an RGB8 image to grayscale using the formula:
 ///
 /// Y = 77 * R + 150 * G + 29 * B
@@ -336,4 +467,40 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn gray_from_rgb_weights() -> Result<(), Box<dyn std::error::Error>> {
+        let image = Image::<f32, 3>::new([2, 1].into(), vec![1.0, 0.5, 0.25, 0.0, 0.0, 1.0])?;
+        let mut gray = Image::<f32, 1>::from_size_val(image.size(), 0.0)?;
+
+        // BT.601 matches the default conversion
+        let mut expected = Image::<f32, 1>::from_size_val(image.size(), 0.0)?;
+        super::gray_from_rgb(&image, &mut expected)?;
+        super::gray_from_rgb_weighted(&image, &mut gray, super::GrayWeights::Bt601)?;
+        assert_eq!(gray.as_slice(), expected.as_slice());
+
+        let weights = super::GrayWeights::Custom([0.0, 0.0, 1.0]);
+        super::gray_from_rgb_weighted(&image, &mut gray, weights)?;
+        assert_eq!(gray.as_slice(), &[0.25, 1.0]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn gray_from_rgba() -> Result<(), Box<dyn std::error::Error>> {
+        let image =
+            Image::<f32, 4>::new([2, 1].into(), vec![1.0, 1.0, 1.0, 0.5, 1.0, 0.0, 0.0, 0.0])?;
+        let mut gray = Image::<f32, 1>::from_size_val(image.size(), 0.0)?;
+
+        let weights = super::GrayWeights::Bt709;
+        super::gray_from_rgba(&image, &mut gray, weights, super::AlphaMode::Ignore)?;
+        assert!((gray.as_slice()[0] - 1.0).abs() < 1e-6);
+        assert!((gray.as_slice()[1] - 0.2126).abs() < 1e-6);
+
+        super::gray_from_rgba(&image, &mut gray, weights, super::AlphaMode::Premultiply)?;
+        assert!((gray.as_slice()[0] - 0.5).abs() < 1e-6);
+        assert_eq!(gray.as_slice()[1], 0.0);
+
+        Ok(())
+    }
 }
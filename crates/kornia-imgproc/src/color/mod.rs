@@ -1,5 +1,14 @@
 mod gray;
+#[cfg(feature = "std")]
 mod hsv;
+#[cfg(feature = "std")]
+mod yuv;
 
-pub use gray::{bgr_from_rgb, gray_from_rgb, gray_from_rgb_u8, rgb_from_gray};
+pub use gray::{
+    bgr_from_rgb, gray_from_rgb, gray_from_rgb_u8, gray_from_rgb_weighted, gray_from_rgba,
+    rgb_from_gray, AlphaMode, GrayWeights,
+};
+#[cfg(feature = "std")]
 pub use hsv::hsv_from_rgb;
+#[cfg(feature = "std")]
+pub use yuv::{gray_from_yuv, rgb_from_yuv, yuv_from_rgb, YuvFormat};
@@ -0,0 +1,533 @@
+use crate::parallel::prelude::*;
+use kornia_image::{Image, ImageError, ImageSize};
+
+/// The memory layout of a YUV buffer.
+///
+/// The chroma planes are subsampled and the odd image sizes are rounded up,
+/// i.e. the chroma width is `(width + 1) / 2`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum YuvFormat {
+    /// 4:2:0 with a full Y plane followed by an interleaved UV plane.
+    Nv12,
+    /// 4:2:0 with a full Y plane followed by the U and V planes.
+    I420,
+    /// 4:2:2 packed as Y0 U Y1 V for each pair of pixels.
+    Yuyv,
+}
+
+impl YuvFormat {
+    /// Returns the number of bytes of a buffer with the given image size.
+    pub fn buffer_size(&self, size: ImageSize) -> usize {
+        let (cw, ch) = chroma_size(size);
+        match self {
+            YuvFormat::Nv12 | YuvFormat::I420 => size.width * size.height + 2 * cw * ch,
+            YuvFormat::Yuyv => 4 * cw * size.height,
+        }
+    }
+}
+
+// the size of the subsampled chroma planes
+fn chroma_size(size: ImageSize) -> (usize, usize) {
+    (size.width.div_ceil(2), size.height.div_ceil(2))
+}
+
+fn check_buffer_size(len: usize, size: ImageSize, format: YuvFormat) -> Result<(), ImageError> {
+    let expected = format.buffer_size(size);
+    if len != expected {
+        return Err(ImageError::InvalidChannelShape(len, expected));
+    }
+    Ok(())
+}
+
+// convert a YUV pixel to RGB using the BT.601 limited range integer approximation
+#[inline(always)]
+fn yuv_to_rgb(y: u8, u: u8, v: u8) -> [u8; 3] {
+    let c = 298 * (y as i32 - 16);
+    let d = u as i32 - 128;
+    let e = v as i32 - 128;
+    [
+        ((c + 409 * e + 128) >> 8).clamp(0, 255) as u8,
+        ((c - 100 * d - 208 * e + 128) >> 8).clamp(0, 255) as u8,
+        ((c + 516 * d + 128) >> 8).clamp(0, 255) as u8,
+    ]
+}
+
+// the number of pixels converted at once, the loops over the lanes have no bounds checks
+// nor branches so they are compiled to SIMD instructions, e.g. SSE2/AVX2 or NEON
+const LANES: usize = 16;
+
+// convert the YUV samples of a block of pixels to interleaved RGB
+#[inline(always)]
+fn yuv_to_rgb_lanes(y: &[u8], u: [u8; LANES], v: [u8; LANES], dst: &mut [u8]) {
+    let y: &[u8; LANES] = y.try_into().expect("a block of LANES pixels");
+    let (mut r, mut g, mut b) = ([0u8; LANES], [0u8; LANES], [0u8; LANES]);
+    for i in 0..LANES {
+        [r[i], g[i], b[i]] = yuv_to_rgb(y[i], u[i], v[i]);
+    }
+    for (i, rgb) in dst.chunks_exact_mut(3).enumerate() {
+        rgb.copy_from_slice(&[r[i], g[i], b[i]]);
+    }
+}
+
+// convert a row of NV12 pixels, each UV pair is shared by two pixels
+fn rgb_row_from_nv12(y_row: &[u8], uv_row: &[u8], dst_row: &mut [u8]) {
+    let dst_row_len = dst_row.len();
+    let mut blocks = dst_row.chunks_exact_mut(3 * LANES);
+    for ((dst, y), uv) in (&mut blocks)
+        .zip(y_row.chunks_exact(LANES))
+        .zip(uv_row.chunks_exact(LANES))
+    {
+        let u = core::array::from_fn(|i| uv[i & !1]);
+        let v = core::array::from_fn(|i| uv[i | 1]);
+        yuv_to_rgb_lanes(y, u, v, dst);
+    }
+
+    // the remaining pixels one by one
+    let x0 = dst_row_len / 3 / LANES * LANES;
+    for (x, dst_pixel) in (x0..).zip(blocks.into_remainder().chunks_exact_mut(3)) {
+        let uv = &uv_row[x & !1..];
+        dst_pixel.copy_from_slice(&yuv_to_rgb(y_row[x], uv[0], uv[1]));
+    }
+}
+
+// convert a row of I420 pixels, each U and V sample is shared by two pixels
+fn rgb_row_from_i420(y_row: &[u8], u_row: &[u8], v_row: &[u8], dst_row: &mut [u8]) {
+    let dst_row_len = dst_row.len();
+    let mut blocks = dst_row.chunks_exact_mut(3 * LANES);
+    for (((dst, y), u), v) in (&mut blocks)
+        .zip(y_row.chunks_exact(LANES))
+        .zip(u_row.chunks_exact(LANES / 2))
+        .zip(v_row.chunks_exact(LANES / 2))
+    {
+        let u = core::array::from_fn(|i| u[i / 2]);
+        let v = core::array::from_fn(|i| v[i / 2]);
+        yuv_to_rgb_lanes(y, u, v, dst);
+    }
+
+    // the remaining pixels one by one
+    let x0 = dst_row_len / 3 / LANES * LANES;
+    for (x, dst_pixel) in (x0..).zip(blocks.into_remainder().chunks_exact_mut(3)) {
+        dst_pixel.copy_from_slice(&yuv_to_rgb(y_row[x], u_row[x / 2], v_row[x / 2]));
+    }
+}
+
+// convert a row of YUYV pixels packed as Y0 U Y1 V
+fn rgb_row_from_yuyv(src_row: &[u8], dst_row: &mut [u8]) {
+    let dst_row_len = dst_row.len();
+    let mut blocks = dst_row.chunks_exact_mut(3 * LANES);
+    for (dst, yuyv) in (&mut blocks).zip(src_row.chunks_exact(2 * LANES)) {
+        let y: [u8; LANES] = core::array::from_fn(|i| yuyv[2 * i]);
+        let u = core::array::from_fn(|i| yuyv[4 * (i / 2) + 1]);
+        let v = core::array::from_fn(|i| yuyv[4 * (i / 2) + 3]);
+        yuv_to_rgb_lanes(&y, u, v, dst);
+    }
+
+    // the remaining pixels one by one
+    let x0 = dst_row_len / 3 / LANES * LANES;
+    for (x, dst_pixel) in (x0..).zip(blocks.into_remainder().chunks_exact_mut(3)) {
+        let block = &src_row[4 * (x / 2)..][..4];
+        dst_pixel.copy_from_slice(&yuv_to_rgb(block[2 * (x % 2)], block[1], block[3]));
+    }
+}
+
+// expand a limited range luma sample to full range, i.e. (y - 16) * 255 / 219 with the
+// fixed point coefficient of `yuv_to_rgb`
+#[inline(always)]
+fn full_range_luma(y: u8) -> u8 {
+    ((298 * (y as i32 - 16) + 128) >> 8).clamp(0, 255) as u8
+}
+
+// compute the luma of an RGB pixel using the BT.601 limited range integer approximation
+#[inline(always)]
+fn luma(rgb: &[u8]) -> u8 {
+    let (r, g, b) = (rgb[0] as i32, rgb[1] as i32, rgb[2] as i32);
+    (((66 * r + 129 * g + 25 * b + 128) >> 8) + 16) as u8
+}
+
+// compute the chroma of an RGB pixel using the BT.601 limited range integer approximation
+#[inline(always)]
+fn chroma(r: i32, g: i32, b: i32) -> (u8, u8) {
+    let u = ((-38 * r - 74 * g + 112 * b + 128) >> 8) + 128;
+    let v = ((112 * r - 94 * g - 18 * b + 128) >> 8) + 128;
+    (u.clamp(0, 255) as u8, v.clamp(0, 255) as u8)
+}
+
+// average the chroma of a block of RGB pixels
+fn block_chroma<'a>(pixels: impl Iterator<Item = &'a [u8]>) -> (u8, u8) {
+    let (mut r, mut g, mut b, mut n) = (0, 0, 0, 0);
+    for p in pixels {
+        r += p[0] as i32;
+        g += p[1] as i32;
+        b += p[2] as i32;
+        n += 1;
+    }
+    chroma((r + n / 2) / n, (g + n / 2) / n, (b + n / 2) / n)
+}
+
+/// Convert a YUV buffer to an RGB image.
+///
+/// The conversion uses the BT.601 limited range coefficients, which is the usual
+/// output of cameras and video decoders.
+///
+/// # Arguments
+///
+/// * `src` - The input YUV buffer.
+/// * `dst` - The output RGB image.
+/// * `format` - The memory layout of the YUV buffer.
+///
+/// Precondition: the input buffer size must be `format.buffer_size(dst.size())`.
+///
+/// # Example
+///
+/// ```
+/// use kornia_image::{Image, ImageSize};
+/// use kornia_imgproc::color::{rgb_from_yuv, YuvFormat};
+///
+/// let size = ImageSize { width: 4, height: 2 };
+/// let nv12 = vec![128u8; YuvFormat::Nv12.buffer_size(size)];
+///
+/// let mut rgb = Image::<u8, 3>::from_size_val(size, 0).unwrap();
+/// rgb_from_yuv(&nv12, &mut rgb, YuvFormat::Nv12).unwrap();
+/// ```
+pub fn rgb_from_yuv(
+    src: &[u8],
+    dst: &mut Image<u8, 3>,
+    format: YuvFormat,
+) -> Result<(), ImageError> {
+    let size = dst.size();
+    check_buffer_size(src.len(), size, format)?;
+
+    let width = size.width;
+    let (cw, ch) = chroma_size(size);
+    let luma_len = width * size.height;
+
+    dst.as_slice_mut()
+        .par_chunks_exact_mut(3 * width)
+        .enumerate()
+        .for_each(|(row, dst_row)| match format {
+            YuvFormat::Nv12 => {
+                let y_row = &src[row * width..][..width];
+                let uv_row = &src[luma_len + (row / 2) * 2 * cw..][..2 * cw];
+                rgb_row_from_nv12(y_row, uv_row, dst_row);
+            }
+            YuvFormat::I420 => {
+                let y_row = &src[row * width..][..width];
+                let u_row = &src[luma_len + (row / 2) * cw..][..cw];
+                let v_row = &src[luma_len + cw * ch + (row / 2) * cw..][..cw];
+                rgb_row_from_i420(y_row, u_row, v_row, dst_row);
+            }
+            YuvFormat::Yuyv => {
+                let src_row = &src[row * 4 * cw..][..4 * cw];
+                rgb_row_from_yuyv(src_row, dst_row);
+            }
+        });
+
+    Ok(())
+}
+
+/// Convert a YUV buffer to a grayscale image.
+///
+/// The BT.601 limited range luma [16, 235] is expanded to the full range [0, 255], so
+/// the result matches the grayscale of [`rgb_from_yuv`] for neutral chroma.
+///
+/// # Arguments
+///
+/// * `src` - The input YUV buffer.
+/// * `dst` - The output grayscale image.
+/// * `format` - The memory layout of the YUV buffer.
+///
+/// Precondition: the input buffer size must be `format.buffer_size(dst.size())`.
+pub fn gray_from_yuv(
+    src: &[u8],
+    dst: &mut Image<u8, 1>,
+    format: YuvFormat,
+) -> Result<(), ImageError> {
+    let size = dst.size();
+    check_buffer_size(src.len(), size, format)?;
+
+    let width = size.width;
+    let (cw, _) = chroma_size(size);
+
+    match format {
+        YuvFormat::Nv12 | YuvFormat::I420 => {
+            dst.as_slice_mut()
+                .par_chunks_exact_mut(width)
+                .zip(src.par_chunks_exact(width))
+                .for_each(|(dst_row, y_row)| {
+                    dst_row
+                        .iter_mut()
+                        .zip(y_row)
+                        .for_each(|(dst_pixel, &y)| *dst_pixel = full_range_luma(y));
+                });
+        }
+        YuvFormat::Yuyv => {
+            dst.as_slice_mut()
+                .par_chunks_exact_mut(width)
+                .zip(src.par_chunks_exact(4 * cw))
+                .for_each(|(dst_row, src_row)| {
+                    dst_row
+                        .iter_mut()
+                        .zip(src_row.iter().step_by(2))
+                        .for_each(|(dst_pixel, &y)| *dst_pixel = full_range_luma(y));
+                });
+        }
+    }
+
+    Ok(())
+}
+
+/// Convert an RGB image to a YUV buffer.
+///
+/// The conversion uses the BT.601 limited range coefficients and the chroma is
+/// averaged over the subsampled pixels.
+///
+/// # Arguments
+///
+/// * `src` - The input RGB image.
+/// * `dst` - The output YUV buffer.
+/// * `format` - The memory layout of the YUV buffer.
+///
+/// Precondition: the output buffer size must be `format.buffer_size(src.size())`.
+pub fn yuv_from_rgb(
+    src: &Image<u8, 3>,
+    dst: &mut [u8],
+    format: YuvFormat,
+) -> Result<(), ImageError> {
+    let size = src.size();
+    check_buffer_size(dst.len(), size, format)?;
+
+    let width = size.width;
+    let (cw, ch) = chroma_size(size);
+    let src_rows = src.as_slice();
+
+    // the chroma of the pixels in rows [row, row + 2) and columns [2 * cx, 2 * cx + 2)
+    let block = |rows: &[u8], cx: usize| {
+        block_chroma(
+            rows.chunks_exact(3 * width)
+                .flat_map(|r| r.chunks_exact(3).skip(2 * cx).take(2)),
+        )
+    };
+
+    match format {
+        YuvFormat::Nv12 | YuvFormat::I420 => {
+            let (y_plane, uv_plane) = dst.split_at_mut(width * size.height);
+
+            y_plane
+                .par_chunks_exact_mut(width)
+                .zip(src_rows.par_chunks_exact(3 * width))
+                .for_each(|(y_row, src_row)| {
+                    y_row
+                        .iter_mut()
+                        .zip(src_row.chunks_exact(3))
+                        .for_each(|(y, rgb)| *y = luma(rgb));
+                });
+
+            if format == YuvFormat::Nv12 {
+                uv_plane
+                    .par_chunks_exact_mut(2 * cw)
+                    .zip(src_rows.par_chunks(6 * width))
+                    .for_each(|(uv_row, rows)| {
+                        for (cx, uv) in uv_row.chunks_exact_mut(2).enumerate() {
+                            (uv[0], uv[1]) = block(rows, cx);
+                        }
+                    });
+            } else {
+                let (u_plane, v_plane) = uv_plane.split_at_mut(cw * ch);
+                u_plane
+                    .par_chunks_exact_mut(cw)
+                    .zip(v_plane.par_chunks_exact_mut(cw))
+                    .zip(src_rows.par_chunks(6 * width))
+                    .for_each(|((u_row, v_row), rows)| {
+                        for (cx, (u, v)) in u_row.iter_mut().zip(v_row.iter_mut()).enumerate() {
+                            (*u, *v) = block(rows, cx);
+                        }
+                    });
+            }
+        }
+        YuvFormat::Yuyv => {
+            dst.par_chunks_exact_mut(4 * cw)
+                .zip(src_rows.par_chunks_exact(3 * width))
+                .for_each(|(dst_row, src_row)| {
+                    for (cx, yuyv) in dst_row.chunks_exact_mut(4).enumerate() {
+                        let x = 2 * cx;
+                        let y0 = luma(&src_row[3 * x..]);
+                        // replicate the last pixel for odd widths
+                        let y1 = if x + 1 < width {
+                            luma(&src_row[3 * (x + 1)..])
+                        } else {
+                            y0
+                        };
+                        let (u, v) = block(src_row, cx);
+                        yuyv.copy_from_slice(&[y0, u, y1, v]);
+                    }
+                });
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn yuv_buffer_size() {
+        let size = ImageSize {
+            width: 5,
+            height: 3,
+        };
+        assert_eq!(YuvFormat::Nv12.buffer_size(size), 15 + 2 * 3 * 2);
+        assert_eq!(YuvFormat::I420.buffer_size(size), 15 + 2 * 3 * 2);
+        assert_eq!(YuvFormat::Yuyv.buffer_size(size), 4 * 3 * 3);
+    }
+
+    #[test]
+    fn rgb_from_yuv_primaries() -> Result<(), ImageError> {
+        let size = ImageSize {
+            width: 2,
+            height: 2,
+        };
+
+        // limited range black, white and red
+        for (y, u, v, expected) in [
+            (16u8, 128u8, 128u8, [0u8, 0, 0]),
+            (235, 128, 128, [255, 255, 255]),
+            (81, 90, 240, [255, 0, 0]),
+        ] {
+            let nv12 = [y, y, y, y, u, v];
+            let i420 = [y, y, y, y, u, v];
+            let yuyv = [y, u, y, v, y, u, y, v];
+
+            for (format, src) in [
+                (YuvFormat::Nv12, &nv12[..]),
+                (YuvFormat::I420, &i420[..]),
+                (YuvFormat::Yuyv, &yuyv[..]),
+            ] {
+                let mut rgb = Image::<u8, 3>::from_size_val(size, 0)?;
+                rgb_from_yuv(src, &mut rgb, format)?;
+                for pixel in rgb.as_slice().chunks_exact(3) {
+                    for (a, b) in pixel.iter().zip(expected.iter()) {
+                        assert!((*a as i32 - *b as i32).abs() <= 1, "{format:?}");
+                    }
+                }
+
+                // the luma is expanded to full range like the rgb conversion
+                let mut gray = Image::<u8, 1>::from_size_val(size, 0)?;
+                gray_from_yuv(src, &mut gray, format)?;
+                assert_eq!(gray.as_slice(), &[full_range_luma(y); 4]);
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn rgb_from_yuv_lanes() -> Result<(), ImageError> {
+        // two blocks of lanes and an odd remainder per row
+        let size = ImageSize {
+            width: 2 * LANES + 5,
+            height: 3,
+        };
+        let (cw, ch) = chroma_size(size);
+
+        for format in [YuvFormat::Nv12, YuvFormat::I420, YuvFormat::Yuyv] {
+            let src = (0..format.buffer_size(size))
+                .map(|i| (i * 37 % 256) as u8)
+                .collect::<Vec<_>>();
+            let mut rgb = Image::<u8, 3>::from_size_val(size, 0)?;
+            rgb_from_yuv(&src, &mut rgb, format)?;
+
+            // the samples of each pixel converted one by one
+            let luma_len = size.width * size.height;
+            for (i, pixel) in rgb.as_slice().chunks_exact(3).enumerate() {
+                let (row, x) = (i / size.width, i % size.width);
+                let (c_row, cx) = (row / 2, x / 2);
+                let (y, u, v) = match format {
+                    YuvFormat::Nv12 => {
+                        let uv = luma_len + c_row * 2 * cw + 2 * cx;
+                        (src[i], src[uv], src[uv + 1])
+                    }
+                    YuvFormat::I420 => (
+                        src[i],
+                        src[luma_len + c_row * cw + cx],
+                        src[luma_len + cw * ch + c_row * cw + cx],
+                    ),
+                    YuvFormat::Yuyv => {
+                        let block = row * 4 * cw + 4 * cx;
+                        (src[block + 2 * (x % 2)], src[block + 1], src[block + 3])
+                    }
+                };
+                assert_eq!(pixel, yuv_to_rgb(y, u, v), "{format:?} at {x}, {row}");
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn gray_from_yuv_full_range() -> Result<(), ImageError> {
+        let size = ImageSize {
+            width: 4,
+            height: 64,
+        };
+        // every luma value with neutral chroma
+        let mut nv12 = (0..=255u8).collect::<Vec<_>>();
+        nv12.resize(YuvFormat::Nv12.buffer_size(size), 128);
+
+        let mut gray = Image::<u8, 1>::from_size_val(size, 0)?;
+        gray_from_yuv(&nv12, &mut gray, YuvFormat::Nv12)?;
+        assert_eq!(gray.as_slice()[..=16], [0; 17]);
+        assert_eq!(gray.as_slice()[235..], [255; 21]);
+        assert_eq!(gray.as_slice()[126], 128);
+
+        let mut rgb = Image::<u8, 3>::from_size_val(size, 0)?;
+        rgb_from_yuv(&nv12, &mut rgb, YuvFormat::Nv12)?;
+        let mut gray_rgb = Image::<u8, 1>::from_size_val(size, 0)?;
+        crate::color::gray_from_rgb_u8(&rgb, &mut gray_rgb)?;
+        assert_eq!(gray.as_slice(), gray_rgb.as_slice());
+
+        Ok(())
+    }
+
+    #[test]
+    fn yuv_roundtrip() -> Result<(), ImageError> {
+        // odd sizes with a smooth image so that the chroma subsampling is lossless enough
+        let size = ImageSize {
+            width: 7,
+            height: 5,
+        };
+        let data = (0..size.width * size.height)
+            .flat_map(|i| {
+                let v = (i * 3) as u8;
+                [100 + v, 120, 140 - v / 2]
+            })
+            .collect::<Vec<_>>();
+        let rgb = Image::<u8, 3>::new(size, data)?;
+
+        for format in [YuvFormat::Nv12, YuvFormat::I420, YuvFormat::Yuyv] {
+            let mut yuv = vec![0u8; format.buffer_size(size)];
+            yuv_from_rgb(&rgb, &mut yuv, format)?;
+
+            let mut rgb_back = Image::<u8, 3>::from_size_val(size, 0)?;
+            rgb_from_yuv(&yuv, &mut rgb_back, format)?;
+
+            for (a, b) in rgb.as_slice().iter().zip(rgb_back.as_slice()) {
+                assert!(
+                    (*a as i32 - *b as i32).abs() <= 12,
+                    "{format:?}: {a} vs {b}"
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn yuv_invalid_buffer_size() -> Result<(), ImageError> {
+        let mut rgb = Image::<u8, 3>::from_size_val([4, 4].into(), 0)?;
+        let res = rgb_from_yuv(&[0; 10], &mut rgb, YuvFormat::Nv12);
+        assert!(matches!(res, Err(ImageError::InvalidChannelShape(10, 24))));
+        Ok(())
+    }
+}
@@ -1,9 +1,6 @@
 // reference: https://www.strchr.com/standard_deviation_in_one_pass
+use crate::parallel::prelude::*;
 use kornia_image::{Image, ImageError};
-use rayon::{
-    iter::{IndexedParallelIterator, ParallelIterator},
-    slice::{ParallelSlice, ParallelSliceMut},
-};
 
 /// Compute the mean and standard deviation of an image.
 ///
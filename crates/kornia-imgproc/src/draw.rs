@@ -1,4 +1,117 @@
-use kornia_image::Image;
+use kornia_image::{Image, ImageError, ImageSize};
+
+// the width and height in pixels of the glyphs of the bitmap font
+const GLYPH_WIDTH: i64 = 5;
+const GLYPH_HEIGHT: i64 = 7;
+
+// 5x7 bitmap font for the printable ASCII characters starting at the space.
+// Each glyph is stored as five columns where the least significant bit is the top row.
+#[rustfmt::skip]
+const FONT_5X7: [[u8; 5]; 95] = [
+    [0x00, 0x00, 0x00, 0x00, 0x00], // ' '
+    [0x00, 0x00, 0x5F, 0x00, 0x00], // '!'
+    [0x00, 0x07, 0x00, 0x07, 0x00], // '"'
+    [0x14, 0x7F, 0x14, 0x7F, 0x14], // '#'
+    [0x24, 0x2A, 0x7F, 0x2A, 0x12], // '$'
+    [0x23, 0x13, 0x08, 0x64, 0x62], // '%'
+    [0x36, 0x49, 0x55, 0x22, 0x50], // '&'
+    [0x00, 0x05, 0x03, 0x00, 0x00], // '''
+    [0x00, 0x1C, 0x22, 0x41, 0x00], // '('
+    [0x00, 0x41, 0x22, 0x1C, 0x00], // ')'
+    [0x08, 0x2A, 0x1C, 0x2A, 0x08], // '*'
+    [0x08, 0x08, 0x3E, 0x08, 0x08], // '+'
+    [0x00, 0x50, 0x30, 0x00, 0x00], // ','
+    [0x08, 0x08, 0x08, 0x08, 0x08], // '-'
+    [0x00, 0x60, 0x60, 0x00, 0x00], // '.'
+    [0x20, 0x10, 0x08, 0x04, 0x02], // '/'
+    [0x3E, 0x51, 0x49, 0x45, 0x3E], // '0'
+    [0x00, 0x42, 0x7F, 0x40, 0x00], // '1'
+    [0x42, 0x61, 0x51, 0x49, 0x46], // '2'
+    [0x21, 0x41, 0x45, 0x4B, 0x31], // '3'
+    [0x18, 0x14, 0x12, 0x7F, 0x10], // '4'
+    [0x27, 0x45, 0x45, 0x45, 0x39], // '5'
+    [0x3C, 0x4A, 0x49, 0x49, 0x30], // '6'
+    [0x01, 0x71, 0x09, 0x05, 0x03], // '7'
+    [0x36, 0x49, 0x49, 0x49, 0x36], // '8'
+    [0x06, 0x49, 0x49, 0x29, 0x1E], // '9'
+    [0x00, 0x36, 0x36, 0x00, 0x00], // ':'
+    [0x00, 0x56, 0x36, 0x00, 0x00], // ';'
+    [0x08, 0x14, 0x22, 0x41, 0x00], // '<'
+    [0x14, 0x14, 0x14, 0x14, 0x14], // '='
+    [0x00, 0x41, 0x22, 0x14, 0x08], // '>'
+    [0x02, 0x01, 0x51, 0x09, 0x06], // '?'
+    [0x32, 0x49, 0x79, 0x41, 0x3E], // '@'
+    [0x7E, 0x11, 0x11, 0x11, 0x7E], // 'A'
+    [0x7F, 0x49, 0x49, 0x49, 0x36], // 'B'
+    [0x3E, 0x41, 0x41, 0x41, 0x22], // 'C'
+    [0x7F, 0x41, 0x41, 0x22, 0x1C], // 'D'
+    [0x7F, 0x49, 0x49, 0x49, 0x41], // 'E'
+    [0x7F, 0x09, 0x09, 0x09, 0x01], // 'F'
+    [0x3E, 0x41, 0x49, 0x49, 0x7A], // 'G'
+    [0x7F, 0x08, 0x08, 0x08, 0x7F], // 'H'
+    [0x00, 0x41, 0x7F, 0x41, 0x00], // 'I'
+    [0x20, 0x40, 0x41, 0x3F, 0x01], // 'J'
+    [0x7F, 0x08, 0x14, 0x22, 0x41], // 'K'
+    [0x7F, 0x40, 0x40, 0x40, 0x40], // 'L'
+    [0x7F, 0x02, 0x0C, 0x02, 0x7F], // 'M'
+    [0x7F, 0x04, 0x08, 0x10, 0x7F], // 'N'
+    [0x3E, 0x41, 0x41, 0x41, 0x3E], // 'O'
+    [0x7F, 0x09, 0x09, 0x09, 0x06], // 'P'
+    [0x3E, 0x41, 0x51, 0x21, 0x5E], // 'Q'
+    [0x7F, 0x09, 0x19, 0x29, 0x46], // 'R'
+    [0x46, 0x49, 0x49, 0x49, 0x31], // 'S'
+    [0x01, 0x01, 0x7F, 0x01, 0x01], // 'T'
+    [0x3F, 0x40, 0x40, 0x40, 0x3F], // 'U'
+    [0x1F, 0x20, 0x40, 0x20, 0x1F], // 'V'
+    [0x3F, 0x40, 0x38, 0x40, 0x3F], // 'W'
+    [0x63, 0x14, 0x08, 0x14, 0x63], // 'X'
+    [0x07, 0x08, 0x70, 0x08, 0x07], // 'Y'
+    [0x61, 0x51, 0x49, 0x45, 0x43], // 'Z'
+    [0x00, 0x7F, 0x41, 0x41, 0x00], // '['
+    [0x02, 0x04, 0x08, 0x10, 0x20], // '\'
+    [0x00, 0x41, 0x41, 0x7F, 0x00], // ']'
+    [0x04, 0x02, 0x01, 0x02, 0x04], // '^'
+    [0x40, 0x40, 0x40, 0x40, 0x40], // '_'
+    [0x00, 0x01, 0x02, 0x04, 0x00], // '`'
+    [0x20, 0x54, 0x54, 0x54, 0x78], // 'a'
+    [0x7F, 0x48, 0x44, 0x44, 0x38], // 'b'
+    [0x38, 0x44, 0x44, 0x44, 0x20], // 'c'
+    [0x38, 0x44, 0x44, 0x48, 0x7F], // 'd'
+    [0x38, 0x54, 0x54, 0x54, 0x18], // 'e'
+    [0x08, 0x7E, 0x09, 0x01, 0x02], // 'f'
+    [0x0C, 0x52, 0x52, 0x52, 0x3E], // 'g'
+    [0x7F, 0x08, 0x04, 0x04, 0x78], // 'h'
+    [0x00, 0x44, 0x7D, 0x40, 0x00], // 'i'
+    [0x20, 0x40, 0x44, 0x3D, 0x00], // 'j'
+    [0x7F, 0x10, 0x28, 0x44, 0x00], // 'k'
+    [0x00, 0x41, 0x7F, 0x40, 0x00], // 'l'
+    [0x7C, 0x04, 0x18, 0x04, 0x78], // 'm'
+    [0x7C, 0x08, 0x04, 0x04, 0x78], // 'n'
+    [0x38, 0x44, 0x44, 0x44, 0x38], // 'o'
+    [0x7C, 0x14, 0x14, 0x14, 0x08], // 'p'
+    [0x08, 0x14, 0x14, 0x18, 0x7C], // 'q'
+    [0x7C, 0x08, 0x04, 0x04, 0x08], // 'r'
+    [0x48, 0x54, 0x54, 0x54, 0x20], // 's'
+    [0x04, 0x3F, 0x44, 0x40, 0x20], // 't'
+    [0x3C, 0x40, 0x40, 0x20, 0x7C], // 'u'
+    [0x1C, 0x20, 0x40, 0x20, 0x1C], // 'v'
+    [0x3C, 0x40, 0x30, 0x40, 0x3C], // 'w'
+    [0x44, 0x28, 0x10, 0x28, 0x44], // 'x'
+    [0x0C, 0x50, 0x50, 0x50, 0x3C], // 'y'
+    [0x44, 0x64, 0x54, 0x4C, 0x44], // 'z'
+    [0x00, 0x08, 0x36, 0x41, 0x00], // '{'
+    [0x00, 0x00, 0x7F, 0x00, 0x00], // '|'
+    [0x00, 0x41, 0x36, 0x08, 0x00], // '}'
+    [0x08, 0x04, 0x08, 0x10, 0x08], // '~'
+];
+
+// set the color of a pixel skipping the pixels outside of the image
+fn put_pixel<const C: usize>(img: &mut Image<u8, C>, x: i64, y: i64, color: [u8; C]) {
+    if x >= 0 && x < img.cols() as i64 && y >= 0 && y < img.rows() as i64 {
+        let offset = (y as usize * img.cols() + x as usize) * C;
+        img.as_slice_mut()[offset..offset + C].copy_from_slice(&color);
+    }
+}
 
 /// Draws a line on an image inplace.
 ///
@@ -38,15 +151,7 @@ pub fn draw_line<const C: usize>(
             for j in 0..thickness as i64 {
                 let x = x0 + i - (thickness as i64 / 2);
                 let y = y0 + j - (thickness as i64 / 2);
-
-                // check if the pixel is within the image bounds otherwise skip
-                if x >= 0 && x < img.cols() as i64 && y >= 0 && y < img.rows() as i64 {
-                    let pixel_linear_index = (y * img.cols() as i64 + x) * C as i64;
-                    for (c, &color_channel) in color.iter().enumerate() {
-                        // TODO: implement safe pixel access
-                        img.as_slice_mut()[pixel_linear_index as usize + c] = color_channel;
-                    }
-                }
+                put_pixel(img, x, y, color);
             }
         }
 
@@ -70,10 +175,524 @@ pub fn draw_line<const C: usize>(
     }
 }
 
+/// Draws the outline of a rectangle on an image inplace.
+///
+/// # Arguments
+///
+/// * `img` - The image to draw on.
+/// * `p0` - The top-left corner of the rectangle as a tuple of (x, y).
+/// * `p1` - The bottom-right corner of the rectangle as a tuple of (x, y).
+/// * `color` - The color of the rectangle as an array of `C` elements.
+/// * `thickness` - The thickness of the outline.
+pub fn draw_rectangle<const C: usize>(
+    img: &mut Image<u8, C>,
+    p0: (i64, i64),
+    p1: (i64, i64),
+    color: [u8; C],
+    thickness: usize,
+) {
+    let (x0, y0) = p0;
+    let (x1, y1) = p1;
+    draw_line(img, (x0, y0), (x1, y0), color, thickness);
+    draw_line(img, (x1, y0), (x1, y1), color, thickness);
+    draw_line(img, (x1, y1), (x0, y1), color, thickness);
+    draw_line(img, (x0, y1), (x0, y0), color, thickness);
+}
+
+/// Draws a filled rectangle on an image inplace.
+///
+/// # Arguments
+///
+/// * `img` - The image to draw on.
+/// * `p0` - The top-left corner of the rectangle as a tuple of (x, y).
+/// * `p1` - The bottom-right corner of the rectangle as a tuple of (x, y), inclusive.
+/// * `color` - The color of the rectangle as an array of `C` elements.
+pub fn draw_filled_rectangle<const C: usize>(
+    img: &mut Image<u8, C>,
+    p0: (i64, i64),
+    p1: (i64, i64),
+    color: [u8; C],
+) {
+    // clip the rectangle to the image bounds
+    let x_min = p0.0.min(p1.0).max(0);
+    let x_max = p0.0.max(p1.0).min(img.cols() as i64 - 1);
+    let y_min = p0.1.min(p1.1).max(0);
+    let y_max = p0.1.max(p1.1).min(img.rows() as i64 - 1);
+
+    for y in y_min..=y_max {
+        for x in x_min..=x_max {
+            put_pixel(img, x, y, color);
+        }
+    }
+}
+
+/// Draws the outline of a circle on an image inplace.
+///
+/// # Arguments
+///
+/// * `img` - The image to draw on.
+/// * `center` - The center of the circle as a tuple of (x, y).
+/// * `radius` - The radius of the circle in pixels.
+/// * `color` - The color of the circle as an array of `C` elements.
+/// * `thickness` - The thickness of the outline.
+pub fn draw_circle<const C: usize>(
+    img: &mut Image<u8, C>,
+    center: (i64, i64),
+    radius: usize,
+    color: [u8; C],
+    thickness: usize,
+) {
+    // the pixels whose distance to the circle is within half of the thickness
+    let half_thickness = thickness.max(1) as f64 / 2.0;
+    let r_in = (radius as f64 - half_thickness).max(0.0);
+    let r_out = radius as f64 + half_thickness;
+    let extent = r_out.ceil() as i64;
+
+    for dy in -extent..=extent {
+        for dx in -extent..=extent {
+            let dist = ((dx * dx + dy * dy) as f64).sqrt();
+            if dist >= r_in && dist < r_out {
+                put_pixel(img, center.0 + dx, center.1 + dy, color);
+            }
+        }
+    }
+}
+
+/// Draws a filled circle on an image inplace.
+///
+/// # Arguments
+///
+/// * `img` - The image to draw on.
+/// * `center` - The center of the circle as a tuple of (x, y).
+/// * `radius` - The radius of the circle in pixels.
+/// * `color` - The color of the circle as an array of `C` elements.
+pub fn draw_filled_circle<const C: usize>(
+    img: &mut Image<u8, C>,
+    center: (i64, i64),
+    radius: usize,
+    color: [u8; C],
+) {
+    let radius = radius as i64;
+    for dy in -radius..=radius {
+        for dx in -radius..=radius {
+            if dx * dx + dy * dy <= radius * radius {
+                put_pixel(img, center.0 + dx, center.1 + dy, color);
+            }
+        }
+    }
+}
+
+/// Draws a cross marker on an image inplace.
+///
+/// # Arguments
+///
+/// * `img` - The image to draw on.
+/// * `center` - The center of the cross as a tuple of (x, y).
+/// * `size` - The length of the arms of the cross from the center.
+/// * `color` - The color of the cross as an array of `C` elements.
+/// * `thickness` - The thickness of the lines.
+pub fn draw_cross<const C: usize>(
+    img: &mut Image<u8, C>,
+    center: (i64, i64),
+    size: usize,
+    color: [u8; C],
+    thickness: usize,
+) {
+    let (x, y) = center;
+    let size = size as i64;
+    draw_line(img, (x - size, y), (x + size, y), color, thickness);
+    draw_line(img, (x, y - size), (x, y + size), color, thickness);
+}
+
+/// Draws text on an image inplace using a 5x7 bitmap font.
+///
+/// Only the printable ASCII characters are supported, the other characters are drawn as `?`.
+/// The new line character moves the cursor to the beginning of the next line.
+///
+/// # Arguments
+///
+/// * `img` - The image to draw on.
+/// * `origin` - The top-left corner of the text as a tuple of (x, y).
+/// * `text` - The text to draw.
+/// * `color` - The color of the text as an array of `C` elements.
+/// * `scale` - The size in pixels of each dot of the font.
+pub fn draw_text<const C: usize>(
+    img: &mut Image<u8, C>,
+    origin: (i64, i64),
+    text: &str,
+    color: [u8; C],
+    scale: usize,
+) {
+    let scale = scale.max(1) as i64;
+    let (mut cursor_x, mut cursor_y) = origin;
+
+    for ch in text.chars() {
+        if ch == '\n' {
+            cursor_x = origin.0;
+            cursor_y += (GLYPH_HEIGHT + 1) * scale;
+            continue;
+        }
+
+        let index = match ch {
+            ' '..='~' => ch as usize - ' ' as usize,
+            _ => '?' as usize - ' ' as usize,
+        };
+
+        for (col, bits) in FONT_5X7[index].iter().enumerate() {
+            for row in 0..GLYPH_HEIGHT {
+                if bits & (1 << row) == 0 {
+                    continue;
+                }
+                let x = cursor_x + col as i64 * scale;
+                let y = cursor_y + row * scale;
+                draw_filled_rectangle(img, (x, y), (x + scale - 1, y + scale - 1), color);
+            }
+        }
+
+        cursor_x += (GLYPH_WIDTH + 1) * scale;
+    }
+}
+
+/// Draws keypoints as circles on an image inplace.
+///
+/// # Arguments
+///
+/// * `img` - The image to draw on.
+/// * `keypoints` - The keypoints as (x, y) coordinates.
+/// * `color` - The color of the keypoints as an array of `C` elements.
+/// * `radius` - The radius of the circles in pixels.
+pub fn draw_keypoints<const C: usize>(
+    img: &mut Image<u8, C>,
+    keypoints: &[[f32; 2]],
+    color: [u8; C],
+    radius: usize,
+) {
+    for kp in keypoints {
+        let center = (kp[0].round() as i64, kp[1].round() as i64);
+        draw_circle(img, center, radius, color, 1);
+    }
+}
+
+/// Draws the matches between the keypoints of two images side by side.
+///
+/// The two images are placed next to each other, the keypoints are drawn as circles
+/// and each match is drawn as a line between the matched keypoints.
+///
+/// # Arguments
+///
+/// * `img1` - The first image, placed on the left.
+/// * `keypoints1` - The keypoints of the first image as (x, y) coordinates.
+/// * `img2` - The second image, placed on the right.
+/// * `keypoints2` - The keypoints of the second image as (x, y) coordinates.
+/// * `matches` - The pairs of indices into `keypoints1` and `keypoints2`.
+/// * `color` - The color of the matches as an array of `C` elements.
+///
+/// # Returns
+///
+/// A new image with the two images side by side and the matches drawn on top.
+/// The matches with indices out of range are skipped.
+pub fn draw_matches<const C: usize>(
+    img1: &Image<u8, C>,
+    keypoints1: &[[f32; 2]],
+    img2: &Image<u8, C>,
+    keypoints2: &[[f32; 2]],
+    matches: &[(usize, usize)],
+    color: [u8; C],
+) -> Result<Image<u8, C>, ImageError> {
+    let mut dst = Image::from_size_val(
+        ImageSize {
+            width: img1.cols() + img2.cols(),
+            height: img1.rows().max(img2.rows()),
+        },
+        0u8,
+    )?;
+
+    // copy the images row by row
+    let dst_row_len = dst.cols() * C;
+    for (dst_row, src_row) in dst
+        .as_slice_mut()
+        .chunks_exact_mut(dst_row_len)
+        .zip(img1.as_slice().chunks_exact(img1.cols() * C))
+    {
+        dst_row[..src_row.len()].copy_from_slice(src_row);
+    }
+    for (dst_row, src_row) in dst
+        .as_slice_mut()
+        .chunks_exact_mut(dst_row_len)
+        .zip(img2.as_slice().chunks_exact(img2.cols() * C))
+    {
+        dst_row[img1.cols() * C..].copy_from_slice(src_row);
+    }
+
+    let offset = img1.cols() as f32;
+    for &(i, j) in matches {
+        let (Some(kp1), Some(kp2)) = (keypoints1.get(i), keypoints2.get(j)) else {
+            continue;
+        };
+        let p1 = (kp1[0].round() as i64, kp1[1].round() as i64);
+        let p2 = ((kp2[0] + offset).round() as i64, kp2[1].round() as i64);
+        draw_circle(&mut dst, p1, 3, color, 1);
+        draw_circle(&mut dst, p2, 3, color, 1);
+        draw_line(&mut dst, p1, p2, color, 1);
+    }
+
+    Ok(dst)
+}
+
+// the number of hues between the primary and secondary colors of the flow color wheel
+const COLOR_WHEEL_SEGMENTS: [usize; 6] = [15, 6, 4, 11, 13, 6];
+
+// the Middlebury color wheel going through red, yellow, green, cyan, blue and magenta
+fn flow_color_wheel() -> Vec<[f32; 3]> {
+    let mut wheel = Vec::with_capacity(COLOR_WHEEL_SEGMENTS.iter().sum());
+    for (segment, &len) in COLOR_WHEEL_SEGMENTS.iter().enumerate() {
+        // the channel fixed at the maximum and the channel which ramps up or down
+        let (fixed, ramp, up) = match segment {
+            0 => (0, 1, true),
+            1 => (1, 0, false),
+            2 => (1, 2, true),
+            3 => (2, 1, false),
+            4 => (2, 0, true),
+            _ => (0, 2, false),
+        };
+        for i in 0..len {
+            let t = (255 * i / len) as f32;
+            let mut color = [0.0; 3];
+            color[fixed] = 255.0;
+            color[ramp] = if up { t } else { 255.0 - t };
+            wheel.push(color);
+        }
+    }
+    wheel
+}
+
+/// Renders an optical flow field with the Middlebury color coding.
+///
+/// The hue of a pixel encodes the direction of its flow vector and the saturation the
+/// magnitude relative to the maximum magnitude. The vectors longer than the maximum
+/// magnitude are darkened and the non-finite vectors are drawn in black.
+///
+/// # Arguments
+///
+/// * `flow` - The flow field with shape (H, W, 2) and the (u, v) displacements.
+/// * `dst` - The destination RGB image with shape (H, W, 3).
+/// * `max_magnitude` - The magnitude of the fully saturated colors, or `None` to use the
+///   largest finite magnitude of the flow.
+pub fn draw_flow(
+    flow: &Image<f32, 2>,
+    dst: &mut Image<u8, 3>,
+    max_magnitude: Option<f32>,
+) -> Result<(), ImageError> {
+    if flow.size() != dst.size() {
+        return Err(ImageError::InvalidImageSize(
+            flow.cols(),
+            flow.rows(),
+            dst.cols(),
+            dst.rows(),
+        ));
+    }
+
+    let max_magnitude = max_magnitude.unwrap_or_else(|| {
+        flow.as_slice()
+            .chunks_exact(2)
+            .map(|uv| uv[0].hypot(uv[1]))
+            .filter(|m| m.is_finite())
+            .fold(0.0, f32::max)
+    });
+    let max_magnitude = max_magnitude.max(f32::EPSILON);
+
+    let wheel = flow_color_wheel();
+    let num_colors = wheel.len();
+
+    for (uv, rgb) in flow
+        .as_slice()
+        .chunks_exact(2)
+        .zip(dst.as_slice_mut().chunks_exact_mut(3))
+    {
+        let (u, v) = (uv[0], uv[1]);
+        if !u.is_finite() || !v.is_finite() {
+            rgb.fill(0);
+            continue;
+        }
+
+        let radius = u.hypot(v) / max_magnitude;
+        let angle = (-v).atan2(-u) / std::f32::consts::PI;
+
+        // interpolate between the two closest colors of the wheel
+        let k = (angle + 1.0) / 2.0 * (num_colors - 1) as f32;
+        let k0 = k.floor() as usize % num_colors;
+        let k1 = (k0 + 1) % num_colors;
+        let f = k - k.floor();
+
+        for (ch, value) in rgb.iter_mut().enumerate() {
+            let color = ((1.0 - f) * wheel[k0][ch] + f * wheel[k1][ch]) / 255.0;
+            let color = if radius <= 1.0 {
+                1.0 - radius * (1.0 - color)
+            } else {
+                color * 0.75
+            };
+            *value = (255.0 * color) as u8;
+        }
+    }
+
+    Ok(())
+}
+
+/// Draws an arrow on an image inplace.
+///
+/// # Arguments
+///
+/// * `img` - The image to draw on.
+/// * `p0` - The tail of the arrow as a tuple of (x, y).
+/// * `p1` - The tip of the arrow as a tuple of (x, y).
+/// * `color` - The color of the arrow as an array of `C` elements.
+/// * `thickness` - The thickness of the lines.
+/// * `tip_length` - The length of the tip relative to the length of the arrow.
+pub fn draw_arrow<const C: usize>(
+    img: &mut Image<u8, C>,
+    p0: (i64, i64),
+    p1: (i64, i64),
+    color: [u8; C],
+    thickness: usize,
+    tip_length: f64,
+) {
+    draw_line(img, p0, p1, color, thickness);
+
+    // the two sides of the tip at 45 degrees from the arrow
+    let (dx, dy) = ((p0.0 - p1.0) as f64, (p0.1 - p1.1) as f64);
+    let tip = dx.hypot(dy) * tip_length;
+    if tip < 1.0 {
+        return;
+    }
+    let angle = dy.atan2(dx);
+    for side in [-1.0, 1.0] {
+        let a = angle + side * std::f64::consts::FRAC_PI_4;
+        let end = (
+            p1.0 + (tip * a.cos()).round() as i64,
+            p1.1 + (tip * a.sin()).round() as i64,
+        );
+        draw_line(img, p1, end, color, thickness);
+    }
+}
+
+/// Draws an optical flow field as arrows on a regular grid.
+///
+/// # Arguments
+///
+/// * `img` - The image to draw on with shape (H, W, C), usually the first frame.
+/// * `flow` - The flow field with shape (H, W, 2) and the (u, v) displacements.
+/// * `step` - The spacing in pixels between the arrows.
+/// * `scale` - The factor to scale the displacements, to make the small motions visible.
+/// * `color` - The color of the arrows as an array of `C` elements.
+///
+/// The vectors shorter than a pixel after the scaling are drawn as points and the
+/// non-finite vectors are skipped.
+pub fn draw_flow_arrows<const C: usize>(
+    img: &mut Image<u8, C>,
+    flow: &Image<f32, 2>,
+    step: usize,
+    scale: f32,
+    color: [u8; C],
+) -> Result<(), ImageError> {
+    if flow.size() != img.size() {
+        return Err(ImageError::InvalidImageSize(
+            flow.cols(),
+            flow.rows(),
+            img.cols(),
+            img.rows(),
+        ));
+    }
+
+    let step = step.max(1);
+    let data = flow.as_slice();
+    for y in (step / 2..flow.rows()).step_by(step) {
+        for x in (step / 2..flow.cols()).step_by(step) {
+            let offset = (y * flow.cols() + x) * 2;
+            let (u, v) = (data[offset] * scale, data[offset + 1] * scale);
+            if !u.is_finite() || !v.is_finite() {
+                continue;
+            }
+
+            let p0 = (x as i64, y as i64);
+            if u.hypot(v) < 1.0 {
+                put_pixel(img, p0.0, p0.1, color);
+            } else {
+                let p1 = ((x as f32 + u).round() as i64, (y as f32 + v).round() as i64);
+                draw_arrow(img, p0, p1, color, 1, 0.3);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Draws the trajectories of tracked points inplace.
+///
+/// Every track is drawn as a polyline through its past positions, with a dot at its
+/// latest position.
+///
+/// # Arguments
+///
+/// * `img` - The image to draw on, usually the latest frame.
+/// * `tracks` - The history of every track as (x, y) positions from the oldest to the
+///   latest.
+/// * `color` - The color of the tracks as an array of `C` elements.
+/// * `thickness` - The thickness of the lines.
+pub fn draw_tracks<const C: usize>(
+    img: &mut Image<u8, C>,
+    tracks: &[Vec<[f32; 2]>],
+    color: [u8; C],
+    thickness: usize,
+) {
+    let to_pixel = |p: &[f32; 2]| (p[0].round() as i64, p[1].round() as i64);
+    for track in tracks {
+        for segment in track.windows(2) {
+            draw_line(
+                img,
+                to_pixel(&segment[0]),
+                to_pixel(&segment[1]),
+                color,
+                thickness,
+            );
+        }
+        if let Some(latest) = track.last() {
+            draw_filled_circle(img, to_pixel(latest), thickness.max(1) + 1, color);
+        }
+    }
+}
+
+/// Renders the legend of the Middlebury color coding of [`draw_flow`].
+///
+/// The color of every pixel is the color of the flow vector from the center of the image
+/// to the pixel, with the full saturation on the largest circle inside the image. The
+/// pixels outside of the circle are black.
+///
+/// # Arguments
+///
+/// * `dst` - The destination RGB image with shape (H, W, 3).
+pub fn draw_flow_color_wheel(dst: &mut Image<u8, 3>) -> Result<(), ImageError> {
+    let (cols, rows) = (dst.cols(), dst.rows());
+    let (cx, cy) = ((cols as f32 - 1.0) / 2.0, (rows as f32 - 1.0) / 2.0);
+    let radius = cx.min(cy).max(f32::EPSILON);
+
+    let mut flow = Vec::with_capacity(cols * rows * 2);
+    for y in 0..rows {
+        for x in 0..cols {
+            let (u, v) = (x as f32 - cx, y as f32 - cy);
+            if u.hypot(v) <= radius {
+                flow.extend([u, v]);
+            } else {
+                flow.extend([f32::NAN; 2]);
+            }
+        }
+    }
+
+    draw_flow(&Image::new(dst.size(), flow)?, dst, Some(radius))
+}
+
 #[cfg(test)]
 mod tests {
-    use super::draw_line;
-    use kornia_image::{Image, ImageError, ImageSize};
+    use super::*;
 
     #[rustfmt::skip]
     #[test]
@@ -98,4 +717,192 @@ mod tests {
         );
         Ok(())
     }
+
+    #[rustfmt::skip]
+    #[test]
+    fn test_draw_rectangle() -> Result<(), ImageError> {
+        let mut img = Image::<u8, 1>::from_size_val([5, 4].into(), 0)?;
+        draw_rectangle(&mut img, (1, 0), (3, 2), [1], 1);
+        assert_eq!(
+            img.as_slice(),
+            vec![
+                0, 1, 1, 1, 0,
+                0, 1, 0, 1, 0,
+                0, 1, 1, 1, 0,
+                0, 0, 0, 0, 0,
+            ]
+        );
+
+        // the rectangle is clipped to the image
+        let mut img = Image::<u8, 1>::from_size_val([3, 3].into(), 0)?;
+        draw_filled_rectangle(&mut img, (-2, 1), (10, 1), [7]);
+        assert_eq!(img.as_slice(), vec![0, 0, 0, 7, 7, 7, 0, 0, 0]);
+        Ok(())
+    }
+
+    #[rustfmt::skip]
+    #[test]
+    fn test_draw_circle() -> Result<(), ImageError> {
+        let mut img = Image::<u8, 1>::from_size_val([5, 5].into(), 0)?;
+        draw_filled_circle(&mut img, (2, 2), 1, [1]);
+        assert_eq!(
+            img.as_slice(),
+            vec![
+                0, 0, 0, 0, 0,
+                0, 0, 1, 0, 0,
+                0, 1, 1, 1, 0,
+                0, 0, 1, 0, 0,
+                0, 0, 0, 0, 0,
+            ]
+        );
+
+        let mut img = Image::<u8, 1>::from_size_val([7, 7].into(), 0)?;
+        draw_circle(&mut img, (3, 3), 2, [1], 1);
+        // the center is empty and the points at the radius are set
+        assert_eq!(img.as_slice()[3 * 7 + 3], 0);
+        for (x, y) in [(1, 3), (5, 3), (3, 1), (3, 5)] {
+            assert_eq!(img.as_slice()[y * 7 + x], 1);
+        }
+        Ok(())
+    }
+
+    #[rustfmt::skip]
+    #[test]
+    fn test_draw_cross() -> Result<(), ImageError> {
+        let mut img = Image::<u8, 1>::from_size_val([3, 3].into(), 0)?;
+        draw_cross(&mut img, (1, 1), 1, [1], 1);
+        assert_eq!(img.as_slice(), vec![0, 1, 0, 1, 1, 1, 0, 1, 0]);
+        Ok(())
+    }
+
+    #[rustfmt::skip]
+    #[test]
+    fn test_draw_text() -> Result<(), ImageError> {
+        let mut img = Image::<u8, 1>::from_size_val([6, 8].into(), 0)?;
+        draw_text(&mut img, (0, 0), "T", [1], 1);
+        assert_eq!(
+            img.as_slice(),
+            vec![
+                1, 1, 1, 1, 1, 0,
+                0, 0, 1, 0, 0, 0,
+                0, 0, 1, 0, 0, 0,
+                0, 0, 1, 0, 0, 0,
+                0, 0, 1, 0, 0, 0,
+                0, 0, 1, 0, 0, 0,
+                0, 0, 1, 0, 0, 0,
+                0, 0, 0, 0, 0, 0,
+            ]
+        );
+
+        // the scale doubles the size of the glyphs
+        let mut img = Image::<u8, 1>::from_size_val([12, 16].into(), 0)?;
+        draw_text(&mut img, (0, 0), "T", [1], 2);
+        assert_eq!(img.as_slice().iter().filter(|&&v| v == 1).count(), 11 * 4);
+        Ok(())
+    }
+
+    #[test]
+    fn test_draw_matches() -> Result<(), ImageError> {
+        let img1 = Image::<u8, 3>::from_size_val([10, 8].into(), 10)?;
+        let img2 = Image::<u8, 3>::from_size_val([6, 12].into(), 20)?;
+
+        let dst = draw_matches(
+            &img1,
+            &[[1.0, 1.0]],
+            &img2,
+            &[[4.0, 1.0], [0.0, 0.0]],
+            &[(0, 0), (5, 1)],
+            [255, 0, 0],
+        )?;
+        assert_eq!(dst.cols(), 16);
+        assert_eq!(dst.rows(), 12);
+
+        // the images are copied side by side and the area below the first is empty
+        assert_eq!(dst.get_pixel(9, 7, 0)?, &10);
+        assert_eq!(dst.get_pixel(10, 11, 0)?, &20);
+        assert_eq!(dst.get_pixel(0, 11, 0)?, &0);
+
+        // the match line is drawn between the keypoints
+        assert_eq!(dst.get_pixel(8, 1, 0)?, &255);
+        assert_eq!(dst.get_pixel(8, 1, 1)?, &0);
+        Ok(())
+    }
+
+    #[test]
+    fn test_draw_flow() -> Result<(), ImageError> {
+        let flow = Image::<f32, 2>::new(
+            [4, 1].into(),
+            vec![0.0, 0.0, -2.0, 0.0, 4.0, 0.0, f32::NAN, 0.0],
+        )?;
+        let mut dst = Image::<u8, 3>::from_size_val(flow.size(), 0)?;
+        draw_flow(&flow, &mut dst, Some(2.0))?;
+
+        // no motion is white, motion to the left is cyan and longer motions are darkened
+        assert_eq!(&dst.as_slice()[0..3], &[255, 255, 255]);
+        assert_eq!(&dst.as_slice()[3..6], &[0, 209, 255]);
+        assert!(dst.as_slice()[6..9].iter().all(|&v| v < 255));
+        assert_eq!(&dst.as_slice()[9..12], &[0, 0, 0]);
+
+        let mut small = Image::<u8, 3>::from_size_val([1, 1].into(), 0)?;
+        assert!(draw_flow(&flow, &mut small, None).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_draw_arrow() -> Result<(), ImageError> {
+        let mut img = Image::<u8, 1>::from_size_val([12, 9].into(), 0)?;
+        draw_arrow(&mut img, (1, 4), (10, 4), [1], 1, 0.3);
+
+        // the shaft and the two sides of the tip behind it
+        assert!((1..=10).all(|x| img.as_slice()[4 * 12 + x] == 1));
+        assert_eq!(img.as_slice()[2 * 12 + 8], 1);
+        assert_eq!(img.as_slice()[6 * 12 + 8], 1);
+        assert_eq!(img.as_slice()[2 * 12 + 3], 0);
+        Ok(())
+    }
+
+    #[test]
+    fn test_draw_flow_arrows() -> Result<(), ImageError> {
+        // a uniform motion to the right
+        let flow = Image::<f32, 2>::new([20, 10].into(), [3.0, 0.0].repeat(200))?;
+        let mut img = Image::<u8, 1>::from_size_val(flow.size(), 0)?;
+        draw_flow_arrows(&mut img, &flow, 10, 2.0, [1])?;
+
+        // the arrows start at the centers of the cells
+        for x0 in [5, 15] {
+            assert!((x0..=(x0 + 6).min(19)).all(|x| img.as_slice()[5 * 20 + x] == 1));
+        }
+        assert_eq!(img.as_slice()[5 * 20 + 4], 0);
+
+        let mut small = Image::<u8, 1>::from_size_val([1, 1].into(), 0)?;
+        assert!(draw_flow_arrows(&mut small, &flow, 10, 1.0, [1]).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_draw_tracks() -> Result<(), ImageError> {
+        let mut img = Image::<u8, 1>::from_size_val([10, 10].into(), 0)?;
+        let tracks = vec![vec![[1.0, 1.0], [4.0, 1.0], [4.0, 5.2]], vec![]];
+        draw_tracks(&mut img, &tracks, [1], 1);
+
+        assert!((1..=4).all(|x| img.as_slice()[10 + x] == 1));
+        assert!((1..=5).all(|y| img.as_slice()[y * 10 + 4] == 1));
+        // the dot at the latest position
+        assert_eq!(img.as_slice()[5 * 10 + 6], 1);
+        assert_eq!(img.as_slice()[5 * 10 + 8], 0);
+        Ok(())
+    }
+
+    #[test]
+    fn test_draw_flow_color_wheel() -> Result<(), ImageError> {
+        let mut wheel = Image::<u8, 3>::from_size_val([21, 21].into(), 0)?;
+        draw_flow_color_wheel(&mut wheel)?;
+
+        let pixel = |x: usize, y: usize| &wheel.as_slice()[(y * 21 + x) * 3..(y * 21 + x) * 3 + 3];
+        // the center is white, the corners are outside and the left is cyan
+        assert_eq!(pixel(10, 10), &[255, 255, 255]);
+        assert_eq!(pixel(0, 0), &[0, 0, 0]);
+        assert_eq!(pixel(0, 10), &[0, 209, 255]);
+        Ok(())
+    }
 }
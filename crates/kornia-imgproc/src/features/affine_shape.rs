@@ -0,0 +1,287 @@
+use kornia_image::{Image, ImageError, ImageSize};
+
+use super::eigenvalues_2x2;
+use crate::filter::{kernels::GradsMode, spatial_gradient_float};
+use crate::interpolation::{interpolate_pixel, InterpolationMode};
+
+/// The parameters of the affine shape adaptation.
+#[derive(Debug, Clone)]
+pub struct AffineAdaptationParams {
+    /// The width and height of the sampled patches in pixels.
+    pub patch_size: usize,
+    /// The radius of the patch in units of the scale of the keypoint.
+    pub radius_factor: f32,
+    /// The maximum number of iterations.
+    pub max_iterations: usize,
+    /// The minimum ratio of the eigenvalues of the second moment matrix of a converged shape.
+    pub convergence_ratio: f32,
+    /// The maximum ratio of the axes of the ellipse of a shape.
+    pub max_anisotropy: f32,
+}
+
+impl Default for AffineAdaptationParams {
+    fn default() -> Self {
+        Self {
+            patch_size: 33,
+            radius_factor: 3.0,
+            max_iterations: 16,
+            convergence_ratio: 0.95,
+            max_anisotropy: 6.0,
+        }
+    }
+}
+
+/// The affine frame of a keypoint.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AffineFrame {
+    /// The center `[x, y]` of the frame in pixels.
+    pub center: [f32; 2],
+    /// The 2x2 row-major matrix mapping the unit circle of the normalized patch to the
+    /// ellipse of the keypoint in the image.
+    pub shape: [[f32; 2]; 2],
+}
+
+impl AffineFrame {
+    /// Maps a point of the normalized patch, in [-1, 1], to the image.
+    pub fn to_image(&self, point: [f32; 2]) -> [f32; 2] {
+        let [[a, b], [c, d]] = self.shape;
+        [
+            self.center[0] + a * point[0] + b * point[1],
+            self.center[1] + c * point[0] + d * point[1],
+        ]
+    }
+}
+
+/// Sample the affine normalized patch of a frame.
+///
+/// The pixels of the patch cover the square [-1, 1] x [-1, 1] of the normalized frame. The
+/// pixels sampled outside the image are zero.
+///
+/// # Arguments
+///
+/// * `src` - The source image with shape (H, W).
+/// * `frame` - The affine frame of the keypoint.
+/// * `dst` - The patch with shape (P, P).
+pub fn extract_affine_patch(
+    src: &Image<f32, 1>,
+    frame: &AffineFrame,
+    dst: &mut Image<f32, 1>,
+) -> Result<(), ImageError> {
+    if dst.cols() != dst.rows() {
+        return Err(ImageError::InvalidImageSize(
+            dst.cols(),
+            dst.rows(),
+            dst.rows(),
+            dst.rows(),
+        ));
+    }
+
+    let patch_size = dst.cols();
+    sample_frame(src, frame, patch_size, dst.as_slice_mut());
+
+    Ok(())
+}
+
+// sample the square patch of a frame into a row-major buffer with bilinear interpolation
+pub(crate) fn sample_frame(
+    src: &Image<f32, 1>,
+    frame: &AffineFrame,
+    patch_size: usize,
+    dst: &mut [f32],
+) {
+    let half = (patch_size.max(2) - 1) as f32 / 2.0;
+    let (cols, rows) = (src.cols() as f32, src.rows() as f32);
+
+    for (i, pixel) in dst.iter_mut().enumerate() {
+        let (px, py) = ((i % patch_size) as f32, (i / patch_size) as f32);
+        let [x, y] = frame.to_image([(px - half) / half, (py - half) / half]);
+        *pixel = if x >= 0.0 && x < cols && y >= 0.0 && y < rows {
+            interpolate_pixel(src, x, y, 0, InterpolationMode::Bilinear)
+        } else {
+            0.0
+        };
+    }
+}
+
+/// Estimate the affine shape of a keypoint with the Baumberg iteration.
+///
+/// The patch of the keypoint is sampled from the current frame and the frame is warped by the
+/// inverse square root of the second moment matrix of the patch, until the matrix is isotropic.
+/// The area of the frame is preserved, so the sampled patches are affine normalized and can be
+/// described as in the wide baseline matching of Mikolajczyk and Schmid.
+///
+/// # Arguments
+///
+/// * `src` - The source image with shape (H, W).
+/// * `center` - The position `[x, y]` of the keypoint in pixels.
+/// * `scale` - The scale of the keypoint in pixels.
+/// * `params` - The parameters of the adaptation.
+///
+/// # Returns
+///
+/// The affine frame, or `None` if the shape did not converge, is too anisotropic or the patch
+/// is flat.
+pub fn affine_shape_adaptation(
+    src: &Image<f32, 1>,
+    center: [f32; 2],
+    scale: f32,
+    params: &AffineAdaptationParams,
+) -> Result<Option<AffineFrame>, ImageError> {
+    let size = ImageSize {
+        width: params.patch_size,
+        height: params.patch_size,
+    };
+    let mut patch = Image::from_size_val(size, 0.0)?;
+    let mut dx = Image::from_size_val(size, 0.0)?;
+    let mut dy = Image::from_size_val(size, 0.0)?;
+
+    // the gaussian window of the second moment matrix, isotropic in the normalized frame
+    let half = (params.patch_size.max(2) - 1) as f32 / 2.0;
+    let window = (0..params.patch_size * params.patch_size)
+        .map(|i| {
+            let x = ((i % params.patch_size) as f32 - half) / half;
+            let y = ((i / params.patch_size) as f32 - half) / half;
+            (-(x * x + y * y) / (2.0 * 0.5 * 0.5)).exp()
+        })
+        .collect::<Vec<_>>();
+
+    let radius = params.radius_factor * scale;
+    let mut frame = AffineFrame {
+        center,
+        shape: [[radius, 0.0], [0.0, radius]],
+    };
+
+    for _ in 0..params.max_iterations {
+        extract_affine_patch(src, &frame, &mut patch)?;
+        spatial_gradient_float(&patch, &mut dx, &mut dy, GradsMode::Sobel)?;
+
+        let mut m = [0.0f32; 3];
+        for ((w, &gx), &gy) in window.iter().zip(dx.as_slice()).zip(dy.as_slice()) {
+            m[0] += w * gx * gx;
+            m[1] += w * gy * gy;
+            m[2] += w * gx * gy;
+        }
+
+        let (e1, e2) = eigenvalues_2x2(m[0], m[1], m[2]);
+        if e2 <= f32::EPSILON * e1.max(1.0) {
+            return Ok(None);
+        }
+        if e2 / e1 >= params.convergence_ratio {
+            return Ok(Some(frame));
+        }
+
+        // warp the frame by the inverse square root of the matrix with a unit determinant
+        let angle = 0.5 * (2.0 * m[2]).atan2(m[0] - m[1]);
+        let (sin, cos) = angle.sin_cos();
+        let norm = (e1 * e2).sqrt().sqrt();
+        let (s1, s2) = (norm / e1.sqrt(), norm / e2.sqrt());
+        let update = [
+            [s1 * cos * cos + s2 * sin * sin, (s1 - s2) * cos * sin],
+            [(s1 - s2) * cos * sin, s1 * sin * sin + s2 * cos * cos],
+        ];
+        frame.shape = matmul2(&frame.shape, &update);
+
+        // keep the area of the frame and reject the degenerate ellipses
+        let det = frame.shape[0][0] * frame.shape[1][1] - frame.shape[0][1] * frame.shape[1][0];
+        let area_scale = radius / det.abs().sqrt();
+        frame.shape = frame.shape.map(|row| row.map(|v| v * area_scale));
+        let [[a, b], [c, d]] = frame.shape;
+        let (l1, l2) = eigenvalues_2x2(a * a + c * c, b * b + d * d, a * b + c * d);
+        if l2 <= 0.0 || (l1 / l2).sqrt() > params.max_anisotropy {
+            return Ok(None);
+        }
+    }
+
+    Ok(None)
+}
+
+fn matmul2(a: &[[f32; 2]; 2], b: &[[f32; 2]; 2]) -> [[f32; 2]; 2] {
+    [
+        [
+            a[0][0] * b[0][0] + a[0][1] * b[1][0],
+            a[0][0] * b[0][1] + a[0][1] * b[1][1],
+        ],
+        [
+            a[1][0] * b[0][0] + a[1][1] * b[1][0],
+            a[1][0] * b[0][1] + a[1][1] * b[1][1],
+        ],
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // an elliptic gaussian blob with the axes `sigma_u` and `sigma_v`, the first rotated by
+    // `angle` from the x axis, centered in a 64x64 image
+    fn elliptic_blob(sigma_u: f32, sigma_v: f32, angle: f32) -> Result<Image<f32, 1>, ImageError> {
+        let (sin, cos) = angle.sin_cos();
+        let data = (0..64 * 64)
+            .map(|i| {
+                let (x, y) = ((i % 64) as f32 - 32.0, (i / 64) as f32 - 32.0);
+                let (u, v) = (cos * x + sin * y, -sin * x + cos * y);
+                (-0.5 * (u * u / (sigma_u * sigma_u) + v * v / (sigma_v * sigma_v))).exp()
+            })
+            .collect();
+        Image::new([64, 64].into(), data)
+    }
+
+    // the ratio of the axes of the ellipse of a frame and the direction of its major axis
+    fn ellipse_axes(frame: &AffineFrame) -> (f32, f32) {
+        let [[a, b], [c, d]] = frame.shape;
+        // the ellipse is the image of the unit circle, i.e. the matrix shape * shape^T
+        let (sxx, syy, sxy) = (a * a + b * b, c * c + d * d, a * c + b * d);
+        let (l1, l2) = eigenvalues_2x2(sxx, syy, sxy);
+        ((l1 / l2).sqrt(), 0.5 * (2.0 * sxy).atan2(sxx - syy))
+    }
+
+    #[test]
+    fn test_affine_shape_adaptation_isotropic() -> Result<(), ImageError> {
+        let src = elliptic_blob(4.0, 4.0, 0.0)?;
+        let params = AffineAdaptationParams::default();
+
+        let frame = affine_shape_adaptation(&src, [32.0, 32.0], 2.0, &params)?;
+        let frame = frame.expect("the shape converges");
+        let (ratio, _) = ellipse_axes(&frame);
+        assert!(ratio < 1.05, "{ratio}");
+        let det = frame.shape[0][0] * frame.shape[1][1] - frame.shape[0][1] * frame.shape[1][0];
+        assert!((det - 36.0).abs() < 1e-3, "{det}");
+
+        // a flat image has no shape
+        let flat = Image::from_size_val([64, 64].into(), 0.5)?;
+        assert_eq!(
+            affine_shape_adaptation(&flat, [32.0, 32.0], 2.0, &params)?,
+            None
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_affine_shape_adaptation_elliptic() -> Result<(), ImageError> {
+        let angle = 30f32.to_radians();
+        let src = elliptic_blob(6.0, 3.0, angle)?;
+        let params = AffineAdaptationParams::default();
+
+        let frame = affine_shape_adaptation(&src, [32.0, 32.0], 2.0, &params)?;
+        let frame = frame.expect("the shape converges");
+
+        // the frame follows the axes of the blob
+        let (ratio, major_angle) = ellipse_axes(&frame);
+        assert!((ratio - 2.0).abs() < 0.2, "{ratio}");
+        assert!(
+            (major_angle - angle).abs() < 3f32.to_radians(),
+            "{major_angle}"
+        );
+
+        // the normalized patch is isotropic
+        let mut patch = Image::from_size_val([33, 33].into(), 0.0)?;
+        extract_affine_patch(&src, &frame, &mut patch)?;
+        let at = |x: usize, y: usize| patch.as_slice()[y * 33 + x];
+        assert!((at(16, 16) - 1.0).abs() < 1e-3);
+        assert!((at(26, 16) - at(16, 26)).abs() < 0.05);
+        assert!((at(26, 16) - at(6, 16)).abs() < 0.05);
+
+        Ok(())
+    }
+}
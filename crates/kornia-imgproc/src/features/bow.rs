@@ -0,0 +1,602 @@
+use std::collections::BTreeMap;
+
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+/// A bag of words vector, with the weight of every word present in an image.
+///
+/// The weights are the TF-IDF weights normalized to a unit L1 norm.
+pub type BowVector = BTreeMap<usize, f32>;
+
+/// The hamming distance between two binary descriptors.
+///
+/// # Example
+///
+/// ```
+/// use kornia_imgproc::features::descriptor_distance;
+///
+/// assert_eq!(descriptor_distance(&[0b1011, 0xff], &[0b0001, 0x0f]), 6);
+/// ```
+pub fn descriptor_distance<const N: usize>(a: &[u8; N], b: &[u8; N]) -> u32 {
+    a.iter().zip(b).map(|(a, b)| (a ^ b).count_ones()).sum()
+}
+
+/// The parameters to train a vocabulary.
+#[derive(Debug, Clone)]
+pub struct VocabularyParams {
+    /// The number of children of every node of the tree.
+    pub branching: usize,
+    /// The number of levels of the tree, with at most `branching ^ depth` words.
+    pub depth: usize,
+    /// The maximum number of k-majority iterations per node.
+    pub max_iterations: usize,
+    /// The seed of the random number generator of the seeding of [`Vocabulary::train`],
+    /// ignored by [`Vocabulary::train_with_rng`].
+    pub seed: u64,
+}
+
+impl Default for VocabularyParams {
+    fn default() -> Self {
+        Self {
+            branching: 10,
+            depth: 4,
+            max_iterations: 10,
+            seed: 0,
+        }
+    }
+}
+
+// a node of the vocabulary tree
+#[derive(Debug, Clone)]
+struct Node<const N: usize> {
+    descriptor: [u8; N],
+    children: Vec<usize>,
+    word: Option<usize>,
+}
+
+/// A vocabulary tree of binary descriptors.
+///
+/// The vocabulary quantizes the descriptors into visual words by descending a tree built
+/// with hierarchical k-majority clustering, where the centers are the bitwise majority of
+/// their descriptors. The words are weighted by their inverse document frequency in the
+/// training images.
+#[derive(Debug, Clone)]
+pub struct Vocabulary<const N: usize> {
+    nodes: Vec<Node<N>>,
+    idf: Vec<f32>,
+}
+
+impl<const N: usize> Vocabulary<N> {
+    /// Train a vocabulary from the descriptors of a set of images.
+    ///
+    /// # Arguments
+    ///
+    /// * `images` - The descriptors of every training image.
+    /// * `params` - The parameters of the training.
+    ///
+    /// # Returns
+    ///
+    /// The vocabulary, with a single word if there are no descriptors.
+    pub fn train(images: &[Vec<[u8; N]>], params: &VocabularyParams) -> Self {
+        Self::train_with_rng(images, params, &mut StdRng::seed_from_u64(params.seed))
+    }
+
+    /// Train a vocabulary with a given random number generator.
+    ///
+    /// The training is the same as [`Vocabulary::train`], with the seeding drawn from `rng`
+    /// instead of a generator seeded with `params.seed`.
+    ///
+    /// # Arguments
+    ///
+    /// * `images` - The descriptors of every training image.
+    /// * `params` - The parameters of the training.
+    /// * `rng` - The random number generator.
+    pub fn train_with_rng<R: Rng + ?Sized>(
+        images: &[Vec<[u8; N]>],
+        params: &VocabularyParams,
+        rng: &mut R,
+    ) -> Self {
+        let descriptors = images.iter().flatten().copied().collect::<Vec<_>>();
+
+        let mut vocabulary = Self {
+            nodes: vec![Node {
+                descriptor: [0; N],
+                children: Vec::new(),
+                word: None,
+            }],
+            idf: Vec::new(),
+        };
+        let all = (0..descriptors.len()).collect::<Vec<_>>();
+        vocabulary.grow(0, &descriptors, all, 0, params, rng);
+
+        // the leaves are the words
+        let mut num_words = 0;
+        for node in vocabulary.nodes.iter_mut() {
+            if node.children.is_empty() {
+                node.word = Some(num_words);
+                num_words += 1;
+            }
+        }
+
+        // the inverse document frequency of the words in the training images
+        let mut frequency = vec![0usize; num_words];
+        for image in images {
+            let mut words = image.iter().map(|d| vocabulary.word(d)).collect::<Vec<_>>();
+            words.sort_unstable();
+            words.dedup();
+            words.iter().for_each(|&w| frequency[w] += 1);
+        }
+        vocabulary.idf = frequency
+            .iter()
+            .map(|&f| {
+                if f == 0 {
+                    0.0
+                } else {
+                    (images.len() as f32 / f as f32).ln()
+                }
+            })
+            .collect();
+
+        vocabulary
+    }
+
+    // split the descriptors of a node into its children
+    fn grow<R: Rng + ?Sized>(
+        &mut self,
+        node: usize,
+        descriptors: &[[u8; N]],
+        members: Vec<usize>,
+        level: usize,
+        params: &VocabularyParams,
+        rng: &mut R,
+    ) {
+        if level >= params.depth || members.len() <= 1 || params.branching < 2 {
+            return;
+        }
+
+        let clusters = if members.len() <= params.branching {
+            // every descriptor is a cluster
+            members.iter().map(|&m| (descriptors[m], vec![m])).collect()
+        } else {
+            k_majority(descriptors, &members, params, rng)
+        };
+
+        for (center, cluster) in clusters {
+            let child = self.nodes.len();
+            self.nodes.push(Node {
+                descriptor: center,
+                children: Vec::new(),
+                word: None,
+            });
+            self.nodes[node].children.push(child);
+            self.grow(child, descriptors, cluster, level + 1, params, rng);
+        }
+    }
+
+    /// The number of words of the vocabulary.
+    pub fn num_words(&self) -> usize {
+        self.idf.len()
+    }
+
+    /// The word of a descriptor.
+    pub fn word(&self, descriptor: &[u8; N]) -> usize {
+        let mut node = &self.nodes[0];
+        while let Some(&child) = node
+            .children
+            .iter()
+            .min_by_key(|&&child| descriptor_distance(&self.nodes[child].descriptor, descriptor))
+        {
+            node = &self.nodes[child];
+        }
+        node.word.unwrap_or(0)
+    }
+
+    /// Convert the descriptors of an image to a bag of words vector.
+    ///
+    /// # Arguments
+    ///
+    /// * `descriptors` - The descriptors of the image.
+    ///
+    /// # Returns
+    ///
+    /// The TF-IDF weights of the words normalized to a unit L1 norm.
+    pub fn transform(&self, descriptors: &[[u8; N]]) -> BowVector {
+        let mut bow = BowVector::new();
+        for descriptor in descriptors {
+            let word = self.word(descriptor);
+            *bow.entry(word).or_insert(0.0) += self.idf[word];
+        }
+        bow.retain(|_, weight| *weight > 0.0);
+
+        let norm = bow.values().sum::<f32>();
+        bow.values_mut().for_each(|weight| *weight /= norm);
+        bow
+    }
+}
+
+/// The similarity of two bag of words vectors, between 0 and 1.
+///
+/// The score is `1 - |a - b| / 2` with the L1 norm, which is 1 for the same words.
+pub fn bow_score(a: &BowVector, b: &BowVector) -> f32 {
+    let shared = a
+        .iter()
+        .filter_map(|(word, &va)| b.get(word).map(|&vb| va.abs() + vb.abs() - (va - vb).abs()))
+        .sum::<f32>();
+    shared / 2.0
+}
+
+// cluster the descriptors with k-majority, seeded with k-means++
+fn k_majority<const N: usize, R: Rng + ?Sized>(
+    descriptors: &[[u8; N]],
+    members: &[usize],
+    params: &VocabularyParams,
+    rng: &mut R,
+) -> Vec<([u8; N], Vec<usize>)> {
+    let k = params.branching;
+    let mut centers = vec![descriptors[members[rng.random_range(0..members.len())]]];
+    let mut distances = members
+        .iter()
+        .map(|&m| descriptor_distance(&descriptors[m], &centers[0]) as f64)
+        .collect::<Vec<_>>();
+    while centers.len() < k {
+        let total = distances.iter().map(|d| d * d).sum::<f64>();
+        if total == 0.0 {
+            // all the descriptors are already centers
+            break;
+        }
+        let mut target = rng.random::<f64>() * total;
+        let next = distances
+            .iter()
+            .position(|&d| {
+                target -= d * d;
+                target < 0.0
+            })
+            .unwrap_or(members.len() - 1);
+
+        let center = descriptors[members[next]];
+        for (d, &m) in distances.iter_mut().zip(members) {
+            *d = d.min(descriptor_distance(&descriptors[m], &center) as f64);
+        }
+        centers.push(center);
+    }
+
+    let mut labels = vec![usize::MAX; members.len()];
+    for _ in 0..params.max_iterations.max(1) {
+        let mut changed = false;
+        for (label, &m) in labels.iter_mut().zip(members) {
+            let closest = (0..centers.len())
+                .min_by_key(|&c| descriptor_distance(&centers[c], &descriptors[m]))
+                .unwrap_or(0);
+            changed |= *label != closest;
+            *label = closest;
+        }
+        if !changed {
+            break;
+        }
+
+        // the bitwise majority of the descriptors of every cluster
+        let mut bits = vec![vec![0usize; 8 * N]; centers.len()];
+        let mut counts = vec![0usize; centers.len()];
+        for (&label, &m) in labels.iter().zip(members) {
+            counts[label] += 1;
+            for (bit, count) in bits[label].iter_mut().enumerate() {
+                *count += ((descriptors[m][bit / 8] >> (bit % 8)) & 1) as usize;
+            }
+        }
+        for ((center, bits), &count) in centers.iter_mut().zip(&bits).zip(&counts) {
+            // the empty clusters keep their center
+            if count == 0 {
+                continue;
+            }
+            *center = [0; N];
+            for (bit, &ones) in bits.iter().enumerate() {
+                if 2 * ones > count {
+                    center[bit / 8] |= 1 << (bit % 8);
+                }
+            }
+        }
+    }
+
+    let mut clusters = centers
+        .into_iter()
+        .map(|center| (center, Vec::new()))
+        .collect::<Vec<_>>();
+    for (&label, &m) in labels.iter().zip(members) {
+        clusters[label].1.push(m);
+    }
+    clusters.retain(|(_, cluster)| !cluster.is_empty());
+    clusters
+}
+
+/// A result of a query to a [`BowDatabase`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BowMatch {
+    /// The index of the entry of the database.
+    pub entry: usize,
+    /// The similarity with the query, between 0 and 1.
+    pub score: f32,
+}
+
+/// A database of images described by bag of words vectors.
+///
+/// The entries are indexed by their words in an inverted index, so a query only visits
+/// the entries which share a word with it.
+#[derive(Debug, Clone)]
+pub struct BowDatabase<const N: usize> {
+    vocabulary: Vocabulary<N>,
+    entries: Vec<BowVector>,
+    inverted_index: Vec<Vec<(usize, f32)>>,
+}
+
+impl<const N: usize> BowDatabase<N> {
+    /// Create an empty database with a vocabulary.
+    pub fn new(vocabulary: Vocabulary<N>) -> Self {
+        let inverted_index = vec![Vec::new(); vocabulary.num_words()];
+        Self {
+            vocabulary,
+            entries: Vec::new(),
+            inverted_index,
+        }
+    }
+
+    /// The vocabulary of the database.
+    pub fn vocabulary(&self) -> &Vocabulary<N> {
+        &self.vocabulary
+    }
+
+    /// The number of entries of the database.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether the database has no entries.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Add the descriptors of an image to the database.
+    ///
+    /// # Returns
+    ///
+    /// The index of the new entry.
+    pub fn add(&mut self, descriptors: &[[u8; N]]) -> usize {
+        let bow = self.vocabulary.transform(descriptors);
+        self.add_bow(bow)
+    }
+
+    /// Add a bag of words vector to the database.
+    ///
+    /// # Returns
+    ///
+    /// The index of the new entry.
+    pub fn add_bow(&mut self, bow: BowVector) -> usize {
+        let entry = self.entries.len();
+        for (&word, &weight) in &bow {
+            self.inverted_index[word].push((entry, weight));
+        }
+        self.entries.push(bow);
+        entry
+    }
+
+    /// Find the entries most similar to a bag of words vector.
+    ///
+    /// # Arguments
+    ///
+    /// * `bow` - The bag of words vector of the query.
+    /// * `max_results` - The maximum number of results.
+    ///
+    /// # Returns
+    ///
+    /// The entries which share a word with the query sorted by decreasing score.
+    pub fn query(&self, bow: &BowVector, max_results: usize) -> Vec<BowMatch> {
+        // accumulate the L1 score of the shared words
+        let mut scores = BTreeMap::<usize, f32>::new();
+        for (&word, &query_weight) in bow {
+            for &(entry, weight) in &self.inverted_index[word] {
+                *scores.entry(entry).or_insert(0.0) +=
+                    query_weight + weight - (query_weight - weight).abs();
+            }
+        }
+
+        let mut matches = scores
+            .into_iter()
+            .map(|(entry, score)| BowMatch {
+                entry,
+                score: score / 2.0,
+            })
+            .collect::<Vec<_>>();
+        matches.sort_by(|a, b| b.score.total_cmp(&a.score));
+        matches.truncate(max_results);
+        matches
+    }
+}
+
+/// The parameters of the loop closure detection.
+#[derive(Debug, Clone)]
+pub struct LoopDetectorParams {
+    /// The minimum score of a loop closure candidate.
+    pub min_score: f32,
+    /// The number of most recent entries which are not candidates.
+    pub exclude_recent: usize,
+    /// The number of consecutive queries with consistent candidates to accept a loop.
+    pub temporal_consistency: usize,
+    /// The maximum distance between the entries of consistent candidates.
+    pub max_entry_distance: usize,
+}
+
+impl Default for LoopDetectorParams {
+    fn default() -> Self {
+        Self {
+            min_score: 0.1,
+            exclude_recent: 20,
+            temporal_consistency: 3,
+            max_entry_distance: 3,
+        }
+    }
+}
+
+/// A loop closure detector over a sequence of images.
+///
+/// Every image is queried against the previous images, skipping the most recent ones, and
+/// added to the database. A loop is reported when the best candidates of several
+/// consecutive images are close to each other in the sequence, which rejects the
+/// accidental matches of a single image.
+#[derive(Debug, Clone)]
+pub struct LoopDetector<const N: usize> {
+    database: BowDatabase<N>,
+    params: LoopDetectorParams,
+    // the last candidate with the number of consecutive consistent candidates
+    previous: Option<(usize, usize)>,
+}
+
+impl<const N: usize> LoopDetector<N> {
+    /// Create a loop detector with a vocabulary.
+    pub fn new(vocabulary: Vocabulary<N>, params: LoopDetectorParams) -> Self {
+        Self {
+            database: BowDatabase::new(vocabulary),
+            params,
+            previous: None,
+        }
+    }
+
+    /// The database of the images of the sequence.
+    pub fn database(&self) -> &BowDatabase<N> {
+        &self.database
+    }
+
+    /// Process the next image of the sequence.
+    ///
+    /// # Arguments
+    ///
+    /// * `descriptors` - The descriptors of the image.
+    ///
+    /// # Returns
+    ///
+    /// The earlier image which closes a loop with this one, if any.
+    pub fn detect(&mut self, descriptors: &[[u8; N]]) -> Option<BowMatch> {
+        let bow = self.database.vocabulary().transform(descriptors);
+        let num_candidates = self
+            .database
+            .len()
+            .saturating_sub(self.params.exclude_recent);
+
+        let candidate = self
+            .database
+            .query(&bow, usize::MAX)
+            .into_iter()
+            .find(|m| m.entry < num_candidates && m.score >= self.params.min_score);
+        self.database.add_bow(bow);
+
+        let Some(candidate) = candidate else {
+            self.previous = None;
+            return None;
+        };
+
+        let consistent = match self.previous {
+            Some((entry, count))
+                if entry.abs_diff(candidate.entry) <= self.params.max_entry_distance =>
+            {
+                count + 1
+            }
+            _ => 1,
+        };
+        self.previous = Some((candidate.entry, consistent));
+
+        (consistent >= self.params.temporal_consistency).then_some(candidate)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // the descriptors of the images of a sequence of places, with noisy observations
+    struct Places {
+        rng: StdRng,
+        places: Vec<Vec<[u8; 32]>>,
+    }
+
+    impl Places {
+        fn new(num_places: usize) -> Self {
+            let mut rng = StdRng::seed_from_u64(7);
+            let places = (0..num_places)
+                .map(|_| (0..80).map(|_| rng.random()).collect())
+                .collect();
+            Self { rng, places }
+        }
+
+        // flip a few bits of the descriptors of a place
+        fn observe(&mut self, place: usize) -> Vec<[u8; 32]> {
+            let mut descriptors = self.places[place].clone();
+            for descriptor in descriptors.iter_mut() {
+                for _ in 0..8 {
+                    let bit = self.rng.random_range(0..256);
+                    descriptor[bit / 8] ^= 1 << (bit % 8);
+                }
+            }
+            descriptors
+        }
+    }
+
+    #[test]
+    fn test_vocabulary() {
+        let places = Places::new(20);
+        let params = VocabularyParams {
+            branching: 8,
+            depth: 3,
+            ..Default::default()
+        };
+        let vocabulary = Vocabulary::train(&places.places, &params);
+        assert!(vocabulary.num_words() > 64 && vocabulary.num_words() <= 512);
+
+        let bow = vocabulary.transform(&places.places[0]);
+        assert!((bow.values().sum::<f32>() - 1.0).abs() < 1e-5);
+        assert!((bow_score(&bow, &bow) - 1.0).abs() < 1e-5);
+        assert!(bow_score(&bow, &vocabulary.transform(&places.places[1])) < 0.5);
+
+        // the training is deterministic
+        let other = Vocabulary::train(&places.places, &params);
+        assert_eq!(
+            other.transform(&places.places[3]),
+            vocabulary.transform(&places.places[3])
+        );
+    }
+
+    #[test]
+    fn test_database_query() {
+        let mut places = Places::new(20);
+        let vocabulary = Vocabulary::train(&places.places, &VocabularyParams::default());
+        let mut database = BowDatabase::new(vocabulary);
+        for place in 0..20 {
+            assert_eq!(database.add(&places.places[place]), place);
+        }
+
+        for place in [0, 7, 19] {
+            let bow = database.vocabulary().transform(&places.observe(place));
+            let matches = database.query(&bow, 3);
+            assert_eq!(matches[0].entry, place);
+            assert!(matches.len() < 2 || matches[0].score > 2.0 * matches[1].score);
+        }
+    }
+
+    #[test]
+    fn test_loop_detector() {
+        let mut places = Places::new(20);
+        let vocabulary = Vocabulary::train(&places.places, &VocabularyParams::default());
+        let params = LoopDetectorParams {
+            exclude_recent: 5,
+            ..Default::default()
+        };
+        let mut detector = LoopDetector::new(vocabulary, params);
+
+        // a trajectory through new places which comes back to the places 3 to 8
+        let mut loops = Vec::new();
+        for (frame, place) in (0..20).chain(3..9).enumerate() {
+            if let Some(m) = detector.detect(&places.observe(place)) {
+                loops.push((frame, m.entry));
+            }
+        }
+
+        // the loop is accepted after three consistent frames
+        assert_eq!(loops, vec![(22, 5), (23, 6), (24, 7), (25, 8)]);
+    }
+}
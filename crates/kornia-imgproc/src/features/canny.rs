@@ -0,0 +1,173 @@
+use crate::parallel::prelude::*;
+use kornia_image::{Image, ImageError};
+
+use crate::filter::{kernels::GradsMode, spatial_gradient_float};
+
+/// Detect the edges of an image with the Canny algorithm.
+///
+/// The gradients are computed with the normalized Sobel operator, thinned to their local
+/// maxima along the gradient direction, and the pixels above `high_threshold` are linked
+/// through the 8-connected pixels above `low_threshold`.
+///
+/// # Arguments
+///
+/// * `src` - The source image with shape (H, W, 1), usually smoothed beforehand.
+/// * `dst` - The destination edge map with shape (H, W, 1), 255 for the edges and 0 elsewhere.
+/// * `low_threshold` - The magnitude of the gradient to continue an edge.
+/// * `high_threshold` - The magnitude of the gradient to start an edge. With the normalized
+///   Sobel operator, a step of 1.0 has a magnitude of 0.5.
+///
+/// # Example
+///
+/// ```
+/// use kornia_image::Image;
+/// use kornia_imgproc::features::canny;
+///
+/// // a vertical step between the columns 3 and 4
+/// let src = Image::<f32, 1>::new(
+///     [8, 4].into(),
+///     (0..32).map(|i| if i % 8 < 4 { 0.0 } else { 1.0 }).collect(),
+/// )
+/// .unwrap();
+///
+/// let mut edges = Image::<u8, 1>::from_size_val(src.size(), 0).unwrap();
+/// canny(&src, &mut edges, 0.1, 0.2).unwrap();
+/// assert_eq!(&edges.as_slice()[..8], &[0, 0, 0, 255, 0, 0, 0, 0]);
+/// ```
+pub fn canny(
+    src: &Image<f32, 1>,
+    dst: &mut Image<u8, 1>,
+    low_threshold: f32,
+    high_threshold: f32,
+) -> Result<(), ImageError> {
+    if src.size() != dst.size() {
+        return Err(ImageError::InvalidImageSize(
+            src.cols(),
+            src.rows(),
+            dst.cols(),
+            dst.rows(),
+        ));
+    }
+
+    let (cols, rows) = (src.cols(), src.rows());
+    let mut dx = Image::from_size_val(src.size(), 0.0)?;
+    let mut dy = Image::from_size_val(src.size(), 0.0)?;
+    spatial_gradient_float(src, &mut dx, &mut dy, GradsMode::Sobel)?;
+
+    let (gx, gy) = (dx.as_slice(), dy.as_slice());
+    let magnitude = gx
+        .iter()
+        .zip(gy)
+        .map(|(x, y)| x.hypot(*y))
+        .collect::<Vec<_>>();
+
+    // tan(22.5) and tan(67.5) split the directions in four sectors
+    const TAN_22_5: f32 = 0.414_213_57;
+    const TAN_67_5: f32 = 2.414_213_6;
+
+    // keep the local maxima along the gradient, the ties go to the first pixel
+    let mut state = vec![0u8; cols * rows];
+    state
+        .par_chunks_exact_mut(cols)
+        .enumerate()
+        .for_each(|(y, state_row)| {
+            for (x, state) in state_row.iter_mut().enumerate() {
+                let i = y * cols + x;
+                let m = magnitude[i];
+                if m <= low_threshold {
+                    continue;
+                }
+
+                let at = |dx: isize, dy: isize| {
+                    let (nx, ny) = (x as isize + dx, y as isize + dy);
+                    if nx < 0 || ny < 0 || nx >= cols as isize || ny >= rows as isize {
+                        0.0
+                    } else {
+                        magnitude[ny as usize * cols + nx as usize]
+                    }
+                };
+
+                let (ax, ay) = (gx[i].abs(), gy[i].abs());
+                let (n1, n2) = if ay <= ax * TAN_22_5 {
+                    (at(-1, 0), at(1, 0))
+                } else if ay >= ax * TAN_67_5 {
+                    (at(0, -1), at(0, 1))
+                } else if (gx[i] > 0.0) == (gy[i] > 0.0) {
+                    (at(-1, -1), at(1, 1))
+                } else {
+                    (at(1, -1), at(-1, 1))
+                };
+
+                if m > n1 && m >= n2 {
+                    *state = if m > high_threshold { 2 } else { 1 };
+                }
+            }
+        });
+
+    // link the weak edges connected to the strong edges
+    let mut stack = (0..cols * rows)
+        .filter(|&i| state[i] == 2)
+        .collect::<Vec<_>>();
+    let data = dst.as_slice_mut();
+    data.fill(0);
+    stack.iter().for_each(|&i| data[i] = 255);
+
+    while let Some(i) = stack.pop() {
+        let (x, y) = (i % cols, i / cols);
+        for ny in y.saturating_sub(1)..(y + 2).min(rows) {
+            for nx in x.saturating_sub(1)..(x + 2).min(cols) {
+                let j = ny * cols + nx;
+                if state[j] == 1 && data[j] == 0 {
+                    data[j] = 255;
+                    stack.push(j);
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_canny() -> Result<(), ImageError> {
+        // a bright square with a weak bar attached to its right side
+        let size = 16;
+        let src = Image::<f32, 1>::new(
+            [size, size].into(),
+            (0..size * size)
+                .map(|i| {
+                    let (x, y) = (i % size, i / size);
+                    if (4..10).contains(&x) && (4..10).contains(&y) {
+                        1.0
+                    } else if (10..14).contains(&x) && (6..8).contains(&y) {
+                        0.3
+                    } else {
+                        0.0
+                    }
+                })
+                .collect(),
+        )?;
+
+        let mut edges = Image::from_size_val(src.size(), 0)?;
+        canny(&src, &mut edges, 0.1, 0.4)?;
+        let is_edge =
+            |edges: &Image<u8, 1>, x: usize, y: usize| edges.as_slice()[y * size + x] == 255;
+
+        // the left side of the square is a single pixel wide
+        let edge = |x, y| is_edge(&edges, x, y);
+        assert!(edge(3, 6) && !edge(2, 6) && !edge(4, 6));
+
+        // the weak bar is linked to the square
+        assert!(edge(12, 5));
+
+        // without the link, the weak edges are dropped
+        canny(&src, &mut edges, 0.2, 0.4)?;
+        assert!(!is_edge(&edges, 12, 5));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,110 @@
+use kornia_image::Image;
+
+use super::{descriptor_distance, Keypoint};
+
+/// An extractor of the descriptors of the keypoints of a grayscale image.
+pub trait DescriptorExtractor {
+    /// The descriptor of a keypoint.
+    type Descriptor;
+    /// The error of the extraction.
+    type Error;
+
+    /// Compute the descriptors of keypoints.
+    ///
+    /// # Arguments
+    ///
+    /// * `src` - The source image with shape (H, W) and values in [0, 1].
+    /// * `keypoints` - The keypoints, e.g. from a [`super::FeatureDetector`].
+    ///
+    /// # Returns
+    ///
+    /// The descriptor of every keypoint, in the order of the keypoints.
+    fn extract(
+        &mut self,
+        src: &Image<f32, 1>,
+        keypoints: &[Keypoint],
+    ) -> Result<Vec<Self::Descriptor>, Self::Error>;
+}
+
+/// A distance between descriptors.
+pub trait Metric {
+    /// The descriptor compared by the metric.
+    type Descriptor: Clone + std::fmt::Debug + Send + Sync;
+
+    /// The distance between two descriptors, zero for identical descriptors.
+    fn distance(a: &Self::Descriptor, b: &Self::Descriptor) -> f32;
+}
+
+/// The hamming distance between binary descriptors of `N` bytes.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Hamming<const N: usize>;
+
+impl<const N: usize> Metric for Hamming<N> {
+    type Descriptor = [u8; N];
+
+    #[inline]
+    fn distance(a: &[u8; N], b: &[u8; N]) -> f32 {
+        descriptor_distance(a, b) as f32
+    }
+}
+
+/// The euclidean distance between float descriptors of `D` dimensions.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct L2<const D: usize>;
+
+impl<const D: usize> Metric for L2<D> {
+    type Descriptor = [f32; D];
+
+    #[inline]
+    fn distance(a: &[f32; D], b: &[f32; D]) -> f32 {
+        a.iter()
+            .zip(b)
+            .map(|(a, b)| (a - b) * (a - b))
+            .sum::<f32>()
+            .sqrt()
+    }
+}
+
+/// The cosine distance `1 - cos(a, b)` between float descriptors of `D` dimensions.
+///
+/// The distance of a zero descriptor to any descriptor is 1.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Cosine<const D: usize>;
+
+impl<const D: usize> Metric for Cosine<D> {
+    type Descriptor = [f32; D];
+
+    #[inline]
+    fn distance(a: &[f32; D], b: &[f32; D]) -> f32 {
+        let (mut dot, mut norm_a, mut norm_b) = (0.0, 0.0, 0.0);
+        for (a, b) in a.iter().zip(b) {
+            dot += a * b;
+            norm_a += a * a;
+            norm_b += b * b;
+        }
+        let norm = (norm_a * norm_b).sqrt();
+        if norm > f32::EPSILON {
+            1.0 - dot / norm
+        } else {
+            1.0
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_metrics() {
+        assert_eq!(
+            Hamming::<2>::distance(&[0b1011, 0xff], &[0b0001, 0x0f]),
+            6.0
+        );
+        assert_eq!(L2::<2>::distance(&[0.0, 3.0], &[4.0, 0.0]), 5.0);
+        assert_eq!(Cosine::<2>::distance(&[1.0, 0.0], &[2.0, 0.0]), 0.0);
+        assert_eq!(Cosine::<2>::distance(&[1.0, 0.0], &[0.0, 3.0]), 1.0);
+        assert_eq!(Cosine::<2>::distance(&[1.0, 0.0], &[-1.0, 0.0]), 2.0);
+        assert_eq!(Cosine::<2>::distance(&[0.0, 0.0], &[1.0, 0.0]), 1.0);
+    }
+}
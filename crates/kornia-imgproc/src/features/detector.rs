@@ -0,0 +1,445 @@
+use kornia_image::{Image, ImageError};
+
+use super::{
+    fast_feature_detector, find_scale_space_extrema, gftt_response, hessian_response,
+    non_max_suppression, threshold_response, HarrisResponse, Keypoint,
+};
+use crate::filter::{gaussian_blur_sigma, kernels::GradsMode, kernels::KernelSizePolicy};
+
+/// A detector of the keypoints of a grayscale image.
+pub trait FeatureDetector {
+    /// Detect the keypoints of an image.
+    ///
+    /// # Arguments
+    ///
+    /// * `src` - The source image with shape (H, W) and values in [0, 1].
+    ///
+    /// # Returns
+    ///
+    /// The keypoints sorted by decreasing response, or in row-major order if the detector
+    /// has no response.
+    fn detect(&mut self, src: &Image<f32, 1>) -> Result<Vec<Keypoint>, ImageError>;
+}
+
+/// The Harris corner detector.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct HarrisDetector {
+    /// The sensitivity `k` of the Harris response, usually between 0.04 and 0.06.
+    pub k: f32,
+    /// The minimum response relative to the maximum response.
+    pub thresh_rel: f32,
+    /// The minimum absolute response.
+    pub thresh_abs: f32,
+    /// The maximum number of keypoints, or 0 to keep all of them.
+    pub max_keypoints: usize,
+}
+
+impl Default for HarrisDetector {
+    fn default() -> Self {
+        Self {
+            k: 0.04,
+            thresh_rel: 0.01,
+            thresh_abs: 0.0,
+            max_keypoints: 0,
+        }
+    }
+}
+
+impl FeatureDetector for HarrisDetector {
+    fn detect(&mut self, src: &Image<f32, 1>) -> Result<Vec<Keypoint>, ImageError> {
+        let mut response = Image::from_size_val(src.size(), 0.0)?;
+        HarrisResponse::new(src.size())
+            .with_k(self.k)
+            .compute(src, &mut response)?;
+        Ok(response_keypoints(
+            &mut response,
+            self.thresh_rel,
+            self.thresh_abs,
+            self.max_keypoints,
+        ))
+    }
+}
+
+/// The Shi-Tomasi (good features to track) corner detector.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GfttDetector {
+    /// The stencil of the gradients.
+    pub grads_mode: GradsMode,
+    /// The minimum response relative to the maximum response.
+    pub thresh_rel: f32,
+    /// The minimum absolute response.
+    pub thresh_abs: f32,
+    /// The maximum number of keypoints, or 0 to keep all of them.
+    pub max_keypoints: usize,
+}
+
+impl Default for GfttDetector {
+    fn default() -> Self {
+        Self {
+            grads_mode: GradsMode::Sobel,
+            thresh_rel: 0.01,
+            thresh_abs: 0.0,
+            max_keypoints: 0,
+        }
+    }
+}
+
+impl FeatureDetector for GfttDetector {
+    fn detect(&mut self, src: &Image<f32, 1>) -> Result<Vec<Keypoint>, ImageError> {
+        let mut response = Image::from_size_val(src.size(), 0.0)?;
+        gftt_response(src, &mut response, self.grads_mode)?;
+        Ok(response_keypoints(
+            &mut response,
+            self.thresh_rel,
+            self.thresh_abs,
+            self.max_keypoints,
+        ))
+    }
+}
+
+/// The FAST corner detector.
+///
+/// The image is quantized to 8 bits before the detection.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FastDetector {
+    /// The minimum difference of intensity, in [0, 255], between the center and the arc.
+    pub threshold: u8,
+    /// The number of consecutive pixels of the circle brighter or darker than the center.
+    pub arc_length: u8,
+    /// The maximum number of keypoints, or 0 to keep all of them.
+    pub max_keypoints: usize,
+}
+
+impl Default for FastDetector {
+    fn default() -> Self {
+        Self {
+            threshold: 20,
+            arc_length: 9,
+            max_keypoints: 0,
+        }
+    }
+}
+
+impl FeatureDetector for FastDetector {
+    fn detect(&mut self, src: &Image<f32, 1>) -> Result<Vec<Keypoint>, ImageError> {
+        let gray = Image::new(
+            src.size(),
+            src.as_slice()
+                .iter()
+                .map(|&v| (v * 255.0).round().clamp(0.0, 255.0) as u8)
+                .collect(),
+        )?;
+        let corners = fast_feature_detector(&gray, self.threshold, self.arc_length)?;
+        Ok(truncate(
+            corners
+                .into_iter()
+                .map(|[x, y]| Keypoint::new([x as f32, y as f32], 1.0, 0.0))
+                .collect(),
+            self.max_keypoints,
+        ))
+    }
+}
+
+/// The difference of Gaussians blob detector over a single octave.
+///
+/// The image is blurred at `num_scales + 3` sigmas spaced by a factor `2^(1 / num_scales)`
+/// and the keypoints are the extrema of the differences of consecutive blurs, as in SIFT.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DogDetector {
+    /// The sigma of the first blur.
+    pub sigma: f32,
+    /// The number of scales of the octave.
+    pub num_scales: usize,
+    /// The minimum absolute response of an extremum.
+    pub threshold: f32,
+    /// The rule to derive the sizes of the Gaussian kernels from the sigmas.
+    pub kernel_size_policy: KernelSizePolicy,
+    /// The maximum number of keypoints, or 0 to keep all of them.
+    pub max_keypoints: usize,
+}
+
+impl Default for DogDetector {
+    fn default() -> Self {
+        Self {
+            sigma: 1.6,
+            num_scales: 3,
+            threshold: 0.01,
+            kernel_size_policy: KernelSizePolicy::OpenCv,
+            max_keypoints: 0,
+        }
+    }
+}
+
+impl FeatureDetector for DogDetector {
+    fn detect(&mut self, src: &Image<f32, 1>) -> Result<Vec<Keypoint>, ImageError> {
+        let num_scales = self.num_scales.max(1);
+        let sigma_at = |scale: f32| self.sigma * (scale / num_scales as f32).exp2();
+
+        let blurs = (0..num_scales + 3)
+            .map(|i| {
+                let sigma = sigma_at(i as f32);
+                let mut dst = Image::from_size_val(src.size(), 0.0)?;
+                gaussian_blur_sigma(src, &mut dst, (sigma, sigma), self.kernel_size_policy)?;
+                Ok(dst)
+            })
+            .collect::<Result<Vec<_>, ImageError>>()?;
+        let dogs = blurs
+            .windows(2)
+            .map(|pair| {
+                let data = pair[1]
+                    .as_slice()
+                    .iter()
+                    .zip(pair[0].as_slice())
+                    .map(|(b, a)| b - a)
+                    .collect();
+                Image::new(src.size(), data)
+            })
+            .collect::<Result<Vec<_>, ImageError>>()?;
+
+        let mut extrema = Vec::new();
+        for (i, layers) in dogs.windows(3).enumerate() {
+            for extremum in
+                find_scale_space_extrema(&layers[0], &layers[1], &layers[2], self.threshold)?
+            {
+                let scale = sigma_at((i + 1) as f32 + extremum.scale_offset);
+                extrema.push((
+                    extremum.response.abs(),
+                    Keypoint::new(extremum.position, scale, 0.0),
+                ));
+            }
+        }
+
+        Ok(sorted_keypoints(extrema, self.max_keypoints))
+    }
+}
+
+/// The determinant of the Hessian blob detector at a single scale.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct HessianDetector {
+    /// The stencil of the second order derivatives.
+    pub grads_mode: GradsMode,
+    /// The minimum response relative to the maximum response.
+    pub thresh_rel: f32,
+    /// The minimum absolute response.
+    pub thresh_abs: f32,
+    /// The maximum number of keypoints, or 0 to keep all of them.
+    pub max_keypoints: usize,
+}
+
+impl Default for HessianDetector {
+    fn default() -> Self {
+        Self {
+            grads_mode: GradsMode::Diff,
+            thresh_rel: 0.01,
+            thresh_abs: 0.0,
+            max_keypoints: 0,
+        }
+    }
+}
+
+impl FeatureDetector for HessianDetector {
+    fn detect(&mut self, src: &Image<f32, 1>) -> Result<Vec<Keypoint>, ImageError> {
+        let mut response = Image::from_size_val(src.size(), 0.0)?;
+        hessian_response(src, &mut response, self.grads_mode)?;
+        Ok(response_keypoints(
+            &mut response,
+            self.thresh_rel,
+            self.thresh_abs,
+            self.max_keypoints,
+        ))
+    }
+}
+
+/// The configuration of a feature detector, to select the detector at runtime.
+///
+/// # Example
+///
+/// ```
+/// use kornia_image::Image;
+/// use kornia_imgproc::features::DetectorConfig;
+///
+/// let mut detector = DetectorConfig::from_name("gftt").unwrap().build();
+///
+/// let image = Image::<f32, 1>::from_size_val([32, 32].into(), 0.0).unwrap();
+/// assert!(detector.detect(&image).unwrap().is_empty());
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(tag = "type", rename_all = "snake_case")
+)]
+pub enum DetectorConfig {
+    /// The Harris corner detector.
+    Harris(HarrisDetector),
+    /// The Shi-Tomasi corner detector.
+    Gftt(GfttDetector),
+    /// The FAST corner detector.
+    Fast(FastDetector),
+    /// The difference of Gaussians blob detector.
+    Dog(DogDetector),
+    /// The determinant of the Hessian blob detector.
+    Hessian(HessianDetector),
+}
+
+impl DetectorConfig {
+    /// The names of the registered detectors.
+    pub const NAMES: [&'static str; 5] = ["harris", "gftt", "fast", "dog", "hessian"];
+
+    /// Create the default configuration of a detector from its name.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the detector, one of [`Self::NAMES`].
+    ///
+    /// # Returns
+    ///
+    /// The configuration, or `None` if the name is unknown.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "harris" => Some(Self::Harris(HarrisDetector::default())),
+            "gftt" => Some(Self::Gftt(GfttDetector::default())),
+            "fast" => Some(Self::Fast(FastDetector::default())),
+            "dog" => Some(Self::Dog(DogDetector::default())),
+            "hessian" => Some(Self::Hessian(HessianDetector::default())),
+            _ => None,
+        }
+    }
+
+    /// The name of the detector.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Harris(_) => "harris",
+            Self::Gftt(_) => "gftt",
+            Self::Fast(_) => "fast",
+            Self::Dog(_) => "dog",
+            Self::Hessian(_) => "hessian",
+        }
+    }
+
+    /// Build the detector of the configuration.
+    pub fn build(self) -> Box<dyn FeatureDetector + Send> {
+        match self {
+            Self::Harris(detector) => Box::new(detector),
+            Self::Gftt(detector) => Box::new(detector),
+            Self::Fast(detector) => Box::new(detector),
+            Self::Dog(detector) => Box::new(detector),
+            Self::Hessian(detector) => Box::new(detector),
+        }
+    }
+}
+
+// threshold a response, keep its local maxima and sort them by decreasing response
+fn response_keypoints(
+    response: &mut Image<f32, 1>,
+    thresh_rel: f32,
+    thresh_abs: f32,
+    max_keypoints: usize,
+) -> Vec<Keypoint> {
+    let threshold = threshold_response(response, thresh_rel, thresh_abs);
+    let cols = response.cols();
+    let maxima = non_max_suppression(response, threshold)
+        .into_iter()
+        .map(|[x, y]| {
+            let value = response.as_slice()[y as usize * cols + x as usize];
+            (value, Keypoint::new([x as f32, y as f32], 1.0, 0.0))
+        })
+        .collect();
+    sorted_keypoints(maxima, max_keypoints)
+}
+
+fn sorted_keypoints(mut keypoints: Vec<(f32, Keypoint)>, max_keypoints: usize) -> Vec<Keypoint> {
+    keypoints.sort_by(|a, b| b.0.total_cmp(&a.0));
+    truncate(
+        keypoints
+            .into_iter()
+            .map(|(_, keypoint)| keypoint)
+            .collect(),
+        max_keypoints,
+    )
+}
+
+fn truncate(mut keypoints: Vec<Keypoint>, max_keypoints: usize) -> Vec<Keypoint> {
+    if max_keypoints > 0 {
+        keypoints.truncate(max_keypoints);
+    }
+    keypoints
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // a bright square on a dark background, with its corners at (8, 8) and (23, 23)
+    fn square() -> Result<Image<f32, 1>, ImageError> {
+        let data = (0..32 * 32)
+            .map(|i| {
+                let (x, y) = (i % 32, i / 32);
+                if (8..24).contains(&x) && (8..24).contains(&y) {
+                    1.0
+                } else {
+                    0.0
+                }
+            })
+            .collect();
+        Image::new([32, 32].into(), data)
+    }
+
+    #[test]
+    fn test_detector_registry() -> Result<(), ImageError> {
+        let src = square()?;
+        let corners = [[8.0, 8.0], [23.0, 8.0], [8.0, 23.0], [23.0, 23.0]];
+
+        for name in DetectorConfig::NAMES {
+            let config = DetectorConfig::from_name(name).expect("a registered detector");
+            assert_eq!(config.name(), name);
+
+            let keypoints = config.build().detect(&src)?;
+            assert!(!keypoints.is_empty(), "{name}");
+
+            // the corner detectors find the corners of the square
+            if matches!(name, "harris" | "gftt") {
+                for corner in corners {
+                    let found = keypoints.iter().any(|k| {
+                        (k.position[0] - corner[0]).abs() <= 1.0
+                            && (k.position[1] - corner[1]).abs() <= 1.0
+                    });
+                    assert!(found, "{name} {corner:?}");
+                }
+            }
+        }
+        assert_eq!(DetectorConfig::from_name("sift"), None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_detector_max_keypoints() -> Result<(), ImageError> {
+        let src = square()?;
+        let mut detector = GfttDetector {
+            max_keypoints: 2,
+            ..Default::default()
+        };
+        assert_eq!(detector.detect(&src)?.len(), 2);
+
+        // a blob is found at its center
+        let blob = Image::new(
+            [32, 32].into(),
+            (0..32 * 32)
+                .map(|i| {
+                    let (x, y) = ((i % 32) as f32 - 16.0, (i / 32) as f32 - 16.0);
+                    (-(x * x + y * y) / (2.0 * 3.0 * 3.0)).exp()
+                })
+                .collect(),
+        )?;
+        let keypoints = DogDetector::default().detect(&blob)?;
+        assert_eq!(keypoints[0].position.map(f32::round), [16.0, 16.0]);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,229 @@
+use kornia_image::{ImageError, ImageSize};
+
+use crate::warp::inverse_perspective_matrix;
+
+/// Evaluates keypoints detected in two views related by a known homography.
+///
+/// The metrics follow the HPatches protocol: only the keypoints whose projection falls
+/// inside the other image are considered, and two keypoints correspond when the distance
+/// between the projection of the first one and the second one is below a threshold.
+///
+/// # Example
+///
+/// ```
+/// use kornia_imgproc::features::HomographyEvaluation;
+///
+/// // the second image is the first one shifted by 10 pixels to the right
+/// let homography = [1.0, 0.0, 10.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0];
+/// let evaluation =
+///     HomographyEvaluation::new(homography, [64, 48].into(), [64, 48].into()).unwrap();
+///
+/// let keypoints1 = [[5.0, 5.0], [20.0, 30.0]];
+/// let keypoints2 = [[15.5, 5.0], [40.0, 40.0]];
+///
+/// assert_eq!(evaluation.repeatability(&keypoints1, &keypoints2), 0.5);
+/// assert_eq!(evaluation.matching_score(&keypoints1, &keypoints2, &[(0, 0), (1, 1)]), 0.5);
+/// ```
+pub struct HomographyEvaluation {
+    homography: [f32; 9],
+    homography_inv: [f32; 9],
+    size1: ImageSize,
+    size2: ImageSize,
+    threshold: f32,
+}
+
+impl HomographyEvaluation {
+    /// Creates a new evaluation with a correspondence threshold of 3 pixels.
+    ///
+    /// # Arguments
+    ///
+    /// * `homography` - The 3x3 homography mapping the first image to the second one.
+    /// * `size1` - The size of the first image.
+    /// * `size2` - The size of the second image.
+    pub fn new(
+        homography: [f32; 9],
+        size1: ImageSize,
+        size2: ImageSize,
+    ) -> Result<Self, ImageError> {
+        Ok(Self {
+            homography,
+            homography_inv: inverse_perspective_matrix(&homography)?,
+            size1,
+            size2,
+            threshold: 3.0,
+        })
+    }
+
+    /// Sets the maximum distance in pixels between corresponding keypoints.
+    ///
+    /// # Arguments
+    ///
+    /// * `threshold` - The distance threshold.
+    pub fn with_threshold(mut self, threshold: f32) -> Self {
+        self.threshold = threshold;
+        self
+    }
+
+    /// Computes the repeatability of a detector.
+    ///
+    /// The repeatability is the number of one-to-one correspondences between the keypoints
+    /// of the two images divided by the smaller number of keypoints in the shared region.
+    ///
+    /// # Arguments
+    ///
+    /// * `keypoints1` - The keypoints of the first image as (x, y) coordinates.
+    /// * `keypoints2` - The keypoints of the second image as (x, y) coordinates.
+    ///
+    /// # Returns
+    ///
+    /// The repeatability in the range [0, 1] or zero if no keypoint is in the shared region.
+    pub fn repeatability(&self, keypoints1: &[[f32; 2]], keypoints2: &[[f32; 2]]) -> f32 {
+        let (shared1, shared2) = self.shared_keypoints(keypoints1, keypoints2);
+
+        // the pairs of keypoints closer than the threshold, from the closest
+        let mut pairs = Vec::new();
+        for &(i, p1) in &shared1 {
+            for &j in &shared2 {
+                let dist = distance(&p1, &keypoints2[j]);
+                if dist <= self.threshold {
+                    pairs.push((dist, i, j));
+                }
+            }
+        }
+        pairs.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+        // greedy one-to-one assignment
+        let mut used1 = vec![false; keypoints1.len()];
+        let mut used2 = vec![false; keypoints2.len()];
+        let mut num_correspondences = 0;
+        for (_, i, j) in pairs {
+            if !used1[i] && !used2[j] {
+                used1[i] = true;
+                used2[j] = true;
+                num_correspondences += 1;
+            }
+        }
+
+        ratio(num_correspondences, shared1.len().min(shared2.len()))
+    }
+
+    /// Computes the matching score of a descriptor.
+    ///
+    /// The matching score is the number of correct matches divided by the smaller number of
+    /// keypoints in the shared region. A match is correct when both keypoints are in the
+    /// shared region and correspond under the homography.
+    ///
+    /// # Arguments
+    ///
+    /// * `keypoints1` - The keypoints of the first image as (x, y) coordinates.
+    /// * `keypoints2` - The keypoints of the second image as (x, y) coordinates.
+    /// * `matches` - The pairs of indices into `keypoints1` and `keypoints2`.
+    ///
+    /// # Returns
+    ///
+    /// The matching score in the range [0, 1] or zero if no keypoint is in the shared region.
+    pub fn matching_score(
+        &self,
+        keypoints1: &[[f32; 2]],
+        keypoints2: &[[f32; 2]],
+        matches: &[(usize, usize)],
+    ) -> f32 {
+        let (shared1, shared2) = self.shared_keypoints(keypoints1, keypoints2);
+
+        let num_correct = matches
+            .iter()
+            .filter(|&&(i, j)| {
+                let (Some(p1), Some(p2)) = (keypoints1.get(i), keypoints2.get(j)) else {
+                    return false;
+                };
+                let projected = project(&self.homography, p1);
+                is_inside(&projected, self.size2)
+                    && is_inside(&project(&self.homography_inv, p2), self.size1)
+                    && distance(&projected, p2) <= self.threshold
+            })
+            .count();
+
+        ratio(num_correct, shared1.len().min(shared2.len()))
+    }
+
+    // the keypoints of each image visible in the other one, with the projections of the
+    // keypoints of the first image into the second one
+    fn shared_keypoints(
+        &self,
+        keypoints1: &[[f32; 2]],
+        keypoints2: &[[f32; 2]],
+    ) -> (Vec<(usize, [f32; 2])>, Vec<usize>) {
+        let shared1 = keypoints1
+            .iter()
+            .enumerate()
+            .map(|(i, p)| (i, project(&self.homography, p)))
+            .filter(|(_, p)| is_inside(p, self.size2))
+            .collect();
+        let shared2 = keypoints2
+            .iter()
+            .enumerate()
+            .filter(|(_, p)| is_inside(&project(&self.homography_inv, p), self.size1))
+            .map(|(j, _)| j)
+            .collect();
+        (shared1, shared2)
+    }
+}
+
+// apply a homography to a point
+fn project(m: &[f32; 9], p: &[f32; 2]) -> [f32; 2] {
+    let w = m[6] * p[0] + m[7] * p[1] + m[8];
+    [
+        (m[0] * p[0] + m[1] * p[1] + m[2]) / w,
+        (m[3] * p[0] + m[4] * p[1] + m[5]) / w,
+    ]
+}
+
+fn is_inside(p: &[f32; 2], size: ImageSize) -> bool {
+    p[0] >= 0.0 && p[1] >= 0.0 && p[0] < size.width as f32 && p[1] < size.height as f32
+}
+
+fn distance(a: &[f32; 2], b: &[f32; 2]) -> f32 {
+    (a[0] - b[0]).hypot(a[1] - b[1])
+}
+
+fn ratio(num: usize, den: usize) -> f32 {
+    if den == 0 {
+        0.0
+    } else {
+        num as f32 / den as f32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_homography_evaluation() -> Result<(), ImageError> {
+        // the second image is the first one scaled by two
+        let homography = [2.0, 0.0, 0.0, 0.0, 2.0, 0.0, 0.0, 0.0, 1.0];
+        let evaluation = HomographyEvaluation::new(homography, [20, 20].into(), [20, 20].into())?
+            .with_threshold(1.0);
+
+        // the last keypoint of the first image projects outside the second image
+        let keypoints1 = [[2.0, 2.0], [5.0, 1.0], [9.0, 9.0], [15.0, 15.0]];
+        let keypoints2 = [[4.5, 4.0], [10.0, 2.0], [2.0, 2.0], [18.0, 16.0]];
+
+        // three and four keypoints in the shared region and two correspondences
+        assert_eq!(
+            evaluation.repeatability(&keypoints1, &keypoints2),
+            2.0 / 3.0
+        );
+
+        let matches = [(0, 0), (1, 1), (2, 3), (3, 1), (9, 0)];
+        assert_eq!(
+            evaluation.matching_score(&keypoints1, &keypoints2, &matches),
+            2.0 / 3.0
+        );
+
+        let singular = [0.0; 9];
+        assert!(HomographyEvaluation::new(singular, [1, 1].into(), [1, 1].into()).is_err());
+
+        Ok(())
+    }
+}
@@ -1,5 +1,7 @@
+use alloc::vec::Vec;
 use kornia_image::{Image, ImageError};
-use rayon::prelude::*;
+
+use crate::parallel::prelude::*;
 
 /// Fast feature detector
 ///
@@ -17,6 +19,7 @@ pub fn fast_feature_detector(
     threshold: u8,
     arc_length: u8,
 ) -> Result<Vec<[i32; 2]>, ImageError> {
+    profile_scope!("fast_feature_detector");
     let (cols, rows) = (src.cols() as i32, src.rows() as i32);
 
     // Precompute the offsets for the Bresenham circle
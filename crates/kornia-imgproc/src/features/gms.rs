@@ -0,0 +1,272 @@
+use std::collections::HashMap;
+
+use kornia_image::ImageSize;
+
+use super::DescriptorMatch;
+
+/// The parameters of the grid-based motion statistics filter.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GmsParams {
+    /// The number of cells of the grid of the first image along each axis.
+    pub grid_size: usize,
+    /// The factor of the threshold of the motion statistics, higher rejects more matches.
+    pub threshold_factor: f32,
+    /// Whether to search the 8 rotations of the cell neighborhoods between the images.
+    pub with_rotation: bool,
+    /// Whether to search 5 relative scales of the grid of the second image.
+    pub with_scale: bool,
+}
+
+impl Default for GmsParams {
+    fn default() -> Self {
+        Self {
+            grid_size: 20,
+            threshold_factor: 6.0,
+            with_rotation: false,
+            with_scale: false,
+        }
+    }
+}
+
+// the permutations of the 3x3 neighborhood of a cell for the 8 rotations, from 1
+const ROTATION_PATTERNS: [[usize; 9]; 8] = [
+    [1, 2, 3, 4, 5, 6, 7, 8, 9],
+    [4, 1, 2, 7, 5, 3, 8, 9, 6],
+    [7, 4, 1, 8, 5, 2, 9, 6, 3],
+    [8, 7, 4, 9, 5, 1, 6, 3, 2],
+    [9, 8, 7, 6, 5, 4, 3, 2, 1],
+    [6, 9, 8, 3, 5, 7, 2, 1, 4],
+    [3, 6, 9, 2, 5, 8, 1, 4, 7],
+    [2, 3, 6, 1, 5, 9, 4, 7, 8],
+];
+
+/// Filter the matches between two images with the grid-based motion statistics.
+///
+/// The true matches are supported by the other matches of their neighborhood, which move
+/// to the same neighborhood of the second image. The images are divided in grids and a
+/// match is kept if its pair of cells is the most frequent pair of its cell in the first
+/// image and the matches of the 3x3 neighboring cells score above `threshold_factor` times
+/// the square root of their mean number of features, as in GMS of Bian et al. The grid of
+/// the first image is shifted by half a cell to catch the matches near the borders of the
+/// cells. The filter is much faster than RANSAC but needs dense matches, e.g. thousands of
+/// ORB matches.
+///
+/// # Arguments
+///
+/// * `kps1` - The keypoints `[x, y]` of the first image.
+/// * `size1` - The size of the first image.
+/// * `kps2` - The keypoints `[x, y]` of the second image.
+/// * `size2` - The size of the second image.
+/// * `matches` - The matches with the query in the first and the train in the second image.
+/// * `params` - The parameters of the filter.
+///
+/// # Returns
+///
+/// Whether every match is kept.
+pub fn gms_filter(
+    kps1: &[[f32; 2]],
+    size1: ImageSize,
+    kps2: &[[f32; 2]],
+    size2: ImageSize,
+    matches: &[DescriptorMatch],
+    params: &GmsParams,
+) -> Vec<bool> {
+    let normalize =
+        |p: [f32; 2], size: ImageSize| [p[0] / size.width as f32, p[1] / size.height as f32];
+    let points1 = matches
+        .iter()
+        .map(|m| normalize(kps1[m.query], size1))
+        .collect::<Vec<_>>();
+    let points2 = matches
+        .iter()
+        .map(|m| normalize(kps2[m.train], size2))
+        .collect::<Vec<_>>();
+
+    let grid1 = params.grid_size.max(1);
+    let rotations = if params.with_rotation {
+        &ROTATION_PATTERNS[..]
+    } else {
+        &ROTATION_PATTERNS[..1]
+    };
+    let scales: &[f32] = if params.with_scale {
+        &[
+            1.0,
+            0.5,
+            std::f32::consts::FRAC_1_SQRT_2,
+            std::f32::consts::SQRT_2,
+            2.0,
+        ]
+    } else {
+        &[1.0]
+    };
+
+    // keep the configuration with the most inliers
+    let mut best = vec![false; matches.len()];
+    let mut best_count = 0;
+    for &scale in scales {
+        let grid2 = ((grid1 as f32 * scale).round() as usize).max(1);
+        let cells2 = points2
+            .iter()
+            .map(|&p| grid_cell(p, grid2, [0.0, 0.0]))
+            .collect::<Vec<_>>();
+
+        for rotation in rotations {
+            let mut inliers = vec![false; matches.len()];
+            for shift in [[0.0, 0.0], [0.5, 0.0], [0.0, 0.5], [0.5, 0.5]] {
+                let cells1 = points1
+                    .iter()
+                    .map(|&p| grid_cell(p, grid1, shift))
+                    .collect::<Vec<_>>();
+                let pairs = cell_pairs(
+                    &cells1,
+                    &cells2,
+                    [grid1, grid2],
+                    rotation,
+                    params.threshold_factor,
+                );
+                for (i, inlier) in inliers.iter_mut().enumerate() {
+                    if let (Some(cell1), Some(cell2)) = (cells1[i], cells2[i]) {
+                        *inlier |= pairs[cell1] == Some(cell2);
+                    }
+                }
+            }
+
+            let count = inliers.iter().filter(|&&inlier| inlier).count();
+            if count > best_count {
+                best = inliers;
+                best_count = count;
+            }
+        }
+    }
+
+    best
+}
+
+// the cell of a normalized point in a square grid shifted by a fraction of a cell
+fn grid_cell(point: [f32; 2], grid: usize, shift: [f32; 2]) -> Option<usize> {
+    let x = (point[0] * grid as f32 + shift[0]).floor();
+    let y = (point[1] * grid as f32 + shift[1]).floor();
+    if x < 0.0 || y < 0.0 || x >= grid as f32 || y >= grid as f32 {
+        return None;
+    }
+    Some(y as usize * grid + x as usize)
+}
+
+// the 3x3 neighborhood of a cell in row-major order
+fn neighborhood(cell: usize, grid: usize) -> [Option<usize>; 9] {
+    let (x, y) = ((cell % grid) as i64, (cell / grid) as i64);
+    std::array::from_fn(|j| {
+        let (nx, ny) = (x + j as i64 % 3 - 1, y + j as i64 / 3 - 1);
+        let inside = nx >= 0 && ny >= 0 && nx < grid as i64 && ny < grid as i64;
+        inside.then(|| ny as usize * grid + nx as usize)
+    })
+}
+
+// the verified cell of the second image of every cell of the first image
+fn cell_pairs(
+    cells1: &[Option<usize>],
+    cells2: &[Option<usize>],
+    [grid1, grid2]: [usize; 2],
+    rotation: &[usize; 9],
+    threshold_factor: f32,
+) -> Vec<Option<usize>> {
+    let mut motion = vec![HashMap::<usize, u32>::new(); grid1 * grid1];
+    let mut num_points = vec![0u32; grid1 * grid1];
+    for (cell1, cell2) in cells1.iter().zip(cells2) {
+        if let (Some(cell1), Some(cell2)) = (*cell1, *cell2) {
+            *motion[cell1].entry(cell2).or_default() += 1;
+            num_points[cell1] += 1;
+        }
+    }
+
+    (0..grid1 * grid1)
+        .map(|cell1| {
+            // the most frequent cell, the smallest on ties
+            let (&cell2, _) = motion[cell1]
+                .iter()
+                .max_by(|a, b| a.1.cmp(b.1).then(b.0.cmp(a.0)))?;
+
+            let neighbors1 = neighborhood(cell1, grid1);
+            let neighbors2 = neighborhood(cell2, grid2);
+            let (mut score, mut total, mut num_cells) = (0, 0, 0);
+            for (j, neighbor1) in neighbors1.iter().enumerate() {
+                let (Some(neighbor1), Some(neighbor2)) = (neighbor1, neighbors2[rotation[j] - 1])
+                else {
+                    continue;
+                };
+                score += motion[*neighbor1].get(&neighbor2).copied().unwrap_or(0);
+                total += num_points[*neighbor1];
+                num_cells += 1;
+            }
+
+            let threshold = threshold_factor * (total as f32 / num_cells as f32).sqrt();
+            (score as f32 >= threshold).then_some(cell2)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    // the true matches of a motion followed by random outliers
+    fn synthetic_matches(
+        motion: impl Fn([f32; 2]) -> [f32; 2],
+        num_inliers: usize,
+        num_outliers: usize,
+    ) -> (Vec<[f32; 2]>, Vec<[f32; 2]>, Vec<DescriptorMatch>) {
+        let mut rng = StdRng::seed_from_u64(11);
+        let mut random = || [rng.random_range(0.0..640.0), rng.random_range(0.0..480.0)];
+        let kps1 = (0..num_inliers + num_outliers)
+            .map(|_| random())
+            .collect::<Vec<_>>();
+        let kps2 = kps1
+            .iter()
+            .enumerate()
+            .map(|(i, &p)| if i < num_inliers { motion(p) } else { random() })
+            .collect::<Vec<_>>();
+        let matches = (0..kps1.len())
+            .map(|i| DescriptorMatch {
+                query: i,
+                train: i,
+                distance: 0.0,
+            })
+            .collect();
+        (kps1, kps2, matches)
+    }
+
+    fn check(inliers: &[bool], num_inliers: usize) {
+        let kept = inliers[..num_inliers].iter().filter(|&&k| k).count();
+        let false_kept = inliers[num_inliers..].iter().filter(|&&k| k).count();
+        assert!(kept as f32 > 0.9 * num_inliers as f32, "{kept}");
+        assert!(
+            false_kept as f32 <= 0.05 * inliers[num_inliers..].len() as f32,
+            "{false_kept}"
+        );
+    }
+
+    #[test]
+    fn test_gms_filter() {
+        let size = ImageSize {
+            width: 640,
+            height: 480,
+        };
+        let (kps1, kps2, matches) =
+            synthetic_matches(|p| [p[0] * 0.98 + 12.0, p[1] * 0.98 + 5.0], 2000, 500);
+        let inliers = gms_filter(&kps1, size, &kps2, size, &matches, &GmsParams::default());
+        check(&inliers, 2000);
+
+        // a rotation by 180 degrees needs the rotated neighborhoods
+        let (kps1, kps2, matches) = synthetic_matches(|p| [640.0 - p[0], 480.0 - p[1]], 2000, 500);
+        let params = GmsParams {
+            with_rotation: true,
+            ..Default::default()
+        };
+        let inliers = gms_filter(&kps1, size, &kps2, size, &matches, &params);
+        check(&inliers, 2000);
+
+        assert!(gms_filter(&kps1, size, &kps2, size, &[], &params).is_empty());
+    }
+}
@@ -0,0 +1,285 @@
+use std::{
+    cmp::{Ordering, Reverse},
+    collections::{BinaryHeap, HashSet},
+};
+
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+use super::{DescriptorIndex, Metric};
+
+/// The parameters of a [`HnswIndex`].
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct HnswParams {
+    /// The number of neighbors of a node in the upper layers, twice in the bottom layer.
+    pub max_neighbors: usize,
+    /// The size of the candidate list of the insertions.
+    pub ef_construction: usize,
+    /// The size of the candidate list of the queries, at least the number of neighbors.
+    pub ef_search: usize,
+    /// The seed of the random number generator of the layers of the nodes.
+    pub seed: u64,
+}
+
+impl Default for HnswParams {
+    fn default() -> Self {
+        Self {
+            max_neighbors: 16,
+            ef_construction: 100,
+            ef_search: 64,
+            seed: 0,
+        }
+    }
+}
+
+// a node with its distance to a query, ordered by distance
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct Candidate(pub(crate) f32, pub(crate) usize);
+
+impl Eq for Candidate {}
+
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Candidate {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.total_cmp(&other.0).then(self.1.cmp(&other.1))
+    }
+}
+
+/// An approximate index of descriptors with a hierarchical navigable small world graph.
+///
+/// The descriptors are the nodes of a hierarchy of proximity graphs, where every layer holds
+/// an exponentially decreasing subset of the nodes. A query descends greedily from the
+/// sparsest layer and explores the bottom layer with a beam search, as in Malkov and
+/// Yashunin, so the queries take a logarithmic time in the number of descriptors.
+#[derive(Debug, Clone)]
+pub struct HnswIndex<M: Metric> {
+    descriptors: Vec<M::Descriptor>,
+    // the neighbors of every node in every layer of the node, from the bottom layer
+    links: Vec<Vec<Vec<usize>>>,
+    entry: Option<usize>,
+    params: HnswParams,
+    rng: StdRng,
+}
+
+impl<M: Metric> HnswIndex<M> {
+    /// Create an index of descriptors.
+    ///
+    /// # Arguments
+    ///
+    /// * `descriptors` - The descriptors of the index.
+    /// * `params` - The parameters of the graph.
+    pub fn new(descriptors: Vec<M::Descriptor>, params: HnswParams) -> Self {
+        let mut index = Self {
+            descriptors: Vec::with_capacity(descriptors.len()),
+            links: Vec::with_capacity(descriptors.len()),
+            entry: None,
+            rng: StdRng::seed_from_u64(params.seed),
+            params,
+        };
+        for descriptor in descriptors {
+            index.insert(descriptor);
+        }
+        index
+    }
+
+    /// The descriptors of the index.
+    pub fn descriptors(&self) -> &[M::Descriptor] {
+        &self.descriptors
+    }
+
+    /// The parameters of the graph.
+    pub fn params(&self) -> &HnswParams {
+        &self.params
+    }
+
+    /// Insert a descriptor in the index.
+    ///
+    /// # Returns
+    ///
+    /// The index of the descriptor.
+    pub fn insert(&mut self, descriptor: M::Descriptor) -> usize {
+        let id = self.descriptors.len();
+        let max_neighbors = self.params.max_neighbors.max(2);
+
+        // the top layer of the node follows a geometric distribution
+        let scale = 1.0 / (max_neighbors as f64).ln();
+        let uniform: f64 = self.rng.random_range(f64::EPSILON..1.0);
+        let level = (-uniform.ln() * scale) as usize;
+
+        let query = descriptor.clone();
+        self.descriptors.push(descriptor);
+        self.links.push(vec![Vec::new(); level + 1]);
+
+        let Some(entry) = self.entry else {
+            self.entry = Some(id);
+            return id;
+        };
+        let top = self.links[entry].len() - 1;
+        let query = &query;
+
+        let mut nearest = Candidate(M::distance(query, &self.descriptors[entry]), entry);
+        for layer in (level + 1..=top).rev() {
+            nearest = self.greedy_search(query, nearest, layer);
+        }
+
+        for layer in (0..=level.min(top)).rev() {
+            let candidates =
+                self.search_layer(query, nearest, self.params.ef_construction.max(1), layer);
+            nearest = candidates[0];
+
+            let max_links = if layer == 0 {
+                2 * max_neighbors
+            } else {
+                max_neighbors
+            };
+            let neighbors = candidates
+                .iter()
+                .take(max_neighbors)
+                .map(|c| c.1)
+                .collect::<Vec<_>>();
+            for &neighbor in &neighbors {
+                self.links[neighbor][layer].push(id);
+                if self.links[neighbor][layer].len() > max_links {
+                    self.prune(neighbor, layer, max_links);
+                }
+            }
+            self.links[id][layer] = neighbors;
+        }
+
+        if level > top {
+            self.entry = Some(id);
+        }
+        id
+    }
+
+    // keep the closest links of a node in a layer
+    fn prune(&mut self, node: usize, layer: usize, max_links: usize) {
+        let descriptor = &self.descriptors[node];
+        let mut links = self.links[node][layer]
+            .iter()
+            .map(|&n| Candidate(M::distance(descriptor, &self.descriptors[n]), n))
+            .collect::<Vec<_>>();
+        links.sort_unstable();
+        self.links[node][layer] = links.into_iter().take(max_links).map(|c| c.1).collect();
+    }
+
+    // move to the closest neighbor while it improves the distance to the query
+    fn greedy_search(
+        &self,
+        query: &M::Descriptor,
+        mut nearest: Candidate,
+        layer: usize,
+    ) -> Candidate {
+        loop {
+            let closer = self.links[nearest.1][layer]
+                .iter()
+                .map(|&n| Candidate(M::distance(query, &self.descriptors[n]), n))
+                .filter(|c| *c < nearest)
+                .min();
+            match closer {
+                Some(closer) => nearest = closer,
+                None => return nearest,
+            }
+        }
+    }
+
+    // the beam search of the `ef` closest nodes of a layer, sorted by increasing distance
+    fn search_layer(
+        &self,
+        query: &M::Descriptor,
+        entry: Candidate,
+        ef: usize,
+        layer: usize,
+    ) -> Vec<Candidate> {
+        let mut visited = HashSet::from([entry.1]);
+        let mut candidates = BinaryHeap::from([Reverse(entry)]);
+        let mut results = BinaryHeap::from([entry]);
+
+        while let Some(Reverse(candidate)) = candidates.pop() {
+            if results.len() >= ef && results.peek().is_some_and(|worst| candidate > *worst) {
+                break;
+            }
+            for &neighbor in &self.links[candidate.1][layer] {
+                if !visited.insert(neighbor) {
+                    continue;
+                }
+                let next = Candidate(M::distance(query, &self.descriptors[neighbor]), neighbor);
+                if results.len() < ef || results.peek().is_some_and(|worst| next < *worst) {
+                    candidates.push(Reverse(next));
+                    results.push(next);
+                    if results.len() > ef {
+                        results.pop();
+                    }
+                }
+            }
+        }
+
+        results.into_sorted_vec()
+    }
+}
+
+impl<M: Metric> DescriptorIndex<M> for HnswIndex<M> {
+    fn len(&self) -> usize {
+        self.descriptors.len()
+    }
+
+    fn knn(&self, query: &M::Descriptor, k: usize) -> Vec<(usize, f32)> {
+        let Some(entry) = self.entry else {
+            return Vec::new();
+        };
+        if k == 0 {
+            return Vec::new();
+        }
+
+        let mut nearest = Candidate(M::distance(query, &self.descriptors[entry]), entry);
+        for layer in (1..self.links[entry].len()).rev() {
+            nearest = self.greedy_search(query, nearest, layer);
+        }
+
+        self.search_layer(query, nearest, self.params.ef_search.max(k), 0)
+            .into_iter()
+            .take(k)
+            .map(|c| (c.1, c.0))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::features::{BruteForceIndex, L2};
+
+    #[test]
+    fn test_hnsw_index() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let descriptors = (0..2000)
+            .map(|_| std::array::from_fn(|_| rng.random_range(-1.0..1.0)))
+            .collect::<Vec<[f32; 16]>>();
+        let index = HnswIndex::<L2<16>>::new(descriptors.clone(), HnswParams::default());
+        let exact = BruteForceIndex::<L2<16>>::new(descriptors);
+        assert_eq!(index.len(), 2000);
+
+        // the recall of the 10 nearest neighbors of random queries
+        let (mut found, k) = (0, 10);
+        for _ in 0..50 {
+            let query = std::array::from_fn(|_| rng.random_range(-1.0..1.0));
+            let neighbors = index.knn(&query, k);
+            assert_eq!(neighbors.len(), k);
+            assert!(neighbors.windows(2).all(|w| w[0].1 <= w[1].1));
+            let expected = exact.knn(&query, k);
+            found += neighbors
+                .iter()
+                .filter(|n| expected.iter().any(|e| e.0 == n.0))
+                .count();
+        }
+        assert!(found as f32 / (50 * k) as f32 > 0.95, "{found}");
+
+        let empty = HnswIndex::<L2<16>>::new(Vec::new(), HnswParams::default());
+        assert!(empty.knn(&[0.0; 16], 1).is_empty());
+    }
+}
@@ -0,0 +1,365 @@
+use std::{cmp::Reverse, collections::BinaryHeap};
+
+use super::{hnsw::Candidate, matcher::push_nearest, DescriptorIndex, L2};
+
+/// The parameters of a [`KdTreeIndex`].
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct KdTreeParams {
+    /// The maximum number of descriptors in a leaf of the tree.
+    pub leaf_size: usize,
+    /// The maximum number of descriptors compared to a query, or 0 for an exact search.
+    pub max_checks: usize,
+}
+
+impl Default for KdTreeParams {
+    fn default() -> Self {
+        Self {
+            leaf_size: 8,
+            max_checks: 512,
+        }
+    }
+}
+
+// a node of the tree, splitting its descriptors at a value of a dimension
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+enum KdNode {
+    Split {
+        dim: usize,
+        value: f32,
+        left: usize,
+        right: usize,
+    },
+    Leaf {
+        start: usize,
+        end: usize,
+    },
+}
+
+/// An approximate index of float descriptors with a kd-tree and a best bin first search.
+///
+/// The tree splits the descriptors at the median of their dimension of largest variance. A
+/// query visits the leaves in increasing order of their distance bound to the query, as in
+/// the best bin first search of Beis and Lowe, and stops after `max_checks` descriptors. The
+/// descriptors are stored as flat arrays, so the index of high dimensional descriptors can
+/// be serialized with the `serde` feature and reloaded without rebuilding the tree. A loaded
+/// index is validated, so a corrupted file fails to load instead of panicking on a query.
+///
+/// # Example
+///
+/// ```
+/// use kornia_imgproc::features::{DescriptorIndex, KdTreeIndex, KdTreeParams};
+///
+/// let descriptors = (0..100).map(|i| [i as f32; 128]).collect::<Vec<_>>();
+/// let index = KdTreeIndex::new(&descriptors, &KdTreeParams::default());
+///
+/// let neighbors = index.knn(&[41.9; 128], 2);
+/// assert_eq!(neighbors[0].0, 42);
+/// assert_eq!(neighbors[1].0, 41);
+/// ```
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "KdTreeIndexData"))]
+pub struct KdTreeIndex<const D: usize> {
+    // the descriptors in row-major order
+    data: Vec<f32>,
+    // the indices of the descriptors grouped by leaf
+    order: Vec<usize>,
+    nodes: Vec<KdNode>,
+    max_checks: usize,
+}
+
+impl<const D: usize> KdTreeIndex<D> {
+    /// Build the index of descriptors.
+    ///
+    /// # Arguments
+    ///
+    /// * `descriptors` - The descriptors of the index.
+    /// * `params` - The parameters of the tree and of the queries.
+    pub fn new(descriptors: &[[f32; D]], params: &KdTreeParams) -> Self {
+        let mut index = Self {
+            data: descriptors.iter().flatten().copied().collect(),
+            order: (0..descriptors.len()).collect(),
+            nodes: Vec::new(),
+            max_checks: params.max_checks,
+        };
+        index.build(0, descriptors.len(), params.leaf_size.max(1));
+        index
+    }
+
+    /// The number of descriptors compared to a query, or 0 for an exact search.
+    pub fn max_checks(&self) -> usize {
+        self.max_checks
+    }
+
+    /// Sets the number of descriptors compared to a query, or 0 for an exact search.
+    pub fn set_max_checks(&mut self, max_checks: usize) {
+        self.max_checks = max_checks;
+    }
+
+    /// The descriptor at an index.
+    pub fn descriptor(&self, index: usize) -> &[f32] {
+        &self.data[index * D..(index + 1) * D]
+    }
+
+    // build the subtree of the descriptors order[start..end] and return its root
+    fn build(&mut self, start: usize, end: usize, leaf_size: usize) -> usize {
+        let id = self.nodes.len();
+        self.nodes.push(KdNode::Leaf { start, end });
+        if end - start <= leaf_size {
+            return id;
+        }
+
+        // the dimension of largest variance
+        let n = (end - start) as f32;
+        let dim = (0..D)
+            .map(|dim| {
+                let values = self.order[start..end]
+                    .iter()
+                    .map(|&i| self.data[i * D + dim]);
+                let mean = values.clone().sum::<f32>() / n;
+                let var = values.map(|v| (v - mean) * (v - mean)).sum::<f32>();
+                (dim, var)
+            })
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .map_or(0, |(dim, _)| dim);
+
+        let mid = start + (end - start) / 2;
+        let data = &self.data;
+        self.order[start..end].select_nth_unstable_by(mid - start, |&a, &b| {
+            data[a * D + dim].total_cmp(&data[b * D + dim])
+        });
+        let value = self.data[self.order[mid] * D + dim];
+
+        let left = self.build(start, mid, leaf_size);
+        let right = self.build(mid, end, leaf_size);
+        self.nodes[id] = KdNode::Split {
+            dim,
+            value,
+            left,
+            right,
+        };
+        id
+    }
+}
+
+// the fields of a serialized index, validated before they become a `KdTreeIndex`
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct KdTreeIndexData {
+    data: Vec<f32>,
+    order: Vec<usize>,
+    nodes: Vec<KdNode>,
+    max_checks: usize,
+}
+
+#[cfg(feature = "serde")]
+impl<const D: usize> TryFrom<KdTreeIndexData> for KdTreeIndex<D> {
+    type Error = String;
+
+    fn try_from(index: KdTreeIndexData) -> Result<Self, Self::Error> {
+        let num_points = index.order.len();
+        if index.data.len() != num_points * D {
+            return Err(format!(
+                "expected {} values for {num_points} descriptors of {D} dimensions, got {}",
+                num_points * D,
+                index.data.len()
+            ));
+        }
+
+        // the order is a permutation of the descriptors
+        let mut seen = vec![false; num_points];
+        for &i in &index.order {
+            if i >= num_points || std::mem::replace(&mut seen[i], true) {
+                return Err(format!("invalid descriptor index {i} in the order"));
+            }
+        }
+
+        if index.nodes.is_empty() {
+            return Err("the tree has no nodes".to_string());
+        }
+        // the children follow their parent, so the tree has no cycles
+        let num_nodes = index.nodes.len();
+        for (id, node) in index.nodes.iter().enumerate() {
+            match *node {
+                KdNode::Split {
+                    dim, left, right, ..
+                } => {
+                    if dim >= D {
+                        return Err(format!("invalid split dimension {dim} of node {id}"));
+                    }
+                    for child in [left, right] {
+                        if child <= id || child >= num_nodes {
+                            return Err(format!("invalid child {child} of node {id}"));
+                        }
+                    }
+                }
+                KdNode::Leaf { start, end } => {
+                    if start > end || end > num_points {
+                        return Err(format!("invalid range {start}..{end} of leaf {id}"));
+                    }
+                }
+            }
+        }
+
+        Ok(Self {
+            data: index.data,
+            order: index.order,
+            nodes: index.nodes,
+            max_checks: index.max_checks,
+        })
+    }
+}
+
+impl<const D: usize> DescriptorIndex<L2<D>> for KdTreeIndex<D> {
+    fn len(&self) -> usize {
+        self.order.len()
+    }
+
+    fn knn(&self, query: &[f32; D], k: usize) -> Vec<(usize, f32)> {
+        // the squared distances of the closest descriptors
+        let mut best = Vec::with_capacity(k + 1);
+        if k == 0 || self.nodes.is_empty() {
+            return best;
+        }
+
+        let mut branches = BinaryHeap::from([Reverse(Candidate(0.0, 0))]);
+        let mut checks = 0;
+        while let Some(Reverse(Candidate(bound, mut node))) = branches.pop() {
+            if best.len() == k && bound >= best[k - 1].1 {
+                break;
+            }
+            if self.max_checks > 0 && checks >= self.max_checks {
+                break;
+            }
+
+            // descend to the closest leaf and keep the other branches for later
+            loop {
+                match self.nodes[node] {
+                    KdNode::Split {
+                        dim,
+                        value,
+                        left,
+                        right,
+                    } => {
+                        let diff = query[dim] - value;
+                        let (near, far) = if diff < 0.0 {
+                            (left, right)
+                        } else {
+                            (right, left)
+                        };
+                        branches.push(Reverse(Candidate(bound.max(diff * diff), far)));
+                        node = near;
+                    }
+                    KdNode::Leaf { start, end } => {
+                        for &i in &self.order[start..end] {
+                            let distance = self
+                                .descriptor(i)
+                                .iter()
+                                .zip(query)
+                                .map(|(a, b)| (a - b) * (a - b))
+                                .sum::<f32>();
+                            push_nearest(&mut best, k, i, distance);
+                        }
+                        checks += end - start;
+                        break;
+                    }
+                }
+            }
+        }
+
+        best.into_iter().map(|(i, d)| (i, d.sqrt())).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::features::BruteForceIndex;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    #[test]
+    fn test_kdtree_index_exact() {
+        let mut rng = StdRng::seed_from_u64(3);
+        let descriptors = (0..1000)
+            .map(|_| std::array::from_fn(|_| rng.random_range(-1.0..1.0)))
+            .collect::<Vec<[f32; 4]>>();
+        let params = KdTreeParams {
+            leaf_size: 4,
+            max_checks: 0,
+        };
+        let index = KdTreeIndex::new(&descriptors, &params);
+        let exact = BruteForceIndex::<L2<4>>::new(descriptors);
+        assert_eq!(index.len(), 1000);
+
+        for _ in 0..20 {
+            let query = std::array::from_fn(|_| rng.random_range(-1.0..1.0));
+            let neighbors = index.knn(&query, 5);
+            let expected = exact.knn(&query, 5);
+            assert_eq!(
+                neighbors.iter().map(|n| n.0).collect::<Vec<_>>(),
+                expected.iter().map(|n| n.0).collect::<Vec<_>>()
+            );
+            for (n, e) in neighbors.iter().zip(&expected) {
+                assert!((n.1 - e.1).abs() < 1e-5);
+            }
+        }
+
+        let empty = KdTreeIndex::<4>::new(&[], &params);
+        assert!(empty.knn(&[0.0; 4], 1).is_empty());
+    }
+
+    #[test]
+    fn test_kdtree_index_approximate() {
+        // noisy copies of 128-D unit descriptors
+        let mut rng = StdRng::seed_from_u64(5);
+        let descriptors = (0..5000)
+            .map(|_| {
+                let d: [f32; 128] = std::array::from_fn(|_| rng.random_range(0.0..1.0));
+                let norm = d.iter().map(|v| v * v).sum::<f32>().sqrt();
+                d.map(|v| v / norm)
+            })
+            .collect::<Vec<_>>();
+        let index = KdTreeIndex::new(&descriptors, &KdTreeParams::default());
+
+        let mut found = 0;
+        for (i, descriptor) in descriptors.iter().enumerate().take(100) {
+            let query = descriptor.map(|v| v + rng.random_range(-0.005..0.005));
+            if index.knn(&query, 1).first().map(|n| n.0) == Some(i) {
+                found += 1;
+            }
+        }
+        assert!(found >= 90, "{found}");
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_kdtree_index_serde() -> Result<(), serde_json::Error> {
+        let descriptors = (0..50).map(|i| [i as f32, -i as f32]).collect::<Vec<_>>();
+        let index = KdTreeIndex::new(&descriptors, &KdTreeParams::default());
+        let json = serde_json::to_value(&index)?;
+
+        let loaded: KdTreeIndex<2> = serde_json::from_value(json.clone())?;
+        assert_eq!(loaded.knn(&[20.2, -20.2], 1), index.knn(&[20.2, -20.2], 1));
+
+        // a child, a leaf range and a descriptor out of range
+        let mut malformed = json.clone();
+        malformed["nodes"][0]["Split"]["right"] = 1000.into();
+        assert!(serde_json::from_value::<KdTreeIndex<2>>(malformed).is_err());
+
+        let mut malformed = json.clone();
+        let last = malformed["nodes"].as_array().map_or(0, |n| n.len() - 1);
+        malformed["nodes"][last]["Leaf"]["end"] = 1000.into();
+        assert!(serde_json::from_value::<KdTreeIndex<2>>(malformed).is_err());
+
+        let mut malformed = json.clone();
+        malformed["order"][0] = 50.into();
+        assert!(serde_json::from_value::<KdTreeIndex<2>>(malformed).is_err());
+
+        // the descriptors of a different dimension
+        assert!(serde_json::from_value::<KdTreeIndex<3>>(json).is_err());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,180 @@
+use std::collections::HashMap;
+
+use rand::{rngs::StdRng, seq::index::sample, SeedableRng};
+
+use super::{matcher::nearest, DescriptorIndex, Hamming, Metric};
+
+/// The parameters of a [`LshIndex`].
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LshParams {
+    /// The number of hash tables.
+    pub num_tables: usize,
+    /// The number of sampled bits of the key of a table, at most 64.
+    pub key_bits: usize,
+    /// The maximum number of flipped bits of the probed keys, 0 to probe only the exact key.
+    pub probe_radius: usize,
+    /// The seed of the random number generator of the sampled bits.
+    pub seed: u64,
+}
+
+impl Default for LshParams {
+    fn default() -> Self {
+        Self {
+            num_tables: 8,
+            key_bits: 16,
+            probe_radius: 1,
+            seed: 0,
+        }
+    }
+}
+
+// a hash table keyed by a subset of the bits of the descriptors
+#[derive(Debug, Clone)]
+struct LshTable {
+    bits: Vec<usize>,
+    buckets: HashMap<u64, Vec<usize>>,
+}
+
+impl LshTable {
+    fn key<const N: usize>(&self, descriptor: &[u8; N]) -> u64 {
+        self.bits.iter().enumerate().fold(0, |key, (i, &bit)| {
+            key | (((descriptor[bit / 8] >> (bit % 8)) & 1) as u64) << i
+        })
+    }
+}
+
+/// An approximate index of binary descriptors with multi-probe locality sensitive hashing.
+///
+/// Every table hashes the descriptors by a random subset of their bits, so close descriptors
+/// in hamming distance likely share a bucket. The query probes the buckets of its key and of
+/// the keys within `probe_radius` flipped bits in every table, as in the multi-probe LSH of
+/// Lv et al., and ranks the descriptors of the probed buckets by their exact distance.
+#[derive(Debug, Clone)]
+pub struct LshIndex<const N: usize> {
+    descriptors: Vec<[u8; N]>,
+    tables: Vec<LshTable>,
+    probe_radius: usize,
+}
+
+impl<const N: usize> LshIndex<N> {
+    /// Create an index of binary descriptors.
+    ///
+    /// # Arguments
+    ///
+    /// * `descriptors` - The descriptors of the index.
+    /// * `params` - The parameters of the hash tables.
+    pub fn new(descriptors: Vec<[u8; N]>, params: &LshParams) -> Self {
+        let mut rng = StdRng::seed_from_u64(params.seed);
+        let key_bits = params.key_bits.min(64).min(8 * N);
+
+        let tables = (0..params.num_tables)
+            .map(|_| {
+                let mut table = LshTable {
+                    bits: sample(&mut rng, 8 * N, key_bits).into_vec(),
+                    buckets: HashMap::new(),
+                };
+                for (i, descriptor) in descriptors.iter().enumerate() {
+                    let key = table.key(descriptor);
+                    table.buckets.entry(key).or_default().push(i);
+                }
+                table
+            })
+            .collect();
+
+        Self {
+            descriptors,
+            tables,
+            probe_radius: params.probe_radius,
+        }
+    }
+
+    /// The descriptors of the index.
+    pub fn descriptors(&self) -> &[[u8; N]] {
+        &self.descriptors
+    }
+}
+
+impl<const N: usize> DescriptorIndex<Hamming<N>> for LshIndex<N> {
+    fn len(&self) -> usize {
+        self.descriptors.len()
+    }
+
+    fn knn(&self, query: &[u8; N], k: usize) -> Vec<(usize, f32)> {
+        let mut candidates = Vec::new();
+        for table in &self.tables {
+            let key = table.key(query);
+            for probe in probe_keys(key, table.bits.len(), self.probe_radius) {
+                if let Some(bucket) = table.buckets.get(&probe) {
+                    candidates.extend_from_slice(bucket);
+                }
+            }
+        }
+        candidates.sort_unstable();
+        candidates.dedup();
+
+        nearest(
+            candidates
+                .into_iter()
+                .map(|i| (i, Hamming::<N>::distance(query, &self.descriptors[i]))),
+            k,
+        )
+    }
+}
+
+// the keys within `radius` flipped bits of a key of `num_bits` bits, the key first
+fn probe_keys(key: u64, num_bits: usize, radius: usize) -> Vec<u64> {
+    let mut keys = vec![key];
+    let mut frontier = vec![(key, 0)];
+    for _ in 0..radius.min(num_bits) {
+        let mut next = Vec::new();
+        for &(probe, first) in &frontier {
+            // flip the bits in increasing order to enumerate every subset once
+            for bit in first..num_bits {
+                next.push((probe ^ (1 << bit), bit + 1));
+            }
+        }
+        keys.extend(next.iter().map(|&(probe, _)| probe));
+        frontier = next;
+    }
+    keys
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::features::BruteForceIndex;
+    use rand::Rng;
+
+    #[test]
+    fn test_probe_keys() {
+        assert_eq!(probe_keys(0b01, 2, 0), vec![0b01]);
+        assert_eq!(probe_keys(0b01, 2, 1), vec![0b01, 0b00, 0b11]);
+        assert_eq!(probe_keys(0b01, 2, 5), vec![0b01, 0b00, 0b11, 0b10]);
+    }
+
+    #[test]
+    fn test_lsh_index() {
+        let mut rng = StdRng::seed_from_u64(42);
+        let descriptors = (0..2000).map(|_| rng.random()).collect::<Vec<[u8; 32]>>();
+        let index = LshIndex::new(descriptors.clone(), &LshParams::default());
+        let exact = BruteForceIndex::<Hamming<32>>::new(descriptors.clone());
+        assert_eq!(index.len(), 2000);
+
+        // the noisy copies of the descriptors are found
+        let mut found = 0;
+        for (i, descriptor) in descriptors.iter().enumerate().take(100) {
+            let mut query = *descriptor;
+            for _ in 0..8 {
+                let bit = rng.random_range(0..256);
+                query[bit / 8] ^= 1 << (bit % 8);
+            }
+            let neighbors = index.knn(&query, 1);
+            if neighbors.first().map(|n| n.0) == Some(i) {
+                found += 1;
+                assert_eq!(neighbors, exact.knn(&query, 1));
+            }
+        }
+        assert!(found >= 95, "{found}");
+    }
+}
@@ -0,0 +1,278 @@
+use std::marker::PhantomData;
+
+use super::Metric;
+use crate::parallel::prelude::*;
+
+/// A match between a query descriptor and a descriptor of an index.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DescriptorMatch {
+    /// The index of the query descriptor.
+    pub query: usize,
+    /// The index of the descriptor in the index.
+    pub train: usize,
+    /// The distance between the descriptors.
+    pub distance: f32,
+}
+
+/// A searchable set of descriptors.
+pub trait DescriptorIndex<M: Metric> {
+    /// The number of descriptors in the index.
+    fn len(&self) -> usize;
+
+    /// Whether the index has no descriptors.
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Find the nearest neighbors of a query descriptor.
+    ///
+    /// The approximate indices may miss some of the exact neighbors.
+    ///
+    /// # Arguments
+    ///
+    /// * `query` - The query descriptor.
+    /// * `k` - The maximum number of neighbors.
+    ///
+    /// # Returns
+    ///
+    /// The indices of the neighbors and their distances, sorted by increasing distance.
+    fn knn(&self, query: &M::Descriptor, k: usize) -> Vec<(usize, f32)>;
+}
+
+/// An exact index comparing the query to every descriptor.
+#[derive(Debug, Clone)]
+pub struct BruteForceIndex<M: Metric> {
+    descriptors: Vec<M::Descriptor>,
+}
+
+impl<M: Metric> BruteForceIndex<M> {
+    /// Create an index of descriptors.
+    pub fn new(descriptors: Vec<M::Descriptor>) -> Self {
+        Self { descriptors }
+    }
+
+    /// The descriptors of the index.
+    pub fn descriptors(&self) -> &[M::Descriptor] {
+        &self.descriptors
+    }
+}
+
+impl<M: Metric> DescriptorIndex<M> for BruteForceIndex<M> {
+    fn len(&self) -> usize {
+        self.descriptors.len()
+    }
+
+    fn knn(&self, query: &M::Descriptor, k: usize) -> Vec<(usize, f32)> {
+        nearest(
+            self.descriptors
+                .iter()
+                .enumerate()
+                .map(|(i, descriptor)| (i, M::distance(query, descriptor))),
+            k,
+        )
+    }
+}
+
+// keep the k closest candidates sorted by increasing distance, ties by increasing index
+pub(crate) fn nearest(
+    candidates: impl Iterator<Item = (usize, f32)>,
+    k: usize,
+) -> Vec<(usize, f32)> {
+    let mut best = Vec::with_capacity(k + 1);
+    for (i, distance) in candidates {
+        push_nearest(&mut best, k, i, distance);
+    }
+    best
+}
+
+// insert a candidate in the sorted list of the k closest candidates
+pub(crate) fn push_nearest(best: &mut Vec<(usize, f32)>, k: usize, i: usize, distance: f32) {
+    if k == 0 || (best.len() == k && distance >= best[k - 1].1) {
+        return;
+    }
+    let at = best.partition_point(|&(_, d)| d <= distance);
+    best.insert(at, (i, distance));
+    best.truncate(k);
+}
+
+/// The parameters of a [`Matcher`].
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MatcherParams {
+    /// The maximum ratio between the distances of the best and the second best neighbors,
+    /// i.e. the Lowe ratio test, or `None` to keep the ambiguous matches.
+    pub ratio: Option<f32>,
+    /// The maximum distance of a match, or `None` for no limit.
+    pub max_distance: Option<f32>,
+}
+
+impl Default for MatcherParams {
+    fn default() -> Self {
+        Self {
+            ratio: Some(0.8),
+            max_distance: None,
+        }
+    }
+}
+
+/// A matcher of descriptors against an index, generic over the metric and the index.
+///
+/// The exact [`BruteForceIndex`] suits small sets of descriptors, the approximate
+/// [`LshIndex`](super::LshIndex) and [`HnswIndex`](super::HnswIndex) scale to tens of
+/// thousands of descriptors.
+///
+/// # Example
+///
+/// ```
+/// use kornia_imgproc::features::{BruteForceIndex, Hamming, Matcher, MatcherParams};
+///
+/// let train = vec![[0b1111_0000u8], [0b0000_1111]];
+/// let matcher = Matcher::<Hamming<1>, _>::new(BruteForceIndex::new(train), MatcherParams::default());
+///
+/// let matches = matcher.match_descriptors(&[[0b0000_0111]]);
+/// assert_eq!((matches[0].query, matches[0].train, matches[0].distance), (0, 1, 1.0));
+/// ```
+pub struct Matcher<M: Metric, I: DescriptorIndex<M>> {
+    index: I,
+    params: MatcherParams,
+    _metric: PhantomData<fn() -> M>,
+}
+
+impl<M: Metric, I: DescriptorIndex<M> + Sync> Matcher<M, I> {
+    /// Create a matcher of an index.
+    ///
+    /// # Arguments
+    ///
+    /// * `index` - The index of the train descriptors.
+    /// * `params` - The parameters of the matcher.
+    pub fn new(index: I, params: MatcherParams) -> Self {
+        Self {
+            index,
+            params,
+            _metric: PhantomData,
+        }
+    }
+
+    /// The index of the train descriptors.
+    pub fn index(&self) -> &I {
+        &self.index
+    }
+
+    /// The parameters of the matcher.
+    pub fn params(&self) -> &MatcherParams {
+        &self.params
+    }
+
+    /// Match the query descriptors to their nearest neighbors in the index.
+    ///
+    /// The queries are matched in parallel.
+    ///
+    /// # Arguments
+    ///
+    /// * `query` - The query descriptors.
+    ///
+    /// # Returns
+    ///
+    /// The matches that pass the ratio test and the maximum distance, sorted by query.
+    pub fn match_descriptors(&self, query: &[M::Descriptor]) -> Vec<DescriptorMatch> {
+        let k = if self.params.ratio.is_some() { 2 } else { 1 };
+        query
+            .par_iter()
+            .enumerate()
+            .filter_map(|(i, descriptor)| {
+                let neighbors = self.index.knn(descriptor, k);
+                let &(train, distance) = neighbors.first()?;
+                if self.params.max_distance.is_some_and(|max| distance > max) {
+                    return None;
+                }
+                if let (Some(ratio), Some(&(_, second))) = (self.params.ratio, neighbors.get(1)) {
+                    if distance > ratio * second {
+                        return None;
+                    }
+                }
+                Some(DescriptorMatch {
+                    query: i,
+                    train,
+                    distance,
+                })
+            })
+            .collect()
+    }
+
+    /// Find the nearest neighbors of every query descriptor in parallel.
+    ///
+    /// # Arguments
+    ///
+    /// * `query` - The query descriptors.
+    /// * `k` - The maximum number of neighbors of a query.
+    ///
+    /// # Returns
+    ///
+    /// The neighbors of every query, sorted by increasing distance.
+    pub fn knn_match(&self, query: &[M::Descriptor], k: usize) -> Vec<Vec<DescriptorMatch>> {
+        query
+            .par_iter()
+            .enumerate()
+            .map(|(i, descriptor)| {
+                self.index
+                    .knn(descriptor, k)
+                    .into_iter()
+                    .map(|(train, distance)| DescriptorMatch {
+                        query: i,
+                        train,
+                        distance,
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::features::{Cosine, L2};
+
+    #[test]
+    fn test_brute_force_matcher() {
+        let train = vec![[0.0, 0.0], [1.0, 0.0], [0.0, 1.0], [1.1, 0.0]];
+        let index = BruteForceIndex::<L2<2>>::new(train.clone());
+        let neighbors = index.knn(&[0.9, 0.0], 2);
+        assert_eq!(
+            neighbors.iter().map(|n| n.0).collect::<Vec<_>>(),
+            vec![1, 3]
+        );
+        assert!((neighbors[0].1 - 0.1).abs() < 1e-6);
+        assert!((neighbors[1].1 - 0.2).abs() < 1e-6);
+        assert!(index.knn(&[0.9, 0.0], 0).is_empty());
+
+        let query = [[0.0, 0.1], [1.05, 0.0], [0.0, 5.0]];
+        let params = MatcherParams {
+            ratio: Some(0.8),
+            max_distance: Some(2.0),
+        };
+        let matcher = Matcher::new(index, params);
+
+        // the second query is ambiguous and the third is too far
+        let matches = matcher.match_descriptors(&query);
+        assert_eq!(matches.len(), 1);
+        assert_eq!((matches[0].query, matches[0].train), (0, 0));
+
+        let neighbors = matcher.knn_match(&query, 3);
+        assert_eq!(neighbors.len(), 3);
+        assert_eq!(neighbors[1].len(), 3);
+        assert_eq!(neighbors[2][0].train, 2);
+
+        // the cosine distance ignores the norms
+        let matcher = Matcher::new(
+            BruteForceIndex::<Cosine<2>>::new(train[1..].to_vec()),
+            MatcherParams {
+                ratio: None,
+                max_distance: None,
+            },
+        );
+        let matches = matcher.match_descriptors(&[[0.0, 7.0]]);
+        assert_eq!(matches[0].train, 1);
+    }
+}
@@ -0,0 +1,157 @@
+use super::descriptor_distance;
+
+/// A match between the features of two images.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FeatureMatch {
+    /// The index of the feature in the first image.
+    pub index1: usize,
+    /// The index of the feature in the second image.
+    pub index2: usize,
+    /// The distance between the descriptors.
+    pub distance: u32,
+}
+
+/// Match binary descriptors between two views constrained by their epipolar geometry.
+///
+/// A feature of the second image is a candidate for a feature of the first image only if
+/// it lies within `max_dist` pixels of its epipolar line `F x1`, and the feature of the
+/// first image lies within `max_dist` pixels of the epipolar line `F^T x2`. The features
+/// are matched to the candidate with the closest descriptor, and only the mutual nearest
+/// neighbours are kept.
+///
+/// # Arguments
+///
+/// * `desc1` - The descriptors of the first image.
+/// * `kps1` - The keypoints of the first image as (x, y).
+/// * `desc2` - The descriptors of the second image.
+/// * `kps2` - The keypoints of the second image as (x, y).
+/// * `fundamental` - The fundamental matrix in row-major order, with `x2^T F x1 = 0`.
+/// * `max_dist` - The maximum distance in pixels to the epipolar lines.
+///
+/// # Returns
+///
+/// The matches sorted by the index of the first feature.
+///
+/// # Example
+///
+/// ```
+/// use kornia_imgproc::features::match_epipolar;
+///
+/// // a pure translation along x, where the epipolar lines are the rows
+/// let fundamental = [0.0, 0.0, 0.0, 0.0, 0.0, -1.0, 0.0, 1.0, 0.0];
+/// let desc = [[0b1111_0000u8], [0b0000_1111]];
+/// let kps1 = [[10.0, 5.0], [20.0, 30.0]];
+///
+/// // the descriptors are swapped in the second image, but only the rows can match
+/// let kps2 = [[15.0, 30.0], [25.0, 5.0]];
+/// let matches = match_epipolar(&desc, &kps1, &desc, &kps2, &fundamental, 1.0);
+/// assert_eq!((matches[0].index1, matches[0].index2, matches[0].distance), (0, 1, 8));
+/// ```
+pub fn match_epipolar<const N: usize>(
+    desc1: &[[u8; N]],
+    kps1: &[[f32; 2]],
+    desc2: &[[u8; N]],
+    kps2: &[[f32; 2]],
+    fundamental: &[f32; 9],
+    max_dist: f32,
+) -> Vec<FeatureMatch> {
+    let f = fundamental;
+    let transposed = [f[0], f[3], f[6], f[1], f[4], f[7], f[2], f[5], f[8]];
+
+    // the epipolar lines of the keypoints in the other image
+    let lines1 = kps1.iter().map(|p| epipolar_line(f, p)).collect::<Vec<_>>();
+    let lines2 = kps2
+        .iter()
+        .map(|p| epipolar_line(&transposed, p))
+        .collect::<Vec<_>>();
+    let in_band = |i: usize, j: usize| {
+        line_distance(&lines1[i], &kps2[j]) <= max_dist
+            && line_distance(&lines2[j], &kps1[i]) <= max_dist
+    };
+
+    // the closest descriptor among the candidates in the band
+    let mut best1 = vec![None::<(usize, u32)>; desc1.len()];
+    let mut best2 = vec![None::<(usize, u32)>; desc2.len()];
+    for (i, d1) in desc1.iter().enumerate().take(kps1.len()) {
+        for (j, d2) in desc2.iter().enumerate().take(kps2.len()) {
+            if !in_band(i, j) {
+                continue;
+            }
+            let distance = descriptor_distance(d1, d2);
+            if best1[i].map_or(true, |(_, d)| distance < d) {
+                best1[i] = Some((j, distance));
+            }
+            if best2[j].map_or(true, |(_, d)| distance < d) {
+                best2[j] = Some((i, distance));
+            }
+        }
+    }
+
+    best1
+        .iter()
+        .enumerate()
+        .filter_map(|(i, best)| {
+            let (j, distance) = (*best)?;
+            (best2[j]?.0 == i).then_some(FeatureMatch {
+                index1: i,
+                index2: j,
+                distance,
+            })
+        })
+        .collect()
+}
+
+// the line F * (x, y, 1) normalized so that the distance is |a x + b y + c|
+fn epipolar_line(f: &[f32; 9], point: &[f32; 2]) -> [f32; 3] {
+    let line = [0, 1, 2].map(|r| f[3 * r] * point[0] + f[3 * r + 1] * point[1] + f[3 * r + 2]);
+    let norm = line[0].hypot(line[1]);
+    if norm > f32::EPSILON {
+        line.map(|v| v / norm)
+    } else {
+        // the epipole has no line and matches nothing
+        [0.0, 0.0, f32::INFINITY]
+    }
+}
+
+fn line_distance(line: &[f32; 3], point: &[f32; 2]) -> f32 {
+    (line[0] * point[0] + line[1] * point[1] + line[2]).abs()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_match_epipolar() {
+        // a camera translated along x with an identity calibration: F = [t]x
+        let fundamental = [0.0, 0.0, 0.0, 0.0, 0.0, -1.0, 0.0, 1.0, 0.0];
+
+        // the same descriptor appears twice in the second image, once off the epipolar line
+        let desc1 = [[0x0fu8, 0xf0], [0xaa, 0x55], [0x00, 0xff]];
+        let kps1 = [[10.0, 10.0], [40.0, 20.0], [70.0, 30.0]];
+        let desc2 = [[0x0f, 0xf0], [0x0f, 0xf0], [0xab, 0x55], [0x00, 0xff]];
+        let kps2 = [[5.0, 40.0], [4.0, 10.5], [30.0, 20.0], [60.0, 90.0]];
+
+        let matches = match_epipolar(&desc1, &kps1, &desc2, &kps2, &fundamental, 1.0);
+        assert_eq!(
+            matches,
+            vec![
+                FeatureMatch {
+                    index1: 0,
+                    index2: 1,
+                    distance: 0
+                },
+                FeatureMatch {
+                    index1: 1,
+                    index2: 2,
+                    distance: 1
+                },
+            ]
+        );
+
+        // a wider band also admits the match far from the epipolar line
+        let matches = match_epipolar(&desc1, &kps1, &desc2, &kps2, &fundamental, 100.0);
+        assert_eq!(matches.len(), 3);
+    }
+}
@@ -1,5 +1,97 @@
+#[cfg(feature = "std")]
 mod responses;
+#[cfg(feature = "std")]
 pub use responses::*;
 
 mod fast;
 pub use fast::*;
+
+#[cfg(feature = "std")]
+mod structure;
+#[cfg(feature = "std")]
+pub use structure::*;
+
+#[cfg(feature = "std")]
+mod scale_space;
+#[cfg(feature = "std")]
+pub use scale_space::*;
+
+#[cfg(feature = "std")]
+mod affine_shape;
+#[cfg(feature = "std")]
+pub use affine_shape::*;
+
+#[cfg(feature = "std")]
+mod patches;
+#[cfg(feature = "std")]
+pub use patches::*;
+
+#[cfg(feature = "std")]
+mod detector;
+#[cfg(feature = "std")]
+pub use detector::*;
+
+#[cfg(feature = "std")]
+mod descriptor;
+#[cfg(feature = "std")]
+pub use descriptor::*;
+
+#[cfg(feature = "std")]
+mod matcher;
+#[cfg(feature = "std")]
+pub use matcher::*;
+
+#[cfg(feature = "std")]
+mod lsh;
+#[cfg(feature = "std")]
+pub use lsh::*;
+
+#[cfg(feature = "std")]
+mod hnsw;
+#[cfg(feature = "std")]
+pub use hnsw::*;
+
+#[cfg(feature = "std")]
+mod kdtree;
+#[cfg(feature = "std")]
+pub use kdtree::*;
+
+#[cfg(feature = "std")]
+mod gms;
+#[cfg(feature = "std")]
+pub use gms::*;
+
+#[cfg(feature = "std")]
+mod nms;
+#[cfg(feature = "std")]
+pub use nms::*;
+
+#[cfg(feature = "std")]
+mod threshold;
+#[cfg(feature = "std")]
+pub use threshold::*;
+
+#[cfg(feature = "std")]
+mod evaluation;
+#[cfg(feature = "std")]
+pub use evaluation::*;
+
+#[cfg(feature = "std")]
+mod canny;
+#[cfg(feature = "std")]
+pub use canny::*;
+
+#[cfg(feature = "std")]
+mod swt;
+#[cfg(feature = "std")]
+pub use swt::*;
+
+#[cfg(feature = "std")]
+mod bow;
+#[cfg(feature = "std")]
+pub use bow::*;
+
+#[cfg(feature = "std")]
+mod matching;
+#[cfg(feature = "std")]
+pub use matching::*;
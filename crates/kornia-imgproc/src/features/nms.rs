@@ -0,0 +1,72 @@
+use crate::parallel::prelude::*;
+use kornia_image::Image;
+
+/// Find the local maxima of a response image in a 3x3 neighborhood.
+///
+/// A pixel is kept if its response is above the threshold, greater than the neighbors
+/// before it and not smaller than the neighbors after it in row-major order, so a plateau
+/// keeps only its first pixel. The border pixels are never kept.
+///
+/// # Arguments
+///
+/// * `src` - The response image, e.g. from [`super::gftt_response`].
+/// * `threshold` - The minimum response of a local maximum.
+///
+/// # Returns
+///
+/// The coordinates `[x, y]` of the local maxima in row-major order.
+pub fn non_max_suppression(src: &Image<f32, 1>, threshold: f32) -> Vec<[i32; 2]> {
+    let (cols, rows) = (src.cols(), src.rows());
+    if cols < 3 || rows < 3 {
+        return Vec::new();
+    }
+    let data = src.as_slice();
+
+    (1..rows - 1)
+        .into_par_iter()
+        .flat_map_iter(|y| {
+            (1..cols - 1).filter_map(move |x| {
+                let value = data[y * cols + x];
+                if value <= threshold {
+                    return None;
+                }
+                let is_max = (0..9).filter(|&i| i != 4).all(|i| {
+                    let neighbor = data[(y + i / 3 - 1) * cols + x + i % 3 - 1];
+                    if i < 4 {
+                        value > neighbor
+                    } else {
+                        value >= neighbor
+                    }
+                });
+                is_max.then_some([x as i32, y as i32])
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use kornia_image::ImageError;
+
+    #[test]
+    fn test_non_max_suppression() -> Result<(), ImageError> {
+        #[rustfmt::skip]
+        let src = Image::from_size_slice(
+            [6, 5].into(),
+            &[
+                1.0, 0.0, 0.0, 0.0, 0.0, 9.0,
+                0.0, 2.0, 1.0, 0.0, 0.0, 0.0,
+                0.0, 1.0, 0.0, 0.0, 3.0, 3.0,
+                0.0, 0.0, 0.0, 0.5, 3.0, 0.0,
+                0.0, 0.0, 0.0, 0.0, 0.0, 0.0,
+            ],
+        )?;
+
+        // the border and the second pixel of the plateau are suppressed
+        assert_eq!(non_max_suppression(&src, 0.0), vec![[1, 1], [4, 2]]);
+        assert_eq!(non_max_suppression(&src, 2.5), vec![[4, 2]]);
+
+        Ok(())
+    }
+}
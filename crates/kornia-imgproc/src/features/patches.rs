@@ -0,0 +1,126 @@
+use kornia_image::{Image, ImageError};
+use kornia_tensor::{CpuAllocator, Tensor3};
+
+use super::affine_shape::{sample_frame, AffineFrame};
+use crate::parallel::prelude::*;
+
+/// An oriented keypoint with a scale.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Keypoint {
+    /// The position `[x, y]` in pixels.
+    pub position: [f32; 2],
+    /// The scale in pixels, e.g. the sigma of the detection.
+    pub scale: f32,
+    /// The orientation in radians, counter-clockwise from the x axis in the image.
+    pub orientation: f32,
+}
+
+impl Keypoint {
+    /// Create a keypoint.
+    pub fn new(position: [f32; 2], scale: f32, orientation: f32) -> Self {
+        Self {
+            position,
+            scale,
+            orientation,
+        }
+    }
+
+    /// The frame of the square region of the keypoint with the half width `scale_factor`
+    /// times the scale, rotated by the orientation.
+    pub fn frame(&self, scale_factor: f32) -> AffineFrame {
+        let radius = scale_factor * self.scale;
+        let (sin, cos) = self.orientation.sin_cos();
+        AffineFrame {
+            center: self.position,
+            shape: [[radius * cos, -radius * sin], [radius * sin, radius * cos]],
+        }
+    }
+}
+
+/// Extract the oriented and scaled patches of keypoints.
+///
+/// The patch of a keypoint covers the square of half width `scale_factor * scale` centered at
+/// the keypoint, with its x axis along the orientation, sampled with bilinear interpolation.
+/// The pixels sampled outside the image are zero. The patches are extracted in parallel.
+///
+/// # Arguments
+///
+/// * `src` - The source image with shape (H, W).
+/// * `keypoints` - The keypoints.
+/// * `patch_size` - The width and height of the patches in pixels.
+/// * `scale_factor` - The half width of the patches in units of the scale of the keypoints.
+///
+/// # Returns
+///
+/// The patches with shape (N, P, P).
+pub fn extract_patches(
+    src: &Image<f32, 1>,
+    keypoints: &[Keypoint],
+    patch_size: usize,
+    scale_factor: f32,
+) -> Result<Tensor3<f32, CpuAllocator>, ImageError> {
+    profile_scope!("extract_patches");
+    let mut data = vec![0.0; keypoints.len() * patch_size * patch_size];
+
+    if patch_size > 0 {
+        data.par_chunks_exact_mut(patch_size * patch_size)
+            .zip(keypoints.par_iter())
+            .for_each(|(patch, keypoint)| {
+                sample_frame(src, &keypoint.frame(scale_factor), patch_size, patch);
+            });
+    }
+
+    Ok(Tensor3::from_shape_vec(
+        [keypoints.len(), patch_size, patch_size],
+        data,
+        CpuAllocator,
+    )?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_patches() -> Result<(), ImageError> {
+        // a horizontal ramp
+        let src = Image::new(
+            [16, 12].into(),
+            (0..16 * 12).map(|i| (i % 16) as f32).collect(),
+        )?;
+
+        let keypoints = [
+            Keypoint::new([8.0, 6.0], 1.0, 0.0),
+            Keypoint::new([8.0, 6.0], 2.0, 0.0),
+            Keypoint::new([8.0, 6.0], 1.0, std::f32::consts::FRAC_PI_2),
+        ];
+        let patches = extract_patches(&src, &keypoints, 3, 2.0)?;
+        assert_eq!(patches.shape, [3, 3, 3]);
+
+        // the patch of half width 2 is sampled every 2 pixels
+        #[rustfmt::skip]
+        assert_eq!(&patches.as_slice()[..9], &[
+            6.0, 8.0, 10.0,
+            6.0, 8.0, 10.0,
+            6.0, 8.0, 10.0,
+        ]);
+        assert_eq!(&patches.as_slice()[9..12], &[4.0, 8.0, 12.0]);
+
+        // the x axis of the rotated patch is along the y axis of the image
+        let rotated = &patches.as_slice()[18..];
+        let expected = [10.0, 8.0, 6.0].into_iter().flat_map(|v| [v; 3]);
+        for (value, expected) in rotated.iter().zip(expected) {
+            assert!((value - expected).abs() < 1e-4, "{rotated:?}");
+        }
+
+        // the pixels outside the image are zero
+        let outside = extract_patches(&src, &[Keypoint::new([0.0, 0.0], 1.0, 0.0)], 3, 2.0)?;
+        assert_eq!(outside.as_slice()[0], 0.0);
+        assert_eq!(outside.as_slice()[3], 0.0);
+        assert_eq!(outside.as_slice()[8], 2.0);
+
+        assert_eq!(extract_patches(&src, &[], 3, 2.0)?.shape, [0, 3, 3]);
+
+        Ok(())
+    }
+}
@@ -1,28 +1,18 @@
-use crate::filter::gaussian_blur;
+use super::eigenvalues_2x2;
+use crate::filter::{gaussian_blur_sigma, kernels};
+use crate::parallel::prelude::*;
 use kornia_image::{Image, ImageError, ImageSize};
-use rayon::prelude::*;
 
-/// Method to calculate gradient for feature response
-#[derive(Default)]
-pub enum GradsMode {
-    /// Sobel operators
-    #[default]
-    Sobel,
-    /// Finite difference
-    Diff,
-}
-
-fn _get_kernel_size(sigma: f32) -> usize {
-    let mut ksize = (2.0 * 4.0 * sigma + 1.0) as usize;
-
-    // matches OpenCV, but may cause padding problem for small images
-    // PyTorch does not allow to pad more than original size.
-    // Therefore there is a hack in forward function
-    if ksize % 2 == 0 {
-        ksize += 1;
-    }
+pub use crate::filter::kernels::{GradsMode, KernelSizePolicy};
 
-    ksize
+/// Method to fuse the structure tensors of the channels of a color image
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ChannelFusion {
+    /// Sum the structure tensors of the channels, as in the color structure tensor
+    #[default]
+    Sum,
+    /// Take the maximum of the responses of the channels
+    Max,
 }
 
 /// Compute the Hessian response of an image.
@@ -32,7 +22,13 @@ fn _get_kernel_size(sigma: f32) -> usize {
 /// Args:
 ///     src: The source image with shape (H, W).
 ///     dst: The destination image with shape (H, W).
-pub fn hessian_response(src: &Image<f32, 1>, dst: &mut Image<f32, 1>) -> Result<(), ImageError> {
+///     mode: The stencil of the second order derivatives.
+pub fn hessian_response(
+    src: &Image<f32, 1>,
+    dst: &mut Image<f32, 1>,
+    mode: GradsMode,
+) -> Result<(), ImageError> {
+    profile_scope!("hessian_response");
     if src.size() != dst.size() {
         return Err(ImageError::InvalidImageSize(
             src.cols(),
@@ -42,6 +38,7 @@ pub fn hessian_response(src: &Image<f32, 1>, dst: &mut Image<f32, 1>) -> Result<
         ));
     }
 
+    let [kernel_xx, kernel_yy, kernel_xy] = kernels::gradient_kernel3_2nd_order(mode);
     let src_data = src.as_slice();
 
     dst.as_slice_mut()
@@ -53,8 +50,6 @@ pub fn hessian_response(src: &Image<f32, 1>, dst: &mut Image<f32, 1>) -> Result<
                 return;
             }
 
-            let row_offset = row_idx * src.cols();
-
             row_chunk
                 .iter_mut()
                 .enumerate()
@@ -64,23 +59,12 @@ pub fn hessian_response(src: &Image<f32, 1>, dst: &mut Image<f32, 1>) -> Result<
                         return;
                     }
 
-                    let current_idx = row_offset + col_idx;
-                    let prev_row_idx = current_idx - src.cols();
-                    let next_row_idx = current_idx + src.cols();
-
-                    let v11 = src_data[prev_row_idx - 1];
-                    let v12 = src_data[prev_row_idx];
-                    let v13 = src_data[prev_row_idx + 1];
-                    let v21 = src_data[current_idx - 1];
-                    let v22 = src_data[current_idx];
-                    let v23 = src_data[current_idx + 1];
-                    let v31 = src_data[next_row_idx - 1];
-                    let v32 = src_data[next_row_idx];
-                    let v33 = src_data[next_row_idx + 1];
-
-                    let dxx = v21 - 2.0 * v22 + v23;
-                    let dyy = v12 - 2.0 * v22 + v32;
-                    let dxy = 0.25 * (v31 - v11 - v33 + v13);
+                    let v = |dr: usize, dc: usize| {
+                        src_data[(row_idx + dr - 1) * src.cols() + col_idx + dc - 1]
+                    };
+                    let dxx = convolve3(&v, &kernel_xx);
+                    let dyy = convolve3(&v, &kernel_yy);
+                    let dxy = convolve3(&v, &kernel_xy);
 
                     let det = dxx * dyy - dxy * dxy;
 
@@ -129,6 +113,7 @@ impl HarrisResponse {
         src: &Image<f32, 1>,
         dst: &mut Image<f32, 1>,
     ) -> Result<(), ImageError> {
+        profile_scope!("harris_response");
         if src.size() != self.image_size {
             return Err(ImageError::InvalidImageSize(
                 src.size().width,
@@ -274,6 +259,222 @@ impl HarrisResponse {
 
         Ok(())
     }
+
+    /// Computes the harris response of a color image.
+    ///
+    /// The structure tensors of the channels are fused so that the corners between the
+    /// colors of the same intensity are detected, which the grayscale conversion discards.
+    ///
+    /// Args:
+    ///     src: The source image with shape (H, W, C).
+    ///     dst: The destination image with shape (H, W).
+    ///     fusion: The method to fuse the channels.
+    pub fn compute_color<const C: usize>(
+        &mut self,
+        src: &Image<f32, C>,
+        dst: &mut Image<f32, 1>,
+        fusion: ChannelFusion,
+    ) -> Result<(), ImageError> {
+        profile_scope!("harris_response_color");
+        if src.size() != self.image_size {
+            return Err(ImageError::InvalidImageSize(
+                src.size().width,
+                src.size().height,
+                self.image_size.width,
+                self.image_size.height,
+            ));
+        }
+
+        let k = self.k;
+        color_response(src, dst, fusion, |[m11, m22, m12]| {
+            let trace = m11 + m22;
+            f32::max(0.0, m11 * m22 - m12 * m12 - k * trace * trace)
+        })
+    }
+}
+
+/// Compute the Shi-Tomasi (good features to track) response of an image.
+///
+/// The response is the minimum eigenvalue of the structure tensor, computed from the
+/// gradients summed over a 3x3 window. Only the interior pixels of `dst` are written.
+///
+/// # Arguments
+///
+/// * `src` - The source image with shape (H, W).
+/// * `dst` - The destination image with shape (H, W).
+/// * `mode` - The stencil of the gradients.
+pub fn gftt_response(
+    src: &Image<f32, 1>,
+    dst: &mut Image<f32, 1>,
+    mode: GradsMode,
+) -> Result<(), ImageError> {
+    profile_scope!("gftt_response");
+    if src.size() != dst.size() {
+        return Err(ImageError::InvalidImageSize(
+            src.cols(),
+            src.rows(),
+            dst.cols(),
+            dst.rows(),
+        ));
+    }
+
+    let (rows, cols) = (src.rows(), src.cols());
+    if rows < 3 || cols < 3 {
+        return Ok(());
+    }
+    let (kernel_x, kernel_y) = kernels::gradient_kernel3(mode);
+    let src_data = src.as_slice();
+
+    // products of the gradients of the interior pixels, zero at the border
+    let mut moments = vec![[0.0f32; 3]; src_data.len()];
+    moments
+        .par_chunks_exact_mut(cols)
+        .enumerate()
+        .skip(1)
+        .take(rows - 2)
+        .for_each(|(r, row)| {
+            for (c, moment) in row.iter_mut().enumerate().take(cols - 1).skip(1) {
+                let v = |dr: usize, dc: usize| src_data[(r + dr - 1) * cols + c + dc - 1];
+                let dx = convolve3(&v, &kernel_x);
+                let dy = convolve3(&v, &kernel_y);
+                *moment = [dx * dx, dy * dy, dx * dy];
+            }
+        });
+
+    dst.as_slice_mut()
+        .par_chunks_exact_mut(cols)
+        .enumerate()
+        .skip(1)
+        .take(rows - 2)
+        .for_each(|(r, row)| {
+            for (c, dst_pixel) in row.iter_mut().enumerate().take(cols - 1).skip(1) {
+                let mut m = [0.0f32; 3];
+                for dr in 0..3 {
+                    for dc in 0..3 {
+                        let p = moments[(r + dr - 1) * cols + c + dc - 1];
+                        m.iter_mut().zip(p).for_each(|(acc, x)| *acc += x);
+                    }
+                }
+
+                // the smallest eigenvalue of [[m0, m2], [m2, m1]]
+                *dst_pixel = eigenvalues_2x2(m[0], m[1], m[2]).1;
+            }
+        });
+
+    Ok(())
+}
+
+/// Compute the Shi-Tomasi (good features to track) response of a color image.
+///
+/// The structure tensors of the channels are computed as in [`gftt_response`] and fused,
+/// so that the corners between the colors of the same intensity are detected. Only the
+/// interior pixels of `dst` are written.
+///
+/// # Arguments
+///
+/// * `src` - The source image with shape (H, W, C).
+/// * `dst` - The destination image with shape (H, W).
+/// * `fusion` - The method to fuse the channels.
+pub fn gftt_response_color<const C: usize>(
+    src: &Image<f32, C>,
+    dst: &mut Image<f32, 1>,
+    fusion: ChannelFusion,
+) -> Result<(), ImageError> {
+    profile_scope!("gftt_response_color");
+    color_response(src, dst, fusion, |[m11, m22, m12]| {
+        eigenvalues_2x2(m11, m22, m12).1
+    })
+}
+
+// correlate the 3x3 neighbourhood of a pixel, given by its (row, col) offsets, with a kernel
+fn convolve3(v: &impl Fn(usize, usize) -> f32, kernel: &[[f32; 3]; 3]) -> f32 {
+    let mut sum = 0.0;
+    for (dr, kernel_row) in kernel.iter().enumerate() {
+        for (dc, k) in kernel_row.iter().enumerate() {
+            sum += k * v(dr, dc);
+        }
+    }
+    sum
+}
+
+// compute a response of the structure tensors of the channels summed over a 3x3 window,
+// writing only the interior pixels
+fn color_response<const C: usize>(
+    src: &Image<f32, C>,
+    dst: &mut Image<f32, 1>,
+    fusion: ChannelFusion,
+    response: impl Fn([f32; 3]) -> f32 + Sync,
+) -> Result<(), ImageError> {
+    if src.size() != dst.size() {
+        return Err(ImageError::InvalidImageSize(
+            src.cols(),
+            src.rows(),
+            dst.cols(),
+            dst.rows(),
+        ));
+    }
+
+    let (rows, cols) = (src.rows(), src.cols());
+    if rows < 3 || cols < 3 {
+        return Ok(());
+    }
+    let (kernel_x, kernel_y) = kernels::gradient_kernel3(GradsMode::Sobel);
+    let src_data = src.as_slice();
+
+    // products of the sobel gradients of every channel, zero at the border
+    let mut moments = vec![[[0.0f32; 3]; C]; rows * cols];
+    moments
+        .par_chunks_exact_mut(cols)
+        .enumerate()
+        .skip(1)
+        .take(rows - 2)
+        .for_each(|(r, row)| {
+            for (c, moment) in row.iter_mut().enumerate().take(cols - 1).skip(1) {
+                for (ch, channel_moment) in moment.iter_mut().enumerate() {
+                    let v = |dr: usize, dc: usize| {
+                        src_data[((r + dr - 1) * cols + c + dc - 1) * C + ch]
+                    };
+                    let dx = convolve3(&v, &kernel_x);
+                    let dy = convolve3(&v, &kernel_y);
+                    *channel_moment = [dx * dx, dy * dy, dx * dy];
+                }
+            }
+        });
+
+    dst.as_slice_mut()
+        .par_chunks_exact_mut(cols)
+        .enumerate()
+        .skip(1)
+        .take(rows - 2)
+        .for_each(|(r, row)| {
+            for (c, dst_pixel) in row.iter_mut().enumerate().take(cols - 1).skip(1) {
+                let mut m = [[0.0f32; 3]; C];
+                for dr in 0..3 {
+                    for dc in 0..3 {
+                        let p = &moments[(r + dr - 1) * cols + c + dc - 1];
+                        for (acc, channel) in m.iter_mut().zip(p) {
+                            acc.iter_mut().zip(channel).for_each(|(a, x)| *a += x);
+                        }
+                    }
+                }
+
+                *dst_pixel = match fusion {
+                    ChannelFusion::Sum => response(m.iter().fold([0.0; 3], |acc, channel| {
+                        [
+                            acc[0] + channel[0],
+                            acc[1] + channel[1],
+                            acc[2] + channel[2],
+                        ]
+                    })),
+                    ChannelFusion::Max => m
+                        .iter()
+                        .map(|&channel| response(channel))
+                        .fold(f32::MIN, f32::max),
+                };
+            }
+        });
+
+    Ok(())
 }
 
 /// Compute the DoG response of an image.
@@ -285,12 +486,15 @@ impl HarrisResponse {
 ///     dst: The destination image with shape (H, W).
 ///     sigma1: The sigma of the first Gaussian kernel.
 ///     sigma2: The sigma of the second Gaussian kernel.
+///     policy: The rule to derive the sizes of the Gaussian kernels from the sigmas.
 pub fn dog_response(
     src: &Image<f32, 1>,
     dst: &mut Image<f32, 1>,
     sigma1: f32,
     sigma2: f32,
+    policy: KernelSizePolicy,
 ) -> Result<(), ImageError> {
+    profile_scope!("dog_response");
     if src.size() != dst.size() {
         return Err(ImageError::InvalidImageSize(
             src.cols(),
@@ -302,11 +506,8 @@ pub fn dog_response(
 
     let mut gauss1 = Image::from_size_val(src.size(), 0.0)?;
     let mut gauss2 = Image::from_size_val(src.size(), 0.0)?;
-    let ks1 = _get_kernel_size(sigma1);
-    let ks2 = _get_kernel_size(sigma2);
-
-    gaussian_blur(src, &mut gauss1, (ks1, ks1), (sigma1, sigma1))?;
-    gaussian_blur(src, &mut gauss2, (ks2, ks2), (sigma2, sigma2))?;
+    gaussian_blur_sigma(src, &mut gauss1, (sigma1, sigma1), policy)?;
+    gaussian_blur_sigma(src, &mut gauss2, (sigma2, sigma2), policy)?;
 
     let gauss1_data = gauss1.as_slice();
     let gauss2_data = gauss2.as_slice();
@@ -341,7 +542,7 @@ mod tests {
         )?;
 
         let mut dst = Image::from_size_val([5, 5].into(), 0.0)?;
-        hessian_response(&src, &mut dst)?;
+        hessian_response(&src, &mut dst, GradsMode::Diff)?;
 
         #[rustfmt::skip]
         assert_eq!(
@@ -393,6 +594,38 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_gftt_response() -> Result<(), ImageError> {
+        #[rustfmt::skip]
+        let src = Image::from_size_slice(
+            [7, 7].into(),
+            &[
+                0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0,
+                0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0,
+                0.0, 0.0, 1.0, 1.0, 1.0, 1.0, 1.0,
+                0.0, 0.0, 1.0, 1.0, 1.0, 1.0, 1.0,
+                0.0, 0.0, 1.0, 1.0, 1.0, 1.0, 1.0,
+                0.0, 0.0, 1.0, 1.0, 1.0, 1.0, 1.0,
+                0.0, 0.0, 1.0, 1.0, 1.0, 1.0, 1.0,
+            ],
+        )?;
+
+        let mut dst = Image::from_size_val(src.size(), -1.0)?;
+        gftt_response(&src, &mut dst, GradsMode::Sobel)?;
+
+        // the border is untouched
+        assert_eq!(dst.get_pixel(0, 0, 0)?, &-1.0);
+        // the corner responds more than the edges and the flat regions
+        let corner = *dst.get_pixel(2, 2, 0)?;
+        assert!(corner > 0.0);
+        assert!(corner > *dst.get_pixel(5, 2, 0)?);
+        assert!(corner > *dst.get_pixel(2, 5, 0)?);
+        assert_eq!(dst.get_pixel(4, 4, 0)?, &0.0);
+        assert!(dst.get_pixel(5, 2, 0)?.abs() < 1e-6);
+
+        Ok(())
+    }
+
     #[test]
     fn test_harris_rectangle() -> Result<(), ImageError> {
         #[rustfmt::skip]
@@ -496,7 +729,7 @@ mod tests {
         let sigma1 = 0.5;
         let sigma2 = 1.0;
 
-        dog_response(&src, &mut dst, sigma1, sigma2)?;
+        dog_response(&src, &mut dst, sigma1, sigma2, KernelSizePolicy::Pytorch)?;
 
         let center_value = dst.as_slice()[2 * 5 + 2];
         let expected_center_value = -0.2195;
@@ -514,4 +747,83 @@ mod tests {
 
         Ok(())
     }
+
+    // a red square on a green background with the same intensity
+    fn isoluminant_square() -> Result<Image<f32, 3>, ImageError> {
+        let mut data = Vec::with_capacity(9 * 9 * 3);
+        for y in 0..9 {
+            for x in 0..9 {
+                let inside = (2..7).contains(&x) && (2..7).contains(&y);
+                data.extend(if inside {
+                    [1.0, 0.0, 0.0]
+                } else {
+                    [0.0, 1.0, 0.0]
+                });
+            }
+        }
+        Image::new([9, 9].into(), data)
+    }
+
+    #[test]
+    fn test_gftt_response_color() -> Result<(), ImageError> {
+        let src = isoluminant_square()?;
+
+        // the average of the channels is flat
+        let gray = Image::new(
+            src.size(),
+            src.as_slice()
+                .chunks_exact(3)
+                .map(|p| (p[0] + p[1]) / 2.0)
+                .collect(),
+        )?;
+        let mut dst = Image::from_size_val(src.size(), 0.0)?;
+        gftt_response(&gray, &mut dst, GradsMode::Sobel)?;
+        assert!(dst.as_slice().iter().all(|&v| v == 0.0));
+
+        // the corners appear in the color responses
+        for fusion in [ChannelFusion::Sum, ChannelFusion::Max] {
+            gftt_response_color(&src, &mut dst, fusion)?;
+            assert!(dst.as_slice()[2 * 9 + 2] > 0.1, "{fusion:?}");
+            assert!(dst.as_slice()[4 * 9 + 4].abs() < 1e-6);
+        }
+
+        // the maximum over the copies of a channel is its response
+        let mut expected = Image::from_size_val(src.size(), 0.0)?;
+        let red = Image::new(
+            src.size(),
+            src.as_slice().chunks_exact(3).map(|p| p[0]).collect(),
+        )?;
+        gftt_response(&red, &mut expected, GradsMode::Sobel)?;
+        let replicated = Image::<f32, 3>::new(
+            src.size(),
+            red.as_slice().iter().flat_map(|&v| [v; 3]).collect(),
+        )?;
+        gftt_response_color(&replicated, &mut dst, ChannelFusion::Max)?;
+        assert_eq!(dst.as_slice(), expected.as_slice());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_harris_response_color() -> Result<(), ImageError> {
+        let src = isoluminant_square()?;
+        let mut harris = HarrisResponse::new(src.size());
+        let mut dst = Image::from_size_val(src.size(), 0.0)?;
+
+        // the channels have the same gradients up to the sign, so the sum doubles them
+        harris.compute_color(&src, &mut dst, ChannelFusion::Max)?;
+        let max = dst.as_slice().to_vec();
+        harris.compute_color(&src, &mut dst, ChannelFusion::Sum)?;
+        for (sum, max) in dst.as_slice().iter().zip(&max) {
+            assert!((sum - 4.0 * max).abs() < 1e-5);
+        }
+        assert!(dst.as_slice()[2 * 9 + 2] > 0.0);
+
+        let mut small = Image::from_size_val([3, 3].into(), 0.0)?;
+        assert!(harris
+            .compute_color(&src, &mut small, ChannelFusion::Sum)
+            .is_err());
+
+        Ok(())
+    }
 }
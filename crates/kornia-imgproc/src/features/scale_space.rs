@@ -0,0 +1,202 @@
+use crate::parallel::prelude::*;
+use kornia_image::{Image, ImageError};
+
+/// A local extremum of a scale space, refined to subpixel and subscale accuracy.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ScaleSpaceExtremum {
+    /// The position `[x, y]` in the pixel coordinates of the response maps.
+    pub position: [f32; 2],
+    /// The offset of the scale relative to the current response map, in units of the scale
+    /// step between the response maps, in [-0.5, 0.5].
+    pub scale_offset: f32,
+    /// The interpolated response at the extremum.
+    pub response: f32,
+}
+
+/// Find the local extrema of the current response map in its 3x3x3 scale space neighborhood.
+///
+/// A pixel is an extremum if its absolute response is above the threshold and it is not
+/// smaller (or not larger) than its 26 neighbors in the current, the finer and the coarser
+/// response maps. The extrema are refined by fitting a 3D quadratic to the neighborhood, as in
+/// SIFT, so the detector is shared by the DoG, the DoH and the LoG scale spaces. The extrema
+/// whose refined offsets leave the pixel are kept at the integer location. The border pixels
+/// are never extrema.
+///
+/// # Arguments
+///
+/// * `below` - The response map at the finer scale with shape (H, W).
+/// * `current` - The response map at the current scale with shape (H, W).
+/// * `above` - The response map at the coarser scale with shape (H, W).
+/// * `threshold` - The minimum absolute response of an extremum.
+///
+/// # Returns
+///
+/// The extrema in row-major order of their integer locations.
+pub fn find_scale_space_extrema(
+    below: &Image<f32, 1>,
+    current: &Image<f32, 1>,
+    above: &Image<f32, 1>,
+    threshold: f32,
+) -> Result<Vec<ScaleSpaceExtremum>, ImageError> {
+    profile_scope!("find_scale_space_extrema");
+    for image in [below, above] {
+        if image.size() != current.size() {
+            return Err(ImageError::InvalidImageSize(
+                current.cols(),
+                current.rows(),
+                image.cols(),
+                image.rows(),
+            ));
+        }
+    }
+
+    let (cols, rows) = (current.cols(), current.rows());
+    if cols < 3 || rows < 3 {
+        return Ok(Vec::new());
+    }
+    let layers = [below.as_slice(), current.as_slice(), above.as_slice()];
+
+    let extrema = (1..rows - 1)
+        .into_par_iter()
+        .flat_map_iter(|y| {
+            let layers = &layers;
+            (1..cols - 1).filter_map(move |x| {
+                // the response at an offset of the scale, the row and the column
+                let v =
+                    |ds: usize, dy: usize, dx: usize| layers[ds][(y + dy - 1) * cols + x + dx - 1];
+                let value = v(1, 1, 1);
+                if value.abs() <= threshold {
+                    return None;
+                }
+
+                let mut neighbors = (0..27)
+                    .filter(|&i| i != 13)
+                    .map(|i| v(i / 9, i / 3 % 3, i % 3));
+                let is_extremum = if value > 0.0 {
+                    neighbors.all(|neighbor| value >= neighbor)
+                } else {
+                    neighbors.all(|neighbor| value <= neighbor)
+                };
+                is_extremum.then(|| refine_extremum(v, x, y))
+            })
+        })
+        .collect();
+
+    Ok(extrema)
+}
+
+// fit a 3D quadratic to the 3x3x3 neighborhood of an extremum at (x, y) of the current map
+fn refine_extremum(
+    v: impl Fn(usize, usize, usize) -> f32,
+    x: usize,
+    y: usize,
+) -> ScaleSpaceExtremum {
+    let value = v(1, 1, 1);
+
+    // the gradient and the hessian of (x, y, scale) by central differences
+    let gradient = [
+        0.5 * (v(1, 1, 2) - v(1, 1, 0)),
+        0.5 * (v(1, 2, 1) - v(1, 0, 1)),
+        0.5 * (v(2, 1, 1) - v(0, 1, 1)),
+    ];
+    let dxx = v(1, 1, 2) - 2.0 * value + v(1, 1, 0);
+    let dyy = v(1, 2, 1) - 2.0 * value + v(1, 0, 1);
+    let dss = v(2, 1, 1) - 2.0 * value + v(0, 1, 1);
+    let dxy = 0.25 * (v(1, 2, 2) - v(1, 2, 0) - v(1, 0, 2) + v(1, 0, 0));
+    let dxs = 0.25 * (v(2, 1, 2) - v(2, 1, 0) - v(0, 1, 2) + v(0, 1, 0));
+    let dys = 0.25 * (v(2, 2, 1) - v(2, 0, 1) - v(0, 2, 1) + v(0, 0, 1));
+    let hessian = [[dxx, dxy, dxs], [dxy, dyy, dys], [dxs, dys, dss]];
+
+    let offset = solve3(&hessian, &gradient.map(|g| -g))
+        .filter(|offset| offset.iter().all(|o| o.abs() <= 0.5))
+        .unwrap_or([0.0; 3]);
+    let response = value
+        + 0.5
+            * gradient
+                .iter()
+                .zip(&offset)
+                .map(|(g, o)| g * o)
+                .sum::<f32>();
+
+    ScaleSpaceExtremum {
+        position: [x as f32 + offset[0], y as f32 + offset[1]],
+        scale_offset: offset[2],
+        response,
+    }
+}
+
+// solve the 3x3 linear system a * x = b with the Cramer's rule
+fn solve3(a: &[[f32; 3]; 3], b: &[f32; 3]) -> Option<[f32; 3]> {
+    let det = |m: &[[f32; 3]; 3]| {
+        m[0][0] * (m[1][1] * m[2][2] - m[1][2] * m[2][1])
+            - m[0][1] * (m[1][0] * m[2][2] - m[1][2] * m[2][0])
+            + m[0][2] * (m[1][0] * m[2][1] - m[1][1] * m[2][0])
+    };
+    let det_a = det(a);
+    if det_a.abs() <= f32::EPSILON {
+        return None;
+    }
+
+    let mut x = [0.0; 3];
+    for (col, x) in x.iter_mut().enumerate() {
+        let mut m = *a;
+        for (row, b) in b.iter().enumerate() {
+            m[row][col] = *b;
+        }
+        *x = det(&m) / det_a;
+    }
+    x.iter().all(|x| x.is_finite()).then_some(x)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // a 3D quadratic blob with its peak at (x0, y0, s0) sampled at the scale s
+    fn blob(x0: f32, y0: f32, s0: f32, s: f32) -> Result<Image<f32, 1>, ImageError> {
+        let data = (0..9 * 7)
+            .map(|i| {
+                let (x, y) = ((i % 9) as f32, (i / 9) as f32);
+                10.0 - (x - x0).powi(2) - 2.0 * (y - y0).powi(2) - 3.0 * (s - s0).powi(2)
+            })
+            .collect();
+        Image::new([9, 7].into(), data)
+    }
+
+    #[test]
+    fn test_find_scale_space_extrema() -> Result<(), ImageError> {
+        let (x0, y0, s0) = (4.3, 2.8, 0.2);
+        let below = blob(x0, y0, s0, -1.0)?;
+        let current = blob(x0, y0, s0, 0.0)?;
+        let above = blob(x0, y0, s0, 1.0)?;
+
+        let extrema = find_scale_space_extrema(&below, &current, &above, 1.0)?;
+        assert_eq!(extrema.len(), 1);
+
+        // the quadratic fit is exact
+        let extremum = extrema[0];
+        assert!((extremum.position[0] - x0).abs() < 1e-4);
+        assert!((extremum.position[1] - y0).abs() < 1e-4);
+        assert!((extremum.scale_offset - s0).abs() < 1e-4);
+        assert!((extremum.response - 10.0).abs() < 1e-4);
+
+        // the minima of the negated responses
+        let negate = |image: &Image<f32, 1>| {
+            Image::new(image.size(), image.as_slice().iter().map(|v| -v).collect())
+        };
+        let extrema =
+            find_scale_space_extrema(&negate(&below)?, &negate(&current)?, &negate(&above)?, 1.0)?;
+        assert_eq!(extrema.len(), 1);
+        assert!((extrema[0].response + 10.0).abs() < 1e-4);
+
+        // the peak is weaker than the threshold
+        assert!(find_scale_space_extrema(&below, &current, &above, 20.0)?.is_empty());
+
+        // the peak is at a coarser scale
+        assert!(
+            find_scale_space_extrema(&below, &current, &blob(x0, y0, 1.0, 1.0)?, 1.0)?.is_empty()
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,152 @@
+use crate::parallel::prelude::*;
+use kornia_image::{Image, ImageError};
+
+/// Compute the eigenvalues of the symmetric 2x2 matrix [[ixx, ixy], [ixy, iyy]].
+///
+/// # Arguments
+///
+/// * `ixx` - The first diagonal element, e.g. the sum of the squared x gradients.
+/// * `iyy` - The second diagonal element, e.g. the sum of the squared y gradients.
+/// * `ixy` - The off-diagonal element, e.g. the sum of the products of the gradients.
+///
+/// # Returns
+///
+/// The largest and the smallest eigenvalues.
+#[inline]
+pub fn eigenvalues_2x2(ixx: f32, iyy: f32, ixy: f32) -> (f32, f32) {
+    let half_trace = 0.5 * (ixx + iyy);
+    let half_diff = 0.5 * (ixx - iyy);
+    let radius = (half_diff * half_diff + ixy * ixy).sqrt();
+    (half_trace + radius, half_trace - radius)
+}
+
+/// Compute the eigen decomposition of the second moment matrix of every pixel.
+///
+/// The orientation is the angle of the eigenvector of the largest eigenvalue, i.e. the
+/// dominant gradient direction, in radians in the range [-pi/2, pi/2]. The orientation of
+/// the isotropic pixels is zero.
+///
+/// # Arguments
+///
+/// * `ixx` - The first diagonal elements of the matrices with shape (H, W).
+/// * `iyy` - The second diagonal elements of the matrices with shape (H, W).
+/// * `ixy` - The off-diagonal elements of the matrices with shape (H, W).
+/// * `e1` - The largest eigenvalues with shape (H, W).
+/// * `e2` - The smallest eigenvalues with shape (H, W).
+/// * `orientation` - The orientations of the largest eigenvalues with shape (H, W).
+pub fn eigenvalues_2x2_map(
+    ixx: &Image<f32, 1>,
+    iyy: &Image<f32, 1>,
+    ixy: &Image<f32, 1>,
+    e1: &mut Image<f32, 1>,
+    e2: &mut Image<f32, 1>,
+    orientation: &mut Image<f32, 1>,
+) -> Result<(), ImageError> {
+    profile_scope!("eigenvalues_2x2_map");
+    for image in [iyy, ixy, &*e1, &*e2, &*orientation] {
+        if image.size() != ixx.size() {
+            return Err(ImageError::InvalidImageSize(
+                ixx.cols(),
+                ixx.rows(),
+                image.cols(),
+                image.rows(),
+            ));
+        }
+    }
+
+    let cols = ixx.cols().max(1);
+
+    e1.as_slice_mut()
+        .par_chunks_mut(cols)
+        .zip(e2.as_slice_mut().par_chunks_mut(cols))
+        .zip(orientation.as_slice_mut().par_chunks_mut(cols))
+        .zip(ixx.as_slice().par_chunks(cols))
+        .zip(iyy.as_slice().par_chunks(cols))
+        .zip(ixy.as_slice().par_chunks(cols))
+        .for_each(
+            |(((((e1_row, e2_row), orientation_row), ixx_row), iyy_row), ixy_row)| {
+                for (i, &xx) in ixx_row.iter().enumerate() {
+                    let (yy, xy) = (iyy_row[i], ixy_row[i]);
+                    (e1_row[i], e2_row[i]) = eigenvalues_2x2(xx, yy, xy);
+                    orientation_row[i] = 0.5 * (2.0 * xy).atan2(xx - yy);
+                }
+            },
+        );
+
+    Ok(())
+}
+
+/// Compute the coherence of the second moment matrix of every pixel from its eigenvalues.
+///
+/// The coherence (e1 - e2) / (e1 + e2) is 1 for a single dominant orientation, e.g. the
+/// ridges of a fingerprint, and 0 for the isotropic or flat regions.
+///
+/// # Arguments
+///
+/// * `e1` - The largest eigenvalues with shape (H, W).
+/// * `e2` - The smallest eigenvalues with shape (H, W).
+/// * `dst` - The coherence with shape (H, W).
+pub fn coherence_map(
+    e1: &Image<f32, 1>,
+    e2: &Image<f32, 1>,
+    dst: &mut Image<f32, 1>,
+) -> Result<(), ImageError> {
+    for image in [e2, &*dst] {
+        if image.size() != e1.size() {
+            return Err(ImageError::InvalidImageSize(
+                e1.cols(),
+                e1.rows(),
+                image.cols(),
+                image.rows(),
+            ));
+        }
+    }
+
+    dst.as_slice_mut()
+        .iter_mut()
+        .zip(e1.as_slice().iter().zip(e2.as_slice()))
+        .for_each(|(dst_pixel, (&e1, &e2))| {
+            let sum = e1 + e2;
+            *dst_pixel = if sum > f32::EPSILON {
+                (e1 - e2) / sum
+            } else {
+                0.0
+            };
+        });
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_eigenvalues_2x2_map() -> Result<(), ImageError> {
+        let size = [3, 1].into();
+        // an x edge, a diagonal edge and an isotropic matrix
+        let ixx = Image::new(size, vec![4.0, 1.0, 2.0])?;
+        let iyy = Image::new(size, vec![0.0, 1.0, 2.0])?;
+        let ixy = Image::new(size, vec![0.0, 1.0, 0.0])?;
+
+        let mut e1 = Image::from_size_val(size, 0.0)?;
+        let mut e2 = Image::from_size_val(size, 0.0)?;
+        let mut orientation = Image::from_size_val(size, 0.0)?;
+        eigenvalues_2x2_map(&ixx, &iyy, &ixy, &mut e1, &mut e2, &mut orientation)?;
+
+        assert_eq!(e1.as_slice(), &[4.0, 2.0, 2.0]);
+        assert_eq!(e2.as_slice(), &[0.0, 0.0, 2.0]);
+        assert_eq!(orientation.as_slice()[0], 0.0);
+        assert!((orientation.as_slice()[1] - std::f32::consts::FRAC_PI_4).abs() < 1e-6);
+        assert_eq!(orientation.as_slice()[2], 0.0);
+
+        let mut coherence = Image::from_size_val(size, 0.0)?;
+        coherence_map(&e1, &e2, &mut coherence)?;
+        assert_eq!(coherence.as_slice(), &[1.0, 1.0, 0.0]);
+
+        let mut wrong = Image::from_size_val([2, 1].into(), 0.0)?;
+        assert!(eigenvalues_2x2_map(&ixx, &iyy, &ixy, &mut e1, &mut e2, &mut wrong).is_err());
+
+        Ok(())
+    }
+}
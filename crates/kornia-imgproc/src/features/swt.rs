@@ -0,0 +1,417 @@
+use kornia_image::{Image, ImageError};
+
+use super::canny;
+use crate::filter::{kernels::GradsMode, spatial_gradient_float};
+
+/// The parameters of the stroke width transform and the text detection.
+#[derive(Debug, Clone)]
+pub struct SwtParams {
+    /// True to detect dark text on a light background, false for light text on a dark one.
+    pub dark_on_light: bool,
+    /// The low threshold of the Canny edges.
+    pub canny_low: f32,
+    /// The high threshold of the Canny edges.
+    pub canny_high: f32,
+    /// The maximum length of a stroke in pixels.
+    pub max_stroke_width: f32,
+    /// The minimum height of a letter in pixels.
+    pub min_letter_height: usize,
+    /// The maximum height of a letter in pixels.
+    pub max_letter_height: usize,
+    /// The maximum ratio of the standard deviation to the mean of the stroke widths of a
+    /// letter.
+    pub max_stroke_variation: f32,
+    /// The minimum number of letters of a text region.
+    pub min_letters: usize,
+}
+
+impl Default for SwtParams {
+    fn default() -> Self {
+        Self {
+            dark_on_light: true,
+            canny_low: 0.05,
+            canny_high: 0.15,
+            max_stroke_width: 50.0,
+            min_letter_height: 8,
+            max_letter_height: 300,
+            max_stroke_variation: 0.5,
+            min_letters: 1,
+        }
+    }
+}
+
+/// A text region proposal.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextRegion {
+    /// The bounding box as [x, y, width, height] in pixels.
+    pub bbox: [usize; 4],
+    /// The mean stroke width of the letters in pixels.
+    pub stroke_width: f32,
+    /// The number of letters chained in the region.
+    pub num_letters: usize,
+}
+
+// the cosine of the maximum angle between the opposite gradients of a stroke, pi / 6
+const MAX_OPPOSITE_COS: f32 = 0.866;
+
+/// Compute the stroke width transform (SWT) of an image.
+///
+/// Rays are cast from each Canny edge pixel along the gradient, towards the inside of the
+/// strokes, until they hit an edge with a roughly opposite gradient. The pixels of a ray
+/// take the length of the ray, then each ray is clamped to its median to fix the corners,
+/// following Epshtein et al. 2010.
+///
+/// # Arguments
+///
+/// * `src` - The source image with shape (H, W, 1).
+/// * `dst` - The destination stroke widths with shape (H, W, 1), 0 outside the strokes.
+/// * `params` - The polarity of the text, the Canny thresholds and the maximum width.
+pub fn stroke_width_transform(
+    src: &Image<f32, 1>,
+    dst: &mut Image<f32, 1>,
+    params: &SwtParams,
+) -> Result<(), ImageError> {
+    if src.size() != dst.size() {
+        return Err(ImageError::InvalidImageSize(
+            src.cols(),
+            src.rows(),
+            dst.cols(),
+            dst.rows(),
+        ));
+    }
+
+    let (cols, rows) = (src.cols(), src.rows());
+    let mut edges = Image::from_size_val(src.size(), 0u8)?;
+    canny(src, &mut edges, params.canny_low, params.canny_high)?;
+
+    let mut dx = Image::from_size_val(src.size(), 0.0)?;
+    let mut dy = Image::from_size_val(src.size(), 0.0)?;
+    spatial_gradient_float(src, &mut dx, &mut dy, GradsMode::Sobel)?;
+
+    // the unit gradients, pointing towards the brighter side
+    let direction = |i: usize| -> Option<(f32, f32)> {
+        let (gx, gy) = (dx.as_slice()[i], dy.as_slice()[i]);
+        let norm = gx.hypot(gy);
+        (norm > 0.0).then(|| (gx / norm, gy / norm))
+    };
+    let sign = if params.dark_on_light { -1.0 } else { 1.0 };
+    let is_edge = |i: usize| edges.as_slice()[i] != 0;
+
+    let widths = dst.as_slice_mut();
+    widths.fill(f32::INFINITY);
+    let mut rays: Vec<Vec<usize>> = Vec::new();
+
+    for i in (0..cols * rows).filter(|&i| is_edge(i)) {
+        let Some((ux, uy)) = direction(i) else {
+            continue;
+        };
+        let (ux, uy) = (sign * ux, sign * uy);
+        let (x0, y0) = ((i % cols) as f32 + 0.5, (i / cols) as f32 + 0.5);
+
+        // march along the ray with half pixel steps
+        let mut ray = vec![i];
+        let mut t = 0.5;
+        while t <= params.max_stroke_width {
+            let (x, y) = (x0 + ux * t, y0 + uy * t);
+            t += 0.5;
+            if x < 0.0 || y < 0.0 || x >= cols as f32 || y >= rows as f32 {
+                break;
+            }
+            let j = y as usize * cols + x as usize;
+            if ray.last() == Some(&j) {
+                continue;
+            }
+            ray.push(j);
+
+            if is_edge(j) {
+                // the opposite side of the stroke has an opposite gradient
+                let opposite = direction(j)
+                    .is_some_and(|(vx, vy)| sign * (vx * ux + vy * uy) < -MAX_OPPOSITE_COS);
+                if opposite {
+                    let (x1, y1) = ((j % cols) as f32 + 0.5, (j / cols) as f32 + 0.5);
+                    let width = (x1 - x0).hypot(y1 - y0);
+                    for &k in &ray {
+                        widths[k] = widths[k].min(width);
+                    }
+                    rays.push(ray);
+                }
+                break;
+            }
+        }
+    }
+
+    // clamp the rays to their median width, which fixes the corners of the strokes
+    for ray in &rays {
+        let mut values = ray.iter().map(|&k| widths[k]).collect::<Vec<_>>();
+        let median = *values
+            .select_nth_unstable_by(ray.len() / 2, |a, b| a.total_cmp(b))
+            .1;
+        for &k in ray {
+            widths[k] = widths[k].min(median);
+        }
+    }
+
+    widths
+        .iter_mut()
+        .filter(|w| w.is_infinite())
+        .for_each(|w| *w = 0.0);
+
+    Ok(())
+}
+
+/// Detect the text regions of an image with the stroke width transform.
+///
+/// The pixels with similar stroke widths are grouped in connected components, the
+/// components with the geometry and the constant stroke width of letters are kept, and
+/// the letters with similar heights and stroke widths which are close to each other are
+/// chained in text regions.
+///
+/// # Arguments
+///
+/// * `src` - The source image with shape (H, W, 1).
+/// * `params` - The parameters of the transform and the letter filters.
+///
+/// # Returns
+///
+/// The text regions with at least `min_letters` letters.
+///
+/// # Example
+///
+/// ```
+/// use kornia_image::Image;
+/// use kornia_imgproc::features::{detect_text_regions, SwtParams};
+///
+/// let image = Image::<f32, 1>::from_size_val([32, 32].into(), 1.0).unwrap();
+/// let regions = detect_text_regions(&image, &SwtParams::default()).unwrap();
+/// assert!(regions.is_empty());
+/// ```
+pub fn detect_text_regions(
+    src: &Image<f32, 1>,
+    params: &SwtParams,
+) -> Result<Vec<TextRegion>, ImageError> {
+    let (cols, rows) = (src.cols(), src.rows());
+    let mut swt = Image::from_size_val(src.size(), 0.0)?;
+    stroke_width_transform(src, &mut swt, params)?;
+    let widths = swt.as_slice();
+
+    // the 8-connected components of the pixels with stroke widths within a ratio of 3
+    let mut labels = vec![usize::MAX; cols * rows];
+    let mut letters = Vec::new();
+    let mut stack = Vec::new();
+
+    for seed in 0..cols * rows {
+        if widths[seed] == 0.0 || labels[seed] != usize::MAX {
+            continue;
+        }
+
+        let label = letters.len();
+        labels[seed] = label;
+        stack.push(seed);
+        let mut pixels = Vec::new();
+
+        while let Some(i) = stack.pop() {
+            pixels.push(i);
+            let (x, y) = (i % cols, i / cols);
+            for ny in y.saturating_sub(1)..(y + 2).min(rows) {
+                for nx in x.saturating_sub(1)..(x + 2).min(cols) {
+                    let j = ny * cols + nx;
+                    if widths[j] == 0.0 || labels[j] != usize::MAX {
+                        continue;
+                    }
+                    let ratio = widths[i].max(widths[j]) / widths[i].min(widths[j]);
+                    if ratio < 3.0 {
+                        labels[j] = label;
+                        stack.push(j);
+                    }
+                }
+            }
+        }
+
+        letters.push(Letter::new(&pixels, widths, cols));
+    }
+
+    letters.retain(|letter| letter.is_letter(params));
+
+    // chain the similar letters which are close to each other
+    let mut parent = (0..letters.len()).collect::<Vec<_>>();
+    fn find(parent: &mut [usize], mut i: usize) -> usize {
+        while parent[i] != i {
+            parent[i] = parent[parent[i]];
+            i = parent[i];
+        }
+        i
+    }
+    for a in 0..letters.len() {
+        for b in a + 1..letters.len() {
+            if letters[a].is_neighbor(&letters[b]) {
+                let (ra, rb) = (find(&mut parent, a), find(&mut parent, b));
+                parent[ra] = rb;
+            }
+        }
+    }
+
+    let mut regions: Vec<(usize, TextRegion)> = Vec::new();
+    for (i, letter) in letters.iter().enumerate() {
+        let root = find(&mut parent, i);
+        let [x, y, w, h] = letter.bbox;
+        match regions.iter_mut().find(|(r, _)| *r == root) {
+            Some((_, region)) => {
+                let [rx, ry, rw, rh] = region.bbox;
+                let (x0, y0) = (rx.min(x), ry.min(y));
+                let (x1, y1) = ((rx + rw).max(x + w), (ry + rh).max(y + h));
+                let n = region.num_letters as f32;
+                region.bbox = [x0, y0, x1 - x0, y1 - y0];
+                region.stroke_width = (region.stroke_width * n + letter.mean) / (n + 1.0);
+                region.num_letters += 1;
+            }
+            None => regions.push((
+                root,
+                TextRegion {
+                    bbox: letter.bbox,
+                    stroke_width: letter.mean,
+                    num_letters: 1,
+                },
+            )),
+        }
+    }
+
+    Ok(regions
+        .into_iter()
+        .map(|(_, region)| region)
+        .filter(|region| region.num_letters >= params.min_letters)
+        .collect())
+}
+
+// the statistics of a connected component of stroke widths
+struct Letter {
+    bbox: [usize; 4],
+    mean: f32,
+    std: f32,
+    median: f32,
+}
+
+impl Letter {
+    fn new(pixels: &[usize], widths: &[f32], cols: usize) -> Self {
+        let (mut x0, mut y0, mut x1, mut y1) = (usize::MAX, usize::MAX, 0, 0);
+        for &i in pixels {
+            let (x, y) = (i % cols, i / cols);
+            (x0, y0, x1, y1) = (x0.min(x), y0.min(y), x1.max(x), y1.max(y));
+        }
+
+        let mut values = pixels.iter().map(|&i| widths[i]).collect::<Vec<_>>();
+        let n = values.len() as f32;
+        let mean = values.iter().sum::<f32>() / n;
+        let std = (values.iter().map(|w| (w - mean).powi(2)).sum::<f32>() / n).sqrt();
+        let median = *values
+            .select_nth_unstable_by(pixels.len() / 2, |a, b| a.total_cmp(b))
+            .1;
+
+        Self {
+            bbox: [x0, y0, x1 - x0 + 1, y1 - y0 + 1],
+            mean,
+            std,
+            median,
+        }
+    }
+
+    fn is_letter(&self, params: &SwtParams) -> bool {
+        let [_, _, width, height] = self.bbox;
+        let aspect = width as f32 / height as f32;
+        let diameter = (width as f32).hypot(height as f32);
+        (params.min_letter_height..=params.max_letter_height).contains(&height)
+            && (0.1..=10.0).contains(&aspect)
+            && self.std <= params.max_stroke_variation * self.mean
+            && diameter <= 10.0 * self.median
+    }
+
+    fn is_neighbor(&self, other: &Letter) -> bool {
+        let [x, y, w, h] = self.bbox;
+        let [ox, oy, ow, oh] = other.bbox;
+        let (h, oh) = (h as f32, oh as f32);
+        if self.median.max(other.median) > 2.0 * self.median.min(other.median)
+            || h.max(oh) > 2.0 * h.min(oh)
+        {
+            return false;
+        }
+
+        // the gap between the boxes is less than three letter widths
+        let gap_x = (ox as f32 - (x + w) as f32).max(x as f32 - (ox + ow) as f32);
+        let gap_y = (oy as f32 - (y + h as usize) as f32).max(y as f32 - (oy + oh as usize) as f32);
+        gap_x < 3.0 * w.max(ow) as f32 && gap_y < 0.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // dark letters I, L and T with a stroke of 4 pixels, and a dark disk
+    fn text_image() -> Result<Image<f32, 1>, ImageError> {
+        let (cols, rows) = (96, 64);
+        let in_rect = |x: usize, y: usize, x0: usize, y0: usize, w: usize, h: usize| {
+            (x0..x0 + w).contains(&x) && (y0..y0 + h).contains(&y)
+        };
+        Image::new(
+            [cols, rows].into(),
+            (0..cols * rows)
+                .map(|i| {
+                    let (x, y) = (i % cols, i / cols);
+                    let letter = in_rect(x, y, 10, 10, 4, 20)
+                        || in_rect(x, y, 20, 10, 4, 20)
+                        || in_rect(x, y, 20, 26, 12, 4)
+                        || in_rect(x, y, 36, 10, 12, 4)
+                        || in_rect(x, y, 40, 10, 4, 20);
+                    let disk = (x as f32 - 72.0).hypot(y as f32 - 40.0) < 16.0;
+                    if letter || disk {
+                        0.1
+                    } else {
+                        0.9
+                    }
+                })
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn test_stroke_width_transform() -> Result<(), ImageError> {
+        let src = text_image()?;
+        let mut swt = Image::from_size_val(src.size(), 0.0)?;
+        stroke_width_transform(&src, &mut swt, &SwtParams::default())?;
+
+        // the stroke of the I is 4 pixels wide
+        let width = swt.as_slice()[20 * 96 + 11];
+        assert!((3.0..=5.0).contains(&width), "{width}");
+
+        // the background has no stroke
+        assert_eq!(swt.as_slice()[5 * 96 + 5], 0.0);
+
+        // the light on dark polarity does not see the dark strokes
+        let params = SwtParams {
+            dark_on_light: false,
+            ..Default::default()
+        };
+        stroke_width_transform(&src, &mut swt, &params)?;
+        assert_eq!(swt.as_slice()[20 * 96 + 11], 0.0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_detect_text_regions() -> Result<(), ImageError> {
+        let src = text_image()?;
+        let params = SwtParams {
+            min_letters: 2,
+            ..Default::default()
+        };
+
+        // the letters are chained in a single region and the disk is rejected
+        let regions = detect_text_regions(&src, &params)?;
+        assert_eq!(regions.len(), 1);
+        assert_eq!(regions[0].num_letters, 3);
+        let [x, y, w, h] = regions[0].bbox;
+        assert!(x >= 9 && y >= 9 && x + w <= 49 && y + h <= 31);
+        assert!((3.0..=5.0).contains(&regions[0].stroke_width));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,131 @@
+use crate::parallel::prelude::*;
+use kornia_image::Image;
+
+/// Method to normalize a response image
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ResponseNormalization {
+    /// Divide by the maximum absolute response, so that the responses are in [-1, 1]
+    #[default]
+    Max,
+    /// Subtract the mean and divide by the standard deviation of the responses
+    ZScore,
+}
+
+/// Normalize a response image in place.
+///
+/// The responses of a flat image, i.e. a zero maximum or standard deviation, are set to zero.
+///
+/// # Arguments
+///
+/// * `dst` - The response image with shape (H, W), e.g. from [`super::gftt_response`].
+/// * `method` - The normalization method.
+pub fn normalize_response(dst: &mut Image<f32, 1>, method: ResponseNormalization) {
+    let data = dst.as_slice_mut();
+    if data.is_empty() {
+        return;
+    }
+
+    let (offset, scale) = match method {
+        ResponseNormalization::Max => {
+            let max = data.iter().fold(0.0f32, |acc, &v| acc.max(v.abs()));
+            (0.0, max)
+        }
+        ResponseNormalization::ZScore => {
+            let n = data.len() as f64;
+            let mean = data.iter().map(|&v| v as f64).sum::<f64>() / n;
+            let var = data.iter().map(|&v| (v as f64 - mean).powi(2)).sum::<f64>() / n;
+            (mean as f32, var.sqrt() as f32)
+        }
+    };
+
+    if scale <= f32::EPSILON {
+        data.fill(0.0);
+        return;
+    }
+
+    data.par_iter_mut().for_each(|v| *v = (*v - offset) / scale);
+}
+
+/// Suppress the responses below a threshold in place.
+///
+/// The threshold is the largest of the absolute threshold and the relative threshold times
+/// the maximum response, as in `skimage.feature.peak_local_max`. The suppressed responses are
+/// set to zero, so that the image is a mask of the kept pixels and the threshold can be used to
+/// extract the points with [`super::non_max_suppression`].
+///
+/// # Arguments
+///
+/// * `dst` - The response image with shape (H, W).
+/// * `thresh_rel` - The minimum response relative to the maximum response, in [0, 1].
+/// * `thresh_abs` - The minimum absolute response.
+///
+/// # Returns
+///
+/// The applied threshold.
+pub fn threshold_response(dst: &mut Image<f32, 1>, thresh_rel: f32, thresh_abs: f32) -> f32 {
+    let data = dst.as_slice_mut();
+    let max = data.iter().copied().fold(f32::MIN, f32::max);
+    let threshold = thresh_abs.max(thresh_rel * max);
+
+    data.par_iter_mut().for_each(|v| {
+        if *v < threshold {
+            *v = 0.0;
+        }
+    });
+
+    threshold
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::features::non_max_suppression;
+    use kornia_image::ImageError;
+
+    #[test]
+    fn test_normalize_response() -> Result<(), ImageError> {
+        let mut dst = Image::new([4, 1].into(), vec![-1.0, 0.0, 2.0, 4.0])?;
+        normalize_response(&mut dst, ResponseNormalization::Max);
+        assert_eq!(dst.as_slice(), &[-0.25, 0.0, 0.5, 1.0]);
+
+        let mut dst = Image::new([4, 1].into(), vec![1.0, 3.0, 1.0, 3.0])?;
+        normalize_response(&mut dst, ResponseNormalization::ZScore);
+        assert_eq!(dst.as_slice(), &[-1.0, 1.0, -1.0, 1.0]);
+
+        // a flat response
+        let mut dst = Image::new([2, 1].into(), vec![5.0, 5.0])?;
+        normalize_response(&mut dst, ResponseNormalization::ZScore);
+        assert_eq!(dst.as_slice(), &[0.0, 0.0]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_threshold_response() -> Result<(), ImageError> {
+        #[rustfmt::skip]
+        let src = Image::new(
+            [5, 4].into(),
+            vec![
+                0.0, 0.0, 0.0, 0.0, 0.0,
+                0.0, 10.0, 0.0, 3.0, 0.0,
+                0.0, 0.0, 0.0, 0.0, 0.0,
+                0.0, 0.0, 0.0, 0.0, 0.0,
+            ],
+        )?;
+
+        // the relative threshold dominates
+        let mut dst = src.clone();
+        let threshold = threshold_response(&mut dst, 0.5, 1.0);
+        assert_eq!(threshold, 5.0);
+        assert_eq!(dst.as_slice()[5 + 3], 0.0);
+        assert_eq!(non_max_suppression(&dst, threshold), vec![[1, 1]]);
+
+        // the absolute threshold dominates
+        let mut dst = src.clone();
+        let threshold = threshold_response(&mut dst, 0.1, 2.0);
+        assert_eq!(threshold, 2.0);
+        assert_eq!(non_max_suppression(&dst, threshold), vec![[1, 1], [3, 1]]);
+
+        Ok(())
+    }
+}
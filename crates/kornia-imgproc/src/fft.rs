@@ -0,0 +1,187 @@
+use std::f32::consts::PI;
+
+use crate::parallel::prelude::*;
+use kornia_image::{Image, ImageError};
+
+/// Compute the 2D discrete Fourier transform of a complex image in place.
+///
+/// The rows and then the columns are transformed with the radix-2 fast Fourier transform
+/// when their length is a power of two, and with the direct transform otherwise. The
+/// inverse transform is scaled by the number of pixels, so that the forward and the
+/// inverse transforms recover the input.
+///
+/// # Arguments
+///
+/// * `re` - The real part of the image with shape (H, W, 1).
+/// * `im` - The imaginary part of the image with shape (H, W, 1).
+/// * `inverse` - Whether to compute the inverse transform.
+///
+/// # Example
+///
+/// ```
+/// use kornia_image::Image;
+/// use kornia_imgproc::fft::fft2;
+///
+/// let mut re = Image::<f32, 1>::from_size_val([4, 4].into(), 1.0).unwrap();
+/// let mut im = Image::<f32, 1>::from_size_val([4, 4].into(), 0.0).unwrap();
+///
+/// // a constant image has only the zero frequency
+/// fft2(&mut re, &mut im, false).unwrap();
+/// assert_eq!(re.as_slice()[0], 16.0);
+/// assert!(re.as_slice()[1..].iter().all(|&v| v.abs() < 1e-6));
+/// ```
+pub fn fft2(
+    re: &mut Image<f32, 1>,
+    im: &mut Image<f32, 1>,
+    inverse: bool,
+) -> Result<(), ImageError> {
+    if re.size() != im.size() {
+        return Err(ImageError::InvalidImageSize(
+            re.cols(),
+            re.rows(),
+            im.cols(),
+            im.rows(),
+        ));
+    }
+
+    let (cols, rows) = (re.cols(), re.rows());
+
+    re.as_slice_mut()
+        .par_chunks_exact_mut(cols)
+        .zip(im.as_slice_mut().par_chunks_exact_mut(cols))
+        .for_each(|(re_row, im_row)| fft(re_row, im_row, inverse));
+
+    // transpose the columns to transform them as contiguous rows
+    let mut re_t = transpose(re.as_slice(), cols, rows);
+    let mut im_t = transpose(im.as_slice(), cols, rows);
+    re_t.par_chunks_exact_mut(rows)
+        .zip(im_t.par_chunks_exact_mut(rows))
+        .for_each(|(re_col, im_col)| fft(re_col, im_col, inverse));
+
+    let scale = if inverse {
+        1.0 / (cols * rows) as f32
+    } else {
+        1.0
+    };
+    for (dst, src) in [(re, re_t), (im, im_t)] {
+        dst.as_slice_mut()
+            .copy_from_slice(&transpose(&src, rows, cols));
+        dst.as_slice_mut().iter_mut().for_each(|v| *v *= scale);
+    }
+
+    Ok(())
+}
+
+fn transpose(src: &[f32], cols: usize, rows: usize) -> Vec<f32> {
+    let mut dst = vec![0.0; src.len()];
+    for (y, row) in src.chunks_exact(cols).enumerate() {
+        for (x, &v) in row.iter().enumerate() {
+            dst[x * rows + y] = v;
+        }
+    }
+    dst
+}
+
+// the unscaled 1D transform of a complex signal
+fn fft(re: &mut [f32], im: &mut [f32], inverse: bool) {
+    let n = re.len();
+    let sign = if inverse { 1.0 } else { -1.0 };
+
+    if n < 2 {
+        return;
+    }
+
+    if !n.is_power_of_two() {
+        let (src_re, src_im) = (re.to_vec(), im.to_vec());
+        for k in 0..n {
+            let (mut sum_re, mut sum_im) = (0.0, 0.0);
+            for t in 0..n {
+                // reduce the product to keep the precision of the angle
+                let angle = sign * 2.0 * PI * ((k * t) % n) as f32 / n as f32;
+                let (s, c) = angle.sin_cos();
+                sum_re += src_re[t] * c - src_im[t] * s;
+                sum_im += src_re[t] * s + src_im[t] * c;
+            }
+            re[k] = sum_re;
+            im[k] = sum_im;
+        }
+        return;
+    }
+
+    // the bit reversal permutation
+    let bits = n.trailing_zeros();
+    for i in 0..n {
+        let j = i.reverse_bits() >> (usize::BITS - bits);
+        if i < j {
+            re.swap(i, j);
+            im.swap(i, j);
+        }
+    }
+
+    // the butterflies of the iterative Cooley-Tukey transform
+    let mut len = 2;
+    while len <= n {
+        let angle = sign * 2.0 * PI / len as f32;
+        for start in (0..n).step_by(len) {
+            for k in 0..len / 2 {
+                let (s, c) = (angle * k as f32).sin_cos();
+                let (a, b) = (start + k, start + k + len / 2);
+                let t_re = re[b] * c - im[b] * s;
+                let t_im = re[b] * s + im[b] * c;
+                re[b] = re[a] - t_re;
+                im[b] = im[a] - t_im;
+                re[a] += t_re;
+                im[a] += t_im;
+            }
+        }
+        len *= 2;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fft_matches_dft() {
+        // the radix-2 and the direct transforms of the same signal
+        let signal = (0..8).map(|i| (i as f32 * 0.7).sin()).collect::<Vec<_>>();
+        let (mut re, mut im) = (signal.clone(), vec![0.0; 8]);
+        fft(&mut re, &mut im, false);
+
+        for k in 0..8 {
+            let (mut dft_re, mut dft_im) = (0.0, 0.0);
+            for (t, &v) in signal.iter().enumerate() {
+                let angle = -2.0 * PI * (k * t) as f32 / 8.0;
+                dft_re += v * angle.cos();
+                dft_im += v * angle.sin();
+            }
+            assert!((re[k] - dft_re).abs() < 1e-4);
+            assert!((im[k] - dft_im).abs() < 1e-4);
+        }
+    }
+
+    #[test]
+    fn test_fft2_roundtrip() -> Result<(), ImageError> {
+        // a power of two and an arbitrary size
+        for (cols, rows) in [(8, 4), (6, 5)] {
+            let data = (0..cols * rows)
+                .map(|i| (i as f32 * 0.37).cos())
+                .collect::<Vec<_>>();
+            let mut re = Image::new([cols, rows].into(), data.clone())?;
+            let mut im = Image::from_size_val(re.size(), 0.0)?;
+
+            fft2(&mut re, &mut im, false)?;
+            let dc = data.iter().sum::<f32>();
+            assert!((re.as_slice()[0] - dc).abs() < 1e-4);
+
+            fft2(&mut re, &mut im, true)?;
+            for (a, b) in re.as_slice().iter().zip(&data) {
+                assert!((a - b).abs() < 1e-5);
+            }
+            assert!(im.as_slice().iter().all(|v| v.abs() < 1e-5));
+        }
+
+        Ok(())
+    }
+}
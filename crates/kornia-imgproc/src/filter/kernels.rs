@@ -1,3 +1,49 @@
+/// The rule to derive the size of a gaussian kernel from its sigma.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum KernelSizePolicy {
+    /// The rule of OpenCV `GaussianBlur`, the size covers 4 sigmas on each side, rounded.
+    OpenCv,
+    /// The rule of PyTorch Kornia, the size covers 4 sigmas on each side, truncated.
+    Pytorch,
+    /// A fixed kernel size, independent of the sigma.
+    Fixed(usize),
+}
+
+impl KernelSizePolicy {
+    /// Computes the size of the gaussian kernel of a sigma.
+    ///
+    /// # Arguments
+    ///
+    /// * `sigma` - The sigma of the gaussian kernel.
+    ///
+    /// # Returns
+    ///
+    /// The size of the kernel, odd except for a fixed even size.
+    pub fn kernel_size(&self, sigma: f32) -> usize {
+        match self {
+            Self::OpenCv => (8.0 * sigma + 1.0).round() as usize | 1,
+            Self::Pytorch => (8.0 * sigma + 1.0) as usize | 1,
+            Self::Fixed(kernel_size) => *kernel_size,
+        }
+    }
+}
+
+/// Computes the sigma of a gaussian kernel from its size.
+///
+/// The rule is shared by OpenCV `getGaussianKernel` and torchvision `gaussian_blur`.
+///
+/// # Arguments
+///
+/// * `kernel_size` - The size of the gaussian kernel.
+///
+/// # Returns
+///
+/// The sigma of the gaussian kernel.
+pub fn sigma_from_kernel_size(kernel_size: usize) -> f32 {
+    0.3 * ((kernel_size as f32 - 1.0) * 0.5 - 1.0) + 0.8
+}
+
 /// Create a box blur kernel.
 ///
 /// # Arguments
@@ -61,6 +107,69 @@ pub fn sobel_kernel_1d(kernel_size: usize) -> (Vec<f32>, Vec<f32>) {
     (kernel_x, kernel_y)
 }
 
+/// The stencil of the 3x3 derivative kernels.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum GradsMode {
+    /// Sobel operators
+    #[default]
+    Sobel,
+    /// Finite difference
+    Diff,
+    /// Scharr operators, more rotationally invariant than Sobel
+    Scharr,
+}
+
+// the normalized smoothing and central difference kernels of a gradient mode
+fn gradient_kernels_1d(mode: GradsMode) -> ([f32; 3], [f32; 3]) {
+    let smooth = match mode {
+        GradsMode::Sobel => [0.25, 0.5, 0.25],
+        GradsMode::Diff => [0.0, 1.0, 0.0],
+        GradsMode::Scharr => [0.1875, 0.625, 0.1875],
+    };
+    (smooth, [-0.5, 0.0, 0.5])
+}
+
+// the 3x3 kernel of the outer product of a column and a row kernel
+fn outer_kernel3(col: [f32; 3], row: [f32; 3]) -> [[f32; 3]; 3] {
+    col.map(|c| row.map(|r| c * r))
+}
+
+/// Create the normalized 3x3 kernels of the first order derivatives.
+///
+/// # Arguments
+///
+/// * `mode` - The stencil of the derivatives.
+///
+/// # Returns
+///
+/// A tuple of two array of the kernel. (dx_kernel, dy_kernel)
+pub fn gradient_kernel3(mode: GradsMode) -> ([[f32; 3]; 3], [[f32; 3]; 3]) {
+    let (smooth, diff) = gradient_kernels_1d(mode);
+    (outer_kernel3(smooth, diff), outer_kernel3(diff, smooth))
+}
+
+/// Create the normalized 3x3 kernels of the second order derivatives.
+///
+/// The mixed derivative is the product of the central differences for all the modes.
+///
+/// # Arguments
+///
+/// * `mode` - The stencil of the derivatives.
+///
+/// # Returns
+///
+/// An array of the three kernels. [dxx_kernel, dyy_kernel, dxy_kernel]
+pub fn gradient_kernel3_2nd_order(mode: GradsMode) -> [[[f32; 3]; 3]; 3] {
+    let (smooth, diff) = gradient_kernels_1d(mode);
+    let diff2 = [1.0, -2.0, 1.0];
+    [
+        outer_kernel3(smooth, diff2),
+        outer_kernel3(diff2, smooth),
+        outer_kernel3(diff, diff),
+    ]
+}
+
 /// Create a normalized 2d sobel kernel.
 ///
 /// # Arguments
@@ -120,6 +229,43 @@ pub fn box_blur_fast_kernels_1d(sigma: f32, kernels: u8) -> Vec<usize> {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_gradient_kernel3() {
+        assert_eq!(
+            gradient_kernel3(GradsMode::Sobel),
+            normalized_sobel_kernel3()
+        );
+
+        let (dx, dy) = gradient_kernel3(GradsMode::Scharr);
+        assert_eq!(dx[0], [-0.09375, 0.0, 0.09375]);
+        assert_eq!(dx[1], [-0.3125, 0.0, 0.3125]);
+        assert_eq!(dy[2], [0.09375, 0.3125, 0.09375]);
+
+        let [dxx, dyy, dxy] = gradient_kernel3_2nd_order(GradsMode::Diff);
+        assert_eq!(dxx, [[0.0; 3], [1.0, -2.0, 1.0], [0.0; 3]]);
+        assert_eq!(dyy, [[0.0, 1.0, 0.0], [0.0, -2.0, 0.0], [0.0, 1.0, 0.0]]);
+        assert_eq!(
+            dxy,
+            [[0.25, 0.0, -0.25], [0.0, 0.0, 0.0], [-0.25, 0.0, 0.25]]
+        );
+    }
+
+    #[test]
+    fn test_kernel_size_policy() {
+        // the truncated and the rounded sizes differ below the next odd size
+        assert_eq!(KernelSizePolicy::OpenCv.kernel_size(0.8), 7);
+        assert_eq!(KernelSizePolicy::Pytorch.kernel_size(0.8), 7);
+        assert_eq!(KernelSizePolicy::OpenCv.kernel_size(1.1), 11);
+        assert_eq!(KernelSizePolicy::Pytorch.kernel_size(1.1), 9);
+        assert_eq!(KernelSizePolicy::OpenCv.kernel_size(1.5), 13);
+        assert_eq!(KernelSizePolicy::Pytorch.kernel_size(1.5), 13);
+        assert_eq!(KernelSizePolicy::Fixed(5).kernel_size(1.1), 5);
+
+        assert!((sigma_from_kernel_size(3) - 0.8).abs() < 1e-6);
+        assert!((sigma_from_kernel_size(5) - 1.1).abs() < 1e-6);
+        assert!((sigma_from_kernel_size(7) - 1.4).abs() < 1e-6);
+    }
+
     #[test]
     fn test_sobel_kernel_1d() {
         let kernel = sobel_kernel_1d(3);
@@ -1,10 +1,10 @@
+use crate::parallel::prelude::*;
 use kornia_image::{Image, ImageError, ImageSize};
-use rayon::{
-    iter::{IndexedParallelIterator, ParallelIterator},
-    slice::ParallelSliceMut,
-};
 
-use super::{fast_horizontal_filter, kernels, separable_filter};
+use super::{
+    fast_horizontal_filter, kernels, kernels::GradsMode, separable_filter,
+    separable_filter_per_channel,
+};
 
 /// Blur an image using a box blur filter
 ///
@@ -20,6 +20,7 @@ pub fn box_blur<const C: usize>(
     dst: &mut Image<f32, C>,
     kernel_size: (usize, usize),
 ) -> Result<(), ImageError> {
+    profile_scope!("box_blur");
     let kernel_x = kernels::box_blur_kernel_1d(kernel_size.0);
     let kernel_y = kernels::box_blur_kernel_1d(kernel_size.1);
     separable_filter(src, dst, &kernel_x, &kernel_y)?;
@@ -43,12 +44,66 @@ pub fn gaussian_blur<const C: usize>(
     kernel_size: (usize, usize),
     sigma: (f32, f32),
 ) -> Result<(), ImageError> {
+    profile_scope!("gaussian_blur");
     let kernel_x = kernels::gaussian_kernel_1d(kernel_size.0, sigma.0);
     let kernel_y = kernels::gaussian_kernel_1d(kernel_size.1, sigma.1);
     separable_filter(src, dst, &kernel_x, &kernel_y)?;
     Ok(())
 }
 
+/// Blur an image using a gaussian blur filter with the kernel size derived from the sigma
+///
+/// # Arguments
+///
+/// * `src` - The source image with shape (H, W, C).
+/// * `dst` - The destination image with shape (H, W, C).
+/// * `sigma` - The sigma of the gaussian kernel (sigma_x, sigma_y).
+/// * `policy` - The rule to derive the size of the kernel from the sigma.
+///
+/// PRECONDITION: `src` and `dst` must have the same shape.
+/// NOTE: This function uses a constant border type.
+pub fn gaussian_blur_sigma<const C: usize>(
+    src: &Image<f32, C>,
+    dst: &mut Image<f32, C>,
+    sigma: (f32, f32),
+    policy: kernels::KernelSizePolicy,
+) -> Result<(), ImageError> {
+    let kernel_size = (policy.kernel_size(sigma.0), policy.kernel_size(sigma.1));
+    gaussian_blur(src, dst, kernel_size, sigma)
+}
+
+/// Blur an image using a gaussian blur filter with different sigmas for each channel
+///
+/// The channels are blurred in a single pass over the interleaved data.
+///
+/// # Arguments
+///
+/// * `src` - The source image with shape (H, W, C).
+/// * `dst` - The destination image with shape (H, W, C).
+/// * `sigmas` - The sigma of the gaussian kernel of each channel (sigma_x, sigma_y).
+/// * `policy` - The rule to derive the size of the kernels from the sigmas.
+///
+/// PRECONDITION: `src` and `dst` must have the same shape.
+/// NOTE: This function uses a constant border type.
+pub fn gaussian_blur_per_channel<const C: usize>(
+    src: &Image<f32, C>,
+    dst: &mut Image<f32, C>,
+    sigmas: [(f32, f32); C],
+    policy: kernels::KernelSizePolicy,
+) -> Result<(), ImageError> {
+    profile_scope!("gaussian_blur_per_channel");
+    let kernels_x = sigmas
+        .map(|(sigma_x, _)| kernels::gaussian_kernel_1d(policy.kernel_size(sigma_x), sigma_x));
+    let kernels_y = sigmas
+        .map(|(_, sigma_y)| kernels::gaussian_kernel_1d(policy.kernel_size(sigma_y), sigma_y));
+    separable_filter_per_channel(
+        src,
+        dst,
+        &std::array::from_fn(|ch| kernels_x[ch].as_slice()),
+        &std::array::from_fn(|ch| kernels_y[ch].as_slice()),
+    )
+}
+
 /// Computer sobel filter
 ///
 /// # Arguments
@@ -63,6 +118,7 @@ pub fn sobel<const C: usize>(
     dst: &mut Image<f32, C>,
     kernel_size: usize,
 ) -> Result<(), ImageError> {
+    profile_scope!("sobel");
     // get the sobel kernels
     let (kernel_x, kernel_y) = kernels::sobel_kernel_1d(kernel_size);
 
@@ -100,6 +156,7 @@ pub fn box_blur_fast<const C: usize>(
     dst: &mut Image<f32, C>,
     sigma: (f32, f32),
 ) -> Result<(), ImageError> {
+    profile_scope!("box_blur_fast");
     let half_kernel_x_sizes = kernels::box_blur_fast_kernels_1d(sigma.0, 3);
     let half_kernel_y_sizes = kernels::box_blur_fast_kernels_1d(sigma.1, 3);
 
@@ -123,17 +180,21 @@ pub fn box_blur_fast<const C: usize>(
     Ok(())
 }
 
-/// Compute the first order image derivative in both x and y using a Sobel operator.
+/// Compute the first order image derivative in both x and y using a 3x3 stencil.
 ///
 /// # Arguments
 ///
-/// * `src` - The source image with shape (H, W).
-/// * `dst` - The destination image with shape (H, W, 2).
+/// * `src` - The source image with shape (H, W, C).
+/// * `dx` - The derivative in x with shape (H, W, C).
+/// * `dy` - The derivative in y with shape (H, W, C).
+/// * `mode` - The stencil of the derivatives.
 pub fn spatial_gradient_float<const C: usize>(
     src: &Image<f32, C>,
     dx: &mut Image<f32, C>,
     dy: &mut Image<f32, C>,
+    mode: GradsMode,
 ) -> Result<(), ImageError> {
+    profile_scope!("spatial_gradient_float");
     if src.size() != dx.size() {
         return Err(ImageError::InvalidImageSize(
             src.cols(),
@@ -152,7 +213,7 @@ pub fn spatial_gradient_float<const C: usize>(
         ));
     }
 
-    let (sobel_x, sobel_y) = kernels::normalized_sobel_kernel3();
+    let (kernel_x, kernel_y) = kernels::gradient_kernel3(mode);
     let cols = src.cols();
 
     let src_data = src.as_slice();
@@ -176,8 +237,8 @@ pub fn spatial_gradient_float<const C: usize>(
                             for ch in 0..C {
                                 let src_pix_offset = (row * src.cols() + col) * C + ch;
                                 let val = unsafe { src_data.get_unchecked(src_pix_offset) };
-                                sum_x[ch] += val * sobel_x[dy][dx];
-                                sum_y[ch] += val * sobel_y[dy][dx];
+                                sum_x[ch] += val * kernel_x[dy][dx];
+                                sum_y[ch] += val * kernel_y[dy][dx];
                             }
                         }
                     }
@@ -189,18 +250,22 @@ pub fn spatial_gradient_float<const C: usize>(
     Ok(())
 }
 
-/// Compute the first order image derivative in both x and y using a Sobel operator.
+/// Compute the first order image derivative in both x and y using a 3x3 stencil.
 /// Parallel by row.
 ///
 /// # Arguments
 ///
-/// * `src` - The source image with shape (H, W).
-/// * `dst` - The destination image with shape (H, W, 2).
+/// * `src` - The source image with shape (H, W, C).
+/// * `dx` - The derivative in x with shape (H, W, C).
+/// * `dy` - The derivative in y with shape (H, W, C).
+/// * `mode` - The stencil of the derivatives.
 pub fn spatial_gradient_float_parallel_row<const C: usize>(
     src: &Image<f32, C>,
     dx: &mut Image<f32, C>,
     dy: &mut Image<f32, C>,
+    mode: GradsMode,
 ) -> Result<(), ImageError> {
+    profile_scope!("spatial_gradient_float_parallel_row");
     if src.size() != dx.size() {
         return Err(ImageError::InvalidImageSize(
             src.cols(),
@@ -219,7 +284,7 @@ pub fn spatial_gradient_float_parallel_row<const C: usize>(
         ));
     }
 
-    let (sobel_x, sobel_y) = kernels::normalized_sobel_kernel3();
+    let (kernel_x, kernel_y) = kernels::gradient_kernel3(mode);
     let cols = src.cols();
 
     let src_data = src.as_slice();
@@ -243,8 +308,8 @@ pub fn spatial_gradient_float_parallel_row<const C: usize>(
                             for ch in 0..C {
                                 let src_pix_offset = (row * src.cols() + col) * C + ch;
                                 let val = unsafe { src_data.get_unchecked(src_pix_offset) };
-                                sum_x[ch] += val * sobel_x[dy][dx];
-                                sum_y[ch] += val * sobel_y[dy][dx];
+                                sum_x[ch] += val * kernel_x[dy][dx];
+                                sum_y[ch] += val * kernel_y[dy][dx];
                             }
                         }
                     }
@@ -256,18 +321,22 @@ pub fn spatial_gradient_float_parallel_row<const C: usize>(
     Ok(())
 }
 
-/// Compute the first order image derivative in both x and y using a Sobel operator.
+/// Compute the first order image derivative in both x and y using a 3x3 stencil.
 /// Parallel both by row and col.
 ///
 /// # Arguments
 ///
-/// * `src` - The source image with shape (H, W).
-/// * `dst` - The destination image with shape (H, W, 2).
+/// * `src` - The source image with shape (H, W, C).
+/// * `dx` - The derivative in x with shape (H, W, C).
+/// * `dy` - The derivative in y with shape (H, W, C).
+/// * `mode` - The stencil of the derivatives.
 pub fn spatial_gradient_float_parallel<const C: usize>(
     src: &Image<f32, C>,
     dx: &mut Image<f32, C>,
     dy: &mut Image<f32, C>,
+    mode: GradsMode,
 ) -> Result<(), ImageError> {
+    profile_scope!("spatial_gradient_float_parallel");
     if src.size() != dx.size() {
         return Err(ImageError::InvalidImageSize(
             src.cols(),
@@ -286,7 +355,7 @@ pub fn spatial_gradient_float_parallel<const C: usize>(
         ));
     }
 
-    let (sobel_x, sobel_y) = kernels::normalized_sobel_kernel3();
+    let (kernel_x, kernel_y) = kernels::gradient_kernel3(mode);
     let cols = src.cols();
 
     let src_data = src.as_slice();
@@ -310,8 +379,8 @@ pub fn spatial_gradient_float_parallel<const C: usize>(
                             for ch in 0..C {
                                 let src_pix_offset = (row * src.cols() + col) * C + ch;
                                 let val = unsafe { src_data.get_unchecked(src_pix_offset) };
-                                sum_x[ch] += val * sobel_x[dy][dx];
-                                sum_y[ch] += val * sobel_y[dy][dx];
+                                sum_x[ch] += val * kernel_x[dy][dx];
+                                sum_y[ch] += val * kernel_y[dy][dx];
                             }
                         }
                     }
@@ -323,6 +392,74 @@ pub fn spatial_gradient_float_parallel<const C: usize>(
     Ok(())
 }
 
+/// Compute the second order image derivatives in x, y and xy using a 3x3 stencil.
+///
+/// # Arguments
+///
+/// * `src` - The source image with shape (H, W, C).
+/// * `dxx` - The second derivative in x with shape (H, W, C).
+/// * `dyy` - The second derivative in y with shape (H, W, C).
+/// * `dxy` - The mixed derivative with shape (H, W, C).
+/// * `mode` - The stencil of the derivatives.
+pub fn spatial_gradient_float_order2<const C: usize>(
+    src: &Image<f32, C>,
+    dxx: &mut Image<f32, C>,
+    dyy: &mut Image<f32, C>,
+    dxy: &mut Image<f32, C>,
+    mode: GradsMode,
+) -> Result<(), ImageError> {
+    profile_scope!("spatial_gradient_float_order2");
+    for dst in [&*dxx, &*dyy, &*dxy] {
+        if src.size() != dst.size() {
+            return Err(ImageError::InvalidImageSize(
+                src.cols(),
+                src.rows(),
+                dst.cols(),
+                dst.rows(),
+            ));
+        }
+    }
+
+    let kernels = kernels::gradient_kernel3_2nd_order(mode);
+    let cols = src.cols();
+
+    let src_data = src.as_slice();
+
+    dxx.as_slice_mut()
+        .par_chunks_mut(cols * C)
+        .zip(dyy.as_slice_mut().par_chunks_mut(cols * C))
+        .zip(dxy.as_slice_mut().par_chunks_mut(cols * C))
+        .enumerate()
+        .for_each(|(r, ((dxx_row, dyy_row), dxy_row))| {
+            for (c, ((dxx_c, dyy_c), dxy_c)) in dxx_row
+                .chunks_mut(C)
+                .zip(dyy_row.chunks_mut(C))
+                .zip(dxy_row.chunks_mut(C))
+                .enumerate()
+            {
+                let mut sums = [[0.0; C]; 3];
+                for dy in 0..3 {
+                    for dx in 0..3 {
+                        // replicate the border pixels
+                        let row = (r + dy).min(src.rows()).max(1) - 1;
+                        let col = (c + dx).min(src.cols()).max(1) - 1;
+                        for ch in 0..C {
+                            let val = src_data[(row * cols + col) * C + ch];
+                            for (sum, kernel) in sums.iter_mut().zip(&kernels) {
+                                sum[ch] += val * kernel[dy][dx];
+                            }
+                        }
+                    }
+                }
+                dxx_c.copy_from_slice(&sums[0]);
+                dyy_c.copy_from_slice(&sums[1]);
+                dxy_c.copy_from_slice(&sums[2]);
+            }
+        });
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -389,11 +526,82 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_gaussian_blur_sigma() -> Result<(), ImageError> {
+        let size = ImageSize {
+            width: 9,
+            height: 7,
+        };
+        let img = Image::<_, 1>::new(size, (0..63).map(|x| (x % 5) as f32).collect())?;
+
+        let mut expected = Image::from_size_val(size, 0.0)?;
+        gaussian_blur(&img, &mut expected, (11, 11), (1.1, 1.1))?;
+
+        let mut dst = Image::from_size_val(size, 0.0)?;
+        gaussian_blur_sigma(
+            &img,
+            &mut dst,
+            (1.1, 1.1),
+            kernels::KernelSizePolicy::OpenCv,
+        )?;
+        assert_eq!(dst.as_slice(), expected.as_slice());
+
+        gaussian_blur(&img, &mut expected, (9, 9), (1.1, 1.1))?;
+        gaussian_blur_sigma(
+            &img,
+            &mut dst,
+            (1.1, 1.1),
+            kernels::KernelSizePolicy::Pytorch,
+        )?;
+        assert_eq!(dst.as_slice(), expected.as_slice());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_gaussian_blur_per_channel() -> Result<(), ImageError> {
+        let size = ImageSize {
+            width: 8,
+            height: 6,
+        };
+        // a flow field with a sharp edge in both components
+        let img = Image::<f32, 2>::new(
+            size,
+            (0..48)
+                .flat_map(|i| if i % 8 < 4 { [1.0, -2.0] } else { [3.0, 0.5] })
+                .collect(),
+        )?;
+
+        let sigmas = [(0.8, 1.2), (1.5, 0.5)];
+        let policy = kernels::KernelSizePolicy::OpenCv;
+        let mut dst = Image::from_size_val(size, 0.0)?;
+        gaussian_blur_per_channel(&img, &mut dst, sigmas, policy)?;
+
+        // the same as blurring the channels separately
+        for (ch, (sigma_x, sigma_y)) in sigmas.into_iter().enumerate() {
+            let mut expected = Image::from_size_val(size, 0.0)?;
+            gaussian_blur_sigma(&img.channel(ch)?, &mut expected, (sigma_x, sigma_y), policy)?;
+            assert_eq!(dst.channel(ch)?.as_slice(), expected.as_slice(), "{ch}");
+        }
+
+        // the same sigmas blur as the shared kernel
+        let mut expected = Image::from_size_val(size, 0.0)?;
+        gaussian_blur_sigma(&img, &mut expected, (1.1, 1.1), policy)?;
+        gaussian_blur_per_channel(&img, &mut dst, [(1.1, 1.1); 2], policy)?;
+        assert_eq!(dst.as_slice(), expected.as_slice());
+
+        Ok(())
+    }
+
     #[test]
     fn test_spatial_gradient() -> Result<(), ImageError> {
         // First, define a type alias for the function signature
-        type FilterFunction =
-            fn(&Image<f32, 2>, &mut Image<f32, 2>, &mut Image<f32, 2>) -> Result<(), ImageError>;
+        type FilterFunction = fn(
+            &Image<f32, 2>,
+            &mut Image<f32, 2>,
+            &mut Image<f32, 2>,
+            GradsMode,
+        ) -> Result<(), ImageError>;
 
         // Then, define a type for the test tuple
         type TestCase = (FilterFunction, &'static str);
@@ -425,7 +633,7 @@ mod tests {
             let mut dx = Image::<_, 2>::from_size_val(size, 0.0)?;
             let mut dy = Image::<_, 2>::from_size_val(size, 0.0)?;
 
-            test_fn(&img, &mut dx, &mut dy)?;
+            test_fn(&img, &mut dx, &mut dy, GradsMode::Sobel)?;
 
             #[rustfmt::skip]
             assert_eq!(
@@ -486,4 +694,78 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_spatial_gradient_modes() -> Result<(), ImageError> {
+        let size = ImageSize {
+            width: 5,
+            height: 5,
+        };
+
+        // the response to an impulse is the flipped kernel
+        let mut img = Image::<f32, 1>::from_size_val(size, 0.0)?;
+        img.as_slice_mut()[2 * 5 + 2] = 1.0;
+
+        let mut dx = Image::from_size_val(size, 0.0)?;
+        let mut dy = Image::from_size_val(size, 0.0)?;
+        for (mode, corner, edge) in [
+            (GradsMode::Sobel, 0.125, 0.25),
+            (GradsMode::Diff, 0.0, 0.5),
+            (GradsMode::Scharr, 0.09375, 0.3125),
+        ] {
+            spatial_gradient_float(&img, &mut dx, &mut dy, mode)?;
+            assert_eq!(dx.as_slice()[5 + 1], corner, "{mode:?}");
+            assert_eq!(dx.as_slice()[2 * 5 + 1], edge, "{mode:?}");
+            assert_eq!(dx.as_slice()[2 * 5 + 3], -edge, "{mode:?}");
+            assert_eq!(dy.as_slice()[5 + 2], edge, "{mode:?}");
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_spatial_gradient_order2() -> Result<(), ImageError> {
+        let size = ImageSize {
+            width: 5,
+            height: 5,
+        };
+
+        // x^2 + x * y, the second derivatives are exact in the interior
+        let img = Image::<f32, 1>::new(
+            size,
+            (0..25)
+                .map(|i| {
+                    let (x, y) = ((i % 5) as f32, (i / 5) as f32);
+                    x * x + x * y
+                })
+                .collect(),
+        )?;
+
+        let mut dxx = Image::from_size_val(size, 0.0)?;
+        let mut dyy = Image::from_size_val(size, 0.0)?;
+        let mut dxy = Image::from_size_val(size, 0.0)?;
+        for mode in [GradsMode::Sobel, GradsMode::Diff, GradsMode::Scharr] {
+            spatial_gradient_float_order2(&img, &mut dxx, &mut dyy, &mut dxy, mode)?;
+            for r in 1..4 {
+                for c in 1..4 {
+                    let idx = r * 5 + c;
+                    assert!((dxx.as_slice()[idx] - 2.0).abs() < 1e-5, "{mode:?}");
+                    assert!(dyy.as_slice()[idx].abs() < 1e-5, "{mode:?}");
+                    assert!((dxy.as_slice()[idx] - 1.0).abs() < 1e-5, "{mode:?}");
+                }
+            }
+        }
+
+        let mut wrong = Image::from_size_val([4, 5].into(), 0.0)?;
+        assert!(spatial_gradient_float_order2(
+            &img,
+            &mut dxx,
+            &mut dyy,
+            &mut wrong,
+            GradsMode::Sobel
+        )
+        .is_err());
+
+        Ok(())
+    }
 }
@@ -56,11 +56,50 @@ pub fn separable_filter<T, const C: usize>(
 where
     T: FloatConversion + Clone + Zero + std::ops::Mul<Output = T> + std::ops::AddAssign,
 {
-    if kernel_x.is_empty() || kernel_y.is_empty() {
-        return Err(ImageError::InvalidKernelLength(
-            kernel_x.len(),
-            kernel_y.len(),
-        ));
+    profile_scope!("separable_filter");
+    separable_filter_impl(src, dst, &[kernel_x; C], &[kernel_y; C])
+}
+
+/// Apply a separable filter with different kernels for each channel to an image.
+///
+/// The channels are filtered in a single pass over the interleaved data, e.g. to blur the
+/// components of a flow field with different strengths.
+///
+/// # Arguments
+///
+/// * `src` - The source image with shape (H, W, C).
+/// * `dst` - The destination image with shape (H, W, C).
+/// * `kernels_x` - The horizontal kernel of each channel.
+/// * `kernels_y` - The vertical kernel of each channel.
+pub fn separable_filter_per_channel<T, const C: usize>(
+    src: &Image<T, C>,
+    dst: &mut Image<T, C>,
+    kernels_x: &[&[f32]; C],
+    kernels_y: &[&[f32]; C],
+) -> Result<(), ImageError>
+where
+    T: FloatConversion + Clone + Zero + std::ops::Mul<Output = T> + std::ops::AddAssign,
+{
+    profile_scope!("separable_filter_per_channel");
+    separable_filter_impl(src, dst, kernels_x, kernels_y)
+}
+
+fn separable_filter_impl<T, const C: usize>(
+    src: &Image<T, C>,
+    dst: &mut Image<T, C>,
+    kernels_x: &[&[f32]; C],
+    kernels_y: &[&[f32]; C],
+) -> Result<(), ImageError>
+where
+    T: FloatConversion + Clone + Zero + std::ops::Mul<Output = T> + std::ops::AddAssign,
+{
+    for (kernel_x, kernel_y) in kernels_x.iter().zip(kernels_y) {
+        if kernel_x.is_empty() || kernel_y.is_empty() {
+            return Err(ImageError::InvalidKernelLength(
+                kernel_x.len(),
+                kernel_y.len(),
+            ));
+        }
     }
 
     if src.size() != dst.size() {
@@ -72,8 +111,8 @@ where
         ));
     }
 
-    let half_kernel_x = kernel_x.len() / 2;
-    let half_kernel_y = kernel_y.len() / 2;
+    let half_kernels_x = kernels_x.map(|kernel| kernel.len() / 2);
+    let half_kernels_y = kernels_y.map(|kernel| kernel.len() / 2);
 
     let src_data = src.as_slice();
     let dst_data = dst.as_slice_mut();
@@ -89,8 +128,8 @@ where
             for ch in 0..C {
                 let pix_offset = col_offset + ch;
                 let mut row_acc = 0.0f32;
-                for (k_idx, k_val) in kernel_x.iter().enumerate() {
-                    let x_pos = c as isize + k_idx as isize - half_kernel_x as isize;
+                for (k_idx, k_val) in kernels_x[ch].iter().enumerate() {
+                    let x_pos = c as isize + k_idx as isize - half_kernels_x[ch] as isize;
                     if x_pos >= 0 && x_pos < src.cols() as isize {
                         let neighbor_idx = (row_offset + x_pos as usize) * C + ch;
                         let neighbor_val = unsafe { src_data.get_unchecked(neighbor_idx) };
@@ -113,8 +152,8 @@ where
             for ch in 0..C {
                 let pix_offset = col_offset + ch;
                 let mut col_acc = 0.0f32;
-                for (k_idx, k_val) in kernel_y.iter().enumerate() {
-                    let y_pos = r as isize + k_idx as isize - half_kernel_y as isize;
+                for (k_idx, k_val) in kernels_y[ch].iter().enumerate() {
+                    let y_pos = r as isize + k_idx as isize - half_kernels_y[ch] as isize;
                     if y_pos >= 0 && y_pos < src.rows() as isize {
                         let neighbor_idx = (y_pos as usize * src.cols() + c) * C + ch;
                         let neighbor_val = unsafe { temp.get_unchecked(neighbor_idx) };
@@ -1,8 +1,5 @@
+use crate::parallel::prelude::*;
 use kornia_image::{Image, ImageError};
-use rayon::{
-    iter::{IndexedParallelIterator, ParallelIterator},
-    slice::{ParallelSlice, ParallelSliceMut},
-};
 
 /// Flip the input image horizontally.
 ///
@@ -0,0 +1,235 @@
+use kornia_image::Image;
+
+// the side of the square grid of a 64-bit hash
+const HASH_SIZE: usize = 8;
+
+// the side of the image transformed by the perceptual hash
+const DCT_SIZE: usize = 32;
+
+/// Compute the average hash (aHash) of a grayscale image.
+///
+/// The image is reduced to 8x8 by averaging the pixels and every bit is set where the
+/// reduced image is brighter than its mean.
+///
+/// # Arguments
+///
+/// * `src` - The grayscale image with shape (H, W, 1).
+///
+/// # Returns
+///
+/// The 64-bit hash, with the bits in the row-major order of the grid.
+///
+/// # Example
+///
+/// ```
+/// use kornia_image::Image;
+/// use kornia_imgproc::hash::{average_hash, hamming_distance};
+///
+/// let src = Image::<f32, 1>::new([16, 16].into(), (0..256).map(|i| i as f32).collect()).unwrap();
+///
+/// // the hash does not depend on the contrast of the image
+/// let scaled = src.map(|&v| 0.5 * v + 10.0).unwrap();
+///
+/// assert_eq!(hamming_distance(average_hash(&src), average_hash(&scaled)), 0);
+/// ```
+pub fn average_hash(src: &Image<f32, 1>) -> u64 {
+    let pixels = area_downsample(src, HASH_SIZE, HASH_SIZE);
+    let mean = pixels.iter().sum::<f32>() / pixels.len() as f32;
+    pack_bits(pixels.iter().map(|&v| v > mean))
+}
+
+/// Compute the difference hash (dHash) of a grayscale image.
+///
+/// The image is reduced to 9x8 by averaging the pixels and every bit is set where a
+/// pixel of the reduced image is darker than its right neighbour.
+///
+/// # Arguments
+///
+/// * `src` - The grayscale image with shape (H, W, 1).
+///
+/// # Returns
+///
+/// The 64-bit hash, with the bits in the row-major order of the gradients.
+pub fn difference_hash(src: &Image<f32, 1>) -> u64 {
+    let pixels = area_downsample(src, HASH_SIZE + 1, HASH_SIZE);
+    pack_bits(
+        pixels
+            .chunks_exact(HASH_SIZE + 1)
+            .flat_map(|row| row.windows(2).map(|w| w[0] < w[1])),
+    )
+}
+
+/// Compute the perceptual hash (pHash) of a grayscale image.
+///
+/// The image is reduced to 32x32 by averaging the pixels and transformed with the
+/// discrete cosine transform. Every bit is set where one of the 8x8 lowest frequency
+/// coefficients is larger than their median, which makes the hash robust to the
+/// compression, the blur and the small changes of the content.
+///
+/// # Arguments
+///
+/// * `src` - The grayscale image with shape (H, W, 1).
+///
+/// # Returns
+///
+/// The 64-bit hash, with the bits in the row-major order of the frequencies.
+pub fn perceptual_hash(src: &Image<f32, 1>) -> u64 {
+    let pixels = area_downsample(src, DCT_SIZE, DCT_SIZE);
+
+    // the cosines of the DCT-II for the low frequencies
+    let basis = (0..HASH_SIZE)
+        .flat_map(|u| {
+            (0..DCT_SIZE).map(move |x| {
+                (std::f32::consts::PI * (2 * x + 1) as f32 * u as f32 / (2 * DCT_SIZE) as f32).cos()
+            })
+        })
+        .collect::<Vec<_>>();
+
+    // transform the rows first and then the columns of the low frequencies
+    let rows = pixels
+        .chunks_exact(DCT_SIZE)
+        .flat_map(|row| {
+            basis
+                .chunks_exact(DCT_SIZE)
+                .map(|cosines| row.iter().zip(cosines).map(|(p, c)| p * c).sum::<f32>())
+        })
+        .collect::<Vec<_>>();
+    let coefficients = basis
+        .chunks_exact(DCT_SIZE)
+        .flat_map(|cosines| {
+            let rows = &rows;
+            (0..HASH_SIZE).map(move |u| {
+                cosines
+                    .iter()
+                    .enumerate()
+                    .map(|(y, c)| rows[y * HASH_SIZE + u] * c)
+                    .sum::<f32>()
+            })
+        })
+        .collect::<Vec<_>>();
+
+    let mut sorted = coefficients.clone();
+    sorted.sort_by(|a, b| a.total_cmp(b));
+    let median = 0.5 * (sorted[sorted.len() / 2 - 1] + sorted[sorted.len() / 2]);
+
+    pack_bits(coefficients.iter().map(|&v| v > median))
+}
+
+/// Compute the Hamming distance between two hashes.
+///
+/// # Arguments
+///
+/// * `a` - The first hash.
+/// * `b` - The second hash.
+///
+/// # Returns
+///
+/// The number of different bits, from 0 for the same hashes to 64.
+pub fn hamming_distance(a: u64, b: u64) -> u32 {
+    (a ^ b).count_ones()
+}
+
+/// Compute the similarity between two hashes.
+///
+/// # Arguments
+///
+/// * `a` - The first hash.
+/// * `b` - The second hash.
+///
+/// # Returns
+///
+/// The fraction of equal bits, from 0 to 1 for the same hashes.
+pub fn hash_similarity(a: u64, b: u64) -> f32 {
+    1.0 - hamming_distance(a, b) as f32 / 64.0
+}
+
+// reduce an image by averaging the pixels in the cells of a grid
+fn area_downsample(src: &Image<f32, 1>, cols: usize, rows: usize) -> Vec<f32> {
+    let (src_cols, src_rows) = (src.cols(), src.rows());
+    let data = src.as_slice();
+
+    // the cells cover at least one pixel when the image is smaller than the grid
+    let range = |i: usize, n: usize, len: usize| {
+        let start = (i * len / n).min(len.saturating_sub(1));
+        start..((i + 1) * len / n).max(start + 1)
+    };
+
+    (0..rows)
+        .flat_map(|r| (0..cols).map(move |c| (r, c)))
+        .map(|(r, c)| {
+            let (ys, xs) = (range(r, rows, src_rows), range(c, cols, src_cols));
+            let count = ys.len() * xs.len();
+            let sum = ys
+                .flat_map(|y| data[y * src_cols + xs.start..y * src_cols + xs.end].iter())
+                .sum::<f32>();
+            sum / count as f32
+        })
+        .collect()
+}
+
+fn pack_bits(bits: impl Iterator<Item = bool>) -> u64 {
+    bits.enumerate()
+        .fold(0, |hash, (i, bit)| hash | ((bit as u64) << i))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use kornia_image::ImageError;
+
+    // a smooth pattern with some structure at the scale of the hashes
+    fn pattern(size: usize, phase: f32) -> Result<Image<f32, 1>, ImageError> {
+        Image::new(
+            [size, size].into(),
+            (0..size * size)
+                .map(|i| {
+                    let (x, y) = (
+                        (i % size) as f32 / size as f32,
+                        (i / size) as f32 / size as f32,
+                    );
+                    (7.0 * x + phase).sin() * (5.0 * y).cos() + x * y
+                })
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn test_hamming_distance() {
+        assert_eq!(hamming_distance(0, 0), 0);
+        assert_eq!(hamming_distance(0b1011, 0b0110), 3);
+        assert_eq!(hamming_distance(0, u64::MAX), 64);
+        assert_eq!(hash_similarity(0, u64::MAX), 0.0);
+        assert_eq!(hash_similarity(42, 42), 1.0);
+    }
+
+    #[test]
+    fn test_image_hashes() -> Result<(), ImageError> {
+        let src = pattern(128, 0.0)?;
+        let resized = pattern(96, 0.0)?;
+        let brighter = src.map(|&v| 1.5 * v + 0.2)?;
+        let other = pattern(128, 2.0)?;
+
+        let hashes: [fn(&Image<f32, 1>) -> u64; 3] =
+            [average_hash, difference_hash, perceptual_hash];
+        for hash in hashes {
+            let h = hash(&src);
+            assert_ne!(h, 0);
+            assert_eq!(hamming_distance(h, hash(&brighter)), 0);
+            assert!(hamming_distance(h, hash(&resized)) <= 4);
+            assert!(hamming_distance(h, hash(&other)) > 12);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_area_downsample() -> Result<(), ImageError> {
+        let src = Image::new([4, 2].into(), vec![0.0, 2.0, 4.0, 6.0, 2.0, 4.0, 6.0, 8.0])?;
+        assert_eq!(area_downsample(&src, 2, 1), vec![2.0, 6.0]);
+
+        // the image is repeated when it is smaller than the grid
+        assert_eq!(area_downsample(&src, 4, 4)[..4], [0.0, 2.0, 4.0, 6.0]);
+
+        Ok(())
+    }
+}
@@ -1,4 +1,7 @@
+use alloc::{vec, vec::Vec};
 use kornia_image::{Image, ImageError};
+#[cfg(not(feature = "std"))]
+use num_traits::Float;
 
 /// Compute the pixel intensity histogram of an image.
 ///
@@ -63,6 +66,116 @@ pub fn compute_histogram(
     Ok(())
 }
 
+/// A normalized histogram of the colors of an image region, for the back-projection.
+#[derive(Debug, Clone)]
+pub struct ColorHistogram<const C: usize> {
+    bins: [usize; C],
+    values: Vec<f32>,
+}
+
+impl<const C: usize> ColorHistogram<C> {
+    /// Compute the histogram of the pixels of an image.
+    ///
+    /// The values are scaled so that the largest bin is 255.
+    ///
+    /// # Arguments
+    ///
+    /// * `src` - The image with shape (H, W, C), such as the hue channel for the tracking.
+    /// * `mask` - The optional mask with shape (H, W, 1), non zero for the pixels to count.
+    /// * `bins` - The number of bins of every channel, between 1 and 256.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a number of bins is invalid or the mask has another size.
+    pub fn from_image(
+        src: &Image<u8, C>,
+        mask: Option<&Image<u8, 1>>,
+        bins: [usize; C],
+    ) -> Result<Self, ImageError> {
+        if let Some(&num_bins) = bins.iter().find(|&&b| b == 0 || b > 256) {
+            return Err(ImageError::InvalidHistogramBins(num_bins));
+        }
+        if let Some(mask) = mask {
+            if mask.size() != src.size() {
+                return Err(ImageError::InvalidImageSize(
+                    src.cols(),
+                    src.rows(),
+                    mask.cols(),
+                    mask.rows(),
+                ));
+            }
+        }
+
+        let mut histogram = Self {
+            bins,
+            values: vec![0.0; bins.iter().product()],
+        };
+        for (i, pixel) in src.as_slice().chunks_exact(C).enumerate() {
+            if mask.is_some_and(|m| m.as_slice()[i] == 0) {
+                continue;
+            }
+            let bin = histogram.bin(pixel);
+            histogram.values[bin] += 1.0;
+        }
+
+        let max = histogram.values.iter().fold(0.0f32, |a, &b| a.max(b));
+        if max > 0.0 {
+            histogram.values.iter_mut().for_each(|v| *v *= 255.0 / max);
+        }
+
+        Ok(histogram)
+    }
+
+    /// The number of bins of every channel.
+    pub fn bins(&self) -> [usize; C] {
+        self.bins
+    }
+
+    /// The values of the bins, with the last channel varying the fastest.
+    pub fn values(&self) -> &[f32] {
+        &self.values
+    }
+
+    /// Replace every pixel of an image with the value of its bin in the histogram.
+    ///
+    /// The result is the likelihood of every pixel to belong to the region of the
+    /// histogram, as used by the mean shift tracking.
+    ///
+    /// # Arguments
+    ///
+    /// * `src` - The image with shape (H, W, C).
+    /// * `dst` - The back-projection with shape (H, W, 1).
+    pub fn back_project(
+        &self,
+        src: &Image<u8, C>,
+        dst: &mut Image<u8, 1>,
+    ) -> Result<(), ImageError> {
+        if src.size() != dst.size() {
+            return Err(ImageError::InvalidImageSize(
+                src.cols(),
+                src.rows(),
+                dst.cols(),
+                dst.rows(),
+            ));
+        }
+
+        dst.as_slice_mut()
+            .iter_mut()
+            .zip(src.as_slice().chunks_exact(C))
+            .for_each(|(dst, pixel)| {
+                *dst = self.values[self.bin(pixel)].round() as u8;
+            });
+
+        Ok(())
+    }
+
+    fn bin(&self, pixel: &[u8]) -> usize {
+        pixel.iter().zip(&self.bins).fold(0, |index, (&v, &bins)| {
+            index * bins + v as usize * bins / 256
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use kornia_image::{Image, ImageError, ImageSize};
@@ -84,4 +197,27 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_color_histogram_back_project() -> Result<(), ImageError> {
+        let image = Image::<u8, 2>::new(
+            ImageSize {
+                width: 3,
+                height: 1,
+            },
+            vec![10, 200, 10, 200, 250, 0],
+        )?;
+        let mask = Image::new(image.size(), vec![255, 255, 0])?;
+
+        let histogram = super::ColorHistogram::from_image(&image, Some(&mask), [2, 2])?;
+        assert_eq!(histogram.values(), &[0.0, 255.0, 0.0, 0.0]);
+
+        let mut projection = Image::from_size_val(image.size(), 0)?;
+        histogram.back_project(&image, &mut projection)?;
+        assert_eq!(projection.as_slice(), &[255, 255, 0]);
+
+        assert!(super::ColorHistogram::from_image(&image, None, [0, 2]).is_err());
+
+        Ok(())
+    }
 }
@@ -1,4 +1,6 @@
 use kornia_image::Image;
+#[cfg(not(feature = "std"))]
+use num_traits::Float;
 
 /// Kernel for bilinear interpolation
 ///
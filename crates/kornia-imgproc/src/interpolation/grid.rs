@@ -1,7 +1,12 @@
 use kornia_tensor::{CpuAllocator, CpuTensor2, TensorError};
 use num_traits::Float;
-use rayon::iter::ParallelIterator;
-use rayon::{iter::IndexedParallelIterator, slice::ParallelSliceMut};
+
+use crate::parallel::prelude::*;
+use alloc::{boxed::Box, string::ToString};
+#[cfg(not(feature = "std"))]
+use core::error::Error;
+#[cfg(feature = "std")]
+use std::error::Error;
 
 /// Create a meshgrid of x and y coordinates using a custom function
 ///
@@ -34,7 +39,7 @@ use rayon::{iter::IndexedParallelIterator, slice::ParallelSliceMut};
 pub fn meshgrid_from_fn<T>(
     cols: usize,
     rows: usize,
-    f: impl Fn(usize, usize) -> Result<(T, T), Box<dyn std::error::Error + Send + Sync>> + Send + Sync,
+    f: impl Fn(usize, usize) -> Result<(T, T), Box<dyn Error + Send + Sync>> + Send + Sync,
 ) -> Result<(CpuTensor2<T>, CpuTensor2<T>), TensorError>
 where
     T: Float + Send + Sync,
@@ -15,6 +15,8 @@ use kornia_tensor::{CpuAllocator, Tensor2};
 /// * `map_y` - The y coordinates of the pixels to interpolate.
 /// * `interpolation` - The interpolation mode to use.
 ///
+/// The pixels of the output image that map outside of the input image are left unchanged.
+///
 /// # Errors
 ///
 /// * The mapx and mapy must have the same size.
@@ -26,6 +28,7 @@ pub fn remap<const C: usize>(
     map_y: &Tensor2<f32, CpuAllocator>,
     interpolation: InterpolationMode,
 ) -> Result<(), ImageError> {
+    profile_scope!("remap");
     if map_x.shape != map_y.shape {
         return Err(ImageError::InvalidImageSize(
             map_x.shape[0],
@@ -45,7 +48,13 @@ pub fn remap<const C: usize>(
     }
 
     // parallelize the remap operation by rows
+    let (max_x, max_y) = ((src.cols() - 1) as f32, (src.rows() - 1) as f32);
     parallel::par_iter_rows_resample(dst, map_x, map_y, |&x, &y, dst_pixel| {
+        // skip the pixels that map outside of the source image
+        if !(x >= 0.0 && x <= max_x && y >= 0.0 && y <= max_y) {
+            return;
+        }
+
         // interpolate the pixel value
         dst_pixel.iter_mut().enumerate().for_each(|(c, pixel)| {
             *pixel = interpolate_pixel(src, x, y, c, interpolation);
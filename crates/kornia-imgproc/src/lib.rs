@@ -1,38 +1,80 @@
+#![cfg_attr(not(feature = "std"), no_std)]
 #![deny(missing_docs)]
 #![doc = env!("CARGO_PKG_DESCRIPTION")]
+
+extern crate alloc;
+
+// Opens a `tracing` span and times the enclosing scope for the profilers. The macro is
+// defined before the modules to be visible in all of them.
+macro_rules! profile_scope {
+    ($name:literal) => {
+        #[cfg(feature = "std")]
+        let _profile_scope = $crate::profiling::OpScope::enter($name);
+        #[cfg(feature = "tracing")]
+        let _tracing_span = tracing::debug_span!($name).entered();
+    };
+}
+
+/// runtime selection of the implementations of the operations.
+#[cfg(feature = "std")]
+pub mod backend;
+
+/// barcode and QR code detection and decoding module.
+#[cfg(feature = "std")]
+pub mod barcode;
+
 /// image undistortion module.
+#[cfg(feature = "std")]
 pub mod calibration;
 
+/// conversions from and to the pixels of the HTML canvas.
+#[cfg(feature = "std")]
+pub mod canvas;
+
 /// color transformations module.
 pub mod color;
 
 /// image basic operations module.
+#[cfg(feature = "std")]
 pub mod core;
 
 /// image cropping module.
+#[cfg(feature = "std")]
 pub mod crop;
 
 // NOTE: not ready yet
 // pub mod distance_transform;
 
 /// utilities to draw on images.
+#[cfg(feature = "std")]
 pub mod draw;
 
 /// image enhancement module.
+#[cfg(feature = "std")]
 pub mod enhance;
 
 /// feature detection module.
 pub mod features;
 
+/// discrete Fourier transform module.
+#[cfg(feature = "std")]
+pub mod fft;
+
 /// image filtering module.
+#[cfg(feature = "std")]
 pub mod filter;
 
 /// image flipping module.
+#[cfg(feature = "std")]
 pub mod flip;
 
 /// compute image histogram module.
 pub mod histogram;
 
+/// perceptual image hashing module.
+#[cfg(feature = "std")]
+pub mod hash;
+
 /// utilities for interpolation.
 pub mod interpolation;
 
@@ -40,19 +82,55 @@ pub mod interpolation;
 pub mod parallel;
 
 /// image processing metrics module.
+#[cfg(feature = "std")]
 pub mod metrics;
 
 /// operations to normalize images.
+#[cfg(feature = "std")]
 pub mod normalize;
 
 /// utility functions for resizing images.
 pub mod resize;
 
+/// computational photography module.
+#[cfg(feature = "std")]
+pub mod photo;
+
+/// visual saliency estimation module.
+#[cfg(feature = "std")]
+pub mod saliency;
+
+/// image segmentation module.
+#[cfg(feature = "std")]
+pub mod segmentation;
+
+/// panorama stitching module.
+#[cfg(feature = "std")]
+pub mod stitching;
+
 /// operations to threshold images.
 pub mod threshold;
 
+/// tiled processing of large images module.
+#[cfg(feature = "std")]
+pub mod tiling;
+
+/// video analysis module.
+#[cfg(feature = "std")]
+pub mod video;
+
 /// image geometric transformations module.
+#[cfg(feature = "std")]
 pub mod warp;
 
+/// timing statistics and tracing spans of the operations.
+#[cfg(feature = "std")]
+pub mod profiling;
+
 /// Pyramid operations
+#[cfg(feature = "std")]
 pub mod pyramid;
+
+/// graph of chained image operations with reused buffers.
+#[cfg(feature = "std")]
+pub mod pipeline;
@@ -0,0 +1,116 @@
+use kornia_image::{Image, ImageError};
+
+/// Compute the ratio of bad pixels between an estimated and a ground truth disparity map.
+///
+/// A pixel is bad when its absolute disparity error is larger than the threshold, as in
+/// the Middlebury stereo benchmark. Only the pixels with a positive and finite ground truth
+/// are evaluated.
+///
+/// # Arguments
+///
+/// * `disparity` - The estimated disparity map with shape (H, W).
+/// * `disparity_gt` - The ground truth disparity map with shape (H, W).
+/// * `threshold` - The maximum absolute error in pixels of a good pixel, e.g. 1.0 or 2.0.
+///
+/// # Returns
+///
+/// The ratio of bad pixels in the range [0, 1] or zero if no pixel has ground truth.
+///
+/// # Example
+///
+/// ```
+/// use kornia_image::Image;
+/// use kornia_imgproc::metrics::bad_pixel_ratio;
+///
+/// let disparity = Image::<f32, 1>::new([4, 1].into(), vec![10.0, 12.0, 5.0, 7.0]).unwrap();
+/// let disparity_gt = Image::<f32, 1>::new([4, 1].into(), vec![10.5, 10.0, 0.0, 7.0]).unwrap();
+///
+/// let ratio = bad_pixel_ratio(&disparity, &disparity_gt, 1.0).unwrap();
+/// assert!((ratio - 1.0 / 3.0).abs() < 1e-6);
+/// ```
+pub fn bad_pixel_ratio(
+    disparity: &Image<f32, 1>,
+    disparity_gt: &Image<f32, 1>,
+    threshold: f32,
+) -> Result<f32, ImageError> {
+    ratio_over_valid(disparity, disparity_gt, |d, d_gt| {
+        (d - d_gt).abs() > threshold
+    })
+}
+
+/// Compute the D1 error between an estimated and a ground truth disparity map.
+///
+/// A pixel is an outlier when its absolute disparity error is larger than 3 pixels and
+/// than 5% of the ground truth disparity, as in the KITTI 2015 stereo benchmark. Only the
+/// pixels with a positive and finite ground truth are evaluated.
+///
+/// # Arguments
+///
+/// * `disparity` - The estimated disparity map with shape (H, W).
+/// * `disparity_gt` - The ground truth disparity map with shape (H, W).
+///
+/// # Returns
+///
+/// The ratio of outliers in the range [0, 1] or zero if no pixel has ground truth.
+pub fn d1_error(
+    disparity: &Image<f32, 1>,
+    disparity_gt: &Image<f32, 1>,
+) -> Result<f32, ImageError> {
+    ratio_over_valid(disparity, disparity_gt, |d, d_gt| {
+        let error = (d - d_gt).abs();
+        error > 3.0 && error > 0.05 * d_gt
+    })
+}
+
+// the ratio of the pixels with a valid ground truth matching the predicate
+fn ratio_over_valid(
+    disparity: &Image<f32, 1>,
+    disparity_gt: &Image<f32, 1>,
+    is_bad: impl Fn(f32, f32) -> bool,
+) -> Result<f32, ImageError> {
+    if disparity.size() != disparity_gt.size() {
+        return Err(ImageError::InvalidImageSize(
+            disparity.cols(),
+            disparity.rows(),
+            disparity_gt.cols(),
+            disparity_gt.rows(),
+        ));
+    }
+
+    let (bad, count) = disparity
+        .as_slice()
+        .iter()
+        .zip(disparity_gt.as_slice())
+        .filter(|(_, &d_gt)| d_gt.is_finite() && d_gt > 0.0)
+        .fold((0usize, 0usize), |(bad, count), (&d, &d_gt)| {
+            (bad + is_bad(d, d_gt) as usize, count + 1)
+        });
+
+    Ok(if count > 0 {
+        bad as f32 / count as f32
+    } else {
+        0.0
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_disparity_errors() -> Result<(), ImageError> {
+        let disparity_gt = Image::<f32, 1>::new([4, 1].into(), vec![10.0, 100.0, 0.0, 20.0])?;
+        let disparity = Image::<f32, 1>::new([4, 1].into(), vec![14.0, 104.0, 50.0, 20.5])?;
+
+        assert_eq!(bad_pixel_ratio(&disparity, &disparity_gt, 1.0)?, 2.0 / 3.0);
+        assert_eq!(bad_pixel_ratio(&disparity, &disparity_gt, 5.0)?, 0.0);
+
+        // an error of 4 pixels is within 5% of a disparity of 100
+        assert_eq!(d1_error(&disparity, &disparity_gt)?, 1.0 / 3.0);
+
+        let empty_gt = Image::<f32, 1>::from_size_val([4, 1].into(), 0.0)?;
+        assert_eq!(d1_error(&disparity, &empty_gt)?, 0.0);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,107 @@
+use kornia_image::{Image, ImageError};
+
+/// Compute the average endpoint error (EPE) between two optical flow fields.
+///
+/// The EPE is the euclidean distance between the estimated and the ground truth flow
+/// vectors, averaged over the pixels where the ground truth is finite.
+///
+/// # Arguments
+///
+/// * `flow` - The estimated flow with shape (H, W, 2) and the (u, v) displacements.
+/// * `flow_gt` - The ground truth flow with shape (H, W, 2), where non-finite values mark
+///   the pixels without ground truth.
+///
+/// # Returns
+///
+/// The average endpoint error in pixels or zero if no pixel has ground truth.
+///
+/// # Example
+///
+/// ```
+/// use kornia_image::Image;
+/// use kornia_imgproc::metrics::endpoint_error;
+///
+/// let flow = Image::<f32, 2>::new([2, 1].into(), vec![1.0, 0.0, 0.0, 0.0]).unwrap();
+/// let flow_gt = Image::<f32, 2>::new([2, 1].into(), vec![1.0, 0.0, 3.0, 4.0]).unwrap();
+///
+/// assert_eq!(endpoint_error(&flow, &flow_gt).unwrap(), 2.5);
+/// ```
+pub fn endpoint_error(flow: &Image<f32, 2>, flow_gt: &Image<f32, 2>) -> Result<f32, ImageError> {
+    mean_over_valid(flow, flow_gt, |[u, v], [u_gt, v_gt]| {
+        (u - u_gt).hypot(v - v_gt)
+    })
+}
+
+/// Compute the average angular error (AE) between two optical flow fields.
+///
+/// The AE is the angle between the space-time vectors (u, v, 1) of the estimated and the
+/// ground truth flows, as in the Middlebury benchmark, averaged over the pixels where the
+/// ground truth is finite.
+///
+/// # Arguments
+///
+/// * `flow` - The estimated flow with shape (H, W, 2) and the (u, v) displacements.
+/// * `flow_gt` - The ground truth flow with shape (H, W, 2), where non-finite values mark
+///   the pixels without ground truth.
+///
+/// # Returns
+///
+/// The average angular error in degrees or zero if no pixel has ground truth.
+pub fn angular_error(flow: &Image<f32, 2>, flow_gt: &Image<f32, 2>) -> Result<f32, ImageError> {
+    mean_over_valid(flow, flow_gt, |[u, v], [u_gt, v_gt]| {
+        let dot = u * u_gt + v * v_gt + 1.0;
+        let norm = ((u * u + v * v + 1.0) * (u_gt * u_gt + v_gt * v_gt + 1.0)).sqrt();
+        (dot / norm).clamp(-1.0, 1.0).acos().to_degrees()
+    })
+}
+
+// average an error of the flow vectors over the pixels with a finite ground truth
+fn mean_over_valid(
+    flow: &Image<f32, 2>,
+    flow_gt: &Image<f32, 2>,
+    error: impl Fn([f32; 2], [f32; 2]) -> f32,
+) -> Result<f32, ImageError> {
+    if flow.size() != flow_gt.size() {
+        return Err(ImageError::InvalidImageSize(
+            flow.cols(),
+            flow.rows(),
+            flow_gt.cols(),
+            flow_gt.rows(),
+        ));
+    }
+
+    let (sum, count) = flow
+        .as_slice()
+        .chunks_exact(2)
+        .zip(flow_gt.as_slice().chunks_exact(2))
+        .filter(|(_, gt)| gt[0].is_finite() && gt[1].is_finite())
+        .fold((0.0, 0usize), |(sum, count), (f, gt)| {
+            (sum + error([f[0], f[1]], [gt[0], gt[1]]), count + 1)
+        });
+
+    Ok(if count > 0 { sum / count as f32 } else { 0.0 })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_flow_errors() -> Result<(), ImageError> {
+        let flow_gt =
+            Image::<f32, 2>::new([3, 1].into(), vec![1.0, 0.0, 0.0, 1.0, f32::NAN, f32::NAN])?;
+
+        assert_eq!(endpoint_error(&flow_gt, &flow_gt)?, 0.0);
+        assert!(angular_error(&flow_gt, &flow_gt)? < 1e-2);
+
+        // the pixel without ground truth is ignored
+        let flow = Image::<f32, 2>::new([3, 1].into(), vec![0.0, 0.0, 0.0, 0.0, 9.0, 9.0])?;
+        assert_eq!(endpoint_error(&flow, &flow_gt)?, 1.0);
+        assert!((angular_error(&flow, &flow_gt)? - 45.0).abs() < 1e-4);
+
+        let small = Image::<f32, 2>::from_size_val([1, 1].into(), 0.0)?;
+        assert!(endpoint_error(&small, &flow_gt).is_err());
+
+        Ok(())
+    }
+}
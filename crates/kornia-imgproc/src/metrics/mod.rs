@@ -1,7 +1,13 @@
+mod disparity;
+mod flow;
 mod huber;
 mod l1;
 mod mse;
+mod ssim;
 
+pub use disparity::{bad_pixel_ratio, d1_error};
+pub use flow::{angular_error, endpoint_error};
 pub use huber::huber;
 pub use l1::l1_loss;
 pub use mse::{mse, psnr};
+pub use ssim::{ms_ssim, ssim};
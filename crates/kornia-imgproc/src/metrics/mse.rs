@@ -114,7 +114,7 @@ pub fn mse<const C: usize>(
 ///
 /// let psnr = psnr(&image1, &image2, 1.0).unwrap();
 ///
-/// assert_eq!(psnr, 320.15698);
+/// assert_eq!(psnr, -1.249387);
 /// ```
 ///
 /// # Panics
@@ -148,7 +148,7 @@ pub fn psnr<const C: usize>(
         return Ok(f32::INFINITY);
     }
 
-    Ok(20f32 * (max_value / mse.sqrt().log10()))
+    Ok(20f32 * (max_value / mse.sqrt()).log10())
 }
 
 #[cfg(test)]
@@ -216,7 +216,7 @@ mod tests {
             vec![1f32, 3f32, 2f32, 4f32, 5f32, 6f32],
         )?;
         let psnr = crate::metrics::psnr(&image1, &image2, 1.0)?;
-        assert_eq!(psnr, 320.15698);
+        assert_eq!(psnr, -1.249387);
 
         Ok(())
     }
@@ -0,0 +1,280 @@
+use kornia_image::{Image, ImageError};
+
+use crate::filter::{kernels, separable_filter};
+
+// the size and sigma of the gaussian window of the original SSIM paper
+const WINDOW_SIZE: usize = 11;
+const WINDOW_SIGMA: f32 = 1.5;
+
+// the stabilizing constants relative to the dynamic range
+const K1: f32 = 0.01;
+const K2: f32 = 0.03;
+
+// the weights of the scales of MS-SSIM from the original paper
+const MS_SSIM_WEIGHTS: [f32; 5] = [0.0448, 0.2856, 0.3001, 0.2363, 0.1333];
+
+/// Compute the structural similarity index (SSIM) between two images.
+///
+/// The SSIM is defined as:
+///
+/// $ SSIM = \frac{(2 \mu_1 \mu_2 + c_1)(2 \sigma_{12} + c_2)}{(\mu_1^2 + \mu_2^2 + c_1)(\sigma_1^2 + \sigma_2^2 + c_2)} $
+///
+/// where the local statistics are computed with a gaussian window of size 11 and sigma 1.5,
+/// and `c_1 = (0.01 MAX)^2` and `c_2 = (0.03 MAX)^2`. The result is the mean of the SSIM
+/// map over the pixels where the window lies inside the image and over the channels.
+///
+/// # Arguments
+///
+/// * `image1` - The first input image with shape (H, W, C).
+/// * `image2` - The second input image with shape (H, W, C).
+/// * `max_value` - The maximum possible pixel value.
+///
+/// # Returns
+///
+/// The structural similarity between the two images in the range [-1, 1].
+///
+/// # Example
+///
+/// ```
+/// use kornia_image::Image;
+/// use kornia_imgproc::metrics::ssim;
+///
+/// let image = Image::<f32, 1>::new(
+///     [16, 16].into(),
+///     (0..256).map(|i| (i % 7) as f32 / 7.0).collect(),
+/// )
+/// .unwrap();
+///
+/// let ssim = ssim(&image, &image, 1.0).unwrap();
+/// assert!((ssim - 1.0).abs() < 1e-4);
+/// ```
+pub fn ssim<const C: usize>(
+    image1: &Image<f32, C>,
+    image2: &Image<f32, C>,
+    max_value: f32,
+) -> Result<f32, ImageError> {
+    let (l, cs) = ssim_components(image1, image2, max_value)?;
+    Ok(l.iter().zip(&cs).map(|(l, cs)| l * cs).sum::<f32>() / l.len() as f32)
+}
+
+/// Compute the multi-scale structural similarity index (MS-SSIM) between two images.
+///
+/// The contrast and structure terms of the SSIM are computed at five scales, halving the
+/// resolution of the images between the scales, and the luminance term at the coarsest
+/// scale only. The terms are combined with the weights of Wang et al. 2003.
+///
+/// # Arguments
+///
+/// * `image1` - The first input image with shape (H, W, C).
+/// * `image2` - The second input image with shape (H, W, C).
+/// * `max_value` - The maximum possible pixel value.
+///
+/// # Returns
+///
+/// The multi-scale structural similarity between the two images in the range [0, 1].
+///
+/// # Errors
+///
+/// The images must be at least 176 pixels wide and high, so that the gaussian window fits
+/// in the coarsest scale.
+pub fn ms_ssim<const C: usize>(
+    image1: &Image<f32, C>,
+    image2: &Image<f32, C>,
+    max_value: f32,
+) -> Result<f32, ImageError> {
+    check_same_size(image1, image2)?;
+
+    let min_size = WINDOW_SIZE << (MS_SSIM_WEIGHTS.len() - 1);
+    if image1.cols() < min_size || image1.rows() < min_size {
+        return Err(ImageError::InvalidImageSize(
+            image1.cols(),
+            image1.rows(),
+            min_size,
+            min_size,
+        ));
+    }
+
+    let mut image1 = image1.clone();
+    let mut image2 = image2.clone();
+    let mut result = 1.0;
+
+    for (scale, weight) in MS_SSIM_WEIGHTS.iter().enumerate() {
+        let (l, cs) = ssim_components(&image1, &image2, max_value)?;
+        let num_pixels = l.len() as f32;
+
+        let value = if scale + 1 == MS_SSIM_WEIGHTS.len() {
+            l.iter().zip(&cs).map(|(l, cs)| l * cs).sum::<f32>() / num_pixels
+        } else {
+            image1 = downsample(&image1)?;
+            image2 = downsample(&image2)?;
+            cs.iter().sum::<f32>() / num_pixels
+        };
+
+        // negative similarities are clamped to keep the power real
+        result *= value.max(0.0).powf(*weight);
+    }
+
+    Ok(result)
+}
+
+// the luminance and the contrast-structure terms of the pixels inside the window
+fn ssim_components<const C: usize>(
+    image1: &Image<f32, C>,
+    image2: &Image<f32, C>,
+    max_value: f32,
+) -> Result<(Vec<f32>, Vec<f32>), ImageError> {
+    check_same_size(image1, image2)?;
+
+    if image1.cols() < WINDOW_SIZE || image1.rows() < WINDOW_SIZE {
+        return Err(ImageError::InvalidImageSize(
+            image1.cols(),
+            image1.rows(),
+            WINDOW_SIZE,
+            WINDOW_SIZE,
+        ));
+    }
+
+    let c1 = (K1 * max_value).powi(2);
+    let c2 = (K2 * max_value).powi(2);
+
+    let window = |src: &Image<f32, C>| -> Result<Image<f32, C>, ImageError> {
+        let kernel = kernels::gaussian_kernel_1d(WINDOW_SIZE, WINDOW_SIGMA);
+        let mut dst = Image::from_size_val(src.size(), 0.0)?;
+        separable_filter(src, &mut dst, &kernel, &kernel)?;
+        Ok(dst)
+    };
+
+    let product = |a: &Image<f32, C>, b: &Image<f32, C>| {
+        Image::<f32, C>::new(
+            a.size(),
+            a.as_slice()
+                .iter()
+                .zip(b.as_slice())
+                .map(|(a, b)| a * b)
+                .collect(),
+        )
+    };
+
+    let mu1 = window(image1)?;
+    let mu2 = window(image2)?;
+    let sigma11 = window(&product(image1, image1)?)?;
+    let sigma22 = window(&product(image2, image2)?)?;
+    let sigma12 = window(&product(image1, image2)?)?;
+
+    // skip the pixels where the window is padded with zeros
+    let half = WINDOW_SIZE / 2;
+    let cols = image1.cols();
+    let num_pixels = (cols - 2 * half) * (image1.rows() - 2 * half) * C;
+
+    let mut l = Vec::with_capacity(num_pixels);
+    let mut cs = Vec::with_capacity(num_pixels);
+
+    for r in half..image1.rows() - half {
+        let start = (r * cols + half) * C;
+        let end = (r * cols + cols - half) * C;
+        for i in start..end {
+            let (m1, m2) = (mu1.as_slice()[i], mu2.as_slice()[i]);
+            let s11 = sigma11.as_slice()[i] - m1 * m1;
+            let s22 = sigma22.as_slice()[i] - m2 * m2;
+            let s12 = sigma12.as_slice()[i] - m1 * m2;
+            l.push((2.0 * m1 * m2 + c1) / (m1 * m1 + m2 * m2 + c1));
+            cs.push((2.0 * s12 + c2) / (s11 + s22 + c2));
+        }
+    }
+
+    Ok((l, cs))
+}
+
+// halve the resolution of the image by averaging blocks of 2x2 pixels
+fn downsample<const C: usize>(src: &Image<f32, C>) -> Result<Image<f32, C>, ImageError> {
+    let (cols, rows) = (src.cols() / 2, src.rows() / 2);
+    let mut data = Vec::with_capacity(cols * rows * C);
+
+    for r in 0..rows {
+        for c in 0..cols {
+            for ch in 0..C {
+                let at = |y: usize, x: usize| src.as_slice()[(y * src.cols() + x) * C + ch];
+                let sum = at(2 * r, 2 * c)
+                    + at(2 * r, 2 * c + 1)
+                    + at(2 * r + 1, 2 * c)
+                    + at(2 * r + 1, 2 * c + 1);
+                data.push(sum / 4.0);
+            }
+        }
+    }
+
+    Image::new([cols, rows].into(), data)
+}
+
+fn check_same_size<const C: usize>(
+    image1: &Image<f32, C>,
+    image2: &Image<f32, C>,
+) -> Result<(), ImageError> {
+    if image1.size() != image2.size() {
+        return Err(ImageError::InvalidImageSize(
+            image1.cols(),
+            image1.rows(),
+            image2.cols(),
+            image2.rows(),
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // a smooth pattern with some texture
+    fn pattern(size: usize) -> Result<Image<f32, 1>, ImageError> {
+        let data = (0..size * size)
+            .map(|i| {
+                let (x, y) = ((i % size) as f32, (i / size) as f32);
+                0.5 + 0.25 * (x * 0.3).sin() * (y * 0.2).cos()
+            })
+            .collect();
+        Image::new([size, size].into(), data)
+    }
+
+    #[test]
+    fn test_ssim() -> Result<(), ImageError> {
+        let image = pattern(32)?;
+        assert!((ssim(&image, &image, 1.0)? - 1.0).abs() < 1e-4);
+
+        // a constant offset only changes the luminance term
+        let offset = image.map(|x| x + 0.1)?;
+        let shifted = ssim(&image, &offset, 1.0)?;
+        assert!(shifted < 1.0 && shifted > 0.9);
+
+        // an inverted pattern has a negative structure term
+        let inverted = image.map(|x| 1.0 - x)?;
+        assert!(ssim(&image, &inverted, 1.0)? < 0.0);
+
+        let small = Image::<f32, 1>::from_size_val([8, 8].into(), 0.0)?;
+        assert!(ssim(&small, &small, 1.0).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_ms_ssim() -> Result<(), ImageError> {
+        let image = pattern(176)?;
+        assert!((ms_ssim(&image, &image, 1.0)? - 1.0).abs() < 1e-4);
+
+        let noisy = Image::<f32, 1>::new(
+            image.size(),
+            image
+                .as_slice()
+                .iter()
+                .enumerate()
+                .map(|(i, x)| x + if i % 2 == 0 { 0.05 } else { -0.05 })
+                .collect(),
+        )?;
+        let value = ms_ssim(&image, &noisy, 1.0)?;
+        assert!(value < 1.0 && value > ssim(&image, &noisy, 1.0)?);
+
+        assert!(ms_ssim(&pattern(64)?, &pattern(64)?, 1.0).is_err());
+
+        Ok(())
+    }
+}
@@ -320,4 +320,33 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn normalize_mean_std_f16() -> Result<(), ImageError> {
+        use half::f16;
+
+        let image = Image::<f16, 3>::new(
+            ImageSize {
+                width: 2,
+                height: 1,
+            },
+            [0.0f32, 1.0, 0.0, 1.0, 2.0, 3.0]
+                .map(f16::from_f32)
+                .to_vec(),
+        )?;
+
+        let mean = [0.5, 1.0, 0.5].map(f16::from_f32);
+        let std = [1.0, 1.0, 0.5].map(f16::from_f32);
+
+        let mut normalized = Image::<f16, 3>::from_size_val(image.size(), f16::ZERO)?;
+
+        super::normalize_mean_std(&image, &mut normalized, &mean, &std)?;
+
+        assert_eq!(
+            normalized.as_slice(),
+            [-0.5f32, 0.0, -1.0, 0.5, 1.0, 5.0].map(f16::from_f32)
+        );
+
+        Ok(())
+    }
 }
@@ -1,8 +1,198 @@
-use rayon::prelude::*;
-
 use kornia_image::Image;
 use kornia_tensor::{CpuAllocator, Tensor2};
 
+use self::prelude::*;
+
+#[cfg(feature = "rayon")]
+pub use rayon::{ThreadPool, ThreadPoolBuildError, ThreadPoolBuilder};
+
+/// The rayon parallel iterators, replaced by sequential iterators without the `rayon` feature.
+pub(crate) mod prelude {
+    #[cfg(feature = "rayon")]
+    pub use rayon::{current_num_threads, prelude::*};
+
+    #[cfg(not(feature = "rayon"))]
+    pub use super::serial::*;
+}
+
+// sequential stand-ins for the rayon traits used by the crate, for targets without threads
+// such as wasm32-unknown-unknown or microcontrollers, of which the no_std build only uses
+// a subset
+#[cfg(not(feature = "rayon"))]
+#[cfg_attr(not(feature = "std"), allow(dead_code))]
+mod serial {
+    use core::iter::{FlatMap, Zip};
+    use core::slice::{Chunks, ChunksExact, ChunksExactMut, ChunksMut};
+
+    pub fn current_num_threads() -> usize {
+        1
+    }
+
+    pub trait ParallelSlice<T> {
+        fn par_chunks(&self, size: usize) -> Chunks<'_, T>;
+        fn par_chunks_exact(&self, size: usize) -> ChunksExact<'_, T>;
+    }
+
+    impl<T> ParallelSlice<T> for [T] {
+        fn par_chunks(&self, size: usize) -> Chunks<'_, T> {
+            self.chunks(size)
+        }
+
+        fn par_chunks_exact(&self, size: usize) -> ChunksExact<'_, T> {
+            self.chunks_exact(size)
+        }
+    }
+
+    pub trait ParallelSliceMut<T> {
+        fn par_chunks_mut(&mut self, size: usize) -> ChunksMut<'_, T>;
+        fn par_chunks_exact_mut(&mut self, size: usize) -> ChunksExactMut<'_, T>;
+    }
+
+    impl<T> ParallelSliceMut<T> for [T] {
+        fn par_chunks_mut(&mut self, size: usize) -> ChunksMut<'_, T> {
+            self.chunks_mut(size)
+        }
+
+        fn par_chunks_exact_mut(&mut self, size: usize) -> ChunksExactMut<'_, T> {
+            self.chunks_exact_mut(size)
+        }
+    }
+
+    pub trait IntoParallelIterator: IntoIterator + Sized {
+        fn into_par_iter(self) -> Self::IntoIter {
+            self.into_iter()
+        }
+    }
+
+    impl<I: IntoIterator> IntoParallelIterator for I {}
+
+    pub trait IntoParallelRefIterator<'a> {
+        type Iter: Iterator;
+        fn par_iter(&'a self) -> Self::Iter;
+    }
+
+    impl<'a, I: 'a + ?Sized> IntoParallelRefIterator<'a> for I
+    where
+        &'a I: IntoIterator,
+    {
+        type Iter = <&'a I as IntoIterator>::IntoIter;
+
+        fn par_iter(&'a self) -> Self::Iter {
+            self.into_iter()
+        }
+    }
+
+    pub trait IntoParallelRefMutIterator<'a> {
+        type Iter: Iterator;
+        fn par_iter_mut(&'a mut self) -> Self::Iter;
+    }
+
+    impl<'a, I: 'a + ?Sized> IntoParallelRefMutIterator<'a> for I
+    where
+        &'a mut I: IntoIterator,
+    {
+        type Iter = <&'a mut I as IntoIterator>::IntoIter;
+
+        fn par_iter_mut(&'a mut self) -> Self::Iter {
+            self.into_iter()
+        }
+    }
+
+    pub trait ParallelIterator: Iterator + Sized {
+        fn flat_map_iter<U: IntoIterator, F: FnMut(Self::Item) -> U>(
+            self,
+            f: F,
+        ) -> FlatMap<Self, U, F> {
+            self.flat_map(f)
+        }
+    }
+
+    impl<I: Iterator> ParallelIterator for I {}
+
+    pub trait IndexedParallelIterator: Iterator + Sized {
+        fn zip_eq<J: IntoIterator>(self, other: J) -> Zip<Self, J::IntoIter> {
+            self.zip(other)
+        }
+    }
+
+    impl<I: Iterator> IndexedParallelIterator for I {}
+}
+
+/// Run an operation inside a caller-provided thread pool.
+///
+/// All the parallel operations called within `op` are scheduled in `pool`
+/// instead of the rayon global pool, which allows to embed the library in
+/// applications that manage their own threads.
+///
+/// # Arguments
+///
+/// * `pool` - The thread pool to run the operation in.
+/// * `op` - The operation to run.
+///
+/// # Returns
+///
+/// The value returned by the operation.
+///
+/// # Example
+///
+/// ```
+/// use kornia_image::{Image, ImageSize};
+/// use kornia_imgproc::color::gray_from_rgb;
+/// use kornia_imgproc::parallel::{install, ThreadPoolBuilder};
+///
+/// let image = Image::<f32, 3>::from_size_val([4, 5].into(), 0.0).unwrap();
+/// let mut gray = Image::<f32, 1>::from_size_val(image.size(), 0.0).unwrap();
+///
+/// let pool = ThreadPoolBuilder::new().num_threads(2).build().unwrap();
+///
+/// install(&pool, || gray_from_rgb(&image, &mut gray)).unwrap();
+/// ```
+#[cfg(feature = "rayon")]
+pub fn install<R: Send>(pool: &ThreadPool, op: impl FnOnce() -> R + Send) -> R {
+    pool.install(op)
+}
+
+/// Run an operation using at most `num_threads` threads.
+///
+/// A new thread pool is created for the duration of the call. For repeated
+/// calls prefer creating a [`ThreadPool`] once and use [`install`].
+///
+/// # Arguments
+///
+/// * `num_threads` - The maximum number of threads to use.
+/// * `op` - The operation to run.
+///
+/// # Returns
+///
+/// The value returned by the operation or an error if the pool cannot be created.
+#[cfg(feature = "rayon")]
+pub fn with_num_threads<R: Send>(
+    num_threads: usize,
+    op: impl FnOnce() -> R + Send,
+) -> Result<R, ThreadPoolBuildError> {
+    let pool = ThreadPoolBuilder::new().num_threads(num_threads).build()?;
+    Ok(pool.install(op))
+}
+
+/// Run an operation in a deterministic single-thread mode.
+///
+/// All the parallel operations are executed sequentially in the calling order,
+/// which makes floating point reductions reproducible across runs and machines.
+///
+/// # Arguments
+///
+/// * `op` - The operation to run.
+///
+/// # Returns
+///
+/// The value returned by the operation or an error if the pool cannot be created.
+#[cfg(feature = "rayon")]
+pub fn with_single_thread<R: Send>(
+    op: impl FnOnce() -> R + Send,
+) -> Result<R, ThreadPoolBuildError> {
+    with_num_threads(1, op)
+}
+
 /// Apply a function to each pixel in the image in parallel.
 ///
 /// # Arguments
@@ -104,3 +294,39 @@ pub fn par_iter_rows_resample<const C: usize>(
                 });
         });
 }
+
+#[cfg(all(test, feature = "rayon"))]
+mod tests {
+    use super::*;
+    use kornia_image::ImageError;
+
+    #[test]
+    fn test_install_thread_pool() -> Result<(), Box<dyn std::error::Error>> {
+        let pool = ThreadPoolBuilder::new().num_threads(3).build()?;
+        let num_threads = install(&pool, rayon::current_num_threads);
+        assert_eq!(num_threads, 3);
+        Ok(())
+    }
+
+    #[test]
+    fn test_single_thread_deterministic() -> Result<(), Box<dyn std::error::Error>> {
+        let src = Image::<f32, 1>::new([4, 3].into(), (0..12).map(|x| x as f32).collect())?;
+        let mut dst = Image::<f32, 1>::from_size_val(src.size(), 0.0)?;
+
+        let num_threads = with_single_thread(|| {
+            par_iter_rows_val(&src, &mut dst, |&x, y| *y = 2.0 * x);
+            rayon::current_num_threads()
+        })?;
+
+        assert_eq!(num_threads, 1);
+        assert_eq!(
+            dst.as_slice(),
+            (0..12).map(|x| 2.0 * x as f32).collect::<Vec<_>>()
+        );
+
+        let res: Result<(), ImageError> = with_num_threads(2, || Ok(()))?;
+        assert!(res.is_ok());
+
+        Ok(())
+    }
+}
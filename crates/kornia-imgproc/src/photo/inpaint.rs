@@ -0,0 +1,342 @@
+use std::{cmp::Ordering, collections::BinaryHeap};
+
+use kornia_image::{Image, ImageError};
+
+/// The method to estimate the inpainted pixels.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InpaintMethod {
+    /// Telea 2004, a weighted average of the known neighbors and their gradients, where the
+    /// neighbors along the normal of the front and close to the pixel weigh more.
+    Telea,
+    /// A Navier-Stokes inspired variant as in OpenCV, where the neighbors along the
+    /// isophotes, the lines of constant intensity, weigh more, which continues the edges
+    /// into the region.
+    NavierStokes,
+}
+
+// the state of a pixel in the fast marching method
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum State {
+    Known,
+    Band,
+    Inside,
+}
+
+// a pixel of the narrow band ordered by increasing distance to the boundary
+struct BandPixel {
+    distance: f32,
+    index: usize,
+}
+
+impl PartialEq for BandPixel {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for BandPixel {}
+
+impl PartialOrd for BandPixel {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for BandPixel {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // reversed to pop the smallest distance first from the max-heap
+        other.distance.total_cmp(&self.distance)
+    }
+}
+
+/// Fill the masked regions of an image from their surroundings.
+///
+/// The pixels are filled from the boundary of the regions inwards in the order of the fast
+/// marching method, each one estimated from the known pixels within the radius.
+///
+/// # Arguments
+///
+/// * `src` - The source image with shape (H, W, C).
+/// * `mask` - The mask of the pixels to fill with shape (H, W), non-zero inside.
+/// * `dst` - The destination image with shape (H, W, C).
+/// * `radius` - The radius in pixels of the neighborhood of each pixel, e.g. 3.0.
+/// * `method` - The method to estimate the pixels.
+///
+/// # Example
+///
+/// ```
+/// use kornia_image::Image;
+/// use kornia_imgproc::photo::{inpaint, InpaintMethod};
+///
+/// let mut src = Image::<f32, 1>::from_size_val([5, 5].into(), 0.5).unwrap();
+/// src.as_slice_mut()[12] = 1.0; // a dead pixel
+///
+/// let mut mask = Image::<u8, 1>::from_size_val([5, 5].into(), 0).unwrap();
+/// mask.as_slice_mut()[12] = 255;
+///
+/// let mut dst = Image::<f32, 1>::from_size_val([5, 5].into(), 0.0).unwrap();
+/// inpaint(&src, &mask, &mut dst, 3.0, InpaintMethod::Telea).unwrap();
+/// assert!((dst.as_slice()[12] - 0.5).abs() < 1e-6);
+/// ```
+pub fn inpaint<const C: usize>(
+    src: &Image<f32, C>,
+    mask: &Image<u8, 1>,
+    dst: &mut Image<f32, C>,
+    radius: f32,
+    method: InpaintMethod,
+) -> Result<(), ImageError> {
+    for size in [mask.size(), dst.size()] {
+        if src.size() != size {
+            return Err(ImageError::InvalidImageSize(
+                src.cols(),
+                src.rows(),
+                size.width,
+                size.height,
+            ));
+        }
+    }
+
+    dst.as_slice_mut().copy_from_slice(src.as_slice());
+
+    let (cols, rows) = (src.cols(), src.rows());
+    let mut state = mask
+        .as_slice()
+        .iter()
+        .map(|&m| if m != 0 { State::Inside } else { State::Known })
+        .collect::<Vec<_>>();
+    let mut distance = state
+        .iter()
+        .map(|&s| if s == State::Inside { f32::MAX } else { 0.0 })
+        .collect::<Vec<_>>();
+
+    let neighbors = |index: usize| {
+        let (x, y) = ((index % cols) as isize, (index / cols) as isize);
+        [(-1, 0), (1, 0), (0, -1), (0, 1)]
+            .into_iter()
+            .map(move |(dx, dy)| (x + dx, y + dy))
+            .filter(|&(nx, ny)| nx >= 0 && ny >= 0 && nx < cols as isize && ny < rows as isize)
+            .map(move |(nx, ny)| ny as usize * cols + nx as usize)
+    };
+
+    // the known pixels next to the masked pixels form the initial band
+    let mut band = BinaryHeap::new();
+    for index in 0..cols * rows {
+        if state[index] == State::Known && neighbors(index).any(|n| state[n] == State::Inside) {
+            state[index] = State::Band;
+            band.push(BandPixel {
+                distance: 0.0,
+                index,
+            });
+        }
+    }
+
+    let radius_sq = radius * radius;
+    let r = radius.ceil() as isize;
+
+    while let Some(BandPixel { index, .. }) = band.pop() {
+        state[index] = State::Known;
+
+        for n in neighbors(index).collect::<Vec<_>>() {
+            if state[n] != State::Inside {
+                continue;
+            }
+
+            let (nx, ny) = (n % cols, n / cols);
+            distance[n] = solve_eikonal(&distance, &state, cols, rows, nx, ny);
+
+            let data = dst.as_slice();
+            let grad_t = gradient(&distance, &state, cols, rows, nx, ny, 1, 0);
+
+            let mut sum_weights = [0.0f32; C];
+            let mut sum_values = [0.0f32; C];
+            for qy in (ny as isize - r).max(0)..=(ny as isize + r).min(rows as isize - 1) {
+                for qx in (nx as isize - r).max(0)..=(nx as isize + r).min(cols as isize - 1) {
+                    let q = qy as usize * cols + qx as usize;
+                    if state[q] == State::Inside {
+                        continue;
+                    }
+                    let (rx, ry) = (nx as f32 - qx as f32, ny as f32 - qy as f32);
+                    let dist_sq = rx * rx + ry * ry;
+                    if dist_sq > radius_sq {
+                        continue;
+                    }
+                    let dist = dist_sq.sqrt();
+                    let (qx, qy) = (qx as usize, qy as usize);
+
+                    for ch in 0..C {
+                        let grad_i = gradient(data, &state, cols, rows, qx, qy, C, ch);
+
+                        let (weight, estimate) = match method {
+                            InpaintMethod::Telea => {
+                                let dir = ((rx * grad_t[0] + ry * grad_t[1]) / dist).abs();
+                                let level = 1.0 / (1.0 + (distance[q] - distance[n]).abs());
+                                // first order extrapolation from the neighbor
+                                let value = data[q * C + ch] + grad_i[0] * rx + grad_i[1] * ry;
+                                (dir.max(1e-6) * level, value)
+                            }
+                            InpaintMethod::NavierStokes => {
+                                // the isophote is perpendicular to the gradient
+                                let norm = grad_i[0].hypot(grad_i[1]);
+                                let along = if norm > 1e-6 {
+                                    ((ry * grad_i[0] - rx * grad_i[1]) / (norm * dist)).abs()
+                                } else {
+                                    1.0
+                                };
+                                (along.max(1e-6), data[q * C + ch])
+                            }
+                        };
+
+                        let weight = weight / dist_sq;
+                        sum_weights[ch] += weight;
+                        sum_values[ch] += weight * estimate;
+                    }
+                }
+            }
+
+            let dst_data = dst.as_slice_mut();
+            for ch in 0..C {
+                if sum_weights[ch] > 0.0 {
+                    dst_data[n * C + ch] = sum_values[ch] / sum_weights[ch];
+                }
+            }
+
+            state[n] = State::Band;
+            band.push(BandPixel {
+                distance: distance[n],
+                index: n,
+            });
+        }
+    }
+
+    Ok(())
+}
+
+// the distance of a pixel from the known region from the distances of its neighbors
+fn solve_eikonal(
+    distance: &[f32],
+    state: &[State],
+    cols: usize,
+    rows: usize,
+    x: usize,
+    y: usize,
+) -> f32 {
+    let at = |x: isize, y: isize| {
+        if x < 0 || y < 0 || x >= cols as isize || y >= rows as isize {
+            return f32::MAX;
+        }
+        let i = y as usize * cols + x as usize;
+        if state[i] == State::Inside {
+            f32::MAX
+        } else {
+            distance[i]
+        }
+    };
+
+    let (x, y) = (x as isize, y as isize);
+    let tx = at(x - 1, y).min(at(x + 1, y));
+    let ty = at(x, y - 1).min(at(x, y + 1));
+
+    let (t1, t2) = (tx.min(ty), tx.max(ty));
+    if t2 == f32::MAX || t2 - t1 >= 1.0 {
+        t1 + 1.0
+    } else {
+        (t1 + t2 + (2.0 - (t1 - t2) * (t1 - t2)).sqrt()) / 2.0
+    }
+}
+
+// the central or one-sided differences of a channel over the pixels which are not inside
+#[allow(clippy::too_many_arguments)]
+fn gradient(
+    data: &[f32],
+    state: &[State],
+    cols: usize,
+    rows: usize,
+    x: usize,
+    y: usize,
+    channels: usize,
+    ch: usize,
+) -> [f32; 2] {
+    let value = |x: usize, y: usize| {
+        let i = y * cols + x;
+        (state[i] != State::Inside).then(|| data[i * channels + ch])
+    };
+
+    let diff = |prev: Option<f32>, center: Option<f32>, next: Option<f32>| match (prev, next) {
+        (Some(p), Some(n)) => (n - p) / 2.0,
+        (Some(p), None) => center.map_or(0.0, |c| c - p),
+        (None, Some(n)) => center.map_or(0.0, |c| n - c),
+        (None, None) => 0.0,
+    };
+
+    let center = value(x, y);
+    let gx = diff(
+        (x > 0).then(|| value(x - 1, y)).flatten(),
+        center,
+        (x + 1 < cols).then(|| value(x + 1, y)).flatten(),
+    );
+    let gy = diff(
+        (y > 0).then(|| value(x, y - 1)).flatten(),
+        center,
+        (y + 1 < rows).then(|| value(x, y + 1)).flatten(),
+    );
+
+    [gx, gy]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_inpaint() -> Result<(), ImageError> {
+        // a vertical edge crossing a square hole
+        let (cols, rows) = (12, 12);
+        let data = (0..cols * rows)
+            .flat_map(|i| {
+                let v = if i % cols < 6 { 0.2 } else { 0.8 };
+                [v, 1.0 - v]
+            })
+            .collect();
+        let src = Image::<f32, 2>::new([cols, rows].into(), data)?;
+
+        let mut mask = Image::<u8, 1>::from_size_val(src.size(), 0)?;
+        for y in 4..8 {
+            for x in 4..8 {
+                mask.as_slice_mut()[y * cols + x] = 1;
+            }
+        }
+
+        let mut holed = src.clone();
+        for (i, m) in mask.as_slice().iter().enumerate() {
+            if *m != 0 {
+                holed.as_slice_mut()[2 * i..2 * i + 2].fill(0.0);
+            }
+        }
+
+        for method in [InpaintMethod::Telea, InpaintMethod::NavierStokes] {
+            let mut dst = Image::<f32, 2>::from_size_val(src.size(), 0.0)?;
+            inpaint(&holed, &mask, &mut dst, 3.0, method)?;
+
+            // the known pixels are unchanged and the hole is filled close to its surroundings
+            for (i, m) in mask.as_slice().iter().enumerate() {
+                for ch in 0..2 {
+                    let (a, b) = (dst.as_slice()[2 * i + ch], src.as_slice()[2 * i + ch]);
+                    if *m == 0 {
+                        assert_eq!(a, b);
+                    } else {
+                        assert!((0.0..=1.0).contains(&a));
+                    }
+                }
+            }
+
+            // the pixels far from the edge keep the side they belong to
+            let at = |x: usize, y: usize| dst.as_slice()[2 * (y * cols + x)];
+            assert!(at(4, 6) < 0.5 && at(7, 6) > 0.5);
+        }
+
+        let mut small = Image::<f32, 2>::from_size_val([2, 2].into(), 0.0)?;
+        assert!(inpaint(&src, &mask, &mut small, 3.0, InpaintMethod::Telea).is_err());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,5 @@
+mod inpaint;
+pub use inpaint::*;
+
+mod seamless_clone;
+pub use seamless_clone::*;
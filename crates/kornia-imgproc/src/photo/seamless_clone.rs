@@ -0,0 +1,278 @@
+use kornia_image::{Image, ImageError};
+
+/// The guidance gradients of the seamless cloning.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CloneMode {
+    /// Use the gradients of the source, replacing the texture of the destination.
+    Normal,
+    /// Use the strongest of the source and destination gradients, keeping the texture of
+    /// the destination where the source is flat, e.g. to insert objects with holes.
+    Mixed,
+}
+
+/// Clone a masked region of an image into another image without visible seams.
+///
+/// The cloned pixels are the solution of the Poisson equation whose guidance field is the
+/// gradient of the source (Pérez et al. 2003), with the destination pixels around the mask
+/// as boundary conditions. The linear system is solved per channel with the conjugate
+/// gradient method.
+///
+/// # Arguments
+///
+/// * `src` - The source image with the region to clone.
+/// * `dst` - The destination image.
+/// * `mask` - The mask of the region to clone with the size of the source, non-zero inside.
+/// * `center` - The (x, y) position in the destination of the center of the bounding box
+///   of the mask.
+/// * `mode` - The guidance gradients.
+///
+/// # Returns
+///
+/// A new image with the destination and the cloned region.
+///
+/// # Errors
+///
+/// Returns an error if the mask and the source have different sizes or if the region does
+/// not fit in the destination.
+///
+/// # Example
+///
+/// ```
+/// use kornia_image::Image;
+/// use kornia_imgproc::photo::{seamless_clone, CloneMode};
+///
+/// let src = Image::<f32, 3>::from_size_val([8, 8].into(), 0.8).unwrap();
+/// let dst = Image::<f32, 3>::from_size_val([16, 16].into(), 0.2).unwrap();
+/// let mask = Image::<u8, 1>::from_size_val([8, 8].into(), 255).unwrap();
+///
+/// // a flat source takes the color of the destination
+/// let out = seamless_clone(&src, &dst, &mask, (8, 8), CloneMode::Normal).unwrap();
+/// assert!(out.as_slice().iter().all(|&v| (v - 0.2).abs() < 1e-4));
+/// ```
+pub fn seamless_clone<const C: usize>(
+    src: &Image<f32, C>,
+    dst: &Image<f32, C>,
+    mask: &Image<u8, 1>,
+    center: (usize, usize),
+    mode: CloneMode,
+) -> Result<Image<f32, C>, ImageError> {
+    if src.size() != mask.size() {
+        return Err(ImageError::InvalidImageSize(
+            src.cols(),
+            src.rows(),
+            mask.cols(),
+            mask.rows(),
+        ));
+    }
+
+    let mut out = dst.clone();
+
+    // the bounding box of the mask
+    let (src_cols, src_rows) = (src.cols(), src.rows());
+    let (mut x0, mut y0, mut x1, mut y1) = (usize::MAX, usize::MAX, 0, 0);
+    for (i, _) in mask.as_slice().iter().enumerate().filter(|(_, &m)| m != 0) {
+        let (x, y) = (i % src_cols, i / src_cols);
+        (x0, y0, x1, y1) = (x0.min(x), y0.min(y), x1.max(x), y1.max(y));
+    }
+    if x0 == usize::MAX {
+        return Ok(out);
+    }
+
+    // the offset from the source to the destination coordinates
+    let dx = center.0 as isize - ((x0 + x1) / 2) as isize;
+    let dy = center.1 as isize - ((y0 + y1) / 2) as isize;
+    let (cols, rows) = (dst.cols(), dst.rows());
+    if x0 as isize + dx < 0
+        || y0 as isize + dy < 0
+        || x1 as isize + dx >= cols as isize
+        || y1 as isize + dy >= rows as isize
+    {
+        return Err(ImageError::PixelIndexOutOfBounds(
+            center.0, center.1, cols, rows,
+        ));
+    }
+
+    // the unknown pixels of the destination, the border pixels stay fixed
+    let mut unknowns = Vec::new();
+    let mut index = vec![usize::MAX; cols * rows];
+    for sy in y0..=y1 {
+        for sx in x0..=x1 {
+            if mask.as_slice()[sy * src_cols + sx] == 0 {
+                continue;
+            }
+            let x = (sx as isize + dx) as usize;
+            let y = (sy as isize + dy) as usize;
+            if x == 0 || y == 0 || x == cols - 1 || y == rows - 1 {
+                continue;
+            }
+            index[y * cols + x] = unknowns.len();
+            unknowns.push((x, y, sx, sy));
+        }
+    }
+
+    let src_data = src.as_slice();
+    let dst_data = dst.as_slice();
+    let offsets: [(isize, isize); 4] = [(-1, 0), (1, 0), (0, -1), (0, 1)];
+
+    for ch in 0..C {
+        let src_at = |x: usize, y: usize| src_data[(y * src_cols + x) * C + ch];
+        let dst_at = |x: usize, y: usize| dst_data[(y * cols + x) * C + ch];
+
+        // the divergence of the guidance field and the known neighbors
+        let mut b = vec![0.0f64; unknowns.len()];
+        let mut x = vec![0.0f64; unknowns.len()];
+        for (i, &(px, py, sx, sy)) in unknowns.iter().enumerate() {
+            for (ox, oy) in offsets {
+                let (qx, qy) = ((px as isize + ox) as usize, (py as isize + oy) as usize);
+
+                // the neighbors outside the source have the value of the pixel
+                let (qsx, qsy) = (sx as isize + ox, sy as isize + oy);
+                let src_q = if qsx >= 0
+                    && qsy >= 0
+                    && (qsx as usize) < src_cols
+                    && (qsy as usize) < src_rows
+                {
+                    src_at(qsx as usize, qsy as usize)
+                } else {
+                    src_at(sx, sy)
+                };
+
+                let src_grad = src_at(sx, sy) - src_q;
+                let guidance = match mode {
+                    CloneMode::Normal => src_grad,
+                    CloneMode::Mixed => {
+                        let dst_grad = dst_at(px, py) - dst_at(qx, qy);
+                        if dst_grad.abs() > src_grad.abs() {
+                            dst_grad
+                        } else {
+                            src_grad
+                        }
+                    }
+                };
+                b[i] += guidance as f64;
+
+                if index[qy * cols + qx] == usize::MAX {
+                    b[i] += dst_at(qx, qy) as f64;
+                }
+            }
+            x[i] = src_at(sx, sy) as f64;
+        }
+
+        // the discrete laplacian restricted to the unknown pixels
+        let laplacian = |v: &[f64], out: &mut [f64]| {
+            for (i, &(px, py, _, _)) in unknowns.iter().enumerate() {
+                let mut acc = 4.0 * v[i];
+                for (ox, oy) in offsets {
+                    let (qx, qy) = ((px as isize + ox) as usize, (py as isize + oy) as usize);
+                    let j = index[qy * cols + qx];
+                    if j != usize::MAX {
+                        acc -= v[j];
+                    }
+                }
+                out[i] = acc;
+            }
+        };
+
+        conjugate_gradient(laplacian, &b, &mut x);
+
+        let out_data = out.as_slice_mut();
+        for (&(px, py, _, _), v) in unknowns.iter().zip(&x) {
+            out_data[(py * cols + px) * C + ch] = *v as f32;
+        }
+    }
+
+    Ok(out)
+}
+
+// solve the symmetric positive definite system `A x = b` starting from `x`
+fn conjugate_gradient(matvec: impl Fn(&[f64], &mut [f64]), b: &[f64], x: &mut [f64]) {
+    const TOLERANCE: f64 = 1e-10;
+
+    let n = b.len();
+    let dot = |a: &[f64], b: &[f64]| a.iter().zip(b).map(|(a, b)| a * b).sum::<f64>();
+
+    let mut ap = vec![0.0; n];
+    matvec(x, &mut ap);
+    let mut r = b.iter().zip(&ap).map(|(b, ax)| b - ax).collect::<Vec<_>>();
+    let mut p = r.clone();
+    let mut rr = dot(&r, &r);
+    let threshold = TOLERANCE * dot(b, b).max(1.0);
+
+    for _ in 0..n {
+        if rr <= threshold {
+            break;
+        }
+        matvec(&p, &mut ap);
+        let alpha = rr / dot(&p, &ap);
+        x.iter_mut().zip(&p).for_each(|(x, p)| *x += alpha * p);
+        r.iter_mut().zip(&ap).for_each(|(r, ap)| *r -= alpha * ap);
+        let rr_new = dot(&r, &r);
+        let beta = rr_new / rr;
+        p.iter_mut().zip(&r).for_each(|(p, r)| *p = r + beta * *p);
+        rr = rr_new;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ramp(cols: usize, rows: usize, offset: f32) -> Result<Image<f32, 1>, ImageError> {
+        let data = (0..cols * rows)
+            .map(|i| offset + 0.1 * (i % cols) as f32 + 0.05 * (i / cols) as f32)
+            .collect();
+        Image::new([cols, rows].into(), data)
+    }
+
+    #[test]
+    fn test_seamless_clone_normal() -> Result<(), ImageError> {
+        // the gradients of the source ramp are continued from the destination border
+        let src = ramp(6, 6, 5.0)?;
+        let dst = ramp(12, 10, 0.0)?;
+        let mask = Image::<u8, 1>::new(
+            src.size(),
+            (0..36)
+                .map(|i| (i % 6 > 0 && i % 6 < 5 && i / 6 > 0 && i / 6 < 5) as u8)
+                .collect(),
+        )?;
+
+        let out = seamless_clone(&src, &dst, &mask, (6, 5), CloneMode::Normal)?;
+        for (a, b) in out.as_slice().iter().zip(dst.as_slice()) {
+            assert!((a - b).abs() < 1e-4);
+        }
+
+        assert!(seamless_clone(&src, &dst, &mask, (0, 0), CloneMode::Normal).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_seamless_clone_mixed() -> Result<(), ImageError> {
+        // a flat source keeps the texture of the destination in the mixed mode
+        let src = Image::<f32, 1>::from_size_val([5, 5].into(), 3.0)?;
+        #[rustfmt::skip]
+        let mask = Image::<u8, 1>::new(
+            [5, 5].into(),
+            vec![
+                0, 0, 0, 0, 0,
+                0, 1, 1, 1, 0,
+                0, 1, 1, 1, 0,
+                0, 1, 1, 1, 0,
+                0, 0, 0, 0, 0,
+            ],
+        )?;
+        let data = (0..64).map(|i| ((i * 7) % 5) as f32).collect();
+        let dst = Image::<f32, 1>::new([8, 8].into(), data)?;
+
+        let out = seamless_clone(&src, &dst, &mask, (4, 4), CloneMode::Mixed)?;
+        for (a, b) in out.as_slice().iter().zip(dst.as_slice()) {
+            assert!((a - b).abs() < 1e-4);
+        }
+
+        // the normal mode smooths the region
+        let out = seamless_clone(&src, &dst, &mask, (4, 4), CloneMode::Normal)?;
+        assert_ne!(out.as_slice(), dst.as_slice());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,524 @@
+use std::{
+    any::Any,
+    marker::PhantomData,
+    time::{Duration, Instant},
+};
+
+use crate::parallel::prelude::*;
+use kornia_image::ImageError;
+
+type AnyBuffer = Box<dyn Any + Send + Sync>;
+
+type StageFn =
+    Box<dyn Fn(&[&AnyBuffer], &mut Option<AnyBuffer>) -> Result<(), PipelineError> + Send + Sync>;
+
+/// An error type for the pipeline module.
+#[derive(thiserror::Error, Debug)]
+pub enum PipelineError {
+    /// Error when an input of the pipeline has no value.
+    #[error("The input {0} has no value")]
+    MissingInput(String),
+
+    /// Error when setting the value of a node which is not an input.
+    #[error("The node {0} is not an input")]
+    NotAnInput(String),
+
+    /// Error when a stage fails.
+    #[error("The stage {0} failed: {1}")]
+    StageFailed(String, ImageError),
+}
+
+/// A typed handle to the output of a stage or an input of a pipeline.
+pub struct Node<T> {
+    id: usize,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T> Clone for Node<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Node<T> {}
+
+/// The duration of a stage in the last run of a pipeline.
+#[derive(Debug, Clone)]
+pub struct StageTiming {
+    /// The name of the stage.
+    pub name: String,
+    /// The time spent in the stage.
+    pub duration: Duration,
+}
+
+/// A builder to declare the stages of a [`Pipeline`].
+///
+/// Each stage reads the outputs of previous stages and writes its own output, so the stages
+/// form a directed acyclic graph.
+#[derive(Default)]
+pub struct PipelineBuilder {
+    names: Vec<String>,
+    inputs: Vec<Vec<usize>>,
+    stages: Vec<Option<StageFn>>,
+}
+
+impl PipelineBuilder {
+    /// Create an empty builder.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn push<T>(&mut self, name: String, inputs: Vec<usize>, stage: Option<StageFn>) -> Node<T> {
+        self.names.push(name);
+        self.inputs.push(inputs);
+        self.stages.push(stage);
+        Node {
+            id: self.stages.len() - 1,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Declare an input of the pipeline, set before each run with [`Pipeline::set_input`].
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the input.
+    pub fn input<T: Send + Sync + 'static>(&mut self, name: impl Into<String>) -> Node<T> {
+        self.push(name.into(), Vec::new(), None)
+    }
+
+    /// Declare a stage with one input.
+    ///
+    /// The output buffer is created with `init` on the first run and reused by the next runs.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the stage.
+    /// * `input` - The node read by the stage.
+    /// * `init` - The function creating the output buffer from the input.
+    /// * `op` - The operation writing the output from the input.
+    ///
+    /// # Returns
+    ///
+    /// The node of the output of the stage.
+    pub fn stage<I, O>(
+        &mut self,
+        name: impl Into<String>,
+        input: Node<I>,
+        init: impl Fn(&I) -> Result<O, ImageError> + Send + Sync + 'static,
+        op: impl Fn(&I, &mut O) -> Result<(), ImageError> + Send + Sync + 'static,
+    ) -> Node<O>
+    where
+        I: Send + Sync + 'static,
+        O: Send + Sync + 'static,
+    {
+        let stage: StageFn = Box::new(move |inputs, output| {
+            let input = downcast::<I>(inputs[0]);
+            if output.is_none() {
+                *output = Some(Box::new(init(input)?));
+            }
+            op(input, downcast_mut::<O>(output))?;
+            Ok(())
+        });
+        self.push(name.into(), vec![input.id], Some(stage))
+    }
+
+    /// Declare a stage with two inputs, e.g. to merge two branches.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the stage.
+    /// * `inputs` - The nodes read by the stage.
+    /// * `init` - The function creating the output buffer from the inputs.
+    /// * `op` - The operation writing the output from the inputs.
+    ///
+    /// # Returns
+    ///
+    /// The node of the output of the stage.
+    pub fn stage2<I1, I2, O>(
+        &mut self,
+        name: impl Into<String>,
+        inputs: (Node<I1>, Node<I2>),
+        init: impl Fn(&I1, &I2) -> Result<O, ImageError> + Send + Sync + 'static,
+        op: impl Fn(&I1, &I2, &mut O) -> Result<(), ImageError> + Send + Sync + 'static,
+    ) -> Node<O>
+    where
+        I1: Send + Sync + 'static,
+        I2: Send + Sync + 'static,
+        O: Send + Sync + 'static,
+    {
+        let stage: StageFn = Box::new(move |inputs, output| {
+            let (a, b) = (downcast::<I1>(inputs[0]), downcast::<I2>(inputs[1]));
+            if output.is_none() {
+                *output = Some(Box::new(init(a, b)?));
+            }
+            op(a, b, downcast_mut::<O>(output))?;
+            Ok(())
+        });
+        self.push(name.into(), vec![inputs.0.id, inputs.1.id], Some(stage))
+    }
+
+    /// Build the pipeline.
+    pub fn build(self) -> Pipeline {
+        // the level of a stage is one more than the deepest of its inputs
+        let mut depth = vec![0usize; self.stages.len()];
+        for id in 0..self.stages.len() {
+            depth[id] = self.inputs[id]
+                .iter()
+                .map(|&input| depth[input] + 1)
+                .max()
+                .unwrap_or(0);
+        }
+
+        let num_levels = depth.iter().max().map_or(0, |d| d + 1);
+        let mut levels = vec![Vec::new(); num_levels];
+        for (id, &d) in depth.iter().enumerate() {
+            if d > 0 {
+                levels[d].push(id);
+            }
+        }
+        levels.retain(|level| !level.is_empty());
+
+        Pipeline {
+            buffers: (0..self.stages.len()).map(|_| None).collect(),
+            names: self.names,
+            inputs: self.inputs,
+            stages: self.stages,
+            levels,
+            timing: false,
+            timings: Vec::new(),
+        }
+    }
+}
+
+// the stages only receive the buffers of the types of their nodes
+fn downcast<T: 'static>(buffer: &AnyBuffer) -> &T {
+    buffer
+        .downcast_ref::<T>()
+        .expect("the node type matches the buffer type")
+}
+
+fn downcast_mut<T: 'static>(buffer: &mut Option<AnyBuffer>) -> &mut T {
+    buffer
+        .as_mut()
+        .and_then(|buffer| buffer.downcast_mut::<T>())
+        .expect("the node type matches the buffer type")
+}
+
+impl From<ImageError> for PipelineError {
+    fn from(error: ImageError) -> Self {
+        PipelineError::StageFailed(String::new(), error)
+    }
+}
+
+/// A graph of image operations with persistent intermediate buffers.
+///
+/// The buffers of the stages are allocated on the first run and reused by the next runs,
+/// and the independent stages of the graph run in parallel.
+///
+/// # Example
+///
+/// ```
+/// use kornia_image::Image;
+/// use kornia_imgproc::{color, features, filter, interpolation::InterpolationMode, resize};
+/// use kornia_imgproc::pipeline::PipelineBuilder;
+///
+/// let mut builder = PipelineBuilder::new();
+/// let input = builder.input::<Image<f32, 3>>("image");
+/// let resized = builder.stage(
+///     "resize",
+///     input,
+///     |_| Image::from_size_val([64, 48].into(), 0.0),
+///     |src, dst| resize::resize_native(src, dst, InterpolationMode::Bilinear),
+/// );
+/// let gray = builder.stage(
+///     "gray",
+///     resized,
+///     |src| Image::from_size_val(src.size(), 0.0),
+///     |src, dst| color::gray_from_rgb(src, dst),
+/// );
+/// let blurred = builder.stage(
+///     "blur",
+///     gray,
+///     |src| Image::from_size_val(src.size(), 0.0),
+///     |src, dst| filter::gaussian_blur(src, dst, (3, 3), (1.0, 1.0)),
+/// );
+/// let response = builder.stage(
+///     "harris",
+///     blurred,
+///     |src: &Image<f32, 1>| Image::from_size_val(src.size(), 0.0),
+///     |src, dst| features::HarrisResponse::new(src.size()).compute(src, dst),
+/// );
+/// let keypoints = builder.stage(
+///     "nms",
+///     response,
+///     |_| Ok(Vec::new()),
+///     |src, dst| {
+///         *dst = features::non_max_suppression(src, 1e-4);
+///         Ok(())
+///     },
+/// );
+///
+/// let mut pipeline = builder.build();
+/// pipeline.set_timing(true);
+/// let image = Image::<f32, 3>::from_size_val([640, 480].into(), 0.5).unwrap();
+/// pipeline.set_input(input, image).unwrap();
+/// pipeline.run().unwrap();
+///
+/// assert!(pipeline.output(keypoints).is_some());
+/// assert_eq!(pipeline.timings().len(), 5);
+/// ```
+pub struct Pipeline {
+    names: Vec<String>,
+    inputs: Vec<Vec<usize>>,
+    stages: Vec<Option<StageFn>>,
+    levels: Vec<Vec<usize>>,
+    buffers: Vec<Option<AnyBuffer>>,
+    timing: bool,
+    timings: Vec<StageTiming>,
+}
+
+impl Pipeline {
+    /// Set the value of an input of the pipeline.
+    ///
+    /// # Arguments
+    ///
+    /// * `node` - The input node from [`PipelineBuilder::input`].
+    /// * `value` - The value of the input.
+    pub fn set_input<T: Send + Sync + 'static>(
+        &mut self,
+        node: Node<T>,
+        value: T,
+    ) -> Result<(), PipelineError> {
+        if self.stages[node.id].is_some() {
+            return Err(PipelineError::NotAnInput(self.names[node.id].clone()));
+        }
+        self.buffers[node.id] = Some(Box::new(value));
+        Ok(())
+    }
+
+    /// The value of a node after the last run, or of an input.
+    pub fn output<T: 'static>(&self, node: Node<T>) -> Option<&T> {
+        self.buffers[node.id]
+            .as_ref()
+            .and_then(|buffer| buffer.downcast_ref::<T>())
+    }
+
+    /// The mutable value of a node, e.g. to write an input in place without allocations.
+    pub fn output_mut<T: 'static>(&mut self, node: Node<T>) -> Option<&mut T> {
+        self.buffers[node.id]
+            .as_mut()
+            .and_then(|buffer| buffer.downcast_mut::<T>())
+    }
+
+    /// Enable or disable the measurement of the duration of the stages.
+    pub fn set_timing(&mut self, enabled: bool) {
+        self.timing = enabled;
+        self.timings.clear();
+    }
+
+    /// The duration of the stages in the last run, in execution order.
+    ///
+    /// The list is empty when the timing is disabled.
+    pub fn timings(&self) -> &[StageTiming] {
+        &self.timings
+    }
+
+    /// Drop the buffers of the stages, e.g. when the size of the inputs changes.
+    pub fn clear_buffers(&mut self) {
+        for (buffer, stage) in self.buffers.iter_mut().zip(&self.stages) {
+            if stage.is_some() {
+                *buffer = None;
+            }
+        }
+    }
+
+    /// Run all the stages of the pipeline.
+    ///
+    /// The stages of a level of the graph run in parallel once the previous levels finish.
+    pub fn run(&mut self) -> Result<(), PipelineError> {
+        self.timings.clear();
+
+        for level in &self.levels {
+            // move the outputs of the level out so the inputs can be shared
+            let mut outputs = level
+                .iter()
+                .map(|&id| (id, self.buffers[id].take()))
+                .collect::<Vec<_>>();
+
+            let (names, inputs, stages, buffers) =
+                (&self.names, &self.inputs, &self.stages, &self.buffers);
+
+            let results = outputs
+                .par_iter_mut()
+                .map(|(id, output)| {
+                    let args = inputs[*id]
+                        .iter()
+                        .map(|&input| {
+                            buffers[input]
+                                .as_ref()
+                                .ok_or_else(|| PipelineError::MissingInput(names[input].clone()))
+                        })
+                        .collect::<Result<Vec<_>, _>>()?;
+
+                    let start = Instant::now();
+                    if let Some(stage) = &stages[*id] {
+                        stage(&args, output).map_err(|error| match error {
+                            PipelineError::StageFailed(_, error) => {
+                                PipelineError::StageFailed(names[*id].clone(), error)
+                            }
+                            error => error,
+                        })?;
+                    }
+                    Ok(start.elapsed())
+                })
+                .collect::<Vec<Result<Duration, PipelineError>>>();
+
+            for (id, output) in outputs {
+                self.buffers[id] = output;
+            }
+
+            for (&id, result) in level.iter().zip(results) {
+                let duration = result?;
+                if self.timing {
+                    self.timings.push(StageTiming {
+                        name: self.names[id].clone(),
+                        duration,
+                    });
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{color, features, features::GradsMode, filter};
+    use kornia_image::Image;
+
+    #[test]
+    fn test_pipeline_branches() -> Result<(), Box<dyn std::error::Error>> {
+        let mut builder = PipelineBuilder::new();
+        let input = builder.input::<Image<f32, 3>>("image");
+        let gray = builder.stage(
+            "gray",
+            input,
+            |src| Image::from_size_val(src.size(), 0.0),
+            color::gray_from_rgb,
+        );
+        let blur = builder.stage(
+            "blur",
+            gray,
+            |src| Image::from_size_val(src.size(), 0.0),
+            |src, dst| filter::gaussian_blur(src, dst, (3, 3), (1.0, 1.0)),
+        );
+        // two branches running in parallel
+        let hessian = builder.stage(
+            "hessian",
+            blur,
+            |src| Image::from_size_val(src.size(), 0.0),
+            |src, dst| features::hessian_response(src, dst, GradsMode::Diff),
+        );
+        let gftt = builder.stage(
+            "gftt",
+            blur,
+            |src| Image::from_size_val(src.size(), 0.0),
+            |src, dst| features::gftt_response(src, dst, GradsMode::Sobel),
+        );
+        let sum = builder.stage2(
+            "sum",
+            (hessian, gftt),
+            |a: &Image<f32, 1>, _| Image::<f32, 1>::from_size_val(a.size(), 0.0),
+            |a, b, dst| {
+                for ((d, a), b) in dst
+                    .as_slice_mut()
+                    .iter_mut()
+                    .zip(a.as_slice())
+                    .zip(b.as_slice())
+                {
+                    *d = a + b;
+                }
+                Ok(())
+            },
+        );
+
+        let mut pipeline = builder.build();
+        assert!(matches!(
+            pipeline.run(),
+            Err(PipelineError::MissingInput(name)) if name == "image"
+        ));
+        assert!(pipeline
+            .set_input(gray, Image::from_size_val([1, 1].into(), 0.0)?)
+            .is_err());
+
+        let data = (0..12 * 10 * 3).map(|x| (x % 5) as f32 / 5.0).collect();
+        let image = Image::<f32, 3>::new([12, 10].into(), data)?;
+        pipeline.set_timing(true);
+        pipeline.set_input(input, image.clone())?;
+        pipeline.run()?;
+
+        let mut gray_expected = Image::from_size_val(image.size(), 0.0)?;
+        let mut blur_expected = Image::from_size_val(image.size(), 0.0)?;
+        let mut hessian_expected = Image::from_size_val(image.size(), 0.0)?;
+        let mut gftt_expected = Image::from_size_val(image.size(), 0.0)?;
+        color::gray_from_rgb(&image, &mut gray_expected)?;
+        filter::gaussian_blur(&gray_expected, &mut blur_expected, (3, 3), (1.0, 1.0))?;
+        features::hessian_response(&blur_expected, &mut hessian_expected, GradsMode::Diff)?;
+        features::gftt_response(&blur_expected, &mut gftt_expected, GradsMode::Sobel)?;
+
+        let output = pipeline.output(sum).ok_or("missing output")?;
+        for ((o, h), g) in output
+            .as_slice()
+            .iter()
+            .zip(hessian_expected.as_slice())
+            .zip(gftt_expected.as_slice())
+        {
+            assert_eq!(*o, h + g);
+        }
+
+        let names = pipeline
+            .timings()
+            .iter()
+            .map(|t| t.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["gray", "blur", "hessian", "gftt", "sum"]);
+
+        // the buffers are reused between the runs
+        let ptr = output.as_slice().as_ptr();
+        pipeline
+            .output_mut(input)
+            .ok_or("missing input")?
+            .as_slice_mut()
+            .fill(0.0);
+        pipeline.run()?;
+        let output = pipeline.output(sum).ok_or("missing output")?;
+        assert_eq!(output.as_slice().as_ptr(), ptr);
+        assert!(output.as_slice().iter().all(|&x| x == 0.0));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_pipeline_stage_error() -> Result<(), Box<dyn std::error::Error>> {
+        let mut builder = PipelineBuilder::new();
+        let input = builder.input::<Image<f32, 1>>("image");
+        builder.stage(
+            "fail",
+            input,
+            |src| Image::from_size_val(src.size(), 0.0),
+            |_: &Image<f32, 1>, _: &mut Image<f32, 1>| Err(ImageError::CastError),
+        );
+
+        let mut pipeline = builder.build();
+        pipeline.set_input(input, Image::from_size_val([2, 2].into(), 0.0)?)?;
+        assert!(matches!(
+            pipeline.run(),
+            Err(PipelineError::StageFailed(name, ImageError::CastError)) if name == "fail"
+        ));
+
+        Ok(())
+    }
+}
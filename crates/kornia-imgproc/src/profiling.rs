@@ -0,0 +1,203 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+// the number of alive profilers, the operations are only timed when positive
+static ACTIVE_PROFILERS: AtomicUsize = AtomicUsize::new(0);
+
+// the statistics of the operations by name
+static STATS: Mutex<BTreeMap<&'static str, OpStats>> = Mutex::new(BTreeMap::new());
+
+/// The timing statistics of an operation.
+///
+/// The durations include the nested operations, e.g. the time of `gaussian_blur` includes
+/// the time of the `separable_filter` it calls.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OpStats {
+    /// The name of the operation.
+    pub name: &'static str,
+    /// The number of calls of the operation.
+    pub calls: u64,
+    /// The total duration of the calls.
+    pub total: Duration,
+    /// The duration of the fastest call.
+    pub min: Duration,
+    /// The duration of the slowest call.
+    pub max: Duration,
+}
+
+impl OpStats {
+    /// Returns the mean duration of the calls.
+    pub fn mean(&self) -> Duration {
+        match self.calls {
+            0 => Duration::ZERO,
+            calls => self.total.div_f64(calls as f64),
+        }
+    }
+}
+
+/// A handle to collect the timing statistics of the image operations.
+///
+/// The filters, responses, warps, resizes and tracking operations are timed while at least
+/// one profiler is alive, and cost a single atomic load otherwise. The statistics are shared
+/// by all the threads and all the profilers.
+///
+/// With the `tracing` feature the operations also open `tracing` spans at the debug level,
+/// independently of the profilers.
+///
+/// # Example
+///
+/// ```
+/// use kornia_image::Image;
+/// use kornia_imgproc::{filter::gaussian_blur, profiling::Profiler};
+///
+/// let profiler = Profiler::new();
+///
+/// let src = Image::<f32, 1>::from_size_val([64, 64].into(), 1.0).unwrap();
+/// let mut dst = Image::<f32, 1>::from_size_val([64, 64].into(), 0.0).unwrap();
+/// gaussian_blur(&src, &mut dst, (5, 5), (1.0, 1.0)).unwrap();
+///
+/// let stats = profiler.stats();
+/// assert!(stats.iter().any(|op| op.name == "gaussian_blur" && op.calls >= 1));
+/// println!("{}", profiler.report());
+/// ```
+pub struct Profiler {
+    _private: (),
+}
+
+impl Profiler {
+    /// Creates a profiler and starts timing the operations.
+    pub fn new() -> Self {
+        ACTIVE_PROFILERS.fetch_add(1, Ordering::Relaxed);
+        Self { _private: () }
+    }
+
+    /// Returns the statistics of the timed operations, by decreasing total duration.
+    pub fn stats(&self) -> Vec<OpStats> {
+        let mut stats = lock_stats().values().copied().collect::<Vec<_>>();
+        stats.sort_by_key(|op| std::cmp::Reverse(op.total));
+        stats
+    }
+
+    /// Clears the statistics of the operations.
+    pub fn reset(&self) {
+        lock_stats().clear();
+    }
+
+    /// Formats the statistics as a table with one line per operation.
+    pub fn report(&self) -> String {
+        let mut report = format!(
+            "{:<32} {:>8} {:>12} {:>12} {:>12} {:>12}\n",
+            "operation", "calls", "total", "mean", "min", "max"
+        );
+        for op in self.stats() {
+            let _ = writeln!(
+                report,
+                "{:<32} {:>8} {:>12.3?} {:>12.3?} {:>12.3?} {:>12.3?}",
+                op.name,
+                op.calls,
+                op.total,
+                op.mean(),
+                op.min,
+                op.max
+            );
+        }
+        report
+    }
+}
+
+impl Default for Profiler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for Profiler {
+    fn drop(&mut self) {
+        ACTIVE_PROFILERS.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+fn lock_stats() -> std::sync::MutexGuard<'static, BTreeMap<&'static str, OpStats>> {
+    // the statistics stay consistent even if a thread panicked while holding the lock
+    STATS.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// A guard timing an operation until it is dropped.
+pub(crate) struct OpScope {
+    name: &'static str,
+    start: Option<Instant>,
+}
+
+impl OpScope {
+    pub(crate) fn enter(name: &'static str) -> Self {
+        let active = ACTIVE_PROFILERS.load(Ordering::Relaxed) > 0;
+        Self {
+            name,
+            start: active.then(Instant::now),
+        }
+    }
+}
+
+impl Drop for OpScope {
+    fn drop(&mut self) {
+        let Some(start) = self.start else {
+            return;
+        };
+        let elapsed = start.elapsed();
+        let mut stats = lock_stats();
+        let op = stats.entry(self.name).or_insert(OpStats {
+            name: self.name,
+            calls: 0,
+            total: Duration::ZERO,
+            min: Duration::MAX,
+            max: Duration::ZERO,
+        });
+        op.calls += 1;
+        op.total += elapsed;
+        op.min = op.min.min(elapsed);
+        op.max = op.max.max(elapsed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::filter::gaussian_blur;
+    use kornia_image::{Image, ImageError};
+
+    #[test]
+    fn test_profiler() -> Result<(), ImageError> {
+        let src = Image::<f32, 1>::from_size_val([32, 32].into(), 1.0)?;
+        let mut dst = Image::<f32, 1>::from_size_val([32, 32].into(), 0.0)?;
+
+        // the operations are not timed without a profiler
+        {
+            let _scope = OpScope::enter("test_profiler_op");
+        }
+
+        let profiler = Profiler::new();
+        gaussian_blur(&src, &mut dst, (3, 3), (1.0, 1.0))?;
+        gaussian_blur(&src, &mut dst, (3, 3), (1.0, 1.0))?;
+        {
+            let _scope = OpScope::enter("test_profiler_op");
+        }
+
+        let stats = profiler.stats();
+        let blur = stats
+            .iter()
+            .find(|op| op.name == "gaussian_blur")
+            .expect("gaussian_blur is timed");
+        assert!(blur.calls >= 2);
+        assert!(blur.min <= blur.max && blur.total >= blur.max);
+        assert!(stats.iter().any(|op| op.name == "separable_filter"));
+
+        let op = stats.iter().find(|op| op.name == "test_profiler_op");
+        assert_eq!(op.map(|op| op.calls), Some(1));
+        assert!(profiler.report().contains("gaussian_blur"));
+
+        Ok(())
+    }
+}
@@ -1,39 +1,122 @@
-use crate::filter::separable_filter;
-use crate::interpolation::InterpolationMode;
-use crate::resize::resize_native;
-use kornia_image::{Image, ImageError};
-
-fn get_pyramid_gaussian_kernel() -> (Vec<f32>, Vec<f32>) {
-    // The 2D kernel is:
-    // [
-    //   [1.0, 4.0, 6.0, 4.0, 1.0],
-    //   [4.0, 16.0, 24.0, 16.0, 4.0],
-    //   [6.0, 24.0, 36.0, 24.0, 6.0],
-    //   [4.0, 16.0, 24.0, 16.0, 4.0],
-    //   [1.0, 4.0, 6.0, 4.0, 1.0],
-    // ] / 256.0
-
-    let kernel_x = [1.0, 4.0, 6.0, 4.0, 1.0]
-        .iter()
-        .map(|&x| x / 16.0)
-        .collect();
-    let kernel_y = [1.0, 4.0, 6.0, 4.0, 1.0]
-        .iter()
-        .map(|&x| x / 16.0)
-        .collect();
-
-    (kernel_x, kernel_y)
+use crate::parallel::prelude::*;
+use kornia_image::{Image, ImageError, ImageSize};
+
+// The 5-tap binomial kernel of the pyramids. The 2D kernel is its outer product:
+// [
+//   [1.0, 4.0, 6.0, 4.0, 1.0],
+//   [4.0, 16.0, 24.0, 16.0, 4.0],
+//   [6.0, 24.0, 36.0, 24.0, 6.0],
+//   [4.0, 16.0, 24.0, 16.0, 4.0],
+//   [1.0, 4.0, 6.0, 4.0, 1.0],
+// ] / 256.0
+const PYRAMID_KERNEL: [f32; 5] = [1.0 / 16.0, 4.0 / 16.0, 6.0 / 16.0, 4.0 / 16.0, 1.0 / 16.0];
+
+// reflect an index at the borders without repeating the border pixel, e.g. `gfedcb|abcdefgh|gfedcba`
+fn reflect_101(i: isize, len: usize) -> usize {
+    let len = len as isize;
+    if len == 1 {
+        return 0;
+    }
+    let period = 2 * (len - 1);
+    let i = i.rem_euclid(period);
+    (if i < len { i } else { period - i }) as usize
+}
+
+// the size of the image one level above in the pyramid
+fn pyrdown_size(size: ImageSize) -> ImageSize {
+    ImageSize {
+        width: size.width.div_ceil(2),
+        height: size.height.div_ceil(2),
+    }
+}
+
+/// Blur an image and then downsample it.
+///
+/// This function applies the 5x5 Gaussian kernel of the pyramids and keeps the even rows
+/// and columns, halving the size of the image. The borders are reflected.
+///
+/// # Arguments
+///
+/// * `src` - The source image to be downsampled.
+/// * `dst` - The destination image with size ((W + 1) / 2, (H + 1) / 2).
+///
+/// # Example
+///
+/// ```
+/// use kornia_image::Image;
+/// use kornia_imgproc::pyramid::pyrdown;
+///
+/// let image = Image::<f32, 1>::from_size_val([5, 4].into(), 1.0).unwrap();
+/// let mut downsampled = Image::<f32, 1>::from_size_val([3, 2].into(), 0.0).unwrap();
+///
+/// pyrdown(&image, &mut downsampled).unwrap();
+/// assert!(downsampled.as_slice().iter().all(|&v| (v - 1.0).abs() < 1e-6));
+/// ```
+pub fn pyrdown<const C: usize>(
+    src: &Image<f32, C>,
+    dst: &mut Image<f32, C>,
+) -> Result<(), ImageError> {
+    profile_scope!("pyrdown");
+    let expected = pyrdown_size(src.size());
+    if dst.size() != expected {
+        return Err(ImageError::InvalidImageSize(
+            expected.width,
+            expected.height,
+            dst.width(),
+            dst.height(),
+        ));
+    }
+
+    let (src_cols, src_rows) = (src.cols(), src.rows());
+    let dst_cols = dst.cols();
+    if dst_cols == 0 || dst.rows() == 0 {
+        return Ok(());
+    }
+
+    // filter the rows at the even columns
+    let mut tmp = vec![0.0f32; dst_cols * src_rows * C];
+    tmp.par_chunks_exact_mut(dst_cols * C)
+        .zip(src.as_slice().par_chunks_exact(src_cols * C))
+        .for_each(|(tmp_row, src_row)| {
+            for x in 0..dst_cols {
+                for (t, k) in PYRAMID_KERNEL.iter().enumerate() {
+                    let sx = reflect_101(2 * x as isize + t as isize - 2, src_cols);
+                    for ch in 0..C {
+                        tmp_row[x * C + ch] += k * src_row[sx * C + ch];
+                    }
+                }
+            }
+        });
+
+    // filter the columns at the even rows
+    dst.as_slice_mut()
+        .par_chunks_exact_mut(dst_cols * C)
+        .enumerate()
+        .for_each(|(y, dst_row)| {
+            dst_row.fill(0.0);
+            for (t, k) in PYRAMID_KERNEL.iter().enumerate() {
+                let sy = reflect_101(2 * y as isize + t as isize - 2, src_rows);
+                let tmp_row = &tmp[sy * dst_cols * C..(sy + 1) * dst_cols * C];
+                for (d, s) in dst_row.iter_mut().zip(tmp_row) {
+                    *d += k * s;
+                }
+            }
+        });
+
+    Ok(())
 }
 
 /// Upsample an image and then blur it.
 ///
-/// This function doubles the size of the input image using bilinear interpolation
-/// and then applies a Gaussian blur to smooth the result.
+/// This function doubles the size of the input image by inserting zeros between the pixels
+/// and then applies the 5x5 Gaussian kernel of the pyramids multiplied by four, as the
+/// inverse of [`pyrdown`]. The borders are reflected.
 ///
 /// # Arguments
 ///
 /// * `src` - The source image to be upsampled.
-/// * `dst` - The destination image to store the result.
+/// * `dst` - The destination image to store the result, with twice the size of the source
+///   or one pixel less to undo the rounding of [`pyrdown`] for odd sizes.
 ///
 /// # Returns
 ///
@@ -67,28 +150,186 @@ pub fn pyrup<const C: usize>(
     src: &Image<f32, C>,
     dst: &mut Image<f32, C>,
 ) -> Result<(), ImageError> {
-    let expected_width = src.width() * 2;
-    let expected_height = src.height() * 2;
-
-    if dst.width() != expected_width || dst.height() != expected_height {
+    profile_scope!("pyrup");
+    if pyrdown_size(dst.size()) != src.size() {
         return Err(ImageError::InvalidImageSize(
-            expected_width,
-            expected_height,
+            src.width() * 2,
+            src.height() * 2,
             dst.width(),
             dst.height(),
         ));
     }
 
-    let mut upsampled = Image::<f32, C>::from_size_val(dst.size(), 0.0)?;
+    let (src_cols, src_rows) = (src.cols(), src.rows());
+    let (dst_cols, dst_rows) = (dst.cols(), dst.rows());
+    if dst_cols == 0 || dst_rows == 0 {
+        return Ok(());
+    }
+
+    // the odd positions of the upsampled image are zeros, so only the taps on even
+    // positions contribute, and the kernel is scaled by two in each direction
+    let taps = |x: usize, len: usize| {
+        PYRAMID_KERNEL.iter().enumerate().filter_map(move |(t, k)| {
+            let ux = reflect_101(x as isize + t as isize - 2, len);
+            (ux % 2 == 0).then_some((ux / 2, 2.0 * k))
+        })
+    };
 
-    resize_native(src, &mut upsampled, InterpolationMode::Bilinear)?;
+    // filter the rows of the source to the width of the destination
+    let mut tmp = vec![0.0f32; dst_cols * src_rows * C];
+    tmp.par_chunks_exact_mut(dst_cols * C)
+        .zip(src.as_slice().par_chunks_exact(src_cols * C))
+        .for_each(|(tmp_row, src_row)| {
+            for x in 0..dst_cols {
+                for (sx, k) in taps(x, dst_cols) {
+                    for ch in 0..C {
+                        tmp_row[x * C + ch] += k * src_row[sx * C + ch];
+                    }
+                }
+            }
+        });
 
-    let (kernel_x, kernel_y) = get_pyramid_gaussian_kernel();
-    separable_filter(&upsampled, dst, &kernel_x, &kernel_y)?;
+    // filter the columns to the height of the destination
+    dst.as_slice_mut()
+        .par_chunks_exact_mut(dst_cols * C)
+        .enumerate()
+        .for_each(|(y, dst_row)| {
+            dst_row.fill(0.0);
+            for (sy, k) in taps(y, dst_rows) {
+                let tmp_row = &tmp[sy * dst_cols * C..(sy + 1) * dst_cols * C];
+                for (d, s) in dst_row.iter_mut().zip(tmp_row) {
+                    *d += k * s;
+                }
+            }
+        });
 
     Ok(())
 }
 
+/// A Laplacian pyramid of an image.
+///
+/// Each level stores the details lost between a level of the Gaussian pyramid and the
+/// upsampled next level, from the finest to the coarsest, and the last level stores the
+/// low-pass residual. The image is recovered exactly with [`LaplacianPyramid::reconstruct`],
+/// and the levels of two pyramids can be combined for multi-band blending.
+///
+/// # Example
+///
+/// ```
+/// use kornia_image::Image;
+/// use kornia_imgproc::pyramid::LaplacianPyramid;
+///
+/// let image = Image::<f32, 1>::new([7, 5].into(), (0..35).map(|x| x as f32).collect()).unwrap();
+///
+/// let pyramid = LaplacianPyramid::new(&image, 3).unwrap();
+/// assert_eq!(pyramid.levels()[2].size(), [2, 2].into());
+///
+/// let reconstructed = pyramid.reconstruct().unwrap();
+/// for (a, b) in reconstructed.as_slice().iter().zip(image.as_slice()) {
+///     assert!((a - b).abs() < 1e-4);
+/// }
+/// ```
+pub struct LaplacianPyramid<const C: usize> {
+    levels: Vec<Image<f32, C>>,
+}
+
+impl<const C: usize> LaplacianPyramid<C> {
+    /// Builds the Laplacian pyramid of an image.
+    ///
+    /// # Arguments
+    ///
+    /// * `src` - The source image.
+    /// * `num_levels` - The number of levels including the low-pass residual, at least one.
+    pub fn new(src: &Image<f32, C>, num_levels: usize) -> Result<Self, ImageError> {
+        let mut levels = Vec::with_capacity(num_levels.max(1));
+        let mut current = src.clone();
+
+        for _ in 1..num_levels {
+            let mut down = Image::from_size_val(pyrdown_size(current.size()), 0.0)?;
+            pyrdown(&current, &mut down)?;
+
+            let mut up = Image::from_size_val(current.size(), 0.0)?;
+            pyrup(&down, &mut up)?;
+
+            current
+                .as_slice_mut()
+                .iter_mut()
+                .zip(up.as_slice())
+                .for_each(|(c, u)| *c -= u);
+
+            levels.push(std::mem::replace(&mut current, down));
+        }
+        levels.push(current);
+
+        Ok(Self { levels })
+    }
+
+    /// Creates a pyramid from its levels, e.g. blended from the levels of other pyramids.
+    ///
+    /// # Arguments
+    ///
+    /// * `levels` - The levels from the finest to the low-pass residual, where each level
+    ///   has the size of the previous one downsampled with [`pyrdown`].
+    pub fn from_levels(levels: Vec<Image<f32, C>>) -> Result<Self, ImageError> {
+        if levels.is_empty() {
+            return Err(ImageError::InvalidImageSize(0, 0, 0, 0));
+        }
+        for pair in levels.windows(2) {
+            let expected = pyrdown_size(pair[0].size());
+            if pair[1].size() != expected {
+                return Err(ImageError::InvalidImageSize(
+                    expected.width,
+                    expected.height,
+                    pair[1].width(),
+                    pair[1].height(),
+                ));
+            }
+        }
+        Ok(Self { levels })
+    }
+
+    /// Returns the levels from the finest to the low-pass residual.
+    pub fn levels(&self) -> &[Image<f32, C>] {
+        &self.levels
+    }
+
+    /// Returns the mutable levels from the finest to the low-pass residual.
+    pub fn levels_mut(&mut self) -> &mut [Image<f32, C>] {
+        &mut self.levels
+    }
+
+    /// Returns the number of levels including the low-pass residual.
+    pub fn num_levels(&self) -> usize {
+        self.levels.len()
+    }
+
+    /// Reconstructs the image by upsampling and adding the levels from the coarsest.
+    ///
+    /// # Returns
+    ///
+    /// The image with the size of the finest level.
+    pub fn reconstruct(&self) -> Result<Image<f32, C>, ImageError> {
+        let mut levels = self.levels.iter().rev();
+        // the pyramid always has at least one level
+        let mut current = levels
+            .next()
+            .cloned()
+            .ok_or(ImageError::ImageDataNotInitialized)?;
+
+        for level in levels {
+            let mut up = Image::from_size_val(level.size(), 0.0)?;
+            pyrup(&current, &mut up)?;
+            up.as_slice_mut()
+                .iter_mut()
+                .zip(level.as_slice())
+                .for_each(|(u, l)| *u += l);
+            current = up;
+        }
+
+        Ok(current)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -123,4 +364,102 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_pyrup_impulse() -> Result<(), ImageError> {
+        let mut src = Image::<f32, 1>::from_size_val([4, 4].into(), 0.0)?;
+        src.as_slice_mut()[4 + 1] = 1.0;
+
+        let mut dst = Image::<f32, 1>::from_size_val([8, 8].into(), 0.0)?;
+        pyrup(&src, &mut dst)?;
+
+        // the separable response of the impulse at (1, 1), as `cv2.pyrUp`, where the
+        // first row and column gather the taps reflected at the top-left border
+        let response = [0.25, 0.5, 0.75, 0.5, 0.125, 0.0, 0.0, 0.0];
+        for (i, v) in dst.as_slice().iter().enumerate() {
+            assert_eq!(*v, response[i / 8] * response[i % 8], "pixel {i}");
+        }
+
+        // a constant image is preserved, including the odd sizes
+        let src = Image::<f32, 1>::from_size_val([3, 2].into(), 2.0)?;
+        let mut dst = Image::<f32, 1>::from_size_val([5, 4].into(), 0.0)?;
+        pyrup(&src, &mut dst)?;
+        assert!(dst.as_slice().iter().all(|&v| (v - 2.0).abs() < 1e-6));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_pyrdown() -> Result<(), ImageError> {
+        #[rustfmt::skip]
+        let src = Image::<f32, 1>::new(
+            [4, 4].into(),
+            vec![
+                0.0, 0.0, 0.0, 0.0,
+                0.0, 16.0, 0.0, 0.0,
+                0.0, 0.0, 0.0, 0.0,
+                0.0, 0.0, 0.0, 0.0,
+            ],
+        )?;
+
+        let mut dst = Image::<f32, 1>::from_size_val([2, 2].into(), 0.0)?;
+        pyrdown(&src, &mut dst)?;
+
+        // the impulse at (1, 1) is reflected at the top-left border and counted twice
+        assert_eq!(dst.as_slice(), &[4.0, 2.0, 2.0, 1.0]);
+
+        let mut wrong = Image::<f32, 1>::from_size_val([1, 2].into(), 0.0)?;
+        assert!(pyrdown(&src, &mut wrong).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_pyrdown_impulse() -> Result<(), ImageError> {
+        let mut src = Image::<f32, 1>::from_size_val([8, 8].into(), 0.0)?;
+        src.as_slice_mut()[4 * 8 + 4] = 256.0;
+
+        let mut dst = Image::<f32, 1>::from_size_val([4, 4].into(), 0.0)?;
+        pyrdown(&src, &mut dst)?;
+
+        // the kernel sampled at the even positions around the impulse at (4, 4), as `cv2.pyrDown`
+        #[rustfmt::skip]
+        let expected = [
+            0.0, 0.0, 0.0, 0.0,
+            0.0, 1.0, 6.0, 1.0,
+            0.0, 6.0, 36.0, 6.0,
+            0.0, 1.0, 6.0, 1.0,
+        ];
+        assert_eq!(dst.as_slice(), &expected);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_laplacian_pyramid() -> Result<(), ImageError> {
+        let data = (0..9 * 6 * 2).map(|i| ((i * 37) % 11) as f32).collect();
+        let src = Image::<f32, 2>::new([9, 6].into(), data)?;
+
+        let pyramid = LaplacianPyramid::new(&src, 4)?;
+        let sizes = pyramid
+            .levels()
+            .iter()
+            .map(|l| l.size())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            sizes,
+            vec![[9, 6].into(), [5, 3].into(), [3, 2].into(), [2, 1].into()]
+        );
+
+        let reconstructed = pyramid.reconstruct()?;
+        for (a, b) in reconstructed.as_slice().iter().zip(src.as_slice()) {
+            assert!((a - b).abs() < 1e-4);
+        }
+
+        let levels = pyramid.levels().to_vec();
+        assert!(LaplacianPyramid::from_levels(levels[1..].to_vec()).is_ok());
+        assert!(LaplacianPyramid::from_levels(vec![levels[0].clone(), levels[2].clone()]).is_err());
+
+        Ok(())
+    }
 }
@@ -2,8 +2,10 @@ use crate::{
     interpolation::{grid::meshgrid_from_fn, interpolate_pixel, InterpolationMode},
     parallel,
 };
+#[cfg(feature = "std")]
 use fast_image_resize::{self as fr};
-use kornia_image::{Image, ImageError};
+use half::f16;
+use kornia_image::{ops, Image, ImageError};
 
 /// Resize an image to a new size.
 ///
@@ -61,6 +63,7 @@ pub fn resize_native<const C: usize>(
 ) -> Result<(), ImageError>
 where
 {
+    profile_scope!("resize_native");
     // check if the input and output images have the same size
     // and copy the input image to the output image if they have the same size
     if src.size() == dst.size() {
@@ -88,6 +91,66 @@ where
     Ok(())
 }
 
+/// Resize an image with half precision pixels to a new size.
+///
+/// The interpolation is computed in single precision and the result is stored
+/// back in half precision, so the output can be fed directly to f16 runtimes.
+///
+/// # Arguments
+///
+/// * `src` - The input image container.
+/// * `dst` - The output image container.
+/// * `interpolation` - The interpolation mode to use.
+///
+/// # Example
+///
+/// ```
+/// use half::f16;
+/// use kornia_image::{Image, ImageSize};
+/// use kornia_imgproc::resize::resize_native_f16;
+/// use kornia_imgproc::interpolation::InterpolationMode;
+///
+/// let image = Image::<_, 3>::new(
+///     ImageSize {
+///         width: 4,
+///         height: 5,
+///     },
+///     vec![f16::ZERO; 4 * 5 * 3],
+/// )
+/// .unwrap();
+///
+/// let new_size = ImageSize {
+///     width: 2,
+///     height: 3,
+/// };
+///
+/// let mut image_resized = Image::<_, 3>::from_size_val(new_size, f16::ZERO).unwrap();
+///
+/// resize_native_f16(
+///     &image,
+///     &mut image_resized,
+///     InterpolationMode::Bilinear,
+/// )
+/// .unwrap();
+///
+/// assert_eq!(image_resized.size().width, 2);
+/// assert_eq!(image_resized.size().height, 3);
+/// ```
+pub fn resize_native_f16<const C: usize>(
+    src: &Image<f16, C>,
+    dst: &mut Image<f16, C>,
+    interpolation: InterpolationMode,
+) -> Result<(), ImageError> {
+    profile_scope!("resize_native_f16");
+    let mut src_f32 = Image::<f32, C>::from_size_val(src.size(), 0.0)?;
+    ops::f32_from_f16(src, &mut src_f32)?;
+
+    let mut dst_f32 = Image::<f32, C>::from_size_val(dst.size(), 0.0)?;
+    resize_native(&src_f32, &mut dst_f32, interpolation)?;
+
+    ops::f16_from_f32(&dst_f32, dst)
+}
+
 /// Resize an image to a new size using the [fast_image_resize](https://crates.io/crates/fast_image_resize) crate.
 ///
 /// The function resizes an image to a new size using the specified interpolation mode.
@@ -141,11 +204,13 @@ where
 /// # Errors
 ///
 /// The function returns an error if the image cannot be resized.
+#[cfg(feature = "std")]
 pub fn resize_fast(
     src: &Image<u8, 3>,
     dst: &mut Image<u8, 3>,
     interpolation: InterpolationMode,
 ) -> Result<(), ImageError> {
+    profile_scope!("resize_fast");
     if dst.size() != dst.size() {
         return Err(ImageError::InvalidImageSize(
             src.size().width,
@@ -269,6 +334,38 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn resize_smoke_f16() -> Result<(), ImageError> {
+        use half::f16;
+        let image = Image::<_, 1>::new(
+            ImageSize {
+                width: 3,
+                height: 3,
+            },
+            (0..9).map(|x| f16::from_f32(x as f32)).collect(),
+        )?;
+
+        let new_size = ImageSize {
+            width: 2,
+            height: 2,
+        };
+
+        let mut image_resized = Image::<_, 1>::from_size_val(new_size, f16::ZERO)?;
+
+        super::resize_native_f16(
+            &image,
+            &mut image_resized,
+            super::InterpolationMode::Bilinear,
+        )?;
+
+        assert_eq!(
+            image_resized.as_slice(),
+            [0.0, 2.0, 6.0, 8.0].map(f16::from_f32)
+        );
+
+        Ok(())
+    }
+
     #[test]
     fn meshgrid() -> Result<(), TensorError> {
         let (map_x, map_y) =
@@ -0,0 +1,170 @@
+use kornia_image::{Image, ImageError, ImageSize};
+
+use crate::{
+    fft::fft2, filter::gaussian_blur, interpolation::InterpolationMode, normalize::find_min_max,
+    resize::resize_native,
+};
+
+// the side of the image where the spectrum is analyzed
+const SPECTRUM_SIZE: usize = 64;
+
+// bounds the log amplitude of the frequencies missing from the spectrum
+const AMPLITUDE_EPS: f32 = 1e-3;
+
+/// Compute the saliency map of an image with the spectral residual.
+///
+/// The image is reduced to 64x64 and the log amplitude of its spectrum is compared with
+/// its local average. The residual, which holds the unexpected part of the spectrum, is
+/// transformed back with the original phase and smoothed into the saliency map, as
+/// described in "Saliency Detection: A Spectral Residual Approach" by Hou and Zhang.
+///
+/// The spectrum assumes a periodic image, so the discontinuities between the opposite
+/// borders may appear as salient.
+///
+/// # Arguments
+///
+/// * `src` - The grayscale image with shape (H, W, 1).
+/// * `dst` - The saliency map with shape (H, W, 1), normalized to the range [0, 1].
+///
+/// # Example
+///
+/// ```
+/// use kornia_image::Image;
+/// use kornia_imgproc::saliency::spectral_residual_saliency;
+///
+/// let src = Image::<f32, 1>::from_size_val([128, 96].into(), 0.5).unwrap();
+/// let mut saliency = Image::<f32, 1>::from_size_val(src.size(), 0.0).unwrap();
+///
+/// spectral_residual_saliency(&src, &mut saliency).unwrap();
+/// assert!(saliency.as_slice().iter().all(|&v| (0.0..=1.0).contains(&v)));
+/// ```
+pub fn spectral_residual_saliency(
+    src: &Image<f32, 1>,
+    dst: &mut Image<f32, 1>,
+) -> Result<(), ImageError> {
+    if src.size() != dst.size() {
+        return Err(ImageError::InvalidImageSize(
+            src.cols(),
+            src.rows(),
+            dst.cols(),
+            dst.rows(),
+        ));
+    }
+
+    let size = ImageSize::from([SPECTRUM_SIZE, SPECTRUM_SIZE]);
+    let mut re = Image::<f32, 1>::from_size_val(size, 0.0)?;
+    let mut im = Image::<f32, 1>::from_size_val(size, 0.0)?;
+    resize_native(src, &mut re, InterpolationMode::Bilinear)?;
+    fft2(&mut re, &mut im, false)?;
+
+    // split the spectrum in log amplitude and phase
+    let mut log_amplitude = Image::<f32, 1>::from_size_val(size, 0.0)?;
+    let mut phase = Image::<f32, 1>::from_size_val(size, 0.0)?;
+    for (((&r, &i), a), p) in re
+        .as_slice()
+        .iter()
+        .zip(im.as_slice())
+        .zip(log_amplitude.as_slice_mut())
+        .zip(phase.as_slice_mut())
+    {
+        *a = (r.hypot(i) + AMPLITUDE_EPS).ln();
+        *p = i.atan2(r);
+    }
+
+    // the local average of the periodic spectrum
+    let n = SPECTRUM_SIZE;
+    let mut average = Image::<f32, 1>::from_size_val(size, 0.0)?;
+    for (i, mean) in average.as_slice_mut().iter_mut().enumerate() {
+        let (x, y) = (i % n, i / n);
+        let sum = [n - 1, 0, 1]
+            .iter()
+            .flat_map(|dy| [n - 1, 0, 1].map(|dx| ((y + dy) % n) * n + (x + dx) % n))
+            .map(|j| log_amplitude.as_slice()[j])
+            .sum::<f32>();
+        *mean = sum / 9.0;
+    }
+
+    // the spectral residual with the original phase
+    for ((((&a, &mean), &p), r), i) in log_amplitude
+        .as_slice()
+        .iter()
+        .zip(average.as_slice())
+        .zip(phase.as_slice())
+        .zip(re.as_slice_mut())
+        .zip(im.as_slice_mut())
+    {
+        let amplitude = (a - mean).exp();
+        (*i, *r) = p.sin_cos();
+        *r *= amplitude;
+        *i *= amplitude;
+    }
+    fft2(&mut re, &mut im, true)?;
+
+    let mut energy = Image::<f32, 1>::from_size_val(size, 0.0)?;
+    for ((&r, &i), e) in re
+        .as_slice()
+        .iter()
+        .zip(im.as_slice())
+        .zip(energy.as_slice_mut())
+    {
+        *e = r * r + i * i;
+    }
+
+    let mut smoothed = Image::<f32, 1>::from_size_val(size, 0.0)?;
+    gaussian_blur(&energy, &mut smoothed, (5, 5), (8.0, 8.0))?;
+
+    // a flat map has no salient region
+    let (min, max) = find_min_max(&smoothed)?;
+    let scale = if max > min { 1.0 / (max - min) } else { 0.0 };
+    for (e, &v) in energy.as_slice_mut().iter_mut().zip(smoothed.as_slice()) {
+        *e = (v - min) * scale;
+    }
+
+    resize_native(&energy, dst, InterpolationMode::Bilinear)?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_spectral_residual_saliency() -> Result<(), ImageError> {
+        // a blob on a textured background
+        let (cols, rows) = (128, 128);
+        let data = (0..cols * rows)
+            .map(|i| {
+                let (x, y) = ((i % cols) as f32, (i / cols) as f32);
+                let blob = (-((x - 88.0).powi(2) + (y - 32.0).powi(2)) / 50.0).exp();
+                // periodic stripes which are repeated everywhere
+                let w = std::f32::consts::TAU / 128.0;
+                let texture = 0.05 * ((6.0 * w * x).sin() + (5.0 * w * y + 3.0 * w * x).sin());
+                0.3 + texture + 0.6 * blob
+            })
+            .collect();
+        let src = Image::new([cols, rows].into(), data)?;
+
+        let mut saliency = Image::from_size_val(src.size(), 0.0)?;
+        spectral_residual_saliency(&src, &mut saliency)?;
+
+        // the borders are salient as the spectrum assumes a periodic image
+        let margin = 8;
+        let (mut best, mut max) = ((0, 0), 0.0);
+        for y in margin..rows - margin {
+            for x in margin..cols - margin {
+                let value = saliency.as_slice()[y * cols + x];
+                if value > max {
+                    (best, max) = ((x, y), value);
+                }
+            }
+        }
+        let (x, y) = best;
+        assert!((80..96).contains(&x) && (24..40).contains(&y), "{x} {y}");
+
+        // the background far from the blob is not salient
+        assert!(saliency.as_slice()[100 * cols + 20] < 0.2);
+
+        Ok(())
+    }
+}
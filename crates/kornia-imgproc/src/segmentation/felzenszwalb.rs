@@ -0,0 +1,182 @@
+use kornia_image::{Image, ImageError};
+
+// a disjoint set forest of the components with their maximum internal edge
+struct Components {
+    parent: Vec<usize>,
+    size: Vec<usize>,
+    internal: Vec<f32>,
+}
+
+impl Components {
+    fn new(num_elements: usize) -> Self {
+        Self {
+            parent: (0..num_elements).collect(),
+            size: vec![1; num_elements],
+            internal: vec![0.0; num_elements],
+        }
+    }
+
+    fn find(&mut self, mut x: usize) -> usize {
+        while self.parent[x] != x {
+            self.parent[x] = self.parent[self.parent[x]];
+            x = self.parent[x];
+        }
+        x
+    }
+
+    fn union(&mut self, a: usize, b: usize, weight: f32) {
+        let (small, large) = if self.size[a] < self.size[b] {
+            (a, b)
+        } else {
+            (b, a)
+        };
+        self.parent[small] = large;
+        self.size[large] += self.size[small];
+        self.internal[large] = weight;
+    }
+}
+
+/// Segment an image with the efficient graph-based segmentation of Felzenszwalb and
+/// Huttenlocher.
+///
+/// The pixels are the nodes of a graph connected to their 8 neighbors by the color
+/// distance. The edges are visited by increasing weight, and two components are merged
+/// when the edge is not larger than the internal difference of both components plus
+/// `k / size`. The components smaller than `min_size` are merged afterwards. The paper
+/// smooths the image with a gaussian of sigma 0.8 beforehand.
+///
+/// # Arguments
+///
+/// * `src` - The source image with shape (H, W, C).
+/// * `k` - The scale of the observation, larger values give larger components.
+/// * `min_size` - The minimum number of pixels of a component.
+///
+/// # Returns
+///
+/// The label image with shape (H, W, 1) and consecutive labels starting at 0 in the order
+/// of the first pixel of each component.
+///
+/// # Example
+///
+/// ```
+/// use kornia_image::Image;
+/// use kornia_imgproc::segmentation::felzenszwalb;
+///
+/// let image = Image::<f32, 1>::new(
+///     [4, 2].into(),
+///     vec![0.0, 0.0, 1.0, 1.0, 0.0, 0.0, 1.0, 1.0],
+/// )
+/// .unwrap();
+///
+/// let labels = felzenszwalb(&image, 0.5, 1).unwrap();
+/// assert_eq!(labels.as_slice(), &[0, 0, 1, 1, 0, 0, 1, 1]);
+/// ```
+pub fn felzenszwalb<const C: usize>(
+    src: &Image<f32, C>,
+    k: f32,
+    min_size: usize,
+) -> Result<Image<u32, 1>, ImageError> {
+    let (cols, rows) = (src.cols(), src.rows());
+    let data = src.as_slice();
+    let distance = |a: usize, b: usize| -> f32 {
+        (0..C)
+            .map(|ch| (data[a * C + ch] - data[b * C + ch]).powi(2))
+            .sum::<f32>()
+            .sqrt()
+    };
+
+    // the forward edges of the 8-connected grid
+    let mut edges = Vec::with_capacity(cols * rows * 4);
+    for y in 0..rows {
+        for x in 0..cols {
+            let i = y * cols + x;
+            if x + 1 < cols {
+                edges.push((distance(i, i + 1), i, i + 1));
+            }
+            if y + 1 < rows {
+                edges.push((distance(i, i + cols), i, i + cols));
+                if x + 1 < cols {
+                    edges.push((distance(i, i + cols + 1), i, i + cols + 1));
+                }
+                if x > 0 {
+                    edges.push((distance(i, i + cols - 1), i, i + cols - 1));
+                }
+            }
+        }
+    }
+    edges.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+    let mut components = Components::new(cols * rows);
+    let mut threshold = vec![k; cols * rows];
+
+    for &(weight, a, b) in &edges {
+        let (a, b) = (components.find(a), components.find(b));
+        if a != b && weight <= threshold[a] && weight <= threshold[b] {
+            components.union(a, b, weight);
+            let root = components.find(a);
+            threshold[root] = weight + k / components.size[root] as f32;
+        }
+    }
+
+    // merge the small components into their most similar neighbor
+    for &(_, a, b) in &edges {
+        let (a, b) = (components.find(a), components.find(b));
+        if a != b && (components.size[a] < min_size || components.size[b] < min_size) {
+            components.union(a, b, 0.0);
+        }
+    }
+
+    let mut labels = vec![u32::MAX; cols * rows];
+    let mut data = Vec::with_capacity(cols * rows);
+    let mut num_labels = 0;
+    for i in 0..cols * rows {
+        let root = components.find(i);
+        if labels[root] == u32::MAX {
+            labels[root] = num_labels;
+            num_labels += 1;
+        }
+        data.push(labels[root]);
+    }
+
+    Image::new(src.size(), data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_felzenszwalb() -> Result<(), ImageError> {
+        // four noisy quadrants
+        let size = 16;
+        let image = Image::<f32, 1>::new(
+            [size, size].into(),
+            (0..size * size)
+                .map(|i| {
+                    let (x, y) = (i % size, i / size);
+                    let noise = ((i * 7919) % 5) as f32 * 0.01;
+                    ((x >= size / 2) as u8 * 2 + (y >= size / 2) as u8) as f32 * 0.3 + noise
+                })
+                .collect(),
+        )?;
+
+        let labels = felzenszwalb(&image, 1.0, 10)?;
+        let labels = labels.as_slice();
+        assert_eq!(labels.iter().max(), Some(&3));
+        for (i, &label) in labels.iter().enumerate() {
+            let (x, y) = (i % size, i / size);
+            let quadrant = labels[(y / 8 * 8) * size + x / 8 * 8];
+            assert_eq!(label, quadrant);
+        }
+
+        // a large scale merges everything
+        let labels = felzenszwalb(&image, 1e6, 1)?;
+        assert!(labels.as_slice().iter().all(|&l| l == 0));
+
+        // the small components are merged
+        let labels = felzenszwalb(&image, 0.0, size * size / 4)?;
+        assert!(labels.as_slice().iter().max() <= Some(&3));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,591 @@
+use kornia_image::{Image, ImageError};
+
+use super::MaxFlow;
+
+// the number of gaussians of the color models, as in the original paper
+const NUM_COMPONENTS: usize = 5;
+
+// the regularization of the covariances for colors in [0, 1]
+const COVARIANCE_EPS: f64 = 1e-4;
+
+// the number of k-means iterations to initialize the color models
+const KMEANS_ITERATIONS: usize = 10;
+
+/// The label of a pixel in the mask of [`GrabCut`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum GrabCutLabel {
+    /// A pixel which is known to be in the background.
+    Background = 0,
+    /// A pixel which is known to be in the foreground.
+    Foreground = 1,
+    /// A pixel which is probably in the background.
+    ProbableBackground = 2,
+    /// A pixel which is probably in the foreground.
+    ProbableForeground = 3,
+}
+
+impl GrabCutLabel {
+    /// Returns true if the label is in the foreground.
+    pub fn is_foreground(&self) -> bool {
+        matches!(
+            self,
+            GrabCutLabel::Foreground | GrabCutLabel::ProbableForeground
+        )
+    }
+
+    /// Returns true if the label is fixed by the user.
+    pub fn is_fixed(&self) -> bool {
+        matches!(self, GrabCutLabel::Background | GrabCutLabel::Foreground)
+    }
+}
+
+impl From<u8> for GrabCutLabel {
+    /// Converts a value of the mask to a label, the unknown values are probable background.
+    fn from(value: u8) -> Self {
+        match value {
+            0 => GrabCutLabel::Background,
+            1 => GrabCutLabel::Foreground,
+            3 => GrabCutLabel::ProbableForeground,
+            _ => GrabCutLabel::ProbableBackground,
+        }
+    }
+}
+
+/// Interactive foreground segmentation with GrabCut.
+///
+/// The foreground and the background are modeled with gaussian mixtures of the colors,
+/// and each iteration assigns the pixels to the gaussians, fits the mixtures and
+/// relabels the probable pixels with the minimum cut of a graph, which trades the color
+/// likelihoods against the contrast of the neighboring pixels. The mask can be edited
+/// between the iterations to fix pixels to the foreground or the background.
+///
+/// # Example
+///
+/// ```
+/// use kornia_image::Image;
+/// use kornia_imgproc::segmentation::GrabCut;
+///
+/// // a red square on a blue background
+/// let image = Image::<f32, 3>::new(
+///     [16, 16].into(),
+///     (0..256)
+///         .flat_map(|i| {
+///             let (x, y) = (i % 16, i / 16);
+///             if (4..12).contains(&x) && (4..12).contains(&y) {
+///                 [0.9, 0.1, 0.1]
+///             } else {
+///                 [0.1, 0.1, 0.9]
+///             }
+///         })
+///         .collect(),
+/// )
+/// .unwrap();
+///
+/// let mut grabcut = GrabCut::from_rect(&image, [2, 2, 12, 12]).unwrap();
+/// grabcut.iterate(&image, 2).unwrap();
+///
+/// let alpha = grabcut.alpha().unwrap();
+/// assert_eq!(alpha.get_pixel(8, 8, 0).unwrap(), &255);
+/// assert_eq!(alpha.get_pixel(2, 2, 0).unwrap(), &0);
+/// ```
+pub struct GrabCut {
+    mask: Image<u8, 1>,
+    background: Gmm,
+    foreground: Gmm,
+    gamma: f64,
+}
+
+impl GrabCut {
+    /// The default weight of the smoothness term, as in the original paper.
+    pub const DEFAULT_GAMMA: f32 = 50.0;
+
+    /// Initializes the segmentation from a rectangle around the foreground.
+    ///
+    /// The pixels outside the rectangle are background and the pixels inside are
+    /// probable foreground.
+    ///
+    /// # Arguments
+    ///
+    /// * `src` - The source image with shape (H, W, 3) and colors in [0, 1].
+    /// * `rect` - The rectangle as [x, y, width, height], which must lie inside the image.
+    pub fn from_rect(src: &Image<f32, 3>, rect: [usize; 4]) -> Result<Self, ImageError> {
+        let [x, y, width, height] = rect;
+        if x + width > src.cols() || y + height > src.rows() {
+            return Err(ImageError::PixelIndexOutOfBounds(
+                x + width,
+                y + height,
+                src.cols(),
+                src.rows(),
+            ));
+        }
+
+        let mut mask = Image::from_size_val(src.size(), GrabCutLabel::Background as u8)?;
+        let cols = src.cols();
+        for r in y..y + height {
+            mask.as_slice_mut()[r * cols + x..r * cols + x + width]
+                .fill(GrabCutLabel::ProbableForeground as u8);
+        }
+
+        Self::from_mask(src, mask)
+    }
+
+    /// Initializes the segmentation from a mask of seeds.
+    ///
+    /// # Arguments
+    ///
+    /// * `src` - The source image with shape (H, W, 3) and colors in [0, 1].
+    /// * `mask` - The mask with shape (H, W, 1) and the values of [`GrabCutLabel`].
+    pub fn from_mask(src: &Image<f32, 3>, mask: Image<u8, 1>) -> Result<Self, ImageError> {
+        if src.size() != mask.size() {
+            return Err(ImageError::InvalidImageSize(
+                src.cols(),
+                src.rows(),
+                mask.cols(),
+                mask.rows(),
+            ));
+        }
+
+        let (background, foreground) = split_colors(src, &mask);
+
+        Ok(Self {
+            background: Gmm::from_kmeans(&background),
+            foreground: Gmm::from_kmeans(&foreground),
+            mask,
+            gamma: Self::DEFAULT_GAMMA as f64,
+        })
+    }
+
+    /// Sets the weight of the smoothness term.
+    ///
+    /// # Arguments
+    ///
+    /// * `gamma` - The weight of the contrast between the neighboring pixels.
+    pub fn with_gamma(mut self, gamma: f32) -> Self {
+        self.gamma = gamma as f64;
+        self
+    }
+
+    /// Returns the mask with the values of [`GrabCutLabel`].
+    pub fn mask(&self) -> &Image<u8, 1> {
+        &self.mask
+    }
+
+    /// Returns the mask to fix pixels between the iterations.
+    pub fn mask_mut(&mut self) -> &mut Image<u8, 1> {
+        &mut self.mask
+    }
+
+    /// Returns the alpha mask of the foreground, with 255 in the foreground and 0 in the
+    /// background.
+    pub fn alpha(&self) -> Result<Image<u8, 1>, ImageError> {
+        self.mask.map(|&value| {
+            if GrabCutLabel::from(value).is_foreground() {
+                255
+            } else {
+                0
+            }
+        })
+    }
+
+    /// Refines the segmentation.
+    ///
+    /// # Arguments
+    ///
+    /// * `src` - The source image with shape (H, W, 3), the same as for the initialization.
+    /// * `num_iterations` - The number of iterations.
+    pub fn iterate(
+        &mut self,
+        src: &Image<f32, 3>,
+        num_iterations: usize,
+    ) -> Result<(), ImageError> {
+        if src.size() != self.mask.size() {
+            return Err(ImageError::InvalidImageSize(
+                src.cols(),
+                src.rows(),
+                self.mask.cols(),
+                self.mask.rows(),
+            ));
+        }
+
+        for _ in 0..num_iterations {
+            // fit the color models to the current segmentation
+            let (background, foreground) = split_colors(src, &self.mask);
+            self.background = self.background.refit(&background);
+            self.foreground = self.foreground.refit(&foreground);
+
+            self.cut(src);
+        }
+
+        Ok(())
+    }
+
+    // relabel the probable pixels with the minimum cut, the source is the foreground
+    fn cut(&mut self, src: &Image<f32, 3>) {
+        let (cols, rows) = (src.cols(), src.rows());
+        let color = |i: usize| -> [f64; 3] {
+            let data = &src.as_slice()[i * 3..i * 3 + 3];
+            [data[0] as f64, data[1] as f64, data[2] as f64]
+        };
+
+        // the forward neighbors with their distance, the others are visited from them
+        let neighbors: [(isize, usize, f64); 4] = [
+            (1, 0, 1.0),
+            (-1, 1, std::f64::consts::SQRT_2),
+            (0, 1, 1.0),
+            (1, 1, std::f64::consts::SQRT_2),
+        ];
+        let neighbor = |x: usize, y: usize, dx: isize, dy: usize| -> Option<usize> {
+            let (nx, ny) = (x.checked_add_signed(dx)?, y + dy);
+            (nx < cols && ny < rows).then_some(ny * cols + nx)
+        };
+
+        // beta normalizes the contrast by its mean over the image
+        let mut sum_sq = 0.0;
+        let mut count = 0usize;
+        for y in 0..rows {
+            for x in 0..cols {
+                for &(dx, dy, _) in &neighbors {
+                    if let Some(j) = neighbor(x, y, dx, dy) {
+                        sum_sq += squared_distance(&color(y * cols + x), &color(j));
+                        count += 1;
+                    }
+                }
+            }
+        }
+        let beta = if sum_sq > 0.0 {
+            count as f64 / (2.0 * sum_sq)
+        } else {
+            0.0
+        };
+
+        // the fixed pixels cost more than any cut of their neighbors
+        let lambda = 9.0 * self.gamma;
+
+        let mut graph = MaxFlow::new(cols * rows);
+        for y in 0..rows {
+            for x in 0..cols {
+                let i = y * cols + x;
+                let pixel = color(i);
+
+                let (source, sink) = match GrabCutLabel::from(self.mask.as_slice()[i]) {
+                    GrabCutLabel::Background => (0.0, lambda),
+                    GrabCutLabel::Foreground => (lambda, 0.0),
+                    _ => (self.background.cost(&pixel), self.foreground.cost(&pixel)),
+                };
+                graph.add_terminal_weights(i, source as f32, sink as f32);
+
+                for &(dx, dy, distance) in &neighbors {
+                    if let Some(j) = neighbor(x, y, dx, dy) {
+                        let contrast = squared_distance(&pixel, &color(j));
+                        let weight = (self.gamma / distance * (-beta * contrast).exp()) as f32;
+                        graph.add_edge(i, j, weight, weight);
+                    }
+                }
+            }
+        }
+
+        graph.solve();
+
+        for (i, value) in self.mask.as_slice_mut().iter_mut().enumerate() {
+            if !GrabCutLabel::from(*value).is_fixed() {
+                *value = if graph.is_source(i) {
+                    GrabCutLabel::ProbableForeground as u8
+                } else {
+                    GrabCutLabel::ProbableBackground as u8
+                };
+            }
+        }
+    }
+}
+
+// the colors of the background and the foreground pixels
+fn split_colors(src: &Image<f32, 3>, mask: &Image<u8, 1>) -> (Vec<[f64; 3]>, Vec<[f64; 3]>) {
+    let mut background = Vec::new();
+    let mut foreground = Vec::new();
+    for (pixel, &value) in src.as_slice().chunks_exact(3).zip(mask.as_slice()) {
+        let color = [pixel[0] as f64, pixel[1] as f64, pixel[2] as f64];
+        if GrabCutLabel::from(value).is_foreground() {
+            foreground.push(color);
+        } else {
+            background.push(color);
+        }
+    }
+    (background, foreground)
+}
+
+fn squared_distance(a: &[f64; 3], b: &[f64; 3]) -> f64 {
+    (0..3).map(|i| (a[i] - b[i]).powi(2)).sum()
+}
+
+// a gaussian of a color mixture model
+#[derive(Clone)]
+struct Gaussian {
+    weight: f64,
+    mean: [f64; 3],
+    inverse: [[f64; 3]; 3],
+    // the normalization of the density, 1 / sqrt((2 pi)^3 det)
+    norm: f64,
+}
+
+impl Gaussian {
+    fn density(&self, color: &[f64; 3]) -> f64 {
+        let d = [
+            color[0] - self.mean[0],
+            color[1] - self.mean[1],
+            color[2] - self.mean[2],
+        ];
+        let mut mahalanobis = 0.0;
+        for (i, row) in self.inverse.iter().enumerate() {
+            mahalanobis += d[i] * (row[0] * d[0] + row[1] * d[1] + row[2] * d[2]);
+        }
+        self.norm * (-0.5 * mahalanobis).exp()
+    }
+}
+
+// a gaussian mixture model of the colors
+#[derive(Clone)]
+struct Gmm {
+    components: Vec<Gaussian>,
+}
+
+impl Gmm {
+    // fit the mixture to the clusters of k-means
+    fn from_kmeans(samples: &[[f64; 3]]) -> Self {
+        let k = NUM_COMPONENTS.min(samples.len());
+        if k == 0 {
+            return Self {
+                components: Vec::new(),
+            };
+        }
+
+        // farthest point initialization keeps the clusters deterministic
+        let mut centers = vec![samples[0]];
+        let mut min_distance = samples
+            .iter()
+            .map(|s| squared_distance(s, &samples[0]))
+            .collect::<Vec<_>>();
+        while centers.len() < k {
+            let (farthest, _) = min_distance
+                .iter()
+                .enumerate()
+                .fold(
+                    (0, -1.0),
+                    |best, (i, &d)| if d > best.1 { (i, d) } else { best },
+                );
+            centers.push(samples[farthest]);
+            for (d, s) in min_distance.iter_mut().zip(samples) {
+                *d = d.min(squared_distance(s, &samples[farthest]));
+            }
+        }
+
+        let mut labels = vec![0; samples.len()];
+        for _ in 0..KMEANS_ITERATIONS {
+            for (label, s) in labels.iter_mut().zip(samples) {
+                *label = (0..k)
+                    .min_by(|&a, &b| {
+                        squared_distance(s, &centers[a])
+                            .total_cmp(&squared_distance(s, &centers[b]))
+                    })
+                    .unwrap_or(0);
+            }
+
+            let mut sums = vec![([0.0; 3], 0usize); k];
+            for (&label, s) in labels.iter().zip(samples) {
+                for (sum, v) in sums[label].0.iter_mut().zip(s) {
+                    *sum += v;
+                }
+                sums[label].1 += 1;
+            }
+            for (center, (sum, n)) in centers.iter_mut().zip(&sums) {
+                if *n > 0 {
+                    *center = sum.map(|v| v / *n as f64);
+                }
+            }
+        }
+
+        Self::fit(samples, &labels, k)
+    }
+
+    // assign the samples to their most likely gaussian and fit the mixture again
+    fn refit(&self, samples: &[[f64; 3]]) -> Self {
+        if self.components.is_empty() {
+            return Self::from_kmeans(samples);
+        }
+
+        let labels = samples
+            .iter()
+            .map(|s| {
+                self.components
+                    .iter()
+                    .map(|g| g.weight * g.density(s))
+                    .enumerate()
+                    .fold(
+                        (0, -1.0),
+                        |best, (i, p)| if p > best.1 { (i, p) } else { best },
+                    )
+                    .0
+            })
+            .collect::<Vec<_>>();
+
+        Self::fit(samples, &labels, self.components.len())
+    }
+
+    fn fit(samples: &[[f64; 3]], labels: &[usize], k: usize) -> Self {
+        let mut counts = vec![0usize; k];
+        let mut sums = vec![[0.0; 3]; k];
+        let mut products = vec![[[0.0; 3]; 3]; k];
+        for (&label, s) in labels.iter().zip(samples) {
+            counts[label] += 1;
+            for i in 0..3 {
+                sums[label][i] += s[i];
+                for j in 0..3 {
+                    products[label][i][j] += s[i] * s[j];
+                }
+            }
+        }
+
+        let mut components = Vec::with_capacity(k);
+        for ((count, sum), product) in counts.iter().zip(&sums).zip(&products) {
+            if *count == 0 {
+                continue;
+            }
+            let n = *count as f64;
+            let mean = sum.map(|v| v / n);
+
+            let mut covariance = [[0.0; 3]; 3];
+            for i in 0..3 {
+                for j in 0..3 {
+                    covariance[i][j] = product[i][j] / n - mean[i] * mean[j];
+                }
+                covariance[i][i] += COVARIANCE_EPS;
+            }
+
+            let Some((inverse, det)) = invert3(&covariance) else {
+                continue;
+            };
+
+            components.push(Gaussian {
+                weight: n / samples.len() as f64,
+                mean,
+                inverse,
+                norm: 1.0 / ((2.0 * std::f64::consts::PI).powi(3) * det).sqrt(),
+            });
+        }
+
+        Self { components }
+    }
+
+    // the negative log likelihood of a color
+    fn cost(&self, color: &[f64; 3]) -> f64 {
+        let likelihood = self
+            .components
+            .iter()
+            .map(|g| g.weight * g.density(color))
+            .sum::<f64>();
+        -likelihood.max(f64::MIN_POSITIVE).ln()
+    }
+}
+
+// the inverse and the determinant of a 3x3 matrix
+fn invert3(m: &[[f64; 3]; 3]) -> Option<([[f64; 3]; 3], f64)> {
+    let cofactor =
+        |r0: usize, r1: usize, c0: usize, c1: usize| m[r0][c0] * m[r1][c1] - m[r0][c1] * m[r1][c0];
+    let c00 = cofactor(1, 2, 1, 2);
+    let c01 = -cofactor(1, 2, 0, 2);
+    let c02 = cofactor(1, 2, 0, 1);
+    let det = m[0][0] * c00 + m[0][1] * c01 + m[0][2] * c02;
+    if det <= f64::EPSILON * f64::EPSILON {
+        return None;
+    }
+
+    let inverse = [
+        [c00, -cofactor(0, 2, 1, 2), cofactor(0, 1, 1, 2)],
+        [c01, cofactor(0, 2, 0, 2), -cofactor(0, 1, 0, 2)],
+        [c02, -cofactor(0, 2, 0, 1), cofactor(0, 1, 0, 1)],
+    ]
+    .map(|row| row.map(|v| v / det));
+
+    Some((inverse, det))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // a noisy red disk on a noisy background with two blue tones
+    fn disk_image(size: usize) -> Result<Image<f32, 3>, ImageError> {
+        let center = size as f32 / 2.0;
+        let data = (0..size * size)
+            .flat_map(|i| {
+                let (x, y) = ((i % size) as f32, (i / size) as f32);
+                let noise = ((i * 7919) % 13) as f32 / 13.0 * 0.1;
+                let r = ((x - center).powi(2) + (y - center).powi(2)).sqrt();
+                if r < size as f32 / 4.0 {
+                    [0.8 + noise, 0.2, 0.1 + noise]
+                } else if x < center {
+                    [0.1, 0.2 + noise, 0.8]
+                } else {
+                    [0.2 + noise, 0.5, 0.7]
+                }
+            })
+            .collect();
+        Image::new([size, size].into(), data)
+    }
+
+    fn is_inside(size: usize, i: usize) -> bool {
+        let center = size as f32 / 2.0;
+        let (x, y) = ((i % size) as f32, (i / size) as f32);
+        ((x - center).powi(2) + (y - center).powi(2)).sqrt() < size as f32 / 4.0
+    }
+
+    #[test]
+    fn test_grabcut_rect() -> Result<(), ImageError> {
+        let size = 32;
+        let image = disk_image(size)?;
+
+        let mut grabcut = GrabCut::from_rect(&image, [4, 4, 24, 24])?;
+        grabcut.iterate(&image, 3)?;
+
+        let alpha = grabcut.alpha()?;
+        for (i, &value) in alpha.as_slice().iter().enumerate() {
+            assert_eq!(value == 255, is_inside(size, i), "pixel {i}");
+        }
+
+        assert!(GrabCut::from_rect(&image, [10, 10, 24, 4]).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_grabcut_mask() -> Result<(), ImageError> {
+        let size = 32;
+        let image = disk_image(size)?;
+
+        // seeds at the center of the disk and in the corners
+        let mut mask = Image::from_size_val(image.size(), GrabCutLabel::ProbableBackground as u8)?;
+        for (i, value) in mask.as_slice_mut().iter_mut().enumerate() {
+            let (x, y) = (i % size, i / size);
+            if (14..18).contains(&x) && (14..18).contains(&y) {
+                *value = GrabCutLabel::Foreground as u8;
+            } else if x < 2 || x >= size - 2 {
+                *value = GrabCutLabel::Background as u8;
+            }
+        }
+
+        let mut grabcut = GrabCut::from_mask(&image, mask)?;
+        grabcut.iterate(&image, 3)?;
+
+        let alpha = grabcut.alpha()?;
+        for (i, &value) in alpha.as_slice().iter().enumerate() {
+            assert_eq!(value == 255, is_inside(size, i), "pixel {i}");
+        }
+
+        // a fixed pixel keeps its label
+        grabcut.mask_mut().as_slice_mut()[0] = GrabCutLabel::Foreground as u8;
+        grabcut.iterate(&image, 1)?;
+        assert_eq!(grabcut.alpha()?.as_slice()[0], 255);
+
+        Ok(())
+    }
+}
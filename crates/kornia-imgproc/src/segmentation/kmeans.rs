@@ -0,0 +1,280 @@
+use crate::parallel::prelude::*;
+use kornia_image::{Image, ImageError};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+/// The termination criteria of k-means.
+#[derive(Debug, Clone)]
+pub struct KMeansCriteria {
+    /// The maximum number of iterations.
+    pub max_iterations: usize,
+    /// The iterations stop when no center moves more than this distance.
+    pub epsilon: f32,
+    /// The seed of the random number generator of the seeding of [`kmeans_colors`],
+    /// ignored by [`kmeans_colors_with_rng`].
+    pub seed: u64,
+}
+
+impl Default for KMeansCriteria {
+    fn default() -> Self {
+        Self {
+            max_iterations: 20,
+            epsilon: 1e-4,
+            seed: 0,
+        }
+    }
+}
+
+/// The colors of an image clustered with k-means.
+pub struct ColorClusters<const C: usize> {
+    /// The colors of the clusters.
+    pub palette: Vec<[f32; C]>,
+    /// The number of pixels of each cluster.
+    pub counts: Vec<usize>,
+    /// The cluster of each pixel, indexing the palette.
+    pub labels: Image<u32, 1>,
+}
+
+impl<const C: usize> ColorClusters<C> {
+    /// Returns the image with each pixel replaced by the color of its cluster.
+    pub fn quantize(&self) -> Result<Image<f32, C>, ImageError> {
+        Image::new(
+            self.labels.size(),
+            self.labels
+                .as_slice()
+                .iter()
+                .flat_map(|&label| self.palette[label as usize])
+                .collect(),
+        )
+    }
+
+    /// Returns the indices of the clusters sorted by decreasing number of pixels, i.e. the
+    /// dominant colors first.
+    pub fn dominant(&self) -> Vec<usize> {
+        let mut indices = (0..self.palette.len()).collect::<Vec<_>>();
+        indices.sort_by(|&a, &b| self.counts[b].cmp(&self.counts[a]));
+        indices
+    }
+}
+
+/// Cluster the colors of an image with k-means.
+///
+/// The centers are seeded with k-means++, which samples the pixels with a probability
+/// proportional to their squared distance to the closest center, and refined with Lloyd's
+/// iterations where the pixels are assigned in parallel.
+///
+/// # Arguments
+///
+/// * `src` - The source image with shape (H, W, C).
+/// * `k` - The number of clusters, at most the number of pixels.
+/// * `criteria` - The termination criteria and the seed.
+///
+/// # Returns
+///
+/// The palette and the labels of the pixels.
+///
+/// # Example
+///
+/// ```
+/// use kornia_image::Image;
+/// use kornia_imgproc::segmentation::{kmeans_colors, KMeansCriteria};
+///
+/// let image = Image::<f32, 1>::new([4, 1].into(), vec![0.0, 0.1, 0.9, 1.0]).unwrap();
+///
+/// let clusters = kmeans_colors(&image, 2, &KMeansCriteria::default()).unwrap();
+/// let labels = clusters.labels.as_slice();
+/// assert_eq!(labels[0], labels[1]);
+/// assert_ne!(labels[1], labels[2]);
+/// assert!((clusters.palette[labels[3] as usize][0] - 0.95).abs() < 1e-6);
+/// ```
+pub fn kmeans_colors<const C: usize>(
+    src: &Image<f32, C>,
+    k: usize,
+    criteria: &KMeansCriteria,
+) -> Result<ColorClusters<C>, ImageError> {
+    kmeans_colors_with_rng(src, k, criteria, &mut StdRng::seed_from_u64(criteria.seed))
+}
+
+/// Cluster the colors of an image with k-means and a given random number generator.
+///
+/// The clustering is the same as [`kmeans_colors`], with the seeding drawn from `rng`
+/// instead of a generator seeded with `criteria.seed`.
+///
+/// # Arguments
+///
+/// * `src` - The source image with shape (H, W, C).
+/// * `k` - The number of clusters, at most the number of pixels.
+/// * `criteria` - The termination criteria.
+/// * `rng` - The random number generator.
+///
+/// # Returns
+///
+/// The palette and the labels of the pixels.
+pub fn kmeans_colors_with_rng<const C: usize, R: Rng + ?Sized>(
+    src: &Image<f32, C>,
+    k: usize,
+    criteria: &KMeansCriteria,
+    rng: &mut R,
+) -> Result<ColorClusters<C>, ImageError> {
+    let colors = src
+        .as_slice()
+        .chunks_exact(C)
+        .map(|pixel| std::array::from_fn::<f32, C, _>(|ch| pixel[ch]))
+        .collect::<Vec<_>>();
+
+    if k == 0 || k > colors.len() {
+        return Err(ImageError::InvalidImageSize(
+            src.cols(),
+            src.rows(),
+            k,
+            colors.len(),
+        ));
+    }
+
+    let mut palette = kmeans_plus_plus(&colors, k, rng);
+    let mut labels = vec![0u32; colors.len()];
+    let mut counts = vec![0usize; k];
+
+    for _ in 0..criteria.max_iterations.max(1) {
+        labels
+            .par_iter_mut()
+            .zip(colors.par_iter())
+            .for_each(|(label, color)| *label = closest(&palette, color).0 as u32);
+
+        let mut sums = vec![[0.0f64; C]; k];
+        counts.fill(0);
+        for (&label, color) in labels.iter().zip(&colors) {
+            for (sum, &v) in sums[label as usize].iter_mut().zip(color) {
+                *sum += v as f64;
+            }
+            counts[label as usize] += 1;
+        }
+
+        let mut max_shift = 0.0f32;
+        for ((center, sum), &count) in palette.iter_mut().zip(&sums).zip(&counts) {
+            // the empty clusters keep their center
+            if count == 0 {
+                continue;
+            }
+            let mean = sum.map(|v| (v / count as f64) as f32);
+            max_shift = max_shift.max(squared_distance(center, &mean).sqrt());
+            *center = mean;
+        }
+
+        if max_shift <= criteria.epsilon {
+            break;
+        }
+    }
+
+    // the labels of the final centers
+    labels
+        .par_iter_mut()
+        .zip(colors.par_iter())
+        .for_each(|(label, color)| *label = closest(&palette, color).0 as u32);
+    counts.fill(0);
+    labels.iter().for_each(|&label| counts[label as usize] += 1);
+
+    Ok(ColorClusters {
+        palette,
+        counts,
+        labels: Image::new(src.size(), labels)?,
+    })
+}
+
+// sample the centers with a probability proportional to the squared distance
+fn kmeans_plus_plus<const C: usize, R: Rng + ?Sized>(
+    colors: &[[f32; C]],
+    k: usize,
+    rng: &mut R,
+) -> Vec<[f32; C]> {
+    let mut centers = vec![colors[rng.random_range(0..colors.len())]];
+    let mut distances = colors
+        .par_iter()
+        .map(|color| squared_distance(color, &centers[0]) as f64)
+        .collect::<Vec<_>>();
+
+    while centers.len() < k {
+        let total = distances.iter().sum::<f64>();
+        let next = if total > 0.0 {
+            let mut target = rng.random::<f64>() * total;
+            distances
+                .iter()
+                .position(|&d| {
+                    target -= d;
+                    target < 0.0
+                })
+                .unwrap_or(colors.len() - 1)
+        } else {
+            // all the colors are already centers
+            rng.random_range(0..colors.len())
+        };
+
+        let center = colors[next];
+        distances
+            .par_iter_mut()
+            .zip(colors.par_iter())
+            .for_each(|(d, color)| *d = d.min(squared_distance(color, &center) as f64));
+        centers.push(center);
+    }
+
+    centers
+}
+
+// the index and the squared distance of the closest center
+fn closest<const C: usize>(centers: &[[f32; C]], color: &[f32; C]) -> (usize, f32) {
+    centers
+        .iter()
+        .map(|center| squared_distance(center, color))
+        .enumerate()
+        .fold(
+            (0, f32::MAX),
+            |best, (i, d)| if d < best.1 { (i, d) } else { best },
+        )
+}
+
+fn squared_distance<const C: usize>(a: &[f32; C], b: &[f32; C]) -> f32 {
+    a.iter().zip(b).map(|(a, b)| (a - b).powi(2)).sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_kmeans_colors() -> Result<(), ImageError> {
+        // three colors with some noise, the red one covering half of the image
+        let colors = [[0.9, 0.1, 0.1], [0.1, 0.9, 0.1], [0.1, 0.1, 0.9]];
+        let image = Image::<f32, 3>::new(
+            [16, 8].into(),
+            (0..128)
+                .flat_map(|i| {
+                    let noise = ((i * 7919) % 7) as f32 * 0.005;
+                    let color = if i % 16 < 8 { 0 } else { 1 + i % 2 };
+                    colors[color].map(|v| v + noise)
+                })
+                .collect(),
+        )?;
+
+        let criteria = KMeansCriteria::default();
+        let clusters = kmeans_colors(&image, 3, &criteria)?;
+        assert_eq!(clusters.counts.iter().sum::<usize>(), 128);
+
+        let dominant = clusters.dominant();
+        assert_eq!(clusters.counts[dominant[0]], 64);
+        assert!(clusters.palette[dominant[0]][0] > 0.9);
+
+        // every pixel is close to its quantized color
+        let quantized = clusters.quantize()?;
+        for (a, b) in image.as_slice().iter().zip(quantized.as_slice()) {
+            assert!((a - b).abs() < 0.02);
+        }
+
+        // the same seed gives the same result
+        let again = kmeans_colors(&image, 3, &criteria)?;
+        assert_eq!(again.labels.as_slice(), clusters.labels.as_slice());
+
+        assert!(kmeans_colors(&image, 0, &criteria).is_err());
+        assert!(kmeans_colors(&image, 129, &criteria).is_err());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,291 @@
+use std::collections::VecDeque;
+
+use crate::parallel::prelude::*;
+use kornia_image::{Image, ImageError};
+
+use crate::pyramid::{pyrdown, pyrup};
+
+// the maximum number of mean shift iterations per pixel, as in OpenCV
+const MAX_ITERATIONS: usize = 5;
+
+// the iterations stop when the color moves less than this fraction of the color radius
+const COLOR_EPSILON: f32 = 0.01;
+
+/// Filter an image with the mean shift in the joint space of the coordinates and the colors.
+///
+/// Each pixel is moved to the mean of the pixels in a window of radius `sp` whose color is
+/// within `sr` of the current color, until convergence, and takes the final color. This
+/// flattens the colors inside the regions and preserves the edges. With `max_level` above
+/// zero, the filter runs on a gaussian pyramid from the coarsest level and only the pixels
+/// near a color discontinuity of the upsampled result are filtered again on the finer
+/// levels, as in OpenCV.
+///
+/// # Arguments
+///
+/// * `src` - The source image with shape (H, W, C).
+/// * `dst` - The destination image with shape (H, W, C).
+/// * `sp` - The spatial radius of the window in pixels.
+/// * `sr` - The color radius in the units of the image.
+/// * `max_level` - The maximum level of the pyramid, 0 to filter the full resolution only.
+///
+/// # Example
+///
+/// ```
+/// use kornia_image::Image;
+/// use kornia_imgproc::segmentation::pyr_mean_shift_filtering;
+///
+/// let src = Image::<f32, 1>::new([4, 1].into(), vec![0.0, 0.1, 0.9, 1.0]).unwrap();
+/// let mut dst = Image::<f32, 1>::from_size_val(src.size(), 0.0).unwrap();
+///
+/// pyr_mean_shift_filtering(&src, &mut dst, 2, 0.2, 0).unwrap();
+/// assert!((dst.as_slice()[0] - dst.as_slice()[1]).abs() < 1e-6);
+/// assert!((dst.as_slice()[2] - dst.as_slice()[3]).abs() < 1e-6);
+/// ```
+pub fn pyr_mean_shift_filtering<const C: usize>(
+    src: &Image<f32, C>,
+    dst: &mut Image<f32, C>,
+    sp: usize,
+    sr: f32,
+    max_level: usize,
+) -> Result<(), ImageError> {
+    if src.size() != dst.size() {
+        return Err(ImageError::InvalidImageSize(
+            src.cols(),
+            src.rows(),
+            dst.cols(),
+            dst.rows(),
+        ));
+    }
+
+    let mut levels = vec![src.clone()];
+    for _ in 0..max_level {
+        let Some(last) = levels.last() else { break };
+        if last.cols() < 2 || last.rows() < 2 {
+            break;
+        }
+        let size = [last.cols().div_ceil(2), last.rows().div_ceil(2)].into();
+        let mut down = Image::from_size_val(size, 0.0)?;
+        pyrdown(last, &mut down)?;
+        levels.push(down);
+    }
+
+    // the upsampling blurs the edges over a few pixels
+    let edge_threshold2 = (sr * 0.25).powi(2);
+
+    // the coarsest level is filtered entirely
+    let mut levels = levels.into_iter().rev();
+    let coarsest = levels.next().ok_or(ImageError::ImageDataNotInitialized)?;
+    let mut result = coarsest.clone();
+    shift_pixels(&coarsest, &mut result, sp, sr, |_, _| true);
+
+    for level in levels {
+        let mut up = Image::from_size_val(level.size(), 0.0)?;
+        pyrup(&result, &mut up)?;
+
+        // refine the pixels where the upsampled colors change abruptly
+        let cols = up.cols();
+        let rows = up.rows();
+        let mask = {
+            let data = up.as_slice();
+            let at = |x: usize, y: usize| &data[(y * cols + x) * C..(y * cols + x + 1) * C];
+            (0..cols * rows)
+                .map(|i| {
+                    let (x, y) = (i % cols, i / cols);
+                    let pixel = at(x, y);
+                    let differs = |nx: usize, ny: usize| {
+                        squared_distance(pixel, at(nx, ny)) > edge_threshold2
+                    };
+                    // the neighbors of the 3x3 window, to cover the blurred edges
+                    (y.saturating_sub(1)..(y + 2).min(rows)).any(|ny| {
+                        (x.saturating_sub(1)..(x + 2).min(cols)).any(|nx| differs(nx, ny))
+                    })
+                })
+                .collect::<Vec<_>>()
+        };
+
+        // the selected pixels restart from their color at this level
+        for (i, pixel) in up.as_slice_mut().chunks_exact_mut(C).enumerate() {
+            if mask[i] {
+                pixel.copy_from_slice(&level.as_slice()[i * C..(i + 1) * C]);
+            }
+        }
+        shift_pixels(&level, &mut up, sp, sr, |x, y| mask[y * cols + x]);
+        result = up;
+    }
+
+    dst.as_slice_mut().copy_from_slice(result.as_slice());
+
+    Ok(())
+}
+
+// move the selected pixels of dst, starting from their colors, with the mean shift in src
+fn shift_pixels<const C: usize>(
+    src: &Image<f32, C>,
+    dst: &mut Image<f32, C>,
+    sp: usize,
+    sr: f32,
+    selected: impl Fn(usize, usize) -> bool + Sync,
+) {
+    let (cols, rows) = (src.cols(), src.rows());
+    let data = src.as_slice();
+    let sr2 = sr * sr;
+    let epsilon = sr * COLOR_EPSILON;
+
+    dst.as_slice_mut()
+        .par_chunks_exact_mut(cols * C)
+        .enumerate()
+        .for_each(|(y, dst_row)| {
+            for (x, pixel) in dst_row.chunks_exact_mut(C).enumerate() {
+                if !selected(x, y) {
+                    continue;
+                }
+
+                let (mut x0, mut y0) = (x, y);
+                let mut color: [f32; C] = std::array::from_fn(|ch| pixel[ch]);
+
+                for _ in 0..MAX_ITERATIONS {
+                    let mut sum_x = 0usize;
+                    let mut sum_y = 0usize;
+                    let mut sum_color = [0.0f32; C];
+                    let mut count = 0usize;
+
+                    for wy in y0.saturating_sub(sp)..(y0 + sp + 1).min(rows) {
+                        for wx in x0.saturating_sub(sp)..(x0 + sp + 1).min(cols) {
+                            let other = &data[(wy * cols + wx) * C..(wy * cols + wx + 1) * C];
+                            if squared_distance(&color, other) <= sr2 {
+                                sum_x += wx;
+                                sum_y += wy;
+                                sum_color.iter_mut().zip(other).for_each(|(s, v)| *s += v);
+                                count += 1;
+                            }
+                        }
+                    }
+
+                    if count == 0 {
+                        break;
+                    }
+
+                    let x1 = (sum_x as f32 / count as f32).round() as usize;
+                    let y1 = (sum_y as f32 / count as f32).round() as usize;
+                    let mean = sum_color.map(|s| s / count as f32);
+                    let shift = squared_distance(&color, &mean).sqrt();
+
+                    let converged = x1 == x0 && y1 == y0 && shift <= epsilon;
+                    (x0, y0, color) = (x1, y1, mean);
+                    if converged {
+                        break;
+                    }
+                }
+
+                pixel.copy_from_slice(&color);
+            }
+        });
+}
+
+/// Segment an image filtered with [`pyr_mean_shift_filtering`] into connected regions.
+///
+/// The regions are grown from each unlabeled pixel to the 4-connected neighbors whose color
+/// is within the radius of the color of the pixel they are reached from, like a flood fill
+/// with a floating range.
+///
+/// # Arguments
+///
+/// * `src` - The filtered image with shape (H, W, C).
+/// * `color_radius` - The maximum color distance between the neighbors of a region.
+///
+/// # Returns
+///
+/// The label image with shape (H, W, 1) and consecutive labels starting at 0.
+pub fn mean_shift_segmentation<const C: usize>(
+    src: &Image<f32, C>,
+    color_radius: f32,
+) -> Result<Image<u32, 1>, ImageError> {
+    let (cols, rows) = (src.cols(), src.rows());
+    let data = src.as_slice();
+    let color = |i: usize| &data[i * C..(i + 1) * C];
+    let radius2 = color_radius * color_radius;
+
+    let mut labels = vec![u32::MAX; cols * rows];
+    let mut num_labels = 0;
+    let mut queue = VecDeque::new();
+
+    for seed in 0..cols * rows {
+        if labels[seed] != u32::MAX {
+            continue;
+        }
+
+        labels[seed] = num_labels;
+        queue.push_back(seed);
+        while let Some(i) = queue.pop_front() {
+            let (x, y) = (i % cols, i / cols);
+            let neighbors = [
+                (x > 0).then(|| i - 1),
+                (x + 1 < cols).then_some(i + 1),
+                (y > 0).then(|| i - cols),
+                (y + 1 < rows).then_some(i + cols),
+            ];
+            for j in neighbors.into_iter().flatten() {
+                if labels[j] == u32::MAX && squared_distance(color(i), color(j)) <= radius2 {
+                    labels[j] = num_labels;
+                    queue.push_back(j);
+                }
+            }
+        }
+        num_labels += 1;
+    }
+
+    Image::new(src.size(), labels)
+}
+
+fn squared_distance(a: &[f32], b: &[f32]) -> f32 {
+    a.iter().zip(b).map(|(a, b)| (a - b).powi(2)).sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // two noisy halves with different colors
+    fn halves(cols: usize, rows: usize) -> Result<Image<f32, 3>, ImageError> {
+        Image::new(
+            [cols, rows].into(),
+            (0..cols * rows)
+                .flat_map(|i| {
+                    let noise = ((i * 7919) % 9) as f32 * 0.01 - 0.04;
+                    if i % cols < cols / 2 {
+                        [0.2 + noise, 0.3, 0.4 - noise]
+                    } else {
+                        [0.8 - noise, 0.6, 0.3 + noise]
+                    }
+                })
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn test_pyr_mean_shift_filtering() -> Result<(), ImageError> {
+        let src = halves(16, 12)?;
+
+        for max_level in [0, 1, 2] {
+            let mut dst = Image::from_size_val(src.size(), 0.0)?;
+            pyr_mean_shift_filtering(&src, &mut dst, 3, 0.2, max_level)?;
+
+            // the edge is preserved, the coarser levels bias the colors near the edge
+            let tolerance = if max_level == 0 { 0.01 } else { 0.06 };
+            for (i, pixel) in dst.as_slice().chunks_exact(3).enumerate() {
+                let expected = if i % 16 < 8 { 0.2 } else { 0.8 };
+                assert!((pixel[0] - expected).abs() < tolerance);
+            }
+
+            let labels = mean_shift_segmentation(&dst, 0.1)?;
+            assert_eq!(labels.as_slice().iter().max(), Some(&1));
+            assert_eq!(labels.as_slice()[0], 0);
+            assert_eq!(labels.as_slice()[15], 1);
+        }
+
+        let mut dst = Image::from_size_val([8, 8].into(), 0.0)?;
+        assert!(pyr_mean_shift_filtering(&src, &mut dst, 3, 0.2, 1).is_err());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,12 @@
+mod felzenszwalb;
+mod grabcut;
+mod kmeans;
+mod maxflow;
+mod mean_shift;
+mod slic;
+pub use felzenszwalb::*;
+pub use grabcut::*;
+pub use kmeans::*;
+pub use maxflow::*;
+pub use mean_shift::*;
+pub use slic::*;
@@ -0,0 +1,312 @@
+use crate::parallel::prelude::*;
+use kornia_image::{Image, ImageError};
+
+/// The center of a superpixel.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SuperpixelCenter<const C: usize> {
+    /// The mean x coordinate of the pixels.
+    pub x: f32,
+    /// The mean y coordinate of the pixels.
+    pub y: f32,
+    /// The mean color of the pixels.
+    pub color: [f32; C],
+    /// The number of pixels.
+    pub num_pixels: usize,
+}
+
+/// The superpixels of an image.
+pub struct Superpixels<const C: usize> {
+    /// The label of each pixel, indexing the centers.
+    pub labels: Image<u32, 1>,
+    /// The centers of the superpixels.
+    pub centers: Vec<SuperpixelCenter<C>>,
+}
+
+/// Segment an image into superpixels with simple linear iterative clustering (SLIC).
+///
+/// The pixels are clustered with k-means in the space of the colors and the coordinates,
+/// where each center only considers the pixels in a window of twice the grid step, and the
+/// disconnected fragments are merged into their neighbors. The color distances are computed
+/// in the space of the input, for which CIELAB gives the best results.
+///
+/// # Arguments
+///
+/// * `src` - The source image with shape (H, W, C).
+/// * `num_superpixels` - The approximate number of superpixels.
+/// * `compactness` - The weight of the spatial distance relative to the color distance,
+///   e.g. 10.0 for CIELAB in [0, 100]; higher values give more regular superpixels.
+/// * `num_iterations` - The number of k-means iterations, e.g. 10.
+///
+/// # Returns
+///
+/// The labels and the centers of the superpixels.
+///
+/// # Example
+///
+/// ```
+/// use kornia_image::Image;
+/// use kornia_imgproc::segmentation::slic;
+///
+/// let image = Image::<f32, 3>::from_size_val([32, 32].into(), 0.5).unwrap();
+///
+/// let superpixels = slic(&image, 16, 10.0, 10).unwrap();
+/// assert_eq!(superpixels.labels.size(), image.size());
+/// assert_eq!(superpixels.centers.len(), 16);
+/// ```
+pub fn slic<const C: usize>(
+    src: &Image<f32, C>,
+    num_superpixels: usize,
+    compactness: f32,
+    num_iterations: usize,
+) -> Result<Superpixels<C>, ImageError> {
+    let (cols, rows) = (src.cols(), src.rows());
+    let num_pixels = cols * rows;
+    if num_pixels == 0 {
+        return Ok(Superpixels {
+            labels: Image::from_size_val(src.size(), 0)?,
+            centers: Vec::new(),
+        });
+    }
+
+    let step = ((num_pixels as f32 / num_superpixels.max(1) as f32)
+        .sqrt()
+        .round() as usize)
+        .max(1);
+    let data = src.as_slice();
+    let color = |x: usize, y: usize| -> [f32; C] {
+        std::array::from_fn(|ch| data[(y * cols + x) * C + ch])
+    };
+
+    // the centers on a regular grid, moved to the lowest gradient of their 3x3 neighborhood
+    let grid_cols = cols.div_ceil(step);
+    let grid_rows = rows.div_ceil(step);
+    let mut centers = Vec::with_capacity(grid_cols * grid_rows);
+    for gy in 0..grid_rows {
+        for gx in 0..grid_cols {
+            let x = (gx * step + step / 2).min(cols - 1);
+            let y = (gy * step + step / 2).min(rows - 1);
+            let (x, y) = lowest_gradient(src, x, y);
+            centers.push(SuperpixelCenter {
+                x: x as f32,
+                y: y as f32,
+                color: color(x, y),
+                num_pixels: 0,
+            });
+        }
+    }
+
+    let spatial_weight = (compactness / step as f32).powi(2);
+    let window = step as f32;
+    let mut labels = vec![0u32; num_pixels];
+
+    for _ in 0..num_iterations.max(1) {
+        // assign the pixels to the closest center among the centers of the nearby cells
+        labels
+            .par_chunks_exact_mut(cols)
+            .enumerate()
+            .for_each(|(y, labels_row)| {
+                let gy = y / step;
+                for (x, label) in labels_row.iter_mut().enumerate() {
+                    let gx = x / step;
+                    let pixel = color(x, y);
+                    let mut best = f32::MAX;
+                    for cy in gy.saturating_sub(2)..(gy + 3).min(grid_rows) {
+                        for cx in gx.saturating_sub(2)..(gx + 3).min(grid_cols) {
+                            let k = cy * grid_cols + cx;
+                            let center = &centers[k];
+                            let (dx, dy) = (x as f32 - center.x, y as f32 - center.y);
+                            if dx.abs() > window || dy.abs() > window {
+                                continue;
+                            }
+                            let dc = pixel
+                                .iter()
+                                .zip(&center.color)
+                                .map(|(a, b)| (a - b) * (a - b))
+                                .sum::<f32>();
+                            let d = dc + (dx * dx + dy * dy) * spatial_weight;
+                            if d < best {
+                                best = d;
+                                *label = k as u32;
+                            }
+                        }
+                    }
+                }
+            });
+
+        // move the centers to the mean of their pixels, keeping the empty ones
+        let updated = compute_centers(src, &labels, centers.len());
+        for (center, new) in centers.iter_mut().zip(updated) {
+            if new.num_pixels > 0 {
+                *center = new;
+            }
+        }
+    }
+
+    let num_labels = enforce_connectivity(&mut labels, cols, rows, step * step / 4);
+    let centers = compute_centers(src, &labels, num_labels);
+
+    Ok(Superpixels {
+        labels: Image::new(src.size(), labels)?,
+        centers,
+    })
+}
+
+// the position with the lowest color gradient in the 3x3 neighborhood of a pixel
+fn lowest_gradient<const C: usize>(src: &Image<f32, C>, x: usize, y: usize) -> (usize, usize) {
+    let (cols, rows) = (src.cols(), src.rows());
+    let data = src.as_slice();
+    let at = |x: usize, y: usize, ch: usize| data[(y * cols + x) * C + ch];
+
+    let mut best = (x, y);
+    let mut best_gradient = f32::MAX;
+    for ny in y.saturating_sub(1)..=(y + 1).min(rows - 1) {
+        for nx in x.saturating_sub(1)..=(x + 1).min(cols - 1) {
+            if nx == 0 || ny == 0 || nx + 1 >= cols || ny + 1 >= rows {
+                continue;
+            }
+            let gradient = (0..C)
+                .map(|ch| {
+                    let gx = at(nx + 1, ny, ch) - at(nx - 1, ny, ch);
+                    let gy = at(nx, ny + 1, ch) - at(nx, ny - 1, ch);
+                    gx * gx + gy * gy
+                })
+                .sum::<f32>();
+            if gradient < best_gradient {
+                best_gradient = gradient;
+                best = (nx, ny);
+            }
+        }
+    }
+    best
+}
+
+// the mean position and color of the pixels of each label
+fn compute_centers<const C: usize>(
+    src: &Image<f32, C>,
+    labels: &[u32],
+    num_labels: usize,
+) -> Vec<SuperpixelCenter<C>> {
+    let cols = src.cols();
+    let mut sums = vec![([0.0f64; 2], [0.0f64; C], 0usize); num_labels];
+
+    for (i, (&label, pixel)) in labels
+        .iter()
+        .zip(src.as_slice().chunks_exact(C))
+        .enumerate()
+    {
+        let sum = &mut sums[label as usize];
+        sum.0[0] += (i % cols) as f64;
+        sum.0[1] += (i / cols) as f64;
+        for (acc, v) in sum.1.iter_mut().zip(pixel) {
+            *acc += *v as f64;
+        }
+        sum.2 += 1;
+    }
+
+    sums.into_iter()
+        .map(|(position, color, n)| {
+            let scale = 1.0 / n.max(1) as f64;
+            SuperpixelCenter {
+                x: (position[0] * scale) as f32,
+                y: (position[1] * scale) as f32,
+                color: color.map(|c| (c * scale) as f32),
+                num_pixels: n,
+            }
+        })
+        .collect()
+}
+
+// relabel the connected components and merge the ones smaller than `min_size` into the
+// previous adjacent component, returning the number of labels
+fn enforce_connectivity(labels: &mut [u32], cols: usize, rows: usize, min_size: usize) -> usize {
+    let mut new_labels = vec![u32::MAX; labels.len()];
+    let mut num_labels = 0u32;
+    let mut component = Vec::new();
+
+    for start in 0..labels.len() {
+        if new_labels[start] != u32::MAX {
+            continue;
+        }
+
+        // an adjacent component already labeled, used to merge small components
+        let (x, y) = (start % cols, start / cols);
+        let adjacent = [(x > 0).then(|| start - 1), (y > 0).then(|| start - cols)]
+            .into_iter()
+            .flatten()
+            .map(|n| new_labels[n])
+            .find(|&l| l != u32::MAX);
+
+        component.clear();
+        component.push(start);
+        new_labels[start] = num_labels;
+        let mut head = 0;
+        while head < component.len() {
+            let i = component[head];
+            head += 1;
+            let (x, y) = (i % cols, i / cols);
+            let neighbors = [
+                (x > 0).then(|| i - 1),
+                (x + 1 < cols).then(|| i + 1),
+                (y > 0).then(|| i - cols),
+                (y + 1 < rows).then(|| i + cols),
+            ];
+            for n in neighbors.into_iter().flatten() {
+                if new_labels[n] == u32::MAX && labels[n] == labels[start] {
+                    new_labels[n] = num_labels;
+                    component.push(n);
+                }
+            }
+        }
+
+        match adjacent {
+            Some(label) if component.len() < min_size => {
+                component.iter().for_each(|&i| new_labels[i] = label);
+            }
+            _ => num_labels += 1,
+        }
+    }
+
+    labels.copy_from_slice(&new_labels);
+    num_labels as usize
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_slic() -> Result<(), ImageError> {
+        // two halves with different colors
+        let (cols, rows) = (40, 20);
+        let data = (0..cols * rows)
+            .map(|i| if i % cols < 20 { 0.0 } else { 1.0 })
+            .collect();
+        let src = Image::<f32, 1>::new([cols, rows].into(), data)?;
+
+        let superpixels = slic(&src, 8, 0.1, 10)?;
+        let labels = superpixels.labels.as_slice();
+
+        // no superpixel crosses the boundary between the halves
+        for center in &superpixels.centers {
+            assert!(center.color[0] == 0.0 || center.color[0] == 1.0);
+        }
+        assert_eq!(
+            superpixels
+                .centers
+                .iter()
+                .map(|c| c.num_pixels)
+                .sum::<usize>(),
+            cols * rows
+        );
+        assert!(labels
+            .iter()
+            .all(|&l| (l as usize) < superpixels.centers.len()));
+
+        // the superpixels are connected, so each label is a single component
+        let mut labels_copy = labels.to_vec();
+        let num_labels = enforce_connectivity(&mut labels_copy, cols, rows, 0);
+        assert_eq!(num_labels, superpixels.centers.len());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,439 @@
+use std::collections::HashMap;
+
+use crate::parallel::prelude::*;
+use kornia_image::{Image, ImageError};
+
+/// The parameters of the exposure compensation.
+///
+/// The gains minimize the squared intensity differences in the overlaps, weighted by the
+/// noise of the intensities, plus the squared deviation of the gains from one, weighted by
+/// their expected spread.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ExposureParams {
+    /// The standard deviation of the intensity noise, in the range of the images.
+    pub noise_sigma: f32,
+    /// The standard deviation of the gains around one.
+    pub gain_sigma: f32,
+}
+
+impl Default for ExposureParams {
+    /// The parameters for images in the range [0, 1].
+    fn default() -> Self {
+        Self {
+            noise_sigma: 10.0 / 255.0,
+            gain_sigma: 0.1,
+        }
+    }
+}
+
+/// Estimate a gain per image which equalizes the brightness of the overlapping images.
+///
+/// The images are warped into the same canvas, and their masks mark the pixels that they
+/// cover, before the seams are found. The gains are found from the mean intensities of
+/// every overlap, so they are robust to the misalignment of the images.
+///
+/// # Arguments
+///
+/// * `images` - The images warped into the same canvas with shape (H, W, C).
+/// * `masks` - The masks of the images with shape (H, W, 1), non zero where they are valid.
+/// * `params` - The parameters of the compensation.
+///
+/// # Returns
+///
+/// The gain of every image, to be applied with [`apply_gain`].
+///
+/// # Example
+///
+/// ```
+/// use kornia_image::Image;
+/// use kornia_imgproc::stitching::{estimate_gains, ExposureParams};
+///
+/// let bright = Image::<f32, 1>::from_size_val([4, 4].into(), 0.6).unwrap();
+/// let dark = Image::<f32, 1>::from_size_val([4, 4].into(), 0.4).unwrap();
+/// let mask = Image::<u8, 1>::from_size_val([4, 4].into(), 255).unwrap();
+///
+/// let gains = estimate_gains(&[bright, dark], &[mask.clone(), mask], &ExposureParams::default()).unwrap();
+/// assert!(gains[0] < 1.0 && gains[1] > 1.0);
+/// ```
+pub fn estimate_gains<const C: usize>(
+    images: &[Image<f32, C>],
+    masks: &[Image<u8, 1>],
+    params: &ExposureParams,
+) -> Result<Vec<f32>, ImageError> {
+    check_inputs(images, masks)?;
+
+    // every image is a single block
+    let overlaps = overlap_statistics(images, masks, |_, _| 0, 1);
+    let gains = solve_gains(images.len(), &overlaps, params);
+
+    Ok(gains.into_iter().map(|g| g as f32).collect())
+}
+
+/// Estimate a map of gains per image which equalizes the brightness of the overlapping
+/// images.
+///
+/// The images are divided into blocks, and a gain is estimated for every block as in
+/// [`estimate_gains`], which also compensates the exposure that varies across the images,
+/// such as the vignetting. The gains are smoothed between the neighbouring blocks.
+///
+/// # Arguments
+///
+/// * `images` - The images warped into the same canvas with shape (H, W, C).
+/// * `masks` - The masks of the images with shape (H, W, 1), non zero where they are valid.
+/// * `block_size` - The width and the height of the blocks in pixels.
+/// * `params` - The parameters of the compensation.
+///
+/// # Returns
+///
+/// The gain map of every image with shape (H, W, 1), interpolated between the centers of
+/// the blocks, to be applied with
+/// [`apply_gain_map`](crate::calibration::vignetting::apply_gain_map).
+pub fn estimate_block_gains<const C: usize>(
+    images: &[Image<f32, C>],
+    masks: &[Image<u8, 1>],
+    block_size: [usize; 2],
+    params: &ExposureParams,
+) -> Result<Vec<Image<f32, 1>>, ImageError> {
+    check_inputs(images, masks)?;
+    let Some(first) = images.first() else {
+        return Ok(Vec::new());
+    };
+
+    let [block_cols, block_rows] = block_size.map(|s| s.max(1));
+    let grid_cols = first.cols().div_ceil(block_cols);
+    let grid_rows = first.rows().div_ceil(block_rows);
+    let num_blocks = grid_cols * grid_rows;
+
+    let overlaps = overlap_statistics(
+        images,
+        masks,
+        |x, y| (y / block_rows) * grid_cols + x / block_cols,
+        num_blocks,
+    );
+    let gains = solve_gains(images.len() * num_blocks, &overlaps, params);
+
+    let (cols, rows) = (first.cols(), first.rows());
+    gains
+        .chunks_exact(num_blocks)
+        .zip(masks)
+        .map(|(grid, mask)| {
+            // the blocks covered by the image
+            let mut covered = vec![false; num_blocks];
+            for (p, _) in mask.as_slice().iter().enumerate().filter(|(_, &m)| m != 0) {
+                covered[(p / cols / block_rows) * grid_cols + p % cols / block_cols] = true;
+            }
+
+            // average the gains of the neighbouring blocks
+            let mut smoothed = vec![1.0f32; num_blocks];
+            for y in 0..grid_rows {
+                for x in 0..grid_cols {
+                    let (mut sum, mut count) = (0.0, 0);
+                    for ny in y.saturating_sub(1)..(y + 2).min(grid_rows) {
+                        for nx in x.saturating_sub(1)..(x + 2).min(grid_cols) {
+                            if covered[ny * grid_cols + nx] {
+                                sum += grid[ny * grid_cols + nx];
+                                count += 1;
+                            }
+                        }
+                    }
+                    if count > 0 {
+                        smoothed[y * grid_cols + x] = (sum / count as f64) as f32;
+                    }
+                }
+            }
+
+            // interpolate the gains bilinearly between the centers of the blocks
+            let block_coordinate = |p: usize, size: usize, num: usize| {
+                let g = ((p as f32 + 0.5) / size as f32 - 0.5).clamp(0.0, (num - 1) as f32);
+                let g0 = (g as usize).min(num - 1);
+                (g0, (g0 + 1).min(num - 1), g - g0 as f32)
+            };
+            let mut map = Vec::with_capacity(cols * rows);
+            for y in 0..rows {
+                let (y0, y1, wy) = block_coordinate(y, block_rows, grid_rows);
+                for x in 0..cols {
+                    let (x0, x1, wx) = block_coordinate(x, block_cols, grid_cols);
+                    let at = |x: usize, y: usize| smoothed[y * grid_cols + x];
+                    let top = at(x0, y0) * (1.0 - wx) + at(x1, y0) * wx;
+                    let bottom = at(x0, y1) * (1.0 - wx) + at(x1, y1) * wx;
+                    map.push(top * (1.0 - wy) + bottom * wy);
+                }
+            }
+            Image::new([cols, rows].into(), map)
+        })
+        .collect()
+}
+
+/// Multiply an image by a gain.
+///
+/// # Arguments
+///
+/// * `src` - The input image with shape (H, W, C).
+/// * `dst` - The output image with shape (H, W, C).
+/// * `gain` - The gain of the image.
+pub fn apply_gain<const C: usize>(
+    src: &Image<f32, C>,
+    dst: &mut Image<f32, C>,
+    gain: f32,
+) -> Result<(), ImageError> {
+    if src.size() != dst.size() {
+        return Err(ImageError::InvalidImageSize(
+            src.cols(),
+            src.rows(),
+            dst.cols(),
+            dst.rows(),
+        ));
+    }
+
+    dst.as_slice_mut()
+        .par_iter_mut()
+        .zip(src.as_slice().par_iter())
+        .for_each(|(d, &s)| *d = s * gain);
+
+    Ok(())
+}
+
+fn check_inputs<const C: usize>(
+    images: &[Image<f32, C>],
+    masks: &[Image<u8, 1>],
+) -> Result<(), ImageError> {
+    if images.len() != masks.len() {
+        return Err(ImageError::InvalidChannelShape(images.len(), masks.len()));
+    }
+    let Some(first) = images.first() else {
+        return Ok(());
+    };
+
+    let size = first.size();
+    for other in images
+        .iter()
+        .map(|image| image.size())
+        .chain(masks.iter().map(|mask| mask.size()))
+    {
+        if other != size {
+            return Err(ImageError::InvalidImageSize(
+                size.width,
+                size.height,
+                other.width,
+                other.height,
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+// the number of pixels and the sums of the intensities of the two blocks in their overlap
+#[derive(Default)]
+struct Overlap {
+    count: f64,
+    sum_a: f64,
+    sum_b: f64,
+}
+
+// the overlaps between the blocks of every pair of images, indexed by the blocks of all the
+// images in order
+fn overlap_statistics<const C: usize>(
+    images: &[Image<f32, C>],
+    masks: &[Image<u8, 1>],
+    block: impl Fn(usize, usize) -> usize,
+    num_blocks: usize,
+) -> HashMap<(usize, usize), Overlap> {
+    let mut overlaps: HashMap<(usize, usize), Overlap> = HashMap::new();
+
+    for i in 0..images.len() {
+        for j in i + 1..images.len() {
+            let (mask_i, mask_j) = (masks[i].as_slice(), masks[j].as_slice());
+            let pixels_i = images[i].as_slice().chunks_exact(C);
+            let pixels_j = images[j].as_slice().chunks_exact(C);
+
+            for (p, (pixel_i, pixel_j)) in pixels_i.zip(pixels_j).enumerate() {
+                if mask_i[p] == 0 || mask_j[p] == 0 {
+                    continue;
+                }
+                let b = block(p % images[i].cols(), p / images[i].cols());
+                let key = (i * num_blocks + b, j * num_blocks + b);
+                let overlap = overlaps.entry(key).or_default();
+                overlap.count += 1.0;
+                overlap.sum_a += pixel_i.iter().sum::<f32>() as f64 / C as f64;
+                overlap.sum_b += pixel_j.iter().sum::<f32>() as f64 / C as f64;
+            }
+        }
+    }
+
+    overlaps
+}
+
+// solve the normal equations of the gains of the blocks
+fn solve_gains(
+    num_blocks: usize,
+    overlaps: &HashMap<(usize, usize), Overlap>,
+    params: &ExposureParams,
+) -> Vec<f64> {
+    let inv_noise = 1.0 / (params.noise_sigma as f64).powi(2);
+    let inv_gain = 1.0 / (params.gain_sigma as f64).powi(2);
+
+    let n = num_blocks;
+    let mut a = vec![0.0f64; n * n];
+    let mut b = vec![0.0f64; n];
+    for (&(u, v), overlap) in overlaps {
+        let mean_u = overlap.sum_a / overlap.count;
+        let mean_v = overlap.sum_b / overlap.count;
+        for (i, j, mean_i, mean_j) in [(u, v, mean_u, mean_v), (v, u, mean_v, mean_u)] {
+            a[i * n + i] += overlap.count * (mean_i * mean_i * inv_noise + inv_gain);
+            a[i * n + j] -= overlap.count * mean_i * mean_j * inv_noise;
+            b[i] += overlap.count * inv_gain;
+        }
+    }
+
+    // the blocks without overlaps keep their exposure
+    for i in 0..n {
+        if a[i * n + i] == 0.0 {
+            a[i * n + i] = 1.0;
+            b[i] = 1.0;
+        }
+    }
+
+    solve_linear(a, b).unwrap_or_else(|| vec![1.0; n])
+}
+
+// solve a square linear system with the gaussian elimination and partial pivoting
+fn solve_linear(mut a: Vec<f64>, mut b: Vec<f64>) -> Option<Vec<f64>> {
+    let n = b.len();
+    for k in 0..n {
+        let pivot = (k..n).max_by(|&p, &q| a[p * n + k].abs().total_cmp(&a[q * n + k].abs()))?;
+        if a[pivot * n + k].abs() < 1e-12 {
+            return None;
+        }
+        if pivot != k {
+            for c in 0..n {
+                a.swap(k * n + c, pivot * n + c);
+            }
+            b.swap(k, pivot);
+        }
+
+        for r in k + 1..n {
+            let factor = a[r * n + k] / a[k * n + k];
+            if factor != 0.0 {
+                for c in k..n {
+                    a[r * n + c] -= factor * a[k * n + c];
+                }
+                b[r] -= factor * b[k];
+            }
+        }
+    }
+
+    let mut x = vec![0.0; n];
+    for k in (0..n).rev() {
+        let sum = (k + 1..n).map(|c| a[k * n + c] * x[c]).sum::<f64>();
+        x[k] = (b[k] - sum) / a[k * n + k];
+    }
+    Some(x)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::calibration::vignetting::apply_gain_map;
+
+    // an image warped into the canvas with its mask
+    type Warped = (Image<f32, 3>, Image<u8, 1>);
+
+    // two views of a scene overlapping on the columns 40 to 60, the second with the
+    // exposure scaled by a function of the column
+    fn views(exposure: impl Fn(usize) -> f32) -> Result<[Warped; 2], ImageError> {
+        let (cols, rows) = (100, 40);
+        let scene =
+            |x: usize, y: usize| 0.5 + 0.2 * ((x as f32 * 0.3).sin() * (y as f32 * 0.2).cos());
+        let mut views = Vec::new();
+        for (range, scale) in [(0..60, None), (40..100, Some(&exposure))] {
+            let mut image = Vec::with_capacity(cols * rows * 3);
+            let mut mask = Vec::with_capacity(cols * rows);
+            for y in 0..rows {
+                for x in 0..cols {
+                    let value = scene(x, y) * scale.map_or(1.0, |f| f(x));
+                    image.extend([value; 3]);
+                    mask.push(if range.contains(&x) { 255 } else { 0 });
+                }
+            }
+            views.push((
+                Image::new([cols, rows].into(), image)?,
+                Image::new([cols, rows].into(), mask)?,
+            ));
+        }
+        let second = views.pop().unwrap();
+        let first = views.pop().unwrap();
+        Ok([first, second])
+    }
+
+    // the mean absolute difference of the images in the columns 40 to 60
+    fn overlap_error(a: &Image<f32, 3>, b: &Image<f32, 3>) -> f32 {
+        let (mut sum, mut count) = (0.0, 0);
+        for (i, (x, y)) in a.as_slice().iter().zip(b.as_slice()).enumerate() {
+            if (40..60).contains(&(i / 3 % 100)) {
+                sum += (x - y).abs();
+                count += 1;
+            }
+        }
+        sum / count as f32
+    }
+
+    #[test]
+    fn test_estimate_gains() -> Result<(), ImageError> {
+        let [(image_a, mask_a), (image_b, mask_b)] = views(|_| 0.7)?;
+        let params = ExposureParams::default();
+        let gains = estimate_gains(
+            &[image_a.clone(), image_b.clone()],
+            &[mask_a, mask_b],
+            &params,
+        )?;
+        assert!(gains[0] < 1.0 && gains[1] > 1.0);
+
+        let mut compensated_a = image_a.clone();
+        let mut compensated_b = image_b.clone();
+        apply_gain(&image_a, &mut compensated_a, gains[0])?;
+        apply_gain(&image_b, &mut compensated_b, gains[1])?;
+        let before = overlap_error(&image_a, &image_b);
+        let after = overlap_error(&compensated_a, &compensated_b);
+        assert!(after < 0.5 * before, "{before} {after}");
+
+        // without a prior on the gains the exposures are equalized exactly
+        let params = ExposureParams {
+            gain_sigma: 10.0,
+            ..params
+        };
+        let [(image_a, mask_a), (image_b, mask_b)] = views(|_| 0.7)?;
+        let gains = estimate_gains(&[image_a, image_b], &[mask_a, mask_b], &params)?;
+        assert!((gains[1] * 0.7 / gains[0] - 1.0).abs() < 1e-3, "{gains:?}");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_estimate_block_gains() -> Result<(), ImageError> {
+        // the exposure of the second view falls off towards the overlap
+        let exposure = |x: usize| 0.6 + 0.4 * (x as f32 - 40.0) / 60.0;
+        let [(image_a, mask_a), (image_b, mask_b)] = views(exposure)?;
+        let images = [image_a.clone(), image_b.clone()];
+        let maps = estimate_block_gains(
+            &images,
+            &[mask_a, mask_b],
+            [10, 10],
+            &ExposureParams::default(),
+        )?;
+        assert_eq!(maps[0].size(), [100, 40].into());
+
+        let mut compensated_a = image_a.clone();
+        let mut compensated_b = image_b.clone();
+        apply_gain_map(&image_a, &mut compensated_a, &maps[0])?;
+        apply_gain_map(&image_b, &mut compensated_b, &maps[1])?;
+        let before = overlap_error(&image_a, &image_b);
+        let after = overlap_error(&compensated_a, &compensated_b);
+        assert!(after < 0.5 * before, "{before} {after}");
+
+        // the blocks without overlap keep their exposure
+        assert!((maps[0].as_slice()[0] - 1.0).abs() < 1e-6);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,5 @@
+mod exposure;
+mod seam;
+
+pub use exposure::*;
+pub use seam::*;
@@ -0,0 +1,366 @@
+use kornia_image::{Image, ImageError};
+
+use crate::segmentation::MaxFlow;
+
+// the cost of the seam outside of the overlap, which keeps the seam inside of it
+const OUTSIDE_COST: f32 = 1e6;
+
+// the capacity which ties the overlap to the pixels covered by a single image
+const TERMINAL_CAPACITY: f32 = 1e9;
+
+/// The algorithm which finds the seams between the images.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SeamMethod {
+    /// The path of minimum cost across the overlap, found with the dynamic programming.
+    DynamicProgramming,
+    /// The minimum cut of the graph of the overlap, which can follow any shape.
+    GraphCut,
+}
+
+/// Find the seam between two overlapping images.
+///
+/// The images are warped into the same canvas, and their masks mark the pixels that they
+/// cover. The overlap is split between the images along the seam of minimum color
+/// difference, so that after the call every pixel of the canvas belongs to at most one
+/// mask. The masks can then weight the images in the multi-band blending.
+///
+/// # Arguments
+///
+/// * `image_a` - The first image with shape (H, W, C).
+/// * `image_b` - The second image with shape (H, W, C).
+/// * `mask_a` - The mask of the first image with shape (H, W, 1), non zero where it is
+///   valid, updated in place.
+/// * `mask_b` - The mask of the second image with shape (H, W, 1), non zero where it is
+///   valid, updated in place.
+/// * `method` - The algorithm to find the seam.
+///
+/// # Example
+///
+/// ```
+/// use kornia_image::Image;
+/// use kornia_imgproc::stitching::{find_seam, SeamMethod};
+///
+/// let image = Image::<f32, 1>::from_size_val([8, 4].into(), 0.5).unwrap();
+/// let mut mask_a = Image::<u8, 1>::new([8, 4].into(), vec![255, 255, 255, 255, 255, 0, 0, 0].repeat(4)).unwrap();
+/// let mut mask_b = Image::<u8, 1>::new([8, 4].into(), vec![0, 0, 0, 255, 255, 255, 255, 255].repeat(4)).unwrap();
+///
+/// find_seam(&image, &image, &mut mask_a, &mut mask_b, SeamMethod::DynamicProgramming).unwrap();
+/// assert!(mask_a.as_slice().iter().zip(mask_b.as_slice()).all(|(&a, &b)| a == 0 || b == 0));
+/// ```
+pub fn find_seam<const C: usize>(
+    image_a: &Image<f32, C>,
+    image_b: &Image<f32, C>,
+    mask_a: &mut Image<u8, 1>,
+    mask_b: &mut Image<u8, 1>,
+    method: SeamMethod,
+) -> Result<(), ImageError> {
+    let size = image_a.size();
+    for other in [image_b.size(), mask_a.size(), mask_b.size()] {
+        if other != size {
+            return Err(ImageError::InvalidImageSize(
+                size.width,
+                size.height,
+                other.width,
+                other.height,
+            ));
+        }
+    }
+
+    let overlap = mask_a
+        .as_slice()
+        .iter()
+        .zip(mask_b.as_slice())
+        .map(|(&a, &b)| a != 0 && b != 0)
+        .collect::<Vec<_>>();
+    if !overlap.contains(&true) {
+        return Ok(());
+    }
+
+    // the color difference of the images
+    let cost = image_a
+        .as_slice()
+        .chunks_exact(C)
+        .zip(image_b.as_slice().chunks_exact(C))
+        .map(|(a, b)| {
+            a.iter()
+                .zip(b)
+                .map(|(a, b)| (a - b).powi(2))
+                .sum::<f32>()
+                .sqrt()
+        })
+        .collect::<Vec<_>>();
+
+    let owner_a = match method {
+        SeamMethod::DynamicProgramming => {
+            seam_dynamic_programming(mask_a, mask_b, &overlap, &cost, size.width)
+        }
+        SeamMethod::GraphCut => seam_graph_cut(mask_a, mask_b, &overlap, &cost, size.width),
+    };
+
+    // give every pixel of the overlap to a single image
+    for (i, &to_a) in owner_a.iter().enumerate() {
+        if overlap[i] {
+            if to_a {
+                mask_b.as_slice_mut()[i] = 0;
+            } else {
+                mask_a.as_slice_mut()[i] = 0;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Find the seams between a set of overlapping images.
+///
+/// The seams are found between every pair of images with [`find_seam`], in order, so that
+/// every pixel of the canvas belongs to at most one mask at the end.
+///
+/// # Arguments
+///
+/// * `images` - The images warped into the same canvas with shape (H, W, C).
+/// * `masks` - The masks of the images with shape (H, W, 1), updated in place.
+/// * `method` - The algorithm to find the seams.
+pub fn find_seams<const C: usize>(
+    images: &[Image<f32, C>],
+    masks: &mut [Image<u8, 1>],
+    method: SeamMethod,
+) -> Result<(), ImageError> {
+    if images.len() != masks.len() {
+        return Err(ImageError::InvalidChannelShape(images.len(), masks.len()));
+    }
+
+    for j in 1..masks.len() {
+        let (head, tail) = masks.split_at_mut(j);
+        for (i, mask_i) in head.iter_mut().enumerate() {
+            find_seam(&images[i], &images[j], mask_i, &mut tail[0], method)?;
+        }
+    }
+
+    Ok(())
+}
+
+// the centroid of the pixels covered only by one of the masks
+fn exclusive_centroid(own: &[u8], other: &[u8], cols: usize) -> Option<[f32; 2]> {
+    let (mut sum, mut count) = ([0.0f32; 2], 0usize);
+    for (i, (&a, &b)) in own.iter().zip(other).enumerate() {
+        if a != 0 && b == 0 {
+            sum[0] += (i % cols) as f32;
+            sum[1] += (i / cols) as f32;
+            count += 1;
+        }
+    }
+    (count > 0).then(|| sum.map(|s| s / count as f32))
+}
+
+// the seam of minimum cost crossing the bounding box of the overlap, returning whether
+// every pixel belongs to the first image
+fn seam_dynamic_programming(
+    mask_a: &Image<u8, 1>,
+    mask_b: &Image<u8, 1>,
+    overlap: &[bool],
+    cost: &[f32],
+    cols: usize,
+) -> Vec<bool> {
+    let (mut x0, mut y0, mut x1, mut y1) = (usize::MAX, usize::MAX, 0, 0);
+    for (i, _) in overlap.iter().enumerate().filter(|(_, &o)| o) {
+        let (x, y) = (i % cols, i / cols);
+        (x0, y0, x1, y1) = (x0.min(x), y0.min(y), x1.max(x), y1.max(y));
+    }
+
+    // the seam runs between the regions covered by a single image
+    let (a, b) = (mask_a.as_slice(), mask_b.as_slice());
+    let centroid_a = exclusive_centroid(a, b, cols).unwrap_or([x0 as f32, y0 as f32]);
+    let centroid_b = exclusive_centroid(b, a, cols).unwrap_or([x1 as f32, y1 as f32]);
+    let (dx, dy) = (centroid_b[0] - centroid_a[0], centroid_b[1] - centroid_a[1]);
+    let vertical = dx.abs() >= dy.abs();
+    let a_first = if vertical { dx >= 0.0 } else { dy >= 0.0 };
+
+    // the coordinates along and across the seam
+    let (len, width) = if vertical {
+        (y1 - y0 + 1, x1 - x0 + 1)
+    } else {
+        (x1 - x0 + 1, y1 - y0 + 1)
+    };
+    let index = |t: usize, s: usize| {
+        if vertical {
+            (y0 + t) * cols + x0 + s
+        } else {
+            (y0 + s) * cols + x0 + t
+        }
+    };
+    let pixel_cost = |t: usize, s: usize| {
+        let i = index(t, s);
+        if overlap[i] {
+            cost[i]
+        } else {
+            OUTSIDE_COST
+        }
+    };
+
+    // the accumulated cost of the best path to every pixel
+    let mut total = vec![0.0f32; len * width];
+    for (s, value) in total[..width].iter_mut().enumerate() {
+        *value = pixel_cost(0, s);
+    }
+    for t in 1..len {
+        for s in 0..width {
+            let best = (s.saturating_sub(1)..(s + 2).min(width))
+                .map(|p| total[(t - 1) * width + p])
+                .fold(f32::MAX, f32::min);
+            total[t * width + s] = best + pixel_cost(t, s);
+        }
+    }
+
+    // backtrack the path from the last row
+    let mut seam = vec![0; len];
+    let last = &total[(len - 1) * width..];
+    seam[len - 1] = (0..width)
+        .min_by(|&p, &q| last[p].total_cmp(&last[q]))
+        .unwrap_or(0);
+    for t in (0..len - 1).rev() {
+        let s = seam[t + 1];
+        seam[t] = (s.saturating_sub(1)..(s + 2).min(width))
+            .min_by(|&p, &q| total[t * width + p].total_cmp(&total[t * width + q]))
+            .unwrap_or(s);
+    }
+
+    let mut owner_a = vec![false; overlap.len()];
+    for (t, &cut) in seam.iter().enumerate() {
+        for s in 0..width {
+            owner_a[index(t, s)] = (s < cut) == a_first;
+        }
+    }
+    owner_a
+}
+
+// the minimum cut of the overlap between the pixels covered only by one of the images,
+// returning whether every pixel belongs to the first image
+fn seam_graph_cut(
+    mask_a: &Image<u8, 1>,
+    mask_b: &Image<u8, 1>,
+    overlap: &[bool],
+    cost: &[f32],
+    cols: usize,
+) -> Vec<bool> {
+    let (a, b) = (mask_a.as_slice(), mask_b.as_slice());
+    let rows = overlap.len() / cols;
+
+    let mut node = vec![usize::MAX; overlap.len()];
+    let mut num_nodes = 0;
+    for (i, _) in overlap.iter().enumerate().filter(|(_, &o)| o) {
+        node[i] = num_nodes;
+        num_nodes += 1;
+    }
+
+    let mut graph = MaxFlow::new(num_nodes);
+    for (i, _) in overlap.iter().enumerate().filter(|(_, &o)| o) {
+        let (x, y) = (i % cols, i / cols);
+        let neighbours = [
+            (x > 0).then(|| i - 1),
+            (x + 1 < cols).then(|| i + 1),
+            (y > 0).then(|| i - cols),
+            (y + 1 < rows).then(|| i + cols),
+        ];
+
+        let (mut source, mut sink) = (0.0, 0.0);
+        for n in neighbours.into_iter().flatten() {
+            if overlap[n] {
+                // every edge is added once, from its first pixel
+                if n > i {
+                    let weight = cost[i] + cost[n] + f32::EPSILON;
+                    graph.add_edge(node[i], node[n], weight, weight);
+                }
+            } else if a[n] != 0 {
+                source = TERMINAL_CAPACITY;
+            } else if b[n] != 0 {
+                sink = TERMINAL_CAPACITY;
+            }
+        }
+        if source > 0.0 || sink > 0.0 {
+            graph.add_terminal_weights(node[i], source, sink);
+        }
+    }
+    graph.solve();
+
+    node.iter()
+        .map(|&n| n != usize::MAX && graph.is_source(n))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // an image warped into the canvas with its mask
+    type Warped = (Image<f32, 3>, Image<u8, 1>);
+
+    // two images overlapping on the columns 40 to 60, with an object in the first one
+    fn overlapping() -> Result<[Warped; 2], ImageError> {
+        let (cols, rows) = (100, 40);
+        let background = |x: usize, y: usize| [x as f32 / 100.0, y as f32 / 40.0, 0.5];
+        let make = |range: std::ops::Range<usize>, object: bool| -> Result<_, ImageError> {
+            let mut image = Vec::with_capacity(cols * rows * 3);
+            let mut mask = Vec::with_capacity(cols * rows);
+            for y in 0..rows {
+                for x in 0..cols {
+                    let inside = object && (42..52).contains(&x) && (10..30).contains(&y);
+                    image.extend(if inside { [1.0; 3] } else { background(x, y) });
+                    mask.push(if range.contains(&x) { 255 } else { 0 });
+                }
+            }
+            Ok((
+                Image::new([cols, rows].into(), image)?,
+                Image::new([cols, rows].into(), mask)?,
+            ))
+        };
+        Ok([make(0..60, true)?, make(40..100, false)?])
+    }
+
+    fn check_seam(method: SeamMethod) -> Result<(), ImageError> {
+        let [(image_a, mut mask_a), (image_b, mut mask_b)] = overlapping()?;
+        find_seam(&image_a, &image_b, &mut mask_a, &mut mask_b, method)?;
+
+        // the masks split the canvas
+        for (i, (&a, &b)) in mask_a.as_slice().iter().zip(mask_b.as_slice()).enumerate() {
+            let x = i % 100;
+            assert!(a == 0 || b == 0);
+            assert_eq!(a != 0 || b != 0, x < 100, "{i}");
+            if x < 40 {
+                assert_ne!(a, 0);
+            } else if x >= 60 {
+                assert_ne!(b, 0);
+            }
+        }
+
+        // the seam does not cut the object
+        let object = (10..30)
+            .flat_map(|y| (42..52).map(move |x| y * 100 + x))
+            .map(|i| mask_a.as_slice()[i] != 0)
+            .collect::<Vec<_>>();
+        assert!(object.iter().all(|&a| a == object[0]), "{method:?}");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_find_seam() -> Result<(), ImageError> {
+        check_seam(SeamMethod::DynamicProgramming)?;
+        check_seam(SeamMethod::GraphCut)
+    }
+
+    #[test]
+    fn test_find_seams() -> Result<(), ImageError> {
+        let [(image_a, mask_a), (image_b, mask_b)] = overlapping()?;
+        let images = [image_a.clone(), image_b, image_a];
+        let mut masks = [mask_a.clone(), mask_b, mask_a];
+        find_seams(&images, &mut masks, SeamMethod::GraphCut)?;
+
+        for i in 0..100 * 40 {
+            let owners = masks.iter().filter(|m| m.as_slice()[i] != 0).count();
+            assert_eq!(owners, 1);
+        }
+
+        Ok(())
+    }
+}
@@ -1,5 +1,8 @@
+use alloc::vec;
+use core::cmp::PartialOrd;
+#[cfg(not(feature = "std"))]
+use num_traits::Float;
 use num_traits::Zero;
-use std::cmp::PartialOrd;
 
 use kornia_image::{Image, ImageError};
 
@@ -367,6 +370,67 @@ where
 
 // TODO: outsu, triangle
 
+/// Compute the optimal global threshold of an image with the method of Otsu.
+///
+/// The threshold maximizes the variance between the two classes of pixels below and
+/// above it, which separates the modes of a bimodal histogram.
+///
+/// # Arguments
+///
+/// * `src` - The input image with shape (H, W, 1).
+///
+/// # Returns
+///
+/// The threshold value, the pixels less or equal are in the first class.
+///
+/// # Example
+///
+/// ```
+/// use kornia_image::{Image, ImageSize};
+/// use kornia_imgproc::threshold::otsu_threshold;
+///
+/// let data = vec![10u8, 20, 15, 200, 210, 205];
+/// let image = Image::<_, 1>::new(ImageSize { width: 3, height: 2 }, data).unwrap();
+///
+/// let threshold = otsu_threshold(&image).unwrap();
+/// assert!((20..200).contains(&threshold));
+/// ```
+pub fn otsu_threshold(src: &Image<u8, 1>) -> Result<u8, ImageError> {
+    let mut histogram = vec![0; 256];
+    crate::histogram::compute_histogram(src, &mut histogram, 256)?;
+
+    let total = src.as_slice().len() as f64;
+    let sum = histogram
+        .iter()
+        .enumerate()
+        .map(|(i, &n)| i as f64 * n as f64)
+        .sum::<f64>();
+
+    // a constant image has a single class
+    let max_value = src.as_slice().iter().copied().max().unwrap_or(0);
+    let mut best = (max_value, -1.0f64);
+    let mut weight_below = 0.0;
+    let mut sum_below = 0.0;
+
+    for (i, &n) in histogram.iter().enumerate() {
+        weight_below += n as f64;
+        sum_below += i as f64 * n as f64;
+        let weight_above = total - weight_below;
+        if weight_below == 0.0 || weight_above == 0.0 {
+            continue;
+        }
+
+        let mean_below = sum_below / weight_below;
+        let mean_above = (sum - sum_below) / weight_above;
+        let variance = weight_below * weight_above * (mean_below - mean_above).powi(2);
+        if variance > best.1 {
+            best = (i as u8, variance);
+        }
+    }
+
+    Ok(best.0)
+}
+
 #[cfg(test)]
 mod tests {
     use kornia_image::{Image, ImageError, ImageSize};
@@ -520,4 +584,24 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_otsu_threshold() -> Result<(), ImageError> {
+        let data = vec![10u8, 12, 14, 100, 102, 104];
+        let image = Image::<_, 1>::new(
+            ImageSize {
+                width: 3,
+                height: 2,
+            },
+            data,
+        )?;
+
+        assert_eq!(super::otsu_threshold(&image)?, 14);
+
+        // a constant image has a single class
+        let image = Image::<u8, 1>::from_size_val(image.size(), 7)?;
+        assert_eq!(super::otsu_threshold(&image)?, 7);
+
+        Ok(())
+    }
 }
@@ -0,0 +1,341 @@
+use crate::parallel::prelude::*;
+use kornia_image::{Image, ImageError, ImageSize};
+
+use crate::crop::crop_image;
+
+/// A tile of a large image.
+///
+/// The regions are given as `[x, y, width, height]` in the pixels of the whole image.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Tile {
+    /// The region which is processed, including the overlap with the neighbouring tiles.
+    pub region: [usize; 4],
+    /// The region of the result which is kept, without the overlap.
+    pub inner: [usize; 4],
+}
+
+impl Tile {
+    /// The size of the processed region.
+    pub fn size(&self) -> ImageSize {
+        [self.region[2], self.region[3]].into()
+    }
+}
+
+/// Process an image by tiles, in parallel and with a bounded memory.
+///
+/// The image is split into a grid of tiles which are processed with an overlap on every
+/// side, and only the inner part of the result of each tile is kept. With an overlap at
+/// least as large as the radius of the operation, e.g. half of the kernel size of a
+/// filter, the result is the same as processing the whole image at once.
+///
+/// # Example
+///
+/// ```
+/// use kornia_image::Image;
+/// use kornia_imgproc::filter::gaussian_blur;
+/// use kornia_imgproc::tiling::TiledProcessor;
+///
+/// let src = Image::<f32, 1>::new([40, 30].into(), (0..1200).map(|v| v as f32).collect()).unwrap();
+/// let mut dst = Image::<f32, 1>::from_size_val(src.size(), 0.0).unwrap();
+///
+/// let processor = TiledProcessor::new([16, 16].into(), 2);
+/// processor
+///     .process(&src, &mut dst, |tile, out| gaussian_blur(tile, out, (5, 5), (1.0, 1.0)))
+///     .unwrap();
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TiledProcessor {
+    tile_size: ImageSize,
+    overlap: usize,
+}
+
+impl TiledProcessor {
+    /// Create a tiled processor.
+    ///
+    /// # Arguments
+    ///
+    /// * `tile_size` - The size of the inner part of the tiles, at least one pixel.
+    /// * `overlap` - The number of pixels added on every side of the tiles.
+    pub fn new(tile_size: ImageSize, overlap: usize) -> Self {
+        Self {
+            tile_size: [tile_size.width.max(1), tile_size.height.max(1)].into(),
+            overlap,
+        }
+    }
+
+    /// The tiles of an image, row by row.
+    pub fn tiles(&self, image_size: ImageSize) -> Vec<Tile> {
+        let (tile_cols, tile_rows) = (self.tile_size.width, self.tile_size.height);
+        let mut tiles = Vec::new();
+
+        for y in (0..image_size.height).step_by(tile_rows) {
+            for x in (0..image_size.width).step_by(tile_cols) {
+                let inner_w = tile_cols.min(image_size.width - x);
+                let inner_h = tile_rows.min(image_size.height - y);
+
+                // the overlap is clipped to the image
+                let x0 = x.saturating_sub(self.overlap);
+                let y0 = y.saturating_sub(self.overlap);
+                let x1 = (x + inner_w + self.overlap).min(image_size.width);
+                let y1 = (y + inner_h + self.overlap).min(image_size.height);
+
+                tiles.push(Tile {
+                    region: [x0, y0, x1 - x0, y1 - y0],
+                    inner: [x, y, inner_w, inner_h],
+                });
+            }
+        }
+
+        tiles
+    }
+
+    /// Process an image in memory by tiles.
+    ///
+    /// # Arguments
+    ///
+    /// * `src` - The source image with shape (H, W, C).
+    /// * `dst` - The destination image with shape (H, W, D).
+    /// * `op` - The operation applied to every tile, writing a result of the same size.
+    pub fn process<T, U, const C: usize, const D: usize, F>(
+        &self,
+        src: &Image<T, C>,
+        dst: &mut Image<U, D>,
+        op: F,
+    ) -> Result<(), ImageError>
+    where
+        T: Copy + Default + Send + Sync,
+        U: Copy + Default + Send + Sync,
+        F: Fn(&Image<T, C>, &mut Image<U, D>) -> Result<(), ImageError> + Sync,
+    {
+        if src.size() != dst.size() {
+            return Err(ImageError::InvalidImageSize(
+                src.cols(),
+                src.rows(),
+                dst.cols(),
+                dst.rows(),
+            ));
+        }
+
+        self.process_streaming(
+            src.size(),
+            |tile| {
+                let mut region = Image::from_size_val(tile.size(), T::default())?;
+                crop_image(src, &mut region, tile.region[0], tile.region[1])?;
+                Ok(region)
+            },
+            op,
+            |tile, result| {
+                copy_inner(tile, result, dst);
+                Ok(())
+            },
+        )
+    }
+
+    /// Process an image which does not fit in memory by tiles.
+    ///
+    /// The tiles are read, processed and written in batches of as many tiles as threads,
+    /// so only a few tiles are in memory at any time. The tiles are processed in parallel
+    /// and written in order.
+    ///
+    /// # Arguments
+    ///
+    /// * `image_size` - The size of the whole image.
+    /// * `read` - The function which reads the region of a tile, e.g. from a file.
+    /// * `op` - The operation applied to every tile, writing a result of the same size.
+    /// * `write` - The function which stores the result of a tile, of which only the inner
+    ///   region is valid.
+    pub fn process_streaming<T, U, const C: usize, const D: usize, R, F, W>(
+        &self,
+        image_size: ImageSize,
+        read: R,
+        op: F,
+        mut write: W,
+    ) -> Result<(), ImageError>
+    where
+        T: Send + Sync,
+        U: Clone + Default + Send + Sync,
+        R: Fn(&Tile) -> Result<Image<T, C>, ImageError> + Sync,
+        F: Fn(&Image<T, C>, &mut Image<U, D>) -> Result<(), ImageError> + Sync,
+        W: FnMut(&Tile, &Image<U, D>) -> Result<(), ImageError>,
+    {
+        let tiles = self.tiles(image_size);
+        let batch_size = current_num_threads().max(1);
+
+        for batch in tiles.chunks(batch_size) {
+            let results = batch
+                .par_iter()
+                .map(|tile| {
+                    let region = read(tile)?;
+                    if region.size() != tile.size() {
+                        return Err(ImageError::InvalidImageSize(
+                            region.cols(),
+                            region.rows(),
+                            tile.region[2],
+                            tile.region[3],
+                        ));
+                    }
+                    let mut result = Image::from_size_val(tile.size(), U::default())?;
+                    op(&region, &mut result)?;
+                    Ok(result)
+                })
+                .collect::<Result<Vec<_>, ImageError>>()?;
+
+            for (tile, result) in batch.iter().zip(&results) {
+                write(tile, result)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Copy the inner region of the result of a tile into the whole image.
+///
+/// # Arguments
+///
+/// * `tile` - The tile of the result.
+/// * `result` - The result of the tile with the size of its region.
+/// * `dst` - The whole image.
+pub fn copy_inner<T: Copy, const C: usize>(
+    tile: &Tile,
+    result: &Image<T, C>,
+    dst: &mut Image<T, C>,
+) {
+    let [x, y, width, height] = tile.inner;
+    let (offset_x, offset_y) = (x - tile.region[0], y - tile.region[1]);
+    let (dst_cols, src_cols) = (dst.cols(), result.cols());
+
+    for row in 0..height {
+        let src_start = ((offset_y + row) * src_cols + offset_x) * C;
+        let dst_start = ((y + row) * dst_cols + x) * C;
+        dst.as_slice_mut()[dst_start..dst_start + width * C]
+            .copy_from_slice(&result.as_slice()[src_start..src_start + width * C]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::filter::gaussian_blur;
+
+    #[test]
+    fn test_tiles() {
+        let tiles = TiledProcessor::new([4, 4].into(), 1).tiles([10, 5].into());
+        assert_eq!(tiles.len(), 6);
+        assert_eq!(
+            tiles[0],
+            Tile {
+                region: [0, 0, 5, 5],
+                inner: [0, 0, 4, 4],
+            }
+        );
+        assert_eq!(
+            tiles[4],
+            Tile {
+                region: [3, 3, 6, 2],
+                inner: [4, 4, 4, 1],
+            }
+        );
+        assert_eq!(
+            tiles[5],
+            Tile {
+                region: [7, 3, 3, 2],
+                inner: [8, 4, 2, 1],
+            }
+        );
+
+        // the inner regions cover the image once
+        let mut covered = [0; 50];
+        for tile in &tiles {
+            let [x, y, w, h] = tile.inner;
+            for row in y..y + h {
+                for col in x..x + w {
+                    covered[row * 10 + col] += 1;
+                }
+            }
+        }
+        assert!(covered.iter().all(|&c| c == 1));
+    }
+
+    #[test]
+    fn test_process() -> Result<(), ImageError> {
+        let size = ImageSize {
+            width: 37,
+            height: 23,
+        };
+        let src = Image::<f32, 1>::new(
+            size,
+            (0..size.width * size.height)
+                .map(|v| ((v * 7919) % 101) as f32)
+                .collect(),
+        )?;
+        let blur = |src: &Image<f32, 1>, dst: &mut Image<f32, 1>| {
+            gaussian_blur(src, dst, (7, 7), (1.5, 1.5))
+        };
+
+        let mut expected = Image::from_size_val(size, 0.0)?;
+        blur(&src, &mut expected)?;
+
+        // the overlap covers the radius of the kernel
+        let mut dst = Image::from_size_val(size, 0.0)?;
+        TiledProcessor::new([8, 5].into(), 3).process(&src, &mut dst, blur)?;
+        assert_eq!(dst.as_slice(), expected.as_slice());
+
+        // without the overlap the borders of the tiles differ
+        TiledProcessor::new([8, 5].into(), 0).process(&src, &mut dst, blur)?;
+        assert_ne!(dst.as_slice(), expected.as_slice());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_process_streaming() -> Result<(), ImageError> {
+        let size = ImageSize {
+            width: 20,
+            height: 10,
+        };
+
+        // the tiles are generated on the fly and written to a sum
+        let mut total = 0u64;
+        let mut written = 0;
+        TiledProcessor::new([6, 6].into(), 2).process_streaming(
+            size,
+            |tile| {
+                let [x0, y0, w, h] = tile.region;
+                Image::<u8, 1>::new(
+                    tile.size(),
+                    (0..w * h)
+                        .map(|i| ((x0 + i % w) + (y0 + i / w)) as u8)
+                        .collect(),
+                )
+            },
+            |src: &Image<u8, 1>, dst: &mut Image<u32, 1>| {
+                dst.as_slice_mut()
+                    .iter_mut()
+                    .zip(src.as_slice())
+                    .for_each(|(d, &s)| *d = s as u32 * 2);
+                Ok(())
+            },
+            |tile, result| {
+                let [x, y, w, h] = tile.inner;
+                let (offset_x, offset_y) = (x - tile.region[0], y - tile.region[1]);
+                for row in offset_y..offset_y + h {
+                    let start = row * result.cols() + offset_x;
+                    total += result.as_slice()[start..start + w]
+                        .iter()
+                        .map(|&v| v as u64)
+                        .sum::<u64>();
+                }
+                written += w * h;
+                Ok(())
+            },
+        )?;
+
+        let expected = (0..10)
+            .flat_map(|y| (0..20).map(move |x| 2 * (x + y) as u64))
+            .sum::<u64>();
+        assert_eq!((total, written), (expected, 200));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,657 @@
+use crate::parallel::prelude::*;
+use kornia_image::{Image, ImageError, ImageSize};
+
+/// The value of the foreground pixels in the masks of the background subtractors.
+pub const FOREGROUND_VALUE: u8 = 255;
+
+/// The value of the shadow pixels in the masks of the background subtractors.
+pub const SHADOW_VALUE: u8 = 127;
+
+/// A model of the background of a video which segments the moving objects.
+pub trait BackgroundSubtractor<const C: usize> {
+    /// Update the model with a frame and compute its foreground mask.
+    ///
+    /// # Arguments
+    ///
+    /// * `frame` - The frame with shape (H, W, C).
+    /// * `mask` - The foreground mask with shape (H, W, 1), with [`FOREGROUND_VALUE`] for
+    ///   the foreground, [`SHADOW_VALUE`] for the shadows and 0 for the background.
+    /// * `learning_rate` - The rate between 0 and 1 at which the model adapts, or `None`
+    ///   to derive it from the length of the history.
+    fn apply(
+        &mut self,
+        frame: &Image<u8, C>,
+        mask: &mut Image<u8, 1>,
+        learning_rate: Option<f32>,
+    ) -> Result<(), ImageError>;
+
+    /// Compute the image of the background seen by the model.
+    ///
+    /// # Arguments
+    ///
+    /// * `dst` - The background image with shape (H, W, C).
+    fn background_image(&self, dst: &mut Image<u8, C>) -> Result<(), ImageError>;
+}
+
+/// The parameters of the Gaussian mixture background subtractor.
+#[derive(Debug, Clone)]
+pub struct Mog2Params {
+    /// The number of frames that contribute to the model.
+    pub history: usize,
+    /// The maximum number of Gaussians per pixel.
+    pub max_components: usize,
+    /// The squared Mahalanobis distance below which a pixel matches the background.
+    pub var_threshold: f32,
+    /// The squared Mahalanobis distance below which a pixel updates a Gaussian instead of
+    /// creating a new one.
+    pub var_threshold_gen: f32,
+    /// The fraction of the weights that the background Gaussians account for.
+    pub background_ratio: f32,
+    /// The variance of the new Gaussians.
+    pub var_init: f32,
+    /// The minimum variance of the Gaussians.
+    pub var_min: f32,
+    /// The maximum variance of the Gaussians.
+    pub var_max: f32,
+    /// The weight removed from the Gaussians at every frame to drop the unused ones.
+    pub complexity_reduction: f32,
+    /// Whether to mark the darker versions of the background as shadows.
+    pub detect_shadows: bool,
+    /// The minimum ratio between the brightness of a shadow and of the background.
+    pub shadow_threshold: f32,
+}
+
+impl Default for Mog2Params {
+    fn default() -> Self {
+        Self {
+            history: 500,
+            max_components: 5,
+            var_threshold: 16.0,
+            var_threshold_gen: 9.0,
+            background_ratio: 0.9,
+            var_init: 15.0,
+            var_min: 4.0,
+            var_max: 75.0,
+            complexity_reduction: 0.05,
+            detect_shadows: true,
+            shadow_threshold: 0.5,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Gaussian<const C: usize> {
+    weight: f32,
+    mean: [f32; C],
+    variance: f32,
+}
+
+/// A background subtractor which models every pixel with an adaptive mixture of Gaussians.
+///
+/// The number of Gaussians of every pixel adapts to the scene, as described in "Improved
+/// Adaptive Gaussian Mixture Model for Background Subtraction" by Zivkovic. The pixels
+/// which are not explained by the heaviest Gaussians are the foreground, unless they are
+/// a darker version of the background and the shadows are detected.
+///
+/// # Example
+///
+/// ```
+/// use kornia_image::Image;
+/// use kornia_imgproc::video::{BackgroundSubtractor, BackgroundSubtractorMog2, Mog2Params};
+///
+/// let frame = Image::<u8, 3>::from_size_val([32, 24].into(), 100).unwrap();
+/// let mut mask = Image::<u8, 1>::from_size_val(frame.size(), 0).unwrap();
+///
+/// let mut subtractor = BackgroundSubtractorMog2::new(frame.size(), Mog2Params::default());
+/// for _ in 0..10 {
+///     subtractor.apply(&frame, &mut mask, None).unwrap();
+/// }
+/// assert!(mask.as_slice().iter().all(|&v| v == 0));
+/// ```
+pub struct BackgroundSubtractorMog2<const C: usize> {
+    params: Mog2Params,
+    size: ImageSize,
+    components: Vec<Gaussian<C>>,
+    num_components: Vec<usize>,
+    num_frames: usize,
+}
+
+impl<const C: usize> BackgroundSubtractorMog2<C> {
+    /// Create an empty model for the frames of the given size.
+    ///
+    /// # Arguments
+    ///
+    /// * `size` - The size of the frames.
+    /// * `params` - The parameters of the model.
+    pub fn new(size: ImageSize, params: Mog2Params) -> Self {
+        let num_pixels = size.width * size.height;
+        let empty = Gaussian {
+            weight: 0.0,
+            mean: [0.0; C],
+            variance: 0.0,
+        };
+        Self {
+            components: vec![empty; num_pixels * params.max_components.max(1)],
+            num_components: vec![0; num_pixels],
+            num_frames: 0,
+            params,
+            size,
+        }
+    }
+
+    /// The parameters of the model.
+    pub fn params(&self) -> &Mog2Params {
+        &self.params
+    }
+}
+
+impl<const C: usize> BackgroundSubtractor<C> for BackgroundSubtractorMog2<C> {
+    fn apply(
+        &mut self,
+        frame: &Image<u8, C>,
+        mask: &mut Image<u8, 1>,
+        learning_rate: Option<f32>,
+    ) -> Result<(), ImageError> {
+        check_sizes(self.size, frame, mask)?;
+
+        self.num_frames += 1;
+        let alpha = match learning_rate {
+            Some(rate) if self.num_frames > 1 => rate.clamp(0.0, 1.0),
+            _ => 1.0 / (2 * self.num_frames).min(self.params.history.max(1)) as f32,
+        };
+
+        let params = &self.params;
+        let max_components = params.max_components.max(1);
+        self.components
+            .par_chunks_exact_mut(max_components)
+            .zip(self.num_components.par_iter_mut())
+            .zip(frame.as_slice().par_chunks_exact(C))
+            .zip(mask.as_slice_mut().par_iter_mut())
+            .for_each(|(((components, count), pixel), mask)| {
+                let data = std::array::from_fn(|k| pixel[k] as f32);
+                *mask = update_mixture(params, components, count, &data, alpha);
+            });
+
+        Ok(())
+    }
+
+    fn background_image(&self, dst: &mut Image<u8, C>) -> Result<(), ImageError> {
+        if dst.size() != self.size {
+            return Err(ImageError::InvalidImageSize(
+                dst.cols(),
+                dst.rows(),
+                self.size.width,
+                self.size.height,
+            ));
+        }
+
+        let max_components = self.params.max_components.max(1);
+        dst.as_slice_mut()
+            .par_chunks_exact_mut(C)
+            .zip(self.components.par_chunks_exact(max_components))
+            .zip(self.num_components.par_iter())
+            .for_each(|((pixel, components), &count)| {
+                // the weighted mean of the background Gaussians
+                let mut mean = [0.0; C];
+                let mut total_weight = 0.0;
+                for gaussian in &components[..count] {
+                    for (m, &g) in mean.iter_mut().zip(&gaussian.mean) {
+                        *m += gaussian.weight * g;
+                    }
+                    total_weight += gaussian.weight;
+                    if total_weight > self.params.background_ratio {
+                        break;
+                    }
+                }
+                if total_weight > 0.0 {
+                    for (p, m) in pixel.iter_mut().zip(mean) {
+                        *p = (m / total_weight).round().clamp(0.0, 255.0) as u8;
+                    }
+                }
+            });
+
+        Ok(())
+    }
+}
+
+// update the Gaussians of a pixel, sorted by decreasing weight, and classify the pixel
+fn update_mixture<const C: usize>(
+    params: &Mog2Params,
+    components: &mut [Gaussian<C>],
+    count: &mut usize,
+    data: &[f32; C],
+    alpha: f32,
+) -> u8 {
+    let prune = -alpha * params.complexity_reduction;
+    let mut background = false;
+    let mut fits = false;
+    let mut total_weight = 0.0;
+
+    for m in 0..*count {
+        let mut weight = (1.0 - alpha) * components[m].weight + prune;
+        let mut index = m;
+
+        if !fits {
+            let gaussian = &mut components[m];
+            let diff: [f32; C] = std::array::from_fn(|k| gaussian.mean[k] - data[k]);
+            let dist2 = diff.iter().map(|d| d * d).sum::<f32>();
+
+            // the Gaussians before this one do not explain the background yet
+            if total_weight < params.background_ratio
+                && dist2 < params.var_threshold * gaussian.variance
+            {
+                background = true;
+            }
+
+            if dist2 < params.var_threshold_gen * gaussian.variance {
+                fits = true;
+                weight += alpha;
+                let k = alpha / weight;
+                for (mean, d) in gaussian.mean.iter_mut().zip(diff) {
+                    *mean -= k * d;
+                }
+                gaussian.variance = (gaussian.variance + k * (dist2 - gaussian.variance))
+                    .clamp(params.var_min, params.var_max);
+
+                // keep the Gaussians sorted by weight
+                while index > 0 && weight > components[index - 1].weight {
+                    components.swap(index, index - 1);
+                    index -= 1;
+                }
+            }
+        }
+
+        // drop the Gaussians that are no longer supported
+        if weight < -prune {
+            weight = 0.0;
+        }
+        components[index].weight = weight;
+        total_weight += weight;
+    }
+
+    // remove the dropped Gaussians and normalize the weights
+    let mut kept = 0;
+    for m in 0..*count {
+        if components[m].weight > 0.0 {
+            components[kept] = components[m];
+            components[kept].weight /= total_weight;
+            kept += 1;
+        }
+    }
+    *count = kept;
+
+    if !fits {
+        // a new Gaussian replaces the weakest one when all are used
+        let index = if *count == components.len() {
+            *count - 1
+        } else {
+            *count += 1;
+            *count - 1
+        };
+        let weight = if *count == 1 {
+            1.0
+        } else {
+            components[..*count]
+                .iter_mut()
+                .for_each(|g| g.weight *= 1.0 - alpha);
+            alpha
+        };
+        components[index] = Gaussian {
+            weight,
+            mean: *data,
+            variance: params.var_init,
+        };
+
+        let mut index = index;
+        while index > 0 && weight > components[index - 1].weight {
+            components.swap(index, index - 1);
+            index -= 1;
+        }
+    }
+
+    if background {
+        0
+    } else if params.detect_shadows
+        && is_shadow(
+            components[..*count]
+                .iter()
+                .map(|g| (g.weight, &g.mean, g.variance)),
+            data,
+            params.var_threshold,
+            params.shadow_threshold,
+            params.background_ratio,
+        )
+    {
+        SHADOW_VALUE
+    } else {
+        FOREGROUND_VALUE
+    }
+}
+
+// whether a pixel is a darker version of one of the background colors, which are given as
+// the weight, the mean and the variance in order of importance
+fn is_shadow<'a, const C: usize>(
+    background: impl Iterator<Item = (f32, &'a [f32; C], f32)>,
+    data: &[f32; C],
+    threshold: f32,
+    shadow_threshold: f32,
+    background_ratio: f32,
+) -> bool {
+    let mut total_weight = 0.0;
+    for (weight, mean, variance) in background {
+        let numerator = data.iter().zip(mean).map(|(d, m)| d * m).sum::<f32>();
+        let denominator = mean.iter().map(|m| m * m).sum::<f32>();
+        if denominator == 0.0 {
+            return false;
+        }
+
+        // the brightness ratio between the pixel and the background
+        let a = numerator / denominator;
+        if a <= 1.0 && a >= shadow_threshold {
+            let dist2 = data
+                .iter()
+                .zip(mean)
+                .map(|(d, m)| (a * m - d).powi(2))
+                .sum::<f32>();
+            if dist2 < threshold * variance * a * a {
+                return true;
+            }
+        }
+
+        total_weight += weight;
+        if total_weight > background_ratio {
+            return false;
+        }
+    }
+    false
+}
+
+/// The parameters of the nearest neighbours background subtractor.
+#[derive(Debug, Clone)]
+pub struct KnnParams {
+    /// The number of frames that contribute to the model.
+    pub history: usize,
+    /// The number of samples stored per pixel.
+    pub num_samples: usize,
+    /// The number of close samples that make a pixel part of the background.
+    pub knn_samples: usize,
+    /// The squared distance below which a sample is close to a pixel.
+    pub dist2_threshold: f32,
+    /// Whether to mark the darker versions of the background as shadows.
+    pub detect_shadows: bool,
+    /// The minimum ratio between the brightness of a shadow and of the background.
+    pub shadow_threshold: f32,
+}
+
+impl Default for KnnParams {
+    fn default() -> Self {
+        Self {
+            history: 500,
+            num_samples: 7,
+            knn_samples: 3,
+            dist2_threshold: 400.0,
+            detect_shadows: true,
+            shadow_threshold: 0.5,
+        }
+    }
+}
+
+/// A background subtractor which compares every pixel with samples of its past values.
+///
+/// A pixel is part of the background when enough of its samples are close to it, following
+/// "Efficient adaptive density estimation per image pixel for the task of background
+/// subtraction" by Zivkovic and van der Heijden. The samples are replaced in turn, at the
+/// rate given by the learning rate, so that the objects that stop are absorbed into the
+/// background.
+pub struct BackgroundSubtractorKnn<const C: usize> {
+    params: KnnParams,
+    size: ImageSize,
+    samples: Vec<[f32; C]>,
+    next_sample: usize,
+    num_frames: usize,
+    // the fraction of a sample replacement accumulated over the frames
+    pending: f32,
+}
+
+impl<const C: usize> BackgroundSubtractorKnn<C> {
+    /// Create an empty model for the frames of the given size.
+    ///
+    /// # Arguments
+    ///
+    /// * `size` - The size of the frames.
+    /// * `params` - The parameters of the model.
+    pub fn new(size: ImageSize, params: KnnParams) -> Self {
+        let num_samples = params.num_samples.max(1);
+        Self {
+            samples: vec![[0.0; C]; size.width * size.height * num_samples],
+            next_sample: 0,
+            num_frames: 0,
+            pending: 0.0,
+            params,
+            size,
+        }
+    }
+
+    /// The parameters of the model.
+    pub fn params(&self) -> &KnnParams {
+        &self.params
+    }
+}
+
+impl<const C: usize> BackgroundSubtractor<C> for BackgroundSubtractorKnn<C> {
+    fn apply(
+        &mut self,
+        frame: &Image<u8, C>,
+        mask: &mut Image<u8, 1>,
+        learning_rate: Option<f32>,
+    ) -> Result<(), ImageError> {
+        check_sizes(self.size, frame, mask)?;
+
+        let params = &self.params;
+        let num_samples = params.num_samples.max(1);
+        let pixels = frame.as_slice().par_chunks_exact(C);
+
+        // the first frame fills all the samples
+        if self.num_frames == 0 {
+            self.samples
+                .par_chunks_exact_mut(num_samples)
+                .zip(pixels.clone())
+                .for_each(|(samples, pixel)| {
+                    samples.fill(std::array::from_fn(|k| pixel[k] as f32));
+                });
+        }
+        self.num_frames += 1;
+
+        // replace a sample of every pixel when a full sample has been accumulated
+        let rate = learning_rate.unwrap_or(1.0 / params.history.max(1) as f32);
+        self.pending += rate.clamp(0.0, 1.0) * num_samples as f32;
+        let replace = self.pending >= 1.0;
+        if replace {
+            self.pending -= self.pending.floor();
+        }
+        let next_sample = self.next_sample;
+
+        self.samples
+            .par_chunks_exact_mut(num_samples)
+            .zip(pixels)
+            .zip(mask.as_slice_mut().par_iter_mut())
+            .for_each(|((samples, pixel), mask)| {
+                let data: [f32; C] = std::array::from_fn(|k| pixel[k] as f32);
+                let close = samples
+                    .iter()
+                    .filter(|s| {
+                        s.iter()
+                            .zip(&data)
+                            .map(|(s, d)| (s - d).powi(2))
+                            .sum::<f32>()
+                            < params.dist2_threshold
+                    })
+                    .count();
+
+                *mask = if close >= params.knn_samples {
+                    0
+                } else if params.detect_shadows
+                    && samples
+                        .iter()
+                        .filter(|s| {
+                            // the samples have the same weight and the threshold as variance
+                            is_shadow(
+                                std::iter::once((0.0, *s, 1.0)),
+                                &data,
+                                params.dist2_threshold,
+                                params.shadow_threshold,
+                                1.0,
+                            )
+                        })
+                        .count()
+                        >= params.knn_samples
+                {
+                    SHADOW_VALUE
+                } else {
+                    FOREGROUND_VALUE
+                };
+
+                if replace {
+                    samples[next_sample] = data;
+                }
+            });
+
+        if replace {
+            self.next_sample = (self.next_sample + 1) % num_samples;
+        }
+
+        Ok(())
+    }
+
+    fn background_image(&self, dst: &mut Image<u8, C>) -> Result<(), ImageError> {
+        if dst.size() != self.size {
+            return Err(ImageError::InvalidImageSize(
+                dst.cols(),
+                dst.rows(),
+                self.size.width,
+                self.size.height,
+            ));
+        }
+
+        // the sample with the most close samples
+        let num_samples = self.params.num_samples.max(1);
+        let threshold = self.params.dist2_threshold;
+        dst.as_slice_mut()
+            .par_chunks_exact_mut(C)
+            .zip(self.samples.par_chunks_exact(num_samples))
+            .for_each(|(pixel, samples)| {
+                let best = samples.iter().max_by_key(|a| {
+                    samples
+                        .iter()
+                        .filter(|b| {
+                            a.iter().zip(*b).map(|(a, b)| (a - b).powi(2)).sum::<f32>() < threshold
+                        })
+                        .count()
+                });
+                if let Some(best) = best {
+                    for (p, &v) in pixel.iter_mut().zip(best) {
+                        *p = v.round().clamp(0.0, 255.0) as u8;
+                    }
+                }
+            });
+
+        Ok(())
+    }
+}
+
+fn check_sizes<const C: usize>(
+    size: ImageSize,
+    frame: &Image<u8, C>,
+    mask: &Image<u8, 1>,
+) -> Result<(), ImageError> {
+    for (cols, rows) in [(frame.cols(), frame.rows()), (mask.cols(), mask.rows())] {
+        if cols != size.width || rows != size.height {
+            return Err(ImageError::InvalidImageSize(
+                cols,
+                rows,
+                size.width,
+                size.height,
+            ));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const COLS: usize = 32;
+    const ROWS: usize = 24;
+
+    // a noisy static background with an optional square scaled in brightness
+    fn frame(t: usize, square: Option<f32>) -> Result<Image<u8, 3>, ImageError> {
+        let mut data = Vec::with_capacity(COLS * ROWS * 3);
+        for y in 0..ROWS {
+            for x in 0..COLS {
+                let noise = ((x * 7 + y * 13 + t * 31) % 5) as f32;
+                let base = [120.0 + noise, 90.0 + x as f32, 60.0 + noise];
+                let inside = (8..16).contains(&x) && (8..16).contains(&y);
+                let scale = square.filter(|_| inside).unwrap_or(1.0);
+                data.extend(base.map(|v| (v * scale).min(255.0) as u8));
+            }
+        }
+        Image::new([COLS, ROWS].into(), data)
+    }
+
+    fn check_subtractor(mut subtractor: impl BackgroundSubtractor<3>) -> Result<(), ImageError> {
+        let mut mask = Image::from_size_val([COLS, ROWS].into(), 0)?;
+        for t in 0..50 {
+            subtractor.apply(&frame(t, None)?, &mut mask, None)?;
+        }
+        assert!(mask.as_slice().iter().all(|&v| v == 0));
+
+        // a bright object and a shadow in the square
+        for (scale, expected) in [(2.0, FOREGROUND_VALUE), (0.7, SHADOW_VALUE)] {
+            subtractor.apply(&frame(50, Some(scale))?, &mut mask, None)?;
+            for (i, &v) in mask.as_slice().iter().enumerate() {
+                let (x, y) = (i % COLS, i / COLS);
+                let inside = (8..16).contains(&x) && (8..16).contains(&y);
+                assert_eq!(v, if inside { expected } else { 0 }, "{x} {y}");
+            }
+        }
+
+        let mut background = Image::from_size_val([COLS, ROWS].into(), 0)?;
+        subtractor.background_image(&mut background)?;
+        let expected = frame(0, None)?;
+        for (&a, &b) in background.as_slice().iter().zip(expected.as_slice()) {
+            assert!(a.abs_diff(b) <= 5);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_background_subtractor_mog2() -> Result<(), ImageError> {
+        let size = [COLS, ROWS].into();
+        check_subtractor(BackgroundSubtractorMog2::new(size, Mog2Params::default()))
+    }
+
+    #[test]
+    fn test_background_subtractor_knn() -> Result<(), ImageError> {
+        let size = [COLS, ROWS].into();
+        check_subtractor(BackgroundSubtractorKnn::new(size, KnnParams::default()))
+    }
+
+    #[test]
+    fn test_background_subtractor_absorbs() -> Result<(), ImageError> {
+        // an object that stops becomes background with a fast learning rate
+        let size = [COLS, ROWS].into();
+        let mut subtractor = BackgroundSubtractorMog2::new(size, Mog2Params::default());
+        let mut mask = Image::from_size_val(size, 0)?;
+        for t in 0..20 {
+            subtractor.apply(&frame(t, None)?, &mut mask, None)?;
+        }
+        for t in 20..60 {
+            subtractor.apply(&frame(t, Some(2.0))?, &mut mask, Some(0.1))?;
+        }
+        assert!(mask.as_slice().iter().all(|&v| v == 0));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,298 @@
+use kornia_image::{Image, ImageError};
+
+// the margin around the window where the object can grow
+const GROW_MARGIN: usize = 10;
+
+/// The termination criteria of the mean shift iterations.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MeanShiftCriteria {
+    /// The maximum number of iterations.
+    pub max_iterations: usize,
+    /// The shift of the window in pixels below which the iterations stop.
+    pub epsilon: f32,
+}
+
+impl Default for MeanShiftCriteria {
+    fn default() -> Self {
+        Self {
+            max_iterations: 10,
+            epsilon: 1.0,
+        }
+    }
+}
+
+/// A rectangle rotated around its center.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RotatedRect {
+    /// The center as [x, y] in pixels.
+    pub center: [f32; 2],
+    /// The length of the sides as [length, width] in pixels, the length along the angle.
+    pub size: [f32; 2],
+    /// The angle of the length side in degrees, from the x axis towards the y axis.
+    pub angle: f32,
+}
+
+/// Move a window to the peak of a probability image with the mean shift.
+///
+/// The window moves to the centroid of the probabilities that it covers until it stops,
+/// which finds the nearest mode of the probabilities.
+///
+/// # Arguments
+///
+/// * `prob` - The probability image with shape (H, W, 1), such as a histogram
+///   back-projection.
+/// * `window` - The initial window as [x, y, width, height] in pixels.
+/// * `criteria` - The termination criteria.
+///
+/// # Returns
+///
+/// The final window and the number of iterations.
+///
+/// # Errors
+///
+/// Returns an error if the window is empty or outside of the image.
+pub fn mean_shift(
+    prob: &Image<u8, 1>,
+    window: [usize; 4],
+    criteria: &MeanShiftCriteria,
+) -> Result<([usize; 4], usize), ImageError> {
+    profile_scope!("mean_shift");
+    check_window(prob, window)?;
+
+    let [mut x, mut y, w, h] = window;
+    for iteration in 0..criteria.max_iterations {
+        let moments = Moments::compute(prob, [x, y, w, h]);
+        if moments.m00 == 0.0 {
+            return Ok(([x, y, w, h], iteration));
+        }
+
+        let dx = (moments.m10 / moments.m00 + 0.5 - 0.5 * w as f64).round() as isize;
+        let dy = (moments.m01 / moments.m00 + 0.5 - 0.5 * h as f64).round() as isize;
+        let new_x = (x as isize + dx).clamp(0, (prob.cols() - w) as isize) as usize;
+        let new_y = (y as isize + dy).clamp(0, (prob.rows() - h) as isize) as usize;
+
+        let shift = new_x.abs_diff(x) + new_y.abs_diff(y);
+        (x, y) = (new_x, new_y);
+        #[cfg(feature = "tracing")]
+        tracing::trace!(iteration, x, y, shift, "mean_shift iteration");
+        if (shift as f32) < criteria.epsilon {
+            return Ok(([x, y, w, h], iteration + 1));
+        }
+    }
+
+    Ok(([x, y, w, h], criteria.max_iterations))
+}
+
+/// Track an object on a probability image with the continuously adaptive mean shift.
+///
+/// The window is first moved with the [`mean_shift`], and then resized and oriented with
+/// the second order moments of the probabilities around it, so that the tracking follows
+/// the changes of scale and rotation of the object, as described in "Computer Vision Face
+/// Tracking For Use in a Perceptual User Interface" by Bradski.
+///
+/// # Arguments
+///
+/// * `prob` - The probability image with shape (H, W, 1), such as a histogram
+///   back-projection.
+/// * `window` - The window of the object in the previous frame as [x, y, width, height].
+/// * `criteria` - The termination criteria of the mean shift.
+///
+/// # Returns
+///
+/// The oriented box of the object and the window to search in the next frame.
+///
+/// # Example
+///
+/// ```
+/// use kornia_image::Image;
+/// use kornia_imgproc::video::{cam_shift, MeanShiftCriteria};
+///
+/// // a square of probabilities
+/// let mut prob = Image::<u8, 1>::from_size_val([40, 40].into(), 0).unwrap();
+/// for y in 20..30 {
+///     for x in 22..32 {
+///         prob.as_slice_mut()[y * 40 + x] = 255;
+///     }
+/// }
+///
+/// let (object, _) = cam_shift(&prob, [14, 14, 10, 10], &MeanShiftCriteria::default()).unwrap();
+/// assert!((object.center[0] - 26.5).abs() < 1.0 && (object.center[1] - 24.5).abs() < 1.0);
+/// ```
+pub fn cam_shift(
+    prob: &Image<u8, 1>,
+    window: [usize; 4],
+    criteria: &MeanShiftCriteria,
+) -> Result<(RotatedRect, [usize; 4]), ImageError> {
+    profile_scope!("cam_shift");
+    let (window, _) = mean_shift(prob, window, criteria)?;
+    let [x, y, w, h] = window;
+
+    // the moments of the window grown by a margin
+    let x0 = x.saturating_sub(GROW_MARGIN);
+    let y0 = y.saturating_sub(GROW_MARGIN);
+    let x1 = (x + w + GROW_MARGIN).min(prob.cols());
+    let y1 = (y + h + GROW_MARGIN).min(prob.rows());
+    let moments = Moments::compute(prob, [x0, y0, x1 - x0, y1 - y0]);
+
+    if moments.m00 == 0.0 {
+        let center = [x as f32 + 0.5 * w as f32, y as f32 + 0.5 * h as f32];
+        return Ok((
+            RotatedRect {
+                center,
+                size: [0.0, 0.0],
+                angle: 0.0,
+            },
+            window,
+        ));
+    }
+
+    let (xc, yc) = (moments.m10 / moments.m00, moments.m01 / moments.m00);
+    let a = moments.m20 / moments.m00 - xc * xc;
+    let b = moments.m11 / moments.m00 - xc * yc;
+    let c = moments.m02 / moments.m00 - yc * yc;
+
+    // the orientation and the axes of the ellipse of the second order moments
+    let square = (4.0 * b * b + (a - c) * (a - c)).sqrt();
+    let theta = (2.0 * b).atan2(a - c + square);
+    let (sn, cs) = theta.sin_cos();
+    let rotate_a = cs * cs * a + 2.0 * cs * sn * b + sn * sn * c;
+    let rotate_c = sn * sn * a - 2.0 * cs * sn * b + cs * cs * c;
+    let length = 4.0 * rotate_a.max(0.0).sqrt();
+    let width = 4.0 * rotate_c.max(0.0).sqrt();
+
+    // the window which contains the box
+    let new_w = ((length * cs).abs().max((width * sn).abs()).round() as usize + 2)
+        .min(prob.cols())
+        .max(1);
+    let new_h = ((length * sn).abs().max((width * cs).abs()).round() as usize + 2)
+        .min(prob.rows())
+        .max(1);
+    let new_x = ((x0 as f64 + xc + 0.5 - 0.5 * new_w as f64).round().max(0.0) as usize)
+        .min(prob.cols() - new_w);
+    let new_y = ((y0 as f64 + yc + 0.5 - 0.5 * new_h as f64).round().max(0.0) as usize)
+        .min(prob.rows() - new_h);
+
+    let object = RotatedRect {
+        center: [(x0 as f64 + xc + 0.5) as f32, (y0 as f64 + yc + 0.5) as f32],
+        size: [length as f32, width as f32],
+        angle: theta.to_degrees() as f32,
+    };
+
+    Ok((object, [new_x, new_y, new_w, new_h]))
+}
+
+// the raw moments of a window relative to its corner
+struct Moments {
+    m00: f64,
+    m10: f64,
+    m01: f64,
+    m20: f64,
+    m11: f64,
+    m02: f64,
+}
+
+impl Moments {
+    fn compute(prob: &Image<u8, 1>, [x, y, w, h]: [usize; 4]) -> Self {
+        let mut moments = Moments {
+            m00: 0.0,
+            m10: 0.0,
+            m01: 0.0,
+            m20: 0.0,
+            m11: 0.0,
+            m02: 0.0,
+        };
+        for (j, row) in prob
+            .as_slice()
+            .chunks_exact(prob.cols())
+            .skip(y)
+            .take(h)
+            .enumerate()
+        {
+            for (i, &p) in row[x..x + w].iter().enumerate() {
+                let (p, i, j) = (p as f64, i as f64, j as f64);
+                moments.m00 += p;
+                moments.m10 += p * i;
+                moments.m01 += p * j;
+                moments.m20 += p * i * i;
+                moments.m11 += p * i * j;
+                moments.m02 += p * j * j;
+            }
+        }
+        moments
+    }
+}
+
+fn check_window(prob: &Image<u8, 1>, [x, y, w, h]: [usize; 4]) -> Result<(), ImageError> {
+    if w == 0 || h == 0 || x + w > prob.cols() || y + h > prob.rows() {
+        return Err(ImageError::InvalidImageSize(
+            x + w,
+            y + h,
+            prob.cols(),
+            prob.rows(),
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // an ellipse of probabilities rotated by the angle
+    fn ellipse(center: [f32; 2], axes: [f32; 2], angle: f32) -> Result<Image<u8, 1>, ImageError> {
+        let (cols, rows) = (100, 80);
+        let (sn, cs) = angle.to_radians().sin_cos();
+        Image::new(
+            [cols, rows].into(),
+            (0..cols * rows)
+                .map(|i| {
+                    let dx = (i % cols) as f32 + 0.5 - center[0];
+                    let dy = (i / cols) as f32 + 0.5 - center[1];
+                    let (u, v) = (dx * cs + dy * sn, -dx * sn + dy * cs);
+                    if (u / axes[0]).powi(2) + (v / axes[1]).powi(2) <= 1.0 {
+                        200
+                    } else {
+                        0
+                    }
+                })
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn test_mean_shift() -> Result<(), ImageError> {
+        let prob = ellipse([60.0, 40.0], [8.0, 8.0], 0.0)?;
+        let (window, iterations) = mean_shift(&prob, [45, 30, 16, 16], &Default::default())?;
+        assert_eq!(window, [52, 32, 16, 16]);
+        assert!(iterations > 1);
+
+        assert!(mean_shift(&prob, [95, 30, 10, 10], &Default::default()).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_cam_shift() -> Result<(), ImageError> {
+        // a long ellipse larger than the window
+        let prob = ellipse([50.0, 40.0], [24.0, 8.0], 30.0)?;
+        let (mut object, mut window) = cam_shift(&prob, [38, 30, 12, 12], &Default::default())?;
+
+        // the window adapts to the object in a few frames
+        for _ in 0..3 {
+            (object, window) = cam_shift(&prob, window, &Default::default())?;
+        }
+
+        assert!((object.center[0] - 50.0).abs() < 0.5);
+        assert!((object.center[1] - 40.0).abs() < 0.5);
+        assert!((object.angle - 30.0).abs() < 2.0, "{object:?}");
+
+        // the axes of a uniform ellipse are twice its standard deviations
+        assert!((object.size[0] - 48.0).abs() < 2.0, "{object:?}");
+        assert!((object.size[1] - 16.0).abs() < 2.0, "{object:?}");
+        assert!(window[2] > 40 && window[3] > 25);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,11 @@
+mod background;
+pub use background::*;
+
+mod camshift;
+pub use camshift::*;
+
+mod motion;
+pub use motion::*;
+
+mod optical_flow;
+pub use optical_flow::*;
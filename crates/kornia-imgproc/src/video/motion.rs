@@ -0,0 +1,538 @@
+use std::collections::VecDeque;
+
+use crate::parallel::prelude::*;
+use kornia_image::{Image, ImageError};
+
+use crate::filter::{kernels::GradsMode, spatial_gradient_float};
+
+/// A connected region of recent motion found by [`segment_motion`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct MotionRegion {
+    /// The bounding box as [x, y, width, height] in pixels.
+    pub bbox: [usize; 4],
+    /// The number of pixels of the region.
+    pub area: usize,
+}
+
+/// Update a motion history image with a silhouette.
+///
+/// The pixels of the silhouette take the timestamp, and the motion older than the duration
+/// is cleared, so that the history fades the trail of the moving objects.
+///
+/// # Arguments
+///
+/// * `silhouette` - The mask of the motion with shape (H, W, 1), non zero where moving.
+/// * `mhi` - The motion history image with shape (H, W, 1), updated in place.
+/// * `timestamp` - The positive time of the silhouette, in arbitrary units.
+/// * `duration` - The duration of the history, in the units of the timestamp.
+///
+/// # Example
+///
+/// ```
+/// use kornia_image::Image;
+/// use kornia_imgproc::video::update_motion_history;
+///
+/// let silhouette = Image::<u8, 1>::new([2, 1].into(), vec![255, 0]).unwrap();
+/// let mut mhi = Image::<f32, 1>::new([2, 1].into(), vec![0.0, 0.5]).unwrap();
+///
+/// update_motion_history(&silhouette, &mut mhi, 2.0, 1.0).unwrap();
+/// assert_eq!(mhi.as_slice(), &[2.0, 0.0]);
+/// ```
+pub fn update_motion_history(
+    silhouette: &Image<u8, 1>,
+    mhi: &mut Image<f32, 1>,
+    timestamp: f32,
+    duration: f32,
+) -> Result<(), ImageError> {
+    if silhouette.size() != mhi.size() {
+        return Err(ImageError::InvalidImageSize(
+            silhouette.cols(),
+            silhouette.rows(),
+            mhi.cols(),
+            mhi.rows(),
+        ));
+    }
+
+    mhi.as_slice_mut()
+        .par_iter_mut()
+        .zip(silhouette.as_slice().par_iter())
+        .for_each(|(value, &moving)| {
+            if moving != 0 {
+                *value = timestamp;
+            } else if *value < timestamp - duration {
+                *value = 0.0;
+            }
+        });
+
+    Ok(())
+}
+
+/// Compute the direction of the motion from a motion history image.
+///
+/// The gradient of the history points from the older to the newer positions of the
+/// objects. It is only valid where the timestamps of the 3x3 neighbourhood differ by an
+/// amount between the deltas, which excludes the borders of the motion and the still
+/// regions.
+///
+/// # Arguments
+///
+/// * `mhi` - The motion history image with shape (H, W, 1).
+/// * `mask` - The mask of the valid orientations with shape (H, W, 1), 255 where valid.
+/// * `orientation` - The direction of the motion with shape (H, W, 1), in degrees between
+///   0 and 360 measured from the x axis towards the y axis.
+/// * `min_delta` - The minimum difference of the timestamps in a neighbourhood.
+/// * `max_delta` - The maximum difference of the timestamps in a neighbourhood.
+pub fn motion_gradient(
+    mhi: &Image<f32, 1>,
+    mask: &mut Image<u8, 1>,
+    orientation: &mut Image<f32, 1>,
+    min_delta: f32,
+    max_delta: f32,
+) -> Result<(), ImageError> {
+    for (cols, rows) in [
+        (mask.cols(), mask.rows()),
+        (orientation.cols(), orientation.rows()),
+    ] {
+        if cols != mhi.cols() || rows != mhi.rows() {
+            return Err(ImageError::InvalidImageSize(
+                mhi.cols(),
+                mhi.rows(),
+                cols,
+                rows,
+            ));
+        }
+    }
+
+    let mut dx = Image::from_size_val(mhi.size(), 0.0)?;
+    let mut dy = Image::from_size_val(mhi.size(), 0.0)?;
+    spatial_gradient_float(mhi, &mut dx, &mut dy, GradsMode::Sobel)?;
+
+    let (cols, rows) = (mhi.cols(), mhi.rows());
+    let data = mhi.as_slice();
+
+    mask.as_slice_mut()
+        .par_iter_mut()
+        .zip(orientation.as_slice_mut().par_iter_mut())
+        .enumerate()
+        .for_each(|(i, (valid, angle))| {
+            let (x, y) = (i % cols, i / cols);
+            let (mut min, mut max) = (f32::MAX, f32::MIN);
+            for ny in y.saturating_sub(1)..(y + 2).min(rows) {
+                for nx in x.saturating_sub(1)..(x + 2).min(cols) {
+                    min = min.min(data[ny * cols + nx]);
+                    max = max.max(data[ny * cols + nx]);
+                }
+            }
+
+            let (gx, gy) = (dx.as_slice()[i], dy.as_slice()[i]);
+            let delta = max - min;
+            if data[i] > 0.0 && delta >= min_delta && delta <= max_delta && (gx != 0.0 || gy != 0.0)
+            {
+                *valid = 255;
+                *angle = gy.atan2(gx).to_degrees().rem_euclid(360.0);
+            } else {
+                *valid = 0;
+                *angle = 0.0;
+            }
+        });
+
+    Ok(())
+}
+
+/// Compute the dominant direction of the recent motion in a region.
+///
+/// The orientations are averaged on the circle, with weights that decrease linearly with
+/// the age of the motion.
+///
+/// # Arguments
+///
+/// * `orientation` - The direction of the motion from [`motion_gradient`].
+/// * `mask` - The mask of the pixels to consider, such as the valid orientations
+///   restricted to a region.
+/// * `mhi` - The motion history image with shape (H, W, 1).
+/// * `timestamp` - The time of the last update of the history.
+/// * `duration` - The duration of the history.
+///
+/// # Returns
+///
+/// The direction in degrees between 0 and 360, or `None` without recent motion.
+pub fn global_motion_orientation(
+    orientation: &Image<f32, 1>,
+    mask: &Image<u8, 1>,
+    mhi: &Image<f32, 1>,
+    timestamp: f32,
+    duration: f32,
+) -> Result<Option<f32>, ImageError> {
+    for (cols, rows) in [(mask.cols(), mask.rows()), (mhi.cols(), mhi.rows())] {
+        if cols != orientation.cols() || rows != orientation.rows() {
+            return Err(ImageError::InvalidImageSize(
+                orientation.cols(),
+                orientation.rows(),
+                cols,
+                rows,
+            ));
+        }
+    }
+
+    let (mut sum_cos, mut sum_sin) = (0.0f64, 0.0f64);
+    for ((&angle, &valid), &time) in orientation
+        .as_slice()
+        .iter()
+        .zip(mask.as_slice())
+        .zip(mhi.as_slice())
+    {
+        let weight = (time - (timestamp - duration)) / duration;
+        if valid != 0 && time > 0.0 && weight > 0.0 {
+            let (sin, cos) = (angle.to_radians() as f64).sin_cos();
+            sum_cos += weight as f64 * cos;
+            sum_sin += weight as f64 * sin;
+        }
+    }
+
+    if sum_cos == 0.0 && sum_sin == 0.0 {
+        return Ok(None);
+    }
+
+    Ok(Some(
+        (sum_sin.atan2(sum_cos).to_degrees() as f32).rem_euclid(360.0),
+    ))
+}
+
+/// Segment a motion history image into the regions of the current motion.
+///
+/// Every region grows from the pixels of the last silhouette into the 4-connected history,
+/// as long as the timestamps of the neighbouring pixels differ by less than the threshold,
+/// so that the objects moving separately get separate regions.
+///
+/// # Arguments
+///
+/// * `mhi` - The motion history image with shape (H, W, 1).
+/// * `labels` - The labels of the regions with shape (H, W, 1), from 1 in the order of the
+///   regions and 0 outside of them.
+/// * `timestamp` - The time of the last update of the history.
+/// * `segment_threshold` - The maximum difference of the timestamps inside a region,
+///   usually larger than the interval between the silhouettes.
+///
+/// # Returns
+///
+/// The regions in the raster order of their first pixel.
+pub fn segment_motion(
+    mhi: &Image<f32, 1>,
+    labels: &mut Image<u32, 1>,
+    timestamp: f32,
+    segment_threshold: f32,
+) -> Result<Vec<MotionRegion>, ImageError> {
+    if mhi.size() != labels.size() {
+        return Err(ImageError::InvalidImageSize(
+            mhi.cols(),
+            mhi.rows(),
+            labels.cols(),
+            labels.rows(),
+        ));
+    }
+
+    let (cols, rows) = (mhi.cols(), mhi.rows());
+    let data = mhi.as_slice();
+    let out = labels.as_slice_mut();
+    out.fill(0);
+
+    let mut regions = Vec::new();
+    let mut queue = VecDeque::new();
+    for seed in 0..data.len() {
+        if out[seed] != 0 || data[seed] != timestamp {
+            continue;
+        }
+
+        let label = regions.len() as u32 + 1;
+        let (mut min_x, mut min_y, mut max_x, mut max_y) = (cols, rows, 0, 0);
+        let mut area = 0;
+        out[seed] = label;
+        queue.push_back(seed);
+
+        while let Some(i) = queue.pop_front() {
+            let (x, y) = (i % cols, i / cols);
+            (min_x, min_y) = (min_x.min(x), min_y.min(y));
+            (max_x, max_y) = (max_x.max(x), max_y.max(y));
+            area += 1;
+
+            let neighbours = [
+                (x > 0).then(|| i - 1),
+                (x + 1 < cols).then(|| i + 1),
+                (y > 0).then(|| i - cols),
+                (y + 1 < rows).then(|| i + cols),
+            ];
+            for n in neighbours.into_iter().flatten() {
+                if out[n] == 0 && data[n] > 0.0 && (data[n] - data[i]).abs() < segment_threshold {
+                    out[n] = label;
+                    queue.push_back(n);
+                }
+            }
+        }
+
+        regions.push(MotionRegion {
+            bbox: [min_x, min_y, max_x - min_x + 1, max_y - min_y + 1],
+            area,
+        });
+    }
+
+    Ok(regions)
+}
+
+/// Fit the global affine motion to point correspondences, such as tracked features.
+///
+/// The motion is fitted with the least squares, and refitted twice without the points
+/// whose residual is larger than three times the median residual, so that a minority of
+/// independently moving points does not bias the motion of the scene.
+///
+/// # Arguments
+///
+/// * `matches` - The pairs of points as ([x, y], [x', y']) before and after the motion.
+///
+/// # Returns
+///
+/// The 2x3 affine matrix in row-major order, mapping the first points to the second ones.
+///
+/// # Errors
+///
+/// Returns an error when the points are collinear or fewer than three.
+///
+/// # Example
+///
+/// ```
+/// use kornia_imgproc::video::fit_affine_motion;
+///
+/// let matches = [
+///     ([0.0, 0.0], [1.0, 2.0]),
+///     ([1.0, 0.0], [2.0, 2.0]),
+///     ([0.0, 1.0], [1.0, 3.0]),
+/// ];
+///
+/// let m = fit_affine_motion(&matches).unwrap();
+/// assert!((m[2] - 1.0).abs() < 1e-5 && (m[5] - 2.0).abs() < 1e-5);
+/// ```
+pub fn fit_affine_motion(matches: &[([f32; 2], [f32; 2])]) -> Result<[f32; 6], ImageError> {
+    let mut inliers = matches.to_vec();
+    let mut m = least_squares_affine(&inliers)?;
+
+    for _ in 0..2 {
+        let residual = |(p, q): &([f32; 2], [f32; 2])| {
+            let x = m[0] * p[0] + m[1] * p[1] + m[2] - q[0];
+            let y = m[3] * p[0] + m[4] * p[1] + m[5] - q[1];
+            x.hypot(y)
+        };
+        let mut residuals = inliers.iter().map(residual).collect::<Vec<_>>();
+        residuals.sort_by(|a, b| a.total_cmp(b));
+        let threshold = 3.0 * residuals[residuals.len() / 2] + 1e-3;
+
+        let kept = inliers
+            .iter()
+            .filter(|pair| residual(pair) <= threshold)
+            .copied()
+            .collect::<Vec<_>>();
+        if kept.len() == inliers.len() {
+            break;
+        }
+        match least_squares_affine(&kept) {
+            Ok(refit) => (inliers, m) = (kept, refit),
+            Err(_) => break,
+        }
+    }
+
+    Ok(m)
+}
+
+/// Estimate the global affine motion from a dense optical flow.
+///
+/// # Arguments
+///
+/// * `flow` - The flow with shape (H, W, 2), the displacement (u, v) of every pixel.
+/// * `mask` - The optional mask with shape (H, W, 1) of the pixels to use, non zero where
+///   valid.
+///
+/// # Returns
+///
+/// The 2x3 affine matrix in row-major order, fitted as in [`fit_affine_motion`] to the
+/// pixels with a finite flow.
+pub fn global_affine_motion(
+    flow: &Image<f32, 2>,
+    mask: Option<&Image<u8, 1>>,
+) -> Result<[f32; 6], ImageError> {
+    if let Some(mask) = mask {
+        if mask.size() != flow.size() {
+            return Err(ImageError::InvalidImageSize(
+                flow.cols(),
+                flow.rows(),
+                mask.cols(),
+                mask.rows(),
+            ));
+        }
+    }
+
+    let cols = flow.cols();
+    let matches = flow
+        .as_slice()
+        .chunks_exact(2)
+        .enumerate()
+        .filter(|&(i, uv)| {
+            uv.iter().all(|v| v.is_finite()) && mask.map_or(true, |m| m.as_slice()[i] != 0)
+        })
+        .map(|(i, uv)| {
+            let (x, y) = ((i % cols) as f32, (i / cols) as f32);
+            ([x, y], [x + uv[0], y + uv[1]])
+        })
+        .collect::<Vec<_>>();
+
+    fit_affine_motion(&matches)
+}
+
+// the least squares affine motion in coordinates centered on the first points
+fn least_squares_affine(matches: &[([f32; 2], [f32; 2])]) -> Result<[f32; 6], ImageError> {
+    if matches.len() < 3 {
+        return Err(ImageError::CannotComputeDeterminant);
+    }
+
+    let n = matches.len() as f64;
+    let mut mean = [0.0f64; 4];
+    for (p, q) in matches {
+        for (m, v) in mean.iter_mut().zip([p[0], p[1], q[0], q[1]]) {
+            *m += v as f64 / n;
+        }
+    }
+
+    let (mut sxx, mut sxy, mut syy) = (0.0, 0.0, 0.0);
+    let mut rhs = [[0.0f64; 2]; 2];
+    for (p, q) in matches {
+        let (x, y) = (p[0] as f64 - mean[0], p[1] as f64 - mean[1]);
+        let (u, v) = (q[0] as f64 - mean[2], q[1] as f64 - mean[3]);
+        (sxx, sxy, syy) = (sxx + x * x, sxy + x * y, syy + y * y);
+        rhs[0] = [rhs[0][0] + x * u, rhs[0][1] + y * u];
+        rhs[1] = [rhs[1][0] + x * v, rhs[1][1] + y * v];
+    }
+
+    let det = sxx * syy - sxy * sxy;
+    if det.abs() <= 1e-9 * (sxx * syy).max(f64::MIN_POSITIVE) {
+        return Err(ImageError::CannotComputeDeterminant);
+    }
+
+    let mut m = [0.0f32; 6];
+    for (row, [bx, by]) in rhs.iter().enumerate() {
+        let a = (syy * bx - sxy * by) / det;
+        let b = (sxx * by - sxy * bx) / det;
+        let t = mean[2 + row] - a * mean[0] - b * mean[1];
+        m[row * 3..row * 3 + 3].copy_from_slice(&[a as f32, b as f32, t as f32]);
+    }
+
+    Ok(m)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // a vertical bar moving one pixel to the right at every time step
+    fn moving_bar(mhi: &mut Image<f32, 1>, steps: usize) -> Result<(), ImageError> {
+        for t in 1..=steps {
+            let silhouette = Image::new(
+                mhi.size(),
+                (0..mhi.cols() * mhi.rows())
+                    .map(|i| if i % mhi.cols() == 4 + t { 255 } else { 0 })
+                    .collect(),
+            )?;
+            update_motion_history(&silhouette, mhi, t as f32, 5.0)?;
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_motion_history() -> Result<(), ImageError> {
+        let mut mhi = Image::from_size_val([20, 8].into(), 0.0)?;
+        moving_bar(&mut mhi, 10)?;
+
+        // the trail keeps the last five steps
+        let row = &mhi.as_slice()[..20];
+        assert_eq!(&row[8..16], &[0.0, 5.0, 6.0, 7.0, 8.0, 9.0, 10.0, 0.0][..]);
+
+        let mut mask = Image::from_size_val(mhi.size(), 0)?;
+        let mut orientation = Image::from_size_val(mhi.size(), 0.0)?;
+        motion_gradient(&mhi, &mut mask, &mut orientation, 0.5, 3.0)?;
+
+        // the bar moves towards the x axis
+        let i = 3 * 20 + 12;
+        assert_eq!(mask.as_slice()[i], 255);
+        assert!(orientation.as_slice()[i] < 1e-3);
+
+        let angle = global_motion_orientation(&orientation, &mask, &mhi, 10.0, 5.0)?;
+        assert!(angle.is_some_and(|a| !(1.0..359.0).contains(&a)));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_segment_motion() -> Result<(), ImageError> {
+        // two objects with separate trails
+        let (cols, rows) = (16, 8);
+        let mut mhi = Image::from_size_val([cols, rows].into(), 0.0)?;
+        for (i, v) in mhi.as_slice_mut().iter_mut().enumerate() {
+            let (x, y) = (i % cols, i / cols);
+            if y < 3 && x < 4 {
+                *v = 7.0 + x as f32;
+            } else if y > 4 && x > 10 {
+                *v = 10.0 - (y - 5) as f32;
+            }
+        }
+
+        let mut labels = Image::from_size_val(mhi.size(), 0)?;
+        let regions = segment_motion(&mhi, &mut labels, 10.0, 1.5)?;
+        assert_eq!(
+            regions,
+            vec![
+                MotionRegion {
+                    bbox: [0, 0, 4, 3],
+                    area: 12
+                },
+                MotionRegion {
+                    bbox: [11, 5, 5, 3],
+                    area: 15
+                },
+            ]
+        );
+        assert_eq!(labels.as_slice()[0], 1);
+        assert_eq!(labels.as_slice()[7 * cols + 15], 2);
+        assert_eq!(labels.as_slice()[4 * cols + 8], 0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_global_affine_motion() -> Result<(), ImageError> {
+        let expected = [0.98, -0.05, 1.5, 0.04, 1.01, -2.0];
+        let (cols, rows) = (40, 30);
+
+        // the flow of the affine motion with an independently moving object
+        let mut flow = Image::<f32, 2>::from_size_val([cols, rows].into(), 0.0)?;
+        for (i, uv) in flow.as_slice_mut().chunks_exact_mut(2).enumerate() {
+            let (x, y) = ((i % cols) as f32, (i / cols) as f32);
+            if x < 8.0 && y < 8.0 {
+                uv.copy_from_slice(&[10.0, -7.0]);
+            } else {
+                uv[0] = expected[0] * x + expected[1] * y + expected[2] - x;
+                uv[1] = expected[3] * x + expected[4] * y + expected[5] - y;
+            }
+        }
+
+        let m = global_affine_motion(&flow, None)?;
+        for (a, b) in m.iter().zip(&expected) {
+            assert!((a - b).abs() < 1e-3, "{m:?}");
+        }
+
+        // collinear points
+        let matches = [
+            ([0.0, 0.0], [0.0, 0.0]),
+            ([1.0, 1.0], [1.0, 1.0]),
+            ([2.0, 2.0], [2.0, 2.0]),
+        ];
+        assert!(fit_affine_motion(&matches).is_err());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,443 @@
+use kornia_image::{Image, ImageError};
+
+use crate::features::eigenvalues_2x2;
+use crate::filter::{kernels::GradsMode, spatial_gradient_float};
+use crate::interpolation::{interpolate_pixel, InterpolationMode};
+use crate::parallel::prelude::*;
+use crate::pyramid::pyrdown;
+
+/// The residual minimized by the Lucas-Kanade tracker between the windows of a point.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum LkResidual {
+    /// The difference of the raw intensities, for images of a constant exposure.
+    #[default]
+    Intensity,
+    /// The difference of the windows normalized to a zero mean and a unit deviation.
+    ZeroMeanNcc,
+    /// The difference after the fit of a gain and a bias of the intensities of the window.
+    GainBias,
+}
+
+/// The parameters of the pyramidal Lucas-Kanade tracker.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LkParams {
+    /// The width and height of the tracked window in pixels, odd.
+    pub window_size: usize,
+    /// The number of levels of the pyramids, 1 to track at the full resolution only.
+    pub num_levels: usize,
+    /// The maximum number of iterations at every level.
+    pub max_iterations: usize,
+    /// The update of the flow in pixels below which the iterations stop.
+    pub epsilon: f32,
+    /// The minimum eigenvalue of the gradient matrix of a window, per pixel, to be tracked.
+    pub min_eigenvalue: f32,
+    /// The residual between the windows, robust to the changes of exposure but for
+    /// [`LkResidual::Intensity`].
+    pub residual: LkResidual,
+}
+
+impl Default for LkParams {
+    fn default() -> Self {
+        Self {
+            window_size: 21,
+            num_levels: 3,
+            max_iterations: 30,
+            epsilon: 0.01,
+            min_eigenvalue: 1e-4,
+            residual: LkResidual::Intensity,
+        }
+    }
+}
+
+/// A point tracked between two images.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TrackedPoint {
+    /// The position `[x, y]` of the point in the next image.
+    pub position: [f32; 2],
+    /// Whether the point was tracked, false if it left the image or its window is flat.
+    pub tracked: bool,
+    /// The mean absolute residual between the windows of the point in the two images, in the
+    /// intensities of the previous image.
+    pub error: f32,
+}
+
+/// Track points between two images with the pyramidal Lucas-Kanade optical flow.
+///
+/// The flow of every point is estimated from the coarsest to the finest level of the
+/// pyramids, as in Bouguet, starting from the previous position of the point. The points
+/// are tracked in parallel.
+///
+/// # Arguments
+///
+/// * `prev` - The previous image with shape (H, W).
+/// * `next` - The next image with shape (H, W).
+/// * `prev_pts` - The positions `[x, y]` of the points in the previous image.
+/// * `params` - The parameters of the tracker.
+///
+/// # Returns
+///
+/// The tracked points, in the order of `prev_pts`.
+pub fn track_points_lk(
+    prev: &Image<f32, 1>,
+    next: &Image<f32, 1>,
+    prev_pts: &[[f32; 2]],
+    params: &LkParams,
+) -> Result<Vec<TrackedPoint>, ImageError> {
+    let points = prev_pts.iter().map(|&p| (p, p)).collect::<Vec<_>>();
+    track_points_lk_with_guesses(prev, next, &points, params)
+}
+
+/// Track points between two images from initial guesses of their next positions.
+///
+/// The search of every point starts from its guess in the next image instead of its
+/// previous position, e.g. from a motion model with [`predict_constant_velocity`] or a gyro
+/// prediction, so the points survive motions larger than the window at the coarsest level
+/// of the pyramids. The tracking is otherwise the same as [`track_points_lk`].
+///
+/// # Arguments
+///
+/// * `prev` - The previous image with shape (H, W).
+/// * `next` - The next image with shape (H, W).
+/// * `points` - The positions `[x, y]` of the points in the previous image and their
+///   predicted positions in the next image.
+/// * `params` - The parameters of the tracker.
+///
+/// # Returns
+///
+/// The tracked points, in the order of `points`.
+pub fn track_points_lk_with_guesses(
+    prev: &Image<f32, 1>,
+    next: &Image<f32, 1>,
+    points: &[([f32; 2], [f32; 2])],
+    params: &LkParams,
+) -> Result<Vec<TrackedPoint>, ImageError> {
+    profile_scope!("track_points_lk");
+    if prev.size() != next.size() {
+        return Err(ImageError::InvalidImageSize(
+            prev.cols(),
+            prev.rows(),
+            next.cols(),
+            next.rows(),
+        ));
+    }
+
+    let prev_levels = build_pyramid(prev, params.num_levels)?;
+    let next_levels = build_pyramid(next, params.num_levels)?;
+    let gradients = prev_levels
+        .iter()
+        .map(|level| {
+            let mut dx = Image::from_size_val(level.size(), 0.0)?;
+            let mut dy = Image::from_size_val(level.size(), 0.0)?;
+            spatial_gradient_float(level, &mut dx, &mut dy, GradsMode::Scharr)?;
+            Ok((dx, dy))
+        })
+        .collect::<Result<Vec<_>, ImageError>>()?;
+
+    let tracked = points
+        .par_iter()
+        .map(|&(point, guess)| {
+            track_point(&prev_levels, &next_levels, &gradients, point, guess, params)
+        })
+        .collect();
+
+    Ok(tracked)
+}
+
+/// Predict the positions of points moving at a constant velocity.
+///
+/// The prediction extrapolates the motion between the two last positions, to seed
+/// [`track_points_lk_with_guesses`].
+///
+/// # Arguments
+///
+/// * `before` - The positions `[x, y]` of the points two frames ago.
+/// * `current` - The positions `[x, y]` of the points in the last frame.
+///
+/// # Returns
+///
+/// The predicted positions in the next frame.
+pub fn predict_constant_velocity(before: &[[f32; 2]], current: &[[f32; 2]]) -> Vec<[f32; 2]> {
+    before
+        .iter()
+        .zip(current)
+        .map(|(b, c)| [2.0 * c[0] - b[0], 2.0 * c[1] - b[1]])
+        .collect()
+}
+
+// the gaussian pyramid of an image, from the full resolution
+fn build_pyramid(src: &Image<f32, 1>, num_levels: usize) -> Result<Vec<Image<f32, 1>>, ImageError> {
+    let mut levels = vec![src.clone()];
+    for _ in 1..num_levels {
+        let Some(last) = levels.last() else { break };
+        if last.cols() < 2 || last.rows() < 2 {
+            break;
+        }
+        let size = [last.cols().div_ceil(2), last.rows().div_ceil(2)].into();
+        let mut down = Image::from_size_val(size, 0.0)?;
+        pyrdown(last, &mut down)?;
+        levels.push(down);
+    }
+    Ok(levels)
+}
+
+// bilinear sampling with the coordinates clamped to the image
+fn sample(image: &Image<f32, 1>, x: f32, y: f32) -> f32 {
+    let x = x.clamp(0.0, (image.cols() - 1) as f32);
+    let y = y.clamp(0.0, (image.rows() - 1) as f32);
+    interpolate_pixel(image, x, y, 0, InterpolationMode::Bilinear)
+}
+
+fn inside(image: &Image<f32, 1>, [x, y]: [f32; 2]) -> bool {
+    x >= 0.0 && y >= 0.0 && x <= (image.cols() - 1) as f32 && y <= (image.rows() - 1) as f32
+}
+
+fn track_point(
+    prev_levels: &[Image<f32, 1>],
+    next_levels: &[Image<f32, 1>],
+    gradients: &[(Image<f32, 1>, Image<f32, 1>)],
+    point: [f32; 2],
+    guess: [f32; 2],
+    params: &LkParams,
+) -> TrackedPoint {
+    let lost = TrackedPoint {
+        position: guess,
+        tracked: false,
+        error: f32::INFINITY,
+    };
+    let half = (params.window_size / 2) as isize;
+    let offsets = (-half..=half)
+        .flat_map(|dy| (-half..=half).map(move |dx| [dx as f32, dy as f32]))
+        .collect::<Vec<_>>();
+    let num_pixels = offsets.len() as f32;
+
+    // the flow at the coarsest level from the initial guess
+    let top = prev_levels.len() - 1;
+    let top_scale = (1 << top) as f32;
+    let mut flow = [
+        (guess[0] - point[0]) / top_scale,
+        (guess[1] - point[1]) / top_scale,
+    ];
+    let mut error = 0.0;
+
+    for level in (0..=top).rev() {
+        let scale = (1 << level) as f32;
+        let (prev, next) = (&prev_levels[level], &next_levels[level]);
+        let (dx, dy) = (&gradients[level].0, &gradients[level].1);
+        let center = [point[0] / scale, point[1] / scale];
+        if !inside(prev, center) {
+            return lost;
+        }
+
+        // the template window and its gradient matrix
+        let template = offsets
+            .iter()
+            .map(|o| {
+                let (x, y) = (center[0] + o[0], center[1] + o[1]);
+                (sample(prev, x, y), sample(dx, x, y), sample(dy, x, y))
+            })
+            .collect::<Vec<_>>();
+        let (mut gxx, mut gyy, mut gxy) = (0.0, 0.0, 0.0);
+        for &(_, ix, iy) in &template {
+            gxx += ix * ix;
+            gyy += iy * iy;
+            gxy += ix * iy;
+        }
+        let det = gxx * gyy - gxy * gxy;
+        if eigenvalues_2x2(gxx, gyy, gxy).1 / num_pixels < params.min_eigenvalue
+            || det.abs() <= f32::EPSILON
+        {
+            return lost;
+        }
+
+        for _ in 0..params.max_iterations {
+            let target = [center[0] + flow[0], center[1] + flow[1]];
+            if !inside(next, target) {
+                return lost;
+            }
+
+            let window = offsets
+                .iter()
+                .map(|o| sample(next, target[0] + o[0], target[1] + o[1]))
+                .collect::<Vec<_>>();
+            let Some((gain, bias)) = photometric_fit(&template, &window, params.residual) else {
+                return lost;
+            };
+
+            let (mut bx, mut by) = (0.0, 0.0);
+            error = 0.0;
+            for (&(value, ix, iy), &warped) in template.iter().zip(&window) {
+                // the next window mapped to the exposure of the previous one
+                let diff = value - (warped - bias) / gain;
+                bx += diff * ix;
+                by += diff * iy;
+                error += diff.abs();
+            }
+
+            let delta = [(gyy * bx - gxy * by) / det, (gxx * by - gxy * bx) / det];
+            flow = [flow[0] + delta[0], flow[1] + delta[1]];
+            if delta[0].hypot(delta[1]) < params.epsilon {
+                break;
+            }
+        }
+
+        if level > 0 {
+            flow = [2.0 * flow[0], 2.0 * flow[1]];
+        }
+    }
+
+    let position = [point[0] + flow[0], point[1] + flow[1]];
+    if !inside(&next_levels[0], position) {
+        return lost;
+    }
+    TrackedPoint {
+        position,
+        tracked: true,
+        error: error / num_pixels,
+    }
+}
+
+// the gain and bias of the intensities of a window relative to the template window
+fn photometric_fit(
+    template: &[(f32, f32, f32)],
+    window: &[f32],
+    residual: LkResidual,
+) -> Option<(f32, f32)> {
+    if residual == LkResidual::Intensity {
+        return Some((1.0, 0.0));
+    }
+
+    let n = window.len() as f32;
+    let mean_t = template.iter().map(|t| t.0).sum::<f32>() / n;
+    let mean_w = window.iter().sum::<f32>() / n;
+    let (mut var_t, mut var_w, mut cov) = (0.0, 0.0, 0.0);
+    for (t, w) in template.iter().zip(window) {
+        let (dt, dw) = (t.0 - mean_t, w - mean_w);
+        var_t += dt * dt;
+        var_w += dw * dw;
+        cov += dt * dw;
+    }
+    if var_t <= f32::EPSILON {
+        return None;
+    }
+
+    // the ratio of the deviations normalizes both windows, the regression fits the window
+    let gain = match residual {
+        LkResidual::ZeroMeanNcc => (var_w / var_t).sqrt(),
+        _ => cov / var_t,
+    };
+    if gain <= f32::EPSILON {
+        return None;
+    }
+    Some((gain, mean_w - gain * mean_t))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // a smooth texture translated by `shift`
+    fn texture(shift: [f32; 2]) -> Result<Image<f32, 1>, ImageError> {
+        let data = (0..96 * 80)
+            .map(|i| {
+                let x = (i % 96) as f32 - shift[0];
+                let y = (i / 96) as f32 - shift[1];
+                0.5 + 0.25 * (x / 5.0).sin() * (y / 7.0).cos() + 0.2 * ((x + y) / 9.0).sin()
+            })
+            .collect();
+        Image::new([96, 80].into(), data)
+    }
+
+    #[test]
+    fn test_track_points_lk() -> Result<(), ImageError> {
+        let shift = [3.3, -2.1];
+        let (prev, next) = (texture([0.0, 0.0])?, texture(shift)?);
+        let points = [[30.0, 30.0], [48.0, 40.0], [60.5, 45.2]];
+
+        let tracked = track_points_lk(&prev, &next, &points, &LkParams::default())?;
+        for (p, t) in points.iter().zip(&tracked) {
+            assert!(t.tracked);
+            assert!((t.position[0] - p[0] - shift[0]).abs() < 0.05, "{t:?}");
+            assert!((t.position[1] - p[1] - shift[1]).abs() < 0.05, "{t:?}");
+            assert!(t.error < 0.01);
+        }
+
+        // a flat image has no flow
+        let flat = Image::from_size_val([96, 80].into(), 0.5)?;
+        let tracked = track_points_lk(&flat, &flat, &points, &LkParams::default())?;
+        assert!(tracked.iter().all(|t| !t.tracked));
+
+        let small = Image::from_size_val([48, 40].into(), 0.5)?;
+        assert!(track_points_lk(&prev, &small, &points, &LkParams::default()).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_track_points_lk_initial_guesses() -> Result<(), ImageError> {
+        // a motion larger than the window at the full resolution
+        let params = LkParams {
+            window_size: 9,
+            num_levels: 1,
+            ..Default::default()
+        };
+        let shift = [12.0, 7.0];
+        let (prev, next) = (texture([0.0, 0.0])?, texture(shift)?);
+        let points = [[40.0, 35.0]];
+        let expected = [40.0 + shift[0], 35.0 + shift[1]];
+        let close = |t: &TrackedPoint| {
+            t.tracked
+                && (t.position[0] - expected[0]).abs() < 0.05
+                && (t.position[1] - expected[1]).abs() < 0.05
+        };
+
+        let tracked = track_points_lk(&prev, &next, &points, &params)?;
+        assert!(!close(&tracked[0]), "{tracked:?}");
+
+        // the constant velocity prediction from the previous motion
+        let guesses = predict_constant_velocity(&[[29.0, 28.5]], &points);
+        assert_eq!(guesses, vec![[51.0, 41.5]]);
+        let tracked =
+            track_points_lk_with_guesses(&prev, &next, &[(points[0], guesses[0])], &params)?;
+        assert!(close(&tracked[0]), "{tracked:?}");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_track_points_lk_exposure() -> Result<(), ImageError> {
+        // the exposure of the next image changes
+        let shift = [3.3, -2.1];
+        let prev = texture([0.0, 0.0])?;
+        let data = texture(shift)?
+            .as_slice()
+            .iter()
+            .map(|v| 0.6 * v + 0.3)
+            .collect();
+        let next = Image::new(prev.size(), data)?;
+        let points = [[30.0, 30.0], [48.0, 40.0], [60.5, 45.2]];
+        let close = |p: &[f32; 2], t: &TrackedPoint| {
+            t.tracked
+                && (t.position[0] - p[0] - shift[0]).abs() < 0.05
+                && (t.position[1] - p[1] - shift[1]).abs() < 0.05
+        };
+
+        let tracked = track_points_lk(&prev, &next, &points, &LkParams::default())?;
+        assert!(!points.iter().zip(&tracked).all(|(p, t)| close(p, t)));
+
+        for residual in [LkResidual::ZeroMeanNcc, LkResidual::GainBias] {
+            let params = LkParams {
+                residual,
+                ..Default::default()
+            };
+            let tracked = track_points_lk(&prev, &next, &points, &params)?;
+            for (p, t) in points.iter().zip(&tracked) {
+                assert!(close(p, t), "{residual:?} {t:?}");
+                assert!(t.error < 0.01, "{residual:?} {t:?}");
+            }
+        }
+
+        Ok(())
+    }
+}
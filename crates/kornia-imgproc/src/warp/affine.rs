@@ -131,6 +131,7 @@ pub fn warp_affine<const C: usize>(
     m: &[f32; 6],
     interpolation: InterpolationMode,
 ) -> Result<(), ImageError> {
+    profile_scope!("warp_affine");
     // invert affine transform matrix to find corresponding positions in src from dst
     let m_inv = invert_affine_transform(m);
 
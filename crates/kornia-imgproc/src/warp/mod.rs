@@ -1,5 +1,8 @@
 mod affine;
 mod perspective;
+mod polar;
 
 pub use affine::{get_rotation_matrix2d, invert_affine_transform, warp_affine};
+pub(crate) use perspective::inverse_perspective_matrix;
 pub use perspective::warp_perspective;
+pub use polar::{warp_polar, warp_polar_inverse, PolarMode};
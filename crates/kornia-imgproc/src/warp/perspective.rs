@@ -28,7 +28,7 @@ fn adjugate3x3(m: &[f32; 9]) -> [f32; 9] {
 }
 
 // TODO: use TensorError
-fn inverse_perspective_matrix(m: &[f32; 9]) -> Result<[f32; 9], ImageError> {
+pub(crate) fn inverse_perspective_matrix(m: &[f32; 9]) -> Result<[f32; 9], ImageError> {
     let det = determinant3x3(m);
 
     if det == 0.0 {
@@ -49,9 +49,10 @@ fn inverse_perspective_matrix(m: &[f32; 9]) -> Result<[f32; 9], ImageError> {
 // implement later as batched operation
 fn transform_point(x: f32, y: f32, m: &[f32; 9]) -> (f32, f32) {
     let w = m[6] * x + m[7] * y + m[8];
-    let x = (m[0] * x + m[1] * y + m[2]) / w;
-    let y = (m[3] * x + m[4] * y + m[5]) / w;
-    (x, y)
+    (
+        (m[0] * x + m[1] * y + m[2]) / w,
+        (m[3] * x + m[4] * y + m[5]) / w,
+    )
 }
 
 /// Applies a perspective transformation to an image.
@@ -101,6 +102,7 @@ pub fn warp_perspective<const C: usize>(
     m: &[f32; 9],
     interpolation: InterpolationMode,
 ) -> Result<(), ImageError> {
+    profile_scope!("warp_perspective");
     // inverse perspective matrix
     // TODO: allow later to skip the inverse calculation if user provides it
     let inv_m = inverse_perspective_matrix(m)?;
@@ -145,6 +147,13 @@ mod tests {
         let (x_expected, y_expected) = (0.0, 2.0);
         assert_eq!(x, x_expected);
         assert_eq!(y, y_expected);
+
+        // the y coordinate depends on the source x, not on the transformed x
+        let m = [1.0, 0.0, 0.0, 1.0, 1.0, 0.0, 0.0, 0.0, 1.0];
+        assert_eq!(super::transform_point(1.0, 1.0, &m), (1.0, 2.0));
+
+        let m = [1.0, 0.0, 0.0, 1.0, 1.0, 0.0, 0.0, 1.0, 1.0];
+        assert_eq!(super::transform_point(1.0, 1.0, &m), (0.5, 1.0));
     }
 
     #[test]
@@ -219,6 +228,32 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn warp_perspective_shear() -> Result<(), ImageError> {
+        let size = ImageSize {
+            width: 4,
+            height: 4,
+        };
+        let image = Image::<_, 1>::new(size, (0..16).map(|v| v as f32).collect())?;
+
+        // (x, y) -> (x + 1, x + y)
+        let m = [1.0, 0.0, 1.0, 1.0, 1.0, 0.0, 0.0, 0.0, 1.0];
+
+        let mut image_transformed = Image::<_, 1>::from_size_val(size, 0.0)?;
+
+        super::warp_perspective(
+            &image,
+            &mut image_transformed,
+            &m,
+            super::InterpolationMode::Nearest,
+        )?;
+
+        // the pixel (2, 3) comes from the pixel (1, 2) of the source
+        assert_eq!(*image_transformed.get_pixel(2, 3, 0)?, 9.0);
+
+        Ok(())
+    }
+
     #[test]
     fn test_warp_perspective_resize() -> Result<(), ImageError> {
         let image = Image::<_, 1>::new(
@@ -0,0 +1,243 @@
+use std::f32::consts::PI;
+
+use kornia_image::{Image, ImageError};
+use kornia_tensor::CpuTensor2;
+
+use crate::interpolation::{grid::meshgrid_from_fn, interpolate_pixel, InterpolationMode};
+use crate::parallel;
+
+/// The radial sampling of the polar transforms.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PolarMode {
+    /// The columns sample the radius linearly from 0 to the maximum radius.
+    Linear,
+    /// The columns sample the logarithm of the radius from 1 to the maximum radius, which
+    /// turns a scaling around the center into a horizontal shift.
+    Log,
+}
+
+// the radius of a column of the polar image and its inverse
+fn radius_scale(mode: PolarMode, cols: usize, max_radius: f32) -> f32 {
+    match mode {
+        PolarMode::Linear => cols as f32 / max_radius,
+        PolarMode::Log => cols as f32 / max_radius.ln(),
+    }
+}
+
+/// Remaps an image to polar or log-polar coordinates.
+///
+/// The columns of the destination sample the radius and the rows sample the angle from 0
+/// to 2 pi, clockwise in the image coordinates. A rotation around the center becomes a
+/// vertical shift, and in the log mode a scaling becomes a horizontal shift, which makes
+/// the transform suited for the rotation and scale invariant registration with the phase
+/// correlation.
+///
+/// # Arguments
+///
+/// * `src` - The input image with shape (H, W, C).
+/// * `dst` - The polar image with shape (angles, radii, C). The pixels mapped outside
+///   of the input are left unchanged.
+/// * `center` - The center of the transform as (x, y) in pixels.
+/// * `max_radius` - The radius of the last column, larger than 1 in the log mode.
+/// * `mode` - The radial sampling.
+/// * `interpolation` - The interpolation mode to use.
+///
+/// # Example
+///
+/// ```
+/// use kornia_image::Image;
+/// use kornia_imgproc::interpolation::InterpolationMode;
+/// use kornia_imgproc::warp::{warp_polar, PolarMode};
+///
+/// let src = Image::<f32, 1>::from_size_val([32, 32].into(), 1.0).unwrap();
+/// let mut dst = Image::<f32, 1>::from_size_val([16, 64].into(), 0.0).unwrap();
+///
+/// warp_polar(&src, &mut dst, (16.0, 16.0), 15.0, PolarMode::Linear, InterpolationMode::Bilinear)
+///     .unwrap();
+/// assert_eq!(dst.as_slice()[0], 1.0);
+/// ```
+pub fn warp_polar<const C: usize>(
+    src: &Image<f32, C>,
+    dst: &mut Image<f32, C>,
+    center: (f32, f32),
+    max_radius: f32,
+    mode: PolarMode,
+    interpolation: InterpolationMode,
+) -> Result<(), ImageError> {
+    profile_scope!("warp_polar");
+    let (cols, rows) = (dst.cols(), dst.rows());
+    let scale = radius_scale(mode, cols, max_radius);
+    let angle_step = 2.0 * PI / rows as f32;
+
+    let (map_x, map_y) = meshgrid_from_fn(cols, rows, |x, y| {
+        let radius = match mode {
+            PolarMode::Linear => x as f32 / scale,
+            PolarMode::Log => (x as f32 / scale).exp(),
+        };
+        let angle = y as f32 * angle_step;
+        Ok((
+            center.0 + radius * angle.cos(),
+            center.1 + radius * angle.sin(),
+        ))
+    })?;
+
+    resample(src, dst, &map_x, &map_y, interpolation);
+
+    Ok(())
+}
+
+/// Remaps a polar or log-polar image back to cartesian coordinates.
+///
+/// This is the inverse of [`warp_polar`] with the same center, radius and mode.
+///
+/// # Arguments
+///
+/// * `src` - The polar image with shape (angles, radii, C).
+/// * `dst` - The output image with shape (H, W, C). The pixels beyond the maximum radius
+///   are left unchanged.
+/// * `center` - The center of the transform as (x, y) in pixels.
+/// * `max_radius` - The radius of the last column of the polar image.
+/// * `mode` - The radial sampling of the polar image.
+/// * `interpolation` - The interpolation mode to use.
+pub fn warp_polar_inverse<const C: usize>(
+    src: &Image<f32, C>,
+    dst: &mut Image<f32, C>,
+    center: (f32, f32),
+    max_radius: f32,
+    mode: PolarMode,
+    interpolation: InterpolationMode,
+) -> Result<(), ImageError> {
+    profile_scope!("warp_polar_inverse");
+    let (cols, rows) = (dst.cols(), dst.rows());
+    let scale = radius_scale(mode, src.cols(), max_radius);
+    let angle_scale = src.rows() as f32 / (2.0 * PI);
+
+    let (map_x, map_y) = meshgrid_from_fn(cols, rows, |x, y| {
+        let (dx, dy) = (x as f32 - center.0, y as f32 - center.1);
+        let radius = dx.hypot(dy);
+        let column = match mode {
+            PolarMode::Linear => radius * scale,
+            // the radii below 1 map to the first column
+            PolarMode::Log => radius.max(1.0).ln() * scale,
+        };
+        let angle = dy.atan2(dx).rem_euclid(2.0 * PI);
+        Ok((column, angle * angle_scale))
+    })?;
+
+    resample(src, dst, &map_x, &map_y, interpolation);
+
+    Ok(())
+}
+
+fn resample<const C: usize>(
+    src: &Image<f32, C>,
+    dst: &mut Image<f32, C>,
+    map_x: &CpuTensor2<f32>,
+    map_y: &CpuTensor2<f32>,
+    interpolation: InterpolationMode,
+) {
+    let (max_x, max_y) = ((src.cols() - 1) as f32, (src.rows() - 1) as f32);
+    parallel::par_iter_rows_resample(dst, map_x, map_y, |&x, &y, dst_pixel| {
+        if x >= 0.0 && x <= max_x && y >= 0.0 && y <= max_y {
+            dst_pixel
+                .iter_mut()
+                .enumerate()
+                .for_each(|(k, pixel)| *pixel = interpolate_pixel(src, x, y, k, interpolation));
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // the distance to the center of the image
+    fn radial(size: usize) -> Result<Image<f32, 1>, ImageError> {
+        let center = (size / 2) as f32;
+        Image::new(
+            [size, size].into(),
+            (0..size * size)
+                .map(|i| ((i % size) as f32 - center).hypot((i / size) as f32 - center))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn test_warp_polar() -> Result<(), ImageError> {
+        let src = radial(64)?;
+        let center = (32.0, 32.0);
+
+        // every row of the polar image is the same ramp
+        let mut polar = Image::from_size_val([30, 90].into(), -1.0)?;
+        warp_polar(
+            &src,
+            &mut polar,
+            center,
+            30.0,
+            PolarMode::Linear,
+            InterpolationMode::Bilinear,
+        )?;
+        // the bilinear interpolation of the distance is inexact next to the center
+        for (i, &value) in polar.as_slice().iter().enumerate() {
+            if i % 30 >= 2 {
+                assert!((value - (i % 30) as f32).abs() < 0.1, "{i} {value}");
+            }
+        }
+
+        // the log mode samples the radius exponentially
+        warp_polar(
+            &src,
+            &mut polar,
+            center,
+            30.0,
+            PolarMode::Log,
+            InterpolationMode::Bilinear,
+        )?;
+        let expected = |x: usize| (x as f32 * 30f32.ln() / 30.0).exp();
+        for (i, &value) in polar.as_slice().iter().enumerate() {
+            if expected(i % 30) >= 2.0 {
+                assert!((value - expected(i % 30)).abs() < 0.1, "{i} {value}");
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_warp_polar_inverse() -> Result<(), ImageError> {
+        let src = radial(64)?;
+        let center = (32.0, 32.0);
+
+        for mode in [PolarMode::Linear, PolarMode::Log] {
+            let mut polar = Image::from_size_val([64, 256].into(), 0.0)?;
+            warp_polar(
+                &src,
+                &mut polar,
+                center,
+                30.0,
+                mode,
+                InterpolationMode::Bilinear,
+            )?;
+
+            let mut back = Image::from_size_val(src.size(), -1.0)?;
+            warp_polar_inverse(
+                &polar,
+                &mut back,
+                center,
+                30.0,
+                mode,
+                InterpolationMode::Bilinear,
+            )?;
+
+            // the round trip recovers the image in the ring between the radii 2 and 28
+            for (i, (&a, &b)) in src.as_slice().iter().zip(back.as_slice()).enumerate() {
+                if (2.0..28.0).contains(&a) {
+                    assert!((a - b).abs() < 0.3, "{mode:?} {i} {a} {b}");
+                }
+            }
+            assert_eq!(back.as_slice()[0], -1.0);
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,187 @@
+//! Parity of the operators with the reference outputs of OpenCV and Kornia.
+//!
+//! The fixtures are generated by `scripts/generate_parity_fixtures.py` into
+//! `tests/data/parity` and checked in, so a missing fixture fails the tests.
+
+use std::error::Error;
+use std::path::PathBuf;
+
+use kornia_image::Image;
+use kornia_imgproc::{
+    color::{gray_from_rgb, gray_from_rgb_u8},
+    features::{gftt_response, hessian_response, GradsMode, HarrisResponse},
+    filter::{box_blur, gaussian_blur, sobel, spatial_gradient_float},
+    interpolation::InterpolationMode,
+    warp::warp_affine,
+};
+use kornia_io::npy::{read_image_npy, NpySample};
+
+type TestResult = Result<(), Box<dyn Error>>;
+
+// the directory of the checked in fixtures
+fn fixture_dir() -> PathBuf {
+    PathBuf::from(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/../../tests/data/parity"
+    ))
+}
+
+fn load<T: NpySample, const C: usize>(name: &str) -> Result<Image<T, C>, Box<dyn Error>> {
+    let path = fixture_dir().join(format!("{name}.npy"));
+    read_image_npy(&path).map_err(|e| {
+        format!(
+            "failed to load the fixture {}: {e}, run `just parity-fixtures` to regenerate it",
+            path.display()
+        )
+        .into()
+    })
+}
+
+// asserts that the images agree within `atol`, ignoring `border` pixels on each side
+fn assert_close<const C: usize>(
+    name: &str,
+    actual: &Image<f32, C>,
+    expected: &Image<f32, C>,
+    atol: f32,
+    border: usize,
+) {
+    assert_eq!(actual.size(), expected.size(), "{name}: size mismatch");
+    let (rows, cols) = (actual.rows(), actual.cols());
+
+    let mut worst = (0.0f32, 0, 0, 0);
+    for r in border..rows - border {
+        for c in border..cols - border {
+            for ch in 0..C {
+                let idx = (r * cols + c) * C + ch;
+                let diff = (actual.as_slice()[idx] - expected.as_slice()[idx]).abs();
+                if diff.is_nan() || diff > worst.0 {
+                    worst = (diff, r, c, ch);
+                }
+            }
+        }
+    }
+
+    let (diff, r, c, ch) = worst;
+    assert!(
+        diff <= atol,
+        "{name}: max difference {diff} > {atol} at (row {r}, col {c}, channel {ch})"
+    );
+}
+
+#[test]
+fn test_parity_blur() -> TestResult {
+    let src = load::<f32, 3>("input_rgb_f32")?;
+
+    let mut dst = Image::from_size_val(src.size(), 0.0)?;
+    gaussian_blur(&src, &mut dst, (5, 5), (1.5, 1.5))?;
+    let expected = load("gaussian_blur_5_1.5")?;
+    assert_close("gaussian_blur", &dst, &expected, 1e-5, 0);
+
+    box_blur(&src, &mut dst, (5, 5))?;
+    let expected = load("box_blur_5")?;
+    assert_close("box_blur", &dst, &expected, 1e-5, 0);
+
+    Ok(())
+}
+
+#[test]
+fn test_parity_gradients() -> TestResult {
+    let src = load::<f32, 3>("input_rgb_f32")?;
+
+    let mut dst = Image::from_size_val(src.size(), 0.0)?;
+    sobel(&src, &mut dst, 3)?;
+    let expected = load("sobel_3")?;
+    assert_close("sobel", &dst, &expected, 1e-5, 0);
+
+    let mut dx = Image::from_size_val(src.size(), 0.0)?;
+    let mut dy = Image::from_size_val(src.size(), 0.0)?;
+    spatial_gradient_float(&src, &mut dx, &mut dy, GradsMode::Sobel)?;
+    let expected_dx = load("spatial_gradient_dx")?;
+    let expected_dy = load("spatial_gradient_dy")?;
+    assert_close("spatial_gradient_dx", &dx, &expected_dx, 1e-5, 0);
+    assert_close("spatial_gradient_dy", &dy, &expected_dy, 1e-5, 0);
+
+    Ok(())
+}
+
+#[test]
+fn test_parity_responses() -> TestResult {
+    let src = load::<f32, 1>("input_gray_f32")?;
+
+    let mut dst = Image::from_size_val(src.size(), 0.0)?;
+    hessian_response(&src, &mut dst, GradsMode::Diff)?;
+    let expected = load("hessian_response")?;
+    assert_close("hessian_response", &dst, &expected, 1e-5, 0);
+
+    // the windows next to the border include the zero moments of the border pixels
+    let mut dst = Image::from_size_val(src.size(), 0.0)?;
+    HarrisResponse::new(src.size()).compute(&src, &mut dst)?;
+    let expected = load("harris_response")?;
+    assert_close("harris_response", &dst, &expected, 1e-5, 2);
+
+    let mut dst = Image::from_size_val(src.size(), 0.0)?;
+    gftt_response(&src, &mut dst, GradsMode::Sobel)?;
+    let expected = load("gftt_response")?;
+    assert_close("gftt_response", &dst, &expected, 1e-5, 2);
+
+    Ok(())
+}
+
+#[test]
+fn test_parity_warp_affine() -> TestResult {
+    let src = load::<f32, 3>("input_rgb_f32")?;
+    let matrix = load::<f32, 1>("warp_affine_matrix")?;
+    let m: [f32; 6] = matrix.as_slice().try_into()?;
+
+    let mut dst = Image::from_size_val(src.size(), 0.0)?;
+    warp_affine(&src, &mut dst, &m, InterpolationMode::Bilinear)?;
+
+    // compare only the pixels sampled inside the source image
+    let valid = load::<u8, 1>("warp_affine_valid")?;
+    let mut expected = load::<f32, 3>("warp_affine_bilinear")?;
+    for ((actual, expected), &valid) in dst
+        .as_slice_mut()
+        .chunks_exact_mut(3)
+        .zip(expected.as_slice_mut().chunks_exact_mut(3))
+        .zip(valid.as_slice())
+    {
+        if valid == 0 {
+            actual.fill(0.0);
+            expected.fill(0.0);
+        }
+    }
+    assert_close("warp_affine", &dst, &expected, 1e-4, 0);
+
+    Ok(())
+}
+
+#[test]
+fn test_parity_color() -> TestResult {
+    let src = load::<f32, 3>("input_rgb_f32")?;
+
+    let mut gray = Image::from_size_val(src.size(), 0.0)?;
+    gray_from_rgb(&src, &mut gray)?;
+    let expected = load("gray_from_rgb")?;
+    assert_close("gray_from_rgb", &gray, &expected, 1e-5, 0);
+
+    // the fixed point coefficients round differently by at most one level
+    let src = load::<u8, 3>("input_rgb_u8")?;
+    let mut gray = Image::from_size_val(src.size(), 0)?;
+    gray_from_rgb_u8(&src, &mut gray)?;
+    let expected = load::<u8, 1>("gray_from_rgb_u8")?;
+    let to_f32 = |image: &Image<u8, 1>| {
+        Image::<f32, 1>::new(
+            image.size(),
+            image.as_slice().iter().map(|&v| v as f32).collect(),
+        )
+    };
+    assert_close(
+        "gray_from_rgb_u8",
+        &to_f32(&gray)?,
+        &to_f32(&expected)?,
+        1.0,
+        0,
+    );
+
+    Ok(())
+}
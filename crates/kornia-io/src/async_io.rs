@@ -0,0 +1,135 @@
+use std::path::{Path, PathBuf};
+
+use kornia_image::Image;
+use tokio::sync::mpsc;
+
+use crate::error::IoError;
+
+/// Runs a blocking io function in the blocking thread pool of the tokio runtime.
+///
+/// Use this function to call any of the synchronous readers and writers of this crate
+/// without blocking the asynchronous executor.
+///
+/// # Arguments
+///
+/// * `f` - The blocking function to run.
+///
+/// # Example
+///
+/// ```
+/// use kornia_io::{async_io, png};
+///
+/// # #[tokio::main(flavor = "current_thread")]
+/// # async fn main() -> Result<(), kornia_io::IoError> {
+/// let image = async_io::blocking(|| png::read_image_png_rgb8("../../tests/data/dog-rgb8.png")).await?;
+/// assert_eq!(image.num_channels(), 3);
+/// # Ok(())
+/// # }
+/// ```
+pub async fn blocking<F, R>(f: F) -> Result<R, IoError>
+where
+    F: FnOnce() -> Result<R, IoError> + Send + 'static,
+    R: Send + 'static,
+{
+    tokio::task::spawn_blocking(f).await?
+}
+
+/// Reads an image asynchronously in any format supported by the image crate.
+///
+/// The file is read with the asynchronous file system api and decoded in the
+/// blocking thread pool, so the executor is never blocked.
+///
+/// # Arguments
+///
+/// * `file_path` - The path to the image.
+///
+/// # Returns
+///
+/// A RGB image with three channels (rgb8).
+pub async fn read_image_async(file_path: impl AsRef<Path>) -> Result<Image<u8, 3>, IoError> {
+    let file_path = file_path.as_ref();
+
+    // verify the file exists
+    if !tokio::fs::try_exists(file_path).await? {
+        return Err(IoError::FileDoesNotExist(file_path.to_path_buf()));
+    }
+
+    let data = tokio::fs::read(file_path).await?;
+
+    blocking(move || crate::functional::decode_image_any_rgb8(&data)).await
+}
+
+/// Decodes a sequence of images in the background and streams them through a channel.
+///
+/// The images are decoded in order by a blocking task. The channel is bounded, so the
+/// decoding pauses when the receiver does not keep up, and stops when the receiver is dropped.
+///
+/// # Arguments
+///
+/// * `file_paths` - The paths to the images to decode.
+/// * `capacity` - The maximum number of decoded images waiting to be received.
+///
+/// # Returns
+///
+/// The receiving end of the channel with the decoded images in the same order as the paths.
+///
+/// PRECONDITION: must be called from within a tokio runtime.
+pub fn stream_images(
+    file_paths: Vec<PathBuf>,
+    capacity: usize,
+) -> mpsc::Receiver<Result<Image<u8, 3>, IoError>> {
+    let (tx, rx) = mpsc::channel(capacity.max(1));
+
+    tokio::task::spawn_blocking(move || {
+        for file_path in file_paths {
+            let image = crate::functional::read_image_any_rgb8(file_path);
+            if tx.blocking_send(image).is_err() {
+                // the receiver was dropped
+                break;
+            }
+        }
+    });
+
+    rx
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn read_image_async_jpeg() -> Result<(), IoError> {
+        let image = read_image_async("../../tests/data/dog.jpeg").await?;
+        assert_eq!(image.cols(), 258);
+        assert_eq!(image.rows(), 195);
+
+        let res = read_image_async("../../tests/data/missing.jpeg").await;
+        assert!(matches!(res, Err(IoError::FileDoesNotExist(_))));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn stream_images_in_order() -> Result<(), IoError> {
+        let file_paths = vec![
+            PathBuf::from("../../tests/data/dog.jpeg"),
+            PathBuf::from("../../tests/data/dog-rgb8.png"),
+            PathBuf::from("../../tests/data/missing.png"),
+        ];
+
+        let mut rx = stream_images(file_paths, 1);
+
+        let first = rx.recv().await.expect("missing first image")?;
+        assert_eq!(first.cols(), 258);
+
+        let second = rx.recv().await.expect("missing second image")?;
+        assert_eq!(second.num_channels(), 3);
+
+        let third = rx.recv().await.expect("missing third result");
+        assert!(third.is_err());
+
+        assert!(rx.recv().await.is_none());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,676 @@
+use std::path::Path;
+
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::{Map, Number, Value};
+
+use super::read_file;
+use crate::error::IoError;
+
+// the type of the matrix nodes
+const MATRIX_TYPE: &str = "opencv-matrix";
+
+/// The formats of the files of the OpenCV `FileStorage`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileStorageFormat {
+    /// YAML with the `%YAML:1.0` directive and the `!!opencv-matrix` tags.
+    Yaml,
+    /// XML with the `opencv_storage` root element.
+    Xml,
+    /// JSON with the matrices as objects with a `type_id`.
+    Json,
+}
+
+impl FileStorageFormat {
+    /// Get the format of a file from its extension, `yml`, `yaml`, `xml` or `json`.
+    ///
+    /// # Arguments
+    ///
+    /// * `file_path` - The path to the file.
+    pub fn from_path(file_path: &Path) -> Result<Self, IoError> {
+        let extension = file_path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .map(|ext| ext.to_ascii_lowercase());
+        match extension.as_deref() {
+            Some("yml" | "yaml") => Ok(Self::Yaml),
+            Some("xml") => Ok(Self::Xml),
+            Some("json") => Ok(Self::Json),
+            _ => Err(IoError::InvalidFileExtension(file_path.to_path_buf())),
+        }
+    }
+}
+
+/// Deserializes a value from the content of an OpenCV `FileStorage` file.
+///
+/// The matrices are deserialized as maps with the `rows`, `cols`, `dt` and `data` keys,
+/// e.g. to an [`super::OpenCvMatrix`].
+///
+/// # Arguments
+///
+/// * `content` - The content of the file.
+/// * `format` - The format of the file.
+///
+/// # Returns
+///
+/// The deserialized value.
+pub fn from_file_storage_str<T: DeserializeOwned>(
+    content: &str,
+    format: FileStorageFormat,
+) -> Result<T, IoError> {
+    let value = parse_value(content, format)?;
+    Ok(serde_json::from_value(value)?)
+}
+
+/// Serializes a value to the content of an OpenCV `FileStorage` file.
+///
+/// The value must serialize to a map. The maps with a `type_id` of `opencv-matrix` are
+/// written as matrices.
+///
+/// # Arguments
+///
+/// * `value` - The value to serialize.
+/// * `format` - The format of the file.
+///
+/// # Returns
+///
+/// The content of the file.
+pub fn to_file_storage_string<T: Serialize>(
+    value: &T,
+    format: FileStorageFormat,
+) -> Result<String, IoError> {
+    let value = serde_json::to_value(value)?;
+    match format {
+        FileStorageFormat::Json => Ok(serde_json::to_string_pretty(&value)?),
+        FileStorageFormat::Yaml => write_yaml(&value),
+        FileStorageFormat::Xml => write_xml(&value),
+    }
+}
+
+/// Reads a value from an OpenCV `FileStorage` file.
+///
+/// The format is chosen from the extension of the file.
+///
+/// # Arguments
+///
+/// * `file_path` - The path to the `.yml`, `.yaml`, `.xml` or `.json` file.
+///
+/// # Returns
+///
+/// The deserialized value, e.g. a [`super::CameraCalibration`].
+///
+/// # Example
+///
+/// ```no_run
+/// use kornia_io::calibration::{read_file_storage, CameraCalibration};
+///
+/// let calib: CameraCalibration = read_file_storage("out_camera_data.xml").unwrap();
+/// ```
+pub fn read_file_storage<T: DeserializeOwned>(file_path: impl AsRef<Path>) -> Result<T, IoError> {
+    Ok(serde_json::from_value(read_value(file_path.as_ref())?)?)
+}
+
+/// Writes a value to an OpenCV `FileStorage` file.
+///
+/// The format is chosen from the extension of the file.
+///
+/// # Arguments
+///
+/// * `file_path` - The path to the `.yml`, `.yaml`, `.xml` or `.json` file.
+/// * `value` - The value to write, which must serialize to a map.
+pub fn write_file_storage<T: Serialize>(
+    file_path: impl AsRef<Path>,
+    value: &T,
+) -> Result<(), IoError> {
+    let file_path = file_path.as_ref();
+    let format = FileStorageFormat::from_path(file_path)?;
+    std::fs::write(file_path, to_file_storage_string(value, format)?)?;
+    Ok(())
+}
+
+// the top level map of a file
+pub(super) fn read_value(file_path: &Path) -> Result<Value, IoError> {
+    let format = FileStorageFormat::from_path(file_path)?;
+    parse_value(&read_file(file_path)?, format)
+}
+
+fn parse_value(content: &str, format: FileStorageFormat) -> Result<Value, IoError> {
+    match format {
+        FileStorageFormat::Json => Ok(serde_json::from_str(content)?),
+        FileStorageFormat::Yaml => parse_yaml(content),
+        FileStorageFormat::Xml => parse_xml(content),
+    }
+}
+
+fn invalid(msg: impl Into<String>) -> IoError {
+    IoError::InvalidFileStorage(msg.into())
+}
+
+fn is_matrix(map: &Map<String, Value>) -> bool {
+    map.get("type_id").and_then(Value::as_str) == Some(MATRIX_TYPE)
+}
+
+fn is_scalar(value: &Value) -> bool {
+    !matches!(value, Value::Array(_) | Value::Object(_))
+}
+
+// remove the `!!opencv-matrix` and `!<tag:yaml.org,2002:opencv-matrix>` tags of a line
+fn strip_yaml_tags(line: &str) -> String {
+    let mut line = line.to_string();
+    for (start, end) in [("!!opencv-", ' '), ("!<tag:yaml.org,2002:opencv-", '>')] {
+        while let Some(begin) = line.find(start) {
+            let stop = line[begin..]
+                .find(end)
+                .map_or(line.len(), |pos| begin + pos + 1);
+            line.replace_range(begin..stop, "");
+        }
+    }
+    line
+}
+
+fn parse_yaml(content: &str) -> Result<Value, IoError> {
+    let mut text = String::with_capacity(content.len());
+    for line in content.lines() {
+        // the `%YAML:1.0` directive of OpenCV is not valid YAML
+        if line.starts_with("%YAML") {
+            continue;
+        }
+        text.push_str(&strip_yaml_tags(line));
+        text.push('\n');
+    }
+
+    let value: Value = serde_yaml::from_str(&text)?;
+    if !value.is_object() {
+        return Err(invalid("expected a map at the top level"));
+    }
+    Ok(value)
+}
+
+fn yaml_scalar(value: &Value) -> String {
+    match value {
+        Value::Null => "\"\"".to_string(),
+        Value::Bool(b) => (*b as u8).to_string(),
+        Value::Number(n) => n.to_string(),
+        Value::String(s) => format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\"")),
+        Value::Array(_) | Value::Object(_) => unreachable!("not a scalar"),
+    }
+}
+
+fn write_yaml_map(out: &mut String, map: &Map<String, Value>, indent: usize) {
+    let matrix = is_matrix(map);
+    for (key, value) in map {
+        if value.is_null() || (matrix && key == "type_id") {
+            continue;
+        }
+        out.push_str(&" ".repeat(indent));
+        out.push_str(key);
+        out.push(':');
+        write_yaml_value(out, value, indent);
+    }
+}
+
+fn write_yaml_value(out: &mut String, value: &Value, indent: usize) {
+    match value {
+        Value::Object(map) if map.is_empty() => out.push_str(" {}\n"),
+        Value::Object(map) => {
+            if is_matrix(map) {
+                out.push_str(" !!opencv-matrix");
+            }
+            out.push('\n');
+            write_yaml_map(out, map, indent + 3);
+        }
+        Value::Array(items) if items.iter().all(is_scalar) => {
+            let items = items.iter().map(yaml_scalar).collect::<Vec<_>>();
+            match items.is_empty() {
+                true => out.push_str(" []\n"),
+                false => out.push_str(&format!(" [ {} ]\n", items.join(", "))),
+            }
+        }
+        Value::Array(items) => {
+            out.push('\n');
+            for item in items {
+                out.push_str(&" ".repeat(indent + 3));
+                out.push('-');
+                write_yaml_value(out, item, indent + 3);
+            }
+        }
+        scalar => {
+            out.push(' ');
+            out.push_str(&yaml_scalar(scalar));
+            out.push('\n');
+        }
+    }
+}
+
+fn write_yaml(value: &Value) -> Result<String, IoError> {
+    let Value::Object(map) = value else {
+        return Err(invalid("expected a map at the top level"));
+    };
+
+    let mut out = String::from("%YAML:1.0\n---\n");
+    write_yaml_map(&mut out, map, 0);
+    Ok(out)
+}
+
+// an element of an XML document
+struct XmlElement {
+    name: String,
+    attributes: Vec<(String, String)>,
+    children: Vec<XmlElement>,
+    text: String,
+}
+
+fn decode_xml_entities(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+fn encode_xml_entities(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+// a minimal parser of the XML subset written by the OpenCV `FileStorage`
+struct XmlParser<'a> {
+    input: &'a str,
+    pos: usize,
+}
+
+impl<'a> XmlParser<'a> {
+    fn rest(&self) -> &'a str {
+        &self.input[self.pos..]
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = self.rest();
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+
+    // skip up to and including the delimiter
+    fn skip_past(&mut self, delimiter: &str) -> Result<(), IoError> {
+        let end = self
+            .rest()
+            .find(delimiter)
+            .ok_or_else(|| invalid(format!("missing `{delimiter}` in the XML document")))?;
+        self.pos += end + delimiter.len();
+        Ok(())
+    }
+
+    // skip the declarations, comments and whitespace before an element
+    fn skip_misc(&mut self) -> Result<(), IoError> {
+        loop {
+            self.skip_whitespace();
+            if self.rest().starts_with("<?") {
+                self.skip_past("?>")?;
+            } else if self.rest().starts_with("<!--") {
+                self.skip_past("-->")?;
+            } else if self.rest().starts_with("<!") {
+                self.skip_past(">")?;
+            } else {
+                return Ok(());
+            }
+        }
+    }
+
+    fn read_name(&mut self) -> Result<String, IoError> {
+        let rest = self.rest();
+        let len = rest
+            .find(|c: char| c.is_whitespace() || c == '/' || c == '>' || c == '=')
+            .unwrap_or(rest.len());
+        if len == 0 {
+            return Err(invalid("missing XML name"));
+        }
+        self.pos += len;
+        Ok(rest[..len].to_string())
+    }
+
+    fn parse_element(&mut self) -> Result<XmlElement, IoError> {
+        if !self.rest().starts_with('<') {
+            return Err(invalid("expected an XML element"));
+        }
+        self.pos += 1;
+
+        let mut element = XmlElement {
+            name: self.read_name()?,
+            attributes: Vec::new(),
+            children: Vec::new(),
+            text: String::new(),
+        };
+
+        // the attributes up to the end of the start tag
+        loop {
+            self.skip_whitespace();
+            if self.rest().starts_with("/>") {
+                self.pos += 2;
+                return Ok(element);
+            }
+            if self.rest().starts_with('>') {
+                self.pos += 1;
+                break;
+            }
+
+            let name = self.read_name()?;
+            self.skip_whitespace();
+            let quote = self
+                .rest()
+                .strip_prefix('=')
+                .map(str::trim_start)
+                .and_then(|rest| rest.chars().next())
+                .filter(|c| *c == '"' || *c == '\'')
+                .ok_or_else(|| invalid(format!("invalid XML attribute {name}")))?;
+            self.skip_past(&quote.to_string())?;
+            let start = self.pos;
+            self.skip_past(&quote.to_string())?;
+            let value = decode_xml_entities(&self.input[start..self.pos - 1]);
+            element.attributes.push((name, value));
+        }
+
+        // the content up to the end tag
+        loop {
+            let rest = self.rest();
+            if rest.starts_with("</") {
+                self.pos += 2;
+                let name = self.read_name()?;
+                if name != element.name {
+                    return Err(invalid(format!(
+                        "expected the end of the XML element {}, found {}",
+                        element.name, name
+                    )));
+                }
+                self.skip_past(">")?;
+                return Ok(element);
+            } else if rest.starts_with("<!--") {
+                self.skip_past("-->")?;
+            } else if rest.starts_with('<') {
+                element.children.push(self.parse_element()?);
+            } else if rest.is_empty() {
+                return Err(invalid(format!(
+                    "missing the end of the XML element {}",
+                    element.name
+                )));
+            } else {
+                let len = rest.find('<').unwrap_or(rest.len());
+                element.text.push_str(&decode_xml_entities(&rest[..len]));
+                element.text.push(' ');
+                self.pos += len;
+            }
+        }
+    }
+}
+
+// split the text of an element in its values, keeping the quoted strings together
+fn xml_tokens(text: &str) -> Vec<Value> {
+    let mut tokens = Vec::new();
+    let mut rest = text.trim_start();
+    while !rest.is_empty() {
+        if let Some(quoted) = rest.strip_prefix('"') {
+            let end = quoted.find('"').unwrap_or(quoted.len());
+            tokens.push(Value::String(quoted[..end].to_string()));
+            rest = quoted.get(end + 1..).unwrap_or("");
+        } else {
+            let end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+            let token = &rest[..end];
+            let value = if let Ok(v) = token.parse::<i64>() {
+                Value::Number(v.into())
+            } else if let Some(v) = token.parse::<f64>().ok().and_then(Number::from_f64) {
+                Value::Number(v)
+            } else {
+                Value::String(token.to_string())
+            };
+            tokens.push(value);
+            rest = &rest[end..];
+        }
+        rest = rest.trim_start();
+    }
+    tokens
+}
+
+fn xml_to_value(element: &XmlElement) -> Value {
+    if element.children.is_empty() {
+        let mut tokens = xml_tokens(&element.text);
+        return match tokens.len() {
+            0 => Value::Null,
+            1 => tokens.remove(0),
+            _ => Value::Array(tokens),
+        };
+    }
+
+    // the elements of a sequence are named `_`
+    if element.children.iter().all(|child| child.name == "_") {
+        return Value::Array(element.children.iter().map(xml_to_value).collect());
+    }
+
+    let mut map = Map::new();
+    for (name, value) in &element.attributes {
+        map.insert(name.clone(), Value::String(value.clone()));
+    }
+    let matrix = is_matrix(&map);
+    for child in &element.children {
+        let value = match xml_to_value(child) {
+            // a matrix with a single element
+            value if matrix && child.name == "data" && is_scalar(&value) => {
+                Value::Array(vec![value])
+            }
+            value => value,
+        };
+        map.insert(child.name.clone(), value);
+    }
+    Value::Object(map)
+}
+
+fn parse_xml(content: &str) -> Result<Value, IoError> {
+    let mut parser = XmlParser {
+        input: content,
+        pos: 0,
+    };
+    parser.skip_misc()?;
+    let root = parser.parse_element()?;
+    if root.name != "opencv_storage" {
+        return Err(invalid(format!(
+            "expected the opencv_storage XML element, found {}",
+            root.name
+        )));
+    }
+
+    match xml_to_value(&root) {
+        Value::Null => Ok(Value::Object(Map::new())),
+        value @ Value::Object(_) => Ok(value),
+        _ => Err(invalid("expected a map at the top level")),
+    }
+}
+
+fn xml_scalar(value: &Value) -> String {
+    match value {
+        Value::Null => "\"\"".to_string(),
+        Value::Bool(b) => (*b as u8).to_string(),
+        Value::Number(n) => n.to_string(),
+        // the strings with whitespace are quoted to be read as a single value
+        Value::String(s) if s.is_empty() || s.contains(char::is_whitespace) => {
+            format!("\"{}\"", encode_xml_entities(s))
+        }
+        Value::String(s) => encode_xml_entities(s),
+        Value::Array(_) | Value::Object(_) => unreachable!("not a scalar"),
+    }
+}
+
+fn write_xml_element(out: &mut String, name: &str, value: &Value, indent: usize) {
+    let pad = " ".repeat(indent);
+    match value {
+        Value::Null => {}
+        Value::Object(map) => {
+            let matrix = is_matrix(map);
+            match matrix {
+                true => out.push_str(&format!("{pad}<{name} type_id=\"{MATRIX_TYPE}\">\n")),
+                false => out.push_str(&format!("{pad}<{name}>\n")),
+            }
+            for (key, value) in map {
+                if !(matrix && key == "type_id") {
+                    write_xml_element(out, key, value, indent + 2);
+                }
+            }
+            out.push_str(&format!("{pad}</{name}>\n"));
+        }
+        Value::Array(items) if items.iter().all(is_scalar) => {
+            let items = items.iter().map(xml_scalar).collect::<Vec<_>>();
+            out.push_str(&format!("{pad}<{name}>{}</{name}>\n", items.join(" ")));
+        }
+        Value::Array(items) => {
+            out.push_str(&format!("{pad}<{name}>\n"));
+            for item in items {
+                write_xml_element(out, "_", item, indent + 2);
+            }
+            out.push_str(&format!("{pad}</{name}>\n"));
+        }
+        scalar => out.push_str(&format!("{pad}<{name}>{}</{name}>\n", xml_scalar(scalar))),
+    }
+}
+
+fn write_xml(value: &Value) -> Result<String, IoError> {
+    let Value::Object(map) = value else {
+        return Err(invalid("expected a map at the top level"));
+    };
+
+    let mut out = String::from("<?xml version=\"1.0\"?>\n<opencv_storage>\n");
+    for (key, value) in map {
+        write_xml_element(&mut out, key, value, 0);
+    }
+    out.push_str("</opencv_storage>\n");
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::calibration::{CameraCalibration, OpenCvMatrix};
+    use kornia_imgproc::calibration::distortion::CameraDistortion;
+
+    // the output of the OpenCV camera calibration sample
+    const OPENCV_YAML: &str = r#"%YAML:1.0
+---
+calibration_time: "Thu 02 Oct 2025 10:00:00 AM CEST"
+image_width: 640
+image_height: 480
+flags: 0
+camera_matrix: !!opencv-matrix
+   rows: 3
+   cols: 3
+   dt: d
+   data: [ 5.3591573396163199e+02, 0., 3.4228315473308373e+02, 0.,
+       5.3591573396163199e+02, 2.3557082909788173e+02, 0., 0., 1. ]
+distortion_coefficients: !!opencv-matrix
+   rows: 5
+   cols: 1
+   dt: d
+   data: [ -2.6637260909660682e-01, -3.8588898922304653e-02,
+       1.7831947042852964e-03, -2.8122100441115472e-04,
+       2.3839153080878486e-01 ]
+avg_reprojection_error: 3.9259098975581364e-01
+"#;
+
+    const OPENCV_XML: &str = r#"<?xml version="1.0"?>
+<opencv_storage>
+<calibration_time>"Thu 02 Oct 2025 10:00:00 AM CEST"</calibration_time>
+<image_width>640</image_width>
+<image_height>480</image_height>
+<!-- the intrinsic parameters -->
+<camera_matrix type_id="opencv-matrix">
+  <rows>3</rows>
+  <cols>3</cols>
+  <dt>d</dt>
+  <data>
+    5.3591573396163199e+02 0. 3.4228315473308373e+02 0.
+    5.3591573396163199e+02 2.3557082909788173e+02 0. 0. 1.</data></camera_matrix>
+<distortion_coefficients type_id="opencv-matrix">
+  <rows>1</rows>
+  <cols>1</cols>
+  <dt>d</dt>
+  <data>-2.6637260909660682e-01</data></distortion_coefficients>
+</opencv_storage>
+"#;
+
+    #[test]
+    fn test_read_opencv_yaml() -> Result<(), IoError> {
+        let calib: CameraCalibration = from_file_storage_str(OPENCV_YAML, FileStorageFormat::Yaml)?;
+        assert_eq!(calib.image_size.width, 640);
+        assert_eq!(calib.intrinsic.cx, 3.4228315473308373e+02);
+        assert_eq!(calib.reprojection_error, Some(3.9259098975581364e-01));
+        let CameraDistortion::Polynomial(d) = calib.distortion else {
+            panic!("expected a polynomial distortion");
+        };
+        assert_eq!(d.k3, 2.3839153080878486e-01);
+
+        // the written file is read back
+        let content = to_file_storage_string(&calib, FileStorageFormat::Yaml)?;
+        assert!(content.starts_with("%YAML:1.0\n---\n"));
+        assert!(content.contains("camera_matrix: !!opencv-matrix\n   rows: 3\n"));
+        let calib_back: CameraCalibration =
+            from_file_storage_str(&content, FileStorageFormat::Yaml)?;
+        assert_eq!(calib_back, calib);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_opencv_xml() -> Result<(), IoError> {
+        let calib: CameraCalibration = from_file_storage_str(OPENCV_XML, FileStorageFormat::Xml)?;
+        assert_eq!(calib.image_size.height, 480);
+        assert_eq!(calib.intrinsic.fy, 535.915733961632);
+        let CameraDistortion::Polynomial(d) = calib.distortion else {
+            panic!("expected a polynomial distortion");
+        };
+        assert_eq!((d.k1, d.k2), (-0.2663726090966068, 0.0));
+
+        let content = to_file_storage_string(&calib, FileStorageFormat::Xml)?;
+        assert!(content.contains("<camera_matrix type_id=\"opencv-matrix\">"));
+        let calib_back: CameraCalibration =
+            from_file_storage_str(&content, FileStorageFormat::Xml)?;
+        assert_eq!(calib_back, calib);
+
+        // the elements must be closed
+        let truncated = &OPENCV_XML[..OPENCV_XML.len() - 20];
+        assert!(
+            from_file_storage_str::<CameraCalibration>(truncated, FileStorageFormat::Xml).is_err()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_file_storage_nested() -> Result<(), IoError> {
+        let tmp_dir = tempfile::tempdir()?;
+        let value = serde_json::json!({
+            "name": "two words",
+            "views": [
+                {"id": 0, "pose": OpenCvMatrix::new(1, 3, vec![0.5, -1.0, 2e-7])},
+                {"id": 1, "pose": OpenCvMatrix::new(1, 1, vec![3.0])},
+            ],
+            "counts": [1, 2, 3],
+        });
+
+        for ext in ["yml", "xml", "json"] {
+            let file_path = tmp_dir.path().join(format!("storage.{ext}"));
+            write_file_storage(&file_path, &value)?;
+            let value_back: Value = read_file_storage(&file_path)?;
+            assert_eq!(value_back["name"], "two words");
+            assert_eq!(value_back["counts"], serde_json::json!([1, 2, 3]));
+            let pose: OpenCvMatrix =
+                serde_json::from_value(value_back["views"][0]["pose"].clone())?;
+            assert_eq!(pose.data, vec![0.5, -1.0, 2e-7]);
+            let pose: OpenCvMatrix =
+                serde_json::from_value(value_back["views"][1]["pose"].clone())?;
+            assert_eq!(pose.to_array::<1, 1>(), Some([[3.0]]));
+        }
+
+        let file_path = tmp_dir.path().join("storage.txt");
+        assert!(matches!(
+            write_file_storage(&file_path, &value),
+            Err(IoError::InvalidFileExtension(_))
+        ));
+
+        Ok(())
+    }
+}
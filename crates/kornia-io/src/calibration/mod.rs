@@ -0,0 +1,383 @@
+/// Readers and writers of the OpenCV `FileStorage` YAML, XML and JSON files.
+pub mod file_storage;
+
+/// Calibrations of stereo pairs in the layout of the OpenCV stereo samples.
+pub mod stereo;
+
+pub use file_storage::{read_file_storage, write_file_storage, FileStorageFormat};
+pub use stereo::{read_stereo_calibration, StereoCalibration, StereoRectification};
+
+use std::path::Path;
+
+use kornia_image::ImageSize;
+use kornia_imgproc::calibration::{
+    distortion::{CameraDistortion, FisheyeDistortion, PolynomialDistortion},
+    CameraIntrinsic,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::error::IoError;
+
+/// A matrix in the layout of the OpenCV `FileStorage`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OpenCvMatrix {
+    /// The type of the node, `opencv-matrix`.
+    #[serde(default = "opencv_matrix_type")]
+    pub type_id: String,
+    /// The number of rows.
+    pub rows: usize,
+    /// The number of columns.
+    pub cols: usize,
+    /// The type of the elements, `d` for `f64` and `f` for `f32`.
+    pub dt: String,
+    /// The elements in row-major order.
+    pub data: Vec<f64>,
+}
+
+fn opencv_matrix_type() -> String {
+    "opencv-matrix".to_string()
+}
+
+impl OpenCvMatrix {
+    /// Create a matrix of `f64` elements.
+    ///
+    /// # Arguments
+    ///
+    /// * `rows` - The number of rows.
+    /// * `cols` - The number of columns.
+    /// * `data` - The `rows * cols` elements in row-major order.
+    pub fn new(rows: usize, cols: usize, data: Vec<f64>) -> Self {
+        Self {
+            type_id: opencv_matrix_type(),
+            rows,
+            cols,
+            dt: "d".to_string(),
+            data,
+        }
+    }
+
+    /// Create a matrix from its rows.
+    ///
+    /// # Arguments
+    ///
+    /// * `array` - The rows of the matrix.
+    pub fn from_array<const R: usize, const C: usize>(array: &[[f64; C]; R]) -> Self {
+        Self::new(R, C, array.iter().flatten().copied().collect())
+    }
+
+    /// Get the rows of the matrix.
+    ///
+    /// # Returns
+    ///
+    /// The rows of the matrix, or `None` if the matrix is not `R x C`.
+    pub fn to_array<const R: usize, const C: usize>(&self) -> Option<[[f64; C]; R]> {
+        if self.rows != R || self.cols != C || self.data.len() != R * C {
+            return None;
+        }
+
+        let mut array = [[0.0; C]; R];
+        for (row, data) in array.iter_mut().zip(self.data.chunks_exact(C)) {
+            row.copy_from_slice(data);
+        }
+        Some(array)
+    }
+}
+
+/// The calibration of a camera, stored with the keys of the OpenCV calibration samples.
+///
+/// The files contain `image_width`, `image_height`, the 3x3 `camera_matrix` and the
+/// `distortion_coefficients` in the OpenCV order `k1, k2, p1, p2, k3, k4, k5, k6`, or
+/// `k1, k2, k3, k4` with `fisheye_model: 1` for the fisheye cameras.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "OpenCvCalibration", into = "OpenCvCalibration")]
+pub struct CameraCalibration {
+    /// The size of the calibrated images.
+    pub image_size: ImageSize,
+    /// The intrinsic parameters of the camera.
+    pub intrinsic: CameraIntrinsic,
+    /// The lens distortion of the camera.
+    pub distortion: CameraDistortion,
+    /// The RMS reprojection error of the calibration in pixels, if known.
+    pub reprojection_error: Option<f64>,
+}
+
+// the file layout of the calibration
+#[derive(Serialize, Deserialize)]
+struct OpenCvCalibration {
+    image_width: usize,
+    image_height: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    fisheye_model: Option<i32>,
+    camera_matrix: OpenCvMatrix,
+    distortion_coefficients: OpenCvMatrix,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    avg_reprojection_error: Option<f64>,
+}
+
+// the coefficients of a distortion model, with the missing ones set to zero
+fn coefficients<const N: usize>(matrix: &OpenCvMatrix) -> Result<[f64; N], String> {
+    if matrix.data.len() > N {
+        return Err(format!(
+            "expected at most {} distortion coefficients, found {}",
+            N,
+            matrix.data.len()
+        ));
+    }
+
+    let mut d = [0.0; N];
+    d[..matrix.data.len()].copy_from_slice(&matrix.data);
+    Ok(d)
+}
+
+// the intrinsic parameters of a 3x3 camera matrix
+fn intrinsic_from_matrix(k: &OpenCvMatrix) -> Result<CameraIntrinsic, String> {
+    let [[fx, _, cx], [_, fy, cy], _] = k.to_array::<3, 3>().ok_or_else(|| {
+        format!(
+            "expected a 3x3 camera matrix, found {}x{} with {} elements",
+            k.rows,
+            k.cols,
+            k.data.len()
+        )
+    })?;
+    Ok(CameraIntrinsic { fx, fy, cx, cy })
+}
+
+fn intrinsic_to_matrix(intrinsic: &CameraIntrinsic) -> OpenCvMatrix {
+    let CameraIntrinsic { fx, fy, cx, cy } = *intrinsic;
+    OpenCvMatrix::from_array(&[[fx, 0.0, cx], [0.0, fy, cy], [0.0, 0.0, 1.0]])
+}
+
+// the distortion of the OpenCV coefficients, `k1, k2, p1, p2, k3, k4, k5, k6` or
+// `k1, k2, k3, k4` for the fisheye model
+fn distortion_from_matrix(d: &OpenCvMatrix, fisheye: bool) -> Result<CameraDistortion, String> {
+    if fisheye {
+        let [k1, k2, k3, k4] = coefficients::<4>(d)?;
+        return Ok(CameraDistortion::Fisheye(FisheyeDistortion {
+            k1,
+            k2,
+            k3,
+            k4,
+        }));
+    }
+
+    let [k1, k2, p1, p2, k3, k4, k5, k6] = coefficients::<8>(d)?;
+    Ok(CameraDistortion::Polynomial(PolynomialDistortion {
+        k1,
+        k2,
+        k3,
+        k4,
+        k5,
+        k6,
+        p1,
+        p2,
+    }))
+}
+
+// the OpenCV coefficients of a distortion and whether it is the fisheye model
+fn distortion_to_matrix(distortion: &CameraDistortion) -> (OpenCvMatrix, bool) {
+    match distortion {
+        CameraDistortion::Polynomial(d) if d.k4 == 0.0 && d.k5 == 0.0 && d.k6 == 0.0 => (
+            OpenCvMatrix::new(1, 5, vec![d.k1, d.k2, d.p1, d.p2, d.k3]),
+            false,
+        ),
+        CameraDistortion::Polynomial(d) => (
+            OpenCvMatrix::new(1, 8, vec![d.k1, d.k2, d.p1, d.p2, d.k3, d.k4, d.k5, d.k6]),
+            false,
+        ),
+        // the fisheye coefficients are a column as in `cv::fisheye::calibrate`
+        CameraDistortion::Fisheye(d) => {
+            (OpenCvMatrix::new(4, 1, vec![d.k1, d.k2, d.k3, d.k4]), true)
+        }
+    }
+}
+
+impl TryFrom<OpenCvCalibration> for CameraCalibration {
+    type Error = String;
+
+    fn try_from(calib: OpenCvCalibration) -> Result<Self, Self::Error> {
+        let fisheye = calib.fisheye_model.unwrap_or(0) != 0;
+        Ok(Self {
+            image_size: ImageSize {
+                width: calib.image_width,
+                height: calib.image_height,
+            },
+            intrinsic: intrinsic_from_matrix(&calib.camera_matrix)?,
+            distortion: distortion_from_matrix(&calib.distortion_coefficients, fisheye)?,
+            reprojection_error: calib.avg_reprojection_error,
+        })
+    }
+}
+
+impl From<CameraCalibration> for OpenCvCalibration {
+    fn from(calib: CameraCalibration) -> Self {
+        let (distortion_coefficients, fisheye) = distortion_to_matrix(&calib.distortion);
+        Self {
+            image_width: calib.image_size.width,
+            image_height: calib.image_size.height,
+            fisheye_model: fisheye.then_some(1),
+            camera_matrix: intrinsic_to_matrix(&calib.intrinsic),
+            distortion_coefficients,
+            avg_reprojection_error: calib.reprojection_error,
+        }
+    }
+}
+
+fn read_file(file_path: &Path) -> Result<String, IoError> {
+    // verify the file exists
+    if !file_path.exists() {
+        return Err(IoError::FileDoesNotExist(file_path.to_path_buf()));
+    }
+
+    Ok(std::fs::read_to_string(file_path)?)
+}
+
+/// Reads a camera calibration from a JSON file.
+///
+/// # Arguments
+///
+/// * `file_path` - The path to the JSON file.
+///
+/// # Returns
+///
+/// The calibration of the camera.
+pub fn read_calibration_json(file_path: impl AsRef<Path>) -> Result<CameraCalibration, IoError> {
+    let content = read_file(file_path.as_ref())?;
+    Ok(serde_json::from_str(&content)?)
+}
+
+/// Writes a camera calibration to a JSON file.
+///
+/// # Arguments
+///
+/// * `file_path` - The path to the JSON file.
+/// * `calibration` - The calibration of the camera.
+pub fn write_calibration_json(
+    file_path: impl AsRef<Path>,
+    calibration: &CameraCalibration,
+) -> Result<(), IoError> {
+    let content = serde_json::to_string_pretty(calibration)?;
+    std::fs::write(file_path, content)?;
+    Ok(())
+}
+
+/// Reads a camera calibration from a YAML file.
+///
+/// The files written by the OpenCV `FileStorage`, with the `%YAML:1.0` directive and the
+/// `!!opencv-matrix` tags, are supported.
+///
+/// # Arguments
+///
+/// * `file_path` - The path to the YAML file.
+///
+/// # Returns
+///
+/// The calibration of the camera.
+pub fn read_calibration_yaml(file_path: impl AsRef<Path>) -> Result<CameraCalibration, IoError> {
+    let content = read_file(file_path.as_ref())?;
+    file_storage::from_file_storage_str(&content, FileStorageFormat::Yaml)
+}
+
+/// Writes a camera calibration to a YAML file readable by the OpenCV `FileStorage`.
+///
+/// # Arguments
+///
+/// * `file_path` - The path to the YAML file.
+/// * `calibration` - The calibration of the camera.
+pub fn write_calibration_yaml(
+    file_path: impl AsRef<Path>,
+    calibration: &CameraCalibration,
+) -> Result<(), IoError> {
+    let content = file_storage::to_file_storage_string(calibration, FileStorageFormat::Yaml)?;
+    std::fs::write(file_path, content)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn calibration(distortion: CameraDistortion) -> CameraCalibration {
+        CameraCalibration {
+            image_size: ImageSize {
+                width: 640,
+                height: 480,
+            },
+            intrinsic: CameraIntrinsic {
+                fx: 500.0,
+                fy: 510.0,
+                cx: 320.5,
+                cy: 240.5,
+            },
+            distortion,
+            reprojection_error: Some(0.25),
+        }
+    }
+
+    #[test]
+    fn test_calibration_json() -> Result<(), IoError> {
+        let tmp_dir = tempfile::tempdir()?;
+        let file_path = tmp_dir.path().join("calibration.json");
+
+        let calib = calibration(CameraDistortion::Polynomial(PolynomialDistortion {
+            k1: -0.1,
+            k2: 0.01,
+            p1: 0.001,
+            p2: -0.002,
+            ..Default::default()
+        }));
+        write_calibration_json(&file_path, &calib)?;
+        assert_eq!(read_calibration_json(&file_path)?, calib);
+
+        let content = std::fs::read_to_string(&file_path)?;
+        assert!(content.contains("\"type_id\": \"opencv-matrix\""));
+        assert!(content.contains("\"image_width\": 640"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_calibration_yaml() -> Result<(), IoError> {
+        let tmp_dir = tempfile::tempdir()?;
+        let file_path = tmp_dir.path().join("calibration.yaml");
+
+        let calib = calibration(CameraDistortion::Fisheye(FisheyeDistortion {
+            k1: 0.1,
+            k2: 0.2,
+            k3: 0.3,
+            k4: 0.4,
+        }));
+        write_calibration_yaml(&file_path, &calib)?;
+        assert_eq!(read_calibration_yaml(&file_path)?, calib);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_calibration_opencv_json() -> Result<(), IoError> {
+        let content = r#"{
+            "image_width": 1280,
+            "image_height": 720,
+            "camera_matrix": {
+                "type_id": "opencv-matrix", "rows": 3, "cols": 3, "dt": "d",
+                "data": [900.0, 0.0, 640.0, 0.0, 905.0, 360.0, 0.0, 0.0, 1.0]
+            },
+            "distortion_coefficients": {
+                "type_id": "opencv-matrix", "rows": 1, "cols": 4, "dt": "d",
+                "data": [-0.2, 0.05, 0.001, 0.002]
+            }
+        }"#;
+        let calib: CameraCalibration = serde_json::from_str(content)?;
+        assert_eq!(calib.intrinsic.fy, 905.0);
+        assert_eq!(calib.reprojection_error, None);
+        let CameraDistortion::Polynomial(d) = calib.distortion else {
+            panic!("expected a polynomial distortion");
+        };
+        assert_eq!((d.k2, d.p2, d.k3), (0.05, 0.002, 0.0));
+
+        // the camera matrix must be 3x3
+        let invalid = content.replace("\"rows\": 3, \"cols\": 3", "\"rows\": 1, \"cols\": 9");
+        assert!(serde_json::from_str::<CameraCalibration>(&invalid).is_err());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,326 @@
+use std::path::Path;
+
+use kornia_image::ImageSize;
+use kornia_imgproc::calibration::{distortion::CameraDistortion, CameraExtrinsic, CameraIntrinsic};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use super::{
+    distortion_from_matrix, distortion_to_matrix, file_storage::read_value, intrinsic_from_matrix,
+    intrinsic_to_matrix, OpenCvMatrix,
+};
+use crate::error::IoError;
+
+/// The rectification of a stereo pair, as computed by `cv::stereoRectify`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StereoRectification {
+    /// The rotation of the left camera to the rectified frame.
+    pub r1: [[f64; 3]; 3],
+    /// The rotation of the right camera to the rectified frame.
+    pub r2: [[f64; 3]; 3],
+    /// The 3x4 projection matrix of the rectified left camera.
+    pub p1: [[f64; 4]; 3],
+    /// The 3x4 projection matrix of the rectified right camera.
+    pub p2: [[f64; 4]; 3],
+    /// The 4x4 matrix mapping the disparities to 3D points.
+    pub q: [[f64; 4]; 4],
+}
+
+/// The calibration of a stereo pair, stored with the keys of the OpenCV stereo samples.
+///
+/// The files contain the camera matrices `M1` and `M2`, the distortion coefficients `D1`
+/// and `D2`, the rotation `R` and the translation `T` between the cameras, and optionally
+/// the rectification `R1`, `R2`, `P1`, `P2` and `Q`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "OpenCvStereoCalibration", into = "OpenCvStereoCalibration")]
+pub struct StereoCalibration {
+    /// The size of the calibrated images, if known.
+    pub image_size: Option<ImageSize>,
+    /// The intrinsic parameters of the left camera.
+    pub left_intrinsic: CameraIntrinsic,
+    /// The lens distortion of the left camera.
+    pub left_distortion: CameraDistortion,
+    /// The intrinsic parameters of the right camera.
+    pub right_intrinsic: CameraIntrinsic,
+    /// The lens distortion of the right camera.
+    pub right_distortion: CameraDistortion,
+    /// The transformation from the left to the right camera frame, `x_right = R x_left + T`.
+    pub extrinsic: CameraExtrinsic,
+    /// The rectification of the pair, if computed.
+    pub rectification: Option<StereoRectification>,
+}
+
+// the file layout of the stereo calibration
+#[allow(non_snake_case)]
+#[derive(Serialize, Deserialize)]
+struct OpenCvStereoCalibration {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    image_width: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    image_height: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    fisheye_model: Option<i32>,
+    M1: OpenCvMatrix,
+    D1: OpenCvMatrix,
+    M2: OpenCvMatrix,
+    D2: OpenCvMatrix,
+    R: OpenCvMatrix,
+    T: OpenCvMatrix,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    R1: Option<OpenCvMatrix>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    R2: Option<OpenCvMatrix>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    P1: Option<OpenCvMatrix>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    P2: Option<OpenCvMatrix>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    Q: Option<OpenCvMatrix>,
+}
+
+fn array<const R: usize, const C: usize>(
+    matrix: &OpenCvMatrix,
+    name: &str,
+) -> Result<[[f64; C]; R], String> {
+    matrix.to_array().ok_or_else(|| {
+        format!(
+            "expected {} to be {}x{}, found {}x{}",
+            name, R, C, matrix.rows, matrix.cols
+        )
+    })
+}
+
+fn rectification(calib: &OpenCvStereoCalibration) -> Result<Option<StereoRectification>, String> {
+    let (Some(r1), Some(r2), Some(p1), Some(p2), Some(q)) =
+        (&calib.R1, &calib.R2, &calib.P1, &calib.P2, &calib.Q)
+    else {
+        return Ok(None);
+    };
+
+    Ok(Some(StereoRectification {
+        r1: array(r1, "R1")?,
+        r2: array(r2, "R2")?,
+        p1: array(p1, "P1")?,
+        p2: array(p2, "P2")?,
+        q: array(q, "Q")?,
+    }))
+}
+
+impl TryFrom<OpenCvStereoCalibration> for StereoCalibration {
+    type Error = String;
+
+    fn try_from(calib: OpenCvStereoCalibration) -> Result<Self, Self::Error> {
+        let fisheye = calib.fisheye_model.unwrap_or(0) != 0;
+
+        // the translation is stored as a column or a row
+        let translation: [f64; 3] = calib
+            .T
+            .data
+            .as_slice()
+            .try_into()
+            .map_err(|_| format!("expected 3 elements in T, found {}", calib.T.data.len()))?;
+
+        let image_size = match (calib.image_width, calib.image_height) {
+            (Some(width), Some(height)) => Some(ImageSize { width, height }),
+            _ => None,
+        };
+
+        Ok(Self {
+            image_size,
+            left_intrinsic: intrinsic_from_matrix(&calib.M1)?,
+            left_distortion: distortion_from_matrix(&calib.D1, fisheye)?,
+            right_intrinsic: intrinsic_from_matrix(&calib.M2)?,
+            right_distortion: distortion_from_matrix(&calib.D2, fisheye)?,
+            extrinsic: CameraExtrinsic {
+                rotation: array(&calib.R, "R")?,
+                translation,
+            },
+            rectification: rectification(&calib)?,
+        })
+    }
+}
+
+impl From<StereoCalibration> for OpenCvStereoCalibration {
+    fn from(calib: StereoCalibration) -> Self {
+        let (d1, fisheye1) = distortion_to_matrix(&calib.left_distortion);
+        let (d2, fisheye2) = distortion_to_matrix(&calib.right_distortion);
+        let rect = calib.rectification;
+        Self {
+            image_width: calib.image_size.map(|size| size.width),
+            image_height: calib.image_size.map(|size| size.height),
+            fisheye_model: (fisheye1 || fisheye2).then_some(1),
+            M1: intrinsic_to_matrix(&calib.left_intrinsic),
+            D1: d1,
+            M2: intrinsic_to_matrix(&calib.right_intrinsic),
+            D2: d2,
+            R: OpenCvMatrix::from_array(&calib.extrinsic.rotation),
+            T: OpenCvMatrix::new(3, 1, calib.extrinsic.translation.to_vec()),
+            R1: rect.map(|rect| OpenCvMatrix::from_array(&rect.r1)),
+            R2: rect.map(|rect| OpenCvMatrix::from_array(&rect.r2)),
+            P1: rect.map(|rect| OpenCvMatrix::from_array(&rect.p1)),
+            P2: rect.map(|rect| OpenCvMatrix::from_array(&rect.p2)),
+            Q: rect.map(|rect| OpenCvMatrix::from_array(&rect.q)),
+        }
+    }
+}
+
+/// Reads a stereo calibration from one or more OpenCV `FileStorage` files.
+///
+/// The keys of the files are merged, e.g. the `intrinsics.yml` and `extrinsics.yml`
+/// written by the OpenCV stereo calibration sample. Use [`super::write_file_storage`] to
+/// write the calibration to a single file.
+///
+/// # Arguments
+///
+/// * `file_paths` - The paths to the `.yml`, `.yaml`, `.xml` or `.json` files.
+///
+/// # Returns
+///
+/// The calibration of the stereo pair.
+///
+/// # Example
+///
+/// ```no_run
+/// use kornia_io::calibration::read_stereo_calibration;
+///
+/// let calib = read_stereo_calibration(&["intrinsics.yml", "extrinsics.yml"]).unwrap();
+/// ```
+pub fn read_stereo_calibration(
+    file_paths: &[impl AsRef<Path>],
+) -> Result<StereoCalibration, IoError> {
+    let mut merged = Map::new();
+    for file_path in file_paths {
+        if let Value::Object(map) = read_value(file_path.as_ref())? {
+            merged.extend(map);
+        }
+    }
+
+    Ok(serde_json::from_value(Value::Object(merged))?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::calibration::write_file_storage;
+    use kornia_imgproc::calibration::distortion::PolynomialDistortion;
+
+    const INTRINSICS: &str = r#"%YAML:1.0
+---
+M1: !!opencv-matrix
+   rows: 3
+   cols: 3
+   dt: d
+   data: [ 5.3e+02, 0., 3.3e+02, 0., 5.3e+02, 2.4e+02, 0., 0., 1. ]
+D1: !!opencv-matrix
+   rows: 1
+   cols: 5
+   dt: d
+   data: [ -2.8e-01, 8.0e-02, 1.0e-03, -1.0e-04, 0. ]
+M2: !!opencv-matrix
+   rows: 3
+   cols: 3
+   dt: d
+   data: [ 5.4e+02, 0., 3.2e+02, 0., 5.4e+02, 2.5e+02, 0., 0., 1. ]
+D2: !!opencv-matrix
+   rows: 1
+   cols: 5
+   dt: d
+   data: [ -2.9e-01, 1.0e-01, 0., 0., -2.0e-02 ]
+"#;
+
+    const EXTRINSICS: &str = r#"<?xml version="1.0"?>
+<opencv_storage>
+<R type_id="opencv-matrix">
+  <rows>3</rows>
+  <cols>3</cols>
+  <dt>d</dt>
+  <data>
+    1. 0. 0. 0. 1. 0. 0. 0. 1.</data></R>
+<T type_id="opencv-matrix">
+  <rows>3</rows>
+  <cols>1</cols>
+  <dt>d</dt>
+  <data>
+    -3.3e+00 4.0e-02 -1.0e-02</data></T>
+</opencv_storage>
+"#;
+
+    #[test]
+    fn test_read_stereo_calibration() -> Result<(), IoError> {
+        let tmp_dir = tempfile::tempdir()?;
+        let intrinsics_path = tmp_dir.path().join("intrinsics.yml");
+        let extrinsics_path = tmp_dir.path().join("extrinsics.xml");
+        std::fs::write(&intrinsics_path, INTRINSICS)?;
+        std::fs::write(&extrinsics_path, EXTRINSICS)?;
+
+        let calib = read_stereo_calibration(&[&intrinsics_path, &extrinsics_path])?;
+        assert_eq!(calib.image_size, None);
+        assert_eq!(calib.right_intrinsic.cy, 2.5e+02);
+        assert_eq!(calib.extrinsic.translation, [-3.3, 4.0e-02, -1.0e-02]);
+        assert_eq!(calib.rectification, None);
+        let CameraDistortion::Polynomial(PolynomialDistortion { k3, .. }) = calib.right_distortion
+        else {
+            panic!("expected a polynomial distortion");
+        };
+        assert_eq!(k3, -2.0e-02);
+
+        // the extrinsics alone are not a calibration
+        assert!(read_stereo_calibration(&[&extrinsics_path]).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_write_stereo_calibration() -> Result<(), IoError> {
+        let tmp_dir = tempfile::tempdir()?;
+        let intrinsic = CameraIntrinsic {
+            fx: 500.0,
+            fy: 500.0,
+            cx: 320.0,
+            cy: 240.0,
+        };
+        let identity = [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]];
+        let calib = StereoCalibration {
+            image_size: Some(ImageSize {
+                width: 640,
+                height: 480,
+            }),
+            left_intrinsic: intrinsic,
+            left_distortion: CameraDistortion::Polynomial(Default::default()),
+            right_intrinsic: intrinsic,
+            right_distortion: CameraDistortion::Polynomial(Default::default()),
+            extrinsic: CameraExtrinsic {
+                rotation: identity,
+                translation: [-0.1, 0.0, 0.0],
+            },
+            rectification: Some(StereoRectification {
+                r1: identity,
+                r2: identity,
+                p1: [
+                    [500.0, 0.0, 320.0, 0.0],
+                    [0.0, 500.0, 240.0, 0.0],
+                    [0.0, 0.0, 1.0, 0.0],
+                ],
+                p2: [
+                    [500.0, 0.0, 320.0, -50.0],
+                    [0.0, 500.0, 240.0, 0.0],
+                    [0.0, 0.0, 1.0, 0.0],
+                ],
+                q: [
+                    [1.0, 0.0, 0.0, -320.0],
+                    [0.0, 1.0, 0.0, -240.0],
+                    [0.0, 0.0, 0.0, 500.0],
+                    [0.0, 0.0, 10.0, 0.0],
+                ],
+            }),
+        };
+
+        for ext in ["yml", "xml"] {
+            let file_path = tmp_dir.path().join(format!("stereo.{ext}"));
+            write_file_storage(&file_path, &calib)?;
+            assert_eq!(read_stereo_calibration(&[&file_path])?, calib);
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,419 @@
+use std::path::Path;
+
+use kornia_image::{Image, ImageSize};
+
+use crate::error::IoError;
+use crate::ifd::TiffReader;
+
+// TIFF/EP and DNG tags used to locate and describe the raw data.
+const TAG_NEW_SUBFILE_TYPE: u16 = 254;
+const TAG_IMAGE_WIDTH: u16 = 256;
+const TAG_IMAGE_LENGTH: u16 = 257;
+const TAG_BITS_PER_SAMPLE: u16 = 258;
+const TAG_COMPRESSION: u16 = 259;
+const TAG_PHOTOMETRIC_INTERPRETATION: u16 = 262;
+const TAG_STRIP_OFFSETS: u16 = 273;
+const TAG_SAMPLES_PER_PIXEL: u16 = 277;
+const TAG_STRIP_BYTE_COUNTS: u16 = 279;
+const TAG_SUB_IFDS: u16 = 330;
+const TAG_CFA_REPEAT_PATTERN_DIM: u16 = 33421;
+const TAG_CFA_PATTERN: u16 = 33422;
+const TAG_DNG_VERSION: u16 = 50706;
+const TAG_BLACK_LEVEL: u16 = 50714;
+const TAG_WHITE_LEVEL: u16 = 50717;
+const TAG_AS_SHOT_NEUTRAL: u16 = 50728;
+
+// The photometric interpretation of the color filter array data.
+const PHOTOMETRIC_CFA: u32 = 32803;
+
+/// The layout of the 2x2 color filter array of a Bayer sensor.
+///
+/// The name lists the colors of the top-left 2x2 block in row-major order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CfaPattern {
+    /// Red, green / green, blue.
+    Rggb,
+    /// Blue, green / green, red.
+    Bggr,
+    /// Green, red / blue, green.
+    Grbg,
+    /// Green, blue / red, green.
+    Gbrg,
+}
+
+impl CfaPattern {
+    /// Create a pattern from the DNG color indices (0 = red, 1 = green, 2 = blue).
+    ///
+    /// # Arguments
+    ///
+    /// * `colors` - The colors of the top-left 2x2 block in row-major order.
+    ///
+    /// # Returns
+    ///
+    /// The pattern or `None` if the colors do not form a Bayer pattern.
+    pub fn from_colors(colors: [u8; 4]) -> Option<Self> {
+        match colors {
+            [0, 1, 1, 2] => Some(Self::Rggb),
+            [2, 1, 1, 0] => Some(Self::Bggr),
+            [1, 0, 2, 1] => Some(Self::Grbg),
+            [1, 2, 0, 1] => Some(Self::Gbrg),
+            _ => None,
+        }
+    }
+}
+
+/// The metadata needed to develop the raw data of a DNG file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DngMetadata {
+    /// The layout of the color filter array.
+    pub cfa_pattern: CfaPattern,
+    /// The number of bits per sample of the raw data.
+    pub bits_per_sample: u16,
+    /// The zero light encoding level of the sensor.
+    pub black_level: f32,
+    /// The fully saturated encoding level of the sensor.
+    pub white_level: u32,
+    /// The neutral white balance in camera space (r, g, b) as shot, if available.
+    pub as_shot_neutral: Option<[f32; 3]>,
+}
+
+/// Read the Bayer data of a DNG file.
+///
+/// Only uncompressed, strip based raw data with 8 or 16 bits per sample is supported.
+/// The raw image is searched in the main image directory and its sub-directories.
+///
+/// # Arguments
+///
+/// * `file_path` - The path to the DNG file.
+///
+/// # Returns
+///
+/// A single channel image with the raw sensor values and the metadata to develop it.
+pub fn read_raw_dng(file_path: impl AsRef<Path>) -> Result<(Image<u16, 1>, DngMetadata), IoError> {
+    // verify the file exists
+    let file_path = file_path.as_ref();
+    if !file_path.exists() {
+        return Err(IoError::FileDoesNotExist(file_path.to_path_buf()));
+    }
+
+    // verify the file extension
+    if file_path
+        .extension()
+        .map_or(true, |ext| !ext.eq_ignore_ascii_case("dng"))
+    {
+        return Err(IoError::InvalidFileExtension(file_path.to_path_buf()));
+    }
+
+    let data = std::fs::read(file_path)?;
+
+    decode_raw_dng(&data)
+}
+
+/// Decode the Bayer data of a DNG file from its raw bytes.
+///
+/// # Arguments
+///
+/// * `data` - The raw bytes of the DNG file.
+///
+/// # Returns
+///
+/// A single channel image with the raw sensor values and the metadata to develop it.
+pub fn decode_raw_dng(data: &[u8]) -> Result<(Image<u16, 1>, DngMetadata), IoError> {
+    let tiff = TiffReader::new(data).ok_or_else(|| dng_error("invalid TIFF header"))?;
+
+    let truncated = || dng_error("truncated image file directory");
+    let root = tiff
+        .first_ifd_offset()
+        .and_then(|offset| tiff.read_ifd(offset))
+        .ok_or_else(truncated)?;
+    if root.get(TAG_DNG_VERSION).is_none() {
+        return Err(dng_error("missing DNG version tag"));
+    }
+
+    // the raw data is either in the main directory or in one of its sub-directories
+    let mut raw_ifd = None;
+    for ifd in std::iter::once(Some(root.clone())).chain(
+        tiff.values_u32(&root, TAG_SUB_IFDS)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|offset| tiff.read_ifd(offset as usize)),
+    ) {
+        let ifd = ifd.ok_or_else(truncated)?;
+        let is_main = tiff.value_u32(&ifd, TAG_NEW_SUBFILE_TYPE).unwrap_or(0) == 0;
+        let is_cfa = tiff.value_u32(&ifd, TAG_PHOTOMETRIC_INTERPRETATION) == Some(PHOTOMETRIC_CFA);
+        if is_main && is_cfa {
+            raw_ifd = Some(ifd);
+            break;
+        }
+    }
+    let ifd = raw_ifd.ok_or_else(|| dng_error("no CFA image found"))?;
+
+    let required = |tag: u16| {
+        tiff.value_u32(&ifd, tag)
+            .ok_or_else(|| dng_error(format!("missing tag {tag}")))
+    };
+
+    let width = required(TAG_IMAGE_WIDTH)? as usize;
+    let height = required(TAG_IMAGE_LENGTH)? as usize;
+    let bits_per_sample = required(TAG_BITS_PER_SAMPLE)? as u16;
+
+    if tiff.value_u32(&ifd, TAG_COMPRESSION).unwrap_or(1) != 1 {
+        return Err(dng_error("only uncompressed raw data is supported"));
+    }
+    if tiff.value_u32(&ifd, TAG_SAMPLES_PER_PIXEL).unwrap_or(1) != 1 {
+        return Err(dng_error("only single sample CFA data is supported"));
+    }
+
+    let cfa_pattern = {
+        let dim = tiff.values_u32(&ifd, TAG_CFA_REPEAT_PATTERN_DIM);
+        let colors = tiff.values_u32(&ifd, TAG_CFA_PATTERN);
+        match (dim.as_deref(), colors.as_deref()) {
+            (Some([2, 2]), Some(&[c0, c1, c2, c3])) => {
+                CfaPattern::from_colors([c0 as u8, c1 as u8, c2 as u8, c3 as u8])
+            }
+            _ => None,
+        }
+        .ok_or_else(|| dng_error("unsupported CFA pattern"))?
+    };
+
+    let black_level = tiff
+        .values_f64(&ifd, TAG_BLACK_LEVEL)
+        .and_then(|v| v.first().copied())
+        .unwrap_or(0.0) as f32;
+
+    let white_level = tiff
+        .value_u32(&ifd, TAG_WHITE_LEVEL)
+        .unwrap_or((1u32 << bits_per_sample.min(16)) - 1);
+
+    let as_shot_neutral =
+        tiff.values_f64(&root, TAG_AS_SHOT_NEUTRAL)
+            .and_then(|v| match v.as_slice() {
+                &[r, g, b] => Some([r as f32, g as f32, b as f32]),
+                _ => None,
+            });
+
+    // gather the strips into a contiguous buffer
+    let offsets = tiff
+        .values_u32(&ifd, TAG_STRIP_OFFSETS)
+        .ok_or_else(|| dng_error("only strip based raw data is supported"))?;
+    let byte_counts = tiff
+        .values_u32(&ifd, TAG_STRIP_BYTE_COUNTS)
+        .ok_or_else(|| dng_error("missing strip byte counts"))?;
+
+    let mut raw = Vec::new();
+    for (&offset, &count) in offsets.iter().zip(byte_counts.iter()) {
+        let strip = data
+            .get(offset as usize..offset as usize + count as usize)
+            .ok_or_else(|| dng_error("strip out of bounds"))?;
+        raw.extend_from_slice(strip);
+    }
+
+    let num_pixels = width * height;
+    let pixels: Vec<u16> = match bits_per_sample {
+        8 => raw.iter().take(num_pixels).map(|&v| v as u16).collect(),
+        16 => raw
+            .chunks_exact(2)
+            .take(num_pixels)
+            .map(|b| tiff.u16_from_bytes([b[0], b[1]]))
+            .collect(),
+        bits => return Err(dng_error(format!("unsupported bits per sample: {bits}"))),
+    };
+
+    if pixels.len() != num_pixels {
+        return Err(IoError::InvalidBufferSize(pixels.len(), num_pixels));
+    }
+
+    let image = Image::new(ImageSize { width, height }, pixels)?;
+
+    let metadata = DngMetadata {
+        cfa_pattern,
+        bits_per_sample,
+        black_level,
+        white_level,
+        as_shot_neutral,
+    };
+
+    Ok((image, metadata))
+}
+
+fn dng_error(msg: impl Into<String>) -> IoError {
+    IoError::DngDecodeError(msg.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // a tag value to be written in the test file
+    enum Value {
+        Short(Vec<u16>),
+        Long(Vec<u32>),
+        Byte(Vec<u8>),
+        Rational(Vec<(u32, u32)>),
+    }
+
+    // build a minimal little or big endian DNG with a thumbnail in the main
+    // directory and the raw data in a sub-directory
+    fn build_dng(raw: &[u16], width: u32, height: u32, little_endian: bool) -> Vec<u8> {
+        let u16b = |v: u16| {
+            if little_endian {
+                v.to_le_bytes()
+            } else {
+                v.to_be_bytes()
+            }
+        };
+        let u32b = |v: u32| {
+            if little_endian {
+                v.to_le_bytes()
+            } else {
+                v.to_be_bytes()
+            }
+        };
+
+        let write_ifd = |buf: &mut Vec<u8>, entries: Vec<(u16, Value)>| {
+            let ifd_start = buf.len();
+            let mut extra = Vec::new();
+            let extra_start = ifd_start + 2 + entries.len() * 12 + 4;
+            buf.extend_from_slice(&u16b(entries.len() as u16));
+            for (tag, value) in entries {
+                let (field_type, count, bytes): (u16, usize, Vec<u8>) = match value {
+                    Value::Short(v) => (3, v.len(), v.iter().flat_map(|&x| u16b(x)).collect()),
+                    Value::Long(v) => (4, v.len(), v.iter().flat_map(|&x| u32b(x)).collect()),
+                    Value::Byte(v) => (1, v.len(), v),
+                    Value::Rational(v) => (
+                        5,
+                        v.len(),
+                        v.iter()
+                            .flat_map(|&(n, d)| [u32b(n), u32b(d)].concat())
+                            .collect(),
+                    ),
+                };
+                buf.extend_from_slice(&u16b(tag));
+                buf.extend_from_slice(&u16b(field_type));
+                buf.extend_from_slice(&u32b(count as u32));
+                if bytes.len() <= 4 {
+                    let mut inline = bytes.clone();
+                    inline.resize(4, 0);
+                    buf.extend_from_slice(&inline);
+                } else {
+                    buf.extend_from_slice(&u32b((extra_start + extra.len()) as u32));
+                    extra.extend_from_slice(&bytes);
+                }
+            }
+            buf.extend_from_slice(&u32b(0));
+            buf.extend_from_slice(&extra);
+        };
+
+        let mut buf = Vec::new();
+        buf.extend_from_slice(if little_endian { b"II" } else { b"MM" });
+        buf.extend_from_slice(&u16b(42));
+        buf.extend_from_slice(&u32b(8));
+
+        // the raw data is placed after the two directories, reserve space for them
+        let raw_offset = 1024u32;
+        let sub_ifd_offset = 512u32;
+
+        write_ifd(
+            &mut buf,
+            vec![
+                (TAG_NEW_SUBFILE_TYPE, Value::Long(vec![1])),
+                (TAG_PHOTOMETRIC_INTERPRETATION, Value::Short(vec![2])),
+                (TAG_SUB_IFDS, Value::Long(vec![sub_ifd_offset])),
+                (TAG_DNG_VERSION, Value::Byte(vec![1, 4, 0, 0])),
+                (
+                    TAG_AS_SHOT_NEUTRAL,
+                    Value::Rational(vec![(1, 2), (1, 1), (2, 3)]),
+                ),
+            ],
+        );
+        buf.resize(sub_ifd_offset as usize, 0);
+
+        write_ifd(
+            &mut buf,
+            vec![
+                (TAG_NEW_SUBFILE_TYPE, Value::Long(vec![0])),
+                (TAG_IMAGE_WIDTH, Value::Long(vec![width])),
+                (TAG_IMAGE_LENGTH, Value::Long(vec![height])),
+                (TAG_BITS_PER_SAMPLE, Value::Short(vec![16])),
+                (TAG_COMPRESSION, Value::Short(vec![1])),
+                (
+                    TAG_PHOTOMETRIC_INTERPRETATION,
+                    Value::Short(vec![PHOTOMETRIC_CFA as u16]),
+                ),
+                (TAG_STRIP_OFFSETS, Value::Long(vec![raw_offset])),
+                (TAG_SAMPLES_PER_PIXEL, Value::Short(vec![1])),
+                (
+                    TAG_STRIP_BYTE_COUNTS,
+                    Value::Long(vec![raw.len() as u32 * 2]),
+                ),
+                (TAG_CFA_REPEAT_PATTERN_DIM, Value::Short(vec![2, 2])),
+                (TAG_CFA_PATTERN, Value::Byte(vec![1, 2, 0, 1])),
+                (TAG_BLACK_LEVEL, Value::Short(vec![64])),
+                (TAG_WHITE_LEVEL, Value::Short(vec![1023])),
+            ],
+        );
+        buf.resize(raw_offset as usize, 0);
+
+        buf.extend(raw.iter().flat_map(|&v| u16b(v)));
+        buf
+    }
+
+    #[test]
+    fn test_decode_raw_dng() -> Result<(), IoError> {
+        let raw = (0..12).map(|v| v * 80 + 64).collect::<Vec<u16>>();
+
+        for little_endian in [true, false] {
+            let data = build_dng(&raw, 4, 3, little_endian);
+            let (image, metadata) = decode_raw_dng(&data)?;
+
+            assert_eq!(image.cols(), 4);
+            assert_eq!(image.rows(), 3);
+            assert_eq!(image.as_slice(), raw.as_slice());
+
+            assert_eq!(metadata.cfa_pattern, CfaPattern::Gbrg);
+            assert_eq!(metadata.bits_per_sample, 16);
+            assert_eq!(metadata.black_level, 64.0);
+            assert_eq!(metadata.white_level, 1023);
+
+            let neutral = metadata.as_shot_neutral.expect("missing as shot neutral");
+            assert!((neutral[0] - 0.5).abs() < 1e-6);
+            assert!((neutral[1] - 1.0).abs() < 1e-6);
+            assert!((neutral[2] - 2.0 / 3.0).abs() < 1e-6);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_raw_dng() -> Result<(), IoError> {
+        let tmp_dir = tempfile::tempdir()?;
+        let file_path = tmp_dir.path().join("raw.dng");
+
+        let raw = vec![100u16; 4 * 2];
+        std::fs::write(&file_path, build_dng(&raw, 4, 2, true))?;
+
+        let (image, _) = read_raw_dng(&file_path)?;
+        assert_eq!(image.as_slice(), raw.as_slice());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_decode_raw_dng_invalid() {
+        assert!(matches!(
+            decode_raw_dng(b"not a dng"),
+            Err(IoError::DngDecodeError(_))
+        ));
+
+        // a plain tiff without the dng version tag
+        let mut tiff = b"II".to_vec();
+        tiff.extend_from_slice(&42u16.to_le_bytes());
+        tiff.extend_from_slice(&8u32.to_le_bytes());
+        tiff.extend_from_slice(&0u16.to_le_bytes());
+        tiff.extend_from_slice(&0u32.to_le_bytes());
+        assert!(matches!(
+            decode_raw_dng(&tiff),
+            Err(IoError::DngDecodeError(_))
+        ));
+    }
+}
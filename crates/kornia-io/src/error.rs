@@ -28,6 +28,11 @@ pub enum IoError {
     #[error(transparent)]
     JpegTurboError(#[from] crate::jpegturbo::JpegTurboError),
 
+    /// Error when an asynchronous task fails to complete.
+    #[cfg(feature = "tokio")]
+    #[error(transparent)]
+    AsyncTaskError(#[from] tokio::task::JoinError),
+
     /// Error to decode the JPEG image.
     #[error(transparent)]
     JpegDecodingError(#[from] zune_jpeg::errors::DecodeErrors),
@@ -44,6 +49,16 @@ pub enum IoError {
     #[error(transparent)]
     ImageDecodeError(#[from] image::ImageError),
 
+    /// Error to decode the TIFF image.
+    #[cfg(feature = "tiff-tiles")]
+    #[error(transparent)]
+    TiffError(#[from] tiff::TiffError),
+
+    /// Error when the layout of the TIFF image is not supported.
+    #[cfg(feature = "tiff-tiles")]
+    #[error("Unsupported TIFF layout: {0}")]
+    UnsupportedTiffLayout(String),
+
     /// Error to encode the PNG image.
     #[error("Failed to encode the png image. {0}")]
     PngEncodingError(String),
@@ -51,4 +66,45 @@ pub enum IoError {
     /// Error to decode the PNG image.
     #[error("Failed to decode the png image. {0}")]
     PngDecodeError(String),
+
+    /// Error when the header of the image file is not valid.
+    #[error("Invalid image header. {0}")]
+    InvalidImageHeader(String),
+
+    /// Error to decode the DNG raw image.
+    #[error("Failed to decode the dng image. {0}")]
+    DngDecodeError(String),
+
+    /// Error when the number of color and depth frames do not match.
+    #[error("The number of color frames {0} does not match the number of depth frames {1}")]
+    FrameCountMismatch(usize, usize),
+
+    /// Error when a ROS 2 message cannot be converted.
+    #[cfg(feature = "ros2")]
+    #[error("Invalid ROS 2 message. {0}")]
+    InvalidRosMessage(String),
+
+    /// Error to serialize or deserialize a JSON file.
+    #[cfg(feature = "serde")]
+    #[error(transparent)]
+    JsonError(#[from] serde_json::Error),
+
+    /// Error to serialize or deserialize a YAML file.
+    #[cfg(feature = "serde")]
+    #[error(transparent)]
+    YamlError(#[from] serde_yaml::Error),
+
+    /// Error when an OpenCV `FileStorage` file is not valid.
+    #[cfg(feature = "serde")]
+    #[error("Invalid OpenCV FileStorage file. {0}")]
+    InvalidFileStorage(String),
+
+    /// Error when a window of the viewer cannot be created or drawn.
+    #[cfg(feature = "viz")]
+    #[error("Viewer error. {0}")]
+    ViewerError(String),
+
+    /// Error when a frame is pushed to a stream that does not exist.
+    #[error("Invalid stream index {0} for {1} streams")]
+    InvalidStreamIndex(usize, usize),
 }
@@ -0,0 +1,596 @@
+use kornia_image::{Image, ImageSize};
+use kornia_imgproc::calibration::CameraIntrinsic;
+
+use crate::error::IoError;
+use crate::ifd::{IfdValue, TiffReader, TiffWriter};
+
+/// The EXIF orientation tag identifier.
+const EXIF_ORIENTATION_TAG: u16 = 0x0112;
+
+// EXIF tags of the main directory.
+const EXIF_MAKE_TAG: u16 = 0x010F;
+const EXIF_MODEL_TAG: u16 = 0x0110;
+const EXIF_DATE_TIME_TAG: u16 = 0x0132;
+const EXIF_IFD_POINTER_TAG: u16 = 0x8769;
+
+// EXIF tags of the Exif sub-directory.
+const EXIF_DATE_TIME_ORIGINAL_TAG: u16 = 0x9003;
+const EXIF_FOCAL_LENGTH_TAG: u16 = 0x920A;
+const EXIF_FOCAL_PLANE_X_RESOLUTION_TAG: u16 = 0xA20E;
+const EXIF_FOCAL_PLANE_Y_RESOLUTION_TAG: u16 = 0xA20F;
+const EXIF_FOCAL_PLANE_RESOLUTION_UNIT_TAG: u16 = 0xA210;
+const EXIF_FOCAL_LENGTH_35MM_TAG: u16 = 0xA405;
+
+// The diagonal of a 35mm film frame in millimeters.
+const FULL_FRAME_DIAGONAL_MM: f64 = 43.266_615;
+
+/// The orientation of an image as stored in the EXIF metadata.
+///
+/// The orientation describes the transformation that needs to be applied to
+/// the stored pixels to display the image upright.
+///
+/// REF: <https://www.cipa.jp/std/documents/e/DC-008-2012_E.pdf>
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ExifOrientation {
+    /// The image is stored upright.
+    #[default]
+    Normal,
+    /// The image is mirrored horizontally.
+    FlipHorizontal,
+    /// The image is rotated by 180 degrees.
+    Rotate180,
+    /// The image is mirrored vertically.
+    FlipVertical,
+    /// The image is mirrored along the top-left to bottom-right diagonal.
+    Transpose,
+    /// The image needs to be rotated 90 degrees clockwise.
+    Rotate90,
+    /// The image is mirrored along the top-right to bottom-left diagonal.
+    Transverse,
+    /// The image needs to be rotated 270 degrees clockwise.
+    Rotate270,
+}
+
+impl ExifOrientation {
+    /// Create an orientation from the raw EXIF tag value.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The value of the orientation tag in the range [1, 8].
+    ///
+    /// # Returns
+    ///
+    /// The orientation or `None` if the value is not valid.
+    pub fn from_u16(value: u16) -> Option<Self> {
+        match value {
+            1 => Some(Self::Normal),
+            2 => Some(Self::FlipHorizontal),
+            3 => Some(Self::Rotate180),
+            4 => Some(Self::FlipVertical),
+            5 => Some(Self::Transpose),
+            6 => Some(Self::Rotate90),
+            7 => Some(Self::Transverse),
+            8 => Some(Self::Rotate270),
+            _ => None,
+        }
+    }
+
+    /// Get the raw EXIF tag value of the orientation.
+    pub fn as_u16(&self) -> u16 {
+        match self {
+            Self::Normal => 1,
+            Self::FlipHorizontal => 2,
+            Self::Rotate180 => 3,
+            Self::FlipVertical => 4,
+            Self::Transpose => 5,
+            Self::Rotate90 => 6,
+            Self::Transverse => 7,
+            Self::Rotate270 => 8,
+        }
+    }
+
+    /// Check if the orientation swaps the width and height of the image.
+    pub fn swaps_dimensions(&self) -> bool {
+        matches!(
+            self,
+            Self::Transpose | Self::Rotate90 | Self::Transverse | Self::Rotate270
+        )
+    }
+}
+
+/// The metadata of an image stored in the EXIF segment.
+///
+/// All the fields are optional since cameras and encoders only write a subset of the tags.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ExifMetadata {
+    /// The orientation of the stored pixels.
+    pub orientation: Option<ExifOrientation>,
+    /// The capture date and time in the EXIF format `YYYY:MM:DD HH:MM:SS`.
+    pub date_time: Option<String>,
+    /// The manufacturer of the camera.
+    pub make: Option<String>,
+    /// The model of the camera.
+    pub model: Option<String>,
+    /// The focal length of the lens in millimeters.
+    pub focal_length: Option<f64>,
+    /// The equivalent focal length for a 35mm film camera in millimeters.
+    pub focal_length_35mm: Option<f64>,
+    /// The number of pixels per millimeter on the sensor in the x and y directions.
+    pub focal_plane_resolution: Option<(f64, f64)>,
+}
+
+impl ExifMetadata {
+    /// Estimate the pinhole intrinsics of the camera from the focal length tags.
+    ///
+    /// The focal length in pixels is computed from the focal length and the focal plane
+    /// resolution if available, otherwise from the 35mm equivalent focal length. The
+    /// principal point is assumed to be at the center of the image since EXIF does not store it.
+    ///
+    /// # Arguments
+    ///
+    /// * `size` - The size of the image as stored in the file.
+    ///
+    /// # Returns
+    ///
+    /// The camera intrinsics or `None` if the focal length is not available.
+    pub fn intrinsics(&self, size: ImageSize) -> Option<CameraIntrinsic> {
+        let (fx, fy) = match (self.focal_length, self.focal_plane_resolution) {
+            (Some(f), Some((res_x, res_y))) => (f * res_x, f * res_y),
+            _ => {
+                let f35 = self.focal_length_35mm?;
+                let diagonal = (size.width as f64).hypot(size.height as f64);
+                let f = f35 * diagonal / FULL_FRAME_DIAGONAL_MM;
+                (f, f)
+            }
+        };
+
+        (fx > 0.0 && fy > 0.0).then_some(CameraIntrinsic {
+            fx,
+            fy,
+            cx: (size.width as f64 - 1.0) / 2.0,
+            cy: (size.height as f64 - 1.0) / 2.0,
+        })
+    }
+
+    /// Store the focal length of the camera intrinsics in the metadata.
+    ///
+    /// The focal length in pixels is encoded as the focal plane resolution relative to the
+    /// lens focal length, which defaults to 1 millimeter if unknown. The principal point is
+    /// not stored.
+    ///
+    /// # Arguments
+    ///
+    /// * `intrinsics` - The camera intrinsics to store.
+    pub fn set_intrinsics(&mut self, intrinsics: &CameraIntrinsic) {
+        let f = *self.focal_length.get_or_insert(1.0);
+        self.focal_plane_resolution = Some((intrinsics.fx / f, intrinsics.fy / f));
+    }
+}
+
+/// Read the EXIF metadata from the raw bytes of a JPEG file.
+///
+/// # Arguments
+///
+/// * `jpeg_data` - The raw bytes of the JPEG file.
+///
+/// # Returns
+///
+/// The metadata of the image or `None` if the file has no valid EXIF segment.
+pub fn read_exif_metadata(jpeg_data: &[u8]) -> Option<ExifMetadata> {
+    let exif = find_jpeg_exif_segment(jpeg_data)?;
+    decode_exif_metadata(exif)
+}
+
+/// Decode the EXIF metadata from a TIFF structure, e.g. the payload of an EXIF segment.
+///
+/// # Arguments
+///
+/// * `tiff_data` - The TIFF structure, starting with the byte order mark.
+///
+/// # Returns
+///
+/// The metadata or `None` if the TIFF structure is not valid.
+pub fn decode_exif_metadata(tiff_data: &[u8]) -> Option<ExifMetadata> {
+    let tiff = TiffReader::new(tiff_data)?;
+    let ifd0 = tiff.read_ifd(tiff.first_ifd_offset()?)?;
+
+    let mut metadata = ExifMetadata {
+        orientation: tiff
+            .value_u32(&ifd0, EXIF_ORIENTATION_TAG)
+            .and_then(|v| ExifOrientation::from_u16(v as u16)),
+        date_time: tiff.value_ascii(&ifd0, EXIF_DATE_TIME_TAG),
+        make: tiff.value_ascii(&ifd0, EXIF_MAKE_TAG),
+        model: tiff.value_ascii(&ifd0, EXIF_MODEL_TAG),
+        ..Default::default()
+    };
+
+    let exif_ifd = tiff
+        .value_u32(&ifd0, EXIF_IFD_POINTER_TAG)
+        .and_then(|offset| tiff.read_ifd(offset as usize));
+
+    if let Some(exif_ifd) = exif_ifd {
+        // prefer the capture time over the modification time
+        if let Some(date_time) = tiff.value_ascii(&exif_ifd, EXIF_DATE_TIME_ORIGINAL_TAG) {
+            metadata.date_time = Some(date_time);
+        }
+        metadata.focal_length = tiff.value_f64(&exif_ifd, EXIF_FOCAL_LENGTH_TAG);
+        metadata.focal_length_35mm = tiff
+            .value_f64(&exif_ifd, EXIF_FOCAL_LENGTH_35MM_TAG)
+            .filter(|f| *f > 0.0);
+
+        // convert the resolution to pixels per millimeter
+        let mm_per_unit = match tiff
+            .value_u32(&exif_ifd, EXIF_FOCAL_PLANE_RESOLUTION_UNIT_TAG)
+            .unwrap_or(2)
+        {
+            2 => Some(25.4),
+            3 => Some(10.0),
+            _ => None,
+        };
+        let res_x = tiff.value_f64(&exif_ifd, EXIF_FOCAL_PLANE_X_RESOLUTION_TAG);
+        let res_y = tiff.value_f64(&exif_ifd, EXIF_FOCAL_PLANE_Y_RESOLUTION_TAG);
+        if let (Some(mm_per_unit), Some(res_x), Some(res_y)) = (mm_per_unit, res_x, res_y) {
+            metadata.focal_plane_resolution = Some((res_x / mm_per_unit, res_y / mm_per_unit));
+        }
+    }
+
+    Some(metadata)
+}
+
+/// Encode the EXIF metadata as the payload of a JPEG APP1 segment.
+///
+/// The payload starts with the `Exif\0\0` header followed by a little endian TIFF structure.
+///
+/// # Arguments
+///
+/// * `metadata` - The metadata to encode.
+///
+/// # Returns
+///
+/// The payload of the APP1 segment.
+pub fn encode_exif_segment(metadata: &ExifMetadata) -> Vec<u8> {
+    let mut exif_entries = Vec::new();
+    if let Some(date_time) = &metadata.date_time {
+        exif_entries.push((
+            EXIF_DATE_TIME_ORIGINAL_TAG,
+            IfdValue::Ascii(date_time.clone()),
+        ));
+    }
+    if let Some(focal_length) = metadata.focal_length {
+        exif_entries.push((EXIF_FOCAL_LENGTH_TAG, IfdValue::Rational(focal_length)));
+    }
+    if let Some(focal_length_35mm) = metadata.focal_length_35mm {
+        exif_entries.push((
+            EXIF_FOCAL_LENGTH_35MM_TAG,
+            IfdValue::Short(focal_length_35mm.round() as u16),
+        ));
+    }
+    if let Some((res_x, res_y)) = metadata.focal_plane_resolution {
+        // store the resolution in pixels per centimeter
+        exif_entries.push((
+            EXIF_FOCAL_PLANE_X_RESOLUTION_TAG,
+            IfdValue::Rational(res_x * 10.0),
+        ));
+        exif_entries.push((
+            EXIF_FOCAL_PLANE_Y_RESOLUTION_TAG,
+            IfdValue::Rational(res_y * 10.0),
+        ));
+        exif_entries.push((EXIF_FOCAL_PLANE_RESOLUTION_UNIT_TAG, IfdValue::Short(3)));
+    }
+
+    let mut ifd0_entries = Vec::new();
+    if let Some(orientation) = metadata.orientation {
+        ifd0_entries.push((EXIF_ORIENTATION_TAG, IfdValue::Short(orientation.as_u16())));
+    }
+    if let Some(date_time) = &metadata.date_time {
+        ifd0_entries.push((EXIF_DATE_TIME_TAG, IfdValue::Ascii(date_time.clone())));
+    }
+    if let Some(make) = &metadata.make {
+        ifd0_entries.push((EXIF_MAKE_TAG, IfdValue::Ascii(make.clone())));
+    }
+    if let Some(model) = &metadata.model {
+        ifd0_entries.push((EXIF_MODEL_TAG, IfdValue::Ascii(model.clone())));
+    }
+
+    // write the Exif sub-directory first so that its offset is known by the main directory
+    let mut writer = TiffWriter::new();
+    if !exif_entries.is_empty() {
+        let exif_offset = writer.offset();
+        writer.write_ifd(exif_entries);
+        ifd0_entries.push((EXIF_IFD_POINTER_TAG, IfdValue::Long(exif_offset as u32)));
+    }
+    let ifd0_offset = writer.offset();
+    writer.write_ifd(ifd0_entries);
+    writer.set_first_ifd_offset(ifd0_offset);
+
+    let mut payload = b"Exif\0\0".to_vec();
+    payload.extend_from_slice(&writer.into_bytes());
+    payload
+}
+
+/// Read the EXIF orientation from the raw bytes of a JPEG file.
+///
+/// The function scans the JPEG markers until the start of the scan data and
+/// parses the first EXIF segment (APP1) found.
+///
+/// # Arguments
+///
+/// * `jpeg_data` - The raw bytes of the JPEG file.
+///
+/// # Returns
+///
+/// The orientation of the image or `None` if the file has no valid orientation tag.
+pub fn read_exif_orientation(jpeg_data: &[u8]) -> Option<ExifOrientation> {
+    read_exif_metadata(jpeg_data)?.orientation
+}
+
+/// Apply the EXIF orientation to an image so that it is displayed upright.
+///
+/// # Arguments
+///
+/// * `src` - The image as stored in the file.
+/// * `orientation` - The EXIF orientation of the image.
+///
+/// # Returns
+///
+/// A new image with the orientation applied. The width and height are swapped
+/// for the orientations that involve a 90 degrees rotation.
+pub fn apply_exif_orientation<T, const C: usize>(
+    src: &Image<T, C>,
+    orientation: ExifOrientation,
+) -> Result<Image<T, C>, IoError>
+where
+    T: Copy + Default,
+{
+    let (src_cols, src_rows) = (src.cols(), src.rows());
+
+    let dst_size = if orientation.swaps_dimensions() {
+        ImageSize {
+            width: src_rows,
+            height: src_cols,
+        }
+    } else {
+        src.size()
+    };
+
+    let mut dst = Image::<T, C>::from_size_val(dst_size, T::default())?;
+    let dst_cols = dst.cols();
+    let dst_data = dst.as_slice_mut();
+
+    for (idx, src_pixel) in src.as_slice().chunks_exact(C).enumerate() {
+        let (x, y) = (idx % src_cols, idx / src_cols);
+        let (u, v) = match orientation {
+            ExifOrientation::Normal => (x, y),
+            ExifOrientation::FlipHorizontal => (src_cols - 1 - x, y),
+            ExifOrientation::Rotate180 => (src_cols - 1 - x, src_rows - 1 - y),
+            ExifOrientation::FlipVertical => (x, src_rows - 1 - y),
+            ExifOrientation::Transpose => (y, x),
+            ExifOrientation::Rotate90 => (src_rows - 1 - y, x),
+            ExifOrientation::Transverse => (src_rows - 1 - y, src_cols - 1 - x),
+            ExifOrientation::Rotate270 => (y, src_cols - 1 - x),
+        };
+        let offset = (v * dst_cols + u) * C;
+        dst_data[offset..offset + C].copy_from_slice(src_pixel);
+    }
+
+    Ok(dst)
+}
+
+// find the payload of the APP1 segment containing the EXIF data (without the Exif header)
+fn find_jpeg_exif_segment(data: &[u8]) -> Option<&[u8]> {
+    // check the start of image marker
+    if data.get(..2)? != [0xFF, 0xD8] {
+        return None;
+    }
+
+    let mut pos = 2;
+    while pos + 4 <= data.len() {
+        if data[pos] != 0xFF {
+            return None;
+        }
+        let marker = data[pos + 1];
+
+        // skip the padding bytes and the markers without payload
+        if marker == 0xFF {
+            pos += 1;
+            continue;
+        }
+        if marker == 0x01 || (0xD0..=0xD7).contains(&marker) {
+            pos += 2;
+            continue;
+        }
+
+        // start of scan or end of image, no more metadata
+        if marker == 0xDA || marker == 0xD9 {
+            return None;
+        }
+
+        let length = u16::from_be_bytes([data[pos + 2], data[pos + 3]]) as usize;
+        let payload = data.get(pos + 4..pos + 2 + length)?;
+
+        if marker == 0xE1 && payload.starts_with(b"Exif\0\0") {
+            return Some(&payload[6..]);
+        }
+
+        pos += 2 + length;
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // build a minimal tiff structure with the orientation tag
+    fn exif_payload(orientation: u16, little_endian: bool) -> Vec<u8> {
+        let u16_bytes = |v: u16| {
+            if little_endian {
+                v.to_le_bytes()
+            } else {
+                v.to_be_bytes()
+            }
+        };
+        let u32_bytes = |v: u32| {
+            if little_endian {
+                v.to_le_bytes()
+            } else {
+                v.to_be_bytes()
+            }
+        };
+
+        let mut tiff = Vec::new();
+        tiff.extend_from_slice(if little_endian { b"II" } else { b"MM" });
+        tiff.extend_from_slice(&u16_bytes(42));
+        tiff.extend_from_slice(&u32_bytes(8));
+        tiff.extend_from_slice(&u16_bytes(1));
+        tiff.extend_from_slice(&u16_bytes(EXIF_ORIENTATION_TAG));
+        tiff.extend_from_slice(&u16_bytes(3));
+        tiff.extend_from_slice(&u32_bytes(1));
+        tiff.extend_from_slice(&u16_bytes(orientation));
+        tiff.extend_from_slice(&[0, 0]);
+        tiff.extend_from_slice(&u32_bytes(0));
+        tiff
+    }
+
+    #[test]
+    fn test_read_exif_orientation() {
+        for little_endian in [true, false] {
+            let mut payload = b"Exif\0\0".to_vec();
+            payload.extend_from_slice(&exif_payload(6, little_endian));
+
+            let mut jpeg = vec![0xFF, 0xD8, 0xFF, 0xE1];
+            jpeg.extend_from_slice(&((payload.len() + 2) as u16).to_be_bytes());
+            jpeg.extend_from_slice(&payload);
+            jpeg.extend_from_slice(&[0xFF, 0xD9]);
+
+            assert_eq!(
+                read_exif_orientation(&jpeg),
+                Some(ExifOrientation::Rotate90)
+            );
+        }
+
+        assert_eq!(read_exif_orientation(&[0xFF, 0xD8, 0xFF, 0xD9]), None);
+        assert_eq!(read_exif_orientation(&[]), None);
+    }
+
+    #[test]
+    fn test_exif_metadata_roundtrip() {
+        let metadata = ExifMetadata {
+            orientation: Some(ExifOrientation::Rotate270),
+            date_time: Some("2023:11:02 08:15:42".to_string()),
+            make: Some("Kornia".to_string()),
+            model: Some("Model X".to_string()),
+            focal_length: Some(4.25),
+            focal_length_35mm: Some(26.0),
+            focal_plane_resolution: Some((250.0, 250.0)),
+        };
+
+        let payload = encode_exif_segment(&metadata);
+        assert!(payload.starts_with(b"Exif\0\0"));
+        assert_eq!(decode_exif_metadata(&payload[6..]), Some(metadata.clone()));
+
+        let mut jpeg = vec![0xFF, 0xD8, 0xFF, 0xE1];
+        jpeg.extend_from_slice(&((payload.len() + 2) as u16).to_be_bytes());
+        jpeg.extend_from_slice(&payload);
+        jpeg.extend_from_slice(&[0xFF, 0xD9]);
+        assert_eq!(read_exif_metadata(&jpeg), Some(metadata));
+        assert_eq!(
+            read_exif_orientation(&jpeg),
+            Some(ExifOrientation::Rotate270)
+        );
+
+        assert_eq!(
+            decode_exif_metadata(&encode_exif_segment(&ExifMetadata::default())[6..]),
+            Some(ExifMetadata::default())
+        );
+    }
+
+    #[test]
+    fn test_exif_intrinsics() {
+        let size = ImageSize {
+            width: 4000,
+            height: 3000,
+        };
+
+        assert_eq!(ExifMetadata::default().intrinsics(size), None);
+
+        // 35mm equivalent focal length
+        let metadata = ExifMetadata {
+            focal_length_35mm: Some(FULL_FRAME_DIAGONAL_MM),
+            ..Default::default()
+        };
+        let intrinsics = metadata.intrinsics(size).expect("missing intrinsics");
+        assert!((intrinsics.fx - 5000.0).abs() < 1e-6);
+        assert_eq!(intrinsics.fx, intrinsics.fy);
+        assert_eq!(intrinsics.cx, 1999.5);
+        assert_eq!(intrinsics.cy, 1499.5);
+
+        // focal plane resolution takes precedence
+        let metadata = ExifMetadata {
+            focal_length: Some(4.0),
+            focal_plane_resolution: Some((800.0, 810.0)),
+            ..metadata
+        };
+        let intrinsics = metadata.intrinsics(size).expect("missing intrinsics");
+        assert_eq!(intrinsics.fx, 3200.0);
+        assert_eq!(intrinsics.fy, 3240.0);
+    }
+
+    #[test]
+    fn test_apply_exif_orientation() -> Result<(), IoError> {
+        #[rustfmt::skip]
+        let image = Image::<u8, 1>::new(
+            ImageSize { width: 3, height: 2 },
+            vec![
+                0, 1, 2,
+                3, 4, 5,
+            ],
+        )?;
+
+        #[rustfmt::skip]
+        let expected: [(ExifOrientation, [usize; 2], [u8; 6]); 8] = [
+            (ExifOrientation::Normal, [3, 2], [0, 1, 2, 3, 4, 5]),
+            (ExifOrientation::FlipHorizontal, [3, 2], [2, 1, 0, 5, 4, 3]),
+            (ExifOrientation::Rotate180, [3, 2], [5, 4, 3, 2, 1, 0]),
+            (ExifOrientation::FlipVertical, [3, 2], [3, 4, 5, 0, 1, 2]),
+            (ExifOrientation::Transpose, [2, 3], [0, 3, 1, 4, 2, 5]),
+            (ExifOrientation::Rotate90, [2, 3], [3, 0, 4, 1, 5, 2]),
+            (ExifOrientation::Transverse, [2, 3], [5, 2, 4, 1, 3, 0]),
+            (ExifOrientation::Rotate270, [2, 3], [2, 5, 1, 4, 0, 3]),
+        ];
+
+        for (orientation, size, data) in expected {
+            let oriented = apply_exif_orientation(&image, orientation)?;
+            assert_eq!(oriented.size(), size.into(), "{:?}", orientation);
+            assert_eq!(oriented.as_slice(), &data, "{:?}", orientation);
+            assert_eq!(
+                ExifOrientation::from_u16(orientation.as_u16()),
+                Some(orientation)
+            );
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_jpeg_oriented() -> Result<(), Box<dyn std::error::Error>> {
+        let tmp_dir = tempfile::tempdir()?;
+        let file_path = tmp_dir.path().join("rotated.jpeg");
+
+        let image = Image::<u8, 3>::from_size_val([6, 4].into(), 128)?;
+
+        let mut payload = b"Exif\0\0".to_vec();
+        payload.extend_from_slice(&exif_payload(8, true));
+
+        let mut encoder = jpeg_encoder::Encoder::new_file(&file_path, 90)?;
+        encoder.add_app_segment(1, &payload)?;
+        encoder.encode(image.as_slice(), 6, 4, jpeg_encoder::ColorType::Rgb)?;
+
+        let oriented = crate::jpeg::read_image_jpeg_rgb8_oriented(&file_path)?;
+        assert_eq!(oriented.cols(), 4);
+        assert_eq!(oriented.rows(), 6);
+
+        Ok(())
+    }
+}
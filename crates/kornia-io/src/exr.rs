@@ -0,0 +1,186 @@
+use std::path::Path;
+
+use image::{ImageBuffer, ImageFormat, Rgb, Rgba};
+use kornia_image::{Image, ImageSize};
+
+use crate::error::IoError;
+
+/// Read an OpenEXR image with three channels (rgb32f).
+///
+/// The pixel values are kept in linear HDR range, i.e. they are not clamped to [0, 1].
+///
+/// # Arguments
+///
+/// * `file_path` - The path to the EXR file.
+///
+/// # Returns
+///
+/// A RGB image with three channels (rgb32f).
+pub fn read_image_exr_rgb32f(file_path: impl AsRef<Path>) -> Result<Image<f32, 3>, IoError> {
+    decode_image_exr_rgb32f(&read_exr_file(file_path)?)
+}
+
+/// Decode an OpenEXR image with three channels (rgb32f) from its raw bytes.
+///
+/// # Arguments
+///
+/// * `src` - The raw bytes of the EXR file.
+///
+/// # Returns
+///
+/// A RGB image with three channels (rgb32f).
+pub fn decode_image_exr_rgb32f(src: &[u8]) -> Result<Image<f32, 3>, IoError> {
+    let img = image::load_from_memory_with_format(src, ImageFormat::OpenExr)?;
+    let size = ImageSize {
+        width: img.width() as usize,
+        height: img.height() as usize,
+    };
+    Ok(Image::new(size, img.into_rgb32f().into_raw())?)
+}
+
+/// Read an OpenEXR image with four channels (rgba32f).
+///
+/// # Arguments
+///
+/// * `file_path` - The path to the EXR file.
+///
+/// # Returns
+///
+/// A RGBA image with four channels (rgba32f).
+pub fn read_image_exr_rgba32f(file_path: impl AsRef<Path>) -> Result<Image<f32, 4>, IoError> {
+    decode_image_exr_rgba32f(&read_exr_file(file_path)?)
+}
+
+/// Decode an OpenEXR image with four channels (rgba32f) from its raw bytes.
+///
+/// # Arguments
+///
+/// * `src` - The raw bytes of the EXR file.
+///
+/// # Returns
+///
+/// A RGBA image with four channels (rgba32f).
+pub fn decode_image_exr_rgba32f(src: &[u8]) -> Result<Image<f32, 4>, IoError> {
+    let img = image::load_from_memory_with_format(src, ImageFormat::OpenExr)?;
+    let size = ImageSize {
+        width: img.width() as usize,
+        height: img.height() as usize,
+    };
+    Ok(Image::new(size, img.into_rgba32f().into_raw())?)
+}
+
+/// Writes the given OpenEXR _(rgb32f)_ data to the given file path.
+///
+/// # Arguments
+///
+/// - `file_path` - The path to the EXR image.
+/// - `image` - The tensor containing the EXR image data.
+pub fn write_image_exr_rgb32f(
+    file_path: impl AsRef<Path>,
+    image: &Image<f32, 3>,
+) -> Result<(), IoError> {
+    let buf = ImageBuffer::<Rgb<f32>, &[f32]>::from_raw(
+        image.cols() as u32,
+        image.rows() as u32,
+        image.as_slice(),
+    )
+    .ok_or(IoError::InvalidBufferSize(
+        image.as_slice().len(),
+        image.cols() * image.rows() * 3,
+    ))?;
+
+    buf.save_with_format(file_path, ImageFormat::OpenExr)?;
+
+    Ok(())
+}
+
+/// Writes the given OpenEXR _(rgba32f)_ data to the given file path.
+///
+/// # Arguments
+///
+/// - `file_path` - The path to the EXR image.
+/// - `image` - The tensor containing the EXR image data.
+pub fn write_image_exr_rgba32f(
+    file_path: impl AsRef<Path>,
+    image: &Image<f32, 4>,
+) -> Result<(), IoError> {
+    let buf = ImageBuffer::<Rgba<f32>, &[f32]>::from_raw(
+        image.cols() as u32,
+        image.rows() as u32,
+        image.as_slice(),
+    )
+    .ok_or(IoError::InvalidBufferSize(
+        image.as_slice().len(),
+        image.cols() * image.rows() * 4,
+    ))?;
+
+    buf.save_with_format(file_path, ImageFormat::OpenExr)?;
+
+    Ok(())
+}
+
+// utility function to read the bytes of the exr file
+fn read_exr_file(file_path: impl AsRef<Path>) -> Result<Vec<u8>, IoError> {
+    // verify the file exists
+    let file_path = file_path.as_ref();
+    if !file_path.exists() {
+        return Err(IoError::FileDoesNotExist(file_path.to_path_buf()));
+    }
+
+    // verify the file extension
+    if file_path
+        .extension()
+        .map_or(true, |ext| !ext.eq_ignore_ascii_case("exr"))
+    {
+        return Err(IoError::InvalidFileExtension(file_path.to_path_buf()));
+    }
+
+    Ok(std::fs::read(file_path)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn read_write_exr_rgb32f() -> Result<(), IoError> {
+        let tmp_dir = tempfile::tempdir()?;
+
+        let file_path = tmp_dir.path().join("hdr.exr");
+        let image = Image::<f32, 3>::new(
+            ImageSize {
+                width: 2,
+                height: 2,
+            },
+            vec![
+                0.0, 0.25, 0.5, 1.0, 2.5, 100.0, -1.0, 0.125, 3.75, 1e-3, 42.0, 0.75,
+            ],
+        )?;
+        write_image_exr_rgb32f(&file_path, &image)?;
+
+        let image_back = read_image_exr_rgb32f(&file_path)?;
+        assert_eq!(image_back.size(), image.size());
+        assert_eq!(image_back.as_slice(), image.as_slice());
+
+        Ok(())
+    }
+
+    #[test]
+    fn read_write_exr_rgba32f() -> Result<(), IoError> {
+        let tmp_dir = tempfile::tempdir()?;
+
+        let file_path = tmp_dir.path().join("hdr.exr");
+        let image = Image::<f32, 4>::from_size_val([3, 2].into(), 1.5)?;
+        write_image_exr_rgba32f(&file_path, &image)?;
+
+        let image_back = read_image_exr_rgba32f(&file_path)?;
+        assert_eq!(image_back.size(), image.size());
+        assert_eq!(image_back.as_slice(), image.as_slice());
+
+        let image_decoded = decode_image_exr_rgba32f(&std::fs::read(&file_path)?)?;
+        assert_eq!(image_decoded.as_slice(), image.as_slice());
+        assert!(decode_image_exr_rgba32f(&[0u8; 16]).is_err());
+
+        Ok(())
+    }
+}
@@ -112,10 +112,25 @@ pub fn read_image_any_rgb8(file_path: impl AsRef<Path>) -> Result<Image<u8, 3>,
     }
 
     // open the file and map it to memory
-    let jpeg_data = std::fs::read(file_path)?;
+    let data = std::fs::read(file_path)?;
+
+    decode_image_any_rgb8(&data)
+}
 
+/// Decodes a RGB8 image from its encoded bytes.
+///
+/// The method tries to decode any image format supported by the image crate.
+///
+/// # Arguments
+///
+/// * `src` - The encoded image data.
+///
+/// # Returns
+///
+/// A tensor image containing the image data in RGB8 format with shape (H, W, 3).
+pub fn decode_image_any_rgb8(src: &[u8]) -> Result<Image<u8, 3>, IoError> {
     // decode the data directly from memory
-    let img = image::ImageReader::new(std::io::Cursor::new(&jpeg_data))
+    let img = image::ImageReader::new(std::io::Cursor::new(src))
         .with_guessed_format()?
         .decode()?;
 
@@ -126,7 +141,7 @@ pub fn read_image_any_rgb8(file_path: impl AsRef<Path>) -> Result<Image<u8, 3>,
             width: img.width() as usize,
             height: img.height() as usize,
         },
-        img.to_rgb8().to_vec(),
+        img.into_rgb8().into_raw(),
     )?;
 
     Ok(image)
@@ -0,0 +1,265 @@
+// Minimal reader of the TIFF structure shared by the EXIF and DNG decoders.
+//
+// REF: <https://www.itu.int/itudoc/itu-t/com16/tiff-fx/docs/tiff6.pdf>
+
+// a single entry of a TIFF image file directory
+#[derive(Debug, Clone)]
+pub(crate) struct IfdEntry {
+    pub(crate) tag: u16,
+    pub(crate) field_type: u16,
+    pub(crate) count: usize,
+    // the offset of the value in the file, resolved for inline values
+    pub(crate) value_offset: usize,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct Ifd(Vec<IfdEntry>);
+
+impl Ifd {
+    pub(crate) fn get(&self, tag: u16) -> Option<&IfdEntry> {
+        self.0.iter().find(|e| e.tag == tag)
+    }
+}
+
+// minimal TIFF structure reader supporting both byte orders
+pub(crate) struct TiffReader<'a> {
+    data: &'a [u8],
+    little_endian: bool,
+}
+
+impl<'a> TiffReader<'a> {
+    // returns None if the data does not start with a valid TIFF header
+    pub(crate) fn new(data: &'a [u8]) -> Option<Self> {
+        let little_endian = match data.get(..2)? {
+            b"II" => true,
+            b"MM" => false,
+            _ => return None,
+        };
+        let reader = Self {
+            data,
+            little_endian,
+        };
+        (reader.read_u16(2)? == 42).then_some(reader)
+    }
+
+    pub(crate) fn u16_from_bytes(&self, bytes: [u8; 2]) -> u16 {
+        if self.little_endian {
+            u16::from_le_bytes(bytes)
+        } else {
+            u16::from_be_bytes(bytes)
+        }
+    }
+
+    pub(crate) fn read_u16(&self, offset: usize) -> Option<u16> {
+        let bytes = self.data.get(offset..offset + 2)?;
+        Some(self.u16_from_bytes([bytes[0], bytes[1]]))
+    }
+
+    pub(crate) fn read_u32(&self, offset: usize) -> Option<u32> {
+        let bytes: [u8; 4] = self.data.get(offset..offset + 4)?.try_into().ok()?;
+        Some(if self.little_endian {
+            u32::from_le_bytes(bytes)
+        } else {
+            u32::from_be_bytes(bytes)
+        })
+    }
+
+    pub(crate) fn first_ifd_offset(&self) -> Option<usize> {
+        self.read_u32(4).map(|v| v as usize)
+    }
+
+    // returns None if the directory is truncated
+    pub(crate) fn read_ifd(&self, offset: usize) -> Option<Ifd> {
+        let num_entries = self.read_u16(offset)? as usize;
+
+        let mut entries = Vec::with_capacity(num_entries);
+        for i in 0..num_entries {
+            let entry_offset = offset + 2 + i * 12;
+            let tag = self.read_u16(entry_offset)?;
+            let field_type = self.read_u16(entry_offset + 2)?;
+            let count = self.read_u32(entry_offset + 4)? as usize;
+
+            // values that fit in 4 bytes are stored inline
+            let size = field_type_size(field_type).unwrap_or(1) * count;
+            let value_offset = if size <= 4 {
+                entry_offset + 8
+            } else {
+                self.read_u32(entry_offset + 8)? as usize
+            };
+
+            entries.push(IfdEntry {
+                tag,
+                field_type,
+                count,
+                value_offset,
+            });
+        }
+
+        Some(Ifd(entries))
+    }
+
+    // read the integer values of a tag
+    pub(crate) fn values_u32(&self, ifd: &Ifd, tag: u16) -> Option<Vec<u32>> {
+        let entry = ifd.get(tag)?;
+        let size = field_type_size(entry.field_type)?;
+        (0..entry.count)
+            .map(|i| {
+                let offset = entry.value_offset + i * size;
+                match entry.field_type {
+                    1 | 7 => self.data.get(offset).map(|&v| v as u32),
+                    3 => self.read_u16(offset).map(|v| v as u32),
+                    4 => self.read_u32(offset),
+                    _ => None,
+                }
+            })
+            .collect()
+    }
+
+    pub(crate) fn value_u32(&self, ifd: &Ifd, tag: u16) -> Option<u32> {
+        self.values_u32(ifd, tag)?.first().copied()
+    }
+
+    // read the numeric values of a tag, including rationals
+    pub(crate) fn values_f64(&self, ifd: &Ifd, tag: u16) -> Option<Vec<f64>> {
+        let entry = ifd.get(tag)?;
+        let size = field_type_size(entry.field_type)?;
+        (0..entry.count)
+            .map(|i| {
+                let offset = entry.value_offset + i * size;
+                match entry.field_type {
+                    1 | 7 => self.data.get(offset).map(|&v| v as f64),
+                    3 => self.read_u16(offset).map(|v| v as f64),
+                    4 => self.read_u32(offset).map(|v| v as f64),
+                    5 => {
+                        let num = self.read_u32(offset)?;
+                        let den = self.read_u32(offset + 4)?;
+                        (den != 0).then(|| num as f64 / den as f64)
+                    }
+                    10 => {
+                        let num = self.read_u32(offset)? as i32;
+                        let den = self.read_u32(offset + 4)? as i32;
+                        (den != 0).then(|| num as f64 / den as f64)
+                    }
+                    _ => None,
+                }
+            })
+            .collect()
+    }
+
+    pub(crate) fn value_f64(&self, ifd: &Ifd, tag: u16) -> Option<f64> {
+        self.values_f64(ifd, tag)?.first().copied()
+    }
+
+    // read a null terminated ascii string, trimming the trailing padding
+    pub(crate) fn value_ascii(&self, ifd: &Ifd, tag: u16) -> Option<String> {
+        let entry = ifd.get(tag)?;
+        if entry.field_type != 2 {
+            return None;
+        }
+        let bytes = self
+            .data
+            .get(entry.value_offset..entry.value_offset + entry.count)?;
+        let end = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
+        let value = std::str::from_utf8(&bytes[..end]).ok()?.trim_end();
+        (!value.is_empty()).then(|| value.to_string())
+    }
+}
+
+// the size in bytes of a TIFF field type
+pub(crate) fn field_type_size(field_type: u16) -> Option<usize> {
+    match field_type {
+        1 | 2 | 6 | 7 => Some(1),
+        3 | 8 => Some(2),
+        4 | 9 | 11 | 13 => Some(4),
+        5 | 10 | 12 => Some(8),
+        _ => None,
+    }
+}
+
+// writer of a little endian TIFF structure with a single chain of directories
+pub(crate) struct TiffWriter {
+    data: Vec<u8>,
+}
+
+// a value to be written in an image file directory
+pub(crate) enum IfdValue {
+    Short(u16),
+    Long(u32),
+    Rational(f64),
+    Ascii(String),
+}
+
+impl TiffWriter {
+    pub(crate) fn new() -> Self {
+        let mut data = b"II".to_vec();
+        data.extend_from_slice(&42u16.to_le_bytes());
+        data.extend_from_slice(&8u32.to_le_bytes());
+        Self { data }
+    }
+
+    // the offset where the next directory will be written
+    pub(crate) fn offset(&self) -> usize {
+        self.data.len()
+    }
+
+    // point the header to the directory at the given offset
+    pub(crate) fn set_first_ifd_offset(&mut self, offset: usize) {
+        self.data[4..8].copy_from_slice(&(offset as u32).to_le_bytes());
+    }
+
+    // write a directory at the current offset with its entries sorted by tag
+    pub(crate) fn write_ifd(&mut self, mut entries: Vec<(u16, IfdValue)>) {
+        entries.sort_by_key(|(tag, _)| *tag);
+
+        let ifd_offset = self.data.len();
+        let mut extra_offset = ifd_offset + 2 + entries.len() * 12 + 4;
+        let mut extra = Vec::new();
+
+        self.data
+            .extend_from_slice(&(entries.len() as u16).to_le_bytes());
+        for (tag, value) in entries {
+            let (field_type, count, mut bytes) = match value {
+                IfdValue::Short(v) => (3u16, 1u32, v.to_le_bytes().to_vec()),
+                IfdValue::Long(v) => (4, 1, v.to_le_bytes().to_vec()),
+                IfdValue::Rational(v) => {
+                    // store the value with a fixed precision
+                    let den = 10000u32;
+                    let num = (v.max(0.0) * den as f64).round() as u32;
+                    let mut bytes = num.to_le_bytes().to_vec();
+                    bytes.extend_from_slice(&den.to_le_bytes());
+                    (5, 1, bytes)
+                }
+                IfdValue::Ascii(v) => {
+                    let mut bytes = v.into_bytes();
+                    bytes.push(0);
+                    (2, bytes.len() as u32, bytes)
+                }
+            };
+
+            self.data.extend_from_slice(&tag.to_le_bytes());
+            self.data.extend_from_slice(&field_type.to_le_bytes());
+            self.data.extend_from_slice(&count.to_le_bytes());
+            if bytes.len() <= 4 {
+                bytes.resize(4, 0);
+                self.data.extend_from_slice(&bytes);
+            } else {
+                self.data
+                    .extend_from_slice(&(extra_offset as u32).to_le_bytes());
+                // keep the values aligned to a word boundary
+                if bytes.len() % 2 == 1 {
+                    bytes.push(0);
+                }
+                extra_offset += bytes.len();
+                extra.extend_from_slice(&bytes);
+            }
+        }
+
+        // no next directory
+        self.data.extend_from_slice(&0u32.to_le_bytes());
+        self.data.extend_from_slice(&extra);
+    }
+
+    pub(crate) fn into_bytes(self) -> Vec<u8> {
+        self.data
+    }
+}
@@ -1,8 +1,13 @@
 use crate::error::IoError;
+use crate::exif::{
+    apply_exif_orientation, encode_exif_segment, read_exif_metadata, read_exif_orientation,
+    ExifMetadata, ExifOrientation,
+};
 use jpeg_encoder::{ColorType, Encoder};
 use kornia_image::{Image, ImageSize};
 use std::fs;
 use std::path::Path;
+use zune_jpeg::zune_core::{colorspace::ColorSpace, options::DecoderOptions};
 
 /// Writes the given JPEG _(rgb8)_ data to the given file path.
 ///
@@ -10,11 +15,13 @@ use std::path::Path;
 ///
 /// - `file_path` - The path to the JPEG image.
 /// - `image` - The tensor containing the JPEG image data
+/// - `quality` - The quality of the JPEG encoding, range from 0 (lowest) to 100 (highest)
 pub fn write_image_jpeg_rgb8(
     file_path: impl AsRef<Path>,
     image: &Image<u8, 3>,
+    quality: u8,
 ) -> Result<(), IoError> {
-    write_image_jpeg_imp(file_path, image, ColorType::Rgb)
+    write_image_jpeg_imp(file_path, image, ColorType::Rgb, quality, None)
 }
 
 /// Writes the given JPEG _(grayscale)_ data to the given file path.
@@ -23,20 +30,61 @@ pub fn write_image_jpeg_rgb8(
 ///
 /// - `file_path` - The path to the JPEG image.
 /// - `image` - The tensor containing the JPEG image data
+/// - `quality` - The quality of the JPEG encoding, range from 0 (lowest) to 100 (highest)
 pub fn write_image_jpeg_gray8(
     file_path: impl AsRef<Path>,
     image: &Image<u8, 1>,
+    quality: u8,
 ) -> Result<(), IoError> {
-    write_image_jpeg_imp(file_path, image, ColorType::Luma)
+    write_image_jpeg_imp(file_path, image, ColorType::Luma, quality, None)
+}
+
+/// Writes the given JPEG _(rgb8)_ data to the given file path with EXIF metadata.
+///
+/// # Arguments
+///
+/// - `file_path` - The path to the JPEG image.
+/// - `image` - The tensor containing the JPEG image data
+/// - `quality` - The quality of the JPEG encoding, range from 0 (lowest) to 100 (highest)
+/// - `metadata` - The EXIF metadata to store in the file.
+pub fn write_image_jpeg_rgb8_with_metadata(
+    file_path: impl AsRef<Path>,
+    image: &Image<u8, 3>,
+    quality: u8,
+    metadata: &ExifMetadata,
+) -> Result<(), IoError> {
+    write_image_jpeg_imp(file_path, image, ColorType::Rgb, quality, Some(metadata))
+}
+
+/// Writes the given JPEG _(grayscale)_ data to the given file path with EXIF metadata.
+///
+/// # Arguments
+///
+/// - `file_path` - The path to the JPEG image.
+/// - `image` - The tensor containing the JPEG image data
+/// - `quality` - The quality of the JPEG encoding, range from 0 (lowest) to 100 (highest)
+/// - `metadata` - The EXIF metadata to store in the file.
+pub fn write_image_jpeg_gray8_with_metadata(
+    file_path: impl AsRef<Path>,
+    image: &Image<u8, 1>,
+    quality: u8,
+    metadata: &ExifMetadata,
+) -> Result<(), IoError> {
+    write_image_jpeg_imp(file_path, image, ColorType::Luma, quality, Some(metadata))
 }
 
 fn write_image_jpeg_imp<const N: usize>(
     file_path: impl AsRef<Path>,
     image: &Image<u8, N>,
     color_type: ColorType,
+    quality: u8,
+    metadata: Option<&ExifMetadata>,
 ) -> Result<(), IoError> {
     let image_size = image.size();
-    let encoder = Encoder::new_file(file_path, 100)?;
+    let mut encoder = Encoder::new_file(file_path, quality)?;
+    if let Some(metadata) = metadata {
+        encoder.add_app_segment(1, &encode_exif_segment(metadata))?;
+    }
     encoder.encode(
         image.as_slice(),
         image_size.width as u16,
@@ -46,7 +94,7 @@ fn write_image_jpeg_imp<const N: usize>(
     Ok(())
 }
 
-/// Read a JPEG image with a four channel _(rgb8)_.
+/// Read a JPEG image with a three channel _(rgb8)_.
 ///
 /// # Arguments
 ///
@@ -54,11 +102,42 @@ fn write_image_jpeg_imp<const N: usize>(
 ///
 /// # Returns
 ///
-/// A RGB image with four channels _(rgb8)_.
+/// A RGB image with three channels _(rgb8)_.
 pub fn read_image_jpeg_rgb8(file_path: impl AsRef<Path>) -> Result<Image<u8, 3>, IoError> {
     read_image_jpeg_impl(file_path)
 }
 
+/// Read a JPEG image with a three channel _(rgb8)_ and apply the EXIF orientation.
+///
+/// The image is rotated and flipped according to the EXIF orientation tag so that
+/// it is returned upright, as photos taken with phones and cameras usually require.
+///
+/// # Arguments
+///
+/// - `file_path` - The path to the JPEG file.
+///
+/// # Returns
+///
+/// A RGB image with three channels _(rgb8)_.
+pub fn read_image_jpeg_rgb8_oriented(file_path: impl AsRef<Path>) -> Result<Image<u8, 3>, IoError> {
+    read_image_jpeg_oriented_impl(file_path)
+}
+
+/// Read a JPEG image with a single channel _(mono8)_ and apply the EXIF orientation.
+///
+/// # Arguments
+///
+/// - `file_path` - The path to the JPEG file.
+///
+/// # Returns
+///
+/// A grayscale image with a single channel _(mono8)_.
+pub fn read_image_jpeg_mono8_oriented(
+    file_path: impl AsRef<Path>,
+) -> Result<Image<u8, 1>, IoError> {
+    read_image_jpeg_oriented_impl(file_path)
+}
+
 /// Reads a JPEG file with a single channel _(mono8)_
 ///
 /// # Arguments
@@ -92,9 +171,69 @@ pub fn decode_image_jpeg_mono8(src: &[u8], dst: &mut Image<u8, 1>) -> Result<(),
     decode_jpeg_impl(src, dst)
 }
 
-fn read_image_jpeg_impl<const N: usize>(
+/// Read a JPEG image with a three channel _(rgb8)_ and its EXIF metadata.
+///
+/// The EXIF orientation is applied to the image, so the orientation of the
+/// returned metadata is reset to [`ExifOrientation::Normal`].
+///
+/// # Arguments
+///
+/// - `file_path` - The path to the JPEG file.
+///
+/// # Returns
+///
+/// The upright image and its metadata, empty if the file has no EXIF segment.
+pub fn read_image_jpeg_rgb8_with_metadata(
+    file_path: impl AsRef<Path>,
+) -> Result<(Image<u8, 3>, ExifMetadata), IoError> {
+    read_image_jpeg_with_metadata_impl(file_path)
+}
+
+/// Read a JPEG image with a single channel _(mono8)_ and its EXIF metadata.
+///
+/// The EXIF orientation is applied to the image, so the orientation of the
+/// returned metadata is reset to [`ExifOrientation::Normal`].
+///
+/// # Arguments
+///
+/// - `file_path` - The path to the JPEG file.
+///
+/// # Returns
+///
+/// The upright image and its metadata, empty if the file has no EXIF segment.
+pub fn read_image_jpeg_mono8_with_metadata(
+    file_path: impl AsRef<Path>,
+) -> Result<(Image<u8, 1>, ExifMetadata), IoError> {
+    read_image_jpeg_with_metadata_impl(file_path)
+}
+
+fn read_image_jpeg_with_metadata_impl<const N: usize>(
+    file_path: impl AsRef<Path>,
+) -> Result<(Image<u8, N>, ExifMetadata), IoError> {
+    let jpeg_data = read_jpeg_file(file_path)?;
+    let mut metadata = read_exif_metadata(&jpeg_data).unwrap_or_default();
+    let image = decode_jpeg_owned_impl(&jpeg_data)?;
+    let image = match metadata.orientation.as_mut() {
+        Some(orientation) => {
+            let image = apply_exif_orientation(&image, *orientation)?;
+            *orientation = ExifOrientation::Normal;
+            image
+        }
+        None => image,
+    };
+    Ok((image, metadata))
+}
+
+fn read_image_jpeg_oriented_impl<const N: usize>(
     file_path: impl AsRef<Path>,
 ) -> Result<Image<u8, N>, IoError> {
+    let jpeg_data = read_jpeg_file(file_path)?;
+    let orientation = read_exif_orientation(&jpeg_data).unwrap_or_default();
+    let image = decode_jpeg_owned_impl(&jpeg_data)?;
+    apply_exif_orientation(&image, orientation)
+}
+
+fn read_jpeg_file(file_path: impl AsRef<Path>) -> Result<Vec<u8>, IoError> {
     let file_path = file_path.as_ref().to_owned();
     if !file_path.exists() {
         return Err(IoError::FileDoesNotExist(file_path.to_path_buf()));
@@ -106,7 +245,17 @@ fn read_image_jpeg_impl<const N: usize>(
         return Err(IoError::InvalidFileExtension(file_path.to_path_buf()));
     }
 
-    let jpeg_data = fs::read(file_path)?;
+    Ok(fs::read(file_path)?)
+}
+
+fn read_image_jpeg_impl<const N: usize>(
+    file_path: impl AsRef<Path>,
+) -> Result<Image<u8, N>, IoError> {
+    let jpeg_data = read_jpeg_file(file_path)?;
+    decode_jpeg_owned_impl(&jpeg_data)
+}
+
+fn decode_jpeg_owned_impl<const N: usize>(jpeg_data: &[u8]) -> Result<Image<u8, N>, IoError> {
     let mut decoder = zune_jpeg::JpegDecoder::new(jpeg_data);
     decoder.decode_headers()?;
 
@@ -121,13 +270,32 @@ fn read_image_jpeg_impl<const N: usize>(
         height: image_info.height as usize,
     };
 
-    let img_data = decoder.decode()?;
+    let img_data = catch_decoder_panic(|| decoder.decode())?;
 
     Ok(Image::new(image_size, img_data)?)
 }
 
+// the decoder may panic on malformed data, which is reported as a decoding error
+fn catch_decoder_panic<R>(
+    decode: impl FnOnce() -> Result<R, zune_jpeg::errors::DecodeErrors>,
+) -> Result<R, IoError> {
+    let result =
+        std::panic::catch_unwind(std::panic::AssertUnwindSafe(decode)).unwrap_or_else(|_| {
+            Err(zune_jpeg::errors::DecodeErrors::Format(String::from(
+                "the decoder failed on malformed data",
+            )))
+        })?;
+    Ok(result)
+}
+
 fn decode_jpeg_impl<const C: usize>(src: &[u8], dst: &mut Image<u8, C>) -> Result<(), IoError> {
-    let mut decoder = zune_jpeg::JpegDecoder::new(src);
+    // convert the colors to the channels of the destination
+    let colorspace = match C {
+        1 => ColorSpace::Luma,
+        _ => ColorSpace::RGB,
+    };
+    let options = DecoderOptions::default().jpeg_set_out_colorspace(colorspace);
+    let mut decoder = zune_jpeg::JpegDecoder::new_with_options(src, options);
     decoder.decode_headers()?;
 
     let image_info = decoder.info().ok_or_else(|| {
@@ -145,8 +313,13 @@ fn decode_jpeg_impl<const C: usize>(src: &[u8], dst: &mut Image<u8, C>) -> Resul
         ));
     }
 
-    decoder.decode_into(dst.as_slice_mut())?;
-    Ok(())
+    // the decoder panics if the buffer does not match the decoded colors
+    let expected = decoder.output_buffer_size().unwrap_or_default();
+    if expected != dst.as_slice().len() {
+        return Err(IoError::InvalidBufferSize(dst.as_slice().len(), expected));
+    }
+
+    catch_decoder_panic(|| decoder.decode_into(dst.as_slice_mut()))
 }
 
 #[cfg(test)]
@@ -169,7 +342,7 @@ mod tests {
 
         let file_path = tmp_dir.path().join("dog.jpeg");
         let image_data = read_image_jpeg_rgb8("../../tests/data/dog.jpeg")?;
-        write_image_jpeg_rgb8(&file_path, &image_data)?;
+        write_image_jpeg_rgb8(&file_path, &image_data, 100)?;
 
         let image_data_back = read_image_jpeg_rgb8(&file_path)?;
         assert!(file_path.exists(), "File does not exist: {:?}", file_path);
@@ -181,6 +354,48 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn read_write_jpeg_with_metadata() -> Result<(), IoError> {
+        let tmp_dir = tempfile::tempdir()?;
+        let file_path = tmp_dir.path().join("rotated.jpeg");
+
+        let image = Image::<u8, 3>::from_size_val([6, 4].into(), 128)?;
+        let mut metadata = ExifMetadata {
+            orientation: Some(ExifOrientation::Rotate90),
+            date_time: Some("2024:05:01 12:30:00".to_string()),
+            make: Some("kornia".to_string()),
+            focal_length: Some(4.5),
+            ..Default::default()
+        };
+        metadata.set_intrinsics(&kornia_imgproc::calibration::CameraIntrinsic {
+            fx: 900.0,
+            fy: 910.0,
+            cx: 2.5,
+            cy: 1.5,
+        });
+        write_image_jpeg_rgb8_with_metadata(&file_path, &image, 90, &metadata)?;
+
+        let (image_back, metadata_back) = read_image_jpeg_rgb8_with_metadata(&file_path)?;
+        assert_eq!(image_back.cols(), 4);
+        assert_eq!(image_back.rows(), 6);
+        assert_eq!(metadata_back.orientation, Some(ExifOrientation::Normal));
+        assert_eq!(metadata_back.date_time, metadata.date_time);
+        assert_eq!(metadata_back.make, metadata.make);
+        assert_eq!(metadata_back.model, None);
+
+        let intrinsics = metadata_back
+            .intrinsics(image.size())
+            .expect("missing intrinsics");
+        assert!((intrinsics.fx - 900.0).abs() < 1e-2);
+        assert!((intrinsics.fy - 910.0).abs() < 1e-2);
+
+        // the plain reader ignores the metadata
+        let image_raw = read_image_jpeg_rgb8(&file_path)?;
+        assert_eq!(image_raw.cols(), 6);
+
+        Ok(())
+    }
+
     #[test]
     fn decode_jpeg() -> Result<(), IoError> {
         let bytes = read("../../tests/data/dog.jpeg")?;
@@ -193,4 +408,52 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn decode_jpeg_mismatched_dst() -> Result<(), IoError> {
+        let bytes = read("../../tests/data/dog.jpeg")?;
+
+        // the color image is converted to the channels of the destination
+        let mut gray: Image<u8, 1> = Image::from_size_val([258, 195].into(), 0)?;
+        decode_image_jpeg_mono8(&bytes, &mut gray)?;
+
+        let mut image: Image<u8, 3> = Image::from_size_val([16, 16].into(), 0)?;
+        assert!(decode_image_jpeg_rgb8(&bytes, &mut image).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn write_jpeg_quality() -> Result<(), IoError> {
+        let tmp_dir = tempfile::tempdir()?;
+        create_dir_all(tmp_dir.path())?;
+
+        let image_data = read_image_jpeg_rgb8("../../tests/data/dog.jpeg")?;
+
+        let file_path_low = tmp_dir.path().join("dog_low.jpeg");
+        write_image_jpeg_rgb8(&file_path_low, &image_data, 10)?;
+
+        let file_path_high = tmp_dir.path().join("dog_high.jpeg");
+        write_image_jpeg_rgb8(&file_path_high, &image_data, 95)?;
+
+        assert!(read(&file_path_low)?.len() < read(&file_path_high)?.len());
+
+        Ok(())
+    }
+
+    #[test]
+    fn read_write_jpeg_gray8() -> Result<(), IoError> {
+        let tmp_dir = tempfile::tempdir()?;
+        create_dir_all(tmp_dir.path())?;
+
+        let file_path = tmp_dir.path().join("gray.jpeg");
+        let image = Image::<u8, 1>::from_size_val([16, 8].into(), 100)?;
+        write_image_jpeg_gray8(&file_path, &image, 90)?;
+
+        let image_back = read_image_jpeg_mono8_oriented(&file_path)?;
+        assert_eq!(image_back.cols(), 16);
+        assert_eq!(image_back.rows(), 8);
+
+        Ok(())
+    }
 }
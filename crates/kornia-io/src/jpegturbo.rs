@@ -70,6 +70,27 @@ impl JpegTurboEncoder {
     ///
     /// The encoded data as `Vec<u8>`.
     pub fn encode_rgb8(&self, image: &Image<u8, 3>) -> Result<Vec<u8>, JpegTurboError> {
+        self.encode(image, turbojpeg::PixelFormat::RGB)
+    }
+
+    /// Encodes the given Gray/Mono8 image into a JPEG image.
+    ///
+    /// # Arguments
+    ///
+    /// * `image` - The image to encode.
+    ///
+    /// # Returns
+    ///
+    /// The encoded data as `Vec<u8>`.
+    pub fn encode_gray8(&self, image: &Image<u8, 1>) -> Result<Vec<u8>, JpegTurboError> {
+        self.encode(image, turbojpeg::PixelFormat::GRAY)
+    }
+
+    fn encode<const C: usize>(
+        &self,
+        image: &Image<u8, C>,
+        format: turbojpeg::PixelFormat,
+    ) -> Result<Vec<u8>, JpegTurboError> {
         // get the image data
         let image_data = image.as_slice();
 
@@ -77,9 +98,9 @@ impl JpegTurboEncoder {
         let buf = turbojpeg::Image {
             pixels: image_data,
             width: image.width(),
-            pitch: 3 * image.width(),
+            pitch: C * image.width(),
             height: image.height(),
-            format: turbojpeg::PixelFormat::RGB,
+            format,
         };
 
         // encode the image
@@ -165,7 +186,7 @@ impl JpegTurboDecoder {
     /// # Returns
     ///
     /// The decoded data as Image<u8, 1>.
-    pub fn decode_gray8(&self, jpeg_data: &[u8]) -> Result<Image<u8, 3>, JpegTurboError> {
+    pub fn decode_gray8(&self, jpeg_data: &[u8]) -> Result<Image<u8, 1>, JpegTurboError> {
         self.decode(jpeg_data, turbojpeg::PixelFormat::GRAY)
     }
 
@@ -184,7 +205,7 @@ impl JpegTurboDecoder {
         let buf = turbojpeg::Image {
             pixels: pixels.as_mut_slice(),
             width: image_size.width,
-            pitch: 3 * image_size.width, // we use no padding between rows
+            pitch: C * image_size.width, // we use no padding between rows
             height: image_size.height,
             format,
         };
@@ -229,4 +250,17 @@ mod tests {
         assert_eq!(image_back.num_channels(), 3);
         Ok(())
     }
+
+    #[test]
+    fn image_encoder_gray8() -> Result<(), Box<dyn std::error::Error>> {
+        let image = kornia_image::Image::<u8, 1>::from_size_val([16, 8].into(), 128)?;
+        let encoder = JpegTurboEncoder::new()?;
+        encoder.set_quality(90)?;
+        let jpeg_data = encoder.encode_gray8(&image)?;
+        let image_back = JpegTurboDecoder::new()?.decode_gray8(&jpeg_data)?;
+        assert_eq!(image_back.cols(), 16);
+        assert_eq!(image_back.rows(), 8);
+        assert_eq!(image_back.num_channels(), 1);
+        Ok(())
+    }
 }
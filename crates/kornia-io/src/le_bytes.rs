@@ -0,0 +1,41 @@
+/// A primitive value stored with its little endian bytes in the raw, NumPy and ROS 2 images.
+///
+/// The trait is private to the crate, which keeps the pixel traits built on it sealed.
+pub trait LeBytes: Copy + Sized {
+    /// Append the little endian bytes of the value.
+    fn write_le(&self, dst: &mut Vec<u8>);
+
+    /// Read the value from its little endian bytes.
+    fn read_le(bytes: &[u8]) -> Self;
+
+    /// Read the value from its big endian bytes.
+    #[cfg(feature = "ros2")]
+    fn read_be(bytes: &[u8]) -> Self;
+}
+
+macro_rules! impl_le_bytes {
+    ($($ty:ty),*) => {
+        $(
+            impl LeBytes for $ty {
+                fn write_le(&self, dst: &mut Vec<u8>) {
+                    dst.extend_from_slice(&self.to_le_bytes());
+                }
+
+                fn read_le(bytes: &[u8]) -> Self {
+                    let mut buf = [0u8; std::mem::size_of::<$ty>()];
+                    buf.copy_from_slice(bytes);
+                    <$ty>::from_le_bytes(buf)
+                }
+
+                #[cfg(feature = "ros2")]
+                fn read_be(bytes: &[u8]) -> Self {
+                    let mut buf = [0u8; std::mem::size_of::<$ty>()];
+                    buf.copy_from_slice(bytes);
+                    <$ty>::from_be_bytes(buf)
+                }
+            }
+        )*
+    };
+}
+
+impl_le_bytes!(u8, i8, u16, i16, u32, i32, f32, f64);
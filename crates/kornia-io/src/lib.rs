@@ -1,12 +1,26 @@
 #![deny(missing_docs)]
 #![doc = env!("CARGO_PKG_DESCRIPTION")]
 
+/// Asynchronous image reading for the tokio runtime.
+#[cfg(feature = "tokio")]
+pub mod async_io;
+
+/// Camera calibration files in the layout of the OpenCV calibration samples.
+#[cfg(feature = "serde")]
+pub mod calibration;
+
 /// Module to handle the error types for the io module.
 pub mod error;
 
 /// Module to handle the camera frame rate.
 pub mod fps_counter;
 
+/// EXIF metadata utilities.
+pub mod exif;
+
+// Shared reader of the TIFF structure used by EXIF and DNG.
+mod ifd;
+
 /// High-level read and write functions for images.
 pub mod functional;
 
@@ -20,11 +34,50 @@ pub mod png;
 /// JPEG image encoding and decoding.
 pub mod jpeg;
 
+/// TIFF image encoding and decoding.
+pub mod tiff;
+
+/// OpenEXR image encoding and decoding.
+pub mod exr;
+
+/// DNG raw image decoding.
+pub mod dng;
+
+// Shared little endian encoding of the samples of the raw, NumPy and ROS 2 images.
+mod le_bytes;
+
+/// NumPy `.npy` array encoding and decoding.
+pub mod npy;
+
+/// PGM, PPM and PFM image encoding and decoding.
+pub mod pnm;
+
+/// Raw binary image dumps with a small header.
+pub mod raw;
+
+/// RGB-D camera abstraction and recorded sequences.
+pub mod rgbd;
+
+/// Conversions between the ROS 2 sensor messages and kornia types.
+#[cfg(feature = "ros2")]
+pub mod ros2;
+
+/// Shared memory ring buffer of image frames between processes.
+pub mod shm;
+
+/// Synchronization of timestamped frames from multiple cameras.
+pub mod sync;
+
+/// Native windows to display images with overlays, in the style of `cv::imshow`.
+#[cfg(feature = "viz")]
+pub mod viz;
+
 /// GStreamer video module for real-time video processing.
 #[cfg(feature = "gstreamer")]
 pub mod stream;
 
 pub use crate::error::IoError;
+pub use crate::raw::ImageRawExt;
 
 /// Utility function to convert 16-bit `Vec<u8>` to `Vec<u16>`
 pub(crate) fn convert_buf_u8_u16(buf: Vec<u8>) -> Vec<u16> {
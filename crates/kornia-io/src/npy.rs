@@ -0,0 +1,290 @@
+use std::io::Write;
+use std::path::Path;
+
+use kornia_image::{Image, ImageSize};
+
+use crate::error::IoError;
+use crate::raw::RawSample;
+
+// the magic bytes identifying a numpy array file
+const NPY_MAGIC: &[u8; 6] = b"\x93NUMPY";
+// the header is padded with spaces so that the data is aligned to 64 bytes
+const NPY_ALIGNMENT: usize = 64;
+
+/// A pixel type that can be stored in a numpy array file.
+pub trait NpySample: RawSample {
+    /// The numpy type descriptor of the little endian values, e.g. `<f4`.
+    const DESCR: &'static str;
+}
+
+macro_rules! impl_npy_sample {
+    ($($ty:ty => $descr:expr),*) => {
+        $(
+            impl NpySample for $ty {
+                const DESCR: &'static str = $descr;
+            }
+        )*
+    };
+}
+
+impl_npy_sample!(u8 => "|u1", u16 => "<u2", u32 => "<u4", i16 => "<i2", i32 => "<i4", f32 => "<f4", f64 => "<f8");
+
+fn invalid_header(msg: impl Into<String>) -> IoError {
+    IoError::InvalidImageHeader(msg.into())
+}
+
+// the value of a key of the header dictionary, e.g. `'descr': '<f4'`
+fn header_value<'a>(header: &'a str, key: &str) -> Result<&'a str, IoError> {
+    let pattern = format!("'{key}':");
+    let start = header
+        .find(&pattern)
+        .ok_or_else(|| invalid_header(format!("missing {key} in the npy header")))?;
+    Ok(header[start + pattern.len()..].trim_start())
+}
+
+// the type descriptor, the fortran order flag and the shape of the header dictionary
+fn parse_header(header: &str) -> Result<(String, bool, Vec<usize>), IoError> {
+    let descr = header_value(header, "descr")?;
+    let descr = descr
+        .strip_prefix('\'')
+        .and_then(|descr| descr.split('\'').next())
+        .ok_or_else(|| invalid_header("invalid descr in the npy header"))?;
+
+    let fortran_order = header_value(header, "fortran_order")?.starts_with("True");
+
+    let shape = header_value(header, "shape")?;
+    let shape = shape
+        .strip_prefix('(')
+        .and_then(|shape| shape.split(')').next())
+        .ok_or_else(|| invalid_header("invalid shape in the npy header"))?;
+    let shape = shape
+        .split(',')
+        .map(str::trim)
+        .filter(|dim| !dim.is_empty())
+        .map(|dim| {
+            dim.parse::<usize>()
+                .map_err(|_| invalid_header(format!("invalid dimension {dim} in the npy header")))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok((descr.to_string(), fortran_order, shape))
+}
+
+// the descriptors are equal up to the byte order of the little endian or single byte types
+fn descr_matches(descr: &str, expected: &str) -> bool {
+    let strip = |d: &str| d.trim_start_matches(['<', '|', '=']).to_string();
+    (descr.starts_with(['<', '|']) || (descr.starts_with('=') && cfg!(target_endian = "little")))
+        && strip(descr) == strip(expected)
+}
+
+/// Reads an image from a numpy array file.
+///
+/// The array must be stored in C order with the shape (H, W, C), or (H, W) for the images
+/// with a single channel, and the data type of the pixels, e.g. `float32` for `f32`.
+///
+/// # Arguments
+///
+/// * `file_path` - The path to the `.npy` file.
+///
+/// # Returns
+///
+/// The image with the size and the values of the array.
+///
+/// # Errors
+///
+/// Returns an error if the data type, the order or the shape does not match the image.
+pub fn read_image_npy<T: NpySample, const C: usize>(
+    file_path: impl AsRef<Path>,
+) -> Result<Image<T, C>, IoError> {
+    // verify the file exists
+    let file_path = file_path.as_ref();
+    if !file_path.exists() {
+        return Err(IoError::FileDoesNotExist(file_path.to_path_buf()));
+    }
+
+    decode_image_npy(&std::fs::read(file_path)?)
+}
+
+/// Decodes an image from the raw bytes of a numpy array file.
+///
+/// See [`read_image_npy`] for the supported arrays.
+///
+/// # Arguments
+///
+/// * `src` - The raw bytes of the `.npy` file.
+///
+/// # Returns
+///
+/// The image with the size and the values of the array.
+pub fn decode_image_npy<T: NpySample, const C: usize>(src: &[u8]) -> Result<Image<T, C>, IoError> {
+    if src.len() < 10 || &src[..6] != NPY_MAGIC {
+        return Err(invalid_header("not a npy file"));
+    }
+
+    // the length of the header is stored in 2 bytes in the version 1 and 4 bytes after
+    let (header_start, header_len): (usize, usize) = match src[6] {
+        1 => (10, u16::from_le_bytes([src[8], src[9]]) as usize),
+        2 | 3 if src.len() >= 12 => (
+            12,
+            u32::from_le_bytes([src[8], src[9], src[10], src[11]]) as usize,
+        ),
+        version => return Err(invalid_header(format!("unsupported npy version {version}"))),
+    };
+    let header = header_start
+        .checked_add(header_len)
+        .and_then(|header_end| src.get(header_start..header_end))
+        .ok_or_else(|| invalid_header("truncated npy header"))?;
+    let header = std::str::from_utf8(header).map_err(|_| invalid_header("invalid npy header"))?;
+
+    let (descr, fortran_order, shape) = parse_header(header)?;
+    if !descr_matches(&descr, T::DESCR) {
+        return Err(invalid_header(format!(
+            "expected src type {}, found {descr}",
+            T::DESCR
+        )));
+    }
+    if fortran_order {
+        return Err(invalid_header("fortran ordered arrays are not supported"));
+    }
+
+    let (height, width) = match shape[..] {
+        [height, width] if C == 1 => (height, width),
+        [height, width, channels] if channels == C => (height, width),
+        _ => {
+            return Err(invalid_header(format!(
+                "expected the shape (H, W, {C}), found {shape:?}"
+            )))
+        }
+    };
+
+    let sample_size = std::mem::size_of::<T>();
+    let num_bytes = height
+        .checked_mul(width)
+        .and_then(|n| n.checked_mul(C * sample_size))
+        .ok_or_else(|| invalid_header(format!("invalid shape {shape:?}")))?;
+    let pixels = &src[header_start + header_len..];
+    if pixels.len() != num_bytes {
+        return Err(invalid_header(format!(
+            "expected {num_bytes} bytes of src, found {}",
+            pixels.len()
+        )));
+    }
+
+    Ok(Image::new(
+        ImageSize { width, height },
+        pixels.chunks_exact(sample_size).map(T::read_le).collect(),
+    )?)
+}
+
+/// Writes an image to a numpy array file.
+///
+/// The array is stored in C order with the shape (H, W, C), or (H, W) for the images with a
+/// single channel, so that it can be loaded with `numpy.load`.
+///
+/// # Arguments
+///
+/// * `file_path` - The path to the `.npy` file.
+/// * `image` - The image to write.
+pub fn write_image_npy<T: NpySample, const C: usize>(
+    file_path: impl AsRef<Path>,
+    image: &Image<T, C>,
+) -> Result<(), IoError> {
+    let shape = match C {
+        1 => format!("({}, {})", image.rows(), image.cols()),
+        _ => format!("({}, {}, {})", image.rows(), image.cols(), C),
+    };
+    let mut header = format!(
+        "{{'descr': '{}', 'fortran_order': False, 'shape': {}, }}",
+        T::DESCR,
+        shape
+    );
+    // pad the header with spaces and end it with a new line
+    let unpadded_len = NPY_MAGIC.len() + 4 + header.len() + 1;
+    let padding = (NPY_ALIGNMENT - unpadded_len % NPY_ALIGNMENT) % NPY_ALIGNMENT;
+    header.extend(std::iter::repeat(' ').take(padding));
+    header.push('\n');
+
+    let mut data = Vec::with_capacity(
+        NPY_MAGIC.len() + 4 + header.len() + std::mem::size_of_val(image.as_slice()),
+    );
+    data.extend_from_slice(NPY_MAGIC);
+    data.extend_from_slice(&[1, 0]);
+    data.extend_from_slice(&(header.len() as u16).to_le_bytes());
+    data.extend_from_slice(header.as_bytes());
+    for v in image.as_slice() {
+        v.write_le(&mut data);
+    }
+
+    let mut writer = std::io::BufWriter::new(std::fs::File::create(file_path)?);
+    writer.write_all(&data)?;
+    writer.flush()?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_write_npy() -> Result<(), IoError> {
+        let tmp_dir = tempfile::tempdir()?;
+
+        let image = Image::<f32, 3>::new([2, 1].into(), vec![0.0, 0.5, 1.0, -1.0, 2.5, 3.0])?;
+        let file_path = tmp_dir.path().join("rgb.npy");
+        write_image_npy(&file_path, &image)?;
+
+        // the data is aligned to 64 bytes
+        let data = std::fs::read(&file_path)?;
+        let data_start = data.len() - 6 * 4;
+        assert_eq!(data_start % 64, 0);
+        assert!(std::str::from_utf8(&data[10..data_start])
+            .is_ok_and(|header| header.contains("'shape': (1, 2, 3)")));
+
+        let read = read_image_npy::<f32, 3>(&file_path)?;
+        assert_eq!(read.size(), image.size());
+        assert_eq!(read.as_slice(), image.as_slice());
+        assert!(read_image_npy::<f32, 1>(&file_path).is_err());
+        assert!(read_image_npy::<u8, 3>(&file_path).is_err());
+
+        let gray = Image::<u8, 1>::new([3, 2].into(), vec![1, 2, 3, 4, 5, 6])?;
+        let file_path = tmp_dir.path().join("gray.npy");
+        write_image_npy(&file_path, &gray)?;
+        let read = read_image_npy::<u8, 1>(&file_path)?;
+        assert_eq!(read.as_slice(), gray.as_slice());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_numpy_header() -> Result<(), IoError> {
+        let tmp_dir = tempfile::tempdir()?;
+
+        // a uint16 array of shape (2, 2) as written by `numpy.save` with the version 2
+        let header = "{'descr': '<u2', 'fortran_order': False, 'shape': (2, 2), }";
+        let mut data = NPY_MAGIC.to_vec();
+        data.extend_from_slice(&[2, 0]);
+        data.extend_from_slice(&(header.len() as u32 + 1).to_le_bytes());
+        data.extend_from_slice(header.as_bytes());
+        data.push(b'\n');
+        for v in [1u16, 2, 300, 4] {
+            data.extend_from_slice(&v.to_le_bytes());
+        }
+        let file_path = tmp_dir.path().join("u16.npy");
+        std::fs::write(&file_path, &data)?;
+
+        let image = read_image_npy::<u16, 1>(&file_path)?;
+        assert_eq!(image.as_slice(), &[1, 2, 300, 4]);
+
+        // a big endian array
+        let big_endian = String::from_utf8_lossy(&data).replace("<u2", ">u2");
+        std::fs::write(&file_path, big_endian.as_bytes())?;
+        assert!(read_image_npy::<u16, 1>(&file_path).is_err());
+
+        // a truncated array
+        std::fs::write(&file_path, &data[..data.len() - 1])?;
+        assert!(read_image_npy::<u16, 1>(&file_path).is_err());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,537 @@
+use std::io::Write;
+use std::path::Path;
+
+use kornia_image::{Image, ImageSize};
+
+use crate::error::IoError;
+
+/// Read a binary PGM image with a single channel (mono8).
+///
+/// # Arguments
+///
+/// * `file_path` - The path to the PGM file.
+///
+/// # Returns
+///
+/// A grayscale image with a single channel (mono8).
+pub fn read_image_pgm_mono8(file_path: impl AsRef<Path>) -> Result<Image<u8, 1>, IoError> {
+    decode_image_pgm_mono8(&read_file(file_path)?)
+}
+
+/// Decode a binary PGM image with a single channel (mono8) from its raw bytes.
+///
+/// # Arguments
+///
+/// * `src` - The raw bytes of the PGM file.
+///
+/// # Returns
+///
+/// A grayscale image with a single channel (mono8).
+pub fn decode_image_pgm_mono8(src: &[u8]) -> Result<Image<u8, 1>, IoError> {
+    let (header, pixels) = parse_header(src, b"P5")?;
+    if header.max_value > 255 {
+        return Err(invalid_header("expected an 8-bit image"));
+    }
+    Ok(Image::new(
+        header.size,
+        take_pixels(pixels, header.size, 1)?.to_vec(),
+    )?)
+}
+
+/// Read a binary PGM image with a single channel (mono16).
+///
+/// The values of 8-bit images are kept as is, i.e. they are not rescaled.
+///
+/// # Arguments
+///
+/// * `file_path` - The path to the PGM file.
+///
+/// # Returns
+///
+/// A grayscale image with a single channel (mono16).
+pub fn read_image_pgm_mono16(file_path: impl AsRef<Path>) -> Result<Image<u16, 1>, IoError> {
+    decode_image_pgm_mono16(&read_file(file_path)?)
+}
+
+/// Decode a binary PGM image with a single channel (mono16) from its raw bytes.
+///
+/// The values of 8-bit images are kept as is, i.e. they are not rescaled.
+///
+/// # Arguments
+///
+/// * `src` - The raw bytes of the PGM file.
+///
+/// # Returns
+///
+/// A grayscale image with a single channel (mono16).
+pub fn decode_image_pgm_mono16(src: &[u8]) -> Result<Image<u16, 1>, IoError> {
+    let (header, pixels) = parse_header(src, b"P5")?;
+    if header.max_value <= 255 {
+        let pixels = take_pixels(pixels, header.size, 1)?;
+        return Ok(Image::new(
+            header.size,
+            pixels.iter().map(|&v| v as u16).collect(),
+        )?);
+    }
+    let pixels = take_pixels(pixels, header.size, 2)?;
+    Ok(Image::new(
+        header.size,
+        crate::convert_buf_u8_u16(pixels.to_vec()),
+    )?)
+}
+
+/// Read a binary PPM image with three channels (rgb8).
+///
+/// # Arguments
+///
+/// * `file_path` - The path to the PPM file.
+///
+/// # Returns
+///
+/// A RGB image with three channels (rgb8).
+pub fn read_image_ppm_rgb8(file_path: impl AsRef<Path>) -> Result<Image<u8, 3>, IoError> {
+    decode_image_ppm_rgb8(&read_file(file_path)?)
+}
+
+/// Decode a binary PPM image with three channels (rgb8) from its raw bytes.
+///
+/// # Arguments
+///
+/// * `src` - The raw bytes of the PPM file.
+///
+/// # Returns
+///
+/// A RGB image with three channels (rgb8).
+pub fn decode_image_ppm_rgb8(src: &[u8]) -> Result<Image<u8, 3>, IoError> {
+    let (header, pixels) = parse_header(src, b"P6")?;
+    if header.max_value > 255 {
+        return Err(invalid_header("expected an 8-bit image"));
+    }
+    Ok(Image::new(
+        header.size,
+        take_pixels(pixels, header.size, 3)?.to_vec(),
+    )?)
+}
+
+/// Writes the given PGM _(mono8)_ data to the given file path.
+///
+/// # Arguments
+///
+/// - `file_path` - The path to the PGM image.
+/// - `image` - The tensor containing the PGM image data.
+pub fn write_image_pgm_mono8(
+    file_path: impl AsRef<Path>,
+    image: &Image<u8, 1>,
+) -> Result<(), IoError> {
+    write_file(
+        file_path,
+        format!("P5\n{} {}\n255\n", image.cols(), image.rows()),
+        image.as_slice(),
+    )
+}
+
+/// Writes the given PGM _(mono16)_ data to the given file path.
+///
+/// # Arguments
+///
+/// - `file_path` - The path to the PGM image.
+/// - `image` - The tensor containing the PGM image data.
+pub fn write_image_pgm_mono16(
+    file_path: impl AsRef<Path>,
+    image: &Image<u16, 1>,
+) -> Result<(), IoError> {
+    write_file(
+        file_path,
+        format!("P5\n{} {}\n65535\n", image.cols(), image.rows()),
+        &crate::convert_buf_u16_u8(image.as_slice()),
+    )
+}
+
+/// Writes the given PPM _(rgb8)_ data to the given file path.
+///
+/// # Arguments
+///
+/// - `file_path` - The path to the PPM image.
+/// - `image` - The tensor containing the PPM image data.
+pub fn write_image_ppm_rgb8(
+    file_path: impl AsRef<Path>,
+    image: &Image<u8, 3>,
+) -> Result<(), IoError> {
+    write_file(
+        file_path,
+        format!("P6\n{} {}\n255\n", image.cols(), image.rows()),
+        image.as_slice(),
+    )
+}
+
+/// Read a PFM image with a single channel (mono32f).
+///
+/// PFM stores the rows from bottom to top, the image is flipped to the usual top to bottom order.
+///
+/// # Arguments
+///
+/// * `file_path` - The path to the PFM file.
+///
+/// # Returns
+///
+/// A grayscale image with a single channel (mono32f).
+pub fn read_image_pfm_mono32f(file_path: impl AsRef<Path>) -> Result<Image<f32, 1>, IoError> {
+    decode_pfm_impl(&read_file(file_path)?, b"Pf")
+}
+
+/// Decode a PFM image with a single channel (mono32f) from its raw bytes.
+///
+/// # Arguments
+///
+/// * `src` - The raw bytes of the PFM file.
+///
+/// # Returns
+///
+/// A grayscale image with a single channel (mono32f).
+pub fn decode_image_pfm_mono32f(src: &[u8]) -> Result<Image<f32, 1>, IoError> {
+    decode_pfm_impl(src, b"Pf")
+}
+
+/// Read a PFM image with three channels (rgb32f).
+///
+/// PFM stores the rows from bottom to top, the image is flipped to the usual top to bottom order.
+///
+/// # Arguments
+///
+/// * `file_path` - The path to the PFM file.
+///
+/// # Returns
+///
+/// A RGB image with three channels (rgb32f).
+pub fn read_image_pfm_rgb32f(file_path: impl AsRef<Path>) -> Result<Image<f32, 3>, IoError> {
+    decode_pfm_impl(&read_file(file_path)?, b"PF")
+}
+
+/// Decode a PFM image with three channels (rgb32f) from its raw bytes.
+///
+/// # Arguments
+///
+/// * `src` - The raw bytes of the PFM file.
+///
+/// # Returns
+///
+/// A RGB image with three channels (rgb32f).
+pub fn decode_image_pfm_rgb32f(src: &[u8]) -> Result<Image<f32, 3>, IoError> {
+    decode_pfm_impl(src, b"PF")
+}
+
+/// Writes the given PFM _(mono32f)_ data to the given file path.
+///
+/// # Arguments
+///
+/// - `file_path` - The path to the PFM image.
+/// - `image` - The tensor containing the PFM image data.
+pub fn write_image_pfm_mono32f(
+    file_path: impl AsRef<Path>,
+    image: &Image<f32, 1>,
+) -> Result<(), IoError> {
+    write_pfm_impl(file_path, image, "Pf")
+}
+
+/// Writes the given PFM _(rgb32f)_ data to the given file path.
+///
+/// # Arguments
+///
+/// - `file_path` - The path to the PFM image.
+/// - `image` - The tensor containing the PFM image data.
+pub fn write_image_pfm_rgb32f(
+    file_path: impl AsRef<Path>,
+    image: &Image<f32, 3>,
+) -> Result<(), IoError> {
+    write_pfm_impl(file_path, image, "PF")
+}
+
+fn decode_pfm_impl<const C: usize>(src: &[u8], magic: &[u8]) -> Result<Image<f32, C>, IoError> {
+    let (header, pixels) = parse_header(src, magic)?;
+
+    // the sign of the scale encodes the byte order
+    let little_endian = header.scale < 0.0;
+    let pixels = take_pixels(pixels, header.size, 4 * C)?;
+    let row_len = header.size.width * C;
+
+    let mut values = Vec::with_capacity(pixels.len() / 4);
+    for row in pixels.chunks_exact((row_len * 4).max(1)).rev() {
+        values.extend(row.chunks_exact(4).map(|b| {
+            let bytes = [b[0], b[1], b[2], b[3]];
+            if little_endian {
+                f32::from_le_bytes(bytes)
+            } else {
+                f32::from_be_bytes(bytes)
+            }
+        }));
+    }
+
+    Ok(Image::new(header.size, values)?)
+}
+
+fn write_pfm_impl<const C: usize>(
+    file_path: impl AsRef<Path>,
+    image: &Image<f32, C>,
+    magic: &str,
+) -> Result<(), IoError> {
+    let row_len = image.cols() * C;
+    let mut data = Vec::with_capacity(image.as_slice().len() * 4);
+    for row in image.as_slice().chunks_exact(row_len.max(1)).rev() {
+        for v in row {
+            data.extend_from_slice(&v.to_le_bytes());
+        }
+    }
+
+    // a negative scale means little endian
+    write_file(
+        file_path,
+        format!("{magic}\n{} {}\n-1.0\n", image.cols(), image.rows()),
+        &data,
+    )
+}
+
+// the fields of a netpbm header
+struct Header {
+    size: ImageSize,
+    // the maximum value for PGM/PPM
+    max_value: u32,
+    // the scale and byte order for PFM
+    scale: f32,
+}
+
+fn invalid_header(msg: &str) -> IoError {
+    IoError::InvalidImageHeader(msg.to_string())
+}
+
+// parse the header and return the remaining bytes with the pixel data
+fn parse_header<'a>(data: &'a [u8], magic: &[u8]) -> Result<(Header, &'a [u8]), IoError> {
+    let mut pos = 0;
+    let mut next_token = || -> Result<&'a str, IoError> {
+        // skip the whitespace and the comments
+        loop {
+            match data.get(pos) {
+                Some(b) if b.is_ascii_whitespace() => pos += 1,
+                Some(b'#') => {
+                    while data.get(pos).is_some_and(|&b| b != b'\n') {
+                        pos += 1;
+                    }
+                }
+                Some(_) => break,
+                None => return Err(invalid_header("unexpected end of file")),
+            }
+        }
+        let start = pos;
+        while data.get(pos).is_some_and(|b| !b.is_ascii_whitespace()) {
+            pos += 1;
+        }
+        std::str::from_utf8(&data[start..pos]).map_err(|_| invalid_header("invalid token"))
+    };
+
+    let file_magic = next_token()?;
+    if file_magic.as_bytes() != magic {
+        return Err(invalid_header(&format!(
+            "expected magic {}, found {file_magic}",
+            String::from_utf8_lossy(magic)
+        )));
+    }
+
+    let mut parse_usize = || -> Result<usize, IoError> {
+        next_token()?
+            .parse()
+            .map_err(|_| invalid_header("invalid image size"))
+    };
+    let width = parse_usize()?;
+    let height = parse_usize()?;
+
+    let last = next_token()?;
+    let (max_value, scale) = if magic.starts_with(b"P") && magic[1].is_ascii_digit() {
+        let max_value = last
+            .parse::<u32>()
+            .ok()
+            .filter(|v| (1..=65535).contains(v))
+            .ok_or_else(|| invalid_header("invalid maximum value"))?;
+        (max_value, 1.0)
+    } else {
+        let scale = last
+            .parse::<f32>()
+            .ok()
+            .filter(|v| *v != 0.0)
+            .ok_or_else(|| invalid_header("invalid scale"))?;
+        (0, scale)
+    };
+
+    // a single whitespace separates the header from the pixel data
+    let header = Header {
+        size: ImageSize { width, height },
+        max_value,
+        scale,
+    };
+    Ok((header, data.get(pos + 1..).unwrap_or_default()))
+}
+
+// take the pixel bytes checking that the file is not truncated
+fn take_pixels(pixels: &[u8], size: ImageSize, bytes_per_pixel: usize) -> Result<&[u8], IoError> {
+    let expected = size
+        .width
+        .checked_mul(size.height)
+        .and_then(|n| n.checked_mul(bytes_per_pixel))
+        .ok_or_else(|| invalid_header("invalid image size"))?;
+    pixels
+        .get(..expected)
+        .ok_or(IoError::InvalidBufferSize(pixels.len(), expected))
+}
+
+fn read_file(file_path: impl AsRef<Path>) -> Result<Vec<u8>, IoError> {
+    // verify the file exists
+    let file_path = file_path.as_ref();
+    if !file_path.exists() {
+        return Err(IoError::FileDoesNotExist(file_path.to_path_buf()));
+    }
+
+    Ok(std::fs::read(file_path)?)
+}
+
+fn write_file(file_path: impl AsRef<Path>, header: String, data: &[u8]) -> Result<(), IoError> {
+    let mut writer = std::io::BufWriter::new(std::fs::File::create(file_path)?);
+    writer.write_all(header.as_bytes())?;
+    writer.write_all(data)?;
+    writer.flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn read_write_pgm() -> Result<(), IoError> {
+        let tmp_dir = tempfile::tempdir()?;
+
+        let file_path = tmp_dir.path().join("gray.pgm");
+        let image = Image::<u8, 1>::new([3, 2].into(), vec![0, 1, 2, 3, 4, 255])?;
+        write_image_pgm_mono8(&file_path, &image)?;
+
+        let image_back = read_image_pgm_mono8(&file_path)?;
+        assert_eq!(image_back.size(), image.size());
+        assert_eq!(image_back.as_slice(), image.as_slice());
+
+        // 8-bit images can be read as 16-bit, but not the opposite
+        let image_back = read_image_pgm_mono16(&file_path)?;
+        assert_eq!(image_back.as_slice(), &[0, 1, 2, 3, 4, 255]);
+
+        let file_path = tmp_dir.path().join("gray16.pgm");
+        let image = Image::<u16, 1>::new([2, 2].into(), vec![0, 256, 1000, 65535])?;
+        write_image_pgm_mono16(&file_path, &image)?;
+
+        let image_back = read_image_pgm_mono16(&file_path)?;
+        assert_eq!(image_back.as_slice(), image.as_slice());
+        assert!(matches!(
+            read_image_pgm_mono8(&file_path),
+            Err(IoError::InvalidImageHeader(_))
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn read_write_ppm() -> Result<(), IoError> {
+        let tmp_dir = tempfile::tempdir()?;
+
+        let file_path = tmp_dir.path().join("rgb.ppm");
+        let image = Image::<u8, 3>::new([2, 1].into(), vec![255, 0, 0, 0, 128, 255])?;
+        write_image_ppm_rgb8(&file_path, &image)?;
+
+        let image_back = read_image_ppm_rgb8(&file_path)?;
+        assert_eq!(image_back.size(), image.size());
+        assert_eq!(image_back.as_slice(), image.as_slice());
+
+        // wrong magic number
+        assert!(matches!(
+            read_image_pgm_mono8(&file_path),
+            Err(IoError::InvalidImageHeader(_))
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn read_pgm_with_comments() -> Result<(), IoError> {
+        let tmp_dir = tempfile::tempdir()?;
+
+        let file_path = tmp_dir.path().join("comments.pgm");
+        let mut data = b"P5\n# created by hand\n2 2 # size\n255\n".to_vec();
+        data.extend_from_slice(&[10, 20, 30, 40]);
+        std::fs::write(&file_path, &data)?;
+
+        let image = read_image_pgm_mono8(&file_path)?;
+        assert_eq!(image.as_slice(), &[10, 20, 30, 40]);
+
+        // truncated pixel data
+        std::fs::write(&file_path, &data[..data.len() - 1])?;
+        assert!(matches!(
+            read_image_pgm_mono8(&file_path),
+            Err(IoError::InvalidBufferSize(3, 4))
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn read_write_pfm() -> Result<(), IoError> {
+        let tmp_dir = tempfile::tempdir()?;
+
+        let file_path = tmp_dir.path().join("disparity.pfm");
+        let image = Image::<f32, 1>::new([2, 2].into(), vec![0.5, -1.25, f32::INFINITY, 1e6])?;
+        write_image_pfm_mono32f(&file_path, &image)?;
+
+        let image_back = read_image_pfm_mono32f(&file_path)?;
+        assert_eq!(image_back.size(), image.size());
+        assert_eq!(image_back.as_slice(), image.as_slice());
+
+        let file_path = tmp_dir.path().join("color.pfm");
+        let image = Image::<f32, 3>::new([1, 2].into(), vec![0.0, 0.1, 0.2, 1.0, 1.1, 1.2])?;
+        write_image_pfm_rgb32f(&file_path, &image)?;
+
+        let image_back = read_image_pfm_rgb32f(&file_path)?;
+        assert_eq!(image_back.as_slice(), image.as_slice());
+
+        Ok(())
+    }
+
+    #[test]
+    fn read_pfm_big_endian() -> Result<(), IoError> {
+        let tmp_dir = tempfile::tempdir()?;
+
+        // the rows are stored from bottom to top
+        let file_path = tmp_dir.path().join("big_endian.pfm");
+        let mut data = b"Pf\n1 2\n1.0\n".to_vec();
+        data.extend_from_slice(&2.0f32.to_be_bytes());
+        data.extend_from_slice(&1.0f32.to_be_bytes());
+        std::fs::write(&file_path, &data)?;
+
+        let image = read_image_pfm_mono32f(&file_path)?;
+        assert_eq!(image.as_slice(), &[1.0, 2.0]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn decode_invalid_headers() -> Result<(), IoError> {
+        // the number of pixels overflows
+        assert!(decode_image_pgm_mono8(b"P5\n4294967296 4294967296\n255\n").is_err());
+        assert!(decode_image_ppm_rgb8(b"P6\n18446744073709551615 2\n255\n").is_err());
+        assert!(decode_image_pfm_rgb32f(b"PF\n4294967296 4294967296\n-1.0\n").is_err());
+
+        // invalid numbers and truncated pixels
+        assert!(decode_image_pgm_mono8(b"P5\n-1 2\n255\n").is_err());
+        assert!(decode_image_pgm_mono16(b"P5\n2 2\n65535\n\x00\x01").is_err());
+
+        // an empty image
+        let image = decode_image_pgm_mono8(b"P5\n6 0\n255\n")?;
+        assert_eq!(image.cols(), 6);
+        assert_eq!(image.rows(), 0);
+        assert!(image.as_slice().is_empty());
+
+        Ok(())
+    }
+}
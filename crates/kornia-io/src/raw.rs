@@ -0,0 +1,253 @@
+use std::io::Write;
+use std::path::Path;
+
+use kornia_image::{Image, ImageSize};
+
+use crate::error::IoError;
+use crate::le_bytes::LeBytes;
+
+// the magic bytes identifying a raw image dump
+const RAW_MAGIC: &[u8; 4] = b"KRAW";
+const RAW_VERSION: u8 = 1;
+// magic, version, data type, padding, width, height and channels
+const RAW_HEADER_LEN: usize = 20;
+
+/// A pixel type that can be stored in a raw image dump.
+pub trait RawSample: LeBytes {
+    /// The identifier of the data type stored in the header.
+    const DTYPE: u8;
+}
+
+macro_rules! impl_raw_sample {
+    ($($ty:ty => $dtype:expr),*) => {
+        $(
+            impl RawSample for $ty {
+                const DTYPE: u8 = $dtype;
+            }
+        )*
+    };
+}
+
+impl_raw_sample!(u8 => 0, u16 => 1, u32 => 2, i16 => 3, i32 => 4, f32 => 5, f64 => 6);
+
+/// Writes an image to a raw binary file with a small header.
+///
+/// The file stores the data type, size and number of channels followed by the
+/// pixel data in little endian. This is useful to dump intermediate results of
+/// any pixel type, e.g. response maps, and read them back losslessly.
+///
+/// # Arguments
+///
+/// * `file_path` - The path to the raw file.
+/// * `image` - The image to write.
+pub fn write_image_raw<T: RawSample, const C: usize>(
+    file_path: impl AsRef<Path>,
+    image: &Image<T, C>,
+) -> Result<(), IoError> {
+    let mut data = Vec::with_capacity(RAW_HEADER_LEN + std::mem::size_of_val(image.as_slice()));
+    data.extend_from_slice(RAW_MAGIC);
+    data.extend_from_slice(&[RAW_VERSION, T::DTYPE, 0, 0]);
+    data.extend_from_slice(&(image.cols() as u32).to_le_bytes());
+    data.extend_from_slice(&(image.rows() as u32).to_le_bytes());
+    data.extend_from_slice(&(C as u32).to_le_bytes());
+    for v in image.as_slice() {
+        v.write_le(&mut data);
+    }
+
+    let mut writer = std::io::BufWriter::new(std::fs::File::create(file_path)?);
+    writer.write_all(&data)?;
+    writer.flush()?;
+
+    Ok(())
+}
+
+/// Reads an image from a raw binary file written by [`write_image_raw`].
+///
+/// # Arguments
+///
+/// * `file_path` - The path to the raw file.
+///
+/// # Returns
+///
+/// The image with the same pixel type, size and channels as written.
+///
+/// # Errors
+///
+/// Returns an error if the data type or the number of channels does not match the file.
+pub fn read_image_raw<T: RawSample, const C: usize>(
+    file_path: impl AsRef<Path>,
+) -> Result<Image<T, C>, IoError> {
+    // verify the file exists
+    let file_path = file_path.as_ref();
+    if !file_path.exists() {
+        return Err(IoError::FileDoesNotExist(file_path.to_path_buf()));
+    }
+
+    decode_image_raw(&std::fs::read(file_path)?)
+}
+
+/// Decodes an image from the raw bytes of a file written by [`write_image_raw`].
+///
+/// # Arguments
+///
+/// * `src` - The raw bytes of the file.
+///
+/// # Returns
+///
+/// The image with the same pixel type, size and channels as written.
+///
+/// # Errors
+///
+/// Returns an error if the data type or the number of channels does not match the file.
+pub fn decode_image_raw<T: RawSample, const C: usize>(src: &[u8]) -> Result<Image<T, C>, IoError> {
+    let header = src
+        .get(..RAW_HEADER_LEN)
+        .ok_or_else(|| IoError::InvalidImageHeader("truncated raw header".to_string()))?;
+    if &header[..4] != RAW_MAGIC || header[4] != RAW_VERSION {
+        return Err(IoError::InvalidImageHeader(
+            "not a raw image file".to_string(),
+        ));
+    }
+    if header[5] != T::DTYPE {
+        return Err(IoError::InvalidImageHeader(format!(
+            "expected data type {}, found {}",
+            T::DTYPE,
+            header[5]
+        )));
+    }
+
+    let read_u32 = |offset: usize| {
+        u32::from_le_bytes([
+            header[offset],
+            header[offset + 1],
+            header[offset + 2],
+            header[offset + 3],
+        ]) as usize
+    };
+    let size = ImageSize {
+        width: read_u32(8),
+        height: read_u32(12),
+    };
+    let channels = read_u32(16);
+    if channels != C {
+        return Err(IoError::InvalidImageHeader(format!(
+            "expected {C} channels, found {channels}"
+        )));
+    }
+
+    let sample_size = std::mem::size_of::<T>();
+    let expected = size
+        .width
+        .checked_mul(size.height)
+        .and_then(|n| n.checked_mul(C * sample_size))
+        .ok_or_else(|| IoError::InvalidImageHeader("invalid image size".to_string()))?;
+    let pixels = &src[RAW_HEADER_LEN..];
+    if pixels.len() != expected {
+        return Err(IoError::InvalidBufferSize(pixels.len(), expected));
+    }
+
+    let values = pixels.chunks_exact(sample_size).map(T::read_le).collect();
+
+    Ok(Image::new(size, values)?)
+}
+
+/// Raw binary dumps of an image as methods, see [`write_image_raw`] and [`read_image_raw`].
+///
+/// # Example
+///
+/// ```no_run
+/// use kornia_image::Image;
+/// use kornia_io::ImageRawExt;
+///
+/// let image = Image::<f32, 1>::from_size_val([4, 4].into(), 0.5).unwrap();
+/// image.write_raw("response.raw").unwrap();
+///
+/// let image_back = Image::<f32, 1>::read_raw("response.raw").unwrap();
+/// ```
+pub trait ImageRawExt: Sized {
+    /// Writes the image to a raw binary file with a small header.
+    ///
+    /// # Arguments
+    ///
+    /// * `file_path` - The path to the raw file.
+    fn write_raw(&self, file_path: impl AsRef<Path>) -> Result<(), IoError>;
+
+    /// Reads an image from a raw binary file written by [`ImageRawExt::write_raw`].
+    ///
+    /// # Arguments
+    ///
+    /// * `file_path` - The path to the raw file.
+    fn read_raw(file_path: impl AsRef<Path>) -> Result<Self, IoError>;
+}
+
+impl<T: RawSample, const C: usize> ImageRawExt for Image<T, C> {
+    fn write_raw(&self, file_path: impl AsRef<Path>) -> Result<(), IoError> {
+        write_image_raw(file_path, self)
+    }
+
+    fn read_raw(file_path: impl AsRef<Path>) -> Result<Self, IoError> {
+        read_image_raw(file_path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn read_write_raw() -> Result<(), IoError> {
+        let tmp_dir = tempfile::tempdir()?;
+
+        let file_path = tmp_dir.path().join("response.raw");
+        let image = Image::<f32, 2>::new(
+            [3, 1].into(),
+            vec![0.0, -1.5, f32::MAX, 1e-9, f32::NEG_INFINITY, 42.0],
+        )?;
+        write_image_raw(&file_path, &image)?;
+
+        let image_back = read_image_raw::<f32, 2>(&file_path)?;
+        assert_eq!(image_back.size(), image.size());
+        assert_eq!(image_back.as_slice(), image.as_slice());
+
+        let file_path = tmp_dir.path().join("depth.raw");
+        let image = Image::<u16, 1>::new([2, 2].into(), vec![0, 1, 1000, 65535])?;
+        write_image_raw(&file_path, &image)?;
+
+        let image_back = read_image_raw::<u16, 1>(&file_path)?;
+        assert_eq!(image_back.as_slice(), image.as_slice());
+
+        // the same dumps with the methods of the image
+        image.write_raw(&file_path)?;
+        let image_back = Image::<u16, 1>::read_raw(&file_path)?;
+        assert_eq!(image_back.as_slice(), image.as_slice());
+        assert!(Image::<u8, 1>::read_raw(&file_path).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn read_raw_mismatch() -> Result<(), IoError> {
+        let tmp_dir = tempfile::tempdir()?;
+
+        let file_path = tmp_dir.path().join("image.raw");
+        let image = Image::<u8, 3>::from_size_val([2, 2].into(), 7)?;
+        write_image_raw(&file_path, &image)?;
+
+        assert!(matches!(
+            read_image_raw::<u16, 3>(&file_path),
+            Err(IoError::InvalidImageHeader(_))
+        ));
+        assert!(matches!(
+            read_image_raw::<u8, 1>(&file_path),
+            Err(IoError::InvalidImageHeader(_))
+        ));
+
+        std::fs::write(&file_path, b"KRAW")?;
+        assert!(matches!(
+            read_image_raw::<u8, 3>(&file_path),
+            Err(IoError::InvalidImageHeader(_))
+        ));
+
+        Ok(())
+    }
+}
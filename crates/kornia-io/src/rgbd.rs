@@ -0,0 +1,258 @@
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use kornia_image::Image;
+use kornia_imgproc::calibration::CameraIntrinsic;
+
+use crate::error::IoError;
+
+/// A pair of synchronized color and depth frames.
+///
+/// The depth image is aligned to the color image, i.e. both images have the same size
+/// and the pixel `(u, v)` of the depth corresponds to the pixel `(u, v)` of the color.
+pub struct RgbdFrame {
+    /// The color image.
+    pub color: Image<u8, 3>,
+    /// The depth image in sensor units, where zero means no measurement.
+    pub depth: Image<u16, 1>,
+    /// The capture timestamp of the frame.
+    pub timestamp: Duration,
+}
+
+/// A camera delivering synchronized color and depth frames, e.g. Intel RealSense or Azure Kinect.
+pub trait DepthCamera {
+    /// Returns the intrinsics of the color camera, to which the depth is aligned.
+    fn intrinsics(&self) -> &CameraIntrinsic;
+
+    /// Returns the scale to convert the depth values to meters.
+    fn depth_scale(&self) -> f32;
+
+    /// Grabs the next color and depth frame.
+    ///
+    /// # Returns
+    ///
+    /// The next frame or None if no frame is available.
+    fn grab(&mut self) -> Result<Option<RgbdFrame>, IoError>;
+}
+
+/// A depth camera replaying a recorded RGB-D sequence from disk.
+///
+/// The sequence is stored in a directory with two sub-directories, `rgb` with the color
+/// images and `depth` with the 16-bit depth images in PNG or TIFF format, as in the TUM
+/// RGB-D dataset. The frames are matched by their position after sorting the file names.
+///
+/// The timestamps are parsed from the file names in seconds, e.g. `1305031102.175304.png`,
+/// otherwise they are set to zero.
+pub struct RgbdSequence {
+    color_paths: Vec<PathBuf>,
+    depth_paths: Vec<PathBuf>,
+    intrinsics: CameraIntrinsic,
+    depth_scale: f32,
+    index: usize,
+}
+
+impl RgbdSequence {
+    /// Creates a new RgbdSequence from the given directory.
+    ///
+    /// # Arguments
+    ///
+    /// * `dir_path` - The path to the directory containing the `rgb` and `depth` directories.
+    /// * `intrinsics` - The intrinsics of the color camera.
+    /// * `depth_scale` - The scale to convert the depth values to meters, e.g. 1 / 5000 for TUM.
+    pub fn new(
+        dir_path: impl AsRef<Path>,
+        intrinsics: CameraIntrinsic,
+        depth_scale: f32,
+    ) -> Result<Self, IoError> {
+        let dir_path = dir_path.as_ref();
+        let color_paths = list_image_files(&dir_path.join("rgb"))?;
+        let depth_paths = list_image_files(&dir_path.join("depth"))?;
+
+        if color_paths.len() != depth_paths.len() {
+            return Err(IoError::FrameCountMismatch(
+                color_paths.len(),
+                depth_paths.len(),
+            ));
+        }
+
+        Ok(Self {
+            color_paths,
+            depth_paths,
+            intrinsics,
+            depth_scale,
+            index: 0,
+        })
+    }
+
+    /// Returns the number of frames in the sequence.
+    pub fn len(&self) -> usize {
+        self.color_paths.len()
+    }
+
+    /// Returns true if the sequence has no frames.
+    pub fn is_empty(&self) -> bool {
+        self.color_paths.is_empty()
+    }
+
+    /// Restarts the sequence from the first frame.
+    pub fn reset(&mut self) {
+        self.index = 0;
+    }
+}
+
+impl DepthCamera for RgbdSequence {
+    fn intrinsics(&self) -> &CameraIntrinsic {
+        &self.intrinsics
+    }
+
+    fn depth_scale(&self) -> f32 {
+        self.depth_scale
+    }
+
+    fn grab(&mut self) -> Result<Option<RgbdFrame>, IoError> {
+        if self.index >= self.len() {
+            return Ok(None);
+        }
+
+        let color_path = &self.color_paths[self.index];
+        let depth_path = &self.depth_paths[self.index];
+        self.index += 1;
+
+        let color = crate::functional::read_image_any_rgb8(color_path)?;
+        let depth = read_depth_image(depth_path)?;
+
+        if color.size() != depth.size() {
+            return Err(IoError::DecodeMismatchResolution(
+                color.rows(),
+                color.cols(),
+                depth.rows(),
+                depth.cols(),
+            ));
+        }
+
+        let timestamp = color_path
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .and_then(|stem| stem.parse::<f64>().ok())
+            .filter(|secs| secs.is_finite() && *secs >= 0.0)
+            .map(Duration::from_secs_f64)
+            .unwrap_or_default();
+
+        Ok(Some(RgbdFrame {
+            color,
+            depth,
+            timestamp,
+        }))
+    }
+}
+
+// read a 16-bit depth image selecting the decoder from the file extension
+fn read_depth_image(file_path: &Path) -> Result<Image<u16, 1>, IoError> {
+    match file_path
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+        .as_deref()
+    {
+        Some("png") => crate::png::read_image_png_mono16(file_path),
+        Some("tif") | Some("tiff") => crate::tiff::read_image_tiff_mono16(file_path),
+        _ => Err(IoError::InvalidFileExtension(file_path.to_path_buf())),
+    }
+}
+
+// list the files of a directory sorted by name
+fn list_image_files(dir_path: &Path) -> Result<Vec<PathBuf>, IoError> {
+    if !dir_path.exists() {
+        return Err(IoError::FileDoesNotExist(dir_path.to_path_buf()));
+    }
+
+    let mut paths = std::fs::read_dir(dir_path)?
+        .map(|entry| entry.map(|e| e.path()))
+        .collect::<Result<Vec<_>, _>>()?;
+    paths.retain(|path| path.is_file());
+    paths.sort();
+
+    Ok(paths)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use kornia_image::ImageSize;
+
+    #[test]
+    fn rgbd_sequence() -> Result<(), IoError> {
+        let tmp_dir = tempfile::tempdir()?;
+        std::fs::create_dir_all(tmp_dir.path().join("rgb"))?;
+        std::fs::create_dir_all(tmp_dir.path().join("depth"))?;
+
+        let size = ImageSize {
+            width: 4,
+            height: 3,
+        };
+
+        for (i, stamp) in ["1305031102.175304", "1305031102.211214"]
+            .iter()
+            .enumerate()
+        {
+            let color = Image::<u8, 3>::from_size_val(size, i as u8)?;
+            let depth = Image::<u16, 1>::from_size_val(size, 5000 * (i as u16 + 1))?;
+            crate::png::write_image_png_rgb8(
+                tmp_dir.path().join("rgb").join(format!("{stamp}.png")),
+                &color,
+            )?;
+            crate::png::write_image_png_gray16(
+                tmp_dir.path().join("depth").join(format!("{stamp}.png")),
+                &depth,
+            )?;
+        }
+
+        let intrinsics = CameraIntrinsic {
+            fx: 525.0,
+            fy: 525.0,
+            cx: 319.5,
+            cy: 239.5,
+        };
+
+        let mut camera = RgbdSequence::new(tmp_dir.path(), intrinsics, 1.0 / 5000.0)?;
+        assert_eq!(camera.len(), 2);
+        assert_eq!(camera.intrinsics(), &intrinsics);
+
+        let frame = camera.grab()?.expect("missing first frame");
+        assert_eq!(frame.color.size(), size);
+        assert_eq!(frame.depth.as_slice()[0], 5000);
+        assert!((frame.timestamp.as_secs_f64() - 1305031102.175304).abs() < 1e-6);
+
+        let frame = camera.grab()?.expect("missing second frame");
+        assert_eq!(frame.color.as_slice()[0], 1);
+        assert_eq!(frame.depth.as_slice()[0] as f32 * camera.depth_scale(), 2.0);
+
+        assert!(camera.grab()?.is_none());
+
+        camera.reset();
+        assert!(camera.grab()?.is_some());
+
+        Ok(())
+    }
+
+    #[test]
+    fn rgbd_sequence_mismatch() -> Result<(), IoError> {
+        let tmp_dir = tempfile::tempdir()?;
+        std::fs::create_dir_all(tmp_dir.path().join("rgb"))?;
+        std::fs::create_dir_all(tmp_dir.path().join("depth"))?;
+
+        let color = Image::<u8, 3>::from_size_val([2, 2].into(), 0)?;
+        crate::png::write_image_png_rgb8(tmp_dir.path().join("rgb").join("0.png"), &color)?;
+
+        let intrinsics = CameraIntrinsic {
+            fx: 1.0,
+            fy: 1.0,
+            cx: 0.0,
+            cy: 0.0,
+        };
+
+        let res = RgbdSequence::new(tmp_dir.path(), intrinsics, 1.0);
+        assert!(matches!(res, Err(IoError::FrameCountMismatch(1, 0))));
+
+        Ok(())
+    }
+}
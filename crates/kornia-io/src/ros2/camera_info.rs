@@ -0,0 +1,230 @@
+use kornia_image::ImageSize;
+use kornia_imgproc::calibration::{
+    distortion::{CameraDistortion, FisheyeDistortion, PolynomialDistortion},
+    CameraIntrinsic,
+};
+
+use super::msg::{CameraInfoMsg, HeaderMsg};
+use crate::error::IoError;
+
+// the coefficients of a distortion model, with the missing ones set to zero
+fn coefficients<const N: usize>(msg: &CameraInfoMsg) -> Result<[f64; N], IoError> {
+    if msg.d.len() > N {
+        return Err(IoError::InvalidRosMessage(format!(
+            "expected at most {} coefficients for the {} distortion, found {}",
+            N,
+            msg.distortion_model,
+            msg.d.len()
+        )));
+    }
+
+    let mut d = [0.0; N];
+    d[..msg.d.len()].copy_from_slice(&msg.d);
+    Ok(d)
+}
+
+/// Converts a `sensor_msgs/CameraInfo` to the kornia camera model.
+///
+/// The calibration of the full resolution is returned, regardless of the binning and the
+/// region of interest of the message.
+///
+/// # Arguments
+///
+/// * `msg` - The camera info message.
+///
+/// # Returns
+///
+/// The size of the calibrated images, the intrinsic parameters and the distortion.
+///
+/// # Errors
+///
+/// Returns an error if the camera is not calibrated or the distortion model is not supported.
+pub fn camera_from_info_msg(
+    msg: &CameraInfoMsg,
+) -> Result<(ImageSize, CameraIntrinsic, CameraDistortion), IoError> {
+    let k = &msg.k;
+    if k[0] == 0.0 || k[4] == 0.0 {
+        return Err(IoError::InvalidRosMessage(
+            "the camera is not calibrated".to_string(),
+        ));
+    }
+
+    let size = ImageSize {
+        width: msg.width as usize,
+        height: msg.height as usize,
+    };
+
+    let intrinsic = CameraIntrinsic {
+        fx: k[0],
+        fy: k[4],
+        cx: k[2],
+        cy: k[5],
+    };
+
+    let distortion = match msg.distortion_model.as_str() {
+        "plumb_bob" | "rational_polynomial" | "" => {
+            let [k1, k2, p1, p2, k3, k4, k5, k6] = match msg.distortion_model.as_str() {
+                "rational_polynomial" => coefficients::<8>(msg)?,
+                _ => {
+                    let [k1, k2, p1, p2, k3] = coefficients::<5>(msg)?;
+                    [k1, k2, p1, p2, k3, 0.0, 0.0, 0.0]
+                }
+            };
+            CameraDistortion::Polynomial(PolynomialDistortion {
+                k1,
+                k2,
+                k3,
+                k4,
+                k5,
+                k6,
+                p1,
+                p2,
+            })
+        }
+        "equidistant" => {
+            let [k1, k2, k3, k4] = coefficients::<4>(msg)?;
+            CameraDistortion::Fisheye(FisheyeDistortion { k1, k2, k3, k4 })
+        }
+        model => {
+            return Err(IoError::InvalidRosMessage(format!(
+                "unsupported distortion model {}",
+                model
+            )))
+        }
+    };
+
+    Ok((size, intrinsic, distortion))
+}
+
+/// Converts the kornia camera model to a `sensor_msgs/CameraInfo`.
+///
+/// The polynomial distortion is stored as `plumb_bob`, or `rational_polynomial` if any of
+/// `k4`, `k5` or `k6` is not zero, and the fisheye distortion as `equidistant`. The
+/// rectification is the identity and the projection matrix is the intrinsic matrix.
+///
+/// # Arguments
+///
+/// * `size` - The size of the calibrated images.
+/// * `intrinsic` - The intrinsic parameters of the camera.
+/// * `distortion` - The distortion of the camera.
+/// * `header` - The header of the message.
+///
+/// # Returns
+///
+/// The camera info message.
+pub fn camera_info_to_msg(
+    size: ImageSize,
+    intrinsic: &CameraIntrinsic,
+    distortion: &CameraDistortion,
+    header: HeaderMsg,
+) -> CameraInfoMsg {
+    let (distortion_model, d) = match distortion {
+        CameraDistortion::Polynomial(d) if d.k4 == 0.0 && d.k5 == 0.0 && d.k6 == 0.0 => {
+            ("plumb_bob", vec![d.k1, d.k2, d.p1, d.p2, d.k3])
+        }
+        CameraDistortion::Polynomial(d) => (
+            "rational_polynomial",
+            vec![d.k1, d.k2, d.p1, d.p2, d.k3, d.k4, d.k5, d.k6],
+        ),
+        CameraDistortion::Fisheye(d) => ("equidistant", vec![d.k1, d.k2, d.k3, d.k4]),
+    };
+
+    let CameraIntrinsic { fx, fy, cx, cy } = *intrinsic;
+
+    CameraInfoMsg {
+        header,
+        height: size.height as u32,
+        width: size.width as u32,
+        distortion_model: distortion_model.to_string(),
+        d,
+        k: [fx, 0.0, cx, 0.0, fy, cy, 0.0, 0.0, 1.0],
+        r: [1.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0],
+        p: [fx, 0.0, cx, 0.0, 0.0, fy, cy, 0.0, 0.0, 0.0, 1.0, 0.0],
+        ..Default::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn camera_info_roundtrip() -> Result<(), IoError> {
+        let intrinsic = CameraIntrinsic {
+            fx: 500.0,
+            fy: 510.0,
+            cx: 320.0,
+            cy: 240.0,
+        };
+        let distortion = CameraDistortion::Polynomial(PolynomialDistortion {
+            k1: 0.1,
+            k2: -0.2,
+            k3: 0.3,
+            k4: 0.0,
+            k5: 0.0,
+            k6: 0.0,
+            p1: 0.01,
+            p2: -0.02,
+        });
+
+        let msg = camera_info_to_msg(
+            [640, 480].into(),
+            &intrinsic,
+            &distortion,
+            HeaderMsg::default(),
+        );
+        assert_eq!(msg.distortion_model, "plumb_bob");
+        assert_eq!(msg.d, vec![0.1, -0.2, 0.01, -0.02, 0.3]);
+        assert_eq!(msg.p[6], 240.0);
+
+        let (size, intrinsic_back, distortion_back) = camera_from_info_msg(&msg)?;
+        assert_eq!(size, [640, 480].into());
+        assert_eq!(intrinsic_back, intrinsic);
+        let CameraDistortion::Polynomial(d) = distortion_back else {
+            panic!("expected a polynomial distortion");
+        };
+        assert_eq!(
+            [d.k1, d.k2, d.k3, d.p1, d.p2],
+            [0.1, -0.2, 0.3, 0.01, -0.02]
+        );
+
+        let distortion = CameraDistortion::Fisheye(FisheyeDistortion {
+            k1: 0.1,
+            k2: 0.2,
+            k3: 0.3,
+            k4: 0.4,
+        });
+        let msg = camera_info_to_msg(
+            [640, 480].into(),
+            &intrinsic,
+            &distortion,
+            HeaderMsg::default(),
+        );
+        assert_eq!(msg.distortion_model, "equidistant");
+        let (_, _, distortion_back) = camera_from_info_msg(&msg)?;
+        assert!(matches!(
+            distortion_back,
+            CameraDistortion::Fisheye(FisheyeDistortion { k4, .. }) if k4 == 0.4
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn camera_info_invalid() {
+        let mut msg = CameraInfoMsg::default();
+        assert!(matches!(
+            camera_from_info_msg(&msg),
+            Err(IoError::InvalidRosMessage(_))
+        ));
+
+        msg.k = [500.0, 0.0, 320.0, 0.0, 500.0, 240.0, 0.0, 0.0, 1.0];
+        msg.distortion_model = "plumb_bob".to_string();
+        msg.d = vec![0.0; 8];
+        assert!(camera_from_info_msg(&msg).is_err());
+
+        msg.distortion_model = "double_sphere".to_string();
+        msg.d = vec![];
+        assert!(camera_from_info_msg(&msg).is_err());
+    }
+}
@@ -0,0 +1,312 @@
+use kornia_image::{Image, ImageSize};
+
+use super::msg::{HeaderMsg, ImageMsg};
+use crate::error::IoError;
+use crate::le_bytes::LeBytes;
+
+// the depths of the generic encodings, e.g. `32FC1`
+const DEPTHS: [&str; 7] = ["8U", "8S", "16U", "16S", "32S", "32F", "64F"];
+
+/// A pixel type that can be stored in a `sensor_msgs/Image`.
+pub trait RosSample: LeBytes {
+    /// The depth of the type in the encodings, e.g. `8U` or `32F`.
+    const DEPTH: &'static str;
+}
+
+macro_rules! impl_ros_sample {
+    ($($ty:ty => $depth:expr),*) => {
+        $(
+            impl RosSample for $ty {
+                const DEPTH: &'static str = $depth;
+            }
+        )*
+    };
+}
+
+impl_ros_sample!(
+    u8 => "8U", i8 => "8S", u16 => "16U", i16 => "16S", i32 => "32S", f32 => "32F", f64 => "64F"
+);
+
+// the conversion of an 8-bit pixel to RGB
+type ToRgb8 = fn(&[u8]) -> [u8; 3];
+
+// the depth and the number of channels of an encoding
+fn parse_encoding(encoding: &str) -> Option<(&'static str, usize)> {
+    match encoding {
+        "mono8" => Some(("8U", 1)),
+        "mono16" => Some(("16U", 1)),
+        "rgb8" | "bgr8" => Some(("8U", 3)),
+        "rgba8" | "bgra8" => Some(("8U", 4)),
+        "rgb16" | "bgr16" => Some(("16U", 3)),
+        "rgba16" | "bgra16" => Some(("16U", 4)),
+        e if e.starts_with("bayer_") && e.ends_with("16") => Some(("16U", 1)),
+        e if e.starts_with("bayer_") && e.ends_with('8') => Some(("8U", 1)),
+        e => {
+            let (depth, channels) = e.split_once('C')?;
+            let depth = DEPTHS.into_iter().find(|d| *d == depth)?;
+            match channels.parse() {
+                Ok(channels) if channels > 0 => Some((depth, channels)),
+                _ => None,
+            }
+        }
+    }
+}
+
+// read the samples of the rows without their padding
+fn read_samples<T: RosSample>(msg: &ImageMsg, channels: usize) -> Result<Vec<T>, IoError> {
+    let (width, height) = (msg.width as usize, msg.height as usize);
+    let sample_size = std::mem::size_of::<T>();
+    let row_len = width * channels * sample_size;
+
+    let step = msg.step as usize;
+    if step < row_len {
+        return Err(IoError::InvalidRosMessage(format!(
+            "the step {} is shorter than a row of {} bytes",
+            step, row_len
+        )));
+    }
+
+    let expected = step * height;
+    if msg.data.len() < expected {
+        return Err(IoError::InvalidBufferSize(msg.data.len(), expected));
+    }
+
+    let big_endian = msg.is_bigendian != 0;
+    let mut samples = Vec::with_capacity(width * height * channels);
+    for row in 0..height {
+        let row = &msg.data[row * step..row * step + row_len];
+        samples.extend(row.chunks_exact(sample_size).map(|bytes| {
+            if big_endian {
+                T::read_be(bytes)
+            } else {
+                T::read_le(bytes)
+            }
+        }));
+    }
+
+    Ok(samples)
+}
+
+/// Converts a `sensor_msgs/Image` to an image.
+///
+/// The pixels keep the channel order of the encoding, e.g. the `bgr8` images are read as BGR.
+/// The padding of the rows and the byte order of the samples are handled.
+///
+/// # Arguments
+///
+/// * `msg` - The image message.
+///
+/// # Returns
+///
+/// The image with the pixels of the message.
+///
+/// # Errors
+///
+/// Returns an error if the encoding does not match the pixel type and the number of channels.
+///
+/// # Example
+///
+/// ```
+/// use kornia_io::ros2::{image_from_msg, ImageMsg};
+///
+/// let msg = ImageMsg {
+///     height: 1,
+///     width: 2,
+///     encoding: "mono16".to_string(),
+///     step: 4,
+///     data: vec![1, 0, 0, 1],
+///     ..Default::default()
+/// };
+///
+/// let image = image_from_msg::<u16, 1>(&msg).unwrap();
+/// assert_eq!(image.as_slice(), &[1, 256]);
+/// ```
+pub fn image_from_msg<T: RosSample, const C: usize>(
+    msg: &ImageMsg,
+) -> Result<Image<T, C>, IoError> {
+    let (depth, channels) = parse_encoding(&msg.encoding).ok_or_else(|| {
+        IoError::InvalidRosMessage(format!("unsupported encoding {}", msg.encoding))
+    })?;
+    if depth != T::DEPTH || channels != C {
+        return Err(IoError::InvalidRosMessage(format!(
+            "expected {}C{} pixels, found the encoding {}",
+            T::DEPTH,
+            C,
+            msg.encoding
+        )));
+    }
+
+    let size = ImageSize {
+        width: msg.width as usize,
+        height: msg.height as usize,
+    };
+
+    Ok(Image::new(size, read_samples(msg, C)?)?)
+}
+
+/// Converts a `sensor_msgs/Image` with 8-bit pixels to an RGB image.
+///
+/// The `rgb8`, `bgr8`, `rgba8`, `bgra8` and `mono8` encodings are converted to RGB, and the
+/// alpha channel is dropped.
+///
+/// # Arguments
+///
+/// * `msg` - The image message.
+///
+/// # Returns
+///
+/// The RGB image.
+pub fn rgb8_image_from_msg(msg: &ImageMsg) -> Result<Image<u8, 3>, IoError> {
+    let (to_rgb, channels): (ToRgb8, usize) = match msg.encoding.as_str() {
+        "rgb8" | "8UC3" => (|p| [p[0], p[1], p[2]], 3),
+        "bgr8" => (|p| [p[2], p[1], p[0]], 3),
+        "rgba8" | "8UC4" => (|p| [p[0], p[1], p[2]], 4),
+        "bgra8" => (|p| [p[2], p[1], p[0]], 4),
+        "mono8" | "8UC1" => (|p| [p[0]; 3], 1),
+        encoding => {
+            return Err(IoError::InvalidRosMessage(format!(
+                "expected an 8-bit color or mono encoding, found {}",
+                encoding
+            )))
+        }
+    };
+
+    let size = ImageSize {
+        width: msg.width as usize,
+        height: msg.height as usize,
+    };
+
+    let samples = read_samples::<u8>(msg, channels)?;
+    let data = samples.chunks_exact(channels).flat_map(to_rgb).collect();
+
+    Ok(Image::new(size, data)?)
+}
+
+/// Converts an image to a `sensor_msgs/Image`.
+///
+/// The encoding is `mono8`, `rgb8` or `rgba8` for the 8-bit images with 1, 3 or 4 channels,
+/// the 16-bit equivalents for `u16`, and the generic encoding otherwise, e.g. `32FC1`.
+/// The rows have no padding and the samples are little endian.
+///
+/// # Arguments
+///
+/// * `image` - The image to convert.
+/// * `header` - The header of the message.
+///
+/// # Returns
+///
+/// The image message.
+pub fn image_to_msg<T: RosSample, const C: usize>(
+    image: &Image<T, C>,
+    header: HeaderMsg,
+) -> ImageMsg {
+    let encoding = match (T::DEPTH, C) {
+        ("8U", 1) => "mono8".to_string(),
+        ("8U", 3) => "rgb8".to_string(),
+        ("8U", 4) => "rgba8".to_string(),
+        ("16U", 1) => "mono16".to_string(),
+        ("16U", 3) => "rgb16".to_string(),
+        ("16U", 4) => "rgba16".to_string(),
+        (depth, channels) => format!("{}C{}", depth, channels),
+    };
+
+    let mut data = Vec::with_capacity(std::mem::size_of_val(image.as_slice()));
+    for v in image.as_slice() {
+        v.write_le(&mut data);
+    }
+
+    ImageMsg {
+        header,
+        height: image.rows() as u32,
+        width: image.cols() as u32,
+        encoding,
+        is_bigendian: 0,
+        step: (image.cols() * C * std::mem::size_of::<T>()) as u32,
+        data,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn image_msg_roundtrip() -> Result<(), IoError> {
+        let header = HeaderMsg {
+            frame_id: "camera".to_string(),
+            ..Default::default()
+        };
+
+        let image = Image::<u8, 3>::new([2, 1].into(), vec![1, 2, 3, 4, 5, 6])?;
+        let msg = image_to_msg(&image, header.clone());
+        assert_eq!(msg.encoding, "rgb8");
+        assert_eq!(msg.step, 6);
+        assert_eq!(msg.header, header);
+        assert_eq!(image_from_msg::<u8, 3>(&msg)?.as_slice(), image.as_slice());
+
+        let image = Image::<f32, 1>::new([2, 2].into(), vec![0.5, -1.0, 2.0, f32::MAX])?;
+        let msg = image_to_msg(&image, header);
+        assert_eq!(msg.encoding, "32FC1");
+        assert_eq!(image_from_msg::<f32, 1>(&msg)?.as_slice(), image.as_slice());
+
+        Ok(())
+    }
+
+    #[test]
+    fn image_msg_step_and_endianness() -> Result<(), IoError> {
+        // two rows of 1 pixel padded to 4 bytes
+        let msg = ImageMsg {
+            height: 2,
+            width: 1,
+            encoding: "16UC1".to_string(),
+            is_bigendian: 1,
+            step: 4,
+            data: vec![1, 0, 9, 9, 0, 2, 9, 9],
+            ..Default::default()
+        };
+        assert_eq!(image_from_msg::<u16, 1>(&msg)?.as_slice(), &[256, 2]);
+
+        let msg = ImageMsg { step: 1, ..msg };
+        assert!(matches!(
+            image_from_msg::<u16, 1>(&msg),
+            Err(IoError::InvalidRosMessage(_))
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn rgb8_image_msg() -> Result<(), IoError> {
+        let msg = ImageMsg {
+            height: 1,
+            width: 2,
+            encoding: "bgra8".to_string(),
+            step: 8,
+            data: vec![1, 2, 3, 255, 4, 5, 6, 255],
+            ..Default::default()
+        };
+        assert_eq!(rgb8_image_from_msg(&msg)?.as_slice(), &[3, 2, 1, 6, 5, 4]);
+
+        let msg = ImageMsg {
+            encoding: "mono8".to_string(),
+            step: 2,
+            data: vec![7, 8],
+            ..msg
+        };
+        assert_eq!(rgb8_image_from_msg(&msg)?.as_slice(), &[7, 7, 7, 8, 8, 8]);
+
+        // the encoding does not match the pixel type
+        assert!(matches!(
+            image_from_msg::<u16, 1>(&msg),
+            Err(IoError::InvalidRosMessage(_))
+        ));
+
+        let msg = ImageMsg {
+            encoding: "mono16".to_string(),
+            ..msg
+        };
+        assert!(rgb8_image_from_msg(&msg).is_err());
+
+        Ok(())
+    }
+}
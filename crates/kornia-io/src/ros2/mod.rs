@@ -0,0 +1,13 @@
+/// Conversions between `sensor_msgs/CameraInfo` and the kornia camera models.
+pub mod camera_info;
+
+/// Conversions between `sensor_msgs/Image` and kornia images.
+pub mod image;
+
+/// Mirrors of the ROS 2 message types.
+pub mod msg;
+
+pub use crate::ros2::camera_info::{camera_from_info_msg, camera_info_to_msg};
+pub use crate::ros2::image::{image_from_msg, image_to_msg, rgb8_image_from_msg, RosSample};
+pub use crate::ros2::msg::{CameraInfoMsg, HeaderMsg, ImageMsg, RegionOfInterestMsg, TimeMsg};
+pub use kornia_imgproc::calibration::distortion::CameraDistortion;
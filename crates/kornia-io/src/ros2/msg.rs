@@ -0,0 +1,81 @@
+//! The fields follow the ROS 2 message definitions, so the messages of a ROS 2 client
+//! library, e.g. `r2r` or `rclrs`, convert to and from these types field by field.
+
+/// A `builtin_interfaces/Time` message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct TimeMsg {
+    /// The seconds since the epoch.
+    pub sec: i32,
+    /// The nanoseconds within the second.
+    pub nanosec: u32,
+}
+
+/// A `std_msgs/Header` message.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct HeaderMsg {
+    /// The acquisition time of the data.
+    pub stamp: TimeMsg,
+    /// The coordinate frame of the data.
+    pub frame_id: String,
+}
+
+/// A `sensor_msgs/Image` message.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ImageMsg {
+    /// The header of the message.
+    pub header: HeaderMsg,
+    /// The number of rows of the image.
+    pub height: u32,
+    /// The number of columns of the image.
+    pub width: u32,
+    /// The encoding of the pixels, e.g. `rgb8`, `mono16` or `32FC1`.
+    pub encoding: String,
+    /// Whether the multi-byte samples are big endian.
+    pub is_bigendian: u8,
+    /// The length of a row in bytes, which may include a padding.
+    pub step: u32,
+    /// The pixels row by row, of length `step * height`.
+    pub data: Vec<u8>,
+}
+
+/// A `sensor_msgs/RegionOfInterest` message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct RegionOfInterestMsg {
+    /// The leftmost column of the region.
+    pub x_offset: u32,
+    /// The topmost row of the region.
+    pub y_offset: u32,
+    /// The number of rows of the region.
+    pub height: u32,
+    /// The number of columns of the region.
+    pub width: u32,
+    /// Whether the region is computed from the rectified image.
+    pub do_rectify: bool,
+}
+
+/// A `sensor_msgs/CameraInfo` message.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct CameraInfoMsg {
+    /// The header of the message.
+    pub header: HeaderMsg,
+    /// The number of rows of the calibrated images.
+    pub height: u32,
+    /// The number of columns of the calibrated images.
+    pub width: u32,
+    /// The distortion model, e.g. `plumb_bob`, `rational_polynomial` or `equidistant`.
+    pub distortion_model: String,
+    /// The distortion coefficients in the order of the model.
+    pub d: Vec<f64>,
+    /// The intrinsic camera matrix 3x3 in row-major order.
+    pub k: [f64; 9],
+    /// The rectification rotation 3x3 in row-major order.
+    pub r: [f64; 9],
+    /// The projection matrix 3x4 of the rectified images in row-major order.
+    pub p: [f64; 12],
+    /// The horizontal binning of the images.
+    pub binning_x: u32,
+    /// The vertical binning of the images.
+    pub binning_y: u32,
+    /// The region of interest of the images.
+    pub roi: RegionOfInterestMsg,
+}
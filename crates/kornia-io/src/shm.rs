@@ -0,0 +1,507 @@
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+use kornia_image::{Image, ImageSize};
+use memmap2::{Mmap, MmapMut};
+
+use crate::error::IoError;
+use crate::raw::RawSample;
+
+// the magic bytes identifying a shared memory ring
+const SHM_MAGIC: &[u8; 4] = b"KSHM";
+const SHM_VERSION: u32 = 1;
+// magic, version, slot count, slot capacity and the number of written frames
+const SHM_HEADER_LEN: usize = 64;
+// stamp, timestamp, width, height, channels, data type and length of the frame
+const SLOT_HEADER_LEN: usize = 64;
+
+// the offsets of the fields in the headers
+const WRITE_SEQ_OFFSET: usize = 24;
+const STAMP_OFFSET: usize = 0;
+const SLOT_META_OFFSET: usize = 8;
+
+// the layout of the ring in the shared memory
+#[derive(Clone, Copy)]
+struct RingLayout {
+    slot_count: usize,
+    slot_capacity: usize,
+}
+
+impl RingLayout {
+    fn slot_stride(&self) -> usize {
+        SLOT_HEADER_LEN + self.slot_capacity.next_multiple_of(64)
+    }
+
+    fn slot_offset(&self, seq: u64) -> usize {
+        SHM_HEADER_LEN + (seq % self.slot_count as u64) as usize * self.slot_stride()
+    }
+
+    fn len(&self) -> usize {
+        SHM_HEADER_LEN + self.slot_count * self.slot_stride()
+    }
+}
+
+// the metadata of a frame stored in its slot after the stamp
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct SlotHeader {
+    timestamp_ns: u64,
+    width: u32,
+    height: u32,
+    channels: u32,
+    dtype: u32,
+    len: u64,
+}
+
+// the stamp of a slot is odd while the frame `seq` is written and even once it is complete
+fn stamp_writing(seq: u64) -> u64 {
+    2 * seq + 1
+}
+
+fn stamp_complete(seq: u64) -> u64 {
+    2 * seq + 2
+}
+
+// SAFETY: the offset is within the mapping and aligned to 8 bytes
+unsafe fn atomic_at<'a>(base: *const u8, offset: usize) -> &'a AtomicU64 {
+    &*(base.add(offset) as *const AtomicU64)
+}
+
+/// A frame read from a shared memory ring.
+pub struct ShmFrame<T, const C: usize> {
+    /// The image of the frame.
+    pub image: Image<T, C>,
+    /// The sequence number of the frame, counting from 0 for the first written frame.
+    pub seq: u64,
+    /// The timestamp given by the writer.
+    pub timestamp: Duration,
+}
+
+/// The writer of a ring of image frames in shared memory.
+///
+/// The ring is a file mapped in memory, e.g. in `/dev/shm` on Linux, which is opened by
+/// [`ShmReader`]s in other processes. The frames are copied once into the next slot without
+/// any serialization, and the oldest frames are overwritten when the ring is full. Each slot
+/// is protected by a sequence lock, so the readers detect the frames overwritten while they
+/// are read.
+///
+/// There must be a single writer for a ring.
+///
+/// # Example
+///
+/// ```no_run
+/// use kornia_image::Image;
+/// use kornia_io::shm::{ShmReader, ShmWriter};
+/// use std::time::Duration;
+///
+/// // in the producer process
+/// let mut writer = ShmWriter::create("/dev/shm/camera", 4, 640 * 480 * 3).unwrap();
+/// let image = Image::<u8, 3>::from_size_val([640, 480].into(), 0).unwrap();
+/// writer.write(&image, Duration::from_millis(33)).unwrap();
+///
+/// // in the consumer process
+/// let mut reader = ShmReader::open("/dev/shm/camera").unwrap();
+/// if let Some(frame) = reader.read_latest::<u8, 3>().unwrap() {
+///     println!("frame {} at {:?}", frame.seq, frame.timestamp);
+/// }
+/// ```
+pub struct ShmWriter {
+    mmap: MmapMut,
+    layout: RingLayout,
+    next_seq: u64,
+}
+
+impl ShmWriter {
+    /// Creates a ring in a new file, replacing any existing file.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the file backing the shared memory.
+    /// * `slot_count` - The number of frames kept in the ring.
+    /// * `slot_capacity` - The maximum size of a frame in bytes.
+    pub fn create(
+        path: impl AsRef<Path>,
+        slot_count: usize,
+        slot_capacity: usize,
+    ) -> Result<Self, IoError> {
+        if slot_count == 0 || slot_count > u32::MAX as usize {
+            return Err(IoError::InvalidImageHeader(format!(
+                "invalid number of slots {slot_count}"
+            )));
+        }
+
+        let layout = RingLayout {
+            slot_count,
+            slot_capacity,
+        };
+
+        let file = std::fs::OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(path)?;
+        file.set_len(layout.len() as u64)?;
+
+        // SAFETY: the file is created by the writer and only read by the readers
+        let mut mmap = unsafe { MmapMut::map_mut(&file)? };
+
+        mmap[..4].copy_from_slice(SHM_MAGIC);
+        mmap[4..8].copy_from_slice(&SHM_VERSION.to_le_bytes());
+        mmap[8..12].copy_from_slice(&(slot_count as u32).to_le_bytes());
+        mmap[16..24].copy_from_slice(&(slot_capacity as u64).to_le_bytes());
+
+        Ok(Self {
+            mmap,
+            layout,
+            next_seq: 0,
+        })
+    }
+
+    /// Writes a frame to the next slot of the ring.
+    ///
+    /// # Arguments
+    ///
+    /// * `image` - The image of the frame.
+    /// * `timestamp` - The timestamp of the frame, e.g. the capture time since the epoch.
+    ///
+    /// # Returns
+    ///
+    /// The sequence number of the frame.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the image is larger than the capacity of the slots.
+    pub fn write<T: RawSample, const C: usize>(
+        &mut self,
+        image: &Image<T, C>,
+        timestamp: Duration,
+    ) -> Result<u64, IoError> {
+        let len = std::mem::size_of_val(image.as_slice());
+        if len > self.layout.slot_capacity {
+            return Err(IoError::InvalidBufferSize(len, self.layout.slot_capacity));
+        }
+
+        let seq = self.next_seq;
+        let offset = self.layout.slot_offset(seq);
+        let base = self.mmap.as_mut_ptr();
+
+        // SAFETY: the slot is within the mapping and the stamp is aligned to 8 bytes
+        let stamp = unsafe { atomic_at(base, offset + STAMP_OFFSET) };
+        stamp.store(stamp_writing(seq), Ordering::Relaxed);
+        std::sync::atomic::fence(Ordering::Release);
+
+        let header = SlotHeader {
+            timestamp_ns: timestamp.as_nanos() as u64,
+            width: image.width() as u32,
+            height: image.height() as u32,
+            channels: C as u32,
+            dtype: T::DTYPE as u32,
+            len: len as u64,
+        };
+
+        // SAFETY: the header and the data fit in the slot
+        unsafe {
+            std::ptr::write_unaligned(
+                base.add(offset + SLOT_META_OFFSET) as *mut SlotHeader,
+                header,
+            );
+            std::ptr::copy_nonoverlapping(
+                image.as_slice().as_ptr() as *const u8,
+                base.add(offset + SLOT_HEADER_LEN),
+                len,
+            );
+        }
+
+        stamp.store(stamp_complete(seq), Ordering::Release);
+
+        // SAFETY: the counter is within the header and aligned to 8 bytes
+        unsafe { atomic_at(base, WRITE_SEQ_OFFSET) }.store(seq + 1, Ordering::Release);
+        self.next_seq += 1;
+
+        Ok(seq)
+    }
+}
+
+/// The reader of a ring of image frames written by a [`ShmWriter`].
+///
+/// The reader starts at the latest written frame and follows the writer. When the reader
+/// falls behind by more than the size of the ring, the overwritten frames are skipped and
+/// counted as dropped.
+pub struct ShmReader {
+    mmap: Mmap,
+    layout: RingLayout,
+    next_seq: u64,
+    dropped: u64,
+}
+
+impl ShmReader {
+    /// Opens the ring of a writer.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the file backing the shared memory.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, IoError> {
+        let path = path.as_ref();
+        if !path.exists() {
+            return Err(IoError::FileDoesNotExist(path.to_path_buf()));
+        }
+
+        let file = std::fs::File::open(path)?;
+        // SAFETY: the mapping is only read, the concurrent writes are detected by the stamps
+        let mmap = unsafe { Mmap::map(&file)? };
+
+        let header = mmap
+            .get(..SHM_HEADER_LEN)
+            .ok_or_else(|| IoError::InvalidImageHeader("truncated shm header".to_string()))?;
+        if &header[..4] != SHM_MAGIC || header[4..8] != SHM_VERSION.to_le_bytes() {
+            return Err(IoError::InvalidImageHeader(
+                "not a shared memory ring".to_string(),
+            ));
+        }
+
+        let slot_count = u32::from_le_bytes([header[8], header[9], header[10], header[11]]);
+        let mut slot_capacity = [0u8; 8];
+        slot_capacity.copy_from_slice(&header[16..24]);
+        let layout = RingLayout {
+            slot_count: slot_count as usize,
+            slot_capacity: u64::from_le_bytes(slot_capacity) as usize,
+        };
+        if layout.slot_count == 0 || mmap.len() < layout.len() {
+            return Err(IoError::InvalidBufferSize(mmap.len(), layout.len()));
+        }
+
+        let mut reader = Self {
+            mmap,
+            layout,
+            next_seq: 0,
+            dropped: 0,
+        };
+        reader.next_seq = reader.written().saturating_sub(1);
+
+        Ok(reader)
+    }
+
+    /// Returns the number of frames written to the ring.
+    pub fn written(&self) -> u64 {
+        // SAFETY: the counter is within the header and aligned to 8 bytes
+        unsafe { atomic_at(self.mmap.as_ptr(), WRITE_SEQ_OFFSET) }.load(Ordering::Acquire)
+    }
+
+    /// Returns the number of frames skipped since they were overwritten before being read.
+    pub fn dropped(&self) -> u64 {
+        self.dropped
+    }
+
+    /// Reads the next frame after the last read one.
+    ///
+    /// # Returns
+    ///
+    /// The frame, or None if no new frame was written.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the frame does not have the pixel type and the number of channels.
+    pub fn read_next<T: RawSample, const C: usize>(
+        &mut self,
+    ) -> Result<Option<ShmFrame<T, C>>, IoError> {
+        loop {
+            let written = self.written();
+            if self.next_seq >= written {
+                return Ok(None);
+            }
+
+            // skip the frames already overwritten by the writer
+            let oldest = written.saturating_sub(self.layout.slot_count as u64);
+            if self.next_seq < oldest {
+                self.dropped += oldest - self.next_seq;
+                self.next_seq = oldest;
+            }
+
+            let seq = self.next_seq;
+            self.next_seq += 1;
+            match self.read_slot(seq)? {
+                Some(frame) => return Ok(Some(frame)),
+                None => self.dropped += 1,
+            }
+        }
+    }
+
+    /// Reads the latest written frame, skipping the frames not read yet.
+    ///
+    /// # Returns
+    ///
+    /// The frame, or None if no new frame was written since the last read.
+    pub fn read_latest<T: RawSample, const C: usize>(
+        &mut self,
+    ) -> Result<Option<ShmFrame<T, C>>, IoError> {
+        let latest = self.written().saturating_sub(1);
+        if latest > self.next_seq {
+            self.next_seq = latest;
+        }
+        self.read_next()
+    }
+
+    // copy a frame out of its slot, or None if it was overwritten meanwhile
+    fn read_slot<T: RawSample, const C: usize>(
+        &self,
+        seq: u64,
+    ) -> Result<Option<ShmFrame<T, C>>, IoError> {
+        let offset = self.layout.slot_offset(seq);
+        let base = self.mmap.as_ptr();
+
+        // SAFETY: the slot is within the mapping and the stamp is aligned to 8 bytes
+        let stamp = unsafe { atomic_at(base, offset + STAMP_OFFSET) };
+        if stamp.load(Ordering::Acquire) != stamp_complete(seq) {
+            return Ok(None);
+        }
+
+        // SAFETY: the header is within the slot
+        let header = unsafe {
+            std::ptr::read_unaligned(base.add(offset + SLOT_META_OFFSET) as *const SlotHeader)
+        };
+
+        let len = (header.len as usize).min(self.layout.slot_capacity);
+        let mut data = Vec::<T>::with_capacity(len / std::mem::size_of::<T>());
+        // SAFETY: the data is within the slot and the vector has the capacity for it
+        unsafe {
+            std::ptr::copy_nonoverlapping(
+                base.add(offset + SLOT_HEADER_LEN),
+                data.as_mut_ptr() as *mut u8,
+                data.capacity() * std::mem::size_of::<T>(),
+            );
+            data.set_len(data.capacity());
+        }
+
+        // the frame is valid if the writer did not start to overwrite it
+        std::sync::atomic::fence(Ordering::Acquire);
+        if stamp.load(Ordering::Relaxed) != stamp_complete(seq) {
+            return Ok(None);
+        }
+
+        if header.dtype != T::DTYPE as u32 || header.channels as usize != C {
+            return Err(IoError::InvalidImageHeader(format!(
+                "expected data type {} with {} channels, found data type {} with {} channels",
+                T::DTYPE,
+                C,
+                header.dtype,
+                header.channels
+            )));
+        }
+
+        let size = ImageSize {
+            width: header.width as usize,
+            height: header.height as usize,
+        };
+
+        Ok(Some(ShmFrame {
+            image: Image::new(size, data)?,
+            seq,
+            timestamp: Duration::from_nanos(header.timestamp_ns),
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shm_write_read() -> Result<(), IoError> {
+        let tmp_dir = tempfile::tempdir()?;
+        let path = tmp_dir.path().join("ring");
+
+        let mut writer = ShmWriter::create(&path, 2, 2 * 2 * 3 * 4)?;
+        let mut reader = ShmReader::open(&path)?;
+        assert!(reader.read_next::<u8, 3>()?.is_none());
+
+        let image = Image::<f32, 3>::from_size_val([2, 2].into(), 1.5)?;
+        assert_eq!(writer.write(&image, Duration::from_millis(10))?, 0);
+
+        let frame = reader.read_next::<f32, 3>()?.expect("expected a frame");
+        assert_eq!(frame.seq, 0);
+        assert_eq!(frame.timestamp, Duration::from_millis(10));
+        assert_eq!(frame.image.as_slice(), image.as_slice());
+        assert!(reader.read_next::<f32, 3>()?.is_none());
+
+        // the frames larger than the slots are rejected
+        let large = Image::<f32, 3>::from_size_val([3, 2].into(), 0.0)?;
+        assert!(matches!(
+            writer.write(&large, Duration::ZERO),
+            Err(IoError::InvalidBufferSize(72, 48))
+        ));
+
+        // the pixel type must match
+        writer.write(&image, Duration::ZERO)?;
+        assert!(matches!(
+            reader.read_next::<u8, 3>(),
+            Err(IoError::InvalidImageHeader(_))
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn shm_ring_overwrite() -> Result<(), IoError> {
+        let tmp_dir = tempfile::tempdir()?;
+        let path = tmp_dir.path().join("ring");
+
+        let mut writer = ShmWriter::create(&path, 3, 4)?;
+        let mut reader = ShmReader::open(&path)?;
+
+        for i in 0..5u8 {
+            let image = Image::<u8, 1>::from_size_val([2, 2].into(), i)?;
+            writer.write(&image, Duration::from_secs(i as u64))?;
+        }
+
+        // the first two frames were overwritten
+        let frame = reader.read_next::<u8, 1>()?.expect("expected a frame");
+        assert_eq!(frame.seq, 2);
+        assert_eq!(frame.image.as_slice(), &[2; 4]);
+        assert_eq!(reader.dropped(), 2);
+
+        let frame = reader.read_latest::<u8, 1>()?.expect("expected a frame");
+        assert_eq!(frame.seq, 4);
+        assert_eq!(frame.timestamp, Duration::from_secs(4));
+
+        // a new reader starts at the latest frame
+        let mut reader = ShmReader::open(&path)?;
+        assert_eq!(reader.written(), 5);
+        assert_eq!(reader.read_next::<u8, 1>()?.map(|f| f.seq), Some(4));
+
+        Ok(())
+    }
+
+    #[test]
+    fn shm_concurrent_reader() -> Result<(), IoError> {
+        let tmp_dir = tempfile::tempdir()?;
+        let path = tmp_dir.path().join("ring");
+
+        let mut writer = ShmWriter::create(&path, 2, 64 * 64)?;
+        let mut reader = ShmReader::open(&path)?;
+
+        let handle = std::thread::spawn(move || -> Result<(), IoError> {
+            for i in 0..500u64 {
+                let image = Image::<u8, 1>::from_size_val([64, 64].into(), i as u8)?;
+                writer.write(&image, Duration::from_nanos(i))?;
+            }
+            Ok(())
+        });
+
+        // the frames are never torn, even when the writer overwrites them
+        let mut last_seq = None;
+        while last_seq != Some(499) {
+            if let Some(frame) = reader.read_next::<u8, 1>()? {
+                let value = frame.seq as u8;
+                assert!(frame.image.as_slice().iter().all(|&v| v == value));
+                assert!(last_seq.map_or(true, |last| frame.seq > last));
+                last_seq = Some(frame.seq);
+            }
+        }
+
+        handle.join().expect("the writer panicked")?;
+
+        Ok(())
+    }
+}
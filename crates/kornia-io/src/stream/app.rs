@@ -0,0 +1,529 @@
+use super::StreamCaptureError;
+use gstreamer::prelude::*;
+use kornia_image::{Image, ImageSize};
+use kornia_imgproc::color::{rgb_from_yuv, yuv_from_rgb, YuvFormat};
+use std::borrow::Cow;
+use std::time::Duration;
+
+/// The raw video formats exchanged with the app elements.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RawVideoFormat {
+    /// 8-bit packed RGB.
+    Rgb,
+    /// 8-bit 4:2:0 with a Y plane followed by an interleaved UV plane, the usual output
+    /// and input of the hardware decoders and encoders.
+    Nv12,
+}
+
+impl RawVideoFormat {
+    /// Returns the name of the format in the GStreamer caps.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RawVideoFormat::Rgb => "RGB",
+            RawVideoFormat::Nv12 => "NV12",
+        }
+    }
+
+    fn from_caps_name(name: &str) -> Option<Self> {
+        match name {
+            "RGB" => Some(RawVideoFormat::Rgb),
+            "NV12" => Some(RawVideoFormat::Nv12),
+            _ => None,
+        }
+    }
+}
+
+// the layout of a raw video buffer with the default GStreamer strides, aligned to 4 bytes
+struct RawLayout {
+    stride: usize,
+    uv_offset: usize,
+    len: usize,
+}
+
+impl RawLayout {
+    fn new(format: RawVideoFormat, size: ImageSize) -> Self {
+        match format {
+            RawVideoFormat::Rgb => {
+                let stride = (3 * size.width).next_multiple_of(4);
+                Self {
+                    stride,
+                    uv_offset: 0,
+                    len: stride * size.height,
+                }
+            }
+            RawVideoFormat::Nv12 => {
+                // the UV plane has a row for every two rows with the same stride
+                let stride = size.width.next_multiple_of(4);
+                let uv_offset = stride * size.height.next_multiple_of(2);
+                Self {
+                    stride,
+                    uv_offset,
+                    len: uv_offset + stride * size.height.div_ceil(2),
+                }
+            }
+        }
+    }
+
+    // whether the buffer has the packed layout of the kornia conversions
+    fn is_packed(&self, format: RawVideoFormat, size: ImageSize) -> bool {
+        match format {
+            RawVideoFormat::Rgb => self.stride == 3 * size.width,
+            RawVideoFormat::Nv12 => {
+                self.stride == size.width && size.width % 2 == 0 && size.height % 2 == 0
+            }
+        }
+    }
+}
+
+// the rows of a plane without their padding
+fn plane_rows(
+    data: &[u8],
+    offset: usize,
+    stride: usize,
+    row_len: usize,
+    rows: usize,
+) -> impl Iterator<Item = &[u8]> {
+    (0..rows).map(move |row| &data[offset + row * stride..][..row_len])
+}
+
+// convert a raw video buffer with the default strides to an RGB image
+fn image_from_raw(
+    data: &[u8],
+    format: RawVideoFormat,
+    size: ImageSize,
+) -> Result<Image<u8, 3>, StreamCaptureError> {
+    let layout = RawLayout::new(format, size);
+    if data.len() < layout.len {
+        return Err(StreamCaptureError::InvalidImageFormat(format!(
+            "Expected a buffer of {} bytes for {} {}x{}, got {}",
+            layout.len,
+            format.as_str(),
+            size.width,
+            size.height,
+            data.len()
+        )));
+    }
+
+    match format {
+        RawVideoFormat::Rgb => {
+            let pixels = plane_rows(data, 0, layout.stride, 3 * size.width, size.height)
+                .flatten()
+                .copied()
+                .collect();
+            Image::new(size, pixels).map_err(|_| StreamCaptureError::CreateImageFrameError)
+        }
+        RawVideoFormat::Nv12 => {
+            let packed = if layout.is_packed(format, size) {
+                Cow::Borrowed(&data[..layout.len])
+            } else {
+                let uv_rows = plane_rows(
+                    data,
+                    layout.uv_offset,
+                    layout.stride,
+                    2 * size.width.div_ceil(2),
+                    size.height.div_ceil(2),
+                );
+                Cow::Owned(
+                    plane_rows(data, 0, layout.stride, size.width, size.height)
+                        .chain(uv_rows)
+                        .flatten()
+                        .copied()
+                        .collect(),
+                )
+            };
+
+            let mut image = Image::from_size_val(size, 0u8)
+                .map_err(|_| StreamCaptureError::CreateImageFrameError)?;
+            rgb_from_yuv(&packed, &mut image, YuvFormat::Nv12)
+                .map_err(|e| StreamCaptureError::ProcessImageFrameError(e.into()))?;
+            Ok(image)
+        }
+    }
+}
+
+// convert an RGB image to a raw video buffer with the default strides
+fn raw_from_image(
+    image: &Image<u8, 3>,
+    format: RawVideoFormat,
+) -> Result<Vec<u8>, StreamCaptureError> {
+    let size = image.size();
+    let layout = RawLayout::new(format, size);
+
+    // the packed pixels and the length of their rows in each plane
+    let (packed, planes) = match format {
+        RawVideoFormat::Rgb => (
+            Cow::Borrowed(image.as_slice()),
+            [(0, 3 * size.width, size.height), (0, 0, 0)],
+        ),
+        RawVideoFormat::Nv12 => {
+            let mut packed = vec![0u8; YuvFormat::Nv12.buffer_size(size)];
+            yuv_from_rgb(image, &mut packed, YuvFormat::Nv12)
+                .map_err(|e| StreamCaptureError::ProcessImageFrameError(e.into()))?;
+            let uv_row_len = 2 * size.width.div_ceil(2);
+            (
+                Cow::Owned(packed),
+                [
+                    (0, size.width, size.height),
+                    (layout.uv_offset, uv_row_len, size.height.div_ceil(2)),
+                ],
+            )
+        }
+    };
+
+    if layout.is_packed(format, size) {
+        return Ok(packed.into_owned());
+    }
+
+    // copy the packed rows of each plane to the padded rows
+    let mut data = vec![0u8; layout.len];
+    let mut offset = 0;
+    for (dst_offset, row_len, rows) in planes {
+        for row in 0..rows {
+            let dst = &mut data[dst_offset + row * layout.stride..][..row_len];
+            dst.copy_from_slice(&packed[offset..offset + row_len]);
+            offset += row_len;
+        }
+    }
+
+    Ok(data)
+}
+
+// the format and the size of the raw video caps
+fn parse_caps(
+    caps: &gstreamer::CapsRef,
+) -> Result<(RawVideoFormat, ImageSize), StreamCaptureError> {
+    let structure = caps.structure(0).ok_or_else(|| {
+        StreamCaptureError::GetCapsError("Failed to get the structure".to_string())
+    })?;
+
+    let format = structure
+        .get::<&str>("format")
+        .map_err(|e| StreamCaptureError::GetCapsError(e.to_string()))?;
+    let format = RawVideoFormat::from_caps_name(format).ok_or_else(|| {
+        StreamCaptureError::GetCapsError(format!("Unsupported format {}", format))
+    })?;
+
+    let width = structure
+        .get::<i32>("width")
+        .map_err(|e| StreamCaptureError::GetCapsError(e.to_string()))?;
+
+    let height = structure
+        .get::<i32>("height")
+        .map_err(|e| StreamCaptureError::GetCapsError(e.to_string()))?;
+
+    Ok((
+        format,
+        ImageSize {
+            width: width as usize,
+            height: height as usize,
+        },
+    ))
+}
+
+// find an element of the pipeline by name and downcast it
+fn app_element<T: IsA<gstreamer::Element>>(
+    pipeline: &gstreamer::Pipeline,
+    name: &str,
+) -> Result<T, StreamCaptureError> {
+    pipeline
+        .by_name(name)
+        .ok_or_else(|| StreamCaptureError::GetElementByNameError)?
+        .dynamic_cast::<T>()
+        .map_err(StreamCaptureError::DowncastPipelineError)
+}
+
+/// An `appsink` of a pipeline yielding RGB images.
+///
+/// The caps of the sink are restricted to the given raw formats, so the upstream elements
+/// negotiate one of them directly. A hardware decoder producing NV12 is linked without a
+/// `videoconvert` and the frames are converted to RGB on the CPU.
+///
+/// The buffers are expected in the default layout of GStreamer, with the rows aligned to 4
+/// bytes.
+///
+/// # Example
+///
+/// ```no_run
+/// use kornia_io::stream::{ImageAppSink, RawVideoFormat};
+/// use gstreamer::prelude::*;
+///
+/// gstreamer::init().unwrap();
+/// let pipeline = gstreamer::parse::launch("videotestsrc ! appsink name=sink")
+///     .unwrap()
+///     .dynamic_cast::<gstreamer::Pipeline>()
+///     .unwrap();
+///
+/// let sink = ImageAppSink::new(&pipeline, "sink", &[RawVideoFormat::Nv12, RawVideoFormat::Rgb])
+///     .unwrap();
+/// pipeline.set_state(gstreamer::State::Playing).unwrap();
+///
+/// let image = sink.pull(std::time::Duration::from_secs(1)).unwrap();
+/// ```
+pub struct ImageAppSink {
+    appsink: gstreamer_app::AppSink,
+}
+
+impl ImageAppSink {
+    /// Wraps the `appsink` of a pipeline and restricts its caps.
+    ///
+    /// # Arguments
+    ///
+    /// * `pipeline` - The pipeline containing the sink.
+    /// * `name` - The name of the `appsink` element.
+    /// * `formats` - The accepted formats, in order of preference.
+    pub fn new(
+        pipeline: &gstreamer::Pipeline,
+        name: &str,
+        formats: &[RawVideoFormat],
+    ) -> Result<Self, StreamCaptureError> {
+        if formats.is_empty() {
+            return Err(StreamCaptureError::InvalidConfig(
+                "Expected at least one format".to_string(),
+            ));
+        }
+
+        let appsink = app_element::<gstreamer_app::AppSink>(pipeline, name)?;
+
+        let caps = gstreamer::Caps::builder("video/x-raw")
+            .field(
+                "format",
+                gstreamer::List::new(formats.iter().map(|f| f.as_str())),
+            )
+            .build();
+        appsink.set_caps(Some(&caps));
+
+        Ok(Self { appsink })
+    }
+
+    /// Pulls the next frame, waiting for it if none is available.
+    ///
+    /// # Arguments
+    ///
+    /// * `timeout` - The maximum time to wait for a frame.
+    ///
+    /// # Returns
+    ///
+    /// The frame as an RGB image, or None if no frame arrived before the timeout or the
+    /// stream ended.
+    pub fn pull(&self, timeout: Duration) -> Result<Option<Image<u8, 3>>, StreamCaptureError> {
+        let timeout = gstreamer::ClockTime::from_nseconds(timeout.as_nanos() as u64);
+        let Some(sample) = self.appsink.try_pull_sample(timeout) else {
+            return Ok(None);
+        };
+
+        let caps = sample.caps().ok_or_else(|| {
+            StreamCaptureError::GetCapsError("Failed to get the caps".to_string())
+        })?;
+        let (format, size) = parse_caps(caps)?;
+
+        let buffer = sample
+            .buffer()
+            .ok_or(StreamCaptureError::GetBufferError)?
+            .map_readable()
+            .map_err(|_| StreamCaptureError::GetBufferError)?;
+
+        image_from_raw(&buffer, format, size).map(Some)
+    }
+
+    /// Returns whether the sink received the end of the stream.
+    pub fn is_eos(&self) -> bool {
+        self.appsink.is_eos()
+    }
+}
+
+/// An `appsrc` of a pipeline fed with RGB images.
+///
+/// The images are converted to the raw format of the source, e.g. NV12 to feed a hardware
+/// encoder without a `videoconvert`, and timestamped from the frame rate.
+///
+/// # Example
+///
+/// ```no_run
+/// use kornia_image::{Image, ImageSize};
+/// use kornia_io::stream::{ImageAppSrc, RawVideoFormat};
+/// use gstreamer::prelude::*;
+///
+/// gstreamer::init().unwrap();
+/// let pipeline = gstreamer::parse::launch("appsrc name=src ! x264enc ! fakesink")
+///     .unwrap()
+///     .dynamic_cast::<gstreamer::Pipeline>()
+///     .unwrap();
+///
+/// let size = ImageSize { width: 640, height: 480 };
+/// let mut src = ImageAppSrc::new(&pipeline, "src", RawVideoFormat::Nv12, size, 30).unwrap();
+/// pipeline.set_state(gstreamer::State::Playing).unwrap();
+///
+/// src.push(&Image::from_size_val(size, 0).unwrap()).unwrap();
+/// src.end_of_stream().unwrap();
+/// ```
+pub struct ImageAppSrc {
+    appsrc: gstreamer_app::AppSrc,
+    format: RawVideoFormat,
+    size: ImageSize,
+    fps: i32,
+    counter: u64,
+}
+
+impl ImageAppSrc {
+    /// Wraps the `appsrc` of a pipeline and sets its caps.
+    ///
+    /// # Arguments
+    ///
+    /// * `pipeline` - The pipeline containing the source.
+    /// * `name` - The name of the `appsrc` element.
+    /// * `format` - The raw format of the buffers pushed to the pipeline.
+    /// * `size` - The size of the images.
+    /// * `fps` - The frames per second of the stream.
+    pub fn new(
+        pipeline: &gstreamer::Pipeline,
+        name: &str,
+        format: RawVideoFormat,
+        size: ImageSize,
+        fps: i32,
+    ) -> Result<Self, StreamCaptureError> {
+        if fps <= 0 {
+            return Err(StreamCaptureError::InvalidConfig(format!(
+                "Invalid frame rate {}",
+                fps
+            )));
+        }
+
+        let appsrc = app_element::<gstreamer_app::AppSrc>(pipeline, name)?;
+
+        let caps = gstreamer::Caps::builder("video/x-raw")
+            .field("format", format.as_str())
+            .field("width", size.width as i32)
+            .field("height", size.height as i32)
+            .field("framerate", gstreamer::Fraction::new(fps, 1))
+            .build();
+        appsrc.set_caps(Some(&caps));
+        appsrc.set_format(gstreamer::Format::Time);
+
+        Ok(Self {
+            appsrc,
+            format,
+            size,
+            fps,
+            counter: 0,
+        })
+    }
+
+    /// Pushes an image to the pipeline.
+    ///
+    /// # Arguments
+    ///
+    /// * `image` - The image with the size of the source.
+    pub fn push(&mut self, image: &Image<u8, 3>) -> Result<(), StreamCaptureError> {
+        if image.size() != self.size {
+            return Err(StreamCaptureError::InvalidImageFormat(format!(
+                "Invalid image size: expected {}x{}, got {}x{}",
+                self.size.width,
+                self.size.height,
+                image.width(),
+                image.height()
+            )));
+        }
+
+        let mut buffer = gstreamer::Buffer::from_mut_slice(raw_from_image(image, self.format)?);
+
+        let frame_ns = 1_000_000_000 / self.fps as u64;
+        let buffer_ref = buffer.get_mut().ok_or(StreamCaptureError::GetBufferError)?;
+        buffer_ref.set_pts(Some(gstreamer::ClockTime::from_nseconds(
+            self.counter * frame_ns,
+        )));
+        buffer_ref.set_duration(Some(gstreamer::ClockTime::from_nseconds(frame_ns)));
+        self.counter += 1;
+
+        self.appsrc.push_buffer(buffer)?;
+
+        Ok(())
+    }
+
+    /// Signals the end of the stream to the pipeline.
+    pub fn end_of_stream(&self) -> Result<(), StreamCaptureError> {
+        self.appsrc.end_of_stream()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn raw_layout_padding() -> Result<(), StreamCaptureError> {
+        // 3 columns are padded to 12 bytes for RGB and 4 bytes for NV12
+        let size = ImageSize {
+            width: 3,
+            height: 2,
+        };
+        let image = Image::<u8, 3>::new(size, (0..18).collect())
+            .map_err(|_| StreamCaptureError::CreateImageFrameError)?;
+
+        let raw = raw_from_image(&image, RawVideoFormat::Rgb)?;
+        assert_eq!(raw.len(), 24);
+        assert_eq!(&raw[9..12], &[0, 0, 0]);
+        let back = image_from_raw(&raw, RawVideoFormat::Rgb, size)?;
+        assert_eq!(back.as_slice(), image.as_slice());
+
+        let raw = raw_from_image(&image, RawVideoFormat::Nv12)?;
+        assert_eq!(raw.len(), 4 * 2 + 4);
+        let back = image_from_raw(&raw, RawVideoFormat::Nv12, size)?;
+        assert_eq!(back.size(), size);
+
+        assert!(image_from_raw(&raw[..8], RawVideoFormat::Nv12, size).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn raw_nv12_gray() -> Result<(), StreamCaptureError> {
+        // a gray image survives the chroma subsampling
+        let size = ImageSize {
+            width: 4,
+            height: 2,
+        };
+        let image = Image::<u8, 3>::from_size_val(size, 128)
+            .map_err(|_| StreamCaptureError::CreateImageFrameError)?;
+
+        let raw = raw_from_image(&image, RawVideoFormat::Nv12)?;
+        assert_eq!(raw.len(), YuvFormat::Nv12.buffer_size(size));
+        let back = image_from_raw(&raw, RawVideoFormat::Nv12, size)?;
+        assert!(back.as_slice().iter().all(|&v| v.abs_diff(128) <= 1));
+
+        Ok(())
+    }
+
+    #[ignore = "need gstreamer in CI"]
+    #[test]
+    fn app_sink_and_src() -> Result<(), Box<dyn std::error::Error>> {
+        gstreamer::init()?;
+
+        let pipeline = gstreamer::parse::launch(
+            "videotestsrc num-buffers=2 ! video/x-raw,width=64,height=48 ! appsink name=sink",
+        )?
+        .dynamic_cast::<gstreamer::Pipeline>()
+        .map_err(StreamCaptureError::DowncastPipelineError)?;
+        let sink = ImageAppSink::new(&pipeline, "sink", &[RawVideoFormat::Nv12])?;
+        pipeline.set_state(gstreamer::State::Playing)?;
+
+        let image = sink
+            .pull(Duration::from_secs(5))?
+            .expect("expected a frame");
+        assert_eq!(image.size(), [64, 48].into());
+        pipeline.set_state(gstreamer::State::Null)?;
+
+        let pipeline = gstreamer::parse::launch("appsrc name=src ! fakesink")?
+            .dynamic_cast::<gstreamer::Pipeline>()
+            .map_err(StreamCaptureError::DowncastPipelineError)?;
+        let mut src = ImageAppSrc::new(&pipeline, "src", RawVideoFormat::Nv12, image.size(), 30)?;
+        pipeline.set_state(gstreamer::State::Playing)?;
+
+        src.push(&image)?;
+        assert!(src.push(&Image::from_size_val([2, 2].into(), 0)?).is_err());
+        src.end_of_stream()?;
+        pipeline.set_state(gstreamer::State::Null)?;
+
+        Ok(())
+    }
+}
@@ -46,6 +46,7 @@ impl CameraCapture {
     /// - The configuration type is unknown
     /// - The `StreamCapture` creation fails
     pub fn new(config: &dyn CameraCaptureConfig) -> Result<Self, StreamCaptureError> {
+        let mut reconnect_interval = None;
         let pipeline = if let Some(config) = config.as_any().downcast_ref::<V4L2CameraConfig>() {
             // check that the device is not empty
             if config.device.is_empty() {
@@ -53,7 +54,7 @@ impl CameraCapture {
                     "device is empty".to_string(),
                 ));
             }
-            v4l2_camera_pipeline_description(&config.device, config.size, config.fps)
+            v4l2_camera_pipeline_description(&config.device, config.size, config.fps, config.format)
         } else if let Some(config) = config.as_any().downcast_ref::<RTSPCameraConfig>() {
             // check that the url is not empty
             if config.url.is_empty() {
@@ -61,6 +62,7 @@ impl CameraCapture {
                     "url is empty".to_string(),
                 ));
             }
+            reconnect_interval = config.reconnect_interval;
             rtsp_camera_pipeline_description(&config.url, config.latency)
         } else {
             return Err(StreamCaptureError::InvalidConfig(
@@ -68,7 +70,12 @@ impl CameraCapture {
             ));
         };
 
-        Ok(Self(StreamCapture::new(&pipeline)?))
+        let mut capture = StreamCapture::new(&pipeline)?;
+        if let Some(interval) = reconnect_interval {
+            capture = capture.with_reconnect(interval);
+        }
+
+        Ok(Self(capture))
     }
 }
 
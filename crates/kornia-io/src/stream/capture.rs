@@ -2,19 +2,49 @@ use crate::stream::error::StreamCaptureError;
 use circular_buffer::CircularBuffer;
 use gstreamer::prelude::*;
 use kornia_image::{Image, ImageSize};
-use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Condvar, Mutex};
+use std::time::{Duration, Instant};
 
 // utility struct to store the frame buffer
 struct FrameBuffer {
     buffer: gstreamer::Buffer,
     width: i32,
     height: i32,
+    arrival: Instant,
+}
+
+/// Statistics about the frames received by a [`StreamCapture`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct StreamStats {
+    /// The number of frames received from the pipeline.
+    pub frames_received: u64,
+    /// The number of frames overwritten before being grabbed.
+    pub frames_dropped: u64,
+    /// The number of times the pipeline was restarted after an error or end of stream.
+    pub reconnects: u64,
+    /// The time the last grabbed frame waited in the capture buffer.
+    pub latency: Duration,
+}
+
+// thread safe counters to compute the stream statistics
+#[derive(Default)]
+struct StreamCounters {
+    frames_received: AtomicU64,
+    frames_dropped: AtomicU64,
+    reconnects: AtomicU64,
+    latency_ns: AtomicU64,
 }
 
 /// Represents a stream capture pipeline using GStreamer.
 pub struct StreamCapture {
     pipeline: gstreamer::Pipeline,
     circular_buffer: Arc<Mutex<CircularBuffer<5, FrameBuffer>>>,
+    frame_ready: Arc<Condvar>,
+    counters: Arc<StreamCounters>,
+    reconnect_interval: Option<Duration>,
+    running: Arc<AtomicBool>,
+    bus_handle: Mutex<Option<std::thread::JoinHandle<()>>>,
 }
 
 impl StreamCapture {
@@ -43,11 +73,15 @@ impl StreamCapture {
             .map_err(StreamCaptureError::DowncastPipelineError)?;
 
         let circular_buffer = Arc::new(Mutex::new(CircularBuffer::new()));
+        let frame_ready = Arc::new(Condvar::new());
+        let counters = Arc::new(StreamCounters::default());
 
         appsink.set_callbacks(
             gstreamer_app::AppSinkCallbacks::builder()
                 .new_sample({
                     let circular_buffer = circular_buffer.clone();
+                    let frame_ready = frame_ready.clone();
+                    let counters = counters.clone();
                     move |sink| {
                         Self::extract_frame_buffer(sink)
                             .map_err(|_| gstreamer::FlowError::Eos)
@@ -55,7 +89,12 @@ impl StreamCapture {
                                 let mut guard = circular_buffer
                                     .lock()
                                     .map_err(|_| gstreamer::FlowError::Error)?;
+                                counters.frames_received.fetch_add(1, Ordering::Relaxed);
+                                if guard.is_full() {
+                                    counters.frames_dropped.fetch_add(1, Ordering::Relaxed);
+                                }
                                 guard.push_back(frame_buffer);
+                                frame_ready.notify_all();
                                 Ok(gstreamer::FlowSuccess::Ok)
                             })
                     }
@@ -66,9 +105,36 @@ impl StreamCapture {
         Ok(Self {
             pipeline,
             circular_buffer,
+            frame_ready,
+            counters,
+            reconnect_interval: None,
+            running: Arc::new(AtomicBool::new(false)),
+            bus_handle: Mutex::new(None),
         })
     }
 
+    /// Enables restarting the pipeline when an error or end of stream is received.
+    ///
+    /// This is useful for network sources that can be disconnected temporarily.
+    ///
+    /// # Arguments
+    ///
+    /// * `interval` - The time to wait before restarting the pipeline.
+    pub fn with_reconnect(mut self, interval: Duration) -> Self {
+        self.reconnect_interval = Some(interval);
+        self
+    }
+
+    /// Returns the statistics of the received frames.
+    pub fn stats(&self) -> StreamStats {
+        StreamStats {
+            frames_received: self.counters.frames_received.load(Ordering::Relaxed),
+            frames_dropped: self.counters.frames_dropped.load(Ordering::Relaxed),
+            reconnects: self.counters.reconnects.load(Ordering::Relaxed),
+            latency: Duration::from_nanos(self.counters.latency_ns.load(Ordering::Relaxed)),
+        }
+    }
+
     /// Starts the stream capture pipeline and processes messages on the bus.
     pub fn start(&self) -> Result<(), StreamCaptureError> {
         self.circular_buffer
@@ -76,6 +142,60 @@ impl StreamCapture {
             .map_err(|_| StreamCaptureError::MutexPoisonError)?
             .clear();
         self.pipeline.set_state(gstreamer::State::Playing)?;
+
+        if let Some(interval) = self.reconnect_interval {
+            self.spawn_reconnect_watcher(interval)?;
+        }
+
+        Ok(())
+    }
+
+    // launch a task to restart the pipeline on errors until the capture is closed
+    fn spawn_reconnect_watcher(&self, interval: Duration) -> Result<(), StreamCaptureError> {
+        let bus = self.pipeline.bus().ok_or(StreamCaptureError::BusError)?;
+        let pipeline = self.pipeline.clone();
+        let running = self.running.clone();
+        let counters = self.counters.clone();
+
+        running.store(true, Ordering::SeqCst);
+
+        let handle = std::thread::spawn(move || {
+            while running.load(Ordering::SeqCst) {
+                let Some(msg) = bus.timed_pop_filtered(
+                    gstreamer::ClockTime::from_mseconds(100),
+                    &[gstreamer::MessageType::Error, gstreamer::MessageType::Eos],
+                ) else {
+                    continue;
+                };
+
+                match msg.view() {
+                    gstreamer::MessageView::Error(err) => {
+                        log::warn!("Stream error, reconnecting: {}", err.error());
+                    }
+                    _ => log::warn!("Stream ended, reconnecting"),
+                }
+
+                if let Err(err) = pipeline.set_state(gstreamer::State::Null) {
+                    log::error!("Failed to stop the pipeline: {}", err);
+                }
+
+                std::thread::sleep(interval);
+                if !running.load(Ordering::SeqCst) {
+                    break;
+                }
+
+                counters.reconnects.fetch_add(1, Ordering::Relaxed);
+                if let Err(err) = pipeline.set_state(gstreamer::State::Playing) {
+                    log::error!("Failed to restart the pipeline: {}", err);
+                }
+            }
+        });
+
+        *self
+            .bus_handle
+            .lock()
+            .map_err(|_| StreamCaptureError::MutexPoisonError)? = Some(handle);
+
         Ok(())
     }
 
@@ -89,28 +209,67 @@ impl StreamCapture {
             .circular_buffer
             .lock()
             .map_err(|_| StreamCaptureError::MutexPoisonError)?;
-        if let Some(frame_buffer) = circular_buffer.pop_front() {
-            // TODO: solve the zero copy issue
-            // https://discourse.gstreamer.org/t/zero-copy-video-frames/3856/2
-            let buffer = frame_buffer
-                .buffer
-                .map_readable()
-                .map_err(|_| StreamCaptureError::GetBufferError)?;
-            let img = Image::<u8, 3>::new(
-                ImageSize {
-                    width: frame_buffer.width as usize,
-                    height: frame_buffer.height as usize,
-                },
-                buffer.to_owned(),
-            )
-            .map_err(|_| StreamCaptureError::CreateImageFrameError)?;
-            return Ok(Some(img));
-        }
-        Ok(None)
+        circular_buffer
+            .pop_front()
+            .map(|frame_buffer| self.frame_buffer_to_image(frame_buffer))
+            .transpose()
+    }
+
+    /// Grabs the next captured image frame, waiting for it if none is available.
+    ///
+    /// # Arguments
+    ///
+    /// * `timeout` - The maximum time to wait for a new frame.
+    ///
+    /// # Returns
+    ///
+    /// An Option containing the captured Image or None if no frame arrived before the timeout.
+    pub fn grab_timeout(
+        &mut self,
+        timeout: Duration,
+    ) -> Result<Option<Image<u8, 3>>, StreamCaptureError> {
+        let circular_buffer = self
+            .circular_buffer
+            .lock()
+            .map_err(|_| StreamCaptureError::MutexPoisonError)?;
+        let (mut circular_buffer, _) = self
+            .frame_ready
+            .wait_timeout_while(circular_buffer, timeout, |buffer| buffer.is_empty())
+            .map_err(|_| StreamCaptureError::MutexPoisonError)?;
+        circular_buffer
+            .pop_front()
+            .map(|frame_buffer| self.frame_buffer_to_image(frame_buffer))
+            .transpose()
+    }
+
+    /// Returns an iterator over the captured image frames.
+    ///
+    /// The iterator blocks until a new frame is available and ends when no frame
+    /// arrives within the given timeout, e.g. when the stream is closed.
+    ///
+    /// # Arguments
+    ///
+    /// * `timeout` - The maximum time to wait for each frame.
+    pub fn frames(
+        &mut self,
+        timeout: Duration,
+    ) -> impl Iterator<Item = Result<Image<u8, 3>, StreamCaptureError>> + '_ {
+        std::iter::from_fn(move || self.grab_timeout(timeout).transpose())
     }
 
     /// Closes the stream capture pipeline.
     pub fn close(&self) -> Result<(), StreamCaptureError> {
+        // stop the reconnect task before sending the end of stream
+        self.running.store(false, Ordering::SeqCst);
+        if let Some(handle) = self
+            .bus_handle
+            .lock()
+            .map_err(|_| StreamCaptureError::MutexPoisonError)?
+            .take()
+        {
+            handle.join().expect("Failed to join thread");
+        }
+
         let res = self.pipeline.send_event(gstreamer::event::Eos::new());
         if !res {
             return Err(StreamCaptureError::SendEosError);
@@ -123,6 +282,32 @@ impl StreamCapture {
         Ok(())
     }
 
+    // converts the frame buffer into an owned image
+    fn frame_buffer_to_image(
+        &self,
+        frame_buffer: FrameBuffer,
+    ) -> Result<Image<u8, 3>, StreamCaptureError> {
+        self.counters.latency_ns.store(
+            frame_buffer.arrival.elapsed().as_nanos() as u64,
+            Ordering::Relaxed,
+        );
+
+        // TODO: solve the zero copy issue
+        // https://discourse.gstreamer.org/t/zero-copy-video-frames/3856/2
+        let buffer = frame_buffer
+            .buffer
+            .map_readable()
+            .map_err(|_| StreamCaptureError::GetBufferError)?;
+        Image::<u8, 3>::new(
+            ImageSize {
+                width: frame_buffer.width as usize,
+                height: frame_buffer.height as usize,
+            },
+            buffer.to_owned(),
+        )
+        .map_err(|_| StreamCaptureError::CreateImageFrameError)
+    }
+
     /// Extracts a frame buffer from the AppSink.
     ///
     /// # Arguments
@@ -161,6 +346,7 @@ impl StreamCapture {
             buffer,
             width,
             height,
+            arrival: Instant::now(),
         };
 
         Ok(frame_buffer)
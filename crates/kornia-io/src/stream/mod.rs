@@ -1,3 +1,6 @@
+/// Image frames exchanged with the `appsink` and `appsrc` elements of a pipeline.
+pub mod app;
+
 /// A module for capturing video streams from v4l2 cameras.
 pub mod camera;
 
@@ -16,9 +19,10 @@ pub mod v4l2;
 /// A module for capturing video streams from video files.
 pub mod video;
 
+pub use crate::stream::app::{ImageAppSink, ImageAppSrc, RawVideoFormat};
 pub use crate::stream::camera::{CameraCapture, CameraCaptureConfig};
-pub use crate::stream::capture::StreamCapture;
+pub use crate::stream::capture::{StreamCapture, StreamStats};
 pub use crate::stream::error::StreamCaptureError;
 pub use crate::stream::rtsp::RTSPCameraConfig;
-pub use crate::stream::v4l2::V4L2CameraConfig;
-pub use crate::stream::video::VideoWriter;
+pub use crate::stream::v4l2::{V4L2CameraConfig, V4L2PixelFormat};
+pub use crate::stream::video::{VideoFrame, VideoReader, VideoWriter};
@@ -1,4 +1,4 @@
-use std::any::Any;
+use std::{any::Any, time::Duration};
 
 use crate::stream::{
     camera::{CameraCapture, CameraCaptureConfig},
@@ -11,6 +11,8 @@ pub struct RTSPCameraConfig {
     pub url: String,
     /// The latency for the Rtsp stream
     pub latency: u32,
+    /// The time to wait before reconnecting after a failure, or None to disable reconnection
+    pub reconnect_interval: Option<Duration>,
 }
 
 impl CameraCaptureConfig for RTSPCameraConfig {
@@ -29,6 +31,7 @@ impl RTSPCameraConfig {
         Self {
             url: String::new(),
             latency: 0,
+            reconnect_interval: None,
         }
     }
 
@@ -54,6 +57,16 @@ impl RTSPCameraConfig {
         self
     }
 
+    /// Enables reconnecting to the Rtsp stream after a failure.
+    ///
+    /// # Arguments
+    ///
+    /// * `interval` - The time to wait before reconnecting
+    pub fn with_reconnect(mut self, interval: Duration) -> Self {
+        self.reconnect_interval = Some(interval);
+        self
+    }
+
     /// Sets the settings for the RTSPCameraConfig.
     ///
     /// # Arguments
@@ -7,6 +7,17 @@ use crate::stream::{
 
 use kornia_image::ImageSize;
 
+/// The pixel format requested to a V4L2 camera.
+///
+/// The frames are always converted to RGB before being delivered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum V4L2PixelFormat {
+    /// Uncompressed packed YUV 4:2:2 format.
+    Yuyv,
+    /// Motion JPEG compressed format, usually needed for high resolutions and frame rates.
+    Mjpeg,
+}
+
 /// A configuration object for capturing frames from a V4L2 camera.
 pub struct V4L2CameraConfig {
     /// The camera device path
@@ -15,6 +26,8 @@ pub struct V4L2CameraConfig {
     pub size: Option<ImageSize>,
     /// The desired frames per second
     pub fps: u32,
+    /// The desired pixel format, or None to let the camera negotiate it
+    pub format: Option<V4L2PixelFormat>,
 }
 
 impl CameraCaptureConfig for V4L2CameraConfig {
@@ -26,7 +39,8 @@ impl CameraCaptureConfig for V4L2CameraConfig {
 impl V4L2CameraConfig {
     /// Creates a new V4L2CameraConfig object with default values.
     ///
-    /// Note: The default device is "/dev/video0", the default image size is None, and the default fps is 30.
+    /// Note: The default device is "/dev/video0", the default image size is None, the default fps is 30
+    /// and the default pixel format is None.
     ///
     /// # Returns
     ///
@@ -36,6 +50,7 @@ impl V4L2CameraConfig {
             device: "/dev/video0".to_string(),
             size: None,
             fps: 30,
+            format: None,
         }
     }
 
@@ -79,6 +94,16 @@ impl V4L2CameraConfig {
         self
     }
 
+    /// Sets the pixel format for the V4L2CameraConfig.
+    ///
+    /// # Arguments
+    ///
+    /// * `format` - The desired pixel format
+    pub fn with_format(mut self, format: V4L2PixelFormat) -> Self {
+        self.format = Some(format);
+        self
+    }
+
     /// Create a new [`CameraCapture`] object.
     pub fn build(self) -> Result<CameraCapture, StreamCaptureError> {
         CameraCapture::new(&self)
@@ -98,19 +123,69 @@ impl Default for V4L2CameraConfig {
 /// * `device` - The camera device path
 /// * `size` - The image size to capture
 /// * `fps` - The desired frames per second
+/// * `format` - The pixel format to request to the camera
 ///
 /// # Returns
 ///
 /// A GStreamer pipeline string
-pub fn v4l2_camera_pipeline_description(device: &str, size: Option<ImageSize>, fps: u32) -> String {
+pub fn v4l2_camera_pipeline_description(
+    device: &str,
+    size: Option<ImageSize>,
+    fps: u32,
+    format: Option<V4L2PixelFormat>,
+) -> String {
     let video_resize = if let Some(size) = size {
-        format!("! video/x-raw,width={},height={} ", size.width, size.height)
+        format!(",width={},height={}", size.width, size.height)
     } else {
         "".to_string()
     };
 
+    // negotiate the format with the camera and decode it if needed
+    let video_source = match format {
+        Some(V4L2PixelFormat::Yuyv) => format!(
+            "! video/x-raw,format=YUY2{},framerate={}/1 ",
+            video_resize, fps
+        ),
+        Some(V4L2PixelFormat::Mjpeg) => format!(
+            "! image/jpeg{},framerate={}/1 ! jpegdec ",
+            video_resize, fps
+        ),
+        None => format!(
+            "! video/x-raw{} ! videorate ! video/x-raw,framerate={}/1 ",
+            video_resize, fps
+        ),
+    };
+
     format!(
-            "v4l2src device={} {}! videorate ! video/x-raw,framerate={}/1 ! videoconvert ! video/x-raw,format=RGB ! appsink name=sink",
-            device, video_resize, fps
-        )
+        "v4l2src device={} {}! videoconvert ! video/x-raw,format=RGB ! appsink name=sink",
+        device, video_source
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn v4l2_pipeline_description() {
+        let size = Some(ImageSize {
+            width: 640,
+            height: 480,
+        });
+
+        assert_eq!(
+            v4l2_camera_pipeline_description("/dev/video0", size, 30, None),
+            "v4l2src device=/dev/video0 ! video/x-raw,width=640,height=480 ! videorate ! video/x-raw,framerate=30/1 ! videoconvert ! video/x-raw,format=RGB ! appsink name=sink"
+        );
+
+        assert_eq!(
+            v4l2_camera_pipeline_description("/dev/video0", None, 60, Some(V4L2PixelFormat::Mjpeg)),
+            "v4l2src device=/dev/video0 ! image/jpeg,framerate=60/1 ! jpegdec ! videoconvert ! video/x-raw,format=RGB ! appsink name=sink"
+        );
+
+        assert_eq!(
+            v4l2_camera_pipeline_description("/dev/video1", size, 15, Some(V4L2PixelFormat::Yuyv)),
+            "v4l2src device=/dev/video1 ! video/x-raw,format=YUY2,width=640,height=480,framerate=15/1 ! videoconvert ! video/x-raw,format=RGB ! appsink name=sink"
+        );
+    }
 }
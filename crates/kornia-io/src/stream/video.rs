@@ -4,9 +4,12 @@ use kornia_image::{Image, ImageSize};
 use std::path::Path;
 
 /// The codec to use for the video writer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum VideoCodec {
     /// H.264 codec.
     H264,
+    /// VP9 codec.
+    VP9,
 }
 
 /// The format of the image to write to the video file.
@@ -23,6 +26,8 @@ pub enum ImageFormat {
 pub struct VideoWriter {
     pipeline: gstreamer::Pipeline,
     appsrc: gstreamer_app::AppSrc,
+    encoder: gstreamer::Element,
+    codec: VideoCodec,
     fps: i32,
     format: ImageFormat,
     counter: u64,
@@ -34,7 +39,8 @@ impl VideoWriter {
     ///
     /// # Arguments
     ///
-    /// * `path` - The path to save the video file.
+    /// * `path` - The path to save the video file. The container is selected from the
+    ///   extension: `mp4` for H.264, `webm` for VP9 and `mkv` for both.
     /// * `codec` - The codec to use for the video writer.
     /// * `format` - The expected image format.
     /// * `fps` - The frames per second of the video.
@@ -51,33 +57,45 @@ impl VideoWriter {
             gstreamer::init()?;
         }
 
-        // TODO: Add support for other codecs
-        #[allow(unreachable_patterns)]
-        let _codec = match codec {
-            VideoCodec::H264 => "x264enc",
+        let path = path.as_ref().to_owned();
+
+        // the container is selected from the file extension
+        let extension = path
+            .extension()
+            .map(|ext| ext.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+
+        let muxer = match (codec, extension.as_str()) {
+            (VideoCodec::H264, "mp4") => "mp4mux",
+            (VideoCodec::VP9, "webm") => "webmmux",
+            (_, "mkv") => "matroskamux",
             _ => {
-                return Err(StreamCaptureError::InvalidConfig(
-                    "Unsupported codec".to_string(),
-                ))
+                return Err(StreamCaptureError::InvalidConfig(format!(
+                    "Unsupported container {:?} for codec {:?}",
+                    extension, codec
+                )))
             }
         };
 
+        let encoder = match codec {
+            VideoCodec::H264 => "x264enc name=encoder ! video/x-h264,profile=main ! h264parse",
+            VideoCodec::VP9 => "vp9enc name=encoder deadline=1",
+        };
+
         // TODO: Add support for other formats
         let format_str = match format {
             ImageFormat::Mono8 => "GRAY8",
             ImageFormat::Rgb8 => "RGB",
         };
 
-        let path = path.as_ref().to_owned();
-
         let pipeline_str = format!(
             "appsrc name=src ! \
             videoconvert ! video/x-raw,format=I420 ! \
-            x264enc ! \
-            video/x-h264,profile=main ! \
-            h264parse ! \
-            mp4mux ! \
+            {} ! \
+            {} ! \
             filesink location={}",
+            encoder,
+            muxer,
             path.to_string_lossy()
         );
 
@@ -91,6 +109,10 @@ impl VideoWriter {
             .dynamic_cast::<gstreamer_app::AppSrc>()
             .map_err(StreamCaptureError::DowncastPipelineError)?;
 
+        let encoder = pipeline
+            .by_name("encoder")
+            .ok_or_else(|| StreamCaptureError::GetElementByNameError)?;
+
         appsrc.set_format(gstreamer::Format::Time);
 
         let caps = gstreamer::Caps::builder("video/x-raw")
@@ -108,6 +130,8 @@ impl VideoWriter {
         Ok(Self {
             pipeline,
             appsrc,
+            encoder,
+            codec,
             fps,
             format,
             counter: 0,
@@ -115,6 +139,23 @@ impl VideoWriter {
         })
     }
 
+    /// Set the target bitrate of the encoder.
+    ///
+    /// Must be called before starting the video writer.
+    ///
+    /// # Arguments
+    ///
+    /// * `bitrate` - The target bitrate in kbit/s.
+    pub fn with_bitrate(self, bitrate: u32) -> Self {
+        match self.codec {
+            VideoCodec::H264 => self.encoder.set_property("bitrate", bitrate),
+            VideoCodec::VP9 => self
+                .encoder
+                .set_property("target-bitrate", (bitrate as i32).saturating_mul(1000)),
+        }
+        self
+    }
+
     /// Start the video writer.
     ///
     /// Set the pipeline to playing and launch a task to handle the bus messages.
@@ -223,9 +264,244 @@ impl Drop for VideoWriter {
     }
 }
 
+/// A decoded video frame with its presentation timestamp.
+pub struct VideoFrame {
+    /// The decoded image in RGB format.
+    pub image: Image<u8, 3>,
+    /// The presentation timestamp of the frame from the start of the video.
+    pub timestamp: std::time::Duration,
+}
+
+/// A struct for reading video files and network streams.
+///
+/// The frames are decoded on demand, so no frame is dropped while reading.
+///
+/// # Example
+///
+/// ```no_run
+/// use kornia_io::stream::VideoReader;
+///
+/// let mut reader = VideoReader::new("video.mp4").unwrap();
+/// reader.start().unwrap();
+///
+/// for frame in reader.by_ref() {
+///     let frame = frame.unwrap();
+///     println!("{:?}: {:?}", frame.timestamp, frame.image.size());
+/// }
+/// ```
+pub struct VideoReader {
+    pipeline: gstreamer::Pipeline,
+    appsink: gstreamer_app::AppSink,
+}
+
+impl VideoReader {
+    /// Create a new VideoReader from a local video file.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path to the video file.
+    pub fn new(path: impl AsRef<Path>) -> Result<Self, StreamCaptureError> {
+        let path = path.as_ref();
+        if !path.exists() {
+            return Err(StreamCaptureError::InvalidConfig(format!(
+                "File does not exist: {}",
+                path.to_string_lossy()
+            )));
+        }
+
+        let path = path
+            .canonicalize()
+            .map_err(|e| StreamCaptureError::InvalidConfig(e.to_string()))?;
+        let uri = gstreamer::glib::filename_to_uri(path, None)?;
+
+        Self::from_uri(&uri)
+    }
+
+    /// Create a new VideoReader from an uri.
+    ///
+    /// # Arguments
+    ///
+    /// * `uri` - The uri of the video, e.g. `file:///path/video.mp4` or `https://host/video.mp4`.
+    pub fn from_uri(uri: &str) -> Result<Self, StreamCaptureError> {
+        // make sure that we do not initialize gstreamer several times
+        if !gstreamer::INITIALIZED.load(std::sync::atomic::Ordering::Relaxed) {
+            gstreamer::init()?;
+        }
+
+        let pipeline_str = format!(
+            "uridecodebin uri={} ! \
+            videoconvert ! video/x-raw,format=RGB ! \
+            appsink name=sink sync=false",
+            uri
+        );
+
+        let pipeline = gstreamer::parse::launch(&pipeline_str)?
+            .dynamic_cast::<gstreamer::Pipeline>()
+            .map_err(StreamCaptureError::DowncastPipelineError)?;
+
+        let appsink = pipeline
+            .by_name("sink")
+            .ok_or_else(|| StreamCaptureError::GetElementByNameError)?
+            .dynamic_cast::<gstreamer_app::AppSink>()
+            .map_err(StreamCaptureError::DowncastPipelineError)?;
+
+        Ok(Self { pipeline, appsink })
+    }
+
+    /// Start the video reader.
+    ///
+    /// Set the pipeline to playing and wait until the first frame is ready.
+    pub fn start(&mut self) -> Result<(), StreamCaptureError> {
+        self.pipeline.set_state(gstreamer::State::Playing)?;
+
+        // wait for the state change to complete so that the stream can be queried and seeked
+        let (res, _, _) = self.pipeline.state(gstreamer::ClockTime::NONE);
+        if res.is_err() {
+            return Err(self
+                .bus_error()
+                .unwrap_or(StreamCaptureError::PipelineNotRunning));
+        }
+
+        Ok(())
+    }
+
+    /// Close the video reader.
+    pub fn close(&mut self) -> Result<(), StreamCaptureError> {
+        self.pipeline.set_state(gstreamer::State::Null)?;
+        Ok(())
+    }
+
+    /// Read the next frame of the video.
+    ///
+    /// # Returns
+    ///
+    /// The next frame or `None` if the end of the video is reached.
+    pub fn read(&mut self) -> Result<Option<VideoFrame>, StreamCaptureError> {
+        let Some(sample) = self.appsink.try_pull_sample(gstreamer::ClockTime::NONE) else {
+            // the sample is missing either because of an error or the end of the stream
+            return match self.bus_error() {
+                Some(err) => Err(err),
+                None => Ok(None),
+            };
+        };
+
+        let caps = sample.caps().ok_or_else(|| {
+            StreamCaptureError::GetCapsError("Failed to get the caps".to_string())
+        })?;
+
+        let structure = caps.structure(0).ok_or_else(|| {
+            StreamCaptureError::GetCapsError("Failed to get the structure".to_string())
+        })?;
+
+        let width = structure
+            .get::<i32>("width")
+            .map_err(|e| StreamCaptureError::GetCapsError(e.to_string()))?
+            as usize;
+
+        let height = structure
+            .get::<i32>("height")
+            .map_err(|e| StreamCaptureError::GetCapsError(e.to_string()))?
+            as usize;
+
+        let buffer = sample
+            .buffer()
+            .ok_or_else(|| StreamCaptureError::GetBufferError)?;
+
+        let timestamp = buffer
+            .pts()
+            .map(|pts| std::time::Duration::from_nanos(pts.nseconds()))
+            .unwrap_or_default();
+
+        let map = buffer
+            .map_readable()
+            .map_err(|_| StreamCaptureError::GetBufferError)?;
+
+        // the rows of the frame might be padded to be aligned in memory
+        let row_size = width * 3;
+        let stride = map.len().checked_div(height).unwrap_or_default();
+        if stride < row_size {
+            return Err(StreamCaptureError::CreateImageFrameError);
+        }
+
+        let mut data = Vec::with_capacity(row_size * height);
+        for row in map.chunks(stride).take(height) {
+            data.extend_from_slice(&row[..row_size]);
+        }
+
+        let image = Image::<u8, 3>::new(ImageSize { width, height }, data)
+            .map_err(|_| StreamCaptureError::CreateImageFrameError)?;
+
+        Ok(Some(VideoFrame { image, timestamp }))
+    }
+
+    /// Seek the video to the given position.
+    ///
+    /// The next frame read will be the one at the given position.
+    ///
+    /// # Arguments
+    ///
+    /// * `position` - The position from the start of the video.
+    pub fn seek(&mut self, position: std::time::Duration) -> Result<(), StreamCaptureError> {
+        self.pipeline.seek_simple(
+            gstreamer::SeekFlags::FLUSH | gstreamer::SeekFlags::ACCURATE,
+            gstreamer::ClockTime::from_nseconds(position.as_nanos() as u64),
+        )?;
+
+        // wait for the seek to complete
+        let (res, _, _) = self.pipeline.state(gstreamer::ClockTime::NONE);
+        res?;
+
+        Ok(())
+    }
+
+    /// Get the duration of the video.
+    ///
+    /// # Returns
+    ///
+    /// The duration or `None` if it is unknown, e.g. for live streams.
+    pub fn duration(&self) -> Option<std::time::Duration> {
+        self.pipeline
+            .query_duration::<gstreamer::ClockTime>()
+            .map(|d| std::time::Duration::from_nanos(d.nseconds()))
+    }
+
+    /// Get the current position of the video.
+    pub fn position(&self) -> Option<std::time::Duration> {
+        self.pipeline
+            .query_position::<gstreamer::ClockTime>()
+            .map(|p| std::time::Duration::from_nanos(p.nseconds()))
+    }
+
+    // pop the first error message of the bus
+    fn bus_error(&self) -> Option<StreamCaptureError> {
+        let bus = self.pipeline.bus()?;
+        let msg = bus.pop_filtered(&[gstreamer::MessageType::Error])?;
+        match msg.view() {
+            gstreamer::MessageView::Error(err) => {
+                Some(StreamCaptureError::GStreamerError(err.error()))
+            }
+            _ => None,
+        }
+    }
+}
+
+impl Iterator for VideoReader {
+    type Item = Result<VideoFrame, StreamCaptureError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.read().transpose()
+    }
+}
+
+impl Drop for VideoReader {
+    fn drop(&mut self) {
+        self.close().expect("Failed to close video reader");
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::{ImageFormat, VideoCodec, VideoWriter};
+    use super::{ImageFormat, VideoCodec, VideoReader, VideoWriter};
     use kornia_image::{Image, ImageSize};
 
     #[ignore = "need gstreamer in CI"]
@@ -279,4 +555,79 @@ mod tests {
 
         Ok(())
     }
+
+    #[ignore = "need gstreamer in CI"]
+    #[test]
+    fn video_reader_rgb8u() -> Result<(), Box<dyn std::error::Error>> {
+        let tmp_dir = tempfile::tempdir()?;
+        std::fs::create_dir_all(tmp_dir.path())?;
+
+        let file_path = tmp_dir.path().join("test.mp4");
+
+        let size = ImageSize {
+            width: 6,
+            height: 4,
+        };
+
+        let mut writer =
+            VideoWriter::new(&file_path, VideoCodec::H264, ImageFormat::Rgb8, 30, size)?;
+        writer.start()?;
+
+        let img = Image::<u8, 3>::new(size, vec![0; size.width * size.height * 3])?;
+        for _ in 0..10 {
+            writer.write(&img)?;
+        }
+        writer.close()?;
+
+        let mut reader = VideoReader::new(&file_path)?;
+        reader.start()?;
+
+        let frames = reader.by_ref().collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(frames.len(), 10);
+        assert_eq!(frames[0].image.size(), size);
+        assert!(frames[1].timestamp > frames[0].timestamp);
+
+        reader.seek(std::time::Duration::from_millis(200))?;
+        let frame = reader.read()?.expect("missing frame after seek");
+        assert!(frame.timestamp >= std::time::Duration::from_millis(166));
+
+        Ok(())
+    }
+
+    #[ignore = "need gstreamer in CI"]
+    #[test]
+    fn video_writer_vp9_bitrate() -> Result<(), Box<dyn std::error::Error>> {
+        let tmp_dir = tempfile::tempdir()?;
+        std::fs::create_dir_all(tmp_dir.path())?;
+
+        let file_path = tmp_dir.path().join("test.webm");
+
+        let size = ImageSize {
+            width: 6,
+            height: 4,
+        };
+
+        let mut writer =
+            VideoWriter::new(&file_path, VideoCodec::VP9, ImageFormat::Rgb8, 15, size)?
+                .with_bitrate(500);
+        writer.start()?;
+
+        let img = Image::<u8, 3>::new(size, vec![0; size.width * size.height * 3])?;
+        writer.write(&img)?;
+        writer.close()?;
+
+        assert!(file_path.exists(), "File does not exist: {:?}", file_path);
+
+        // the codec must match the container
+        let res = VideoWriter::new(
+            tmp_dir.path().join("test.webm"),
+            VideoCodec::H264,
+            ImageFormat::Rgb8,
+            15,
+            size,
+        );
+        assert!(res.is_err());
+
+        Ok(())
+    }
 }
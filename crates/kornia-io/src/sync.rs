@@ -0,0 +1,238 @@
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use crate::error::IoError;
+
+/// A frame with its capture timestamp.
+pub struct TimestampedFrame<T> {
+    /// The capture timestamp of the frame.
+    pub timestamp: Duration,
+    /// The frame, e.g. an image from a capture backend.
+    pub frame: T,
+}
+
+/// Aligns the frames of multiple timestamped streams, e.g. the cameras of a stereo rig.
+///
+/// The frames are pushed per stream in capture order and emitted as tuples with one frame
+/// per stream whose timestamps are all within the tolerance. Frames which cannot be matched
+/// anymore, because a later frame of another stream is already too far ahead, are dropped.
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+/// use kornia_io::sync::FrameSynchronizer;
+///
+/// let mut sync = FrameSynchronizer::new(2, Duration::from_millis(5));
+///
+/// sync.push(0, Duration::from_millis(100), "left").unwrap();
+/// assert!(sync.pop().is_none());
+///
+/// sync.push(1, Duration::from_millis(102), "right").unwrap();
+/// let frames = sync.pop().unwrap();
+/// assert_eq!(frames[0].frame, "left");
+/// assert_eq!(frames[1].frame, "right");
+/// ```
+pub struct FrameSynchronizer<T> {
+    queues: Vec<VecDeque<TimestampedFrame<T>>>,
+    tolerance: Duration,
+    max_queue_len: usize,
+    num_dropped: usize,
+}
+
+impl<T> FrameSynchronizer<T> {
+    /// The default maximum number of frames buffered per stream.
+    pub const DEFAULT_MAX_QUEUE_LEN: usize = 32;
+
+    /// Creates a new FrameSynchronizer.
+    ///
+    /// # Arguments
+    ///
+    /// * `num_streams` - The number of streams to align.
+    /// * `tolerance` - The maximum difference between the timestamps of an aligned tuple.
+    pub fn new(num_streams: usize, tolerance: Duration) -> Self {
+        Self {
+            queues: (0..num_streams).map(|_| VecDeque::new()).collect(),
+            tolerance,
+            max_queue_len: Self::DEFAULT_MAX_QUEUE_LEN,
+            num_dropped: 0,
+        }
+    }
+
+    /// Sets the maximum number of frames buffered per stream.
+    ///
+    /// When a stream stalls, the oldest frames of the other streams are dropped once their
+    /// queues are full.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_queue_len` - The maximum number of frames per stream, at least one.
+    pub fn with_max_queue_len(mut self, max_queue_len: usize) -> Self {
+        self.max_queue_len = max_queue_len.max(1);
+        self
+    }
+
+    /// Returns the number of streams.
+    pub fn num_streams(&self) -> usize {
+        self.queues.len()
+    }
+
+    /// Returns the tolerance of the aligned tuples.
+    pub fn tolerance(&self) -> Duration {
+        self.tolerance
+    }
+
+    /// Returns the number of frames dropped since the creation.
+    pub fn num_dropped(&self) -> usize {
+        self.num_dropped
+    }
+
+    /// Returns the number of frames buffered for a stream.
+    pub fn queue_len(&self, stream: usize) -> Result<usize, IoError> {
+        self.queue(stream).map(|queue| queue.len())
+    }
+
+    /// Pushes a frame to a stream.
+    ///
+    /// Frames older than the last frame of the stream are out of order and are dropped.
+    ///
+    /// # Arguments
+    ///
+    /// * `stream` - The index of the stream.
+    /// * `timestamp` - The capture timestamp of the frame.
+    /// * `frame` - The frame.
+    pub fn push(&mut self, stream: usize, timestamp: Duration, frame: T) -> Result<(), IoError> {
+        let max_queue_len = self.max_queue_len;
+        let queue = self.queue_mut(stream)?;
+
+        if queue.back().is_some_and(|last| timestamp < last.timestamp) {
+            self.num_dropped += 1;
+            return Ok(());
+        }
+
+        queue.push_back(TimestampedFrame { timestamp, frame });
+
+        if queue.len() > max_queue_len {
+            queue.pop_front();
+            self.num_dropped += 1;
+        }
+
+        Ok(())
+    }
+
+    /// Pops the next aligned tuple of frames.
+    ///
+    /// # Returns
+    ///
+    /// The frames ordered by stream or None if no tuple can be aligned yet.
+    pub fn pop(&mut self) -> Option<Vec<TimestampedFrame<T>>> {
+        if self.queues.is_empty() {
+            return None;
+        }
+
+        loop {
+            let mut oldest = 0;
+            let mut newest = Duration::ZERO;
+            for (i, queue) in self.queues.iter().enumerate() {
+                let timestamp = queue.front()?.timestamp;
+                if timestamp < self.queues[oldest].front()?.timestamp {
+                    oldest = i;
+                }
+                newest = newest.max(timestamp);
+            }
+
+            let oldest_timestamp = self.queues[oldest].front()?.timestamp;
+            if newest - oldest_timestamp <= self.tolerance {
+                return self
+                    .queues
+                    .iter_mut()
+                    .map(|queue| queue.pop_front())
+                    .collect();
+            }
+
+            // the following frames of the newest stream are even later
+            self.queues[oldest].pop_front();
+            self.num_dropped += 1;
+        }
+    }
+
+    /// Removes all the buffered frames.
+    pub fn clear(&mut self) {
+        self.queues.iter_mut().for_each(|queue| queue.clear());
+    }
+
+    fn queue(&self, stream: usize) -> Result<&VecDeque<TimestampedFrame<T>>, IoError> {
+        let num_streams = self.queues.len();
+        self.queues
+            .get(stream)
+            .ok_or(IoError::InvalidStreamIndex(stream, num_streams))
+    }
+
+    fn queue_mut(&mut self, stream: usize) -> Result<&mut VecDeque<TimestampedFrame<T>>, IoError> {
+        let num_streams = self.queues.len();
+        self.queues
+            .get_mut(stream)
+            .ok_or(IoError::InvalidStreamIndex(stream, num_streams))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ms(millis: u64) -> Duration {
+        Duration::from_millis(millis)
+    }
+
+    #[test]
+    fn test_synchronize_streams() -> Result<(), IoError> {
+        let mut sync = FrameSynchronizer::new(3, ms(5));
+
+        // the first frame of the stream 0 has no match in the stream 1
+        sync.push(0, ms(0), 0)?;
+        sync.push(0, ms(33), 1)?;
+        sync.push(1, ms(31), 10)?;
+        sync.push(2, ms(29), 20)?;
+        sync.push(2, ms(62), 21)?;
+
+        let frames = sync.pop().expect("aligned tuple");
+        assert_eq!(
+            frames.iter().map(|f| f.frame).collect::<Vec<_>>(),
+            vec![1, 10, 20]
+        );
+        assert_eq!(sync.num_dropped(), 1);
+
+        // the stream 0 has no frame left
+        assert!(sync.pop().is_none());
+        assert_eq!(sync.queue_len(2)?, 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_synchronize_drop() -> Result<(), IoError> {
+        let mut sync = FrameSynchronizer::new(2, ms(1)).with_max_queue_len(2);
+
+        sync.push(0, ms(10), 0)?;
+        sync.push(0, ms(20), 1)?;
+        sync.push(0, ms(30), 2)?;
+        assert_eq!(sync.queue_len(0)?, 2);
+
+        // out of order frames are dropped
+        sync.push(0, ms(25), 3)?;
+        assert_eq!(sync.num_dropped(), 2);
+
+        sync.push(1, ms(30), 10)?;
+        let frames = sync.pop().expect("aligned tuple");
+        assert_eq!(frames[0].frame, 2);
+        assert_eq!(frames[1].timestamp, ms(30));
+        assert_eq!(sync.num_dropped(), 3);
+
+        assert!(matches!(
+            sync.push(2, ms(40), 0),
+            Err(IoError::InvalidStreamIndex(2, 2))
+        ));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,629 @@
+use std::path::Path;
+#[cfg(feature = "tiff-tiles")]
+use std::{fs::File, io::Cursor};
+
+use image::{DynamicImage, ImageBuffer, ImageFormat, Luma, Pixel, Rgb};
+use kornia_image::{Image, ImageSize};
+#[cfg(feature = "tiff-tiles")]
+use rayon::prelude::*;
+#[cfg(feature = "tiff-tiles")]
+use tiff::decoder::{ChunkType, Decoder, DecodingResult};
+
+use crate::error::IoError;
+
+/// Read a TIFF image with a single channel (mono8).
+///
+/// # Arguments
+///
+/// * `file_path` - The path to the TIFF file.
+///
+/// # Returns
+///
+/// A grayscale image with a single channel (mono8).
+pub fn read_image_tiff_mono8(file_path: impl AsRef<Path>) -> Result<Image<u8, 1>, IoError> {
+    decode_image_tiff_mono8(&read_tiff_file(file_path)?)
+}
+
+/// Decode a TIFF image with a single channel (mono8) from its raw bytes.
+///
+/// # Arguments
+///
+/// * `src` - The raw bytes of the TIFF file.
+///
+/// # Returns
+///
+/// A grayscale image with a single channel (mono8).
+pub fn decode_image_tiff_mono8(src: &[u8]) -> Result<Image<u8, 1>, IoError> {
+    let img = image::load_from_memory_with_format(src, ImageFormat::Tiff)?;
+    let size = image_size(&img);
+    Ok(Image::new(size, img.into_luma8().into_raw())?)
+}
+
+/// Read a TIFF image with a three channels (rgb8).
+///
+/// # Arguments
+///
+/// * `file_path` - The path to the TIFF file.
+///
+/// # Returns
+///
+/// A RGB image with three channels (rgb8).
+pub fn read_image_tiff_rgb8(file_path: impl AsRef<Path>) -> Result<Image<u8, 3>, IoError> {
+    decode_image_tiff_rgb8(&read_tiff_file(file_path)?)
+}
+
+/// Decode a TIFF image with three channels (rgb8) from its raw bytes.
+///
+/// # Arguments
+///
+/// * `src` - The raw bytes of the TIFF file.
+///
+/// # Returns
+///
+/// A RGB image with three channels (rgb8).
+pub fn decode_image_tiff_rgb8(src: &[u8]) -> Result<Image<u8, 3>, IoError> {
+    let img = image::load_from_memory_with_format(src, ImageFormat::Tiff)?;
+    let size = image_size(&img);
+    Ok(Image::new(size, img.into_rgb8().into_raw())?)
+}
+
+/// Read a TIFF image with a single channel (mono16).
+///
+/// This is the usual format to store depth maps and thermal images.
+///
+/// # Arguments
+///
+/// * `file_path` - The path to the TIFF file.
+///
+/// # Returns
+///
+/// A grayscale image with a single channel (mono16).
+pub fn read_image_tiff_mono16(file_path: impl AsRef<Path>) -> Result<Image<u16, 1>, IoError> {
+    decode_image_tiff_mono16(&read_tiff_file(file_path)?)
+}
+
+/// Decode a TIFF image with a single channel (mono16) from its raw bytes.
+///
+/// # Arguments
+///
+/// * `src` - The raw bytes of the TIFF file.
+///
+/// # Returns
+///
+/// A grayscale image with a single channel (mono16).
+pub fn decode_image_tiff_mono16(src: &[u8]) -> Result<Image<u16, 1>, IoError> {
+    let img = image::load_from_memory_with_format(src, ImageFormat::Tiff)?;
+    let size = image_size(&img);
+    Ok(Image::new(size, img.into_luma16().into_raw())?)
+}
+
+/// Read a TIFF image with a three channels (rgb16).
+///
+/// # Arguments
+///
+/// * `file_path` - The path to the TIFF file.
+///
+/// # Returns
+///
+/// A RGB image with three channels (rgb16).
+pub fn read_image_tiff_rgb16(file_path: impl AsRef<Path>) -> Result<Image<u16, 3>, IoError> {
+    decode_image_tiff_rgb16(&read_tiff_file(file_path)?)
+}
+
+/// Decode a TIFF image with three channels (rgb16) from its raw bytes.
+///
+/// # Arguments
+///
+/// * `src` - The raw bytes of the TIFF file.
+///
+/// # Returns
+///
+/// A RGB image with three channels (rgb16).
+pub fn decode_image_tiff_rgb16(src: &[u8]) -> Result<Image<u16, 3>, IoError> {
+    let img = image::load_from_memory_with_format(src, ImageFormat::Tiff)?;
+    let size = image_size(&img);
+    Ok(Image::new(size, img.into_rgb16().into_raw())?)
+}
+
+/// Writes the given TIFF _(grayscale 8-bit)_ data to the given file path.
+///
+/// # Arguments
+///
+/// - `file_path` - The path to the TIFF image.
+/// - `image` - The tensor containing the TIFF image data.
+pub fn write_image_tiff_mono8(
+    file_path: impl AsRef<Path>,
+    image: &Image<u8, 1>,
+) -> Result<(), IoError> {
+    write_tiff_impl::<Luma<u8>, 1>(file_path, image)
+}
+
+/// Writes the given TIFF _(rgb8)_ data to the given file path.
+///
+/// # Arguments
+///
+/// - `file_path` - The path to the TIFF image.
+/// - `image` - The tensor containing the TIFF image data.
+pub fn write_image_tiff_rgb8(
+    file_path: impl AsRef<Path>,
+    image: &Image<u8, 3>,
+) -> Result<(), IoError> {
+    write_tiff_impl::<Rgb<u8>, 3>(file_path, image)
+}
+
+/// Writes the given TIFF _(grayscale 16-bit)_ data to the given file path.
+///
+/// # Arguments
+///
+/// - `file_path` - The path to the TIFF image.
+/// - `image` - The tensor containing the TIFF image data.
+pub fn write_image_tiff_mono16(
+    file_path: impl AsRef<Path>,
+    image: &Image<u16, 1>,
+) -> Result<(), IoError> {
+    write_tiff_impl::<Luma<u16>, 1>(file_path, image)
+}
+
+/// Writes the given TIFF _(rgb16)_ data to the given file path.
+///
+/// # Arguments
+///
+/// - `file_path` - The path to the TIFF image.
+/// - `image` - The tensor containing the TIFF image data.
+pub fn write_image_tiff_rgb16(
+    file_path: impl AsRef<Path>,
+    image: &Image<u16, 3>,
+) -> Result<(), IoError> {
+    write_tiff_impl::<Rgb<u16>, 3>(file_path, image)
+}
+
+#[cfg(feature = "tiff-tiles")]
+/// A sample type that can be decoded from the chunks of a TIFF file.
+pub trait TiffSample: Copy + Send + Sync + 'static {
+    /// Extracts the samples from a decoded chunk, or None if the sample type does not match.
+    #[doc(hidden)]
+    fn from_decoding_result(result: DecodingResult) -> Option<Vec<Self>>;
+}
+
+#[cfg(feature = "tiff-tiles")]
+impl TiffSample for u8 {
+    fn from_decoding_result(result: DecodingResult) -> Option<Vec<Self>> {
+        match result {
+            DecodingResult::U8(data) => Some(data),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(feature = "tiff-tiles")]
+impl TiffSample for u16 {
+    fn from_decoding_result(result: DecodingResult) -> Option<Vec<Self>> {
+        match result {
+            DecodingResult::U16(data) => Some(data),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(feature = "tiff-tiles")]
+impl TiffSample for f32 {
+    fn from_decoding_result(result: DecodingResult) -> Option<Vec<Self>> {
+        match result {
+            DecodingResult::F32(data) => Some(data),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(feature = "tiff-tiles")]
+/// A tile of a larger image.
+pub struct ImageTile<T, const C: usize> {
+    /// The column of the top-left pixel of the tile in the full image.
+    pub x: usize,
+    /// The row of the top-left pixel of the tile in the full image.
+    pub y: usize,
+    /// The pixels of the tile.
+    pub image: Image<T, C>,
+}
+
+#[cfg(feature = "tiff-tiles")]
+/// A reader to decode very large TIFF images tile by tile.
+///
+/// The file is memory mapped and each tile (or strip) stored in the file is decoded
+/// independently, so the full image is never loaded in memory. The file can also be
+/// given as bytes already in memory with [`TiffTileReader::from_bytes`].
+///
+/// # Example
+///
+/// ```no_run
+/// use kornia_io::tiff::TiffTileReader;
+///
+/// let reader = TiffTileReader::open("gigapixel.tiff").unwrap();
+///
+/// reader
+///     .for_each_tile::<u8, 3>(|tile| {
+///         println!("tile at ({}, {}): {:?}", tile.x, tile.y, tile.image.size());
+///     })
+///     .unwrap();
+/// ```
+pub struct TiffTileReader {
+    // the memory mapped file or the bytes given by the caller
+    data: Box<dyn AsRef<[u8]> + Send + Sync>,
+    size: ImageSize,
+    tile_size: ImageSize,
+    tiles_across: usize,
+    num_tiles: usize,
+    num_channels: usize,
+}
+
+#[cfg(feature = "tiff-tiles")]
+impl TiffTileReader {
+    /// Opens a TIFF file to read it tile by tile.
+    ///
+    /// The file must not be modified while the reader is alive.
+    ///
+    /// # Arguments
+    ///
+    /// * `file_path` - The path to the TIFF file.
+    pub fn open(file_path: impl AsRef<Path>) -> Result<Self, IoError> {
+        let file_path = file_path.as_ref();
+        validate_tiff_path(file_path)?;
+
+        let file = File::open(file_path)?;
+
+        // SAFETY: the file is only read and must not be modified while it is mapped
+        let mmap = unsafe { memmap2::Mmap::map(&file)? };
+
+        Self::new(Box::new(mmap))
+    }
+
+    /// Reads a TIFF file already in memory tile by tile.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - The raw bytes of the TIFF file.
+    pub fn from_bytes(data: Vec<u8>) -> Result<Self, IoError> {
+        Self::new(Box::new(data))
+    }
+
+    // parse the layout of the image from the header of the file
+    fn new(data: Box<dyn AsRef<[u8]> + Send + Sync>) -> Result<Self, IoError> {
+        let mut decoder = Decoder::new(Cursor::new((*data).as_ref()))?;
+
+        if decoder.find_tag_unsigned::<u16>(tiff::tags::Tag::PlanarConfiguration)? == Some(2) {
+            return Err(IoError::UnsupportedTiffLayout(
+                "planar configuration".to_string(),
+            ));
+        }
+
+        let (width, height) = decoder.dimensions()?;
+        let (tile_width, tile_height) = decoder.chunk_dimensions();
+
+        let num_channels = match decoder.colortype()? {
+            tiff::ColorType::Gray(_) => 1,
+            tiff::ColorType::GrayA(_) => 2,
+            tiff::ColorType::RGB(_) => 3,
+            tiff::ColorType::RGBA(_) => 4,
+            color_type => {
+                return Err(IoError::UnsupportedTiffLayout(format!(
+                    "color type {:?}",
+                    color_type
+                )))
+            }
+        };
+
+        let (tiles_across, num_tiles) = match decoder.get_chunk_type() {
+            ChunkType::Strip => (1, decoder.strip_count()? as usize),
+            ChunkType::Tile => (
+                (width as usize).div_ceil(tile_width as usize),
+                decoder.tile_count()? as usize,
+            ),
+        };
+
+        Ok(Self {
+            data,
+            size: ImageSize {
+                width: width as usize,
+                height: height as usize,
+            },
+            tile_size: ImageSize {
+                width: tile_width as usize,
+                height: tile_height as usize,
+            },
+            tiles_across,
+            num_tiles,
+            num_channels,
+        })
+    }
+
+    /// Returns the size of the full image.
+    pub fn size(&self) -> ImageSize {
+        self.size
+    }
+
+    /// Returns the size of the tiles, the tiles at the borders can be smaller.
+    pub fn tile_size(&self) -> ImageSize {
+        self.tile_size
+    }
+
+    /// Returns the number of tiles of the image.
+    pub fn num_tiles(&self) -> usize {
+        self.num_tiles
+    }
+
+    /// Returns the number of channels of the image.
+    pub fn num_channels(&self) -> usize {
+        self.num_channels
+    }
+
+    /// Decodes a single tile of the image.
+    ///
+    /// The tiles are indexed in row-major order.
+    ///
+    /// # Arguments
+    ///
+    /// * `index` - The index of the tile in the range [0, num_tiles).
+    pub fn read_tile<T: TiffSample, const C: usize>(
+        &self,
+        index: usize,
+    ) -> Result<ImageTile<T, C>, IoError> {
+        let mut decoder = self.decoder()?;
+        self.read_tile_with(&mut decoder, index)
+    }
+
+    /// Decodes all the tiles of the image in parallel and visits them.
+    ///
+    /// The tiles are visited in no particular order.
+    ///
+    /// # Arguments
+    ///
+    /// * `f` - The function to call with each decoded tile.
+    pub fn for_each_tile<T: TiffSample, const C: usize>(
+        &self,
+        f: impl Fn(ImageTile<T, C>) + Send + Sync,
+    ) -> Result<(), IoError> {
+        (0..self.num_tiles)
+            .into_par_iter()
+            .map_init(
+                || self.decoder(),
+                |decoder, index| {
+                    let decoder = decoder
+                        .as_mut()
+                        .map_err(|e| IoError::UnsupportedTiffLayout(e.to_string()))?;
+                    f(self.read_tile_with(decoder, index)?);
+                    Ok(())
+                },
+            )
+            .collect()
+    }
+
+    // create a new decoder over the mapped file
+    fn decoder(&self) -> Result<Decoder<Cursor<&[u8]>>, IoError> {
+        Ok(Decoder::new(Cursor::new((*self.data).as_ref()))?)
+    }
+
+    fn read_tile_with<T: TiffSample, const C: usize>(
+        &self,
+        decoder: &mut Decoder<Cursor<&[u8]>>,
+        index: usize,
+    ) -> Result<ImageTile<T, C>, IoError> {
+        if C != self.num_channels {
+            return Err(IoError::UnsupportedTiffLayout(format!(
+                "expected {} channels, but the image has {}",
+                C, self.num_channels
+            )));
+        }
+
+        if index >= self.num_tiles {
+            return Err(IoError::UnsupportedTiffLayout(format!(
+                "tile index {} out of bounds {}",
+                index, self.num_tiles
+            )));
+        }
+
+        let (width, height) = decoder.chunk_data_dimensions(index as u32);
+        let data = T::from_decoding_result(decoder.read_chunk(index as u32)?).ok_or_else(|| {
+            IoError::UnsupportedTiffLayout("the sample type does not match".to_string())
+        })?;
+
+        let image = Image::new(
+            ImageSize {
+                width: width as usize,
+                height: height as usize,
+            },
+            data,
+        )?;
+
+        Ok(ImageTile {
+            x: (index % self.tiles_across) * self.tile_size.width,
+            y: (index / self.tiles_across) * self.tile_size.height,
+            image,
+        })
+    }
+}
+
+fn image_size(img: &DynamicImage) -> ImageSize {
+    ImageSize {
+        width: img.width() as usize,
+        height: img.height() as usize,
+    }
+}
+
+// utility function to read the bytes of the tiff file
+fn read_tiff_file(file_path: impl AsRef<Path>) -> Result<Vec<u8>, IoError> {
+    let file_path = file_path.as_ref();
+    validate_tiff_path(file_path)?;
+
+    Ok(std::fs::read(file_path)?)
+}
+
+// verify the file exists and has a tiff extension
+fn validate_tiff_path(file_path: &Path) -> Result<(), IoError> {
+    if !file_path.exists() {
+        return Err(IoError::FileDoesNotExist(file_path.to_path_buf()));
+    }
+
+    if file_path.extension().map_or(true, |ext| {
+        !ext.eq_ignore_ascii_case("tif") && !ext.eq_ignore_ascii_case("tiff")
+    }) {
+        return Err(IoError::InvalidFileExtension(file_path.to_path_buf()));
+    }
+
+    Ok(())
+}
+
+// utility function to write the tiff file
+fn write_tiff_impl<P, const C: usize>(
+    file_path: impl AsRef<Path>,
+    image: &Image<P::Subpixel, C>,
+) -> Result<(), IoError>
+where
+    P: Pixel + image::PixelWithColorType,
+    [P::Subpixel]: image::EncodableLayout,
+{
+    let image_size = image.size();
+    let buf = ImageBuffer::<P, &[P::Subpixel]>::from_raw(
+        image_size.width as u32,
+        image_size.height as u32,
+        image.as_slice(),
+    )
+    .ok_or(IoError::InvalidBufferSize(
+        image.as_slice().len(),
+        image_size.width * image_size.height * C,
+    ))?;
+
+    buf.save_with_format(file_path, ImageFormat::Tiff)?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::create_dir_all;
+
+    #[test]
+    fn read_write_tiff_mono16() -> Result<(), IoError> {
+        let tmp_dir = tempfile::tempdir()?;
+        create_dir_all(tmp_dir.path())?;
+
+        let file_path = tmp_dir.path().join("depth.tiff");
+        let image = Image::<u16, 1>::new(
+            ImageSize {
+                width: 4,
+                height: 2,
+            },
+            vec![0, 1, 255, 256, 1000, 4096, 40000, 65535],
+        )?;
+        write_image_tiff_mono16(&file_path, &image)?;
+
+        let image_back = read_image_tiff_mono16(&file_path)?;
+        assert_eq!(image_back.size(), image.size());
+        assert_eq!(image_back.as_slice(), image.as_slice());
+
+        Ok(())
+    }
+
+    #[test]
+    fn read_write_tiff_rgb8() -> Result<(), IoError> {
+        let tmp_dir = tempfile::tempdir()?;
+        create_dir_all(tmp_dir.path())?;
+
+        let file_path = tmp_dir.path().join("image.tif");
+        let image = Image::<u8, 3>::new(
+            ImageSize {
+                width: 2,
+                height: 1,
+            },
+            vec![0, 1, 2, 253, 254, 255],
+        )?;
+        write_image_tiff_rgb8(&file_path, &image)?;
+
+        let image_back = read_image_tiff_rgb8(&file_path)?;
+        assert_eq!(image_back.as_slice(), image.as_slice());
+
+        let image_mono = read_image_tiff_mono8(&file_path)?;
+        assert_eq!(image_mono.num_channels(), 1);
+        assert_eq!(image_mono.size(), image.size());
+
+        let image_decoded = decode_image_tiff_rgb8(&std::fs::read(&file_path)?)?;
+        assert_eq!(image_decoded.as_slice(), image.as_slice());
+        assert!(decode_image_tiff_rgb8(b"II*\0garbage").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn read_tiff_invalid_extension() {
+        let res = read_image_tiff_mono8("../../tests/data/dog.jpeg");
+        assert!(matches!(res, Err(IoError::InvalidFileExtension(_))));
+    }
+
+    #[cfg(feature = "tiff-tiles")]
+    #[test]
+    fn tiff_tile_reader() -> Result<(), IoError> {
+        let tmp_dir = tempfile::tempdir()?;
+        let file_path = tmp_dir.path().join("strips.tiff");
+
+        let size = ImageSize {
+            width: 10,
+            height: 7,
+        };
+        let data = (0..size.width * size.height)
+            .map(|i| i as u16 * 300)
+            .collect::<Vec<_>>();
+
+        // write the image in strips of 3 rows
+        {
+            let file = File::create(&file_path)?;
+            let mut encoder = tiff::encoder::TiffEncoder::new(file)?;
+            let mut image = encoder.new_image::<tiff::encoder::colortype::Gray16>(
+                size.width as u32,
+                size.height as u32,
+            )?;
+            image.rows_per_strip(3)?;
+            image.write_data(&data)?;
+        }
+
+        let reader = TiffTileReader::open(&file_path)?;
+        assert_eq!(reader.size(), size);
+        assert_eq!(reader.num_tiles(), 3);
+        assert_eq!(reader.num_channels(), 1);
+
+        let last = reader.read_tile::<u16, 1>(2)?;
+        assert_eq!((last.x, last.y), (0, 6));
+        assert_eq!(last.image.rows(), 1);
+
+        // reconstruct the full image from the tiles
+        let full = std::sync::Mutex::new(vec![0u16; data.len()]);
+        reader.for_each_tile::<u16, 1>(|tile| {
+            let mut full = full.lock().unwrap();
+            for (r, row) in tile
+                .image
+                .as_slice()
+                .chunks_exact(tile.image.cols())
+                .enumerate()
+            {
+                let offset = (tile.y + r) * size.width + tile.x;
+                full[offset..offset + row.len()].copy_from_slice(row);
+            }
+        })?;
+        assert_eq!(full.into_inner().unwrap(), data);
+
+        // the number of channels and the sample type must match
+        assert!(reader.read_tile::<u16, 3>(0).is_err());
+        assert!(reader.read_tile::<u8, 1>(0).is_err());
+        assert!(reader.read_tile::<u16, 1>(3).is_err());
+
+        // the same file already in memory
+        let reader = TiffTileReader::from_bytes(std::fs::read(&file_path)?)?;
+        assert_eq!(reader.num_tiles(), 3);
+        assert_eq!(
+            reader.read_tile::<u16, 1>(2)?.image.as_slice(),
+            last.image.as_slice()
+        );
+        assert!(TiffTileReader::from_bytes(b"II*\0garbage".to_vec()).is_err());
+
+        Ok(())
+    }
+}
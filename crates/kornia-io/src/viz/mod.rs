@@ -0,0 +1,307 @@
+mod window;
+
+use std::cell::RefCell;
+use std::time::Duration;
+
+use kornia_image::{Image, ImageSize};
+use kornia_imgproc::draw::{draw_keypoints, draw_text};
+
+use crate::error::IoError;
+use window::Viewer;
+
+/// A key pressed in a window of the viewer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Key {
+    /// A printable character, including the space.
+    Char(char),
+    /// The escape key.
+    Escape,
+    /// The enter key.
+    Enter,
+    /// The left arrow key.
+    Left,
+    /// The right arrow key.
+    Right,
+    /// The up arrow key.
+    Up,
+    /// The down arrow key.
+    Down,
+    /// Any other key.
+    Other,
+}
+
+/// A drawing on top of a displayed image.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Overlay {
+    /// Keypoints drawn as circles.
+    Keypoints {
+        /// The keypoints as (x, y) coordinates.
+        points: Vec<[f32; 2]>,
+        /// The RGB color of the circles.
+        color: [u8; 3],
+        /// The radius of the circles in pixels.
+        radius: usize,
+    },
+    /// Text drawn with a bitmap font.
+    Text {
+        /// The top-left corner of the text as (x, y) coordinates.
+        origin: (i64, i64),
+        /// The text to draw.
+        text: String,
+        /// The RGB color of the text.
+        color: [u8; 3],
+        /// The size in pixels of each dot of the font.
+        scale: usize,
+    },
+}
+
+thread_local! {
+    // the viewer is bound to the thread of its event loop
+    static VIEWER: RefCell<Option<Viewer>> = const { RefCell::new(None) };
+}
+
+fn with_viewer<R>(f: impl FnOnce(&mut Viewer) -> Result<R, IoError>) -> Result<R, IoError> {
+    VIEWER.with(|viewer| {
+        let mut viewer = viewer.borrow_mut();
+        let initialized = match viewer.take() {
+            Some(initialized) => initialized,
+            None => Viewer::new()?,
+        };
+        f(viewer.insert(initialized))
+    })
+}
+
+/// Converts an 8-bit image to RGB and draws the overlays on top of it.
+///
+/// # Arguments
+///
+/// * `image` - The grayscale, RGB or RGBA image.
+/// * `overlays` - The drawings on top of the image.
+///
+/// # Returns
+///
+/// The RGB image with the overlays.
+pub fn render_overlays<const C: usize>(
+    image: &Image<u8, C>,
+    overlays: &[Overlay],
+) -> Result<Image<u8, 3>, IoError> {
+    let data = match C {
+        1 => image.as_slice().iter().flat_map(|&v| [v, v, v]).collect(),
+        3 => image.as_slice().to_vec(),
+        4 => image
+            .as_slice()
+            .chunks_exact(4)
+            .flat_map(|px| [px[0], px[1], px[2]])
+            .collect(),
+        _ => {
+            return Err(IoError::ViewerError(format!(
+                "unsupported number of channels {}",
+                C
+            )))
+        }
+    };
+    let mut rgb = Image::new(image.size(), data)?;
+
+    for overlay in overlays {
+        match overlay {
+            Overlay::Keypoints {
+                points,
+                color,
+                radius,
+            } => draw_keypoints(&mut rgb, points, *color, *radius),
+            Overlay::Text {
+                origin,
+                text,
+                color,
+                scale,
+            } => draw_text(&mut rgb, *origin, text, *color, *scale),
+        }
+    }
+
+    Ok(rgb)
+}
+
+/// Displays an image in a native window.
+///
+/// The window is created on the first call with its name and resized to the image. As with
+/// `cv::imshow`, the window is updated when the events are processed by [`wait_key`].
+///
+/// The viewer runs the event loop of the windowing system on the calling thread, which must
+/// be the main thread on macOS.
+///
+/// # Arguments
+///
+/// * `image` - The grayscale, RGB or RGBA image to display.
+/// * `window_name` - The name of the window.
+///
+/// # Example
+///
+/// ```no_run
+/// use kornia_image::Image;
+/// use kornia_io::viz::{show, wait_key};
+///
+/// let image = Image::<u8, 3>::from_size_val([640, 480].into(), 128).unwrap();
+/// show(&image, "image").unwrap();
+/// wait_key(None).unwrap();
+/// ```
+pub fn show<const C: usize>(image: &Image<u8, C>, window_name: &str) -> Result<(), IoError> {
+    show_with_overlays(image, window_name, &[])
+}
+
+/// Displays an image with keypoints and text drawn on top of it in a native window.
+///
+/// See [`show`] for the behavior of the windows.
+///
+/// # Arguments
+///
+/// * `image` - The grayscale, RGB or RGBA image to display.
+/// * `window_name` - The name of the window.
+/// * `overlays` - The drawings on top of the image.
+pub fn show_with_overlays<const C: usize>(
+    image: &Image<u8, C>,
+    window_name: &str,
+    overlays: &[Overlay],
+) -> Result<(), IoError> {
+    let frame = render_overlays(image, overlays)?;
+    with_viewer(|viewer| {
+        viewer.push_frame(window_name, frame);
+        Ok(())
+    })
+}
+
+/// Processes the events of the windows and waits for a key press.
+///
+/// # Arguments
+///
+/// * `timeout` - The maximum time to wait, or `None` to wait until a key is pressed or all
+///   the windows are closed.
+///
+/// # Returns
+///
+/// The pressed key, or `None` if the timeout elapsed or all the windows were closed.
+pub fn wait_key(timeout: Option<Duration>) -> Result<Option<Key>, IoError> {
+    with_viewer(|viewer| viewer.wait_key(timeout))
+}
+
+/// Closes all the windows of the viewer.
+pub fn destroy_all_windows() -> Result<(), IoError> {
+    with_viewer(|viewer| {
+        viewer.close_windows();
+        Ok(())
+    })
+}
+
+// the largest rectangle with the aspect ratio of the image centered in the window
+fn fit_rect(image: ImageSize, window: ImageSize) -> [i32; 4] {
+    let scale = f64::min(
+        window.width as f64 / image.width as f64,
+        window.height as f64 / image.height as f64,
+    );
+    let width = (image.width as f64 * scale).round() as i32;
+    let height = (image.height as f64 * scale).round() as i32;
+    let x0 = (window.width as i32 - width) / 2;
+    let y0 = (window.height as i32 - height) / 2;
+    [x0, y0, x0 + width, y0 + height]
+}
+
+// scale the frame to fit the window with the nearest pixels, packed as 0RGB, and fill the
+// borders in black
+fn blit_frame(frame: &Image<u8, 3>, buffer: &mut [u32], window: ImageSize) {
+    buffer.fill(0);
+
+    let [x0, y0, x1, y1] = fit_rect(frame.size(), window).map(|v| v.max(0) as usize);
+    let (width, height) = (x1 - x0, y1 - y0);
+    if width == 0 || height == 0 {
+        return;
+    }
+
+    let src = frame.as_slice();
+    for (y, dst_row) in buffer
+        .chunks_exact_mut(window.width)
+        .enumerate()
+        .skip(y0)
+        .take(height)
+    {
+        let src_row = &src[(y - y0) * frame.height() / height * frame.width() * 3..];
+        for (x, dst_pixel) in dst_row[x0..x1].iter_mut().enumerate() {
+            let px = &src_row[x * frame.width() / width * 3..];
+            *dst_pixel = u32::from_be_bytes([0, px[0], px[1], px[2]]);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_overlays() -> Result<(), IoError> {
+        let image = Image::<u8, 1>::from_size_val([32, 16].into(), 10)?;
+        let overlays = [
+            Overlay::Keypoints {
+                points: vec![[8.0, 8.0]],
+                color: [255, 0, 0],
+                radius: 3,
+            },
+            Overlay::Text {
+                origin: (20, 2),
+                text: "k".to_string(),
+                color: [0, 255, 0],
+                scale: 1,
+            },
+        ];
+
+        let rgb = render_overlays(&image, &overlays)?;
+        assert_eq!(rgb.size(), image.size());
+        assert_eq!(rgb.get_pixel(0, 0, 0)?, &10);
+        assert_eq!(rgb.get_pixel(11, 8, 0)?, &255);
+        assert!(rgb.as_slice().chunks_exact(3).any(|px| px == [0, 255, 0]));
+
+        let rgba = Image::<u8, 4>::from_size_val([2, 2].into(), 7)?;
+        assert_eq!(render_overlays(&rgba, &[])?.as_slice(), &[7; 12]);
+        let two = Image::<u8, 2>::from_size_val([2, 2].into(), 7)?;
+        assert!(render_overlays(&two, &[]).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_fit_rect() {
+        let image = ImageSize {
+            width: 100,
+            height: 50,
+        };
+        let window = ImageSize {
+            width: 400,
+            height: 400,
+        };
+        assert_eq!(fit_rect(image, window), [0, 100, 400, 300]);
+        assert_eq!(fit_rect(image, image), [0, 0, 100, 50]);
+    }
+
+    #[test]
+    fn test_blit_frame() -> Result<(), IoError> {
+        let frame = Image::<u8, 3>::new([2, 1].into(), vec![255, 0, 0, 1, 2, 3])?;
+
+        // each pixel is scaled to 2x2 and centered with a black row above and below
+        let window = ImageSize {
+            width: 4,
+            height: 4,
+        };
+        let mut buffer = vec![7u32; window.width * window.height];
+        blit_frame(&frame, &mut buffer, window);
+        let (red, other) = (0x00ff0000, 0x00010203);
+        assert_eq!(
+            buffer,
+            [
+                [0, 0, 0, 0],
+                [red, red, other, other],
+                [red, red, other, other],
+                [0, 0, 0, 0],
+            ]
+            .concat()
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,227 @@
+use std::error::Error;
+use std::num::NonZeroU32;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+use kornia_image::{Image, ImageSize};
+use softbuffer::{Context, Surface};
+use winit::{
+    application::ApplicationHandler,
+    dpi::PhysicalSize,
+    event::{ElementState, WindowEvent},
+    event_loop::{ActiveEventLoop, EventLoop, OwnedDisplayHandle},
+    keyboard::{Key as WinitKey, NamedKey},
+    platform::pump_events::{EventLoopExtPumpEvents, PumpStatus},
+    window::{Window, WindowId},
+};
+
+use super::{blit_frame, Key};
+use crate::error::IoError;
+
+// the interval to check the timeout while waiting for the events
+const POLL_INTERVAL: Duration = Duration::from_millis(16);
+
+/// The event loop and the windows of the viewer.
+pub(crate) struct Viewer {
+    event_loop: EventLoop<()>,
+    app: ViewerApp,
+}
+
+#[derive(Default)]
+struct ViewerApp {
+    // the frames shown since the last processing of the events
+    pending: Vec<(String, Image<u8, 3>)>,
+    windows: Vec<ViewerWindow>,
+    context: Option<Context<OwnedDisplayHandle>>,
+    key: Option<Key>,
+    error: Option<String>,
+}
+
+// a window with the software surface of the displayed frame
+struct ViewerWindow {
+    name: String,
+    frame: Image<u8, 3>,
+    surface: Surface<OwnedDisplayHandle, Rc<Window>>,
+    window: Rc<Window>,
+}
+
+fn viewer_error(e: impl std::fmt::Display) -> IoError {
+    IoError::ViewerError(e.to_string())
+}
+
+impl Viewer {
+    pub(crate) fn new() -> Result<Self, IoError> {
+        let event_loop = EventLoop::new().map_err(viewer_error)?;
+        Ok(Self {
+            event_loop,
+            app: ViewerApp::default(),
+        })
+    }
+
+    pub(crate) fn push_frame(&mut self, window_name: &str, frame: Image<u8, 3>) {
+        self.app.pending.retain(|(name, _)| name != window_name);
+        self.app.pending.push((window_name.to_string(), frame));
+    }
+
+    pub(crate) fn close_windows(&mut self) {
+        self.app.pending.clear();
+        self.app.windows.clear();
+        // process the events to close the native windows
+        let _ = self
+            .event_loop
+            .pump_app_events(Some(Duration::ZERO), &mut self.app);
+    }
+
+    pub(crate) fn wait_key(&mut self, timeout: Option<Duration>) -> Result<Option<Key>, IoError> {
+        let deadline = timeout.map(|timeout| Instant::now() + timeout);
+        self.app.key = None;
+
+        loop {
+            let remaining =
+                deadline.map(|deadline| deadline.saturating_duration_since(Instant::now()));
+            let wait = remaining.map_or(POLL_INTERVAL, |remaining| remaining.min(POLL_INTERVAL));
+            let status = self.event_loop.pump_app_events(Some(wait), &mut self.app);
+
+            if let Some(error) = self.app.error.take() {
+                return Err(IoError::ViewerError(error));
+            }
+            if let Some(key) = self.app.key.take() {
+                return Ok(Some(key));
+            }
+            if matches!(status, PumpStatus::Exit(_)) || self.app.windows.is_empty() {
+                return Ok(None);
+            }
+            if remaining.is_some_and(|remaining| remaining.is_zero()) {
+                return Ok(None);
+            }
+        }
+    }
+}
+
+impl ViewerApp {
+    fn create_window(
+        &mut self,
+        event_loop: &ActiveEventLoop,
+        name: &str,
+        frame: Image<u8, 3>,
+    ) -> Result<ViewerWindow, Box<dyn Error>> {
+        let attributes = Window::default_attributes()
+            .with_title(name)
+            .with_inner_size(PhysicalSize::new(
+                frame.width() as u32,
+                frame.height() as u32,
+            ));
+
+        let window = Rc::new(event_loop.create_window(attributes)?);
+
+        // the context of the display is created with the first window
+        let context = match self.context.take() {
+            Some(context) => context,
+            None => Context::new(event_loop.owned_display_handle())?,
+        };
+        let surface = Surface::new(self.context.insert(context), window.clone())?;
+
+        Ok(ViewerWindow {
+            name: name.to_string(),
+            frame,
+            surface,
+            window,
+        })
+    }
+}
+
+impl ViewerWindow {
+    fn render(&mut self) -> Result<(), Box<dyn Error>> {
+        let window_size = self.window.inner_size();
+        let (Some(width), Some(height)) = (
+            NonZeroU32::new(window_size.width),
+            NonZeroU32::new(window_size.height),
+        ) else {
+            // a minimized window has nothing to draw
+            return Ok(());
+        };
+        self.surface.resize(width, height)?;
+
+        let mut buffer = self.surface.buffer_mut()?;
+        blit_frame(
+            &self.frame,
+            &mut buffer,
+            ImageSize {
+                width: window_size.width as usize,
+                height: window_size.height as usize,
+            },
+        );
+        buffer.present()?;
+
+        Ok(())
+    }
+}
+
+fn map_key(key: &WinitKey) -> Key {
+    match key {
+        WinitKey::Named(NamedKey::Escape) => Key::Escape,
+        WinitKey::Named(NamedKey::Enter) => Key::Enter,
+        WinitKey::Named(NamedKey::Space) => Key::Char(' '),
+        WinitKey::Named(NamedKey::ArrowLeft) => Key::Left,
+        WinitKey::Named(NamedKey::ArrowRight) => Key::Right,
+        WinitKey::Named(NamedKey::ArrowUp) => Key::Up,
+        WinitKey::Named(NamedKey::ArrowDown) => Key::Down,
+        WinitKey::Character(text) => text.chars().next().map_or(Key::Other, Key::Char),
+        _ => Key::Other,
+    }
+}
+
+impl ApplicationHandler for ViewerApp {
+    fn resumed(&mut self, _event_loop: &ActiveEventLoop) {}
+
+    fn window_event(
+        &mut self,
+        _event_loop: &ActiveEventLoop,
+        window_id: WindowId,
+        event: WindowEvent,
+    ) {
+        let Some(index) = self.windows.iter().position(|w| w.window.id() == window_id) else {
+            return;
+        };
+
+        match event {
+            WindowEvent::CloseRequested => {
+                self.windows.remove(index);
+            }
+            WindowEvent::Resized(_) => {
+                self.windows[index].window.request_redraw();
+            }
+            WindowEvent::RedrawRequested => {
+                if let Err(e) = self.windows[index].render() {
+                    self.error = Some(e.to_string());
+                }
+            }
+            WindowEvent::KeyboardInput { event, .. } if event.state == ElementState::Pressed => {
+                self.key = Some(map_key(&event.logical_key));
+            }
+            _ => {}
+        }
+    }
+
+    fn about_to_wait(&mut self, event_loop: &ActiveEventLoop) {
+        // display the new frames, creating the windows if needed
+        for (name, frame) in std::mem::take(&mut self.pending) {
+            let result = match self.windows.iter_mut().find(|w| w.name == name) {
+                Some(window) => {
+                    window.frame = frame;
+                    window.render()
+                }
+                None => self
+                    .create_window(event_loop, &name, frame)
+                    .and_then(|mut window| {
+                        window.render()?;
+                        self.windows.push(window);
+                        Ok(())
+                    }),
+            };
+            if let Err(e) = result {
+                self.error = Some(e.to_string());
+            }
+        }
+    }
+}
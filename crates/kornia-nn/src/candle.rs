@@ -0,0 +1,422 @@
+use candle_core::{DType, Device, Tensor, WithDType};
+use kornia_image::{Image, ImageError, ImageSize};
+use kornia_imgproc::interpolation::InterpolationMode;
+use kornia_tensor::{CpuAllocator, Tensor as KorniaTensor};
+
+use crate::error::NnError;
+
+/// Copy an image to a candle tensor with shape `[height, width, channels]`.
+///
+/// # Arguments
+///
+/// * `image` - The input image.
+/// * `device` - The device of the tensor, e.g. `Device::Cpu` or a CUDA device.
+///
+/// # Returns
+///
+/// The tensor with the pixels of the image.
+pub fn image_to_candle<T: WithDType, const C: usize>(
+    image: &Image<T, C>,
+    device: &Device,
+) -> Result<Tensor, NnError> {
+    let shape = (image.rows(), image.cols(), C);
+    Ok(Tensor::from_slice(image.as_slice(), shape, device)?)
+}
+
+/// Move an image to a candle tensor with shape `[height, width, channels]`.
+///
+/// On the CPU the buffer of the image is reused by the tensor without copies.
+///
+/// # Arguments
+///
+/// * `image` - The input image.
+/// * `device` - The device of the tensor.
+///
+/// # Returns
+///
+/// The tensor owning the pixels of the image.
+pub fn image_into_candle<T: WithDType, const C: usize>(
+    image: Image<T, C>,
+    device: &Device,
+) -> Result<Tensor, NnError> {
+    let shape = (image.rows(), image.cols(), C);
+    Ok(Tensor::from_vec(image.0.into_vec(), shape, device)?)
+}
+
+/// Copy a candle tensor to an image.
+///
+/// The tensor is moved to the CPU and cast to the pixel type if needed.
+///
+/// # Arguments
+///
+/// * `tensor` - The tensor with shape `[height, width, channels]`, or `[height, width]`
+///   for a single channel.
+///
+/// # Returns
+///
+/// The image with the values of the tensor.
+pub fn candle_to_image<T: WithDType, const C: usize>(
+    tensor: &Tensor,
+) -> Result<Image<T, C>, NnError> {
+    let (rows, cols) = match *tensor.dims() {
+        [rows, cols, channels] if channels == C => (rows, cols),
+        [rows, cols] if C == 1 => (rows, cols),
+        _ => {
+            return Err(NnError::InvalidTensorShape(
+                tensor.dims().to_vec(),
+                format!("[height, width, {C}]"),
+            ))
+        }
+    };
+
+    let data = tensor
+        .to_dtype(T::DTYPE)?
+        .flatten_all()?
+        .to_device(&Device::Cpu)?
+        .to_vec1::<T>()?;
+
+    Ok(Image::new([cols, rows].into(), data)?)
+}
+
+/// Move a tensor to a candle tensor with the same shape.
+///
+/// On the CPU the buffer of the tensor is reused without copies.
+///
+/// # Arguments
+///
+/// * `tensor` - The input tensor, e.g. from [`crate::preprocess::image_to_tensor`].
+/// * `device` - The device of the candle tensor.
+///
+/// # Returns
+///
+/// The candle tensor owning the values of the tensor.
+pub fn tensor_into_candle<T: WithDType, const N: usize>(
+    tensor: KorniaTensor<T, N, CpuAllocator>,
+    device: &Device,
+) -> Result<Tensor, NnError> {
+    let shape = tensor.shape.to_vec();
+    Ok(Tensor::from_vec(tensor.into_vec(), shape, device)?)
+}
+
+/// Copy a candle tensor to a tensor with a static number of dimensions.
+///
+/// # Arguments
+///
+/// * `tensor` - The candle tensor with `N` dimensions.
+///
+/// # Returns
+///
+/// The tensor with the values of the candle tensor.
+pub fn candle_to_tensor<T: WithDType, const N: usize>(
+    tensor: &Tensor,
+) -> Result<KorniaTensor<T, N, CpuAllocator>, NnError> {
+    let shape: [usize; N] = tensor
+        .dims()
+        .try_into()
+        .map_err(|_| NnError::InvalidTensorShape(tensor.dims().to_vec(), format!("{N} dims")))?;
+
+    let data = tensor
+        .to_dtype(T::DTYPE)?
+        .flatten_all()?
+        .to_device(&Device::Cpu)?
+        .to_vec1::<T>()?;
+
+    Ok(KorniaTensor::from_shape_vec(shape, data, CpuAllocator)?)
+}
+
+// split the dimensions into the leading ones and the spatial ones
+fn spatial_dims(tensor: &Tensor) -> Result<(&[usize], usize, usize), NnError> {
+    match tensor.dims() {
+        [lead @ .., rows, cols] if *rows > 0 && *cols > 0 => Ok((lead, *rows, *cols)),
+        dims => Err(NnError::InvalidTensorShape(
+            dims.to_vec(),
+            "[..., height, width]".to_string(),
+        )),
+    }
+}
+
+// sample the spatial dimensions of a tensor at the coordinates of the flattened maps
+fn remap(
+    src: &Tensor,
+    map_x: &Tensor,
+    map_y: &Tensor,
+    new_size: ImageSize,
+    interpolation: InterpolationMode,
+) -> Result<Tensor, NnError> {
+    let (lead, rows, cols) = spatial_dims(src)?;
+    let flat = src
+        .to_dtype(DType::F32)?
+        .reshape((lead.iter().product::<usize>(), rows * cols))?;
+    let (max_x, max_y) = ((cols - 1) as f32, (rows - 1) as f32);
+
+    // gather the pixels at integer coordinates inside the tensor
+    let gather = |x: &Tensor, y: &Tensor| -> candle_core::Result<Tensor> {
+        let x = x.clamp(0f32, max_x)?;
+        let y = y.clamp(0f32, max_y)?;
+        let idx = (y.affine(cols as f64, 0.0)? + x)?.to_dtype(DType::U32)?;
+        flat.index_select(&idx, 1)
+    };
+
+    let dst = match interpolation {
+        InterpolationMode::Nearest => gather(&map_x.round()?, &map_y.round()?)?,
+        InterpolationMode::Bilinear => {
+            let (x0, y0) = (map_x.floor()?, map_y.floor()?);
+            let (x1, y1) = (x0.affine(1.0, 1.0)?, y0.affine(1.0, 1.0)?);
+            let (fx, fy) = ((map_x - &x0)?, (map_y - &y0)?);
+            let (gx, gy) = (fx.affine(-1.0, 1.0)?, fy.affine(-1.0, 1.0)?);
+
+            let v00 = gather(&x0, &y0)?.broadcast_mul(&(&gx * &gy)?)?;
+            let v01 = gather(&x1, &y0)?.broadcast_mul(&(&fx * &gy)?)?;
+            let v10 = gather(&x0, &y1)?.broadcast_mul(&(&gx * &fy)?)?;
+            let v11 = gather(&x1, &y1)?.broadcast_mul(&(&fx * &fy)?)?;
+            (((v00 + v01)? + v10)? + v11)?
+        }
+    };
+
+    let mut shape = lead.to_vec();
+    shape.extend([new_size.height, new_size.width]);
+    Ok(dst.reshape(shape)?)
+}
+
+// the pixel coordinates of a grid flattened in row-major order
+fn meshgrid(size: ImageSize, device: &Device) -> Result<(Tensor, Tensor), NnError> {
+    let (rows, cols) = (size.height, size.width);
+    let xs = Tensor::arange(0u32, cols as u32, device)?
+        .to_dtype(DType::F32)?
+        .unsqueeze(0)?
+        .broadcast_as((rows, cols))?
+        .flatten_all()?;
+    let ys = Tensor::arange(0u32, rows as u32, device)?
+        .to_dtype(DType::F32)?
+        .unsqueeze(1)?
+        .broadcast_as((rows, cols))?
+        .flatten_all()?;
+    Ok((xs, ys))
+}
+
+/// Normalize the channels of a tensor with the mean and standard deviation.
+///
+/// # Arguments
+///
+/// * `tensor` - The tensor with shape `[..., channels, height, width]`.
+/// * `mean` - The mean of each channel.
+/// * `std` - The standard deviation of each channel.
+///
+/// # Returns
+///
+/// The tensor `(tensor - mean) / std` on the device of the input.
+pub fn normalize(tensor: &Tensor, mean: &[f32], std: &[f32]) -> Result<Tensor, NnError> {
+    let dims = tensor.dims();
+    let channels = mean.len();
+    if dims.len() < 3 || dims[dims.len() - 3] != channels || std.len() != channels {
+        return Err(NnError::InvalidTensorShape(
+            dims.to_vec(),
+            format!("[..., {channels}, height, width]"),
+        ));
+    }
+
+    let mean = Tensor::from_slice(mean, (channels, 1, 1), tensor.device())?;
+    let std = Tensor::from_slice(std, (channels, 1, 1), tensor.device())?;
+    let dst = tensor
+        .to_dtype(DType::F32)?
+        .broadcast_sub(&mean)?
+        .broadcast_div(&std)?;
+
+    Ok(dst)
+}
+
+/// Resize the spatial dimensions of a tensor on its device.
+///
+/// The sampling grid matches [`kornia_imgproc::resize::resize_native`]. The values are
+/// interpolated in single precision and cast back to the type of the input.
+///
+/// # Arguments
+///
+/// * `tensor` - The tensor with shape `[..., height, width]`.
+/// * `new_size` - The new spatial size.
+/// * `interpolation` - The interpolation mode.
+///
+/// # Returns
+///
+/// The tensor with shape `[..., new_height, new_width]`.
+pub fn resize(
+    tensor: &Tensor,
+    new_size: ImageSize,
+    interpolation: InterpolationMode,
+) -> Result<Tensor, NnError> {
+    let (_, rows, cols) = spatial_dims(tensor)?;
+    if (cols, rows) == (new_size.width, new_size.height) {
+        return Ok(tensor.clone());
+    }
+
+    let step = |src: usize, dst: usize| {
+        if dst > 1 {
+            (src - 1) as f64 / (dst - 1) as f64
+        } else {
+            0.0
+        }
+    };
+    let (xs, ys) = meshgrid(new_size, tensor.device())?;
+    let map_x = xs.affine(step(cols, new_size.width), 0.0)?;
+    let map_y = ys.affine(step(rows, new_size.height), 0.0)?;
+
+    let dst = remap(tensor, &map_x, &map_y, new_size, interpolation)?;
+    Ok(dst.to_dtype(tensor.dtype())?)
+}
+
+/// Apply a perspective transformation to the spatial dimensions of a tensor on its device.
+///
+/// The pixels mapped outside of the input are set to zero, as
+/// [`kornia_imgproc::warp::warp_perspective`].
+///
+/// # Arguments
+///
+/// * `tensor` - The tensor with shape `[..., height, width]`.
+/// * `m` - The 3x3 perspective transformation matrix src -> dst in row-major order.
+/// * `new_size` - The spatial size of the output.
+/// * `interpolation` - The interpolation mode.
+///
+/// # Returns
+///
+/// The warped tensor with shape `[..., new_height, new_width]`.
+pub fn warp_perspective(
+    tensor: &Tensor,
+    m: &[f32; 9],
+    new_size: ImageSize,
+    interpolation: InterpolationMode,
+) -> Result<Tensor, NnError> {
+    let (lead, rows, cols) = spatial_dims(tensor)?;
+    let inv = inverse_matrix(m)?.map(|v| v as f64);
+
+    // map the output pixels to the input
+    let (xs, ys) = meshgrid(new_size, tensor.device())?;
+    let w = (xs.affine(inv[6], inv[8])? + ys.affine(inv[7], 0.0)?)?;
+    let map_x = (xs.affine(inv[0], inv[2])? + ys.affine(inv[1], 0.0)?)?.div(&w)?;
+    let map_y = (xs.affine(inv[3], inv[5])? + ys.affine(inv[4], 0.0)?)?.div(&w)?;
+
+    let inside = (((map_x.ge(0f32)? * map_x.lt(cols as f32)?)? * map_y.ge(0f32)?)?
+        * map_y.lt(rows as f32)?)?;
+
+    let dst = remap(tensor, &map_x, &map_y, new_size, interpolation)?;
+    let mut shape = lead.to_vec();
+    shape.extend([new_size.height, new_size.width]);
+    let inside = inside
+        .reshape((new_size.height, new_size.width))?
+        .broadcast_as(shape.as_slice())?;
+    let dst = inside.where_cond(&dst, &dst.zeros_like()?)?;
+
+    Ok(dst.to_dtype(tensor.dtype())?)
+}
+
+// invert a 3x3 matrix in row-major order
+fn inverse_matrix(m: &[f32; 9]) -> Result<[f32; 9], ImageError> {
+    let det = m[0] * (m[4] * m[8] - m[5] * m[7]) - m[1] * (m[3] * m[8] - m[5] * m[6])
+        + m[2] * (m[3] * m[7] - m[4] * m[6]);
+    if det == 0.0 {
+        return Err(ImageError::CannotComputeDeterminant);
+    }
+
+    let adj = [
+        m[4] * m[8] - m[5] * m[7],
+        m[2] * m[7] - m[1] * m[8],
+        m[1] * m[5] - m[2] * m[4],
+        m[5] * m[6] - m[3] * m[8],
+        m[0] * m[8] - m[2] * m[6],
+        m[2] * m[3] - m[0] * m[5],
+        m[3] * m[7] - m[4] * m[6],
+        m[1] * m[6] - m[0] * m[7],
+        m[0] * m[4] - m[1] * m[3],
+    ];
+    Ok(adj.map(|v| v / det))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+    use kornia_imgproc::resize::resize_native;
+
+    #[test]
+    fn test_image_candle_roundtrip() -> Result<(), NnError> {
+        let image = Image::<u8, 3>::new([2, 1].into(), vec![1, 2, 3, 4, 5, 6])?;
+
+        let tensor = image_to_candle(&image, &Device::Cpu)?;
+        assert_eq!(tensor.dims(), &[1, 2, 3]);
+
+        let tensor = image_into_candle(image, &Device::Cpu)?;
+        let image = candle_to_image::<f32, 3>(&tensor)?;
+        assert_eq!(image.as_slice(), &[1.0, 2.0, 3.0, 4.0, 5.0, 6.0]);
+        assert!(candle_to_image::<u8, 1>(&tensor).is_err());
+
+        let chw = candle_to_tensor::<u8, 3>(&tensor.permute((2, 0, 1))?)?;
+        assert_eq!(chw.shape, [3, 1, 2]);
+        assert_eq!(chw.as_slice(), &[1, 4, 2, 5, 3, 6]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_normalize() -> Result<(), NnError> {
+        let tensor = Tensor::from_vec(vec![1.0f32, 3.0, 2.0, 6.0], (2, 1, 2), &Device::Cpu)?;
+        let dst = normalize(&tensor, &[1.0, 2.0], &[2.0, 4.0])?;
+        assert_eq!(dst.flatten_all()?.to_vec1::<f32>()?, &[0.0, 1.0, 0.0, 1.0]);
+        assert!(normalize(&tensor, &[0.0; 3], &[1.0; 3]).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_resize() -> Result<(), NnError> {
+        let size = ImageSize {
+            width: 5,
+            height: 4,
+        };
+        let image = Image::<f32, 1>::new(size, (0..20).map(|v| (v * v) as f32).collect())?;
+        let new_size = ImageSize {
+            width: 3,
+            height: 7,
+        };
+
+        for interpolation in [InterpolationMode::Bilinear, InterpolationMode::Nearest] {
+            let mut expected = Image::<f32, 1>::from_size_val(new_size, 0.0)?;
+            resize_native(&image, &mut expected, interpolation)?;
+
+            let tensor = image_to_candle(&image, &Device::Cpu)?.permute((2, 0, 1))?;
+            let dst = resize(&tensor, new_size, interpolation)?;
+            assert_eq!(dst.dims(), &[1, 7, 3]);
+            for (a, b) in dst
+                .flatten_all()?
+                .to_vec1::<f32>()?
+                .iter()
+                .zip(expected.as_slice())
+            {
+                assert_relative_eq!(a, b, epsilon = 1e-3);
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_warp_perspective() -> Result<(), NnError> {
+        // shift the image one pixel to the right on a batch of two images
+        let tensor = Tensor::arange(0u8, 12, &Device::Cpu)?.reshape((2, 1, 2, 3))?;
+        let m = [1.0, 0.0, 1.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0];
+        let size = ImageSize {
+            width: 3,
+            height: 2,
+        };
+
+        let dst = warp_perspective(&tensor, &m, size, InterpolationMode::Bilinear)?;
+        assert_eq!(dst.dtype(), DType::U8);
+        assert_eq!(
+            dst.flatten_all()?.to_vec1::<u8>()?,
+            &[0, 0, 1, 0, 3, 4, 0, 6, 7, 0, 9, 10]
+        );
+
+        let singular = [0.0; 9];
+        assert!(warp_perspective(&tensor, &singular, size, InterpolationMode::Nearest).is_err());
+
+        Ok(())
+    }
+}
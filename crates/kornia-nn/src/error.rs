@@ -0,0 +1,32 @@
+use kornia_image::ImageError;
+use kornia_tensor::TensorError;
+
+/// An error type for the neural network module.
+#[derive(thiserror::Error, Debug)]
+pub enum NnError {
+    /// Error when processing an image.
+    #[error(transparent)]
+    ImageError(#[from] ImageError),
+
+    /// Error when creating a tensor.
+    #[error(transparent)]
+    TensorError(#[from] TensorError),
+
+    /// Error when the batch of images is empty.
+    #[error("The batch of images is empty")]
+    EmptyBatch,
+
+    /// Error when the shape of a tensor is not the expected one.
+    #[error("Invalid tensor shape {0:?}, expected {1}")]
+    InvalidTensorShape(Vec<usize>, String),
+
+    /// Error from candle.
+    #[cfg(feature = "candle")]
+    #[error(transparent)]
+    CandleError(#[from] candle_core::Error),
+
+    /// Error from ONNX Runtime.
+    #[cfg(feature = "onnx")]
+    #[error(transparent)]
+    OrtError(#[from] ort::Error),
+}
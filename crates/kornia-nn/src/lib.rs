@@ -0,0 +1,21 @@
+#![deny(missing_docs)]
+#![doc = env!("CARGO_PKG_DESCRIPTION")]
+
+/// Interoperability with candle tensors and image operations on its devices.
+#[cfg(feature = "candle")]
+pub mod candle;
+
+/// Error types for the neural network module.
+pub mod error;
+
+/// Inference with ONNX Runtime.
+#[cfg(feature = "onnx")]
+pub mod onnx;
+
+/// Mapping of the output tensors of the models to images and detections.
+pub mod postprocess;
+
+/// Conversion of images to normalized input tensors.
+pub mod preprocess;
+
+pub use crate::error::NnError;
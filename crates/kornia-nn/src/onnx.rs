@@ -0,0 +1,275 @@
+use std::path::Path;
+
+use kornia_image::Image;
+use kornia_imgproc::features::{extract_patches, DescriptorExtractor, Keypoint};
+use kornia_tensor::{CpuAllocator, Tensor, Tensor2, Tensor3, Tensor4};
+use ort::{session::Session, value::TensorRef};
+
+use crate::{error::NnError, postprocess::l2_normalize_descriptors};
+
+/// An output tensor of a model with a dynamic number of dimensions.
+#[derive(Debug, Clone)]
+pub struct OnnxOutput {
+    /// The name of the output in the model.
+    pub name: String,
+    /// The shape of the tensor.
+    pub shape: Vec<usize>,
+    /// The values of the tensor in row-major order.
+    pub data: Vec<f32>,
+}
+
+impl OnnxOutput {
+    /// Convert the output to a tensor with a static number of dimensions.
+    ///
+    /// # Returns
+    ///
+    /// The tensor or an error if the output does not have `N` dimensions.
+    pub fn into_tensor<const N: usize>(self) -> Result<Tensor<f32, N, CpuAllocator>, NnError> {
+        let shape: [usize; N] =
+            self.shape.as_slice().try_into().map_err(|_| {
+                NnError::InvalidTensorShape(self.shape.clone(), format!("{N} dims"))
+            })?;
+        Ok(Tensor::from_shape_vec(shape, self.data, CpuAllocator)?)
+    }
+}
+
+/// A model running on ONNX Runtime.
+///
+/// The ONNX Runtime shared library is loaded at runtime from the path in the
+/// `ORT_DYLIB_PATH` environment variable or from the library search path.
+///
+/// # Example
+///
+/// ```no_run
+/// use kornia_image::Image;
+/// use kornia_nn::onnx::OnnxModel;
+/// use kornia_nn::postprocess::{decode_detections, non_max_suppression};
+/// use kornia_nn::preprocess::{image_to_tensor, letterbox, TensorLayout};
+///
+/// let mut model = OnnxModel::from_file("yolov8n.onnx").unwrap();
+///
+/// let image = Image::<u8, 3>::from_size_val([1280, 720].into(), 0).unwrap();
+/// let mut input = Image::<u8, 3>::from_size_val([640, 640].into(), 0).unwrap();
+/// let transform = letterbox(&image, &mut input, [114, 114, 114]).unwrap();
+/// let tensor = image_to_tensor(&input, &[0.0; 3], &[1.0; 3], TensorLayout::Nchw).unwrap();
+///
+/// let outputs = model.run(&tensor).unwrap();
+/// let output = outputs[0].clone().into_tensor::<3>().unwrap();
+/// for detection in non_max_suppression(&decode_detections(&output, 0.25).unwrap()[0], 0.45) {
+///     println!("{:?} {:?}", detection.class_id, transform.map_box(detection.bbox));
+/// }
+/// ```
+pub struct OnnxModel {
+    session: Session,
+}
+
+impl OnnxModel {
+    /// Load a model from an ONNX file.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path to the ONNX file.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, NnError> {
+        let session = Session::builder()?.commit_from_file(path)?;
+        Ok(Self { session })
+    }
+
+    /// Load a model from the bytes of an ONNX file.
+    ///
+    /// # Arguments
+    ///
+    /// * `bytes` - The content of the ONNX file.
+    pub fn from_memory(bytes: &[u8]) -> Result<Self, NnError> {
+        let session = Session::builder()?.commit_from_memory(bytes)?;
+        Ok(Self { session })
+    }
+
+    /// The names of the inputs of the model.
+    pub fn input_names(&self) -> Vec<String> {
+        self.session
+            .inputs()
+            .iter()
+            .map(|input| input.name().to_string())
+            .collect()
+    }
+
+    /// The names of the outputs of the model.
+    pub fn output_names(&self) -> Vec<String> {
+        self.session
+            .outputs()
+            .iter()
+            .map(|output| output.name().to_string())
+            .collect()
+    }
+
+    /// Run the model on an input tensor.
+    ///
+    /// The tensor is passed to the first input of the model without copies.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - The input tensor, e.g. from [`crate::preprocess::image_to_tensor`].
+    ///
+    /// # Returns
+    ///
+    /// The float outputs of the model in the order of the model outputs.
+    pub fn run(&mut self, input: &Tensor4<f32, CpuAllocator>) -> Result<Vec<OnnxOutput>, NnError> {
+        self.run_slice(input.shape, input.as_slice())
+    }
+
+    // run the model on the data of a 4D tensor borrowed from another container
+    fn run_slice(&mut self, shape: [usize; 4], data: &[f32]) -> Result<Vec<OnnxOutput>, NnError> {
+        let input = TensorRef::from_array_view((shape, data))?;
+        let outputs = self.session.run(ort::inputs![input])?;
+
+        outputs
+            .iter()
+            .map(|(name, value)| {
+                let (shape, data) = value.try_extract_tensor::<f32>()?;
+                Ok(OnnxOutput {
+                    name: name.to_string(),
+                    shape: shape.iter().map(|&d| d as usize).collect(),
+                    data: data.to_vec(),
+                })
+            })
+            .collect()
+    }
+}
+
+/// The size of the descriptors of HardNet and SOSNet.
+pub const PATCH_DESCRIPTOR_SIZE: usize = 128;
+
+/// A learned descriptor of image patches, e.g. HardNet or SOSNet exported to ONNX.
+///
+/// The model takes a batch of grayscale patches with shape `[N, 1, P, P]` and returns the
+/// descriptors with shape `[N, 128]`. The patches are usually 32x32 pixels in the range
+/// [0, 1], as extracted by [`kornia_imgproc::features::extract_patches`].
+///
+/// # Example
+///
+/// ```no_run
+/// use kornia_image::Image;
+/// use kornia_imgproc::features::{extract_patches, Keypoint};
+/// use kornia_nn::onnx::PatchDescriptor;
+///
+/// let mut descriptor = PatchDescriptor::from_file("hardnet.onnx", 32).unwrap();
+///
+/// let image = Image::<f32, 1>::from_size_val([640, 480].into(), 0.0).unwrap();
+/// let keypoints = [Keypoint::new([320.0, 240.0], 2.0, 0.0)];
+/// let patches = extract_patches(&image, &keypoints, 32, 6.0).unwrap();
+///
+/// let descriptors = descriptor.describe(&patches).unwrap();
+/// assert_eq!(descriptors.shape, [1, 128]);
+/// ```
+pub struct PatchDescriptor {
+    model: OnnxModel,
+    patch_size: usize,
+    scale_factor: f32,
+}
+
+impl PatchDescriptor {
+    /// Load a patch descriptor from an ONNX file.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path to the ONNX file.
+    /// * `patch_size` - The width and height of the input patches of the model.
+    pub fn from_file(path: impl AsRef<Path>, patch_size: usize) -> Result<Self, NnError> {
+        Ok(Self {
+            model: OnnxModel::from_file(path)?,
+            patch_size,
+            scale_factor: 6.0,
+        })
+    }
+
+    /// Load a patch descriptor from the bytes of an ONNX file.
+    ///
+    /// # Arguments
+    ///
+    /// * `bytes` - The content of the ONNX file.
+    /// * `patch_size` - The width and height of the input patches of the model.
+    pub fn from_memory(bytes: &[u8], patch_size: usize) -> Result<Self, NnError> {
+        Ok(Self {
+            model: OnnxModel::from_memory(bytes)?,
+            patch_size,
+            scale_factor: 6.0,
+        })
+    }
+
+    /// Sets the half width of the patches of [`DescriptorExtractor::extract`] in units of the
+    /// scale of the keypoints, 6 by default.
+    pub fn with_scale_factor(self, scale_factor: f32) -> Self {
+        Self {
+            scale_factor,
+            ..self
+        }
+    }
+
+    /// The width and height of the input patches of the model.
+    pub fn patch_size(&self) -> usize {
+        self.patch_size
+    }
+
+    /// Compute the descriptors of a batch of patches.
+    ///
+    /// # Arguments
+    ///
+    /// * `patches` - The patches with shape `[N, P, P]`.
+    ///
+    /// # Returns
+    ///
+    /// The L2 normalized descriptors with shape `[N, 128]`.
+    pub fn describe(
+        &mut self,
+        patches: &Tensor3<f32, CpuAllocator>,
+    ) -> Result<Tensor2<f32, CpuAllocator>, NnError> {
+        let [num_patches, rows, cols] = patches.shape;
+        if rows != self.patch_size || cols != self.patch_size {
+            return Err(NnError::InvalidTensorShape(
+                patches.shape.to_vec(),
+                format!("[N, {0}, {0}]", self.patch_size),
+            ));
+        }
+        if num_patches == 0 {
+            return Ok(Tensor::zeros([0, PATCH_DESCRIPTOR_SIZE], CpuAllocator));
+        }
+
+        let output = self
+            .model
+            .run_slice([num_patches, 1, rows, cols], patches.as_slice())?
+            .into_iter()
+            .next()
+            .ok_or_else(|| {
+                NnError::InvalidTensorShape(Vec::new(), "a descriptor output".to_string())
+            })?;
+        if output.shape != [num_patches, PATCH_DESCRIPTOR_SIZE] {
+            return Err(NnError::InvalidTensorShape(
+                output.shape,
+                format!("[{num_patches}, {PATCH_DESCRIPTOR_SIZE}]"),
+            ));
+        }
+
+        let mut descriptors = output.into_tensor::<2>()?;
+        l2_normalize_descriptors(&mut descriptors);
+        Ok(descriptors)
+    }
+}
+
+impl DescriptorExtractor for PatchDescriptor {
+    type Descriptor = [f32; PATCH_DESCRIPTOR_SIZE];
+    type Error = NnError;
+
+    fn extract(
+        &mut self,
+        src: &Image<f32, 1>,
+        keypoints: &[Keypoint],
+    ) -> Result<Vec<Self::Descriptor>, NnError> {
+        let patches = extract_patches(src, keypoints, self.patch_size, self.scale_factor)?;
+        let descriptors = self.describe(&patches)?;
+        Ok(descriptors
+            .as_slice()
+            .chunks_exact(PATCH_DESCRIPTOR_SIZE)
+            .map(|d| std::array::from_fn(|i| d[i]))
+            .collect())
+    }
+}
@@ -0,0 +1,308 @@
+use kornia_image::Image;
+use kornia_tensor::{CpuAllocator, Tensor2, Tensor3, Tensor4};
+
+use crate::{error::NnError, preprocess::TensorLayout};
+
+/// An object detected by a model.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Detection {
+    /// The bounding box as `[x_min, y_min, x_max, y_max]` in pixels.
+    pub bbox: [f32; 4],
+    /// The confidence score of the detection.
+    pub score: f32,
+    /// The index of the class of the object.
+    pub class_id: usize,
+}
+
+impl Detection {
+    /// Compute the intersection over union with another detection.
+    pub fn iou(&self, other: &Detection) -> f32 {
+        let [ax0, ay0, ax1, ay1] = self.bbox;
+        let [bx0, by0, bx1, by1] = other.bbox;
+        let w = (ax1.min(bx1) - ax0.max(bx0)).max(0.0);
+        let h = (ay1.min(by1) - ay0.max(by0)).max(0.0);
+        let intersection = w * h;
+        let union = (ax1 - ax0) * (ay1 - ay0) + (bx1 - bx0) * (by1 - by0) - intersection;
+        if union > 0.0 {
+            intersection / union
+        } else {
+            0.0
+        }
+    }
+}
+
+/// Decode the raw output of a single-stage detector into detections.
+///
+/// The output has the shape `[batch, 4 + num_classes, num_anchors]`, where each anchor holds
+/// the box as `[cx, cy, w, h]` followed by the score of each class, as the YOLOv8 family of
+/// models. The class with the highest score is kept for each anchor.
+///
+/// # Arguments
+///
+/// * `output` - The output tensor of the model.
+/// * `score_threshold` - The minimum score of a detection.
+///
+/// # Returns
+///
+/// The detections of each image of the batch in the coordinates of the input tensor.
+pub fn decode_detections(
+    output: &Tensor3<f32, CpuAllocator>,
+    score_threshold: f32,
+) -> Result<Vec<Vec<Detection>>, NnError> {
+    let [batch, rows, num_anchors] = output.shape;
+    if rows <= 4 {
+        return Err(NnError::InvalidTensorShape(
+            output.shape.to_vec(),
+            "[batch, 4 + num_classes, num_anchors]".to_string(),
+        ));
+    }
+
+    let detections = output
+        .as_slice()
+        .chunks_exact(rows * num_anchors)
+        .take(batch)
+        .map(|data| {
+            let at = |row: usize, anchor: usize| data[row * num_anchors + anchor];
+            (0..num_anchors)
+                .filter_map(|i| {
+                    let (class_id, score) = (4..rows)
+                        .map(|row| (row - 4, at(row, i)))
+                        .max_by(|a, b| a.1.total_cmp(&b.1))?;
+                    if score < score_threshold {
+                        return None;
+                    }
+                    let (cx, cy, w, h) = (at(0, i), at(1, i), at(2, i), at(3, i));
+                    Some(Detection {
+                        bbox: [cx - w / 2.0, cy - h / 2.0, cx + w / 2.0, cy + h / 2.0],
+                        score,
+                        class_id,
+                    })
+                })
+                .collect()
+        })
+        .collect();
+
+    Ok(detections)
+}
+
+/// Suppress the overlapping detections of the same class keeping the highest scores.
+///
+/// # Arguments
+///
+/// * `detections` - The detections to filter.
+/// * `iou_threshold` - The maximum intersection over union between two kept detections.
+///
+/// # Returns
+///
+/// The kept detections sorted by decreasing score.
+pub fn non_max_suppression(detections: &[Detection], iou_threshold: f32) -> Vec<Detection> {
+    let mut sorted = detections.to_vec();
+    sorted.sort_by(|a, b| b.score.total_cmp(&a.score));
+
+    let mut kept: Vec<Detection> = Vec::new();
+    for detection in sorted {
+        let suppressed = kept
+            .iter()
+            .any(|k| k.class_id == detection.class_id && k.iou(&detection) > iou_threshold);
+        if !suppressed {
+            kept.push(detection);
+        }
+    }
+
+    kept
+}
+
+/// Convert the logits of a segmentation model to a class map.
+///
+/// # Arguments
+///
+/// * `logits` - The output tensor with the shape `[batch, num_classes, height, width]`.
+/// * `batch` - The index of the image in the batch.
+///
+/// # Returns
+///
+/// The image with the index of the class with the highest logit per pixel.
+pub fn segmentation_argmax(
+    logits: &Tensor4<f32, CpuAllocator>,
+    batch: usize,
+) -> Result<Image<u8, 1>, NnError> {
+    let [num_batch, num_classes, rows, cols] = logits.shape;
+    if batch >= num_batch || num_classes == 0 || num_classes > 256 {
+        return Err(NnError::InvalidTensorShape(
+            logits.shape.to_vec(),
+            format!("[> {batch}, 1..=256, height, width]"),
+        ));
+    }
+
+    let plane = rows * cols;
+    let data = &logits.as_slice()[batch * num_classes * plane..(batch + 1) * num_classes * plane];
+    let classes = (0..plane)
+        .map(|i| {
+            (0..num_classes)
+                .max_by(|&a, &b| data[a * plane + i].total_cmp(&data[b * plane + i]))
+                .unwrap_or(0) as u8
+        })
+        .collect();
+
+    Ok(Image::new([cols, rows].into(), classes)?)
+}
+
+/// Convert an image of a batch tensor back to an image, e.g. a predicted depth map.
+///
+/// # Arguments
+///
+/// * `tensor` - The tensor with `C` channels.
+/// * `batch` - The index of the image in the batch.
+/// * `layout` - The memory layout of the tensor.
+///
+/// # Returns
+///
+/// The image with the values of the tensor.
+pub fn tensor_to_image<const C: usize>(
+    tensor: &Tensor4<f32, CpuAllocator>,
+    batch: usize,
+    layout: TensorLayout,
+) -> Result<Image<f32, C>, NnError> {
+    let [num_batch, d1, d2, d3] = tensor.shape;
+    let (channels, rows, cols) = match layout {
+        TensorLayout::Nchw => (d1, d2, d3),
+        TensorLayout::Nhwc => (d3, d1, d2),
+    };
+    if batch >= num_batch || channels != C {
+        return Err(NnError::InvalidTensorShape(
+            tensor.shape.to_vec(),
+            format!("{C} channels and more than {batch} images"),
+        ));
+    }
+
+    let plane = rows * cols;
+    let data = &tensor.as_slice()[batch * C * plane..(batch + 1) * C * plane];
+    let pixels = match layout {
+        TensorLayout::Nhwc => data.to_vec(),
+        TensorLayout::Nchw => (0..plane * C)
+            .map(|i| data[(i % C) * plane + i / C])
+            .collect(),
+    };
+
+    Ok(Image::new([cols, rows].into(), pixels)?)
+}
+
+/// Normalize each descriptor of a batch to a unit L2 norm in place.
+///
+/// The descriptors with a zero norm are left unchanged.
+///
+/// # Arguments
+///
+/// * `descriptors` - The descriptors with shape `[N, D]`.
+pub fn l2_normalize_descriptors(descriptors: &mut Tensor2<f32, CpuAllocator>) {
+    let dim = descriptors.shape[1];
+    if dim == 0 {
+        return;
+    }
+
+    for descriptor in descriptors.as_slice_mut().chunks_exact_mut(dim) {
+        let norm = descriptor.iter().map(|v| v * v).sum::<f32>().sqrt();
+        if norm > f32::EPSILON {
+            descriptor.iter_mut().for_each(|v| *v /= norm);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use kornia_tensor::Tensor;
+
+    #[test]
+    fn test_decode_detections() -> Result<(), NnError> {
+        // two anchors with two classes
+        #[rustfmt::skip]
+        let data = vec![
+            10.0, 50.0, // cx
+            20.0, 50.0, // cy
+            4.0, 10.0,  // w
+            8.0, 10.0,  // h
+            0.9, 0.1,   // class 0
+            0.2, 0.3,   // class 1
+        ];
+        let output = Tensor::from_shape_vec([1, 6, 2], data, CpuAllocator)?;
+
+        let detections = decode_detections(&output, 0.25)?;
+        assert_eq!(
+            detections,
+            vec![vec![
+                Detection {
+                    bbox: [8.0, 16.0, 12.0, 24.0],
+                    score: 0.9,
+                    class_id: 0,
+                },
+                Detection {
+                    bbox: [45.0, 45.0, 55.0, 55.0],
+                    score: 0.3,
+                    class_id: 1,
+                }
+            ]]
+        );
+
+        let output = Tensor::from_shape_vec([1, 4, 1], vec![0.0; 4], CpuAllocator)?;
+        assert!(decode_detections(&output, 0.25).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_non_max_suppression() {
+        let detection = |x: f32, score: f32, class_id: usize| Detection {
+            bbox: [x, 0.0, x + 10.0, 10.0],
+            score,
+            class_id,
+        };
+        let detections = [
+            detection(0.0, 0.5, 0),
+            detection(1.0, 0.9, 0),
+            detection(1.0, 0.8, 1),
+            detection(20.0, 0.7, 0),
+        ];
+
+        let kept = non_max_suppression(&detections, 0.5);
+        assert_eq!(kept, vec![detections[1], detections[2], detections[3]]);
+    }
+
+    #[test]
+    fn test_segmentation_argmax() -> Result<(), NnError> {
+        // two classes on a 1x3 image
+        let logits = Tensor::from_shape_vec(
+            [1, 2, 1, 3],
+            vec![1.0, -1.0, 0.0, 0.0, 2.0, 0.5],
+            CpuAllocator,
+        )?;
+        let classes = segmentation_argmax(&logits, 0)?;
+        assert_eq!(classes.as_slice(), &[0, 1, 1]);
+        assert!(segmentation_argmax(&logits, 1).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_tensor_to_image() -> Result<(), NnError> {
+        let tensor = Tensor::from_shape_vec([1, 2, 1, 2], vec![1.0, 2.0, 3.0, 4.0], CpuAllocator)?;
+        let image = tensor_to_image::<2>(&tensor, 0, TensorLayout::Nchw)?;
+        assert_eq!(image.as_slice(), &[1.0, 3.0, 2.0, 4.0]);
+
+        let image = tensor_to_image::<2>(&tensor, 0, TensorLayout::Nhwc)?;
+        assert_eq!(image.as_slice(), &[1.0, 2.0, 3.0, 4.0]);
+        assert!(tensor_to_image::<3>(&tensor, 0, TensorLayout::Nchw).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_l2_normalize_descriptors() -> Result<(), NnError> {
+        let mut descriptors =
+            Tensor::from_shape_vec([3, 2], vec![3.0, 4.0, 0.0, 0.0, -2.0, 0.0], CpuAllocator)?;
+        l2_normalize_descriptors(&mut descriptors);
+        assert_eq!(descriptors.as_slice(), &[0.6, 0.8, 0.0, 0.0, -1.0, 0.0]);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,346 @@
+use kornia_image::{Image, ImageError, ImageSize};
+use kornia_imgproc::{interpolation::InterpolationMode, resize::resize_fast};
+use kornia_tensor::{CpuAllocator, Tensor, Tensor4};
+use rayon::prelude::*;
+
+use crate::error::NnError;
+
+/// The memory layout of a batch of images in a tensor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TensorLayout {
+    /// Batch, channels, height and width, as expected by most of the models.
+    #[default]
+    Nchw,
+    /// Batch, height, width and channels, as the images are stored.
+    Nhwc,
+}
+
+/// The transformation applied to an image by [`letterbox`].
+///
+/// It maps the coordinates predicted by a model on the letterboxed image back to the
+/// original image.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Letterbox {
+    /// The scale applied to the original image.
+    pub scale: f32,
+    /// The horizontal padding on the left of the resized image.
+    pub pad_x: usize,
+    /// The vertical padding on the top of the resized image.
+    pub pad_y: usize,
+    /// The size of the original image.
+    pub original_size: ImageSize,
+    /// The size of the letterboxed image.
+    pub input_size: ImageSize,
+}
+
+impl Letterbox {
+    /// Map a point of the letterboxed image to the original image.
+    ///
+    /// # Arguments
+    ///
+    /// * `point` - The x and y coordinates in the letterboxed image.
+    ///
+    /// # Returns
+    ///
+    /// The x and y coordinates in the original image.
+    pub fn map_point(&self, point: [f32; 2]) -> [f32; 2] {
+        [
+            (point[0] - self.pad_x as f32) / self.scale,
+            (point[1] - self.pad_y as f32) / self.scale,
+        ]
+    }
+
+    /// Map a bounding box of the letterboxed image to the original image.
+    ///
+    /// # Arguments
+    ///
+    /// * `bbox` - The box as `[x_min, y_min, x_max, y_max]` in the letterboxed image.
+    ///
+    /// # Returns
+    ///
+    /// The box in the original image, clipped to its boundaries.
+    pub fn map_box(&self, bbox: [f32; 4]) -> [f32; 4] {
+        let (w, h) = (
+            self.original_size.width as f32,
+            self.original_size.height as f32,
+        );
+        let [x_min, y_min] = self.map_point([bbox[0], bbox[1]]);
+        let [x_max, y_max] = self.map_point([bbox[2], bbox[3]]);
+        [
+            x_min.clamp(0.0, w),
+            y_min.clamp(0.0, h),
+            x_max.clamp(0.0, w),
+            y_max.clamp(0.0, h),
+        ]
+    }
+
+    /// Map a dense prediction of the letterboxed image, e.g. a segmentation mask, to the
+    /// original image.
+    ///
+    /// The prediction can have a lower resolution than the letterboxed image, as long as
+    /// it has the same aspect ratio. The pixels are sampled with the nearest neighbor.
+    ///
+    /// # Arguments
+    ///
+    /// * `src` - The prediction covering the letterboxed image.
+    ///
+    /// # Returns
+    ///
+    /// The prediction with the size of the original image.
+    pub fn restore_image<T: Copy + Default + Send + Sync, const C: usize>(
+        &self,
+        src: &Image<T, C>,
+    ) -> Result<Image<T, C>, ImageError> {
+        let mut dst = Image::from_size_val(self.original_size, T::default())?;
+        let (src_cols, src_rows) = (src.cols(), src.rows());
+        if src_cols == 0 || src_rows == 0 {
+            return Ok(dst);
+        }
+
+        // the scale from the letterboxed image to the prediction
+        let sx = src_cols as f32 / self.input_size.width as f32;
+        let sy = src_rows as f32 / self.input_size.height as f32;
+
+        let src_data = src.as_slice();
+        dst.as_slice_mut()
+            .par_chunks_exact_mut(self.original_size.width * C)
+            .enumerate()
+            .for_each(|(v, row)| {
+                for (u, pixel) in row.chunks_exact_mut(C).enumerate() {
+                    let x = (u as f32 + 0.5) * self.scale + self.pad_x as f32;
+                    let y = (v as f32 + 0.5) * self.scale + self.pad_y as f32;
+                    let su = ((x * sx) as usize).min(src_cols - 1);
+                    let sv = ((y * sy) as usize).min(src_rows - 1);
+                    let idx = (sv * src_cols + su) * C;
+                    pixel.copy_from_slice(&src_data[idx..idx + C]);
+                }
+            });
+
+        Ok(dst)
+    }
+}
+
+/// Resize an image to fit the destination keeping the aspect ratio and pad the borders.
+///
+/// The resized image is centered in the destination image and the remaining area is
+/// filled with the padding value, as expected by most of the detection models.
+///
+/// # Arguments
+///
+/// * `src` - The input image.
+/// * `dst` - The output image with the input size of the model.
+/// * `pad_value` - The color of the padding.
+///
+/// # Returns
+///
+/// The transformation to map the predictions back to the input image.
+///
+/// # Example
+///
+/// ```
+/// use kornia_image::Image;
+/// use kornia_nn::preprocess::letterbox;
+///
+/// let image = Image::<u8, 3>::from_size_val([64, 32].into(), 255).unwrap();
+/// let mut input = Image::<u8, 3>::from_size_val([32, 32].into(), 0).unwrap();
+/// let transform = letterbox(&image, &mut input, [114, 114, 114]).unwrap();
+/// assert_eq!(transform.scale, 0.5);
+/// assert_eq!(transform.pad_y, 8);
+/// ```
+pub fn letterbox(
+    src: &Image<u8, 3>,
+    dst: &mut Image<u8, 3>,
+    pad_value: [u8; 3],
+) -> Result<Letterbox, ImageError> {
+    let (src_cols, src_rows) = (src.cols(), src.rows());
+    let (dst_cols, dst_rows) = (dst.cols(), dst.rows());
+
+    let scale = (dst_cols as f32 / src_cols as f32).min(dst_rows as f32 / src_rows as f32);
+    let new_size = ImageSize {
+        width: ((src_cols as f32 * scale).round() as usize).clamp(1, dst_cols),
+        height: ((src_rows as f32 * scale).round() as usize).clamp(1, dst_rows),
+    };
+    let (pad_x, pad_y) = (
+        (dst_cols - new_size.width) / 2,
+        (dst_rows - new_size.height) / 2,
+    );
+
+    let mut resized = Image::from_size_val(new_size, 0u8)?;
+    if new_size == src.size() {
+        resized.as_slice_mut().copy_from_slice(src.as_slice());
+    } else {
+        resize_fast(src, &mut resized, InterpolationMode::Bilinear)?;
+    }
+
+    // fill the padding and copy the resized image to the center
+    dst.as_slice_mut()
+        .par_chunks_exact_mut(dst_cols * 3)
+        .enumerate()
+        .for_each(|(v, row)| {
+            row.chunks_exact_mut(3)
+                .for_each(|pixel| pixel.copy_from_slice(&pad_value));
+            if v < pad_y || v >= pad_y + new_size.height {
+                return;
+            }
+            let src_row = &resized.as_slice()
+                [(v - pad_y) * new_size.width * 3..(v - pad_y + 1) * new_size.width * 3];
+            row[pad_x * 3..(pad_x + new_size.width) * 3].copy_from_slice(src_row);
+        });
+
+    Ok(Letterbox {
+        scale,
+        pad_x,
+        pad_y,
+        original_size: src.size(),
+        input_size: dst.size(),
+    })
+}
+
+/// Convert a batch of images to a normalized input tensor.
+///
+/// The pixels are scaled to the range [0, 1] and normalized per channel as
+/// `(value / 255 - mean) / std`.
+///
+/// # Arguments
+///
+/// * `images` - The images of the batch, all with the same size.
+/// * `mean` - The mean of each channel.
+/// * `std` - The standard deviation of each channel.
+/// * `layout` - The memory layout of the tensor.
+///
+/// # Returns
+///
+/// The tensor with shape `[batch, channels, height, width]` or `[batch, height, width, channels]`.
+pub fn images_to_tensor<const C: usize>(
+    images: &[Image<u8, C>],
+    mean: &[f32; C],
+    std: &[f32; C],
+    layout: TensorLayout,
+) -> Result<Tensor4<f32, CpuAllocator>, NnError> {
+    let size = images.first().ok_or(NnError::EmptyBatch)?.size();
+    if let Some(image) = images.iter().find(|image| image.size() != size) {
+        return Err(ImageError::InvalidImageSize(
+            image.cols(),
+            image.rows(),
+            size.width,
+            size.height,
+        )
+        .into());
+    }
+
+    let (cols, rows) = (size.width, size.height);
+    let shape = match layout {
+        TensorLayout::Nchw => [images.len(), C, rows, cols],
+        TensorLayout::Nhwc => [images.len(), rows, cols, C],
+    };
+    let mut tensor = Tensor::zeros(shape, CpuAllocator);
+
+    // precompute the affine transformation of each channel
+    let scale = std.map(|s| 1.0 / (255.0 * s));
+    let offset: [f32; C] = std::array::from_fn(|c| -mean[c] / std[c]);
+
+    tensor
+        .as_slice_mut()
+        .par_chunks_exact_mut(rows * cols * C)
+        .zip(images.par_iter())
+        .for_each(|(dst, image)| match layout {
+            TensorLayout::Nhwc => {
+                for (d, (s, c)) in dst
+                    .iter_mut()
+                    .zip(image.as_slice().iter().zip((0..C).cycle()))
+                {
+                    *d = *s as f32 * scale[c] + offset[c];
+                }
+            }
+            TensorLayout::Nchw => {
+                for (c, plane) in dst.chunks_exact_mut(rows * cols).enumerate() {
+                    for (d, pixel) in plane.iter_mut().zip(image.as_slice().chunks_exact(C)) {
+                        *d = pixel[c] as f32 * scale[c] + offset[c];
+                    }
+                }
+            }
+        });
+
+    Ok(tensor)
+}
+
+/// Convert an image to a normalized input tensor with a batch of one.
+///
+/// # Arguments
+///
+/// * `image` - The input image.
+/// * `mean` - The mean of each channel.
+/// * `std` - The standard deviation of each channel.
+/// * `layout` - The memory layout of the tensor.
+///
+/// # Returns
+///
+/// The tensor with shape `[1, channels, height, width]` or `[1, height, width, channels]`.
+pub fn image_to_tensor<const C: usize>(
+    image: &Image<u8, C>,
+    mean: &[f32; C],
+    std: &[f32; C],
+    layout: TensorLayout,
+) -> Result<Tensor4<f32, CpuAllocator>, NnError> {
+    images_to_tensor(std::slice::from_ref(image), mean, std, layout)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn test_letterbox() -> Result<(), ImageError> {
+        let image = Image::<u8, 3>::new([4, 2].into(), (0..24).collect())?;
+        let mut input = Image::<u8, 3>::from_size_val([4, 4].into(), 0)?;
+
+        let transform = letterbox(&image, &mut input, [114, 0, 0])?;
+        assert_eq!(transform.scale, 1.0);
+        assert_eq!((transform.pad_x, transform.pad_y), (0, 1));
+
+        // the padding rows and the centered image
+        assert_eq!(&input.as_slice()[..3], &[114, 0, 0]);
+        assert_eq!(&input.as_slice()[12..36], image.as_slice());
+        assert_eq!(&input.as_slice()[45..], &[114, 0, 0]);
+
+        assert_eq!(transform.map_point([2.0, 2.0]), [2.0, 1.0]);
+        assert_eq!(
+            transform.map_box([-1.0, 0.0, 3.0, 4.0]),
+            [0.0, 0.0, 3.0, 2.0]
+        );
+
+        // a mask at half the resolution of the input
+        let mask = Image::<u8, 1>::new([2, 2].into(), vec![1, 2, 3, 4])?;
+        let restored = transform.restore_image(&mask)?;
+        assert_eq!(restored.as_slice(), &[1, 1, 2, 2, 3, 3, 4, 4]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_images_to_tensor() -> Result<(), NnError> {
+        let image = Image::<u8, 2>::new([2, 1].into(), vec![0, 255, 51, 102])?;
+        let mean = [0.0, 0.5];
+        let std = [1.0, 0.5];
+
+        let tensor = image_to_tensor(&image, &mean, &std, TensorLayout::Nchw)?;
+        assert_eq!(tensor.shape, [1, 2, 1, 2]);
+        for (a, b) in tensor.as_slice().iter().zip([0.0, 0.2, 1.0, -0.2]) {
+            assert_relative_eq!(*a, b, epsilon = 1e-6);
+        }
+
+        let tensor = images_to_tensor(&[image.clone(), image], &mean, &std, TensorLayout::Nhwc)?;
+        assert_eq!(tensor.shape, [2, 1, 2, 2]);
+        for (a, b) in tensor.as_slice()[4..].iter().zip([0.0, 1.0, 0.2, -0.2]) {
+            assert_relative_eq!(*a, b, epsilon = 1e-6);
+        }
+
+        assert!(matches!(
+            images_to_tensor::<3>(&[], &[0.0; 3], &[1.0; 3], TensorLayout::Nchw),
+            Err(NnError::EmptyBatch)
+        ));
+
+        Ok(())
+    }
+}
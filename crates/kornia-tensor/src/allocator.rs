@@ -1,5 +1,5 @@
-use std::alloc;
-use std::alloc::Layout;
+use alloc::alloc;
+use core::alloc::Layout;
 
 use thiserror::Error;
 
@@ -56,6 +56,10 @@ impl TensorAllocator for CpuAllocator {
     ///
     /// A non-null pointer to the allocated memory if successful, otherwise an error.
     fn alloc(&self, layout: Layout) -> Result<*mut u8, TensorAllocatorError> {
+        // the global allocator must not be called with a zero size, use a dangling pointer
+        if layout.size() == 0 {
+            return Ok(layout.align() as *mut u8);
+        }
         let ptr = unsafe { alloc::alloc(layout) };
         if ptr.is_null() {
             Err(TensorAllocatorError::NullPointer)?
@@ -75,7 +79,8 @@ impl TensorAllocator for CpuAllocator {
     /// The pointer must be non-null and the layout must be correct.
     #[allow(clippy::not_unsafe_ptr_arg_deref)]
     fn dealloc(&self, ptr: *mut u8, layout: Layout) {
-        if !ptr.is_null() {
+        // the empty buffers, e.g. of an empty vector, were never allocated
+        if !ptr.is_null() && layout.size() != 0 {
             unsafe { alloc::dealloc(ptr, layout) }
         }
     }
@@ -91,6 +96,11 @@ mod tests {
         let layout = Layout::from_size_align(1024, 64).unwrap();
         let ptr = allocator.alloc(layout)?;
         allocator.dealloc(ptr, layout);
+
+        let layout = Layout::from_size_align(0, 8).unwrap();
+        let ptr = allocator.alloc(layout)?;
+        assert!(!ptr.is_null());
+        allocator.dealloc(ptr, layout);
         Ok(())
     }
 }
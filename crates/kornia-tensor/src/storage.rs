@@ -1,4 +1,5 @@
-use std::{alloc::Layout, ptr::NonNull};
+use alloc::vec::Vec;
+use core::{alloc::Layout, ptr::NonNull};
 
 use crate::allocator::TensorAllocator;
 
@@ -29,13 +30,13 @@ impl<T, A: TensorAllocator> TensorStorage<T, A> {
 
     /// Returns the data pointer as a slice.
     pub fn as_slice(&self) -> &[T] {
-        unsafe { std::slice::from_raw_parts(self.as_ptr(), self.len / std::mem::size_of::<T>()) }
+        unsafe { core::slice::from_raw_parts(self.as_ptr(), self.len / core::mem::size_of::<T>()) }
     }
 
     /// Returns the data pointer as a mutable slice.
     pub fn as_mut_slice(&mut self) -> &mut [T] {
         unsafe {
-            std::slice::from_raw_parts_mut(self.as_mut_ptr(), self.len / std::mem::size_of::<T>())
+            core::slice::from_raw_parts_mut(self.as_mut_ptr(), self.len / core::mem::size_of::<T>())
         }
     }
 
@@ -70,12 +71,12 @@ impl<T, A: TensorAllocator> TensorStorage<T, A> {
         // Safety
         // Vec::as_ptr guaranteed to not be null
         let ptr = unsafe { NonNull::new_unchecked(value.as_ptr() as _) };
-        let len = value.len() * std::mem::size_of::<T>();
+        let len = value.len() * core::mem::size_of::<T>();
         // Safety
         // Vec guaranteed to have a valid layout matching that of `Layout::array`
         // This is based on `RawVec::current_memory`
         let layout = unsafe { Layout::array::<T>(value.capacity()).unwrap_unchecked() };
-        std::mem::forget(value);
+        core::mem::forget(value);
 
         Self {
             ptr,
@@ -92,7 +93,7 @@ impl<T, A: TensorAllocator> TensorStorage<T, A> {
     /// The pointer must be non-null and the length must be valid.
     pub unsafe fn from_raw_parts(data: *const T, len: usize, alloc: A) -> Self {
         let ptr = NonNull::new_unchecked(data as _);
-        let layout = Layout::from_size_align_unchecked(len, std::mem::size_of::<T>());
+        let layout = Layout::from_size_align_unchecked(len, core::mem::size_of::<T>());
         Self {
             ptr,
             len,
@@ -108,7 +109,7 @@ impl<T, A: TensorAllocator> TensorStorage<T, A> {
         // TODO: check if the buffer is a cpu buffer or comes from a custom allocator
         let _layout = &self.layout;
 
-        let vec_capacity = self.layout.size() / std::mem::size_of::<T>();
+        let vec_capacity = self.layout.size() / core::mem::size_of::<T>();
         //match Layout::array::<T>(vec_capacity) {
         //    Ok(expected) if layout == &expected => {}
         //    e => return Err(TensorAllocatorError::LayoutError(e.unwrap_err())),
@@ -116,10 +117,10 @@ impl<T, A: TensorAllocator> TensorStorage<T, A> {
 
         let length = self.len;
         let ptr = self.ptr;
-        let vec_len = length / std::mem::size_of::<T>();
+        let vec_len = length / core::mem::size_of::<T>();
 
         // Safety
-        std::mem::forget(self);
+        core::mem::forget(self);
         unsafe { Vec::from_raw_parts(ptr.as_ptr(), vec_len, vec_capacity) }
     }
 }
@@ -158,10 +159,11 @@ mod tests {
     use super::TensorStorage;
     use crate::allocator::{CpuAllocator, TensorAllocatorError};
     use crate::TensorAllocator;
-    use std::alloc::Layout;
-    use std::cell::RefCell;
-    use std::ptr::NonNull;
-    use std::rc::Rc;
+    use alloc::rc::Rc;
+    use alloc::{vec, vec::Vec};
+    use core::alloc::Layout;
+    use core::cell::RefCell;
+    use core::ptr::NonNull;
 
     #[test]
     fn test_tensor_buffer_create_raw() -> Result<(), TensorAllocatorError> {
@@ -174,7 +176,7 @@ mod tests {
 
         let buffer = TensorStorage {
             alloc: allocator,
-            len: size * std::mem::size_of::<u8>(),
+            len: size * core::mem::size_of::<u8>(),
             layout,
             ptr,
         };
@@ -184,7 +186,7 @@ mod tests {
         assert_eq!(buffer.layout, layout);
         assert_eq!(buffer.len(), size);
         assert!(!buffer.is_empty());
-        assert_eq!(buffer.len(), size * std::mem::size_of::<u8>());
+        assert_eq!(buffer.len(), size * core::mem::size_of::<u8>());
 
         Ok(())
     }
@@ -199,7 +201,7 @@ mod tests {
 
         // check alignment
         let ptr_raw = ptr.as_ptr() as usize;
-        let alignment = std::mem::align_of::<u8>();
+        let alignment = core::mem::align_of::<u8>();
         assert_eq!(ptr_raw % alignment, 0);
 
         Ok(())
@@ -268,7 +270,7 @@ mod tests {
             assert_eq!(*allocator.bytes_allocated.borrow(), 0);
 
             assert_eq!(result_vec.capacity(), vec_capacity);
-            assert!(std::ptr::eq(result_vec.as_ptr(), vec_ptr));
+            assert!(core::ptr::eq(result_vec.as_ptr(), vec_ptr));
         }
         assert_eq!(*allocator.bytes_allocated.borrow(), 0);
 
@@ -285,11 +287,11 @@ mod tests {
 
         // check NO copy
         let buffer_ptr = buffer.as_ptr();
-        assert!(std::ptr::eq(buffer_ptr, vec_ptr));
+        assert!(core::ptr::eq(buffer_ptr, vec_ptr));
 
         // check alignment
         let buffer_ptr = buffer.as_ptr() as usize;
-        let alignment = std::mem::align_of::<i32>();
+        let alignment = core::mem::align_of::<i32>();
         assert_eq!(buffer_ptr % alignment, 0);
 
         // check accessors
@@ -332,7 +334,7 @@ mod tests {
 
         // check NO copy
         assert_eq!(result_vec.capacity(), vec_cap);
-        assert!(std::ptr::eq(result_vec.as_ptr(), vec_ptr));
+        assert!(core::ptr::eq(result_vec.as_ptr(), vec_ptr));
 
         Ok(())
     }
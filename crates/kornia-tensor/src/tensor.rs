@@ -1,3 +1,10 @@
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+
 use thiserror::Error;
 
 use super::{
@@ -32,6 +39,26 @@ pub enum TensorError {
     /// Unsupported operation for the given data type or tensor configuration.
     #[error("Unsupported operation: {0}")]
     UnsupportedOperation(String),
+
+    /// The number of elements of the shape does not fit in `usize`.
+    #[error("The number of elements of the shape {0:?} overflows")]
+    ShapeOverflow(Vec<usize>),
+}
+
+/// Compute the number of elements of a tensor from its shape.
+///
+/// # Arguments
+///
+/// * `shape` - The shape of the tensor.
+///
+/// # Returns
+///
+/// * `numel` - The number of elements, or an error if it overflows.
+pub fn get_numel_from_shape<const N: usize>(shape: [usize; N]) -> Result<usize, TensorError> {
+    shape
+        .iter()
+        .try_fold(1usize, |numel, &dim| numel.checked_mul(dim))
+        .ok_or_else(|| TensorError::ShapeOverflow(shape.to_vec()))
 }
 
 /// Compute the strides from the shape of a tensor.
@@ -162,7 +189,7 @@ where
     /// assert_eq!(t.shape, [2, 2]);
     /// ```
     pub fn from_shape_vec(shape: [usize; N], data: Vec<T>, alloc: A) -> Result<Self, TensorError> {
-        let numel = shape.iter().product::<usize>();
+        let numel = get_numel_from_shape(shape)?;
         if numel != data.len() {
             return Err(TensorError::InvalidShape(numel));
         }
@@ -194,7 +221,7 @@ where
     where
         T: Clone,
     {
-        let numel = shape.iter().product::<usize>();
+        let numel = get_numel_from_shape(shape)?;
         if numel != data.len() {
             return Err(TensorError::InvalidShape(numel));
         }
@@ -213,12 +240,16 @@ where
     ///
     /// * `shape` - An array containing the shape of the tensor.
     /// * `data` - A pointer to the data of the tensor.
-    /// * `len` - The length of the data.
+    /// * `len` - The length of the data in bytes.
     /// * `alloc` - The allocator to use.
     ///
     /// # Safety
     ///
     /// The pointer must be non-null and the length must be valid.
+    ///
+    /// # Errors
+    ///
+    /// If the length in bytes does not match the shape of the tensor, an error is returned.
     pub unsafe fn from_raw_parts(
         shape: [usize; N],
         data: *const T,
@@ -228,6 +259,10 @@ where
     where
         T: Clone,
     {
+        let numel = get_numel_from_shape(shape)?;
+        if numel.checked_mul(core::mem::size_of::<T>()) != Some(len) {
+            return Err(TensorError::InvalidShape(numel));
+        }
         let storage = TensorStorage::from_raw_parts(data, len, alloc);
         let strides = get_strides_from_shape(shape);
         Ok(Self {
@@ -334,7 +369,7 @@ where
     /// The number of elements in the tensor.
     #[inline]
     pub fn numel(&self) -> usize {
-        self.storage.len() / std::mem::size_of::<T>()
+        self.storage.len() / core::mem::size_of::<T>()
     }
 
     /// Get the offset of the element at the given index.
@@ -511,7 +546,7 @@ where
         &self,
         shape: [usize; M],
     ) -> Result<TensorView<T, M, A>, TensorError> {
-        let numel = shape.iter().product::<usize>();
+        let numel = get_numel_from_shape(shape)?;
         if numel != self.storage.len() {
             return Err(TensorError::DimensionMismatch(format!(
                 "Cannot reshape tensor of shape {:?} with {} elements to shape {:?} with {} elements",
@@ -735,12 +770,12 @@ where
     }
 }
 
-impl<T, const N: usize, A> std::fmt::Display for Tensor<T, N, A>
+impl<T, const N: usize, A> core::fmt::Display for Tensor<T, N, A>
 where
-    T: std::fmt::Display + std::fmt::LowerExp,
+    T: core::fmt::Display + core::fmt::LowerExp,
     A: TensorAllocator + 'static,
 {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         let width = self
             .storage
             .as_slice()
@@ -815,7 +850,8 @@ where
 #[cfg(test)]
 mod tests {
     use crate::allocator::CpuAllocator;
-    use crate::tensor::{Tensor, TensorError};
+    use crate::tensor::{get_numel_from_shape, Tensor, TensorError};
+    use alloc::{string::ToString, vec, vec::Vec};
 
     #[test]
     fn constructor_1d() -> Result<(), TensorError> {
@@ -1101,7 +1137,7 @@ mod tests {
         assert_eq!(view.as_slice(), t.as_slice());
 
         // check that the data pointer is the same
-        assert!(std::ptr::eq(view.as_ptr(), t.as_ptr()));
+        assert!(core::ptr::eq(view.as_ptr(), t.as_ptr()));
 
         Ok(())
     }
@@ -1329,9 +1365,52 @@ mod tests {
     fn from_raw_parts() -> Result<(), TensorError> {
         let data: Vec<u8> = vec![1, 2, 3, 4];
         let t = unsafe { Tensor::from_raw_parts([2, 2], data.as_ptr(), data.len(), CpuAllocator)? };
-        std::mem::forget(data);
+        core::mem::forget(data);
         assert_eq!(t.shape, [2, 2]);
         assert_eq!(t.as_slice(), &[1, 2, 3, 4]);
+
+        let data: Vec<u8> = vec![1, 2, 3];
+        let t = unsafe { Tensor::from_raw_parts([2, 2], data.as_ptr(), data.len(), CpuAllocator) };
+        assert_eq!(t.err(), Some(TensorError::InvalidShape(4)));
+        Ok(())
+    }
+
+    #[test]
+    fn from_raw_parts_f32() -> Result<(), TensorError> {
+        let data: Vec<f32> = vec![1.0, 2.0, 3.0, 4.0];
+        let len = data.len() * core::mem::size_of::<f32>();
+        let t = unsafe { Tensor::from_raw_parts([2, 2], data.as_ptr(), len, CpuAllocator)? };
+        core::mem::forget(data);
+        assert_eq!(t.shape, [2, 2]);
+        assert_eq!(t.as_slice(), &[1.0, 2.0, 3.0, 4.0]);
+
+        // the length is given in bytes, so the number of elements is rejected
+        let data: Vec<f32> = vec![1.0, 2.0, 3.0, 4.0];
+        let t = unsafe { Tensor::from_raw_parts([2, 2], data.as_ptr(), data.len(), CpuAllocator) };
+        assert_eq!(t.err(), Some(TensorError::InvalidShape(4)));
         Ok(())
     }
+
+    #[test]
+    fn constructor_empty() -> Result<(), TensorError> {
+        let t = Tensor::<u8, 3, _>::from_shape_vec([6, 0, 1], vec![], CpuAllocator)?;
+        assert_eq!(t.numel(), 0);
+        assert!(t.as_slice().is_empty());
+        let t = t.clone();
+        assert_eq!(t.shape, [6, 0, 1]);
+        Ok(())
+    }
+
+    #[test]
+    fn constructor_overflow() {
+        let shape = [usize::MAX / 2 + 1, 2, 1];
+        assert_eq!(
+            get_numel_from_shape(shape),
+            Err(TensorError::ShapeOverflow(shape.to_vec()))
+        );
+        let t = Tensor::<u8, 3, _>::from_shape_slice(shape, &[], CpuAllocator);
+        assert_eq!(t.err(), Some(TensorError::ShapeOverflow(shape.to_vec())));
+        let t = Tensor::<u8, 3, _>::from_shape_vec(shape, vec![], CpuAllocator);
+        assert_eq!(t.err(), Some(TensorError::ShapeOverflow(shape.to_vec())));
+    }
 }
@@ -1,3 +1,5 @@
+use alloc::vec::Vec;
+
 use crate::{
     get_strides_from_shape, storage::TensorStorage, CpuAllocator, Tensor, TensorAllocator,
 };
@@ -30,7 +32,7 @@ impl<T, const N: usize, A: TensorAllocator + 'static> TensorView<'_, T, N, A> {
     /// Returns the length of the tensor.
     #[inline]
     pub fn numel(&self) -> usize {
-        self.storage.len() / std::mem::size_of::<T>()
+        self.storage.len() / core::mem::size_of::<T>()
     }
 
     /// Get the element at the given index.
@@ -92,6 +94,7 @@ impl<T, const N: usize, A: TensorAllocator + 'static> TensorView<'_, T, N, A> {
 mod tests {
     use super::*;
     use crate::allocator::{CpuAllocator, TensorAllocatorError};
+    use alloc::vec;
 
     #[test]
     fn test_tensor_view_from_vec() -> Result<(), TensorAllocatorError> {
@@ -0,0 +1,223 @@
+use kornia_3d::io::{colmap::ColmapError, pcd::PcdError, ply::PlyError};
+use kornia_3d::{pose_graph::PoseGraphError, ransac::RansacError};
+use kornia_image::ImageError;
+use kornia_imgproc::{backend::BackendError, barcode::BarcodeError, pipeline::PipelineError};
+use kornia_io::IoError;
+use kornia_tensor::TensorError;
+use kornia_tensor_ops::TensorOpsError;
+
+/// An error of any of the kornia crates.
+///
+/// The errors of the individual crates convert into this type with `?`, so a pipeline
+/// spanning several crates can return a single error type. The errors can be wrapped
+/// with a description of the failing step with [`ResultExt::context`], and the original
+/// error stays available through [`std::error::Error::source`].
+///
+/// # Example
+///
+/// ```
+/// use kornia::error::{KorniaError, ResultExt};
+/// use kornia::image::Image;
+///
+/// fn load() -> Result<Image<u8, 3>, KorniaError> {
+///     let image = Image::from_size_val([4, 4].into(), 0).context("allocating the frame")?;
+///     Ok(image)
+/// }
+/// ```
+#[derive(Debug, thiserror::Error)]
+pub enum KorniaError {
+    /// An error of the tensor crate.
+    #[error(transparent)]
+    Tensor(#[from] TensorError),
+
+    /// An error of the tensor operations.
+    #[error(transparent)]
+    TensorOps(#[from] TensorOpsError),
+
+    /// An error of the image crate.
+    #[error(transparent)]
+    Image(#[from] ImageError),
+
+    /// An error reading or writing images.
+    #[error(transparent)]
+    Io(#[from] IoError),
+
+    /// An error of an image processing pipeline.
+    #[error(transparent)]
+    Pipeline(#[from] PipelineError),
+
+    /// An error of an image processing backend.
+    #[error(transparent)]
+    Backend(#[from] BackendError),
+
+    /// An error decoding or encoding a barcode.
+    #[error(transparent)]
+    Barcode(#[from] BarcodeError),
+
+    /// An error of a RANSAC estimation.
+    #[error(transparent)]
+    Ransac(#[from] RansacError),
+
+    /// An error of a pose graph optimization.
+    #[error(transparent)]
+    PoseGraph(#[from] PoseGraphError),
+
+    /// An error reading or writing a PCD file.
+    #[error(transparent)]
+    Pcd(#[from] PcdError),
+
+    /// An error reading or writing a PLY file.
+    #[error(transparent)]
+    Ply(#[from] PlyError),
+
+    /// An error reading or writing a COLMAP reconstruction.
+    #[error(transparent)]
+    Colmap(#[from] ColmapError),
+
+    /// An error capturing a video stream.
+    #[cfg(feature = "gstreamer")]
+    #[error(transparent)]
+    StreamCapture(#[from] kornia_io::stream::error::StreamCaptureError),
+
+    /// The shape of a tensor or image differs from the expected one.
+    #[error("Shape mismatch in {name}: expected {expected:?}, got {actual:?}")]
+    ShapeMismatch {
+        /// The name of the mismatched value, e.g. an argument.
+        name: String,
+        /// The expected shape.
+        expected: Vec<usize>,
+        /// The actual shape.
+        actual: Vec<usize>,
+    },
+
+    /// An error with a description of the step which failed.
+    #[error("{context}")]
+    Context {
+        /// The description of the failing step.
+        context: String,
+        /// The underlying error.
+        #[source]
+        source: Box<KorniaError>,
+    },
+
+    /// An error of another crate, e.g. a GPU backend or a user callback.
+    #[error(transparent)]
+    Other(Box<dyn std::error::Error + Send + Sync + 'static>),
+}
+
+impl KorniaError {
+    /// Create a shape mismatch error.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the mismatched value.
+    /// * `expected` - The expected shape.
+    /// * `actual` - The actual shape.
+    pub fn shape_mismatch(name: impl Into<String>, expected: &[usize], actual: &[usize]) -> Self {
+        Self::ShapeMismatch {
+            name: name.into(),
+            expected: expected.to_vec(),
+            actual: actual.to_vec(),
+        }
+    }
+
+    /// Wrap an error of a crate without a dedicated variant.
+    pub fn other(error: impl std::error::Error + Send + Sync + 'static) -> Self {
+        Self::Other(Box::new(error))
+    }
+
+    /// Wrap the error with a description of the step which failed.
+    pub fn context(self, context: impl Into<String>) -> Self {
+        Self::Context {
+            context: context.into(),
+            source: Box::new(self),
+        }
+    }
+
+    /// The innermost error, below all the contexts.
+    pub fn root_cause(&self) -> &KorniaError {
+        match self {
+            Self::Context { source, .. } => source.root_cause(),
+            error => error,
+        }
+    }
+
+    /// The descriptions of the error and of all its sources, from the outermost.
+    pub fn chain(&self) -> Vec<String> {
+        let mut messages = vec![self.to_string()];
+        let mut source = std::error::Error::source(self);
+        while let Some(error) = source {
+            messages.push(error.to_string());
+            source = error.source();
+        }
+        messages
+    }
+}
+
+/// Add a description of the failing step to the error of a result.
+pub trait ResultExt<T> {
+    /// Wrap the error with a description of the step which failed.
+    fn context(self, context: impl Into<String>) -> Result<T, KorniaError>;
+
+    /// Wrap the error with a description built only on failure.
+    fn with_context<S: Into<String>, F: FnOnce() -> S>(self, f: F) -> Result<T, KorniaError>;
+}
+
+impl<T, E: Into<KorniaError>> ResultExt<T> for Result<T, E> {
+    fn context(self, context: impl Into<String>) -> Result<T, KorniaError> {
+        self.map_err(|e| e.into().context(context))
+    }
+
+    fn with_context<S: Into<String>, F: FnOnce() -> S>(self, f: F) -> Result<T, KorniaError> {
+        self.map_err(|e| e.into().context(f()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use kornia_image::Image;
+
+    fn allocate(width: usize) -> Result<Image<u8, 1>, KorniaError> {
+        let image = Image::new([width, 2].into(), vec![0; 4]).context("allocating the mask")?;
+        Ok(image)
+    }
+
+    #[test]
+    fn test_from_crate_errors() {
+        let error: KorniaError = RansacError::NoModelFound.into();
+        assert!(matches!(
+            error,
+            KorniaError::Ransac(RansacError::NoModelFound)
+        ));
+        assert_eq!(error.to_string(), RansacError::NoModelFound.to_string());
+
+        let error = KorniaError::other(std::io::Error::other("device lost"));
+        assert_eq!(error.to_string(), "device lost");
+    }
+
+    #[test]
+    fn test_context_chain() {
+        assert!(allocate(2).is_ok());
+
+        let Err(error) = allocate(3).with_context(|| format!("loading frame {}", 7)) else {
+            panic!("the allocation should fail");
+        };
+        assert!(matches!(error.root_cause(), KorniaError::Image(_)));
+
+        let chain = error.chain();
+        assert_eq!(chain.len(), 3);
+        assert_eq!(chain[0], "loading frame 7");
+        assert_eq!(chain[1], "allocating the mask");
+        assert_eq!(chain[2], error.root_cause().to_string());
+    }
+
+    #[test]
+    fn test_shape_mismatch() {
+        let error = KorniaError::shape_mismatch("src", &[480, 640, 3], &[480, 640, 1]);
+        assert_eq!(
+            error.to_string(),
+            "Shape mismatch in src: expected [480, 640, 3], got [480, 640, 1]"
+        );
+    }
+}
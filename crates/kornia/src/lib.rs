@@ -20,3 +20,14 @@ pub use kornia_3d as k3d;
 
 #[doc(inline)]
 pub use kornia_icp as icp;
+
+#[cfg(feature = "datasets")]
+#[doc(inline)]
+pub use kornia_datasets as datasets;
+
+/// Error type of the kornia crates with context chaining.
+pub mod error;
+
+/// Helpers to log images, features and 3D data to the Rerun viewer.
+#[cfg(feature = "rerun")]
+pub mod rerun;
@@ -0,0 +1,292 @@
+//! # Example
+//!
+//! ```no_run
+//! use kornia::image::Image;
+//!
+//! let rec = ::rerun::RecordingStreamBuilder::new("kornia").spawn()?;
+//! let image = Image::<u8, 3>::from_size_val([640, 480].into(), 0)?;
+//! kornia::rerun::log_image(&rec, "image", &image)?;
+//! kornia::rerun::log_keypoints(&rec, "image/keypoints", &[[10.0, 20.0]], [255, 0, 0])?;
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use ::rerun::{EntityPath, RecordingStream, RecordingStreamResult};
+use kornia_3d::pointcloud::PointCloud;
+use kornia_image::{Image, ImageSize};
+use kornia_imgproc::calibration::CameraIntrinsic;
+
+/// An image type that can be logged to Rerun.
+pub trait AsRerunImage {
+    /// Convert the image to a Rerun image archetype.
+    fn as_rerun_image(&self) -> ::rerun::Image;
+}
+
+impl AsRerunImage for Image<u8, 1> {
+    fn as_rerun_image(&self) -> ::rerun::Image {
+        ::rerun::Image::from_l8(self.as_slice().to_vec(), resolution(self.size()))
+    }
+}
+
+impl AsRerunImage for Image<u8, 3> {
+    fn as_rerun_image(&self) -> ::rerun::Image {
+        ::rerun::Image::from_rgb24(self.as_slice().to_vec(), resolution(self.size()))
+    }
+}
+
+impl AsRerunImage for Image<u8, 4> {
+    fn as_rerun_image(&self) -> ::rerun::Image {
+        ::rerun::Image::from_rgba32(self.as_slice().to_vec(), resolution(self.size()))
+    }
+}
+
+impl AsRerunImage for Image<f32, 1> {
+    fn as_rerun_image(&self) -> ::rerun::Image {
+        ::rerun::Image::from_elements(
+            self.as_slice(),
+            resolution(self.size()),
+            ::rerun::ColorModel::L,
+        )
+    }
+}
+
+impl AsRerunImage for Image<f32, 3> {
+    fn as_rerun_image(&self) -> ::rerun::Image {
+        ::rerun::Image::from_elements(
+            self.as_slice(),
+            resolution(self.size()),
+            ::rerun::ColorModel::RGB,
+        )
+    }
+}
+
+// the resolution of an image as expected by rerun
+fn resolution(size: ImageSize) -> [u32; 2] {
+    [size.width as u32, size.height as u32]
+}
+
+/// Log an image.
+///
+/// # Arguments
+///
+/// * `rec` - The recording stream.
+/// * `entity_path` - The entity path to log the image to.
+/// * `image` - The image to log, grayscale, RGB or RGBA.
+pub fn log_image(
+    rec: &RecordingStream,
+    entity_path: impl Into<EntityPath>,
+    image: &impl AsRerunImage,
+) -> RecordingStreamResult<()> {
+    rec.log(entity_path, &image.as_rerun_image())
+}
+
+/// Log a depth image.
+///
+/// # Arguments
+///
+/// * `rec` - The recording stream.
+/// * `entity_path` - The entity path to log the depth image to.
+/// * `depth` - The depth image in sensor units.
+/// * `depth_scale` - The scale to convert the depth values to meters, e.g. 1 / 1000 for millimeters.
+pub fn log_depth_image(
+    rec: &RecordingStream,
+    entity_path: impl Into<EntityPath>,
+    depth: &Image<u16, 1>,
+    depth_scale: f32,
+) -> RecordingStreamResult<()> {
+    let bytes = depth
+        .as_slice()
+        .iter()
+        .flat_map(|v| v.to_le_bytes())
+        .collect::<Vec<_>>();
+    rec.log(
+        entity_path,
+        &::rerun::DepthImage::from_gray16(bytes, resolution(depth.size()))
+            .with_meter(1.0 / depth_scale),
+    )
+}
+
+/// Log a set of 2D keypoints.
+///
+/// # Arguments
+///
+/// * `rec` - The recording stream.
+/// * `entity_path` - The entity path to log the keypoints to, usually a child of an image.
+/// * `keypoints` - The keypoints as (x, y) pixel coordinates.
+/// * `color` - The RGB color of the keypoints.
+pub fn log_keypoints(
+    rec: &RecordingStream,
+    entity_path: impl Into<EntityPath>,
+    keypoints: &[[f32; 2]],
+    color: [u8; 3],
+) -> RecordingStreamResult<()> {
+    rec.log(
+        entity_path,
+        &::rerun::Points2D::new(keypoints.iter().copied())
+            .with_colors([::rerun::Color::from_rgb(color[0], color[1], color[2])]),
+    )
+}
+
+/// Log the trajectories of tracked 2D points.
+///
+/// # Arguments
+///
+/// * `rec` - The recording stream.
+/// * `entity_path` - The entity path to log the tracks to, usually a child of an image.
+/// * `tracks` - The tracks, each one a sequence of (x, y) pixel coordinates.
+/// * `color` - The RGB color of the tracks.
+pub fn log_tracks(
+    rec: &RecordingStream,
+    entity_path: impl Into<EntityPath>,
+    tracks: &[Vec<[f32; 2]>],
+    color: [u8; 3],
+) -> RecordingStreamResult<()> {
+    rec.log(
+        entity_path,
+        &::rerun::LineStrips2D::new(tracks.iter().map(|track| track.iter().copied()))
+            .with_colors([::rerun::Color::from_rgb(color[0], color[1], color[2])]),
+    )
+}
+
+/// Log a rigid transformation, e.g. the pose of a camera in the world.
+///
+/// The transformation maps the points from the frame of the entity to the frame of its parent.
+///
+/// # Arguments
+///
+/// * `rec` - The recording stream.
+/// * `entity_path` - The entity path to log the pose to.
+/// * `rotation` - The 3x3 rotation matrix in row-major order.
+/// * `translation` - The translation vector.
+pub fn log_pose(
+    rec: &RecordingStream,
+    entity_path: impl Into<EntityPath>,
+    rotation: &[[f64; 3]; 3],
+    translation: &[f64; 3],
+) -> RecordingStreamResult<()> {
+    // rerun expects the matrix in column-major order
+    let mut columns = [[0.0f32; 3]; 3];
+    for (i, row) in rotation.iter().enumerate() {
+        for (j, v) in row.iter().enumerate() {
+            columns[j][i] = *v as f32;
+        }
+    }
+    rec.log(
+        entity_path,
+        &::rerun::Transform3D::from_translation_mat3x3(translation.map(|v| v as f32), columns),
+    )
+}
+
+/// Log a pinhole camera model to project the children of the entity into an image.
+///
+/// # Arguments
+///
+/// * `rec` - The recording stream.
+/// * `entity_path` - The entity path to log the camera model to.
+/// * `intrinsics` - The intrinsic parameters of the camera.
+/// * `size` - The size of the image.
+pub fn log_pinhole(
+    rec: &RecordingStream,
+    entity_path: impl Into<EntityPath>,
+    intrinsics: &CameraIntrinsic,
+    size: ImageSize,
+) -> RecordingStreamResult<()> {
+    rec.log(
+        entity_path,
+        &::rerun::Pinhole::from_focal_length_and_resolution(
+            [intrinsics.fx as f32, intrinsics.fy as f32],
+            [size.width as f32, size.height as f32],
+        )
+        .with_principal_point([intrinsics.cx as f32, intrinsics.cy as f32]),
+    )
+}
+
+/// Log a point cloud with its colors if available.
+///
+/// # Arguments
+///
+/// * `rec` - The recording stream.
+/// * `entity_path` - The entity path to log the point cloud to.
+/// * `pointcloud` - The point cloud to log.
+pub fn log_pointcloud(
+    rec: &RecordingStream,
+    entity_path: impl Into<EntityPath>,
+    pointcloud: &PointCloud,
+) -> RecordingStreamResult<()> {
+    let points = pointcloud
+        .points()
+        .iter()
+        .map(|p| [p[0] as f32, p[1] as f32, p[2] as f32]);
+
+    let mut points3d = ::rerun::Points3D::new(points);
+    if let Some(colors) = pointcloud.colors() {
+        points3d = points3d.with_colors(
+            colors
+                .iter()
+                .map(|c| ::rerun::Color::from_rgb(c[0], c[1], c[2])),
+        );
+    }
+
+    rec.log(entity_path, &points3d)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn log_to_memory() -> Result<(), Box<dyn std::error::Error>> {
+        let (rec, storage) = ::rerun::RecordingStreamBuilder::new("kornia_test").memory()?;
+
+        let image = Image::<u8, 3>::from_size_val([4, 3].into(), 128)?;
+        log_image(&rec, "image", &image)?;
+        log_image(
+            &rec,
+            "gray",
+            &Image::<f32, 1>::from_size_val([4, 3].into(), 0.5)?,
+        )?;
+        log_depth_image(
+            &rec,
+            "depth",
+            &Image::<u16, 1>::from_size_val([4, 3].into(), 1000)?,
+            1e-3,
+        )?;
+        log_keypoints(
+            &rec,
+            "image/keypoints",
+            &[[1.0, 2.0], [3.0, 1.0]],
+            [255, 0, 0],
+        )?;
+        log_tracks(
+            &rec,
+            "image/tracks",
+            &[vec![[0.0, 0.0], [1.0, 1.0]]],
+            [0, 255, 0],
+        )?;
+
+        let intrinsics = CameraIntrinsic {
+            fx: 10.0,
+            fy: 10.0,
+            cx: 2.0,
+            cy: 1.5,
+        };
+        log_pose(
+            &rec,
+            "camera",
+            &[[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]],
+            &[0.0, 0.0, 1.0],
+        )?;
+        log_pinhole(&rec, "camera/image", &intrinsics, image.size())?;
+
+        let pointcloud = PointCloud::new(
+            vec![[0.0, 0.0, 1.0], [1.0, 0.0, 1.0]],
+            Some(vec![[255, 0, 0], [0, 0, 255]]),
+            None,
+        );
+        log_pointcloud(&rec, "points", &pointcloud)?;
+
+        rec.flush_blocking();
+        assert!(!storage.take().is_empty());
+
+        Ok(())
+    }
+}
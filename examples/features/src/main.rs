@@ -51,7 +51,11 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         imgproc::color::gray_from_rgb(&img_f32, &mut gray)?;
 
         // compute the hessian response
-        imgproc::features::hessian_response(&gray, &mut hessian)?;
+        imgproc::features::hessian_response(
+            &gray,
+            &mut hessian,
+            imgproc::features::GradsMode::Diff,
+        )?;
 
         // compute the corners
         imgproc::threshold::threshold_binary(&hessian, &mut corners, 0.01, 1.0)?;
@@ -48,6 +48,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             &args.camera_port,
             &args.stream,
         )
+        .with_reconnect(std::time::Duration::from_secs(1))
         .build()?;
 
     // start the stream capture
@@ -97,6 +98,17 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         )?;
 
         rec.log_static("fps", &rerun::Scalar::new(fps_counter.fps() as f64))?;
+
+        // log the stream statistics
+        let stats = capture.stats();
+        rec.log_static(
+            "dropped_frames",
+            &rerun::Scalar::new(stats.frames_dropped as f64),
+        )?;
+        rec.log_static(
+            "latency_ms",
+            &rerun::Scalar::new(stats.latency.as_secs_f64() * 1e3),
+        )?;
     }
 
     capture.close()?;
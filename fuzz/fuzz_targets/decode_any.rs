@@ -0,0 +1,8 @@
+#![no_main]
+
+use kornia_io::functional::decode_image_any_rgb8;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let _ = decode_image_any_rgb8(data);
+});
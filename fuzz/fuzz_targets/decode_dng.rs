@@ -0,0 +1,9 @@
+#![no_main]
+
+use kornia_io::{dng::decode_raw_dng, exif::decode_exif_metadata};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let _ = decode_raw_dng(data);
+    let _ = decode_exif_metadata(data);
+});
@@ -0,0 +1,9 @@
+#![no_main]
+
+use kornia_io::exr::{decode_image_exr_rgb32f, decode_image_exr_rgba32f};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let _ = decode_image_exr_rgb32f(data);
+    let _ = decode_image_exr_rgba32f(data);
+});
@@ -0,0 +1,27 @@
+#![no_main]
+
+use kornia_image::Image;
+use kornia_io::{
+    exif::{read_exif_metadata, read_exif_orientation},
+    jpeg::{decode_image_jpeg_mono8, decode_image_jpeg_rgb8},
+};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let _ = read_exif_metadata(data);
+    let _ = read_exif_orientation(data);
+
+    // the destination size is given by the first two bytes
+    if data.len() < 2 {
+        return;
+    }
+    let size = [data[0] as usize, data[1] as usize].into();
+    let data = &data[2..];
+
+    if let Ok(mut dst) = Image::<u8, 1>::from_size_val(size, 0) {
+        let _ = decode_image_jpeg_mono8(data, &mut dst);
+    }
+    if let Ok(mut dst) = Image::<u8, 3>::from_size_val(size, 0) {
+        let _ = decode_image_jpeg_rgb8(data, &mut dst);
+    }
+});
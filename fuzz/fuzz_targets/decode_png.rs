@@ -0,0 +1,24 @@
+#![no_main]
+
+use kornia_image::Image;
+use kornia_io::png::{decode_image_png_mono8, decode_image_png_rgb16, decode_image_png_rgb8};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    // the destination size is given by the first two bytes
+    if data.len() < 2 {
+        return;
+    }
+    let size = [data[0] as usize, data[1] as usize].into();
+    let data = &data[2..];
+
+    if let Ok(mut dst) = Image::<u8, 1>::from_size_val(size, 0) {
+        let _ = decode_image_png_mono8(data, &mut dst);
+    }
+    if let Ok(mut dst) = Image::<u8, 3>::from_size_val(size, 0) {
+        let _ = decode_image_png_rgb8(data, &mut dst);
+    }
+    if let Ok(mut dst) = Image::<u16, 3>::from_size_val(size, 0) {
+        let _ = decode_image_png_rgb16(data, &mut dst);
+    }
+});
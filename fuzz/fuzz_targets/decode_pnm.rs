@@ -0,0 +1,15 @@
+#![no_main]
+
+use kornia_io::pnm::{
+    decode_image_pfm_mono32f, decode_image_pfm_rgb32f, decode_image_pgm_mono16,
+    decode_image_pgm_mono8, decode_image_ppm_rgb8,
+};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let _ = decode_image_pgm_mono8(data);
+    let _ = decode_image_pgm_mono16(data);
+    let _ = decode_image_ppm_rgb8(data);
+    let _ = decode_image_pfm_mono32f(data);
+    let _ = decode_image_pfm_rgb32f(data);
+});
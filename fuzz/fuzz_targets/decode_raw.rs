@@ -0,0 +1,11 @@
+#![no_main]
+
+use kornia_io::{npy::decode_image_npy, raw::decode_image_raw};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let _ = decode_image_raw::<u8, 1>(data);
+    let _ = decode_image_raw::<f32, 3>(data);
+    let _ = decode_image_npy::<u8, 1>(data);
+    let _ = decode_image_npy::<f32, 3>(data);
+});
@@ -0,0 +1,24 @@
+#![no_main]
+
+use kornia_io::tiff::{
+    decode_image_tiff_mono16, decode_image_tiff_mono8, decode_image_tiff_rgb16,
+    decode_image_tiff_rgb8, TiffTileReader,
+};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let _ = decode_image_tiff_mono8(data);
+    let _ = decode_image_tiff_rgb8(data);
+    let _ = decode_image_tiff_mono16(data);
+    let _ = decode_image_tiff_rgb16(data);
+
+    // the tile table is read from the file, decode only the first tiles
+    if let Ok(reader) = TiffTileReader::from_bytes(data.to_vec()) {
+        for index in 0..reader.num_tiles().min(4) {
+            let _ = reader.read_tile::<u8, 1>(index);
+            let _ = reader.read_tile::<u8, 3>(index);
+            let _ = reader.read_tile::<u16, 1>(index);
+            let _ = reader.read_tile::<f32, 1>(index);
+        }
+    }
+});
@@ -0,0 +1,24 @@
+#![no_main]
+
+use kornia_image::{Image, ImageSize};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    // the size is given by the first 16 bytes and the pixels by the remaining bytes
+    if data.len() < 16 {
+        return;
+    }
+    let (header, pixels) = data.split_at(16);
+    let read_u64 = |bytes: &[u8]| u64::from_le_bytes(bytes.try_into().unwrap()) as usize;
+    let size = ImageSize {
+        width: read_u64(&header[..8]),
+        height: read_u64(&header[8..]),
+    };
+
+    if let Ok(image) = Image::<u8, 3>::from_size_slice(size, pixels) {
+        assert_eq!(image.as_slice().len(), pixels.len());
+    }
+    if let Ok(image) = Image::<u8, 1>::new(size, pixels.to_vec()) {
+        assert_eq!(image.size(), size);
+    }
+});
@@ -0,0 +1,58 @@
+import assert from "node:assert/strict";
+import { readFileSync } from "node:fs";
+import { test } from "node:test";
+
+import * as K from "../index.js";
+
+const DATA_DIR = new URL("../../tests/data/", import.meta.url);
+
+test("decode and read image", () => {
+  const image = K.decodeImage(readFileSync(new URL("dog.jpeg", DATA_DIR)));
+  assert.equal(image.width, 258);
+  assert.equal(image.height, 195);
+  assert.equal(image.channels, 3);
+  assert.equal(image.data.length, 258 * 195 * 3);
+
+  const read = K.readImage(new URL("dog.jpeg", DATA_DIR).pathname);
+  assert.deepEqual(read.data, image.data);
+
+  assert.throws(() => K.decodeImage(Buffer.alloc(4)));
+});
+
+test("resize", () => {
+  const image = { width: 4, height: 4, channels: 3, data: Buffer.alloc(4 * 4 * 3, 255) };
+  const resized = K.resize(image, 2, 3, "bilinear");
+  assert.equal(resized.width, 2);
+  assert.equal(resized.height, 3);
+  assert.ok(resized.data.every((v) => v === 255));
+
+  const gray = { width: 4, height: 4, channels: 1, data: Buffer.alloc(16, 10) };
+  assert.equal(K.resize(gray, 8, 8, "nearest").data.length, 64);
+
+  assert.throws(() => K.resize(image, 2, 2, "bicubic"));
+});
+
+test("color conversion", () => {
+  const image = { width: 1, height: 1, channels: 3, data: Buffer.from([0, 128, 255]) };
+  assert.deepEqual([...K.bgrFromRgb(image).data], [255, 128, 0]);
+
+  const gray = K.grayFromRgb(image);
+  assert.equal(gray.channels, 1);
+  assert.deepEqual([...K.rgbFromGray(gray).data], Array(3).fill(gray.data[0]));
+
+  // the data does not match the size
+  assert.throws(() => K.grayFromRgb({ ...image, width: 2 }));
+});
+
+test("fast feature detector", () => {
+  const data = Buffer.alloc(40 * 40);
+  for (let y = 20; y < 30; y++) {
+    data.fill(255, y * 40 + 22, y * 40 + 32);
+  }
+  const image = { width: 40, height: 40, channels: 1, data };
+  const keypoints = K.fastFeatureDetector(image, 100);
+  assert.ok(keypoints.length > 0);
+  assert.ok(keypoints.every(({ x, y }) => x >= 19 && x <= 34 && y >= 17 && y <= 32));
+
+  assert.throws(() => K.fastFeatureDetector(image, 256));
+});
@@ -0,0 +1,71 @@
+use kornia_image::Image;
+use kornia_imgproc::color;
+use napi_derive::napi;
+
+use crate::image::{JsImage, ToJsImage};
+
+/// Convert an RGB image to grayscale.
+///
+/// # Arguments
+///
+/// * `image` - The RGB image.
+///
+/// # Returns
+///
+/// The grayscale image.
+#[napi]
+pub fn gray_from_rgb(image: JsImage) -> napi::Result<JsImage> {
+    let image_rgb = image.view::<3>()?;
+
+    let mut image_gray = Image::from_size_val(image_rgb.size(), 0u8)
+        .map_err(|e| napi::Error::from_reason(format!("dst image: {}", e)))?;
+
+    color::gray_from_rgb_u8(&image_rgb, &mut image_gray)
+        .map_err(|e| napi::Error::from_reason(format!("failed to convert image: {}", e)))?;
+
+    Ok(image_gray.to_js_image())
+}
+
+/// Convert a grayscale image to RGB.
+///
+/// # Arguments
+///
+/// * `image` - The grayscale image.
+///
+/// # Returns
+///
+/// The RGB image.
+#[napi]
+pub fn rgb_from_gray(image: JsImage) -> napi::Result<JsImage> {
+    let image_gray = image.view::<1>()?;
+
+    let mut image_rgb = Image::from_size_val(image_gray.size(), 0u8)
+        .map_err(|e| napi::Error::from_reason(format!("dst image: {}", e)))?;
+
+    color::rgb_from_gray(&image_gray, &mut image_rgb)
+        .map_err(|e| napi::Error::from_reason(format!("failed to convert image: {}", e)))?;
+
+    Ok(image_rgb.to_js_image())
+}
+
+/// Convert an RGB image to BGR.
+///
+/// # Arguments
+///
+/// * `image` - The RGB image.
+///
+/// # Returns
+///
+/// The BGR image.
+#[napi]
+pub fn bgr_from_rgb(image: JsImage) -> napi::Result<JsImage> {
+    let image_rgb = image.view::<3>()?;
+
+    let mut image_bgr = Image::from_size_val(image_rgb.size(), 0u8)
+        .map_err(|e| napi::Error::from_reason(format!("dst image: {}", e)))?;
+
+    color::bgr_from_rgb(&image_rgb, &mut image_bgr)
+        .map_err(|e| napi::Error::from_reason(format!("failed to convert image: {}", e)))?;
+
+    Ok(image_bgr.to_js_image())
+}
@@ -0,0 +1,47 @@
+use kornia_imgproc::features;
+use napi_derive::napi;
+
+use crate::image::JsImage;
+
+/// A keypoint in pixels.
+#[napi(object)]
+pub struct Keypoint {
+    /// The column of the keypoint.
+    pub x: i32,
+    /// The row of the keypoint.
+    pub y: i32,
+}
+
+/// Detect FAST corners in a grayscale image.
+///
+/// # Arguments
+///
+/// * `image` - The grayscale image.
+/// * `threshold` - The intensity difference to the center pixel, between 0 and 255.
+/// * `arc_length` - The number of consecutive brighter or darker pixels on the circle,
+///   9 by default.
+///
+/// # Returns
+///
+/// The detected corners.
+#[napi]
+pub fn fast_feature_detector(
+    image: JsImage,
+    threshold: u32,
+    arc_length: Option<u32>,
+) -> napi::Result<Vec<Keypoint>> {
+    let image = image.view::<1>()?;
+
+    let threshold = u8::try_from(threshold)
+        .map_err(|_| napi::Error::from_reason("threshold must be between 0 and 255"))?;
+    let arc_length = u8::try_from(arc_length.unwrap_or(9))
+        .map_err(|_| napi::Error::from_reason("arc_length must be between 0 and 255"))?;
+
+    let keypoints = features::fast_feature_detector(&image, threshold, arc_length)
+        .map_err(|e| napi::Error::from_reason(format!("failed to detect corners: {}", e)))?;
+
+    Ok(keypoints
+        .into_iter()
+        .map(|[x, y]| Keypoint { x, y })
+        .collect())
+}
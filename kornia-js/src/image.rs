@@ -0,0 +1,94 @@
+use std::marker::PhantomData;
+use std::mem::ManuallyDrop;
+use std::ops::Deref;
+
+use kornia_image::{Image, ImageSize};
+use napi::bindgen_prelude::Buffer;
+use napi_derive::napi;
+
+/// An 8-bit image exchanged with JavaScript.
+///
+/// The pixels are stored row by row with interleaved channels in a `Buffer`.
+#[napi(object)]
+pub struct JsImage {
+    /// The width of the image in pixels.
+    pub width: u32,
+    /// The height of the image in pixels.
+    pub height: u32,
+    /// The number of channels, 1 (gray), 3 (RGB) or 4 (RGBA).
+    pub channels: u32,
+    /// The pixels of the image.
+    pub data: Buffer,
+}
+
+/// A kornia image borrowing the pixels of a [`JsImage`] without a copy.
+pub struct ImageView<'a, const C: usize> {
+    // the pixels belong to the buffer, so the image must never free them
+    image: ManuallyDrop<Image<u8, C>>,
+    _data: PhantomData<&'a [u8]>,
+}
+
+impl<const C: usize> Deref for ImageView<'_, C> {
+    type Target = Image<u8, C>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.image
+    }
+}
+
+impl JsImage {
+    /// The size of the image.
+    pub fn size(&self) -> ImageSize {
+        ImageSize {
+            width: self.width as usize,
+            height: self.height as usize,
+        }
+    }
+
+    /// Borrow the image as a kornia image with `C` channels.
+    pub fn view<const C: usize>(&self) -> napi::Result<ImageView<'_, C>> {
+        if self.channels as usize != C {
+            return Err(napi::Error::from_reason(format!(
+                "expected an image with {} channels, got {}",
+                C, self.channels
+            )));
+        }
+
+        let size = self.size();
+        let expected = size.width * size.height * C;
+        if self.data.len() != expected {
+            return Err(napi::Error::from_reason(format!(
+                "expected {} bytes of data, got {}",
+                expected,
+                self.data.len()
+            )));
+        }
+
+        // SAFETY: the length matches the size and the buffer outlives the view
+        let image = unsafe { Image::from_raw_parts(size, self.data.as_ptr(), self.data.len()) }
+            .map_err(|e| napi::Error::from_reason(format!("src image: {}", e)))?;
+
+        Ok(ImageView {
+            image: ManuallyDrop::new(image),
+            _data: PhantomData,
+        })
+    }
+}
+
+/// A trait to convert a kornia image to a [`JsImage`].
+pub trait ToJsImage {
+    /// Convert the image, moving its pixels to the buffer without a copy.
+    fn to_js_image(self) -> JsImage;
+}
+
+impl<const C: usize> ToJsImage for Image<u8, C> {
+    fn to_js_image(self) -> JsImage {
+        let size = self.size();
+        JsImage {
+            width: size.width as u32,
+            height: size.height as u32,
+            channels: C as u32,
+            data: self.0.into_vec().into(),
+        }
+    }
+}
@@ -0,0 +1,39 @@
+use kornia_io::functional as F;
+use napi::bindgen_prelude::Buffer;
+use napi_derive::napi;
+
+use crate::image::{JsImage, ToJsImage};
+
+/// Decode an encoded image, e.g. JPEG or PNG, to an RGB image.
+///
+/// # Arguments
+///
+/// * `data` - The encoded bytes.
+///
+/// # Returns
+///
+/// The decoded RGB image.
+#[napi]
+pub fn decode_image(data: Buffer) -> napi::Result<JsImage> {
+    let image = F::decode_image_any_rgb8(&data)
+        .map_err(|e| napi::Error::from_reason(format!("failed to decode image: {}", e)))?;
+
+    Ok(image.to_js_image())
+}
+
+/// Read an image file to an RGB image.
+///
+/// # Arguments
+///
+/// * `file_path` - The path to the image file.
+///
+/// # Returns
+///
+/// The RGB image.
+#[napi]
+pub fn read_image(file_path: String) -> napi::Result<JsImage> {
+    let image = F::read_image_any_rgb8(&file_path)
+        .map_err(|e| napi::Error::from_reason(format!("failed to read image: {}", e)))?;
+
+    Ok(image.to_js_image())
+}
@@ -0,0 +1,13 @@
+mod color;
+mod features;
+mod image;
+mod io;
+mod resize;
+
+use napi_derive::napi;
+
+/// The version of the package.
+#[napi]
+pub fn version() -> String {
+    env!("CARGO_PKG_VERSION").to_string()
+}
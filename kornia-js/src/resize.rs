@@ -0,0 +1,80 @@
+use kornia_image::{Image, ImageSize};
+use kornia_imgproc::{interpolation::InterpolationMode, resize};
+use napi_derive::napi;
+
+use crate::image::{JsImage, ToJsImage};
+
+// resize through the float implementation, for the images without a fast path
+fn resize_native<const C: usize>(
+    image: &JsImage,
+    new_size: ImageSize,
+    interpolation: InterpolationMode,
+) -> napi::Result<JsImage> {
+    let image = image
+        .view::<C>()?
+        .cast::<f32>()
+        .map_err(|e| napi::Error::from_reason(format!("src image: {}", e)))?;
+
+    let mut image_resized = Image::from_size_val(new_size, 0f32)
+        .map_err(|e| napi::Error::from_reason(format!("dst image: {}", e)))?;
+
+    resize::resize_native(&image, &mut image_resized, interpolation)
+        .map_err(|e| napi::Error::from_reason(format!("failed to resize image: {}", e)))?;
+
+    let image_resized = image_resized
+        .map(|&v| v.round().clamp(0.0, 255.0) as u8)
+        .map_err(|e| napi::Error::from_reason(format!("dst image: {}", e)))?;
+
+    Ok(image_resized.to_js_image())
+}
+
+/// Resize an image.
+///
+/// # Arguments
+///
+/// * `image` - The image with 1, 3 or 4 channels.
+/// * `width` - The width of the resized image.
+/// * `height` - The height of the resized image.
+/// * `interpolation` - The interpolation mode, `"nearest"` or `"bilinear"`.
+///
+/// # Returns
+///
+/// The resized image.
+#[napi]
+pub fn resize(
+    image: JsImage,
+    width: u32,
+    height: u32,
+    interpolation: String,
+) -> napi::Result<JsImage> {
+    let new_size = ImageSize {
+        width: width as usize,
+        height: height as usize,
+    };
+
+    let interpolation = match interpolation.to_lowercase().as_str() {
+        "nearest" => InterpolationMode::Nearest,
+        "bilinear" => InterpolationMode::Bilinear,
+        _ => return Err(napi::Error::from_reason("Invalid interpolation mode")),
+    };
+
+    match image.channels {
+        1 => resize_native::<1>(&image, new_size, interpolation),
+        3 => {
+            let image = image.view::<3>()?;
+
+            let mut image_resized = Image::from_size_val(new_size, 0u8)
+                .map_err(|e| napi::Error::from_reason(format!("dst image: {}", e)))?;
+
+            resize::resize_fast(&image, &mut image_resized, interpolation)
+                .map_err(|e| napi::Error::from_reason(format!("failed to resize image: {}", e)))?;
+
+            Ok(image_resized.to_js_image())
+        }
+        4 => resize_native::<4>(&image, new_size, interpolation),
+        channels => Err(napi::Error::from_reason(format!(
+            "unsupported number of channels {}, expected 1, 3 or 4",
+            channels
+        ))),
+    }
+}
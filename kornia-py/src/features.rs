@@ -0,0 +1,105 @@
+use pyo3::prelude::*;
+
+use crate::image::{FromPyImage, PyImage, PyImageF32, ToPyImageF32};
+use kornia_image::{Image, ImageError};
+use kornia_imgproc::features;
+
+// compute a response of a grayscale float image with shape (H, W, 1)
+fn compute_response(
+    image: PyImageF32,
+    op: impl FnOnce(&Image<f32, 1>, &mut Image<f32, 1>) -> Result<(), ImageError>,
+) -> PyResult<PyImageF32> {
+    let src: Image<f32, 1> = Image::from_pyimage(image)
+        .map_err(|e| PyErr::new::<pyo3::exceptions::PyException, _>(format!("src image: {}", e)))?;
+
+    let mut dst = Image::from_size_val(src.size(), 0f32)
+        .map_err(|e| PyErr::new::<pyo3::exceptions::PyException, _>(format!("dst image: {}", e)))?;
+
+    op(&src, &mut dst).map_err(|e| {
+        PyErr::new::<pyo3::exceptions::PyException, _>(format!("failed to compute response: {}", e))
+    })?;
+
+    Ok(dst.to_pyimage_f32())
+}
+
+/// Compute the Harris corner response of an image.
+/// --
+///
+/// # Arguments
+///
+/// * `image` - The input image with shape (H, W, 1) and dtype float32.
+/// * `k` - The sensitivity of the response, usually between 0.04 and 0.06.
+///
+/// # Returns
+///
+/// The response with shape (H, W, 1).
+#[pyfunction]
+#[pyo3(signature = (image, k=0.04))]
+pub fn harris_response(image: PyImageF32, k: f32) -> PyResult<PyImageF32> {
+    compute_response(image, |src, dst| {
+        features::HarrisResponse::new(src.size())
+            .with_k(k)
+            .compute(src, dst)
+    })
+}
+
+/// Compute the Shi-Tomasi (good features to track) response of an image.
+/// --
+///
+/// # Arguments
+///
+/// * `image` - The input image with shape (H, W, 1) and dtype float32.
+///
+/// # Returns
+///
+/// The response with shape (H, W, 1).
+#[pyfunction]
+pub fn gftt_response(image: PyImageF32) -> PyResult<PyImageF32> {
+    compute_response(image, |src, dst| {
+        features::gftt_response(src, dst, features::GradsMode::Sobel)
+    })
+}
+
+/// Compute the determinant of the Hessian response of an image.
+/// --
+///
+/// # Arguments
+///
+/// * `image` - The input image with shape (H, W, 1) and dtype float32.
+///
+/// # Returns
+///
+/// The response with shape (H, W, 1).
+#[pyfunction]
+pub fn hessian_response(image: PyImageF32) -> PyResult<PyImageF32> {
+    compute_response(image, |src, dst| {
+        features::hessian_response(src, dst, features::GradsMode::Diff)
+    })
+}
+
+/// Detect FAST corners in an image.
+/// --
+///
+/// # Arguments
+///
+/// * `image` - The input image with shape (H, W, 1) and dtype uint8.
+/// * `threshold` - The intensity difference to the center pixel.
+/// * `arc_length` - The number of consecutive brighter or darker pixels on the circle.
+///
+/// # Returns
+///
+/// The corners as a list of (x, y).
+#[pyfunction]
+#[pyo3(signature = (image, threshold, arc_length=9))]
+pub fn fast_feature_detector(
+    image: PyImage,
+    threshold: u8,
+    arc_length: u8,
+) -> PyResult<Vec<[i32; 2]>> {
+    let image: Image<u8, 1> = Image::from_pyimage(image)
+        .map_err(|e| PyErr::new::<pyo3::exceptions::PyException, _>(format!("src image: {}", e)))?;
+
+    features::fast_feature_detector(&image, threshold, arc_length).map_err(|e| {
+        PyErr::new::<pyo3::exceptions::PyException, _>(format!("failed to detect corners: {}", e))
+    })
+}
@@ -0,0 +1,99 @@
+use pyo3::prelude::*;
+
+use crate::image::{num_channels, FromPyImage, PyImageF32, ToPyImageF32};
+use kornia_image::{Image, ImageError};
+use kornia_imgproc::filter;
+
+// apply a filter to a float image with shape (H, W, C)
+fn apply_filter<const C: usize>(
+    image: PyImageF32,
+    op: impl Fn(&Image<f32, C>, &mut Image<f32, C>) -> Result<(), ImageError>,
+) -> PyResult<PyImageF32> {
+    let src: Image<f32, C> = Image::from_pyimage(image)
+        .map_err(|e| PyErr::new::<pyo3::exceptions::PyException, _>(format!("src image: {}", e)))?;
+
+    let mut dst = Image::from_size_val(src.size(), 0f32)
+        .map_err(|e| PyErr::new::<pyo3::exceptions::PyException, _>(format!("dst image: {}", e)))?;
+
+    op(&src, &mut dst).map_err(|e| {
+        PyErr::new::<pyo3::exceptions::PyException, _>(format!("failed to filter image: {}", e))
+    })?;
+
+    Ok(dst.to_pyimage_f32())
+}
+
+fn unsupported_channels(channels: usize) -> PyErr {
+    PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+        "Unsupported number of channels {}, expected 1 or 3",
+        channels
+    ))
+}
+
+/// Blur an image with a gaussian kernel.
+/// --
+///
+/// # Arguments
+///
+/// * `image` - The input image with shape (H, W, C) and dtype float32, with C 1 or 3.
+/// * `kernel_size` - The size of the kernel as (kernel_x, kernel_y).
+/// * `sigma` - The standard deviation of the kernel as (sigma_x, sigma_y).
+///
+/// # Returns
+///
+/// The blurred image with the same shape.
+#[pyfunction]
+pub fn gaussian_blur(
+    image: PyImageF32,
+    kernel_size: (usize, usize),
+    sigma: (f32, f32),
+) -> PyResult<PyImageF32> {
+    match num_channels(&image) {
+        1 => apply_filter::<1>(image, |src, dst| {
+            filter::gaussian_blur(src, dst, kernel_size, sigma)
+        }),
+        3 => apply_filter::<3>(image, |src, dst| {
+            filter::gaussian_blur(src, dst, kernel_size, sigma)
+        }),
+        c => Err(unsupported_channels(c)),
+    }
+}
+
+/// Blur an image with a box kernel.
+/// --
+///
+/// # Arguments
+///
+/// * `image` - The input image with shape (H, W, C) and dtype float32, with C 1 or 3.
+/// * `kernel_size` - The size of the kernel as (kernel_x, kernel_y).
+///
+/// # Returns
+///
+/// The blurred image with the same shape.
+#[pyfunction]
+pub fn box_blur(image: PyImageF32, kernel_size: (usize, usize)) -> PyResult<PyImageF32> {
+    match num_channels(&image) {
+        1 => apply_filter::<1>(image, |src, dst| filter::box_blur(src, dst, kernel_size)),
+        3 => apply_filter::<3>(image, |src, dst| filter::box_blur(src, dst, kernel_size)),
+        c => Err(unsupported_channels(c)),
+    }
+}
+
+/// Compute the magnitude of the sobel gradients of an image.
+/// --
+///
+/// # Arguments
+///
+/// * `image` - The input image with shape (H, W, C) and dtype float32, with C 1 or 3.
+/// * `kernel_size` - The size of the sobel kernel.
+///
+/// # Returns
+///
+/// The gradient magnitude with the same shape.
+#[pyfunction]
+pub fn sobel(image: PyImageF32, kernel_size: usize) -> PyResult<PyImageF32> {
+    match num_channels(&image) {
+        1 => apply_filter::<1>(image, |src, dst| filter::sobel(src, dst, kernel_size)),
+        3 => apply_filter::<3>(image, |src, dst| filter::sobel(src, dst, kernel_size)),
+        c => Err(unsupported_channels(c)),
+    }
+}
@@ -57,6 +57,7 @@ impl PyICPResult {
             translation: [0.0, 0.0, 0.0],
             num_iterations: 0,
             rmse: 0.0,
+            fitness: 0.0,
         }))
     }
 
@@ -80,17 +81,22 @@ impl PyICPResult {
         self.0.rmse
     }
 
+    #[getter]
+    pub fn fitness(&self) -> f64 {
+        self.0.fitness
+    }
+
     fn __str__(&self) -> PyResult<String> {
         Ok(format!(
-            "ICPResult(rotation: {:?}, translation: {:?}, num_iterations: {}, rmse: {})",
-            self.0.rotation, self.0.translation, self.0.num_iterations, self.0.rmse
+            "ICPResult(rotation: {:?}, translation: {:?}, num_iterations: {}, rmse: {}, fitness: {})",
+            self.0.rotation, self.0.translation, self.0.num_iterations, self.0.rmse, self.0.fitness
         ))
     }
 
     fn __repr__(&self) -> PyResult<String> {
         Ok(format!(
-            "ICPResult(rotation: {:?}, translation: {:?}, num_iterations: {}, rmse: {})",
-            self.0.rotation, self.0.translation, self.0.num_iterations, self.0.rmse
+            "ICPResult(rotation: {:?}, translation: {:?}, num_iterations: {}, rmse: {}, fitness: {})",
+            self.0.rotation, self.0.translation, self.0.num_iterations, self.0.rmse, self.0.fitness
         ))
     }
 }
@@ -1,4 +1,4 @@
-use numpy::{PyArray, PyArray3, PyArrayMethods, PyUntypedArrayMethods};
+use numpy::{PyArray, PyArray1, PyArray3, PyArrayMethods, PyUntypedArrayMethods};
 
 use kornia_image::{Image, ImageError, ImageSize};
 use pyo3::prelude::*;
@@ -6,6 +6,9 @@ use pyo3::prelude::*;
 // type alias for a 3D numpy array of u8
 pub type PyImage = Py<PyArray3<u8>>;
 
+// type alias for a 3D numpy array of f32
+pub type PyImageF32 = Py<PyArray3<f32>>;
+
 /// Trait to convert an image to a PyImage (3D numpy array of u8)
 pub trait ToPyImage {
     fn to_pyimage(self) -> PyImage;
@@ -13,11 +16,14 @@ pub trait ToPyImage {
 
 impl<const C: usize> ToPyImage for Image<u8, C> {
     fn to_pyimage(self) -> PyImage {
-        Python::with_gil(|py| unsafe {
-            let array = PyArray::<u8, _>::new(py, [self.height(), self.width(), C], false);
-            // TODO: verify that the data is contiguous, otherwise iterate over the image and copy
-            std::ptr::copy_nonoverlapping(self.as_ptr(), array.data(), self.numel());
-            array.unbind()
+        let shape = [self.height(), self.width(), C];
+        Python::with_gil(|py| {
+            // the buffer of the image is moved to numpy without copying
+            let array = PyArray1::from_vec(py, self.0.into_vec());
+            array
+                .reshape(shape)
+                .expect("the buffer has the size of the image")
+                .unbind()
         })
     }
 }
@@ -41,6 +47,25 @@ impl<const C: usize> ToPyImage for Image<u16, C> {
     }
 }
 
+/// Trait to convert an image to a PyImageF32 (3D numpy array of f32)
+pub trait ToPyImageF32 {
+    fn to_pyimage_f32(self) -> PyImageF32;
+}
+
+impl<const C: usize> ToPyImageF32 for Image<f32, C> {
+    fn to_pyimage_f32(self) -> PyImageF32 {
+        let shape = [self.height(), self.width(), C];
+        Python::with_gil(|py| {
+            // the buffer of the image is moved to numpy without copying
+            let array = PyArray1::from_vec(py, self.0.into_vec());
+            array
+                .reshape(shape)
+                .expect("the buffer has the size of the image")
+                .unbind()
+        })
+    }
+}
+
 /// Trait to convert a PyImage (3D numpy array of u8) to an image
 pub trait FromPyImage<I, T, const C: usize> {
     fn from_pyimage(image: I) -> Result<Image<T, C>, ImageError>;
@@ -94,6 +119,31 @@ impl<const C: usize> FromPyImage<PyImage, u16, C> for Image<u16, C> {
     }
 }
 
+impl<const C: usize> FromPyImage<PyImageF32, f32, C> for Image<f32, C> {
+    fn from_pyimage(image: PyImageF32) -> Result<Image<f32, C>, ImageError> {
+        Python::with_gil(|py| {
+            let pyarray = image.bind(py);
+
+            let data = match pyarray.to_vec() {
+                Ok(d) => d,
+                Err(_) => return Err(ImageError::ImageDataNotContiguous),
+            };
+
+            let size = ImageSize {
+                width: pyarray.shape()[1],
+                height: pyarray.shape()[0],
+            };
+
+            Image::new(size, data)
+        })
+    }
+}
+
+/// The number of channels of a numpy image with shape (H, W, C).
+pub fn num_channels<T: numpy::Element>(image: &Py<PyArray3<T>>) -> usize {
+    Python::with_gil(|py| image.bind(py).shape()[2])
+}
+
 fn convert_buf_u8_u16(buf: Vec<u8>) -> Vec<u16> {
     let mut buf_u16 = Vec::with_capacity(buf.len() / 2);
     for chunk in buf.chunks_exact(2) {
@@ -1,11 +1,14 @@
 mod color;
 mod enhance;
+mod features;
+mod filter;
 mod histogram;
 mod icp;
 mod image;
 mod io;
 mod pointcloud;
 mod resize;
+mod tracking;
 mod warp;
 
 use crate::icp::{PyICPConvergenceCriteria, PyICPResult};
@@ -41,6 +44,16 @@ pub fn kornia_rs(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_function(wrap_pyfunction!(warp::warp_affine, m)?)?;
     m.add_function(wrap_pyfunction!(warp::warp_perspective, m)?)?;
     m.add_function(wrap_pyfunction!(histogram::compute_histogram, m)?)?;
+    m.add_function(wrap_pyfunction!(filter::gaussian_blur, m)?)?;
+    m.add_function(wrap_pyfunction!(filter::box_blur, m)?)?;
+    m.add_function(wrap_pyfunction!(filter::sobel, m)?)?;
+    m.add_function(wrap_pyfunction!(features::harris_response, m)?)?;
+    m.add_function(wrap_pyfunction!(features::gftt_response, m)?)?;
+    m.add_function(wrap_pyfunction!(features::hessian_response, m)?)?;
+    m.add_function(wrap_pyfunction!(features::fast_feature_detector, m)?)?;
+    m.add_function(wrap_pyfunction!(tracking::mean_shift, m)?)?;
+    m.add_function(wrap_pyfunction!(tracking::cam_shift, m)?)?;
+    m.add_function(wrap_pyfunction!(tracking::track_points_lk, m)?)?;
     m.add_function(wrap_pyfunction!(decode_image_png, m)?)?;
     m.add_function(wrap_pyfunction!(decode_image_jpeg, m)?)?;
     m.add_function(wrap_pyfunction!(decode_image_raw_jpeg, m)?)?;
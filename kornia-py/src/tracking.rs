@@ -0,0 +1,189 @@
+use pyo3::prelude::*;
+
+use crate::image::{FromPyImage, PyImage, PyImageF32};
+use kornia_image::Image;
+use kornia_imgproc::video;
+
+// a rotated rectangle as ((center_x, center_y), (length, width), angle)
+type PyRotatedRect = ((f32, f32), (f32, f32), f32);
+
+// a tracked point as ((x, y), tracked, error)
+type PyTrackedPoint = ((f32, f32), bool, f32);
+
+fn from_pyprob(prob: PyImage) -> PyResult<Image<u8, 1>> {
+    Image::from_pyimage(prob)
+        .map_err(|e| PyErr::new::<pyo3::exceptions::PyException, _>(format!("prob image: {}", e)))
+}
+
+/// Move a window to the peak of a probability image with the mean shift.
+/// --
+///
+/// # Arguments
+///
+/// * `prob` - The probability image with shape (H, W, 1) and dtype uint8, e.g. a histogram
+///   back-projection.
+/// * `window` - The initial window as (x, y, width, height).
+/// * `max_iterations` - The maximum number of iterations.
+/// * `epsilon` - The shift of the window in pixels below which the iterations stop.
+///
+/// # Returns
+///
+/// The final window and the number of iterations.
+#[pyfunction]
+#[pyo3(signature = (prob, window, max_iterations=10, epsilon=1.0))]
+pub fn mean_shift(
+    prob: PyImage,
+    window: [usize; 4],
+    max_iterations: usize,
+    epsilon: f32,
+) -> PyResult<([usize; 4], usize)> {
+    let prob = from_pyprob(prob)?;
+    let criteria = video::MeanShiftCriteria {
+        max_iterations,
+        epsilon,
+    };
+
+    video::mean_shift(&prob, window, &criteria).map_err(|e| {
+        PyErr::new::<pyo3::exceptions::PyException, _>(format!("failed to track window: {}", e))
+    })
+}
+
+/// Track an object in a probability image with the continuously adaptive mean shift.
+/// --
+///
+/// # Arguments
+///
+/// * `prob` - The probability image with shape (H, W, 1) and dtype uint8, e.g. a histogram
+///   back-projection.
+/// * `window` - The initial window as (x, y, width, height).
+/// * `max_iterations` - The maximum number of iterations.
+/// * `epsilon` - The shift of the window in pixels below which the iterations stop.
+///
+/// # Returns
+///
+/// The object as ((center_x, center_y), (length, width), angle) and the window to search
+/// in the next frame.
+#[pyfunction]
+#[pyo3(signature = (prob, window, max_iterations=10, epsilon=1.0))]
+pub fn cam_shift(
+    prob: PyImage,
+    window: [usize; 4],
+    max_iterations: usize,
+    epsilon: f32,
+) -> PyResult<(PyRotatedRect, [usize; 4])> {
+    let prob = from_pyprob(prob)?;
+    let criteria = video::MeanShiftCriteria {
+        max_iterations,
+        epsilon,
+    };
+
+    let (object, window) = video::cam_shift(&prob, window, &criteria).map_err(|e| {
+        PyErr::new::<pyo3::exceptions::PyException, _>(format!("failed to track object: {}", e))
+    })?;
+
+    let rect = (
+        (object.center[0], object.center[1]),
+        (object.size[0], object.size[1]),
+        object.angle,
+    );
+
+    Ok((rect, window))
+}
+
+/// Track points between two images with the pyramidal Lucas-Kanade optical flow.
+/// --
+///
+/// # Arguments
+///
+/// * `prev` - The previous image with shape (H, W, 1) and dtype float32.
+/// * `next` - The next image with shape (H, W, 1) and dtype float32.
+/// * `points` - The positions (x, y) of the points in the previous image.
+/// * `guesses` - The predicted positions (x, y) of the points in the next image, or None to
+///   start from the previous positions.
+/// * `window_size` - The width and height of the tracked window in pixels, odd.
+/// * `num_levels` - The number of levels of the pyramids.
+/// * `max_iterations` - The maximum number of iterations at every level.
+/// * `epsilon` - The update of the flow in pixels below which the iterations stop.
+/// * `min_eigenvalue` - The minimum eigenvalue of the gradient matrix of a window, per pixel.
+/// * `residual` - The residual between the windows: "intensity", "zncc" or "gain_bias".
+///
+/// # Returns
+///
+/// The tracked points as ((x, y), tracked, error), in the order of the points.
+#[pyfunction]
+#[pyo3(signature = (
+    prev,
+    next,
+    points,
+    guesses=None,
+    window_size=21,
+    num_levels=3,
+    max_iterations=30,
+    epsilon=0.01,
+    min_eigenvalue=1e-4,
+    residual="intensity",
+))]
+#[allow(clippy::too_many_arguments)]
+pub fn track_points_lk(
+    prev: PyImageF32,
+    next: PyImageF32,
+    points: Vec<(f32, f32)>,
+    guesses: Option<Vec<(f32, f32)>>,
+    window_size: usize,
+    num_levels: usize,
+    max_iterations: usize,
+    epsilon: f32,
+    min_eigenvalue: f32,
+    residual: &str,
+) -> PyResult<Vec<PyTrackedPoint>> {
+    let prev: Image<f32, 1> = Image::from_pyimage(prev).map_err(|e| {
+        PyErr::new::<pyo3::exceptions::PyException, _>(format!("prev image: {}", e))
+    })?;
+    let next: Image<f32, 1> = Image::from_pyimage(next).map_err(|e| {
+        PyErr::new::<pyo3::exceptions::PyException, _>(format!("next image: {}", e))
+    })?;
+
+    let residual = match residual.to_lowercase().as_str() {
+        "intensity" => video::LkResidual::Intensity,
+        "zncc" => video::LkResidual::ZeroMeanNcc,
+        "gain_bias" => video::LkResidual::GainBias,
+        _ => {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                "Invalid residual",
+            ))
+        }
+    };
+
+    let guesses = guesses.unwrap_or_else(|| points.clone());
+    if guesses.len() != points.len() {
+        return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+            "expected {} guesses, got {}",
+            points.len(),
+            guesses.len()
+        )));
+    }
+    let points = points
+        .iter()
+        .zip(&guesses)
+        .map(|(p, g)| ([p.0, p.1], [g.0, g.1]))
+        .collect::<Vec<_>>();
+
+    let params = video::LkParams {
+        window_size,
+        num_levels,
+        max_iterations,
+        epsilon,
+        min_eigenvalue,
+        residual,
+    };
+
+    let tracked =
+        video::track_points_lk_with_guesses(&prev, &next, &points, &params).map_err(|e| {
+            PyErr::new::<pyo3::exceptions::PyException, _>(format!("failed to track points: {}", e))
+        })?;
+
+    Ok(tracked
+        .into_iter()
+        .map(|t| ((t.position[0], t.position[1]), t.tracked, t.error))
+        .collect())
+}
@@ -0,0 +1,36 @@
+import kornia_rs as K
+
+import numpy as np
+
+
+def test_harris_response():
+    img = np.zeros((9, 9, 1), dtype=np.float32)
+    img[4:, 4:, 0] = 1.0
+
+    response: np.ndarray = K.harris_response(img)
+    assert response.shape == (9, 9, 1)
+    assert response.dtype == np.float32
+
+
+def test_gftt_response():
+    img = np.zeros((9, 9, 1), dtype=np.float32)
+    img[4:, 4:, 0] = 1.0
+
+    response: np.ndarray = K.gftt_response(img)
+    assert response.shape == (9, 9, 1)
+
+
+def test_hessian_response():
+    img = np.zeros((9, 9, 1), dtype=np.float32)
+
+    response: np.ndarray = K.hessian_response(img)
+    assert response.shape == (9, 9, 1)
+    assert (response == 0.0).all()
+
+
+def test_fast_feature_detector():
+    img = np.zeros((7, 7, 1), dtype=np.uint8)
+    img[3, 3, 0] = 255
+
+    keypoints: list[list[int]] = K.fast_feature_detector(img, threshold=100)
+    assert [list(kp) for kp in keypoints] == [[3, 3]]
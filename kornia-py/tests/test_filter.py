@@ -0,0 +1,30 @@
+import kornia_rs as K
+
+import numpy as np
+
+
+def test_gaussian_blur():
+    img = np.zeros((5, 5, 1), dtype=np.float32)
+    img[2, 2, 0] = 1.0
+
+    img_blurred: np.ndarray = K.gaussian_blur(img, (3, 3), (1.0, 1.0))
+    assert img_blurred.shape == (5, 5, 1)
+    assert img_blurred.dtype == np.float32
+    assert np.isclose(img_blurred.sum(), 1.0, atol=1e-5)
+    assert img_blurred[2, 2, 0] == img_blurred.max()
+
+
+def test_box_blur():
+    img = np.ones((4, 6, 3), dtype=np.float32)
+
+    img_blurred: np.ndarray = K.box_blur(img, (3, 3))
+    assert img_blurred.shape == (4, 6, 3)
+    assert np.allclose(img_blurred[1:-1, 1:-1], 1.0)
+
+
+def test_sobel():
+    img = np.ones((6, 6, 1), dtype=np.float32)
+
+    img_sobel: np.ndarray = K.sobel(img, 3)
+    assert img_sobel.shape == (6, 6, 1)
+    assert np.allclose(img_sobel[2:-2, 2:-2], 0.0)
@@ -0,0 +1,53 @@
+import kornia_rs as K
+
+import numpy as np
+import pytest
+
+
+def test_mean_shift():
+    prob = np.zeros((40, 40, 1), dtype=np.uint8)
+    prob[20:30, 22:32, 0] = 255
+
+    window, num_iterations = K.mean_shift(prob, (14, 14, 10, 10))
+    assert abs(window[0] - 22) <= 1 and abs(window[1] - 20) <= 1
+    assert tuple(window[2:]) == (10, 10)
+    assert num_iterations > 0
+
+
+def test_cam_shift():
+    prob = np.zeros((40, 40, 1), dtype=np.uint8)
+    prob[20:30, 22:32, 0] = 255
+
+    (center, size, angle), window = K.cam_shift(prob, (14, 14, 10, 10))
+    assert abs(center[0] - 26.5) < 1.0
+    assert abs(center[1] - 24.5) < 1.0
+    assert len(window) == 4
+
+
+def test_track_points_lk():
+    # a smooth texture translated by (3, -2)
+    ys, xs = np.mgrid[0:80, 0:96].astype(np.float32)
+
+    def texture(dx, dy):
+        x, y = xs - dx, ys - dy
+        value = 0.5 + 0.25 * np.sin(x / 5.0) * np.cos(y / 7.0) + 0.2 * np.sin((x + y) / 9.0)
+        return value[..., None].astype(np.float32)
+
+    prev, next = texture(0.0, 0.0), texture(3.0, -2.0)
+    points = [(30.0, 30.0), (48.0, 40.0)]
+
+    for residual in ["intensity", "zncc", "gain_bias"]:
+        tracked = K.track_points_lk(prev, next, points, residual=residual)
+        assert len(tracked) == 2
+        for (x, y), (position, ok, error) in zip(points, tracked):
+            assert ok
+            assert abs(position[0] - x - 3.0) < 0.1
+            assert abs(position[1] - y + 2.0) < 0.1
+            assert error < 0.01
+
+    guesses = [(33.0, 28.0), (51.0, 38.0)]
+    tracked = K.track_points_lk(prev, next, points, guesses=guesses, num_levels=1)
+    assert all(ok for _, ok, _ in tracked)
+
+    with pytest.raises(ValueError):
+        K.track_points_lk(prev, next, points, guesses=guesses[:1])
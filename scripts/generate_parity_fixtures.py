@@ -0,0 +1,145 @@
+"""Generate the reference outputs of the parity tests of kornia-imgproc.
+
+The inputs and the expected outputs are written as `.npy` files to `tests/data/parity` and
+read by `crates/kornia-imgproc/tests/parity.rs`. The references are computed with OpenCV and
+Kornia, configured to match the conventions of the Rust operators, e.g. the border handling.
+The fixtures are checked in, commit them again after changing this script.
+
+Usage:
+    pip install numpy opencv-python-headless torch kornia
+    python scripts/generate_parity_fixtures.py
+"""
+
+from pathlib import Path
+
+import cv2
+import kornia
+import numpy as np
+import torch
+
+OUTPUT_DIR = Path(__file__).resolve().parents[1] / "tests" / "data" / "parity"
+
+HEIGHT, WIDTH = 48, 64
+BLOCK = 8
+
+
+def save(name: str, array: np.ndarray) -> None:
+    np.save(OUTPUT_DIR / f"{name}.npy", np.ascontiguousarray(array))
+    print(f"wrote {name}.npy {array.dtype} {array.shape}")
+
+
+def make_input() -> np.ndarray:
+    # blocks of ramps so that the image has both edges and smooth regions, the integer
+    # pattern keeps the fixtures reproducible across the numpy and OpenCV versions
+    ys, xs, cs = np.meshgrid(np.arange(HEIGHT), np.arange(WIDTH), np.arange(3), indexing="ij")
+    bx, by = xs // BLOCK, ys // BLOCK
+    block = (bx * 97 + by * 61 + cs * 151 + bx * by * 13) % 201
+    image = block + (xs % BLOCK) * 3 + (ys % BLOCK) * 3
+    return image.astype(np.uint8)
+
+
+def to_tensor(image: np.ndarray) -> torch.Tensor:
+    # (H, W, C) -> (1, C, H, W)
+    return torch.from_numpy(image).permute(2, 0, 1)[None].double()
+
+
+def from_tensor(tensor: torch.Tensor) -> np.ndarray:
+    return tensor[0].permute(1, 2, 0).numpy()
+
+
+def separable(image: np.ndarray, kernel_x: np.ndarray, kernel_y: np.ndarray) -> np.ndarray:
+    # correlation with a zero border, as `separable_filter`
+    return cv2.sepFilter2D(
+        image.astype(np.float64),
+        -1,
+        kernel_x.astype(np.float64),
+        kernel_y.astype(np.float64),
+        borderType=cv2.BORDER_CONSTANT,
+    )
+
+
+def interior(response: np.ndarray) -> np.ndarray:
+    # the responses only write the interior pixels
+    out = np.zeros_like(response)
+    out[1:-1, 1:-1] = response[1:-1, 1:-1]
+    return out
+
+
+def structure_tensor(gray: np.ndarray) -> tuple:
+    # normalized Sobel gradients of the interior pixels summed over a 3x3 window
+    dx = interior(separable(gray, np.array([-1.0, 0.0, 1.0]), np.array([1.0, 2.0, 1.0])) / 8.0)
+    dy = interior(separable(gray, np.array([1.0, 2.0, 1.0]), np.array([-1.0, 0.0, 1.0])) / 8.0)
+    window = np.ones(3)
+    return (
+        separable(dx * dx, window, window),
+        separable(dy * dy, window, window),
+        separable(dx * dy, window, window),
+    )
+
+
+def main() -> None:
+    OUTPUT_DIR.mkdir(parents=True, exist_ok=True)
+
+    rgb_u8 = make_input()
+    rgb = rgb_u8.astype(np.float32) / 255.0
+    gray = cv2.cvtColor(rgb, cv2.COLOR_RGB2GRAY).astype(np.float64)
+    save("input_rgb_u8", rgb_u8)
+    save("input_rgb_f32", rgb)
+    save("input_gray_f32", gray.astype(np.float32))
+
+    # blur
+    gaussian = cv2.getGaussianKernel(5, 1.5)[:, 0]
+    save("gaussian_blur_5_1.5", separable(rgb, gaussian, gaussian).astype(np.float32))
+    box = np.full(5, 1.0 / 5.0)
+    save("box_blur_5", separable(rgb, box, box).astype(np.float32))
+
+    # gradients
+    gx = cv2.Sobel(rgb.astype(np.float64), -1, 1, 0, ksize=3, borderType=cv2.BORDER_CONSTANT)
+    gy = cv2.Sobel(rgb.astype(np.float64), -1, 0, 1, ksize=3, borderType=cv2.BORDER_CONSTANT)
+    save("sobel_3", np.sqrt(gx * gx + gy * gy).astype(np.float32))
+
+    grad = kornia.filters.spatial_gradient(to_tensor(rgb), mode="sobel", order=1, normalized=True)
+    save("spatial_gradient_dx", from_tensor(grad[:, :, 0]).astype(np.float32))
+    save("spatial_gradient_dy", from_tensor(grad[:, :, 1]).astype(np.float32))
+
+    # responses
+    dxx = separable(gray, np.array([1.0, -2.0, 1.0]), np.array([0.0, 1.0, 0.0]))
+    dyy = separable(gray, np.array([0.0, 1.0, 0.0]), np.array([1.0, -2.0, 1.0]))
+    dxy = separable(gray, np.array([-1.0, 0.0, 1.0]), np.array([-1.0, 0.0, 1.0])) * 0.25
+    save("hessian_response", interior(dxx * dyy - dxy * dxy).astype(np.float32))
+
+    m11, m22, m12 = structure_tensor(gray)
+    trace = m11 + m22
+    harris = np.maximum(0.0, m11 * m22 - m12 * m12 - 0.04 * trace * trace)
+    save("harris_response", interior(harris).astype(np.float32))
+    min_eigenvalue = 0.5 * trace - np.sqrt((0.5 * (m11 - m22)) ** 2 + m12 * m12)
+    save("gftt_response", interior(min_eigenvalue).astype(np.float32))
+
+    # warps, the matrix maps the source to the destination pixel coordinates
+    m = cv2.getRotationMatrix2D((WIDTH / 2.0, HEIGHT / 2.0), 30.0, 0.8).astype(np.float32)
+    save("warp_affine_matrix", m.reshape(1, 6))
+    warped = kornia.geometry.transform.warp_affine(
+        to_tensor(rgb),
+        torch.from_numpy(m)[None].double(),
+        (HEIGHT, WIDTH),
+        mode="bilinear",
+        padding_mode="zeros",
+        align_corners=True,
+    )
+    save("warp_affine_bilinear", from_tensor(warped).astype(np.float32))
+    # the pixels sampled inside the source image, the border conventions differ outside
+    m_inv = cv2.invertAffineTransform(m.astype(np.float64))
+    xs, ys = np.meshgrid(np.arange(WIDTH), np.arange(HEIGHT))
+    u = m_inv[0, 0] * xs + m_inv[0, 1] * ys + m_inv[0, 2]
+    v = m_inv[1, 0] * xs + m_inv[1, 1] * ys + m_inv[1, 2]
+    valid = (u >= 0.0) & (u <= WIDTH - 1.0) & (v >= 0.0) & (v <= HEIGHT - 1.0)
+    save("warp_affine_valid", valid.astype(np.uint8))
+
+    # color conversions
+    gray_kornia = from_tensor(kornia.color.rgb_to_grayscale(to_tensor(rgb)))[..., 0]
+    save("gray_from_rgb", gray_kornia.astype(np.float32))
+    save("gray_from_rgb_u8", cv2.cvtColor(rgb_u8, cv2.COLOR_RGB2GRAY))
+
+
+if __name__ == "__main__":
+    main()